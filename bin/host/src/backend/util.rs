@@ -2,10 +2,17 @@
 
 use crate::KeyValueStore;
 use alloy_consensus::EMPTY_ROOT_HASH;
-use alloy_primitives::keccak256;
+use alloy_eips::{
+    eip2718::Encodable2718,
+    eip4844::{Blob, FIELD_ELEMENTS_PER_BLOB},
+};
+use alloy_primitives::{B256, FixedBytes, keccak256};
 use alloy_rlp::EMPTY_STRING_CODE;
 use anyhow::Result;
+use ark_ff::{BigInteger, PrimeField};
 use kona_preimage::{PreimageKey, PreimageKeyType};
+use kona_proof::l1::ROOTS_OF_UNITY;
+use kona_providers_alloy::L1Archive;
 use tokio::sync::RwLock;
 
 /// Constructs a merkle patricia trie from the ordered list passed and stores all encoded
@@ -39,3 +46,76 @@ pub(crate) async fn store_ordered_trie<KV: KeyValueStore + ?Sized, T: AsRef<[u8]
 
     Ok(())
 }
+
+/// Stores the preimages of the blob with the given versioned hash in the [KeyValueStore]: its KZG
+/// commitment, each of its field elements, and its KZG proof.
+pub(crate) async fn store_blob<KV: KeyValueStore + ?Sized>(
+    kv: &RwLock<KV>,
+    hash: B256,
+    kzg_commitment: &FixedBytes<48>,
+    blob: &Blob,
+    kzg_proof: &FixedBytes<48>,
+) -> Result<()> {
+    // Acquire a lock on the key-value store and set the preimages.
+    let mut kv_lock = kv.write().await;
+
+    // Set the preimage for the blob commitment.
+    kv_lock
+        .set(PreimageKey::new(*hash, PreimageKeyType::Sha256).into(), kzg_commitment.to_vec())?;
+
+    // Write all the field elements to the key-value store. There should be 4096.
+    // The preimage oracle key for each field element is the keccak256 hash of
+    // `abi.encodePacked(sidecar.KZGCommitment, bytes32(ROOTS_OF_UNITY[i]))`.
+    let mut blob_key = [0u8; 80];
+    blob_key[..48].copy_from_slice(kzg_commitment.as_ref());
+    for i in 0..FIELD_ELEMENTS_PER_BLOB {
+        blob_key[48..]
+            .copy_from_slice(ROOTS_OF_UNITY[i as usize].into_bigint().to_bytes_be().as_ref());
+        let blob_key_hash = keccak256(blob_key.as_ref());
+
+        kv_lock.set(PreimageKey::new_keccak256(*blob_key_hash).into(), blob_key.into())?;
+        kv_lock.set(
+            PreimageKey::new(*blob_key_hash, PreimageKeyType::Blob).into(),
+            blob[(i as usize) << 5..(i as usize + 1) << 5].to_vec(),
+        )?;
+    }
+
+    // Write the KZG Proof as the 4096th element.
+    // Note: This is not associated with a root of unity, as to be backwards compatible
+    // with ZK users of kona that use this proof for the overall blob.
+    blob_key[72..].copy_from_slice(FIELD_ELEMENTS_PER_BLOB.to_be_bytes().as_ref());
+    let blob_key_hash = keccak256(blob_key.as_ref());
+
+    kv_lock.set(PreimageKey::new_keccak256(*blob_key_hash).into(), blob_key.into())?;
+    kv_lock
+        .set(PreimageKey::new(*blob_key_hash, PreimageKeyType::Blob).into(), kzg_proof.to_vec())?;
+
+    Ok(())
+}
+
+/// Stores the preimages of all L1 data in the given [L1Archive] in the [KeyValueStore], as the
+/// hint handlers would when fetching it: the headers, the transaction and receipt tries of each
+/// block, and the blobs.
+pub(crate) async fn store_l1_archive<KV: KeyValueStore + ?Sized>(
+    kv: &RwLock<KV>,
+    archive: &L1Archive,
+) -> Result<()> {
+    {
+        let mut kv_lock = kv.write().await;
+        for (hash, header) in archive.headers() {
+            kv_lock.set(PreimageKey::new_keccak256(**hash).into(), alloy_rlp::encode(header))?;
+        }
+    }
+    for (_, transactions) in archive.all_transactions() {
+        let encoded = transactions.iter().map(|tx| tx.encoded_2718()).collect::<Vec<_>>();
+        store_ordered_trie(kv, encoded.as_slice()).await?;
+    }
+    for (_, receipts) in archive.all_receipts() {
+        let encoded = receipts.iter().map(|r| r.encoded_2718()).collect::<Vec<_>>();
+        store_ordered_trie(kv, encoded.as_slice()).await?;
+    }
+    for (hash, blob) in archive.blobs() {
+        store_blob(kv, *hash, &blob.kzg_commitment, &blob.blob, &blob.kzg_proof).await?;
+    }
+    Ok(())
+}
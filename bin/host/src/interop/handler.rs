@@ -3,20 +3,16 @@
 use super::InteropHost;
 use crate::{
     HintHandler, OnlineHostBackend, OnlineHostBackendCfg, PreimageServer, SharedKeyValueStore,
-    backend::util::store_ordered_trie,
+    backend::util::{store_blob, store_ordered_trie},
 };
 use alloy_consensus::{Header, Sealed};
-use alloy_eips::{
-    eip2718::Encodable2718,
-    eip4844::{FIELD_ELEMENTS_PER_BLOB, IndexedBlobHash},
-};
+use alloy_eips::{eip2718::Encodable2718, eip4844::IndexedBlobHash};
 use alloy_op_evm::OpEvmFactory;
 use alloy_primitives::{Address, B256, Bytes, keccak256};
 use alloy_provider::Provider;
 use alloy_rlp::{Decodable, Encodable};
 use alloy_rpc_types::Block;
 use anyhow::{Result, anyhow, ensure};
-use ark_ff::{BigInteger, PrimeField};
 use async_trait::async_trait;
 use kona_derive::EthereumDataSource;
 use kona_driver::Driver;
@@ -28,7 +24,7 @@ use kona_preimage::{
 use kona_proof::{
     CachingOracle, Hint,
     executor::KonaExecutor,
-    l1::{OracleBlobProvider, OracleL1ChainProvider, OraclePipeline, ROOTS_OF_UNITY},
+    l1::{OracleBlobProvider, OracleL1ChainProvider, OraclePipeline},
     l2::OracleL2ChainProvider,
     sync::new_oracle_pipeline_cursor,
 };
@@ -115,45 +111,14 @@ impl HintHandler for InteropHintHandler {
                 }
                 let sidecar = sidecars.remove(0);
 
-                // Acquire a lock on the key-value store and set the preimages.
-                let mut kv_lock = kv.write().await;
-
-                // Set the preimage for the blob commitment.
-                kv_lock.set(
-                    PreimageKey::new(*hash, PreimageKeyType::Sha256).into(),
-                    sidecar.kzg_commitment.to_vec(),
-                )?;
-
-                // Write all the field elements to the key-value store. There should be 4096.
-                // The preimage oracle key for each field element is the keccak256 hash of
-                // `abi.encodePacked(sidecar.KZGCommitment, bytes32(ROOTS_OF_UNITY[i]))`.
-                let mut blob_key = [0u8; 80];
-                blob_key[..48].copy_from_slice(sidecar.kzg_commitment.as_ref());
-                for i in 0..FIELD_ELEMENTS_PER_BLOB {
-                    blob_key[48..].copy_from_slice(
-                        ROOTS_OF_UNITY[i as usize].into_bigint().to_bytes_be().as_ref(),
-                    );
-                    let blob_key_hash = keccak256(blob_key.as_ref());
-
-                    kv_lock
-                        .set(PreimageKey::new_keccak256(*blob_key_hash).into(), blob_key.into())?;
-                    kv_lock.set(
-                        PreimageKey::new(*blob_key_hash, PreimageKeyType::Blob).into(),
-                        sidecar.blob[(i as usize) << 5..(i as usize + 1) << 5].to_vec(),
-                    )?;
-                }
-
-                // Write the KZG Proof as the 4096th element.
-                // Note: This is not associated with a root of unity, as to be backwards compatible
-                // with ZK users of kona that use this proof for the overall blob.
-                blob_key[72..].copy_from_slice((FIELD_ELEMENTS_PER_BLOB).to_be_bytes().as_ref());
-                let blob_key_hash = keccak256(blob_key.as_ref());
-
-                kv_lock.set(PreimageKey::new_keccak256(*blob_key_hash).into(), blob_key.into())?;
-                kv_lock.set(
-                    PreimageKey::new(*blob_key_hash, PreimageKeyType::Blob).into(),
-                    sidecar.kzg_proof.to_vec(),
-                )?;
+                store_blob(
+                    kv.as_ref(),
+                    hash,
+                    &sidecar.kzg_commitment,
+                    &sidecar.blob,
+                    &sidecar.kzg_proof,
+                )
+                .await?;
             }
             HintType::L1Precompile => {
                 ensure!(hint.data.len() >= 28, "Invalid hint data length");
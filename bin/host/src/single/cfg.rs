@@ -4,7 +4,7 @@ use super::{SingleChainHintHandler, SingleChainLocalInputs};
 use crate::{
     DiskKeyValueStore, MemoryKeyValueStore, OfflineHostBackend, OnlineHostBackend,
     OnlineHostBackendCfg, PreimageServer, SharedKeyValueStore, SplitKeyValueStore,
-    eth::http_provider, server::PreimageServerError,
+    backend::util::store_l1_archive, eth::http_provider, server::PreimageServerError,
 };
 use alloy_primitives::B256;
 use alloy_provider::RootProvider;
@@ -15,7 +15,7 @@ use kona_preimage::{
     BidirectionalChannel, Channel, HintReader, HintWriter, OracleReader, OracleServer,
 };
 use kona_proof::HintType;
-use kona_providers_alloy::{OnlineBeaconClient, OnlineBlobProvider};
+use kona_providers_alloy::{L1Archive, L1ArchiveError, OnlineBeaconClient, OnlineBlobProvider};
 use kona_std_fpvm::{FileChannel, FileDescriptor};
 use op_alloy_network::Optimism;
 use serde::Serialize;
@@ -110,6 +110,11 @@ pub struct SingleChainHost {
     /// the execution layer.
     #[arg(long, env)]
     pub enable_experimental_witness_endpoint: bool,
+    /// Path to an L1 archive recorded by `kona-node --l1.record`. If provided, all L1 data in the
+    /// archive is stored in the key-value store before the server starts, so that the L1 side of
+    /// a proof can be run in offline mode without an L1 node or beacon API.
+    #[arg(long, env)]
+    pub l1_archive: Option<PathBuf>,
 }
 
 /// An error that can occur when handling single chain hosts
@@ -121,6 +126,12 @@ pub enum SingleChainHostError {
     /// An IO error.
     #[error("IO error: {0}")]
     IOError(#[from] std::io::Error),
+    /// An error reading the L1 archive.
+    #[error("Failed reading L1 archive: {0}")]
+    L1Archive(#[from] L1ArchiveError),
+    /// An error storing preimages in the key-value store.
+    #[error("Failed storing preimages: {0}")]
+    KeyValueStore(#[from] anyhow::Error),
     /// A JSON parse error.
     #[error("Failed deserializing RollupConfig: {0}")]
     ParseError(#[from] serde_json::Error),
@@ -156,6 +167,9 @@ impl SingleChainHost {
         C: Channel + Send + Sync + 'static,
     {
         let kv_store = self.create_key_value_store()?;
+        if let Some(path) = &self.l1_archive {
+            store_l1_archive(kv_store.as_ref(), &L1Archive::read(path)?).await?;
+        }
 
         let task_handle = if self.is_offline() {
             task::spawn(async {
@@ -346,6 +360,11 @@ mod test {
                 .as_slice(),
                 true,
             ),
+            (
+                ["--server", "--l2-chain-id", "0", "--data-dir", "dummy", "--l1-archive", "dummy"]
+                    .as_slice(),
+                true,
+            ),
             // invalid
             (["--server", "--native", "--l2-chain-id", "0"].as_slice(), false),
             (["--l2-chain-id", "0", "--rollup-config-path", "dummy", "--server"].as_slice(), false),
@@ -0,0 +1,122 @@
+//! An opt-in health beacon, that periodically posts a minimal health summary of the node to a
+//! configurable endpoint.
+//!
+//! The [`HealthReport`] is anonymous: it carries the node's version, chain id, heads, head lag and
+//! peer count, but nothing that identifies the node or its operator. Failures to send a report
+//! are logged, but never fail the node.
+
+use async_trait::async_trait;
+use kona_node_service::{NodeExtension, NodeHandles};
+use kona_p2p::P2pRpcRequest;
+use std::{
+    error::Error,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{sync::oneshot, time::MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+use url::Url;
+
+/// The timeout of a single health report request, including the peer count query.
+const REPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The health summary posted by the [`HealthBeacon`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// The version of the node.
+    pub version: String,
+    /// The L2 chain id.
+    pub chain_id: u64,
+    /// The number of the L2 unsafe head.
+    pub unsafe_head: u64,
+    /// The number of the L2 safe head.
+    pub safe_head: u64,
+    /// The time (in seconds) since the timestamp of the L2 unsafe head.
+    pub head_lag_secs: u64,
+    /// The number of peers connected over gossip, or `None` if the p2p network is disabled or did
+    /// not respond.
+    pub peer_count: Option<usize>,
+    /// Whether all of the node's actors report healthy.
+    pub healthy: bool,
+}
+
+/// A [`NodeExtension`] that posts a [`HealthReport`] to an endpoint at a fixed interval.
+#[derive(Debug)]
+pub struct HealthBeacon {
+    /// The endpoint the reports are posted to.
+    endpoint: Url,
+    /// The interval between reports.
+    interval: Duration,
+    /// The L2 chain id.
+    chain_id: u64,
+}
+
+impl HealthBeacon {
+    /// Creates a new [`HealthBeacon`] that posts a report to `endpoint` every `interval`.
+    pub const fn new(endpoint: Url, interval: Duration, chain_id: u64) -> Self {
+        Self { endpoint, interval, chain_id }
+    }
+
+    /// Builds a [`HealthReport`] from the current state of the node.
+    pub async fn report(&self, handles: &NodeHandles) -> HealthReport {
+        let state = *handles.engine_state.borrow();
+        let (unsafe_head, safe_head) = (state.unsafe_head(), state.safe_head());
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        HealthReport {
+            version: crate::version::SHORT_VERSION.to_string(),
+            chain_id: self.chain_id,
+            unsafe_head: unsafe_head.block_info.number,
+            safe_head: safe_head.block_info.number,
+            head_lag_secs: now.saturating_sub(unsafe_head.block_info.timestamp),
+            peer_count: Self::peer_count(handles).await,
+            healthy: handles.health.is_healthy(),
+        }
+    }
+
+    /// Queries the number of peers connected over gossip from the p2p network driver.
+    async fn peer_count(handles: &NodeHandles) -> Option<usize> {
+        let requests = handles.p2p_requests.as_ref()?;
+        let (tx, rx) = oneshot::channel();
+        requests.send(P2pRpcRequest::PeerCount(tx)).await.ok()?;
+        let (_, gossip) = tokio::time::timeout(REPORT_TIMEOUT, rx).await.ok()?.ok()?;
+        Some(gossip)
+    }
+}
+
+#[async_trait]
+impl NodeExtension for HealthBeacon {
+    fn name(&self) -> &'static str {
+        "beacon"
+    }
+
+    async fn run(
+        self: Box<Self>,
+        handles: NodeHandles,
+        cancellation: CancellationToken,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let client = reqwest::Client::builder().timeout(REPORT_TIMEOUT).build()?;
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = cancellation.cancelled() => return Ok(()),
+                _ = ticker.tick() => {}
+            }
+
+            let report = self.report(&handles).await;
+            let res = client
+                .post(self.endpoint.clone())
+                .json(&report)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status());
+            match res {
+                Ok(_) => debug!(target: "beacon", ?report, "Sent health report"),
+                Err(err) => warn!(target: "beacon", %err, "Failed to send health report"),
+            }
+        }
+    }
+}
@@ -1,13 +1,19 @@
 //! Contains the node CLI.
 
 use crate::{
-    commands::{BootstoreCommand, InfoCommand, NetCommand, NodeCommand, RegistryCommand},
-    flags::{GlobalArgs, init_unified_metrics},
+    commands::{
+        BenchCommand, BootstoreCommand, CheckCommand, ConfigAction, ConfigCommand, DbCommand,
+        DebugCommand, InfoCommand, JournalCommand, KeysCommand, MultiCommand, NetCommand,
+        NodeCommand, RegistryCommand, SnapshotCommand,
+    },
+    config::ConfigFile,
+    flags::{GlobalArgs, init_multi_chain_metrics, init_unified_metrics, shutdown_otlp},
     version,
 };
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, parser::ValueSource};
 use kona_cli::{cli_styles, metrics_args::MetricsArgs};
+use std::ffi::OsString;
 
 /// Subcommands for the CLI.
 #[derive(Debug, PartialEq, Clone, Subcommand)]
@@ -16,6 +22,8 @@ pub enum Commands {
     /// Runs the consensus node.
     #[command(alias = "n")]
     Node(NodeCommand),
+    /// Runs the consensus nodes of multiple chains in one process.
+    Multi(MultiCommand),
     /// Runs the networking stack for the node.
     #[command(alias = "p2p", alias = "network")]
     Net(NetCommand),
@@ -27,6 +35,22 @@ pub enum Commands {
     Bootstore(BootstoreCommand),
     /// Get info about op chain.
     Info(InfoCommand),
+    /// Exports or imports a snapshot of the node's state.
+    Snapshot(SnapshotCommand),
+    /// Dumps the event journal recorded by the node.
+    Journal(JournalCommand),
+    /// Inspects the node's configuration.
+    Config(ConfigCommand),
+    /// Inspects and trims the data stored in the node's datadir.
+    Db(DbCommand),
+    /// Manages the P2P identity and unsafe block signer key.
+    Keys(KeysCommand),
+    /// Validates the node's configuration and endpoints without starting it.
+    Check(CheckCommand),
+    /// Benchmarks the node's components.
+    Bench(BenchCommand),
+    /// Debugs the derivation of the chain, e.g. where an L2 block was derived from.
+    Debug(DebugCommand),
 }
 
 /// The node CLI.
@@ -52,41 +76,142 @@ pub struct Cli {
 }
 
 impl Cli {
+    /// Parses the CLI from the process arguments, with the defaults set by the [`ConfigFile`]
+    /// passed with `--config`, if any. Exits on a CLI error, like [`Parser::parse`].
+    pub fn parse_with_config() -> Result<Self> {
+        let args = std::env::args_os().collect::<Vec<_>>();
+        let (cmd, file) = Self::command_with_config(&args)?;
+        let matches = cmd.clone().get_matches_from(args);
+        Self::from_config_matches(cmd, file.as_ref(), &matches)
+    }
+
+    /// Parses the CLI from the given arguments, like [`Self::parse_with_config`], returning CLI
+    /// errors instead of exiting.
+    pub fn try_parse_with_config_from<I, T>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let args = args.into_iter().map(Into::into).collect::<Vec<_>>();
+        let (cmd, file) = Self::command_with_config(&args)?;
+        let matches = cmd.clone().try_get_matches_from(args)?;
+        Self::from_config_matches(cmd, file.as_ref(), &matches)
+    }
+
+    /// Returns the CLI command, with the defaults set by the [`ConfigFile`] located in the given
+    /// arguments applied.
+    fn command_with_config(args: &[OsString]) -> Result<(clap::Command, Option<ConfigFile>)> {
+        let cmd = Self::command();
+        match ConfigFile::locate(args) {
+            Some(path) => {
+                let file = ConfigFile::load(path)?;
+                Ok((file.apply(cmd)?, Some(file)))
+            }
+            None => Ok((cmd, None)),
+        }
+    }
+
+    /// Builds the CLI from the parsed matches, rendering the effective configuration for the
+    /// `config dump` subcommand.
+    fn from_config_matches(
+        mut cmd: clap::Command,
+        file: Option<&ConfigFile>,
+        matches: &clap::ArgMatches,
+    ) -> Result<Self> {
+        let mut cli = Self::from_arg_matches(matches)?;
+        if let Some(ref network) = cli.global.network {
+            let explicit = match matches.value_source("l2_chain_id") {
+                Some(ValueSource::CommandLine | ValueSource::EnvVariable) => true,
+                _ => file.is_some_and(|file| file.values.contains_key("l2-chain-id")),
+            };
+            cli.global.l2_chain_id = network.resolve_chain_id(cli.global.l2_chain_id, explicit)?;
+        }
+        if let Commands::Config(ConfigCommand { action: ConfigAction::Dump(ref mut dump) }) =
+            cli.subcommand
+        {
+            cmd.build();
+            let dump_cmd = cmd.find_subcommand("config").and_then(|c| c.find_subcommand("dump"));
+            let dump_matches =
+                matches.subcommand_matches("config").and_then(|m| m.subcommand_matches("dump"));
+            if let (Some(dump_cmd), Some(dump_matches)) = (dump_cmd, dump_matches) {
+                dump.effective = ConfigFile::dump(file, dump_cmd, dump_matches);
+            }
+        }
+        Ok(cli)
+    }
+
     /// Runs the CLI.
     pub fn run(self) -> Result<()> {
-        // Initialize unified metrics
-        init_unified_metrics(&self.metrics)?;
+        // Initialize unified metrics, labeled with the chain and the role of the node. The
+        // metrics of multiple chains are each labeled with the chain of the node recording them.
+        if let Commands::Multi(_) = self.subcommand {
+            init_multi_chain_metrics(&self.metrics)?;
+        } else {
+            let mut labels = vec![("chain_id", self.global.l2_chain_id.to_string())];
+            if let Commands::Node(ref node) = self.subcommand {
+                labels.push(("role", node.role().to_string()));
+            }
+            init_unified_metrics(&self.metrics.clone().with_default_labels(labels))?;
+        }
 
         // Initialize telemetry - allow subcommands to customize the filter.
         match self.subcommand {
             Commands::Node(ref node) => node.init_logs(&self.global)?,
+            Commands::Multi(ref multi) => multi.init_logs(&self.global)?,
             Commands::Net(ref net) => net.init_logs(&self.global)?,
             Commands::Registry(ref registry) => registry.init_logs(&self.global)?,
             Commands::Bootstore(ref bootstore) => bootstore.init_logs(&self.global)?,
             Commands::Info(ref info) => info.init_logs(&self.global)?,
+            Commands::Snapshot(ref snapshot) => snapshot.init_logs(&self.global)?,
+            Commands::Journal(ref journal) => journal.init_logs(&self.global)?,
+            Commands::Config(ref config) => config.init_logs(&self.global)?,
+            Commands::Db(ref db) => db.init_logs(&self.global)?,
+            Commands::Keys(ref keys) => keys.init_logs(&self.global)?,
+            Commands::Check(ref check) => check.init_logs(&self.global)?,
+            Commands::Bench(ref bench) => bench.init_logs(&self.global)?,
+            Commands::Debug(ref debug) => debug.init_logs(&self.global)?,
         }
 
-        // If metrics are enabled, initialize the global cli metrics.
-        if self.metrics.enabled {
+        if let Some(ref network) = self.global.network {
+            network.log_summary();
+        }
+
+        // If metrics are enabled, initialize the global cli metrics. The `multi` subcommand
+        // initializes those of each of its chains instead.
+        if self.metrics.enabled && !matches!(self.subcommand, Commands::Multi(_)) {
             self.global.init_cli_metrics();
         }
 
         // Allow subcommands to initialize cli metrics.
         match self.subcommand {
             Commands::Node(ref node) => node.init_cli_metrics(&self.metrics)?,
+            Commands::Multi(ref multi) => multi.init_cli_metrics(&self.metrics)?,
             _ => {
                 tracing::debug!(target: "cli", "No CLI metrics initialized for subcommand: {:?}", self.subcommand)
             }
         }
 
         // Run the subcommand.
-        match self.subcommand {
+        let result = match self.subcommand {
             Commands::Node(node) => Self::run_until_ctrl_c(node.run(&self.global)),
+            Commands::Multi(multi) => Self::run_until_ctrl_c(multi.run(&self.global)),
             Commands::Net(net) => Self::run_until_ctrl_c(net.run(&self.global)),
-            Commands::Registry(registry) => registry.run(&self.global),
+            Commands::Registry(registry) => Self::run_until_ctrl_c(registry.run(&self.global)),
             Commands::Bootstore(bootstore) => bootstore.run(&self.global),
             Commands::Info(info) => info.run(&self.global),
-        }
+            Commands::Snapshot(snapshot) => Self::run_until_ctrl_c(snapshot.run(&self.global)),
+            Commands::Journal(journal) => journal.run(&self.global),
+            Commands::Config(config) => config.run(&self.global),
+            Commands::Db(db) => db.run(&self.global),
+            Commands::Keys(keys) => keys.run(&self.global),
+            Commands::Check(check) => Self::run_until_ctrl_c(check.run(&self.global)),
+            Commands::Bench(bench) => Self::run_until_ctrl_c(bench.run(&self.global)),
+            Commands::Debug(debug) => Self::run_until_ctrl_c(debug.run(&self.global)),
+        };
+
+        // Flush any traces that are still buffered for export.
+        shutdown_otlp();
+        result
     }
 
     /// Run until ctrl-c is pressed.
@@ -127,4 +252,37 @@ mod tests {
         let cli = Cli::parse_from(args);
         assert_eq!(cli.subcommand, subcommand);
     }
+
+    #[test]
+    fn test_parse_cli_network() {
+        let cli =
+            Cli::try_parse_with_config_from(["kona-node", "--network", "base-sepolia", "info"])
+                .unwrap();
+        assert_eq!(cli.global.network.unwrap().name, "base-sepolia");
+        assert_eq!(cli.global.l2_chain_id, 84532);
+
+        let cli = Cli::try_parse_with_config_from([
+            "kona-node",
+            "--network",
+            "base-sepolia",
+            "--l2-chain-id",
+            "84532",
+            "info",
+        ])
+        .unwrap();
+        assert_eq!(cli.global.l2_chain_id, 84532);
+
+        let res = Cli::try_parse_with_config_from([
+            "kona-node",
+            "--network",
+            "base-sepolia",
+            "--l2-chain-id",
+            "10",
+            "info",
+        ]);
+        assert!(res.is_err());
+
+        let res = Cli::try_parse_with_config_from(["kona-node", "--network", "nope", "info"]);
+        assert!(res.is_err());
+    }
 }
@@ -0,0 +1,202 @@
+//! Providers for the derivation benchmark that cache all fetched data in memory.
+
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_eips::eip4844::{Blob, IndexedBlobHash};
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use kona_derive::{BlobProvider, ChainProvider, L2ChainProvider};
+use kona_genesis::{RollupConfig, SystemConfig};
+use kona_protocol::{BatchValidationProvider, BlockInfo, L2BlockInfo};
+use kona_providers_alloy::{
+    AlloyChainProvider, AlloyL2ChainProvider, OnlineBeaconClient, OnlineBlobProvider,
+};
+use op_alloy_consensus::OpBlock;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// The in-memory cache shared by all providers of the derivation benchmark.
+///
+/// Unlike the LRU caches of the alloy providers, which are copied when a provider is cloned, the
+/// cache is shared by all clones and pipelines, and never evicts. Once a range was derived, it can
+/// be derived again without fetching any data.
+#[derive(Debug, Default)]
+pub(crate) struct BenchCache {
+    headers: Mutex<HashMap<B256, Header>>,
+    block_infos: Mutex<HashMap<u64, BlockInfo>>,
+    receipts: Mutex<HashMap<B256, Vec<Receipt>>>,
+    transactions: Mutex<HashMap<B256, (BlockInfo, Vec<TxEnvelope>)>>,
+    blobs: Mutex<HashMap<B256, Vec<Box<Blob>>>>,
+    l2_block_infos: Mutex<HashMap<u64, L2BlockInfo>>,
+    l2_blocks: Mutex<HashMap<u64, OpBlock>>,
+    system_configs: Mutex<HashMap<u64, SystemConfig>>,
+    /// The number of requests that were not served from the cache.
+    misses: AtomicU64,
+}
+
+impl BenchCache {
+    /// Returns the number of cache misses since the last call, and resets it.
+    pub(crate) fn take_misses(&self) -> u64 {
+        self.misses.swap(0, Ordering::Relaxed)
+    }
+
+    /// Returns the cached value for the given key, or fetches and caches it.
+    async fn get_or_fetch<K, V, E>(
+        &self,
+        map: &Mutex<HashMap<K, V>>,
+        key: K,
+        fetch: impl Future<Output = Result<V, E>>,
+    ) -> Result<V, E>
+    where
+        K: Eq + Hash,
+        V: Clone,
+    {
+        if let Some(value) = map.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            return Ok(value.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = fetch.await?;
+        map.lock().unwrap_or_else(|e| e.into_inner()).insert(key, value.clone());
+        Ok(value)
+    }
+}
+
+/// A [`ChainProvider`] backed by the [`BenchCache`].
+#[derive(Debug, Clone)]
+pub(crate) struct CachedChainProvider {
+    inner: AlloyChainProvider,
+    cache: Arc<BenchCache>,
+}
+
+impl CachedChainProvider {
+    /// Creates a new [`CachedChainProvider`].
+    pub(crate) const fn new(inner: AlloyChainProvider, cache: Arc<BenchCache>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl ChainProvider for CachedChainProvider {
+    type Error = <AlloyChainProvider as ChainProvider>::Error;
+
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        let fetch = self.inner.header_by_hash(hash);
+        self.cache.get_or_fetch(&self.cache.headers, hash, fetch).await
+    }
+
+    async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo, Self::Error> {
+        let fetch = self.inner.block_info_by_number(number);
+        self.cache.get_or_fetch(&self.cache.block_infos, number, fetch).await
+    }
+
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error> {
+        let fetch = self.inner.receipts_by_hash(hash);
+        self.cache.get_or_fetch(&self.cache.receipts, hash, fetch).await
+    }
+
+    async fn block_info_and_transactions_by_hash(
+        &mut self,
+        hash: B256,
+    ) -> Result<(BlockInfo, Vec<TxEnvelope>), Self::Error> {
+        let fetch = self.inner.block_info_and_transactions_by_hash(hash);
+        self.cache.get_or_fetch(&self.cache.transactions, hash, fetch).await
+    }
+}
+
+/// A [`BlobProvider`] backed by the [`BenchCache`].
+///
+/// Blobs are cached by block, since the pipeline always requests all batcher blobs of a block.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedBlobProvider {
+    inner: OnlineBlobProvider<OnlineBeaconClient>,
+    cache: Arc<BenchCache>,
+}
+
+impl CachedBlobProvider {
+    /// Creates a new [`CachedBlobProvider`].
+    pub(crate) const fn new(
+        inner: OnlineBlobProvider<OnlineBeaconClient>,
+        cache: Arc<BenchCache>,
+    ) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl BlobProvider for CachedBlobProvider {
+    type Error = <OnlineBlobProvider<OnlineBeaconClient> as BlobProvider>::Error;
+
+    async fn get_blobs(
+        &mut self,
+        block_ref: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Box<Blob>>, Self::Error> {
+        let fetch = self.inner.get_blobs(block_ref, blob_hashes);
+        self.cache.get_or_fetch(&self.cache.blobs, block_ref.hash, fetch).await
+    }
+}
+
+/// An [`L2ChainProvider`] backed by the [`BenchCache`].
+#[derive(Debug, Clone)]
+pub(crate) struct CachedL2ChainProvider {
+    inner: AlloyL2ChainProvider,
+    cache: Arc<BenchCache>,
+}
+
+impl CachedL2ChainProvider {
+    /// Creates a new [`CachedL2ChainProvider`].
+    pub(crate) const fn new(inner: AlloyL2ChainProvider, cache: Arc<BenchCache>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl BatchValidationProvider for CachedL2ChainProvider {
+    type Error = <AlloyL2ChainProvider as BatchValidationProvider>::Error;
+
+    async fn l2_block_info_by_number(&mut self, number: u64) -> Result<L2BlockInfo, Self::Error> {
+        let fetch = self.inner.l2_block_info_by_number(number);
+        self.cache.get_or_fetch(&self.cache.l2_block_infos, number, fetch).await
+    }
+
+    async fn block_by_number(&mut self, number: u64) -> Result<OpBlock, Self::Error> {
+        let fetch = self.inner.block_by_number(number);
+        self.cache.get_or_fetch(&self.cache.l2_blocks, number, fetch).await
+    }
+}
+
+#[async_trait]
+impl L2ChainProvider for CachedL2ChainProvider {
+    type Error = <AlloyL2ChainProvider as L2ChainProvider>::Error;
+
+    async fn system_config_by_number(
+        &mut self,
+        number: u64,
+        rollup_config: Arc<RollupConfig>,
+    ) -> Result<SystemConfig, <Self as L2ChainProvider>::Error> {
+        let fetch = self.inner.system_config_by_number(number, rollup_config);
+        self.cache.get_or_fetch(&self.cache.system_configs, number, fetch).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_get_or_fetch() {
+        let cache = BenchCache::default();
+        let map = Mutex::new(HashMap::new());
+        let value = cache.get_or_fetch(&map, 1u64, async { Ok::<_, ()>(2u64) }).await;
+        assert_eq!(value, Ok(2));
+        let value = cache.get_or_fetch(&map, 1u64, async { Err(()) }).await;
+        assert_eq!(value, Ok(2));
+        assert_eq!(cache.take_misses(), 1);
+        assert_eq!(cache.take_misses(), 0);
+    }
+}
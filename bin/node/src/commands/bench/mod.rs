@@ -0,0 +1,393 @@
+//! Bench Subcommand
+
+use crate::flags::GlobalArgs;
+use alloy_provider::RootProvider;
+use anyhow::{Context, anyhow, bail};
+use clap::{Args, Parser, Subcommand};
+use kona_derive::{
+    ActivationSignal, ChainProvider, DerivationPipeline, EthereumDataSource, Metrics,
+    OriginProvider, Pipeline, PipelineBuilder, PipelineError, PipelineErrorKind,
+    PolledAttributesQueueStage, ResetError, ResetSignal, SignalReceiver, StatefulAttributesBuilder,
+    StepResult,
+};
+use kona_genesis::RollupConfig;
+use kona_protocol::{BatchValidationProvider, BlockInfo};
+use kona_providers_alloy::{
+    AlloyChainProvider, AlloyL2ChainProvider, OnlineBeaconClient, OnlineBlobProvider,
+};
+use op_alloy_network::Optimism;
+use serde::Serialize;
+use std::{
+    fs::File,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use url::Url;
+
+mod cache;
+use cache::{BenchCache, CachedBlobProvider, CachedChainProvider, CachedL2ChainProvider};
+
+mod recorder;
+use recorder::{BenchRecorder, SampleValue};
+
+/// The derivation pipeline benchmarked by the `bench derive` action.
+pub(crate) type BenchPipeline = DerivationPipeline<
+    PolledAttributesQueueStage<
+        EthereumDataSource<CachedChainProvider, CachedBlobProvider>,
+        CachedChainProvider,
+        CachedL2ChainProvider,
+        StatefulAttributesBuilder<CachedChainProvider, CachedL2ChainProvider>,
+    >,
+    CachedL2ChainProvider,
+>;
+
+/// The stages of the derivation pipeline whose timings are reported, with the histogram that
+/// `kona-derive` records them in.
+const STAGES: [(&str, &str); 4] = [
+    ("l1 traversal", Metrics::PIPELINE_ORIGIN_ADVANCE),
+    ("decompression", Metrics::PIPELINE_DECOMPRESSION_DURATION),
+    ("batch validation", Metrics::PIPELINE_CHECK_BATCH_PREFIX),
+    ("attributes", Metrics::PIPELINE_ATTRIBUTES_BUILD_DURATION),
+];
+
+/// The size of the LRU caches of the underlying alloy providers.
+const PROVIDER_CACHE_SIZE: usize = 1024;
+
+/// The `bench` Subcommand
+///
+/// The `bench` subcommand measures the performance of the node's components outside of a running
+/// node, so that regressions can be caught before they reach production.
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node bench derive --l2.start <BLOCK> --l1.blocks 100 [--iterations 3] [--json]
+/// ```
+#[derive(Parser, PartialEq, Debug, Clone)]
+#[command(about = "Benchmarks the node's components")]
+pub struct BenchCommand {
+    /// The benchmark to run.
+    #[command(subcommand)]
+    pub action: BenchAction,
+}
+
+/// The actions of the [`BenchCommand`].
+#[derive(Subcommand, PartialEq, Debug, Clone)]
+pub enum BenchAction {
+    /// Derives a range of L1 blocks and reports the throughput of the derivation pipeline.
+    ///
+    /// The range is first derived once against the configured RPCs, caching all fetched data in
+    /// memory. Each iteration then derives the range again from the cache, so that the reported
+    /// timings only cover the pipeline itself.
+    Derive(BenchDeriveArgs),
+}
+
+/// The arguments of the `bench derive` action.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct BenchDeriveArgs {
+    /// URL of the L1 execution client RPC API.
+    #[arg(long, visible_alias = "l1", env = "KONA_NODE_L1_ETH_RPC")]
+    pub l1_eth_rpc: Url,
+    /// URL of the L1 beacon API.
+    #[arg(long, visible_alias = "l1.beacon", env = "KONA_NODE_L1_BEACON")]
+    pub l1_beacon: Url,
+    /// An L2 RPC Url. The L2 chain held by the node is used as the parent of derived blocks.
+    #[arg(long, visible_alias = "l2.provider", env = "KONA_NODE_L2_ETH_RPC")]
+    pub l2_provider_rpc: Url,
+    /// Path to a custom L2 rollup configuration file
+    /// (overrides the default rollup configuration from the registry)
+    #[arg(long, visible_alias = "rollup-cfg", env = "KONA_NODE_ROLLUP_CONFIG")]
+    pub l2_config_file: Option<PathBuf>,
+    /// The L2 safe head to derive from. Derivation starts a channel timeout before its L1 origin.
+    #[arg(long = "l2.start")]
+    pub l2_start: u64,
+    /// The number of L1 blocks to derive.
+    #[arg(long = "l1.blocks", default_value_t = 100)]
+    pub l1_blocks: u64,
+    /// The number of timed iterations, derived from the cache.
+    #[arg(long = "iterations", default_value_t = 3, value_parser = clap::value_parser!(u32).range(1..))]
+    pub iterations: u32,
+    /// Prints the results as JSON.
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+/// The results of a single derivation of the benchmarked range.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct DeriveRun {
+    /// The wall-clock time taken to derive the range.
+    #[serde(serialize_with = "serialize_secs")]
+    pub elapsed: Duration,
+    /// The number of L2 blocks derived.
+    pub l2_blocks: u64,
+    /// The number of L1 blocks traversed.
+    pub l1_blocks: u64,
+    /// The number of bytes decompressed from channels.
+    pub decompressed_bytes: u64,
+    /// The number of requests that were not served from the cache.
+    pub cache_misses: u64,
+    /// The time spent in each stage, in seconds, and the number of times it ran.
+    pub stages: Vec<StageTiming>,
+}
+
+/// The time spent in a stage of the derivation pipeline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageTiming {
+    /// The name of the stage.
+    pub stage: &'static str,
+    /// The total time spent in the stage, in seconds.
+    pub seconds: f64,
+    /// The number of times the stage ran.
+    pub count: u64,
+}
+
+fn serialize_secs<S: serde::Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(d.as_secs_f64())
+}
+
+impl DeriveRun {
+    /// Returns the number of L2 blocks derived per second.
+    pub fn blocks_per_sec(&self) -> f64 {
+        self.l2_blocks as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// Returns the number of bytes decompressed per second.
+    pub fn bytes_per_sec(&self) -> f64 {
+        self.decompressed_bytes as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl BenchCommand {
+    /// Initializes the logging system based on global arguments.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        args.init_tracing(None)?;
+        Ok(())
+    }
+
+    /// Runs the subcommand.
+    pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        match self.action {
+            BenchAction::Derive(derive) => derive.run(args).await,
+        }
+    }
+}
+
+impl BenchDeriveArgs {
+    /// Runs the derivation benchmark.
+    pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        let cfg = Arc::new(self.rollup_config(args)?);
+        let recorder = Arc::new(BenchRecorder::default());
+        metrics::set_global_recorder(recorder.clone()).map_err(|_| {
+            anyhow!("A metrics recorder is already installed, disable --metrics.enabled")
+        })?;
+
+        let cache = Arc::new(BenchCache::default());
+        let beacon = OnlineBeaconClient::new_http(self.l1_beacon.to_string());
+        let blobs = CachedBlobProvider::new(OnlineBlobProvider::init(beacon).await, cache.clone());
+        let chain = CachedChainProvider::new(
+            AlloyChainProvider::new_http(self.l1_eth_rpc.clone(), PROVIDER_CACHE_SIZE),
+            cache.clone(),
+        );
+        let l2 = CachedL2ChainProvider::new(
+            AlloyL2ChainProvider::new(
+                RootProvider::<Optimism>::new_http(self.l2_provider_rpc.clone()),
+                cfg.clone(),
+                PROVIDER_CACHE_SIZE,
+            ),
+            cache.clone(),
+        );
+
+        if !self.json {
+            println!("Warming up the cache from L2 block {}...", self.l2_start);
+        }
+        let warmup = self.derive(&cfg, &recorder, &cache, &blobs, &chain, &l2).await?;
+        if warmup.l2_blocks == 0 {
+            bail!("No L2 blocks were derived from {} L1 blocks", warmup.l1_blocks);
+        }
+
+        let mut runs = Vec::with_capacity(self.iterations as usize);
+        for i in 0..self.iterations {
+            let run = self.derive(&cfg, &recorder, &cache, &blobs, &chain, &l2).await?;
+            if !self.json {
+                println!(
+                    "Iteration {}: {:.3}s, {:.1} blocks/s, {:.1} MB/s decompressed",
+                    i + 1,
+                    run.elapsed.as_secs_f64(),
+                    run.blocks_per_sec(),
+                    run.bytes_per_sec() / 1e6,
+                );
+            }
+            runs.push(run);
+        }
+
+        if self.json {
+            println!("{}", serde_json::json!({ "warmup": warmup, "runs": runs }));
+            return Ok(());
+        }
+
+        let n = runs.len() as f64;
+        let elapsed = runs.iter().map(|r| r.elapsed.as_secs_f64()).sum::<f64>() / n;
+        let best = runs.iter().map(|r| r.elapsed).min().unwrap_or_default();
+        println!();
+        println!(
+            "Derived {} L2 blocks from {} L1 blocks ({} bytes decompressed)",
+            warmup.l2_blocks, warmup.l1_blocks, warmup.decompressed_bytes
+        );
+        println!("Mean: {elapsed:.3}s, {:.1} blocks/s", warmup.l2_blocks as f64 / elapsed);
+        let best_rate = warmup.l2_blocks as f64 / best.as_secs_f64();
+        println!("Best: {:.3}s, {best_rate:.1} blocks/s", best.as_secs_f64());
+        println!(
+            "Mean decompression: {:.1} MB/s",
+            warmup.decompressed_bytes as f64 / elapsed / 1e6
+        );
+        println!("Stage timings (mean per iteration):");
+        for (i, (stage, _)) in STAGES.iter().enumerate() {
+            let seconds = runs.iter().map(|r| r.stages[i].seconds).sum::<f64>() / n;
+            let count = warmup.stages[i].count;
+            println!("  {stage:<17} {seconds:>9.4}s {count:>8} calls");
+        }
+        let misses = runs.iter().map(|r| r.cache_misses).sum::<u64>();
+        if misses > 0 {
+            println!("Warning: {misses} requests were not served from the cache");
+        }
+        Ok(())
+    }
+
+    /// Returns the rollup config, either from a file or the superchain registry.
+    pub fn rollup_config(&self, args: &GlobalArgs) -> anyhow::Result<RollupConfig> {
+        match &self.l2_config_file {
+            Some(path) => {
+                let file = File::open(path)
+                    .with_context(|| format!("Failed to open l2 config file {}", path.display()))?;
+                serde_json::from_reader(file).context("Failed to parse l2 config")
+            }
+            None => args
+                .rollup_config()
+                .with_context(|| format!("No rollup config for chain ID {}", args.l2_chain_id)),
+        }
+    }
+
+    /// Derives the benchmarked range once with a new pipeline.
+    async fn derive(
+        &self,
+        cfg: &Arc<RollupConfig>,
+        recorder: &BenchRecorder,
+        cache: &BenchCache,
+        blobs: &CachedBlobProvider,
+        chain: &CachedChainProvider,
+        l2: &CachedL2ChainProvider,
+    ) -> anyhow::Result<DeriveRun> {
+        let (mut chain, mut l2) = (chain.clone(), l2.clone());
+        let dap = EthereumDataSource::new_from_parts(chain.clone(), blobs.clone(), cfg);
+        let builder = StatefulAttributesBuilder::new(cfg.clone(), l2.clone(), chain.clone());
+        let mut pipeline: BenchPipeline = PipelineBuilder::new()
+            .rollup_config(cfg.clone())
+            .dap_source(dap)
+            .l2_chain_provider(l2.clone())
+            .chain_provider(chain.clone())
+            .builder(builder)
+            .origin(BlockInfo::default())
+            .build_polled();
+
+        // Start from a channel timeout before the safe head's L1 origin, so that channels which
+        // include its batch are read in full.
+        let l2_safe_head = l2.l2_block_info_by_number(self.l2_start).await?;
+        let origin = l2_safe_head
+            .l1_origin
+            .number
+            .saturating_sub(cfg.channel_timeout(l2_safe_head.block_info.timestamp));
+        let l1_origin = chain.block_info_by_number(origin).await?;
+        let system_config = pipeline.system_config_by_number(self.l2_start).await?;
+        let end = origin + self.l1_blocks;
+
+        recorder.reset();
+        cache.take_misses();
+        let mut run = DeriveRun::default();
+        let start = Instant::now();
+        pipeline
+            .signal(
+                ResetSignal { l2_safe_head, l1_origin, system_config: Some(system_config) }
+                    .signal(),
+            )
+            .await?;
+
+        let mut parent = l2_safe_head;
+        while pipeline.origin().is_some_and(|o| o.number < end) {
+            match pipeline.step(parent).await {
+                StepResult::PreparedAttributes => {}
+                StepResult::AdvancedOrigin => run.l1_blocks += 1,
+                StepResult::OriginAdvanceErr(e) | StepResult::StepFailed(e) => match e {
+                    PipelineErrorKind::Temporary(PipelineError::NotEnoughData) => {}
+                    // The L1 chain or the L2 chain held by the node is exhausted.
+                    PipelineErrorKind::Temporary(_) => break,
+                    PipelineErrorKind::Reset(ResetError::HoloceneActivation) => {
+                        let l1_origin =
+                            pipeline.origin().ok_or(PipelineError::MissingOrigin.crit())?;
+                        let system_config =
+                            pipeline.system_config_by_number(parent.block_info.number).await?;
+                        pipeline
+                            .signal(
+                                ActivationSignal {
+                                    l2_safe_head: parent,
+                                    l1_origin,
+                                    system_config: Some(system_config),
+                                }
+                                .signal(),
+                            )
+                            .await?;
+                    }
+                    e => bail!("Derivation failed on L2 block {}: {e}", parent.block_info.number),
+                },
+            }
+
+            if pipeline.next().is_some() {
+                run.l2_blocks += 1;
+                parent = match l2.l2_block_info_by_number(parent.block_info.number + 1).await {
+                    Ok(block) => block,
+                    Err(_) => break,
+                };
+            }
+        }
+        run.elapsed = start.elapsed();
+
+        run.decompressed_bytes = recorder.get(Metrics::PIPELINE_DECOMPRESSED_BYTES).value as u64;
+        run.cache_misses = cache.take_misses();
+        run.stages = STAGES
+            .iter()
+            .map(|(stage, metric)| {
+                let SampleValue { sum, count, .. } = recorder.get(metric);
+                StageTiming { stage, seconds: sum, count }
+            })
+            .collect();
+        Ok(run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_derive_args() {
+        let cmd = BenchCommand::parse_from([
+            "bench",
+            "derive",
+            "--l1",
+            "http://localhost:8545",
+            "--l1.beacon",
+            "http://localhost:5052",
+            "--l2.provider",
+            "http://localhost:9545",
+            "--l2.start",
+            "1000",
+        ]);
+        let BenchAction::Derive(derive) = cmd.action;
+        assert_eq!(derive.l2_start, 1000);
+        assert_eq!(derive.l1_blocks, 100);
+        assert_eq!(derive.iterations, 3);
+
+        let args = GlobalArgs { l2_chain_id: 10, ..Default::default() };
+        assert_eq!(derive.rollup_config(&args).unwrap().l2_chain_id, 10);
+    }
+}
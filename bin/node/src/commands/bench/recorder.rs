@@ -0,0 +1,138 @@
+//! A metrics recorder aggregating the metrics of the derivation pipeline in memory.
+
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// A [`Recorder`] that aggregates all recorded metrics by name, across labels.
+///
+/// Gauges and counters hold their current value, and histograms the sum and count of all
+/// recorded values. The derivation benchmark reads the stage timings recorded by `kona-derive`
+/// from it.
+#[derive(Debug, Default)]
+pub(crate) struct BenchRecorder {
+    samples: Mutex<HashMap<String, Arc<Sample>>>,
+}
+
+/// The aggregated value of a metric.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct SampleValue {
+    /// The value of a gauge or counter.
+    pub(crate) value: f64,
+    /// The sum of the values recorded by a histogram.
+    pub(crate) sum: f64,
+    /// The number of values recorded by a histogram.
+    pub(crate) count: u64,
+}
+
+/// A metric handle, updating the [`SampleValue`] of a metric.
+#[derive(Debug, Default)]
+struct Sample(Mutex<SampleValue>);
+
+impl Sample {
+    fn update(&self, f: impl FnOnce(&mut SampleValue)) {
+        f(&mut self.0.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+}
+
+impl CounterFn for Sample {
+    fn increment(&self, value: u64) {
+        self.update(|s| s.value += value as f64);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.update(|s| s.value = s.value.max(value as f64));
+    }
+}
+
+impl GaugeFn for Sample {
+    fn increment(&self, value: f64) {
+        self.update(|s| s.value += value);
+    }
+
+    fn decrement(&self, value: f64) {
+        self.update(|s| s.value -= value);
+    }
+
+    fn set(&self, value: f64) {
+        self.update(|s| s.value = value);
+    }
+}
+
+impl HistogramFn for Sample {
+    fn record(&self, value: f64) {
+        self.update(|s| {
+            s.sum += value;
+            s.count += 1;
+        });
+    }
+}
+
+impl BenchRecorder {
+    /// Returns the aggregated value of the metric with the given name.
+    pub(crate) fn get(&self, name: &str) -> SampleValue {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples
+            .get(name)
+            .map(|s| *s.0.lock().unwrap_or_else(|e| e.into_inner()))
+            .unwrap_or_default()
+    }
+
+    /// Resets all metrics to zero.
+    pub(crate) fn reset(&self) {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.values().for_each(|s| s.update(|s| *s = SampleValue::default()));
+    }
+
+    fn sample(&self, key: &Key) -> Arc<Sample> {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.entry(key.name().to_string()).or_default().clone()
+    }
+}
+
+impl Recorder for BenchRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.sample(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.sample(key))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(self.sample(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_recorder() {
+        let recorder = BenchRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            metrics::histogram!("duration", "type" => "a").record(1.5);
+            metrics::histogram!("duration", "type" => "b").record(0.5);
+            metrics::gauge!("bytes").increment(10.0);
+            metrics::gauge!("bytes").increment(5.0);
+        });
+        assert_eq!(recorder.get("duration"), SampleValue { value: 0.0, sum: 2.0, count: 2 });
+        assert_eq!(recorder.get("bytes").value, 15.0);
+        assert_eq!(recorder.get("missing"), SampleValue::default());
+
+        recorder.reset();
+        assert_eq!(recorder.get("duration"), SampleValue::default());
+    }
+}
@@ -0,0 +1,189 @@
+//! Check Subcommand
+
+use crate::{
+    commands::NodeCommand,
+    datadir::DATADIR_VERSION,
+    flags::GlobalArgs,
+    keystore,
+    preflight::{CheckOutcome, PreflightReport},
+};
+use anyhow::bail;
+use clap::Parser;
+use kona_genesis::RollupConfig;
+use tracing_subscriber::EnvFilter;
+
+/// The `check` Subcommand
+///
+/// The `check` subcommand performs all startup validations of the `node` subcommand, from the
+/// configuration to the [`Preflight`] checks of the configured endpoints, without starting the
+/// node. It prints a report of every check, and exits with a non-zero status if any check failed.
+///
+/// [`Preflight`]: crate::preflight::Preflight
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node check [--json] [NODE FLAGS]
+/// ```
+#[derive(Parser, PartialEq, Debug, Clone)]
+#[command(about = "Validates the node's configuration and endpoints without starting it")]
+pub struct CheckCommand {
+    /// Prints the report as JSON, for deploy pipelines.
+    #[arg(long = "json")]
+    pub json: bool,
+    /// The node flags to validate.
+    #[command(flatten)]
+    pub node: NodeCommand,
+}
+
+impl CheckCommand {
+    /// Initializes the logging system based on global arguments. Logs are disabled when printing
+    /// the report as JSON, so that it is the only output.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        args.init_tracing(self.json.then(|| EnvFilter::new("off")))?;
+        Ok(())
+    }
+
+    /// Runs the subcommand.
+    pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        let (mut report, cfg) = self.check_config(args);
+        if let Some(cfg) = cfg {
+            report.extend(self.node.preflight_checks(&cfg)?.run().await);
+        }
+
+        if self.json {
+            println!("{}", serde_json::json!({ "ok": report.is_ok(), "checks": report.checks }));
+        } else {
+            print!("{report}");
+        }
+
+        if !report.is_ok() {
+            let failed = report.failures().map(|c| c.name).collect::<Vec<_>>();
+            bail!("Checks failed: {}", failed.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Checks the node's configuration. Returns the report, and the rollup config if it could be
+    /// loaded.
+    pub fn check_config(&self, args: &GlobalArgs) -> (PreflightReport, Option<RollupConfig>) {
+        let node = &self.node;
+        let mut report = PreflightReport::default();
+
+        let cfg = match node.get_l2_config(args) {
+            Ok(cfg) if cfg.l2_chain_id != args.l2_chain_id => {
+                report.record(
+                    "rollup config",
+                    CheckOutcome::Fail(format!(
+                        "rollup config is for chain {}, expected {}",
+                        cfg.l2_chain_id, args.l2_chain_id
+                    )),
+                );
+                None
+            }
+            Ok(cfg) => {
+                report.record("rollup config", CheckOutcome::Pass);
+                Some(cfg)
+            }
+            Err(e) => {
+                report.record("rollup config", CheckOutcome::fail(e));
+                None
+            }
+        };
+
+        let datadir = args.datadir();
+        let outcome = match datadir.version() {
+            Ok(None) => CheckOutcome::Pass,
+            Ok(Some(version)) if version > DATADIR_VERSION => CheckOutcome::Fail(format!(
+                "datadir has version {version}, newer than the supported version \
+                 {DATADIR_VERSION}"
+            )),
+            Ok(Some(version)) if version < DATADIR_VERSION => CheckOutcome::Warn(format!(
+                "datadir will be migrated from version {version} to {DATADIR_VERSION}"
+            )),
+            Ok(Some(_)) => CheckOutcome::Pass,
+            Err(e) => CheckOutcome::fail(e),
+        };
+        report.record("datadir", outcome);
+
+        let outcome = if node.engine_build_budget.is_nan() || node.engine_build_budget <= 0.0 {
+            CheckOutcome::Fail(format!(
+                "build budget must be positive, got {}",
+                node.engine_build_budget
+            ))
+        } else {
+            CheckOutcome::Pass
+        };
+        report.record("build budget", outcome);
+
+        let outcome = match node.p2p_flags.check_ports() {
+            Ok(()) => CheckOutcome::Pass,
+            Err(e) => CheckOutcome::fail(e),
+        };
+        report.record("p2p ports", outcome);
+
+        let p2p = &node.p2p_flags;
+        let identity = match (&p2p.priv_keystore, &p2p.priv_path) {
+            (Some(path), _) => Some(
+                keystore::load_key(path, p2p.priv_password_file.as_deref())
+                    .and_then(|mut key| Ok(kona_cli::SecretKeyLoader::parse(&mut key.0)?)),
+            ),
+            // A missing key file is generated at startup.
+            (None, Some(path)) if path.exists() => {
+                Some(kona_cli::SecretKeyLoader::load(path).map_err(Into::into))
+            }
+            _ => p2p.private_key.map(|mut key| Ok(kona_cli::SecretKeyLoader::parse(&mut key.0)?)),
+        };
+        if let Some(identity) = identity {
+            let outcome = match identity {
+                Ok(_) => CheckOutcome::Pass,
+                Err(e) => CheckOutcome::fail(e),
+            };
+            report.record("p2p identity", outcome);
+        }
+
+        if node.supervisor_flags.rpc_enabled {
+            let outcome = match node.supervisor_flags.as_rpc_config() {
+                Ok(_) => CheckOutcome::Pass,
+                Err(e) => CheckOutcome::fail(e),
+            };
+            report.record("supervisor config", outcome);
+        }
+
+        (report, cfg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAGS: [&str; 9] = [
+        "check",
+        "--l1-eth-rpc",
+        "http://localhost:8545",
+        "--l1-beacon",
+        "http://localhost:5052",
+        "--l2-engine-rpc",
+        "http://localhost:8551",
+        "--l2-provider-rpc",
+        "http://localhost:8545",
+    ];
+
+    #[test]
+    fn test_check_config() {
+        let cmd =
+            CheckCommand::parse_from(FLAGS.iter().chain(&["--json", "--p2p.listen.tcp", "0"]));
+        assert!(cmd.json);
+
+        let args = GlobalArgs { l2_chain_id: 10, ..Default::default() };
+        let (report, cfg) = cmd.check_config(&args);
+        assert!(report.is_ok(), "{report}");
+        assert_eq!(cfg.unwrap().l2_chain_id, 10);
+
+        let args = GlobalArgs { l2_chain_id: 0, ..Default::default() };
+        let (report, cfg) = cmd.check_config(&args);
+        assert_eq!(report.failures().map(|c| c.name).collect::<Vec<_>>(), vec!["rollup config"]);
+        assert!(cfg.is_none());
+    }
+}
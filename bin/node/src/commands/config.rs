@@ -0,0 +1,59 @@
+//! Config Subcommand
+
+use crate::{commands::NodeCommand, flags::GlobalArgs};
+use clap::{Args, Parser, Subcommand};
+
+/// The `config` Subcommand
+///
+/// The `config` subcommand inspects the node's configuration, as merged from the config file
+/// passed with `--config`, environment variables and CLI flags.
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node --config <FILE> config dump [NODE FLAGS]
+/// ```
+#[derive(Parser, PartialEq, Debug, Clone)]
+#[command(about = "Inspects the node's configuration")]
+pub struct ConfigCommand {
+    /// The config action to perform.
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+/// The actions of the [`ConfigCommand`].
+#[derive(Subcommand, PartialEq, Debug, Clone)]
+pub enum ConfigAction {
+    /// Prints the effective configuration of the `node` subcommand, annotated with the source of
+    /// each value.
+    Dump(ConfigDumpArgs),
+}
+
+/// The arguments of the `config dump` action.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct ConfigDumpArgs {
+    /// The node flags, validated like those of the `node` subcommand.
+    #[command(flatten)]
+    pub node: NodeCommand,
+    /// The effective configuration, rendered by [`Cli::parse_with_config`].
+    ///
+    /// [`Cli::parse_with_config`]: crate::cli::Cli::parse_with_config
+    #[arg(skip)]
+    pub effective: String,
+}
+
+impl ConfigCommand {
+    /// Initializes the logging system based on global arguments.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        args.init_tracing(None)?;
+        Ok(())
+    }
+
+    /// Runs the subcommand.
+    pub fn run(self, _args: &GlobalArgs) -> anyhow::Result<()> {
+        match self.action {
+            ConfigAction::Dump(dump) => print!("{}", dump.effective),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+//! Db Subcommand
+
+use crate::{
+    datadir::{DATADIR_VERSION, DatadirComponent},
+    flags::GlobalArgs,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+/// The `db` Subcommand
+///
+/// The `db` subcommand inspects and trims the data stored in the node's datadir for the chain
+/// selected with `--l2-chain-id`.
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node --datadir <DIR> db info
+/// kona-node --datadir <DIR> db prune --component journal,peers
+/// ```
+#[derive(Parser, PartialEq, Debug, Clone)]
+#[command(about = "Inspects and trims the data stored in the node's datadir")]
+pub struct DbCommand {
+    /// The db action to perform.
+    #[command(subcommand)]
+    pub action: DbAction,
+}
+
+/// The actions of the [`DbCommand`].
+#[derive(Subcommand, PartialEq, Debug, Clone)]
+pub enum DbAction {
+    /// Prints the layout version of the datadir and the disk usage of each of its components.
+    Info,
+    /// Removes the data stored by the given components. The node must not be running.
+    Prune(DbPruneArgs),
+}
+
+/// The arguments of the `db prune` action.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct DbPruneArgs {
+    /// The components to prune, e.g. `journal,peers`.
+    #[arg(long = "component", value_delimiter = ',', required_unless_present = "all")]
+    pub components: Vec<DatadirComponent>,
+    /// Prunes all components.
+    #[arg(long = "all", conflicts_with = "components")]
+    pub all: bool,
+    /// Prints the data that would be removed, without removing it.
+    #[arg(long = "dry-run")]
+    pub dry_run: bool,
+}
+
+impl DbPruneArgs {
+    /// Returns the components to prune.
+    pub fn components(&self) -> Vec<DatadirComponent> {
+        if self.all { DatadirComponent::value_variants().to_vec() } else { self.components.clone() }
+    }
+}
+
+impl DbCommand {
+    /// Initializes the logging system based on global arguments.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        args.init_tracing(None)?;
+        Ok(())
+    }
+
+    /// Runs the subcommand.
+    pub fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        let datadir = args.datadir();
+        match self.action {
+            DbAction::Info => {
+                let Some(version) = datadir.version()? else {
+                    println!("No datadir found at {}", datadir.chain_dir().display());
+                    return Ok(());
+                };
+                println!("Datadir: {}", datadir.chain_dir().display());
+                println!("Version: {version} (supported: {DATADIR_VERSION})");
+                for component in DatadirComponent::value_variants() {
+                    let usage = datadir.usage(*component)?;
+                    println!(
+                        "{:<11} {:>6} files {:>12} bytes",
+                        component.to_string(),
+                        usage.files,
+                        usage.bytes
+                    );
+                }
+            }
+            DbAction::Prune(prune) => {
+                if !prune.dry_run {
+                    datadir.open()?;
+                }
+                for component in prune.components() {
+                    let usage = if prune.dry_run {
+                        datadir.usage(component)?
+                    } else {
+                        datadir.prune(component)?
+                    };
+                    let verb = if prune.dry_run { "Would remove" } else { "Removed" };
+                    println!("{verb} {} files ({} bytes) of {component}", usage.files, usage.bytes);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_prune_args() {
+        let cmd = DbCommand::parse_from(["db", "prune", "--component", "journal,peers"]);
+        let DbAction::Prune(prune) = cmd.action else { panic!("expected prune") };
+        assert_eq!(prune.components(), vec![DatadirComponent::Journal, DatadirComponent::Peers]);
+
+        let cmd = DbCommand::parse_from(["db", "prune", "--all", "--dry-run"]);
+        let DbAction::Prune(prune) = cmd.action else { panic!("expected prune") };
+        assert_eq!(prune.components().len(), 4);
+        assert!(prune.dry_run);
+
+        assert!(DbCommand::try_parse_from(["db", "prune"]).is_err());
+        assert!(
+            DbCommand::try_parse_from(["db", "prune", "--all", "--component", "engine"]).is_err()
+        );
+    }
+}
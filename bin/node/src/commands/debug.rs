@@ -0,0 +1,629 @@
+//! Debug Subcommand
+
+use crate::flags::GlobalArgs;
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{Address, B256, Bytes, FixedBytes, hex};
+use alloy_provider::RootProvider;
+use anyhow::{Context, bail};
+use async_trait::async_trait;
+use clap::{Args, Parser, Subcommand};
+use kona_derive::{
+    ActivationSignal, ChainProvider, DataAvailabilityProvider, DerivationPipeline,
+    EthereumDataSource, OriginProvider, Pipeline, PipelineBuilder, PipelineError,
+    PipelineErrorKind, PipelineResult, PolledAttributesQueueStage, ResetError, ResetSignal,
+    SignalReceiver, StatefulAttributesBuilder, StepResult,
+};
+use kona_genesis::RollupConfig;
+use kona_protocol::{
+    Batch, BatchReader, BatchValidationProvider, BlockInfo, Channel, ChannelId, Frame, L2BlockInfo,
+    OpAttributesWithParent,
+};
+use kona_providers_alloy::{
+    AlloyChainProvider, AlloyL2ChainProvider, OnlineBeaconClient, OnlineBlobProvider,
+};
+use op_alloy_network::Optimism;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+use url::Url;
+
+/// The data source of the traced pipeline, recording the data it reads from L1.
+type TraceDataSource = TracingDataSource<
+    EthereumDataSource<AlloyChainProvider, OnlineBlobProvider<OnlineBeaconClient>>,
+>;
+
+/// The derivation pipeline run by the `debug derive-block` action.
+type TracePipeline = DerivationPipeline<
+    PolledAttributesQueueStage<
+        TraceDataSource,
+        AlloyChainProvider,
+        AlloyL2ChainProvider,
+        StatefulAttributesBuilder<AlloyChainProvider, AlloyL2ChainProvider>,
+    >,
+    AlloyL2ChainProvider,
+>;
+
+/// The size of the LRU caches of the underlying alloy providers.
+const PROVIDER_CACHE_SIZE: usize = 1024;
+
+/// The `debug` Subcommand
+///
+/// The `debug` subcommand answers questions about the chain outside of a running node, e.g. where
+/// an L2 block was derived from.
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node debug derive-block --l2.block <BLOCK> [--json]
+/// ```
+#[derive(Parser, PartialEq, Debug, Clone)]
+#[command(about = "Debugs the derivation of the chain")]
+pub struct DebugCommand {
+    /// The debug action to run.
+    #[command(subcommand)]
+    pub action: DebugAction,
+}
+
+/// The actions of the [`DebugCommand`].
+#[derive(Subcommand, PartialEq, Debug, Clone)]
+pub enum DebugAction {
+    /// Derives a single L2 block on top of its canonical parent, and prints every intermediate
+    /// artifact: the frames read from L1, the channel they belong to, the batch and the payload
+    /// attributes.
+    DeriveBlock(DebugDeriveBlockArgs),
+}
+
+/// The arguments of the `debug derive-block` action.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct DebugDeriveBlockArgs {
+    /// URL of the L1 execution client RPC API.
+    #[arg(long, visible_alias = "l1", env = "KONA_NODE_L1_ETH_RPC")]
+    pub l1_eth_rpc: Url,
+    /// URL of the L1 beacon API.
+    #[arg(long, visible_alias = "l1.beacon", env = "KONA_NODE_L1_BEACON")]
+    pub l1_beacon: Url,
+    /// An L2 RPC Url. The parent of the derived block is read from it.
+    #[arg(long, visible_alias = "l2.provider", env = "KONA_NODE_L2_ETH_RPC")]
+    pub l2_provider_rpc: Url,
+    /// Path to a custom L2 rollup configuration file
+    /// (overrides the default rollup configuration from the registry)
+    #[arg(long, visible_alias = "rollup-cfg", env = "KONA_NODE_ROLLUP_CONFIG")]
+    pub l2_config_file: Option<PathBuf>,
+    /// The number of the L2 block to derive.
+    #[arg(long = "l2.block")]
+    pub l2_block: u64,
+    /// Prints the trace as JSON.
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+/// The trace of the derivation of a single L2 block.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTrace {
+    /// The number of the derived L2 block.
+    pub l2_block: u64,
+    /// The canonical parent the block was derived on top of.
+    pub parent: L2BlockInfo,
+    /// The L1 block the pipeline had reached when the attributes were produced.
+    pub derived_from: BlockInfo,
+    /// The batch the block was derived from, or `None` if no batch was read for it, e.g. when
+    /// the sequencing window expired and the block was derived as an empty batch.
+    pub batch: Option<BatchTrace>,
+    /// The derived payload attributes.
+    pub attributes: AttributesTrace,
+    /// The canonical block with the same number, as held by the L2 RPC.
+    pub canonical: Option<CanonicalTrace>,
+}
+
+/// The batch an L2 block was derived from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchTrace {
+    /// The id of the channel the batch was read from, hex encoded.
+    pub channel_id: String,
+    /// The frames of the channel, in the order they were read from L1.
+    pub frames: Vec<FrameTrace>,
+    /// The kind of the batch, `single` or `span`.
+    pub kind: &'static str,
+    /// The L1 origin number of the block.
+    pub epoch_num: u64,
+    /// The L1 origin hash of the block. Only set for single batches.
+    pub epoch_hash: Option<B256>,
+    /// The parent hash of the block. Only set for single batches.
+    pub parent_hash: Option<B256>,
+    /// The timestamp of the block.
+    pub timestamp: u64,
+    /// The number of sequenced transactions of the block.
+    pub transactions: usize,
+    /// The span the block belongs to. Only set for span batches.
+    pub span: Option<SpanTrace>,
+}
+
+/// The span batch an L2 block was read from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanTrace {
+    /// The index of the block in the span.
+    pub index: usize,
+    /// The number of blocks in the span.
+    pub blocks: usize,
+    /// The first 20 bytes of the parent hash of the first block of the span.
+    pub parent_check: FixedBytes<20>,
+    /// The first 20 bytes of the L1 origin hash of the last block of the span.
+    pub l1_origin_check: FixedBytes<20>,
+}
+
+/// A frame read from L1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameTrace {
+    /// The number of the frame in its channel.
+    pub number: u16,
+    /// The size of the frame data, in bytes.
+    pub size: usize,
+    /// Whether the frame is the last of its channel.
+    pub is_last: bool,
+    /// The L1 block the frame was included in.
+    pub l1_block: BlockInfo,
+}
+
+/// The payload attributes derived for an L2 block.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributesTrace {
+    /// The timestamp of the block.
+    pub timestamp: u64,
+    /// The randomness of the block, i.e. the L1 origin's mix hash.
+    pub prev_randao: B256,
+    /// The fee recipient of the block.
+    pub fee_recipient: Address,
+    /// The gas limit of the block.
+    pub gas_limit: Option<u64>,
+    /// Whether the transaction pool is disabled for the block.
+    pub no_tx_pool: bool,
+    /// The number of deposit transactions, including the L1 info transaction.
+    pub deposits: usize,
+    /// The number of sequenced transactions.
+    pub transactions: usize,
+}
+
+/// The canonical L2 block that a derived block is compared to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanonicalTrace {
+    /// The hash of the canonical block.
+    pub hash: B256,
+    /// Whether the transactions of the canonical block match the derived attributes.
+    pub matches: bool,
+}
+
+/// A [`DataAvailabilityProvider`] that records the data read by the pipeline, with the L1 block it
+/// was read from.
+#[derive(Debug, Clone)]
+struct TracingDataSource<D> {
+    /// The wrapped data source.
+    inner: D,
+    /// The data read so far, shared with the trace.
+    records: Arc<Mutex<Vec<(BlockInfo, Bytes)>>>,
+}
+
+#[async_trait]
+impl<D> DataAvailabilityProvider for TracingDataSource<D>
+where
+    D: DataAvailabilityProvider + Send + Sync,
+{
+    type Item = Bytes;
+
+    async fn next(
+        &mut self,
+        block_ref: &BlockInfo,
+        batcher_addr: Address,
+    ) -> PipelineResult<Bytes> {
+        let data: Bytes = self.inner.next(block_ref, batcher_addr).await?.into();
+        self.records
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((*block_ref, data.clone()));
+        Ok(data)
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+/// A channel read from L1, with the frames it was assembled from and the batches it holds.
+#[derive(Debug)]
+struct TracedChannel {
+    /// The id of the channel.
+    id: ChannelId,
+    /// The frames of the channel, in the order they were read.
+    frames: Vec<FrameTrace>,
+    /// The batches decoded from the channel.
+    batches: Vec<Batch>,
+}
+
+impl DebugCommand {
+    /// Initializes the logging system based on global arguments.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        args.init_tracing(None)?;
+        Ok(())
+    }
+
+    /// Runs the subcommand.
+    pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        match self.action {
+            DebugAction::DeriveBlock(derive) => derive.run(args).await,
+        }
+    }
+}
+
+impl DebugDeriveBlockArgs {
+    /// Derives the block and prints its trace.
+    pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        let trace = self.trace(args).await?;
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&trace)?);
+        } else {
+            print_trace(&trace);
+        }
+        Ok(())
+    }
+
+    /// Returns the rollup config, either from a file or the superchain registry.
+    pub fn rollup_config(&self, args: &GlobalArgs) -> anyhow::Result<RollupConfig> {
+        match &self.l2_config_file {
+            Some(path) => {
+                let file = File::open(path)
+                    .with_context(|| format!("Failed to open l2 config file {}", path.display()))?;
+                serde_json::from_reader(file).context("Failed to parse l2 config")
+            }
+            None => args
+                .rollup_config()
+                .with_context(|| format!("No rollup config for chain ID {}", args.l2_chain_id)),
+        }
+    }
+
+    /// Derives the block on top of its canonical parent, and returns its trace.
+    pub async fn trace(&self, args: &GlobalArgs) -> anyhow::Result<BlockTrace> {
+        let cfg = Arc::new(self.rollup_config(args)?);
+        if self.l2_block <= cfg.genesis.l2.number {
+            bail!("L2 block {} is not after the genesis block", self.l2_block);
+        }
+
+        let beacon = OnlineBeaconClient::new_http(self.l1_beacon.to_string());
+        let blobs = OnlineBlobProvider::init(beacon).await;
+        let mut chain = AlloyChainProvider::new_http(self.l1_eth_rpc.clone(), PROVIDER_CACHE_SIZE);
+        let mut l2 = AlloyL2ChainProvider::new(
+            RootProvider::<Optimism>::new_http(self.l2_provider_rpc.clone()),
+            cfg.clone(),
+            PROVIDER_CACHE_SIZE,
+        );
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let dap = TracingDataSource {
+            inner: EthereumDataSource::new_from_parts(chain.clone(), blobs, &cfg),
+            records: records.clone(),
+        };
+        let builder = StatefulAttributesBuilder::new(cfg.clone(), l2.clone(), chain.clone());
+        let mut pipeline: TracePipeline = PipelineBuilder::new()
+            .rollup_config(cfg.clone())
+            .dap_source(dap)
+            .l2_chain_provider(l2.clone())
+            .chain_provider(chain.clone())
+            .builder(builder)
+            .origin(BlockInfo::default())
+            .build_polled();
+
+        // Start from a channel timeout before the parent's L1 origin, so that channels which
+        // include the block's batch are read in full.
+        let parent = l2.l2_block_info_by_number(self.l2_block - 1).await?;
+        let origin = parent
+            .l1_origin
+            .number
+            .saturating_sub(cfg.channel_timeout(parent.block_info.timestamp));
+        let l1_origin = chain.block_info_by_number(origin).await?;
+        let system_config = pipeline.system_config_by_number(parent.block_info.number).await?;
+        pipeline
+            .signal(
+                ResetSignal { l2_safe_head: parent, l1_origin, system_config: Some(system_config) }
+                    .signal(),
+            )
+            .await?;
+
+        // The batch of the block must be included within the sequencing window of its L1
+        // origin, which is at most one block after the parent's.
+        let end = parent.l1_origin.number + 1 + cfg.seq_window_size;
+        let attributes = loop {
+            if let Some(attributes) = pipeline.next() {
+                break attributes;
+            }
+            if pipeline.origin().is_none_or(|o| o.number > end) {
+                bail!("L2 block {} was not derived by L1 block {end}", self.l2_block);
+            }
+            match pipeline.step(parent).await {
+                StepResult::PreparedAttributes | StepResult::AdvancedOrigin => {}
+                StepResult::OriginAdvanceErr(e) | StepResult::StepFailed(e) => match e {
+                    PipelineErrorKind::Temporary(PipelineError::NotEnoughData) => {}
+                    // The L1 chain or the L2 chain held by the node is exhausted.
+                    PipelineErrorKind::Temporary(e) => {
+                        bail!("L2 block {} is not derivable yet: {e}", self.l2_block)
+                    }
+                    PipelineErrorKind::Reset(ResetError::HoloceneActivation) => {
+                        let l1_origin =
+                            pipeline.origin().ok_or(PipelineError::MissingOrigin.crit())?;
+                        pipeline
+                            .signal(
+                                ActivationSignal {
+                                    l2_safe_head: parent,
+                                    l1_origin,
+                                    system_config: Some(system_config),
+                                }
+                                .signal(),
+                            )
+                            .await?;
+                    }
+                    e => bail!("Derivation of L2 block {} failed: {e}", self.l2_block),
+                },
+            }
+        };
+
+        let records = std::mem::take(&mut *records.lock().unwrap_or_else(PoisonError::into_inner));
+        let channels = decode_channels(&cfg, &records);
+        let batch = find_batch(&channels, &parent, attributes.inner.payload_attributes.timestamp);
+        let canonical = match l2.block_by_number(self.l2_block).await {
+            Ok(block) => {
+                let transactions: Vec<Bytes> =
+                    block.body.transactions.iter().map(|tx| tx.encoded_2718().into()).collect();
+                Some(CanonicalTrace {
+                    hash: block.header.hash_slow(),
+                    matches: attributes.inner.transactions.as_deref() ==
+                        Some(transactions.as_slice()),
+                })
+            }
+            Err(_) => None,
+        };
+
+        Ok(BlockTrace {
+            l2_block: self.l2_block,
+            parent,
+            derived_from: attributes.l1_origin,
+            batch,
+            attributes: AttributesTrace::from(&attributes),
+            canonical,
+        })
+    }
+}
+
+impl From<&OpAttributesWithParent> for AttributesTrace {
+    fn from(attributes: &OpAttributesWithParent) -> Self {
+        let inner = &attributes.inner;
+        let transactions = inner.transactions.as_deref().unwrap_or_default();
+        let deposits = transactions
+            .iter()
+            .filter(|tx| tx.first() == Some(&op_alloy_consensus::DEPOSIT_TX_TYPE_ID))
+            .count();
+        Self {
+            timestamp: inner.payload_attributes.timestamp,
+            prev_randao: inner.payload_attributes.prev_randao,
+            fee_recipient: inner.payload_attributes.suggested_fee_recipient,
+            gas_limit: inner.gas_limit,
+            no_tx_pool: inner.no_tx_pool.unwrap_or_default(),
+            deposits,
+            transactions: transactions.len() - deposits,
+        }
+    }
+}
+
+/// Assembles the frames of the given L1 data into channels, in the order the channels were
+/// completed, and decodes their batches. Data that is not a valid batcher transaction, and frames
+/// that do not fit their channel, are skipped, as the pipeline does.
+fn decode_channels(cfg: &RollupConfig, records: &[(BlockInfo, Bytes)]) -> Vec<TracedChannel> {
+    let mut pending: HashMap<ChannelId, (Channel, Vec<FrameTrace>)> = HashMap::new();
+    let mut channels = Vec::new();
+    for (l1_block, data) in records {
+        let Ok(frames) = Frame::parse_frames_bytes(data) else { continue };
+        for frame in frames {
+            let trace = FrameTrace {
+                number: frame.number,
+                size: frame.data.len(),
+                is_last: frame.is_last,
+                l1_block: *l1_block,
+            };
+            let (channel, traces) = pending
+                .entry(frame.id)
+                .or_insert_with(|| (Channel::new(frame.id, *l1_block), Vec::new()));
+            if channel.add_frame(frame, *l1_block).is_err() {
+                continue;
+            }
+            traces.push(trace);
+            if !channel.is_ready() {
+                continue;
+            }
+
+            let id = channel.id();
+            let Some((channel, frames)) = pending.remove(&id) else { continue };
+            let mut batches = Vec::new();
+            if let Some(data) = channel.frame_data() {
+                let max_rlp_bytes = cfg.max_rlp_bytes_per_channel(l1_block.timestamp) as usize;
+                let mut reader = BatchReader::new(data.to_vec(), max_rlp_bytes);
+                while let Some(batch) = reader.next_batch(cfg) {
+                    batches.push(batch);
+                }
+            }
+            channels.push(TracedChannel { id, frames, batches });
+        }
+    }
+    channels
+}
+
+/// Returns the first batch of the given channels for the block with the given parent and
+/// timestamp.
+fn find_batch(
+    channels: &[TracedChannel],
+    parent: &L2BlockInfo,
+    timestamp: u64,
+) -> Option<BatchTrace> {
+    channels.iter().find_map(|channel| {
+        let trace = channel.batches.iter().find_map(|batch| match batch {
+            Batch::Single(single)
+                if single.timestamp == timestamp &&
+                    single.parent_hash == parent.block_info.hash =>
+            {
+                Some(BatchTrace {
+                    channel_id: String::new(),
+                    frames: Vec::new(),
+                    kind: "single",
+                    epoch_num: single.epoch_num,
+                    epoch_hash: Some(single.epoch_hash),
+                    parent_hash: Some(single.parent_hash),
+                    timestamp,
+                    transactions: single.transactions.len(),
+                    span: None,
+                })
+            }
+            Batch::Span(span) => {
+                let index = span.batches.iter().position(|b| b.timestamp == timestamp)?;
+                let element = &span.batches[index];
+                Some(BatchTrace {
+                    channel_id: String::new(),
+                    frames: Vec::new(),
+                    kind: "span",
+                    epoch_num: element.epoch_num,
+                    epoch_hash: None,
+                    parent_hash: None,
+                    timestamp,
+                    transactions: element.transactions.len(),
+                    span: Some(SpanTrace {
+                        index,
+                        blocks: span.batches.len(),
+                        parent_check: span.parent_check,
+                        l1_origin_check: span.l1_origin_check,
+                    }),
+                })
+            }
+            Batch::Single(_) => None,
+        })?;
+        Some(BatchTrace {
+            channel_id: hex::encode_prefixed(channel.id),
+            frames: channel.frames.clone(),
+            ..trace
+        })
+    })
+}
+
+/// Prints the trace in a human-readable form.
+fn print_trace(trace: &BlockTrace) {
+    let BlockTrace { l2_block, parent, derived_from, batch, attributes, canonical } = trace;
+    println!("L2 block {l2_block}");
+    println!("  parent:       #{} {}", parent.block_info.number, parent.block_info.hash);
+    println!("  derived from: L1 #{} {}", derived_from.number, derived_from.hash);
+
+    match batch {
+        Some(batch) => {
+            println!("Channel {}", batch.channel_id);
+            for frame in &batch.frames {
+                println!(
+                    "  frame {:>3}: {:>7} bytes{} in L1 #{} {}",
+                    frame.number,
+                    frame.size,
+                    if frame.is_last { " (last)" } else { "" },
+                    frame.l1_block.number,
+                    frame.l1_block.hash,
+                );
+            }
+            println!("Batch ({})", batch.kind);
+            if let Some(span) = &batch.span {
+                println!("  span:         block {} of {}", span.index + 1, span.blocks);
+                println!("  parent check: {}", span.parent_check);
+                println!("  origin check: {}", span.l1_origin_check);
+            }
+            if let Some(parent_hash) = batch.parent_hash {
+                println!("  parent hash:  {parent_hash}");
+            }
+            match batch.epoch_hash {
+                Some(hash) => println!("  epoch:        L1 #{} {hash}", batch.epoch_num),
+                None => println!("  epoch:        L1 #{}", batch.epoch_num),
+            }
+            println!("  timestamp:    {}", batch.timestamp);
+            println!("  transactions: {}", batch.transactions);
+        }
+        None => println!("No batch read for the block, it was derived as an empty batch"),
+    }
+
+    println!("Attributes");
+    println!("  timestamp:    {}", attributes.timestamp);
+    println!("  prev randao:  {}", attributes.prev_randao);
+    println!("  fee recipient: {}", attributes.fee_recipient);
+    if let Some(gas_limit) = attributes.gas_limit {
+        println!("  gas limit:    {gas_limit}");
+    }
+    println!("  no tx pool:   {}", attributes.no_tx_pool);
+    println!("  deposits:     {}", attributes.deposits);
+    println!("  transactions: {}", attributes.transactions);
+
+    match canonical {
+        Some(canonical) if canonical.matches => {
+            println!("Matches the canonical block {}", canonical.hash)
+        }
+        Some(canonical) => println!("Does NOT match the canonical block {}", canonical.hash),
+        None => println!("The canonical block is not available"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_derive_block_args() {
+        let cmd = DebugCommand::parse_from([
+            "debug",
+            "derive-block",
+            "--l1",
+            "http://localhost:8545",
+            "--l1.beacon",
+            "http://localhost:5052",
+            "--l2.provider",
+            "http://localhost:9545",
+            "--l2.block",
+            "1000",
+        ]);
+        let DebugAction::DeriveBlock(derive) = cmd.action;
+        assert_eq!(derive.l2_block, 1000);
+        assert!(!derive.json);
+
+        let args = GlobalArgs { l2_chain_id: 10, ..Default::default() };
+        assert_eq!(derive.rollup_config(&args).unwrap().l2_chain_id, 10);
+    }
+
+    #[test]
+    fn test_decode_channels_records_frame_provenance() {
+        let id = [7; 16];
+        let block = |number| BlockInfo { number, ..Default::default() };
+        let data = |frame: Frame| {
+            let mut data = vec![0];
+            data.extend(frame.encode());
+            Bytes::from(data)
+        };
+        let records = vec![
+            (block(1), data(Frame::new(id, 0, vec![1, 2, 3], false))),
+            (block(2), Bytes::from_static(&[1, 2, 3])),
+            (block(3), data(Frame::new(id, 1, vec![4], true))),
+        ];
+
+        let channels = decode_channels(&RollupConfig::default(), &records);
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].id, id);
+        // The channel data is not a valid compressed batch.
+        assert!(channels[0].batches.is_empty());
+        let frames: Vec<_> =
+            channels[0].frames.iter().map(|f| (f.number, f.size, f.l1_block.number)).collect();
+        assert_eq!(frames, [(0, 3, 1), (1, 1, 3)]);
+        assert!(find_batch(&channels, &L2BlockInfo::default(), 0).is_none());
+    }
+}
@@ -0,0 +1,128 @@
+//! Journal Subcommand
+
+use crate::flags::GlobalArgs;
+use clap::Parser;
+use kona_node_service::{EventJournal, JournalEntry, JournalEventKind};
+use std::path::PathBuf;
+
+/// The `journal` Subcommand
+///
+/// The `journal` subcommand dumps the event journal recorded by a node run with
+/// `--journal.path`, optionally filtered by event kind and time.
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node journal --journal.path <FILE> [OPTIONS]
+/// ```
+#[derive(Parser, Default, PartialEq, Debug, Clone)]
+#[command(about = "Dumps the event journal recorded by the node")]
+pub struct JournalCommand {
+    /// The path of the event journal.
+    #[arg(long = "journal.path", env = "KONA_NODE_JOURNAL_PATH")]
+    pub path: PathBuf,
+    /// Only prints events of the given kinds, e.g. `reset,reorg`.
+    #[arg(long = "kind", value_delimiter = ',')]
+    pub kinds: Vec<JournalEventKind>,
+    /// Only prints events recorded at or after the given unix timestamp, in seconds.
+    #[arg(long = "since")]
+    pub since: Option<u64>,
+    /// Only prints the given number of most recent events.
+    #[arg(long = "limit")]
+    pub limit: Option<usize>,
+    /// Prints the events as JSON lines.
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+impl JournalCommand {
+    /// Initializes the logging system based on global arguments.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        args.init_tracing(None)?;
+        Ok(())
+    }
+
+    /// Runs the subcommand.
+    pub fn run(self, _args: &GlobalArgs) -> anyhow::Result<()> {
+        let entries = EventJournal::read(&self.path)?;
+        for entry in self.filter(entries) {
+            if self.json {
+                println!("{}", serde_json::to_string(&entry)?);
+            } else if entry.context.is_null() {
+                println!("{} {:<17} {}", entry.timestamp, entry.kind, entry.message);
+            } else {
+                println!(
+                    "{} {:<17} {} {}",
+                    entry.timestamp, entry.kind, entry.message, entry.context
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the entries that match the filters of the command, oldest first.
+    fn filter(&self, entries: Vec<JournalEntry>) -> Vec<JournalEntry> {
+        let since = self.since.map(|secs| secs.saturating_mul(1000)).unwrap_or_default();
+        let mut entries: Vec<_> = entries
+            .into_iter()
+            .filter(|entry| entry.timestamp >= since)
+            .filter(|entry| self.kinds.is_empty() || self.kinds.contains(&entry.kind))
+            .collect();
+        if let Some(limit) = self.limit {
+            entries.drain(..entries.len().saturating_sub(limit));
+        }
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: u64, kind: JournalEventKind) -> JournalEntry {
+        JournalEntry { timestamp, kind, message: String::new(), context: Default::default() }
+    }
+
+    #[test]
+    fn test_journal_filter() {
+        let cmd = JournalCommand::parse_from([
+            "journal",
+            "--journal.path",
+            "journal.jsonl",
+            "--kind",
+            "reset,reorg",
+            "--since",
+            "2",
+            "--limit",
+            "2",
+        ]);
+        assert_eq!(cmd.kinds, vec![JournalEventKind::Reset, JournalEventKind::Reorg]);
+
+        let entries = vec![
+            entry(1_000, JournalEventKind::Reset),
+            entry(2_000, JournalEventKind::Reorg),
+            entry(3_000, JournalEventKind::SequencerStarted),
+            entry(4_000, JournalEventKind::Reset),
+            entry(5_000, JournalEventKind::Reorg),
+        ];
+        assert_eq!(
+            cmd.filter(entries),
+            vec![entry(4_000, JournalEventKind::Reset), entry(5_000, JournalEventKind::Reorg)]
+        );
+    }
+
+    #[test]
+    fn test_journal_read_rotated() {
+        let dir = std::env::temp_dir().join(format!("kona-journal-{}", std::process::id()));
+        let path = dir.join("journal.jsonl");
+        let journal = EventJournal::open(&path, 512).unwrap();
+        for i in 0..16 {
+            journal.record(JournalEventKind::Reset, format!("reset {i}"), Default::default());
+        }
+
+        let entries = EventJournal::read(&path).unwrap();
+        assert!(!entries.is_empty() && entries.len() < 16);
+        assert_eq!(entries.last().unwrap().message, "reset 15");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,270 @@
+//! Keys Subcommand
+
+use crate::{
+    flags::GlobalArgs,
+    keystore::{self, DEFAULT_PBKDF2_ROUNDS, Keystore},
+};
+use alloy_primitives::B256;
+use alloy_signer_local::PrivateKeySigner;
+use anyhow::{Context, bail};
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use serde::Deserialize;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The `keys` Subcommand
+///
+/// The `keys` subcommand manages the secp256k1 key that serves as the node's P2P identity and, on
+/// sequencers, as the unsafe block signer. Keys are written as encrypted keystores, which the node
+/// loads with `--p2p.priv.keystore`. The keystore password is read from `--password-file`, the
+/// `KONA_NODE_KEYSTORE_PASSWORD` environment variable, or prompted for on the terminal.
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node keys generate --out <FILE>
+/// kona-node keys import --out <FILE> --raw-file <FILE>
+/// kona-node keys inspect <FILE>
+/// ```
+#[derive(Parser, PartialEq, Debug, Clone)]
+#[command(about = "Manages the P2P identity and unsafe block signer key")]
+pub struct KeysCommand {
+    /// The key action to perform.
+    #[command(subcommand)]
+    pub action: KeysAction,
+}
+
+/// The actions of the [`KeysCommand`].
+#[derive(Subcommand, PartialEq, Debug, Clone)]
+pub enum KeysAction {
+    /// Generates a new key.
+    Generate(KeysOutputArgs),
+    /// Imports an existing key from a keystore, a JSON file or raw hex.
+    Import(KeysImportArgs),
+    /// Prints the signer address and peer ID of a key.
+    Inspect(KeysInspectArgs),
+}
+
+/// The arguments of the actions of the [`KeysCommand`] writing a key.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct KeysOutputArgs {
+    /// The file to write the key to. Must not exist yet.
+    #[arg(long = "out")]
+    pub out: PathBuf,
+    /// File containing the password to encrypt the key with.
+    #[arg(long = "password-file")]
+    pub password_file: Option<PathBuf>,
+    /// Writes the key unencrypted, as hex, in the format read by `--p2p.priv.path`.
+    #[arg(long = "insecure-plaintext")]
+    pub plaintext: bool,
+    /// Number of `pbkdf2` rounds used to encrypt the key.
+    #[arg(long = "kdf.rounds", default_value_t = DEFAULT_PBKDF2_ROUNDS, hide = true)]
+    pub rounds: u32,
+}
+
+/// The arguments of the `keys import` action.
+#[derive(Args, PartialEq, Debug, Clone)]
+#[command(group(ArgGroup::new("source").required(true).args(["raw", "raw_file", "json", "keystore"])))]
+pub struct KeysImportArgs {
+    /// The hex-encoded 32-byte private key to import.
+    #[arg(long = "raw")]
+    pub raw: Option<B256>,
+    /// File containing the hex-encoded 32-byte private key to import, e.g. a `--p2p.priv.path`
+    /// file.
+    #[arg(long = "raw-file")]
+    pub raw_file: Option<PathBuf>,
+    /// JSON file containing the hex-encoded private key to import, in a `privateKey` field.
+    #[arg(long = "json")]
+    pub json: Option<PathBuf>,
+    /// Encrypted keystore to import, e.g. to re-encrypt it with a new password.
+    #[arg(long = "keystore")]
+    pub keystore: Option<PathBuf>,
+    /// File containing the password of the imported keystore.
+    #[arg(long = "keystore.password-file", requires = "keystore")]
+    pub keystore_password_file: Option<PathBuf>,
+    /// The output of the imported key.
+    #[command(flatten)]
+    pub output: KeysOutputArgs,
+}
+
+/// The arguments of the `keys inspect` action.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct KeysInspectArgs {
+    /// The key file, either an encrypted keystore or a hex-encoded private key.
+    pub path: PathBuf,
+    /// File containing the password of the keystore.
+    #[arg(long = "password-file")]
+    pub password_file: Option<PathBuf>,
+}
+
+/// A JSON file containing a private key.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonKey {
+    /// The hex-encoded private key.
+    #[serde(alias = "private_key")]
+    private_key: B256,
+}
+
+impl KeysCommand {
+    /// Initializes the logging system based on global arguments.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        args.init_tracing(None)?;
+        Ok(())
+    }
+
+    /// Runs the subcommand.
+    pub fn run(self, _args: &GlobalArgs) -> anyhow::Result<()> {
+        match self.action {
+            KeysAction::Generate(output) => {
+                let secret = PrivateKeySigner::random().to_bytes();
+                output.write(&secret)?;
+                print_key(&secret)?;
+            }
+            KeysAction::Import(import) => {
+                let secret = import.secret()?;
+                import.output.write(&secret)?;
+                print_key(&secret)?;
+            }
+            KeysAction::Inspect(inspect) => {
+                print_key(&read_key(&inspect.path, inspect.password_file.as_deref())?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl KeysOutputArgs {
+    /// Writes the secret key to the output file.
+    pub fn write(&self, secret: &B256) -> anyhow::Result<()> {
+        if self.plaintext {
+            return keystore::write_new(&self.out, &alloy_primitives::hex::encode(secret));
+        }
+        let password = match &self.password_file {
+            Some(_) => keystore::read_password(self.password_file.as_deref(), "")?,
+            None if std::env::var_os(keystore::KEYSTORE_PASSWORD_ENV).is_some() => {
+                keystore::read_password(None, "")?
+            }
+            None => {
+                let password = keystore::prompt_password("New password: ")?;
+                if keystore::prompt_password("Repeat password: ")? != password {
+                    bail!("Passwords do not match");
+                }
+                password
+            }
+        };
+        let address = PrivateKeySigner::from_bytes(secret)?.address();
+        Keystore::encrypt(secret, &password, address, self.rounds).write(&self.out)?;
+        println!("Wrote encrypted keystore to {}", self.out.display());
+        Ok(())
+    }
+}
+
+impl KeysImportArgs {
+    /// Returns the secret key to import.
+    pub fn secret(&self) -> anyhow::Result<B256> {
+        if let Some(raw) = self.raw {
+            return Ok(raw);
+        }
+        if let Some(path) = &self.raw_file {
+            return read_raw(path);
+        }
+        if let Some(path) = &self.json {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let key: JsonKey = serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            return Ok(key.private_key);
+        }
+        let Some(path) = &self.keystore else { bail!("No key to import") };
+        keystore::load_key(path, self.keystore_password_file.as_deref())
+    }
+}
+
+/// Reads the secret key from the given file, either an encrypted keystore or a hex-encoded private
+/// key.
+fn read_key(path: &Path, password_file: Option<&Path>) -> anyhow::Result<B256> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if contents.trim_start().starts_with('{') {
+        keystore::load_key(path, password_file)
+    } else {
+        read_raw(path)
+    }
+}
+
+/// Reads a hex-encoded private key from the given file.
+fn read_raw(path: &Path) -> anyhow::Result<B256> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    B256::from_str(contents.trim())
+        .with_context(|| format!("Invalid private key in {}", path.display()))
+}
+
+/// Prints the signer address and peer ID of the secret key.
+fn print_key(secret: &B256) -> anyhow::Result<()> {
+    let signer = PrivateKeySigner::from_bytes(secret)?;
+    let keypair = kona_cli::SecretKeyLoader::parse(&mut secret.0.clone())?;
+    println!("Signer address: {}", signer.address());
+    println!("Peer ID: {}", keypair.public().to_peer_id());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    #[test]
+    fn test_keys_import_requires_source() {
+        assert!(KeysCommand::try_parse_from(["keys", "import", "--out", "key.json"]).is_err());
+        assert!(
+            KeysCommand::try_parse_from([
+                "keys",
+                "import",
+                "--out",
+                "key.json",
+                "--raw-file",
+                "a.txt",
+                "--json",
+                "b.json"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_keys_import_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("kona-keys-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let secret = b256!("59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d");
+        let password = dir.join("password.txt");
+        std::fs::write(&password, "hunter2\n").unwrap();
+        let json = dir.join("key.json");
+        std::fs::write(&json, format!(r#"{{"privateKey":"{secret}"}}"#)).unwrap();
+
+        let out = dir.join("keystore.json");
+        let cmd = KeysCommand::parse_from([
+            "keys",
+            "import",
+            "--json",
+            json.to_str().unwrap(),
+            "--out",
+            out.to_str().unwrap(),
+            "--password-file",
+            password.to_str().unwrap(),
+            "--kdf.rounds",
+            "2",
+        ]);
+        let KeysAction::Import(import) = cmd.action else { panic!("expected import") };
+        assert_eq!(import.secret().unwrap(), secret);
+        import.output.write(&secret).unwrap();
+        assert!(import.output.write(&secret).is_err());
+
+        assert_eq!(read_key(&out, Some(&password)).unwrap(), secret);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
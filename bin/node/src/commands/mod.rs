@@ -1,16 +1,48 @@
 //! Contains subcommands for the kona node.
 
+mod bench;
+pub use bench::{BenchAction, BenchCommand, BenchDeriveArgs, DeriveRun, StageTiming};
+
+mod check;
+pub use check::CheckCommand;
+
+mod config;
+pub use config::{ConfigAction, ConfigCommand, ConfigDumpArgs};
+
+mod db;
+pub use db::{DbAction, DbCommand, DbPruneArgs};
+
+mod debug;
+pub use debug::{
+    AttributesTrace, BatchTrace, BlockTrace, CanonicalTrace, DebugAction, DebugCommand,
+    DebugDeriveBlockArgs, FrameTrace, SpanTrace,
+};
+
+mod keys;
+pub use keys::{KeysAction, KeysCommand, KeysImportArgs, KeysInspectArgs, KeysOutputArgs};
+
 mod info;
 pub use info::InfoCommand;
 
+mod multi;
+pub use multi::{L1Clients, MultiCommand};
+
 mod node;
 pub use node::NodeCommand;
 
 mod bootstore;
 pub use bootstore::BootstoreCommand;
 
+mod journal;
+pub use journal::JournalCommand;
+
 mod net;
 pub use net::NetCommand;
 
 mod registry;
-pub use registry::RegistryCommand;
+pub use registry::{
+    ConfigDrift, DriftKind, RegistryAction, RegistryCommand, RegistryDiffArgs, diff_rollup_configs,
+};
+
+mod snapshot;
+pub use snapshot::{NodeSnapshot, SNAPSHOT_VERSION, SnapshotAction, SnapshotArgs, SnapshotCommand};
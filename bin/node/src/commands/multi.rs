@@ -0,0 +1,227 @@
+//! Multi Subcommand
+
+use crate::{
+    cli::{Cli, Commands},
+    commands::NodeCommand,
+    flags::GlobalArgs,
+};
+use alloy_provider::RootProvider;
+use anyhow::{Context, bail};
+use clap::Parser;
+use kona_cli::metrics_args::MetricsArgs;
+use kona_node_service::{sync_with_chain_id, with_chain_id};
+use kona_providers_alloy::OnlineBeaconClient;
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    path::PathBuf,
+};
+use tracing::info;
+use url::Url;
+
+/// The L1 clients of a node, shared with the other nodes of the process that are connected to the
+/// same L1 endpoints.
+#[derive(Debug, Clone)]
+pub struct L1Clients {
+    /// The L1 EL provider.
+    pub provider: RootProvider,
+    /// The L1 beacon client, if the node is configured with a beacon API.
+    pub beacon: Option<OnlineBeaconClient>,
+}
+
+/// The `multi` Subcommand
+///
+/// The `multi` subcommand runs the rollup nodes of multiple chains in one process, e.g. for an
+/// interop devnet, or to operate many small chains. Each chain is configured by its own
+/// [config file], which sets the global and `node` flags of its node, as if it were run with
+/// `kona-node --config <FILE> node`.
+///
+/// Each node runs its own actors against its own execution layer, and serves its own RPC and p2p
+/// endpoints, whose ports must not collide. The nodes connected to the same L1 endpoints share
+/// their L1 clients, and the metrics of each node are labeled with its chain id.
+///
+/// [config file]: crate::config::ConfigFile
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node multi --chain chain-a.toml --chain chain-b.toml
+/// ```
+#[derive(Parser, Default, PartialEq, Debug, Clone)]
+#[command(about = "Runs the rollup nodes of multiple chains in one process")]
+pub struct MultiCommand {
+    /// Path to the config file of a chain to run a node for. Repeat for each chain.
+    #[arg(long = "chain", required = true, value_name = "FILE")]
+    pub chains: Vec<PathBuf>,
+}
+
+impl MultiCommand {
+    /// Initializes the logging system based on global arguments.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        // Filter out discovery warnings since they're very very noisy.
+        let filter = tracing_subscriber::EnvFilter::from_default_env()
+            .add_directive("discv5=error".parse()?);
+
+        args.init_reloadable_tracing(Some(filter))?;
+        Ok(())
+    }
+
+    /// Initializes the CLI metrics of each chain, labeled with its chain id.
+    pub fn init_cli_metrics(&self, args: &MetricsArgs) -> anyhow::Result<()> {
+        if !args.enabled {
+            return Ok(());
+        }
+        for (global, node) in self.load_chains()? {
+            sync_with_chain_id(global.l2_chain_id, || {
+                global.init_cli_metrics();
+                node.init_cli_metrics(args)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Loads the global arguments and the `node` subcommand of each chain from its config file.
+    pub fn load_chains(&self) -> anyhow::Result<Vec<(GlobalArgs, NodeCommand)>> {
+        let chains = self
+            .chains
+            .iter()
+            .map(|path| {
+                let args: [OsString; 4] =
+                    ["kona-node".into(), "--config".into(), path.into(), "node".into()];
+                let cli = Cli::try_parse_with_config_from(args)
+                    .with_context(|| format!("Invalid config of chain {}", path.display()))?;
+                match cli.subcommand {
+                    Commands::Node(node) => Ok((cli.global, node)),
+                    _ => unreachable!("the node subcommand is parsed"),
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        check_distinct(&chains)?;
+        Ok(chains)
+    }
+
+    /// Runs the subcommand, until all the nodes exited or any of them failed.
+    pub async fn run(self, _args: &GlobalArgs) -> anyhow::Result<()> {
+        let mut clients = SharedL1Clients::default();
+        let nodes = self.load_chains()?.into_iter().map(|(global, node)| {
+            let l1_clients = clients.clients(&node);
+            let chain_id = global.l2_chain_id;
+            info!(target: "multi", chain_id, l1 = %node.l1_eth_rpc, "Starting rollup node");
+            with_chain_id(chain_id, async move {
+                node.run_with_l1_clients(&global, Some(l1_clients))
+                    .await
+                    .with_context(|| format!("Rollup node of chain {chain_id} failed"))?;
+                info!(target: "multi", chain_id, "Rollup node exited");
+                Ok::<_, anyhow::Error>(())
+            })
+        });
+        futures::future::try_join_all(nodes).await?;
+        Ok(())
+    }
+}
+
+/// The L1 clients of the nodes of a [`MultiCommand`], keyed by their URL.
+#[derive(Debug, Default)]
+struct SharedL1Clients {
+    /// The L1 EL providers.
+    providers: HashMap<Url, RootProvider>,
+    /// The L1 beacon clients.
+    beacons: HashMap<Url, OnlineBeaconClient>,
+}
+
+impl SharedL1Clients {
+    /// Returns the [`L1Clients`] of the given node, created if no other node is connected to the
+    /// same L1 endpoints.
+    fn clients(&mut self, node: &NodeCommand) -> L1Clients {
+        let provider = self
+            .providers
+            .entry(node.l1_eth_rpc.clone())
+            .or_insert_with_key(|url| RootProvider::new_http(url.clone()))
+            .clone();
+        let beacon = node.l1_beacon.as_ref().map(|url| {
+            self.beacons
+                .entry(url.clone())
+                .or_insert_with_key(|url| OnlineBeaconClient::new_http(url.to_string()))
+                .clone()
+        });
+        L1Clients { provider, beacon }
+    }
+}
+
+/// Checks that the nodes of the given chains do not collide: each must be of another chain, and
+/// listen on ports of its own.
+fn check_distinct(chains: &[(GlobalArgs, NodeCommand)]) -> anyhow::Result<()> {
+    let mut chain_ids = HashSet::new();
+    let mut ports = HashMap::new();
+    for (global, node) in chains {
+        let chain_id = global.l2_chain_id;
+        if !chain_ids.insert(chain_id) {
+            bail!("Chain {chain_id} is configured more than once");
+        }
+
+        let mut listen = vec![
+            ("p2p", "tcp", node.p2p_flags.listen_tcp_port),
+            ("p2p", "udp", node.p2p_flags.listen_udp_port),
+        ];
+        if !node.rpc_flags.rpc_disabled {
+            listen.push(("rpc", "tcp", node.rpc_flags.listen_port));
+        }
+        // Port 0 binds a random port, which cannot collide.
+        for (name, protocol, port) in listen.into_iter().filter(|(_, _, port)| *port != 0) {
+            if let Some(other) = ports.insert((protocol, port), chain_id) {
+                bail!(
+                    "The {name} {protocol} port {port} of chain {chain_id} is already used by chain {other}"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(chain_id: u64, port_offset: u16) -> (GlobalArgs, NodeCommand) {
+        let global = GlobalArgs { l2_chain_id: chain_id, ..Default::default() };
+        let mut node = NodeCommand::default();
+        node.p2p_flags.listen_tcp_port += port_offset;
+        node.p2p_flags.listen_udp_port += port_offset;
+        node.rpc_flags.listen_port += port_offset;
+        (global, node)
+    }
+
+    #[test]
+    fn test_parse_multi_command() {
+        let cli = Cli::try_parse_with_config_from([
+            "kona-node",
+            "multi",
+            "--chain",
+            "a.toml",
+            "--chain",
+            "b.toml",
+        ])
+        .unwrap();
+        let Commands::Multi(multi) = cli.subcommand else { panic!("expected multi subcommand") };
+        assert_eq!(multi.chains, vec![PathBuf::from("a.toml"), PathBuf::from("b.toml")]);
+
+        assert!(Cli::try_parse_with_config_from(["kona-node", "multi"]).is_err());
+    }
+
+    #[test]
+    fn test_check_distinct() {
+        assert!(check_distinct(&[chain(1, 0), chain(2, 1)]).is_ok());
+
+        let err = check_distinct(&[chain(1, 0), chain(1, 1)]).unwrap_err();
+        assert!(err.to_string().contains("Chain 1 is configured more than once"));
+
+        let err = check_distinct(&[chain(1, 0), chain(2, 0)]).unwrap_err();
+        assert!(err.to_string().contains("already used by chain 1"));
+
+        let (global, mut node) = chain(2, 1);
+        node.rpc_flags.listen_port = chain(1, 0).1.rpc_flags.listen_port;
+        assert!(check_distinct(&[chain(1, 0), (global.clone(), node.clone())]).is_err());
+        node.rpc_flags.rpc_disabled = true;
+        assert!(check_distinct(&[chain(1, 0), (global, node)]).is_ok());
+    }
+}
@@ -1,21 +1,40 @@
 //! Node Subcommand.
 
 use crate::{
-    flags::{GlobalArgs, P2PArgs, RpcArgs, SequencerArgs, SupervisorArgs},
+    commands::L1Clients,
+    datadir::DATADIR_VERSION,
+    flags::{
+        BeaconArgs, BusArgs, EngineTransportArgs, ExportArgs, GlobalArgs, OtlpArgs, P2PArgs,
+        RpcArgs, SequencerArgs, SupervisorArgs,
+    },
     metrics::CliMetrics,
+    preflight::Preflight,
+    systemd::SystemdNotifier,
 };
 use alloy_rpc_types_engine::JwtSecret;
 use anyhow::{Result, bail};
 use backon::{ExponentialBuilder, Retryable};
 use clap::Parser;
 use kona_cli::metrics_args::MetricsArgs;
-use kona_engine::EngineKind;
+use kona_engine::{
+    BuildBudget, DEFAULT_BUILD_BUDGET_FRACTION, DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY,
+    DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY, ElSyncConfig, EngineKind, EngineTaskKind,
+    EngineTaskTimeouts, ForkchoiceRefresh, SafeHeadRetention, SyncMode, SyncStatusCheck,
+    SyncingBufferConfig, TaskTimeoutPolicy, UnsafeLagConfig,
+};
 use kona_genesis::RollupConfig;
-use kona_node_service::{RollupNode, RollupNodeService};
+use kona_node_service::{
+    BackfillConfig, ChainHaltConfig, EventJournal, RestartConfig, RollupNode, RollupNodeService,
+    SafeHeadHintSource, ShadowConfig,
+};
+use kona_providers_alloy::{L1Recorder, SidecarVerification};
+use kona_rpc::{ReloadableConfig, ReloadableConfigUpdate};
+use kona_sources::SafeHeadHint;
 use op_alloy_provider::ext::engine::OpEngineApi;
 use serde_json::from_reader;
-use std::{fs::File, path::PathBuf, sync::Arc};
-use tracing::{debug, error};
+use std::{fs::File, path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::watch;
+use tracing::{debug, error, info, warn};
 use url::Url;
 
 /// The Node subcommand.
@@ -30,9 +49,66 @@ pub struct NodeCommand {
     /// URL of the L1 execution client RPC API.
     #[arg(long, visible_alias = "l1", env = "KONA_NODE_L1_ETH_RPC")]
     pub l1_eth_rpc: Url,
-    /// URL of the L1 beacon API.
+    /// URL of the L1 beacon API. Required unless the chain never activates Ecotone, in which case
+    /// it posts no blobs and is derived from calldata only.
     #[arg(long, visible_alias = "l1.beacon", env = "KONA_NODE_L1_BEACON")]
-    pub l1_beacon: Url,
+    pub l1_beacon: Option<Url>,
+    /// URL of a "cold" L1 execution client RPC API, e.g. an archive node. The derivation pipeline
+    /// queries it first for blocks older than `--l1.cold-rpc.after-blocks`, or than the pruning
+    /// horizon of `--l1-eth-rpc` once it reports pruned history, and falls back to `--l1-eth-rpc`,
+    /// which keeps serving head tracking.
+    #[arg(long = "l1.cold-rpc", env = "KONA_NODE_L1_COLD_RPC")]
+    pub l1_cold_rpc: Option<Url>,
+    /// Age (in L1 blocks behind the head) after which blocks are fetched from the cold L1 RPC.
+    #[arg(
+        long = "l1.cold-rpc.after-blocks",
+        default_value = "256",
+        env = "KONA_NODE_L1_COLD_RPC_AFTER_BLOCKS"
+    )]
+    pub l1_cold_rpc_after_blocks: u64,
+    /// URL of a "cold" L1 beacon API, e.g. a blob archiver. The derivation pipeline queries it
+    /// first for the blobs of blocks older than `--l1.beacon.cold.after`, and falls back to
+    /// `--l1-beacon`.
+    #[arg(long = "l1.beacon.cold", env = "KONA_NODE_L1_BEACON_COLD")]
+    pub l1_beacon_cold: Option<Url>,
+    /// Age (in seconds) after which blobs are fetched from the cold L1 beacon API. Defaults to
+    /// 14 days, a little under the blob retention period of beacon nodes.
+    #[arg(
+        long = "l1.beacon.cold.after",
+        default_value = "1209600",
+        env = "KONA_NODE_L1_BEACON_COLD_AFTER"
+    )]
+    pub l1_beacon_cold_after: u64,
+    /// Comma-separated list of fallback L1 beacon API URLs, e.g. other beacon nodes or blob
+    /// archivers. The range of slots each endpoint serves blob sidecars for is probed at startup,
+    /// so that the sidecars of old blocks are fetched from the endpoints that retain them, and a
+    /// request that fails on one endpoint resumes from the next.
+    #[arg(
+        long = "l1.beacon.fallbacks",
+        value_delimiter = ',',
+        env = "KONA_NODE_L1_BEACON_FALLBACKS"
+    )]
+    pub l1_beacon_fallbacks: Vec<Url>,
+    /// How strictly the blob sidecars served by the L1 beacon APIs are verified against the
+    /// inclusion proofs of their KZG commitments in the beacon block header they are served with,
+    /// and of that header against the beacon block root committed to by the L1 chain. `strict`
+    /// rejects the sidecars of an endpoint that fail verification, and fetches them from
+    /// the next endpoint, `warn` only logs them, and `disabled` skips the verification.
+    #[arg(
+        long = "l1.beacon.sidecar-verification",
+        default_value = "strict",
+        env = "KONA_NODE_L1_BEACON_SIDECAR_VERIFICATION"
+    )]
+    pub l1_beacon_sidecar_verification: SidecarVerification,
+    /// Number of confirmations that L1 blocks must have before the derivation pipeline consumes
+    /// them. Deeper confirmations delay the safe head, but make the pipeline resets caused by
+    /// shallow L1 reorgs rare. 0 derives from the L1 head.
+    #[arg(
+        long = "l1.confirmation-depth",
+        default_value = "0",
+        env = "KONA_NODE_L1_CONFIRMATION_DEPTH"
+    )]
+    pub l1_confirmation_depth: u64,
     /// URL of the engine API endpoint of an L2 execution client.
     #[arg(long, visible_alias = "l2", env = "KONA_NODE_L2_ENGINE_RPC")]
     pub l2_engine_rpc: Url,
@@ -56,6 +132,37 @@ pub struct NodeCommand {
         help = "DEPRECATED. The kind of engine client, used to control the behavior of optimism in respect to different types of engine clients. Supported engine clients are: [\"geth\", \"reth\", \"erigon\"]."
     )]
     pub l2_engine_kind: EngineKind,
+    /// The strategy used to start syncing the L2 chain.
+    #[arg(
+        long,
+        visible_alias = "syncmode",
+        default_value = "execution-layer",
+        env = "KONA_NODE_SYNC_MODE",
+        help = "The strategy used to start syncing the L2 chain. `execution-layer` waits for the execution client to sync the unsafe chain before deriving, `consensus` walks back from the execution client's heads and starts derivation immediately, and `auto` selects `consensus` if the execution client has a finalized block past genesis, and `execution-layer` otherwise. Supported modes are: [\"auto\", \"execution-layer\", \"consensus\"]."
+    )]
+    pub sync_mode: SyncMode,
+    /// Trusted safe head hint, as `<l2_number>:<l2_hash>@<l1_number>:<l1_hash>`: the safe head
+    /// of the L2 chain at the L1 block. Once verified, derivation starts from the hinted safe head
+    /// rather than from the sync start found from the execution client's heads, for the fast
+    /// bootstrap of nodes that do not need to validate the whole chain.
+    #[arg(long = "sync.safe-head-hint", env = "KONA_NODE_SYNC_SAFE_HEAD_HINT")]
+    pub sync_safe_head_hint: Option<SafeHeadHint>,
+    /// URL of the rollup RPC of a trusted node to get the safe head hint from, through
+    /// `optimism_safeHeadAtL1Block`, instead of `--sync.safe-head-hint`.
+    #[arg(
+        long = "sync.trusted-rpc",
+        env = "KONA_NODE_SYNC_TRUSTED_RPC",
+        conflicts_with = "sync_safe_head_hint"
+    )]
+    pub sync_trusted_rpc: Option<Url>,
+    /// L1 block to get the safe head hint at from the trusted node. Defaults to the finalized L1
+    /// block.
+    #[arg(
+        long = "sync.trusted-l1-block",
+        env = "KONA_NODE_SYNC_TRUSTED_L1_BLOCK",
+        requires = "sync_trusted_rpc"
+    )]
+    pub sync_trusted_l1_block: Option<u64>,
     /// Poll interval (in seconds) for reloading the runtime config.
     /// Provides a backup for when config events are not being picked up.
     /// Disabled if `0`.
@@ -66,6 +173,303 @@ pub struct NodeCommand {
         env = "KONA_NODE_L1_RUNTIME_CONFIG_RELOAD_INTERVAL",
     )]
     pub l1_runtime_config_reload_interval: u64,
+    /// Grace period (in seconds) given to each actor group to exit during shutdown before it is
+    /// aborted. Actors are shut down in order: sequencer, derivation, engine, then networking.
+    #[arg(
+        long = "shutdown.grace-period",
+        default_value = "10",
+        env = "KONA_NODE_SHUTDOWN_GRACE_PERIOD"
+    )]
+    pub shutdown_grace_period: u64,
+    /// Timeout (in seconds) after which a derivation reset that the engine did not pick up is
+    /// requested again, and the derivation actor is reported as degraded.
+    #[arg(
+        long = "derivation.reset-timeout",
+        default_value = "30",
+        env = "KONA_NODE_DERIVATION_RESET_TIMEOUT"
+    )]
+    pub derivation_reset_timeout: u64,
+    /// Maximum number of gossiped unsafe payloads buffered ahead of the unsafe head, e.g. when
+    /// they arrive out-of-order or while the execution client is syncing.
+    #[arg(
+        long = "unsafe-buffer.capacity",
+        default_value_t = DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY,
+        env = "KONA_NODE_UNSAFE_BUFFER_CAPACITY"
+    )]
+    pub unsafe_buffer_capacity: usize,
+    /// Directory to persist buffered unsafe payloads to, so that they survive restarts. If not
+    /// set, the buffer is kept in memory.
+    #[arg(long = "unsafe-buffer.dir", env = "KONA_NODE_UNSAFE_BUFFER_DIR")]
+    pub unsafe_buffer_dir: Option<PathBuf>,
+    /// Number of payload envelopes of the latest unsafe blocks, built or imported, kept for
+    /// retrieval by hash over the admin RPC.
+    #[arg(
+        long = "envelope-store.capacity",
+        default_value_t = DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY,
+        env = "KONA_NODE_ENVELOPE_STORE_CAPACITY"
+    )]
+    pub envelope_store_capacity: usize,
+    /// Directory to persist the payload envelopes of the latest unsafe blocks to, so that they
+    /// survive restarts. If not set, they are kept in memory.
+    #[arg(long = "envelope-store.dir", env = "KONA_NODE_ENVELOPE_STORE_DIR")]
+    pub envelope_store_dir: Option<PathBuf>,
+    /// Number of L1 blocks whose safe head is kept to serve `optimism_safeHeadAtL1Block`, or
+    /// `full` to keep the full history, e.g. on archive nodes. Older entries are pruned in the
+    /// background.
+    #[arg(
+        long = "safe-head-index.retention",
+        default_value_t = SafeHeadRetention::default(),
+        env = "KONA_NODE_SAFE_HEAD_INDEX_RETENTION"
+    )]
+    pub safe_head_retention: SafeHeadRetention,
+    /// Path to persist the safe head index to, so that it survives restarts. If not set, it is
+    /// kept in memory.
+    #[arg(long = "safe-head-index.path", env = "KONA_NODE_SAFE_HEAD_INDEX_PATH")]
+    pub safe_head_index_path: Option<PathBuf>,
+    /// Path to persist the L2 blocks awaiting finalization to, so that the finalized head keeps
+    /// advancing right after a restart, instead of once the blocks derived after the restart are
+    /// finalized. If not set, they are kept in memory.
+    #[arg(long = "finality.path", env = "KONA_NODE_FINALITY_PATH")]
+    pub finality_path: Option<PathBuf>,
+    /// Memory ceiling (in MiB) of the channels, frames and batches buffered by the derivation
+    /// pipeline. Beyond it, the oldest buffered channels are pruned. Disabled if `0`.
+    #[arg(
+        long = "derivation.memory-ceiling",
+        default_value = "0",
+        env = "KONA_NODE_DERIVATION_MEMORY_CEILING"
+    )]
+    pub derivation_memory_ceiling: usize,
+    /// Maximum downtime (in seconds) of a graceful restart. On shutdown, the engine heads are
+    /// saved to the datadir, along with the derived attributes not executed yet. If the node is
+    /// started again within this window, and the execution client still holds the saved unsafe
+    /// head, execution layer sync is skipped, the persisted unsafe payloads are imported right
+    /// away, and the saved attributes are replayed. Implies `--unsafe-buffer.dir`.
+    /// Disabled if `0`.
+    #[arg(long = "restart.window", default_value = "0", env = "KONA_NODE_RESTART_WINDOW")]
+    pub restart_window: u64,
+    /// Minimum age (in seconds) of a gossiped unsafe block before it advances the unsafe head.
+    /// Gives follower nodes a safety margin against sequencer equivocation. Can be changed at
+    /// runtime through the reloadable config. Disabled if `0`.
+    #[arg(
+        long = "unsafe-delay.seconds",
+        default_value = "0",
+        env = "KONA_NODE_UNSAFE_DELAY_SECONDS"
+    )]
+    pub unsafe_delay_secs: u64,
+    /// Number of newer unsafe blocks that must be gossiped before a gossiped unsafe block advances
+    /// the unsafe head. Can be changed at runtime through the reloadable config. Disabled if `0`.
+    #[arg(
+        long = "unsafe-delay.blocks",
+        default_value = "0",
+        env = "KONA_NODE_UNSAFE_DELAY_BLOCKS"
+    )]
+    pub unsafe_delay_blocks: u64,
+    /// Interval (in seconds) between `eth_syncing` checks of the execution client, run while the
+    /// engine is idle. Disabled if `0`.
+    #[arg(
+        long = "engine.sync-check-interval",
+        default_value = "30",
+        env = "KONA_NODE_ENGINE_SYNC_CHECK_INTERVAL"
+    )]
+    pub engine_sync_check_interval: u64,
+    /// Interval (in seconds) at which the current forkchoice state is re-sent to the execution
+    /// client while the engine is idle, e.g. to recover from an execution client restart.
+    /// Disabled if `0`.
+    #[arg(
+        long = "engine.forkchoice-refresh-interval",
+        default_value = "0",
+        env = "KONA_NODE_ENGINE_FORKCHOICE_REFRESH_INTERVAL"
+    )]
+    pub engine_forkchoice_refresh_interval: u64,
+    /// Interval (in seconds) between `eth_syncing` polls of the execution client while it syncs
+    /// the unsafe chain, to report its progress and detect stalls.
+    #[arg(
+        long = "engine.el-sync-poll-interval",
+        default_value = "5",
+        env = "KONA_NODE_ENGINE_EL_SYNC_POLL_INTERVAL"
+    )]
+    pub engine_el_sync_poll_interval: u64,
+    /// Time (in seconds) without progress after which the execution client sync is considered
+    /// stalled, and is re-triggered with the latest unsafe payload.
+    #[arg(
+        long = "engine.el-sync-stall-timeout",
+        default_value = "120",
+        env = "KONA_NODE_ENGINE_EL_SYNC_STALL_TIMEOUT"
+    )]
+    pub engine_el_sync_stall_timeout: u64,
+    /// Number of blocks the unsafe head may lag behind the tip of gossip. Once the execution
+    /// client has synced, an unsafe head lagging by more, without advancing for the resync
+    /// timeout, re-triggers execution client sync towards the tip. Disabled if `0`.
+    #[arg(long = "engine.resync-lag", default_value = "0", env = "KONA_NODE_ENGINE_RESYNC_LAG")]
+    pub engine_resync_lag: u64,
+    /// Time (in seconds) the unsafe head may lag behind the tip of gossip by more than the resync
+    /// lag without advancing, before execution client sync is re-triggered.
+    #[arg(
+        long = "engine.resync-timeout",
+        default_value = "60",
+        env = "KONA_NODE_ENGINE_RESYNC_TIMEOUT"
+    )]
+    pub engine_resync_timeout: u64,
+    /// Timeout (in milliseconds) of engine forkchoice update tasks, after which they are
+    /// cancelled. Disabled if `0`.
+    #[arg(
+        long = "engine.timeout.forkchoice",
+        default_value = "0",
+        env = "KONA_NODE_ENGINE_TIMEOUT_FORKCHOICE"
+    )]
+    pub engine_timeout_forkchoice: u64,
+    /// Timeout (in milliseconds) of engine unsafe payload insertion tasks, after which they are
+    /// cancelled. Disabled if `0`.
+    #[arg(
+        long = "engine.timeout.insert",
+        default_value = "0",
+        env = "KONA_NODE_ENGINE_TIMEOUT_INSERT"
+    )]
+    pub engine_timeout_insert: u64,
+    /// Timeout (in milliseconds) of engine block building tasks, after which they are cancelled.
+    /// Disabled if `0`.
+    #[arg(
+        long = "engine.timeout.build",
+        default_value = "0",
+        env = "KONA_NODE_ENGINE_TIMEOUT_BUILD"
+    )]
+    pub engine_timeout_build: u64,
+    /// Timeout (in milliseconds) of engine consolidation tasks, after which they are cancelled.
+    /// Disabled if `0`.
+    #[arg(
+        long = "engine.timeout.consolidate",
+        default_value = "0",
+        env = "KONA_NODE_ENGINE_TIMEOUT_CONSOLIDATE"
+    )]
+    pub engine_timeout_consolidate: u64,
+    /// Timeout (in milliseconds) of engine finalization tasks, after which they are cancelled.
+    /// Disabled if `0`.
+    #[arg(
+        long = "engine.timeout.finalize",
+        default_value = "0",
+        env = "KONA_NODE_ENGINE_TIMEOUT_FINALIZE"
+    )]
+    pub engine_timeout_finalize: u64,
+    /// Number of times an engine task that timed out is retried before it is dropped, and the
+    /// engine is reported as degraded.
+    #[arg(
+        long = "engine.timeout.retries",
+        default_value = "3",
+        env = "KONA_NODE_ENGINE_TIMEOUT_RETRIES"
+    )]
+    pub engine_timeout_retries: u32,
+    /// Maximum number of payload attributes held back while the execution client is syncing,
+    /// and resubmitted once it is synced. Once full, the oldest attributes are dropped. If `0`,
+    /// the attributes are retried right away instead.
+    #[arg(
+        long = "engine.syncing-buffer",
+        default_value = "64",
+        env = "KONA_NODE_ENGINE_SYNCING_BUFFER"
+    )]
+    pub engine_syncing_buffer: usize,
+    /// Delay (in milliseconds) before the payload attributes held back while the execution client
+    /// is syncing are first resubmitted. It doubles each time they are held back again.
+    #[arg(
+        long = "engine.syncing-backoff",
+        default_value = "500",
+        env = "KONA_NODE_ENGINE_SYNCING_BACKOFF"
+    )]
+    pub engine_syncing_backoff: u64,
+    /// Maximum delay (in milliseconds) between resubmissions of the payload attributes held back
+    /// while the execution client is syncing.
+    #[arg(
+        long = "engine.syncing-max-backoff",
+        default_value = "16000",
+        env = "KONA_NODE_ENGINE_SYNCING_MAX_BACKOFF"
+    )]
+    pub engine_syncing_max_backoff: u64,
+    /// Fraction of the block time that building, importing and canonicalizing a block may take
+    /// before a warning is logged. The timings of each phase are exported as metrics.
+    #[arg(
+        long = "engine.build-budget",
+        default_value_t = DEFAULT_BUILD_BUDGET_FRACTION,
+        env = "KONA_NODE_ENGINE_BUILD_BUDGET"
+    )]
+    pub engine_build_budget: f64,
+    /// Time (in seconds) ahead of a hardfork that changes the engine API versions, during which
+    /// the `engine_newPayload` and `engine_getPayload` calls are repeated with the versions it
+    /// activates. These shadow calls are logged and exported as metrics, but never trusted, to
+    /// validate the compatibility of the execution layer before activation. Disabled if `0`.
+    #[arg(
+        long = "engine.shadow-lookahead",
+        default_value = "0",
+        env = "KONA_NODE_ENGINE_SHADOW_LOOKAHEAD"
+    )]
+    pub engine_shadow_lookahead: u64,
+    /// Check each block promoted to safe against the execution client: its transactions and
+    /// withdrawals root must match the derived attributes exactly. Mismatches are logged, exported
+    /// as metrics and recorded in the journal, and the engine is reported as degraded.
+    #[arg(long = "engine.consistency-check", env = "KONA_NODE_ENGINE_CONSISTENCY_CHECK")]
+    pub engine_consistency_check: bool,
+    /// Interval (in seconds) between historical safe blocks re-derived and checked against the
+    /// execution client by the backfill actor. Disabled if `0`.
+    #[arg(long = "backfill.interval", default_value = "0", env = "KONA_NODE_BACKFILL_INTERVAL")]
+    pub backfill_interval: u64,
+    /// Number of blocks behind the safe head from which the backfill actor starts verifying.
+    #[arg(long = "backfill.depth", default_value = "100000", env = "KONA_NODE_BACKFILL_DEPTH")]
+    pub backfill_depth: u64,
+    /// URL of a reference op-node's rollup RPC. If set, the node runs in shadow mode: its safe
+    /// blocks are compared against the reference node's one by one, and the first divergence is
+    /// reported.
+    #[arg(long = "shadow.rollup-rpc", env = "KONA_NODE_SHADOW_ROLLUP_RPC")]
+    pub shadow_rollup_rpc: Option<Url>,
+    /// URL of the execution client of the reference op-node. If set, the payload attributes of a
+    /// diverging block are compared against the reference block.
+    #[arg(long = "shadow.l2-rpc", env = "KONA_NODE_SHADOW_L2_RPC", requires = "shadow_rollup_rpc")]
+    pub shadow_l2_rpc: Option<Url>,
+    /// Interval (in seconds) between comparisons against the reference op-node in shadow mode.
+    #[arg(long = "shadow.interval", default_value = "2", env = "KONA_NODE_SHADOW_INTERVAL")]
+    pub shadow_interval: u64,
+    /// Duration (in seconds) the safe head may stall for, while the L1 chain advances, before the
+    /// chain-halt watchdog reports a halt. Disabled if `0`.
+    #[arg(
+        long = "watchdog.halt-timeout",
+        default_value = "0",
+        env = "KONA_NODE_WATCHDOG_HALT_TIMEOUT"
+    )]
+    pub watchdog_halt_timeout: u64,
+    /// Number of derivation pipeline resets during a stall at which the chain-halt watchdog
+    /// attributes the halt to a reset loop.
+    #[arg(
+        long = "watchdog.reset-threshold",
+        default_value = "3",
+        env = "KONA_NODE_WATCHDOG_RESET_THRESHOLD"
+    )]
+    pub watchdog_reset_threshold: u64,
+    /// Path to an append-only journal of key node events (resets, reorgs, invalid payloads,
+    /// sequencer starts and stops, supervisor updates, shadow divergences), for post-incident
+    /// analysis. Dump it with the `journal` subcommand. Disabled if not set.
+    #[arg(long = "journal.path", env = "KONA_NODE_JOURNAL_PATH")]
+    pub journal_path: Option<PathBuf>,
+    /// Maximum size (in MiB) of the event journal on disk, including its rotated backup.
+    #[arg(long = "journal.max-size", default_value = "16", env = "KONA_NODE_JOURNAL_MAX_SIZE")]
+    pub journal_max_size: u64,
+    /// Path to an archive that all L1 data (headers, transactions, receipts and blobs) consumed by
+    /// the derivation pipeline is recorded into, appending to it if it exists. The archive can be
+    /// replayed by derivation tests, or served by the host in offline mode. Disabled if not set.
+    #[arg(long = "l1.record", env = "KONA_NODE_L1_RECORD")]
+    pub l1_record: Option<PathBuf>,
+    /// Path to a JSON file containing a partial update to the runtime-reloadable config (log
+    /// filter and levels, static peers, sequencer recovery mode, unsafe block delay). The file is
+    /// re-read and applied when the node receives a `SIGHUP`.
+    #[arg(long = "reload.config-file", env = "KONA_NODE_RELOAD_CONFIG_FILE")]
+    pub reload_config_file: Option<PathBuf>,
+    /// Skip the startup preflight checks of the configured endpoints.
+    #[arg(long = "preflight.skip", env = "KONA_NODE_PREFLIGHT_SKIP")]
+    pub preflight_skip: bool,
+    /// Refuse to start if any preflight check fails. By default, failures are reported and the
+    /// node starts regardless.
+    #[arg(
+        long = "preflight.strict",
+        env = "KONA_NODE_PREFLIGHT_STRICT",
+        conflicts_with = "preflight_skip"
+    )]
+    pub preflight_strict: bool,
     /// P2P CLI arguments.
     #[command(flatten)]
     pub p2p_flags: P2PArgs,
@@ -78,23 +482,98 @@ pub struct NodeCommand {
     /// SUPERVISOR CLI arguments.
     #[command(flatten)]
     pub supervisor_flags: SupervisorArgs,
+    /// Actor channel CLI arguments.
+    #[command(flatten)]
+    pub bus_flags: BusArgs,
+    /// OpenTelemetry trace export CLI arguments.
+    #[command(flatten)]
+    pub otlp_flags: OtlpArgs,
+    /// Health beacon CLI arguments.
+    #[command(flatten)]
+    pub beacon_flags: BeaconArgs,
+    /// Chain event export CLI arguments.
+    #[command(flatten)]
+    pub export_flags: ExportArgs,
+    /// Engine transport CLI arguments.
+    #[command(flatten)]
+    pub engine_transport_flags: EngineTransportArgs,
 }
 
 impl Default for NodeCommand {
     fn default() -> Self {
         Self {
             l1_eth_rpc: Url::parse("http://localhost:8545").unwrap(),
-            l1_beacon: Url::parse("http://localhost:5052").unwrap(),
+            l1_beacon: Some(Url::parse("http://localhost:5052").unwrap()),
+            l1_cold_rpc: None,
+            l1_cold_rpc_after_blocks: 256,
+            l1_beacon_cold: None,
+            l1_beacon_cold_after: 1_209_600,
+            l1_beacon_fallbacks: Vec::new(),
+            l1_beacon_sidecar_verification: SidecarVerification::Strict,
+            l1_confirmation_depth: 0,
             l2_engine_rpc: Url::parse("http://localhost:8551").unwrap(),
             l2_provider_rpc: Url::parse("http://localhost:8545").unwrap(),
             l2_engine_jwt_secret: None,
             l2_config_file: None,
             l1_runtime_config_reload_interval: 600,
+            shutdown_grace_period: 10,
+            derivation_reset_timeout: 30,
+            unsafe_buffer_capacity: DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY,
+            unsafe_buffer_dir: None,
+            envelope_store_capacity: DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY,
+            envelope_store_dir: None,
+            safe_head_retention: SafeHeadRetention::default(),
+            safe_head_index_path: None,
+            finality_path: None,
+            derivation_memory_ceiling: 0,
+            restart_window: 0,
+            unsafe_delay_secs: 0,
+            unsafe_delay_blocks: 0,
+            engine_sync_check_interval: 30,
+            engine_forkchoice_refresh_interval: 0,
+            engine_el_sync_poll_interval: 5,
+            engine_el_sync_stall_timeout: 120,
+            engine_resync_lag: 0,
+            engine_resync_timeout: 60,
+            engine_timeout_forkchoice: 0,
+            engine_timeout_insert: 0,
+            engine_timeout_build: 0,
+            engine_timeout_consolidate: 0,
+            engine_timeout_finalize: 0,
+            engine_timeout_retries: 3,
+            engine_syncing_buffer: 64,
+            engine_syncing_backoff: 500,
+            engine_syncing_max_backoff: 16_000,
+            engine_build_budget: DEFAULT_BUILD_BUDGET_FRACTION,
+            engine_shadow_lookahead: 0,
+            engine_consistency_check: false,
+            backfill_interval: 0,
+            backfill_depth: 100_000,
+            shadow_rollup_rpc: None,
+            shadow_l2_rpc: None,
+            shadow_interval: 2,
+            watchdog_halt_timeout: 0,
+            watchdog_reset_threshold: 3,
+            journal_path: None,
+            journal_max_size: 16,
+            l1_record: None,
+            reload_config_file: None,
+            preflight_skip: false,
+            preflight_strict: false,
             p2p_flags: P2PArgs::default(),
             rpc_flags: RpcArgs::default(),
             sequencer_flags: SequencerArgs::default(),
             l2_engine_kind: EngineKind::Geth,
+            sync_mode: SyncMode::ExecutionLayer,
+            sync_safe_head_hint: None,
+            sync_trusted_rpc: None,
+            sync_trusted_l1_block: None,
             supervisor_flags: SupervisorArgs::default(),
+            bus_flags: BusArgs::default(),
+            otlp_flags: OtlpArgs::default(),
+            beacon_flags: BeaconArgs::default(),
+            export_flags: ExportArgs::default(),
+            engine_transport_flags: EngineTransportArgs::default(),
         }
     }
 }
@@ -106,10 +585,69 @@ impl NodeCommand {
         let filter = tracing_subscriber::EnvFilter::from_default_env()
             .add_directive("discv5=error".parse()?);
 
-        args.init_tracing(Some(filter))?;
+        args.init_reloadable_tracing_with(Some(filter), self.otlp_flags.layer()?)?;
+        Ok(())
+    }
+
+    /// Spawns the tasks that apply runtime config reloads that are handled outside of the node's
+    /// actors: log filter and level updates, and re-reading the [`Self::reload_config_file`] on
+    /// `SIGHUP`.
+    pub fn spawn_config_reloader(
+        &self,
+        reload: &watch::Sender<ReloadableConfig>,
+    ) -> anyhow::Result<()> {
+        let mut log_rx = reload.subscribe();
+        tokio::spawn(async move {
+            let log_config =
+                |config: &ReloadableConfig| (config.log_filter.clone(), config.log_levels.clone());
+            let mut current = log_config(&log_rx.borrow_and_update());
+            while log_rx.changed().await.is_ok() {
+                let next = log_config(&log_rx.borrow_and_update());
+                if next == current {
+                    continue;
+                }
+                let (filter, levels) = &next;
+                match kona_cli::reload_tracing_levels(filter.as_deref(), levels) {
+                    Ok(()) => info!(target: "rollup_node", ?filter, ?levels, "Reloaded log filter"),
+                    Err(e) => {
+                        warn!(target: "rollup_node", ?filter, ?levels, "Failed to reload log filter: {e}")
+                    }
+                }
+                current = next;
+            }
+        });
+
+        #[cfg(unix)]
+        if let Some(path) = self.reload_config_file.clone() {
+            let mut sighup =
+                tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+            let reload = reload.clone();
+            tokio::spawn(async move {
+                while sighup.recv().await.is_some() {
+                    let update = File::open(&path).map_err(anyhow::Error::from).and_then(|f| {
+                        from_reader::<_, ReloadableConfigUpdate>(f).map_err(Into::into)
+                    });
+                    match update {
+                        Ok(update) => {
+                            let modified = reload.send_if_modified(|config| update.apply(config));
+                            info!(target: "rollup_node", modified, "Reloaded runtime config on SIGHUP");
+                        }
+                        Err(e) => {
+                            warn!(target: "rollup_node", ?path, "Failed to read reload config file: {e}");
+                        }
+                    }
+                }
+            });
+        }
+
         Ok(())
     }
 
+    /// Returns the role of the node, used to label its metrics.
+    pub const fn role(&self) -> &'static str {
+        if self.sequencer_flags.enabled { "sequencer" } else { "validator" }
+    }
+
     /// Initializes CLI metrics for the Node subcommand.
     pub fn init_cli_metrics(&self, args: &MetricsArgs) -> anyhow::Result<()> {
         if !args.enabled {
@@ -150,17 +688,81 @@ impl NodeCommand {
         Ok(())
     }
 
+    /// Runs the [`Preflight`] checks against the configured endpoints and logs a summary.
+    ///
+    /// ## Errors
+    ///
+    /// - If [`Self::preflight_strict`] is set and any check failed.
+    pub async fn preflight(&self, config: &RollupConfig) -> anyhow::Result<()> {
+        if self.preflight_skip {
+            debug!(target: "preflight", "Preflight checks are disabled");
+            return Ok(());
+        }
+
+        let report = self.preflight_checks(config)?.run().await;
+        report.log_summary();
+
+        if self.preflight_strict && !report.is_ok() {
+            let failed = report.failures().map(|c| c.name).collect::<Vec<_>>();
+            bail!("Preflight checks failed: {}", failed.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Returns the [`Preflight`] checks of the configured endpoints.
+    ///
+    /// ## Errors
+    ///
+    /// - If the engine transport configured by the `--l2.engine-*` flags cannot be loaded.
+    pub fn preflight_checks(&self, config: &RollupConfig) -> anyhow::Result<Preflight> {
+        Ok(Preflight {
+            cfg: Arc::new(config.clone()),
+            l1_eth_rpc: self.l1_eth_rpc.clone(),
+            l1_beacon: self.l1_beacon.clone().filter(|_| config.blobs_enabled()),
+            l2_engine_rpc: self.l2_engine_rpc.clone(),
+            l2_provider_rpc: self.l2_provider_rpc.clone(),
+            jwt_secret: self.jwt_secret(),
+            engine_transport: self.engine_transport_flags.transport()?,
+            supervisor_addr: self
+                .supervisor_flags
+                .rpc_enabled
+                .then(|| self.supervisor_flags.socket_addr())
+                .filter(|addr| addr.port() != 0),
+        })
+    }
+
+    /// Validates the L1 beacon API flags against the [`RollupConfig`]. Chains that never activate
+    /// Ecotone post no blobs, and need no beacon API.
+    ///
+    /// ## Errors
+    ///
+    /// - If the chain activates Ecotone, and `--l1-beacon` is not set.
+    pub fn validate_l1_beacon(&self, config: &RollupConfig) -> anyhow::Result<()> {
+        if config.blobs_enabled() {
+            if self.l1_beacon.is_none() {
+                bail!("--l1-beacon is required, the chain activates Ecotone and may post blobs");
+            }
+        } else if self.l1_beacon.is_some() ||
+            self.l1_beacon_cold.is_some() ||
+            !self.l1_beacon_fallbacks.is_empty()
+        {
+            warn!("The chain never activates Ecotone, the L1 beacon API is not used");
+        }
+        Ok(())
+    }
+
     /// Validate the jwt secret if specified by exchanging capabilities with the engine.
     /// Since the engine client will fail if the jwt token is invalid, this allows to ensure
     /// that the jwt token passed as a cli arg is correct.
     pub async fn validate_jwt(&self, config: &RollupConfig) -> anyhow::Result<JwtSecret> {
         let jwt_secret = self.jwt_secret().ok_or(anyhow::anyhow!("Invalid JWT secret"))?;
-        let engine_client = kona_engine::EngineClient::new_http(
+        let engine_client = kona_engine::EngineClient::new_with_transport(
             self.l2_engine_rpc.clone(),
             self.l2_provider_rpc.clone(),
             self.l1_eth_rpc.clone(),
             Arc::new(config.clone()),
             jwt_secret,
+            &self.engine_transport_flags.transport()?,
         );
 
         let exchange = || async {
@@ -194,8 +796,23 @@ impl NodeCommand {
 
     /// Run the Node subcommand.
     pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        self.run_with_l1_clients(args, None).await
+    }
+
+    /// Runs the Node subcommand like [`Self::run`], with the given [`L1Clients`] shared with the
+    /// other nodes of the process, if any, instead of clients of its own.
+    pub async fn run_with_l1_clients(
+        mut self,
+        args: &GlobalArgs,
+        l1_clients: Option<L1Clients>,
+    ) -> anyhow::Result<()> {
+        self.open_datadir(args)?;
         let cfg = self.get_l2_config(args)?;
+        self.validate_l1_beacon(&cfg)?;
+        self.preflight(&cfg).await?;
         let jwt_secret = self.validate_jwt(&cfg).await?;
+        let task_timeouts = self.engine_task_timeouts();
+        let safe_head_hint = self.safe_head_hint_source();
 
         let supervisor_rpc_config =
             match (self.supervisor_flags.as_rpc_config(), self.supervisor_flags.rpc_enabled) {
@@ -204,27 +821,188 @@ impl NodeCommand {
                 (_, false) => None,
             };
 
+        let reloadable_config = watch::Sender::new(ReloadableConfig {
+            sequencer_recover: self.sequencer_flags.recover,
+            unsafe_delay_secs: self.unsafe_delay_secs,
+            unsafe_delay_blocks: self.unsafe_delay_blocks,
+            ..Default::default()
+        });
+        self.spawn_config_reloader(&reloadable_config)?;
+
         self.p2p_flags.check_ports()?;
         let p2p_config = self.p2p_flags.config(&cfg, args, Some(self.l1_eth_rpc.clone())).await?;
         let rpc_config = self.rpc_flags.into();
 
         let runtime_interval =
             std::time::Duration::from_secs(self.l1_runtime_config_reload_interval);
+        if self.engine_build_budget.is_nan() || self.engine_build_budget <= 0.0 {
+            anyhow::bail!("Build budget must be positive, got {}", self.engine_build_budget);
+        }
 
-        RollupNode::builder(cfg)
+        let beacon = self.beacon_flags.beacon(cfg.l2_chain_id);
+        let chain_id = cfg.l2_chain_id;
+        let mut builder = RollupNode::builder(cfg)
             .with_jwt_secret(jwt_secret)
+            .with_engine_transport(self.engine_transport_flags.transport()?)
             .with_l1_provider_rpc_url(self.l1_eth_rpc)
-            .with_l1_beacon_api_url(self.l1_beacon)
             .with_l2_provider_rpc_url(self.l2_provider_rpc)
             .with_l2_engine_rpc_url(self.l2_engine_rpc)
             .with_runtime_load_interval(runtime_interval)
+            .with_sync_mode(self.sync_mode)
+            .with_unsafe_buffer_capacity(self.unsafe_buffer_capacity)
+            .with_envelope_store_capacity(self.envelope_store_capacity)
+            .with_safe_head_retention(self.safe_head_retention)
+            .with_sequencer_l1_confs(self.sequencer_flags.l1_confs)
+            .with_l1_confirmation_depth(self.l1_confirmation_depth)
+            .with_shutdown_grace_period(std::time::Duration::from_secs(self.shutdown_grace_period))
+            .with_reset_timeout(std::time::Duration::from_secs(self.derivation_reset_timeout))
+            .with_reloadable_config(reloadable_config)
             .with_p2p_config(p2p_config)
             .with_rpc_config(rpc_config)
-            .with_supervisor_rpc_config(supervisor_rpc_config.unwrap_or_default())
-            .build()
-            .start()
-            .await
-            .map_err(Into::into)
+            .with_bus_config(self.bus_flags.into())
+            .with_build_budget(BuildBudget::new(self.engine_build_budget))
+            .with_consistency_check(self.engine_consistency_check)
+            .with_engine_task_timeouts(task_timeouts)
+            .with_syncing_buffer_config(self.syncing_buffer_config())
+            .with_el_sync_config(ElSyncConfig {
+                poll_interval: std::time::Duration::from_secs(
+                    self.engine_el_sync_poll_interval.max(1),
+                ),
+                stall_timeout: std::time::Duration::from_secs(self.engine_el_sync_stall_timeout),
+            })
+            .with_supervisor_rpc_config(supervisor_rpc_config.unwrap_or_default());
+        if self.engine_resync_lag > 0 {
+            builder = builder.with_unsafe_lag_config(UnsafeLagConfig {
+                threshold: self.engine_resync_lag,
+                timeout: std::time::Duration::from_secs(self.engine_resync_timeout),
+            });
+        }
+        if let Some(dir) = self.unsafe_buffer_dir {
+            builder = builder.with_unsafe_buffer_dir(dir);
+        }
+        if let Some(dir) = self.envelope_store_dir {
+            builder = builder.with_envelope_store_dir(dir);
+        }
+        if let Some(path) = self.safe_head_index_path {
+            builder = builder.with_safe_head_index_path(path);
+        }
+        if self.engine_shadow_lookahead > 0 {
+            builder = builder.with_engine_shadow_lookahead(std::time::Duration::from_secs(
+                self.engine_shadow_lookahead,
+            ));
+        }
+        if let Some(source) = safe_head_hint {
+            builder = builder.with_safe_head_hint(source);
+        }
+        if let Some(path) = self.finality_path {
+            builder = builder.with_finality_path(path);
+        }
+        if self.derivation_memory_ceiling > 0 {
+            builder = builder.with_derivation_memory_ceiling(
+                self.derivation_memory_ceiling.saturating_mul(1 << 20),
+            );
+        }
+        if let Some(url) = self.l1_cold_rpc {
+            builder = builder.with_l1_cold_provider_rpc_url(url, self.l1_cold_rpc_after_blocks);
+        }
+        if let Some(url) = self.l1_beacon {
+            builder = builder.with_l1_beacon_api_url(url);
+        }
+        if let Some(url) = self.l1_beacon_cold {
+            builder = builder.with_l1_cold_beacon_api_url(url, self.l1_beacon_cold_after);
+        }
+        builder = builder.with_l1_beacon_fallback_api_urls(self.l1_beacon_fallbacks);
+        builder = builder.with_sidecar_verification(self.l1_beacon_sidecar_verification);
+        if let Some(l1_clients) = l1_clients {
+            builder = builder.with_l1_provider(l1_clients.provider);
+            if let Some(beacon) = l1_clients.beacon {
+                builder = builder.with_l1_beacon_client(beacon);
+            }
+        }
+        if self.engine_sync_check_interval > 0 {
+            builder = builder.with_maintenance_task(SyncStatusCheck {
+                interval: std::time::Duration::from_secs(self.engine_sync_check_interval),
+            });
+        }
+        if self.engine_forkchoice_refresh_interval > 0 {
+            builder = builder.with_maintenance_task(ForkchoiceRefresh {
+                interval: std::time::Duration::from_secs(self.engine_forkchoice_refresh_interval),
+            });
+        }
+        if self.backfill_interval > 0 {
+            builder = builder.with_backfill_config(BackfillConfig {
+                interval: std::time::Duration::from_secs(self.backfill_interval),
+                depth: self.backfill_depth,
+            });
+        }
+        if let Some(rollup_rpc) = self.shadow_rollup_rpc {
+            builder = builder.with_shadow_config(ShadowConfig {
+                rollup_rpc,
+                l2_rpc: self.shadow_l2_rpc,
+                interval: std::time::Duration::from_secs(self.shadow_interval.max(1)),
+            });
+        }
+        if self.watchdog_halt_timeout > 0 {
+            builder = builder.with_chain_halt_config(ChainHaltConfig {
+                timeout: std::time::Duration::from_secs(self.watchdog_halt_timeout),
+                reset_threshold: self.watchdog_reset_threshold,
+            });
+        }
+        if let Some(path) = self.journal_path {
+            let journal = EventJournal::open(&path, self.journal_max_size.saturating_mul(1 << 20))
+                .map_err(|e| anyhow::anyhow!("Failed to open event journal {path:?}: {e}"))?;
+            builder = builder.with_journal(journal);
+        }
+        if let Some(path) = self.l1_record {
+            let recorder = L1Recorder::open(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to open L1 archive {path:?}: {e}"))?;
+            builder = builder.with_l1_recorder(recorder);
+        }
+        if self.restart_window > 0 {
+            builder = builder.with_restart_config(RestartConfig {
+                path: args.datadir().restart_checkpoint_path(),
+                backlog_path: args.datadir().attributes_backlog_path(),
+                window: std::time::Duration::from_secs(self.restart_window),
+            });
+        }
+        if let Some(beacon) = beacon {
+            builder = builder.with_extension(beacon);
+        }
+        if let Some(notifier) = SystemdNotifier::from_env() {
+            builder = builder.with_extension(notifier);
+        }
+        builder = self.export_flags.apply(builder, chain_id).await?;
+
+        builder.build().start().await.map_err(Into::into)
+    }
+
+    /// Opens the datadir, migrating it to the current layout. If the datadir is set explicitly, the
+    /// stores whose own flags are unset default to their location in the datadir.
+    fn open_datadir(&mut self, args: &GlobalArgs) -> anyhow::Result<()> {
+        let datadir = args.datadir();
+        if let Some(from) = datadir.open()? {
+            info!(
+                target: "datadir",
+                from,
+                to = DATADIR_VERSION,
+                dir = %datadir.chain_dir().display(),
+                "Migrated datadir"
+            );
+        }
+        if args.datadir.is_some() {
+            self.p2p_flags.bootstore.get_or_insert_with(|| datadir.root.clone());
+            self.rpc_flags.admin_persistence.get_or_insert_with(|| datadir.admin_state_path());
+            self.unsafe_buffer_dir.get_or_insert_with(|| datadir.unsafe_buffer_dir());
+            self.envelope_store_dir.get_or_insert_with(|| datadir.envelope_store_dir());
+            self.safe_head_index_path.get_or_insert_with(|| datadir.safe_head_index_path());
+            self.finality_path.get_or_insert_with(|| datadir.finality_path());
+            self.journal_path.get_or_insert_with(|| datadir.journal_path());
+        }
+        // Graceful restarts rely on the unsafe payloads surviving the restart.
+        if self.restart_window > 0 {
+            self.unsafe_buffer_dir.get_or_insert_with(|| datadir.unsafe_buffer_dir());
+        }
+        Ok(())
     }
 
     /// Get the L2 rollup config, either from a file or the superchain registry.
@@ -258,6 +1036,42 @@ impl NodeCommand {
         Self::default_jwt_secret()
     }
 
+    /// Returns the [`EngineTaskTimeouts`] configured by the `--engine.timeout.*` flags.
+    pub fn engine_task_timeouts(&self) -> EngineTaskTimeouts {
+        [
+            (EngineTaskKind::ForkchoiceUpdate, self.engine_timeout_forkchoice),
+            (EngineTaskKind::InsertUnsafe, self.engine_timeout_insert),
+            (EngineTaskKind::BuildBlock, self.engine_timeout_build),
+            (EngineTaskKind::Consolidate, self.engine_timeout_consolidate),
+            (EngineTaskKind::Finalize, self.engine_timeout_finalize),
+        ]
+        .into_iter()
+        .filter(|(_, millis)| *millis > 0)
+        .fold(
+            EngineTaskTimeouts::default()
+                .with_policy(TaskTimeoutPolicy::Retry(self.engine_timeout_retries)),
+            |timeouts, (kind, millis)| timeouts.with(kind, Duration::from_millis(millis)),
+        )
+    }
+
+    /// Returns the [`SyncingBufferConfig`] configured by the `--engine.syncing-*` flags.
+    pub const fn syncing_buffer_config(&self) -> SyncingBufferConfig {
+        SyncingBufferConfig {
+            capacity: self.engine_syncing_buffer,
+            initial_backoff: Duration::from_millis(self.engine_syncing_backoff),
+            max_backoff: Duration::from_millis(self.engine_syncing_max_backoff),
+        }
+    }
+
+    /// Returns the [`SafeHeadHintSource`] configured by the `--sync.*` flags, if any.
+    pub fn safe_head_hint_source(&self) -> Option<SafeHeadHintSource> {
+        self.sync_safe_head_hint.map(SafeHeadHintSource::Static).or_else(|| {
+            self.sync_trusted_rpc
+                .clone()
+                .map(|url| SafeHeadHintSource::Rpc { url, l1_block: self.sync_trusted_l1_block })
+        })
+    }
+
     /// Uses the current directory to attempt to read
     /// the JWT secret from a file named `jwt.hex`.
     /// If the file is not found, it will return `None`.
@@ -284,6 +1098,9 @@ impl NodeCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_eips::BlockNumHash;
+    use alloy_primitives::B256;
+    use kona_node_service::bus::{BusConfig, ChannelConfig, OverflowPolicy};
 
     const fn default_flags() -> &'static [&'static str] {
         &[
@@ -306,8 +1123,23 @@ mod tests {
 
     #[test]
     fn test_node_cli_missing_l1_beacon() {
-        let err = NodeCommand::try_parse_from(["node", "--l1-eth-rpc", "http://localhost:8545"])
-            .unwrap_err();
+        let args = NodeCommand::try_parse_from([
+            "node",
+            "--l1-eth-rpc",
+            "http://localhost:8545",
+            "--l2-engine-rpc",
+            "http://localhost:8551",
+            "--l2-provider-rpc",
+            "http://localhost:8545",
+        ])
+        .unwrap();
+        assert_eq!(args.l1_beacon, None);
+
+        // The beacon API is only required by chains that post blobs.
+        let mut config = RollupConfig::default();
+        assert!(args.validate_l1_beacon(&config).is_ok());
+        config.hardforks.ecotone_time = Some(10);
+        let err = args.validate_l1_beacon(&config).unwrap_err();
         assert!(err.to_string().contains("--l1-beacon"));
     }
 
@@ -344,6 +1176,18 @@ mod tests {
         let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
         assert_eq!(args.l2_engine_kind, EngineKind::Geth);
         assert_eq!(args.l1_runtime_config_reload_interval, 600);
+        assert_eq!(args.shutdown_grace_period, 10);
+        assert_eq!(args.backfill_interval, 0);
+        assert_eq!(args.sync_mode, SyncMode::ExecutionLayer);
+        assert_eq!(args.role(), "validator");
+    }
+
+    #[test]
+    fn test_node_cli_sequencer_role() {
+        let args = NodeCommand::parse_from(
+            ["node", "--sequencer.enabled"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert_eq!(args.role(), "sequencer");
     }
 
     #[test]
@@ -357,6 +1201,304 @@ mod tests {
         assert_eq!(args.l1_runtime_config_reload_interval, 0);
     }
 
+    #[test]
+    fn test_node_cli_shutdown_grace_period() {
+        let args = NodeCommand::parse_from(
+            ["node", "--shutdown.grace-period", "30"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert_eq!(args.shutdown_grace_period, 30);
+    }
+
+    #[test]
+    fn test_node_cli_derivation_reset_timeout() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.derivation_reset_timeout, 30);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--derivation.reset-timeout", "5"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.derivation_reset_timeout, 5);
+    }
+
+    #[test]
+    fn test_node_cli_unsafe_buffer() {
+        let args = NodeCommand::parse_from(
+            ["node", "--unsafe-buffer.capacity", "64", "--unsafe-buffer.dir", "/tmp/unsafe"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.unsafe_buffer_capacity, 64);
+        assert_eq!(args.unsafe_buffer_dir, Some(PathBuf::from("/tmp/unsafe")));
+    }
+
+    #[test]
+    fn test_node_cli_envelope_store() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.envelope_store_capacity, DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY);
+        assert_eq!(args.envelope_store_dir, None);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--envelope-store.capacity", "16", "--envelope-store.dir", "/tmp/envelopes"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.envelope_store_capacity, 16);
+        assert_eq!(args.envelope_store_dir, Some(PathBuf::from("/tmp/envelopes")));
+    }
+
+    #[test]
+    fn test_node_cli_safe_head_index() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.safe_head_retention, SafeHeadRetention::default());
+        assert_eq!(args.safe_head_index_path, None);
+
+        let args = NodeCommand::parse_from(
+            [
+                "node",
+                "--safe-head-index.retention",
+                "full",
+                "--safe-head-index.path",
+                "/tmp/safe-heads.jsonl",
+            ]
+            .iter()
+            .chain(default_flags().iter())
+            .copied(),
+        );
+        assert_eq!(args.safe_head_retention, SafeHeadRetention::Full);
+        assert_eq!(args.safe_head_index_path, Some(PathBuf::from("/tmp/safe-heads.jsonl")));
+
+        let args = NodeCommand::parse_from(
+            ["node", "--safe-head-index.retention", "64"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.safe_head_retention, SafeHeadRetention::Epochs(64));
+
+        let err = NodeCommand::try_parse_from(
+            ["node", "--safe-head-index.retention", "0"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_node_cli_finality_path() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.finality_path, None);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--finality.path", "/tmp/finality.json"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.finality_path, Some(PathBuf::from("/tmp/finality.json")));
+    }
+
+    #[test]
+    fn test_node_cli_derivation_memory_ceiling() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.derivation_memory_ceiling, 0);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--derivation.memory-ceiling", "512"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.derivation_memory_ceiling, 512);
+    }
+
+    #[test]
+    fn test_node_cli_journal() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.journal_path, None);
+        assert_eq!(args.journal_max_size, 16);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--journal.path", "/tmp/journal.jsonl", "--journal.max-size", "4"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.journal_path, Some(PathBuf::from("/tmp/journal.jsonl")));
+        assert_eq!(args.journal_max_size, 4);
+    }
+
+    #[test]
+    fn test_node_cli_l1_record() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.l1_record, None);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--l1.record", "/tmp/l1.archive"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.l1_record, Some(PathBuf::from("/tmp/l1.archive")));
+    }
+
+    #[test]
+    fn test_node_cli_l1_confirmation_depth() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.l1_confirmation_depth, 0);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--l1.confirmation-depth", "12"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert_eq!(args.l1_confirmation_depth, 12);
+    }
+
+    #[test]
+    fn test_node_cli_beacon() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.beacon_flags, BeaconArgs::default());
+
+        let args = NodeCommand::parse_from(
+            [
+                "node",
+                "--beacon.endpoint",
+                "http://localhost:8080/heartbeat",
+                "--beacon.interval",
+                "60",
+            ]
+            .iter()
+            .chain(default_flags().iter())
+            .copied(),
+        );
+        assert_eq!(
+            args.beacon_flags.beacon_endpoint,
+            Some(Url::parse("http://localhost:8080/heartbeat").unwrap())
+        );
+        assert_eq!(args.beacon_flags.beacon_interval, 60);
+        assert!(args.beacon_flags.beacon(10).is_some());
+    }
+
+    #[test]
+    fn test_node_cli_build_budget() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.engine_build_budget, DEFAULT_BUILD_BUDGET_FRACTION);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--engine.build-budget", "0.8"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert_eq!(args.engine_build_budget, 0.8);
+    }
+
+    #[test]
+    fn test_node_cli_backfill() {
+        let args = NodeCommand::parse_from(
+            ["node", "--backfill.interval", "5", "--backfill.depth", "1000"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.backfill_interval, 5);
+        assert_eq!(args.backfill_depth, 1000);
+    }
+
+    #[test]
+    fn test_node_cli_watchdog() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.watchdog_halt_timeout, 0);
+        assert_eq!(args.watchdog_reset_threshold, 3);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--watchdog.halt-timeout", "600", "--watchdog.reset-threshold", "5"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.watchdog_halt_timeout, 600);
+        assert_eq!(args.watchdog_reset_threshold, 5);
+    }
+
+    #[test]
+    fn test_node_cli_preflight() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert!(!args.preflight_skip);
+        assert!(!args.preflight_strict);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--preflight.strict"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert!(args.preflight_strict);
+
+        let err = NodeCommand::try_parse_from(
+            ["node", "--preflight.strict", "--preflight.skip"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn test_node_cli_bus() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(BusConfig::from(args.bus_flags), BusConfig::default());
+
+        let args = NodeCommand::parse_from(
+            [
+                "node",
+                "--channel.unsafe-blocks.capacity",
+                "64",
+                "--channel.unsafe-blocks.policy",
+                "drop-oldest",
+            ]
+            .iter()
+            .chain(default_flags().iter())
+            .copied(),
+        );
+        let bus = BusConfig::from(args.bus_flags);
+        assert_eq!(
+            bus.unsafe_blocks,
+            ChannelConfig::new(64).with_policy(OverflowPolicy::DropOldest)
+        );
+
+        assert!(
+            NodeCommand::try_parse_from(
+                ["node", "--channel.attributes.capacity", "0"]
+                    .iter()
+                    .chain(default_flags().iter())
+                    .copied(),
+            )
+            .is_err()
+        );
+
+        for flag in ["--channel.attributes.policy", "--channel.derivation-signals.policy"] {
+            for policy in ["drop-oldest", "error"] {
+                assert!(
+                    NodeCommand::try_parse_from(
+                        ["node", flag, policy].iter().chain(default_flags().iter()).copied(),
+                    )
+                    .is_err()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_node_cli_reload_config_file() {
+        let args = NodeCommand::parse_from(
+            ["node", "--reload.config-file", "reload.json"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.reload_config_file, Some(PathBuf::from("reload.json")));
+    }
+
     #[test]
     fn test_node_cli_engine_kind() {
         let args = NodeCommand::parse_from(
@@ -364,4 +1506,309 @@ mod tests {
         );
         assert_eq!(args.l2_engine_kind, EngineKind::Reth);
     }
+
+    #[test]
+    fn test_node_cli_sync_mode() {
+        let args = NodeCommand::parse_from(
+            ["node", "--syncmode", "consensus"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert_eq!(args.sync_mode, SyncMode::Consensus);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--syncmode", "auto"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert_eq!(args.sync_mode, SyncMode::Auto);
+    }
+
+    #[test]
+    fn test_node_cli_sidecar_verification() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.l1_beacon_sidecar_verification, SidecarVerification::Strict);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--l1.beacon.sidecar-verification", "warn"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.l1_beacon_sidecar_verification, SidecarVerification::Warn);
+
+        let err = NodeCommand::try_parse_from(
+            ["node", "--l1.beacon.sidecar-verification", "lenient"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid sidecar verification"));
+    }
+
+    #[test]
+    fn test_node_cli_engine_maintenance() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.engine_sync_check_interval, 30);
+        assert_eq!(args.engine_forkchoice_refresh_interval, 0);
+
+        let args = NodeCommand::parse_from(
+            [
+                "node",
+                "--engine.sync-check-interval",
+                "0",
+                "--engine.forkchoice-refresh-interval",
+                "60",
+            ]
+            .iter()
+            .chain(default_flags().iter())
+            .copied(),
+        );
+        assert_eq!(args.engine_sync_check_interval, 0);
+        assert_eq!(args.engine_forkchoice_refresh_interval, 60);
+    }
+
+    #[test]
+    fn test_node_cli_engine_el_sync() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.engine_el_sync_poll_interval, 5);
+        assert_eq!(args.engine_el_sync_stall_timeout, 120);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--engine.el-sync-poll-interval", "2", "--engine.el-sync-stall-timeout", "30"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.engine_el_sync_poll_interval, 2);
+        assert_eq!(args.engine_el_sync_stall_timeout, 30);
+    }
+
+    #[test]
+    fn test_node_cli_engine_resync() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.engine_resync_lag, 0);
+        assert_eq!(args.engine_resync_timeout, 60);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--engine.resync-lag", "1800", "--engine.resync-timeout", "120"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.engine_resync_lag, 1800);
+        assert_eq!(args.engine_resync_timeout, 120);
+    }
+
+    #[test]
+    fn test_node_cli_engine_task_timeouts() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(
+            args.engine_task_timeouts(),
+            EngineTaskTimeouts::default().with_policy(TaskTimeoutPolicy::Retry(3))
+        );
+
+        let args = NodeCommand::parse_from(
+            [
+                "node",
+                "--engine.timeout.build",
+                "1500",
+                "--engine.timeout.consolidate",
+                "4000",
+                "--engine.timeout.retries",
+                "0",
+            ]
+            .iter()
+            .chain(default_flags().iter())
+            .copied(),
+        );
+        let timeouts = args.engine_task_timeouts();
+        assert_eq!(timeouts.get(EngineTaskKind::BuildBlock), Some(Duration::from_millis(1500)));
+        assert_eq!(timeouts.get(EngineTaskKind::Consolidate), Some(Duration::from_secs(4)));
+        assert_eq!(timeouts.get(EngineTaskKind::InsertUnsafe), None);
+        assert_eq!(timeouts.policy, TaskTimeoutPolicy::Retry(0));
+    }
+
+    #[test]
+    fn test_node_cli_engine_syncing_buffer() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.syncing_buffer_config(), SyncingBufferConfig::default());
+
+        let args = NodeCommand::parse_from(
+            [
+                "node",
+                "--engine.syncing-buffer",
+                "0",
+                "--engine.syncing-backoff",
+                "250",
+                "--engine.syncing-max-backoff",
+                "4000",
+            ]
+            .iter()
+            .chain(default_flags().iter())
+            .copied(),
+        );
+        assert_eq!(
+            args.syncing_buffer_config(),
+            SyncingBufferConfig {
+                capacity: 0,
+                initial_backoff: Duration::from_millis(250),
+                max_backoff: Duration::from_secs(4),
+            }
+        );
+    }
+
+    #[test]
+    fn test_node_cli_engine_shadow_lookahead() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.engine_shadow_lookahead, 0);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--engine.shadow-lookahead", "86400"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.engine_shadow_lookahead, 86400);
+    }
+
+    #[test]
+    fn test_node_cli_engine_consistency_check() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert!(!args.engine_consistency_check);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--engine.consistency-check"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert!(args.engine_consistency_check);
+    }
+
+    #[test]
+    fn test_node_cli_safe_head_hint() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.safe_head_hint_source(), None);
+
+        let hint = SafeHeadHint {
+            l1_block: BlockNumHash { number: 100, hash: B256::repeat_byte(1) },
+            safe_head: BlockNumHash { number: 2_000, hash: B256::repeat_byte(2) },
+        };
+        let flag = hint.to_string();
+        let args = NodeCommand::parse_from(
+            ["node", "--sync.safe-head-hint", &flag].iter().chain(default_flags().iter()).copied(),
+        );
+        assert_eq!(args.safe_head_hint_source(), Some(SafeHeadHintSource::Static(hint)));
+
+        let args = NodeCommand::parse_from(
+            [
+                "node",
+                "--sync.trusted-rpc",
+                "http://localhost:9545",
+                "--sync.trusted-l1-block",
+                "100",
+            ]
+            .iter()
+            .chain(default_flags().iter())
+            .copied(),
+        );
+        assert_eq!(
+            args.safe_head_hint_source(),
+            Some(SafeHeadHintSource::Rpc {
+                url: Url::parse("http://localhost:9545").unwrap(),
+                l1_block: Some(100),
+            })
+        );
+
+        let conflicting =
+            ["node", "--sync.safe-head-hint", &flag, "--sync.trusted-rpc", "http://a"];
+        let err =
+            NodeCommand::try_parse_from(conflicting.iter().chain(default_flags().iter()).copied())
+                .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+        assert!(
+            NodeCommand::try_parse_from(
+                ["node", "--sync.safe-head-hint", "2000@100"]
+                    .iter()
+                    .chain(default_flags().iter())
+                    .copied()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_node_cli_unsafe_delay() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.unsafe_delay_secs, 0);
+        assert_eq!(args.unsafe_delay_blocks, 0);
+
+        let args = NodeCommand::parse_from(
+            ["node", "--unsafe-delay.seconds", "12", "--unsafe-delay.blocks", "4"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(args.unsafe_delay_secs, 12);
+        assert_eq!(args.unsafe_delay_blocks, 4);
+    }
+
+    #[test]
+    fn test_node_cli_cold_tiers() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.l1_cold_rpc, None);
+        assert_eq!(args.l1_beacon_cold, None);
+
+        let args = NodeCommand::parse_from(
+            [
+                "node",
+                "--l1.cold-rpc",
+                "http://archive:8545",
+                "--l1.cold-rpc.after-blocks",
+                "64",
+                "--l1.beacon.cold",
+                "http://archiver:5052",
+            ]
+            .iter()
+            .chain(default_flags().iter())
+            .copied(),
+        );
+        assert_eq!(args.l1_cold_rpc, Some(Url::parse("http://archive:8545").unwrap()));
+        assert_eq!(args.l1_cold_rpc_after_blocks, 64);
+        assert_eq!(args.l1_beacon_cold, Some(Url::parse("http://archiver:5052").unwrap()));
+        assert_eq!(args.l1_beacon_cold_after, 1_209_600);
+    }
+
+    #[test]
+    fn test_node_cli_beacon_fallbacks() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert!(args.l1_beacon_fallbacks.is_empty());
+
+        let args = NodeCommand::parse_from(
+            ["node", "--l1.beacon.fallbacks", "http://beacon:5052,http://archiver:5052"]
+                .iter()
+                .chain(default_flags().iter())
+                .copied(),
+        );
+        assert_eq!(
+            args.l1_beacon_fallbacks,
+            [
+                Url::parse("http://beacon:5052").unwrap(),
+                Url::parse("http://archiver:5052").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_node_cli_restart_window() {
+        let args = NodeCommand::parse_from(["node"].iter().chain(default_flags().iter()).copied());
+        assert_eq!(args.restart_window, 0);
+
+        let mut args = NodeCommand::parse_from(
+            ["node", "--restart.window", "120"].iter().chain(default_flags().iter()).copied(),
+        );
+        assert_eq!(args.restart_window, 120);
+
+        let root = std::env::temp_dir().join(format!("kona-restart-{}", std::process::id()));
+        let global =
+            GlobalArgs { datadir: Some(root.clone()), l2_chain_id: 10, ..Default::default() };
+        args.open_datadir(&global).unwrap();
+        assert_eq!(args.unsafe_buffer_dir, Some(global.datadir().unsafe_buffer_dir()));
+        std::fs::remove_dir_all(root).unwrap();
+    }
 }
@@ -1,7 +1,13 @@
 //! Registry Subcommand
 
 use crate::flags::GlobalArgs;
-use clap::Parser;
+use anyhow::{Context, bail};
+use clap::{Args, Parser, Subcommand};
+use kona_genesis::{RollupConfig, Superchains};
+use kona_registry::Registry;
+use serde::Serialize;
+use serde_json::Value;
+use std::{fmt, fs::File, path::PathBuf};
 
 /// The `registry` Subcommand
 ///
@@ -11,10 +17,89 @@ use clap::Parser;
 ///
 /// ```sh
 /// kona-node registry [FLAGS] [OPTIONS]
+/// kona-node --l2-chain-id 10 registry diff --rollup-cfg rollup.json
 /// ```
 #[derive(Parser, Default, PartialEq, Debug, Clone)]
 #[command(about = "Lists the OP Stack chains available in the superchain-registry")]
-pub struct RegistryCommand;
+pub struct RegistryCommand {
+    /// The registry action to perform. Lists the chains if unset.
+    #[command(subcommand)]
+    pub action: Option<RegistryAction>,
+}
+
+/// The actions of the [`RegistryCommand`].
+#[derive(Subcommand, PartialEq, Debug, Clone)]
+pub enum RegistryAction {
+    /// Diffs a rollup config against the registry's config for the chain selected with
+    /// `--l2-chain-id`. Exits with a non-zero status if they differ.
+    Diff(RegistryDiffArgs),
+}
+
+/// The arguments of the `registry diff` action.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct RegistryDiffArgs {
+    /// Path to the rollup configuration file to check, as passed to the node.
+    #[arg(long, visible_alias = "rollup-cfg", env = "KONA_NODE_ROLLUP_CONFIG")]
+    pub l2_config_file: PathBuf,
+    /// Path or URL of the superchain configs to diff against, in the format of the embedded
+    /// `configs.json`. Defaults to the registry embedded in the binary.
+    #[arg(long = "registry")]
+    pub registry: Option<String>,
+    /// Prints the differences as JSON.
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+/// The kind of a [`ConfigDrift`], ordered by how likely it is to cause a consensus split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftKind {
+    /// A hardfork activation time.
+    Fork,
+    /// A field of the chain's genesis.
+    Genesis,
+    /// A contract address.
+    Address,
+    /// Any other field.
+    Parameter,
+}
+
+impl fmt::Display for DriftKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fork => write!(f, "fork"),
+            Self::Genesis => write!(f, "genesis"),
+            Self::Address => write!(f, "address"),
+            Self::Parameter => write!(f, "parameter"),
+        }
+    }
+}
+
+/// A field of a rollup config that differs from the registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigDrift {
+    /// The kind of the field.
+    pub kind: DriftKind,
+    /// The path of the field in the JSON rollup config, e.g. `genesis.l2.hash`.
+    pub field: String,
+    /// The value in the registry, or `null` if unset.
+    pub registry: Value,
+    /// The value in the local config, or `null` if unset.
+    pub local: Value,
+}
+
+impl fmt::Display for ConfigDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<9} {}: registry {}, local {}",
+            self.kind.to_string(),
+            self.field,
+            self.registry,
+            self.local
+        )
+    }
+}
 
 impl RegistryCommand {
     /// Initializes the logging system based on global arguments.
@@ -24,15 +109,151 @@ impl RegistryCommand {
     }
 
     /// Runs the subcommand.
-    pub fn run(self, _args: &GlobalArgs) -> anyhow::Result<()> {
-        let chains = kona_registry::CHAINS.chains.clone();
-        let mut table = tabled::Table::new(chains);
-        table.with(tabled::settings::Style::modern());
-        table.modify(
-            tabled::settings::object::Columns::first(),
-            tabled::settings::Alignment::right(),
-        );
-        println!("{}", table);
+    pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        match self.action {
+            Some(RegistryAction::Diff(diff)) => diff.run(args).await,
+            None => {
+                let chains = kona_registry::CHAINS.chains.clone();
+                let mut table = tabled::Table::new(chains);
+                table.with(tabled::settings::Style::modern());
+                table.modify(
+                    tabled::settings::object::Columns::first(),
+                    tabled::settings::Alignment::right(),
+                );
+                println!("{}", table);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl RegistryDiffArgs {
+    /// Diffs the local rollup config against the registry.
+    pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        let path = &self.l2_config_file;
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open l2 config file {}", path.display()))?;
+        let local: RollupConfig = serde_json::from_reader(file)
+            .with_context(|| format!("Failed to parse l2 config file {}", path.display()))?;
+        let registry = self.registry_config(args.l2_chain_id).await?;
+
+        let drift = diff_rollup_configs(&registry, &local)?;
+        let chain_id = args.l2_chain_id;
+        if self.json {
+            println!("{}", serde_json::json!({ "chain_id": chain_id, "drift": drift }));
+        } else if drift.is_empty() {
+            println!("{} matches the registry config of chain {chain_id}", path.display());
+        } else {
+            println!("{} differs from the registry config of chain {chain_id}:", path.display());
+            drift.iter().for_each(|d| println!("  {d}"));
+        }
+
+        if !drift.is_empty() {
+            bail!("Rollup config differs from the registry in {} fields", drift.len());
+        }
         Ok(())
     }
+
+    /// Returns the registry's rollup config for the given chain.
+    pub async fn registry_config(&self, chain_id: u64) -> anyhow::Result<RollupConfig> {
+        let Some(source) = &self.registry else {
+            return kona_registry::ROLLUP_CONFIGS
+                .get(&chain_id)
+                .cloned()
+                .with_context(|| format!("Chain {chain_id} is not in the embedded registry"));
+        };
+
+        let contents = if source.starts_with("http://") || source.starts_with("https://") {
+            reqwest::get(source)
+                .await
+                .and_then(|r| r.error_for_status())
+                .with_context(|| format!("Failed to download {source}"))?
+                .text()
+                .await?
+        } else {
+            std::fs::read_to_string(source).with_context(|| format!("Failed to read {source}"))?
+        };
+        let superchains: Superchains = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse superchain configs from {source}"))?;
+        Registry::from_superchains(Registry::read_chain_list(), superchains)
+            .rollup_configs
+            .remove(&chain_id)
+            .with_context(|| format!("Chain {chain_id} is not in the registry at {source}"))
+    }
+}
+
+/// Returns the fields of the local rollup config that differ from the registry, ordered by kind.
+pub fn diff_rollup_configs(
+    registry: &RollupConfig,
+    local: &RollupConfig,
+) -> anyhow::Result<Vec<ConfigDrift>> {
+    let mut drift = Vec::new();
+    diff_values("", &serde_json::to_value(registry)?, &serde_json::to_value(local)?, &mut drift);
+    drift.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.field.cmp(&b.field)));
+    Ok(drift)
+}
+
+/// Recursively diffs two JSON values, recording the differing leaves.
+fn diff_values(path: &str, registry: &Value, local: &Value, drift: &mut Vec<ConfigDrift>) {
+    if let (Value::Object(a), Value::Object(b)) = (registry, local) {
+        let mut keys = a.keys().chain(b.keys()).collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let field = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+            let (a, b) = (a.get(key).unwrap_or(&Value::Null), b.get(key).unwrap_or(&Value::Null));
+            diff_values(&field, a, b, drift);
+        }
+        return;
+    }
+    if registry == local {
+        return;
+    }
+
+    let is_address = |v: &Value| v.as_str().is_some_and(|s| s.len() == 42 && s.starts_with("0x"));
+    let kind = if path.starts_with("genesis.") {
+        DriftKind::Genesis
+    } else if path.ends_with("_time") {
+        DriftKind::Fork
+    } else if is_address(registry) || is_address(local) {
+        DriftKind::Address
+    } else {
+        DriftKind::Parameter
+    };
+    drift.push(ConfigDrift {
+        kind,
+        field: path.to_string(),
+        registry: registry.clone(),
+        local: local.clone(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_registry_diff() {
+        let registry = kona_registry::ROLLUP_CONFIGS.get(&10).unwrap();
+        assert!(diff_rollup_configs(registry, registry).unwrap().is_empty());
+
+        let mut local = registry.clone();
+        local.hardforks.isthmus_time = None;
+        local.batch_inbox_address = address!("0000000000000000000000000000000000000001");
+        local.genesis.l2.number += 1;
+        local.channel_timeout += 1;
+        let drift = diff_rollup_configs(registry, &local).unwrap();
+        let fields = drift.iter().map(|d| (d.kind, d.field.as_str())).collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                (DriftKind::Fork, "isthmus_time"),
+                (DriftKind::Genesis, "genesis.l2.number"),
+                (DriftKind::Address, "batch_inbox_address"),
+                (DriftKind::Parameter, "channel_timeout"),
+            ]
+        );
+        assert_eq!(drift[0].local, Value::Null);
+    }
 }
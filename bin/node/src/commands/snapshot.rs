@@ -0,0 +1,360 @@
+//! Snapshot Subcommand
+
+use crate::flags::GlobalArgs;
+use alloy_eips::BlockNumberOrTag;
+use alloy_rpc_types_engine::{ForkchoiceState, ForkchoiceUpdated, JwtSecret};
+use anyhow::{Context, bail};
+use clap::{Args, Parser, Subcommand};
+use discv5::Enr;
+use kona_engine::{EngineClient, EngineForkchoiceVersion};
+use kona_peers::BootStore;
+use kona_protocol::L2BlockInfo;
+use op_alloy_provider::ext::engine::OpEngineApi;
+use std::{fs::File, path::PathBuf, sync::Arc};
+use tracing::info;
+use url::Url;
+
+/// The current version of the [`NodeSnapshot`] format.
+pub const SNAPSHOT_VERSION: u64 = 1;
+
+/// The `snapshot` Subcommand
+///
+/// The `snapshot` subcommand exports a portable snapshot of a node's state, and imports it on
+/// another machine. This allows follower nodes to be bootstrapped without re-deriving the chain
+/// from genesis.
+///
+/// # Usage
+///
+/// ```sh
+/// kona-node snapshot export [FLAGS] [OPTIONS] --output <FILE>
+/// kona-node snapshot import [FLAGS] [OPTIONS] --input <FILE>
+/// ```
+#[derive(Parser, PartialEq, Debug, Clone)]
+#[command(about = "Exports or imports a snapshot of the node's state")]
+pub struct SnapshotCommand {
+    /// The snapshot action to perform.
+    #[command(subcommand)]
+    pub action: SnapshotAction,
+}
+
+/// The actions of the [`SnapshotCommand`].
+#[derive(Subcommand, PartialEq, Debug, Clone)]
+pub enum SnapshotAction {
+    /// Exports a snapshot of the node's state to a file.
+    Export {
+        /// The file to write the snapshot to.
+        #[arg(long, short)]
+        output: PathBuf,
+        /// The connection arguments for the execution client.
+        #[command(flatten)]
+        args: SnapshotArgs,
+    },
+    /// Imports a snapshot of the node's state from a file.
+    ///
+    /// The snapshot's peers are merged into the local bootstore, and the execution client is
+    /// pointed at the snapshot's heads with a forkchoice update. The execution client then syncs
+    /// up to the snapshot's unsafe head, from which the node can start derivation.
+    Import {
+        /// The file to read the snapshot from.
+        #[arg(long, short)]
+        input: PathBuf,
+        /// The connection arguments for the execution client.
+        #[command(flatten)]
+        args: SnapshotArgs,
+    },
+}
+
+/// The arguments used to connect to the execution client and bootstore.
+#[derive(Args, PartialEq, Debug, Clone)]
+pub struct SnapshotArgs {
+    /// URL of the L1 execution client RPC API.
+    #[arg(long, visible_alias = "l1", env = "KONA_NODE_L1_ETH_RPC")]
+    pub l1_eth_rpc: Url,
+    /// URL of the engine API endpoint of an L2 execution client.
+    #[arg(long, visible_alias = "l2", env = "KONA_NODE_L2_ENGINE_RPC")]
+    pub l2_engine_rpc: Url,
+    /// An L2 RPC Url.
+    #[arg(long, visible_alias = "l2.provider", env = "KONA_NODE_L2_ETH_RPC")]
+    pub l2_provider_rpc: Url,
+    /// JWT secret for the auth-rpc endpoint of the execution client.
+    /// This MUST be a valid path to a file containing the hex-encoded JWT secret.
+    #[arg(long, visible_alias = "l2.jwt-secret", env = "KONA_NODE_L2_ENGINE_AUTH")]
+    pub l2_engine_jwt_secret: PathBuf,
+    /// The directory of the bootstore.
+    #[arg(long = "p2p.bootstore", env = "KONA_NODE_P2P_BOOTSTORE")]
+    pub bootstore: Option<PathBuf>,
+}
+
+impl SnapshotArgs {
+    /// Creates an [`EngineClient`] for the chain in the [`GlobalArgs`].
+    fn engine_client(&self, args: &GlobalArgs) -> anyhow::Result<EngineClient> {
+        let Some(cfg) = args.rollup_config() else {
+            bail!("Failed to find rollup config for chain ID {}", args.l2_chain_id);
+        };
+        let jwt_secret = JwtSecret::from_file(&self.l2_engine_jwt_secret)
+            .map_err(|e| anyhow::anyhow!("Failed to read JWT secret: {e}"))?;
+        Ok(EngineClient::new_http(
+            self.l2_engine_rpc.clone(),
+            self.l2_provider_rpc.clone(),
+            self.l1_eth_rpc.clone(),
+            Arc::new(cfg),
+            jwt_secret,
+        ))
+    }
+}
+
+/// A portable snapshot of a node's state.
+///
+/// The derivation checkpoint of the snapshot is the L1 origin of its safe head.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeSnapshot {
+    /// The version of the snapshot format.
+    pub version: u64,
+    /// The L2 chain ID.
+    pub chain_id: u64,
+    /// The unsafe head of the engine.
+    pub unsafe_head: L2BlockInfo,
+    /// The safe head of the engine.
+    pub safe_head: L2BlockInfo,
+    /// The finalized head of the engine.
+    pub finalized_head: L2BlockInfo,
+    /// The peers in the node's bootstore.
+    pub peers: Vec<Enr>,
+}
+
+impl SnapshotCommand {
+    /// Initializes the logging system based on global arguments.
+    pub fn init_logs(&self, args: &GlobalArgs) -> anyhow::Result<()> {
+        args.init_tracing(None)?;
+        Ok(())
+    }
+
+    /// Runs the subcommand.
+    pub async fn run(self, args: &GlobalArgs) -> anyhow::Result<()> {
+        match self.action {
+            SnapshotAction::Export { output, args: snapshot_args } => {
+                Self::export(args, &snapshot_args, output).await
+            }
+            SnapshotAction::Import { input, args: snapshot_args } => {
+                Self::import(args, &snapshot_args, input).await
+            }
+        }
+    }
+
+    /// Exports a [`NodeSnapshot`] to the given file.
+    pub async fn export(
+        args: &GlobalArgs,
+        snapshot_args: &SnapshotArgs,
+        output: PathBuf,
+    ) -> anyhow::Result<()> {
+        let client = snapshot_args.engine_client(args)?;
+        let head = async |label: BlockNumberOrTag| -> anyhow::Result<L2BlockInfo> {
+            client
+                .l2_block_info_by_label(label)
+                .await?
+                .with_context(|| format!("Execution client has no {label} block"))
+        };
+
+        let mut bootstore =
+            BootStore::from_chain_id(args.l2_chain_id, snapshot_args.bootstore.clone(), vec![]);
+        let snapshot = NodeSnapshot {
+            version: SNAPSHOT_VERSION,
+            chain_id: args.l2_chain_id,
+            unsafe_head: head(BlockNumberOrTag::Latest).await?,
+            safe_head: head(BlockNumberOrTag::Safe).await?,
+            finalized_head: head(BlockNumberOrTag::Finalized).await?,
+            peers: bootstore.peers().iter().cloned().collect(),
+        };
+
+        let file = File::create(&output)
+            .with_context(|| format!("Failed to create snapshot file {}", output.display()))?;
+        serde_json::to_writer_pretty(file, &snapshot)?;
+
+        info!(
+            target: "snapshot",
+            unsafe_head = snapshot.unsafe_head.block_info.number,
+            safe_head = snapshot.safe_head.block_info.number,
+            finalized_head = snapshot.finalized_head.block_info.number,
+            peers = snapshot.peers.len(),
+            path = %output.display(),
+            "Exported node snapshot"
+        );
+        Ok(())
+    }
+
+    /// Imports a [`NodeSnapshot`] from the given file.
+    pub async fn import(
+        args: &GlobalArgs,
+        snapshot_args: &SnapshotArgs,
+        input: PathBuf,
+    ) -> anyhow::Result<()> {
+        let file = File::open(&input)
+            .with_context(|| format!("Failed to open snapshot file {}", input.display()))?;
+        let snapshot: NodeSnapshot = serde_json::from_reader(file)?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            bail!("Unsupported snapshot version {}", snapshot.version);
+        }
+        if snapshot.chain_id != args.l2_chain_id {
+            bail!(
+                "Snapshot is for chain ID {}, but the node is configured for chain ID {}",
+                snapshot.chain_id,
+                args.l2_chain_id
+            );
+        }
+
+        // Merge the snapshot's peers into the local bootstore.
+        let mut bootstore = BootStore::from_chain_id(
+            snapshot.chain_id,
+            snapshot_args.bootstore.clone(),
+            snapshot.peers.clone(),
+        );
+        bootstore.sync();
+
+        // Point the execution client at the snapshot's heads, so that it syncs the chain up to
+        // the unsafe head.
+        let client = snapshot_args.engine_client(args)?;
+        let update = Self::update_forkchoice(&client, &snapshot).await?;
+
+        info!(
+            target: "snapshot",
+            unsafe_head = snapshot.unsafe_head.block_info.number,
+            safe_head = snapshot.safe_head.block_info.number,
+            finalized_head = snapshot.finalized_head.block_info.number,
+            peers = snapshot.peers.len(),
+            status = %update.payload_status.status,
+            "Imported node snapshot"
+        );
+        Ok(())
+    }
+
+    /// Sends a forkchoice update pointing the execution client at the heads of the given
+    /// [`NodeSnapshot`], with the `engine_forkchoiceUpdated` version of the hardfork active at its
+    /// unsafe head.
+    async fn update_forkchoice(
+        client: &EngineClient,
+        snapshot: &NodeSnapshot,
+    ) -> anyhow::Result<ForkchoiceUpdated> {
+        let forkchoice = ForkchoiceState {
+            head_block_hash: snapshot.unsafe_head.block_info.hash,
+            safe_block_hash: snapshot.safe_head.block_info.hash,
+            finalized_block_hash: snapshot.finalized_head.block_info.hash,
+        };
+        let version = EngineForkchoiceVersion::from_cfg(
+            client.cfg(),
+            snapshot.unsafe_head.block_info.timestamp,
+        );
+        let update = match version {
+            EngineForkchoiceVersion::V1 => client.fork_choice_updated_v1(forkchoice, None).await,
+            EngineForkchoiceVersion::V2 => client.fork_choice_updated_v2(forkchoice, None).await,
+            EngineForkchoiceVersion::V3 => client.fork_choice_updated_v3(forkchoice, None).await,
+        };
+        update.context("Failed to send forkchoice update to the execution client")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use kona_engine::MockEngineClient;
+    use kona_genesis::{HardForkConfig, RollupConfig};
+    use kona_protocol::BlockInfo;
+
+    #[test]
+    fn test_snapshot_export_args() {
+        let cmd = SnapshotCommand::parse_from([
+            "snapshot",
+            "export",
+            "--output",
+            "snapshot.json",
+            "--l1-eth-rpc",
+            "http://localhost:8545",
+            "--l2-engine-rpc",
+            "http://localhost:8551",
+            "--l2-provider-rpc",
+            "http://localhost:9545",
+            "--l2-engine-jwt-secret",
+            "jwt.hex",
+        ]);
+        let SnapshotAction::Export { output, args } = cmd.action else {
+            panic!("expected export action");
+        };
+        assert_eq!(output, PathBuf::from("snapshot.json"));
+        assert_eq!(args.l2_engine_jwt_secret, PathBuf::from("jwt.hex"));
+        assert_eq!(args.bootstore, None);
+    }
+
+    #[test]
+    fn test_snapshot_serde_round_trip() {
+        let snapshot = NodeSnapshot {
+            version: SNAPSHOT_VERSION,
+            chain_id: 10,
+            unsafe_head: L2BlockInfo::default(),
+            safe_head: L2BlockInfo::default(),
+            finalized_head: L2BlockInfo::default(),
+            peers: vec![],
+        };
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<NodeSnapshot>(&json).unwrap(), snapshot);
+    }
+
+    /// Returns a snapshot whose heads are identified by their hashes, with an unsafe head at the
+    /// given timestamp.
+    fn snapshot(timestamp: u64) -> NodeSnapshot {
+        let head = |byte, timestamp| L2BlockInfo {
+            block_info: BlockInfo {
+                hash: B256::repeat_byte(byte),
+                timestamp,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        NodeSnapshot {
+            version: SNAPSHOT_VERSION,
+            chain_id: 10,
+            unsafe_head: head(1, timestamp),
+            safe_head: head(2, 0),
+            finalized_head: head(3, 0),
+            peers: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_import_forkchoice_version() {
+        let cfg = RollupConfig {
+            hardforks: HardForkConfig {
+                canyon_time: Some(10),
+                ecotone_time: Some(20),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for (timestamp, method) in [
+            (0, "engine_forkchoiceUpdatedV1"),
+            (10, "engine_forkchoiceUpdatedV2"),
+            (20, "engine_forkchoiceUpdatedV3"),
+        ] {
+            let mock = MockEngineClient::new(Arc::new(cfg.clone()));
+            let update = SnapshotCommand::update_forkchoice(&mock.client(), &snapshot(timestamp))
+                .await
+                .unwrap();
+            assert!(update.is_valid());
+
+            // The execution client is pointed at the heads of the snapshot.
+            let calls = mock.calls();
+            assert_eq!(calls.len(), 1);
+            assert_eq!(calls[0].method, method);
+            let forkchoice: ForkchoiceState =
+                serde_json::from_value(calls[0].params[0].clone()).unwrap();
+            assert_eq!(
+                forkchoice,
+                ForkchoiceState {
+                    head_block_hash: B256::repeat_byte(1),
+                    safe_block_hash: B256::repeat_byte(2),
+                    finalized_block_hash: B256::repeat_byte(3),
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Configuration files for the node.
+//!
+//! A [`ConfigFile`] sets the values of the node's CLI flags from a TOML file, passed with
+//! `--config` or `KONA_NODE_CONFIG`. Each key of the file is the long name of a flag, or one of
+//! its aliases, and nested tables are joined with dots, so that the following are equivalent:
+//!
+//! ```toml
+//! l1-eth-rpc = "http://localhost:8545"
+//! "p2p.listen.tcp" = 9222
+//!
+//! [p2p.listen]
+//! tcp = 9222
+//! ```
+//!
+//! Values from the file take the lowest precedence: `file < env < CLI`. The values of the file
+//! are validated before the CLI is parsed, and errors point at the offending key.
+
+use anyhow::{Context, anyhow, bail};
+use clap::{Arg, ArgAction, ArgMatches, Command, parser::ValueSource};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    ffi::OsString,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// The long name of the flag that sets the path of the [`ConfigFile`].
+pub const CONFIG_FLAG: &str = "config";
+
+/// The environment variable that sets the path of the [`ConfigFile`].
+pub const CONFIG_ENV: &str = "KONA_NODE_CONFIG";
+
+/// The flags whose values are redacted by [`ConfigFile::dump`].
+const SECRET_FLAGS: [&str; 2] = ["p2p.priv.raw", "supervisor.jwt.secret"];
+
+/// A TOML file that sets the values of CLI flags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigFile {
+    /// The path the file was loaded from.
+    pub path: PathBuf,
+    /// The values of the file, keyed by their dotted key.
+    pub values: BTreeMap<String, Vec<String>>,
+}
+
+impl ConfigFile {
+    /// Returns the path of the config file passed in the given CLI arguments, or in the
+    /// [`CONFIG_ENV`] environment variable.
+    pub fn locate(args: &[OsString]) -> Option<PathBuf> {
+        let flag = format!("--{CONFIG_FLAG}");
+        let mut args = args.iter().map(|arg| arg.to_string_lossy());
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                break;
+            }
+            if arg == flag {
+                return args.next().map(|path| PathBuf::from(path.as_ref()));
+            }
+            if let Some(path) = arg.strip_prefix(&flag).and_then(|rest| rest.strip_prefix('=')) {
+                return Some(PathBuf::from(path));
+            }
+        }
+        std::env::var_os(CONFIG_ENV).map(PathBuf::from)
+    }
+
+    /// Loads the config file at the given path.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(path, &contents)
+    }
+
+    /// Parses the contents of the config file at the given path.
+    pub fn parse(path: impl Into<PathBuf>, contents: &str) -> anyhow::Result<Self> {
+        let path = path.into();
+        let table: toml::Table = contents
+            .parse()
+            .map_err(|e| anyhow!("Failed to parse config file {}: {e}", path.display()))?;
+        let mut values = BTreeMap::new();
+        flatten(None, table, &mut values)
+            .map_err(|e| anyhow!("Invalid config file {}: {e}", path.display()))?;
+        Ok(Self { path, values })
+    }
+
+    /// Sets the values of the file as the defaults of the matching arguments of the command and
+    /// its subcommands, so that they are overridden by environment variables and CLI flags.
+    ///
+    /// ## Errors
+    ///
+    /// - If a key of the file matches no argument.
+    /// - If a value of the file is invalid for its argument.
+    pub fn apply(&self, cmd: Command) -> anyhow::Result<Command> {
+        let mut used = BTreeSet::new();
+        let cmd = self.apply_to(cmd, &mut used)?;
+        if let Some(key) = self.values.keys().find(|key| !used.contains(*key)) {
+            bail!("Unknown key `{key}` in config file {}", self.path.display());
+        }
+        Ok(cmd)
+    }
+
+    /// Applies the file to the arguments of the command, and recursively to its subcommands,
+    /// recording the keys that matched an argument.
+    fn apply_to(&self, mut cmd: Command, used: &mut BTreeSet<String>) -> anyhow::Result<Command> {
+        let matched = cmd
+            .get_arguments()
+            .filter_map(|arg| {
+                let names =
+                    arg.get_long().into_iter().chain(arg.get_all_aliases().into_iter().flatten());
+                names
+                    .into_iter()
+                    .find(|name| self.values.contains_key(*name))
+                    .map(|name| (arg.get_id().clone(), name.to_string()))
+            })
+            .collect::<Vec<_>>();
+
+        for (id, key) in matched {
+            let values = &self.values[&key];
+            if let Some(arg) = cmd.get_arguments().find(|arg| arg.get_id() == &id) {
+                self.validate(&key, arg, values)?;
+            }
+            // A required argument is satisfied by the value of the file.
+            cmd = cmd.mut_arg(id, |arg| arg.default_values(values.clone()).required(false));
+            used.insert(key);
+        }
+
+        let subcommands =
+            cmd.get_subcommands().map(|sub| sub.get_name().to_string()).collect::<Vec<_>>();
+        for name in subcommands {
+            let mut res = Ok(());
+            cmd = cmd.mut_subcommand(name, |sub| match self.apply_to(sub.clone(), used) {
+                Ok(sub) => sub,
+                Err(e) => {
+                    res = Err(e);
+                    sub
+                }
+            });
+            res?;
+        }
+        Ok(cmd)
+    }
+
+    /// Validates the values of the given key with the value parser of its argument.
+    fn validate(&self, key: &str, arg: &Arg, values: &[String]) -> anyhow::Result<()> {
+        let parser = arg.get_value_parser();
+        let mut cmd = Command::new(key.to_string()).no_binary_name(true).arg(
+            Arg::new("value")
+                .value_parser(parser.clone())
+                .num_args(1..)
+                .allow_hyphen_values(true)
+                .action(ArgAction::Append),
+        );
+        for value in values {
+            if let Err(e) = cmd.try_get_matches_from_mut([value]) {
+                let reason = e.source().map(ToString::to_string).unwrap_or_else(|| {
+                    parser.possible_values().map_or_else(
+                        || "invalid value".to_string(),
+                        |values| {
+                            let values =
+                                values.map(|v| v.get_name().to_string()).collect::<Vec<_>>();
+                            format!("expected one of {}", values.join(", "))
+                        },
+                    )
+                });
+                bail!(
+                    "Invalid value `{value}` for `{key}` in config file {}: {reason}",
+                    self.path.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the effective values of the arguments of the command, as parsed into the given
+    /// matches, as a TOML config file sorted by key. Each value is annotated with its source:
+    /// `cli`, `env`, `file` or `default`.
+    ///
+    /// The command must have been built, so that it contains the global arguments of its parents.
+    pub fn dump(file: Option<&Self>, cmd: &Command, matches: &ArgMatches) -> String {
+        let mut lines = BTreeMap::new();
+        for arg in cmd.get_arguments() {
+            let Some(long) =
+                arg.get_long().filter(|long| !["help", "version", CONFIG_FLAG].contains(long))
+            else {
+                continue;
+            };
+            let (Some(source), Some(raw)) = (
+                matches.value_source(arg.get_id().as_str()),
+                matches.try_get_raw(arg.get_id().as_str()).ok().flatten(),
+            ) else {
+                continue;
+            };
+
+            let source = match source {
+                ValueSource::CommandLine => "cli",
+                ValueSource::EnvVariable => "env",
+                _ if file.is_some_and(|file| {
+                    arg.get_all_aliases()
+                        .into_iter()
+                        .flatten()
+                        .chain([long])
+                        .any(|name| file.values.contains_key(name))
+                }) =>
+                {
+                    "file"
+                }
+                _ => "default",
+            };
+            let value = if SECRET_FLAGS.contains(&long) {
+                toml::Value::String("<redacted>".to_string())
+            } else {
+                let mut values = raw.map(|raw| to_toml(&raw.to_string_lossy())).collect::<Vec<_>>();
+                match (values.len(), arg.get_num_args().is_some_and(|n| n.max_values() > 1)) {
+                    (1, false) => values.remove(0),
+                    _ => toml::Value::Array(values),
+                }
+            };
+
+            let key = if long.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                long.to_string()
+            } else {
+                toml::Value::String(long.to_string()).to_string()
+            };
+            lines.insert(long, format!("{key} = {value} # {source}"));
+        }
+        lines.into_values().fold(String::new(), |mut out, line| {
+            let _ = writeln!(out, "{line}");
+            out
+        })
+    }
+}
+
+/// Flattens the given table into dotted keys, converting each value to the strings that are
+/// parsed by the argument of the key.
+fn flatten(
+    prefix: Option<&str>,
+    table: toml::Table,
+    out: &mut BTreeMap<String, Vec<String>>,
+) -> anyhow::Result<()> {
+    for (key, value) in table {
+        let key = prefix.map_or_else(|| key.clone(), |prefix| format!("{prefix}.{key}"));
+        let values = match value {
+            toml::Value::Table(table) => {
+                flatten(Some(&key), table, out)?;
+                continue;
+            }
+            toml::Value::Array(values) => values
+                .into_iter()
+                .map(|value| {
+                    from_toml(value).ok_or_else(|| anyhow!("unsupported value for `{key}`"))
+                })
+                .collect::<anyhow::Result<_>>()?,
+            value => {
+                vec![from_toml(value).ok_or_else(|| anyhow!("unsupported value for `{key}`"))?]
+            }
+        };
+        if out.insert(key.clone(), values).is_some() {
+            bail!("duplicate key `{key}`");
+        }
+    }
+    Ok(())
+}
+
+/// Converts a scalar TOML value to the string parsed by an argument.
+fn from_toml(value: toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        toml::Value::Datetime(d) => Some(d.to_string()),
+        toml::Value::Array(_) | toml::Value::Table(_) => None,
+    }
+}
+
+/// Converts the raw value of an argument to the TOML value it is rendered as.
+fn to_toml(raw: &str) -> toml::Value {
+    if let Ok(b) = raw.parse() {
+        return toml::Value::Boolean(b);
+    }
+    if let Ok(i) = raw.parse() {
+        return toml::Value::Integer(i);
+    }
+    match raw.parse::<f64>() {
+        Ok(f) if f.is_finite() && raw.contains('.') => toml::Value::Float(f),
+        _ => toml::Value::String(raw.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::CommandFactory;
+
+    const CONFIG: &str = r#"
+        l1-eth-rpc = "http://localhost:8545"
+        l1-beacon = "http://localhost:5052"
+        l2-engine-rpc = "http://localhost:8551"
+        l2-provider-rpc = "http://localhost:8545"
+
+        [p2p.listen]
+        tcp = 9333
+
+        [journal]
+        max-size = 4
+    "#;
+
+    #[test]
+    fn test_config_file_flatten() {
+        let file = ConfigFile::parse("config.toml", CONFIG).unwrap();
+        assert_eq!(file.values["p2p.listen.tcp"], vec!["9333"]);
+        assert_eq!(file.values["journal.max-size"], vec!["4"]);
+    }
+
+    #[test]
+    fn test_config_file_locate() {
+        let args = ["kona-node", "--config=a.toml", "node"].map(OsString::from);
+        assert_eq!(ConfigFile::locate(&args), Some(PathBuf::from("a.toml")));
+        let args = ["kona-node", "node", "--config", "b.toml"].map(OsString::from);
+        assert_eq!(ConfigFile::locate(&args), Some(PathBuf::from("b.toml")));
+    }
+
+    #[test]
+    fn test_config_file_unknown_key() {
+        let file = ConfigFile::parse("config.toml", "[p2p]\nlisten.udp.typo = 1").unwrap();
+        let err = file.apply(Cli::command()).unwrap_err();
+        assert!(err.to_string().contains("`p2p.listen.udp.typo`"), "{err}");
+    }
+
+    #[test]
+    fn test_config_file_invalid_value() {
+        let file = ConfigFile::parse("config.toml", "journal.max-size = \"big\"").unwrap();
+        let err = file.apply(Cli::command()).unwrap_err();
+        assert!(err.to_string().contains("`journal.max-size`"), "{err}");
+    }
+
+    #[test]
+    fn test_config_file_precedence() {
+        let file = ConfigFile::parse("config.toml", CONFIG).unwrap();
+        let args = ["kona-node", "node", "--journal.max-size", "8"];
+        let matches = file.apply(Cli::command()).unwrap().get_matches_from(args);
+        let node = matches.subcommand_matches("node").unwrap();
+        assert_eq!(node.get_one::<u16>("listen_tcp_port"), Some(&9333));
+        assert_eq!(node.get_one::<u64>("journal_max_size"), Some(&8));
+        assert_eq!(node.value_source("journal_max_size"), Some(ValueSource::CommandLine));
+    }
+
+    #[test]
+    fn test_config_dump() {
+        let path = std::env::temp_dir().join(format!("kona-config-{}.toml", std::process::id()));
+        std::fs::write(&path, CONFIG).unwrap();
+        let config = format!("--config={}", path.display());
+        let cli = Cli::try_parse_with_config_from([
+            "kona-node",
+            config.as_str(),
+            "config",
+            "dump",
+            "--journal.max-size",
+            "8",
+        ])
+        .unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let crate::cli::Commands::Config(cmd) = cli.subcommand else {
+            panic!("expected the config subcommand");
+        };
+        let crate::commands::ConfigAction::Dump(dump) = cmd.action;
+        assert_eq!(dump.node.p2p_flags.listen_tcp_port, 9333);
+        assert!(dump.effective.contains("l1-eth-rpc = \"http://localhost:8545\" # file\n"));
+        assert!(dump.effective.contains("\"p2p.listen.tcp\" = 9333 # file\n"));
+        assert!(dump.effective.contains("\"journal.max-size\" = 8 # cli\n"));
+        assert!(dump.effective.contains("\"rpc.port\" = 9545 # default\n"));
+        assert!(dump.effective.contains("l2-chain-id = 10 # default\n"));
+    }
+}
@@ -0,0 +1,342 @@
+//! The node's data directory.
+//!
+//! Each chain has its own directory below the datadir root, laid out as follows:
+//!
+//! ```text
+//! <root>/<chain id>/
+//! ├── VERSION                   layout version of the directory
+//! ├── bootstore.json            peer store
+//! ├── engine/
+//! │   ├── admin-state.json      state changes made through the admin API
+//! │   ├── finality.json         L2 blocks awaiting finalization
+//! │   └── unsafe-payloads/      buffered unsafe payloads
+//! ├── derivation/               derivation checkpoints
+//! └── journal/
+//!     └── events.jsonl          event journal, and its rotated backup
+//! ```
+//!
+//! Directories written by older releases are migrated to the [`DATADIR_VERSION`] layout when
+//! opened. Directories written by newer releases are rejected.
+
+use anyhow::{Context, bail};
+use clap::ValueEnum;
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The current layout version of the datadir.
+pub const DATADIR_VERSION: u32 = 1;
+
+/// The name of the file recording the layout version of a chain's datadir.
+const VERSION_FILE: &str = "VERSION";
+
+/// A migration of the datadir layout from one version to the next, run against the chain's
+/// directory.
+type Migration = fn(&Path) -> io::Result<()>;
+
+/// The datadir migrations, where the migration at index `i` migrates from version `i` to
+/// version `i + 1`.
+///
+/// Version `0` is the unversioned layout of older releases, which only contained the peer store.
+const MIGRATIONS: [Migration; DATADIR_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Creates the component directories of the versioned layout. The peer store is kept in place.
+fn migrate_v0_to_v1(dir: &Path) -> io::Result<()> {
+    for component in ["engine", "derivation", "journal"] {
+        fs::create_dir_all(dir.join(component))?;
+    }
+    Ok(())
+}
+
+/// A component of the node's state stored in the [`Datadir`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DatadirComponent {
+    /// The admin API state, the L2 blocks awaiting finalization and buffered unsafe payloads.
+    Engine,
+    /// Derivation checkpoints.
+    Derivation,
+    /// The peer store.
+    Peers,
+    /// The event journal.
+    Journal,
+}
+
+impl fmt::Display for DatadirComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Engine => f.write_str("engine"),
+            Self::Derivation => f.write_str("derivation"),
+            Self::Peers => f.write_str("peers"),
+            Self::Journal => f.write_str("journal"),
+        }
+    }
+}
+
+/// The disk usage of a [`DatadirComponent`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComponentUsage {
+    /// The number of stored files.
+    pub files: usize,
+    /// The total size of the stored files, in bytes.
+    pub bytes: u64,
+}
+
+/// The data directory of the node for a single chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Datadir {
+    /// The root of the datadir, shared by all chains.
+    pub root: PathBuf,
+    /// The chain id.
+    pub chain_id: u64,
+}
+
+impl Datadir {
+    /// Returns the [`Datadir`] of the given chain below the given root, or below `~/.kona` if no
+    /// root is given.
+    pub fn new(root: Option<PathBuf>, chain_id: u64) -> Self {
+        let root = root.unwrap_or_else(|| {
+            let mut home = dirs::home_dir().expect("Failed to get home directory");
+            home.push(".kona");
+            home
+        });
+        Self { root, chain_id }
+    }
+
+    /// Returns the directory of the chain.
+    pub fn chain_dir(&self) -> PathBuf {
+        self.root.join(self.chain_id.to_string())
+    }
+
+    /// Returns the path of the peer store.
+    pub fn bootstore_path(&self) -> PathBuf {
+        self.chain_dir().join("bootstore.json")
+    }
+
+    /// Returns the path of the admin API state file.
+    pub fn admin_state_path(&self) -> PathBuf {
+        self.chain_dir().join("engine").join("admin-state.json")
+    }
+
+    /// Returns the path of the engine's restart checkpoint.
+    pub fn restart_checkpoint_path(&self) -> PathBuf {
+        self.chain_dir().join("engine").join("restart.json")
+    }
+
+    /// Returns the path of the derived attributes not executed yet at the last shutdown.
+    pub fn attributes_backlog_path(&self) -> PathBuf {
+        self.chain_dir().join("engine").join("attributes.json")
+    }
+
+    /// Returns the path of the L2 blocks awaiting finalization.
+    pub fn finality_path(&self) -> PathBuf {
+        self.chain_dir().join("engine").join("finality.json")
+    }
+
+    /// Returns the directory of the persisted unsafe payloads.
+    pub fn unsafe_buffer_dir(&self) -> PathBuf {
+        self.chain_dir().join("engine").join("unsafe-payloads")
+    }
+
+    /// Returns the directory of the persisted payload envelopes of the latest unsafe blocks.
+    pub fn envelope_store_dir(&self) -> PathBuf {
+        self.chain_dir().join("engine").join("payload-envelopes")
+    }
+
+    /// Returns the path of the safe head index.
+    pub fn safe_head_index_path(&self) -> PathBuf {
+        self.chain_dir().join("engine").join("safe-heads.jsonl")
+    }
+
+    /// Returns the directory of the derivation checkpoints.
+    pub fn derivation_dir(&self) -> PathBuf {
+        self.chain_dir().join("derivation")
+    }
+
+    /// Returns the path of the event journal.
+    pub fn journal_path(&self) -> PathBuf {
+        self.chain_dir().join("journal").join("events.jsonl")
+    }
+
+    /// Returns the paths stored by the given component.
+    pub fn paths(&self, component: DatadirComponent) -> Vec<PathBuf> {
+        match component {
+            DatadirComponent::Engine => {
+                vec![
+                    self.admin_state_path(),
+                    self.restart_checkpoint_path(),
+                    self.attributes_backlog_path(),
+                    self.finality_path(),
+                    self.unsafe_buffer_dir(),
+                    self.envelope_store_dir(),
+                    self.safe_head_index_path(),
+                ]
+            }
+            DatadirComponent::Derivation => vec![self.derivation_dir()],
+            DatadirComponent::Peers => vec![self.bootstore_path()],
+            DatadirComponent::Journal => {
+                let journal = self.journal_path();
+                let mut rotated = journal.clone().into_os_string();
+                rotated.push(".1");
+                vec![journal, rotated.into()]
+            }
+        }
+    }
+
+    /// Returns the layout version of the datadir, or `None` if it does not exist yet.
+    ///
+    /// A datadir without a version file was written by a release predating versioning, and has
+    /// version `0`.
+    pub fn version(&self) -> anyhow::Result<Option<u32>> {
+        let dir = self.chain_dir();
+        if !dir.exists() {
+            return Ok(None);
+        }
+        let path = dir.join(VERSION_FILE);
+        match fs::read_to_string(&path) {
+            Ok(version) => version
+                .trim()
+                .parse()
+                .map(Some)
+                .with_context(|| format!("Invalid datadir version in {}", path.display())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Some(0)),
+            Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Opens the datadir, creating it if it does not exist and migrating it to the
+    /// [`DATADIR_VERSION`] layout. Returns the version the datadir was migrated from, if any.
+    pub fn open(&self) -> anyhow::Result<Option<u32>> {
+        let dir = self.chain_dir();
+        let version = self.version()?;
+        let from = match version {
+            Some(version) if version > DATADIR_VERSION => bail!(
+                "Datadir {} has version {version}, which is newer than the supported version \
+                 {DATADIR_VERSION}",
+                dir.display()
+            ),
+            Some(DATADIR_VERSION) => return Ok(None),
+            Some(version) => version,
+            // A new datadir starts at version `0`, without any data to migrate.
+            None => 0,
+        };
+
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create datadir {}", dir.display()))?;
+        for (version, migrate) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+            migrate(&dir).with_context(|| {
+                format!("Failed to migrate datadir {} from version {version}", dir.display())
+            })?;
+            fs::write(dir.join(VERSION_FILE), format!("{}\n", version + 1))
+                .with_context(|| format!("Failed to write version of datadir {}", dir.display()))?;
+        }
+        Ok(version.map(|_| from))
+    }
+
+    /// Returns the disk usage of the given component.
+    pub fn usage(&self, component: DatadirComponent) -> io::Result<ComponentUsage> {
+        let mut usage = ComponentUsage::default();
+        for path in self.paths(component) {
+            add_usage(&path, &mut usage)?;
+        }
+        Ok(usage)
+    }
+
+    /// Removes the data stored by the given component, keeping its directories. The node must not
+    /// be running. Returns the disk usage of the removed data.
+    pub fn prune(&self, component: DatadirComponent) -> io::Result<ComponentUsage> {
+        let usage = self.usage(component)?;
+        for path in self.paths(component) {
+            match fs::symlink_metadata(&path) {
+                Ok(meta) if meta.is_dir() => {
+                    fs::remove_dir_all(&path)?;
+                    fs::create_dir_all(&path)?;
+                }
+                Ok(_) => fs::remove_file(&path)?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(usage)
+    }
+}
+
+/// Adds the disk usage of the file or directory at the given path to `usage`.
+fn add_usage(path: &Path, usage: &mut ComponentUsage) -> io::Result<()> {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            add_usage(&entry?.path(), usage)?;
+        }
+    } else {
+        usage.files += 1;
+        usage.bytes += meta.len();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datadir(name: &str) -> Datadir {
+        let root = std::env::temp_dir().join(format!("kona-datadir-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        Datadir::new(Some(root), 10)
+    }
+
+    #[test]
+    fn test_datadir_open_new() {
+        let datadir = datadir("new");
+        assert_eq!(datadir.version().unwrap(), None);
+        assert_eq!(datadir.open().unwrap(), None);
+        assert_eq!(datadir.version().unwrap(), Some(DATADIR_VERSION));
+        assert!(datadir.derivation_dir().is_dir());
+        assert_eq!(datadir.open().unwrap(), None);
+        fs::remove_dir_all(&datadir.root).unwrap();
+    }
+
+    #[test]
+    fn test_datadir_migrate_unversioned() {
+        let datadir = datadir("unversioned");
+        fs::create_dir_all(datadir.chain_dir()).unwrap();
+        fs::write(datadir.bootstore_path(), "[]").unwrap();
+        assert_eq!(datadir.version().unwrap(), Some(0));
+
+        assert_eq!(datadir.open().unwrap(), Some(0));
+        assert_eq!(datadir.version().unwrap(), Some(DATADIR_VERSION));
+        assert_eq!(fs::read_to_string(datadir.bootstore_path()).unwrap(), "[]");
+        fs::remove_dir_all(&datadir.root).unwrap();
+    }
+
+    #[test]
+    fn test_datadir_newer_version() {
+        let datadir = datadir("newer");
+        fs::create_dir_all(datadir.chain_dir()).unwrap();
+        fs::write(datadir.chain_dir().join(VERSION_FILE), "99").unwrap();
+        assert!(datadir.open().is_err());
+        fs::remove_dir_all(&datadir.root).unwrap();
+    }
+
+    #[test]
+    fn test_datadir_prune() {
+        let datadir = datadir("prune");
+        datadir.open().unwrap();
+        fs::create_dir_all(datadir.unsafe_buffer_dir()).unwrap();
+        fs::write(datadir.unsafe_buffer_dir().join("1.json"), "{}").unwrap();
+        fs::write(datadir.admin_state_path(), "{}").unwrap();
+        fs::write(datadir.bootstore_path(), "[]").unwrap();
+
+        let usage = datadir.usage(DatadirComponent::Engine).unwrap();
+        assert_eq!(usage, ComponentUsage { files: 2, bytes: 4 });
+        assert_eq!(datadir.prune(DatadirComponent::Engine).unwrap(), usage);
+        assert_eq!(datadir.usage(DatadirComponent::Engine).unwrap(), ComponentUsage::default());
+        assert!(datadir.unsafe_buffer_dir().is_dir());
+        assert!(datadir.bootstore_path().exists());
+        fs::remove_dir_all(&datadir.root).unwrap();
+    }
+}
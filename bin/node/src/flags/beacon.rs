@@ -0,0 +1,70 @@
+//! Health Beacon CLI Flags
+
+use crate::beacon::HealthBeacon;
+use clap::{Parser, builder::RangedU64ValueParser};
+use std::time::Duration;
+use url::Url;
+
+/// CLI flags for the opt-in health beacon.
+///
+/// When an endpoint is set, the node periodically posts an anonymous [`HealthReport`] to it, so
+/// that operators of large fleets get a lightweight external heartbeat of each node.
+///
+/// [`HealthReport`]: crate::beacon::HealthReport
+#[derive(Parser, Clone, Debug, PartialEq, Eq)]
+pub struct BeaconArgs {
+    /// HTTP endpoint to post health reports to, e.g. `https://fleet.example.com/heartbeat`. No
+    /// reports are sent if unset.
+    #[arg(long = "beacon.endpoint", env = "KONA_NODE_BEACON_ENDPOINT")]
+    pub beacon_endpoint: Option<Url>,
+    /// Interval (in seconds) between health reports.
+    #[arg(
+        long = "beacon.interval",
+        default_value = "300",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..),
+        env = "KONA_NODE_BEACON_INTERVAL"
+    )]
+    pub beacon_interval: u64,
+}
+
+impl Default for BeaconArgs {
+    fn default() -> Self {
+        // Construct default values using the clap parser.
+        // This works since none of the cli flags are required.
+        Self::parse_from::<[_; 0], &str>([])
+    }
+}
+
+impl BeaconArgs {
+    /// Returns the [`HealthBeacon`] reporting the health of the node of the given chain, or `None`
+    /// if the beacon is disabled.
+    pub fn beacon(&self, chain_id: u64) -> Option<HealthBeacon> {
+        let endpoint = self.beacon_endpoint.clone()?;
+        Some(HealthBeacon::new(endpoint, Duration::from_secs(self.beacon_interval), chain_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beacon_disabled_by_default() {
+        let args = BeaconArgs::default();
+        assert_eq!(args.beacon_endpoint, None);
+        assert_eq!(args.beacon_interval, 300);
+        assert!(args.beacon(10).is_none());
+    }
+
+    #[test]
+    fn test_beacon_zero_interval() {
+        let res = BeaconArgs::try_parse_from([
+            "beacon",
+            "--beacon.endpoint",
+            "http://localhost:8080",
+            "--beacon.interval",
+            "0",
+        ]);
+        assert!(res.is_err());
+    }
+}
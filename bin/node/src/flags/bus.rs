@@ -0,0 +1,94 @@
+//! Actor Channel CLI Flags
+
+use clap::{Parser, builder::RangedU64ValueParser};
+use kona_node_service::bus::{BusConfig, ChannelConfig, ChannelLimits, OverflowPolicy};
+
+/// CLI flags for the event channels between the node's actors.
+///
+/// Each channel has a capacity, and an overflow policy applied when it is full: `block` waits for
+/// the receiver to make room, `drop-oldest` drops the oldest queued event, and `error` fails the
+/// send. Only the channel of unsafe blocks accepts a lossy policy, since the node cannot recover
+/// from a lost payload attribute or derivation signal.
+#[derive(Parser, Clone, Debug, PartialEq, Eq)]
+pub struct BusArgs {
+    /// Capacity of the channel of unsafe blocks forwarded from the network to the engine.
+    #[arg(
+        long = "channel.unsafe-blocks.capacity",
+        default_value = "1024",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        env = "KONA_NODE_CHANNEL_UNSAFE_BLOCKS_CAPACITY"
+    )]
+    pub unsafe_blocks_capacity: usize,
+    /// Overflow policy of the channel of unsafe blocks forwarded from the network to the engine.
+    #[arg(
+        long = "channel.unsafe-blocks.policy",
+        default_value = "block",
+        env = "KONA_NODE_CHANNEL_UNSAFE_BLOCKS_POLICY"
+    )]
+    pub unsafe_blocks_policy: OverflowPolicy,
+    /// Capacity of the channel of payload attributes sent from derivation to the engine.
+    #[arg(
+        long = "channel.attributes.capacity",
+        default_value = "16",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        env = "KONA_NODE_CHANNEL_ATTRIBUTES_CAPACITY"
+    )]
+    pub attributes_capacity: usize,
+    /// Overflow policy of the channel of payload attributes sent from derivation to the engine.
+    /// Only `block` is allowed.
+    #[arg(
+        long = "channel.attributes.policy",
+        default_value = "block",
+        value_parser = lossless_policy,
+        env = "KONA_NODE_CHANNEL_ATTRIBUTES_POLICY"
+    )]
+    pub attributes_policy: OverflowPolicy,
+    /// Capacity of the channel of signals sent from the engine to derivation.
+    #[arg(
+        long = "channel.derivation-signals.capacity",
+        default_value = "16",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        env = "KONA_NODE_CHANNEL_DERIVATION_SIGNALS_CAPACITY"
+    )]
+    pub derivation_signals_capacity: usize,
+    /// Overflow policy of the channel of signals sent from the engine to derivation. Only `block`
+    /// is allowed.
+    #[arg(
+        long = "channel.derivation-signals.policy",
+        default_value = "block",
+        value_parser = lossless_policy,
+        env = "KONA_NODE_CHANNEL_DERIVATION_SIGNALS_POLICY"
+    )]
+    pub derivation_signals_policy: OverflowPolicy,
+}
+
+/// Parses an [`OverflowPolicy`], rejecting the policies that may lose events.
+fn lossless_policy(s: &str) -> Result<OverflowPolicy, String> {
+    let policy = s.parse::<OverflowPolicy>()?;
+    if !policy.is_lossless() {
+        return Err(format!("overflow policy {policy} may lose events, only block is allowed"));
+    }
+    Ok(policy)
+}
+
+impl Default for BusArgs {
+    fn default() -> Self {
+        // Construct default values using the clap parser.
+        // This works since none of the cli flags are required.
+        Self::parse_from::<[_; 0], &str>([])
+    }
+}
+
+impl From<BusArgs> for BusConfig {
+    fn from(args: BusArgs) -> Self {
+        Self {
+            unsafe_blocks: ChannelConfig::new(args.unsafe_blocks_capacity)
+                .with_policy(args.unsafe_blocks_policy),
+            attributes: ChannelConfig::new(args.attributes_capacity)
+                .with_policy(args.attributes_policy),
+            derivation_signals: ChannelConfig::new(args.derivation_signals_capacity)
+                .with_policy(args.derivation_signals_policy),
+            limits: ChannelLimits::default(),
+        }
+    }
+}
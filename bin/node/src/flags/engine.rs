@@ -0,0 +1,88 @@
+//! Engine Transport CLI Flags
+
+use anyhow::Context;
+use clap::Parser;
+use kona_engine::{EngineTls, EngineTransport, RequestSigner};
+use std::path::PathBuf;
+
+/// CLI flags hardening the transport to the execution client, beyond the JWT secret.
+///
+/// Mutual TLS is enabled when a client certificate and key are set, in which case the engine API
+/// and L2 RPC URLs may use `https`. Requests are signed when a signing key is set. Both apply to
+/// the engine API and L2 RPC endpoints of the execution client.
+#[derive(Parser, Clone, Debug, PartialEq, Eq)]
+pub struct EngineTransportArgs {
+    /// Path to the PEM-encoded client certificate chain presented to the execution client.
+    #[arg(long = "l2.engine-tls-cert", env = "KONA_NODE_L2_ENGINE_TLS_CERT", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+    /// Path to the PEM-encoded private key of the client certificate.
+    #[arg(long = "l2.engine-tls-key", env = "KONA_NODE_L2_ENGINE_TLS_KEY", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+    /// Path to the PEM-encoded CA certificates that the certificate of the execution client is
+    /// verified against. Defaults to the Mozilla root certificates.
+    #[arg(long = "l2.engine-tls-ca", env = "KONA_NODE_L2_ENGINE_TLS_CA", requires = "tls_cert")]
+    pub tls_ca: Option<PathBuf>,
+    /// Path to a file containing the hex-encoded key that requests to the execution client are
+    /// signed with, in the `X-Engine-Signature` header.
+    #[arg(long = "l2.engine-signing-key", env = "KONA_NODE_L2_ENGINE_SIGNING_KEY")]
+    pub signing_key: Option<PathBuf>,
+}
+
+impl Default for EngineTransportArgs {
+    fn default() -> Self {
+        // Construct default values using the clap parser.
+        // This works since none of the cli flags are required.
+        Self::parse_from::<[_; 0], &str>([])
+    }
+}
+
+impl EngineTransportArgs {
+    /// Loads the [`EngineTransport`] configured by the flags.
+    pub fn transport(&self) -> anyhow::Result<EngineTransport> {
+        let mut transport = EngineTransport::default();
+        if let (Some(cert), Some(key)) = (&self.tls_cert, &self.tls_key) {
+            let tls = EngineTls::from_pem_files(cert, key, self.tls_ca.as_deref())
+                .context("Failed to load the engine TLS configuration")?;
+            transport = transport.with_tls(tls);
+        }
+        if let Some(path) = &self.signing_key {
+            let signer = RequestSigner::from_hex_file(path)
+                .with_context(|| format!("Failed to load signing key {}", path.display()))?;
+            transport = transport.with_signer(signer);
+        }
+        Ok(transport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_plain_transport_by_default() {
+        let transport = EngineTransportArgs::default().transport().unwrap();
+        assert!(!transport.tls_enabled());
+        assert!(!transport.signing_enabled());
+    }
+
+    #[test]
+    fn test_tls_cert_requires_key() {
+        let res =
+            EngineTransportArgs::try_parse_from(["engine", "--l2.engine-tls-cert", "client.pem"]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_signing_key() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "deadbeef").unwrap();
+        let args = EngineTransportArgs::try_parse_from([
+            "engine",
+            "--l2.engine-signing-key",
+            file.path().to_str().unwrap(),
+        ])
+        .unwrap();
+        assert!(args.transport().unwrap().signing_enabled());
+    }
+}
@@ -0,0 +1,117 @@
+//! Chain Event Export CLI Flags
+
+use clap::Parser;
+use kona_node_service::RollupNodeBuilder;
+
+/// CLI flags for the export of chain events to message queues.
+///
+/// When a NATS server or Kafka brokers are set, the node publishes the payload attributes it
+/// derives, the updates of its unsafe and safe heads, and the reorgs it observes to them. NATS
+/// and Kafka support are only compiled in with the `export-nats` and `export-kafka` features.
+#[derive(Parser, Clone, Debug, PartialEq, Eq)]
+pub struct ExportArgs {
+    /// Address of the NATS server to publish chain events to, e.g. `nats://localhost:4222`.
+    #[arg(long = "export.nats-url", env = "KONA_NODE_EXPORT_NATS_URL")]
+    pub nats_url: Option<String>,
+    /// Comma-separated Kafka bootstrap brokers to publish chain events to, e.g.
+    /// `localhost:9092`.
+    #[arg(
+        long = "export.kafka-brokers",
+        env = "KONA_NODE_EXPORT_KAFKA_BROKERS",
+        conflicts_with = "nats_url"
+    )]
+    pub kafka_brokers: Option<String>,
+    /// Prefix of the NATS subjects or Kafka topics that chain events are published under, as
+    /// `<prefix>.<event kind>`.
+    #[arg(long = "export.prefix", default_value = "kona", env = "KONA_NODE_EXPORT_PREFIX")]
+    pub prefix: String,
+    /// Number of derived attributes queued for export before the oldest are dropped.
+    #[arg(long = "export.capacity", default_value = "256", env = "KONA_NODE_EXPORT_CAPACITY")]
+    pub capacity: usize,
+}
+
+impl Default for ExportArgs {
+    fn default() -> Self {
+        // Construct default values using the clap parser.
+        // This works since none of the cli flags are required.
+        Self::parse_from::<[_; 0], &str>([])
+    }
+}
+
+impl ExportArgs {
+    /// Returns `true` if chain events are exported.
+    pub const fn enabled(&self) -> bool {
+        self.nats_url.is_some() || self.kafka_brokers.is_some()
+    }
+
+    /// Registers the chain event exporter of the node of the given chain on the builder, if
+    /// export is enabled. Fails if the configured message queue cannot be reached, or is not
+    /// supported by this build.
+    #[allow(unused_variables, unused_mut)]
+    pub async fn apply(
+        &self,
+        mut builder: RollupNodeBuilder,
+        chain_id: u64,
+    ) -> anyhow::Result<RollupNodeBuilder> {
+        if !self.enabled() {
+            return Ok(builder);
+        }
+
+        #[cfg(any(feature = "export-nats", feature = "export-kafka"))]
+        let config = kona_node_service::ExportConfig {
+            chain_id,
+            prefix: self.prefix.clone(),
+            capacity: self.capacity.max(1),
+        };
+
+        if let Some(url) = &self.nats_url {
+            #[cfg(not(feature = "export-nats"))]
+            anyhow::bail!("NATS export requested, but the node was built without `export-nats`");
+            #[cfg(feature = "export-nats")]
+            {
+                let sink = kona_node_service::NatsSink::connect(url)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to connect to NATS at {url}: {e}"))?;
+                builder = builder
+                    .with_extension(kona_node_service::ChainEventExporter::new(sink, config));
+            }
+        } else if let Some(brokers) = &self.kafka_brokers {
+            #[cfg(not(feature = "export-kafka"))]
+            anyhow::bail!("Kafka export requested, but the node was built without `export-kafka`");
+            #[cfg(feature = "export-kafka")]
+            {
+                let sink = kona_node_service::KafkaSink::new(brokers).map_err(|e| {
+                    anyhow::anyhow!("Failed to create Kafka producer for {brokers}: {e}")
+                })?;
+                builder = builder
+                    .with_extension(kona_node_service::ChainEventExporter::new(sink, config));
+            }
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_disabled_by_default() {
+        let args = ExportArgs::default();
+        assert!(!args.enabled());
+        assert_eq!(args.prefix, "kona");
+        assert_eq!(args.capacity, 256);
+    }
+
+    #[test]
+    fn test_export_single_queue() {
+        let res = ExportArgs::try_parse_from([
+            "export",
+            "--export.nats-url",
+            "nats://localhost:4222",
+            "--export.kafka-brokers",
+            "localhost:9092",
+        ]);
+        assert!(res.is_err());
+    }
+}
@@ -1,12 +1,13 @@
 //! Global arguments for the CLI.
 
-use crate::metrics::CliMetrics;
+use crate::{datadir::Datadir, metrics::CliMetrics};
 use alloy_primitives::Address;
 use clap::Parser;
-use kona_cli::log::LogArgs;
+use kona_cli::{ReloadableRegistry, log::LogArgs};
 use kona_genesis::RollupConfig;
 use kona_registry::{OPCHAINS, ROLLUP_CONFIGS};
-use tracing_subscriber::EnvFilter;
+use std::path::PathBuf;
+use tracing_subscriber::{EnvFilter, Layer};
 
 /// Global arguments for the CLI.
 #[derive(Parser, Default, Clone, Debug)]
@@ -24,6 +25,20 @@ pub struct GlobalArgs {
         help = "The L2 chain ID to use"
     )]
     pub l2_chain_id: u64,
+    /// Named network preset from the superchain registry, e.g. `op-mainnet` or `base-sepolia`.
+    /// Sets the L2 chain id, and with it the rollup config, bootnodes and unsafe block signer of
+    /// the network, each of which can still be overridden by its own flag.
+    #[arg(long = "network", global = true, env = "KONA_NODE_NETWORK")]
+    pub network: Option<super::Network>,
+    /// Path to a TOML config file setting the values of any of the CLI flags, keyed by their long
+    /// name. Values from the file are overridden by environment variables and CLI flags.
+    #[arg(long = "config", global = true, env = "KONA_NODE_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Root of the node's datadir, holding one versioned directory per chain. Defaults to
+    /// `~/.kona`. When set, the node stores its peer store, admin API state, buffered unsafe
+    /// payloads and event journal in the datadir, unless their own flags are set.
+    #[arg(long = "datadir", global = true, env = "KONA_NODE_DATADIR")]
+    pub datadir: Option<PathBuf>,
     /// Embed the override flags globally to provide override values adjacent to the configs.
     #[command(flatten)]
     pub override_args: super::OverrideArgs,
@@ -35,6 +50,24 @@ impl GlobalArgs {
         self.v.init_tracing(filter)
     }
 
+    /// Initializes the telemetry stack with a log filter that can be reloaded at runtime.
+    pub fn init_reloadable_tracing(&self, filter: Option<EnvFilter>) -> anyhow::Result<()> {
+        self.v.init_reloadable_tracing(filter)
+    }
+
+    /// Initializes the telemetry stack like [`Self::init_reloadable_tracing`], with an extra
+    /// [`Layer`], e.g. to export traces.
+    pub fn init_reloadable_tracing_with<L>(
+        &self,
+        filter: Option<EnvFilter>,
+        layer: L,
+    ) -> anyhow::Result<()>
+    where
+        L: Layer<ReloadableRegistry> + Send + Sync + 'static,
+    {
+        self.v.init_reloadable_tracing_with(filter, layer)
+    }
+
     /// Initializes cli metrics for global argument values.
     pub fn init_cli_metrics(&self) {
         metrics::describe_gauge!(
@@ -77,6 +110,11 @@ impl GlobalArgs {
         }
     }
 
+    /// Returns the [`Datadir`] of the [`GlobalArgs::l2_chain_id`].
+    pub fn datadir(&self) -> Datadir {
+        Datadir::new(self.datadir.clone(), self.l2_chain_id)
+    }
+
     /// Returns the [`RollupConfig`] for the [`GlobalArgs::l2_chain_id`] specified on the global
     /// arguments.
     pub fn rollup_config(&self) -> Option<RollupConfig> {
@@ -4,6 +4,7 @@
 
 use crate::metrics::VersionInfo;
 use kona_cli::metrics_args::MetricsArgs;
+use kona_node_service::ChainLabelLayer;
 
 /// Initializes metrics for a Kona application, including Prometheus and node-specific metrics.
 /// Initialize the tracing stack and Prometheus metrics recorder.
@@ -11,6 +12,21 @@ use kona_cli::metrics_args::MetricsArgs;
 /// This function should be called at the beginning of the program.
 pub fn init_unified_metrics(args: &MetricsArgs) -> anyhow::Result<()> {
     args.init_metrics()?;
+    describe_unified_metrics(args);
+    Ok(())
+}
+
+/// Initializes metrics like [`init_unified_metrics`], for a process running the nodes of multiple
+/// chains: the metrics recorded by each node are labeled with its chain id by the
+/// [`ChainLabelLayer`].
+pub fn init_multi_chain_metrics(args: &MetricsArgs) -> anyhow::Result<()> {
+    args.init_metrics_with_layer(ChainLabelLayer)?;
+    describe_unified_metrics(args);
+    Ok(())
+}
+
+/// Describes the metrics of the node's crates, if metrics are enabled.
+fn describe_unified_metrics(args: &MetricsArgs) {
     if args.enabled {
         kona_p2p::Metrics::init();
         kona_engine::Metrics::init();
@@ -18,7 +34,6 @@ pub fn init_unified_metrics(args: &MetricsArgs) -> anyhow::Result<()> {
         kona_derive::Metrics::init();
         VersionInfo::from_build().register_version_metrics();
     }
-    Ok(())
 }
 
 #[cfg(test)]
@@ -13,10 +13,28 @@ mod overrides;
 pub use overrides::OverrideArgs;
 
 mod metrics;
-pub use metrics::init_unified_metrics;
+pub use metrics::{init_multi_chain_metrics, init_unified_metrics};
 
 mod sequencer;
 pub use sequencer::SequencerArgs;
 
 mod supervisor;
 pub use supervisor::SupervisorArgs;
+
+mod bus;
+pub use bus::BusArgs;
+
+mod otlp;
+pub use otlp::{OtlpArgs, shutdown_otlp};
+
+mod beacon;
+pub use beacon::BeaconArgs;
+
+mod export;
+pub use export::ExportArgs;
+
+mod engine;
+pub use engine::EngineTransportArgs;
+
+mod network;
+pub use network::Network;
@@ -0,0 +1,125 @@
+//! Network Preset CLI Flags
+
+use anyhow::bail;
+use kona_genesis::{ChainConfig, RollupConfig};
+use kona_peers::BootNodes;
+use kona_registry::{CHAINS, OPCHAINS, ROLLUP_CONFIGS};
+use std::{fmt, str::FromStr};
+use tracing::info;
+
+/// A named OP Stack network from the superchain registry, e.g. `op-mainnet` or `base-sepolia`.
+///
+/// Selecting a network with `--network` sets the L2 chain id, from which the node loads the
+/// network's rollup config, bootnodes and unsafe block signer. Each can still be overridden by its
+/// own flag, e.g. `--l2-config-file`, `--p2p.bootnodes` or `--p2p.unsafe.block.signer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    /// The name of the network.
+    pub name: String,
+    /// The L2 chain id of the network.
+    pub chain_id: u64,
+}
+
+impl Network {
+    /// Returns the names of all networks in the superchain registry.
+    pub fn names() -> Vec<String> {
+        CHAINS.chains.iter().map(|chain| Self::name_of(&chain.identifier)).collect()
+    }
+
+    /// Returns the name of the network with the given superchain registry identifier, e.g.
+    /// `op-mainnet` for `mainnet/op`.
+    fn name_of(identifier: &str) -> String {
+        identifier.split_once('/').map_or_else(
+            || identifier.to_string(),
+            |(superchain, chain)| format!("{chain}-{superchain}"),
+        )
+    }
+
+    /// Returns the chain config of the network.
+    pub fn chain_config(&self) -> Option<&'static ChainConfig> {
+        OPCHAINS.get(&self.chain_id)
+    }
+
+    /// Returns the rollup config of the network.
+    pub fn rollup_config(&self) -> Option<&'static RollupConfig> {
+        ROLLUP_CONFIGS.get(&self.chain_id)
+    }
+
+    /// Resolves the `--l2-chain-id` value against the network. The network's chain id replaces
+    /// the default chain id, but conflicts with a different chain id set explicitly through the
+    /// CLI, the environment or a config file.
+    pub fn resolve_chain_id(&self, l2_chain_id: u64, explicit: bool) -> anyhow::Result<u64> {
+        if explicit && l2_chain_id != self.chain_id {
+            bail!(
+                "L2 chain id {l2_chain_id} conflicts with network {}, which has chain id {}",
+                self.name,
+                self.chain_id
+            );
+        }
+        Ok(self.chain_id)
+    }
+
+    /// Logs the configuration that the network preset provides.
+    pub fn log_summary(&self) {
+        let chain = self.chain_config();
+        let roles = chain.and_then(|chain| chain.roles.as_ref());
+        info!(
+            target: "cli",
+            network = %self.name,
+            chain_id = self.chain_id,
+            l1_chain_id = ?chain.map(|chain| chain.l1_chain_id),
+            unsafe_block_signer = ?roles.and_then(|roles| roles.unsafe_block_signer),
+            batcher = ?self.rollup_config().and_then(|cfg| cfg.genesis.system_config).map(|cfg| cfg.batcher_address),
+            bootnodes = BootNodes::from_chain_id(self.chain_id).len(),
+            "Using network preset"
+        );
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    /// Parses a network from its name, e.g. `op-mainnet`, or from its superchain registry
+    /// identifier, e.g. `mainnet/op`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CHAINS
+            .chains
+            .iter()
+            .find(|chain| {
+                Self::name_of(&chain.identifier).eq_ignore_ascii_case(s) ||
+                    chain.identifier.eq_ignore_ascii_case(s)
+            })
+            .map(|chain| Self { name: Self::name_of(&chain.identifier), chain_id: chain.chain_id })
+            .ok_or_else(|| {
+                format!("unknown network `{s}`, expected one of: {}", Self::names().join(", "))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_from_str() {
+        let network = Network::from_str("op-mainnet").unwrap();
+        assert_eq!(network, Network { name: "op-mainnet".to_string(), chain_id: 10 });
+        assert_eq!(Network::from_str("base-sepolia").unwrap().chain_id, 84532);
+        assert_eq!(Network::from_str("mainnet/base").unwrap().chain_id, 8453);
+        assert!(Network::from_str("op-devnet-42").is_err());
+    }
+
+    #[test]
+    fn test_network_resolve_chain_id() {
+        let network = Network::from_str("base-mainnet").unwrap();
+        assert_eq!(network.resolve_chain_id(10, false).unwrap(), 8453);
+        assert_eq!(network.resolve_chain_id(8453, true).unwrap(), 8453);
+        assert!(network.resolve_chain_id(10, true).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! OpenTelemetry Trace Export CLI Flags
+
+use clap::Parser;
+use kona_cli::ReloadableRegistry;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    trace::{Sampler, SdkTracerProvider, Tracer},
+};
+use std::sync::OnceLock;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use url::Url;
+
+/// The tracer provider installed by [`OtlpArgs::layer`], flushed on exit by [`shutdown_otlp`].
+static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
+/// CLI flags for exporting traces over OTLP.
+///
+/// The node's spans follow each block from the L1 head update it was derived from, through the
+/// derivation pipeline and the engine API calls that build, import and canonicalize it, so that an
+/// exported trace shows a waterfall for every block.
+#[derive(Parser, Clone, Debug, PartialEq)]
+pub struct OtlpArgs {
+    /// OTLP/HTTP endpoint to export traces to, e.g. `http://localhost:4318/v1/traces`. Traces are
+    /// not exported if unset.
+    #[arg(long = "otlp.endpoint", env = "KONA_NODE_OTLP_ENDPOINT")]
+    pub endpoint: Option<Url>,
+    /// Service name that traces are exported under.
+    #[arg(
+        long = "otlp.service-name",
+        default_value = "kona-node",
+        env = "KONA_NODE_OTLP_SERVICE_NAME"
+    )]
+    pub service_name: String,
+    /// Ratio of traces to sample, between `0` and `1`.
+    #[arg(long = "otlp.sample-ratio", default_value = "1.0", env = "KONA_NODE_OTLP_SAMPLE_RATIO")]
+    pub sample_ratio: f64,
+}
+
+impl Default for OtlpArgs {
+    fn default() -> Self {
+        // Construct default values using the clap parser.
+        // This works since none of the cli flags are required.
+        Self::parse_from::<[_; 0], &str>([])
+    }
+}
+
+impl OtlpArgs {
+    /// Returns the tracing layer that exports spans to the configured endpoint, or `None` if
+    /// trace export is disabled.
+    ///
+    /// Spans are exported in batches from a background thread. Call [`shutdown_otlp`] before
+    /// exiting to flush the remaining spans.
+    pub fn layer(&self) -> anyhow::Result<Option<OpenTelemetryLayer<ReloadableRegistry, Tracer>>> {
+        let Some(endpoint) = &self.endpoint else {
+            return Ok(None);
+        };
+        if !(0.0..=1.0).contains(&self.sample_ratio) {
+            anyhow::bail!("OTLP sample ratio must be between 0 and 1, got {}", self.sample_ratio);
+        }
+
+        let exporter =
+            SpanExporter::builder().with_http().with_endpoint(endpoint.as_str()).build()?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                self.sample_ratio,
+            ))))
+            .with_resource(Resource::builder().with_service_name(self.service_name.clone()).build())
+            .build();
+        let tracer = provider.tracer("kona-node");
+        let _ = TRACER_PROVIDER.set(provider);
+
+        Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+    }
+}
+
+/// Flushes and shuts down the OTLP trace exporter, if it was installed by [`OtlpArgs::layer`].
+pub fn shutdown_otlp() {
+    if let Some(Err(err)) = TRACER_PROVIDER.get().map(|provider| provider.shutdown()) {
+        eprintln!("Failed to flush OTLP traces: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_otlp_disabled_by_default() {
+        let args = OtlpArgs::default();
+        assert_eq!(args.endpoint, None);
+        assert_eq!(args.service_name, "kona-node");
+        assert!(args.layer().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_otlp_invalid_sample_ratio() {
+        let args = OtlpArgs::parse_from([
+            "otlp",
+            "--otlp.endpoint",
+            "http://localhost:4318/v1/traces",
+            "--otlp.sample-ratio",
+            "2",
+        ]);
+        assert!(args.layer().is_err());
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //! [op-node]: https://github.com/ethereum-optimism/optimism/blob/develop/op-node/flags/p2p_flags.go
 
-use crate::flags::GlobalArgs;
+use crate::{flags::GlobalArgs, keystore};
 use alloy_primitives::B256;
 use alloy_signer::Signer;
 use alloy_signer_local::PrivateKeySigner;
@@ -40,6 +40,22 @@ pub struct P2PArgs {
     /// The hex-encoded 32-byte private key for the peer ID.
     #[arg(long = "p2p.priv.raw", env = "KONA_NODE_P2P_PRIV_RAW")]
     pub private_key: Option<B256>,
+    /// Read the private key for the peer ID from this encrypted keystore, e.g. as written by the
+    /// `keys` subcommand. The password is read from `--p2p.priv.password-file`, the
+    /// `KONA_NODE_KEYSTORE_PASSWORD` environment variable, or prompted for on the terminal.
+    #[arg(
+        long = "p2p.priv.keystore",
+        env = "KONA_NODE_P2P_PRIV_KEYSTORE",
+        conflicts_with_all = ["priv_path", "private_key"]
+    )]
+    pub priv_keystore: Option<PathBuf>,
+    /// File containing the password of the `--p2p.priv.keystore`.
+    #[arg(
+        long = "p2p.priv.password-file",
+        env = "KONA_NODE_P2P_PRIV_PASSWORD_FILE",
+        requires = "priv_keystore"
+    )]
+    pub priv_password_file: Option<PathBuf>,
 
     /// IP to advertise to external peers from Discv5.
     /// Optional argument. Use the `p2p.listen.ip` if not set.
@@ -340,11 +356,16 @@ impl P2PArgs {
     ///
     /// Errors if the genesis unsafe block signer isn't available for the specified L2 Chain ID.
     pub async fn config(
-        self,
+        mut self,
         config: &RollupConfig,
         args: &GlobalArgs,
         l1_rpc: Option<Url>,
     ) -> anyhow::Result<Config> {
+        // Decrypt the keystore once, so the key is shared by the peer ID and the local signer.
+        if let Some(path) = &self.priv_keystore {
+            self.private_key = Some(keystore::load_key(path, self.priv_password_file.as_deref())?);
+        }
+
         // Note: the advertised address is contained in the ENR for external peers from the
         // discovery layer to use.
 
@@ -519,6 +540,23 @@ mod tests {
         assert_eq!(args.p2p.priv_path, Some(PathBuf::from("test.txt")));
     }
 
+    #[test]
+    fn test_p2p_args_priv_keystore() {
+        let args = MockCommand::parse_from(["test", "--p2p.priv.keystore", "key.json"]);
+        assert_eq!(args.p2p.priv_keystore, Some(PathBuf::from("key.json")));
+        assert!(
+            MockCommand::try_parse_from([
+                "test",
+                "--p2p.priv.keystore",
+                "key.json",
+                "--p2p.priv.path",
+                "test.txt"
+            ])
+            .is_err()
+        );
+        assert!(MockCommand::try_parse_from(["test", "--p2p.priv.password-file", "pw"]).is_err());
+    }
+
     #[test]
     fn test_p2p_args_private_key() {
         let args = MockCommand::parse_from([
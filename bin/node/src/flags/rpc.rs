@@ -3,7 +3,7 @@
 //! Flags for configuring the RPC server.
 
 use clap::Parser;
-use kona_rpc::RpcConfig;
+use kona_rpc::{BlockLabelSource, RpcConfig};
 use std::{
     net::{IpAddr, SocketAddr},
     path::PathBuf,
@@ -34,6 +34,19 @@ pub struct RpcArgs {
     /// Enables websocket rpc server to track block production
     #[arg(long = "rpc.ws-enabled", default_value = "false", env = "KONA_NODE_RPC_WS_ENABLED")]
     pub ws_enabled: bool,
+    /// The view that the `latest`, `safe` and `finalized` block labels of RPC queries are
+    /// resolved from: `execution-layer` leaves them to the L2 EL, and `engine` resolves them from
+    /// the engine state of the node, consistently with `optimism_syncStatus`.
+    #[arg(
+        long = "rpc.block-labels",
+        default_value = "execution-layer",
+        env = "KONA_NODE_RPC_BLOCK_LABELS"
+    )]
+    pub block_labels: BlockLabelSource,
+    /// Serve the `eth_*` methods that take a block label, forwarded to the L2 EL with their
+    /// labels resolved from the engine state of the node.
+    #[arg(long = "rpc.eth-proxy", default_value = "false", env = "KONA_NODE_RPC_ETH_PROXY")]
+    pub eth_proxy: bool,
 }
 
 impl Default for RpcArgs {
@@ -53,6 +66,8 @@ impl From<RpcArgs> for RpcConfig {
             enable_admin: args.enable_admin,
             admin_persistence: args.admin_persistence.clone(),
             ws_enabled: args.ws_enabled,
+            block_labels: args.block_labels,
+            eth_proxy: args.eth_proxy,
         }
     }
 }
@@ -70,6 +85,8 @@ mod tests {
     #[case::disable_rpc(&["--rpc.port", "8743"], |args: &mut RpcArgs| { args.listen_port = 8743; })]
     #[case::disable_rpc(&["--rpc.enable-admin"], |args: &mut RpcArgs| { args.enable_admin = true; })]
     #[case::disable_rpc(&["--rpc.admin-state", "/"], |args: &mut RpcArgs| { args.admin_persistence = Some(PathBuf::from("/")); })]
+    #[case::block_labels(&["--rpc.block-labels", "engine"], |args: &mut RpcArgs| { args.block_labels = BlockLabelSource::Engine; })]
+    #[case::eth_proxy(&["--rpc.eth-proxy"], |args: &mut RpcArgs| { args.eth_proxy = true; })]
     fn test_parse_rpc_args(#[case] args: &[&str], #[case] mutate: impl Fn(&mut RpcArgs)) {
         let args = [&["kona-node"], args].concat();
         let cli = RpcArgs::parse_from(args);
@@ -0,0 +1,337 @@
+//! Encrypted key storage.
+//!
+//! Keys are stored encrypted at rest in the [Web3 Secret Storage] format (version 3), using the
+//! `pbkdf2` key derivation function with `hmac-sha256` and the `aes-128-ctr` cipher. Keystores
+//! using the `scrypt` key derivation function can't be decrypted, and must be converted first.
+//!
+//! [Web3 Secret Storage]: https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use alloy_primitives::{Address, B256, hex, keccak256};
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::Path,
+};
+use subtle::ConstantTimeEq;
+
+/// The environment variable holding the password of a keystore.
+pub const KEYSTORE_PASSWORD_ENV: &str = "KONA_NODE_KEYSTORE_PASSWORD";
+
+/// The number of `pbkdf2` rounds used to encrypt new keystores.
+pub const DEFAULT_PBKDF2_ROUNDS: u32 = 262_144;
+
+/// The length of the key derived from the password, in bytes.
+const DERIVED_KEY_LEN: usize = 32;
+
+/// The `aes-128-ctr` cipher.
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// A key stored encrypted in the Web3 Secret Storage format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    /// The version of the format, always `3`.
+    pub version: u32,
+    /// A random identifier of the keystore.
+    pub id: String,
+    /// The address of the key, as lowercase hex without prefix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// The encrypted key.
+    #[serde(alias = "Crypto")]
+    pub crypto: KeystoreCrypto,
+}
+
+/// The encryption parameters and ciphertext of a [`Keystore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    /// The cipher, always `aes-128-ctr`.
+    pub cipher: String,
+    /// The parameters of the cipher.
+    pub cipherparams: CipherParams,
+    /// The hex-encoded encrypted key.
+    pub ciphertext: String,
+    /// The key derivation function.
+    pub kdf: String,
+    /// The parameters of the key derivation function.
+    pub kdfparams: serde_json::Value,
+    /// The hex-encoded MAC of the ciphertext.
+    pub mac: String,
+}
+
+/// The parameters of the `aes-128-ctr` cipher.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherParams {
+    /// The hex-encoded initialization vector.
+    pub iv: String,
+}
+
+/// The parameters of the `pbkdf2` key derivation function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pbkdf2Params {
+    /// The number of rounds.
+    pub c: u32,
+    /// The length of the derived key, in bytes.
+    pub dklen: usize,
+    /// The pseudorandom function, always `hmac-sha256`.
+    pub prf: String,
+    /// The hex-encoded salt.
+    pub salt: String,
+}
+
+impl Keystore {
+    /// Encrypts the given secret key with the given password, using `rounds` rounds of `pbkdf2`.
+    pub fn encrypt(secret: &B256, password: &str, address: Address, rounds: u32) -> Self {
+        let salt = B256::random();
+        let iv = &B256::random()[..16];
+        let key = pbkdf2_sha256(password.as_bytes(), salt.as_slice(), rounds);
+
+        let mut ciphertext = secret.to_vec();
+        Aes128Ctr::new(key[..16].into(), iv.into()).apply_keystream(&mut ciphertext);
+        let mac = keccak256([&key[16..], &ciphertext].concat());
+
+        let params = Pbkdf2Params {
+            c: rounds,
+            dklen: DERIVED_KEY_LEN,
+            prf: "hmac-sha256".to_string(),
+            salt: hex::encode(salt),
+        };
+        Self {
+            version: 3,
+            id: random_uuid(),
+            address: Some(hex::encode(address)),
+            crypto: KeystoreCrypto {
+                cipher: "aes-128-ctr".to_string(),
+                cipherparams: CipherParams { iv: hex::encode(iv) },
+                ciphertext: hex::encode(ciphertext),
+                kdf: "pbkdf2".to_string(),
+                kdfparams: serde_json::to_value(params).expect("Failed to serialize kdf params"),
+                mac: hex::encode(mac),
+            },
+        }
+    }
+
+    /// Decrypts the secret key with the given password.
+    pub fn decrypt(&self, password: &str) -> anyhow::Result<B256> {
+        let crypto = &self.crypto;
+        if self.version != 3 {
+            bail!("Unsupported keystore version {}", self.version);
+        }
+        if crypto.kdf != "pbkdf2" {
+            bail!("Unsupported keystore key derivation function `{}`", crypto.kdf);
+        }
+        if crypto.cipher != "aes-128-ctr" {
+            bail!("Unsupported keystore cipher `{}`", crypto.cipher);
+        }
+        let params: Pbkdf2Params = serde_json::from_value(crypto.kdfparams.clone())
+            .context("Invalid keystore kdf params")?;
+        if params.prf != "hmac-sha256" || params.dklen != DERIVED_KEY_LEN {
+            bail!("Unsupported keystore pbkdf2 params {params:?}");
+        }
+
+        let salt = hex::decode(&params.salt).context("Invalid keystore salt")?;
+        let iv = hex::decode(&crypto.cipherparams.iv).context("Invalid keystore iv")?;
+        let mut plaintext =
+            hex::decode(&crypto.ciphertext).context("Invalid keystore ciphertext")?;
+        let mac = hex::decode(&crypto.mac).context("Invalid keystore mac")?;
+        if iv.len() != 16 || plaintext.len() != 32 {
+            bail!("Invalid keystore iv or ciphertext length");
+        }
+
+        let key = pbkdf2_sha256(password.as_bytes(), &salt, params.c);
+        let computed = keccak256([&key[16..], &plaintext].concat());
+        if !bool::from(computed.as_slice().ct_eq(&mac)) {
+            bail!("Invalid keystore password");
+        }
+        Aes128Ctr::new(key[..16].into(), iv.as_slice().into()).apply_keystream(&mut plaintext);
+        Ok(B256::from_slice(&plaintext))
+    }
+
+    /// Reads a keystore from the given file.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read keystore {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse keystore {}", path.display()))
+    }
+
+    /// Writes the keystore to the given file, which must not exist yet.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        write_new(path, &serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Reads and decrypts the secret key of the keystore at the given path, with the password read by
+/// [`read_password`].
+pub fn load_key(path: &Path, password_file: Option<&Path>) -> anyhow::Result<B256> {
+    let keystore = Keystore::read(path)?;
+    let password = read_password(password_file, &format!("Password for {}: ", path.display()))?;
+    keystore.decrypt(&password).with_context(|| format!("Failed to decrypt {}", path.display()))
+}
+
+/// Reads a password from the given file, or else from the [`KEYSTORE_PASSWORD_ENV`] environment
+/// variable, or else by prompting for it on the terminal.
+pub fn read_password(file: Option<&Path>, prompt: &str) -> anyhow::Result<String> {
+    if let Some(file) = file {
+        let password = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read password file {}", file.display()))?;
+        return Ok(password.trim_end_matches(['\r', '\n']).to_string());
+    }
+    if let Ok(password) = std::env::var(KEYSTORE_PASSWORD_ENV) {
+        return Ok(password);
+    }
+    prompt_password(prompt)
+}
+
+/// Prompts for a password on the terminal, without echoing it.
+pub fn prompt_password(prompt: &str) -> anyhow::Result<String> {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        bail!("No password provided, and stdin is not a terminal to prompt for one");
+    }
+    eprint!("{prompt}");
+    io::stderr().flush()?;
+
+    let echo = EchoGuard::disable();
+    let mut password = String::new();
+    let res = stdin.lock().read_line(&mut password);
+    drop(echo);
+    eprintln!();
+    res?;
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Writes the given contents to a new file, readable only by its owner.
+pub fn write_new(path: &Path, contents: &str) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file =
+        options.open(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// Disables echoing of the input on the terminal, until dropped.
+struct EchoGuard {
+    /// The terminal attributes to restore.
+    #[cfg(unix)]
+    original: Option<libc::termios>,
+}
+
+impl EchoGuard {
+    #[cfg(unix)]
+    fn disable() -> Self {
+        // SAFETY: `termios` is a plain C struct, and is only read after `tcgetattr` filled it in.
+        unsafe {
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+                return Self { original: None };
+            }
+            let original = termios;
+            termios.c_lflag &= !libc::ECHO;
+            termios.c_lflag |= libc::ECHONL;
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios);
+            Self { original: Some(original) }
+        }
+    }
+
+    #[cfg(not(unix))]
+    const fn disable() -> Self {
+        Self {}
+    }
+}
+
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(original) = self.original {
+            // SAFETY: restores the attributes previously read by `tcgetattr`.
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &original);
+            }
+        }
+    }
+}
+
+/// Derives a [`DERIVED_KEY_LEN`] long key from the password with `pbkdf2`, using `hmac-sha256` as
+/// the pseudorandom function.
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], rounds: u32) -> [u8; DERIVED_KEY_LEN] {
+    pbkdf2::pbkdf2_hmac_array::<Sha256, DERIVED_KEY_LEN>(password, salt, rounds)
+}
+
+/// Returns a random version 4 UUID.
+fn random_uuid() -> String {
+    let mut bytes = B256::random().0;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(&bytes[..16]);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256};
+
+    #[test]
+    fn test_pbkdf2_sha256() {
+        assert_eq!(
+            pbkdf2_sha256(b"password", b"salt", 1),
+            b256!("120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b")
+        );
+        assert_eq!(
+            pbkdf2_sha256(b"password", b"salt", 2),
+            b256!("ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43")
+        );
+    }
+
+    #[test]
+    fn test_keystore_decrypt_test_vector() {
+        let keystore: Keystore = serde_json::from_str(
+            r#"{
+                "crypto": {
+                    "cipher": "aes-128-ctr",
+                    "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+                    "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                    "kdf": "pbkdf2",
+                    "kdfparams": {
+                        "c": 262144,
+                        "dklen": 32,
+                        "prf": "hmac-sha256",
+                        "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                    },
+                    "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+                },
+                "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+                "version": 3
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            keystore.decrypt("testpassword").unwrap(),
+            b256!("7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d")
+        );
+        assert!(keystore.decrypt("wrongpassword").is_err());
+    }
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let secret = b256!("59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d");
+        let address = address!("70997970c51812dc3a010c7d01b50e0d17dc79c8");
+        let keystore = Keystore::encrypt(&secret, "hunter2", address, 2);
+        assert_eq!(keystore.address.as_deref(), Some("70997970c51812dc3a010c7d01b50e0d17dc79c8"));
+
+        let json = serde_json::to_string(&keystore).unwrap();
+        let keystore: Keystore = serde_json::from_str(&json).unwrap();
+        assert_eq!(keystore.decrypt("hunter2").unwrap(), secret);
+        assert!(keystore.decrypt("hunter3").is_err());
+    }
+}
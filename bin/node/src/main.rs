@@ -6,20 +6,30 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod beacon;
 pub mod cli;
 pub mod commands;
+pub mod config;
+pub mod datadir;
 pub mod flags;
+pub mod keystore;
 pub mod metrics;
+pub mod preflight;
+pub mod systemd;
 
 pub(crate) mod version;
 
-fn main() {
-    use clap::Parser;
+/// The jemalloc allocator, whose statistics and heap profiles are served by the `debug_heapStats`
+/// and `debug_heapProfile` RPC methods.
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static ALLOCATOR: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+fn main() {
     kona_cli::sigsegv_handler::install();
     kona_cli::backtrace::enable();
 
-    if let Err(err) = cli::Cli::parse().run() {
+    if let Err(err) = cli::Cli::parse_with_config().and_then(|cli| cli.run()) {
         eprintln!("Error: {err:?}");
         std::process::exit(1);
     }
@@ -0,0 +1,387 @@
+//! Startup preflight checks for the node's configured endpoints.
+//!
+//! Before the node's actors are started, the [`Preflight`] checks validate that every configured
+//! endpoint is reachable and supports the methods the node depends on, so that misconfiguration
+//! surfaces at startup in a single [`PreflightReport`] rather than when an endpoint is first used.
+
+use alloy_eips::BlockNumberOrTag;
+use alloy_provider::{Provider, RootProvider, network::Ethereum};
+use alloy_rpc_types_engine::JwtSecret;
+use alloy_rpc_types_eth::Filter;
+use kona_engine::{EngineClient, EngineTransport};
+use kona_genesis::RollupConfig;
+use kona_providers_alloy::{BeaconClient, OnlineBeaconClient};
+use op_alloy_provider::ext::engine::OpEngineApi;
+use serde::Serialize;
+use std::{
+    fmt::{self, Display},
+    net::SocketAddr,
+    sync::Arc,
+};
+use tracing::{error, info, warn};
+use url::Url;
+
+/// The engine API methods that the node requires the execution client to support.
+pub const REQUIRED_ENGINE_METHODS: [&str; 3] =
+    ["engine_forkchoiceUpdatedV3", "engine_newPayloadV3", "engine_getPayloadV3"];
+
+/// The outcome of a single preflight check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "lowercase")]
+pub enum CheckOutcome {
+    /// The check passed.
+    Pass,
+    /// The check found an issue that does not prevent the node from starting.
+    Warn(String),
+    /// The check found an issue that prevents the node from operating.
+    Fail(String),
+}
+
+impl CheckOutcome {
+    /// Returns a [`CheckOutcome::Fail`] with the given reason.
+    pub fn fail(reason: impl Display) -> Self {
+        Self::Fail(reason.to_string())
+    }
+}
+
+/// A named preflight check and its outcome.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PreflightCheck {
+    /// The name of the check.
+    pub name: &'static str,
+    /// The outcome of the check.
+    #[serde(flatten)]
+    pub outcome: CheckOutcome,
+}
+
+/// The summary of all preflight checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PreflightReport {
+    /// The checks that were run, in order.
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    /// Records the outcome of a check.
+    pub fn record(&mut self, name: &'static str, outcome: CheckOutcome) {
+        self.checks.push(PreflightCheck { name, outcome });
+    }
+
+    /// Returns the checks that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks.iter().filter(|c| matches!(c.outcome, CheckOutcome::Fail(_)))
+    }
+
+    /// Returns the checks that passed with a warning.
+    pub fn warnings(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks.iter().filter(|c| matches!(c.outcome, CheckOutcome::Warn(_)))
+    }
+
+    /// Returns `true` if no check failed.
+    pub fn is_ok(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Appends the checks of the given report.
+    pub fn extend(&mut self, other: Self) {
+        self.checks.extend(other.checks);
+    }
+
+    /// Logs the outcome of every check, followed by a summary line.
+    pub fn log_summary(&self) {
+        for check in &self.checks {
+            match &check.outcome {
+                CheckOutcome::Pass => info!(target: "preflight", check = check.name, "Passed"),
+                CheckOutcome::Warn(reason) => {
+                    warn!(target: "preflight", check = check.name, "Warning: {reason}")
+                }
+                CheckOutcome::Fail(reason) => {
+                    error!(target: "preflight", check = check.name, "Failed: {reason}")
+                }
+            }
+        }
+
+        let failed = self.failures().count();
+        let warned = self.warnings().count();
+        let passed = self.checks.len() - failed - warned;
+        if failed > 0 {
+            error!(target: "preflight", passed, warned, failed, "Preflight checks failed");
+        } else {
+            info!(target: "preflight", passed, warned, failed, "Preflight checks complete");
+        }
+    }
+}
+
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            match &check.outcome {
+                CheckOutcome::Pass => writeln!(f, "PASS {}", check.name)?,
+                CheckOutcome::Warn(reason) => writeln!(f, "WARN {}: {reason}", check.name)?,
+                CheckOutcome::Fail(reason) => writeln!(f, "FAIL {}: {reason}", check.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validates the node's configured endpoints before startup.
+#[derive(Debug, Clone)]
+pub struct Preflight {
+    /// The rollup config.
+    pub cfg: Arc<RollupConfig>,
+    /// URL of the L1 execution client RPC API.
+    pub l1_eth_rpc: Url,
+    /// URL of the L1 beacon API, or `None` if the chain posts no blobs.
+    pub l1_beacon: Option<Url>,
+    /// URL of the L2 execution client's engine API.
+    pub l2_engine_rpc: Url,
+    /// URL of the L2 execution client's RPC API.
+    pub l2_provider_rpc: Url,
+    /// The JWT secret for the engine API, if one could be loaded.
+    pub jwt_secret: Option<JwtSecret>,
+    /// The [`EngineTransport`] to the L2 execution client.
+    pub engine_transport: EngineTransport,
+    /// The socket address the supervisor RPC server binds to, if it is enabled.
+    pub supervisor_addr: Option<SocketAddr>,
+}
+
+impl Preflight {
+    /// Runs all preflight checks and returns the [`PreflightReport`].
+    pub async fn run(&self) -> PreflightReport {
+        let mut report = PreflightReport::default();
+        self.check_engine(&mut report).await;
+        self.check_l2(&mut report).await;
+        let l1_timestamp = self.check_l1(&mut report).await;
+        self.check_beacon(&mut report, l1_timestamp).await;
+        self.check_supervisor(&mut report);
+        report
+    }
+
+    /// Checks that the engine API accepts the JWT secret and supports the required methods.
+    async fn check_engine(&self, report: &mut PreflightReport) {
+        let Some(jwt_secret) = self.jwt_secret else {
+            report.record("engine auth", CheckOutcome::fail("no valid JWT secret was found"));
+            return;
+        };
+        let client = EngineClient::new_with_transport(
+            self.l2_engine_rpc.clone(),
+            self.l2_provider_rpc.clone(),
+            self.l1_eth_rpc.clone(),
+            self.cfg.clone(),
+            jwt_secret,
+            &self.engine_transport,
+        );
+
+        let capabilities = match client.exchange_capabilities(vec![]).await {
+            Ok(capabilities) => capabilities,
+            Err(e) => {
+                report.record("engine auth", CheckOutcome::fail(e));
+                return;
+            }
+        };
+        report.record("engine auth", CheckOutcome::Pass);
+
+        let missing = REQUIRED_ENGINE_METHODS
+            .iter()
+            .filter(|method| !capabilities.iter().any(|c| c == *method))
+            .copied()
+            .collect::<Vec<_>>();
+        let outcome = if missing.is_empty() {
+            CheckOutcome::Pass
+        } else {
+            CheckOutcome::Warn(format!("engine does not advertise {}", missing.join(", ")))
+        };
+        report.record("engine capabilities", outcome);
+    }
+
+    /// Checks that the L2 RPC serves the configured chain, starting from the configured genesis.
+    async fn check_l2(&self, report: &mut PreflightReport) {
+        let provider = RootProvider::<Ethereum>::new_http(self.l2_provider_rpc.clone());
+        let outcome = match provider.get_chain_id().await {
+            Ok(id) if id == self.cfg.l2_chain_id => CheckOutcome::Pass,
+            Ok(id) => CheckOutcome::Fail(format!(
+                "L2 RPC serves chain {id}, expected {}",
+                self.cfg.l2_chain_id
+            )),
+            Err(e) => CheckOutcome::fail(e),
+        };
+        report.record("l2 chain id", outcome);
+
+        let genesis = self.cfg.genesis.l2;
+        let outcome = match provider.get_block_by_number(genesis.number.into()).await {
+            Ok(Some(block)) if block.header.hash == genesis.hash => CheckOutcome::Pass,
+            Ok(Some(block)) => CheckOutcome::Fail(format!(
+                "L2 block {} has hash {}, expected genesis {}",
+                genesis.number, block.header.hash, genesis.hash
+            )),
+            Ok(None) => {
+                CheckOutcome::Warn(format!("L2 genesis block {} is unknown", genesis.number))
+            }
+            Err(e) => CheckOutcome::fail(e),
+        };
+        report.record("l2 genesis", outcome);
+    }
+
+    /// Checks that the L1 RPC serves the configured chain and the methods used by derivation.
+    /// Returns the timestamp of the finalized L1 block, or of the latest one if none is finalized
+    /// yet, if it could be fetched.
+    async fn check_l1(&self, report: &mut PreflightReport) -> Option<u64> {
+        let provider = RootProvider::<Ethereum>::new_http(self.l1_eth_rpc.clone());
+        let outcome = match provider.get_chain_id().await {
+            Ok(id) if id == self.cfg.l1_chain_id => CheckOutcome::Pass,
+            Ok(id) => CheckOutcome::Fail(format!(
+                "L1 RPC serves chain {id}, expected {}",
+                self.cfg.l1_chain_id
+            )),
+            Err(e) => CheckOutcome::fail(e),
+        };
+        report.record("l1 chain id", outcome);
+
+        let block = match provider.get_block_by_number(BlockNumberOrTag::Latest).await {
+            Ok(Some(block)) => block,
+            Ok(None) => {
+                report.record("l1 eth_getBlockByNumber", CheckOutcome::fail("no latest block"));
+                return None;
+            }
+            Err(e) => {
+                report.record("l1 eth_getBlockByNumber", CheckOutcome::fail(e));
+                return None;
+            }
+        };
+        report.record("l1 eth_getBlockByNumber", CheckOutcome::Pass);
+
+        let hash = block.header.hash;
+        let outcome = match provider.get_block_receipts(hash.into()).await {
+            Ok(_) => CheckOutcome::Pass,
+            Err(e) => CheckOutcome::fail(e),
+        };
+        report.record("l1 eth_getBlockReceipts", outcome);
+
+        let outcome = match provider.get_logs(&Filter::new().at_block_hash(hash)).await {
+            Ok(_) => CheckOutcome::Pass,
+            Err(e) => CheckOutcome::fail(e),
+        };
+        report.record("l1 eth_getLogs", outcome);
+
+        // The beacon node may not have imported the block of the latest slot yet, so its blob
+        // sidecars are checked at the finalized block instead.
+        match provider.get_block_by_number(BlockNumberOrTag::Finalized).await {
+            Ok(Some(finalized)) => Some(finalized.header.timestamp),
+            _ => Some(block.header.timestamp),
+        }
+    }
+
+    /// Checks that the beacon API is reachable and serves blob sidecars for the slot of the
+    /// finalized L1 block. Missing blob sidecar support is only a failure once Ecotone is active.
+    /// The check is skipped for chains that post no blobs.
+    async fn check_beacon(&self, report: &mut PreflightReport, l1_timestamp: Option<u64>) {
+        let Some(url) = &self.l1_beacon else {
+            return;
+        };
+        let beacon = OnlineBeaconClient::new_http(url.to_string());
+        let (spec, genesis) = match (beacon.config_spec().await, beacon.beacon_genesis().await) {
+            (Ok(spec), Ok(genesis)) => (spec, genesis),
+            (Err(e), _) | (_, Err(e)) => {
+                report.record("beacon api", CheckOutcome::fail(e));
+                return;
+            }
+        };
+        report.record("beacon api", CheckOutcome::Pass);
+
+        let Some(l1_timestamp) = l1_timestamp else {
+            report.record(
+                "beacon blob sidecars",
+                CheckOutcome::Warn("skipped, the finalized L1 block is unknown".to_string()),
+            );
+            return;
+        };
+        let seconds_per_slot = spec.data.seconds_per_slot.max(1);
+        let slot = l1_timestamp.saturating_sub(genesis.data.genesis_time) / seconds_per_slot;
+        let outcome = match beacon.beacon_blob_side_cars(slot, &[]).await {
+            Ok(_) => CheckOutcome::Pass,
+            Err(e) if self.cfg.is_ecotone_active(l1_timestamp) => CheckOutcome::fail(e),
+            Err(e) => CheckOutcome::Warn(e.to_string()),
+        };
+        report.record("beacon blob sidecars", outcome);
+    }
+
+    /// Checks that the supervisor RPC server's socket address can be bound, if it is enabled.
+    fn check_supervisor(&self, report: &mut PreflightReport) {
+        let Some(addr) = self.supervisor_addr else {
+            return;
+        };
+        let outcome = match std::net::TcpListener::bind(addr) {
+            Ok(_) => CheckOutcome::Pass,
+            Err(e) => CheckOutcome::Fail(format!("cannot bind {addr}: {e}")),
+        };
+        report.record("supervisor rpc", outcome);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preflight(supervisor_addr: Option<SocketAddr>) -> Preflight {
+        Preflight {
+            cfg: Arc::new(RollupConfig::default()),
+            l1_eth_rpc: Url::parse("http://localhost:8545").unwrap(),
+            l1_beacon: Some(Url::parse("http://localhost:5052").unwrap()),
+            l2_engine_rpc: Url::parse("http://localhost:8551").unwrap(),
+            l2_provider_rpc: Url::parse("http://localhost:8545").unwrap(),
+            jwt_secret: None,
+            engine_transport: EngineTransport::default(),
+            supervisor_addr,
+        }
+    }
+
+    #[test]
+    fn test_report_summary() {
+        let mut report = PreflightReport::default();
+        report.record("a", CheckOutcome::Pass);
+        report.record("b", CheckOutcome::Warn("slow".to_string()));
+        assert!(report.is_ok());
+        assert_eq!(report.warnings().count(), 1);
+
+        report.record("c", CheckOutcome::fail("unreachable"));
+        assert!(!report.is_ok());
+        assert_eq!(report.failures().map(|c| c.name).collect::<Vec<_>>(), vec!["c"]);
+    }
+
+    #[test]
+    fn test_report_format() {
+        let mut report = PreflightReport::default();
+        report.record("a", CheckOutcome::Pass);
+        report.record("b", CheckOutcome::fail("unreachable"));
+        assert_eq!(report.to_string(), "PASS a\nFAIL b: unreachable\n");
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            r#"{"checks":[{"name":"a","status":"pass"},{"name":"b","status":"fail","reason":"unreachable"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_supervisor_check() {
+        let mut report = PreflightReport::default();
+        preflight(None).check_supervisor(&mut report);
+        assert!(report.checks.is_empty());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        preflight(Some(addr)).check_supervisor(&mut report);
+        assert!(!report.is_ok());
+
+        drop(listener);
+        let mut report = PreflightReport::default();
+        preflight(Some(addr)).check_supervisor(&mut report);
+        assert!(report.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_engine_check_without_jwt() {
+        let mut report = PreflightReport::default();
+        preflight(None).check_engine(&mut report).await;
+        assert_eq!(report.failures().map(|c| c.name).collect::<Vec<_>>(), vec!["engine auth"]);
+    }
+}
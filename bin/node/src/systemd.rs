@@ -0,0 +1,166 @@
+//! Integration with the systemd service manager, through the [`sd_notify`] protocol.
+//!
+//! When the node runs as a `Type=notify` service, systemd passes the path of a notification socket
+//! in the `NOTIFY_SOCKET` environment variable. The [`SystemdNotifier`] then reports the node as
+//! ready once it is running, keeps the service watchdog fed and the service status up to date, and
+//! reports the node as stopping once it shuts down.
+//!
+//! [`sd_notify`]: https://www.freedesktop.org/software/systemd/man/latest/sd_notify.html
+
+use async_trait::async_trait;
+use kona_node_service::{NodeExtension, NodeHandles, ShutdownStage};
+use std::{error::Error, ffi::OsString, io, time::Duration};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+/// The environment variable holding the path of the notification socket.
+const NOTIFY_SOCKET: &str = "NOTIFY_SOCKET";
+
+/// The environment variable holding the watchdog interval, in microseconds.
+const WATCHDOG_USEC: &str = "WATCHDOG_USEC";
+
+/// The environment variable holding the pid of the process the watchdog applies to.
+const WATCHDOG_PID: &str = "WATCHDOG_PID";
+
+/// Sends a notification to the given systemd notification socket. Socket paths starting with `@`
+/// refer to the abstract namespace.
+#[cfg(unix)]
+pub fn notify(socket: &OsString, state: &str) -> io::Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    let sock = UnixDatagram::unbound()?;
+    let path = socket.to_string_lossy();
+    #[cfg(target_os = "linux")]
+    if let Some(name) = path.strip_prefix('@') {
+        use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+        let addr = SocketAddr::from_abstract_name(name.as_bytes())?;
+        return sock.send_to_addr(state.as_bytes(), &addr).map(drop);
+    }
+    sock.send_to(state.as_bytes(), path.as_ref()).map(drop)
+}
+
+/// Sends a notification to the given systemd notification socket.
+#[cfg(not(unix))]
+pub fn notify(_socket: &OsString, _state: &str) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "sd_notify requires unix sockets"))
+}
+
+/// A [`NodeExtension`] that reports the state of the node to systemd.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemdNotifier {
+    /// The path of the notification socket.
+    socket: OsString,
+    /// The interval at which the watchdog must be fed, if the watchdog is enabled.
+    watchdog: Option<Duration>,
+}
+
+impl SystemdNotifier {
+    /// Creates a new [`SystemdNotifier`] for the given socket and watchdog interval.
+    pub const fn new(socket: OsString, watchdog: Option<Duration>) -> Self {
+        Self { socket, watchdog }
+    }
+
+    /// Returns the [`SystemdNotifier`] configured by the service manager in the environment of
+    /// the process, or `None` if the node does not run as a `Type=notify` service.
+    pub fn from_env() -> Option<Self> {
+        let socket = std::env::var_os(NOTIFY_SOCKET).filter(|s| !s.is_empty())?;
+        let pid = std::env::var(WATCHDOG_PID).ok().and_then(|pid| pid.parse::<u32>().ok());
+        let watchdog = std::env::var(WATCHDOG_USEC)
+            .ok()
+            .and_then(|usec| usec.parse::<u64>().ok())
+            .filter(|usec| *usec > 0 && pid.is_none_or(|pid| pid == std::process::id()))
+            .map(Duration::from_micros);
+        Some(Self::new(socket, watchdog))
+    }
+
+    /// Sends a notification to systemd. Failures are logged, but never fail the node.
+    fn notify(&self, state: &str) {
+        match notify(&self.socket, state) {
+            Ok(()) => debug!(target: "systemd", state, "Sent notification"),
+            Err(err) => warn!(target: "systemd", %err, state, "Failed to send notification"),
+        }
+    }
+
+    /// Returns the `STATUS` line describing the health of the node.
+    fn status(handles: &NodeHandles) -> String {
+        let unhealthy = handles.health.unhealthy();
+        let unsafe_head = handles.engine_state.borrow().unsafe_head().block_info.number;
+        if unhealthy.is_empty() {
+            format!("STATUS=Running, unsafe head {unsafe_head}")
+        } else {
+            format!(
+                "STATUS=Running, unsafe head {unsafe_head}, unhealthy: {}",
+                unhealthy.join(", ")
+            )
+        }
+    }
+}
+
+#[async_trait]
+impl NodeExtension for SystemdNotifier {
+    fn name(&self) -> &'static str {
+        "systemd"
+    }
+
+    /// The notifier is cancelled first, so that systemd knows the node is stopping while its
+    /// actors shut down.
+    fn stage(&self) -> ShutdownStage {
+        ShutdownStage::Sequencer
+    }
+
+    async fn run(
+        self: Box<Self>,
+        handles: NodeHandles,
+        cancellation: CancellationToken,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        info!(target: "systemd", watchdog = ?self.watchdog, "Notifying systemd of readiness");
+        self.notify(&format!("READY=1\n{}", Self::status(&handles)));
+
+        // The watchdog is fed at half its interval, as recommended by systemd. Without a watchdog,
+        // the status is still refreshed periodically.
+        let interval = self.watchdog.map_or(Duration::from_secs(30), |w| w / 2);
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut health = handles.health.subscribe();
+
+        loop {
+            tokio::select! {
+                _ = cancellation.cancelled() => {
+                    self.notify("STOPPING=1\nSTATUS=Shutting down");
+                    return Ok(());
+                }
+                _ = ticker.tick() => {
+                    // The watchdog detects a hung process, so it is fed as long as the runtime
+                    // makes progress, regardless of the health of the actors.
+                    match self.watchdog {
+                        Some(_) => self.notify(&format!("WATCHDOG=1\n{}", Self::status(&handles))),
+                        None => self.notify(&Self::status(&handles)),
+                    }
+                }
+                Ok(()) = health.changed() => {
+                    self.notify(&Self::status(&handles));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixDatagram;
+
+    #[test]
+    fn test_notify() {
+        let path = std::env::temp_dir().join(format!("kona-notify-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let server = UnixDatagram::bind(&path).unwrap();
+
+        notify(&path.clone().into_os_string(), "READY=1").unwrap();
+        let mut buf = [0u8; 64];
+        let n = server.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -47,8 +47,12 @@ mod tests {
 
     #[test]
     fn test_init_metrics_when_disabled() {
-        let args =
-            MetricsArgs { enabled: false, port: 1234, addr: IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)) };
+        let args = MetricsArgs {
+            enabled: false,
+            port: 1234,
+            addr: IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)),
+            ..Default::default()
+        };
         let result = args.init_metrics();
         assert!(
             result.is_ok(),
@@ -63,6 +67,7 @@ mod tests {
             enabled: true,
             port: 9876,
             addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            ..Default::default()
         };
         let result = args.init_metrics();
         assert!(
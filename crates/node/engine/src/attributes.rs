@@ -1,8 +1,9 @@
 //! Contains a utility method to check if attributes match a block.
 
+use crate::Metrics;
 use alloy_eips::{Decodable2718, eip1559::BaseFeeParams};
 use alloy_network::TransactionResponse;
-use alloy_primitives::{Address, B256, Bytes};
+use alloy_primitives::{Address, B256, Bytes, Keccak256, keccak256};
 use alloy_rpc_types_eth::{Block, BlockTransactions, Withdrawals};
 use kona_genesis::RollupConfig;
 use kona_protocol::OpAttributesWithParent;
@@ -363,6 +364,125 @@ impl From<AttributesMismatch> for AttributesMatch {
     }
 }
 
+/// A field-by-field diff between [`OpAttributesWithParent`] and a [`Block`], reported when
+/// consolidation finds that they do not match.
+///
+/// Where [`AttributesMatch::check`] stops at the first mismatch, the diff compares each of the
+/// fields below, holding the `(attributes, block)` values of those that differ.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AttributesDiff {
+    /// The timestamps.
+    pub timestamp: Option<(u64, u64)>,
+    /// The prev randao values.
+    pub prev_randao: Option<(B256, B256)>,
+    /// The hashes of the transaction lists, committing to the ordered transaction hashes.
+    pub transactions_hash: Option<(B256, B256)>,
+    /// The gas limits.
+    pub gas_limit: Option<(Option<u64>, u64)>,
+    /// The withdrawals roots. After Isthmus, the root commits to the storage of the message
+    /// passer, which the attributes do not carry, so it is only compared before.
+    pub withdrawals_root: Option<(Option<B256>, Option<B256>)>,
+}
+
+impl AttributesDiff {
+    /// Compares the specified [`OpAttributesWithParent`] with the specified [`Block`].
+    pub fn new(
+        config: &RollupConfig,
+        attributes: &OpAttributesWithParent,
+        block: &Block<Transaction>,
+    ) -> Self {
+        let payload_attributes = &attributes.inner().payload_attributes;
+        let header = &block.header.inner;
+
+        let attributes_txs = attributes.inner().transactions.as_deref().unwrap_or_default();
+        let attributes_txs_hash = Self::transactions_hash(attributes_txs.iter().map(keccak256));
+        let block_txs_hash = Self::transactions_hash(block.transactions.hashes());
+
+        let withdrawals_root = if config.is_isthmus_active(header.timestamp) {
+            None
+        } else {
+            let expected = payload_attributes
+                .withdrawals
+                .as_deref()
+                .map(alloy_consensus::proofs::calculate_withdrawals_root);
+            Self::differ(expected, header.withdrawals_root)
+        };
+
+        Self {
+            timestamp: Self::differ(payload_attributes.timestamp, header.timestamp),
+            prev_randao: Self::differ(payload_attributes.prev_randao, header.mix_hash),
+            transactions_hash: Self::differ(attributes_txs_hash, block_txs_hash),
+            gas_limit: (attributes.inner().gas_limit != Some(header.gas_limit))
+                .then_some((attributes.inner().gas_limit, header.gas_limit)),
+            withdrawals_root,
+        }
+    }
+
+    /// Returns true if none of the compared fields differ. The attributes may still mismatch the
+    /// block on other fields, see [`AttributesMatch::check`].
+    pub const fn is_empty(&self) -> bool {
+        self.timestamp.is_none() &&
+            self.prev_randao.is_none() &&
+            self.transactions_hash.is_none() &&
+            self.gas_limit.is_none() &&
+            self.withdrawals_root.is_none()
+    }
+
+    /// Returns the label of the first field that differs, in the order of [`AttributesDiff`]'s
+    /// fields, or [`Metrics::ATTRIBUTES_MISMATCH_OTHER`] if none of them do.
+    pub const fn first_mismatch(&self) -> &'static str {
+        if self.timestamp.is_some() {
+            Metrics::ATTRIBUTES_MISMATCH_TIMESTAMP
+        } else if self.prev_randao.is_some() {
+            Metrics::ATTRIBUTES_MISMATCH_PREV_RANDAO
+        } else if self.transactions_hash.is_some() {
+            Metrics::ATTRIBUTES_MISMATCH_TRANSACTIONS
+        } else if self.gas_limit.is_some() {
+            Metrics::ATTRIBUTES_MISMATCH_GAS_LIMIT
+        } else if self.withdrawals_root.is_some() {
+            Metrics::ATTRIBUTES_MISMATCH_WITHDRAWALS_ROOT
+        } else {
+            Metrics::ATTRIBUTES_MISMATCH_OTHER
+        }
+    }
+
+    /// Returns the given values if they differ.
+    fn differ<T: PartialEq>(attributes: T, block: T) -> Option<(T, T)> {
+        (attributes != block).then_some((attributes, block))
+    }
+
+    /// Hashes an ordered list of transaction hashes.
+    fn transactions_hash(hashes: impl Iterator<Item = B256>) -> B256 {
+        let mut hasher = Keccak256::new();
+        hashes.for_each(|hash| hasher.update(hash));
+        hasher.finalize()
+    }
+}
+
+impl core::fmt::Display for AttributesDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("none of the compared fields differ");
+        }
+        let fields = [
+            self.timestamp
+                .map(|(a, b)| (Metrics::ATTRIBUTES_MISMATCH_TIMESTAMP, format!("{a} != {b}"))),
+            self.prev_randao
+                .map(|(a, b)| (Metrics::ATTRIBUTES_MISMATCH_PREV_RANDAO, format!("{a} != {b}"))),
+            self.transactions_hash
+                .map(|(a, b)| (Metrics::ATTRIBUTES_MISMATCH_TRANSACTIONS, format!("{a} != {b}"))),
+            self.gas_limit
+                .map(|(a, b)| (Metrics::ATTRIBUTES_MISMATCH_GAS_LIMIT, format!("{a:?} != {b}"))),
+            self.withdrawals_root.map(|(a, b)| {
+                (Metrics::ATTRIBUTES_MISMATCH_WITHDRAWALS_ROOT, format!("{a:?} != {b:?}"))
+            }),
+        ];
+        let fields: Vec<_> =
+            fields.into_iter().flatten().map(|(name, diff)| format!("{name}: {diff}")).collect();
+        f.write_str(&fields.join(", "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -931,4 +1051,49 @@ mod tests {
         assert_eq!(check, AttributesMatch::Match);
         assert!(check.is_match());
     }
+
+    #[test]
+    fn test_attributes_diff_empty_on_match() {
+        let cfg = default_rollup_config();
+        let (attributes, block) = test_transactions_match_helper();
+        let diff = AttributesDiff::new(cfg, &attributes, &block);
+        assert!(diff.is_empty());
+        assert_eq!(diff.first_mismatch(), Metrics::ATTRIBUTES_MISMATCH_OTHER);
+    }
+
+    #[test]
+    fn test_attributes_diff_fields() {
+        let cfg = default_rollup_config();
+        let (mut attributes, mut block) = test_transactions_match_helper();
+        attributes.inner.payload_attributes.prev_randao = B256::with_last_byte(1);
+        attributes.inner.gas_limit = None;
+        attributes.inner.payload_attributes.withdrawals = Some(vec![]);
+        let BlockTransactions::Full(ref mut txs) = block.transactions else { unreachable!() };
+        txs.swap(0, 1);
+
+        let diff = AttributesDiff::new(cfg, &attributes, &block);
+        assert_eq!(diff.timestamp, None);
+        assert_eq!(diff.prev_randao, Some((B256::with_last_byte(1), B256::ZERO)));
+        assert!(diff.transactions_hash.is_some_and(|(a, b)| a != b));
+        assert_eq!(diff.gas_limit, Some((None, 0)));
+        assert_eq!(diff.withdrawals_root, Some((Some(EMPTY_ROOT_HASH), None)));
+        assert_eq!(diff.first_mismatch(), Metrics::ATTRIBUTES_MISMATCH_PREV_RANDAO);
+
+        let display = diff.to_string();
+        assert!(display.starts_with("prev_randao: "), "{display}");
+        assert!(display.contains(", gas_limit: None != 0, "), "{display}");
+    }
+
+    #[test]
+    fn test_attributes_diff_first_mismatch_timestamp() {
+        let cfg = default_rollup_config();
+        let mut attributes = default_attributes();
+        attributes.inner.gas_limit = Some(1);
+        attributes.inner.payload_attributes.timestamp = 2;
+        let diff = AttributesDiff::new(cfg, &attributes, &Block::<Transaction>::default());
+        assert_eq!(diff.timestamp, Some((2, 0)));
+        assert_eq!(diff.gas_limit, Some((Some(1), 0)));
+        assert_eq!(diff.transactions_hash, None);
+        assert_eq!(diff.first_mismatch(), Metrics::ATTRIBUTES_MISMATCH_TIMESTAMP);
+    }
 }
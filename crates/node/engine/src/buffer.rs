@@ -0,0 +1,477 @@
+//! Contains the [`UnsafePayloadBuffer`], which holds gossiped unsafe payloads that arrive ahead of
+//! the unsafe head until they can be inserted in order.
+
+use crate::Metrics;
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::{ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3};
+use kona_protocol::{L2BlockInfo, MemoryAudit, MemoryComponent};
+use op_alloy_rpc_types_engine::{
+    OpExecutionPayload, OpExecutionPayloadEnvelope, OpExecutionPayloadV4,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// The default number of payloads held by the [`UnsafePayloadBuffer`].
+pub const DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY: usize = 256;
+
+/// A bounded buffer of unsafe payloads, ordered by block number.
+///
+/// Payloads that arrive ahead of the unsafe head, either out-of-order or while the execution layer
+/// is syncing, are held in the buffer instead of being dropped. Once the unsafe head catches up,
+/// [`UnsafePayloadBuffer::pop_next`] yields the payload that extends it.
+///
+/// When the buffer is full, the payloads furthest ahead of the unsafe head are evicted first. If
+/// a directory is configured, each buffered payload is also written to disk, so that the buffer
+/// survives restarts.
+#[derive(Debug)]
+pub struct UnsafePayloadBuffer {
+    /// The buffered payloads, keyed by block number.
+    payloads: BTreeMap<u64, OpExecutionPayloadEnvelope>,
+    /// The maximum number of buffered payloads.
+    capacity: usize,
+    /// The directory the buffered payloads are persisted to, if any.
+    dir: Option<PathBuf>,
+    /// The audit the size of the buffered payloads is recorded into, if any.
+    audit: Option<MemoryAudit>,
+}
+
+impl Default for UnsafePayloadBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY)
+    }
+}
+
+impl UnsafePayloadBuffer {
+    /// Creates a new, in-memory [`UnsafePayloadBuffer`] with the given capacity.
+    pub const fn new(capacity: usize) -> Self {
+        Self { payloads: BTreeMap::new(), capacity, dir: None, audit: None }
+    }
+
+    /// Creates a new [`UnsafePayloadBuffer`] that persists its payloads to the given directory,
+    /// loading any payloads left over from a previous run. Persisted payloads that cannot be read
+    /// back, e.g. because the node was killed while writing them, are logged and discarded.
+    pub fn with_persistence(
+        capacity: usize,
+        dir: impl Into<PathBuf>,
+    ) -> Result<Self, UnsafePayloadBufferError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut payloads = BTreeMap::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match Self::load(&path) {
+                Ok(envelope) => {
+                    payloads.insert(envelope.payload.block_number(), envelope);
+                }
+                Err(err) => {
+                    warn!(target: "engine", ?path, ?err, "Discarding unreadable persisted unsafe payload");
+                    if let Err(err) = fs::remove_file(&path) {
+                        warn!(target: "engine", ?path, ?err, "Failed to remove persisted unsafe payload");
+                    }
+                }
+            }
+        }
+
+        let mut buffer = Self { payloads, capacity, dir: Some(dir), audit: None };
+        while buffer.payloads.len() > capacity {
+            let Some((&number, _)) = buffer.payloads.last_key_value() else { break };
+            buffer.remove(number);
+        }
+        buffer.update_metrics();
+        Ok(buffer)
+    }
+
+    /// Reads back the payload persisted at the given path.
+    fn load(path: &Path) -> Result<OpExecutionPayloadEnvelope, UnsafePayloadBufferError> {
+        Ok(serde_json::from_slice::<PersistedPayload>(&fs::read(path)?)?.into())
+    }
+
+    /// Records the size of the buffered payloads into the given [`MemoryAudit`].
+    pub fn with_memory_audit(mut self, audit: MemoryAudit) -> Self {
+        self.audit = Some(audit);
+        self.update_metrics();
+        self
+    }
+
+    /// Returns the number of bytes held by the buffered payloads, counting their transactions.
+    pub fn size(&self) -> usize {
+        self.payloads
+            .values()
+            .map(|envelope| {
+                size_of::<OpExecutionPayloadEnvelope>() +
+                    envelope.payload.as_v1().transactions.iter().map(|tx| tx.len()).sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Returns the number of buffered payloads.
+    pub fn len(&self) -> usize {
+        self.payloads.len()
+    }
+
+    /// Returns `true` if there are no buffered payloads.
+    pub fn is_empty(&self) -> bool {
+        self.payloads.is_empty()
+    }
+
+    /// Returns the maximum number of buffered payloads.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if a payload for the given block number is buffered.
+    pub fn contains(&self, number: u64) -> bool {
+        self.payloads.contains_key(&number)
+    }
+
+    /// Returns the buffered payload furthest ahead of the unsafe head, if any.
+    pub fn latest(&self) -> Option<&OpExecutionPayloadEnvelope> {
+        self.payloads.last_key_value().map(|(_, envelope)| envelope)
+    }
+
+    /// Buffers a payload. Returns `false` if the payload was not buffered, because it is not ahead
+    /// of the unsafe head, or because the buffer is full of payloads closer to the unsafe head.
+    ///
+    /// A payload replaces any buffered payload with the same block number, as it is more likely to
+    /// be canonical after an unsafe reorg.
+    pub fn insert(
+        &mut self,
+        envelope: OpExecutionPayloadEnvelope,
+        unsafe_head: &L2BlockInfo,
+    ) -> bool {
+        let number = envelope.payload.block_number();
+        if number <= unsafe_head.block_info.number || self.capacity == 0 {
+            return false;
+        }
+        if self
+            .payloads
+            .get(&number)
+            .is_some_and(|p| p.payload.block_hash() == envelope.payload.block_hash())
+        {
+            return true;
+        }
+
+        if !self.payloads.contains_key(&number) && self.payloads.len() >= self.capacity {
+            match self.payloads.last_key_value() {
+                Some((&last, _)) if last > number => {
+                    self.remove(last);
+                }
+                _ => return false,
+            }
+        }
+
+        self.persist(&envelope);
+        self.payloads.insert(number, envelope);
+        self.update_metrics();
+        true
+    }
+
+    /// Returns the buffered payload that extends the given unsafe head, if any. Payloads at or
+    /// behind the unsafe head are discarded.
+    ///
+    /// If the buffered payload at the next block number does not build on the unsafe head, the
+    /// unsafe chain has reorged away from the buffered payloads. The payload, and the buffered
+    /// payloads that build on it, are discarded.
+    pub fn pop_next(&mut self, unsafe_head: &L2BlockInfo) -> Option<OpExecutionPayloadEnvelope> {
+        let head = unsafe_head.block_info.number;
+        while let Some((&number, _)) = self.payloads.first_key_value() {
+            if number > head {
+                break;
+            }
+            self.remove(number);
+        }
+        self.update_metrics();
+
+        let (&number, next) = self.payloads.first_key_value()?;
+        if number != head + 1 {
+            return None;
+        }
+
+        if next.payload.parent_hash() == unsafe_head.block_info.hash {
+            let next = self.remove(number);
+            self.update_metrics();
+            return next;
+        }
+
+        warn!(
+            target: "engine",
+            number,
+            parent_hash = %next.payload.parent_hash(),
+            unsafe_head = %unsafe_head.block_info.hash,
+            "Buffered unsafe payload does not extend the unsafe head, discarding its chain"
+        );
+        self.discard_chain(number);
+        self.update_metrics();
+        None
+    }
+
+    /// Discards all buffered payloads.
+    pub fn clear(&mut self) {
+        let numbers: Vec<_> = self.payloads.keys().copied().collect();
+        numbers.into_iter().for_each(|n| {
+            self.remove(n);
+        });
+        self.update_metrics();
+    }
+
+    /// Discards the buffered payload at the given block number, and every consecutive buffered
+    /// payload that builds on it.
+    fn discard_chain(&mut self, mut number: u64) {
+        let Some(first) = self.remove(number) else { return };
+        let mut parent: B256 = first.payload.block_hash();
+        while self.payloads.get(&(number + 1)).is_some_and(|p| p.payload.parent_hash() == parent) {
+            number += 1;
+            parent = self.remove(number).map(|p| p.payload.block_hash()).unwrap_or_default();
+        }
+    }
+
+    /// Removes the payload at the given block number from the buffer and from disk.
+    fn remove(&mut self, number: u64) -> Option<OpExecutionPayloadEnvelope> {
+        let envelope = self.payloads.remove(&number)?;
+        if let Some(dir) = &self.dir {
+            if let Err(err) = fs::remove_file(Self::path(dir, number)) {
+                warn!(target: "engine", number, ?err, "Failed to remove persisted unsafe payload");
+            }
+        }
+        Some(envelope)
+    }
+
+    /// Writes the payload to disk, if persistence is enabled. Failures are logged, and the
+    /// payload is kept in memory.
+    fn persist(&self, envelope: &OpExecutionPayloadEnvelope) {
+        let Some(dir) = &self.dir else { return };
+        let number = envelope.payload.block_number();
+        let result = serde_json::to_vec(&PersistedPayload::from(envelope.clone()))
+            .map_err(UnsafePayloadBufferError::from)
+            .and_then(|bytes| Ok(fs::write(Self::path(dir, number), bytes)?));
+        if let Err(err) = result {
+            warn!(target: "engine", number, ?err, "Failed to persist unsafe payload");
+        }
+    }
+
+    /// Returns the path of the persisted payload at the given block number.
+    fn path(dir: &Path, number: u64) -> PathBuf {
+        dir.join(format!("{number}.json"))
+    }
+
+    /// Updates the buffer size gauge, and records the size of the buffer into the audit, if any.
+    fn update_metrics(&self) {
+        kona_macros::set!(gauge, Metrics::UNSAFE_PAYLOAD_BUFFER_SIZE, self.payloads.len() as f64);
+        if let Some(audit) = &self.audit {
+            audit.record(MemoryComponent::UnsafePayloads, self.size());
+        }
+    }
+}
+
+/// The on-disk format of a buffered payload.
+///
+/// The payload version is stored explicitly, since [`OpExecutionPayload`] is deserialized by
+/// trying each version in turn, which cannot tell a V1 payload apart from a V2 payload.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PersistedPayload {
+    /// The parent beacon block root, if any.
+    parent_beacon_block_root: Option<B256>,
+    /// The versioned execution payload.
+    payload: PersistedPayloadVersion,
+}
+
+/// A versioned execution payload, as persisted by the [`UnsafePayloadBuffer`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "version", content = "payload", rename_all = "lowercase")]
+enum PersistedPayloadVersion {
+    /// A V1 payload.
+    V1(ExecutionPayloadV1),
+    /// A V2 payload.
+    V2(ExecutionPayloadV2),
+    /// A V3 payload.
+    V3(ExecutionPayloadV3),
+    /// A V4 payload.
+    V4(OpExecutionPayloadV4),
+}
+
+impl From<OpExecutionPayloadEnvelope> for PersistedPayload {
+    fn from(envelope: OpExecutionPayloadEnvelope) -> Self {
+        let payload = match envelope.payload {
+            OpExecutionPayload::V1(payload) => PersistedPayloadVersion::V1(payload),
+            OpExecutionPayload::V2(payload) => PersistedPayloadVersion::V2(payload),
+            OpExecutionPayload::V3(payload) => PersistedPayloadVersion::V3(payload),
+            OpExecutionPayload::V4(payload) => PersistedPayloadVersion::V4(payload),
+        };
+        Self { parent_beacon_block_root: envelope.parent_beacon_block_root, payload }
+    }
+}
+
+impl From<PersistedPayload> for OpExecutionPayloadEnvelope {
+    fn from(persisted: PersistedPayload) -> Self {
+        let payload = match persisted.payload {
+            PersistedPayloadVersion::V1(payload) => OpExecutionPayload::V1(payload),
+            PersistedPayloadVersion::V2(payload) => OpExecutionPayload::V2(payload),
+            PersistedPayloadVersion::V3(payload) => OpExecutionPayload::V3(payload),
+            PersistedPayloadVersion::V4(payload) => OpExecutionPayload::V4(payload),
+        };
+        Self { parent_beacon_block_root: persisted.parent_beacon_block_root, payload }
+    }
+}
+
+/// An error from the [`UnsafePayloadBuffer`].
+#[derive(Error, Debug)]
+pub enum UnsafePayloadBufferError {
+    /// An I/O error on the persistence directory.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A persisted payload could not be (de)serialized.
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloy_primitives::{Address, Bloom, Bytes, U256};
+    use kona_protocol::BlockInfo;
+
+    pub(crate) fn hash(n: u64) -> B256 {
+        B256::with_last_byte(n as u8)
+    }
+
+    pub(crate) fn envelope(
+        number: u64,
+        parent_hash: B256,
+        block_hash: B256,
+    ) -> OpExecutionPayloadEnvelope {
+        OpExecutionPayloadEnvelope {
+            parent_beacon_block_root: None,
+            payload: OpExecutionPayload::V1(ExecutionPayloadV1 {
+                parent_hash,
+                fee_recipient: Address::ZERO,
+                state_root: B256::ZERO,
+                receipts_root: B256::ZERO,
+                logs_bloom: Bloom::ZERO,
+                prev_randao: B256::ZERO,
+                block_number: number,
+                gas_limit: 0,
+                gas_used: 0,
+                timestamp: number,
+                extra_data: Bytes::new(),
+                base_fee_per_gas: U256::ZERO,
+                block_hash,
+                transactions: vec![],
+            }),
+        }
+    }
+
+    fn head(number: u64) -> L2BlockInfo {
+        L2BlockInfo {
+            block_info: BlockInfo { number, hash: hash(number), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_buffer_drains_in_order() {
+        let mut buffer = UnsafePayloadBuffer::new(8);
+        assert!(buffer.insert(envelope(3, hash(2), hash(3)), &head(1)));
+        assert!(buffer.insert(envelope(2, hash(1), hash(2)), &head(1)));
+        assert!(!buffer.insert(envelope(1, hash(0), hash(1)), &head(1)));
+        assert_eq!(buffer.latest().unwrap().payload.block_number(), 3);
+
+        assert_eq!(buffer.pop_next(&head(1)).unwrap().payload.block_number(), 2);
+        assert_eq!(buffer.pop_next(&head(2)).unwrap().payload.block_number(), 3);
+        assert!(buffer.pop_next(&head(3)).is_none());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_buffer_waits_for_gap() {
+        let mut buffer = UnsafePayloadBuffer::new(8);
+        assert!(buffer.insert(envelope(5, hash(4), hash(5)), &head(1)));
+        assert!(buffer.pop_next(&head(1)).is_none());
+        assert_eq!(buffer.len(), 1);
+
+        // The unsafe head passes the buffered payload.
+        assert!(buffer.pop_next(&head(6)).is_none());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_buffer_evicts_furthest() {
+        let mut buffer = UnsafePayloadBuffer::new(2);
+        assert!(buffer.insert(envelope(3, hash(2), hash(3)), &head(1)));
+        assert!(buffer.insert(envelope(4, hash(3), hash(4)), &head(1)));
+        assert!(!buffer.insert(envelope(5, hash(4), hash(5)), &head(1)));
+        assert!(buffer.insert(envelope(2, hash(1), hash(2)), &head(1)));
+        assert!(buffer.contains(2) && buffer.contains(3) && !buffer.contains(4));
+    }
+
+    #[test]
+    fn test_buffer_discards_reorged_chain() {
+        let mut buffer = UnsafePayloadBuffer::new(8);
+        let fork = |n: u64| B256::with_last_byte(0x80 | n as u8);
+        assert!(buffer.insert(envelope(2, fork(1), fork(2)), &head(0)));
+        assert!(buffer.insert(envelope(3, fork(2), fork(3)), &head(0)));
+        assert!(buffer.insert(envelope(5, hash(4), hash(5)), &head(0)));
+
+        assert!(buffer.pop_next(&head(1)).is_none());
+        assert!(!buffer.contains(2) && !buffer.contains(3));
+        assert!(buffer.contains(5));
+    }
+
+    #[test]
+    fn test_buffer_records_size() {
+        let audit = MemoryAudit::new();
+        let mut buffer = UnsafePayloadBuffer::new(8).with_memory_audit(audit.clone());
+        let mut payload = envelope(2, hash(1), hash(2));
+        payload.payload.as_v1_mut().transactions = vec![Bytes::from(vec![0; 100])];
+        assert!(buffer.insert(payload, &head(1)));
+        assert!(buffer.insert(envelope(3, hash(2), hash(3)), &head(1)));
+
+        let size = 2 * size_of::<OpExecutionPayloadEnvelope>() + 100;
+        assert_eq!(buffer.size(), size);
+        assert_eq!(audit.usage(MemoryComponent::UnsafePayloads).current, size);
+
+        buffer.clear();
+        let usage = audit.usage(MemoryComponent::UnsafePayloads);
+        assert_eq!((usage.current, usage.peak), (0, size));
+    }
+
+    #[test]
+    fn test_buffer_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut buffer = UnsafePayloadBuffer::with_persistence(8, dir.path()).unwrap();
+            assert!(buffer.insert(envelope(2, hash(1), hash(2)), &head(1)));
+            assert!(buffer.insert(envelope(3, hash(2), hash(3)), &head(1)));
+            assert_eq!(buffer.pop_next(&head(1)).unwrap().payload.block_number(), 2);
+        }
+
+        let mut buffer = UnsafePayloadBuffer::with_persistence(8, dir.path()).unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.pop_next(&head(2)).unwrap().payload.block_number(), 3);
+    }
+
+    #[test]
+    fn test_buffer_persistence_skips_corrupt_payloads() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut buffer = UnsafePayloadBuffer::with_persistence(8, dir.path()).unwrap();
+            assert!(buffer.insert(envelope(2, hash(1), hash(2)), &head(1)));
+        }
+        let corrupt = UnsafePayloadBuffer::path(dir.path(), 3);
+        fs::write(&corrupt, b"{\"version\":").unwrap();
+
+        let mut buffer = UnsafePayloadBuffer::with_persistence(8, dir.path()).unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert!(!corrupt.exists());
+        assert_eq!(buffer.pop_next(&head(1)).unwrap().payload.block_number(), 2);
+    }
+}
@@ -0,0 +1,316 @@
+//! A cache of the responses of the execution layer to idempotent queries.
+//!
+//! Engine resets walk the L2 chain back by hash from the heads of the execution layer, and
+//! repeated resets walk the same blocks again. The [`EngineCache`] answers these lookups from
+//! memory. It is applied to the transports of the [`EngineClient`] with the [`EngineCacheLayer`].
+//!
+//! [`EngineClient`]: crate::EngineClient
+
+use crate::Metrics;
+use alloy_json_rpc::{RequestPacket, Response, ResponsePacket, ResponsePayload};
+use alloy_primitives::B256;
+use alloy_transport::{TransportError, TransportFut};
+use lru::LruCache;
+use serde_json::value::RawValue;
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+/// The default number of responses held by an [`EngineCache`].
+pub const DEFAULT_ENGINE_CACHE_SIZE: usize = 256;
+
+/// The methods whose responses are cached.
+///
+/// Their responses never go stale: blocks and payload bodies are looked up by hash, so the same
+/// request always returns the same content, and the chain id is constant. Lookups by number or
+/// by label are not cached, since they change with the canonical chain.
+pub const CACHED_METHODS: [&str; 3] =
+    ["eth_getBlockByHash", "engine_getPayloadBodiesByHashV1", "eth_chainId"];
+
+/// The hits and misses of an [`EngineCache`] so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineCacheStats {
+    /// The number of requests answered from the cache.
+    pub hits: u64,
+    /// The number of requests to [cached methods][CACHED_METHODS] forwarded to the execution
+    /// layer.
+    pub misses: u64,
+}
+
+impl EngineCacheStats {
+    /// Returns the fraction of the requests to cached methods that were answered from the cache.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 { 0.0 } else { self.hits as f64 / total as f64 }
+    }
+}
+
+/// A cache of the responses of the execution layer to the [`CACHED_METHODS`].
+///
+/// Only successful responses holding a result are cached: an unknown block, or a payload body
+/// missing from a batch, is looked up again on the next request, since the execution layer may
+/// have imported it in the meantime.
+///
+/// Clones share the same entries and [`EngineCacheStats`], so that a single cache can be applied
+/// to several transports.
+#[derive(Debug, Clone)]
+pub struct EngineCache {
+    inner: Arc<EngineCacheInner>,
+}
+
+/// The shared state of an [`EngineCache`].
+#[derive(Debug)]
+struct EngineCacheInner {
+    /// The cached results, keyed by method and hash of the parameters.
+    entries: Mutex<LruCache<(&'static str, B256), Box<RawValue>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Default for EngineCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_ENGINE_CACHE_SIZE)
+    }
+}
+
+impl EngineCache {
+    /// Creates a new [`EngineCache`] holding up to the given number of responses.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            inner: Arc::new(EngineCacheInner {
+                entries: Mutex::new(LruCache::new(capacity)),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Returns the [`EngineCacheStats`] of the cache so far.
+    pub fn stats(&self) -> EngineCacheStats {
+        EngineCacheStats {
+            hits: self.inner.hits.load(Ordering::Relaxed),
+            misses: self.inner.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the number of cached responses.
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    /// Returns true if no response is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    /// Drops all cached responses, e.g. after the execution layer was wiped.
+    pub fn clear(&self) {
+        self.entries().clear();
+    }
+
+    fn entries(&self) -> MutexGuard<'_, LruCache<(&'static str, B256), Box<RawValue>>> {
+        self.inner.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the cached result for the given key, recording a hit or a miss.
+    fn get(&self, key: &(&'static str, B256)) -> Option<Box<RawValue>> {
+        let result = self.entries().get(key).cloned();
+        let label = if result.is_some() {
+            self.inner.hits.fetch_add(1, Ordering::Relaxed);
+            Metrics::ENGINE_CACHE_HIT
+        } else {
+            self.inner.misses.fetch_add(1, Ordering::Relaxed);
+            Metrics::ENGINE_CACHE_MISS
+        };
+        kona_macros::inc!(counter, Metrics::ENGINE_CACHE_REQUESTS, "method" => key.0, "result" => label);
+        result
+    }
+
+    /// Caches the given result, if it is complete.
+    fn insert(&self, key: (&'static str, B256), result: &RawValue) {
+        if !Self::is_complete(key.0, result) {
+            return;
+        }
+        self.entries().put(key, result.to_owned());
+    }
+
+    /// Returns true if the result holds everything that was queried.
+    fn is_complete(method: &str, result: &RawValue) -> bool {
+        match method {
+            "engine_getPayloadBodiesByHashV1" => {
+                serde_json::from_str::<Vec<Option<&RawValue>>>(result.get())
+                    .is_ok_and(|bodies| bodies.iter().all(Option::is_some))
+            }
+            _ => result.get() != "null",
+        }
+    }
+}
+
+/// A [`tower::Layer`] answering the requests of an RPC transport to the [`CACHED_METHODS`] from
+/// an [`EngineCache`]. Batched requests are forwarded as-is.
+#[derive(Debug, Clone)]
+pub struct EngineCacheLayer {
+    cache: EngineCache,
+}
+
+impl EngineCacheLayer {
+    /// Creates a new [`EngineCacheLayer`] for the given cache.
+    pub const fn new(cache: EngineCache) -> Self {
+        Self { cache }
+    }
+}
+
+impl<S> tower::Layer<S> for EngineCacheLayer {
+    type Service = EngineCacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        EngineCacheService { inner, cache: self.cache.clone() }
+    }
+}
+
+/// An RPC transport answering requests from an [`EngineCache`]. See [`EngineCacheLayer`].
+#[derive(Debug, Clone)]
+pub struct EngineCacheService<S> {
+    inner: S,
+    cache: EngineCache,
+}
+
+impl<S> tower::Service<RequestPacket> for EngineCacheService<S>
+where
+    S: tower::Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        // Take the service that was driven to readiness, leaving a clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let RequestPacket::Single(req) = &request else {
+            return inner.call(request);
+        };
+        let Some(method) = CACHED_METHODS.into_iter().find(|method| *method == req.method()) else {
+            return inner.call(request);
+        };
+        let key = (method, req.params_hash());
+        if let Some(result) = self.cache.get(&key) {
+            let response =
+                Response { id: req.id().clone(), payload: ResponsePayload::Success(result) };
+            return Box::pin(async move { Ok(ResponsePacket::Single(response)) });
+        }
+
+        let cache = self.cache.clone();
+        Box::pin(async move {
+            let response = inner.call(request).await?;
+            if let ResponsePacket::Single(Response {
+                payload: ResponsePayload::Success(result),
+                ..
+            }) = &response
+            {
+                cache.insert(key, result);
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockEngineClient;
+    use alloy_consensus::Header;
+    use alloy_eips::eip1898::BlockNumberOrTag;
+    use alloy_provider::Provider;
+    use kona_genesis::RollupConfig;
+    use op_alloy_rpc_types::Transaction;
+
+    fn block(number: u64) -> alloy_rpc_types_eth::Block<Transaction> {
+        let header = Header { number, ..Default::default() };
+        alloy_rpc_types_eth::Block {
+            header: alloy_rpc_types_eth::Header::new(header),
+            ..Default::default()
+        }
+    }
+
+    fn calls(mock: &MockEngineClient, method: &str) -> usize {
+        mock.calls().iter().filter(|call| call.method == method).count()
+    }
+
+    #[tokio::test]
+    async fn test_caches_blocks_by_hash() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let block = block(1);
+        let hash = block.header.hash;
+        mock.insert_block(1, &block);
+        let client = mock.client();
+
+        for _ in 0..3 {
+            let fetched = client.l2_provider().get_block_by_hash(hash).full().await.unwrap();
+            assert_eq!(fetched.unwrap().header.hash, hash);
+        }
+        assert_eq!(calls(&mock, "eth_getBlockByHash"), 1);
+        assert_eq!(client.cache().stats(), EngineCacheStats { hits: 2, misses: 1 });
+        assert!((client.cache().stats().hit_rate() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        client.cache().clear();
+        client.l2_provider().get_block_by_hash(hash).full().await.unwrap();
+        assert_eq!(calls(&mock, "eth_getBlockByHash"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_cache_unknown_blocks() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let client = mock.client();
+        let block = block(1);
+        let hash = block.header.hash;
+
+        assert!(client.l2_provider().get_block_by_hash(hash).full().await.unwrap().is_none());
+        mock.insert_block(1, &block);
+        assert!(client.l2_provider().get_block_by_hash(hash).full().await.unwrap().is_some());
+        assert_eq!(calls(&mock, "eth_getBlockByHash"), 2);
+        assert_eq!(client.cache().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_cache_blocks_by_number() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        mock.insert_block(1, &block(1));
+        let client = mock.client();
+
+        for _ in 0..2 {
+            client.l2_block_by_label(BlockNumberOrTag::Number(1)).await.unwrap().unwrap();
+        }
+        assert_eq!(calls(&mock, "eth_getBlockByNumber"), 2);
+        assert!(client.cache().is_empty());
+        assert_eq!(client.cache().stats(), EngineCacheStats::default());
+    }
+
+    #[test]
+    fn test_payload_bodies_completeness() {
+        let complete = RawValue::from_string(r#"[{"transactions":[],"withdrawals":null}]"#.into());
+        let partial = RawValue::from_string(r#"[{"transactions":[]},null]"#.into());
+        let method = "engine_getPayloadBodiesByHashV1";
+        assert!(EngineCache::is_complete(method, &complete.unwrap()));
+        assert!(!EngineCache::is_complete(method, &partial.unwrap()));
+        assert!(!EngineCache::is_complete("eth_getBlockByHash", &RawValue::NULL.to_owned()));
+    }
+}
@@ -0,0 +1,267 @@
+//! Contains the [`ElChainConfig`], the chain configuration reported by the execution layer, which
+//! the attributes of block builds are validated against before they are sent to it.
+
+use crate::{EngineClient, EngineClientError};
+use alloy_provider::Provider;
+use kona_genesis::RollupConfig;
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The method returning the chain configuration of the execution layer.
+pub const CHAIN_CONFIG_METHOD: &str = "debug_chainConfig";
+
+/// The blob parameters of a fork, as reported by the execution layer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElBlobParams {
+    /// The target number of blobs per block.
+    pub target: u64,
+    /// The maximum number of blobs per block.
+    pub max: u64,
+    /// The update fraction of the blob base fee.
+    pub base_fee_update_fraction: u64,
+}
+
+/// A reduced chain configuration of the execution layer, as returned by
+/// [`debug_chainConfig`](CHAIN_CONFIG_METHOD), holding the activations that the shape of the
+/// payload attributes depends on.
+///
+/// The execution layer rejects attributes built for a fork that it activates at another time
+/// than the rollup config with an `INVALID` status that does not name the fork. Validating the
+/// attributes against this configuration first turns those into precise errors.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElChainConfig {
+    /// The chain ID.
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+    /// The activation time of Shanghai.
+    #[serde(default)]
+    pub shanghai_time: Option<u64>,
+    /// The activation time of Cancun.
+    #[serde(default)]
+    pub cancun_time: Option<u64>,
+    /// The activation time of Prague.
+    #[serde(default)]
+    pub prague_time: Option<u64>,
+    /// The activation time of Canyon.
+    #[serde(default)]
+    pub canyon_time: Option<u64>,
+    /// The activation time of Ecotone.
+    #[serde(default)]
+    pub ecotone_time: Option<u64>,
+    /// The activation time of Isthmus.
+    #[serde(default)]
+    pub isthmus_time: Option<u64>,
+    /// The blob parameters of each fork, by lowercase fork name.
+    #[serde(default)]
+    pub blob_schedule: BTreeMap<String, ElBlobParams>,
+}
+
+/// An error raised when payload attributes do not match the [`ElChainConfig`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AttributesPreflightError {
+    /// The rollup config and the execution layer disagree on whether a fork is active at the
+    /// timestamp of the attributes.
+    #[error(
+        "{fork} activation differs at timestamp {timestamp}: the rollup config activates it at {rollup:?}, the EL chain config at {el:?}"
+    )]
+    ForkMismatch {
+        /// The name of the fork.
+        fork: &'static str,
+        /// The timestamp of the attributes.
+        timestamp: u64,
+        /// The activation time of the fork in the rollup config.
+        rollup: Option<u64>,
+        /// The activation time of the fork in the EL chain config.
+        el: Option<u64>,
+    },
+    /// The execution layer reports a blob schedule that misses an active fork.
+    #[error("The EL blob schedule has no entry for {fork}, active at timestamp {timestamp}")]
+    MissingBlobSchedule {
+        /// The lowercase name of the fork.
+        fork: &'static str,
+        /// The timestamp of the attributes.
+        timestamp: u64,
+    },
+}
+
+impl ElChainConfig {
+    /// Validates the given attributes against the chain configuration, with the given
+    /// [`RollupConfig`]: the forks that the shape of the attributes depends on must be active in
+    /// both or in neither, and the blob schedule, if reported, must cover the active blob forks.
+    pub fn validate(
+        &self,
+        cfg: &RollupConfig,
+        attributes: &OpPayloadAttributes,
+    ) -> Result<(), AttributesPreflightError> {
+        let timestamp = attributes.payload_attributes.timestamp;
+        let forks = [
+            (
+                "Canyon",
+                cfg.is_canyon_active(timestamp),
+                cfg.hardforks.canyon_time,
+                self.canyon_time.or(self.shanghai_time),
+            ),
+            (
+                "Ecotone",
+                cfg.is_ecotone_active(timestamp),
+                cfg.hardforks.ecotone_time,
+                self.ecotone_time.or(self.cancun_time),
+            ),
+            (
+                "Isthmus",
+                cfg.is_isthmus_active(timestamp),
+                cfg.hardforks.isthmus_time,
+                self.isthmus_time.or(self.prague_time),
+            ),
+        ];
+        for (fork, rollup_active, rollup, el) in forks {
+            if rollup_active != el.is_some_and(|t| timestamp >= t) {
+                return Err(AttributesPreflightError::ForkMismatch { fork, timestamp, rollup, el });
+            }
+        }
+
+        if !self.blob_schedule.is_empty() {
+            for (fork, activation) in [("cancun", self.cancun_time), ("prague", self.prague_time)] {
+                let active = activation.is_some_and(|t| timestamp >= t);
+                if active && !self.blob_schedule.contains_key(fork) {
+                    return Err(AttributesPreflightError::MissingBlobSchedule { fork, timestamp });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EngineClient {
+    /// Fetches the [`ElChainConfig`] from the L2 RPC, and validates the attributes of the block
+    /// builds against it from then on. The configuration is only fetched once: subsequent calls
+    /// return the configuration fetched first.
+    pub async fn load_el_chain_config(&self) -> Result<&ElChainConfig, EngineClientError> {
+        if let Some(config) = self.el_chain_config.get() {
+            return Ok(config);
+        }
+        let config: ElChainConfig =
+            self.l2_provider.client().request_noparams(CHAIN_CONFIG_METHOD).await?;
+        if config.chain_id.is_some_and(|chain_id| chain_id != self.cfg.l2_chain_id) {
+            warn!(
+                target: "engine",
+                el = ?config.chain_id,
+                rollup = self.cfg.l2_chain_id,
+                "The EL chain config and the rollup config have different chain IDs"
+            );
+        }
+        Ok(self.el_chain_config.get_or_init(|| config))
+    }
+
+    /// Returns the [`ElChainConfig`], if it was [loaded](Self::load_el_chain_config).
+    pub fn el_chain_config(&self) -> Option<&ElChainConfig> {
+        self.el_chain_config.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kona_genesis::HardForkConfig;
+
+    fn attributes(timestamp: u64) -> OpPayloadAttributes {
+        let mut attributes = OpPayloadAttributes::default();
+        attributes.payload_attributes.timestamp = timestamp;
+        attributes
+    }
+
+    fn rollup_config() -> RollupConfig {
+        RollupConfig {
+            hardforks: HardForkConfig {
+                canyon_time: Some(0),
+                ecotone_time: Some(10),
+                isthmus_time: Some(20),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_deserialize_chain_config() {
+        let json = r#"{
+            "chainId": 10,
+            "shanghaiTime": 0,
+            "cancunTime": 10,
+            "pragueTime": 20,
+            "canyonTime": 0,
+            "ecotoneTime": 10,
+            "isthmusTime": 20,
+            "optimism": {"eip1559Elasticity": 6},
+            "blobSchedule": {"cancun": {"target": 3, "max": 6, "baseFeeUpdateFraction": 3338477}}
+        }"#;
+        let config: ElChainConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.chain_id, Some(10));
+        assert_eq!(config.isthmus_time, Some(20));
+        assert_eq!(config.blob_schedule["cancun"].max, 6);
+    }
+
+    #[test]
+    fn test_validate_matching_forks() {
+        let config = ElChainConfig {
+            canyon_time: Some(0),
+            ecotone_time: Some(10),
+            isthmus_time: Some(20),
+            ..Default::default()
+        };
+        for timestamp in [0, 10, 20, 30] {
+            assert_eq!(config.validate(&rollup_config(), &attributes(timestamp)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_validate_fork_mismatch() {
+        // The EL activates Isthmus later, through Prague only.
+        let config = ElChainConfig {
+            shanghai_time: Some(0),
+            cancun_time: Some(10),
+            prague_time: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(config.validate(&rollup_config(), &attributes(15)), Ok(()));
+        let err = config.validate(&rollup_config(), &attributes(25)).unwrap_err();
+        assert_eq!(
+            err,
+            AttributesPreflightError::ForkMismatch {
+                fork: "Isthmus",
+                timestamp: 25,
+                rollup: Some(20),
+                el: Some(30),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Isthmus activation differs at timestamp 25: the rollup config activates it at Some(20), the EL chain config at Some(30)"
+        );
+    }
+
+    #[test]
+    fn test_validate_blob_schedule() {
+        let mut config = ElChainConfig {
+            canyon_time: Some(0),
+            ecotone_time: Some(10),
+            isthmus_time: Some(20),
+            cancun_time: Some(10),
+            prague_time: Some(20),
+            ..Default::default()
+        };
+        // No blob schedule reported, nothing to validate.
+        assert_eq!(config.validate(&rollup_config(), &attributes(25)), Ok(()));
+
+        config.blob_schedule.insert("cancun".to_string(), ElBlobParams::default());
+        assert_eq!(config.validate(&rollup_config(), &attributes(15)), Ok(()));
+        assert_eq!(
+            config.validate(&rollup_config(), &attributes(25)),
+            Err(AttributesPreflightError::MissingBlobSchedule { fork: "prague", timestamp: 25 })
+        );
+    }
+}
@@ -1,8 +1,8 @@
 //! An Engine API Client.
 
-use crate::Metrics;
+use crate::{ElChainConfig, EngineCache, EngineCacheLayer, EngineTransport, Metrics};
 use alloy_eips::eip1898::BlockNumberOrTag;
-use alloy_network::{AnyNetwork, Network};
+use alloy_network::AnyNetwork;
 use alloy_primitives::{B256, BlockHash, Bytes};
 use alloy_provider::{Provider, RootProvider};
 use alloy_rpc_client::RpcClient;
@@ -11,13 +11,12 @@ use alloy_rpc_types_engine::{
     ExecutionPayloadV3, ForkchoiceState, ForkchoiceUpdated, JwtSecret, PayloadId, PayloadStatus,
 };
 use alloy_rpc_types_eth::Block;
-use alloy_transport::{RpcError, TransportErrorKind, TransportResult};
+use alloy_transport::{
+    BoxTransport, IntoBoxTransport, RpcError, TransportErrorKind, TransportResult,
+};
 use alloy_transport_http::{
-    AuthLayer, AuthService, Http, HyperClient,
-    hyper_util::{
-        client::legacy::{Client, connect::HttpConnector},
-        rt::TokioExecutor,
-    },
+    AuthService, Http, HyperClient,
+    hyper_util::client::legacy::{Client, connect::HttpConnector},
 };
 use derive_more::Deref;
 use http_body_util::Full;
@@ -30,9 +29,11 @@ use op_alloy_rpc_types_engine::{
     OpExecutionPayloadEnvelopeV3, OpExecutionPayloadEnvelopeV4, OpExecutionPayloadV4,
     OpPayloadAttributes, ProtocolVersion,
 };
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
-use tower::ServiceBuilder;
 use url::Url;
 
 /// An error that occurred in the [`EngineClient`].
@@ -47,35 +48,31 @@ pub enum EngineClientError {
     BlockInfoDecodeError(#[from] FromBlockError),
 }
 /// A Hyper HTTP client with a JWT authentication layer.
-type HyperAuthClient<B = Full<Bytes>> = HyperClient<B, AuthService<Client<HttpConnector, B>>>;
+pub(crate) type HyperAuthClient<B = Full<Bytes>> =
+    HyperClient<B, AuthService<Client<HttpConnector, B>>>;
 
 /// An external engine api client
 #[derive(Debug, Deref, Clone)]
 pub struct EngineClient {
     /// The L2 engine provider.
     #[deref]
-    engine: RootProvider<AnyNetwork>,
+    pub(crate) engine: RootProvider<AnyNetwork>,
     /// The L2 chain provider.
-    l2_provider: RootProvider<Optimism>,
+    pub(crate) l2_provider: RootProvider<Optimism>,
     /// The L1 chain provider.
-    l1_provider: RootProvider,
+    pub(crate) l1_provider: RootProvider,
     /// The [RollupConfig] for the chain used to timestamp which version of the engine api to use.
-    cfg: Arc<RollupConfig>,
+    pub(crate) cfg: Arc<RollupConfig>,
+    /// The [`EngineCache`] answering idempotent queries to the engine and L2 providers.
+    pub(crate) cache: EngineCache,
+    /// How long ahead of a hardfork the engine API calls are [shadowed](crate::shadow), if they
+    /// are.
+    pub(crate) shadow_lookahead: Option<Duration>,
+    /// The chain configuration of the execution layer, once loaded. Shared between clones.
+    pub(crate) el_chain_config: Arc<OnceLock<ElChainConfig>>,
 }
 
 impl EngineClient {
-    /// Creates a new RPC client for the given address and JWT secret.
-    fn rpc_client<T: Network>(addr: Url, jwt: JwtSecret) -> RootProvider<T> {
-        let hyper_client = Client::builder(TokioExecutor::new()).build_http::<Full<Bytes>>();
-        let auth_layer = AuthLayer::new(jwt);
-        let service = ServiceBuilder::new().layer(auth_layer).service(hyper_client);
-        let layer_transport = HyperClient::with_service(service);
-
-        let http_hyper = Http::with_client(layer_transport, addr);
-        let rpc_client = RpcClient::new(http_hyper, false);
-        RootProvider::<T>::new(rpc_client)
-    }
-
     /// Creates a new [`EngineClient`] from the provided [Url] and [JwtSecret].
     pub fn new_http(
         engine: Url,
@@ -84,11 +81,52 @@ impl EngineClient {
         cfg: Arc<RollupConfig>,
         jwt: JwtSecret,
     ) -> Self {
-        let engine = Self::rpc_client::<AnyNetwork>(engine, jwt);
-        let l2_provider = Self::rpc_client::<Optimism>(l2_rpc, jwt);
+        Self::new_with_transport(engine, l2_rpc, l1_rpc, cfg, jwt, &EngineTransport::default())
+    }
+
+    /// Creates a new [`EngineClient`] like [`Self::new_http`], reaching the engine API and L2 RPC
+    /// endpoints over the given [`EngineTransport`].
+    pub fn new_with_transport(
+        engine: Url,
+        l2_rpc: Url,
+        l1_rpc: Url,
+        cfg: Arc<RollupConfig>,
+        jwt: JwtSecret,
+        transport: &EngineTransport,
+    ) -> Self {
+        let cache = EngineCache::default();
+        let layer = EngineCacheLayer::new(cache.clone());
+        let engine = wrap(&transport.provider::<AnyNetwork>(engine, jwt), &layer);
+        let l2_provider = wrap(&transport.provider::<Optimism>(l2_rpc, jwt), &layer);
         let l1_provider = RootProvider::new_http(l1_rpc);
 
-        Self { engine, l2_provider, l1_provider, cfg }
+        Self {
+            engine,
+            l2_provider,
+            l1_provider,
+            cfg,
+            cache,
+            shadow_lookahead: None,
+            el_chain_config: Default::default(),
+        }
+    }
+
+    /// Wraps the transports of the engine, L2 and L1 providers in the given [`tower::Layer`], e.g.
+    /// to inject faults into the requests of the client.
+    pub fn with_layer<L>(self, layer: L) -> Self
+    where
+        L: tower::Layer<BoxTransport>,
+        L::Service: IntoBoxTransport,
+    {
+        Self {
+            engine: wrap(&self.engine, &layer),
+            l2_provider: wrap(&self.l2_provider, &layer),
+            l1_provider: wrap(&self.l1_provider, &layer),
+            cfg: self.cfg,
+            cache: self.cache,
+            shadow_lookahead: self.shadow_lookahead,
+            el_chain_config: self.el_chain_config,
+        }
     }
 
     /// Returns a reference to the inner L2 [`RootProvider`].
@@ -106,6 +144,11 @@ impl EngineClient {
         self.cfg.as_ref()
     }
 
+    /// Returns the [`EngineCache`] answering idempotent queries to the engine and L2 providers.
+    pub const fn cache(&self) -> &EngineCache {
+        &self.cache
+    }
+
     /// Fetches the [`Block<T>`] for the given [`BlockNumberOrTag`].
     pub async fn l2_block_by_label(
         &self,
@@ -283,6 +326,17 @@ impl OpEngineApi<AnyNetwork, Http<HyperAuthClient>> for EngineClient {
     }
 }
 
+/// Returns a copy of the given provider whose transport is wrapped in the given layer.
+fn wrap<N: Network, L>(provider: &RootProvider<N>, layer: &L) -> RootProvider<N>
+where
+    L: tower::Layer<BoxTransport>,
+    L::Service: IntoBoxTransport,
+{
+    let client = provider.client();
+    let transport = layer.layer(client.transport().clone());
+    RootProvider::new(RpcClient::new(transport, client.is_local()))
+}
+
 /// Wrapper to record the time taken for a call to the engine API and log the result as a metric.
 async fn record_call_time<T>(
     f: impl Future<Output = TransportResult<T>>,
@@ -0,0 +1,245 @@
+//! Contains the [`DelayedUnsafePayloads`] queue, which holds back gossiped unsafe payloads until
+//! they are old enough to be imported.
+
+use crate::Metrics;
+use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
+use std::{collections::BTreeMap, time::Duration};
+
+/// The minimum age of a gossiped unsafe payload before it may advance the unsafe head.
+///
+/// Follower nodes can hold back gossiped payloads as a safety margin against sequencer
+/// equivocation: a sequencer that gossips conflicting blocks for the same height within the delay
+/// is caught before either block is imported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnsafeDelay {
+    /// The number of seconds that must have passed since the payload's timestamp.
+    pub seconds: u64,
+    /// The number of payloads with a higher block number that must have been gossiped.
+    pub blocks: u64,
+}
+
+impl UnsafeDelay {
+    /// Returns `true` if payloads are held back at all.
+    pub const fn is_enabled(&self) -> bool {
+        self.seconds > 0 || self.blocks > 0
+    }
+}
+
+/// A bounded queue of gossiped unsafe payloads, held back until they satisfy the configured
+/// [`UnsafeDelay`].
+///
+/// Payloads are released in block number order by [`DelayedUnsafePayloads::pop_ready`]. If two
+/// payloads with different hashes are gossiped for the same block number while held back, the
+/// sequencer has equivocated, and both are discarded. When the queue is full, the payloads
+/// furthest ahead are evicted first.
+#[derive(Debug)]
+pub struct DelayedUnsafePayloads {
+    /// The held back payloads, keyed by block number.
+    payloads: BTreeMap<u64, OpExecutionPayloadEnvelope>,
+    /// The highest block number gossiped so far.
+    highest: u64,
+    /// The maximum number of held back payloads.
+    capacity: usize,
+}
+
+impl DelayedUnsafePayloads {
+    /// Creates a new, empty [`DelayedUnsafePayloads`] queue with the given capacity.
+    pub const fn new(capacity: usize) -> Self {
+        Self { payloads: BTreeMap::new(), highest: 0, capacity }
+    }
+
+    /// Returns the number of held back payloads.
+    pub fn len(&self) -> usize {
+        self.payloads.len()
+    }
+
+    /// Returns `true` if no payloads are held back.
+    pub fn is_empty(&self) -> bool {
+        self.payloads.is_empty()
+    }
+
+    /// Holds back a gossiped payload. Returns `false` if the payload was discarded, because it
+    /// conflicts with a held back payload, or because the queue is full of payloads closer to
+    /// release.
+    pub fn insert(&mut self, envelope: OpExecutionPayloadEnvelope) -> bool {
+        let number = envelope.payload.block_number();
+        self.highest = self.highest.max(number);
+
+        if let Some(held) = self.payloads.get(&number) {
+            if held.payload.block_hash() == envelope.payload.block_hash() {
+                return true;
+            }
+            warn!(
+                target: "engine",
+                number,
+                held = %held.payload.block_hash(),
+                gossiped = %envelope.payload.block_hash(),
+                "Sequencer equivocation detected, discarding conflicting unsafe payloads"
+            );
+            kona_macros::inc!(counter, Metrics::UNSAFE_PAYLOAD_EQUIVOCATIONS);
+            self.payloads.remove(&number);
+            self.update_metrics();
+            return false;
+        }
+
+        if self.payloads.len() >= self.capacity {
+            match self.payloads.last_key_value() {
+                Some((&last, _)) if last > number => {
+                    self.payloads.remove(&last);
+                }
+                _ => return false,
+            }
+        }
+
+        self.payloads.insert(number, envelope);
+        self.update_metrics();
+        true
+    }
+
+    /// Returns the lowest held back payload, if it satisfies the given [`UnsafeDelay`] at the
+    /// given unix timestamp.
+    pub fn pop_ready(
+        &mut self,
+        delay: UnsafeDelay,
+        now: u64,
+    ) -> Option<OpExecutionPayloadEnvelope> {
+        let (&number, next) = self.payloads.first_key_value()?;
+        let old_enough =
+            delay.seconds == 0 || next.payload.timestamp().saturating_add(delay.seconds) <= now;
+        let deep_enough = number.saturating_add(delay.blocks) <= self.highest;
+        if !old_enough || !deep_enough {
+            return None;
+        }
+        let next = self.payloads.remove(&number);
+        self.update_metrics();
+        next
+    }
+
+    /// Returns the time until the lowest held back payload satisfies the seconds-based part of
+    /// the given [`UnsafeDelay`], or `None` if the queue is empty or the payload is waiting on
+    /// newer payloads to be gossiped.
+    pub fn ready_in(&self, delay: UnsafeDelay, now: u64) -> Option<Duration> {
+        let (&number, next) = self.payloads.first_key_value()?;
+        if number.saturating_add(delay.blocks) > self.highest {
+            return None;
+        }
+        let ready_at = next.payload.timestamp().saturating_add(delay.seconds);
+        Some(Duration::from_secs(ready_at.saturating_sub(now)))
+    }
+
+    /// Discards all held back payloads, and forgets the highest block number gossiped so far, so
+    /// that payloads gossiped after a reset or a reorg of the unsafe chain are held back again.
+    pub fn clear(&mut self) {
+        self.payloads.clear();
+        self.highest = 0;
+        self.update_metrics();
+    }
+
+    /// Updates the held back payloads gauge.
+    fn update_metrics(&self) {
+        kona_macros::set!(gauge, Metrics::UNSAFE_DELAYED_PAYLOADS, self.payloads.len() as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, B256, Bloom, Bytes, U256};
+    use alloy_rpc_types_engine::ExecutionPayloadV1;
+    use op_alloy_rpc_types_engine::OpExecutionPayload;
+
+    fn envelope(number: u64, block_hash: u8, timestamp: u64) -> OpExecutionPayloadEnvelope {
+        OpExecutionPayloadEnvelope {
+            parent_beacon_block_root: None,
+            payload: OpExecutionPayload::V1(ExecutionPayloadV1 {
+                parent_hash: B256::ZERO,
+                fee_recipient: Address::ZERO,
+                state_root: B256::ZERO,
+                receipts_root: B256::ZERO,
+                logs_bloom: Bloom::ZERO,
+                prev_randao: B256::ZERO,
+                block_number: number,
+                gas_limit: 0,
+                gas_used: 0,
+                timestamp,
+                extra_data: Bytes::new(),
+                base_fee_per_gas: U256::ZERO,
+                block_hash: B256::with_last_byte(block_hash),
+                transactions: vec![],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_delay_by_seconds() {
+        let delay = UnsafeDelay { seconds: 10, blocks: 0 };
+        let mut queue = DelayedUnsafePayloads::new(8);
+        assert!(queue.insert(envelope(1, 1, 100)));
+
+        assert!(queue.pop_ready(delay, 105).is_none());
+        assert_eq!(queue.ready_in(delay, 105), Some(Duration::from_secs(5)));
+        assert_eq!(queue.pop_ready(delay, 110).unwrap().payload.block_number(), 1);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_delay_by_blocks() {
+        let delay = UnsafeDelay { seconds: 0, blocks: 2 };
+        let mut queue = DelayedUnsafePayloads::new(8);
+        assert!(queue.insert(envelope(1, 1, 0)));
+        assert!(queue.insert(envelope(2, 2, 0)));
+        assert!(queue.pop_ready(delay, 0).is_none());
+        assert_eq!(queue.ready_in(delay, 0), None);
+
+        assert!(queue.insert(envelope(3, 3, 0)));
+        assert_eq!(queue.pop_ready(delay, 0).unwrap().payload.block_number(), 1);
+        assert!(queue.pop_ready(delay, 0).is_none());
+    }
+
+    #[test]
+    fn test_disabled_delay_releases_immediately() {
+        let mut queue = DelayedUnsafePayloads::new(8);
+        assert!(queue.insert(envelope(1, 1, u64::MAX)));
+        assert!(queue.pop_ready(UnsafeDelay::default(), 0).is_some());
+    }
+
+    #[test]
+    fn test_clear_forgets_highest() {
+        let delay = UnsafeDelay { seconds: 0, blocks: 2 };
+        let mut queue = DelayedUnsafePayloads::new(8);
+        assert!(queue.insert(envelope(10, 10, 0)));
+        queue.clear();
+        assert!(queue.is_empty());
+
+        // After a reorg, payloads below the previously gossiped height are held back again.
+        assert!(queue.insert(envelope(5, 5, 0)));
+        assert!(queue.pop_ready(delay, 0).is_none());
+        assert!(queue.insert(envelope(7, 7, 0)));
+        assert_eq!(queue.pop_ready(delay, 0).unwrap().payload.block_number(), 5);
+    }
+
+    #[test]
+    fn test_equivocation_discards_both() {
+        let mut queue = DelayedUnsafePayloads::new(8);
+        assert!(queue.insert(envelope(1, 1, 0)));
+        assert!(queue.insert(envelope(1, 1, 0)));
+        assert_eq!(queue.len(), 1);
+
+        assert!(!queue.insert(envelope(1, 2, 0)));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_evicts_furthest() {
+        let mut queue = DelayedUnsafePayloads::new(2);
+        assert!(queue.insert(envelope(2, 2, 0)));
+        assert!(queue.insert(envelope(4, 4, 0)));
+        assert!(!queue.insert(envelope(5, 5, 0)));
+        assert!(queue.insert(envelope(3, 3, 0)));
+
+        let delay = UnsafeDelay::default();
+        assert_eq!(queue.pop_ready(delay, 0).unwrap().payload.block_number(), 2);
+        assert_eq!(queue.pop_ready(delay, 0).unwrap().payload.block_number(), 3);
+        assert!(queue.pop_ready(delay, 0).is_none());
+    }
+}
@@ -0,0 +1,305 @@
+//! Supervision of the execution layer sync.
+//!
+//! In [`SyncMode::ExecutionLayer`], the execution layer syncs the unsafe chain from the p2p
+//! network towards the unsafe payloads inserted by the engine, and derivation only starts once it
+//! has finished. The [`ElSyncSupervisor`] polls `eth_syncing` in the meantime, so that a sync
+//! which stopped making progress is detected and re-triggered instead of being waited on forever.
+//!
+//! Once synced, the unsafe chain is followed block by block. Gaps in it are not filled over
+//! req/resp, so a node that falls far enough behind gossip may never catch up on its own. The
+//! [`UnsafeLagMonitor`] detects an unsafe head that lags behind the tip of gossip, and does not
+//! advance, so that EL sync is re-triggered towards the tip.
+//!
+//! [`SyncMode::ExecutionLayer`]: crate::SyncMode::ExecutionLayer
+
+use crate::{EngineClient, Metrics};
+use alloy_provider::Provider;
+use alloy_rpc_types_eth::SyncStatus;
+use alloy_transport::{RpcError, TransportErrorKind};
+use kona_protocol::ElSyncProgress;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The default interval between `eth_syncing` polls of the [`ElSyncSupervisor`].
+pub const DEFAULT_EL_SYNC_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The default time without progress after which the execution layer sync is considered stalled.
+pub const DEFAULT_EL_SYNC_STALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The configuration of the [`ElSyncSupervisor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElSyncConfig {
+    /// The interval between `eth_syncing` polls.
+    pub poll_interval: Duration,
+    /// The time without progress after which the sync is considered stalled, and re-triggered.
+    pub stall_timeout: Duration,
+}
+
+impl Default for ElSyncConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: DEFAULT_EL_SYNC_POLL_INTERVAL,
+            stall_timeout: DEFAULT_EL_SYNC_STALL_TIMEOUT,
+        }
+    }
+}
+
+/// The configuration of the [`UnsafeLagMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsafeLagConfig {
+    /// The number of blocks the unsafe head may lag behind the tip of gossip.
+    pub threshold: u64,
+    /// The time the unsafe head may lag by more than the threshold without advancing, before EL
+    /// sync is re-triggered.
+    pub timeout: Duration,
+}
+
+/// Detects an unsafe head that lags behind the tip of gossip.
+///
+/// The unsafe head lags when the highest unsafe payload received from gossip is more than the
+/// [threshold][UnsafeLagConfig::threshold] ahead of it. If it keeps lagging, without advancing,
+/// for the [timeout][UnsafeLagConfig::timeout], the gap is not being filled, and the caller is
+/// expected to re-trigger EL sync towards the tip.
+#[derive(Debug, Clone)]
+pub struct UnsafeLagMonitor {
+    /// The configuration of the monitor.
+    config: UnsafeLagConfig,
+    /// The lagging unsafe head, and the time it was first observed lagging at.
+    lagging: Option<(u64, Instant)>,
+}
+
+impl UnsafeLagMonitor {
+    /// Creates a new [`UnsafeLagMonitor`].
+    pub const fn new(config: UnsafeLagConfig) -> Self {
+        Self { config, lagging: None }
+    }
+
+    /// Records the given unsafe head and tip of gossip, observed at `now`.
+    pub const fn observe(&mut self, unsafe_head: u64, tip: u64, now: Instant) {
+        if tip.saturating_sub(unsafe_head) <= self.config.threshold {
+            self.lagging = None;
+            return;
+        }
+        match self.lagging {
+            Some((head, _)) if head == unsafe_head => {}
+            _ => self.lagging = Some((unsafe_head, now)),
+        }
+    }
+
+    /// Returns the time at which EL sync must be re-triggered, if the unsafe head lags.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.lagging.map(|(_, since)| since + self.config.timeout)
+    }
+
+    /// Clears the lag, once EL sync was re-triggered.
+    pub const fn clear(&mut self) {
+        self.lagging = None;
+    }
+}
+
+/// Monitors the progress of the execution layer sync, and detects stalls.
+///
+/// The sync makes progress when the current block reported by `eth_syncing` increases. If it does
+/// not increase within the [stall timeout][ElSyncConfig::stall_timeout], including when the
+/// execution layer reports that it is not syncing at all, the sync is stalled. The caller is then
+/// expected to re-trigger it, e.g. by re-sending the latest unsafe payload, and the stall timeout
+/// starts over.
+#[derive(Debug, Clone)]
+pub struct ElSyncSupervisor {
+    /// The configuration of the supervisor.
+    config: ElSyncConfig,
+    /// The last observed progress.
+    progress: ElSyncProgress,
+    /// The last time the sync made progress, or was re-triggered.
+    last_progress: Instant,
+    /// The time of the next poll.
+    next_poll: Instant,
+}
+
+impl ElSyncSupervisor {
+    /// Creates a new [`ElSyncSupervisor`], polling first one interval after `now`.
+    pub fn new(config: ElSyncConfig, now: Instant) -> Self {
+        Self {
+            config,
+            progress: ElSyncProgress::default(),
+            last_progress: now,
+            next_poll: now + config.poll_interval,
+        }
+    }
+
+    /// Returns the last observed [`ElSyncProgress`].
+    pub const fn progress(&self) -> ElSyncProgress {
+        self.progress
+    }
+
+    /// Returns the time of the next poll.
+    pub const fn next_poll(&self) -> Instant {
+        self.next_poll
+    }
+
+    /// Polls `eth_syncing` on the execution layer. Returns `true` if the sync is stalled and
+    /// should be re-triggered.
+    ///
+    /// If the poll fails, the next one is still scheduled, and no progress is recorded.
+    pub async fn poll(
+        &mut self,
+        client: &EngineClient,
+    ) -> Result<bool, RpcError<TransportErrorKind>> {
+        self.next_poll = Instant::now() + self.config.poll_interval;
+        let status = client.l2_provider().syncing().await?;
+        Ok(self.observe(&status, Instant::now()))
+    }
+
+    /// Records the given `eth_syncing` status, observed at `now`. Returns `true` if the sync is
+    /// stalled and should be re-triggered.
+    pub fn observe(&mut self, status: &SyncStatus, now: Instant) -> bool {
+        if let SyncStatus::Info(info) = status {
+            let current = info.current_block.saturating_to::<u64>();
+            let highest = info.highest_block.saturating_to::<u64>();
+            self.progress.highest_block = highest;
+            if current > self.progress.current_block {
+                self.progress.current_block = current;
+                self.progress.stalled = false;
+                self.last_progress = now;
+            }
+            kona_macros::set!(
+                gauge,
+                Metrics::EL_SYNC_REMAINING_BLOCKS,
+                self.progress.remaining() as f64
+            );
+        }
+
+        if now.saturating_duration_since(self.last_progress) < self.config.stall_timeout {
+            return false;
+        }
+        self.progress.stalled = true;
+        self.progress.retries += 1;
+        self.last_progress = now;
+        kona_macros::inc!(counter, Metrics::EL_SYNC_STALL_COUNT);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockEngineClient;
+    use alloy_primitives::U256;
+    use alloy_rpc_types_eth::SyncInfo;
+    use kona_genesis::RollupConfig;
+    use std::sync::Arc;
+
+    const CONFIG: ElSyncConfig = ElSyncConfig {
+        poll_interval: Duration::from_secs(1),
+        stall_timeout: Duration::from_secs(10),
+    };
+
+    fn syncing(current: u64, highest: u64) -> SyncStatus {
+        SyncStatus::Info(Box::new(SyncInfo {
+            current_block: U256::from(current),
+            highest_block: U256::from(highest),
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn test_progress_resets_stall_timeout() {
+        let start = Instant::now();
+        let mut supervisor = ElSyncSupervisor::new(CONFIG, start);
+
+        for i in 1..=5 {
+            let now = start + Duration::from_secs(i * 9);
+            assert!(!supervisor.observe(&syncing(i * 100, 1_000), now));
+        }
+        assert_eq!(
+            supervisor.progress(),
+            ElSyncProgress { current_block: 500, highest_block: 1_000, stalled: false, retries: 0 }
+        );
+    }
+
+    #[test]
+    fn test_detects_and_retries_stalls() {
+        let start = Instant::now();
+        let mut supervisor = ElSyncSupervisor::new(CONFIG, start);
+        assert!(!supervisor.observe(&syncing(100, 1_000), start + Duration::from_secs(1)));
+
+        // The current block does not move, even though the highest block does.
+        assert!(!supervisor.observe(&syncing(100, 1_100), start + Duration::from_secs(10)));
+        assert!(supervisor.observe(&syncing(100, 1_100), start + Duration::from_secs(11)));
+        assert_eq!(
+            supervisor.progress(),
+            ElSyncProgress { current_block: 100, highest_block: 1_100, stalled: true, retries: 1 }
+        );
+
+        // The stall timeout starts over after a retry.
+        assert!(!supervisor.observe(&syncing(100, 1_100), start + Duration::from_secs(20)));
+        assert!(supervisor.observe(&syncing(100, 1_100), start + Duration::from_secs(21)));
+        assert_eq!(supervisor.progress().retries, 2);
+
+        // Progress clears the stall, but keeps the retry count.
+        assert!(!supervisor.observe(&syncing(200, 1_100), start + Duration::from_secs(22)));
+        assert_eq!(
+            supervisor.progress(),
+            ElSyncProgress { current_block: 200, highest_block: 1_100, stalled: false, retries: 2 }
+        );
+    }
+
+    #[test]
+    fn test_not_syncing_stalls() {
+        let start = Instant::now();
+        let mut supervisor = ElSyncSupervisor::new(CONFIG, start);
+        assert!(!supervisor.observe(&SyncStatus::None, start + Duration::from_secs(5)));
+        assert!(supervisor.observe(&SyncStatus::None, start + Duration::from_secs(10)));
+        assert_eq!(
+            supervisor.progress(),
+            ElSyncProgress { stalled: true, retries: 1, ..Default::default() }
+        );
+    }
+
+    const LAG: UnsafeLagConfig =
+        UnsafeLagConfig { threshold: 100, timeout: Duration::from_secs(30) };
+
+    #[test]
+    fn test_unsafe_lag_within_threshold() {
+        let start = Instant::now();
+        let mut monitor = UnsafeLagMonitor::new(LAG);
+        monitor.observe(1_000, 1_100, start);
+        assert_eq!(monitor.deadline(), None);
+    }
+
+    #[test]
+    fn test_unsafe_lag_deadline() {
+        let start = Instant::now();
+        let mut monitor = UnsafeLagMonitor::new(LAG);
+        monitor.observe(1_000, 1_101, start);
+        assert_eq!(monitor.deadline(), Some(start + LAG.timeout));
+
+        // The deadline is kept while the unsafe head does not advance, even as the tip does.
+        monitor.observe(1_000, 1_200, start + Duration::from_secs(10));
+        assert_eq!(monitor.deadline(), Some(start + LAG.timeout));
+
+        // It starts over when the unsafe head advances, and is cleared once it caught up.
+        let later = start + Duration::from_secs(20);
+        monitor.observe(1_050, 1_200, later);
+        assert_eq!(monitor.deadline(), Some(later + LAG.timeout));
+        monitor.observe(1_150, 1_200, later);
+        assert_eq!(monitor.deadline(), None);
+
+        monitor.observe(1_000, 1_200, start);
+        monitor.clear();
+        assert_eq!(monitor.deadline(), None);
+    }
+
+    #[tokio::test]
+    async fn test_poll() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        mock.set_syncing(syncing(10, 20));
+        let mut supervisor = ElSyncSupervisor::new(CONFIG, Instant::now());
+
+        assert!(!supervisor.poll(&mock.client()).await.unwrap());
+        assert_eq!(supervisor.progress().current_block, 10);
+        assert_eq!(supervisor.progress().remaining(), 10);
+        assert!(supervisor.next_poll() > Instant::now());
+        assert_eq!(mock.methods(), ["eth_syncing"]);
+    }
+}
@@ -0,0 +1,234 @@
+//! Contains the [`PayloadEnvelopeStore`], which keeps the payload envelopes of the latest unsafe
+//! blocks built or imported by the node.
+
+use crate::{Metrics, buffer::PersistedPayload};
+use alloy_primitives::B256;
+use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+use thiserror::Error;
+
+/// The default number of payload envelopes kept by the [`PayloadEnvelopeStore`].
+pub const DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY: usize = 128;
+
+/// A ring buffer of the payload envelopes of the last unsafe blocks, retrievable by block hash.
+///
+/// Re-publishing a block over gossip, or handing it to a conductor after a blip, needs the full
+/// payload envelope, which the execution layer can only rebuild from the block. The store keeps
+/// the envelopes of the last `capacity` blocks built or imported by the node, evicting the oldest
+/// first. If a directory is configured, each envelope is also written to disk, so that the store
+/// survives restarts.
+///
+/// Clones share the same envelopes, so that the store can be written by the engine and the
+/// sequencer, and read by the RPC server.
+#[derive(Debug, Clone)]
+pub struct PayloadEnvelopeStore {
+    /// The stored envelopes, shared between clones.
+    inner: Arc<Mutex<StoreInner>>,
+}
+
+/// The envelopes of a [`PayloadEnvelopeStore`].
+#[derive(Debug)]
+struct StoreInner {
+    /// The stored envelopes, keyed by block hash.
+    envelopes: HashMap<B256, OpExecutionPayloadEnvelope>,
+    /// The block hashes of the stored envelopes, oldest first.
+    order: VecDeque<B256>,
+    /// The maximum number of stored envelopes.
+    capacity: usize,
+    /// The directory the envelopes are persisted to, if any.
+    dir: Option<PathBuf>,
+}
+
+impl Default for PayloadEnvelopeStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY)
+    }
+}
+
+impl PayloadEnvelopeStore {
+    /// Creates a new, in-memory [`PayloadEnvelopeStore`] with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self::from_inner(StoreInner {
+            envelopes: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+            dir: None,
+        })
+    }
+
+    /// Creates a new [`PayloadEnvelopeStore`] that persists its envelopes to the given directory,
+    /// loading any envelopes left over from a previous run.
+    pub fn with_persistence(
+        capacity: usize,
+        dir: impl Into<PathBuf>,
+    ) -> Result<Self, PayloadEnvelopeStoreError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut loaded = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let envelope: OpExecutionPayloadEnvelope =
+                serde_json::from_slice::<PersistedPayload>(&fs::read(&path)?)?.into();
+            loaded.push(envelope);
+        }
+        loaded.sort_by_key(|envelope| envelope.payload.block_number());
+
+        let mut inner = StoreInner {
+            envelopes: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+            dir: Some(dir),
+        };
+        for envelope in loaded {
+            let hash = envelope.payload.block_hash();
+            inner.order.push_back(hash);
+            inner.envelopes.insert(hash, envelope);
+        }
+        inner.evict();
+        Ok(Self::from_inner(inner))
+    }
+
+    /// Wraps the given envelopes into a store, and updates the store size gauge.
+    fn from_inner(inner: StoreInner) -> Self {
+        inner.update_metrics();
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Locks the stored envelopes.
+    fn lock(&self) -> MutexGuard<'_, StoreInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the number of stored envelopes.
+    pub fn len(&self) -> usize {
+        self.lock().order.len()
+    }
+
+    /// Returns `true` if no envelopes are stored.
+    pub fn is_empty(&self) -> bool {
+        self.lock().order.is_empty()
+    }
+
+    /// Returns the maximum number of stored envelopes.
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    /// Returns the envelope of the block with the given hash, if it is stored.
+    pub fn get(&self, hash: &B256) -> Option<OpExecutionPayloadEnvelope> {
+        self.lock().envelopes.get(hash).cloned()
+    }
+
+    /// Stores the given envelope, evicting the oldest envelope if the store is full. Envelopes
+    /// that are already stored are ignored.
+    pub fn insert(&self, envelope: OpExecutionPayloadEnvelope) {
+        let mut inner = self.lock();
+        let hash = envelope.payload.block_hash();
+        if inner.capacity == 0 || inner.envelopes.contains_key(&hash) {
+            return;
+        }
+        inner.persist(&envelope);
+        inner.order.push_back(hash);
+        inner.envelopes.insert(hash, envelope);
+        inner.evict();
+        inner.update_metrics();
+    }
+}
+
+impl StoreInner {
+    /// Evicts the oldest envelopes until the store is within its capacity.
+    fn evict(&mut self) {
+        while self.order.len() > self.capacity {
+            let Some(hash) = self.order.pop_front() else { break };
+            self.envelopes.remove(&hash);
+            if let Some(dir) = &self.dir {
+                if let Err(err) = fs::remove_file(Self::path(dir, &hash)) {
+                    warn!(target: "engine", %hash, ?err, "Failed to remove persisted payload envelope");
+                }
+            }
+        }
+    }
+
+    /// Writes the envelope to disk, if persistence is enabled. Failures are logged, and the
+    /// envelope is kept in memory.
+    fn persist(&self, envelope: &OpExecutionPayloadEnvelope) {
+        let Some(dir) = &self.dir else { return };
+        let hash = envelope.payload.block_hash();
+        let result = serde_json::to_vec(&PersistedPayload::from(envelope.clone()))
+            .map_err(PayloadEnvelopeStoreError::from)
+            .and_then(|bytes| Ok(fs::write(Self::path(dir, &hash), bytes)?));
+        if let Err(err) = result {
+            warn!(target: "engine", %hash, ?err, "Failed to persist payload envelope");
+        }
+    }
+
+    /// Returns the path of the persisted envelope of the block with the given hash.
+    fn path(dir: &Path, hash: &B256) -> PathBuf {
+        dir.join(format!("{hash}.json"))
+    }
+
+    /// Updates the store size gauge.
+    fn update_metrics(&self) {
+        kona_macros::set!(gauge, Metrics::PAYLOAD_ENVELOPE_STORE_SIZE, self.order.len() as f64);
+    }
+}
+
+/// An error from the [`PayloadEnvelopeStore`].
+#[derive(Error, Debug)]
+pub enum PayloadEnvelopeStoreError {
+    /// An I/O error on the persistence directory.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A persisted envelope could not be (de)serialized.
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::tests::{envelope, hash};
+
+    #[test]
+    fn test_store_evicts_oldest() {
+        let store = PayloadEnvelopeStore::new(2);
+        store.insert(envelope(1, hash(0), hash(1)));
+        store.insert(envelope(2, hash(1), hash(2)));
+        store.insert(envelope(2, hash(1), hash(2)));
+        assert_eq!(store.len(), 2);
+
+        store.insert(envelope(3, hash(2), hash(3)));
+        assert_eq!(store.len(), 2);
+        assert!(store.get(&hash(1)).is_none());
+        assert_eq!(store.get(&hash(3)).unwrap().payload.block_number(), 3);
+    }
+
+    #[test]
+    fn test_store_persists_envelopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PayloadEnvelopeStore::with_persistence(2, dir.path()).unwrap();
+        for n in 1..=3 {
+            store.insert(envelope(n, hash(n - 1), hash(n)));
+        }
+
+        // Reloading keeps the latest envelopes, which remain retrievable by hash.
+        let store = PayloadEnvelopeStore::with_persistence(2, dir.path()).unwrap();
+        assert_eq!(store.len(), 2);
+        assert!(store.get(&hash(1)).is_none());
+        assert_eq!(store.get(&hash(2)).unwrap().payload.block_hash(), hash(2));
+
+        // A smaller capacity evicts the oldest persisted envelopes.
+        let store = PayloadEnvelopeStore::with_persistence(1, dir.path()).unwrap();
+        assert!(store.get(&hash(2)).is_none());
+        assert!(store.get(&hash(3)).is_some());
+    }
+}
@@ -11,28 +11,94 @@ extern crate tracing;
 
 mod task_queue;
 pub use task_queue::{
-    BuildTask, BuildTaskError, ConsolidateTask, ConsolidateTaskError, Engine, EngineResetError,
-    EngineTask, EngineTaskError, EngineTaskExt, FinalizeTask, FinalizeTaskError, ForkchoiceTask,
-    ForkchoiceTaskError, InsertUnsafeTask, InsertUnsafeTaskError,
+    BuildBudget, BuildTask, BuildTaskError, BuildTimings, ConsolidateTask, ConsolidateTaskError,
+    DEFAULT_BUILD_BUDGET_FRACTION, DEFAULT_SYNCING_BUFFER_CAPACITY,
+    DEFAULT_SYNCING_INITIAL_BACKOFF, DEFAULT_SYNCING_MAX_BACKOFF, Engine, EngineResetError,
+    EngineTask, EngineTaskError, EngineTaskExt, EngineTaskKind, EngineTaskTimeout,
+    EngineTaskTimeouts, FinalizeTask, FinalizeTaskError, ForkchoiceTask, ForkchoiceTaskError,
+    InsertUnsafeTask, InsertUnsafeTaskError, PayloadStats, SyncingBufferConfig, TaskTimeoutPolicy,
+};
+
+mod buffer;
+pub use buffer::{
+    DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY, UnsafePayloadBuffer, UnsafePayloadBufferError,
+};
+
+mod envelopes;
+pub use envelopes::{
+    DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY, PayloadEnvelopeStore, PayloadEnvelopeStoreError,
+};
+
+mod safe_heads;
+pub use safe_heads::{
+    DEFAULT_SAFE_HEAD_PRUNE_INTERVAL, DEFAULT_SAFE_HEAD_RETENTION_EPOCHS, SafeHeadEntry,
+    SafeHeadIndex, SafeHeadIndexError, SafeHeadIndexPruning, SafeHeadRetention,
+};
+
+mod restart;
+pub use restart::{AttributesBacklog, RestartCheckpoint, RestartCheckpointError};
+
+mod delay;
+pub use delay::{DelayedUnsafePayloads, UnsafeDelay};
+
+mod maintenance;
+pub use maintenance::{
+    DEFAULT_MAINTENANCE_TASK_TIMEOUT, ForkchoiceRefresh, MaintenanceRegistry, MaintenanceScheduler,
+    MaintenanceTask, MaintenanceTaskError, SyncStatusCheck,
 };
 
 mod attributes;
-pub use attributes::{AttributesMatch, AttributesMismatch};
+pub use attributes::{AttributesDiff, AttributesMatch, AttributesMismatch};
+
+mod cache;
+pub use cache::{
+    CACHED_METHODS, DEFAULT_ENGINE_CACHE_SIZE, EngineCache, EngineCacheLayer, EngineCacheService,
+    EngineCacheStats,
+};
 
 mod client;
 pub use client::{EngineClient, EngineClientError};
 
+mod chain_config;
+pub use chain_config::{
+    AttributesPreflightError, CHAIN_CONFIG_METHOD, ElBlobParams, ElChainConfig,
+};
+
+mod transport;
+pub use transport::{
+    EngineTls, EngineTransport, EngineTransportError, RequestSigner, SIGNATURE_HEADER,
+};
+
+mod shadow;
+pub use shadow::ShadowOutcome;
+
 mod versions;
 pub use versions::{EngineForkchoiceVersion, EngineGetPayloadVersion, EngineNewPayloadVersion};
 
 mod state;
-pub use state::EngineState;
+pub use state::{
+    DEFAULT_ENGINE_STATE_HISTORY_CAPACITY, EngineState, EngineStateHistory, EngineStateTransition,
+};
 
 mod kinds;
 pub use kinds::EngineKind;
 
+mod sync;
+pub use sync::SyncMode;
+
+mod el_sync;
+pub use el_sync::{
+    DEFAULT_EL_SYNC_POLL_INTERVAL, DEFAULT_EL_SYNC_STALL_TIMEOUT, ElSyncConfig, ElSyncSupervisor,
+    UnsafeLagConfig, UnsafeLagMonitor,
+};
+
 mod query;
 pub use query::{EngineQueries, EngineQueriesError, EngineQuerySender};
 
 mod metrics;
 pub use metrics::Metrics;
+
+#[cfg(any(test, feature = "test-utils"))]
+mod test_utils;
+#[cfg(any(test, feature = "test-utils"))]
+pub use test_utils::{MockEngineCall, MockEngineClient};
@@ -0,0 +1,286 @@
+//! Low-priority [`MaintenanceTask`]s, run against the execution layer while the [`Engine`] task
+//! queue is idle.
+//!
+//! [`Engine`]: crate::Engine
+
+use crate::{EngineClient, EngineState, Metrics};
+use alloy_provider::Provider;
+use alloy_rpc_types_eth::SyncStatus;
+use alloy_transport::{RpcError, TransportErrorKind};
+use async_trait::async_trait;
+use op_alloy_provider::ext::engine::OpEngineApi;
+use std::{fmt::Debug, sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::time::Instant;
+
+/// The default maximum duration of a single run of a [`MaintenanceTask`].
+pub const DEFAULT_MAINTENANCE_TASK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A periodic, low-priority task run against the execution layer.
+///
+/// Maintenance tasks are registered in a [`MaintenanceRegistry`], and are only run by the engine
+/// actor when its task queue is empty and no other events are pending, so that they never delay
+/// block processing. They are given read-only access to the [`EngineState`], and their failures
+/// are logged rather than propagated.
+#[async_trait]
+pub trait MaintenanceTask: Debug + Send + Sync + 'static {
+    /// The name of the task, used for logs and metrics.
+    fn name(&self) -> &'static str;
+
+    /// The interval between runs of the task.
+    fn interval(&self) -> Duration;
+
+    /// The maximum duration of a run of the task, after which it is abandoned, so that an
+    /// unresponsive execution layer cannot stall the engine actor.
+    fn timeout(&self) -> Duration {
+        DEFAULT_MAINTENANCE_TASK_TIMEOUT
+    }
+
+    /// Runs the task once.
+    async fn run(
+        &self,
+        client: &EngineClient,
+        state: &EngineState,
+    ) -> Result<(), MaintenanceTaskError>;
+}
+
+/// An error from a [`MaintenanceTask`].
+#[derive(Debug, Error)]
+pub enum MaintenanceTaskError {
+    /// An RPC to the execution layer failed.
+    #[error(transparent)]
+    Rpc(#[from] RpcError<TransportErrorKind>),
+    /// Any other error.
+    #[error(transparent)]
+    Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// A registry of [`MaintenanceTask`]s.
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceRegistry {
+    /// The registered tasks.
+    tasks: Vec<Arc<dyn MaintenanceTask>>,
+}
+
+impl MaintenanceRegistry {
+    /// Registers a [`MaintenanceTask`].
+    pub fn register(&mut self, task: impl MaintenanceTask) {
+        self.tasks.push(Arc::new(task));
+    }
+
+    /// Returns the registry with the given [`MaintenanceTask`] registered.
+    pub fn with_task(mut self, task: impl MaintenanceTask) -> Self {
+        self.register(task);
+        self
+    }
+
+    /// Returns the number of registered tasks.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if no tasks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Creates a [`MaintenanceScheduler`] for the registered tasks, each first due one interval
+    /// after `now`.
+    pub fn scheduler(&self, now: Instant) -> MaintenanceScheduler {
+        let tasks = self.tasks.iter().map(|task| (now + task.interval(), task.clone())).collect();
+        MaintenanceScheduler { tasks }
+    }
+}
+
+/// Tracks when each registered [`MaintenanceTask`] is next due.
+#[derive(Debug)]
+pub struct MaintenanceScheduler {
+    /// The registered tasks, with the instant they are next due.
+    tasks: Vec<(Instant, Arc<dyn MaintenanceTask>)>,
+}
+
+impl MaintenanceScheduler {
+    /// Returns the instant at which the next task is due, if any tasks are registered.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.tasks.iter().map(|(due, _)| *due).min()
+    }
+
+    /// Runs the most overdue task, if any task is due, and schedules its next run.
+    /// Returns `true` if a task was run.
+    pub async fn run_next(&mut self, client: &EngineClient, state: &EngineState) -> bool {
+        let now = Instant::now();
+        let Some((due, task)) =
+            self.tasks.iter_mut().filter(|(due, _)| *due <= now).min_by_key(|(due, _)| *due)
+        else {
+            return false;
+        };
+
+        trace!(target: "engine", task = task.name(), "Running maintenance task");
+        kona_macros::inc!(counter, Metrics::ENGINE_MAINTENANCE_TASK_COUNT, "task" => task.name());
+        match tokio::time::timeout(task.timeout(), task.run(client, state)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                warn!(target: "engine", task = task.name(), %err, "Maintenance task failed")
+            }
+            Err(_) => warn!(
+                target: "engine",
+                task = task.name(),
+                timeout = ?task.timeout(),
+                "Maintenance task timed out"
+            ),
+        }
+        *due = Instant::now() + task.interval();
+        true
+    }
+}
+
+/// A [`MaintenanceTask`] that polls `eth_syncing` on the execution layer, and reports whether it
+/// is syncing through the [`Metrics::EL_SYNCING`] gauge.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncStatusCheck {
+    /// The interval between checks.
+    pub interval: Duration,
+}
+
+#[async_trait]
+impl MaintenanceTask for SyncStatusCheck {
+    fn name(&self) -> &'static str {
+        "sync_status"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn run(
+        &self,
+        client: &EngineClient,
+        _: &EngineState,
+    ) -> Result<(), MaintenanceTaskError> {
+        let syncing = match client.l2_provider().syncing().await? {
+            SyncStatus::Info(info) => {
+                debug!(
+                    target: "engine",
+                    current = %info.current_block,
+                    highest = %info.highest_block,
+                    "Execution layer is syncing"
+                );
+                true
+            }
+            SyncStatus::None => false,
+        };
+        kona_macros::set!(gauge, Metrics::EL_SYNCING, if syncing { 1.0 } else { 0.0 });
+        Ok(())
+    }
+}
+
+/// A [`MaintenanceTask`] that re-sends the current forkchoice state to the execution layer, e.g.
+/// so that an execution client that was restarted picks the chain back up without waiting for
+/// the next block.
+///
+/// The task is skipped while the execution layer is syncing.
+#[derive(Debug, Clone, Copy)]
+pub struct ForkchoiceRefresh {
+    /// The interval between refreshes.
+    pub interval: Duration,
+}
+
+#[async_trait]
+impl MaintenanceTask for ForkchoiceRefresh {
+    fn name(&self) -> &'static str {
+        "forkchoice_refresh"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn run(
+        &self,
+        client: &EngineClient,
+        state: &EngineState,
+    ) -> Result<(), MaintenanceTaskError> {
+        if !state.el_sync_finished {
+            return Ok(());
+        }
+        let response = client.fork_choice_updated_v3(state.create_forkchoice_state(), None).await?;
+        if !response.payload_status.status.is_valid() {
+            warn!(
+                target: "engine",
+                status = %response.payload_status.status,
+                "Forkchoice refresh was not accepted by the execution layer"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::MockEngineClient;
+    use kona_genesis::RollupConfig;
+
+    #[derive(Debug)]
+    struct Noop(u64);
+
+    /// A task that never completes.
+    #[derive(Debug)]
+    struct Stuck;
+
+    #[async_trait]
+    impl MaintenanceTask for Stuck {
+        fn name(&self) -> &'static str {
+            "stuck"
+        }
+
+        fn interval(&self) -> Duration {
+            Duration::from_secs(1)
+        }
+
+        async fn run(&self, _: &EngineClient, _: &EngineState) -> Result<(), MaintenanceTaskError> {
+            std::future::pending().await
+        }
+    }
+
+    #[async_trait]
+    impl MaintenanceTask for Noop {
+        fn name(&self) -> &'static str {
+            "noop"
+        }
+
+        fn interval(&self) -> Duration {
+            Duration::from_secs(self.0)
+        }
+
+        async fn run(&self, _: &EngineClient, _: &EngineState) -> Result<(), MaintenanceTaskError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_scheduler_next_due() {
+        let now = Instant::now();
+        assert_eq!(MaintenanceRegistry::default().scheduler(now).next_due(), None);
+
+        let registry = MaintenanceRegistry::default().with_task(Noop(30)).with_task(Noop(10));
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.scheduler(now).next_due(), Some(now + Duration::from_secs(10)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduler_times_out_task() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let start = Instant::now();
+        let mut scheduler = MaintenanceRegistry::default().with_task(Stuck).scheduler(start);
+        tokio::time::advance(Duration::from_secs(1)).await;
+
+        assert!(scheduler.run_next(&mock.client(), &EngineState::default()).await);
+        assert_eq!(
+            Instant::now(),
+            start + Duration::from_secs(1) + DEFAULT_MAINTENANCE_TASK_TIMEOUT
+        );
+        assert_eq!(scheduler.next_due(), Some(Instant::now() + Duration::from_secs(1)));
+    }
+}
@@ -31,6 +31,10 @@ impl Metrics {
     /// Finalize task label.
     pub const FINALIZE_TASK_LABEL: &str = "finalize";
 
+    /// Identifier for the counter that tracks the number of engine tasks that timed out, labeled
+    /// by task.
+    pub const ENGINE_TASK_TIMEOUT_COUNT: &str = "kona_node_engine_task_timeouts";
+
     /// Identifier for the histogram that tracks engine method call time.
     pub const ENGINE_METHOD_REQUEST_DURATION: &str = "kona_node_engine_method_request_duration";
     /// `engine_forkchoiceUpdatedV<N>` label
@@ -40,9 +44,145 @@ impl Metrics {
     /// `engine_getPayloadV<N>` label.
     pub const GET_PAYLOAD_METHOD: &str = "engine_getPayload";
 
+    /// Identifier for the counter of [shadow] engine API calls, labeled by method and by
+    /// [`ShadowOutcome`].
+    ///
+    /// [shadow]: crate::EngineClient::with_shadowing
+    /// [`ShadowOutcome`]: crate::ShadowOutcome
+    pub const ENGINE_SHADOW_CALLS: &str = "kona_node_engine_shadow_calls";
+
+    /// Identifier for the counter of requests to the methods cached by the [`EngineCache`],
+    /// labeled by method and by whether they were answered from the cache.
+    ///
+    /// [`EngineCache`]: crate::EngineCache
+    pub const ENGINE_CACHE_REQUESTS: &str = "kona_node_engine_cache_requests";
+    /// Cache hit label.
+    pub const ENGINE_CACHE_HIT: &str = "hit";
+    /// Cache miss label.
+    pub const ENGINE_CACHE_MISS: &str = "miss";
+
     /// Identifier for the counter that tracks the number of times the engine has been reset.
     pub const ENGINE_RESET_COUNT: &str = "kona_node_engine_reset_count";
 
+    /// Identifier for the counter of derived attributes dropped because their parent was no longer
+    /// the local safe head, e.g. after a concurrent rollback.
+    pub const STALE_ATTRIBUTES_DROPPED: &str = "kona_node_engine_stale_attributes_dropped";
+
+    /// Identifier for the gauge of engine tasks held back while the execution layer is syncing.
+    pub const SYNCING_TASKS_BUFFERED: &str = "kona_node_engine_syncing_tasks_buffered";
+
+    /// Identifier for the counter of engine tasks dropped because the buffer of tasks held back
+    /// while the execution layer is syncing was full.
+    pub const SYNCING_TASKS_DROPPED: &str = "kona_node_engine_syncing_tasks_dropped";
+
+    /// Identifier for the counter of maintenance tasks run while the engine was idle.
+    pub const ENGINE_MAINTENANCE_TASK_COUNT: &str = "kona_node_engine_maintenance_tasks";
+
+    /// Identifier for the counter of unsafe blocks that did not match the attributes derived for
+    /// them during consolidation, labeled by the first mismatching field.
+    pub const ATTRIBUTES_MISMATCH: &str = "kona_node_engine_attributes_mismatch";
+    /// Mismatching timestamp label.
+    pub const ATTRIBUTES_MISMATCH_TIMESTAMP: &str = "timestamp";
+    /// Mismatching prev randao label.
+    pub const ATTRIBUTES_MISMATCH_PREV_RANDAO: &str = "prev_randao";
+    /// Mismatching transaction list label.
+    pub const ATTRIBUTES_MISMATCH_TRANSACTIONS: &str = "transactions";
+    /// Mismatching gas limit label.
+    pub const ATTRIBUTES_MISMATCH_GAS_LIMIT: &str = "gas_limit";
+    /// Mismatching withdrawals root label.
+    pub const ATTRIBUTES_MISMATCH_WITHDRAWALS_ROOT: &str = "withdrawals_root";
+    /// Label for mismatches on fields other than the ones above, e.g. the fee recipient.
+    pub const ATTRIBUTES_MISMATCH_OTHER: &str = "other";
+    /// All attributes mismatch labels.
+    pub const ATTRIBUTES_MISMATCH_FIELDS: [&str; 6] = [
+        Self::ATTRIBUTES_MISMATCH_TIMESTAMP,
+        Self::ATTRIBUTES_MISMATCH_PREV_RANDAO,
+        Self::ATTRIBUTES_MISMATCH_TRANSACTIONS,
+        Self::ATTRIBUTES_MISMATCH_GAS_LIMIT,
+        Self::ATTRIBUTES_MISMATCH_WITHDRAWALS_ROOT,
+        Self::ATTRIBUTES_MISMATCH_OTHER,
+    ];
+
+    /// Identifier for the gauge that tracks whether the execution layer reports that it is
+    /// syncing (`1`) or not (`0`).
+    pub const EL_SYNCING: &str = "kona_node_el_syncing";
+
+    /// Identifier for the gauge that tracks the number of blocks left for the execution layer to
+    /// sync, as reported by `eth_syncing` while the [`ElSyncSupervisor`] polls it.
+    ///
+    /// [`ElSyncSupervisor`]: crate::ElSyncSupervisor
+    pub const EL_SYNC_REMAINING_BLOCKS: &str = "kona_node_el_sync_remaining_blocks";
+
+    /// Identifier for the counter that tracks the number of times the execution layer sync
+    /// stalled and was re-triggered.
+    pub const EL_SYNC_STALL_COUNT: &str = "kona_node_el_sync_stall_count";
+
+    /// Identifier for the counter that tracks the number of times the execution layer sync was
+    /// restarted after it had finished, e.g. because the unsafe head lagged behind gossip.
+    pub const EL_RESYNC_COUNT: &str = "kona_node_el_resync_count";
+
+    /// Identifier for the gauge that tracks the number of the last block produced by the
+    /// deposits-only fallback, replacing a derived payload that the execution layer found invalid.
+    pub const DEPOSITS_ONLY_BLOCK: &str = "kona_node_engine_deposits_only_block";
+
+    /// Identifier for the gauge that tracks the number of buffered unsafe payloads.
+    pub const UNSAFE_PAYLOAD_BUFFER_SIZE: &str = "kona_node_unsafe_payload_buffer_size";
+
+    /// Identifier for the gauge that tracks the number of payload envelopes kept for the latest
+    /// unsafe blocks.
+    pub const PAYLOAD_ENVELOPE_STORE_SIZE: &str = "kona_node_payload_envelope_store_size";
+
+    /// Identifier for the gauge that tracks the number of L1 blocks whose safe head is indexed.
+    pub const SAFE_HEAD_INDEX_SIZE: &str = "kona_node_safe_head_index_size";
+
+    /// Identifier for the gauge that tracks the number of gossiped unsafe payloads held back by
+    /// the unsafe delay.
+    pub const UNSAFE_DELAYED_PAYLOADS: &str = "kona_node_unsafe_delayed_payloads";
+
+    /// Identifier for the counter of conflicting unsafe payloads gossiped for the same block
+    /// number while held back (strictly for alerting.)
+    pub const UNSAFE_PAYLOAD_EQUIVOCATIONS: &str = "kona_node_unsafe_payload_equivocations";
+
+    /// Identifier for the histogram that tracks the wall-clock duration of each phase of a
+    /// [`BuildTask`], in seconds, labeled by phase.
+    ///
+    /// [`BuildTask`]: crate::BuildTask
+    pub const BUILD_PHASE_DURATION: &str = "kona_node_engine_build_phase_duration_seconds";
+    /// Identifier for the histogram that tracks the duration of each phase of a [`BuildTask`] as
+    /// a fraction of the block time, labeled by phase.
+    ///
+    /// [`BuildTask`]: crate::BuildTask
+    pub const BUILD_PHASE_BLOCK_TIME_RATIO: &str = "kona_node_engine_build_phase_block_time_ratio";
+    /// Identifier for the counter of blocks whose [`BuildTask`] exceeded its latency budget.
+    ///
+    /// [`BuildTask`]: crate::BuildTask
+    pub const BUILD_BUDGET_EXCEEDED: &str = "kona_node_engine_build_budget_exceeded";
+    /// Forkchoice update with payload attributes build phase label.
+    pub const BUILD_PHASE_FCU: &str = "fcu";
+    /// Payload fetch build phase label.
+    pub const BUILD_PHASE_GET_PAYLOAD: &str = "get_payload";
+    /// Payload import build phase label.
+    pub const BUILD_PHASE_NEW_PAYLOAD: &str = "new_payload";
+    /// Canonicalization build phase label.
+    pub const BUILD_PHASE_CANONICALIZE: &str = "canonicalize";
+    /// Total build label, covering all phases.
+    pub const BUILD_PHASE_TOTAL: &str = "total";
+
+    /// Identifier for the histogram of the number of transactions of the built blocks, labeled
+    /// by kind.
+    pub const PAYLOAD_TRANSACTIONS: &str = "kona_node_engine_payload_transactions";
+    /// Deposit transactions label.
+    pub const PAYLOAD_DEPOSIT_TRANSACTIONS: &str = "deposit";
+    /// User transactions label.
+    pub const PAYLOAD_USER_TRANSACTIONS: &str = "user";
+    /// Identifier for the histogram of the gas used by the built blocks.
+    pub const PAYLOAD_GAS_USED: &str = "kona_node_engine_payload_gas_used";
+    /// Identifier for the histogram of the fraction of the gas limit used by the built blocks.
+    pub const PAYLOAD_GAS_UTILIZATION: &str = "kona_node_engine_payload_gas_utilization";
+    /// Identifier for the histogram of the size of the user transactions of the built blocks,
+    /// i.e. of their batch data before compression, in bytes.
+    pub const PAYLOAD_BATCH_DATA_SIZE: &str = "kona_node_engine_payload_batch_data_size_bytes";
+
     /// Initializes metrics for the engine.
     ///
     /// This does two things:
@@ -62,6 +202,11 @@ impl Metrics {
 
         // Engine task counts
         metrics::describe_counter!(Self::ENGINE_TASK_COUNT, "Engine task counts");
+        metrics::describe_counter!(
+            Self::ENGINE_TASK_TIMEOUT_COUNT,
+            metrics::Unit::Count,
+            "Engine tasks that timed out, by task"
+        );
 
         // Engine method request duration histogram
         metrics::describe_histogram!(
@@ -70,12 +215,156 @@ impl Metrics {
             "Engine method request duration"
         );
 
+        // Shadow engine method calls
+        metrics::describe_counter!(
+            Self::ENGINE_SHADOW_CALLS,
+            metrics::Unit::Count,
+            "Shadow engine method calls ahead of a hardfork, by method and outcome"
+        );
+
+        // Engine maintenance tasks
+        metrics::describe_counter!(
+            Self::ENGINE_MAINTENANCE_TASK_COUNT,
+            metrics::Unit::Count,
+            "Maintenance tasks run while the engine was idle"
+        );
+        metrics::describe_gauge!(
+            Self::EL_SYNCING,
+            "Whether the execution layer is syncing (1) or not (0)"
+        );
+
+        // Execution layer sync supervision
+        metrics::describe_gauge!(
+            Self::EL_SYNC_REMAINING_BLOCKS,
+            metrics::Unit::Count,
+            "Blocks left for the execution layer to sync"
+        );
+        metrics::describe_counter!(
+            Self::EL_SYNC_STALL_COUNT,
+            metrics::Unit::Count,
+            "Execution layer sync stalls"
+        );
+        metrics::describe_counter!(
+            Self::EL_RESYNC_COUNT,
+            metrics::Unit::Count,
+            "Execution layer syncs restarted after they had finished"
+        );
+
+        // Deposits-only fallback
+        metrics::describe_gauge!(
+            Self::DEPOSITS_ONLY_BLOCK,
+            "Number of the last block produced by the deposits-only fallback"
+        );
+
+        // Unsafe payload buffer size
+        metrics::describe_gauge!(
+            Self::UNSAFE_PAYLOAD_BUFFER_SIZE,
+            metrics::Unit::Count,
+            "Number of buffered unsafe payloads"
+        );
+
+        // Payload envelope store size
+        metrics::describe_gauge!(
+            Self::PAYLOAD_ENVELOPE_STORE_SIZE,
+            metrics::Unit::Count,
+            "Number of payload envelopes kept for the latest unsafe blocks"
+        );
+
+        // Safe head index size
+        metrics::describe_gauge!(
+            Self::SAFE_HEAD_INDEX_SIZE,
+            metrics::Unit::Count,
+            "Number of L1 blocks whose safe head is indexed"
+        );
+
+        // Delayed unsafe payloads
+        metrics::describe_gauge!(
+            Self::UNSAFE_DELAYED_PAYLOADS,
+            metrics::Unit::Count,
+            "Number of gossiped unsafe payloads held back by the unsafe delay"
+        );
+        metrics::describe_counter!(
+            Self::UNSAFE_PAYLOAD_EQUIVOCATIONS,
+            metrics::Unit::Count,
+            "Conflicting unsafe payloads gossiped for the same block number"
+        );
+
+        // Build task latency budget
+        metrics::describe_histogram!(
+            Self::BUILD_PHASE_DURATION,
+            metrics::Unit::Seconds,
+            "Wall-clock duration of each phase of a build task"
+        );
+        metrics::describe_histogram!(
+            Self::BUILD_PHASE_BLOCK_TIME_RATIO,
+            "Duration of each phase of a build task as a fraction of the block time"
+        );
+        metrics::describe_counter!(
+            Self::BUILD_BUDGET_EXCEEDED,
+            metrics::Unit::Count,
+            "Blocks whose build task exceeded its latency budget"
+        );
+
+        // Built payload statistics
+        metrics::describe_histogram!(
+            Self::PAYLOAD_TRANSACTIONS,
+            metrics::Unit::Count,
+            "Number of transactions of the built blocks, by kind"
+        );
+        metrics::describe_histogram!(
+            Self::PAYLOAD_GAS_USED,
+            metrics::Unit::Count,
+            "Gas used by the built blocks"
+        );
+        metrics::describe_histogram!(
+            Self::PAYLOAD_GAS_UTILIZATION,
+            "Fraction of the gas limit used by the built blocks"
+        );
+        metrics::describe_histogram!(
+            Self::PAYLOAD_BATCH_DATA_SIZE,
+            metrics::Unit::Bytes,
+            "Size of the user transactions of the built blocks, before compression"
+        );
+
+        // Engine cache
+        metrics::describe_counter!(
+            Self::ENGINE_CACHE_REQUESTS,
+            metrics::Unit::Count,
+            "Requests to cached engine methods, by method and cache hit or miss"
+        );
+
+        // Attributes mismatches
+        metrics::describe_counter!(
+            Self::ATTRIBUTES_MISMATCH,
+            metrics::Unit::Count,
+            "Unsafe blocks that did not match their derived attributes, by first mismatching field"
+        );
+
         // Engine reset counter
         metrics::describe_counter!(
             Self::ENGINE_RESET_COUNT,
             metrics::Unit::Count,
             "Engine reset count"
         );
+
+        // Stale attributes
+        metrics::describe_counter!(
+            Self::STALE_ATTRIBUTES_DROPPED,
+            metrics::Unit::Count,
+            "Derived attributes dropped because their parent was no longer the local safe head"
+        );
+
+        // Tasks held back while the EL is syncing
+        metrics::describe_gauge!(
+            Self::SYNCING_TASKS_BUFFERED,
+            metrics::Unit::Count,
+            "Engine tasks held back while the execution layer is syncing"
+        );
+        metrics::describe_counter!(
+            Self::SYNCING_TASKS_DROPPED,
+            metrics::Unit::Count,
+            "Engine tasks dropped because the buffer of tasks held back while syncing was full"
+        );
     }
 
     /// Initializes metrics to `0` so they can be queried immediately by consumers of prometheus
@@ -88,8 +377,45 @@ impl Metrics {
         kona_macros::set!(counter, Self::ENGINE_TASK_COUNT, Self::FORKCHOICE_TASK_LABEL, 0);
         kona_macros::set!(counter, Self::ENGINE_TASK_COUNT, Self::BUILD_TASK_LABEL, 0);
         kona_macros::set!(counter, Self::ENGINE_TASK_COUNT, Self::FINALIZE_TASK_LABEL, 0);
+        for kind in crate::EngineTaskKind::KINDS {
+            kona_macros::set!(counter, Self::ENGINE_TASK_TIMEOUT_COUNT, "task", kind.label(), 0);
+        }
 
         // Engine reset count
         kona_macros::set!(counter, Self::ENGINE_RESET_COUNT, 0);
+        kona_macros::set!(counter, Self::STALE_ATTRIBUTES_DROPPED, 0);
+        kona_macros::set!(gauge, Self::SYNCING_TASKS_BUFFERED, 0.0);
+        kona_macros::set!(counter, Self::SYNCING_TASKS_DROPPED, 0);
+
+        // Shadow engine method calls
+        for method in ["engine_newPayloadV4", "engine_getPayloadV4"] {
+            for outcome in crate::ShadowOutcome::ALL {
+                metrics::counter!(Self::ENGINE_SHADOW_CALLS, "method" => method, "outcome" => outcome.label())
+                    .absolute(0);
+            }
+        }
+
+        // Execution layer sync stalls
+        kona_macros::set!(counter, Self::EL_SYNC_STALL_COUNT, 0);
+        kona_macros::set!(counter, Self::EL_RESYNC_COUNT, 0);
+
+        // Engine cache
+        for method in crate::CACHED_METHODS {
+            for result in [Self::ENGINE_CACHE_HIT, Self::ENGINE_CACHE_MISS] {
+                metrics::counter!(Self::ENGINE_CACHE_REQUESTS, "method" => method, "result" => result)
+                    .absolute(0);
+            }
+        }
+
+        // Attributes mismatches
+        for field in Self::ATTRIBUTES_MISMATCH_FIELDS {
+            kona_macros::set!(counter, Self::ATTRIBUTES_MISMATCH, "field", field, 0);
+        }
+
+        // Unsafe payload equivocations
+        kona_macros::set!(counter, Self::UNSAFE_PAYLOAD_EQUIVOCATIONS, 0);
+
+        // Build task latency budget
+        kona_macros::set!(counter, Self::BUILD_BUDGET_EXCEEDED, 0);
     }
 }
@@ -0,0 +1,241 @@
+//! Contains the [`RestartCheckpoint`], which lets a restarted rollup node re-attach to its
+//! execution layer without waiting for it to sync again.
+
+use crate::{EngineClient, EngineClientError, EngineState};
+use alloy_eips::eip1898::BlockNumberOrTag;
+use kona_protocol::{L2BlockInfo, OpAttributesWithParent};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+use thiserror::Error;
+
+/// The heads of the engine, saved when the rollup node shuts down gracefully.
+///
+/// A node restarted shortly after, e.g. during a routine upgrade, loads the checkpoint and, if the
+/// execution layer still holds the checkpointed unsafe head, skips execution layer sync. Together
+/// with the persisted [`UnsafePayloadBuffer`], gossiped blocks are then imported again as soon as
+/// the node is up, instead of lagging behind until the execution layer reports being synced.
+///
+/// [`UnsafePayloadBuffer`]: crate::UnsafePayloadBuffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartCheckpoint {
+    /// The unsafe head at shutdown.
+    pub unsafe_head: L2BlockInfo,
+    /// The safe head at shutdown.
+    pub safe_head: L2BlockInfo,
+    /// The finalized head at shutdown.
+    pub finalized_head: L2BlockInfo,
+    /// The unix timestamp (in seconds) at which the checkpoint was saved.
+    pub saved_at: u64,
+}
+
+impl RestartCheckpoint {
+    /// Creates a new [`RestartCheckpoint`] of the given [`EngineState`].
+    pub const fn new(state: &EngineState, saved_at: u64) -> Self {
+        Self {
+            unsafe_head: state.unsafe_head(),
+            safe_head: state.safe_head(),
+            finalized_head: state.finalized_head(),
+            saved_at,
+        }
+    }
+
+    /// Writes the checkpoint to the given path, replacing any previous checkpoint atomically.
+    pub fn save(&self, path: &Path) -> Result<(), RestartCheckpointError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Loads and removes the checkpoint at the given path, so that it is only used by the next
+    /// start. Returns `None` if there is no checkpoint.
+    pub fn take(path: &Path) -> Result<Option<Self>, RestartCheckpointError> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        fs::remove_file(path)?;
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    /// Returns `true` if the checkpoint was saved at most `window` seconds before `now`.
+    pub const fn is_fresh(&self, window: u64, now: u64) -> bool {
+        now.saturating_sub(self.saved_at) <= window
+    }
+
+    /// Returns `true` if the execution layer still holds the checkpointed unsafe head, i.e. it
+    /// has not lost or reorged blocks while the rollup node was down.
+    pub async fn verify(&self, client: &EngineClient) -> Result<bool, EngineClientError> {
+        let number = self.unsafe_head.block_info.number;
+        let block = client.l2_block_info_by_label(BlockNumberOrTag::Number(number)).await?;
+        Ok(block.is_some_and(|b| b.block_info.hash == self.unsafe_head.block_info.hash))
+    }
+}
+
+/// The derived payload attributes that were not executed yet when the rollup node shut down
+/// gracefully, saved alongside the [`RestartCheckpoint`].
+///
+/// On restart, the attributes that extend the safe head the engine re-attached at are
+/// consolidated again before derivation resumes, so that the safe head does not fall back to the
+/// start of the channel that was being derived.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributesBacklog {
+    /// The derived attributes, in the order they were derived.
+    pub attributes: Vec<OpAttributesWithParent>,
+}
+
+impl AttributesBacklog {
+    /// Writes the backlog to the given path, replacing any previous backlog atomically.
+    pub fn save(&self, path: &Path) -> Result<(), RestartCheckpointError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Loads and removes the backlog at the given path, so that it is only replayed by the next
+    /// start. Returns an empty backlog if there is none.
+    pub fn take(path: &Path) -> Result<Self, RestartCheckpointError> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        fs::remove_file(path)?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Returns the attributes that extend the given safe head, in order.
+    ///
+    /// The attributes up to the safe head are skipped, since they were already executed. The
+    /// remaining ones are kept as long as each is the child of the previous one, starting with a
+    /// child of the safe head. Attributes derived on top of another chain were invalidated by a
+    /// reorg or a reset, and are dropped, along with those derived after them.
+    pub fn extending(self, safe_head: &L2BlockInfo) -> Vec<OpAttributesWithParent> {
+        let mut parent = (safe_head.block_info.number, Some(safe_head.block_info.hash));
+        self.attributes
+            .into_iter()
+            .skip_while(|attributes| attributes.block_number() <= safe_head.block_info.number)
+            .take_while(|attributes| {
+                let block = &attributes.parent.block_info;
+                // The hashes of the blocks built from the backlog are only known once executed.
+                let extends = block.number == parent.0 && parent.1.is_none_or(|h| h == block.hash);
+                parent = (attributes.block_number(), None);
+                extends
+            })
+            .collect()
+    }
+}
+
+/// An error from loading or saving a [`RestartCheckpoint`] or an [`AttributesBacklog`].
+#[derive(Error, Debug)]
+pub enum RestartCheckpointError {
+    /// An I/O error on the checkpoint file.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The checkpoint could not be (de)serialized.
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use kona_protocol::BlockInfo;
+
+    fn attributes(parent: u64, parent_hash: u8) -> OpAttributesWithParent {
+        let parent = L2BlockInfo {
+            block_info: BlockInfo {
+                number: parent,
+                hash: B256::with_last_byte(parent_hash),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let inner = op_alloy_rpc_types_engine::OpPayloadAttributes {
+            gas_limit: Some(30_000_000),
+            ..Default::default()
+        };
+        OpAttributesWithParent::new(inner, parent, BlockInfo::default(), false)
+    }
+
+    #[test]
+    fn test_attributes_backlog_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine").join("attributes.json");
+        assert!(AttributesBacklog::take(&path).unwrap().attributes.is_empty());
+
+        let backlog = AttributesBacklog { attributes: vec![attributes(10, 10), attributes(11, 0)] };
+        backlog.save(&path).unwrap();
+        assert_eq!(AttributesBacklog::take(&path).unwrap(), backlog);
+        assert!(AttributesBacklog::take(&path).unwrap().attributes.is_empty());
+    }
+
+    #[test]
+    fn test_attributes_backlog_extending() {
+        let safe_head = attributes(11, 0).parent;
+        let backlog = AttributesBacklog {
+            attributes: vec![
+                attributes(10, 10),
+                attributes(11, 11),
+                attributes(12, 0),
+                attributes(13, 0),
+                attributes(15, 0),
+            ],
+        };
+        let numbers = |attributes: Vec<OpAttributesWithParent>| {
+            attributes.iter().map(|a| a.block_number()).collect::<Vec<_>>()
+        };
+
+        // The attributes up to the safe head are skipped, and the gap ends the backlog.
+        let mut safe_head = safe_head;
+        safe_head.block_info.hash = B256::with_last_byte(11);
+        assert_eq!(numbers(backlog.clone().extending(&safe_head)), vec![12, 13, 14]);
+
+        // Attributes derived on top of another safe head are dropped.
+        safe_head.block_info.hash = B256::with_last_byte(12);
+        assert!(backlog.clone().extending(&safe_head).is_empty());
+
+        // A safe head past the backlog leaves nothing to replay.
+        safe_head.block_info.number = 20;
+        assert!(backlog.extending(&safe_head).is_empty());
+    }
+
+    #[test]
+    fn test_restart_checkpoint_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine").join("restart.json");
+        assert!(RestartCheckpoint::take(&path).unwrap().is_none());
+
+        let mut state = EngineState::default();
+        state.set_unsafe_head(L2BlockInfo {
+            block_info: BlockInfo {
+                number: 10,
+                hash: B256::with_last_byte(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let checkpoint = RestartCheckpoint::new(&state, 100);
+        checkpoint.save(&path).unwrap();
+
+        let loaded = RestartCheckpoint::take(&path).unwrap().unwrap();
+        assert_eq!(loaded, checkpoint);
+        assert_eq!(loaded.unsafe_head.block_info.number, 10);
+        assert!(RestartCheckpoint::take(&path).unwrap().is_none());
+
+        assert!(loaded.is_fresh(30, 130));
+        assert!(!loaded.is_fresh(30, 131));
+        assert!(loaded.is_fresh(30, 50));
+    }
+}
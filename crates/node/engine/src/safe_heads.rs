@@ -0,0 +1,463 @@
+//! Contains the [`SafeHeadIndex`], which keeps the safe head that was derived from each L1 block.
+
+use crate::{EngineClient, EngineState, MaintenanceTask, MaintenanceTaskError, Metrics};
+use alloy_eips::BlockNumHash;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// The default number of L1 blocks whose safe head is kept by the [`SafeHeadIndex`], about a day
+/// of L1 blocks.
+pub const DEFAULT_SAFE_HEAD_RETENTION_EPOCHS: u64 = 7200;
+
+/// The default interval between runs of the [`SafeHeadIndexPruning`] task.
+pub const DEFAULT_SAFE_HEAD_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How much of the history of the [`SafeHeadIndex`] is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafeHeadRetention {
+    /// The safe heads of the last `n` L1 blocks are kept.
+    Epochs(u64),
+    /// The safe heads of all L1 blocks are kept, e.g. by archive nodes.
+    Full,
+}
+
+impl Default for SafeHeadRetention {
+    fn default() -> Self {
+        Self::Epochs(DEFAULT_SAFE_HEAD_RETENTION_EPOCHS)
+    }
+}
+
+impl SafeHeadRetention {
+    /// Returns the lowest L1 block number whose safe head is kept, given the latest one.
+    pub const fn oldest_kept(&self, latest: u64) -> u64 {
+        match self {
+            Self::Epochs(n) => latest.saturating_sub(n.saturating_sub(1)),
+            Self::Full => 0,
+        }
+    }
+}
+
+impl fmt::Display for SafeHeadRetention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Epochs(n) => write!(f, "{n}"),
+            Self::Full => f.write_str("full"),
+        }
+    }
+}
+
+impl FromStr for SafeHeadRetention {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("full") {
+            return Ok(Self::Full);
+        }
+        match s.parse::<u64>() {
+            Ok(0) => Err("the safe head retention must keep at least one L1 block".to_string()),
+            Ok(n) => Ok(Self::Epochs(n)),
+            Err(_) => {
+                Err(format!("invalid safe head retention {s:?}, expected a number or `full`"))
+            }
+        }
+    }
+}
+
+/// The safe head derived from the data of an L1 block, and of the L1 blocks before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeHeadEntry {
+    /// The L1 block.
+    pub l1_block: BlockNumHash,
+    /// The safe head derived from the L1 chain up to and including the L1 block.
+    pub safe_head: BlockNumHash,
+}
+
+/// An index of the safe head derived from each L1 block, serving `optimism_safeHeadAtL1Block`.
+///
+/// The engine records the safe head each time it is promoted, keyed by the L1 block the
+/// promoted attributes were derived from. A record invalidates the entries of later L1 blocks,
+/// and of later safe heads, so that the index follows L1 reorgs and engine resets.
+///
+/// Only the L1 blocks within the [`SafeHeadRetention`] are kept. Older entries are removed by
+/// [`SafeHeadIndex::prune`], run in the background by the [`SafeHeadIndexPruning`] task. If a
+/// path is configured, each record is appended to it as a JSON line, so that the index survives
+/// restarts, and the file is compacted to the kept entries when pruning.
+///
+/// Clones share the same entries, so that the index can be written by the engine and read by the
+/// RPC server.
+#[derive(Debug, Clone)]
+pub struct SafeHeadIndex {
+    /// The indexed safe heads, shared between clones.
+    inner: Arc<Mutex<IndexInner>>,
+}
+
+/// The entries of a [`SafeHeadIndex`].
+#[derive(Debug)]
+struct IndexInner {
+    /// The indexed safe heads, keyed by L1 block number.
+    entries: BTreeMap<u64, SafeHeadEntry>,
+    /// How much of the history is kept.
+    retention: SafeHeadRetention,
+    /// The file the records are appended to, if any.
+    path: Option<PathBuf>,
+    /// The number of records in the file, including the ones superseded since it was compacted.
+    records: usize,
+}
+
+impl Default for SafeHeadIndex {
+    fn default() -> Self {
+        Self::new(SafeHeadRetention::default())
+    }
+}
+
+impl SafeHeadIndex {
+    /// Creates a new, in-memory [`SafeHeadIndex`] with the given retention.
+    pub fn new(retention: SafeHeadRetention) -> Self {
+        Self::from_inner(IndexInner { entries: BTreeMap::new(), retention, path: None, records: 0 })
+    }
+
+    /// Creates a new [`SafeHeadIndex`] that appends its records to the given file, replaying any
+    /// records left over from a previous run.
+    pub fn with_persistence(
+        retention: SafeHeadRetention,
+        path: impl Into<PathBuf>,
+    ) -> Result<Self, SafeHeadIndexError> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut inner = IndexInner { entries: BTreeMap::new(), retention, path: None, records: 0 };
+        match File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    inner.apply(serde_json::from_str(&line)?);
+                    inner.records += 1;
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        inner.path = Some(path);
+        inner.prune()?;
+        Ok(Self::from_inner(inner))
+    }
+
+    /// Wraps the given entries into an index, and updates the index size gauge.
+    fn from_inner(inner: IndexInner) -> Self {
+        inner.update_metrics();
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Locks the indexed safe heads.
+    fn lock(&self) -> MutexGuard<'_, IndexInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the number of indexed L1 blocks.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if no L1 blocks are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.lock().entries.is_empty()
+    }
+
+    /// Returns the [`SafeHeadRetention`] of the index.
+    pub fn retention(&self) -> SafeHeadRetention {
+        self.lock().retention
+    }
+
+    /// Records the safe head derived from the given L1 block. The entries of later L1 blocks, or
+    /// of later safe heads, are removed, as they were derived from a reorged L1 chain or before
+    /// an engine reset.
+    pub fn record(&self, l1_block: BlockNumHash, safe_head: BlockNumHash) {
+        let mut inner = self.lock();
+        let entry = SafeHeadEntry { l1_block, safe_head };
+        if inner.entries.get(&l1_block.number) == Some(&entry) {
+            return;
+        }
+        inner.persist(&entry);
+        inner.apply(entry);
+        inner.update_metrics();
+    }
+
+    /// Returns the safe head derived from the L1 chain up to and including the given L1 block,
+    /// i.e. the entry of the latest indexed L1 block at or below it.
+    ///
+    /// Returns `None` if the L1 block is older than the oldest indexed one, since the safe head
+    /// at that L1 block is no longer known.
+    pub fn safe_head_at(&self, l1_block: u64) -> Option<SafeHeadEntry> {
+        self.lock().entries.range(..=l1_block).next_back().map(|(_, entry)| *entry)
+    }
+
+    /// Removes the entries of the L1 blocks outside the [`SafeHeadRetention`], and compacts the
+    /// persisted records to the kept entries. Returns the number of removed entries.
+    pub fn prune(&self) -> Result<usize, SafeHeadIndexError> {
+        let mut inner = self.lock();
+        let pruned = inner.prune()?;
+        inner.update_metrics();
+        Ok(pruned)
+    }
+}
+
+impl IndexInner {
+    /// Inserts the given entry, removing the entries it invalidates.
+    fn apply(&mut self, entry: SafeHeadEntry) {
+        self.entries.split_off(&entry.l1_block.number);
+        while self
+            .entries
+            .last_key_value()
+            .is_some_and(|(_, last)| last.safe_head.number > entry.safe_head.number)
+        {
+            self.entries.pop_last();
+        }
+        self.entries.insert(entry.l1_block.number, entry);
+    }
+
+    /// Appends the entry to the file, if persistence is enabled. Failures are logged, and the
+    /// entry is kept in memory.
+    fn persist(&mut self, entry: &SafeHeadEntry) {
+        let Some(path) = &self.path else { return };
+        let result = serde_json::to_vec(entry).map_err(SafeHeadIndexError::from).and_then(|line| {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(&line)?;
+            file.write_all(b"\n")?;
+            Ok(())
+        });
+        match result {
+            Ok(()) => self.records += 1,
+            Err(err) => warn!(
+                target: "engine",
+                l1_block = entry.l1_block.number,
+                ?err,
+                "Failed to persist safe head"
+            ),
+        }
+    }
+
+    /// Removes the entries outside the retention, and rewrites the file if it holds any record
+    /// that is no longer an entry.
+    fn prune(&mut self) -> Result<usize, SafeHeadIndexError> {
+        let Some(latest) = self.entries.last_key_value().map(|(number, _)| *number) else {
+            return Ok(0);
+        };
+        let kept = self.entries.split_off(&self.retention.oldest_kept(latest));
+        let pruned = std::mem::replace(&mut self.entries, kept).len();
+
+        if let Some(path) = &self.path {
+            if self.records > self.entries.len() {
+                Self::compact(path, self.entries.values())?;
+                self.records = self.entries.len();
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// Replaces the file at the given path with the given entries, atomically.
+    fn compact<'a>(
+        path: &Path,
+        entries: impl Iterator<Item = &'a SafeHeadEntry>,
+    ) -> Result<(), SafeHeadIndexError> {
+        let mut bytes = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut bytes, entry)?;
+            bytes.push(b'\n');
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Updates the index size gauge.
+    fn update_metrics(&self) {
+        kona_macros::set!(gauge, Metrics::SAFE_HEAD_INDEX_SIZE, self.entries.len() as f64);
+    }
+}
+
+/// An error from the [`SafeHeadIndex`].
+#[derive(Error, Debug)]
+pub enum SafeHeadIndexError {
+    /// An I/O error on the persisted records.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A persisted record could not be (de)serialized.
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+/// A [`MaintenanceTask`] that prunes the [`SafeHeadIndex`] to its [`SafeHeadRetention`], so that
+/// neither its memory nor its file grows without bound.
+#[derive(Debug, Clone)]
+pub struct SafeHeadIndexPruning {
+    /// The pruned index.
+    pub index: SafeHeadIndex,
+    /// The interval between prunes.
+    pub interval: Duration,
+}
+
+#[async_trait]
+impl MaintenanceTask for SafeHeadIndexPruning {
+    fn name(&self) -> &'static str {
+        "safe_head_index_pruning"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn run(&self, _: &EngineClient, _: &EngineState) -> Result<(), MaintenanceTaskError> {
+        let index = self.index.clone();
+        let pruned = tokio::task::spawn_blocking(move || index.prune())
+            .await
+            .map_err(|err| MaintenanceTaskError::Other(Box::new(err)))?
+            .map_err(|err| MaintenanceTaskError::Other(Box::new(err)))?;
+        if pruned > 0 {
+            debug!(target: "engine", pruned, "Pruned safe head index");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockEngineClient;
+    use alloy_primitives::B256;
+    use kona_genesis::RollupConfig;
+
+    fn block(number: u64, salt: u8) -> BlockNumHash {
+        BlockNumHash { number, hash: B256::repeat_byte(salt) }
+    }
+
+    #[test]
+    fn test_retention_from_str() {
+        assert_eq!("full".parse::<SafeHeadRetention>(), Ok(SafeHeadRetention::Full));
+        assert_eq!("128".parse::<SafeHeadRetention>(), Ok(SafeHeadRetention::Epochs(128)));
+        assert!("0".parse::<SafeHeadRetention>().is_err());
+        assert!("forever".parse::<SafeHeadRetention>().is_err());
+        assert_eq!(SafeHeadRetention::Epochs(128).to_string(), "128");
+        assert_eq!(SafeHeadRetention::Full.to_string(), "full");
+    }
+
+    #[test]
+    fn test_safe_head_at_latest_l1_block_at_or_below() {
+        let index = SafeHeadIndex::new(SafeHeadRetention::Full);
+        index.record(block(10, 1), block(100, 1));
+        index.record(block(12, 1), block(110, 1));
+
+        assert_eq!(index.safe_head_at(9), None);
+        assert_eq!(index.safe_head_at(10).unwrap().safe_head, block(100, 1));
+        assert_eq!(index.safe_head_at(11).unwrap().safe_head, block(100, 1));
+        assert_eq!(index.safe_head_at(12).unwrap().l1_block, block(12, 1));
+        assert_eq!(index.safe_head_at(20).unwrap().safe_head, block(110, 1));
+
+        // The safe head advances within the same L1 block.
+        index.record(block(12, 1), block(115, 1));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.safe_head_at(12).unwrap().safe_head, block(115, 1));
+    }
+
+    #[test]
+    fn test_record_drops_invalidated_entries() {
+        let index = SafeHeadIndex::new(SafeHeadRetention::Full);
+        for n in 10..15 {
+            index.record(block(n, 1), block(n * 10, 1));
+        }
+
+        // An L1 reorg at block 13 invalidates the entries derived from the reorged blocks.
+        index.record(block(13, 2), block(130, 2));
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.safe_head_at(14).unwrap().l1_block, block(13, 2));
+
+        // An engine reset back to an older safe head invalidates the later safe heads.
+        index.record(block(13, 2), block(115, 3));
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.safe_head_at(13).unwrap().safe_head, block(115, 3));
+        assert_eq!(index.safe_head_at(12).unwrap().safe_head, block(110, 1));
+    }
+
+    #[test]
+    fn test_prune_keeps_last_epochs() {
+        let index = SafeHeadIndex::new(SafeHeadRetention::Epochs(3));
+        for n in 1..=5 {
+            index.record(block(n, 1), block(n * 10, 1));
+        }
+        assert_eq!(index.len(), 5);
+
+        assert_eq!(index.prune().unwrap(), 2);
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.safe_head_at(2), None);
+        assert_eq!(index.safe_head_at(3).unwrap().safe_head, block(30, 1));
+        assert_eq!(index.prune().unwrap(), 0);
+
+        let index = SafeHeadIndex::new(SafeHeadRetention::Full);
+        for n in 1..=5 {
+            index.record(block(n, 1), block(n * 10, 1));
+        }
+        assert_eq!(index.prune().unwrap(), 0);
+        assert_eq!(index.len(), 5);
+    }
+
+    #[test]
+    fn test_index_persists_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("safe-heads.jsonl");
+        let index = SafeHeadIndex::with_persistence(SafeHeadRetention::Full, &path).unwrap();
+        for n in 1..=4 {
+            index.record(block(n, 1), block(n * 10, 1));
+        }
+        index.record(block(4, 1), block(45, 1));
+        index.record(block(3, 2), block(35, 2));
+
+        // Reloading replays the records, including the ones that invalidated entries.
+        let index = SafeHeadIndex::with_persistence(SafeHeadRetention::Full, &path).unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.safe_head_at(10).unwrap().l1_block, block(3, 2));
+        assert_eq!(index.safe_head_at(3).unwrap().safe_head, block(35, 2));
+
+        // Loading compacted the file to the kept entries.
+        let lines = fs::read_to_string(&path).unwrap().lines().count();
+        assert_eq!(lines, 3);
+
+        // A smaller retention prunes the oldest persisted entries.
+        let index = SafeHeadIndex::with_persistence(SafeHeadRetention::Epochs(2), &path).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.safe_head_at(1), None);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pruning_task_prunes_index() {
+        let index = SafeHeadIndex::new(SafeHeadRetention::Epochs(1));
+        index.record(block(1, 1), block(10, 1));
+        index.record(block(2, 1), block(20, 1));
+
+        let task = SafeHeadIndexPruning {
+            index: index.clone(),
+            interval: DEFAULT_SAFE_HEAD_PRUNE_INTERVAL,
+        };
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        task.run(&mock.client(), &EngineState::default()).await.unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.safe_head_at(2).unwrap().safe_head, block(20, 1));
+    }
+}
@@ -0,0 +1,335 @@
+//! Shadowing of the engine API versions of upcoming hardforks.
+//!
+//! Ahead of a hardfork that changes the version of the `engine_newPayload` or `engine_getPayload`
+//! methods, an [`EngineClient`] [with shadowing][EngineClient::with_shadowing] repeats the calls
+//! it makes to these methods with the version that the hardfork activates. The shadow calls run
+//! in the background, and their outcomes are logged and recorded in the
+//! [`Metrics::ENGINE_SHADOW_CALLS`] counter, but never trusted. Both methods are idempotent, so
+//! the shadow calls do not change the state of the execution layer.
+//!
+//! Until the hardfork activates, an execution layer that supports the new version is expected to
+//! reject the shadow calls with an [unsupported fork][ShadowOutcome::UnsupportedFork] error,
+//! whereas one that does not support it rejects them as calls to an
+//! [unknown method][ShadowOutcome::MethodNotFound].
+
+use crate::{
+    EngineClient, EngineGetPayloadVersion, EngineNewPayloadVersion, Metrics,
+    client::HyperAuthClient,
+};
+use alloy_network::AnyNetwork;
+use alloy_primitives::B256;
+use alloy_provider::RootProvider;
+use alloy_rpc_types_engine::{
+    ExecutionPayloadV2, ExecutionPayloadV3, PayloadId, PayloadStatusEnum,
+};
+use alloy_transport::{RpcError, TransportResult};
+use alloy_transport_http::Http;
+use kona_genesis::RollupConfig;
+use op_alloy_provider::ext::engine::OpEngineApi;
+use op_alloy_rpc_types_engine::{
+    OpExecutionPayload, OpExecutionPayloadEnvelope, OpExecutionPayloadV4,
+};
+use std::{mem::discriminant, time::Duration};
+
+/// The error code of engine API calls whose version does not match the fork active at the
+/// timestamp of their payload.
+const UNSUPPORTED_FORK_ERROR: i64 = -38005;
+
+/// The error code of calls to unknown methods.
+const METHOD_NOT_FOUND_ERROR: i64 = -32601;
+
+/// The outcome of a shadow engine API call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowOutcome {
+    /// The call succeeded, with the same result as the trusted call.
+    Match,
+    /// The call succeeded, with another result than the trusted call.
+    Mismatch,
+    /// The call was rejected because the hardfork of its version is not active yet, as expected
+    /// from an execution layer that supports it.
+    UnsupportedFork,
+    /// The method is unknown to the execution layer, which does not support the hardfork.
+    MethodNotFound,
+    /// The call failed with another error.
+    Error,
+}
+
+impl ShadowOutcome {
+    /// All the [`ShadowOutcome`]s.
+    pub const ALL: [Self; 5] =
+        [Self::Match, Self::Mismatch, Self::UnsupportedFork, Self::MethodNotFound, Self::Error];
+
+    /// Returns the metric label of the outcome.
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Match => "match",
+            Self::Mismatch => "mismatch",
+            Self::UnsupportedFork => "unsupported_fork",
+            Self::MethodNotFound => "method_not_found",
+            Self::Error => "error",
+        }
+    }
+
+    /// Returns the outcome of a shadow call, given whether its result matches the result of the
+    /// trusted call.
+    fn from_result<T>(result: &TransportResult<T>, matches: impl FnOnce(&T) -> bool) -> Self {
+        match result {
+            Ok(value) if matches(value) => Self::Match,
+            Ok(_) => Self::Mismatch,
+            Err(RpcError::ErrorResp(err)) if err.code == UNSUPPORTED_FORK_ERROR => {
+                Self::UnsupportedFork
+            }
+            Err(RpcError::ErrorResp(err)) if err.code == METHOD_NOT_FOUND_ERROR => {
+                Self::MethodNotFound
+            }
+            Err(_) => Self::Error,
+        }
+    }
+
+    /// Logs the outcome of a shadow call to the given method, and records it.
+    fn record<T>(self, method: &'static str, result: &TransportResult<T>) {
+        let error = result.as_ref().err().map(|err| err.to_string());
+        match self {
+            Self::Match | Self::UnsupportedFork => {
+                debug!(target: "engine", method, outcome = self.label(), ?error, "Shadow engine call")
+            }
+            Self::Mismatch | Self::MethodNotFound | Self::Error => {
+                warn!(target: "engine", method, outcome = self.label(), ?error, "Shadow engine call")
+            }
+        }
+        kona_macros::inc!(
+            counter,
+            Metrics::ENGINE_SHADOW_CALLS,
+            "method" => method,
+            "outcome" => self.label()
+        );
+    }
+}
+
+impl EngineClient {
+    /// Shadows the engine API calls for payloads whose timestamp is within the given lookahead
+    /// of a hardfork that changes the engine API versions. See the [module docs](crate::shadow).
+    pub const fn with_shadowing(mut self, lookahead: Duration) -> Self {
+        self.shadow_lookahead = Some(lookahead);
+        self
+    }
+
+    /// Returns the version that the calls for the payload of the given timestamp are shadowed
+    /// with, if a hardfork activating a later version than the current one is within the
+    /// lookahead.
+    fn shadow_version<V: PartialOrd>(
+        &self,
+        timestamp: u64,
+        from_cfg: impl Fn(&RollupConfig, u64) -> V,
+    ) -> Option<V> {
+        let lookahead = self.shadow_lookahead?;
+        let current = from_cfg(&self.cfg, timestamp);
+        let shadow = from_cfg(&self.cfg, timestamp.saturating_add(lookahead.as_secs()));
+        (shadow > current).then_some(shadow)
+    }
+
+    /// Shadows the `engine_newPayload` call that imported the given payload envelope, to which
+    /// the execution layer answered with the given trusted status.
+    pub fn shadow_new_payload(
+        &self,
+        envelope: &OpExecutionPayloadEnvelope,
+        trusted: &PayloadStatusEnum,
+    ) {
+        let Some(version) =
+            self.shadow_version(envelope.payload.timestamp(), EngineNewPayloadVersion::from_cfg)
+        else {
+            return;
+        };
+        let engine = self.engine.clone();
+        let payload = into_v3(envelope.payload.clone());
+        let parent_beacon_block_root = envelope.parent_beacon_block_root.unwrap_or_default();
+        let trusted = discriminant(trusted);
+        tokio::spawn(async move {
+            let result = match version {
+                EngineNewPayloadVersion::V4 => {
+                    // The withdrawals root of the payload is only committed to from Isthmus.
+                    let withdrawals_root = B256::ZERO;
+                    let payload = OpExecutionPayloadV4 { payload_inner: payload, withdrawals_root };
+                    <RootProvider<AnyNetwork> as OpEngineApi<
+                        AnyNetwork,
+                        Http<HyperAuthClient>,
+                    >>::new_payload_v4(&engine, payload, parent_beacon_block_root)
+                    .await
+                }
+                EngineNewPayloadVersion::V2 | EngineNewPayloadVersion::V3 => <RootProvider<
+                    AnyNetwork,
+                > as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::new_payload_v3(
+                    &engine,
+                    payload,
+                    parent_beacon_block_root,
+                )
+                .await,
+            };
+            ShadowOutcome::from_result(&result, |status| discriminant(&status.status) == trusted)
+                .record(version.method(), &result);
+        });
+    }
+
+    /// Shadows the `engine_getPayload` call that fetched the payload of the given id and
+    /// timestamp, which the execution layer built with the given trusted block hash.
+    pub fn shadow_get_payload(&self, payload_id: PayloadId, timestamp: u64, trusted: B256) {
+        let Some(version) = self.shadow_version(timestamp, EngineGetPayloadVersion::from_cfg)
+        else {
+            return;
+        };
+        let engine = self.engine.clone();
+        tokio::spawn(async move {
+            let result =
+                match version {
+                    EngineGetPayloadVersion::V4 => {
+                        <RootProvider<AnyNetwork> as OpEngineApi<
+                            AnyNetwork,
+                            Http<HyperAuthClient>,
+                        >>::get_payload_v4(&engine, payload_id)
+                        .await
+                        .map(|envelope| {
+                            envelope.execution_payload.payload_inner.payload_inner.payload_inner
+                        })
+                    }
+                    EngineGetPayloadVersion::V2 | EngineGetPayloadVersion::V3 => {
+                        <RootProvider<AnyNetwork> as OpEngineApi<
+                            AnyNetwork,
+                            Http<HyperAuthClient>,
+                        >>::get_payload_v3(&engine, payload_id)
+                        .await
+                        .map(|envelope| envelope.execution_payload.payload_inner.payload_inner)
+                    }
+                };
+            ShadowOutcome::from_result(&result, |payload| payload.block_hash == trusted)
+                .record(version.method(), &result);
+        });
+    }
+}
+
+/// Returns the given payload as a V3 payload, with the fields introduced after its version
+/// zeroed.
+fn into_v3(payload: OpExecutionPayload) -> ExecutionPayloadV3 {
+    let v3 =
+        |payload_inner| ExecutionPayloadV3 { payload_inner, blob_gas_used: 0, excess_blob_gas: 0 };
+    match payload {
+        OpExecutionPayload::V1(payload) => {
+            v3(ExecutionPayloadV2 { payload_inner: payload, withdrawals: vec![] })
+        }
+        OpExecutionPayload::V2(payload) => v3(payload),
+        OpExecutionPayload::V3(payload) => payload,
+        OpExecutionPayload::V4(payload) => payload.payload_inner,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockEngineClient;
+    use alloy_json_rpc::ErrorPayload;
+    use alloy_rpc_types_engine::{ExecutionPayloadV1, PayloadStatus};
+    use std::sync::Arc;
+
+    /// Returns a client shadowing with the given lookahead the calls to the mock engine of a
+    /// chain that activates Isthmus at timestamp `10`.
+    fn client(lookahead: u64) -> (MockEngineClient, EngineClient) {
+        let mut cfg = RollupConfig::default();
+        cfg.hardforks.ecotone_time = Some(0);
+        cfg.hardforks.isthmus_time = Some(10);
+        let mock = MockEngineClient::new(Arc::new(cfg));
+        let client = mock.client().as_ref().clone().with_shadowing(Duration::from_secs(lookahead));
+        (mock, client)
+    }
+
+    fn envelope(timestamp: u64) -> OpExecutionPayloadEnvelope {
+        OpExecutionPayloadEnvelope {
+            parent_beacon_block_root: Some(B256::ZERO),
+            payload: OpExecutionPayload::V1(ExecutionPayloadV1 {
+                parent_hash: B256::ZERO,
+                fee_recipient: Default::default(),
+                state_root: B256::ZERO,
+                receipts_root: B256::ZERO,
+                logs_bloom: Default::default(),
+                prev_randao: B256::ZERO,
+                block_number: 1,
+                gas_limit: 0,
+                gas_used: 0,
+                timestamp,
+                extra_data: Default::default(),
+                base_fee_per_gas: Default::default(),
+                block_hash: B256::ZERO,
+                transactions: vec![],
+            }),
+        }
+    }
+
+    /// Waits for the spawned shadow calls to complete, and returns the called methods.
+    async fn methods(mock: &MockEngineClient) -> Vec<String> {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        mock.calls().into_iter().map(|call| call.method).collect()
+    }
+
+    #[tokio::test]
+    async fn test_shadow_new_payload_ahead_of_hardfork() {
+        let (mock, client) = client(10);
+        client.shadow_new_payload(&envelope(2), &PayloadStatusEnum::Valid);
+        assert_eq!(methods(&mock).await, vec!["engine_newPayloadV4"]);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_new_payload_outside_lookahead() {
+        let (mock, client) = client(5);
+        client.shadow_new_payload(&envelope(2), &PayloadStatusEnum::Valid);
+        // Once the hardfork is active, there is no later version to shadow.
+        client.shadow_new_payload(&envelope(12), &PayloadStatusEnum::Valid);
+        assert!(methods(&mock).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shadow_get_payload_ahead_of_hardfork() {
+        let (mock, client) = client(10);
+        client.shadow_get_payload(PayloadId::new([1; 8]), 2, B256::ZERO);
+        assert_eq!(methods(&mock).await, vec!["engine_getPayloadV4"]);
+    }
+
+    #[tokio::test]
+    async fn test_no_shadowing_by_default() {
+        let (mock, _) = client(10);
+        mock.client().shadow_new_payload(&envelope(2), &PayloadStatusEnum::Valid);
+        assert!(methods(&mock).await.is_empty());
+    }
+
+    #[test]
+    fn test_shadow_outcome_from_result() {
+        let status = |status| Ok(PayloadStatus::new(status, None));
+        let matches = |trusted: PayloadStatusEnum| {
+            move |status: &PayloadStatus| discriminant(&status.status) == discriminant(&trusted)
+        };
+        let error =
+            |code| Err(RpcError::ErrorResp(ErrorPayload { code, message: "".into(), data: None }));
+
+        let valid = status(PayloadStatusEnum::Valid);
+        assert_eq!(
+            ShadowOutcome::from_result(&valid, matches(PayloadStatusEnum::Valid)),
+            ShadowOutcome::Match
+        );
+        assert_eq!(
+            ShadowOutcome::from_result(&valid, matches(PayloadStatusEnum::Syncing)),
+            ShadowOutcome::Mismatch
+        );
+        for (code, outcome) in [
+            (UNSUPPORTED_FORK_ERROR, ShadowOutcome::UnsupportedFork),
+            (METHOD_NOT_FOUND_ERROR, ShadowOutcome::MethodNotFound),
+            (-32000, ShadowOutcome::Error),
+        ] {
+            assert_eq!(
+                ShadowOutcome::from_result(&error(code), matches(PayloadStatusEnum::Valid)),
+                outcome
+            );
+        }
+    }
+}
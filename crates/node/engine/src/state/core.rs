@@ -1,11 +1,14 @@
 //! The internal state of the engine controller.
 
 use crate::Metrics;
+use alloy_eips::BlockNumberOrTag;
 use alloy_rpc_types_engine::ForkchoiceState;
-use kona_protocol::L2BlockInfo;
+use kona_protocol::{ElSyncProgress, L2BlockInfo};
+use serde::{Deserialize, Serialize};
 
 /// The chain state viewed by the engine controller.
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct EngineState {
     /// Most recent block found on the p2p network
     pub(crate) unsafe_head: L2BlockInfo,
@@ -22,6 +25,11 @@ pub struct EngineState {
 
     /// Whether or not the EL has finished syncing.
     pub el_sync_finished: bool,
+    /// The last progress of the EL sync reported by the [`crate::ElSyncSupervisor`].
+    pub(crate) el_sync_progress: Option<ElSyncProgress>,
+    /// The last block produced by the deposits-only fallback, replacing a derived payload that
+    /// the EL found invalid.
+    pub(crate) deposits_only_head: Option<L2BlockInfo>,
 
     /// If a forkchoice update call is needed.
     pub forkchoice_update_needed: bool,
@@ -86,6 +94,37 @@ impl EngineState {
         self.finalized_head
     }
 
+    /// Resolves the given block label to the number of the block it designates in the engine
+    /// state, rather than in the view of the EL. `latest` and `pending` designate the unsafe head.
+    /// Block numbers and `earliest` are returned as is.
+    pub const fn resolve_label(&self, block: BlockNumberOrTag) -> BlockNumberOrTag {
+        let head = match block {
+            BlockNumberOrTag::Latest | BlockNumberOrTag::Pending => self.unsafe_head,
+            BlockNumberOrTag::Safe => self.safe_head,
+            BlockNumberOrTag::Finalized => self.finalized_head,
+            BlockNumberOrTag::Earliest | BlockNumberOrTag::Number(_) => return block,
+        };
+        BlockNumberOrTag::Number(head.block_info.number)
+    }
+
+    /// Returns the progress of the EL sync, if the EL is still syncing and its progress was
+    /// polled.
+    pub const fn el_sync_progress(&self) -> Option<ElSyncProgress> {
+        if self.el_sync_finished { None } else { self.el_sync_progress }
+    }
+
+    /// Returns the last block produced by the deposits-only fallback, if any. Such blocks replace
+    /// a derived payload that the EL found invalid, and hold its deposits only.
+    pub const fn deposits_only_head(&self) -> Option<L2BlockInfo> {
+        self.deposits_only_head
+    }
+
+    /// Records a block produced by the deposits-only fallback.
+    pub fn set_deposits_only_head(&mut self, block: L2BlockInfo) {
+        self.deposits_only_head = Some(block);
+        kona_macros::set!(gauge, Metrics::DEPOSITS_ONLY_BLOCK, block.block_info.number as f64);
+    }
+
     /// Set the unsafe head.
     pub fn set_unsafe_head(&mut self, unsafe_head: L2BlockInfo) {
         self.unsafe_head = unsafe_head;
@@ -141,6 +180,25 @@ mod test {
     use metrics_exporter_prometheus::PrometheusBuilder;
     use rstest::rstest;
 
+    #[test]
+    fn test_resolve_label() {
+        let block = |number| L2BlockInfo {
+            block_info: BlockInfo { number, ..Default::default() },
+            ..Default::default()
+        };
+        let mut state = EngineState::default();
+        state.set_unsafe_head(block(10));
+        state.set_safe_head(block(8));
+        state.set_finalized_head(block(4));
+
+        assert_eq!(state.resolve_label(BlockNumberOrTag::Latest), BlockNumberOrTag::Number(10));
+        assert_eq!(state.resolve_label(BlockNumberOrTag::Pending), BlockNumberOrTag::Number(10));
+        assert_eq!(state.resolve_label(BlockNumberOrTag::Safe), BlockNumberOrTag::Number(8));
+        assert_eq!(state.resolve_label(BlockNumberOrTag::Finalized), BlockNumberOrTag::Number(4));
+        assert_eq!(state.resolve_label(BlockNumberOrTag::Earliest), BlockNumberOrTag::Earliest);
+        assert_eq!(state.resolve_label(BlockNumberOrTag::Number(3)), BlockNumberOrTag::Number(3));
+    }
+
     #[rstest]
     #[case::set_unsafe(EngineState::set_unsafe_head, Metrics::UNSAFE_BLOCK_LABEL, 1)]
     #[case::set_cross_unsafe(
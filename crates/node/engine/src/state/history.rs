@@ -0,0 +1,192 @@
+//! Contains the [`EngineStateHistory`], a ring buffer of the latest [`EngineState`] transitions.
+
+use crate::EngineState;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The default number of transitions kept by the [`EngineStateHistory`].
+pub const DEFAULT_ENGINE_STATE_HISTORY_CAPACITY: usize = 64;
+
+/// A transition of the [`EngineState`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineStateTransition {
+    /// The unix timestamp of the transition, in milliseconds.
+    pub timestamp_ms: u64,
+    /// What caused the transition: the label of the engine task, or `reset`.
+    pub cause: String,
+    /// The names of the fields of the [`EngineState`] that changed.
+    pub changed: Vec<String>,
+    /// The [`EngineState`] before the transition.
+    pub previous: EngineState,
+    /// The [`EngineState`] after the transition.
+    pub state: EngineState,
+}
+
+impl EngineStateTransition {
+    /// Returns the names of the heads and sync flags that differ between the two states. The
+    /// progress of the EL sync and the forkchoice update flags are not considered.
+    pub fn changed_fields(previous: &EngineState, state: &EngineState) -> Vec<String> {
+        [
+            ("unsafeHead", previous.unsafe_head != state.unsafe_head),
+            ("crossUnsafeHead", previous.cross_unsafe_head != state.cross_unsafe_head),
+            ("localSafeHead", previous.local_safe_head != state.local_safe_head),
+            ("safeHead", previous.safe_head != state.safe_head),
+            ("finalizedHead", previous.finalized_head != state.finalized_head),
+            ("elSyncFinished", previous.el_sync_finished != state.el_sync_finished),
+            ("depositsOnlyHead", previous.deposits_only_head != state.deposits_only_head),
+        ]
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then(|| name.to_string()))
+        .collect()
+    }
+}
+
+/// A ring buffer of the last [`EngineStateTransition`]s, oldest first.
+///
+/// Only the transitions that change a head or a sync flag of the [`EngineState`] are recorded,
+/// so that flapping heads can be analyzed after the fact without trace-level logs.
+///
+/// Clones share the same transitions, so that the history can be written by the [`Engine`] and
+/// read by the RPC server.
+///
+/// [`Engine`]: crate::Engine
+#[derive(Debug, Clone)]
+pub struct EngineStateHistory {
+    /// The recorded transitions, shared between clones.
+    inner: Arc<Mutex<HistoryInner>>,
+}
+
+/// The transitions of an [`EngineStateHistory`].
+#[derive(Debug)]
+struct HistoryInner {
+    /// The recorded transitions, oldest first.
+    transitions: VecDeque<EngineStateTransition>,
+    /// The maximum number of recorded transitions.
+    capacity: usize,
+}
+
+impl Default for EngineStateHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_ENGINE_STATE_HISTORY_CAPACITY)
+    }
+}
+
+impl EngineStateHistory {
+    /// Creates a new, empty [`EngineStateHistory`] with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        let inner = HistoryInner { transitions: VecDeque::with_capacity(capacity), capacity };
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Locks the recorded transitions.
+    fn lock(&self) -> MutexGuard<'_, HistoryInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the number of recorded transitions.
+    pub fn len(&self) -> usize {
+        self.lock().transitions.len()
+    }
+
+    /// Returns `true` if no transitions are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.lock().transitions.is_empty()
+    }
+
+    /// Records the transition from `previous` to `state` caused by `cause`, evicting the oldest
+    /// transition if the history is full. Returns `false` if no head or sync flag changed, in
+    /// which case nothing is recorded.
+    pub fn record(&self, cause: &str, previous: &EngineState, state: &EngineState) -> bool {
+        let changed = EngineStateTransition::changed_fields(previous, state);
+        if changed.is_empty() {
+            return false;
+        }
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        trace!(target: "engine", cause, ?changed, "Recorded engine state transition");
+
+        let mut inner = self.lock();
+        if inner.capacity == 0 {
+            return true;
+        }
+        if inner.transitions.len() == inner.capacity {
+            inner.transitions.pop_front();
+        }
+        inner.transitions.push_back(EngineStateTransition {
+            timestamp_ms,
+            cause: cause.to_string(),
+            changed,
+            previous: *previous,
+            state: *state,
+        });
+        true
+    }
+
+    /// Returns the recorded transitions, oldest first.
+    pub fn transitions(&self) -> Vec<EngineStateTransition> {
+        self.lock().transitions.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kona_protocol::{BlockInfo, L2BlockInfo};
+
+    fn block(number: u64) -> L2BlockInfo {
+        L2BlockInfo { block_info: BlockInfo { number, ..Default::default() }, ..Default::default() }
+    }
+
+    #[test]
+    fn test_record_transitions() {
+        let history = EngineStateHistory::new(2);
+        let mut state = EngineState::default();
+
+        // Transitions that change no head are not recorded.
+        let previous = state;
+        state.forkchoice_update_needed = true;
+        assert!(!history.record("forkchoice-update", &previous, &state));
+        assert!(history.is_empty());
+
+        for number in 1..=3 {
+            let previous = state;
+            state.set_unsafe_head(block(number));
+            if number == 3 {
+                state.set_safe_head(block(number));
+            }
+            assert!(history.record("insert", &previous, &state));
+        }
+
+        // The oldest transition was evicted.
+        let transitions = history.transitions();
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].previous.unsafe_head(), block(1));
+        assert_eq!(transitions[0].state.unsafe_head(), block(2));
+        assert_eq!(transitions[0].changed, ["unsafeHead"]);
+        assert_eq!(transitions[1].changed, ["unsafeHead", "safeHead"]);
+        assert_eq!(transitions[1].cause, "insert");
+    }
+
+    #[test]
+    fn test_transition_serde() {
+        let history = EngineStateHistory::default();
+        let previous = EngineState::default();
+        let mut state = previous;
+        state.set_finalized_head(block(7));
+        history.record("finalize", &previous, &state);
+
+        let json = serde_json::to_value(history.transitions()).unwrap();
+        assert_eq!(json[0]["cause"], "finalize");
+        assert_eq!(json[0]["changed"][0], "finalizedHead");
+        assert_eq!(json[0]["state"]["finalizedHead"]["number"], 7);
+        let transitions: Vec<EngineStateTransition> = serde_json::from_value(json).unwrap();
+        assert_eq!(transitions, history.transitions());
+    }
+}
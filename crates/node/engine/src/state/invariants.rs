@@ -0,0 +1,389 @@
+//! Property tests for the invariants of the [`EngineState`] heads.
+//!
+//! Arbitrary sequences of engine tasks, interleaved with faults of the execution layer, are run
+//! against a [`MockEngineClient`]. After every task, whether it succeeded or not, the heads must
+//! be ordered as `finalized <= safe <= local-safe <= cross-unsafe <= unsafe`, the safe and
+//! finalized heads must not move backwards, and they must be ancestors of the unsafe head. The
+//! unsafe heads may only move backwards through a reorg by derived attributes.
+
+use crate::{
+    BuildTask, ConsolidateTask, EngineState, EngineTaskExt, FinalizeTask, ForkchoiceTask,
+    InsertUnsafeTask, MockEngineClient,
+};
+use alloy_consensus::{Header, transaction::Recovered};
+use alloy_eips::Encodable2718;
+use alloy_json_rpc::ErrorPayload;
+use alloy_primitives::{Address, B256, Bytes, U256};
+use alloy_rpc_types_engine::{
+    ExecutionPayloadEnvelopeV2, ExecutionPayloadFieldV2, ExecutionPayloadV1, PayloadAttributes,
+    PayloadStatusEnum,
+};
+use alloy_rpc_types_eth::BlockTransactions;
+use kona_genesis::{RollupConfig, SystemConfig};
+use kona_protocol::{BlockInfo, L1BlockInfoTx, L2BlockInfo, OpAttributesWithParent};
+use op_alloy_consensus::{OpBlock, OpTxEnvelope};
+use op_alloy_rpc_types_engine::{
+    OpExecutionPayload, OpExecutionPayloadEnvelope, OpPayloadAttributes,
+};
+use proptest::{
+    collection::vec,
+    prelude::{Just, Strategy, any},
+    prop_oneof, proptest,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// An engine task, or a fault of the execution layer.
+#[derive(Debug, Clone)]
+enum Op {
+    /// Inserts an unsafe payload built on the canonical block `depth` blocks below the unsafe
+    /// head.
+    InsertUnsafe { depth: u64, variant: u8 },
+    /// Builds a sequencer block on the unsafe head.
+    Sequence { variant: u8 },
+    /// Consolidates derived attributes on the safe head.
+    Derive { variant: u8, last_in_span: bool },
+    /// Finalizes the block `depth` blocks below the safe head.
+    Finalize { depth: i64 },
+    /// Sends a forkchoice update.
+    Forkchoice,
+    /// Scripts a fault of the execution layer.
+    Fault(Fault),
+}
+
+/// A fault of the execution layer, affecting the next matching call.
+#[derive(Debug, Clone, Copy)]
+enum Fault {
+    /// `engine_newPayload` returns `SYNCING`.
+    NewPayloadSyncing,
+    /// `engine_newPayload` returns `INVALID`.
+    NewPayloadInvalid,
+    /// `engine_forkchoiceUpdated` returns `SYNCING`.
+    ForkchoiceSyncing,
+    /// `engine_forkchoiceUpdatedV1` fails.
+    ForkchoiceV1Error,
+    /// `engine_forkchoiceUpdatedV3` fails.
+    ForkchoiceV3Error,
+    /// `eth_getBlockByNumber` fails.
+    BlockFetchError,
+}
+
+fn fault() -> impl Strategy<Value = Fault> {
+    prop_oneof![
+        Just(Fault::NewPayloadSyncing),
+        Just(Fault::NewPayloadInvalid),
+        Just(Fault::ForkchoiceSyncing),
+        Just(Fault::ForkchoiceV1Error),
+        Just(Fault::ForkchoiceV3Error),
+        Just(Fault::BlockFetchError),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..4u64, 0..3u8).prop_map(|(depth, variant)| Op::InsertUnsafe { depth, variant }),
+        2 => (0..3u8).prop_map(|variant| Op::Sequence { variant }),
+        3 => (0..3u8, any::<bool>())
+            .prop_map(|(variant, last_in_span)| Op::Derive { variant, last_in_span }),
+        2 => (-1..4i64).prop_map(|depth| Op::Finalize { depth }),
+        1 => Just(Op::Forkchoice),
+        2 => fault().prop_map(Op::Fault),
+    ]
+}
+
+/// Runs engine tasks against a [`MockEngineClient`], serving the chain of the unsafe head.
+#[derive(Debug)]
+struct Harness {
+    cfg: Arc<RollupConfig>,
+    mock: MockEngineClient,
+    state: EngineState,
+    /// The blocks built by the harness, by hash.
+    blocks: HashMap<B256, L2BlockInfo>,
+    /// The RPC representations of the blocks built by the harness, by hash.
+    rpc_blocks: HashMap<B256, alloy_rpc_types_eth::Block<op_alloy_rpc_types::Transaction>>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        Self {
+            cfg,
+            mock,
+            state: EngineState::default(),
+            blocks: HashMap::new(),
+            rpc_blocks: HashMap::new(),
+        }
+    }
+
+    /// Returns the chain of the unsafe head, by block number.
+    fn canonical(&self) -> Vec<L2BlockInfo> {
+        let mut chain = vec![];
+        let mut head = self.state.unsafe_head();
+        while head.block_info.number > 0 {
+            chain.push(head);
+            head =
+                *self.blocks.get(&head.block_info.parent_hash).unwrap_or(&L2BlockInfo::default());
+        }
+        chain.push(head);
+        chain.reverse();
+        chain
+    }
+
+    /// Returns the attributes of a child of the given parent. Variants of the same child differ
+    /// in their `prev_randao`, variant 0 being the one derived from L1.
+    fn attributes(
+        &self,
+        parent: L2BlockInfo,
+        variant: u8,
+        is_last_in_span: bool,
+    ) -> OpAttributesWithParent {
+        let number = parent.block_info.number + 1;
+        let timestamp = self.cfg.genesis.l2_time + number * self.cfg.block_time;
+        let (_, deposit) = L1BlockInfoTx::try_new_with_deposit_tx(
+            &self.cfg,
+            &SystemConfig::default(),
+            number,
+            &Header::default(),
+            timestamp,
+        )
+        .unwrap();
+        let attributes = OpPayloadAttributes {
+            payload_attributes: PayloadAttributes {
+                timestamp,
+                prev_randao: B256::with_last_byte(variant),
+                suggested_fee_recipient: Address::ZERO,
+                withdrawals: None,
+                parent_beacon_block_root: None,
+            },
+            transactions: Some(vec![OpTxEnvelope::Deposit(deposit).encoded_2718().into()]),
+            no_tx_pool: Some(true),
+            gas_limit: Some(30_000_000),
+            eip_1559_params: None,
+        };
+        OpAttributesWithParent::new(attributes, parent, BlockInfo::default(), is_last_in_span)
+    }
+
+    /// Returns the payload built from the given attributes, and records its block.
+    fn payload(&mut self, attributes: &OpAttributesWithParent) -> ExecutionPayloadV1 {
+        let inner = attributes.inner();
+        let mut payload = ExecutionPayloadV1 {
+            parent_hash: attributes.parent.block_info.hash,
+            fee_recipient: inner.payload_attributes.suggested_fee_recipient,
+            state_root: B256::ZERO,
+            receipts_root: B256::ZERO,
+            logs_bloom: Default::default(),
+            prev_randao: inner.payload_attributes.prev_randao,
+            block_number: attributes.block_number(),
+            gas_limit: inner.gas_limit.unwrap_or_default(),
+            gas_used: 0,
+            timestamp: inner.payload_attributes.timestamp,
+            extra_data: Bytes::new(),
+            base_fee_per_gas: U256::from(1),
+            block_hash: B256::ZERO,
+            transactions: inner.transactions.clone().unwrap_or_default(),
+        };
+        let block: OpBlock = payload.clone().try_into_block().unwrap();
+        let hash = block.header.hash_slow();
+        payload.block_hash = hash;
+
+        let info = L2BlockInfo::from_block_and_genesis(&block, &self.cfg.genesis).unwrap();
+        let transactions = block
+            .body
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let OpTxEnvelope::Deposit(deposit) = tx else { unreachable!("deposits only") };
+                op_alloy_rpc_types::Transaction {
+                    inner: alloy_rpc_types_eth::Transaction {
+                        inner: Recovered::new_unchecked(tx.clone(), deposit.from),
+                        block_hash: Some(hash),
+                        block_number: Some(block.header.number),
+                        transaction_index: Some(index as u64),
+                        effective_gas_price: Some(0),
+                    },
+                    deposit_nonce: None,
+                    deposit_receipt_version: None,
+                }
+            })
+            .collect();
+        let rpc_block = alloy_rpc_types_eth::Block {
+            header: alloy_rpc_types_eth::Header {
+                hash,
+                inner: block.header,
+                total_difficulty: Some(U256::ZERO),
+                size: None,
+            },
+            uncles: vec![],
+            transactions: BlockTransactions::Full(transactions),
+            withdrawals: None,
+        };
+        self.blocks.insert(hash, info);
+        self.rpc_blocks.insert(hash, rpc_block);
+        payload
+    }
+
+    /// Serves the payload built from the given attributes from the next payload building job.
+    fn push_payload(&mut self, attributes: &OpAttributesWithParent) {
+        let payload = self.payload(attributes);
+        self.mock.push_payload(&ExecutionPayloadEnvelopeV2 {
+            execution_payload: ExecutionPayloadFieldV2::V1(payload),
+            block_value: U256::ZERO,
+        });
+    }
+
+    /// Scripts the given fault.
+    fn inject(&self, fault: Fault) {
+        match fault {
+            Fault::NewPayloadSyncing => {
+                self.mock.push_new_payload_status(PayloadStatusEnum::Syncing)
+            }
+            Fault::NewPayloadInvalid => {
+                self.mock.push_new_payload_status(PayloadStatusEnum::Invalid {
+                    validation_error: "fault".to_string(),
+                })
+            }
+            Fault::ForkchoiceSyncing => {
+                self.mock.push_forkchoice_status(PayloadStatusEnum::Syncing)
+            }
+            Fault::ForkchoiceV1Error => {
+                self.mock.push_error("engine_forkchoiceUpdatedV1", ErrorPayload::internal_error())
+            }
+            Fault::ForkchoiceV3Error => {
+                self.mock.push_error("engine_forkchoiceUpdatedV3", ErrorPayload::internal_error())
+            }
+            Fault::BlockFetchError => {
+                self.mock.push_error("eth_getBlockByNumber", ErrorPayload::internal_error())
+            }
+        }
+    }
+
+    /// Runs the given operation, ignoring task errors, and serves the resulting chain of the
+    /// unsafe head.
+    async fn run(&mut self, op: Op) {
+        let client = self.mock.client();
+        let _ = match op {
+            Op::InsertUnsafe { depth, variant } => {
+                let number = self.state.unsafe_head().block_info.number.saturating_sub(depth);
+                let parent = self.canonical()[number as usize];
+                let payload = self.payload(&self.attributes(parent, variant, false));
+                let envelope = OpExecutionPayloadEnvelope {
+                    payload: OpExecutionPayload::V1(payload),
+                    parent_beacon_block_root: None,
+                };
+                InsertUnsafeTask::new(client, self.cfg.clone(), envelope)
+                    .execute(&mut self.state)
+                    .await
+            }
+            Op::Sequence { variant } => {
+                let attributes = self.attributes(self.state.unsafe_head(), variant, false);
+                self.push_payload(&attributes);
+                BuildTask::new(client, self.cfg.clone(), attributes, false, None)
+                    .execute(&mut self.state)
+                    .await
+            }
+            Op::Derive { variant, last_in_span } => {
+                let attributes = self.attributes(self.state.safe_head(), variant, last_in_span);
+                self.push_payload(&attributes);
+                ConsolidateTask::new(client, self.cfg.clone(), attributes, true)
+                    .execute(&mut self.state)
+                    .await
+            }
+            Op::Finalize { depth } => {
+                let number = self.state.safe_head().block_info.number.saturating_add_signed(-depth);
+                FinalizeTask::new(client, number).execute(&mut self.state).await
+            }
+            Op::Forkchoice => ForkchoiceTask::new(client).execute(&mut self.state).await,
+            Op::Fault(fault) => {
+                self.inject(fault);
+                Ok(())
+            }
+        };
+
+        self.mock.clear_payloads();
+        for block in self.canonical().into_iter().skip(1) {
+            self.mock
+                .insert_block(block.block_info.number, &self.rpc_blocks[&block.block_info.hash]);
+        }
+    }
+
+    /// Asserts the invariants of the heads, given the state before the last operation.
+    fn check(&self, previous: &EngineState, op: &Op) {
+        let state = &self.state;
+        let numbers = [
+            state.finalized_head(),
+            state.safe_head(),
+            state.local_safe_head(),
+            state.cross_unsafe_head(),
+            state.unsafe_head(),
+        ]
+        .map(|head| head.block_info.number);
+        assert!(numbers.is_sorted(), "heads out of order after {op:?}: {state:?}");
+
+        for (label, previous, head) in [
+            ("finalized", previous.finalized_head(), state.finalized_head()),
+            ("safe", previous.safe_head(), state.safe_head()),
+            ("local safe", previous.local_safe_head(), state.local_safe_head()),
+        ] {
+            assert!(
+                head.block_info.number >= previous.block_info.number,
+                "{label} head moved backwards after {op:?}: {previous:?} -> {head:?}"
+            );
+        }
+
+        let canonical = self.canonical();
+        for (label, head) in [
+            ("finalized", state.finalized_head()),
+            ("safe", state.safe_head()),
+            ("local safe", state.local_safe_head()),
+        ] {
+            assert_eq!(
+                canonical[head.block_info.number as usize], head,
+                "{label} head is not an ancestor of the unsafe head after {op:?}"
+            );
+        }
+    }
+}
+
+/// Runs the given operations, checking the invariants after each of them.
+async fn run(ops: Vec<Op>) -> Harness {
+    let mut harness = Harness::new();
+    for op in ops {
+        let previous = harness.state;
+        harness.run(op.clone()).await;
+        harness.check(&previous, &op);
+    }
+    harness
+}
+
+proptest! {
+    #[test]
+    fn test_engine_state_head_invariants(ops in vec(op(), 1..48)) {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(run(ops));
+    }
+}
+
+#[tokio::test]
+async fn test_finalize_below_finalized_head() {
+    let harness = run(vec![
+        Op::Derive { variant: 0, last_in_span: true },
+        Op::Derive { variant: 0, last_in_span: true },
+        Op::Finalize { depth: 0 },
+        Op::Finalize { depth: 1 },
+    ])
+    .await;
+    assert_eq!(harness.state.finalized_head().block_info.number, 2);
+}
+
+#[tokio::test]
+async fn test_insert_unsafe_below_safe_head() {
+    let harness = run(vec![
+        Op::Derive { variant: 0, last_in_span: true },
+        Op::Derive { variant: 0, last_in_span: true },
+        Op::InsertUnsafe { depth: 2, variant: 1 },
+    ])
+    .await;
+    assert_eq!(harness.state.unsafe_head(), harness.state.safe_head());
+    assert_eq!(harness.state.unsafe_head().block_info.number, 2);
+}
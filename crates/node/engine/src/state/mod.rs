@@ -2,3 +2,11 @@
 
 mod core;
 pub use core::EngineState;
+
+mod history;
+pub use history::{
+    DEFAULT_ENGINE_STATE_HISTORY_CAPACITY, EngineStateHistory, EngineStateTransition,
+};
+
+#[cfg(test)]
+mod invariants;
@@ -0,0 +1,79 @@
+//! Contains the [`SyncMode`], which selects how the rollup node starts syncing the L2 chain.
+
+use crate::{EngineClient, EngineClientError};
+use alloy_eips::eip1898::BlockNumberOrTag;
+use derive_more::Display;
+use std::str::FromStr;
+
+/// The strategy used by the rollup node to sync the L2 chain on startup.
+#[derive(Debug, Display, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Select the sync mode based on the state of the execution layer. See
+    /// [`SyncMode::resolve`].
+    #[display("auto")]
+    Auto,
+    /// Let the execution layer sync the unsafe chain from the p2p network, and only start
+    /// derivation once it reports that it has finished syncing.
+    #[default]
+    #[display("execution-layer")]
+    ExecutionLayer,
+    /// Walk back from the execution layer's heads to find a sync starting point with verified L1
+    /// origins, and start derivation immediately.
+    #[display("consensus")]
+    Consensus,
+}
+
+impl SyncMode {
+    /// Contains all valid sync modes.
+    pub const MODES: [Self; 3] = [Self::Auto, Self::ExecutionLayer, Self::Consensus];
+
+    /// Resolves [`SyncMode::Auto`] into a concrete sync mode, given the state of the execution
+    /// layer. Other modes are returned as-is.
+    ///
+    /// If the execution layer already has a finalized block past the L2 genesis, it holds a
+    /// canonical chain that the rollup node can start derivation from, so
+    /// [`SyncMode::Consensus`] is selected. Otherwise, the execution layer is (re)starting from
+    /// genesis and is left to sync the chain itself with [`SyncMode::ExecutionLayer`].
+    pub async fn resolve(self, client: &EngineClient) -> Result<Self, EngineClientError> {
+        if self != Self::Auto {
+            return Ok(self);
+        }
+
+        let finalized = client.l2_block_info_by_label(BlockNumberOrTag::Finalized).await?;
+        let genesis = client.cfg().genesis.l2.number;
+        Ok(match finalized {
+            Some(finalized) if finalized.block_info.number > genesis => Self::Consensus,
+            _ => Self::ExecutionLayer,
+        })
+    }
+}
+
+impl FromStr for SyncMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::MODES
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| format!("invalid sync mode: {s}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_mode_default() {
+        assert_eq!(SyncMode::default(), SyncMode::ExecutionLayer);
+    }
+
+    #[test]
+    fn test_sync_mode_round_trip() {
+        for mode in SyncMode::MODES {
+            assert_eq!(mode.to_string().parse::<SyncMode>().unwrap(), mode);
+        }
+        assert_eq!("execution-layer".parse::<SyncMode>().unwrap(), SyncMode::ExecutionLayer);
+        assert!("snap".parse::<SyncMode>().is_err());
+    }
+}
@@ -1,12 +1,17 @@
 //! The [`Engine`] is a task queue that receives and executes [`EngineTask`]s.
 
-use super::{EngineTaskError, EngineTaskExt};
-use crate::{EngineClient, EngineState, EngineTask, Metrics};
+use super::{
+    EngineTaskError, EngineTaskExt, EngineTaskKind, EngineTaskTimeout, EngineTaskTimeouts,
+    SyncingBuffer, SyncingBufferConfig, TaskTimeoutPolicy,
+};
+use crate::{EngineClient, EngineState, EngineStateHistory, EngineTask, Metrics};
 use alloy_provider::Provider;
 use alloy_rpc_types_eth::Transaction;
 use kona_genesis::{RollupConfig, SystemConfig};
-use kona_protocol::{BlockInfo, L2BlockInfo, OpBlockConversionError, to_system_config};
-use kona_sources::{SyncStartError, find_starting_forkchoice};
+use kona_protocol::{
+    BlockInfo, ElSyncProgress, L2BlockInfo, OpBlockConversionError, to_system_config,
+};
+use kona_sources::{SafeHeadHint, SyncStartError, find_starting_forkchoice};
 use op_alloy_consensus::OpTxEnvelope;
 use std::{collections::BinaryHeap, sync::Arc};
 use thiserror::Error;
@@ -25,6 +30,16 @@ use tokio::sync::watch::Sender;
 /// Tasks within the queue are also considered fallible. If they fail with a temporary error,
 /// they are not popped from the queue, the error is returned, and they are retried on the
 /// next call to [`Engine::drain`].
+///
+/// Tasks can be given a timeout by kind with [`Engine::with_task_timeouts`]. A task that times
+/// out is cancelled, and retried or dropped according to the [`TaskTimeoutPolicy`]. Consolidation
+/// of derived attributes is always retried.
+///
+/// Tasks that fail because the execution layer is syncing are held back, and resubmitted with
+/// backoff until the execution layer is synced. See [`SyncingBufferConfig`].
+///
+/// The transitions of the [`EngineState`] are recorded into an [`EngineStateHistory`], along
+/// with the task that caused them.
 #[derive(Debug)]
 pub struct Engine {
     /// The state of the engine.
@@ -33,6 +48,18 @@ pub struct Engine {
     state_sender: Sender<EngineState>,
     /// The task queue.
     tasks: BinaryHeap<EngineTask>,
+    /// The timeouts of the tasks, by kind.
+    timeouts: EngineTaskTimeouts,
+    /// The number of times the task at the head of the queue was retried after timing out.
+    timeout_retries: u32,
+    /// The kind of the last task dropped after timing out, until a task of that kind completes.
+    timed_out: Option<EngineTaskKind>,
+    /// The trusted [`SafeHeadHint`] that resets fast-forward the safe head to, if any.
+    safe_head_hint: Option<SafeHeadHint>,
+    /// The tasks held back while the execution layer is syncing.
+    syncing: SyncingBuffer,
+    /// The latest transitions of the engine state.
+    history: EngineStateHistory,
 }
 
 impl Engine {
@@ -41,7 +68,53 @@ impl Engine {
     /// An initial [`EngineTask::ForkchoiceUpdate`] is added to the task queue to synchronize the
     /// engine with the forkchoice state of the [`EngineState`].
     pub fn new(initial_state: EngineState, state_sender: Sender<EngineState>) -> Self {
-        Self { state: initial_state, state_sender, tasks: BinaryHeap::default() }
+        Self {
+            state: initial_state,
+            state_sender,
+            tasks: BinaryHeap::default(),
+            timeouts: EngineTaskTimeouts::default(),
+            timeout_retries: 0,
+            timed_out: None,
+            safe_head_hint: None,
+            syncing: SyncingBuffer::new(SyncingBufferConfig::default()),
+            history: EngineStateHistory::default(),
+        }
+    }
+
+    /// Returns the [`Engine`] with the given [`EngineTaskTimeouts`].
+    pub const fn with_task_timeouts(mut self, timeouts: EngineTaskTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Returns the [`Engine`] with the given [`SyncingBufferConfig`].
+    pub fn with_syncing_buffer(mut self, config: SyncingBufferConfig) -> Self {
+        self.syncing = SyncingBuffer::new(config);
+        self
+    }
+
+    /// Returns the [`Engine`] recording its state transitions into the given
+    /// [`EngineStateHistory`].
+    pub fn with_state_history(mut self, history: EngineStateHistory) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Returns the [`EngineStateHistory`] the state transitions are recorded into.
+    pub const fn history(&self) -> &EngineStateHistory {
+        &self.history
+    }
+
+    /// Sets the trusted [`SafeHeadHint`] that subsequent [resets][Self::reset] fast-forward the
+    /// safe head to.
+    pub const fn set_safe_head_hint(&mut self, hint: Option<SafeHeadHint>) {
+        self.safe_head_hint = hint;
+    }
+
+    /// Returns the kind of the last task that was dropped after timing out, if no task of that
+    /// kind has completed since. The engine should then be considered degraded.
+    pub const fn timed_out(&self) -> Option<EngineTaskKind> {
+        self.timed_out
     }
 
     /// Returns a reference to the inner [`EngineState`].
@@ -49,11 +122,63 @@ impl Engine {
         &self.state
     }
 
+    /// Marks the execution layer as synced, skipping execution layer sync. Used when the rollup
+    /// node starts in [`SyncMode::Consensus`].
+    ///
+    /// [`SyncMode::Consensus`]: crate::SyncMode::Consensus
+    pub fn skip_el_sync(&mut self) {
+        let previous = self.state;
+        self.state.el_sync_finished = true;
+        self.history.record("skip-el-sync", &previous, &self.state);
+        self.state_sender.send_replace(self.state);
+    }
+
+    /// Marks the execution layer as syncing again, e.g. when the unsafe head lags too far behind
+    /// gossip. Unsafe payloads then drive EL sync towards the tip, until a forkchoice update
+    /// reports the EL synced again.
+    pub fn restart_el_sync(&mut self) {
+        let previous = self.state;
+        self.state.el_sync_finished = false;
+        self.state.el_sync_progress = None;
+        self.history.record("restart-el-sync", &previous, &self.state);
+        self.state_sender.send_replace(self.state);
+        kona_macros::inc!(counter, Metrics::EL_RESYNC_COUNT);
+    }
+
+    /// Records the progress of the EL sync, reported by the [`ElSyncSupervisor`].
+    ///
+    /// [`ElSyncSupervisor`]: crate::ElSyncSupervisor
+    pub fn set_el_sync_progress(&mut self, progress: ElSyncProgress) {
+        self.state.el_sync_progress = Some(progress);
+        self.state_sender.send_replace(self.state);
+    }
+
     /// Returns a receiver that can be used to listen to engine state updates.
     pub fn subscribe(&self) -> tokio::sync::watch::Receiver<EngineState> {
         self.state_sender.subscribe()
     }
 
+    /// Returns the number of queued [`EngineTask`]s.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if no [`EngineTask`]s are queued.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Returns the number of [`EngineTask`]s held back while the execution layer is syncing.
+    pub fn syncing_len(&self) -> usize {
+        self.syncing.len()
+    }
+
+    /// Returns the time at which the [`EngineTask`]s held back while the execution layer is
+    /// syncing are resubmitted, if any are. [`Engine::drain`] must be called then.
+    pub const fn syncing_retry_at(&self) -> Option<tokio::time::Instant> {
+        self.syncing.retry_at()
+    }
+
     /// Enqueues a new [`EngineTask`] for execution.
     pub fn enqueue(&mut self, task: EngineTask) {
         self.tasks.push(task);
@@ -62,6 +187,10 @@ impl Engine {
     /// Resets the engine by finding a plausible sync starting point via
     /// [`find_starting_forkchoice`]. The state will be updated to the starting point, and a
     /// forkchoice update will be enqueued in order to reorg the execution layer.
+    ///
+    /// If a [`SafeHeadHint`] is set, and it verifies and is ahead of the starting point, the safe
+    /// head is fast-forwarded to it, so that derivation starts from the hinted safe head. A hint
+    /// that fails verification is ignored.
     pub async fn reset(
         &mut self,
         client: Arc<EngineClient>,
@@ -70,21 +199,50 @@ impl Engine {
         // Clear any outstanding tasks to prepare for the reset.
         self.clear();
 
-        let start =
+        let mut start =
             find_starting_forkchoice(config, client.l1_provider(), client.l2_provider()).await?;
+        if let Some(hint) = self.safe_head_hint {
+            match hint.verify(config, client.l1_provider(), client.l2_provider()).await {
+                Ok(safe) => {
+                    if start.fast_forward(safe) {
+                        info!(target: "engine", %hint, "Fast-forwarded the safe head to the trusted hint");
+                    }
+                }
+                Err(err @ SyncStartError::RpcError(_)) => return Err(err.into()),
+                Err(err) => {
+                    warn!(target: "engine", %hint, ?err, "Ignoring invalid safe head hint");
+                }
+            }
+        }
 
+        let previous = self.state;
         self.state.set_unsafe_head(start.un_safe);
         self.state.set_cross_unsafe_head(start.un_safe);
         self.state.set_local_safe_head(start.safe);
         self.state.set_safe_head(start.safe);
         self.state.set_finalized_head(start.finalized);
+        self.history.record("reset", &previous, &self.state);
+
+        let (l1_origin_info, system_config) =
+            Self::derivation_start(&client, config, start.safe).await?;
+
+        kona_macros::inc!(counter, Metrics::ENGINE_RESET_COUNT);
+
+        Ok((start.safe, l1_origin_info, system_config))
+    }
 
-        // Find the new safe head's L1 origin and SystemConfig.
-        let origin_block = start
-            .safe
+    /// Returns the L1 block and the [`SystemConfig`] that the derivation pipeline must be reset to
+    /// in order to derive the L2 blocks after the given safe head.
+    pub async fn derivation_start(
+        client: &EngineClient,
+        config: &RollupConfig,
+        safe_head: L2BlockInfo,
+    ) -> Result<(BlockInfo, SystemConfig), EngineResetError> {
+        // Find the safe head's L1 origin and SystemConfig.
+        let origin_block = safe_head
             .l1_origin
             .number
-            .saturating_sub(config.channel_timeout(start.safe.block_info.timestamp));
+            .saturating_sub(config.channel_timeout(safe_head.block_info.timestamp));
         let l1_origin_info: BlockInfo = client
             .l1_provider()
             .get_block(origin_block.into())
@@ -95,7 +253,7 @@ impl Engine {
             .into();
         let l2_safe_block = client
             .l2_provider()
-            .get_block(start.safe.block_info.hash.into())
+            .get_block(safe_head.block_info.hash.into())
             .full()
             .await
             .map_err(SyncStartError::RpcError)?
@@ -103,33 +261,155 @@ impl Engine {
             .into_consensus()
             .map_transactions(|t| <Transaction<OpTxEnvelope> as Clone>::clone(&t).into_inner());
         let system_config = to_system_config(&l2_safe_block, config)?;
-
-        kona_macros::inc!(counter, Metrics::ENGINE_RESET_COUNT);
-
-        Ok((start.safe, l1_origin_info, system_config))
+        Ok((l1_origin_info, system_config))
     }
 
-    /// Clears the task queue.
+    /// Clears the task queue, and the tasks held back while the execution layer is syncing.
     pub fn clear(&mut self) {
         self.tasks.clear();
+        self.syncing.clear();
+        self.timeout_retries = 0;
+    }
+
+    /// Cancels the queued tasks of the given kind, including those held back while the execution
+    /// layer is syncing. Returns the number of cancelled tasks.
+    pub fn cancel(&mut self, kind: EngineTaskKind) -> usize {
+        let queued = self.tasks.len();
+        self.tasks.retain(|task| task.kind() != kind);
+        if self.tasks.len() != queued {
+            self.timeout_retries = 0;
+        }
+        queued - self.tasks.len() + self.syncing.cancel(kind)
     }
 
     /// Attempts to drain the queue by executing all [`EngineTask`]s in-order. If any task returns
     /// an error along the way, it is not popped from the queue (in case it must be retried) and
-    /// the error is returned.
+    /// the error is returned. A task failing with [`EngineTaskError::Stale`] is dropped instead,
+    /// along with the queued [`EngineTask::Consolidate`] tasks, which were derived after it.
+    ///
+    /// A task failing with [`EngineTaskError::Syncing`] is held back, and the rest of the queue
+    /// proceeds, except for the [`EngineTask::Consolidate`] tasks, which are held back behind it
+    /// since they build on it. Once its backoff elapses, the oldest task held back is resubmitted
+    /// first, and the others follow once a block was built. If holding back tasks is disabled,
+    /// the task is kept in the queue and a temporary error is returned.
     pub async fn drain(&mut self) -> Result<(), EngineTaskError> {
+        if let Some(task) = self.syncing.probe(tokio::time::Instant::now()) {
+            debug!(target: "engine", task = %task.kind(), held = self.syncing.len(), "Resubmitting task held back while the EL is syncing");
+            self.tasks.push(task);
+        }
+
         // Drain tasks in order of priority, halting on errors for a retry to be attempted.
         while let Some(task) = self.tasks.peek() {
-            // Execute the task
-            task.execute(&mut self.state).await?;
+            let kind = task.kind();
+
+            // Derived attributes build on the ones held back, and must not be consolidated ahead
+            // of them.
+            if kind == EngineTaskKind::Consolidate && self.syncing.is_holding() {
+                if let Some(task) = self.tasks.pop() {
+                    if let Some(dropped) = self.syncing.defer(task) {
+                        warn!(target: "engine", task = %dropped.kind(), "Dropped task held back while the EL is syncing");
+                    }
+                }
+                continue;
+            }
+
+            // Execute the task against a copy of the state, so that a task cancelled on timeout
+            // leaves the state untouched.
+            let previous = self.state;
+            let mut state = previous;
+            let result = match self.timeouts.get(kind) {
+                Some(timeout) => {
+                    match tokio::time::timeout(timeout, task.execute(&mut state)).await {
+                        Ok(result) => result,
+                        Err(_) => {
+                            self.handle_timeout(kind, timeout)?;
+                            continue;
+                        }
+                    }
+                }
+                None => task.execute(&mut state).await,
+            };
+            self.state = state;
+            self.history.record(kind.label(), &previous, &self.state);
+            if let Err(EngineTaskError::Stale(err)) = result {
+                // The task no longer applies. The attributes consolidated after it were derived
+                // on top of it, and are stale too.
+                self.tasks.pop();
+                let dropped = self.cancel(EngineTaskKind::Consolidate);
+                warn!(target: "engine", task = %kind, dropped, %err, "Dropped stale engine task");
+                return Err(EngineTaskError::Stale(err));
+            }
+            if let Err(EngineTaskError::Syncing(err)) = result {
+                if !self.syncing.is_enabled() {
+                    return Err(EngineTaskError::Temporary(err));
+                }
+                // Hold the task back, so that the tasks driving the EL sync proceed meanwhile.
+                if let Some(task) = self.tasks.pop() {
+                    if let Some(dropped) = self.syncing.hold(task, tokio::time::Instant::now()) {
+                        warn!(target: "engine", task = %dropped.kind(), "Dropped task held back while the EL is syncing");
+                    }
+                }
+                debug!(target: "engine", task = %kind, held = self.syncing.len(), %err, "Holding back task while the EL is syncing");
+                continue;
+            }
+            result?;
+
+            self.timeout_retries = 0;
+            if self.timed_out == Some(kind) {
+                self.timed_out = None;
+            }
 
             // Update the state and notify the engine actor.
             self.state_sender.send_replace(self.state);
 
             // Pop the task from the queue now that it's been executed.
             self.tasks.pop();
+
+            // A block was built, so the EL is synced, and the tasks held back are resubmitted.
+            if matches!(kind, EngineTaskKind::BuildBlock | EngineTaskKind::Consolidate) &&
+                !self.syncing.is_empty()
+            {
+                let resubmitted = self.syncing.synced();
+                info!(target: "engine", count = resubmitted.len(), "EL synced, resubmitting held back tasks");
+                self.tasks.extend(resubmitted);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles a timeout of the task at the head of the queue, according to the
+    /// [`TaskTimeoutPolicy`]. Returns an error if the task is kept in the queue to be retried.
+    ///
+    /// [`EngineTask::Consolidate`] tasks are always retried: the derived attributes they carry
+    /// are not derived again, so dropping one would leave the safe chain with a gap.
+    fn handle_timeout(
+        &mut self,
+        kind: EngineTaskKind,
+        timeout: std::time::Duration,
+    ) -> Result<(), EngineTaskError> {
+        kona_macros::inc!(counter, Metrics::ENGINE_TASK_TIMEOUT_COUNT, "task" => kind.label());
+
+        if kind == EngineTaskKind::Consolidate {
+            self.timeout_retries = self.timeout_retries.saturating_add(1);
+            let timeout = EngineTaskTimeout { kind, timeout, retry: self.timeout_retries };
+            warn!(target: "engine", %timeout, "Derived engine task timed out, retrying");
+            return Err(EngineTaskError::Temporary(Box::new(timeout)));
+        }
+
+        if let TaskTimeoutPolicy::Retry(max_retries) = self.timeouts.policy {
+            if self.timeout_retries < max_retries {
+                self.timeout_retries += 1;
+                let timeout = EngineTaskTimeout { kind, timeout, retry: self.timeout_retries };
+                warn!(target: "engine", %timeout, "Engine task timed out, retrying");
+                return Err(EngineTaskError::Temporary(Box::new(timeout)));
+            }
         }
 
+        warn!(target: "engine", task = %kind, ?timeout, "Engine task timed out, dropping it");
+        self.tasks.pop();
+        self.timeout_retries = 0;
+        self.timed_out = Some(kind);
         Ok(())
     }
 }
@@ -147,3 +427,276 @@ pub enum EngineResetError {
     #[error(transparent)]
     SystemConfigConversion(#[from] OpBlockConversionError),
 }
+
+impl EngineResetError {
+    /// Returns `true` if the reset failed on an RPC error, and may succeed if retried.
+    pub const fn is_temporary(&self) -> bool {
+        matches!(self, Self::SyncStart(SyncStartError::RpcError(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BuildTask, ConsolidateTask, DEFAULT_SYNCING_INITIAL_BACKOFF, ForkchoiceTask,
+        MockEngineClient,
+    };
+    use alloy_primitives::B256;
+    use alloy_rpc_types_engine::PayloadStatusEnum;
+    use kona_protocol::OpAttributesWithParent;
+    use std::time::Duration;
+
+    /// Returns an [`Engine`] with a forkchoice update queued against the mock, whose requests are
+    /// answered after the given latency.
+    fn engine(mock: &MockEngineClient, latency: Duration, timeouts: EngineTaskTimeouts) -> Engine {
+        mock.set_latency(latency);
+        let state = EngineState { forkchoice_update_needed: true, ..Default::default() };
+        let (sender, _) = tokio::sync::watch::channel(state);
+        let mut engine = Engine::new(state, sender).with_task_timeouts(timeouts);
+        engine.enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(mock.client())));
+        engine
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_retries_timed_out_task() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let timeouts = EngineTaskTimeouts::default()
+            .with(EngineTaskKind::ForkchoiceUpdate, Duration::from_secs(1))
+            .with_policy(TaskTimeoutPolicy::Retry(1));
+        let mut engine = engine(&mock, Duration::from_secs(5), timeouts);
+
+        let err = engine.drain().await.unwrap_err();
+        assert!(err.to_string().contains("forkchoice-update task timed out after 1s (retry 1)"));
+        assert_eq!(engine.len(), 1);
+        // The cancelled task left the state untouched.
+        assert!(engine.state().forkchoice_update_needed);
+
+        // The task succeeds once the execution layer answers in time.
+        mock.set_latency(Duration::from_millis(10));
+        engine.drain().await.unwrap();
+        assert!(engine.is_empty());
+        assert!(!engine.state().forkchoice_update_needed);
+        assert_eq!(engine.timed_out(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_drops_timed_out_task() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let timeouts = EngineTaskTimeouts::default()
+            .with(EngineTaskKind::ForkchoiceUpdate, Duration::from_secs(1))
+            .with_policy(TaskTimeoutPolicy::Retry(1));
+        let mut engine = engine(&mock, Duration::from_secs(5), timeouts);
+
+        // The task is dropped once out of retries, and the engine reports it as timed out until a
+        // task of the same kind completes.
+        assert!(engine.drain().await.is_err());
+        engine.drain().await.unwrap();
+        assert!(engine.is_empty());
+        assert_eq!(engine.timed_out(), Some(EngineTaskKind::ForkchoiceUpdate));
+
+        mock.set_latency(Duration::ZERO);
+        engine.enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(mock.client())));
+        engine.drain().await.unwrap();
+        assert_eq!(engine.timed_out(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_drop_policy() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let timeouts = EngineTaskTimeouts::default()
+            .with(EngineTaskKind::ForkchoiceUpdate, Duration::from_secs(1))
+            .with_policy(TaskTimeoutPolicy::Drop);
+        let mut engine = engine(&mock, Duration::from_secs(5), timeouts);
+
+        engine.drain().await.unwrap();
+        assert!(engine.is_empty());
+        assert_eq!(engine.timed_out(), Some(EngineTaskKind::ForkchoiceUpdate));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_never_drops_consolidation() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        mock.set_latency(Duration::from_secs(5));
+        let timeouts = EngineTaskTimeouts::default()
+            .with(EngineTaskKind::Consolidate, Duration::from_secs(1))
+            .with_policy(TaskTimeoutPolicy::Drop);
+        let (sender, _) = tokio::sync::watch::channel(EngineState::default());
+        let mut engine = Engine::new(EngineState::default(), sender).with_task_timeouts(timeouts);
+        let attributes = OpAttributesWithParent::new(
+            Default::default(),
+            L2BlockInfo::default(),
+            BlockInfo::default(),
+            true,
+        );
+        engine.enqueue(EngineTask::Consolidate(ConsolidateTask::new(
+            mock.client(),
+            cfg,
+            attributes,
+            true,
+        )));
+
+        // The derived attributes are kept in the queue, whatever the policy.
+        for _ in 0..3 {
+            let err = engine.drain().await.unwrap_err();
+            assert!(matches!(err, EngineTaskError::Temporary(_)));
+            assert_eq!(engine.len(), 1);
+        }
+        assert_eq!(engine.timed_out(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_without_timeout() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let timeouts = EngineTaskTimeouts::default()
+            .with(EngineTaskKind::BuildBlock, Duration::from_secs(1))
+            .with_policy(TaskTimeoutPolicy::Drop);
+        let mut engine = engine(&mock, Duration::from_secs(5), timeouts);
+
+        engine.drain().await.unwrap();
+        assert!(!engine.state().forkchoice_update_needed);
+        assert_eq!(engine.timed_out(), None);
+    }
+
+    #[tokio::test]
+    async fn test_drain_drops_stale_attributes() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        let (sender, _) = tokio::sync::watch::channel(EngineState::default());
+        let mut engine = Engine::new(EngineState::default(), sender);
+
+        // Attributes derived on top of a block that is no longer the local safe head.
+        let stale = BlockInfo { number: 1, hash: B256::repeat_byte(1), ..Default::default() };
+        for number in 1..=2 {
+            let parent =
+                L2BlockInfo { block_info: BlockInfo { number, ..stale }, ..Default::default() };
+            let attributes =
+                OpAttributesWithParent::new(Default::default(), parent, BlockInfo::default(), true);
+            engine.enqueue(EngineTask::Consolidate(ConsolidateTask::new(
+                mock.client(),
+                cfg.clone(),
+                attributes,
+                true,
+            )));
+        }
+
+        let err = engine.drain().await.unwrap_err();
+        assert!(matches!(err, EngineTaskError::Stale(_)));
+        assert!(engine.is_empty());
+        assert_eq!(engine.state().safe_head(), L2BlockInfo::default());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_holds_back_syncing_build() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        mock.push_forkchoice_status(PayloadStatusEnum::Syncing);
+        let (sender, _) = tokio::sync::watch::channel(EngineState::default());
+        let mut engine = Engine::new(EngineState::default(), sender);
+        let attributes = OpAttributesWithParent::new(
+            Default::default(),
+            L2BlockInfo::default(),
+            BlockInfo::default(),
+            false,
+        );
+        let task = BuildTask::new(mock.client(), cfg, attributes, false, None);
+        engine.enqueue(EngineTask::BuildBlock(task.clone()));
+
+        engine.drain().await.unwrap();
+        assert!(engine.is_empty());
+        assert_eq!(engine.syncing_len(), 1);
+        let retry_at = engine.syncing_retry_at().unwrap();
+        assert_eq!(retry_at - tokio::time::Instant::now(), DEFAULT_SYNCING_INITIAL_BACKOFF);
+
+        // Draining before the backoff elapses does not resubmit the task.
+        engine.drain().await.unwrap();
+        assert_eq!(mock.methods(), ["engine_forkchoiceUpdatedV1"]);
+
+        // With buffering disabled, the task is kept in the queue instead.
+        let (sender, _) = tokio::sync::watch::channel(EngineState::default());
+        let mut engine = Engine::new(EngineState::default(), sender)
+            .with_syncing_buffer(SyncingBufferConfig { capacity: 0, ..Default::default() });
+        mock.push_forkchoice_status(PayloadStatusEnum::Syncing);
+        engine.enqueue(EngineTask::BuildBlock(task));
+        let err = engine.drain().await.unwrap_err();
+        assert!(matches!(err, EngineTaskError::Temporary(_)));
+        assert_eq!(engine.len(), 1);
+        assert_eq!(engine.syncing_len(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_holds_back_attributes_behind_syncing_consolidation() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        mock.push_forkchoice_status(PayloadStatusEnum::Syncing);
+        let (sender, _) = tokio::sync::watch::channel(EngineState::default());
+        let mut engine = Engine::new(EngineState::default(), sender);
+
+        // The second attributes build on the first ones, which are answered with `SYNCING`.
+        let first = OpAttributesWithParent::new(
+            Default::default(),
+            L2BlockInfo::default(),
+            BlockInfo::default(),
+            true,
+        );
+        let parent = L2BlockInfo {
+            block_info: BlockInfo { number: 1, hash: B256::repeat_byte(1), ..Default::default() },
+            ..Default::default()
+        };
+        let second =
+            OpAttributesWithParent::new(Default::default(), parent, BlockInfo::default(), true);
+        for attributes in [second, first] {
+            engine.enqueue(EngineTask::Consolidate(ConsolidateTask::new(
+                mock.client(),
+                cfg.clone(),
+                attributes,
+                true,
+            )));
+        }
+
+        // Both are held back, rather than the second being dropped as stale.
+        engine.drain().await.unwrap();
+        assert!(engine.is_empty());
+        assert_eq!(engine.syncing_len(), 2);
+        assert_eq!(mock.methods(), ["engine_forkchoiceUpdatedV1"]);
+    }
+
+    #[test]
+    fn test_state_transitions_recorded() {
+        let (sender, _) = tokio::sync::watch::channel(EngineState::default());
+        let mut engine = Engine::new(EngineState::default(), sender);
+        engine.skip_el_sync();
+
+        let transitions = engine.history().transitions();
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].cause, "skip-el-sync");
+        assert_eq!(transitions[0].changed, ["elSyncFinished"]);
+        assert!(transitions[0].state.el_sync_finished);
+    }
+
+    #[test]
+    fn test_restart_el_sync() {
+        let (sender, _) = tokio::sync::watch::channel(EngineState::default());
+        let mut engine = Engine::new(EngineState::default(), sender);
+        engine.skip_el_sync();
+        engine.restart_el_sync();
+
+        assert!(!engine.state().el_sync_finished);
+        let transitions = engine.history().transitions();
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[1].cause, "restart-el-sync");
+        assert_eq!(transitions[1].changed, ["elSyncFinished"]);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let mut engine = engine(&mock, Duration::ZERO, EngineTaskTimeouts::default());
+        engine.enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(mock.client())));
+
+        assert_eq!(engine.cancel(EngineTaskKind::Finalize), 0);
+        assert_eq!(engine.cancel(EngineTaskKind::ForkchoiceUpdate), 2);
+        assert!(engine.is_empty());
+    }
+}
@@ -5,3 +5,13 @@ pub use core::{Engine, EngineResetError};
 
 mod tasks;
 pub use tasks::*;
+
+mod syncing;
+pub(crate) use syncing::SyncingBuffer;
+pub use syncing::{
+    DEFAULT_SYNCING_BUFFER_CAPACITY, DEFAULT_SYNCING_INITIAL_BACKOFF, DEFAULT_SYNCING_MAX_BACKOFF,
+    SyncingBufferConfig,
+};
+
+mod timeout;
+pub use timeout::{EngineTaskTimeout, EngineTaskTimeouts, TaskTimeoutPolicy};
@@ -0,0 +1,233 @@
+//! The [`EngineTask`]s held back by the [`Engine`] while the execution layer is syncing.
+//!
+//! [`EngineTask`]: crate::EngineTask
+//! [`Engine`]: crate::Engine
+
+use crate::{EngineTask, EngineTaskKind, Metrics};
+use std::{collections::VecDeque, time::Duration};
+use tokio::time::Instant;
+
+/// The default number of tasks held back while the execution layer is syncing.
+pub const DEFAULT_SYNCING_BUFFER_CAPACITY: usize = 64;
+
+/// The default delay before the first resubmission of the tasks held back while the execution
+/// layer is syncing.
+pub const DEFAULT_SYNCING_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The default maximum delay between resubmissions of the tasks held back while the execution
+/// layer is syncing.
+pub const DEFAULT_SYNCING_MAX_BACKOFF: Duration = Duration::from_secs(16);
+
+/// The configuration of the buffering of payload attributes while the execution layer is syncing.
+///
+/// A [`EngineTask::BuildBlock`] or [`EngineTask::Consolidate`] task whose
+/// `engine_forkchoiceUpdated` call is answered with `SYNCING` is moved out of the task queue and
+/// held back, so that the rest of the queue, e.g. the unsafe payloads driving the sync, proceeds.
+/// Buffering is disabled if the capacity is `0`, in which case the task is kept at the head of
+/// the queue and retried on the next drain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncingBufferConfig {
+    /// The maximum number of tasks held back. Once full, the oldest task is dropped.
+    pub capacity: usize,
+    /// The delay before the first resubmission, doubled after each resubmission that is answered
+    /// with `SYNCING` again.
+    pub initial_backoff: Duration,
+    /// The maximum delay between resubmissions.
+    pub max_backoff: Duration,
+}
+
+impl Default for SyncingBufferConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_SYNCING_BUFFER_CAPACITY,
+            initial_backoff: DEFAULT_SYNCING_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_SYNCING_MAX_BACKOFF,
+        }
+    }
+}
+
+/// A bounded queue of the tasks held back while the execution layer is syncing.
+///
+/// Once the backoff elapses, the oldest task is resubmitted alone, to probe the execution layer.
+/// If it is answered with `SYNCING` again, it is held back once more and the backoff is doubled.
+/// Once a probe, or any other block building task, succeeds, the execution layer is synced, and
+/// the remaining tasks are resubmitted at once, in the order they were held back in.
+#[derive(Debug, Default)]
+pub(crate) struct SyncingBuffer {
+    /// The configuration of the buffer.
+    config: SyncingBufferConfig,
+    /// The tasks held back, oldest first.
+    tasks: VecDeque<EngineTask>,
+    /// The delay until the next probe.
+    backoff: Duration,
+    /// The time of the next probe, if tasks are held back.
+    retry_at: Option<Instant>,
+    /// Whether a probe was resubmitted, and has not completed yet.
+    probing: bool,
+}
+
+impl SyncingBuffer {
+    /// Creates a new, empty [`SyncingBuffer`].
+    pub(crate) fn new(config: SyncingBufferConfig) -> Self {
+        Self { config, backoff: config.initial_backoff, ..Default::default() }
+    }
+
+    /// Returns `true` if tasks answered with `SYNCING` are held back, rather than retried.
+    pub(crate) const fn is_enabled(&self) -> bool {
+        self.config.capacity > 0
+    }
+
+    /// Returns the number of tasks held back.
+    pub(crate) fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if no tasks are held back, and no probe is pending.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tasks.is_empty() && !self.probing
+    }
+
+    /// Returns the time of the next probe, if tasks are held back.
+    pub(crate) const fn retry_at(&self) -> Option<Instant> {
+        self.retry_at
+    }
+
+    /// Holds back the given task, answered with `SYNCING` at `now`. Returns the task dropped to
+    /// make room for it, if the buffer was full.
+    pub(crate) fn hold(&mut self, task: EngineTask, now: Instant) -> Option<EngineTask> {
+        let dropped = if self.probing {
+            // The probe is the oldest task, and is still answered with `SYNCING`.
+            self.probing = false;
+            self.backoff = self.backoff.saturating_mul(2).min(self.config.max_backoff);
+            self.tasks.push_front(task);
+            None
+        } else {
+            self.tasks.push_back(task);
+            (self.tasks.len() > self.config.capacity).then(|| self.tasks.pop_front()).flatten()
+        };
+        if dropped.is_some() {
+            kona_macros::inc!(counter, Metrics::SYNCING_TASKS_DROPPED);
+        }
+        self.retry_at = Some(now + self.backoff);
+        self.record();
+        dropped
+    }
+
+    /// Returns `true` if tasks are held back, and none of them is being probed. Tasks derived after
+    /// them must then be [deferred][Self::defer] rather than executed, since they build on them.
+    pub(crate) fn is_holding(&self) -> bool {
+        !self.tasks.is_empty() && !self.probing
+    }
+
+    /// Holds back the given task behind the tasks already held, without it having been answered
+    /// with `SYNCING`. Returns the task dropped to make room for it, if the buffer was full.
+    pub(crate) fn defer(&mut self, task: EngineTask) -> Option<EngineTask> {
+        self.tasks.push_back(task);
+        let dropped =
+            (self.tasks.len() > self.config.capacity).then(|| self.tasks.pop_front()).flatten();
+        if dropped.is_some() {
+            kona_macros::inc!(counter, Metrics::SYNCING_TASKS_DROPPED);
+        }
+        self.record();
+        dropped
+    }
+
+    /// Returns the oldest task to resubmit as a probe, if the backoff elapsed at `now`.
+    pub(crate) fn probe(&mut self, now: Instant) -> Option<EngineTask> {
+        if self.probing || self.retry_at.is_none_or(|at| at > now) {
+            return None;
+        }
+        let task = self.tasks.pop_front()?;
+        self.probing = true;
+        self.retry_at = None;
+        self.record();
+        Some(task)
+    }
+
+    /// Records that the execution layer built a block, i.e. that it is synced, returning the tasks
+    /// to resubmit, oldest first.
+    pub(crate) fn synced(&mut self) -> VecDeque<EngineTask> {
+        self.probing = false;
+        self.backoff = self.config.initial_backoff;
+        self.retry_at = None;
+        let tasks = std::mem::take(&mut self.tasks);
+        self.record();
+        tasks
+    }
+
+    /// Drops the tasks of the given kind held back. Returns the number of dropped tasks.
+    pub(crate) fn cancel(&mut self, kind: EngineTaskKind) -> usize {
+        let held = self.tasks.len();
+        self.tasks.retain(|task| task.kind() != kind);
+        if self.tasks.is_empty() {
+            self.retry_at = None;
+        }
+        self.record();
+        held - self.tasks.len()
+    }
+
+    /// Drops the tasks held back.
+    pub(crate) fn clear(&mut self) {
+        self.tasks.clear();
+        self.backoff = self.config.initial_backoff;
+        self.retry_at = None;
+        self.probing = false;
+        self.record();
+    }
+
+    /// Records the number of tasks held back.
+    fn record(&self) {
+        kona_macros::set!(gauge, Metrics::SYNCING_TASKS_BUFFERED, self.tasks.len() as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ForkchoiceTask, MockEngineClient};
+    use kona_genesis::RollupConfig;
+    use std::sync::Arc;
+
+    /// Returns a task to hold back.
+    fn task() -> EngineTask {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(mock.client()))
+    }
+
+    #[test]
+    fn test_syncing_buffer_backoff() {
+        let config = SyncingBufferConfig {
+            capacity: 2,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(3),
+        };
+        let mut buffer = SyncingBuffer::new(config);
+        let now = Instant::now();
+
+        assert!(buffer.hold(task(), now).is_none());
+        assert!(buffer.hold(task(), now).is_none());
+        assert!(buffer.hold(task(), now).is_some());
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.retry_at(), Some(now + Duration::from_secs(1)));
+
+        // The oldest task is resubmitted alone once the backoff elapses.
+        assert!(buffer.probe(now).is_none());
+        let probe = buffer.probe(now + Duration::from_secs(1)).unwrap();
+        assert_eq!(buffer.len(), 1);
+        assert!(buffer.probe(now + Duration::from_secs(10)).is_none());
+
+        // The backoff doubles while the probe is answered with `SYNCING`, up to the maximum.
+        buffer.hold(probe, now);
+        assert_eq!(buffer.retry_at(), Some(now + Duration::from_secs(2)));
+        let probe = buffer.probe(now + Duration::from_secs(2)).unwrap();
+        buffer.hold(probe, now);
+        assert_eq!(buffer.retry_at(), Some(now + Duration::from_secs(3)));
+
+        // Once synced, all tasks are resubmitted and the backoff starts over.
+        buffer.probe(now + Duration::from_secs(3)).unwrap();
+        assert_eq!(buffer.synced().len(), 1);
+        assert!(buffer.is_empty());
+        buffer.hold(task(), now);
+        assert_eq!(buffer.retry_at(), Some(now + Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,145 @@
+//! Latency budget of the [`BuildTask`].
+//!
+//! [`BuildTask`]: crate::BuildTask
+
+use crate::Metrics;
+use std::time::Duration;
+
+/// The default fraction of the block time that a [`BuildTask`] may take before it is reported as
+/// over budget.
+///
+/// [`BuildTask`]: crate::BuildTask
+pub const DEFAULT_BUILD_BUDGET_FRACTION: f64 = 0.5;
+
+/// The wall-clock timings of the phases of a [`BuildTask`].
+///
+/// [`BuildTask`]: crate::BuildTask
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuildTimings {
+    /// The `engine_forkchoiceUpdated` call that starts the build job.
+    pub fcu: Duration,
+    /// The `engine_getPayload` call that waits for the built payload.
+    pub get_payload: Duration,
+    /// The `engine_newPayload` call that imports the built payload.
+    pub new_payload: Duration,
+    /// The `engine_forkchoiceUpdated` call that canonicalizes the imported block.
+    pub canonicalize: Duration,
+}
+
+impl BuildTimings {
+    /// Returns the total duration of all phases.
+    pub const fn total(&self) -> Duration {
+        self.fcu
+            .saturating_add(self.get_payload)
+            .saturating_add(self.new_payload)
+            .saturating_add(self.canonicalize)
+    }
+
+    /// Returns the duration of each phase and the total, labeled as in the
+    /// [`Metrics::BUILD_PHASE_DURATION`] histogram.
+    pub const fn phases(&self) -> [(&'static str, Duration); 5] {
+        [
+            (Metrics::BUILD_PHASE_FCU, self.fcu),
+            (Metrics::BUILD_PHASE_GET_PAYLOAD, self.get_payload),
+            (Metrics::BUILD_PHASE_NEW_PAYLOAD, self.new_payload),
+            (Metrics::BUILD_PHASE_CANONICALIZE, self.canonicalize),
+            (Metrics::BUILD_PHASE_TOTAL, self.total()),
+        ]
+    }
+}
+
+/// The latency budget of a [`BuildTask`], as a fraction of the chain's block time.
+///
+/// The timings of each build are recorded against the block time, and a structured warning is
+/// logged when the total time to build, import and canonicalize a block exceeds the budget.
+///
+/// [`BuildTask`]: crate::BuildTask
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BuildBudget {
+    /// The fraction of the block time a build may take.
+    pub fraction: f64,
+}
+
+impl Default for BuildBudget {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUILD_BUDGET_FRACTION)
+    }
+}
+
+impl BuildBudget {
+    /// Creates a new [`BuildBudget`] that allows builds to take the given fraction of the block
+    /// time.
+    pub const fn new(fraction: f64) -> Self {
+        Self { fraction }
+    }
+
+    /// Returns the budget of a build for the given block time, in seconds.
+    pub fn budget(&self, block_time: u64) -> Duration {
+        Duration::from_secs(block_time).mul_f64(self.fraction.max(0.0))
+    }
+
+    /// Returns `true` if the given timings exceed the budget for the given block time.
+    pub fn is_exceeded(&self, timings: &BuildTimings, block_time: u64) -> bool {
+        timings.total() > self.budget(block_time)
+    }
+
+    /// Records the given timings of the build of block `l2_number` against the budget, and warns
+    /// if they exceed it.
+    pub(crate) fn record(&self, timings: &BuildTimings, block_time: u64, l2_number: u64) {
+        let block_time_secs = block_time as f64;
+        for (phase, duration) in timings.phases() {
+            kona_macros::record!(
+                histogram,
+                Metrics::BUILD_PHASE_DURATION,
+                "phase",
+                phase,
+                duration.as_secs_f64()
+            );
+            if block_time > 0 {
+                kona_macros::record!(
+                    histogram,
+                    Metrics::BUILD_PHASE_BLOCK_TIME_RATIO,
+                    "phase",
+                    phase,
+                    duration.as_secs_f64() / block_time_secs
+                );
+            }
+        }
+
+        if self.is_exceeded(timings, block_time) {
+            kona_macros::inc!(counter, Metrics::BUILD_BUDGET_EXCEEDED);
+            warn!(
+                target: "engine_builder",
+                l2_number,
+                total = ?timings.total(),
+                budget = ?self.budget(block_time),
+                block_time,
+                fcu = ?timings.fcu,
+                get_payload = ?timings.get_payload,
+                new_payload = ?timings.new_payload,
+                canonicalize = ?timings.canonicalize,
+                "Block build exceeded its latency budget"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_budget_exceeded() {
+        let budget = BuildBudget::new(0.5);
+        let timings = BuildTimings {
+            fcu: Duration::from_millis(100),
+            get_payload: Duration::from_millis(500),
+            new_payload: Duration::from_millis(300),
+            canonicalize: Duration::from_millis(50),
+        };
+        assert_eq!(timings.total(), Duration::from_millis(950));
+        assert_eq!(budget.budget(2), Duration::from_secs(1));
+        assert!(!budget.is_exceeded(&timings, 2));
+        assert!(budget.is_exceeded(&timings, 1));
+    }
+}
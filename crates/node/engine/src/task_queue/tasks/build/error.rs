@@ -1,6 +1,6 @@
 //! Contains error types for the [crate::ForkchoiceTask].
 
-use crate::EngineTaskError;
+use crate::{AttributesPreflightError, EngineTaskError};
 use alloy_rpc_types_engine::PayloadStatusEnum;
 use alloy_transport::{RpcError, TransportErrorKind};
 use kona_protocol::FromBlockError;
@@ -17,6 +17,10 @@ pub enum BuildTaskError {
     /// The engine is syncing.
     #[error("Attempting to update forkchoice state while EL syncing")]
     EngineSyncing,
+    /// The attributes do not match the chain configuration of the execution layer, which would
+    /// reject them.
+    #[error("Attributes rejected before the build: {0}")]
+    Preflight(#[from] AttributesPreflightError),
     /// The forkchoice update call to the engine api failed.
     #[error(transparent)]
     ForkchoiceUpdateFailed(RpcError<TransportErrorKind>),
@@ -63,7 +67,8 @@ impl From<BuildTaskError> for EngineTaskError {
     fn from(value: BuildTaskError) -> Self {
         match value {
             BuildTaskError::NoForkchoiceUpdateNeeded => Self::Temporary(Box::new(value)),
-            BuildTaskError::EngineSyncing => Self::Temporary(Box::new(value)),
+            BuildTaskError::EngineSyncing => Self::Syncing(Box::new(value)),
+            BuildTaskError::Preflight(_) => Self::Temporary(Box::new(value)),
             BuildTaskError::ForkchoiceUpdateFailed(_) => Self::Temporary(Box::new(value)),
             BuildTaskError::MissingPayloadId => Self::Temporary(Box::new(value)),
             BuildTaskError::UnexpectedPayloadStatus(_) => Self::Temporary(Box::new(value)),
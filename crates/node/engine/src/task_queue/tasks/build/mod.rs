@@ -3,5 +3,11 @@
 mod task;
 pub use task::BuildTask;
 
+mod budget;
+pub use budget::{BuildBudget, BuildTimings, DEFAULT_BUILD_BUDGET_FRACTION};
+
+mod stats;
+pub use stats::PayloadStats;
+
 mod error;
 pub use error::BuildTaskError;
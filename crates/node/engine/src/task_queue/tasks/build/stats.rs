@@ -0,0 +1,130 @@
+//! Size and gas statistics of the payloads built by the [`BuildTask`].
+//!
+//! [`BuildTask`]: crate::BuildTask
+
+use crate::Metrics;
+use op_alloy_consensus::OpTxType;
+use op_alloy_rpc_types_engine::OpExecutionPayload;
+
+/// The maximum number of bytes of data a blob can hold, once encoded.
+const BLOB_DATA_SIZE: usize = (4 * 31 + 3) * 1024 - 4;
+
+/// The size and gas statistics of a built payload.
+///
+/// The batch data size is an upper bound of the data the batcher submits to L1 for the block:
+/// the encoded user transactions, before compression. Deposits are derived from L1, and are not
+/// batched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadStats {
+    /// The number of deposit transactions.
+    pub deposits: usize,
+    /// The number of user transactions.
+    pub user_transactions: usize,
+    /// The gas used by the block.
+    pub gas_used: u64,
+    /// The gas limit of the block.
+    pub gas_limit: u64,
+    /// The total size of the encoded user transactions, in bytes.
+    pub batch_data_size: usize,
+}
+
+impl PayloadStats {
+    /// Computes the statistics of the given payload.
+    pub fn from_payload(payload: &OpExecutionPayload) -> Self {
+        let payload = payload.as_v1();
+        let (deposits, user): (Vec<_>, Vec<_>) = payload
+            .transactions
+            .iter()
+            .partition(|tx| tx.first() == Some(&(OpTxType::Deposit as u8)));
+        Self {
+            deposits: deposits.len(),
+            user_transactions: user.len(),
+            gas_used: payload.gas_used,
+            gas_limit: payload.gas_limit,
+            batch_data_size: user.iter().map(|tx| tx.len()).sum(),
+        }
+    }
+
+    /// Returns the total number of transactions.
+    pub const fn transactions(&self) -> usize {
+        self.deposits + self.user_transactions
+    }
+
+    /// Returns the fraction of the gas limit used by the block.
+    pub fn gas_utilization(&self) -> f64 {
+        if self.gas_limit == 0 {
+            return 0.0;
+        }
+        self.gas_used as f64 / self.gas_limit as f64
+    }
+
+    /// Returns the number of blobs the batch data of the block fills, if submitted uncompressed.
+    pub const fn estimated_blobs(&self) -> usize {
+        self.batch_data_size.div_ceil(BLOB_DATA_SIZE)
+    }
+
+    /// Records the statistics as metrics.
+    pub(crate) fn record(&self) {
+        kona_macros::record!(
+            histogram,
+            Metrics::PAYLOAD_TRANSACTIONS,
+            "kind",
+            Metrics::PAYLOAD_DEPOSIT_TRANSACTIONS,
+            self.deposits as f64
+        );
+        kona_macros::record!(
+            histogram,
+            Metrics::PAYLOAD_TRANSACTIONS,
+            "kind",
+            Metrics::PAYLOAD_USER_TRANSACTIONS,
+            self.user_transactions as f64
+        );
+        kona_macros::record!(histogram, Metrics::PAYLOAD_GAS_USED, self.gas_used as f64);
+        kona_macros::record!(histogram, Metrics::PAYLOAD_GAS_UTILIZATION, self.gas_utilization());
+        kona_macros::record!(
+            histogram,
+            Metrics::PAYLOAD_BATCH_DATA_SIZE,
+            self.batch_data_size as f64
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{B256, Bloom, Bytes, U256};
+    use alloy_rpc_types_engine::ExecutionPayloadV1;
+
+    #[test]
+    fn test_payload_stats() {
+        let payload = OpExecutionPayload::V1(ExecutionPayloadV1 {
+            parent_hash: B256::ZERO,
+            fee_recipient: Default::default(),
+            state_root: B256::ZERO,
+            receipts_root: B256::ZERO,
+            logs_bloom: Bloom::ZERO,
+            prev_randao: B256::ZERO,
+            block_number: 1,
+            gas_limit: 30_000_000,
+            gas_used: 7_500_000,
+            timestamp: 2,
+            extra_data: Bytes::new(),
+            base_fee_per_gas: U256::from(1),
+            block_hash: B256::ZERO,
+            transactions: vec![
+                Bytes::from(vec![OpTxType::Deposit as u8, 0, 0]),
+                Bytes::from(vec![0x02; 100_000]),
+                Bytes::from(vec![0x02; 50_000]),
+            ],
+        });
+
+        let stats = PayloadStats::from_payload(&payload);
+        assert_eq!(stats.deposits, 1);
+        assert_eq!(stats.user_transactions, 2);
+        assert_eq!(stats.transactions(), 3);
+        assert_eq!(stats.batch_data_size, 150_000);
+        assert_eq!(stats.estimated_blobs(), 2);
+        assert_eq!(stats.gas_utilization(), 0.25);
+        assert_eq!(PayloadStats::default().gas_utilization(), 0.0);
+    }
+}
@@ -1,13 +1,14 @@
 //! A task for building a new block and importing it.
 
-use super::BuildTaskError;
+use super::{BuildBudget, BuildTaskError, BuildTimings, DEFAULT_BUILD_BUDGET_FRACTION};
 use crate::{
     EngineClient, EngineForkchoiceVersion, EngineGetPayloadVersion, EngineState, EngineTaskError,
-    EngineTaskExt, ForkchoiceTask, Metrics,
+    EngineTaskExt, ForkchoiceTask, Metrics, PayloadStats,
 };
 use alloy_provider::ext::EngineApi;
 use alloy_rpc_types_engine::{
-    ExecutionPayloadFieldV2, ExecutionPayloadInputV2, ForkchoiceState, PayloadId, PayloadStatusEnum,
+    ExecutionPayloadFieldV2, ExecutionPayloadInputV2, ExecutionPayloadV2, ForkchoiceState,
+    PayloadId, PayloadStatusEnum,
 };
 use alloy_transport::RpcError;
 use async_trait::async_trait;
@@ -17,6 +18,7 @@ use op_alloy_provider::ext::engine::OpEngineApi;
 use op_alloy_rpc_types_engine::{OpExecutionPayload, OpExecutionPayloadEnvelope};
 use std::{sync::Arc, time::Instant};
 use tokio::sync::mpsc;
+use tracing::Instrument;
 
 /// The [`BuildTask`] is responsible for building new blocks and importing them via the engine API.
 #[derive(Debug, Clone)]
@@ -32,6 +34,8 @@ pub struct BuildTask {
     /// An optional channel to send the built [`OpExecutionPayloadEnvelope`] to, after the block
     /// has been built, imported, and canonicalized.
     pub payload_tx: Option<mpsc::Sender<OpExecutionPayloadEnvelope>>,
+    /// The [`BuildBudget`] that the timings of the build are recorded against.
+    pub budget: BuildBudget,
 }
 
 impl BuildTask {
@@ -43,7 +47,20 @@ impl BuildTask {
         is_attributes_derived: bool,
         payload_tx: Option<mpsc::Sender<OpExecutionPayloadEnvelope>>,
     ) -> Self {
-        Self { engine, cfg, attributes, is_attributes_derived, payload_tx }
+        Self {
+            engine,
+            cfg,
+            attributes,
+            is_attributes_derived,
+            payload_tx,
+            budget: BuildBudget::new(DEFAULT_BUILD_BUDGET_FRACTION),
+        }
+    }
+
+    /// Sets the [`BuildBudget`] that the timings of the build are recorded against.
+    pub const fn with_budget(mut self, budget: BuildBudget) -> Self {
+        self.budget = budget;
+        self
     }
 
     /// Starts the block building process by sending an initial `engine_forkchoiceUpdate` call with
@@ -61,8 +78,10 @@ impl BuildTask {
     /// If the forkchoice update fails, the external actor is notified of the failure.
     ///
     /// ### Syncing (`SYNCING`)
-    /// If the EL is syncing, the payload attributes are buffered and the function returns early.
-    /// This is a temporary state, and the function should be called again later.
+    /// If the EL is syncing, the function returns early, and the [`Engine`] holds back the task
+    /// until the EL is synced.
+    ///
+    /// [`Engine`]: crate::Engine
     async fn start_build(
         &self,
         engine_client: &EngineClient,
@@ -153,6 +172,7 @@ impl BuildTask {
         engine: &EngineClient,
         payload_id: PayloadId,
         payload_attrs: OpAttributesWithParent,
+        timings: &mut BuildTimings,
     ) -> Result<(OpExecutionPayloadEnvelope, L2BlockInfo), BuildTaskError> {
         let payload_timestamp = payload_attrs.inner().payload_attributes.timestamp;
 
@@ -164,17 +184,24 @@ impl BuildTask {
         );
 
         let get_payload_version = EngineGetPayloadVersion::from_cfg(cfg, payload_timestamp);
+        let start = Instant::now();
         let (payload_envelope, response) = match get_payload_version {
             EngineGetPayloadVersion::V4 => {
-                let payload = engine.get_payload_v4(payload_id).await.map_err(|e| {
-                    error!(target: "engine_builder", "Payload fetch failed: {e}");
-                    BuildTaskError::GetPayloadFailed(e)
-                })?;
+                let payload = engine
+                    .get_payload_v4(payload_id)
+                    .instrument(info_span!(target: "engine_builder", "engine_getPayload"))
+                    .await
+                    .map_err(|e| {
+                        error!(target: "engine_builder", "Payload fetch failed: {e}");
+                        BuildTaskError::GetPayloadFailed(e)
+                    })?;
+                timings.get_payload = start.elapsed();
                 let response = engine
                     .new_payload_v4(
                         payload.execution_payload.clone(),
                         payload.parent_beacon_block_root,
                     )
+                    .instrument(info_span!(target: "engine_builder", "engine_newPayload"))
                     .await
                     .map_err(|e| {
                         error!(target: "engine_builder", "Payload import failed: {e}");
@@ -190,15 +217,21 @@ impl BuildTask {
                 )
             }
             EngineGetPayloadVersion::V3 => {
-                let payload = engine.get_payload_v3(payload_id).await.map_err(|e| {
-                    error!(target: "engine_builder", "Payload fetch failed: {e}");
-                    BuildTaskError::GetPayloadFailed(e)
-                })?;
+                let payload = engine
+                    .get_payload_v3(payload_id)
+                    .instrument(info_span!(target: "engine_builder", "engine_getPayload"))
+                    .await
+                    .map_err(|e| {
+                        error!(target: "engine_builder", "Payload fetch failed: {e}");
+                        BuildTaskError::GetPayloadFailed(e)
+                    })?;
+                timings.get_payload = start.elapsed();
                 let response = engine
                     .new_payload_v3(
                         payload.execution_payload.clone(),
                         payload.parent_beacon_block_root,
                     )
+                    .instrument(info_span!(target: "engine_builder", "engine_newPayload"))
                     .await
                     .map_err(|e| {
                         error!(target: "engine_builder", "Payload import failed: {e}");
@@ -214,20 +247,43 @@ impl BuildTask {
                 )
             }
             EngineGetPayloadVersion::V2 => {
-                let payload = engine.get_payload_v2(payload_id).await.map_err(|e| {
-                    error!(target: "engine_builder", "Payload fetch failed: {e}");
-                    BuildTaskError::GetPayloadFailed(e)
-                })?;
-                match payload.execution_payload {
+                let payload = engine
+                    .get_payload_v2(payload_id)
+                    .instrument(info_span!(target: "engine_builder", "engine_getPayload"))
+                    .await
+                    .map_err(|e| {
+                        error!(target: "engine_builder", "Payload fetch failed: {e}");
+                        BuildTaskError::GetPayloadFailed(e)
+                    })?;
+                timings.get_payload = start.elapsed();
+                // The V2 payload field is untagged, so a V2 payload without withdrawals is
+                // deserialized as V1. Canyon payloads must still be imported and hashed with their
+                // empty withdrawals.
+                let execution_payload = match payload.execution_payload {
+                    ExecutionPayloadFieldV2::V1(payload)
+                        if cfg.is_canyon_active(payload.timestamp) =>
+                    {
+                        ExecutionPayloadFieldV2::V2(ExecutionPayloadV2 {
+                            payload_inner: payload,
+                            withdrawals: vec![],
+                        })
+                    }
+                    payload => payload,
+                };
+                match execution_payload {
                     ExecutionPayloadFieldV2::V2(payload) => {
                         let payload_input = ExecutionPayloadInputV2 {
                             execution_payload: payload.payload_inner.clone(),
                             withdrawals: Some(payload.withdrawals.clone()),
                         };
-                        let response = engine.new_payload_v2(payload_input).await.map_err(|e| {
-                            error!(target: "engine_builder", "Payload import failed: {e}");
-                            BuildTaskError::NewPayloadFailed(e)
-                        })?;
+                        let response = engine
+                            .new_payload_v2(payload_input)
+                            .instrument(info_span!(target: "engine_builder", "engine_newPayload"))
+                            .await
+                            .map_err(|e| {
+                                error!(target: "engine_builder", "Payload import failed: {e}");
+                                BuildTaskError::NewPayloadFailed(e)
+                            })?;
 
                         (
                             OpExecutionPayloadEnvelope {
@@ -238,8 +294,11 @@ impl BuildTask {
                         )
                     }
                     ExecutionPayloadFieldV2::V1(payload) => {
-                        let response =
-                            engine.new_payload_v1(payload.clone()).await.map_err(|e| {
+                        let response = engine
+                            .new_payload_v1(payload.clone())
+                            .instrument(info_span!(target: "engine_builder", "engine_newPayload"))
+                            .await
+                            .map_err(|e| {
                                 error!(target: "engine_builder", "Payload import failed: {e}");
                                 BuildTaskError::NewPayloadFailed(e)
                             })?;
@@ -255,6 +314,13 @@ impl BuildTask {
                 }
             }
         };
+        timings.new_payload = start.elapsed().saturating_sub(timings.get_payload);
+        engine.shadow_get_payload(
+            payload_id,
+            payload_timestamp,
+            payload_envelope.payload.block_hash(),
+        );
+        engine.shadow_new_payload(&payload_envelope, &response.status);
 
         match response.status {
             PayloadStatusEnum::Valid | PayloadStatusEnum::Syncing => {
@@ -285,11 +351,13 @@ impl BuildTask {
                         self.is_attributes_derived,
                         self.payload_tx.clone(),
                     )
+                    .with_budget(self.budget)
                     .execute(state)
                     .await
                     {
                         Ok(_) => {
-                            info!(target: "engine_builder", "Successfully imported deposits-only payload")
+                            info!(target: "engine_builder", "Successfully imported deposits-only payload");
+                            state.set_deposits_only_head(state.unsafe_head());
                         }
                         Err(_) => return Err(BuildTaskError::DepositOnlyPayloadReattemptFailed),
                     }
@@ -312,6 +380,20 @@ impl BuildTask {
 #[async_trait]
 impl EngineTaskExt for BuildTask {
     async fn execute(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
+        let span = info_span!(
+            target: "engine_builder",
+            "build",
+            l2_number = self.attributes.parent.block_info.number + 1,
+            correlation_id = %self.attributes.correlation_id(),
+            derived = self.is_attributes_derived,
+        );
+        self.build(state).instrument(span).await
+    }
+}
+
+impl BuildTask {
+    /// Builds, imports and canonicalizes the block. See [`EngineTaskExt::execute`].
+    async fn build(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
         // Sanity check if the head is behind the finalized head. If it is, this is a critical
         // error.
         if state.unsafe_head().block_info.number < state.finalized_head().block_info.number {
@@ -322,6 +404,15 @@ impl EngineTaskExt for BuildTask {
             .into());
         }
 
+        // Validate the attributes against the chain configuration of the EL, if loaded, rather
+        // than letting the EL reject them with an opaque `INVALID` status.
+        if let Some(el_config) = self.engine.el_chain_config() {
+            el_config.validate(&self.cfg, self.attributes.inner()).map_err(|err| {
+                error!(target: "engine_builder", %err, "Attributes do not match the EL chain config");
+                BuildTaskError::from(err)
+            })?;
+        }
+
         // Send the forkchoice update through the input, with the current engine state and the
         // payload attributes for the block building job.
         let mut forkchoice = state.create_forkchoice_state();
@@ -329,13 +420,15 @@ impl EngineTaskExt for BuildTask {
 
         // Start the build by sending an FCU call with the current forkchoice and the input
         // payload attributes.
+        let mut timings = BuildTimings::default();
         let fcu_start_time = Instant::now();
-        let payload_id =
-            self.start_build(&self.engine, forkchoice, self.attributes.clone()).await?;
-        let fcu_duration = fcu_start_time.elapsed();
+        let payload_id = self
+            .start_build(&self.engine, forkchoice, self.attributes.clone())
+            .instrument(info_span!(target: "engine_builder", "engine_forkchoiceUpdated"))
+            .await?;
+        timings.fcu = fcu_start_time.elapsed();
 
         // Fetch the payload from the EL and import it into the engine.
-        let block_import_start_time = Instant::now();
         let (new_payload, new_block_ref) = self
             .fetch_and_import_payload(
                 state,
@@ -343,9 +436,9 @@ impl EngineTaskExt for BuildTask {
                 &self.engine,
                 payload_id,
                 self.attributes.clone(),
+                &mut timings,
             )
             .await?;
-        let block_import_duration = block_import_start_time.elapsed();
 
         // Update the engine state.
         state.set_unsafe_head(new_block_ref);
@@ -356,7 +449,14 @@ impl EngineTaskExt for BuildTask {
         }
 
         // Send a FCU to canonicalize the imported block.
-        ForkchoiceTask::new(Arc::clone(&self.engine)).execute(state).await?;
+        let canonicalize_start_time = Instant::now();
+        ForkchoiceTask::new(Arc::clone(&self.engine))
+            .execute(state)
+            .instrument(info_span!(target: "engine_builder", "canonicalize"))
+            .await?;
+        timings.canonicalize = canonicalize_start_time.elapsed();
+
+        let stats = PayloadStats::from_payload(&new_payload.payload);
 
         // If a channel was provided, send the built payload envelope to it.
         if let Some(tx) = &self.payload_tx {
@@ -367,15 +467,240 @@ impl EngineTaskExt for BuildTask {
             target: "engine_builder",
             l2_number = new_block_ref.block_info.number,
             l2_time = new_block_ref.block_info.timestamp,
-            fcu_duration = ?fcu_duration,
-            block_import_duration = ?block_import_duration,
+            fcu_duration = ?timings.fcu,
+            get_payload_duration = ?timings.get_payload,
+            new_payload_duration = ?timings.new_payload,
+            canonicalize_duration = ?timings.canonicalize,
+            transactions = stats.transactions(),
+            deposits = stats.deposits,
+            gas_used = stats.gas_used,
+            gas_utilization = stats.gas_utilization(),
+            batch_data_size = stats.batch_data_size,
+            estimated_blobs = stats.estimated_blobs(),
             "Built and imported new {} block",
             if self.is_attributes_derived { "safe" } else { "unsafe" },
         );
 
         // Update metrics.
         kona_macros::inc!(counter, Metrics::ENGINE_TASK_COUNT, Metrics::BUILD_TASK_LABEL);
+        self.budget.record(&timings, self.cfg.block_time, new_block_ref.block_info.number);
+        stats.record();
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ElChainConfig, MockEngineClient};
+    use alloy_consensus::{Header, Signed, TxLegacy};
+    use alloy_eips::Encodable2718;
+    use alloy_primitives::{B256, Bytes, Signature, U256};
+    use alloy_rpc_types_engine::{
+        ExecutionPayloadEnvelopeV2, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3,
+        PayloadAttributes,
+    };
+    use kona_genesis::SystemConfig;
+    use kona_protocol::{BlockInfo, L1BlockInfoTx};
+    use op_alloy_consensus::{OpBlock, OpTxEnvelope};
+    use op_alloy_rpc_types_engine::{OpExecutionPayloadEnvelopeV3, OpPayloadAttributes};
+
+    /// Returns the attributes of the first block after genesis.
+    fn attributes(cfg: &RollupConfig) -> OpAttributesWithParent {
+        let timestamp = cfg.genesis.l2_time + cfg.block_time;
+        let (_, deposit) = L1BlockInfoTx::try_new_with_deposit_tx(
+            cfg,
+            &SystemConfig::default(),
+            1,
+            &Header::default(),
+            timestamp,
+        )
+        .unwrap();
+        let attributes = OpPayloadAttributes {
+            payload_attributes: PayloadAttributes {
+                timestamp,
+                prev_randao: B256::ZERO,
+                suggested_fee_recipient: Default::default(),
+                withdrawals: None,
+                parent_beacon_block_root: None,
+            },
+            transactions: Some(vec![OpTxEnvelope::Deposit(deposit).encoded_2718().into()]),
+            no_tx_pool: Some(true),
+            gas_limit: Some(30_000_000),
+            eip_1559_params: None,
+        };
+        OpAttributesWithParent::new(attributes, L2BlockInfo::default(), BlockInfo::default(), true)
+    }
+
+    /// Returns the payload built from the given attributes.
+    fn payload(attributes: &OpAttributesWithParent) -> ExecutionPayloadV1 {
+        let inner = attributes.inner();
+        let mut payload = ExecutionPayloadV1 {
+            parent_hash: attributes.parent.block_info.hash,
+            fee_recipient: inner.payload_attributes.suggested_fee_recipient,
+            state_root: B256::ZERO,
+            receipts_root: B256::ZERO,
+            logs_bloom: Default::default(),
+            prev_randao: inner.payload_attributes.prev_randao,
+            block_number: attributes.block_number(),
+            gas_limit: inner.gas_limit.unwrap_or_default(),
+            gas_used: 0,
+            timestamp: inner.payload_attributes.timestamp,
+            extra_data: Bytes::new(),
+            base_fee_per_gas: U256::from(1),
+            block_hash: B256::ZERO,
+            transactions: inner.transactions.clone().unwrap_or_default(),
+        };
+        let block: OpBlock = payload.clone().try_into_block().unwrap();
+        payload.block_hash = block.header.hash_slow();
+        payload
+    }
+
+    #[tokio::test]
+    async fn test_build_retries_while_engine_syncing() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        let attributes = attributes(&cfg);
+        let payload = payload(&attributes);
+        mock.push_forkchoice_status(PayloadStatusEnum::Syncing);
+        mock.push_payload(&ExecutionPayloadEnvelopeV2 {
+            execution_payload: ExecutionPayloadFieldV2::V1(payload.clone()),
+            block_value: U256::ZERO,
+        });
+
+        let task = BuildTask::new(mock.client(), cfg, attributes, true, None);
+        let mut state = EngineState::default();
+        let err = task.execute(&mut state).await.unwrap_err();
+        assert!(matches!(err, EngineTaskError::Syncing(_)));
+        assert_eq!(state, EngineState::default());
+
+        task.execute(&mut state).await.unwrap();
+        assert_eq!(state.unsafe_head().block_info.hash, payload.block_hash);
+        assert_eq!(state.safe_head().block_info.hash, payload.block_hash);
+        assert_eq!(
+            mock.methods(),
+            [
+                "engine_forkchoiceUpdatedV1",
+                "engine_forkchoiceUpdatedV1",
+                "engine_getPayloadV2",
+                "engine_newPayloadV1",
+                "engine_forkchoiceUpdatedV3",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_imports_canyon_payload_without_withdrawals_as_v2() {
+        let mut cfg = RollupConfig::default();
+        cfg.hardforks.canyon_time = Some(0);
+        let cfg = Arc::new(cfg);
+        let mock = MockEngineClient::new(cfg.clone());
+        let mut attributes = attributes(&cfg);
+        attributes.inner.payload_attributes.withdrawals = Some(vec![]);
+        let payload = payload(&attributes);
+        // A V2 payload with empty withdrawals is served as a V1 payload.
+        mock.push_payload(&ExecutionPayloadEnvelopeV2 {
+            execution_payload: ExecutionPayloadFieldV2::V1(payload.clone()),
+            block_value: U256::ZERO,
+        });
+
+        let task = BuildTask::new(mock.client(), cfg, attributes, true, None);
+        let mut state = EngineState::default();
+        task.execute(&mut state).await.unwrap();
+        assert_eq!(state.unsafe_head().block_info.hash, payload.block_hash);
+
+        // The payload is imported with its empty withdrawals.
+        let new_payload = mock
+            .calls()
+            .into_iter()
+            .find(|call| call.method.starts_with("engine_newPayload"))
+            .unwrap();
+        assert_eq!(new_payload.method, "engine_newPayloadV2");
+        assert_eq!(new_payload.params[0]["withdrawals"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_build_rejects_attributes_mismatching_el_chain_config() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        // The EL activates Ecotone at genesis, unlike the rollup config.
+        let el_config = ElChainConfig { ecotone_time: Some(0), ..Default::default() };
+        mock.client().el_chain_config.set(el_config).unwrap();
+
+        let task = BuildTask::new(mock.client(), cfg.clone(), attributes(&cfg), true, None);
+        let mut state = EngineState::default();
+        let err = task.execute(&mut state).await.unwrap_err();
+        assert!(matches!(err, EngineTaskError::Temporary(_)));
+        assert!(err.to_string().contains("Ecotone activation differs at timestamp"), "{err}");
+        // The attributes were rejected before reaching the EL.
+        assert!(mock.methods().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_build_invalid_deposits_only_payload() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        let attributes = attributes(&cfg);
+        let payload = payload(&attributes);
+        mock.invalidate(payload.block_hash);
+        mock.push_payload(&ExecutionPayloadEnvelopeV2 {
+            execution_payload: ExecutionPayloadFieldV2::V1(payload),
+            block_value: U256::ZERO,
+        });
+
+        let task = BuildTask::new(mock.client(), cfg, attributes, true, None);
+        let mut state = EngineState::default();
+        let err = task.execute(&mut state).await.unwrap_err();
+        assert!(matches!(err, EngineTaskError::Critical(_)));
+        assert_eq!(
+            err.to_string(),
+            EngineTaskError::from(BuildTaskError::DepositOnlyPayloadFailed).to_string()
+        );
+        assert_eq!(state, EngineState::default());
+    }
+
+    #[tokio::test]
+    async fn test_build_invalid_payload_falls_back_to_deposits_only() {
+        let mut cfg = RollupConfig::default();
+        cfg.hardforks.holocene_time = Some(0);
+        let cfg = Arc::new(cfg);
+        let mock = MockEngineClient::new(cfg.clone());
+
+        // A derived payload of a user transaction, that the EL finds invalid.
+        let mut attributes = attributes(&cfg);
+        let deposits_only = payload(&attributes);
+        let tx = TxLegacy { chain_id: Some(10), gas_limit: 21_000, ..Default::default() };
+        let tx = OpTxEnvelope::Legacy(Signed::new_unchecked(
+            tx,
+            Signature::test_signature(),
+            B256::ZERO,
+        ));
+        attributes.inner.transactions = Some(vec![tx.encoded_2718().into()]);
+        let invalid = payload(&attributes);
+        mock.invalidate(invalid.block_hash);
+        for payload in [invalid, deposits_only] {
+            mock.push_payload(&OpExecutionPayloadEnvelopeV3 {
+                execution_payload: ExecutionPayloadV3 {
+                    payload_inner: ExecutionPayloadV2 {
+                        payload_inner: payload,
+                        withdrawals: vec![],
+                    },
+                    blob_gas_used: 0,
+                    excess_blob_gas: 0,
+                },
+                block_value: U256::ZERO,
+                blobs_bundle: Default::default(),
+                should_override_builder: false,
+                parent_beacon_block_root: B256::ZERO,
+            });
+        }
+
+        let task = BuildTask::new(mock.client(), cfg, attributes, true, None);
+        let mut state = EngineState::default();
+        let err = task.execute(&mut state).await.unwrap_err();
+        assert!(matches!(err, EngineTaskError::Flush(_)), "{err:?}");
+        assert_eq!(state.safe_head().block_info.number, 1);
+        assert_eq!(state.deposits_only_head(), Some(state.safe_head()));
+    }
+}
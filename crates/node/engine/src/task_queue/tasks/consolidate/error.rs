@@ -1,6 +1,7 @@
 //! Contains error types for the [`crate::ConsolidateTask`].
 
 use crate::EngineTaskError;
+use alloy_eips::BlockNumHash;
 use thiserror::Error;
 
 /// An error that occurs when running the [`crate::ConsolidateTask`].
@@ -12,6 +13,14 @@ pub enum ConsolidateTaskError {
     /// Failed to fetch the unsafe L2 block.
     #[error("Failed to fetch the unsafe L2 block")]
     FailedToFetchUnsafeL2Block,
+    /// The parent of the derived attributes is no longer the local safe head.
+    #[error("Attributes parent {parent:?} is not the local safe head {safe_head:?}")]
+    StaleParent {
+        /// The parent of the attributes.
+        parent: BlockNumHash,
+        /// The local safe head.
+        safe_head: BlockNumHash,
+    },
 }
 
 impl From<ConsolidateTaskError> for EngineTaskError {
@@ -19,6 +28,7 @@ impl From<ConsolidateTaskError> for EngineTaskError {
         match value {
             ConsolidateTaskError::MissingUnsafeL2Block(_) => Self::Reset(Box::new(value)),
             ConsolidateTaskError::FailedToFetchUnsafeL2Block => Self::Temporary(Box::new(value)),
+            ConsolidateTaskError::StaleParent { .. } => Self::Stale(Box::new(value)),
         }
     }
 }
@@ -1,13 +1,14 @@
 //! A task to consolidate the engine state.
 
 use crate::{
-    BuildTask, ConsolidateTaskError, EngineClient, EngineState, EngineTaskError, EngineTaskExt,
-    ForkchoiceTask, Metrics,
+    BuildBudget, BuildTask, ConsolidateTaskError, DEFAULT_BUILD_BUDGET_FRACTION, EngineClient,
+    EngineState, EngineTaskError, EngineTaskExt, ForkchoiceTask, Metrics,
 };
 use async_trait::async_trait;
 use kona_genesis::RollupConfig;
 use kona_protocol::{L2BlockInfo, OpAttributesWithParent};
 use std::{sync::Arc, time::Instant};
+use tracing::Instrument;
 
 /// The [`ConsolidateTask`] attempts to consolidate the engine state
 /// using the specified payload attributes and the oldest unsafe head.
@@ -23,6 +24,8 @@ pub struct ConsolidateTask {
     pub attributes: OpAttributesWithParent,
     /// Whether or not the payload was derived, or created by the sequencer.
     pub is_attributes_derived: bool,
+    /// The [`BuildBudget`] of the [`BuildTask`] run if consolidation fails.
+    pub build_budget: BuildBudget,
 }
 
 impl ConsolidateTask {
@@ -33,7 +36,19 @@ impl ConsolidateTask {
         attributes: OpAttributesWithParent,
         is_attributes_derived: bool,
     ) -> Self {
-        Self { client, cfg: config, attributes, is_attributes_derived }
+        Self {
+            client,
+            cfg: config,
+            attributes,
+            is_attributes_derived,
+            build_budget: BuildBudget::new(DEFAULT_BUILD_BUDGET_FRACTION),
+        }
+    }
+
+    /// Sets the [`BuildBudget`] of the [`BuildTask`] run if consolidation fails.
+    pub const fn with_build_budget(mut self, budget: BuildBudget) -> Self {
+        self.build_budget = budget;
+        self
     }
 
     /// Executes the [`ForkchoiceTask`] if the attributes match the block.
@@ -42,7 +57,7 @@ impl ConsolidateTask {
         state: &mut EngineState,
     ) -> Result<(), EngineTaskError> {
         let task = ForkchoiceTask::new(Arc::clone(&self.client));
-        task.execute(state).await
+        task.execute(state).instrument(info_span!(target: "engine", "canonicalize")).await
     }
 
     /// Executes a new [`BuildTask`].
@@ -54,7 +69,8 @@ impl ConsolidateTask {
             self.attributes.clone(),
             self.is_attributes_derived,
             None,
-        );
+        )
+        .with_budget(self.build_budget);
         build_task.execute(state).await
     }
 
@@ -82,7 +98,22 @@ impl ConsolidateTask {
         // If this is successful, the forkchoice change synchronizes.
         // Otherwise, the attributes need to be processed.
         let block_hash = block.header.hash;
-        if crate::AttributesMatch::check(&self.cfg, &self.attributes, &block).is_match() {
+        let check = crate::AttributesMatch::check(&self.cfg, &self.attributes, &block);
+        if let crate::AttributesMatch::Mismatch(mismatch) = check {
+            let diff = crate::AttributesDiff::new(&self.cfg, &self.attributes, &block);
+            let field = diff.first_mismatch();
+            kona_macros::inc!(counter, Metrics::ATTRIBUTES_MISMATCH, "field" => field);
+            warn!(
+                target: "engine",
+                number = block_num,
+                block_hash = %block_hash,
+                ?mismatch,
+                field,
+                %diff,
+                "Derived attributes do not match the unsafe block"
+            );
+        }
+        if check.is_match() {
             trace!(
                 target: "engine",
                 attributes = ?self.attributes,
@@ -151,11 +182,112 @@ impl ConsolidateTask {
 #[async_trait]
 impl EngineTaskExt for ConsolidateTask {
     async fn execute(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
-        // Skip to building the payload attributes if consolidation is not needed.
-        if state.safe_head().block_info.number < state.unsafe_head().block_info.number {
-            self.consolidate(state).await
-        } else {
-            self.execute_build_task(state).await
+        let span = info_span!(
+            target: "engine",
+            "consolidate",
+            l2_number = self.attributes.block_number(),
+            correlation_id = %self.attributes.correlation_id(),
+        );
+        async {
+            // Derived attributes must build on the local safe head. If it was rolled back since
+            // they were derived, they are stale, and building them would fail the forkchoice
+            // update and reset the engine.
+            let safe_head = state.local_safe_head();
+            if self.is_attributes_derived &&
+                self.attributes.parent.block_info.hash != safe_head.block_info.hash
+            {
+                kona_macros::inc!(counter, Metrics::STALE_ATTRIBUTES_DROPPED);
+                return Err(ConsolidateTaskError::StaleParent {
+                    parent: self.attributes.parent.block_info.id(),
+                    safe_head: safe_head.block_info.id(),
+                }
+                .into());
+            }
+
+            // Skip to building the payload attributes if consolidation is not needed.
+            if state.safe_head().block_info.number < state.unsafe_head().block_info.number {
+                self.consolidate(state).await
+            } else {
+                self.execute_build_task(state).await
+            }
         }
+        .instrument(span)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockEngineClient;
+    use alloy_primitives::B256;
+    use kona_protocol::BlockInfo;
+    use op_alloy_rpc_types_engine::OpPayloadAttributes;
+
+    #[tokio::test]
+    async fn test_consolidate_missing_unsafe_block() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        let unsafe_head = L2BlockInfo {
+            block_info: BlockInfo { number: 1, ..Default::default() },
+            ..Default::default()
+        };
+        let mut state = EngineState::default();
+        state.set_unsafe_head(unsafe_head);
+        let attributes = OpAttributesWithParent::new(
+            OpPayloadAttributes::default(),
+            L2BlockInfo::default(),
+            BlockInfo::default(),
+            true,
+        );
+
+        let err = ConsolidateTask::new(mock.client(), cfg, attributes, true)
+            .execute(&mut state)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineTaskError::Reset(_)));
+        assert_eq!(state.safe_head(), L2BlockInfo::default());
+        assert_eq!(mock.methods(), ["eth_getBlockByNumber"]);
+    }
+
+    #[tokio::test]
+    async fn test_consolidate_stale_parent() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        let safe_head = L2BlockInfo {
+            block_info: BlockInfo {
+                number: 5,
+                hash: B256::with_last_byte(5),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut state = EngineState::default();
+        state.set_local_safe_head(safe_head);
+        state.set_safe_head(safe_head);
+
+        // The attributes were derived on top of a block that was rolled back since.
+        let parent = L2BlockInfo {
+            block_info: BlockInfo {
+                number: 6,
+                hash: B256::with_last_byte(6),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let attributes = OpAttributesWithParent::new(
+            OpPayloadAttributes::default(),
+            parent,
+            BlockInfo::default(),
+            true,
+        );
+
+        let err = ConsolidateTask::new(mock.client(), cfg, attributes, true)
+            .execute(&mut state)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineTaskError::Stale(_)));
+        assert_eq!(state.safe_head(), safe_head);
+        assert!(mock.methods().is_empty());
     }
 }
@@ -29,6 +29,17 @@ impl FinalizeTask {
 #[async_trait]
 impl EngineTaskExt for FinalizeTask {
     async fn execute(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
+        // The finalized head never moves backwards.
+        if self.block_number <= state.finalized_head().block_info.number {
+            debug!(
+                target: "engine",
+                number = self.block_number,
+                finalized_head = state.finalized_head().block_info.number,
+                "Block is already finalized"
+            );
+            return Ok(());
+        }
+
         // Sanity check that the block that is being finalized is at least safe.
         if state.safe_head().block_info.number < self.block_number {
             return Err(FinalizeTaskError::BlockNotSafe.into());
@@ -70,3 +70,39 @@ impl EngineTaskExt for ForkchoiceTask {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockEngineClient;
+    use alloy_json_rpc::ErrorPayload;
+    use kona_genesis::RollupConfig;
+
+    #[tokio::test]
+    async fn test_forkchoice_update() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let mut state = EngineState { forkchoice_update_needed: true, ..Default::default() };
+
+        ForkchoiceTask::new(mock.client()).execute(&mut state).await.unwrap();
+        assert!(!state.forkchoice_update_needed);
+        assert_eq!(mock.methods(), ["engine_forkchoiceUpdatedV3"]);
+    }
+
+    #[tokio::test]
+    async fn test_forkchoice_update_invalid_state() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        mock.push_error(
+            "engine_forkchoiceUpdatedV3",
+            ErrorPayload {
+                code: INVALID_FORK_CHOICE_STATE_ERROR as i64,
+                message: "Invalid forkchoice state".into(),
+                data: None,
+            },
+        );
+        let mut state = EngineState { forkchoice_update_needed: true, ..Default::default() };
+
+        let err = ForkchoiceTask::new(mock.client()).execute(&mut state).await.unwrap_err();
+        assert!(matches!(err, EngineTaskError::Reset(_)));
+        assert!(state.forkchoice_update_needed);
+    }
+}
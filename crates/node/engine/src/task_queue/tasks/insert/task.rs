@@ -2,7 +2,7 @@
 
 use crate::{
     EngineClient, EngineForkchoiceVersion, EngineState, EngineTaskError, EngineTaskExt,
-    InsertUnsafeTaskError, Metrics,
+    InsertUnsafeTaskError, Metrics, PayloadEnvelopeStore,
 };
 use alloy_eips::eip7685::EMPTY_REQUESTS_HASH;
 use alloy_provider::ext::EngineApi;
@@ -31,6 +31,8 @@ pub struct InsertUnsafeTask {
     version: EngineForkchoiceVersion,
     /// The network payload envelope.
     envelope: OpExecutionPayloadEnvelope,
+    /// The store the envelope is kept in once inserted, if any.
+    store: Option<PayloadEnvelopeStore>,
 }
 
 impl InsertUnsafeTask {
@@ -42,7 +44,13 @@ impl InsertUnsafeTask {
     ) -> Self {
         let version =
             EngineForkchoiceVersion::from_cfg(rollup_config.as_ref(), envelope.payload.timestamp());
-        Self { client, rollup_config, version, envelope }
+        Self { client, rollup_config, version, envelope, store: None }
+    }
+
+    /// Keeps the envelope in the given [`PayloadEnvelopeStore`] once it is inserted.
+    pub fn with_envelope_store(mut self, store: PayloadEnvelopeStore) -> Self {
+        self.store = Some(store);
+        self
     }
 
     /// Checks the response of the `engine_newPayload` call.
@@ -73,6 +81,19 @@ impl EngineTaskExt for InsertUnsafeTask {
     async fn execute(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
         let time_start = Instant::now();
 
+        // Payloads at or below the safe head cannot become the unsafe head, which must never fall
+        // behind the safe head. They are dropped, as the derived chain supersedes them.
+        let number = self.envelope.payload.block_number();
+        if number <= state.safe_head().block_info.number {
+            debug!(
+                target: "engine",
+                number,
+                safe_head = state.safe_head().block_info.number,
+                "Dropping unsafe payload at or below the safe head"
+            );
+            return Ok(());
+        }
+
         // Insert the new payload.
         // Form the new unsafe block ref from the execution payload.
         let parent_beacon_block_root = self.envelope.parent_beacon_block_root.unwrap_or_default();
@@ -131,6 +152,7 @@ impl EngineTaskExt for InsertUnsafeTask {
         if !self.check_new_payload_status(&response.status) {
             return Err(InsertUnsafeTaskError::UnexpectedPayloadStatus(response.status).into());
         }
+        self.client.shadow_new_payload(&self.envelope, &response.status);
         let insert_duration = insert_time_start.elapsed();
 
         let new_unsafe_ref =
@@ -189,6 +211,9 @@ impl EngineTaskExt for InsertUnsafeTask {
             fcu_duration = ?fcu_duration,
             "Inserted new unsafe block"
         );
+        if let Some(store) = &self.store {
+            store.insert(self.envelope.clone());
+        }
 
         // Update metrics.
         kona_macros::inc!(counter, Metrics::ENGINE_TASK_COUNT, Metrics::INSERT_TASK_LABEL);
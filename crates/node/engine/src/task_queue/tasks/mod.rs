@@ -1,7 +1,7 @@
 //! Tasks to update the engine state.
 
 mod task;
-pub use task::{EngineTask, EngineTaskError, EngineTaskExt};
+pub use task::{EngineTask, EngineTaskError, EngineTaskExt, EngineTaskKind};
 
 mod forkchoice;
 pub use forkchoice::{ForkchoiceTask, ForkchoiceTaskError};
@@ -10,7 +10,10 @@ mod insert;
 pub use insert::{InsertUnsafeTask, InsertUnsafeTaskError};
 
 mod build;
-pub use build::{BuildTask, BuildTaskError};
+pub use build::{
+    BuildBudget, BuildTask, BuildTaskError, BuildTimings, DEFAULT_BUILD_BUDGET_FRACTION,
+    PayloadStats,
+};
 
 mod consolidate;
 pub use consolidate::{ConsolidateTask, ConsolidateTaskError};
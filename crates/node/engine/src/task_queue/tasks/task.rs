@@ -3,8 +3,9 @@
 //! [`Engine`]: crate::Engine
 
 use super::{BuildTask, ConsolidateTask, FinalizeTask, ForkchoiceTask, InsertUnsafeTask};
-use crate::EngineState;
+use crate::{EngineState, Metrics};
 use async_trait::async_trait;
+use derive_more::Display;
 use std::cmp::Ordering;
 use thiserror::Error;
 
@@ -27,7 +28,60 @@ pub enum EngineTask {
     Finalize(FinalizeTask),
 }
 
+/// The kind of an [`EngineTask`], i.e. its variant.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EngineTaskKind {
+    /// An [`EngineTask::ForkchoiceUpdate`].
+    #[display("{}", Metrics::FORKCHOICE_TASK_LABEL)]
+    ForkchoiceUpdate,
+    /// An [`EngineTask::InsertUnsafe`].
+    #[display("{}", Metrics::INSERT_TASK_LABEL)]
+    InsertUnsafe,
+    /// An [`EngineTask::BuildBlock`].
+    #[display("{}", Metrics::BUILD_TASK_LABEL)]
+    BuildBlock,
+    /// An [`EngineTask::Consolidate`].
+    #[display("{}", Metrics::CONSOLIDATE_TASK_LABEL)]
+    Consolidate,
+    /// An [`EngineTask::Finalize`].
+    #[display("{}", Metrics::FINALIZE_TASK_LABEL)]
+    Finalize,
+}
+
+impl EngineTaskKind {
+    /// Contains all task kinds.
+    pub const KINDS: [Self; 5] = [
+        Self::ForkchoiceUpdate,
+        Self::InsertUnsafe,
+        Self::BuildBlock,
+        Self::Consolidate,
+        Self::Finalize,
+    ];
+
+    /// Returns the label of the task kind, as used in metrics.
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::ForkchoiceUpdate => Metrics::FORKCHOICE_TASK_LABEL,
+            Self::InsertUnsafe => Metrics::INSERT_TASK_LABEL,
+            Self::BuildBlock => Metrics::BUILD_TASK_LABEL,
+            Self::Consolidate => Metrics::CONSOLIDATE_TASK_LABEL,
+            Self::Finalize => Metrics::FINALIZE_TASK_LABEL,
+        }
+    }
+}
+
 impl EngineTask {
+    /// Returns the [`EngineTaskKind`] of the task.
+    pub const fn kind(&self) -> EngineTaskKind {
+        match self {
+            Self::ForkchoiceUpdate(_) => EngineTaskKind::ForkchoiceUpdate,
+            Self::InsertUnsafe(_) => EngineTaskKind::InsertUnsafe,
+            Self::BuildBlock(_) => EngineTaskKind::BuildBlock,
+            Self::Consolidate(_) => EngineTaskKind::Consolidate,
+            Self::Finalize(_) => EngineTaskKind::Finalize,
+        }
+    }
+
     /// Executes the task without consuming it.
     async fn execute_inner(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
         match self.clone() {
@@ -42,14 +96,7 @@ impl EngineTask {
 
 impl PartialEq for EngineTask {
     fn eq(&self, other: &Self) -> bool {
-        matches!(
-            (self, other),
-            (Self::ForkchoiceUpdate(_), Self::ForkchoiceUpdate(_)) |
-                (Self::InsertUnsafe(_), Self::InsertUnsafe(_)) |
-                (Self::BuildBlock(_), Self::BuildBlock(_)) |
-                (Self::Consolidate(_), Self::Consolidate(_)) |
-                (Self::Finalize(_), Self::Finalize(_))
-        )
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -77,7 +124,10 @@ impl Ord for EngineTask {
         match (self, other) {
             // Same variant cases
             (Self::InsertUnsafe(_), Self::InsertUnsafe(_)) => Ordering::Equal,
-            (Self::Consolidate(_), Self::Consolidate(_)) => Ordering::Equal,
+            // Derived attributes build on each other, so are consolidated in order.
+            (Self::Consolidate(a), Self::Consolidate(b)) => {
+                b.attributes.block_number().cmp(&a.attributes.block_number())
+            }
             (Self::BuildBlock(_), Self::BuildBlock(_)) => Ordering::Equal,
             (Self::ForkchoiceUpdate(_), Self::ForkchoiceUpdate(_)) => Ordering::Equal,
             (Self::Finalize(_), Self::Finalize(_)) => Ordering::Equal,
@@ -123,6 +173,14 @@ impl EngineTaskExt for EngineTask {
                     warn!(target: "engine", "Engine requested derivation flush");
                     return Err(EngineTaskError::Flush(e));
                 }
+                EngineTaskError::Stale(e) => {
+                    debug!(target: "engine", "{e}");
+                    return Err(EngineTaskError::Stale(e));
+                }
+                EngineTaskError::Syncing(e) => {
+                    debug!(target: "engine", "{e}");
+                    return Err(EngineTaskError::Syncing(e));
+                }
             }
         }
 
@@ -152,4 +210,15 @@ pub enum EngineTaskError {
     /// An error that requires the derivation pipeline to be flushed.
     #[error("Derivation pipeline flush required: {0}")]
     Flush(Box<dyn std::error::Error + Send + Sync>),
+    /// The task no longer applies to the engine state, e.g. derived attributes whose parent was
+    /// rolled back. The task is dropped, and derivation must be realigned with the local safe
+    /// head, without resetting the engine.
+    #[error("Stale engine task: {0}")]
+    Stale(Box<dyn std::error::Error + Send + Sync>),
+    /// The execution layer is syncing, and cannot build the block yet. The task is held back by
+    /// the [`Engine`], and resubmitted once the execution layer is synced.
+    ///
+    /// [`Engine`]: crate::Engine
+    #[error("Execution layer syncing: {0}")]
+    Syncing(Box<dyn std::error::Error + Send + Sync>),
 }
@@ -0,0 +1,111 @@
+//! Timeouts of the [`EngineTask`]s executed by the [`Engine`].
+//!
+//! [`EngineTask`]: crate::EngineTask
+//! [`Engine`]: crate::Engine
+
+use crate::EngineTaskKind;
+use std::time::Duration;
+use thiserror::Error;
+
+/// What the [`Engine`] does with a task that timed out.
+///
+/// The policy does not apply to [`EngineTask::Consolidate`] tasks, which are always retried, since
+/// the attributes they carry are not derived again.
+///
+/// [`Engine`]: crate::Engine
+/// [`EngineTask::Consolidate`]: crate::EngineTask::Consolidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskTimeoutPolicy {
+    /// Keep the task at the head of the queue, and retry it on the next drains, up to the given
+    /// number of times. A task that times out once more is dropped.
+    Retry(u32),
+    /// Drop the task right away, so that the rest of the queue can proceed.
+    Drop,
+}
+
+impl Default for TaskTimeoutPolicy {
+    fn default() -> Self {
+        Self::Retry(3)
+    }
+}
+
+/// The timeouts of the [`EngineTask`]s, by [`EngineTaskKind`].
+///
+/// A task that runs for longer than the timeout of its kind, e.g. on a `engine_getPayload` call
+/// that never returns, is cancelled, leaving the engine state untouched. It is then handled
+/// according to the [`TaskTimeoutPolicy`]. Tasks without a timeout may run indefinitely.
+///
+/// [`EngineTask`]: crate::EngineTask
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineTaskTimeouts {
+    /// The timeout of [`EngineTask::ForkchoiceUpdate`] tasks.
+    ///
+    /// [`EngineTask::ForkchoiceUpdate`]: crate::EngineTask::ForkchoiceUpdate
+    pub forkchoice: Option<Duration>,
+    /// The timeout of [`EngineTask::InsertUnsafe`] tasks.
+    ///
+    /// [`EngineTask::InsertUnsafe`]: crate::EngineTask::InsertUnsafe
+    pub insert: Option<Duration>,
+    /// The timeout of [`EngineTask::BuildBlock`] tasks.
+    ///
+    /// [`EngineTask::BuildBlock`]: crate::EngineTask::BuildBlock
+    pub build: Option<Duration>,
+    /// The timeout of [`EngineTask::Consolidate`] tasks.
+    ///
+    /// [`EngineTask::Consolidate`]: crate::EngineTask::Consolidate
+    pub consolidate: Option<Duration>,
+    /// The timeout of [`EngineTask::Finalize`] tasks.
+    ///
+    /// [`EngineTask::Finalize`]: crate::EngineTask::Finalize
+    pub finalize: Option<Duration>,
+    /// What to do with the tasks that timed out.
+    pub policy: TaskTimeoutPolicy,
+}
+
+impl EngineTaskTimeouts {
+    /// Returns the timeout of the tasks of the given kind, if any.
+    pub const fn get(&self, kind: EngineTaskKind) -> Option<Duration> {
+        match kind {
+            EngineTaskKind::ForkchoiceUpdate => self.forkchoice,
+            EngineTaskKind::InsertUnsafe => self.insert,
+            EngineTaskKind::BuildBlock => self.build,
+            EngineTaskKind::Consolidate => self.consolidate,
+            EngineTaskKind::Finalize => self.finalize,
+        }
+    }
+
+    /// Returns the timeouts with the given timeout set for the tasks of the given kind.
+    pub const fn with(mut self, kind: EngineTaskKind, timeout: Duration) -> Self {
+        match kind {
+            EngineTaskKind::ForkchoiceUpdate => self.forkchoice = Some(timeout),
+            EngineTaskKind::InsertUnsafe => self.insert = Some(timeout),
+            EngineTaskKind::BuildBlock => self.build = Some(timeout),
+            EngineTaskKind::Consolidate => self.consolidate = Some(timeout),
+            EngineTaskKind::Finalize => self.finalize = Some(timeout),
+        }
+        self
+    }
+
+    /// Returns the timeouts with the given [`TaskTimeoutPolicy`].
+    pub const fn with_policy(mut self, policy: TaskTimeoutPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+/// An [`EngineTask`] timed out, and was kept in the queue to be retried.
+///
+/// It is returned by [`Engine::drain`] as a temporary error.
+///
+/// [`EngineTask`]: crate::EngineTask
+/// [`Engine::drain`]: crate::Engine::drain
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("{kind} task timed out after {timeout:?} (retry {retry})")]
+pub struct EngineTaskTimeout {
+    /// The kind of the task.
+    pub kind: EngineTaskKind,
+    /// The timeout of the task.
+    pub timeout: Duration,
+    /// The number of times the task was retried after timing out, including this one.
+    pub retry: u32,
+}
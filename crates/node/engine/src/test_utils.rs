@@ -0,0 +1,408 @@
+//! Test utilities for `kona-engine`.
+
+use crate::{EngineCache, EngineCacheLayer, EngineClient};
+use alloy_eips::BlockNumberOrTag;
+use alloy_json_rpc::{
+    ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload, SerializedRequest,
+};
+use alloy_primitives::B256;
+use alloy_provider::RootProvider;
+use alloy_rpc_client::RpcClient;
+use alloy_rpc_types_engine::{
+    ForkchoiceState, ForkchoiceUpdated, PayloadId, PayloadStatus, PayloadStatusEnum,
+};
+use alloy_rpc_types_eth::SyncStatus;
+use alloy_transport::{TransportError, TransportFut};
+use kona_genesis::RollupConfig;
+use serde::Serialize;
+use serde_json::{Value, value::RawValue};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tower::Layer;
+
+/// The error code of `engine_getPayload` for unknown payload ids.
+const UNKNOWN_PAYLOAD_ERROR: i64 = -38001;
+
+/// The error code of calls to methods that are not served.
+const METHOD_NOT_FOUND_ERROR: i64 = -32601;
+
+/// A mock execution engine, serving the engine API from scripted responses.
+///
+/// The [`EngineClient`] returned by [`Self::client`] sends all of its requests, on the engine,
+/// L2 and L1 providers, to the mock instead of an execution layer. Unless scripted otherwise:
+///
+/// - `engine_newPayload` and `engine_forkchoiceUpdated` return `VALID`.
+/// - `engine_forkchoiceUpdated` with payload attributes returns a new [`PayloadId`], under which
+///   `engine_getPayload` serves the next payload [pushed][Self::push_payload].
+/// - `eth_getBlockByNumber` and `eth_getBlockByHash` serve the [inserted][Self::insert_block]
+///   blocks.
+/// - `eth_syncing` returns `false`.
+///
+/// Every request is [recorded][Self::calls], so tests can assert on the calls made by a task.
+///
+/// Clones share the same script.
+#[derive(Debug, Clone)]
+pub struct MockEngineClient {
+    /// The script and the recorded calls.
+    state: Arc<Mutex<MockEngineState>>,
+    /// The client sending its requests to the mock.
+    client: Arc<EngineClient>,
+}
+
+/// A request received by a [`MockEngineClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockEngineCall {
+    /// The method of the request.
+    pub method: String,
+    /// The parameters of the request.
+    pub params: Value,
+}
+
+/// The script of a [`MockEngineClient`].
+#[derive(Debug, Default)]
+struct MockEngineState {
+    /// The statuses returned by the next `engine_newPayload` calls.
+    new_payload_statuses: VecDeque<PayloadStatusEnum>,
+    /// The statuses returned by the next `engine_forkchoiceUpdated` calls.
+    forkchoice_statuses: VecDeque<PayloadStatusEnum>,
+    /// The errors returned by the next calls, by method.
+    errors: HashMap<String, VecDeque<ErrorPayload>>,
+    /// The block hashes that are rejected as `INVALID`.
+    invalid: HashSet<B256>,
+    /// The payloads served by the next payload building jobs.
+    payloads: VecDeque<Value>,
+    /// The payloads of the started payload building jobs.
+    built: HashMap<PayloadId, Value>,
+    /// The L2 blocks, by number.
+    blocks: BTreeMap<u64, Value>,
+    /// The status returned by `eth_syncing`, `false` if not set.
+    syncing: Option<SyncStatus>,
+    /// The delay before answering each request.
+    latency: Duration,
+    /// The requests received so far.
+    calls: Vec<MockEngineCall>,
+}
+
+impl MockEngineClient {
+    /// Creates a new [`MockEngineClient`] for the chain with the given [`RollupConfig`].
+    pub fn new(cfg: Arc<RollupConfig>) -> Self {
+        let state = Arc::new(Mutex::new(MockEngineState::default()));
+        let transport = MockEngineTransport { state: state.clone() };
+        let cache = EngineCache::default();
+        let layer = EngineCacheLayer::new(cache.clone());
+        let client = EngineClient {
+            engine: RootProvider::new(RpcClient::new(layer.layer(transport.clone()), true)),
+            l2_provider: RootProvider::new(RpcClient::new(layer.layer(transport.clone()), true)),
+            l1_provider: RootProvider::new(RpcClient::new(transport, true)),
+            cfg,
+            cache,
+            shadow_lookahead: None,
+            el_chain_config: Default::default(),
+        };
+        Self { state, client: Arc::new(client) }
+    }
+
+    /// Delays the answer to each request by the given latency.
+    pub fn with_latency(self, latency: Duration) -> Self {
+        self.set_latency(latency);
+        self
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockEngineState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the [`EngineClient`] sending its requests to the mock.
+    pub fn client(&self) -> Arc<EngineClient> {
+        self.client.clone()
+    }
+
+    /// Delays the answer to each subsequent request by the given latency.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state().latency = latency;
+    }
+
+    /// Scripts the status of the next `engine_newPayload` call that does not import an
+    /// [invalid][Self::invalidate] block.
+    pub fn push_new_payload_status(&self, status: PayloadStatusEnum) {
+        self.state().new_payload_statuses.push_back(status);
+    }
+
+    /// Scripts the status of the next `engine_forkchoiceUpdated` call whose head is not an
+    /// [invalid][Self::invalidate] block.
+    pub fn push_forkchoice_status(&self, status: PayloadStatusEnum) {
+        self.state().forkchoice_statuses.push_back(status);
+    }
+
+    /// Returns the given error to the next call to the given method, instead of a response.
+    pub fn push_error(&self, method: &str, error: ErrorPayload) {
+        self.state().errors.entry(method.to_string()).or_default().push_back(error);
+    }
+
+    /// Rejects the block with the given hash as `INVALID`, when it is imported with
+    /// `engine_newPayload` or made the head with `engine_forkchoiceUpdated`.
+    pub fn invalidate(&self, hash: B256) {
+        self.state().invalid.insert(hash);
+    }
+
+    /// Serves the given payload envelope from the next payload building job, whichever
+    /// `engine_getPayload` version fetches it.
+    pub fn push_payload<T: Serialize>(&self, payload: &T) {
+        let payload = serde_json::to_value(payload).expect("payloads serialize");
+        self.state().payloads.push_back(payload);
+    }
+
+    /// Discards the [pushed][Self::push_payload] payloads that no payload building job served.
+    pub fn clear_payloads(&self) {
+        self.state().payloads.clear();
+    }
+
+    /// Serves the given L2 block from `eth_getBlockByNumber` and `eth_getBlockByHash`. The block
+    /// with the highest number is the `latest` block.
+    pub fn insert_block<T: Serialize>(&self, number: u64, block: &T) {
+        let block = serde_json::to_value(block).expect("blocks serialize");
+        self.state().blocks.insert(number, block);
+    }
+
+    /// Returns the given status from the subsequent `eth_syncing` calls.
+    pub fn set_syncing(&self, status: SyncStatus) {
+        self.state().syncing = Some(status);
+    }
+
+    /// Returns the requests received so far.
+    pub fn calls(&self) -> Vec<MockEngineCall> {
+        self.state().calls.clone()
+    }
+
+    /// Returns the methods of the requests received so far.
+    pub fn methods(&self) -> Vec<String> {
+        self.state().calls.iter().map(|call| call.method.clone()).collect()
+    }
+}
+
+/// The transport of the providers of a [`MockEngineClient`].
+#[derive(Debug, Clone)]
+struct MockEngineTransport {
+    /// The script of the mock.
+    state: Arc<Mutex<MockEngineState>>,
+}
+
+impl MockEngineTransport {
+    fn state(&self) -> MutexGuard<'_, MockEngineState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Answers the given request packet.
+    async fn handle(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let latency = self.state().latency;
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        Ok(match request {
+            RequestPacket::Single(request) => ResponsePacket::Single(self.respond(&request)),
+            RequestPacket::Batch(requests) => ResponsePacket::Batch(
+                requests.iter().map(|request| self.respond(request)).collect(),
+            ),
+        })
+    }
+
+    /// Answers a single request.
+    fn respond(&self, request: &SerializedRequest) -> Response {
+        let method = request.method();
+        let params: Value = request
+            .params()
+            .and_then(|params| serde_json::from_str(params.get()).ok())
+            .unwrap_or(Value::Null);
+
+        let mut state = self.state();
+        state.calls.push(MockEngineCall { method: method.to_string(), params: params.clone() });
+        let error = state.errors.get_mut(method).and_then(VecDeque::pop_front);
+        let payload = error.map_or_else(|| state.answer(method, &params), Err);
+
+        Response {
+            id: request.id().clone(),
+            payload: match payload {
+                Ok(result) => ResponsePayload::Success(
+                    RawValue::from_string(result.to_string()).expect("valid json"),
+                ),
+                Err(error) => ResponsePayload::Failure(error),
+            },
+        }
+    }
+}
+
+impl MockEngineState {
+    /// Returns the result of the given call.
+    fn answer(&mut self, method: &str, params: &Value) -> Result<Value, ErrorPayload> {
+        match method {
+            "engine_newPayloadV1" |
+            "engine_newPayloadV2" |
+            "engine_newPayloadV3" |
+            "engine_newPayloadV4" => {
+                let hash: B256 = param(params, 0, "blockHash")?;
+                let status = self.status(hash, |state| state.new_payload_statuses.pop_front());
+                to_value(PayloadStatus::new(status, Some(hash)))
+            }
+            "engine_forkchoiceUpdatedV1" |
+            "engine_forkchoiceUpdatedV2" |
+            "engine_forkchoiceUpdatedV3" => {
+                let forkchoice: ForkchoiceState = param(params, 0, "")?;
+                let head = forkchoice.head_block_hash;
+                let status = self.status(head, |state| state.forkchoice_statuses.pop_front());
+                let has_attributes = params.get(1).is_some_and(|attributes| !attributes.is_null());
+
+                let mut update = ForkchoiceUpdated::from_status(status.clone());
+                if status.is_valid() {
+                    update = update.with_latest_valid_hash(head);
+                    if has_attributes {
+                        let id = PayloadId::new((self.built.len() as u64 + 1).to_be_bytes());
+                        if let Some(payload) = self.payloads.pop_front() {
+                            self.built.insert(id, payload);
+                        }
+                        update = update.with_payload_id(id);
+                    }
+                }
+                to_value(update)
+            }
+            "engine_getPayloadV2" | "engine_getPayloadV3" | "engine_getPayloadV4" => {
+                let id: PayloadId = param(params, 0, "")?;
+                self.built.get(&id).cloned().ok_or_else(|| ErrorPayload {
+                    code: UNKNOWN_PAYLOAD_ERROR,
+                    message: "Unknown payload".into(),
+                    data: None,
+                })
+            }
+            "eth_getBlockByNumber" => {
+                let number: BlockNumberOrTag = param(params, 0, "")?;
+                let block = match number {
+                    BlockNumberOrTag::Number(number) => self.blocks.get(&number),
+                    BlockNumberOrTag::Earliest => self.blocks.values().next(),
+                    _ => self.blocks.values().next_back(),
+                };
+                Ok(block.cloned().unwrap_or(Value::Null))
+            }
+            "eth_getBlockByHash" => {
+                let hash: B256 = param(params, 0, "")?;
+                let hash = Value::from(hash.to_string());
+                let block = self.blocks.values().find(|block| block.get("hash") == Some(&hash));
+                Ok(block.cloned().unwrap_or(Value::Null))
+            }
+            "eth_syncing" => to_value(self.syncing.clone().unwrap_or(SyncStatus::None)),
+            _ => Err(ErrorPayload {
+                code: METHOD_NOT_FOUND_ERROR,
+                message: format!("Method not found: {method}").into(),
+                data: None,
+            }),
+        }
+    }
+
+    /// Returns `INVALID` if the given block is invalid, the next scripted status otherwise, and
+    /// `VALID` if no status is scripted.
+    fn status(
+        &mut self,
+        hash: B256,
+        next: impl FnOnce(&mut Self) -> Option<PayloadStatusEnum>,
+    ) -> PayloadStatusEnum {
+        if self.invalid.contains(&hash) {
+            return PayloadStatusEnum::Invalid { validation_error: format!("invalid block {hash}") };
+        }
+        next(self).unwrap_or(PayloadStatusEnum::Valid)
+    }
+}
+
+impl tower::Service<RequestPacket> for MockEngineTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().handle(request))
+    }
+}
+
+/// Deserializes the parameter at the given index, or the given field of it if not empty.
+fn param<T: serde::de::DeserializeOwned>(
+    params: &Value,
+    index: usize,
+    field: &str,
+) -> Result<T, ErrorPayload> {
+    let param = params.get(index).unwrap_or(&Value::Null);
+    let param = if field.is_empty() { param } else { param.get(field).unwrap_or(&Value::Null) };
+    serde_json::from_value(param.clone()).map_err(|e| ErrorPayload {
+        code: -32602,
+        message: format!("Invalid params: {e}").into(),
+        data: None,
+    })
+}
+
+/// Serializes the result of a call.
+fn to_value<T: Serialize>(result: T) -> Result<Value, ErrorPayload> {
+    Ok(serde_json::to_value(result).expect("results serialize"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_provider::Provider;
+    use op_alloy_provider::ext::engine::OpEngineApi;
+
+    #[tokio::test]
+    async fn test_forkchoice_scripted_statuses() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        mock.push_forkchoice_status(PayloadStatusEnum::Syncing);
+        let client = mock.client();
+
+        let forkchoice = ForkchoiceState::default();
+        let update = client.fork_choice_updated_v2(forkchoice, None).await.unwrap();
+        assert_eq!(update.payload_status.status, PayloadStatusEnum::Syncing);
+        let update = client.fork_choice_updated_v2(forkchoice, None).await.unwrap();
+        assert_eq!(update.payload_status.status, PayloadStatusEnum::Valid);
+        assert_eq!(mock.methods(), ["engine_forkchoiceUpdatedV2", "engine_forkchoiceUpdatedV2"]);
+    }
+
+    #[tokio::test]
+    async fn test_forkchoice_invalid_head() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let head = B256::repeat_byte(1);
+        mock.invalidate(head);
+
+        let forkchoice = ForkchoiceState { head_block_hash: head, ..Default::default() };
+        let update = mock.client().fork_choice_updated_v3(forkchoice, None).await.unwrap();
+        assert!(update.payload_status.status.is_invalid());
+    }
+
+    #[tokio::test]
+    async fn test_scripted_error() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        mock.push_error("eth_getBlockByNumber", ErrorPayload::internal_error());
+
+        let client = mock.client();
+        assert!(client.l2_block_by_label(BlockNumberOrTag::Latest).await.is_err());
+        assert!(client.l2_block_by_label(BlockNumberOrTag::Latest).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method() {
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default()));
+        let err = mock.client().l1_provider().get_chain_id().await.unwrap_err();
+        assert_eq!(err.as_error_resp().unwrap().code, METHOD_NOT_FOUND_ERROR);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_latency() {
+        let latency = Duration::from_secs(3);
+        let mock = MockEngineClient::new(Arc::new(RollupConfig::default())).with_latency(latency);
+
+        let start = tokio::time::Instant::now();
+        mock.client().fork_choice_updated_v2(ForkchoiceState::default(), None).await.unwrap();
+        assert!(start.elapsed() >= latency);
+    }
+}
@@ -0,0 +1,342 @@
+//! Hardening of the transport to the execution client.
+//!
+//! By default, the [`EngineClient`] authenticates to the engine API with the JWT secret only, over
+//! plain HTTP. Deployments where the execution client sits across a network boundary can
+//! configure an [`EngineTransport`] on top of it:
+//!
+//! - [`EngineTls`] enables mutual TLS, presenting a client certificate to the execution client and
+//!   verifying its server certificate against a given CA bundle.
+//! - [`RequestSigner`] signs the body of each request with HMAC-SHA256, in the [`SIGNATURE_HEADER`]
+//!   header, so that a proxy in front of the execution client can reject requests that were
+//!   tampered with or replayed.
+//!
+//! The transport applies to the engine API and L2 RPC endpoints, which are both served by the
+//! execution client.
+//!
+//! [`EngineClient`]: crate::EngineClient
+
+use alloy_network::Network;
+use alloy_primitives::{Bytes, hex};
+use alloy_provider::RootProvider;
+use alloy_rpc_client::RpcClient;
+use alloy_rpc_types_engine::JwtSecret;
+use alloy_transport_http::{
+    AuthLayer, Http, HyperClient,
+    hyper::{Request, Response, header::HeaderValue},
+    hyper_util::{
+        client::legacy::{Client, connect::Connect},
+        rt::TokioExecutor,
+    },
+};
+use hmac::{Hmac, Mac};
+use http_body_util::{BodyExt, Full};
+use hyper_rustls::{ConfigBuilderExt, HttpsConnectorBuilder};
+use rustls::{
+    ClientConfig, RootCertStore,
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+};
+use sha2::Sha256;
+use std::{
+    fmt::Debug,
+    future::Future,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+use tower::{Layer, Service, ServiceBuilder};
+use url::Url;
+
+/// The header carrying the signature of a request signed by a [`RequestSigner`].
+///
+/// Its value is `t=<unix timestamp>,v1=<signature>`, where the signature is the hex-encoded
+/// HMAC-SHA256 of `<unix timestamp>.<request body>`.
+pub const SIGNATURE_HEADER: &str = "x-engine-signature";
+
+/// An error configuring the [`EngineTransport`].
+#[derive(Error, Debug)]
+pub enum EngineTransportError {
+    /// A certificate or private key could not be read.
+    #[error("Failed to read PEM file: {0}")]
+    Pem(#[from] rustls::pki_types::pem::Error),
+    /// The TLS configuration was rejected.
+    #[error("Invalid TLS configuration: {0}")]
+    Tls(#[from] rustls::Error),
+    /// The signing key could not be read.
+    #[error("Failed to read signing key: {0}")]
+    Io(#[from] std::io::Error),
+    /// The signing key is not hex-encoded, or empty.
+    #[error("Invalid signing key: expected a non-empty hex string")]
+    InvalidSigningKey,
+}
+
+/// The transport of the [`EngineClient`](crate::EngineClient) to the execution client. See the
+/// [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct EngineTransport {
+    /// The mutual TLS configuration, if any.
+    tls: Option<EngineTls>,
+    /// The signer of the requests, if any.
+    signer: Option<RequestSigner>,
+}
+
+impl EngineTransport {
+    /// Enables mutual TLS with the given configuration.
+    pub fn with_tls(self, tls: EngineTls) -> Self {
+        Self { tls: Some(tls), ..self }
+    }
+
+    /// Signs the requests with the given signer.
+    pub fn with_signer(self, signer: RequestSigner) -> Self {
+        Self { signer: Some(signer), ..self }
+    }
+
+    /// Returns `true` if mutual TLS is enabled.
+    pub const fn tls_enabled(&self) -> bool {
+        self.tls.is_some()
+    }
+
+    /// Returns `true` if the requests are signed.
+    pub const fn signing_enabled(&self) -> bool {
+        self.signer.is_some()
+    }
+
+    /// Creates a provider for the given address, authenticated with the given JWT secret.
+    pub(crate) fn provider<N: Network>(&self, addr: Url, jwt: JwtSecret) -> RootProvider<N> {
+        RootProvider::new(self.rpc_client(addr, jwt))
+    }
+
+    /// Creates an RPC client for the given address, authenticated with the given JWT secret.
+    pub fn rpc_client(&self, addr: Url, jwt: JwtSecret) -> RpcClient {
+        match &self.tls {
+            Some(tls) => {
+                let connector = HttpsConnectorBuilder::new()
+                    .with_tls_config(tls.config.as_ref().clone())
+                    .https_or_http()
+                    .enable_http1()
+                    .build();
+                self.layered(Client::builder(TokioExecutor::new()).build(connector), addr, jwt)
+            }
+            None => self.layered(
+                Client::builder(TokioExecutor::new()).build_http::<Full<Bytes>>(),
+                addr,
+                jwt,
+            ),
+        }
+    }
+
+    /// Creates an RPC client for the given address over the given hyper client.
+    fn layered<C>(&self, client: Client<C, Full<Bytes>>, addr: Url, jwt: JwtSecret) -> RpcClient
+    where
+        C: Connect + Clone + Send + Sync + 'static,
+    {
+        let service = ServiceBuilder::new()
+            .layer(AuthLayer::new(jwt))
+            .layer(SigningLayer { signer: self.signer.clone() })
+            .service(client);
+        RpcClient::new(Http::with_client(HyperClient::with_service(service), addr), false)
+    }
+}
+
+/// The mutual TLS configuration of the [`EngineTransport`].
+#[derive(Clone)]
+pub struct EngineTls {
+    /// The rustls client configuration.
+    config: Arc<ClientConfig>,
+}
+
+impl Debug for EngineTls {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EngineTls").finish_non_exhaustive()
+    }
+}
+
+impl EngineTls {
+    /// Loads the client certificate chain and private key from the given PEM files.
+    ///
+    /// The server certificate is verified against the CA certificates of the given PEM bundle if
+    /// any, or against the Mozilla root certificates otherwise.
+    pub fn from_pem_files(
+        client_cert: &Path,
+        client_key: &Path,
+        ca_cert: Option<&Path>,
+    ) -> Result<Self, EngineTransportError> {
+        let certs = CertificateDer::pem_file_iter(client_cert)?.collect::<Result<Vec<_>, _>>()?;
+        let key = PrivateKeyDer::from_pem_file(client_key)?;
+
+        let builder =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()?;
+        let builder = match ca_cert {
+            Some(path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in CertificateDer::pem_file_iter(path)? {
+                    roots.add(cert?)?;
+                }
+                builder.with_root_certificates(roots)
+            }
+            None => builder.with_webpki_roots(),
+        };
+        let config = builder.with_client_auth_cert(certs, key)?;
+        Ok(Self { config: Arc::new(config) })
+    }
+}
+
+/// Signs the requests of the [`EngineTransport`] with HMAC-SHA256. See [`SIGNATURE_HEADER`].
+#[derive(Clone)]
+pub struct RequestSigner {
+    /// The shared signing key.
+    key: Arc<[u8]>,
+}
+
+impl Debug for RequestSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestSigner").finish_non_exhaustive()
+    }
+}
+
+impl RequestSigner {
+    /// Creates a new [`RequestSigner`] with the given key.
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self { key: key.into().into() }
+    }
+
+    /// Reads the hex-encoded key of the signer from the given file.
+    pub fn from_hex_file(path: &Path) -> Result<Self, EngineTransportError> {
+        let key = hex::decode(std::fs::read_to_string(path)?.trim())
+            .map_err(|_| EngineTransportError::InvalidSigningKey)?;
+        if key.is_empty() {
+            return Err(EngineTransportError::InvalidSigningKey);
+        }
+        Ok(Self::new(key))
+    }
+
+    /// Returns the value of the [`SIGNATURE_HEADER`] of a request with the given body, sent at
+    /// the given unix timestamp.
+    pub fn sign(&self, timestamp: u64, body: &[u8]) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        format!("t={timestamp},v1={}", hex::encode(mac.finalize().into_bytes()))
+    }
+}
+
+/// A [`Layer`] signing requests with an optional [`RequestSigner`].
+#[derive(Debug, Clone)]
+struct SigningLayer {
+    /// The signer, if requests are signed.
+    signer: Option<RequestSigner>,
+}
+
+impl<S> Layer<S> for SigningLayer {
+    type Service = SigningService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SigningService { inner, signer: self.signer.clone() }
+    }
+}
+
+/// The [`Service`] of the [`SigningLayer`].
+#[derive(Debug, Clone)]
+struct SigningService<S> {
+    /// The inner service.
+    inner: S,
+    /// The signer, if requests are signed.
+    signer: Option<RequestSigner>,
+}
+
+impl<S, ResBody> Service<Request<Full<Bytes>>> for SigningService<S>
+where
+    S: Service<Request<Full<Bytes>>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Full<Bytes>>) -> Self::Future {
+        let Some(signer) = self.signer.clone() else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        // Take the service that was driven to readiness, leaving a clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            let body = match body.collect().await {
+                Ok(collected) => collected.to_bytes(),
+                Err(never) => match never {},
+            };
+            let timestamp =
+                SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            let signature = HeaderValue::from_str(&signer.sign(timestamp, &body))
+                .expect("signature is a valid header value");
+            parts.headers.insert(SIGNATURE_HEADER, signature);
+            inner.call(Request::from_parts(parts, Full::new(body.into()))).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_signature_binds_timestamp_and_body() {
+        let signer = RequestSigner::new(b"secret".to_vec());
+        let signature = signer.sign(1_700_000_000, b"{}");
+        assert!(signature.starts_with("t=1700000000,v1="));
+        assert_eq!(signature, signer.sign(1_700_000_000, b"{}"));
+        assert_ne!(signature, signer.sign(1_700_000_001, b"{}"));
+        assert_ne!(signature, signer.sign(1_700_000_000, b"[]"));
+        assert_ne!(signature, RequestSigner::new(b"other".to_vec()).sign(1_700_000_000, b"{}"));
+    }
+
+    #[test]
+    fn test_signing_key_from_hex_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "0x0102").unwrap();
+        let signer = RequestSigner::from_hex_file(file.path()).unwrap();
+        assert_eq!(signer.sign(0, b""), RequestSigner::new([1u8, 2]).sign(0, b""));
+
+        for contents in ["", "not hex"] {
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            write!(file, "{contents}").unwrap();
+            assert!(matches!(
+                RequestSigner::from_hex_file(file.path()),
+                Err(EngineTransportError::InvalidSigningKey)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signing_service_signs_body() {
+        let signer = RequestSigner::new(b"secret".to_vec());
+        let inner = tower::service_fn(|request: Request<Full<Bytes>>| async move {
+            let header = request.headers().get(SIGNATURE_HEADER).cloned();
+            Ok::<_, std::convert::Infallible>(Response::new(header))
+        });
+
+        let mut signed = SigningLayer { signer: Some(signer.clone()) }.layer(inner);
+        let body = Bytes::from_static(b"{\"method\":\"engine_exchangeCapabilities\"}");
+        let response = signed.call(Request::new(Full::new(body.clone()))).await.unwrap();
+        let header = response.into_body().unwrap();
+        let header = header.to_str().unwrap();
+        let timestamp = header[2..header.find(',').unwrap()].parse().unwrap();
+        assert_eq!(header, signer.sign(timestamp, &body));
+
+        let mut unsigned = SigningLayer { signer: None }.layer(inner);
+        let response = unsigned.call(Request::new(Full::new(body))).await.unwrap();
+        assert!(response.into_body().is_none());
+    }
+}
@@ -37,7 +37,7 @@ impl EngineForkchoiceVersion {
 }
 
 /// The method version for the `engine_newPayload` api.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EngineNewPayloadVersion {
     /// The `engine_newPayload` api version 2.
     V2,
@@ -61,10 +61,19 @@ impl EngineNewPayloadVersion {
             Self::V2
         }
     }
+
+    /// Returns the name of the method of this version.
+    pub const fn method(&self) -> &'static str {
+        match self {
+            Self::V2 => "engine_newPayloadV2",
+            Self::V3 => "engine_newPayloadV3",
+            Self::V4 => "engine_newPayloadV4",
+        }
+    }
 }
 
 /// The method version for the `engine_getPayload` api.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EngineGetPayloadVersion {
     /// The `engine_getPayload` api version 2.
     V2,
@@ -88,4 +97,13 @@ impl EngineGetPayloadVersion {
             Self::V2
         }
     }
+
+    /// Returns the name of the method of this version.
+    pub const fn method(&self) -> &'static str {
+        match self {
+            Self::V2 => "engine_getPayloadV2",
+            Self::V3 => "engine_getPayloadV3",
+            Self::V4 => "engine_getPayloadV4",
+        }
+    }
 }
@@ -403,7 +403,7 @@ impl Discv5Driver {
                     _ = interval.tick() => {
                         let id = NodeId::random();
                         trace!(target: "discovery", "Finding random node: {}", id);
-                        kona_macros::inc!(gauge, crate::Metrics::FIND_NODE_REQUEST, "find_node" => "find_node");
+                        kona_macros::inc!(gauge, crate::Metrics::FIND_NODE_REQUEST);
                         let fut = self.disc.find_node(id);
                         let enr_sender = enr_sender.clone();
                         tokio::spawn(async move {
@@ -427,7 +427,7 @@ impl Discv5Driver {
                         self.store.sync();
                         let elapsed = start.elapsed();
                         debug!(target: "discovery", "Bootstore ENRs stored in {:?}", elapsed);
-                        kona_macros::record!(histogram, crate::Metrics::ENR_STORE_TIME, "store_time", "store_time", elapsed.as_secs_f64());
+                        kona_macros::record!(histogram, crate::Metrics::ENR_STORE_TIME, elapsed.as_secs_f64());
                         kona_macros::set!(gauge, crate::Metrics::DISCOVERY_PEER_COUNT, self.disc.connected_peers() as f64);
                     }
                     _ = removal_interval.tick() => {
@@ -0,0 +1,201 @@
+//! An archive of the gossip messages that failed validation.
+
+use crate::GossipValidationError;
+use alloy_primitives::Bytes;
+use libp2p::{PeerId, gossipsub::Message};
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The default maximum number of messages kept in the [`InvalidGossipArchive`].
+pub const DEFAULT_INVALID_GOSSIP_ARCHIVE_CAPACITY: usize = 64;
+
+/// The default maximum number of bytes of message data kept in the [`InvalidGossipArchive`].
+pub const DEFAULT_INVALID_GOSSIP_ARCHIVE_BYTES: usize = 8 * 1024 * 1024;
+
+/// A gossip message that failed validation, kept for offline analysis.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidGossipMessage {
+    /// The unix timestamp at which the message was received, in seconds.
+    pub received_at: u64,
+    /// The peer that propagated the message to the node.
+    pub peer: String,
+    /// The topic the message was received on.
+    pub topic: String,
+    /// The [kind](GossipValidationError::kind) of validation failure.
+    pub kind: String,
+    /// The validation error.
+    pub reason: String,
+    /// The size of the message data, in bytes.
+    pub size: usize,
+    /// The message data, truncated to the byte budget of the archive.
+    pub data: Bytes,
+}
+
+impl InvalidGossipMessage {
+    /// Creates a new [`InvalidGossipMessage`] from the given message, propagated by the given
+    /// peer, and the error it failed validation with. Only the first `max_bytes` of the message
+    /// data are copied.
+    pub fn new(
+        peer: &PeerId,
+        message: &Message,
+        err: &GossipValidationError,
+        max_bytes: usize,
+    ) -> Self {
+        let received_at =
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        Self {
+            received_at,
+            peer: peer.to_string(),
+            topic: message.topic.to_string(),
+            kind: err.kind().to_string(),
+            reason: err.to_string(),
+            size: message.data.len(),
+            data: Bytes::copy_from_slice(&message.data[..message.data.len().min(max_bytes)]),
+        }
+    }
+}
+
+/// A bounded archive of the gossip messages that failed validation, with the peers that
+/// propagated them.
+///
+/// The archive keeps at most `capacity` messages, and at most `max_bytes` of message data,
+/// evicting the oldest messages first. The data of a message larger than the whole byte budget
+/// is truncated.
+#[derive(Debug, Clone)]
+pub struct InvalidGossipArchive {
+    /// The maximum number of messages kept.
+    capacity: usize,
+    /// The maximum number of bytes of message data kept.
+    max_bytes: usize,
+    /// The archived messages, oldest first.
+    messages: VecDeque<InvalidGossipMessage>,
+    /// The number of bytes of message data kept.
+    bytes: usize,
+}
+
+impl Default for InvalidGossipArchive {
+    fn default() -> Self {
+        Self::new(DEFAULT_INVALID_GOSSIP_ARCHIVE_CAPACITY, DEFAULT_INVALID_GOSSIP_ARCHIVE_BYTES)
+    }
+}
+
+impl InvalidGossipArchive {
+    /// Creates a new [`InvalidGossipArchive`], keeping at most `capacity` messages and
+    /// `max_bytes` of message data. A capacity of `0` disables the archive.
+    pub const fn new(capacity: usize, max_bytes: usize) -> Self {
+        Self { capacity, max_bytes, messages: VecDeque::new(), bytes: 0 }
+    }
+
+    /// Archives the given message, evicting the oldest messages to stay within bounds.
+    pub fn record(&mut self, mut message: InvalidGossipMessage) {
+        if self.capacity == 0 {
+            return;
+        }
+        message.data.0.truncate(self.max_bytes);
+        let len = message.data.len();
+        while self.messages.len() >= self.capacity || self.bytes + len > self.max_bytes {
+            let Some(evicted) = self.messages.pop_front() else { break };
+            self.bytes -= evicted.data.len();
+        }
+        self.bytes += len;
+        self.messages.push_back(message);
+    }
+
+    /// Returns the archived messages, oldest first.
+    pub fn messages(&self) -> Vec<InvalidGossipMessage> {
+        self.messages.iter().cloned().collect()
+    }
+
+    /// Returns the number of archived messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if no message is archived.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns the number of bytes of message data kept.
+    pub const fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the maximum number of bytes of message data kept.
+    pub const fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(data: &[u8]) -> InvalidGossipMessage {
+        InvalidGossipMessage {
+            received_at: 0,
+            peer: PeerId::random().to_string(),
+            topic: "/optimism/10/1/blocks".to_string(),
+            kind: "decode".to_string(),
+            reason: "Failed to decode block".to_string(),
+            size: data.len(),
+            data: Bytes::copy_from_slice(data),
+        }
+    }
+
+    #[test]
+    fn test_archive_capacity() {
+        let mut archive = InvalidGossipArchive::new(2, 1024);
+        for byte in 1..=3 {
+            archive.record(message(&[byte; 4]));
+        }
+        let messages = archive.messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].data, Bytes::from_static(&[2; 4]));
+        assert_eq!(messages[1].data, Bytes::from_static(&[3; 4]));
+        assert_eq!(archive.bytes(), 8);
+    }
+
+    #[test]
+    fn test_archive_byte_budget() {
+        let mut archive = InvalidGossipArchive::new(8, 10);
+        archive.record(message(&[1; 4]));
+        archive.record(message(&[2; 4]));
+        archive.record(message(&[3; 4]));
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.bytes(), 8);
+
+        // A message larger than the whole budget is truncated, and evicts all others.
+        archive.record(message(&[4; 16]));
+        let messages = archive.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].size, 16);
+        assert_eq!(messages[0].data.len(), 10);
+        assert_eq!(archive.bytes(), 10);
+    }
+
+    #[test]
+    fn test_message_data_truncated_on_copy() {
+        let topic = libp2p::gossipsub::TopicHash::from_raw("/optimism/10/1/blocks");
+        let message = Message {
+            source: None,
+            data: vec![1; 16],
+            sequence_number: None,
+            topic: topic.clone(),
+        };
+        let err = GossipValidationError::UnknownTopic(topic);
+        let message = InvalidGossipMessage::new(&PeerId::random(), &message, &err, 10);
+        assert_eq!(message.size, 16);
+        assert_eq!(message.data, Bytes::from_static(&[1; 10]));
+    }
+
+    #[test]
+    fn test_archive_disabled() {
+        let mut archive = InvalidGossipArchive::new(0, 1024);
+        archive.record(message(&[1; 4]));
+        assert!(archive.is_empty());
+    }
+}
@@ -113,7 +113,17 @@ impl BlockHandler {
     ) -> Result<(), BlockInvalidError> {
         let current_timestamp =
             SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        self.block_valid_at(envelope, current_timestamp)
+    }
 
+    /// Determines if a block is valid, as if it was received at the given unix timestamp.
+    ///
+    /// See [`Self::block_valid`].
+    pub fn block_valid_at(
+        &mut self,
+        envelope: &OpNetworkPayloadEnvelope,
+        current_timestamp: u64,
+    ) -> Result<(), BlockInvalidError> {
         // The timestamp is at most 5 seconds in the future.
         let is_future = envelope.payload.timestamp() > current_timestamp + 5;
         // The timestamp is at most 60 seconds in the past.
@@ -169,19 +179,10 @@ impl BlockHandler {
             );
         }
 
-        // CHECK: The signature is valid.
-        let msg = envelope.payload_hash.signature_message(self.rollup_config.l2_chain_id);
+        // CHECK: The signature is valid, and by an expected signer, by default the unsafe block
+        // signer.
         let block_signer = *self.signer_recv.borrow();
-
-        // The block has a valid signature.
-        let Ok(msg_signer) = envelope.signature.recover_address_from_prehash(&msg) else {
-            return Err(BlockInvalidError::Signature);
-        };
-
-        // The block is signed by the expected signer (the unsafe block signer).
-        if msg_signer != block_signer {
-            return Err(BlockInvalidError::Signer { expected: msg_signer, received: block_signer });
-        }
+        self.signature_scheme.verify(self.rollup_config.l2_chain_id, envelope, block_signer)?;
 
         // Mark the block as seen.
         if self.seen_hashes.len() >= Self::SEEN_HASH_CACHE_SIZE {
@@ -7,11 +7,12 @@ use libp2p::{
     Multiaddr, StreamProtocol, SwarmBuilder, gossipsub::Config, identity::Keypair,
     noise::Config as NoiseConfig, tcp::Config as TcpConfig, yamux::Config as YamuxConfig,
 };
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::watch::{self};
 
 use crate::{
-    Behaviour, BlockHandler, GossipDriver, GossipDriverBuilderError, gossip::gater::GaterConfig,
+    Behaviour, BlockHandler, GossipDriver, GossipDriverBuilderError, InvalidGossipArchive,
+    SignatureScheme, gossip::gater::GaterConfig,
 };
 
 /// A builder for the [`GossipDriver`].
@@ -38,6 +39,12 @@ pub struct GossipDriverBuilder {
     gater_config: Option<GaterConfig>,
     /// Topic scoring. Disabled by default.
     topic_scoring: bool,
+    /// The [`SignatureScheme`] of the blocks. Defaults to the [`SingleSigner`] scheme.
+    ///
+    /// [`SingleSigner`]: crate::SingleSigner
+    signature_scheme: Option<Arc<dyn SignatureScheme>>,
+    /// The [`InvalidGossipArchive`] the messages failing validation are kept in.
+    invalid_messages: Option<InvalidGossipArchive>,
 }
 
 impl GossipDriverBuilder {
@@ -59,6 +66,8 @@ impl GossipDriverBuilder {
             gater_config: None,
             rollup_config,
             topic_scoring: false,
+            signature_scheme: None,
+            invalid_messages: None,
         }
     }
 
@@ -100,6 +109,21 @@ impl GossipDriverBuilder {
         self
     }
 
+    /// Sets the [`SignatureScheme`] the signatures of the blocks are verified with.
+    pub fn with_signature_scheme(mut self, scheme: Arc<dyn SignatureScheme>) -> Self {
+        self.signature_scheme = Some(scheme);
+        self
+    }
+
+    /// Sets the [`InvalidGossipArchive`] the gossip messages failing validation are kept in.
+    /// Defaults to an archive bounded by [`DEFAULT_INVALID_GOSSIP_ARCHIVE_CAPACITY`] messages.
+    ///
+    /// [`DEFAULT_INVALID_GOSSIP_ARCHIVE_CAPACITY`]: crate::DEFAULT_INVALID_GOSSIP_ARCHIVE_CAPACITY
+    pub fn with_invalid_message_archive(mut self, archive: InvalidGossipArchive) -> Self {
+        self.invalid_messages = Some(archive);
+        self
+    }
+
     /// Sets the [`Keypair`] for the node.
     pub fn with_keypair(mut self, keypair: Keypair) -> Self {
         self.keypair = keypair;
@@ -143,7 +167,10 @@ impl GossipDriverBuilder {
         let (signer_tx, signer_rx) = watch::channel(signer_recv);
 
         // Block Handler setup
-        let handler = BlockHandler::new(rollup_config, signer_rx);
+        let mut handler = BlockHandler::new(rollup_config, signer_rx);
+        if let Some(scheme) = self.signature_scheme.take() {
+            handler = handler.with_signature_scheme(scheme);
+        }
 
         // Construct the gossip behaviour
         let config = self.config.unwrap_or(crate::default_config());
@@ -215,6 +242,8 @@ impl GossipDriverBuilder {
         let gater_config = self.gater_config.take().unwrap_or_default();
         let gate = crate::ConnectionGater::new(gater_config);
 
-        Ok((GossipDriver::new(swarm, addr, handler, sync_handler, sync_protocol, gate), signer_tx))
+        let mut driver = GossipDriver::new(swarm, addr, handler, sync_handler, sync_protocol, gate);
+        driver.invalid_messages = self.invalid_messages.take().unwrap_or_default();
+        Ok((driver, signer_tx))
     }
 }
@@ -8,7 +8,7 @@ use kona_genesis::RollupConfig;
 use kona_peers::{EnrValidation, PeerMonitoring, enr_to_multiaddr};
 use libp2p::{
     Multiaddr, PeerId, Swarm, TransportError,
-    gossipsub::{IdentTopic, MessageId},
+    gossipsub::{IdentTopic, MessageAcceptance, MessageId},
     swarm::SwarmEvent,
 };
 use libp2p_identity::Keypair;
@@ -22,7 +22,8 @@ use std::{
 use tokio::sync::Mutex;
 
 use crate::{
-    Behaviour, BlockHandler, ConnectionGate, Event, GossipDriverBuilder, Handler, PublishError,
+    Behaviour, BlockHandler, ConnectionGate, Event, GossipDriverBuilder, Handler,
+    InvalidGossipArchive, InvalidGossipMessage, PublishError,
 };
 
 /// A driver for a [`Swarm`] instance.
@@ -60,6 +61,8 @@ pub struct GossipDriver<G: ConnectionGate> {
     pub connection_gate: G,
     /// Tracks ping times for peers.
     pub ping: Arc<Mutex<HashMap<PeerId, Duration>>>,
+    /// The gossip messages that failed validation, with the peers that propagated them.
+    pub invalid_messages: InvalidGossipArchive,
 }
 
 impl<G> GossipDriver<G>
@@ -96,6 +99,7 @@ where
             sync_protocol: Some(sync_protocol),
             connection_gate: gate,
             ping: Arc::new(Mutex::new(Default::default())),
+            invalid_messages: Default::default(),
         }
     }
 
@@ -200,7 +204,7 @@ where
 
         if self.swarm.connected_peers().any(|p| p == &peer_id) {
             debug!(target: "gossip", peer=?addr, "Already connected to peer, not dialing");
-            kona_macros::inc!(gauge, crate::Metrics::DIAL_PEER_ERROR, "type" => "already_connected", "peer" => peer_id.to_string());
+            kona_macros::inc!(gauge, crate::Metrics::DIAL_PEER_ERROR, "type" => "already_connected");
             return;
         }
 
@@ -212,12 +216,12 @@ where
             Ok(_) => {
                 trace!(target: "gossip", peer=?addr, "Dialed peer");
                 self.connection_gate.dialed(&addr);
-                kona_macros::inc!(gauge, crate::Metrics::DIAL_PEER, "peer" => peer_id.to_string());
+                kona_macros::inc!(gauge, crate::Metrics::DIAL_PEER);
             }
             Err(e) => {
                 error!(target: "gossip", "Failed to connect to peer: {:?}", e);
                 self.connection_gate.remove_dial(&peer_id);
-                kona_macros::inc!(gauge, crate::Metrics::DIAL_PEER_ERROR, "type" => "connection_error", "error" => e.to_string(), "peer" => peer_id.to_string());
+                kona_macros::inc!(gauge, crate::Metrics::DIAL_PEER_ERROR, "type" => "connection_error");
             }
         }
     }
@@ -240,13 +244,7 @@ where
 
                 // Record the peer score in the metrics if available.
                 if let Some(peer_score) = self.behaviour_mut().gossipsub.peer_score(&peer) {
-                    kona_macros::record!(
-                        histogram,
-                        crate::Metrics::PEER_SCORES,
-                        "peer",
-                        peer.to_string(),
-                        peer_score
-                    );
+                    kona_macros::record!(histogram, crate::Metrics::PEER_SCORES, peer_score);
                 }
 
                 let pings = Arc::clone(&self.ping);
@@ -298,7 +296,18 @@ where
                 trace!(target: "gossip", "Received message with topic: {}", message.topic);
                 kona_macros::inc!(gauge, crate::Metrics::GOSSIP_EVENT, "type" => "message", "topic" => message.topic.to_string());
                 if self.handler.topics().contains(&message.topic) {
-                    let (status, payload) = self.handler.handle(message);
+                    let (status, payload) = match self.handler.validate(&message) {
+                        Ok(envelope) => (MessageAcceptance::Accept, Some(envelope)),
+                        Err(err) if err.is_duplicate() => (err.into(), None),
+                        Err(err) => {
+                            warn!(target: "gossip", peer = ?src, %err, "Received invalid block");
+                            kona_macros::inc!(gauge, crate::Metrics::INVALID_GOSSIP_MESSAGES, "kind" => err.kind());
+                            let max_bytes = self.invalid_messages.max_bytes();
+                            self.invalid_messages
+                                .record(InvalidGossipMessage::new(&src, &message, &err, max_bytes));
+                            (err.into(), None)
+                        }
+                    };
                     _ = self
                         .swarm
                         .behaviour_mut()
@@ -317,11 +326,11 @@ where
             }
             libp2p::gossipsub::Event::SlowPeer { peer_id, .. } => {
                 trace!(target: "gossip", "Slow peer: {:?}", peer_id);
-                kona_macros::inc!(gauge, crate::Metrics::GOSSIP_EVENT, "type" => "slow_peer", "peer" => peer_id.to_string());
+                kona_macros::inc!(gauge, crate::Metrics::GOSSIP_EVENT, "type" => "slow_peer");
             }
             libp2p::gossipsub::Event::GossipsubNotSupported { peer_id } => {
                 trace!(target: "gossip", "Peer: {:?} does not support gossipsub", peer_id);
-                kona_macros::inc!(gauge, crate::Metrics::GOSSIP_EVENT, "type" => "not_supported", "peer" => peer_id.to_string());
+                kona_macros::inc!(gauge, crate::Metrics::GOSSIP_EVENT, "type" => "not_supported");
             }
         }
         None
@@ -340,28 +349,25 @@ where
                     gauge,
                     crate::Metrics::GOSSIPSUB_CONNECTION,
                     "type" => "connected",
-                    "peer" => peer_id.to_string(),
                 );
                 kona_macros::set!(gauge, crate::Metrics::GOSSIP_PEER_COUNT, peer_count as f64);
 
                 self.peer_connection_start.insert(peer_id, Instant::now());
             }
-            SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
+            SwarmEvent::OutgoingConnectionError { error, .. } => {
                 debug!(target: "gossip", "Outgoing connection error: {:?}", error);
                 kona_macros::inc!(
                     gauge,
                     crate::Metrics::GOSSIPSUB_CONNECTION,
-                    "type" => "outgoing_error",
-                    "peer" => peer_id.map(|p| p.to_string()).unwrap_or_default()
+                    "type" => "outgoing_error"
                 );
             }
-            SwarmEvent::IncomingConnectionError { error, connection_id, .. } => {
+            SwarmEvent::IncomingConnectionError { error, .. } => {
                 debug!(target: "gossip", "Incoming connection error: {:?}", error);
                 kona_macros::inc!(
                     gauge,
                     crate::Metrics::GOSSIPSUB_CONNECTION,
-                    "type" => "incoming_error",
-                    "connection_id" => connection_id.to_string()
+                    "type" => "incoming_error"
                 );
             }
             SwarmEvent::ConnectionClosed { peer_id, cause, .. } => {
@@ -370,8 +376,7 @@ where
                 kona_macros::inc!(
                     gauge,
                     crate::Metrics::GOSSIPSUB_CONNECTION,
-                    "type" => "closed",
-                    "peer" => peer_id.to_string()
+                    "type" => "closed"
                 );
                 kona_macros::set!(gauge, crate::Metrics::GOSSIP_PEER_COUNT, peer_count as f64);
 
@@ -387,13 +392,7 @@ where
 
                 // Record the peer score in the metrics if available.
                 if let Some(peer_score) = self.behaviour_mut().gossipsub.peer_score(&peer_id) {
-                    kona_macros::record!(
-                        histogram,
-                        crate::Metrics::PEER_SCORES,
-                        "peer",
-                        peer_id.to_string(),
-                        peer_score
-                    );
+                    kona_macros::record!(histogram, crate::Metrics::PEER_SCORES, peer_score);
                 }
 
                 let pings = Arc::clone(&self.ping);
@@ -1,7 +1,8 @@
 //! Contains the error from the gossip builder.
 
-use crate::BehaviourError;
+use crate::{BehaviourError, BlockInvalidError};
 use derive_more::From;
+use libp2p::gossipsub::MessageAcceptance;
 use thiserror::Error;
 
 /// An error publishing a payload.
@@ -26,6 +27,48 @@ pub enum HandlerEncodeError {
     UnknownTopic(libp2p::gossipsub::TopicHash),
 }
 
+/// An error raised when a gossiped block fails validation in the [`crate::BlockHandler`].
+#[derive(Debug, Error)]
+pub enum GossipValidationError {
+    /// The message was received on a topic that is not a block topic.
+    #[error("Unknown topic: {0}")]
+    UnknownTopic(libp2p::gossipsub::TopicHash),
+    /// The message could not be decoded as a payload envelope of the version of its topic.
+    #[error("Failed to decode block: {0}")]
+    Decode(#[from] op_alloy_rpc_types_engine::PayloadEnvelopeError),
+    /// The decoded block is invalid.
+    #[error(transparent)]
+    Invalid(#[from] BlockInvalidError),
+}
+
+impl GossipValidationError {
+    /// Returns the kind of the validation failure, used as a label.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::UnknownTopic(_) => "unknown_topic",
+            Self::Decode(_) => "decode",
+            Self::Invalid(BlockInvalidError::Signature | BlockInvalidError::Signer { .. }) => {
+                "signature"
+            }
+            Self::Invalid(_) => "invalid",
+        }
+    }
+
+    /// Returns `true` if the block was already seen, which does not make the message invalid.
+    pub const fn is_duplicate(&self) -> bool {
+        matches!(self, Self::Invalid(BlockInvalidError::BlockSeen { .. }))
+    }
+}
+
+impl From<GossipValidationError> for MessageAcceptance {
+    fn from(value: GossipValidationError) -> Self {
+        match value {
+            GossipValidationError::Invalid(err) => err.into(),
+            _ => Self::Reject,
+        }
+    }
+}
+
 /// An error type for the [`crate::GossipDriverBuilder`].
 #[derive(Debug, Clone, PartialEq, Eq, From, Error)]
 pub enum GossipDriverBuilderError {
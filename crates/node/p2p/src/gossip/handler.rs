@@ -1,11 +1,14 @@
 //! Block Handler
 
-use crate::HandlerEncodeError;
+use crate::{GossipValidationError, HandlerEncodeError, SignatureScheme, SingleSigner};
 use alloy_primitives::{Address, B256};
 use kona_genesis::RollupConfig;
 use libp2p::gossipsub::{IdentTopic, Message, MessageAcceptance, TopicHash};
 use op_alloy_rpc_types_engine::OpNetworkPayloadEnvelope;
-use std::collections::{BTreeMap, HashSet};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
 use tokio::sync::watch::Receiver;
 
 /// This trait defines the functionality required to process incoming messages
@@ -29,6 +32,8 @@ pub struct BlockHandler {
     pub rollup_config: RollupConfig,
     /// A [`Receiver`] to monitor changes to the unsafe block signer.
     pub signer_recv: Receiver<Address>,
+    /// The [`SignatureScheme`] the signatures of the blocks are verified with.
+    pub signature_scheme: Arc<dyn SignatureScheme>,
     /// The libp2p topic for pre Canyon/Shangai blocks.
     pub blocks_v1_topic: IdentTopic,
     /// The libp2p topic for Canyon/Delta blocks.
@@ -46,30 +51,11 @@ impl Handler for BlockHandler {
     /// Checks validity of a [`OpNetworkPayloadEnvelope`] received over P2P gossip.
     /// If valid, sends the [`OpNetworkPayloadEnvelope`] to the block update channel.
     fn handle(&mut self, msg: Message) -> (MessageAcceptance, Option<OpNetworkPayloadEnvelope>) {
-        let decoded = if msg.topic == self.blocks_v1_topic.hash() {
-            OpNetworkPayloadEnvelope::decode_v1(&msg.data)
-        } else if msg.topic == self.blocks_v2_topic.hash() {
-            OpNetworkPayloadEnvelope::decode_v2(&msg.data)
-        } else if msg.topic == self.blocks_v3_topic.hash() {
-            OpNetworkPayloadEnvelope::decode_v3(&msg.data)
-        } else if msg.topic == self.blocks_v4_topic.hash() {
-            OpNetworkPayloadEnvelope::decode_v4(&msg.data)
-        } else {
-            warn!(target: "gossip", topic = ?msg.topic, "Received block with unknown topic");
-            return (MessageAcceptance::Reject, None);
-        };
-
-        match decoded {
-            Ok(envelope) => match self.block_valid(&envelope) {
-                Ok(()) => (MessageAcceptance::Accept, Some(envelope)),
-                Err(err) => {
-                    warn!(target: "gossip", ?err, hash = ?envelope.payload_hash, "Received invalid block");
-                    (err.into(), None)
-                }
-            },
+        match self.validate(&msg) {
+            Ok(envelope) => (MessageAcceptance::Accept, Some(envelope)),
             Err(err) => {
-                warn!(target: "gossip", ?err, "Failed to decode block");
-                (MessageAcceptance::Reject, None)
+                warn!(target: "gossip", %err, "Received invalid block");
+                (err.into(), None)
             }
         }
     }
@@ -94,6 +80,7 @@ impl BlockHandler {
         Self {
             rollup_config,
             signer_recv,
+            signature_scheme: Arc::new(SingleSigner),
             blocks_v1_topic: IdentTopic::new(format!("/optimism/{}/0/blocks", chain_id)),
             blocks_v2_topic: IdentTopic::new(format!("/optimism/{}/1/blocks", chain_id)),
             blocks_v3_topic: IdentTopic::new(format!("/optimism/{}/2/blocks", chain_id)),
@@ -102,6 +89,34 @@ impl BlockHandler {
         }
     }
 
+    /// Sets the [`SignatureScheme`] the signatures of the blocks are verified with. Defaults to
+    /// the [`SingleSigner`] scheme.
+    pub fn with_signature_scheme(mut self, scheme: Arc<dyn SignatureScheme>) -> Self {
+        self.signature_scheme = scheme;
+        self
+    }
+
+    /// Decodes the block of the given message, according to the version of its topic, and
+    /// validates it.
+    pub fn validate(
+        &mut self,
+        msg: &Message,
+    ) -> Result<OpNetworkPayloadEnvelope, GossipValidationError> {
+        let envelope = if msg.topic == self.blocks_v1_topic.hash() {
+            OpNetworkPayloadEnvelope::decode_v1(&msg.data)?
+        } else if msg.topic == self.blocks_v2_topic.hash() {
+            OpNetworkPayloadEnvelope::decode_v2(&msg.data)?
+        } else if msg.topic == self.blocks_v3_topic.hash() {
+            OpNetworkPayloadEnvelope::decode_v3(&msg.data)?
+        } else if msg.topic == self.blocks_v4_topic.hash() {
+            OpNetworkPayloadEnvelope::decode_v4(&msg.data)?
+        } else {
+            return Err(GossipValidationError::UnknownTopic(msg.topic.clone()));
+        };
+        self.block_valid(&envelope)?;
+        Ok(envelope)
+    }
+
     /// Returns the topic using the specified timestamp and optional [`RollupConfig`].
     ///
     /// Reference: <https://github.com/ethereum-optimism/optimism/blob/0bc5fe8d16155dc68bcdf1fa5733abc58689a618/op-node/p2p/gossip.go#L604C1-L612C3>
@@ -223,6 +238,27 @@ mod tests {
         assert!(matches!(handler.handle(message).0, MessageAcceptance::Reject));
     }
 
+    #[test]
+    fn test_unknown_topic() {
+        let (_, unsafe_signer) = tokio::sync::watch::channel(Address::ZERO);
+        let mut handler = BlockHandler::new(
+            RollupConfig { l2_chain_id: 10, ..Default::default() },
+            unsafe_signer,
+        );
+        let message = Message {
+            source: None,
+            sequence_number: None,
+            topic: IdentTopic::new("/optimism/11/1/blocks").into(),
+            data: vec![0; 32],
+        };
+
+        let err = handler.validate(&message).unwrap_err();
+        assert!(matches!(err, GossipValidationError::UnknownTopic(_)));
+        assert_eq!(err.kind(), "unknown_topic");
+        assert!(!err.is_duplicate());
+        assert!(matches!(handler.handle(message).0, MessageAcceptance::Reject));
+    }
+
     /// The message contains a wrong version so the payload won't be properly decoded.
     #[test]
     fn test_invalid_decode_version_mismatch() {
@@ -25,7 +25,9 @@ mod builder;
 pub use builder::GossipDriverBuilder;
 
 mod error;
-pub use error::{GossipDriverBuilderError, HandlerEncodeError, PublishError};
+pub use error::{
+    GossipDriverBuilderError, GossipValidationError, HandlerEncodeError, PublishError,
+};
 
 mod event;
 pub use event::Event;
@@ -39,5 +41,14 @@ pub use driver::GossipDriver;
 mod block_validity;
 pub use block_validity::BlockInvalidError;
 
+mod archive;
+pub use archive::{
+    DEFAULT_INVALID_GOSSIP_ARCHIVE_BYTES, DEFAULT_INVALID_GOSSIP_ARCHIVE_CAPACITY,
+    InvalidGossipArchive, InvalidGossipMessage,
+};
+
+mod signature;
+pub use signature::{AuthorizedSigners, SignatureScheme, SingleSigner};
+
 #[cfg(test)]
 pub(crate) use block_validity::tests::*;
@@ -0,0 +1,155 @@
+//! Signature schemes of the blocks gossiped by the network.
+
+use alloy_primitives::{Address, Signature};
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use op_alloy_rpc_types_engine::{OpNetworkPayloadEnvelope, PayloadHash};
+use std::{collections::HashSet, fmt::Debug};
+
+use super::BlockInvalidError;
+
+/// The scheme the blocks gossiped by the network are signed and verified with.
+///
+/// The standard scheme is [`SingleSigner`]: a block is signed by the unsafe block signer of the
+/// system config, with a secp256k1 signature over the
+/// [signature message](PayloadHash::signature_message) of its payload hash. Custom chains may
+/// plug in another scheme, e.g. [`AuthorizedSigners`] to accept blocks from several sequencers.
+pub trait SignatureScheme: Debug + Send + Sync {
+    /// Signs the payload with the given hash, for the chain with the given ID, with the local
+    /// signer.
+    fn sign(
+        &self,
+        chain_id: u64,
+        payload_hash: &PayloadHash,
+        signer: &PrivateKeySigner,
+    ) -> alloy_signer::Result<Signature> {
+        signer.sign_hash_sync(&payload_hash.signature_message(chain_id))
+    }
+
+    /// Verifies the signature of the given envelope, for the chain with the given ID, given the
+    /// current unsafe block signer of the system config.
+    fn verify(
+        &self,
+        chain_id: u64,
+        envelope: &OpNetworkPayloadEnvelope,
+        unsafe_signer: Address,
+    ) -> Result<(), BlockInvalidError>;
+}
+
+/// Recovers the signer of the given envelope.
+fn recover_signer(
+    chain_id: u64,
+    envelope: &OpNetworkPayloadEnvelope,
+) -> Result<Address, BlockInvalidError> {
+    let msg = envelope.payload_hash.signature_message(chain_id);
+    envelope.signature.recover_address_from_prehash(&msg).map_err(|_| BlockInvalidError::Signature)
+}
+
+/// The standard [`SignatureScheme`]: blocks must be signed by the unsafe block signer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SingleSigner;
+
+impl SignatureScheme for SingleSigner {
+    fn verify(
+        &self,
+        chain_id: u64,
+        envelope: &OpNetworkPayloadEnvelope,
+        unsafe_signer: Address,
+    ) -> Result<(), BlockInvalidError> {
+        let msg_signer = recover_signer(chain_id, envelope)?;
+        if msg_signer != unsafe_signer {
+            return Err(BlockInvalidError::Signer { expected: msg_signer, received: unsafe_signer });
+        }
+        Ok(())
+    }
+}
+
+/// A [`SignatureScheme`] accepting blocks signed by the unsafe block signer or by any of a set of
+/// additional authorized signers, e.g. the sequencers of a chain with sequencer rotation.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AuthorizedSigners {
+    /// The signers authorized in addition to the unsafe block signer.
+    pub signers: HashSet<Address>,
+}
+
+impl AuthorizedSigners {
+    /// Creates a new [`AuthorizedSigners`] scheme, authorizing the given signers in addition to
+    /// the unsafe block signer.
+    pub fn new(signers: impl IntoIterator<Item = Address>) -> Self {
+        Self { signers: signers.into_iter().collect() }
+    }
+}
+
+impl SignatureScheme for AuthorizedSigners {
+    fn verify(
+        &self,
+        chain_id: u64,
+        envelope: &OpNetworkPayloadEnvelope,
+        unsafe_signer: Address,
+    ) -> Result<(), BlockInvalidError> {
+        let msg_signer = recover_signer(chain_id, envelope)?;
+        if msg_signer != unsafe_signer && !self.signers.contains(&msg_signer) {
+            return Err(BlockInvalidError::Signer { expected: msg_signer, received: unsafe_signer });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gossip::v2_valid_block;
+    use alloy_primitives::B256;
+    use alloy_rpc_types_engine::ExecutionPayloadV2;
+    use op_alloy_rpc_types_engine::OpExecutionPayload;
+
+    fn signer(byte: u8) -> PrivateKeySigner {
+        PrivateKeySigner::from_bytes(&B256::repeat_byte(byte)).unwrap()
+    }
+
+    fn envelope(
+        signer: &PrivateKeySigner,
+        scheme: &dyn SignatureScheme,
+    ) -> OpNetworkPayloadEnvelope {
+        let payload_hash = PayloadHash(B256::repeat_byte(1));
+        OpNetworkPayloadEnvelope {
+            payload: OpExecutionPayload::V2(ExecutionPayloadV2::from_block_slow(&v2_valid_block())),
+            signature: scheme.sign(10, &payload_hash, signer).unwrap(),
+            payload_hash,
+            parent_beacon_block_root: None,
+        }
+    }
+
+    #[test]
+    fn test_single_signer_round_trip() {
+        let signer = signer(1);
+        let envelope = envelope(&signer, &SingleSigner);
+        assert!(SingleSigner.verify(10, &envelope, signer.address()).is_ok());
+
+        // The signature is bound to the chain ID.
+        assert!(matches!(
+            SingleSigner.verify(11, &envelope, signer.address()),
+            Err(BlockInvalidError::Signer { .. })
+        ));
+        assert!(matches!(
+            SingleSigner.verify(10, &envelope, Address::random()),
+            Err(BlockInvalidError::Signer { .. })
+        ));
+    }
+
+    #[test]
+    fn test_authorized_signers() {
+        let (unsafe_signer, rotated, other) = (signer(1), signer(2), signer(3));
+        let scheme = AuthorizedSigners::new([rotated.address()]);
+
+        for signer in [&unsafe_signer, &rotated] {
+            let envelope = envelope(signer, &scheme);
+            assert!(scheme.verify(10, &envelope, unsafe_signer.address()).is_ok());
+        }
+        let envelope = envelope(&other, &scheme);
+        assert!(matches!(
+            scheme.verify(10, &envelope, unsafe_signer.address()),
+            Err(BlockInvalidError::Signer { .. })
+        ));
+    }
+}
@@ -24,12 +24,14 @@ pub use rpc::{
 
 mod gossip;
 pub use gossip::{
-    Behaviour, BehaviourError, BlockHandler, BlockInvalidError, ConnectionGate, ConnectionGater,
+    AuthorizedSigners, Behaviour, BehaviourError, BlockHandler, BlockInvalidError, ConnectionGate,
+    ConnectionGater, DEFAULT_INVALID_GOSSIP_ARCHIVE_BYTES, DEFAULT_INVALID_GOSSIP_ARCHIVE_CAPACITY,
     DEFAULT_MESH_D, DEFAULT_MESH_DHI, DEFAULT_MESH_DLAZY, DEFAULT_MESH_DLO, DialInfo, Event,
     GLOBAL_VALIDATE_THROTTLE, GOSSIP_HEARTBEAT, GaterConfig, GossipDriver, GossipDriverBuilder,
-    GossipDriverBuilderError, Handler, HandlerEncodeError, MAX_GOSSIP_SIZE, MAX_OUTBOUND_QUEUE,
+    GossipDriverBuilderError, GossipValidationError, Handler, HandlerEncodeError,
+    InvalidGossipArchive, InvalidGossipMessage, MAX_GOSSIP_SIZE, MAX_OUTBOUND_QUEUE,
     MAX_VALIDATE_QUEUE, MIN_GOSSIP_SIZE, PEER_SCORE_INSPECT_FREQUENCY, PublishError,
-    SEEN_MESSAGES_TTL, default_config, default_config_builder,
+    SEEN_MESSAGES_TTL, SignatureScheme, SingleSigner, default_config, default_config_builder,
 };
 
 mod discv5;
@@ -1,6 +1,9 @@
 //! Metrics for the P2P stack.
 
 /// Container for metrics.
+///
+/// Labels only take a bounded set of values, such as event types or topics, so that the number of
+/// series does not grow with the number of peers seen.
 #[derive(Debug, Clone)]
 pub struct Metrics;
 
@@ -51,6 +54,9 @@ impl Metrics {
     pub const GOSSIP_PEER_CONNECTION_DURATION_SECONDS: &str =
         "kona_node_gossip_peer_connection_duration_seconds";
 
+    /// Identifier for the gauge that tracks gossip messages that failed validation.
+    pub const INVALID_GOSSIP_MESSAGES: &str = "kona_node_invalid_gossip_messages";
+
     /// Initializes metrics for the P2P stack.
     ///
     /// This does two things:
@@ -105,6 +111,10 @@ impl Metrics {
             Self::GOSSIP_PEER_CONNECTION_DURATION_SECONDS,
             "Duration of peer connections in seconds"
         );
+        metrics::describe_gauge!(
+            Self::INVALID_GOSSIP_MESSAGES,
+            "Gossip messages that failed validation, by kind of failure"
+        );
     }
 
     /// Initializes metrics to `0` so they can be queried immediately by consumers of prometheus
@@ -129,6 +139,7 @@ impl Metrics {
         kona_macros::set!(gauge, Self::RPC_CALLS, "method", "opp2p_unprotectPeer", 0);
         kona_macros::set!(gauge, Self::RPC_CALLS, "method", "opp2p_connectPeer", 0);
         kona_macros::set!(gauge, Self::RPC_CALLS, "method", "opp2p_disconnectPeer", 0);
+        kona_macros::set!(gauge, Self::RPC_CALLS, "method", "admin_invalidGossipMessages", 0);
 
         // Gossip Events
         kona_macros::set!(gauge, Self::GOSSIP_EVENT, "type", "message", 0);
@@ -169,5 +180,11 @@ impl Metrics {
 
         // Banned Peers
         kona_macros::set!(gauge, Self::BANNED_PEERS, 0);
+
+        // Invalid Gossip Messages
+        kona_macros::set!(gauge, Self::INVALID_GOSSIP_MESSAGES, "kind", "unknown_topic", 0);
+        kona_macros::set!(gauge, Self::INVALID_GOSSIP_MESSAGES, "kind", "decode", 0);
+        kona_macros::set!(gauge, Self::INVALID_GOSSIP_MESSAGES, "kind", "signature", 0);
+        kona_macros::set!(gauge, Self::INVALID_GOSSIP_MESSAGES, "kind", "invalid", 0);
     }
 }
@@ -7,12 +7,13 @@ use kona_genesis::RollupConfig;
 use kona_peers::{PeerMonitoring, PeerScoreLevel};
 use libp2p::{Multiaddr, identity::Keypair};
 use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
-use std::{path::PathBuf, time::Duration};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 use tokio::sync::broadcast::Sender as BroadcastSender;
 
 use crate::{
-    Broadcast, Config, Discv5Builder, GossipDriverBuilder, Network, NetworkBuilderError,
-    P2pRpcRequest, discv5::LocalNode, gossip::GaterConfig,
+    Broadcast, Config, Discv5Builder, GossipDriverBuilder, InvalidGossipArchive, Network,
+    NetworkBuilderError, P2pRpcRequest, SignatureScheme, SingleSigner, discv5::LocalNode,
+    gossip::GaterConfig,
 };
 
 /// Constructs a [`Network`] for the OP Stack Consensus Layer.
@@ -28,6 +29,8 @@ pub struct NetworkBuilder {
     payload_tx: Option<BroadcastSender<OpExecutionPayloadEnvelope>>,
     /// A local signer for payloads.
     local_signer: Option<PrivateKeySigner>,
+    /// The [`SignatureScheme`] payloads are signed and verified with.
+    signature_scheme: Option<Arc<dyn SignatureScheme>>,
 }
 
 impl From<Config> for NetworkBuilder {
@@ -50,6 +53,7 @@ impl From<Config> for NetworkBuilder {
         .with_topic_scoring(config.topic_scoring)
         .with_gater_config(config.gater_config)
         .with_local_signer(config.local_signer)
+        .with_signature_scheme(config.signature_scheme)
     }
 }
 
@@ -78,6 +82,7 @@ impl NetworkBuilder {
             rpc_recv: None,
             payload_tx: None,
             local_signer: None,
+            signature_scheme: None,
         }
     }
 
@@ -91,6 +96,21 @@ impl NetworkBuilder {
         Self { local_signer, ..self }
     }
 
+    /// Sets the [`SignatureScheme`] the payloads of the [`Network`] are signed and verified with.
+    /// Defaults to the [`SingleSigner`] scheme.
+    pub fn with_signature_scheme(self, scheme: Arc<dyn SignatureScheme>) -> Self {
+        Self {
+            gossip: self.gossip.with_signature_scheme(scheme.clone()),
+            signature_scheme: Some(scheme),
+            ..self
+        }
+    }
+
+    /// Sets the [`InvalidGossipArchive`] the gossip messages failing validation are kept in.
+    pub fn with_invalid_message_archive(self, archive: InvalidGossipArchive) -> Self {
+        Self { gossip: self.gossip.with_invalid_message_archive(archive), ..self }
+    }
+
     /// Sets the bootstore path for the [`crate::Discv5Driver`].
     pub fn with_bootstore(self, bootstore: Option<PathBuf>) -> Self {
         if let Some(bootstore) = bootstore {
@@ -183,6 +203,7 @@ impl NetworkBuilder {
             broadcast: Broadcast::new(payload_tx),
             publish_rx,
             local_signer: self.local_signer,
+            signature_scheme: self.signature_scheme.unwrap_or_else(|| Arc::new(SingleSigner)),
         })
     }
 }
@@ -1,13 +1,13 @@
 //! Configuration for the `Network`.
 
-use crate::{discv5::LocalNode, gossip::GaterConfig};
+use crate::{SignatureScheme, SingleSigner, discv5::LocalNode, gossip::GaterConfig};
 use alloy_primitives::Address;
 use alloy_signer_local::PrivateKeySigner;
 use discv5::Enr;
 use kona_genesis::RollupConfig;
 use kona_peers::{PeerMonitoring, PeerScoreLevel};
 use libp2p::{Multiaddr, identity::Keypair};
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 use tokio::time::Duration;
 
 /// Configuration for kona's P2P stack.
@@ -46,6 +46,8 @@ pub struct Config {
     pub rollup_config: RollupConfig,
     /// A local signer for payloads.
     pub local_signer: Option<PrivateKeySigner>,
+    /// The [`SignatureScheme`] payloads are signed and verified with.
+    pub signature_scheme: Arc<dyn SignatureScheme>,
 }
 
 impl Config {
@@ -77,6 +79,7 @@ impl Config {
             topic_scoring: Default::default(),
             monitor_peers: Default::default(),
             local_signer: Default::default(),
+            signature_scheme: Arc::new(SingleSigner),
         }
     }
 }
@@ -1,13 +1,13 @@
 //! Driver for network services.
 
 use alloy_primitives::{Address, hex};
-use alloy_signer::SignerSync;
 use alloy_signer_local::PrivateKeySigner;
 use futures::{AsyncReadExt, AsyncWriteExt, StreamExt};
+use kona_protocol::CorrelationId;
 use libp2p::TransportError;
 use libp2p_stream::IncomingStreams;
 use op_alloy_rpc_types_engine::{OpExecutionPayloadEnvelope, OpNetworkPayloadEnvelope};
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 use tokio::{
     select,
     sync::{broadcast::Receiver as BroadcastReceiver, watch::Sender},
@@ -16,6 +16,7 @@ use tokio::{
 
 use crate::{
     Broadcast, Config, Discv5Driver, GossipDriver, HandlerRequest, NetworkBuilder, P2pRpcRequest,
+    SignatureScheme,
 };
 
 /// Network
@@ -43,6 +44,8 @@ pub struct Network {
     pub discovery: Discv5Driver,
     /// The local signer for unsigned payloads.
     pub local_signer: Option<PrivateKeySigner>,
+    /// The [`SignatureScheme`] unsigned payloads are signed with.
+    pub signature_scheme: Arc<dyn SignatureScheme>,
 }
 
 impl Network {
@@ -139,13 +142,17 @@ impl Network {
                             warn!(target: "net", "No local signer available to sign the payload");
                             continue;
                         };
-                        use ssz::Encode;
-                        let ssz_bytes = block.as_ssz_bytes();
-                        let Ok(signature) = signer.sign_message_sync(&ssz_bytes) else {
-                            warn!(target: "net", "Failed to sign the payload bytes");
+                        let payload_hash = block.payload_hash();
+                        let chain_id = self.gossip.handler.rollup_config.l2_chain_id;
+                        let Ok(signature) =
+                            self.signature_scheme.sign(chain_id, &payload_hash, signer)
+                        else {
+                            warn!(target: "net", "Failed to sign the payload hash");
                             continue;
                         };
-                        let payload_hash = block.payload_hash();
+                        let number = block.payload.block_number();
+                        let correlation_id =
+                            CorrelationId::new(block.payload.parent_hash(), timestamp);
                         let payload = OpNetworkPayloadEnvelope {
                             payload: block.payload,
                             signature,
@@ -153,8 +160,8 @@ impl Network {
                             parent_beacon_block_root: block.parent_beacon_block_root,
                         };
                         match self.gossip.publish(selector, Some(payload)) {
-                            Ok(id) => info!("Published unsafe payload | {:?}", id),
-                            Err(e) => warn!("Failed to publish unsafe payload: {:?}", e),
+                            Ok(id) => info!(target: "net", number, %correlation_id, "Published unsafe payload | {:?}", id),
+                            Err(e) => warn!(target: "net", number, %correlation_id, "Failed to publish unsafe payload: {:?}", e),
                         }
                     }
                     event = self.gossip.next() => {
@@ -190,7 +197,7 @@ impl Network {
                                 let score = self.gossip.swarm.behaviour().gossipsub.peer_score(peer_id).unwrap_or_default();
 
                                 // Record the peer score in the metrics.
-                                kona_macros::record!(histogram, crate::Metrics::PEER_SCORES, "peer", peer_id.to_string(), score);
+                                kona_macros::record!(histogram, crate::Metrics::PEER_SCORES, score);
 
                                 if score < ban_peers.ban_threshold {
                                    return Some(*peer_id);
@@ -222,8 +229,7 @@ impl Network {
                             if let Some(info) = self.gossip.peerstore.remove(&peer_to_remove){
                                 use crate::ConnectionGate;
                                 self.gossip.connection_gate.remove_dial(&peer_to_remove);
-                                let score = self.gossip.swarm.behaviour().gossipsub.peer_score(&peer_to_remove).unwrap_or_default();
-                                kona_macros::inc!(gauge, crate::Metrics::BANNED_PEERS, "peer_id" => peer_to_remove.to_string(), "score" => score.to_string());
+                                kona_macros::inc!(gauge, crate::Metrics::BANNED_PEERS);
                                 return Some(info.listen_addrs);
                             }
 
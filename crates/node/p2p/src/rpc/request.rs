@@ -7,7 +7,7 @@ use std::{
     sync::Arc,
 };
 
-use crate::{Discv5Handler, GossipDriver, GossipScores};
+use crate::{Discv5Handler, GossipDriver, GossipScores, InvalidGossipMessage};
 use alloy_primitives::map::foldhash::fast::RandomState;
 use discv5::{
     enr::{NodeId, k256::ecdsa},
@@ -113,6 +113,9 @@ pub enum P2pRpcRequest {
     /// This information can be used to briefly monitor the current state of the p2p network for a
     /// given peer.
     PeerStats(Sender<PeerStats>),
+    /// Returns the gossip messages that failed validation, archived by the
+    /// [`crate::GossipDriver`].
+    InvalidGossipMessages(Sender<Vec<InvalidGossipMessage>>),
 }
 
 impl P2pRpcRequest {
@@ -137,6 +140,7 @@ impl P2pRpcRequest {
             Self::BlockSubnet { address } => Self::block_subnet(address, gossip),
             Self::UnblockSubnet { address } => Self::unblock_subnet(address, gossip),
             Self::ListBlockedSubnets(s) => Self::list_blocked_subnets(s, gossip),
+            Self::InvalidGossipMessages(s) => Self::invalid_gossip_messages(s, gossip),
             Self::PostUnsafePayload { payload } => {
                 // Unsafe payload handling happens in the network driver.
                 // This must never be reached.
@@ -221,6 +225,15 @@ impl P2pRpcRequest {
         }
     }
 
+    fn invalid_gossip_messages<G: ConnectionGate>(
+        s: Sender<Vec<InvalidGossipMessage>>,
+        gossip: &GossipDriver<G>,
+    ) {
+        if let Err(e) = s.send(gossip.invalid_messages.messages()) {
+            warn!(target: "p2p::rpc", "Failed to send invalid gossip messages through response channel: {} messages", e.len());
+        }
+    }
+
     fn handle_discovery_table(sender: Sender<Vec<String>>, disc: &Discv5Handler) {
         let enrs = disc.table_enrs();
         tokio::spawn(async move {
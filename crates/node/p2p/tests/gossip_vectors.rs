@@ -0,0 +1,164 @@
+//! Test vectors of signed, snappy-compressed gossip block messages.
+//!
+//! The vectors in `testdata/gossip` are laid out in the wire format of the `op-node` block topics:
+//! `snappy(signature ++ [parent_beacon_block_root] ++ ssz(payload))`, with the 65-byte signature
+//! of the unsafe block signer over the payload hash. Each vector is checked against the layout
+//! directly, decoded and validated by the [`BlockHandler`], and re-encoded.
+
+use alloy_primitives::{Address, B256, Bytes, Signature, keccak256};
+use kona_genesis::{HardForkConfig, RollupConfig};
+use kona_p2p::{BlockHandler, BlockInvalidError};
+use libp2p::gossipsub::IdentTopic;
+use op_alloy_rpc_types_engine::{OpNetworkPayloadEnvelope, PayloadEnvelopeError, PayloadHash};
+use serde::Deserialize;
+
+/// A signed gossip block message.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GossipVector {
+    /// The topic the message is published on.
+    topic: String,
+    /// The L2 chain id the message is signed for.
+    chain_id: u64,
+    /// The address of the unsafe block signer.
+    signer: Address,
+    /// The timestamp of the block.
+    timestamp: u64,
+    /// The number of the block.
+    block_number: u64,
+    /// The hash of the block.
+    block_hash: B256,
+    /// The payload hash signed by the unsafe block signer.
+    payload_hash: B256,
+    /// The parent beacon block root, on the V3 and V4 topics.
+    parent_beacon_block_root: Option<B256>,
+    /// The number of transactions in the block.
+    transactions: usize,
+    /// The snappy-compressed message.
+    message: Bytes,
+}
+
+/// Returns the vectors, along with the version of the block topic they are published on.
+fn vectors() -> Vec<(u8, GossipVector)> {
+    [
+        (2, include_str!("../testdata/gossip/blocks_v2.json")),
+        (3, include_str!("../testdata/gossip/blocks_v3.json")),
+        (4, include_str!("../testdata/gossip/blocks_v4.json")),
+    ]
+    .into_iter()
+    .map(|(version, json)| (version, serde_json::from_str(json).unwrap()))
+    .collect()
+}
+
+/// Returns a [`BlockHandler`] for the chain of the vector, with the hardforks of the topic version
+/// active, that accepts blocks from the signer of the vector.
+fn handler(version: u8, vector: &GossipVector) -> BlockHandler {
+    let hardforks = HardForkConfig {
+        canyon_time: Some(0),
+        ecotone_time: (version >= 3).then_some(0),
+        isthmus_time: (version >= 4).then_some(0),
+        ..Default::default()
+    };
+    let config = RollupConfig { l2_chain_id: vector.chain_id, hardforks, ..Default::default() };
+    let (_, signer) = tokio::sync::watch::channel(vector.signer);
+    BlockHandler::new(config, signer)
+}
+
+/// Decodes a message published on the block topic of the given version.
+fn decode(version: u8, data: &[u8]) -> Result<OpNetworkPayloadEnvelope, PayloadEnvelopeError> {
+    match version {
+        2 => OpNetworkPayloadEnvelope::decode_v2(data),
+        3 => OpNetworkPayloadEnvelope::decode_v3(data),
+        _ => OpNetworkPayloadEnvelope::decode_v4(data),
+    }
+}
+
+#[test]
+fn test_vectors_wire_layout() {
+    for (version, vector) in vectors() {
+        let data = snap::raw::Decoder::new().decompress_vec(&vector.message).unwrap();
+        let (signature, rest) = data.split_at(65);
+        match (version, vector.parent_beacon_block_root) {
+            (2, None) => {}
+            (3 | 4, Some(root)) => assert_eq!(&rest[..32], root.as_slice()),
+            _ => panic!("v{version}: unexpected parent beacon block root"),
+        }
+        assert_eq!(keccak256(rest), vector.payload_hash);
+
+        let signature = Signature::try_from(signature).unwrap();
+        let msg = PayloadHash(vector.payload_hash).signature_message(vector.chain_id);
+        assert_eq!(signature.recover_address_from_prehash(&msg).unwrap(), vector.signer);
+    }
+}
+
+#[test]
+fn test_vectors_decode_and_validate() {
+    for (version, vector) in vectors() {
+        let mut handler = handler(version, &vector);
+        assert_eq!(
+            handler.topic(vector.timestamp).hash(),
+            IdentTopic::new(&vector.topic).hash(),
+            "v{version}"
+        );
+
+        let envelope = decode(version, &vector.message).unwrap();
+        assert_eq!(envelope.payload_hash.0, vector.payload_hash);
+        assert_eq!(envelope.parent_beacon_block_root, vector.parent_beacon_block_root);
+        assert_eq!(envelope.payload.block_number(), vector.block_number);
+        assert_eq!(envelope.payload.block_hash(), vector.block_hash);
+        assert_eq!(envelope.payload.as_v1().transactions.len(), vector.transactions);
+
+        handler.block_valid_at(&envelope, vector.timestamp).unwrap();
+    }
+}
+
+#[test]
+fn test_vectors_roundtrip() {
+    for (version, vector) in vectors() {
+        let handler = handler(version, &vector);
+        let envelope = decode(version, &vector.message).unwrap();
+
+        let encoded = handler.encode(handler.topic(vector.timestamp), envelope.clone()).unwrap();
+        // Snappy encoders are free to pick different compressions of the same data, so the
+        // messages are compared once decompressed.
+        let mut decoder = snap::raw::Decoder::new();
+        assert_eq!(
+            decoder.decompress_vec(&encoded).unwrap(),
+            decoder.decompress_vec(&vector.message).unwrap(),
+            "v{version}"
+        );
+        assert_eq!(decode(version, &encoded).unwrap(), envelope);
+    }
+}
+
+#[test]
+fn test_vectors_reject_tampering() {
+    for (version, vector) in vectors() {
+        // A message signed by another signer.
+        let mut handler = handler(version, &vector);
+        handler.signer_recv = tokio::sync::watch::channel(Address::ZERO).1;
+        let envelope = decode(version, &vector.message).unwrap();
+        let err = handler.block_valid_at(&envelope, vector.timestamp).unwrap_err();
+        assert!(matches!(err, BlockInvalidError::Signer { .. }), "v{version}: {err}");
+
+        // A message whose payload was altered after signing.
+        let mut data = snap::raw::Decoder::new().decompress_vec(&vector.message).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        let message = snap::raw::Encoder::new().compress_vec(&data).unwrap();
+        let mut handler = self::handler(version, &vector);
+        if let Ok(envelope) = decode(version, &message) {
+            assert_ne!(envelope.payload_hash.0, vector.payload_hash);
+            assert!(handler.block_valid_at(&envelope, vector.timestamp).is_err(), "v{version}");
+        }
+
+        // A message decoded as published on another topic.
+        let other = if version == 2 { 3 } else { 2 };
+        assert!(
+            decode(other, &vector.message).map_or(true, |envelope| handler
+                .block_valid_at(&envelope, vector.timestamp)
+                .is_err()),
+            "v{version} as v{other}"
+        );
+    }
+}
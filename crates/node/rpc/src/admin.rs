@@ -1,12 +1,12 @@
 //! Admin RPC Module
 
-use crate::{AdminApiServer, NetworkRpc};
+use crate::{AdminApiServer, GossipArchiveApiServer, NetworkRpc};
 use async_trait::async_trait;
 use jsonrpsee::{
     core::RpcResult,
     types::{ErrorCode, ErrorObject},
 };
-use kona_p2p::P2pRpcRequest;
+use kona_p2p::{InvalidGossipMessage, P2pRpcRequest};
 use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
 
 #[async_trait]
@@ -22,3 +22,16 @@ impl AdminApiServer for NetworkRpc {
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))
     }
 }
+
+#[async_trait]
+impl GossipArchiveApiServer for NetworkRpc {
+    async fn admin_invalid_gossip_messages(&self) -> RpcResult<Vec<InvalidGossipMessage>> {
+        kona_macros::inc!(gauge, kona_p2p::Metrics::RPC_CALLS, "method" => "admin_invalidGossipMessages");
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.sender
+            .send(P2pRpcRequest::InvalidGossipMessages(tx))
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?;
+        rx.await.map_err(|_| ErrorObject::from(ErrorCode::InternalError))
+    }
+}
@@ -2,7 +2,7 @@
 
 use jsonrpsee::RpcModule;
 
-use crate::RpcLauncher;
+use crate::{BlockLabelSource, RpcLauncher};
 use std::{net::SocketAddr, path::PathBuf};
 
 /// The RPC configuration.
@@ -21,6 +21,11 @@ pub struct RpcConfig {
     pub admin_persistence: Option<PathBuf>,
     /// Enable the websocket rpc server
     pub ws_enabled: bool,
+    /// The view that the block labels of queries are resolved from.
+    pub block_labels: BlockLabelSource,
+    /// Serve the `eth_*` methods that take a block label, forwarded to the L2 EL with their
+    /// labels resolved from the engine state.
+    pub eth_proxy: bool,
 }
 
 impl RpcConfig {
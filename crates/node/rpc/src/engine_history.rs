@@ -0,0 +1,31 @@
+//! Contains the RPC server for retrieving the latest transitions of the engine state.
+
+use crate::EngineDebugApiServer;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use kona_engine::{EngineStateHistory, EngineStateTransition};
+
+/// The RPC server serving the transitions of the [`EngineStateHistory`].
+#[derive(Debug, Clone)]
+pub struct EngineStateHistoryRpc {
+    /// The [`EngineStateHistory`], written by the engine.
+    pub history: EngineStateHistory,
+}
+
+impl EngineStateHistoryRpc {
+    /// The identifier for the Metric that tracks engine state history RPC calls.
+    pub const RPC_IDENT: &'static str = "kona_node_rpc_calls";
+
+    /// Constructs a new [`EngineStateHistoryRpc`] given the [`EngineStateHistory`].
+    pub const fn new(history: EngineStateHistory) -> Self {
+        Self { history }
+    }
+}
+
+#[async_trait]
+impl EngineDebugApiServer for EngineStateHistoryRpc {
+    async fn debug_engine_state_history(&self) -> RpcResult<Vec<EngineStateTransition>> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "debug_engineStateHistory");
+        Ok(self.history.transitions())
+    }
+}
@@ -0,0 +1,36 @@
+//! Contains the RPC server for retrieving the payload envelopes of the latest unsafe blocks.
+
+use crate::PayloadEnvelopeApiServer;
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use kona_engine::PayloadEnvelopeStore;
+use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
+
+/// The RPC server serving the envelopes of the [`PayloadEnvelopeStore`].
+#[derive(Debug, Clone)]
+pub struct PayloadEnvelopeRpc {
+    /// The [`PayloadEnvelopeStore`], shared with the engine and the sequencer.
+    pub store: PayloadEnvelopeStore,
+}
+
+impl PayloadEnvelopeRpc {
+    /// The identifier for the Metric that tracks payload envelope RPC calls.
+    pub const RPC_IDENT: &'static str = "kona_node_rpc_calls";
+
+    /// Constructs a new [`PayloadEnvelopeRpc`] given the [`PayloadEnvelopeStore`].
+    pub const fn new(store: PayloadEnvelopeStore) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl PayloadEnvelopeApiServer for PayloadEnvelopeRpc {
+    async fn admin_payload_envelope_by_hash(
+        &self,
+        hash: B256,
+    ) -> RpcResult<Option<OpExecutionPayloadEnvelope>> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "admin_payloadEnvelopeByHash");
+        Ok(self.store.get(&hash))
+    }
+}
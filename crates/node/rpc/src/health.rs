@@ -0,0 +1,144 @@
+//! Contains the [`HealthRegistry`], which tracks the [`HealthStatus`] of the rollup node's actors.
+
+use std::collections::BTreeMap;
+use tokio::sync::watch;
+
+/// The health of a single component of the rollup node.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "status", content = "reason", rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The component is starting up, and is not yet able to serve its purpose.
+    Starting,
+    /// The component is operating normally.
+    Healthy,
+    /// The component is operating, but with reduced functionality.
+    Degraded(String),
+    /// The component is not operating.
+    Unhealthy(String),
+    /// The component has exited cleanly, e.g. because it has nothing left to do. A stopped
+    /// component is not considered failing.
+    Stopped,
+}
+
+impl HealthStatus {
+    /// Returns `true` if the component is operating, i.e. [`HealthStatus::Healthy`] or
+    /// [`HealthStatus::Degraded`].
+    pub const fn is_ok(&self) -> bool {
+        matches!(self, Self::Healthy | Self::Degraded(_))
+    }
+
+    /// Returns `true` if the component is expected to operate but does not, i.e.
+    /// [`HealthStatus::Starting`] or [`HealthStatus::Unhealthy`].
+    pub const fn is_failing(&self) -> bool {
+        matches!(self, Self::Starting | Self::Unhealthy(_))
+    }
+}
+
+/// A registry of the [`HealthStatus`] of each named component of the rollup node.
+///
+/// The registry is backed by a [`watch`] channel, so consumers may either take a
+/// [snapshot][HealthRegistry::snapshot] or [subscribe][HealthRegistry::subscribe] to updates.
+#[derive(Debug, Clone)]
+pub struct HealthRegistry {
+    /// The current status of each component.
+    statuses: watch::Sender<BTreeMap<String, HealthStatus>>,
+}
+
+impl Default for HealthRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthRegistry {
+    /// Creates a new, empty [`HealthRegistry`].
+    pub fn new() -> Self {
+        Self { statuses: watch::Sender::new(BTreeMap::new()) }
+    }
+
+    /// Sets the [`HealthStatus`] of the named component. Returns `true` if the status changed.
+    pub fn set(&self, name: &str, status: HealthStatus) -> bool {
+        self.statuses.send_if_modified(|statuses| {
+            if statuses.get(name) == Some(&status) {
+                return false;
+            }
+            statuses.insert(name.to_string(), status);
+            true
+        })
+    }
+
+    /// Returns the [`HealthStatus`] of the named component, if it has reported one.
+    pub fn get(&self, name: &str) -> Option<HealthStatus> {
+        self.statuses.borrow().get(name).cloned()
+    }
+
+    /// Returns the current [`HealthStatus`] of all components.
+    pub fn snapshot(&self) -> BTreeMap<String, HealthStatus> {
+        self.statuses.borrow().clone()
+    }
+
+    /// Subscribes to updates of the registry.
+    pub fn subscribe(&self) -> watch::Receiver<BTreeMap<String, HealthStatus>> {
+        self.statuses.subscribe()
+    }
+
+    /// Returns the names of all components that are [failing][HealthStatus::is_failing].
+    pub fn unhealthy(&self) -> Vec<String> {
+        self.statuses
+            .borrow()
+            .iter()
+            .filter(|(_, status)| status.is_failing())
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Returns `true` if no component is [failing][HealthStatus::is_failing].
+    pub fn is_healthy(&self) -> bool {
+        !self.statuses.borrow().values().any(HealthStatus::is_failing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_set() {
+        let registry = HealthRegistry::new();
+        assert!(registry.is_healthy());
+
+        assert!(registry.set("engine", HealthStatus::Starting));
+        assert!(!registry.set("engine", HealthStatus::Starting));
+        assert!(registry.set("network", HealthStatus::Healthy));
+        assert!(!registry.is_healthy());
+        assert_eq!(registry.unhealthy(), vec!["engine".to_string()]);
+
+        assert!(registry.set("engine", HealthStatus::Degraded("slow".to_string())));
+        assert!(registry.is_healthy());
+        assert_eq!(registry.get("engine"), Some(HealthStatus::Degraded("slow".to_string())));
+    }
+
+    #[test]
+    fn test_registry_stopped_is_not_failing() {
+        let registry = HealthRegistry::new();
+        registry.set("engine", HealthStatus::Healthy);
+        registry.set("rpc", HealthStatus::Stopped);
+        assert!(registry.is_healthy());
+        assert!(registry.unhealthy().is_empty());
+
+        registry.set("rpc", HealthStatus::Unhealthy("server exited".to_string()));
+        assert!(!registry.is_healthy());
+        assert_eq!(registry.unhealthy(), vec!["rpc".to_string()]);
+    }
+
+    #[test]
+    fn test_status_serde() {
+        let status = HealthStatus::Unhealthy("channel closed".to_string());
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(json, r#"{"status":"unhealthy","reason":"channel closed"}"#);
+        assert_eq!(serde_json::from_str::<HealthStatus>(&json).unwrap(), status);
+
+        let json = serde_json::to_string(&HealthStatus::Healthy).unwrap();
+        assert_eq!(json, r#"{"status":"healthy"}"#);
+    }
+}
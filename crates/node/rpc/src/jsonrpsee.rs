@@ -1,21 +1,29 @@
 //! The Optimism RPC API using `jsonrpsee`
 
-use crate::{OutputResponse, SafeHeadResponse};
+use crate::{
+    ActorStatsSnapshot, DependencySetUpdate, OutputResponse, ReloadableConfig,
+    ReloadableConfigUpdate, SafeHeadResponse, SubmissionStatus,
+};
 use alloy_eips::BlockNumberOrTag;
 use alloy_primitives::B256;
-use core::net::IpAddr;
-use ipnet::IpNet;
 use jsonrpsee::{
     core::{RpcResult, SubscriptionResult},
     proc_macros::rpc,
 };
+use kona_engine::EngineStateTransition;
 use kona_genesis::RollupConfig;
 use kona_interop::ExecutingDescriptor;
-use kona_p2p::{PeerCount, PeerDump, PeerInfo, PeerStats};
 use kona_protocol::SyncStatus;
 use op_alloy_consensus::interop::SafetyLevel;
 use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
 
+#[cfg(feature = "p2p")]
+use core::net::IpAddr;
+#[cfg(feature = "p2p")]
+use ipnet::IpNet;
+#[cfg(feature = "p2p")]
+use kona_p2p::{InvalidGossipMessage, PeerCount, PeerDump, PeerInfo, PeerStats};
+
 #[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), allow(unused_imports))]
 use getrandom as _; // required for compiling wasm32-unknown-unknown
 
@@ -55,6 +63,7 @@ pub trait RollupNodeApi {
 }
 
 /// The opp2p namespace handles peer interactions.
+#[cfg(feature = "p2p")]
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "opp2p"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "opp2p"))]
 pub trait OpP2PApi {
@@ -147,6 +156,10 @@ pub trait Ws {
     /// Subscribes to the stream of unsafe head updates.
     #[subscription(name = "subscribe_unsafe_head", item = kona_protocol::L2BlockInfo)]
     async fn ws_unsafe_head_updates(&self) -> SubscriptionResult;
+
+    /// Subscribes to the stream of L1 reorgs, unsafe chain reorgs, and safe head rewinds.
+    #[subscription(name = "subscribe_reorgs", item = crate::ReorgEvent)]
+    async fn ws_reorg_events(&self) -> SubscriptionResult;
 }
 
 /// SupervisorEvents
@@ -182,3 +195,118 @@ pub trait AdminApi {
     async fn admin_post_unsafe_payload(&self, payload: OpExecutionPayloadEnvelope)
     -> RpcResult<()>;
 }
+
+/// The admin namespace methods for inspecting the gossip messages that failed validation.
+#[cfg(feature = "p2p")]
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait GossipArchiveApi {
+    /// Returns the archived gossip messages that failed validation, oldest first, with the peers
+    /// that propagated them.
+    #[method(name = "invalidGossipMessages")]
+    async fn admin_invalid_gossip_messages(&self) -> RpcResult<Vec<InvalidGossipMessage>>;
+}
+
+/// The admin namespace methods for reloading the node's runtime configuration.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait ConfigReloadApi {
+    /// Applies the given update to the node's [`ReloadableConfig`], returning the resulting
+    /// config. Fields that are not set in the update are left unchanged.
+    #[method(name = "reloadConfig")]
+    async fn admin_reload_config(
+        &self,
+        update: ReloadableConfigUpdate,
+    ) -> RpcResult<ReloadableConfig>;
+
+    /// Sets the log level of the given target, or the log filter of the node if no target is
+    /// given, returning the resulting config. An empty level removes the override of the target.
+    #[method(name = "setLogLevel")]
+    async fn admin_set_log_level(
+        &self,
+        level: String,
+        target: Option<String>,
+    ) -> RpcResult<ReloadableConfig>;
+
+    /// Limits the capacity of the given event channel of the node, e.g. `attributes`, returning
+    /// the resulting config. A capacity of `0` removes the limit.
+    #[method(name = "setChannelCapacity")]
+    async fn admin_set_channel_capacity(
+        &self,
+        channel: String,
+        capacity: usize,
+    ) -> RpcResult<ReloadableConfig>;
+
+    /// Applies the given change to the interop dependency set of the node, returning the
+    /// resulting config.
+    #[method(name = "updateDependencySet")]
+    async fn admin_update_dependency_set(
+        &self,
+        update: DependencySetUpdate,
+    ) -> RpcResult<ReloadableConfig>;
+}
+
+/// The admin namespace methods for retrieving the payload envelopes of the latest unsafe blocks.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait PayloadEnvelopeApi {
+    /// Returns the [`OpExecutionPayloadEnvelope`] of the unsafe block with the given hash, if it
+    /// is among the latest blocks built or imported by the node.
+    #[method(name = "payloadEnvelopeByHash")]
+    async fn admin_payload_envelope_by_hash(
+        &self,
+        hash: B256,
+    ) -> RpcResult<Option<OpExecutionPayloadEnvelope>>;
+}
+
+/// The admin namespace methods for inspecting the utilization of the node's actors.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait ActorStatsApi {
+    /// Returns the latest [`ActorStatsSnapshot`]: the select-loop iteration rate and busy time of
+    /// each actor, and the depth of each event channel between them.
+    #[method(name = "actorStats")]
+    async fn admin_actor_stats(&self) -> RpcResult<ActorStatsSnapshot>;
+}
+
+/// The admin namespace methods for tracking the submission of the unsafe chain to L1.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait SubmissionApi {
+    /// Returns the [`SubmissionStatus`]: the highest L2 block covered by a batch read from L1,
+    /// and the number of unsafe blocks not yet covered by one.
+    #[method(name = "submissionStatus")]
+    async fn admin_submission_status(&self) -> RpcResult<SubmissionStatus>;
+}
+
+/// The debug namespace methods for inspecting the engine of the node.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "debug"))]
+pub trait EngineDebugApi {
+    /// Returns the latest [`EngineStateTransition`]s, oldest first: which heads or sync flags of
+    /// the engine state changed, which engine task changed them, and when.
+    #[method(name = "engineStateHistory")]
+    async fn debug_engine_state_history(&self) -> RpcResult<Vec<EngineStateTransition>>;
+}
+
+/// The debug namespace methods for profiling the node.
+#[cfg(feature = "profiling")]
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "debug"))]
+pub trait ProfilingApi {
+    /// Samples the CPU usage of the node for the given number of seconds, and returns the
+    /// profile as an SVG flamegraph.
+    #[method(name = "cpuProfile")]
+    async fn debug_cpu_profile(&self, seconds: u64) -> RpcResult<String>;
+
+    /// Returns the current [`HeapStats`] of the node.
+    ///
+    /// [`HeapStats`]: crate::HeapStats
+    #[method(name = "heapStats")]
+    async fn debug_heap_stats(&self) -> RpcResult<crate::HeapStats>;
+
+    /// Dumps a jemalloc heap profile of the node, in the format read by `jeprof`. Heap profiling
+    /// must be enabled at startup, with `MALLOC_CONF=prof:true`.
+    #[method(name = "heapProfile")]
+    async fn debug_heap_profile(&self) -> RpcResult<String>;
+}
@@ -0,0 +1,55 @@
+//! Contains the [`BlockLabelSource`], which selects the view that block labels are resolved from.
+
+use core::{fmt, str::FromStr};
+
+/// The view of the chain that the `latest`, `safe` and `finalized` block labels of RPC queries
+/// are resolved from.
+///
+/// The EL learns about the forkchoice of the node with some delay, so queries resolved by the EL
+/// may lag behind, or disagree with, the heads reported by `optimism_syncStatus`. Resolving the
+/// labels from the engine state of the node keeps both consistent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BlockLabelSource {
+    /// The labels are resolved by the EL.
+    #[default]
+    ExecutionLayer,
+    /// The labels are resolved from the engine state of the node.
+    Engine,
+}
+
+impl fmt::Display for BlockLabelSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExecutionLayer => f.write_str("execution-layer"),
+            Self::Engine => f.write_str("engine"),
+        }
+    }
+}
+
+impl FromStr for BlockLabelSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "execution-layer" | "el" => Ok(Self::ExecutionLayer),
+            "engine" => Ok(Self::Engine),
+            other => Err(format!(
+                "invalid block label source `{other}`, expected `execution-layer` or `engine`"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_label_source_roundtrip() {
+        for source in [BlockLabelSource::ExecutionLayer, BlockLabelSource::Engine] {
+            assert_eq!(source.to_string().parse::<BlockLabelSource>(), Ok(source));
+        }
+        assert_eq!("el".parse::<BlockLabelSource>(), Ok(BlockLabelSource::ExecutionLayer));
+        assert!("latest".parse::<BlockLabelSource>().is_err());
+    }
+}
@@ -1,9 +1,9 @@
 //! Contains the [`RpcLauncher`] service.
 
 use jsonrpsee::server::{RegisterMethodError, RpcModule, Server, ServerHandle};
-use std::net::SocketAddr;
+use std::{collections::BTreeMap, net::SocketAddr};
 
-use crate::RpcConfig;
+use crate::{BlockLabelSource, HealthRegistry, HealthStatus, RpcConfig};
 
 /// An error that can occur when using the [`RpcLauncher`].
 #[derive(Debug, thiserror::Error)]
@@ -30,6 +30,10 @@ impl PartialEq for RpcLauncherError {
 pub struct HealthzResponse {
     /// The application version.
     version: String,
+    /// Whether all components of the node are healthy.
+    healthy: bool,
+    /// The health of each component of the node.
+    components: BTreeMap<String, HealthStatus>,
 }
 
 /// Launches a [`Server`] using a set of [`RpcModule`]s.
@@ -58,16 +62,38 @@ impl RpcLauncher {
                 enable_admin: false,
                 admin_persistence: None,
                 ws_enabled: false,
+                block_labels: BlockLabelSource::ExecutionLayer,
+                eth_proxy: false,
             },
             module: RpcModule::new(()),
         }
     }
 
+    /// Returns whether the RPC server is enabled.
+    pub const fn enabled(&self) -> bool {
+        !self.config.disabled
+    }
+
     /// Returns whether WebSocket RPC endpoint is enabled
     pub const fn ws_enabled(&self) -> bool {
         self.config.ws_enabled
     }
 
+    /// Returns whether the admin RPC namespace is enabled.
+    pub const fn admin_enabled(&self) -> bool {
+        self.config.enable_admin
+    }
+
+    /// Returns the view that the block labels of queries are resolved from.
+    pub const fn block_labels(&self) -> BlockLabelSource {
+        self.config.block_labels
+    }
+
+    /// Returns whether the `eth_*` proxy is enabled.
+    pub const fn eth_proxy_enabled(&self) -> bool {
+        self.config.eth_proxy
+    }
+
     /// Merges a given [`RpcModule`] into the [`RpcLauncher`].
     pub fn merge<CTX>(&mut self, other: RpcModule<CTX>) -> Result<(), RegisterMethodError> {
         self.module.merge(other)?;
@@ -92,10 +118,15 @@ impl RpcLauncher {
         self
     }
 
-    /// Registers the healthz endpoint on the [`RpcLauncher`].
-    pub fn with_healthz(mut self) -> Result<Self, RegisterMethodError> {
-        self.module.register_method("healthz", |_, _, _| {
-            let response = HealthzResponse { version: std::env!("CARGO_PKG_VERSION").to_string() };
+    /// Registers the healthz endpoint on the [`RpcLauncher`], reporting the health of the
+    /// components in the given [`HealthRegistry`].
+    pub fn with_healthz(mut self, registry: HealthRegistry) -> Result<Self, RegisterMethodError> {
+        self.module.register_method("healthz", move |_, _, _| {
+            let response = HealthzResponse {
+                version: std::env!("CARGO_PKG_VERSION").to_string(),
+                healthy: registry.is_healthy(),
+                components: registry.snapshot(),
+            };
             jsonrpsee::core::RpcResult::Ok(response)
         })?;
 
@@ -132,6 +163,8 @@ mod tests {
             enable_admin: false,
             admin_persistence: None,
             ws_enabled: false,
+            block_labels: BlockLabelSource::ExecutionLayer,
+            eth_proxy: false,
         });
         let result = launcher.launch().await;
         assert!(result.is_ok());
@@ -146,6 +179,8 @@ mod tests {
             enable_admin: false,
             admin_persistence: None,
             ws_enabled: false,
+            block_labels: BlockLabelSource::ExecutionLayer,
+            eth_proxy: false,
         });
         launcher.merge(RpcModule::new(())).expect("module merge");
         launcher.merge::<()>(RpcModule::new(())).expect("module merge");
@@ -9,20 +9,33 @@
 #[macro_use]
 extern crate tracing;
 
+#[cfg(feature = "p2p")]
 mod admin;
 
 mod config;
 pub use config::RpcConfig;
 
+mod health;
+pub use health::{HealthRegistry, HealthStatus};
+
+mod stats;
+pub use stats::{ActorStats, ActorStatsRegistry, ActorStatsRpc, ActorStatsSnapshot, QueueStats};
+
+mod submission;
+pub use submission::{SubmissionRegistry, SubmissionRpc, SubmissionStatus};
+
 mod launcher;
 pub use launcher::{HealthzResponse, RpcLauncher, RpcLauncherError};
 
+#[cfg(feature = "p2p")]
 mod net;
+#[cfg(feature = "p2p")]
 pub use net::NetworkRpc;
 
 mod supervisor;
 pub use supervisor::{SupervisorRpcConfig, SupervisorRpcServer};
 
+#[cfg(feature = "p2p")]
 mod p2p;
 
 mod response;
@@ -32,10 +45,20 @@ mod output;
 pub use output::OutputResponse;
 
 mod jsonrpsee;
+#[cfg(feature = "profiling")]
+pub use jsonrpsee::ProfilingApiServer;
 pub use jsonrpsee::{
-    AdminApiServer, MinerApiExtServer, OpAdminApiServer, OpP2PApiServer, RollupNodeApiServer,
-    SupervisorEventsServer, WsServer,
+    ActorStatsApiServer, AdminApiServer, ConfigReloadApiServer, EngineDebugApiServer,
+    MinerApiExtServer, OpAdminApiServer, PayloadEnvelopeApiServer, RollupNodeApiServer,
+    SubmissionApiServer, SupervisorEventsServer, WsServer,
 };
+#[cfg(feature = "p2p")]
+pub use jsonrpsee::{GossipArchiveApiServer, OpP2PApiServer};
+
+#[cfg(feature = "profiling")]
+mod profiling;
+#[cfg(feature = "profiling")]
+pub use profiling::{HeapStats, ProfilingRpc};
 
 #[cfg(feature = "reqwest")]
 pub mod reqwest;
@@ -53,8 +76,28 @@ pub use kona_supervisor_rpc::SupervisorApiClient;
 mod rollup;
 pub use rollup::RollupRpc;
 
+mod labels;
+pub use labels::BlockLabelSource;
+
+#[cfg(feature = "reqwest")]
+mod proxy;
+#[cfg(feature = "reqwest")]
+pub use proxy::EthProxyRpc;
+
 mod l1_watcher;
 pub use l1_watcher::{L1State, L1WatcherQueries, L1WatcherQuerySender};
 
 mod ws;
 pub use ws::WsRPC;
+
+mod reorg;
+pub use reorg::ReorgEvent;
+
+mod reload;
+pub use reload::{ConfigReloadRpc, DependencySetUpdate, ReloadableConfig, ReloadableConfigUpdate};
+
+mod envelopes;
+pub use envelopes::PayloadEnvelopeRpc;
+
+mod engine_history;
+pub use engine_history::EngineStateHistoryRpc;
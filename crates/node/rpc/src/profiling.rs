@@ -0,0 +1,166 @@
+//! RPC module for profiling the node.
+//!
+//! The heap statistics and profiles are read from jemalloc, which the binary must install as its
+//! global allocator. Inspecting the async tasks with `tokio-console` is not supported: its layer
+//! needs the runtime's trace-level spans, which the node's log filter drops.
+
+use crate::ProfilingApiServer;
+use async_trait::async_trait;
+use jsonrpsee::{
+    core::RpcResult,
+    types::{ErrorCode, ErrorObject},
+};
+use std::{
+    ffi::CString,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+use tikv_jemalloc_ctl::{epoch, raw, stats};
+
+/// The maximum duration of a CPU profile, in seconds.
+const MAX_CPU_PROFILE_SECS: u64 = 300;
+
+/// The frequency at which the CPU profiler samples the stacks of all threads, in Hz.
+const CPU_PROFILE_FREQUENCY: i32 = 99;
+
+/// The number of heap profiles dumped, used to name their files.
+static HEAP_PROFILES: AtomicU64 = AtomicU64::new(0);
+
+/// The heap usage of the node, as reported by jemalloc.
+///
+/// The stats only cover the heap of the node if jemalloc is its global allocator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeapStats {
+    /// The number of bytes allocated by the node.
+    pub allocated_bytes: u64,
+    /// The number of bytes in the pages holding allocations. At least `allocated_bytes`.
+    pub active_bytes: u64,
+    /// The number of bytes in the physically resident pages mapped by the allocator.
+    pub resident_bytes: u64,
+    /// The number of bytes in the pages mapped by the allocator. At least `active_bytes`.
+    pub mapped_bytes: u64,
+    /// The number of bytes in the pages retained by the allocator, rather than returned to the
+    /// operating system.
+    pub retained_bytes: u64,
+}
+
+impl HeapStats {
+    /// Returns the current [`HeapStats`].
+    pub fn current() -> Result<Self, tikv_jemalloc_ctl::Error> {
+        // The stats are cached by jemalloc, and only refreshed when the epoch is advanced.
+        epoch::advance()?;
+        Ok(Self {
+            allocated_bytes: stats::allocated::read()? as u64,
+            active_bytes: stats::active::read()? as u64,
+            resident_bytes: stats::resident::read()? as u64,
+            mapped_bytes: stats::mapped::read()? as u64,
+            retained_bytes: stats::retained::read()? as u64,
+        })
+    }
+}
+
+/// An RPC module for profiling the node, to diagnose performance regressions on a live node.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProfilingRpc;
+
+impl ProfilingRpc {
+    /// Samples the stacks of all threads for `duration`, and renders them as an SVG flamegraph.
+    fn cpu_profile(duration: Duration) -> Result<String, pprof::Error> {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(CPU_PROFILE_FREQUENCY)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()?;
+        std::thread::sleep(duration);
+
+        let mut svg = Vec::new();
+        guard.report().build()?.flamegraph(&mut svg)?;
+        Ok(String::from_utf8_lossy(&svg).into_owned())
+    }
+
+    /// Dumps a jemalloc heap profile to a temporary file, and returns its contents.
+    fn heap_profile() -> Result<String, String> {
+        // SAFETY: `opt.prof` is a boolean.
+        let enabled = unsafe { raw::read::<bool>(b"opt.prof\0") }.unwrap_or_default();
+        if !enabled {
+            return Err(
+                "heap profiling is disabled, start the node with MALLOC_CONF=prof:true".to_string()
+            );
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "kona-heap-{}-{}.prof",
+            std::process::id(),
+            HEAP_PROFILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let c_path =
+            CString::new(path.to_string_lossy().into_owned()).map_err(|e| e.to_string())?;
+        // SAFETY: `prof.dump` takes a pointer to a NUL-terminated path, which outlives the call.
+        unsafe { raw::write(b"prof.dump\0", c_path.as_ptr()) }
+            .map_err(|e| format!("failed to dump heap profile: {e}"))?;
+        let profile = std::fs::read(&path).map_err(|e| format!("failed to read heap profile: {e}"));
+        let _ = std::fs::remove_file(&path);
+        Ok(String::from_utf8_lossy(&profile?).into_owned())
+    }
+}
+
+#[async_trait]
+impl ProfilingApiServer for ProfilingRpc {
+    async fn debug_cpu_profile(&self, seconds: u64) -> RpcResult<String> {
+        kona_macros::inc!(gauge, kona_p2p::Metrics::RPC_CALLS, "method" => "debug_cpuProfile");
+        if !(1..=MAX_CPU_PROFILE_SECS).contains(&seconds) {
+            return Err(ErrorObject::owned(
+                ErrorCode::InvalidParams.code(),
+                format!("profile duration must be between 1 and {MAX_CPU_PROFILE_SECS} seconds"),
+                None::<()>,
+            ));
+        }
+
+        info!(target: "rpc", seconds, "Recording CPU profile");
+        tokio::task::spawn_blocking(move || Self::cpu_profile(Duration::from_secs(seconds)))
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?
+            .map_err(|e| {
+                warn!(target: "rpc", "Failed to record CPU profile: {e}");
+                ErrorObject::owned(ErrorCode::InternalError.code(), e.to_string(), None::<()>)
+            })
+    }
+
+    async fn debug_heap_stats(&self) -> RpcResult<HeapStats> {
+        kona_macros::inc!(gauge, kona_p2p::Metrics::RPC_CALLS, "method" => "debug_heapStats");
+        HeapStats::current().map_err(|e| {
+            warn!(target: "rpc", "Failed to read heap stats: {e}");
+            ErrorObject::owned(ErrorCode::InternalError.code(), e.to_string(), None::<()>)
+        })
+    }
+
+    async fn debug_heap_profile(&self) -> RpcResult<String> {
+        kona_macros::inc!(gauge, kona_p2p::Metrics::RPC_CALLS, "method" => "debug_heapProfile");
+        info!(target: "rpc", "Dumping heap profile");
+        tokio::task::spawn_blocking(Self::heap_profile)
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?
+            .map_err(|e| {
+                warn!(target: "rpc", "Failed to dump heap profile: {e}");
+                ErrorObject::owned(ErrorCode::InternalError.code(), e, None::<()>)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heap_stats() {
+        let stats = HeapStats::current().unwrap();
+        assert!(stats.allocated_bytes <= stats.active_bytes);
+        assert!(stats.active_bytes <= stats.mapped_bytes);
+    }
+
+    #[test]
+    fn test_heap_profile_disabled() {
+        let err = ProfilingRpc::heap_profile().unwrap_err();
+        assert!(err.contains("MALLOC_CONF=prof:true"));
+    }
+}
@@ -0,0 +1,232 @@
+//! Contains the [`EthProxyRpc`], which serves the `eth_*` methods that take a block label by
+//! forwarding them to the EL, with their labels resolved from the engine state of the node.
+
+use alloy_eips::BlockNumberOrTag;
+use alloy_rpc_client::ReqwestClient;
+use jsonrpsee::{
+    RpcModule,
+    core::RpcResult,
+    server::RegisterMethodError,
+    types::{ErrorCode, ErrorObject, Params},
+};
+use kona_engine::{EngineQueries, EngineQuerySender, EngineState};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// Where a method takes the block it is queried at.
+#[derive(Debug, Clone, Copy)]
+enum BlockParam {
+    /// The param at the given index, which is required.
+    Required(usize),
+    /// The param at the given index, which defaults to `latest` if omitted.
+    Optional(usize),
+    /// The `fromBlock` and `toBlock` fields of the filter object of `eth_getLogs`, which default
+    /// to `latest` if omitted, unless the filter selects a block by hash.
+    Filter,
+}
+
+/// The methods forwarded by the [`EthProxyRpc`], and where they take their block.
+const PROXIED_METHODS: [(&str, BlockParam); 13] = [
+    ("eth_getBlockByNumber", BlockParam::Required(0)),
+    ("eth_getBlockTransactionCountByNumber", BlockParam::Required(0)),
+    ("eth_getTransactionByBlockNumberAndIndex", BlockParam::Required(0)),
+    ("eth_getBlockReceipts", BlockParam::Required(0)),
+    ("eth_getBalance", BlockParam::Optional(1)),
+    ("eth_getCode", BlockParam::Optional(1)),
+    ("eth_getTransactionCount", BlockParam::Optional(1)),
+    ("eth_getStorageAt", BlockParam::Optional(2)),
+    ("eth_call", BlockParam::Optional(1)),
+    ("eth_estimateGas", BlockParam::Optional(1)),
+    ("eth_createAccessList", BlockParam::Optional(1)),
+    ("eth_getProof", BlockParam::Optional(2)),
+    ("eth_getLogs", BlockParam::Filter),
+];
+
+/// A proxy of the `eth_*` methods of the EL that take a block label.
+///
+/// The `latest`, `pending`, `safe` and `finalized` labels of the forwarded queries are rewritten
+/// to the numbers of the blocks they designate in the engine state of the node, so that clients
+/// sensitive to consistency observe the same heads as reported by `optimism_syncStatus`, even
+/// while the EL has not caught up with the forkchoice of the node. `eth_blockNumber` returns the
+/// unsafe head of the node.
+#[derive(Debug)]
+pub struct EthProxyRpc {
+    /// The channel to send [`EngineQueries`]s.
+    engine_sender: EngineQuerySender,
+    /// The client of the EL RPC that queries are forwarded to.
+    client: ReqwestClient,
+}
+
+impl EthProxyRpc {
+    /// The identifier for the Metric that tracks proxied RPC calls.
+    pub const RPC_IDENT: &'static str = "eth_proxy_rpc";
+
+    /// Constructs a new [`EthProxyRpc`], forwarding queries to the given EL RPC client.
+    pub const fn new(engine_sender: EngineQuerySender, client: ReqwestClient) -> Self {
+        Self { engine_sender, client }
+    }
+
+    /// Converts the proxy into an [`RpcModule`] serving the proxied methods.
+    pub fn into_rpc(self) -> Result<RpcModule<Self>, RegisterMethodError> {
+        let mut module = RpcModule::new(self);
+        module.register_async_method("eth_blockNumber", |_, proxy, _| async move {
+            kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "eth_blockNumber");
+            let state = proxy.engine_state().await?;
+            RpcResult::Ok(format!("{:#x}", state.unsafe_head().block_info.number))
+        })?;
+        for (method, param) in PROXIED_METHODS {
+            module.register_async_method(method, move |params, proxy, _| async move {
+                proxy.forward(method, param, params).await
+            })?;
+        }
+        Ok(module)
+    }
+
+    /// Forwards the given query to the EL, with its block labels resolved from the engine state.
+    async fn forward(
+        &self,
+        method: &'static str,
+        param: BlockParam,
+        params: Params<'static>,
+    ) -> RpcResult<Value> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => method);
+
+        let mut params: Vec<Value> = match params.as_str() {
+            Some(_) => params.parse()?,
+            None => Vec::new(),
+        };
+        let state = self.engine_state().await?;
+        rewrite_params(&mut params, param, &state);
+
+        // The errors of the EL are passed through to the client.
+        self.client.request(method, params).await.map_err(|e| {
+            e.as_error_resp().map_or_else(
+                || ErrorObject::from(ErrorCode::InternalError),
+                |err| {
+                    ErrorObject::owned(err.code as i32, err.message.to_string(), err.data.clone())
+                },
+            )
+        })
+    }
+
+    /// Queries the current engine state.
+    async fn engine_state(&self) -> RpcResult<EngineState> {
+        let (state_send, state_recv) = tokio::sync::oneshot::channel();
+        self.engine_sender
+            .send(EngineQueries::State(state_send))
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?;
+        state_recv.await.map_err(|_| ErrorObject::from(ErrorCode::InternalError))
+    }
+}
+
+/// Rewrites the block labels of the given params, taken as the given [`BlockParam`], to the
+/// numbers of the blocks they designate in the given engine state.
+fn rewrite_params(params: &mut Vec<Value>, param: BlockParam, state: &EngineState) {
+    let latest = || Value::String("latest".to_string());
+    match param {
+        BlockParam::Required(index) => {
+            if let Some(block) = params.get_mut(index) {
+                rewrite_block(block, state);
+            }
+        }
+        BlockParam::Optional(index) => {
+            // Params before the block may be omitted too, in which case the EL rejects the query
+            // anyway.
+            if params.len() == index {
+                params.push(latest());
+            }
+            if let Some(block) = params.get_mut(index) {
+                rewrite_block(block, state);
+            }
+        }
+        BlockParam::Filter => {
+            let Some(Value::Object(filter)) = params.get_mut(0) else {
+                return;
+            };
+            if filter.contains_key("blockHash") {
+                return;
+            }
+            for field in ["fromBlock", "toBlock"] {
+                rewrite_block(filter.entry(field).or_insert_with(latest), state);
+            }
+        }
+    }
+}
+
+/// Rewrites the given block, either a block number or tag, or an [EIP-1898] block object, if it
+/// is a label.
+///
+/// [EIP-1898]: https://eips.ethereum.org/EIPS/eip-1898
+fn rewrite_block(block: &mut Value, state: &EngineState) {
+    let block = match block {
+        Value::Object(object) => match object.get_mut("blockNumber") {
+            Some(number) => number,
+            None => return,
+        },
+        block => block,
+    };
+    let Value::String(label) = block else {
+        return;
+    };
+    let Ok(tag) = BlockNumberOrTag::from_str(label) else {
+        return;
+    };
+    if let BlockNumberOrTag::Number(number) = state.resolve_label(tag) {
+        *block = Value::String(format!("{number:#x}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kona_protocol::{BlockInfo, L2BlockInfo};
+    use serde_json::json;
+
+    fn state() -> EngineState {
+        let block = |number| L2BlockInfo {
+            block_info: BlockInfo { number, ..Default::default() },
+            ..Default::default()
+        };
+        let mut state = EngineState::default();
+        state.set_unsafe_head(block(10));
+        state.set_safe_head(block(8));
+        state.set_finalized_head(block(4));
+        state
+    }
+
+    #[test]
+    fn test_rewrite_block_params() {
+        let state = state();
+
+        let mut params = vec![json!("safe"), json!(false)];
+        rewrite_params(&mut params, BlockParam::Required(0), &state);
+        assert_eq!(params, vec![json!("0x8"), json!(false)]);
+
+        let mut params = vec![json!("0xabc")];
+        rewrite_params(&mut params, BlockParam::Optional(1), &state);
+        assert_eq!(params, vec![json!("0xabc"), json!("0xa")]);
+
+        let mut params = vec![json!("0xabc"), json!({ "blockNumber": "finalized" })];
+        rewrite_params(&mut params, BlockParam::Optional(1), &state);
+        assert_eq!(params, vec![json!("0xabc"), json!({ "blockNumber": "0x4" })]);
+
+        let mut params = vec![json!("0xabc"), json!("0x2"), json!("earliest")];
+        rewrite_params(&mut params, BlockParam::Optional(2), &state);
+        assert_eq!(params, vec![json!("0xabc"), json!("0x2"), json!("earliest")]);
+    }
+
+    #[test]
+    fn test_rewrite_filter_params() {
+        let state = state();
+
+        let mut params = vec![json!({ "fromBlock": "finalized" })];
+        rewrite_params(&mut params, BlockParam::Filter, &state);
+        assert_eq!(params, vec![json!({ "fromBlock": "0x4", "toBlock": "0xa" })]);
+
+        let filter = json!({ "blockHash": "0x01" });
+        let mut params = vec![filter.clone()];
+        rewrite_params(&mut params, BlockParam::Filter, &state);
+        assert_eq!(params, vec![filter]);
+    }
+}
@@ -0,0 +1,359 @@
+//! Contains the [`ReloadableConfig`] and the RPC server used to update it at runtime.
+
+use crate::ConfigReloadApiServer;
+use async_trait::async_trait;
+use jsonrpsee::{
+    core::RpcResult,
+    types::{ErrorCode, ErrorObject},
+};
+use kona_interop::{ChainDependency, DependencySet};
+use std::{collections::BTreeMap, str::FromStr};
+use tokio::sync::watch;
+use tracing::level_filters::LevelFilter;
+
+/// The subset of the rollup node's configuration that can be reloaded at runtime, without
+/// restarting the node.
+///
+/// The current value is held in a [`watch`] channel, which actors subscribe to in order to pick up
+/// changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadableConfig {
+    /// The log filter directives, in `RUST_LOG` syntax (e.g. `info,engine=debug`).
+    ///
+    /// If `None`, the filter configured at startup is used.
+    pub log_filter: Option<String>,
+    /// Per-target log level overrides applied on top of the log filter, e.g. `engine_builder` to
+    /// `trace`.
+    pub log_levels: BTreeMap<String, String>,
+    /// A list of peer multiaddrs that the node should connect to.
+    pub static_peers: Vec<String>,
+    /// Whether the sequencer is in recovery mode. In recovery mode, the sequencer only builds
+    /// empty blocks.
+    pub sequencer_recover: bool,
+    /// The number of seconds a gossiped unsafe block must be old before it advances the unsafe
+    /// head. Disabled if `0`.
+    pub unsafe_delay_secs: u64,
+    /// The number of newer unsafe blocks that must be gossiped before a gossiped unsafe block
+    /// advances the unsafe head. Disabled if `0`.
+    pub unsafe_delay_blocks: u64,
+    /// Limits on the capacity of the node's event channels, by channel name, e.g. `attributes`.
+    /// Channels without a limit keep the capacity configured at startup, which also caps the
+    /// limits.
+    pub channel_capacities: BTreeMap<String, usize>,
+    /// The interop dependency set: the chains that the node accepts messages from, and the
+    /// expiry window of those messages. `None` if no dependency set was configured.
+    pub dependency_set: Option<DependencySet>,
+}
+
+/// A change to the interop [`DependencySet`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencySetUpdate {
+    /// The IDs of the chains to add to the dependency set.
+    #[serde(default)]
+    pub add_chains: Vec<u64>,
+    /// The IDs of the chains to remove from the dependency set.
+    #[serde(default)]
+    pub remove_chains: Vec<u64>,
+    /// The new message expiry window, in seconds. A window of `0` restores the default one.
+    pub message_expiry_window: Option<u64>,
+}
+
+impl DependencySetUpdate {
+    /// Applies the update to the given [`DependencySet`]. Chains are removed after the chains
+    /// to add were added.
+    pub fn apply(self, set: &mut DependencySet) {
+        for chain_id in self.add_chains {
+            set.dependencies.insert(chain_id, ChainDependency {});
+        }
+        for chain_id in self.remove_chains {
+            set.dependencies.remove(&chain_id);
+        }
+        if let Some(window) = self.message_expiry_window {
+            set.override_message_expiry_window = (window > 0).then_some(window);
+        }
+    }
+}
+
+/// A partial update to the [`ReloadableConfig`]. Fields that are `None` are left unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadableConfigUpdate {
+    /// The new log filter directives.
+    pub log_filter: Option<String>,
+    /// Per-target log level overrides to merge into the current ones. An empty level removes the
+    /// override for its target.
+    pub log_levels: Option<BTreeMap<String, String>>,
+    /// The new list of static peer multiaddrs.
+    pub static_peers: Option<Vec<String>>,
+    /// The new sequencer recovery mode.
+    pub sequencer_recover: Option<bool>,
+    /// The new unsafe block delay, in seconds.
+    pub unsafe_delay_secs: Option<u64>,
+    /// The new unsafe block delay, in blocks.
+    pub unsafe_delay_blocks: Option<u64>,
+    /// Channel capacity limits to merge into the current ones. A limit of `0` removes the limit
+    /// of its channel.
+    pub channel_capacities: Option<BTreeMap<String, usize>>,
+    /// The change to the interop dependency set. A dependency set is created if none was
+    /// configured.
+    pub dependency_set: Option<DependencySetUpdate>,
+}
+
+impl ReloadableConfigUpdate {
+    /// Applies the update to the given [`ReloadableConfig`]. Returns `true` if the config was
+    /// modified.
+    pub fn apply(self, config: &mut ReloadableConfig) -> bool {
+        let previous = config.clone();
+        if let Some(log_filter) = self.log_filter {
+            config.log_filter = Some(log_filter);
+        }
+        for (target, level) in self.log_levels.into_iter().flatten() {
+            if level.is_empty() {
+                config.log_levels.remove(&target);
+            } else {
+                config.log_levels.insert(target, level);
+            }
+        }
+        if let Some(static_peers) = self.static_peers {
+            config.static_peers = static_peers;
+        }
+        if let Some(sequencer_recover) = self.sequencer_recover {
+            config.sequencer_recover = sequencer_recover;
+        }
+        if let Some(unsafe_delay_secs) = self.unsafe_delay_secs {
+            config.unsafe_delay_secs = unsafe_delay_secs;
+        }
+        if let Some(unsafe_delay_blocks) = self.unsafe_delay_blocks {
+            config.unsafe_delay_blocks = unsafe_delay_blocks;
+        }
+        for (channel, capacity) in self.channel_capacities.into_iter().flatten() {
+            if capacity == 0 {
+                config.channel_capacities.remove(&channel);
+            } else {
+                config.channel_capacities.insert(channel, capacity);
+            }
+        }
+        if let Some(update) = self.dependency_set {
+            let set = config.dependency_set.get_or_insert_with(|| DependencySet {
+                dependencies: Default::default(),
+                override_message_expiry_window: None,
+            });
+            update.apply(set);
+        }
+        previous != *config
+    }
+}
+
+/// The RPC server for reloading the [`ReloadableConfig`].
+#[derive(Debug, Clone)]
+pub struct ConfigReloadRpc {
+    /// The sender for the current [`ReloadableConfig`].
+    pub sender: watch::Sender<ReloadableConfig>,
+}
+
+impl ConfigReloadRpc {
+    /// The identifier for the Metric that tracks config reload RPC calls.
+    pub const RPC_IDENT: &'static str = "kona_node_rpc_calls";
+
+    /// Constructs a new [`ConfigReloadRpc`] given the [`ReloadableConfig`] sender.
+    pub const fn new(sender: watch::Sender<ReloadableConfig>) -> Self {
+        Self { sender }
+    }
+
+    /// Applies the given update, and returns the resulting config.
+    fn apply(&self, update: ReloadableConfigUpdate) -> ReloadableConfig {
+        let modified = self.sender.send_if_modified(|config| update.apply(config));
+        info!(target: "rpc", modified, "Reloaded runtime configuration");
+        self.sender.borrow().clone()
+    }
+}
+
+#[async_trait]
+impl ConfigReloadApiServer for ConfigReloadRpc {
+    async fn admin_reload_config(
+        &self,
+        update: ReloadableConfigUpdate,
+    ) -> RpcResult<ReloadableConfig> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "admin_reloadConfig");
+        Ok(self.apply(update))
+    }
+
+    async fn admin_set_log_level(
+        &self,
+        level: String,
+        target: Option<String>,
+    ) -> RpcResult<ReloadableConfig> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "admin_setLogLevel");
+        let update = match target {
+            Some(target) => {
+                // An empty level removes the override of the target.
+                if !level.is_empty() {
+                    LevelFilter::from_str(&level)
+                        .map_err(|_| ErrorObject::from(ErrorCode::InvalidParams))?;
+                }
+                ReloadableConfigUpdate {
+                    log_levels: Some(BTreeMap::from([(target, level)])),
+                    ..Default::default()
+                }
+            }
+            None => {
+                if level.is_empty() || LevelFilter::from_str(&level).is_err() {
+                    return Err(ErrorObject::from(ErrorCode::InvalidParams));
+                }
+                ReloadableConfigUpdate { log_filter: Some(level), ..Default::default() }
+            }
+        };
+        Ok(self.apply(update))
+    }
+
+    async fn admin_set_channel_capacity(
+        &self,
+        channel: String,
+        capacity: usize,
+    ) -> RpcResult<ReloadableConfig> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "admin_setChannelCapacity");
+        let update = ReloadableConfigUpdate {
+            channel_capacities: Some(BTreeMap::from([(channel, capacity)])),
+            ..Default::default()
+        };
+        Ok(self.apply(update))
+    }
+
+    async fn admin_update_dependency_set(
+        &self,
+        update: DependencySetUpdate,
+    ) -> RpcResult<ReloadableConfig> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "admin_updateDependencySet");
+        let update = ReloadableConfigUpdate { dependency_set: Some(update), ..Default::default() };
+        Ok(self.apply(update))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_update() {
+        let mut config = ReloadableConfig::default();
+        let update = ReloadableConfigUpdate {
+            log_filter: Some("debug".to_string()),
+            sequencer_recover: Some(true),
+            ..Default::default()
+        };
+        assert!(update.clone().apply(&mut config));
+        assert_eq!(config.log_filter.as_deref(), Some("debug"));
+        assert!(config.sequencer_recover);
+        assert!(config.static_peers.is_empty());
+
+        // Applying the same update again is a no-op.
+        assert!(!update.apply(&mut config));
+    }
+
+    #[test]
+    fn test_apply_unsafe_delay_update() {
+        let mut config = ReloadableConfig { unsafe_delay_secs: 12, ..Default::default() };
+        let update: ReloadableConfigUpdate =
+            serde_json::from_str(r#"{"unsafeDelayBlocks":3}"#).unwrap();
+        assert!(update.apply(&mut config));
+        assert_eq!(config.unsafe_delay_secs, 12);
+        assert_eq!(config.unsafe_delay_blocks, 3);
+    }
+
+    #[test]
+    fn test_apply_log_levels_update() {
+        let mut config = ReloadableConfig::default();
+        let update: ReloadableConfigUpdate =
+            serde_json::from_str(r#"{"logLevels":{"engine_builder":"trace","p2p":"debug"}}"#)
+                .unwrap();
+        assert!(update.apply(&mut config));
+        assert_eq!(config.log_levels.len(), 2);
+
+        let update: ReloadableConfigUpdate =
+            serde_json::from_str(r#"{"logLevels":{"p2p":""}}"#).unwrap();
+        assert!(update.apply(&mut config));
+        assert_eq!(
+            config.log_levels,
+            BTreeMap::from([("engine_builder".to_string(), "trace".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_apply_channel_capacities_update() {
+        let mut config = ReloadableConfig::default();
+        let update: ReloadableConfigUpdate =
+            serde_json::from_str(r#"{"channelCapacities":{"attributes":4,"unsafe_blocks":64}}"#)
+                .unwrap();
+        assert!(update.apply(&mut config));
+        assert_eq!(config.channel_capacities.len(), 2);
+
+        let update: ReloadableConfigUpdate =
+            serde_json::from_str(r#"{"channelCapacities":{"unsafe_blocks":0}}"#).unwrap();
+        assert!(update.apply(&mut config));
+        assert_eq!(config.channel_capacities, BTreeMap::from([("attributes".to_string(), 4)]));
+    }
+
+    #[test]
+    fn test_apply_dependency_set_update() {
+        let mut config = ReloadableConfig::default();
+        let update: ReloadableConfigUpdate =
+            serde_json::from_str(r#"{"dependencySet":{"addChains":[10,8453]}}"#).unwrap();
+        assert!(update.apply(&mut config));
+        let set = config.dependency_set.as_ref().unwrap();
+        assert_eq!(set.dependencies.len(), 2);
+        assert_eq!(set.get_message_expiry_window(), kona_interop::MESSAGE_EXPIRY_WINDOW);
+
+        let update: ReloadableConfigUpdate = serde_json::from_str(
+            r#"{"dependencySet":{"removeChains":[8453],"messageExpiryWindow":3600}}"#,
+        )
+        .unwrap();
+        assert!(update.clone().apply(&mut config));
+        let set = config.dependency_set.as_ref().unwrap();
+        assert!(set.dependencies.contains_key(&10));
+        assert!(!set.dependencies.contains_key(&8453));
+        assert_eq!(set.get_message_expiry_window(), 3600);
+        assert!(!update.apply(&mut config));
+    }
+
+    #[tokio::test]
+    async fn test_set_log_level() {
+        let rpc = ConfigReloadRpc::new(watch::Sender::new(ReloadableConfig::default()));
+
+        let config = rpc.admin_set_log_level("debug".to_string(), None).await.unwrap();
+        assert_eq!(config.log_filter.as_deref(), Some("debug"));
+
+        let config =
+            rpc.admin_set_log_level("trace".to_string(), Some("engine".to_string())).await.unwrap();
+        assert_eq!(config.log_levels.get("engine").map(String::as_str), Some("trace"));
+        let config =
+            rpc.admin_set_log_level(String::new(), Some("engine".to_string())).await.unwrap();
+        assert!(config.log_levels.is_empty());
+
+        assert!(rpc.admin_set_log_level("loud".to_string(), None).await.is_err());
+        assert!(rpc.admin_set_log_level(String::new(), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_channel_capacity() {
+        let rpc = ConfigReloadRpc::new(watch::Sender::new(ReloadableConfig::default()));
+        let config = rpc.admin_set_channel_capacity("attributes".to_string(), 2).await.unwrap();
+        assert_eq!(config.channel_capacities.get("attributes"), Some(&2));
+        let config = rpc.admin_set_channel_capacity("attributes".to_string(), 0).await.unwrap();
+        assert!(config.channel_capacities.is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_partial_update() {
+        let update: ReloadableConfigUpdate =
+            serde_json::from_str(r#"{"staticPeers":["/ip4/127.0.0.1/tcp/9222"]}"#).unwrap();
+        assert_eq!(
+            update,
+            ReloadableConfigUpdate {
+                static_peers: Some(vec!["/ip4/127.0.0.1/tcp/9222".to_string()]),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Reorg events observed by the rollup node.
+
+use kona_engine::EngineState;
+use kona_protocol::{BlockInfo, L2BlockInfo};
+
+/// A reorg observed by the rollup node.
+///
+/// Reorg events are published on a broadcast channel, and streamed over the
+/// `ws_subscribe_reorgs` subscription, so that downstream consumers can invalidate the data they
+/// hold for the replaced blocks rather than polling the node's heads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ReorgEvent {
+    /// The L1 head no longer extends the previously observed L1 head.
+    L1 {
+        /// The previous L1 head.
+        old_head: BlockInfo,
+        /// The new L1 head.
+        new_head: BlockInfo,
+    },
+    /// The unsafe head no longer extends the previous unsafe head, e.g. after the unsafe chain
+    /// was replaced by derived blocks or by an engine reset.
+    Unsafe {
+        /// The previous unsafe head.
+        old_head: L2BlockInfo,
+        /// The new unsafe head.
+        new_head: L2BlockInfo,
+    },
+    /// The safe head was rewound, or replaced with a block that does not extend it.
+    SafeRewind {
+        /// The previous safe head.
+        old_head: L2BlockInfo,
+        /// The new safe head.
+        new_head: L2BlockInfo,
+    },
+}
+
+impl ReorgEvent {
+    /// Returns a [`ReorgEvent::L1`] if the new L1 head does not extend the old one.
+    pub fn l1(old_head: BlockInfo, new_head: BlockInfo) -> Option<Self> {
+        is_reorg(&old_head, &new_head).then_some(Self::L1 { old_head, new_head })
+    }
+
+    /// Returns a [`ReorgEvent::Unsafe`] if the new unsafe head does not extend the old one.
+    pub fn unsafe_head(old_head: L2BlockInfo, new_head: L2BlockInfo) -> Option<Self> {
+        is_reorg(&old_head.block_info, &new_head.block_info)
+            .then_some(Self::Unsafe { old_head, new_head })
+    }
+
+    /// Returns a [`ReorgEvent::SafeRewind`] if the new safe head does not extend the old one.
+    pub fn safe_head(old_head: L2BlockInfo, new_head: L2BlockInfo) -> Option<Self> {
+        is_reorg(&old_head.block_info, &new_head.block_info)
+            .then_some(Self::SafeRewind { old_head, new_head })
+    }
+
+    /// Returns the reorgs of the unsafe and safe heads between two [`EngineState`]s.
+    pub fn from_engine_states(old: &EngineState, new: &EngineState) -> impl Iterator<Item = Self> {
+        [
+            Self::unsafe_head(old.unsafe_head(), new.unsafe_head()),
+            Self::safe_head(old.safe_head(), new.safe_head()),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Returns `true` if the new head replaces blocks of the chain ending at the old head.
+///
+/// A new head more than one block ahead of the old head cannot be checked without fetching the
+/// blocks in between, and is assumed to extend it. An unset (zero hash) old head is never
+/// reorged.
+fn is_reorg(old: &BlockInfo, new: &BlockInfo) -> bool {
+    if old.hash.is_zero() || old.hash == new.hash {
+        return false;
+    }
+    new.number <= old.number || (new.number == old.number + 1 && !old.is_parent_of(new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    fn block(number: u64, hash: u8, parent: u8) -> BlockInfo {
+        BlockInfo::new(B256::with_last_byte(hash), number, B256::with_last_byte(parent), 0)
+    }
+
+    #[test]
+    fn test_l1_reorg_detection() {
+        let head = block(10, 10, 9);
+        assert_eq!(ReorgEvent::l1(head, head), None);
+        assert_eq!(ReorgEvent::l1(head, block(11, 11, 10)), None);
+        assert_eq!(ReorgEvent::l1(head, block(13, 13, 12)), None);
+        assert_eq!(ReorgEvent::l1(BlockInfo::default(), head), None);
+
+        let sibling = block(10, 20, 9);
+        assert_eq!(
+            ReorgEvent::l1(head, sibling),
+            Some(ReorgEvent::L1 { old_head: head, new_head: sibling })
+        );
+        assert!(ReorgEvent::l1(head, block(11, 21, 20)).is_some());
+        assert!(ReorgEvent::l1(head, block(8, 8, 7)).is_some());
+    }
+
+    #[test]
+    fn test_safe_rewind_detection() {
+        let head = L2BlockInfo { block_info: block(10, 10, 9), ..Default::default() };
+        let rewound = L2BlockInfo { block_info: block(5, 5, 4), ..Default::default() };
+        assert_eq!(
+            ReorgEvent::safe_head(head, rewound),
+            Some(ReorgEvent::SafeRewind { old_head: head, new_head: rewound })
+        );
+        assert_eq!(ReorgEvent::safe_head(rewound, head), None);
+    }
+
+    #[test]
+    fn test_reorg_event_serde() {
+        let event = ReorgEvent::L1 { old_head: block(10, 10, 9), new_head: block(10, 20, 9) };
+        let json = serde_json::to_value(event).unwrap();
+        assert_eq!(json["kind"], "l1");
+        assert_eq!(json["newHead"]["number"], 10);
+        assert_eq!(serde_json::from_value::<ReorgEvent>(json).unwrap(), event);
+    }
+}
@@ -8,13 +8,13 @@ use jsonrpsee::{
     core::RpcResult,
     types::{ErrorCode, ErrorObject},
 };
-use kona_engine::{EngineQueries, EngineQuerySender, EngineState};
+use kona_engine::{EngineQueries, EngineQuerySender, EngineState, SafeHeadIndex};
 use kona_genesis::RollupConfig;
 use kona_protocol::SyncStatus;
 
 use crate::{
-    L1State, L1WatcherQueries, OutputResponse, RollupNodeApiServer, SafeHeadResponse,
-    l1_watcher::L1WatcherQuerySender,
+    BlockLabelSource, L1State, L1WatcherQueries, OutputResponse, RollupNodeApiServer,
+    SafeHeadResponse, l1_watcher::L1WatcherQuerySender,
 };
 
 /// RollupRpc
@@ -26,6 +26,10 @@ pub struct RollupRpc {
     pub engine_sender: EngineQuerySender,
     /// The channel to send [`crate::L1WatcherQueries`]s.
     pub l1_watcher_sender: L1WatcherQuerySender,
+    /// The view that the block labels of queries are resolved from.
+    pub block_labels: BlockLabelSource,
+    /// The [`SafeHeadIndex`] that `optimism_safeHeadAtL1Block` is served from, if any.
+    pub safe_heads: Option<SafeHeadIndex>,
 }
 
 impl RollupRpc {
@@ -37,7 +41,70 @@ impl RollupRpc {
         engine_sender: EngineQuerySender,
         l1_watcher_sender: L1WatcherQuerySender,
     ) -> Self {
-        Self { engine_sender, l1_watcher_sender }
+        Self {
+            engine_sender,
+            l1_watcher_sender,
+            block_labels: BlockLabelSource::ExecutionLayer,
+            safe_heads: None,
+        }
+    }
+
+    /// Sets the view that the block labels of queries are resolved from.
+    pub const fn with_block_labels(mut self, block_labels: BlockLabelSource) -> Self {
+        self.block_labels = block_labels;
+        self
+    }
+
+    /// Sets the [`SafeHeadIndex`] that `optimism_safeHeadAtL1Block` is served from.
+    pub fn with_safe_head_index(mut self, safe_heads: SafeHeadIndex) -> Self {
+        self.safe_heads = Some(safe_heads);
+        self
+    }
+
+    /// Resolves the given L1 block to its number. Labels are resolved against the L1 heads of
+    /// the L1 watcher.
+    async fn resolve_l1_block(&self, block: BlockNumberOrTag) -> RpcResult<u64> {
+        let label = match block {
+            BlockNumberOrTag::Number(number) => return Ok(number),
+            BlockNumberOrTag::Earliest => return Ok(0),
+            label => label,
+        };
+
+        let (l1_state_send, l1_state_recv) = tokio::sync::oneshot::channel();
+        self.l1_watcher_sender
+            .send(L1WatcherQueries::L1State(l1_state_send))
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?;
+        let l1_state =
+            l1_state_recv.await.map_err(|_| ErrorObject::from(ErrorCode::InternalError))?;
+        let head = match label {
+            BlockNumberOrTag::Safe => l1_state.safe_l1,
+            BlockNumberOrTag::Finalized => l1_state.finalized_l1,
+            _ => l1_state.head_l1,
+        };
+        head.map(|head| head.number).ok_or_else(|| {
+            ErrorObject::owned(
+                ErrorCode::InvalidParams.code(),
+                format!("L1 block {label} is not known yet"),
+                None::<()>,
+            )
+        })
+    }
+
+    /// Resolves the label of the given block from the engine state, if configured to. Otherwise,
+    /// the label is left to be resolved by the EL.
+    async fn resolve_block(&self, block: BlockNumberOrTag) -> RpcResult<BlockNumberOrTag> {
+        if self.block_labels == BlockLabelSource::ExecutionLayer || block.is_number() {
+            return Ok(block);
+        }
+
+        let (state_send, state_recv) = tokio::sync::oneshot::channel();
+        self.engine_sender
+            .send(EngineQueries::State(state_send))
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?;
+        let state = state_recv.await.map_err(|_| ErrorObject::from(ErrorCode::InternalError))?;
+        Ok(state.resolve_label(block))
     }
 
     // Important note: we zero-out the fields that can't be derived yet to follow op-node's
@@ -57,6 +124,8 @@ impl RollupRpc {
             local_safe_l2: l2_sync_status.local_safe_head(),
             safe_l2: l2_sync_status.safe_head(),
             finalized_l2: l2_sync_status.finalized_head(),
+            el_sync: l2_sync_status.el_sync_progress(),
+            deposits_only_l2: l2_sync_status.deposits_only_head(),
         }
     }
 }
@@ -66,6 +135,7 @@ impl RollupNodeApiServer for RollupRpc {
     async fn op_output_at_block(&self, block_num: BlockNumberOrTag) -> RpcResult<OutputResponse> {
         kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "op_outputAtBlock");
 
+        let block_num = self.resolve_block(block_num).await?;
         let (output_send, output_recv) = tokio::sync::oneshot::channel();
         let (l1_sync_status_send, l1_sync_status_recv) = tokio::sync::oneshot::channel();
 
@@ -93,14 +163,27 @@ impl RollupNodeApiServer for RollupRpc {
         Ok(OutputResponse::from_v0(output_root, sync_status, l2_block_info))
     }
 
-    /// This RPC endpoint is not supported. It is not necessary to track the safe head for every L1
-    /// block post-interop anymore so we can remove this method from the rpc interface.
+    /// Returns the safe head derived from the L1 chain up to and including the given L1 block,
+    /// from the [`SafeHeadIndex`]. Only the L1 blocks within the retention of the index are
+    /// served.
     async fn op_safe_head_at_l1_block(
         &self,
-        _block_num: BlockNumberOrTag,
+        block_num: BlockNumberOrTag,
     ) -> RpcResult<SafeHeadResponse> {
         kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "op_safeHeadAtL1Block");
-        return Err(ErrorObject::from(ErrorCode::MethodNotFound));
+
+        let Some(safe_heads) = &self.safe_heads else {
+            return Err(ErrorObject::from(ErrorCode::MethodNotFound));
+        };
+        let number = self.resolve_l1_block(block_num).await?;
+        let entry = safe_heads.safe_head_at(number).ok_or_else(|| {
+            ErrorObject::owned(
+                ErrorCode::InvalidParams.code(),
+                format!("no safe head indexed at L1 block {number}"),
+                None::<()>,
+            )
+        })?;
+        Ok(SafeHeadResponse { l1_block: entry.l1_block, safe_head: entry.safe_head })
     }
 
     async fn op_sync_status(&self) -> RpcResult<SyncStatus> {
@@ -150,3 +233,57 @@ impl RollupNodeApiServer for RollupRpc {
         return Ok(RPC_VERSION.to_string());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::BlockNumHash;
+    use alloy_primitives::B256;
+    use kona_engine::SafeHeadRetention;
+    use kona_protocol::BlockInfo;
+
+    fn block(number: u64) -> BlockNumHash {
+        BlockNumHash { number, hash: B256::with_last_byte(number as u8) }
+    }
+
+    #[tokio::test]
+    async fn test_safe_head_at_l1_block() {
+        let (engine_sender, _engine_recv) = tokio::sync::mpsc::channel(1);
+        let (l1_watcher_sender, mut l1_watcher_recv) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            while let Some(L1WatcherQueries::L1State(sender)) = l1_watcher_recv.recv().await {
+                let head = BlockInfo { number: 12, ..Default::default() };
+                let safe = BlockInfo { number: 9, ..Default::default() };
+                sender
+                    .send(L1State {
+                        current_l1: None,
+                        current_l1_finalized: None,
+                        head_l1: Some(head),
+                        safe_l1: Some(safe),
+                        finalized_l1: None,
+                    })
+                    .ok();
+            }
+        });
+
+        let rpc = RollupRpc::new(engine_sender.clone(), l1_watcher_sender.clone());
+        let err = rpc.op_safe_head_at_l1_block(BlockNumberOrTag::Number(10)).await.unwrap_err();
+        assert_eq!(err.code(), ErrorCode::MethodNotFound.code());
+
+        let safe_heads = SafeHeadIndex::new(SafeHeadRetention::Full);
+        safe_heads.record(block(10), block(100));
+        safe_heads.record(block(11), block(110));
+        let rpc = RollupRpc::new(engine_sender, l1_watcher_sender).with_safe_head_index(safe_heads);
+
+        let response = rpc.op_safe_head_at_l1_block(BlockNumberOrTag::Number(10)).await.unwrap();
+        assert_eq!(response, SafeHeadResponse { l1_block: block(10), safe_head: block(100) });
+        let response = rpc.op_safe_head_at_l1_block(BlockNumberOrTag::Latest).await.unwrap();
+        assert_eq!(response, SafeHeadResponse { l1_block: block(11), safe_head: block(110) });
+
+        // L1 blocks before the oldest indexed one, and unknown labels, are not served.
+        let err = rpc.op_safe_head_at_l1_block(BlockNumberOrTag::Safe).await.unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+        let err = rpc.op_safe_head_at_l1_block(BlockNumberOrTag::Finalized).await.unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+    }
+}
@@ -0,0 +1,143 @@
+//! Contains the [`ActorStatsRegistry`], which tracks the utilization of the rollup node's actors,
+//! and the RPC server that serves it.
+
+use crate::ActorStatsApiServer;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use std::collections::BTreeMap;
+use tokio::sync::watch;
+
+/// The utilization of a single actor of the rollup node.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActorStats {
+    /// The number of iterations of the actor's select loop since it was started, i.e. the
+    /// number of times its task was polled.
+    pub iterations: u64,
+    /// The total time spent in the actor's select loop since it was started, in milliseconds.
+    pub busy_ms: u64,
+    /// The number of iterations per second over the last sampling interval.
+    pub iteration_rate: f64,
+    /// The fraction of the last sampling interval spent in the actor's select loop, between `0`
+    /// and `1`. An actor close to `1` is the bottleneck of the node.
+    pub busy_ratio: f64,
+}
+
+/// The occupancy of a single event channel between the rollup node's actors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueStats {
+    /// The number of events queued in the channel.
+    pub depth: usize,
+    /// The effective capacity of the channel.
+    pub capacity: usize,
+}
+
+/// A sample of the [`ActorStats`] of each actor and the [`QueueStats`] of each event channel.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActorStatsSnapshot {
+    /// The length of the sampling interval the rates and ratios are computed over, in
+    /// milliseconds.
+    pub interval_ms: u64,
+    /// The utilization of each actor, by name.
+    pub actors: BTreeMap<String, ActorStats>,
+    /// The occupancy of each event channel, by name.
+    pub queues: BTreeMap<String, QueueStats>,
+}
+
+/// A registry of the latest [`ActorStatsSnapshot`] of the rollup node.
+///
+/// Like the [`HealthRegistry`], the registry is backed by a [`watch`] channel, so consumers may
+/// either take a [snapshot][ActorStatsRegistry::snapshot] or
+/// [subscribe][ActorStatsRegistry::subscribe] to new samples.
+///
+/// [`HealthRegistry`]: crate::HealthRegistry
+#[derive(Debug, Clone)]
+pub struct ActorStatsRegistry {
+    /// The latest sample.
+    stats: watch::Sender<ActorStatsSnapshot>,
+}
+
+impl Default for ActorStatsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActorStatsRegistry {
+    /// Creates a new, empty [`ActorStatsRegistry`].
+    pub fn new() -> Self {
+        Self { stats: watch::Sender::new(ActorStatsSnapshot::default()) }
+    }
+
+    /// Replaces the latest sample.
+    pub fn set(&self, snapshot: ActorStatsSnapshot) {
+        self.stats.send_replace(snapshot);
+    }
+
+    /// Returns the [`ActorStats`] of the named actor in the latest sample, if any.
+    pub fn get(&self, name: &str) -> Option<ActorStats> {
+        self.stats.borrow().actors.get(name).cloned()
+    }
+
+    /// Returns the latest sample.
+    pub fn snapshot(&self) -> ActorStatsSnapshot {
+        self.stats.borrow().clone()
+    }
+
+    /// Subscribes to new samples.
+    pub fn subscribe(&self) -> watch::Receiver<ActorStatsSnapshot> {
+        self.stats.subscribe()
+    }
+}
+
+/// The RPC server serving the latest sample of the [`ActorStatsRegistry`].
+#[derive(Debug, Clone)]
+pub struct ActorStatsRpc {
+    /// The [`ActorStatsRegistry`], written by the node's sampler.
+    pub registry: ActorStatsRegistry,
+}
+
+impl ActorStatsRpc {
+    /// The identifier for the Metric that tracks actor stats RPC calls.
+    pub const RPC_IDENT: &'static str = "kona_node_rpc_calls";
+
+    /// Constructs a new [`ActorStatsRpc`] given the [`ActorStatsRegistry`].
+    pub const fn new(registry: ActorStatsRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait]
+impl ActorStatsApiServer for ActorStatsRpc {
+    async fn admin_actor_stats(&self) -> RpcResult<ActorStatsSnapshot> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "admin_actorStats");
+        Ok(self.registry.snapshot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_serde() {
+        let registry = ActorStatsRegistry::new();
+        let mut snapshot = ActorStatsSnapshot { interval_ms: 1000, ..Default::default() };
+        snapshot.actors.insert(
+            "engine".to_string(),
+            ActorStats { iterations: 10, busy_ms: 500, iteration_rate: 2.0, busy_ratio: 0.5 },
+        );
+        snapshot.queues.insert("attributes".to_string(), QueueStats { depth: 3, capacity: 16 });
+        registry.set(snapshot.clone());
+        assert_eq!(registry.get("engine").unwrap().iterations, 10);
+
+        let json = serde_json::to_string(&registry.snapshot()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"intervalMs":1000,"actors":{"engine":{"iterations":10,"busyMs":500,"iterationRate":2.0,"busyRatio":0.5}},"queues":{"attributes":{"depth":3,"capacity":16}}}"#
+        );
+        assert_eq!(serde_json::from_str::<ActorStatsSnapshot>(&json).unwrap(), snapshot);
+    }
+}
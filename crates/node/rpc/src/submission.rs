@@ -0,0 +1,160 @@
+//! Contains the [`SubmissionRegistry`], which tracks how much of the unsafe chain has been
+//! submitted to the batch inbox on L1, and the RPC server that serves it.
+
+use crate::SubmissionApiServer;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use tokio::sync::watch;
+
+/// How much of the unsafe chain has been observed as submitted to the batch inbox on L1.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionStatus {
+    /// The number of the L2 unsafe head.
+    pub unsafe_head: u64,
+    /// The number of the L2 safe head.
+    pub safe_head: u64,
+    /// The number of the highest L2 block covered by a batch read from L1, if any batch was read
+    /// since the node started.
+    pub submitted_head: Option<u64>,
+    /// The number of unsafe blocks that are neither safe nor covered by a batch read from L1.
+    ///
+    /// On a sequencer, a count that keeps growing means that the batcher is not submitting, and
+    /// that the safe head will eventually lag behind by more than the sequencing window.
+    pub unsubmitted: u64,
+}
+
+impl SubmissionStatus {
+    /// Recomputes the number of [unsubmitted][Self::unsubmitted] blocks.
+    const fn update(&mut self) {
+        let submitted = match self.submitted_head {
+            Some(submitted) if submitted > self.safe_head => submitted,
+            _ => self.safe_head,
+        };
+        self.unsubmitted = self.unsafe_head.saturating_sub(submitted);
+    }
+}
+
+/// A registry of the [`SubmissionStatus`] of the rollup node.
+///
+/// Like the [`HealthRegistry`], the registry is backed by a [`watch`] channel, so consumers may
+/// either take a [snapshot][SubmissionRegistry::status] or
+/// [subscribe][SubmissionRegistry::subscribe] to updates.
+///
+/// [`HealthRegistry`]: crate::HealthRegistry
+#[derive(Debug, Clone)]
+pub struct SubmissionRegistry {
+    /// The latest status.
+    status: watch::Sender<SubmissionStatus>,
+}
+
+impl Default for SubmissionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SubmissionRegistry {
+    /// Creates a new [`SubmissionRegistry`], with no block submitted.
+    pub fn new() -> Self {
+        Self { status: watch::Sender::new(SubmissionStatus::default()) }
+    }
+
+    /// Records that the L2 block with the given number was covered by a batch read from L1.
+    /// Blocks below the highest one recorded so far are ignored, since batches are re-read when
+    /// the derivation pipeline is reset.
+    pub fn record_submitted(&self, number: u64) {
+        self.status.send_if_modified(|status| {
+            if status.submitted_head.is_some_and(|submitted| submitted >= number) {
+                return false;
+            }
+            status.submitted_head = Some(number);
+            status.update();
+            true
+        });
+    }
+
+    /// Updates the numbers of the L2 unsafe and safe heads.
+    pub fn update_heads(&self, unsafe_head: u64, safe_head: u64) {
+        self.status.send_if_modified(|status| {
+            if status.unsafe_head == unsafe_head && status.safe_head == safe_head {
+                return false;
+            }
+            status.unsafe_head = unsafe_head;
+            status.safe_head = safe_head;
+            status.update();
+            true
+        });
+    }
+
+    /// Returns the latest status.
+    pub fn status(&self) -> SubmissionStatus {
+        *self.status.borrow()
+    }
+
+    /// Subscribes to updates of the status.
+    pub fn subscribe(&self) -> watch::Receiver<SubmissionStatus> {
+        self.status.subscribe()
+    }
+}
+
+/// The RPC server serving the [`SubmissionStatus`] of the [`SubmissionRegistry`].
+#[derive(Debug, Clone)]
+pub struct SubmissionRpc {
+    /// The [`SubmissionRegistry`], written by the node's submission tracker.
+    pub registry: SubmissionRegistry,
+}
+
+impl SubmissionRpc {
+    /// The identifier for the Metric that tracks submission RPC calls.
+    pub const RPC_IDENT: &'static str = "kona_node_rpc_calls";
+
+    /// Constructs a new [`SubmissionRpc`] given the [`SubmissionRegistry`].
+    pub const fn new(registry: SubmissionRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait]
+impl SubmissionApiServer for SubmissionRpc {
+    async fn admin_submission_status(&self) -> RpcResult<SubmissionStatus> {
+        kona_macros::inc!(gauge, Self::RPC_IDENT, "method" => "admin_submissionStatus");
+        Ok(self.registry.status())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsubmitted_blocks() {
+        let registry = SubmissionRegistry::new();
+        registry.update_heads(100, 80);
+        assert_eq!(registry.status().unsubmitted, 20);
+
+        // Batches read below the safe head do not count.
+        registry.record_submitted(70);
+        assert_eq!(registry.status().unsubmitted, 20);
+
+        registry.record_submitted(90);
+        assert_eq!(registry.status().unsubmitted, 10);
+
+        // Batches re-read after a reset do not move the submitted head back.
+        registry.record_submitted(85);
+        assert_eq!(registry.status().submitted_head, Some(90));
+
+        registry.update_heads(110, 95);
+        assert_eq!(registry.status().unsubmitted, 15);
+    }
+
+    #[test]
+    fn test_status_serde() {
+        let registry = SubmissionRegistry::new();
+        registry.update_heads(12, 4);
+        registry.record_submitted(8);
+        let json = serde_json::to_string(&registry.status()).unwrap();
+        assert_eq!(json, r#"{"unsafeHead":12,"safeHead":4,"submittedHead":8,"unsubmitted":4}"#);
+        assert_eq!(serde_json::from_str::<SubmissionStatus>(&json).unwrap(), registry.status());
+    }
+}
@@ -5,22 +5,28 @@ use jsonrpsee::{
 };
 use kona_engine::{EngineQueries, EngineQuerySender, EngineState};
 use kona_protocol::L2BlockInfo;
+use tokio::sync::broadcast::{self, error::RecvError};
 
 use jsonrpsee::core::to_json_raw_value;
 
-use crate::jsonrpsee::WsServer;
+use crate::{ReorgEvent, jsonrpsee::WsServer};
 
 /// An RPC server that handles subscriptions to the node's state.
 #[derive(Debug)]
 pub struct WsRPC {
     /// The engine query sender.
     engine_query_sender: EngineQuerySender,
+    /// The sender of the node's [`ReorgEvent`]s, used to subscribe to them.
+    reorgs: broadcast::Sender<ReorgEvent>,
 }
 
 impl WsRPC {
     /// Constructs a new [`WsRPC`] instance.
-    pub const fn new(engine_query_sender: EngineQuerySender) -> Self {
-        Self { engine_query_sender }
+    pub const fn new(
+        engine_query_sender: EngineQuerySender,
+        reorgs: broadcast::Sender<ReorgEvent>,
+    ) -> Self {
+        Self { engine_query_sender, reorgs }
     }
 
     async fn engine_state_watcher(
@@ -124,4 +130,34 @@ impl WsServer for WsRPC {
         warn!(target: "rpc::ws", "Subscription to unsafe head updates has been closed.");
         Ok(())
     }
+
+    async fn ws_reorg_events(&self, sink: PendingSubscriptionSink) -> SubscriptionResult {
+        let mut reorgs = self.reorgs.subscribe();
+        let sink = sink.accept().await?;
+
+        loop {
+            let event = match reorgs.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(target: "rpc::ws", skipped, "Reorg subscriber lagged behind; events were dropped.");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            sink.send(to_json_raw_value(&event).map_err(|_| {
+                jsonrpsee::core::SubscriptionError::from(
+                    "Internal error. Impossible to convert reorg event to json",
+                )
+            })?)
+            .await
+            .map_err(|_| {
+                jsonrpsee::core::SubscriptionError::from(
+                    "Failed to send reorg event. Subscription likely dropped.",
+                )
+            })?;
+        }
+
+        warn!(target: "rpc::ws", "Subscription to reorg events has been closed.");
+        Ok(())
+    }
 }
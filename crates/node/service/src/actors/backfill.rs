@@ -0,0 +1,593 @@
+//! [NodeActor] implementation for the backfill sub-routine.
+
+use crate::{Clock, HealthReporter, Metrics, NodeActor, actors::CancellableContext};
+use alloy_eips::BlockNumberOrTag;
+use alloy_provider::Provider;
+use async_trait::async_trait;
+use kona_derive::{
+    ActivationSignal, Pipeline, PipelineError, PipelineErrorKind, ResetError, ResetSignal,
+    SignalReceiver, StepResult,
+};
+use kona_engine::{AttributesMatch, EngineClient, EngineClientError};
+use kona_protocol::{BlockInfo, L2BlockInfo, OpAttributesWithParent};
+use std::{sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+
+/// The [NodeActor] for the backfill sub-routine.
+///
+/// The backfill actor runs a second derivation pipeline, starting a configurable number of blocks
+/// behind the engine's safe head. At a low, fixed rate, it re-derives the payload attributes of
+/// the next historical safe block and checks them against the block held by the execution layer.
+/// Any divergence is logged, counted in [`Metrics::BACKFILL_DIVERGENCE_COUNT`], and marks the
+/// actor as degraded until a later block is verified. Once it has caught up to the safe head, the
+/// actor follows it.
+///
+/// The actor never modifies the execution layer, and errors are retried rather than propagated,
+/// so that the integrity check cannot bring down the node.
+#[derive(Debug)]
+pub struct BackfillActor<P>
+where
+    P: Pipeline + SignalReceiver,
+{
+    /// The state for the backfill actor.
+    state: BackfillState<P>,
+    /// The last verified L2 block, from which the next block is derived.
+    cursor: Option<L2BlockInfo>,
+    /// Whether the pipeline must be reset to the cursor before it is stepped.
+    needs_reset: bool,
+}
+
+/// The configuration for the [`BackfillActor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillConfig {
+    /// The interval between verified blocks.
+    pub interval: Duration,
+    /// The number of blocks behind the safe head to start verifying from.
+    pub depth: u64,
+}
+
+/// The state for the backfill actor.
+#[derive(Debug)]
+pub struct BackfillState<P>
+where
+    P: Pipeline + SignalReceiver,
+{
+    /// The derivation pipeline used to re-derive historical blocks. This must not be shared
+    /// with the derivation actor.
+    pub pipeline: P,
+    /// The engine client, used to fetch historical blocks from the execution layer.
+    pub client: Arc<EngineClient>,
+    /// The [`BackfillConfig`].
+    pub config: BackfillConfig,
+    /// The [`Clock`] that drives the verification interval.
+    pub clock: Arc<dyn Clock>,
+}
+
+/// The communication context used by the backfill actor.
+#[derive(Debug)]
+pub struct BackfillContext {
+    /// The receiver for L2 safe head update notifications.
+    pub engine_l2_safe_head: watch::Receiver<L2BlockInfo>,
+    /// Reports the health of the backfill actor.
+    pub health: HealthReporter,
+    /// The cancellation token, shared between all tasks.
+    pub cancellation: CancellationToken,
+}
+
+impl CancellableContext for BackfillContext {
+    fn cancelled(&self) -> WaitForCancellationFuture<'_> {
+        self.cancellation.cancelled()
+    }
+}
+
+impl<P> BackfillActor<P>
+where
+    P: Pipeline + SignalReceiver,
+{
+    /// Creates a new instance of the [BackfillActor].
+    pub const fn new(state: BackfillState<P>) -> ((), Self) {
+        ((), Self { state, cursor: None, needs_reset: true })
+    }
+
+    /// Resets the pipeline so that the next block it derives is the child of the given L2 block.
+    async fn reset(&mut self, number: u64) -> Result<L2BlockInfo, BackfillError> {
+        let client = &self.state.client;
+        let l2_safe_head = client
+            .l2_block_info_by_label(BlockNumberOrTag::Number(number))
+            .await?
+            .ok_or(BackfillError::BlockNotFound(number))?;
+
+        // Start from a channel timeout before the block's L1 origin, so that channels which
+        // include the block's batch are read in full.
+        let origin = l2_safe_head
+            .l1_origin
+            .number
+            .saturating_sub(client.cfg().channel_timeout(l2_safe_head.block_info.timestamp));
+        let l1_origin: BlockInfo = client
+            .l1_provider()
+            .get_block(origin.into())
+            .await?
+            .ok_or(BackfillError::BlockNotFound(origin))?
+            .into_consensus()
+            .into();
+        let system_config = self.state.pipeline.system_config_by_number(number).await?;
+
+        self.state
+            .pipeline
+            .signal(
+                ResetSignal { l2_safe_head, l1_origin, system_config: Some(system_config) }
+                    .signal(),
+            )
+            .await?;
+        debug!(target: "backfill", l2_block = number, l1_block = origin, "Reset backfill pipeline");
+
+        self.cursor = Some(l2_safe_head);
+        self.needs_reset = false;
+        Ok(l2_safe_head)
+    }
+
+    /// Steps the pipeline until it produces the attributes of the child of the given L2 block.
+    /// Returns [`None`] if the pipeline ran out of data.
+    async fn derive_next(
+        &mut self,
+        parent: L2BlockInfo,
+    ) -> Result<Option<OpAttributesWithParent>, BackfillError> {
+        loop {
+            match self.state.pipeline.step(parent).await {
+                StepResult::PreparedAttributes | StepResult::AdvancedOrigin => {}
+                StepResult::OriginAdvanceErr(e) | StepResult::StepFailed(e) => match e {
+                    PipelineErrorKind::Temporary(PipelineError::NotEnoughData) => continue,
+                    PipelineErrorKind::Temporary(_) => return Ok(None),
+                    PipelineErrorKind::Reset(ResetError::HoloceneActivation) => {
+                        let l1_origin = self
+                            .state
+                            .pipeline
+                            .origin()
+                            .ok_or(PipelineError::MissingOrigin.crit())?;
+                        let system_config = self
+                            .state
+                            .pipeline
+                            .system_config_by_number(parent.block_info.number)
+                            .await?;
+                        self.state
+                            .pipeline
+                            .signal(
+                                ActivationSignal {
+                                    l2_safe_head: parent,
+                                    l1_origin,
+                                    system_config: Some(system_config),
+                                }
+                                .signal(),
+                            )
+                            .await?;
+                    }
+                    e => return Err(e.into()),
+                },
+            }
+
+            if let Some(attributes) = self.state.pipeline.next() {
+                return Ok(Some(attributes));
+            }
+        }
+    }
+
+    /// Re-derives the block following the cursor, if it is not past the given safe head, and
+    /// checks it against the execution layer.
+    async fn verify_next(
+        &mut self,
+        safe_head: L2BlockInfo,
+        health: &HealthReporter,
+    ) -> Result<(), BackfillError> {
+        let cursor = match self.cursor {
+            Some(cursor) if !self.needs_reset => cursor,
+            cursor => {
+                let genesis = self.state.client.cfg().genesis.l2.number;
+                let start = cursor.map(|c| c.block_info.number).unwrap_or_else(|| {
+                    safe_head.block_info.number.saturating_sub(self.state.config.depth).max(genesis)
+                });
+                self.reset(start).await?
+            }
+        };
+
+        // Only verify blocks that the node has already marked safe.
+        if cursor.block_info.number >= safe_head.block_info.number {
+            trace!(target: "backfill", "Caught up to the safe head");
+            return Ok(());
+        }
+
+        let Some(attributes) = self.derive_next(cursor).await? else {
+            trace!(target: "backfill", "Exhausted data source; waiting for the next tick");
+            return Ok(());
+        };
+
+        let number = cursor.block_info.number + 1;
+        let block = self
+            .state
+            .client
+            .l2_block_by_label(BlockNumberOrTag::Number(number))
+            .await?
+            .ok_or(BackfillError::BlockNotFound(number))?;
+        match AttributesMatch::check(self.state.client.cfg(), &attributes, &block) {
+            AttributesMatch::Match => {
+                debug!(target: "backfill", number, hash = %block.header.hash, "Verified safe block");
+                health.healthy();
+            }
+            AttributesMatch::Mismatch(mismatch) => {
+                error!(
+                    target: "backfill",
+                    number,
+                    hash = %block.header.hash,
+                    ?mismatch,
+                    "Re-derived attributes diverge from the execution layer's safe block"
+                );
+                kona_macros::inc!(counter, Metrics::BACKFILL_DIVERGENCE_COUNT);
+                health.degraded(format!("safe block {number} diverges: {mismatch:?}"));
+            }
+        }
+
+        // Continue along the execution layer's chain, even if the block diverged, so that a
+        // single mismatch is reported once.
+        self.cursor = Some(
+            self.state
+                .client
+                .l2_block_info_by_label(BlockNumberOrTag::Number(number))
+                .await?
+                .ok_or(BackfillError::BlockNotFound(number))?,
+        );
+        kona_macros::set!(gauge, Metrics::BACKFILL_VERIFIED_BLOCK, number as f64);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P> NodeActor for BackfillActor<P>
+where
+    P: Pipeline + SignalReceiver + Send + Sync + 'static,
+{
+    const NAME: &'static str = "backfill";
+    type Error = BackfillError;
+    type InboundData = BackfillContext;
+    type State = BackfillState<P>;
+    type OutboundData = ();
+
+    fn build(state: Self::State) -> (Self::OutboundData, Self) {
+        Self::new(state)
+    }
+
+    async fn start(
+        mut self,
+        BackfillContext { engine_l2_safe_head, health, cancellation }: Self::InboundData,
+    ) -> Result<(), Self::Error> {
+        let clock = self.state.clock.clone();
+        let mut next_tick = clock.now();
+        loop {
+            tokio::select! {
+                _ = cancellation.cancelled() => {
+                    info!(target: "backfill", "Received shutdown signal. Exiting backfill task.");
+                    return Ok(());
+                }
+                _ = clock.sleep_until(next_tick) => {
+                    next_tick += self.state.config.interval;
+
+                    // Wait for the engine to initialize its safe head.
+                    let safe_head = *engine_l2_safe_head.borrow();
+                    if safe_head.block_info.hash.is_zero() {
+                        continue;
+                    }
+
+                    if let Err(e) = self.verify_next(safe_head, &health).await {
+                        warn!(target: "backfill", %e, "Failed to verify safe block; retrying from the last verified block");
+                        self.needs_reset = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An error from the [`BackfillActor`].
+#[derive(Error, Debug)]
+pub enum BackfillError {
+    /// An error from the derivation pipeline.
+    #[error(transparent)]
+    Pipeline(#[from] PipelineErrorKind),
+    /// An error from the engine client.
+    #[error(transparent)]
+    Client(#[from] EngineClientError),
+    /// An error from the L1 provider.
+    #[error(transparent)]
+    Rpc(#[from] alloy_transport::TransportError),
+    /// A block was not found.
+    #[error("Block {0} not found")]
+    BlockNotFound(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SystemClock;
+    use alloy_consensus::{Header, Sealed, transaction::Recovered};
+    use alloy_eips::Encodable2718;
+    use alloy_primitives::{Address, B256, U256};
+    use alloy_rpc_types_engine::PayloadAttributes;
+    use alloy_rpc_types_eth::BlockTransactions;
+    use kona_derive::{OriginProvider, PipelineResult, PipelineStateSize, Signal};
+    use kona_engine::MockEngineClient;
+    use kona_genesis::{RollupConfig, SystemConfig};
+    use kona_protocol::L1BlockInfoTx;
+    use kona_rpc::{HealthRegistry, HealthStatus};
+    use op_alloy_consensus::{OpTxEnvelope, TxDeposit};
+    use op_alloy_rpc_types_engine::OpPayloadAttributes;
+    use std::collections::VecDeque;
+
+    /// The gas limit of the L2 blocks.
+    const GAS_LIMIT: u64 = 30_000_000;
+
+    /// A [`Pipeline`] that prepares the queued attributes, one per step, and records the signals
+    /// it receives.
+    #[derive(Debug, Default)]
+    struct QueuedPipeline {
+        queued: VecDeque<OpAttributesWithParent>,
+        prepared: Option<OpAttributesWithParent>,
+        signals: Vec<Signal>,
+        config: RollupConfig,
+    }
+
+    impl Iterator for QueuedPipeline {
+        type Item = OpAttributesWithParent;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.prepared.take()
+        }
+    }
+
+    impl OriginProvider for QueuedPipeline {
+        fn origin(&self) -> Option<BlockInfo> {
+            Some(BlockInfo::default())
+        }
+    }
+
+    #[async_trait]
+    impl SignalReceiver for QueuedPipeline {
+        async fn signal(&mut self, signal: Signal) -> PipelineResult<()> {
+            self.signals.push(signal);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Pipeline for QueuedPipeline {
+        fn peek(&self) -> Option<&OpAttributesWithParent> {
+            self.prepared.as_ref()
+        }
+
+        async fn step(&mut self, _: L2BlockInfo) -> StepResult {
+            match self.queued.pop_front() {
+                Some(attributes) => {
+                    self.prepared = Some(attributes);
+                    StepResult::PreparedAttributes
+                }
+                None => StepResult::StepFailed(PipelineError::Eof.temp()),
+            }
+        }
+
+        fn rollup_config(&self) -> &RollupConfig {
+            &self.config
+        }
+
+        fn state_size(&self) -> PipelineStateSize {
+            PipelineStateSize::default()
+        }
+
+        async fn system_config_by_number(
+            &mut self,
+            _: u64,
+        ) -> Result<SystemConfig, PipelineErrorKind> {
+            Ok(SystemConfig::default())
+        }
+    }
+
+    /// Returns the L1 info deposit of the L2 block with the given number.
+    fn deposit(cfg: &RollupConfig, number: u64) -> Sealed<TxDeposit> {
+        let (_, deposit) = L1BlockInfoTx::try_new_with_deposit_tx(
+            cfg,
+            &SystemConfig::default(),
+            number,
+            &Header::default(),
+            number * 2,
+        )
+        .unwrap();
+        deposit
+    }
+
+    /// Returns the L2 block with the given number and parent, holding its L1 info deposit.
+    fn block(
+        cfg: &RollupConfig,
+        number: u64,
+        parent_hash: B256,
+    ) -> alloy_rpc_types_eth::Block<op_alloy_rpc_types::Transaction> {
+        let header = Header {
+            number,
+            parent_hash,
+            timestamp: number * 2,
+            gas_limit: GAS_LIMIT,
+            ..Default::default()
+        };
+        let hash = header.hash_slow();
+        let deposit = deposit(cfg, number);
+        let from = deposit.from;
+        let tx = op_alloy_rpc_types::Transaction {
+            inner: alloy_rpc_types_eth::Transaction {
+                inner: Recovered::new_unchecked(OpTxEnvelope::Deposit(deposit), from),
+                block_hash: Some(hash),
+                block_number: Some(number),
+                transaction_index: Some(0),
+                effective_gas_price: Some(0),
+            },
+            deposit_nonce: None,
+            deposit_receipt_version: None,
+        };
+        alloy_rpc_types_eth::Block {
+            header: alloy_rpc_types_eth::Header {
+                hash,
+                inner: header,
+                total_difficulty: Some(U256::ZERO),
+                size: None,
+            },
+            uncles: vec![],
+            transactions: BlockTransactions::Full(vec![tx]),
+            withdrawals: None,
+        }
+    }
+
+    /// Returns the attributes deriving the child of the given block, with the given
+    /// `prev_randao`. The attributes match the child iff `prev_randao` is zero.
+    fn attributes(
+        cfg: &RollupConfig,
+        parent: L2BlockInfo,
+        prev_randao: B256,
+    ) -> OpAttributesWithParent {
+        let number = parent.block_info.number + 1;
+        let attributes = OpPayloadAttributes {
+            payload_attributes: PayloadAttributes {
+                timestamp: number * 2,
+                prev_randao,
+                suggested_fee_recipient: Address::ZERO,
+                withdrawals: None,
+                parent_beacon_block_root: None,
+            },
+            transactions: Some(vec![
+                OpTxEnvelope::Deposit(deposit(cfg, number)).encoded_2718().into(),
+            ]),
+            no_tx_pool: Some(true),
+            gas_limit: Some(GAS_LIMIT),
+            eip_1559_params: None,
+        };
+        OpAttributesWithParent::new(attributes, parent, BlockInfo::default(), true)
+    }
+
+    /// A backfill actor verifying a mock execution layer holding the blocks up to 2, one block
+    /// behind the safe head.
+    struct Harness {
+        actor: BackfillActor<QueuedPipeline>,
+        mock: MockEngineClient,
+        registry: HealthRegistry,
+        health: HealthReporter,
+    }
+
+    impl Harness {
+        /// Creates a harness verifying from one block behind the safe head.
+        fn new() -> Self {
+            let cfg = Arc::new(RollupConfig::default());
+            let mock = MockEngineClient::new(cfg.clone());
+
+            // Block 0 stands in for the L1 origin of the L2 blocks.
+            let origin = Header::default();
+            mock.insert_block(
+                0,
+                &alloy_rpc_types_eth::Block::<alloy_rpc_types_eth::Transaction> {
+                    header: alloy_rpc_types_eth::Header {
+                        hash: origin.hash_slow(),
+                        inner: origin,
+                        total_difficulty: Some(U256::ZERO),
+                        size: None,
+                    },
+                    uncles: vec![],
+                    transactions: BlockTransactions::Full(vec![]),
+                    withdrawals: None,
+                },
+            );
+            let first = block(&cfg, 1, B256::ZERO);
+            let second = block(&cfg, 2, first.header.hash);
+            mock.insert_block(1, &first);
+            mock.insert_block(2, &second);
+
+            let state = BackfillState {
+                pipeline: QueuedPipeline::default(),
+                client: mock.client(),
+                config: BackfillConfig { interval: Duration::from_secs(1), depth: 1 },
+                clock: Arc::new(SystemClock),
+            };
+            let (_, actor) = BackfillActor::new(state);
+            let registry = HealthRegistry::new();
+            let health =
+                HealthReporter::new(BackfillActor::<QueuedPipeline>::NAME, registry.clone());
+            Self { actor, mock, registry, health }
+        }
+
+        /// Returns the block reference of the given block of the execution layer.
+        async fn local(&self, number: u64) -> L2BlockInfo {
+            self.mock
+                .client()
+                .l2_block_info_by_label(BlockNumberOrTag::Number(number))
+                .await
+                .unwrap()
+                .unwrap()
+        }
+
+        /// Returns the health status of the actor.
+        fn status(&self) -> Option<HealthStatus> {
+            self.registry.get(BackfillActor::<QueuedPipeline>::NAME)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_next_match() {
+        let mut harness = Harness::new();
+        let (first, safe_head) = (harness.local(1).await, harness.local(2).await);
+        let cfg = harness.actor.state.client.cfg().clone();
+        harness.actor.state.pipeline.queued.push_back(attributes(&cfg, first, B256::ZERO));
+
+        harness.actor.verify_next(safe_head, &harness.health).await.unwrap();
+
+        // The pipeline was reset to the block at the configured depth behind the safe head.
+        assert!(matches!(
+            harness.actor.state.pipeline.signals.as_slice(),
+            [Signal::Reset(ResetSignal { l2_safe_head, .. })] if *l2_safe_head == first
+        ));
+        assert_eq!(harness.status(), Some(HealthStatus::Healthy));
+        assert_eq!(harness.actor.cursor, Some(safe_head));
+
+        // Once caught up to the safe head, no block is derived.
+        harness.actor.verify_next(safe_head, &harness.health).await.unwrap();
+        assert_eq!(harness.actor.cursor, Some(safe_head));
+        assert_eq!(harness.actor.state.pipeline.signals.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_verify_next_divergence() {
+        let mut harness = Harness::new();
+        let (first, safe_head) = (harness.local(1).await, harness.local(2).await);
+        let cfg = harness.actor.state.client.cfg().clone();
+        let diverging = attributes(&cfg, first, B256::repeat_byte(1));
+        harness.actor.state.pipeline.queued.push_back(diverging);
+
+        harness.actor.verify_next(safe_head, &harness.health).await.unwrap();
+
+        // The divergence is reported, and verification continues along the execution layer's
+        // chain.
+        assert!(matches!(harness.status(), Some(HealthStatus::Degraded(_))));
+        assert_eq!(harness.actor.cursor, Some(safe_head));
+    }
+
+    #[tokio::test]
+    async fn test_verify_next_out_of_data() {
+        let mut harness = Harness::new();
+        let (first, safe_head) = (harness.local(1).await, harness.local(2).await);
+
+        // Without data, the cursor stays at the last verified block.
+        harness.actor.verify_next(safe_head, &harness.health).await.unwrap();
+        assert_eq!(harness.actor.cursor, Some(first));
+        assert_eq!(harness.status(), None);
+
+        // A later tick verifies the block, without resetting the pipeline again.
+        let cfg = harness.actor.state.client.cfg().clone();
+        harness.actor.state.pipeline.queued.push_back(attributes(&cfg, first, B256::ZERO));
+        harness.actor.verify_next(safe_head, &harness.health).await.unwrap();
+        assert_eq!(harness.actor.state.pipeline.signals.len(), 1);
+        assert_eq!(harness.status(), Some(HealthStatus::Healthy));
+        assert_eq!(harness.actor.cursor, Some(safe_head));
+    }
+}
@@ -1,18 +1,31 @@
 //! [NodeActor] implementation for the derivation sub-routine.
 
-use crate::{Metrics, NodeActor, actors::CancellableContext};
+use crate::{
+    HealthReporter, Metrics, NodeActor,
+    actors::{CancellableContext, ResetCoordinator, ResetPhase},
+    bus::{self, BusConfig, ChannelConfig, EventReceiver, EventSender, OverflowPolicy},
+};
 use async_trait::async_trait;
 use kona_derive::{
     ActivationSignal, Pipeline, PipelineError, PipelineErrorKind, ResetError, ResetSignal, Signal,
     SignalReceiver, StepResult,
 };
 use kona_protocol::{BlockInfo, L2BlockInfo, OpAttributesWithParent};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 use tokio::{
     select,
-    sync::{mpsc, oneshot, watch},
+    sync::{oneshot, watch},
 };
 use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+use tracing::{Instrument, field};
+
+/// The interval after which derivation is retried when the pipeline yields on a temporary error
+/// other than an exhausted data source, such as a failed request to the L1 provider.
+const TEMPORARY_ERROR_RETRY_INTERVAL: Duration = Duration::from_millis(500);
 
 /// The [NodeActor] for the derivation sub-routine.
 ///
@@ -26,11 +39,8 @@ where
 {
     /// The state for the derivation actor.
     state: DerivationState<P>,
-    /// The sender for derived [`OpAttributesWithParent`]s produced by the actor.
-    attributes_out: mpsc::Sender<OpAttributesWithParent>,
-    /// The reset request sender, used to handle [`PipelineErrorKind::Reset`] events and forward
-    /// them to the engine.
-    reset_request_tx: mpsc::Sender<()>,
+    /// The sender for [`DerivedAttributes`] produced by the actor.
+    attributes_out: EventSender<DerivedAttributes>,
 }
 
 /// The state for the derivation actor.
@@ -44,19 +54,121 @@ where
     /// A flag indicating whether or not derivation is idle. Derivation is considered idle when it
     /// has yielded to wait for more data on the DAL.
     pub derivation_idle: bool,
-    /// A flag indicating whether or not derivation is waiting for a signal. When waiting for a
-    /// signal, derivation cannot process any incoming events.
-    pub waiting_for_signal: bool,
+    /// The id of the last reset applied to the pipeline, that derived attributes are tagged with.
+    pub reset_id: u64,
+    /// A flag indicating whether or not derivation yielded on a temporary error other than an
+    /// exhausted data source. Such errors don't imply that new L1 data is needed, so derivation
+    /// is retried after [`TEMPORARY_ERROR_RETRY_INTERVAL`] rather than on the next L1 head.
+    pub retry_pending: bool,
+    /// The [`BusConfig`] of the actor's outbound channels.
+    pub bus: BusConfig,
+    /// The sender for the [`DerivationProgress`] of the actor.
+    pub progress: watch::Sender<DerivationProgress>,
+    /// The instant of the last reset of the pipeline, used to measure the interval between
+    /// resets.
+    pub last_reset: Option<Instant>,
+    /// The [`AttributesFanout`] that derived attributes are copied to, for the consumers other
+    /// than the engine actor.
+    pub fanout: AttributesFanout,
+    /// The number of bytes the state buffered by the pipeline must not exceed, if any. Beyond it,
+    /// the oldest buffered channels are pruned.
+    pub memory_ceiling: Option<usize>,
+}
+
+/// The progress made by the derivation actor since it was started.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DerivationProgress {
+    /// The block number of the current L1 origin of the pipeline.
+    pub l1_origin: u64,
+    /// The number of payload attributes sent to the engine.
+    pub attributes: u64,
+    /// The number of times the pipeline was reset after a derivation error.
+    pub resets: u64,
+}
+
+/// Payload attributes derived by the derivation actor, tagged with the id of the last reset of the
+/// pipeline, so that the engine can drop the attributes derived before a later reset.
+#[derive(Debug, Clone)]
+pub struct DerivedAttributes {
+    /// The id of the last reset applied to the pipeline when the attributes were derived.
+    pub reset_id: u64,
+    /// The derived [`OpAttributesWithParent`].
+    pub attributes: OpAttributesWithParent,
+}
+
+/// A fan-out of the [`DerivedAttributes`] produced by the derivation actor to consumers other than
+/// the engine actor, such as attributes auditors, analytics exporters or the shadow actor.
+///
+/// The engine actor consumes the derived attributes from the `attributes_out` channel, which
+/// holds derivation back when the engine falls behind. Each subscriber of the fan-out instead
+/// receives a copy of the attributes derived after it subscribed, on a channel of its own that
+/// drops its oldest attributes when full, so that a slow subscriber never holds back derivation or
+/// the engine.
+///
+/// The fan-out is cheaply cloneable, and its clones share the same subscribers.
+#[derive(Debug, Clone, Default)]
+pub struct AttributesFanout {
+    /// The senders of the subscribers.
+    subscribers: Arc<Mutex<Vec<EventSender<DerivedAttributes>>>>,
+}
+
+impl AttributesFanout {
+    /// Subscribes to the derived attributes, on a new channel of the given name and capacity.
+    pub fn subscribe(
+        &self,
+        name: &'static str,
+        capacity: usize,
+    ) -> EventReceiver<DerivedAttributes> {
+        let config = ChannelConfig::new(capacity).with_policy(OverflowPolicy::DropOldest);
+        let (sender, receiver) = bus::channel(name, config);
+        self.add(sender);
+        receiver
+    }
+
+    /// Subscribes the given sender to the derived attributes. Its channel should drop its oldest
+    /// events when full, or a slow subscriber holds back derivation.
+    pub fn add(&self, sender: EventSender<DerivedAttributes>) {
+        self.lock().push(sender);
+    }
+
+    /// Returns the number of subscribers.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if there are no subscribers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sends a copy of the given attributes to each subscriber, and unsubscribes those whose
+    /// receiver was dropped.
+    async fn publish(&self, attributes: &DerivedAttributes) {
+        let subscribers = self.lock().clone();
+        let mut closed = false;
+        for subscriber in &subscribers {
+            if subscriber.send(attributes.clone()).await.is_err() {
+                trace!(target: "derivation", channel = subscriber.name(), "Attributes subscriber stopped");
+                closed = true;
+            }
+        }
+        if closed {
+            self.lock().retain(|subscriber| !subscriber.is_closed());
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<EventSender<DerivedAttributes>>> {
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
 /// The outbound channels for the derivation actor.
 #[derive(Debug)]
 pub struct DerivationOutboundChannels {
-    /// The receiver for derived [`OpAttributesWithParent`]s produced by the actor.
-    pub attributes_out: mpsc::Receiver<OpAttributesWithParent>,
-    /// The receiver for reset requests, used to handle [`PipelineErrorKind::Reset`] events and
-    /// forward them to the engine.
-    pub reset_request_tx: mpsc::Receiver<()>,
+    /// The receiver for [`DerivedAttributes`] produced by the actor.
+    pub attributes_out: EventReceiver<DerivedAttributes>,
+    /// The receiver for the [`DerivationProgress`] of the actor.
+    pub progress: watch::Receiver<DerivationProgress>,
 }
 
 /// The communication context used by the derivation actor.
@@ -68,6 +180,11 @@ pub struct DerivationContext {
     pub engine_l2_safe_head: watch::Receiver<L2BlockInfo>,
     /// A receiver that tells derivation to begin. Completing EL sync consumes the instance.
     pub el_sync_complete_rx: oneshot::Receiver<()>,
+    /// A receiver of whether the engine re-triggered EL sync, e.g. because the unsafe head lagged
+    /// too far behind gossip. Derivation is paused until the EL is synced again.
+    pub el_resync: watch::Receiver<bool>,
+    /// The [`ResetCoordinator`], through which the pipeline is reset by the engine.
+    pub reset: ResetCoordinator,
     /// A receiver that sends a [`Signal`] to the derivation pipeline.
     ///
     /// The derivation actor steps over the derivation pipeline to generate
@@ -86,7 +203,9 @@ pub struct DerivationContext {
     /// occurs.
     ///
     /// Specs: <https://specs.optimism.io/protocol/derivation.html#l1-sync-payload-attributes-processing>
-    pub derivation_signal_rx: mpsc::Receiver<Signal>,
+    pub derivation_signal_rx: EventReceiver<Signal>,
+    /// Reports the health of the derivation actor, degraded while its reset requests time out.
+    pub health: HealthReporter,
     /// The cancellation token, shared between all tasks.
     pub cancellation: CancellationToken,
 }
@@ -102,11 +221,47 @@ where
     P: Pipeline + SignalReceiver,
 {
     /// Creates a new instance of the [DerivationState].
-    pub const fn new(pipeline: P) -> Self {
-        Self { pipeline, derivation_idle: true, waiting_for_signal: false }
+    pub fn new(pipeline: P, bus: BusConfig) -> Self {
+        Self {
+            pipeline,
+            derivation_idle: true,
+            reset_id: 0,
+            retry_pending: false,
+            bus,
+            progress: watch::Sender::new(DerivationProgress::default()),
+            last_reset: None,
+            fanout: AttributesFanout::default(),
+            memory_ceiling: None,
+        }
+    }
+
+    /// Copies the derived attributes to the subscribers of the given [`AttributesFanout`].
+    pub fn with_fanout(self, fanout: AttributesFanout) -> Self {
+        Self { fanout, ..self }
     }
 
-    /// Handles a [`Signal`] received over the derivation signal receiver channel.
+    /// Keeps the state buffered by the pipeline within the given number of bytes, by pruning its
+    /// oldest buffered channels rather than growing without bound.
+    pub fn with_memory_ceiling(self, bytes: usize) -> Self {
+        Self { memory_ceiling: Some(bytes), ..self }
+    }
+
+    /// Prunes the pipeline if the state it buffers exceeds the memory ceiling, if any.
+    async fn enforce_memory_ceiling(&mut self) {
+        let bytes = self.pipeline.state_size().total_bytes();
+        kona_macros::set!(gauge, Metrics::DERIVATION_STATE_SIZE, bytes as f64);
+
+        let Some(ceiling) = self.memory_ceiling.filter(|ceiling| bytes > *ceiling) else {
+            return;
+        };
+        warn!(target: "derivation", bytes, ceiling, "Pipeline state exceeds its memory ceiling, pruning");
+        kona_macros::inc!(counter, Metrics::DERIVATION_STATE_PRUNE_COUNT);
+        if let Err(e) = self.pipeline.signal(Signal::Prune(bytes - ceiling)).await {
+            error!(target: "derivation", ?e, "Failed to prune the derivation pipeline");
+        }
+    }
+
+    /// Applies a [`Signal`] to the derivation pipeline.
     async fn signal(&mut self, signal: Signal) {
         if let Signal::Reset(ResetSignal { l1_origin, .. }) = signal {
             kona_macros::set!(counter, Metrics::DERIVATION_L1_ORIGIN, l1_origin.number);
@@ -120,25 +275,48 @@ where
         }
     }
 
+    /// Resets the pipeline to the target of the given [`ResetPhase`], if the engine picked one
+    /// that was not applied yet, and acknowledges it. Returns `true` if the pipeline was reset.
+    async fn apply_reset(&mut self, phase: ResetPhase, reset: &ResetCoordinator) -> bool {
+        let ResetPhase::Targeted { id, target, .. } = phase else {
+            return false;
+        };
+        if id == self.reset_id {
+            return false;
+        }
+
+        self.signal(target.signal()).await;
+        self.reset_id = id;
+        self.derivation_idle = true;
+        if !reset.acknowledge(id) {
+            debug!(target: "derivation", id, "Reset superseded by a later one");
+        }
+        true
+    }
+
     /// Attempts to step the derivation pipeline forward as much as possible in order to produce the
     /// next safe payload.
     async fn produce_next_attributes(
         &mut self,
         engine_l2_safe_head: &watch::Receiver<L2BlockInfo>,
-        reset_request_tx: &mpsc::Sender<()>,
+        reset: &ResetCoordinator,
     ) -> Result<OpAttributesWithParent, DerivationError> {
         // As we start the safe head at the disputed block's parent, we step the pipeline until the
         // first attributes are produced. All batches at and before the safe head will be
         // dropped, so the first payload will always be the disputed one.
         loop {
             let l2_safe_head = *engine_l2_safe_head.borrow();
-            match self.pipeline.step(l2_safe_head).await {
+            let step = debug_span!(target: "derivation", "step");
+            let result = self.pipeline.step(l2_safe_head).instrument(step).await;
+            self.enforce_memory_ceiling().await;
+            match result {
                 StepResult::PreparedAttributes => { /* continue; attributes will be sent off. */ }
                 StepResult::AdvancedOrigin => {
                     let origin =
                         self.pipeline.origin().ok_or(PipelineError::MissingOrigin.crit())?.number;
 
                     kona_macros::set!(counter, Metrics::DERIVATION_L1_ORIGIN, origin);
+                    self.progress.send_modify(|progress| progress.l1_origin = origin);
                     debug!(target: "derivation", l1_block = origin, "Advanced L1 origin");
                 }
                 StepResult::OriginAdvanceErr(e) | StepResult::StepFailed(e) => {
@@ -150,10 +328,19 @@ where
                                 continue;
                             }
 
-                            debug!(
-                                target: "derivation",
-                                "Exhausted data source for now; Yielding until the chain has extended."
-                            );
+                            if matches!(e, PipelineError::Eof) {
+                                debug!(
+                                    target: "derivation",
+                                    "Exhausted data source for now; Yielding until the chain has extended."
+                                );
+                            } else {
+                                warn!(
+                                    target: "derivation",
+                                    %e,
+                                    "Temporary error while stepping the pipeline; Yielding before retrying."
+                                );
+                                self.retry_pending = true;
+                            }
                             return Err(DerivationError::Yield);
                         }
                         PipelineErrorKind::Reset(e) => {
@@ -189,19 +376,18 @@ where
 
                                     kona_macros::inc!(counter, Metrics::L1_REORG_COUNT);
                                 }
-                                // send the `reset` signal to the engine actor only when interop is
-                                // not active.
-                                if !self
-                                    .pipeline
-                                    .rollup_config()
-                                    .is_interop_active(l2_safe_head.block_info.timestamp)
-                                {
-                                    reset_request_tx.send(()).await.map_err(|e| {
-                                        error!(target: "derivation", ?e, "Failed to send reset request");
-                                        DerivationError::Sender(Box::new(e))
-                                    })?;
+                                // Stop deriving until the engine picks the reset target. When
+                                // interop is active, the reset is left to the supervisor.
+                                let id = reset.request();
+                                debug!(target: "derivation", id, "Requested a reset");
+                                self.progress.send_modify(|progress| progress.resets += 1);
+                                if let Some(last_reset) = self.last_reset.replace(Instant::now()) {
+                                    kona_macros::record!(
+                                        histogram,
+                                        Metrics::DERIVATION_RESET_INTERVAL,
+                                        last_reset.elapsed().as_secs_f64()
+                                    );
                                 }
-                                self.waiting_for_signal = true;
                                 return Err(DerivationError::Yield);
                             }
                         }
@@ -238,15 +424,19 @@ where
         msg: InboundDerivationMessage,
         engine_l2_safe_head: &mut watch::Receiver<L2BlockInfo>,
         el_sync_complete_rx: &oneshot::Receiver<()>,
-        attributes_out: &mpsc::Sender<OpAttributesWithParent>,
-        reset_request_tx: &mpsc::Sender<()>,
+        el_resync: &watch::Receiver<bool>,
+        attributes_out: &EventSender<DerivedAttributes>,
+        reset: &ResetCoordinator,
     ) -> Result<(), DerivationError> {
         // Only attempt derivation once the engine finishes syncing.
         if !el_sync_complete_rx.is_terminated() {
             trace!(target: "derivation", "Engine not ready, skipping derivation");
             return Ok(());
-        } else if self.waiting_for_signal {
-            trace!(target: "derivation", "Waiting to receive a signal, skipping derivation");
+        } else if *el_resync.borrow() {
+            trace!(target: "derivation", "Engine re-syncing, skipping derivation");
+            return Ok(());
+        } else if !reset.is_idle() {
+            trace!(target: "derivation", "Reset in progress, skipping derivation");
             return Ok(());
         }
 
@@ -274,20 +464,33 @@ where
             return Ok(());
         }
 
+        // Open a span for the derivation of the next payload attributes, which is the parent of
+        // the engine tasks that import the derived block.
+        let span = info_span!(
+            target: "derivation",
+            "derive",
+            l2_safe_head = engine_safe_head.block_info.number,
+            l1_origin = field::Empty,
+            correlation_id = field::Empty,
+        );
+
         // Advance the pipeline as much as possible, new data may be available or there still may be
         // payloads in the attributes queue.
-        let payload_attrs =
-            match self.produce_next_attributes(engine_l2_safe_head, reset_request_tx).await {
-                Ok(attrs) => attrs,
-                Err(DerivationError::Yield) => {
-                    // Yield until more data is available.
-                    self.derivation_idle = true;
-                    return Ok(());
-                }
-                Err(e) => {
-                    return Err(e);
-                }
-            };
+        let payload_attrs = match self
+            .produce_next_attributes(engine_l2_safe_head, reset)
+            .instrument(span.clone())
+            .await
+        {
+            Ok(attrs) => attrs,
+            Err(DerivationError::Yield) => {
+                // Yield until more data is available.
+                self.derivation_idle = true;
+                return Ok(());
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        };
 
         // Mark derivation as busy.
         self.derivation_idle = false;
@@ -296,10 +499,22 @@ where
         engine_l2_safe_head.borrow_and_update();
 
         // Send payload attributes out for processing.
+        span.record("l1_origin", payload_attrs.l1_origin.number);
+        span.record("correlation_id", field::display(payload_attrs.correlation_id()));
+        debug!(
+            target: "derivation",
+            parent: &span,
+            l2_number = payload_attrs.block_number(),
+            "Derived payload attributes"
+        );
+        let derived = DerivedAttributes { reset_id: self.reset_id, attributes: payload_attrs };
+        self.fanout.publish(&derived).await;
         attributes_out
-            .send(payload_attrs)
+            .send(derived)
+            .instrument(span)
             .await
             .map_err(|e| DerivationError::Sender(Box::new(e)))?;
+        self.progress.send_modify(|progress| progress.attributes += 1);
 
         Ok(())
     }
@@ -311,17 +526,12 @@ where
 {
     /// Creates a new instance of the [DerivationActor].
     pub fn new(state: DerivationState<P>) -> (DerivationOutboundChannels, Self) {
-        let (derived_payload_tx, derived_payload_rx) = mpsc::channel(16);
-        let (reset_request_tx, reset_request_rx) = mpsc::channel(16);
-        let actor = Self { state, attributes_out: derived_payload_tx, reset_request_tx };
-
-        (
-            DerivationOutboundChannels {
-                attributes_out: derived_payload_rx,
-                reset_request_tx: reset_request_rx,
-            },
-            actor,
-        )
+        let (derived_payload_tx, derived_payload_rx) =
+            state.bus.limits.channel("attributes", state.bus.attributes);
+        let progress = state.progress.subscribe();
+        let actor = Self { state, attributes_out: derived_payload_tx };
+
+        (DerivationOutboundChannels { attributes_out: derived_payload_rx, progress }, actor)
     }
 }
 
@@ -330,6 +540,7 @@ impl<P> NodeActor for DerivationActor<P>
 where
     P: Pipeline + SignalReceiver + Send + Sync + 'static,
 {
+    const NAME: &'static str = "derivation";
     type Error = DerivationError;
     type InboundData = DerivationContext;
     type State = DerivationState<P>;
@@ -345,11 +556,25 @@ where
             mut l1_head_updates,
             mut engine_l2_safe_head,
             mut el_sync_complete_rx,
+            mut el_resync,
+            reset,
             mut derivation_signal_rx,
+            health,
             cancellation,
         }: Self::InboundData,
     ) -> Result<(), Self::Error> {
+        let mut reset_phase = reset.subscribe();
+        // The number of times the pending reset request was sent again.
+        let mut request_retries = 0u64;
         loop {
+            // A reset request that the engine does not pick up in time is requested again.
+            let phase = *reset_phase.borrow();
+            let requested = matches!(phase, ResetPhase::Requested { .. });
+            if !requested && request_retries > 0 {
+                request_retries = 0;
+                health.healthy();
+            }
+
             select! {
                 biased;
 
@@ -361,17 +586,35 @@ where
                     return Ok(());
                 }
                 signal = derivation_signal_rx.recv() => {
-                    let Some(signal) = signal else {
+                    let Some(bus::Event { payload: signal, span }) = signal else {
                         error!(
                             target: "derivation",
-                            ?signal,
                             "DerivationActor failed to receive signal"
                         );
                         return Err(DerivationError::SignalReceiveFailed);
                     };
 
-                    self.state.signal(signal).await;
-                    self.state.waiting_for_signal = false;
+                    self.state.signal(signal).instrument(span).await;
+                    // The pipeline may also be reset by the supervisor, which settles the pending
+                    // reset request.
+                    if matches!(signal, Signal::Reset(_)) {
+                        reset.withdraw();
+                    }
+                }
+                Ok(()) = reset_phase.changed() => {
+                    let phase = *reset_phase.borrow_and_update();
+                    if self.state.apply_reset(phase, &reset).await {
+                        // The engine published the heads that the pipeline was reset to.
+                        self.state.process(InboundDerivationMessage::NewDataAvailable, &mut engine_l2_safe_head, &el_sync_complete_rx, &el_resync, &self.attributes_out, &reset).await?;
+                    }
+                }
+                _ = reset.timeout_of(&phase), if requested => {
+                    reset.timed_out(&reset.phase());
+                    let id = reset.request();
+                    request_retries += 1;
+                    health.degraded(format!(
+                        "reset {id} not picked up by the engine after {request_retries} retries"
+                    ));
                 }
                 msg = l1_head_updates.changed() => {
                     if let Err(err) = msg {
@@ -383,15 +626,31 @@ where
                         return Ok(());
                     }
 
-                    self.state.process(InboundDerivationMessage::NewDataAvailable, &mut engine_l2_safe_head, &el_sync_complete_rx, &self.attributes_out, &self.reset_request_tx).await?;
+                    // Open a span for the L1 head update, which is the root cause of any payload
+                    // attributes derived from the new data.
+                    let l1_head = l1_head_updates.borrow().map(|head| head.number);
+                    let span = info_span!(target: "derivation", "l1_head", number = l1_head);
+                    self.state.process(InboundDerivationMessage::NewDataAvailable, &mut engine_l2_safe_head, &el_sync_complete_rx, &el_resync, &self.attributes_out, &reset).instrument(span).await?;
+                }
+                _ = tokio::time::sleep(TEMPORARY_ERROR_RETRY_INTERVAL), if self.state.retry_pending => {
+                    self.state.retry_pending = false;
+                    self.state.process(InboundDerivationMessage::NewDataAvailable, &mut engine_l2_safe_head, &el_sync_complete_rx, &el_resync, &self.attributes_out, &reset).await?;
                 }
                 _ = engine_l2_safe_head.changed() => {
-                    self.state.process(InboundDerivationMessage::SafeHeadUpdated, &mut engine_l2_safe_head, &el_sync_complete_rx, &self.attributes_out, &self.reset_request_tx).await?;
+                    self.state.process(InboundDerivationMessage::SafeHeadUpdated, &mut engine_l2_safe_head, &el_sync_complete_rx, &el_resync, &self.attributes_out, &reset).await?;
                 }
                 _ = &mut el_sync_complete_rx, if !el_sync_complete_rx.is_terminated() => {
                     info!(target: "derivation", "Engine finished syncing, starting derivation.");
                     // Optimistically process the first message.
-                    self.state.process(InboundDerivationMessage::NewDataAvailable, &mut engine_l2_safe_head, &el_sync_complete_rx, &self.attributes_out, &self.reset_request_tx).await?;
+                    self.state.process(InboundDerivationMessage::NewDataAvailable, &mut engine_l2_safe_head, &el_sync_complete_rx, &el_resync, &self.attributes_out, &reset).await?;
+                }
+                Ok(()) = el_resync.changed() => {
+                    if *el_resync.borrow_and_update() {
+                        warn!(target: "derivation", "Engine re-syncing, pausing derivation.");
+                        continue;
+                    }
+                    info!(target: "derivation", "Engine finished re-syncing, resuming derivation.");
+                    self.state.process(InboundDerivationMessage::NewDataAvailable, &mut engine_l2_safe_head, &el_sync_complete_rx, &el_resync, &self.attributes_out, &reset).await?;
                 }
             }
         }
@@ -427,3 +686,194 @@ pub enum DerivationError {
     #[error("Failed to receive L2 safe head")]
     L2SafeHeadReceiveFailed,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VirtualClock;
+    use alloy_primitives::B256;
+    use kona_derive::{OriginProvider, PipelineResult, PipelineStateSize};
+    use kona_genesis::{RollupConfig, SystemConfig};
+    use kona_rpc::{HealthRegistry, HealthStatus};
+    use op_alloy_rpc_types_engine::OpPayloadAttributes;
+    use std::collections::VecDeque;
+
+    /// A [`Pipeline`] that prepares the queued attributes, one per step.
+    #[derive(Debug, Default)]
+    struct QueuedPipeline {
+        queued: VecDeque<OpAttributesWithParent>,
+        prepared: Option<OpAttributesWithParent>,
+        config: RollupConfig,
+    }
+
+    impl Iterator for QueuedPipeline {
+        type Item = OpAttributesWithParent;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.prepared.take()
+        }
+    }
+
+    impl OriginProvider for QueuedPipeline {
+        fn origin(&self) -> Option<BlockInfo> {
+            Some(BlockInfo::default())
+        }
+    }
+
+    #[async_trait]
+    impl SignalReceiver for QueuedPipeline {
+        async fn signal(&mut self, _: Signal) -> PipelineResult<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl Pipeline for QueuedPipeline {
+        fn peek(&self) -> Option<&OpAttributesWithParent> {
+            self.prepared.as_ref()
+        }
+
+        async fn step(&mut self, _: L2BlockInfo) -> StepResult {
+            match self.queued.pop_front() {
+                Some(attributes) => {
+                    self.prepared = Some(attributes);
+                    StepResult::PreparedAttributes
+                }
+                None => StepResult::StepFailed(PipelineError::Eof.temp()),
+            }
+        }
+
+        fn rollup_config(&self) -> &RollupConfig {
+            &self.config
+        }
+
+        fn state_size(&self) -> PipelineStateSize {
+            PipelineStateSize::default()
+        }
+
+        async fn system_config_by_number(
+            &mut self,
+            _: u64,
+        ) -> Result<SystemConfig, PipelineErrorKind> {
+            Ok(SystemConfig::default())
+        }
+    }
+
+    fn head(number: u64) -> L2BlockInfo {
+        L2BlockInfo {
+            block_info: BlockInfo {
+                number,
+                hash: B256::with_last_byte(number as u8 + 1),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn attributes(parent: u64) -> OpAttributesWithParent {
+        OpAttributesWithParent::new(
+            OpPayloadAttributes::default(),
+            head(parent),
+            BlockInfo::default(),
+            true,
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_paused_during_el_resync() {
+        let pipeline = QueuedPipeline { queued: [attributes(0)].into(), ..Default::default() };
+        let (mut outbound, actor) =
+            DerivationActor::new(DerivationState::new(pipeline, BusConfig::default()));
+        let (l1_head_tx, l1_head_updates) = watch::channel(None);
+        let (_safe_head_tx, engine_l2_safe_head) = watch::channel(head(0));
+        let (sync_complete_tx, el_sync_complete_rx) = oneshot::channel();
+        let (el_resync_tx, el_resync) = watch::channel(true);
+        let (_signal_tx, derivation_signal_rx) =
+            bus::channel("derivation_signals", ChannelConfig::new(1));
+        let cancellation = CancellationToken::new();
+        let context = DerivationContext {
+            l1_head_updates,
+            engine_l2_safe_head,
+            el_sync_complete_rx,
+            el_resync,
+            reset: ResetCoordinator::new(Duration::from_secs(10)),
+            derivation_signal_rx,
+            health: HealthReporter::new("derivation", HealthRegistry::new()),
+            cancellation: cancellation.clone(),
+        };
+        let handle = tokio::spawn(actor.start(context));
+
+        // Nothing is derived while the engine re-syncs, even once new L1 data is available.
+        sync_complete_tx.send(()).unwrap();
+        l1_head_tx.send_replace(Some(BlockInfo::default()));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(outbound.attributes_out.drain().is_empty());
+
+        // Derivation resumes once the EL is synced again.
+        el_resync_tx.send_replace(false);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let derived = outbound.attributes_out.drain();
+        assert_eq!(derived.len(), 1);
+        assert_eq!(derived[0].attributes.block_number(), 1);
+
+        cancellation.cancel();
+        handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stalled_reset_request_degrades_health() {
+        let (_outbound, actor) = DerivationActor::new(DerivationState::new(
+            QueuedPipeline::default(),
+            BusConfig::default(),
+        ));
+        let (_l1_head_tx, l1_head_updates) = watch::channel(None);
+        let (_safe_head_tx, engine_l2_safe_head) = watch::channel(head(0));
+        let (_sync_complete_tx, el_sync_complete_rx) = oneshot::channel();
+        let (_el_resync_tx, el_resync) = watch::channel(false);
+        let (_signal_tx, derivation_signal_rx) =
+            bus::channel("derivation_signals", ChannelConfig::new(1));
+        let clock = VirtualClock::new();
+        let reset =
+            ResetCoordinator::new(Duration::from_secs(10)).with_clock(Arc::new(clock.clone()));
+        let registry = HealthRegistry::new();
+        let cancellation = CancellationToken::new();
+        let context = DerivationContext {
+            l1_head_updates,
+            engine_l2_safe_head,
+            el_sync_complete_rx,
+            el_resync,
+            reset: reset.clone(),
+            derivation_signal_rx,
+            health: HealthReporter::new("derivation", registry.clone()),
+            cancellation: cancellation.clone(),
+        };
+        let handle = tokio::spawn(actor.start(context));
+
+        // A pending reset request is not reported until it times out.
+        let id = reset.request();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(registry.get("derivation"), None);
+
+        // The request is sent again once the engine does not pick it up in time.
+        clock.advance(Duration::from_secs(10));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(
+            registry.get("derivation"),
+            Some(HealthStatus::Degraded(format!(
+                "reset {id} not picked up by the engine after 1 retries"
+            )))
+        );
+        assert!(
+            matches!(reset.phase(), ResetPhase::Requested { since, .. } if since == clock.now())
+        );
+
+        // The actor is healthy again once the engine picks up the reset.
+        assert_eq!(reset.target(ResetSignal::default()), id);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(reset.is_idle());
+        assert_eq!(registry.get("derivation"), Some(HealthStatus::Healthy));
+
+        cancellation.cancel();
+        handle.await.unwrap().unwrap();
+    }
+}
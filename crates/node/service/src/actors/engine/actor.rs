@@ -1,27 +1,46 @@
 //! The [`EngineActor`].
 
-use super::{EngineError, L2Finalizer};
+use super::{ConsistencyChecker, EngineError, L2Finalizer, SafeHeadHintSource};
 use alloy_rpc_types_engine::JwtSecret;
 use async_trait::async_trait;
 use kona_derive::{ResetSignal, Signal};
 use kona_engine::{
-    ConsolidateTask, Engine, EngineClient, EngineQueries, EngineState as InnerEngineState,
-    EngineTask, EngineTaskError, InsertUnsafeTask,
+    AttributesBacklog, BuildBudget, ConsolidateTask, DelayedUnsafePayloads, ElSyncConfig,
+    ElSyncSupervisor, Engine, EngineClient, EngineQueries, EngineState as InnerEngineState,
+    EngineTask, EngineTaskError, EngineTaskTimeouts, EngineTransport, ForkchoiceTask,
+    InsertUnsafeTask, MaintenanceRegistry, PayloadEnvelopeStore, RestartCheckpoint, SafeHeadIndex,
+    SafeHeadRetention, SyncMode, SyncingBufferConfig, UnsafeDelay, UnsafeLagConfig,
+    UnsafeLagMonitor, UnsafePayloadBuffer,
 };
-use kona_genesis::RollupConfig;
-use kona_protocol::{L2BlockInfo, OpAttributesWithParent};
+use kona_genesis::{RollupConfig, SystemConfig};
+use kona_protocol::{BlockInfo, L2BlockInfo, MemoryAudit, OpAttributesWithParent};
+use kona_rpc::{HealthStatus, ReloadableConfig, ReorgEvent};
 use kona_sources::RuntimeConfig;
 use op_alloy_provider::ext::engine::OpEngineApi;
 use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
-use std::sync::Arc;
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use tokio::{
-    sync::{mpsc, oneshot, watch},
+    sync::{broadcast, mpsc, oneshot, watch},
     task::JoinHandle,
+    time::Instant,
 };
 use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+use tracing::{Instrument, Span};
 use url::Url;
 
-use crate::{NodeActor, actors::CancellableContext};
+use crate::{
+    Clock, EventJournal, HealthReporter, JournalEventKind, Metrics, NodeActor,
+    actors::{CancellableContext, DerivedAttributes, ResetCoordinator, ResetPhase},
+    bus::{BusConfig, Event, EventReceiver, EventSender},
+};
+
+/// The interval between attempts to reset the engine after an RPC error.
+const RESET_RETRY_INTERVAL: Duration = Duration::from_secs(1);
 
 /// The [`EngineActor`] is responsible for managing the operations sent to the execution layer's
 /// Engine API. To accomplish this, it uses the [`Engine`] task queue to order Engine API
@@ -30,26 +49,86 @@ use crate::{NodeActor, actors::CancellableContext};
 pub struct EngineActor {
     /// The [`EngineActorState`] used to build the actor.
     state: EngineActorState,
-    /// The receiver for L2 safe head update notifications.
-    engine_l2_safe_head_tx: watch::Sender<L2BlockInfo>,
+    /// The senders of the heads of the engine.
+    heads: EngineHeadSenders,
     /// A channel to send a signal that EL sync has completed. Informs the derivation actor to
     /// start. Because the EL sync state machine within [`InnerEngineState`] can only complete
     /// once, this channel is consumed after the first successful send. Future cases where EL
     /// sync is re-triggered can occur, but we will not block derivation on it.
     sync_complete_tx: oneshot::Sender<()>,
     /// A way for the engine actor to send a [`Signal`] back to the derivation actor.
-    derivation_signal_tx: mpsc::Sender<Signal>,
+    derivation_signal_tx: EventSender<Signal>,
+    /// Tells the derivation actor whether EL sync was re-triggered after it first completed.
+    /// Derivation is paused until the EL is synced again.
+    el_resync_tx: watch::Sender<bool>,
+    /// Gossiped unsafe payloads held back until they satisfy the configured [`UnsafeDelay`].
+    delayed_payloads: DelayedUnsafePayloads,
+    /// A unix timestamp, in seconds, and the [`Clock`] instant it was taken at, from which the age
+    /// of held back unsafe payloads is measured.
+    clock_origin: (u64, Instant),
+    /// A map of `L2 block number -> L1 block derived from` of the derived blocks awaiting
+    /// promotion to safe, used to measure the [`Metrics::SAFE_PROMOTION_LATENCY`] and to index
+    /// the safe heads by L1 block.
+    awaiting_safe: BTreeMap<u64, BlockInfo>,
+    /// The [`ConsistencyChecker`] of the blocks promoted to safe, if enabled.
+    consistency: Option<ConsistencyChecker>,
+}
+
+/// The senders of the heads of the [`Engine`]. Each head is published on its own channel, and
+/// only when it changes, so that consumers are not woken up by changes of the other heads.
+#[derive(Debug)]
+pub struct EngineHeadSenders {
+    /// The sender of the unsafe head.
+    pub unsafe_head: watch::Sender<L2BlockInfo>,
+    /// The sender of the cross-unsafe head.
+    pub cross_unsafe_head: watch::Sender<L2BlockInfo>,
+    /// The sender of the local-safe head.
+    pub local_safe_head: watch::Sender<L2BlockInfo>,
+    /// The sender of the safe head.
+    pub safe_head: watch::Sender<L2BlockInfo>,
+    /// The sender of the finalized head.
+    pub finalized_head: watch::Sender<L2BlockInfo>,
+}
+
+impl EngineHeadSenders {
+    /// Publishes the heads of the given [`InnerEngineState`] that changed.
+    pub fn publish(&self, state: &InnerEngineState) {
+        let heads = [
+            (&self.unsafe_head, state.unsafe_head()),
+            (&self.cross_unsafe_head, state.cross_unsafe_head()),
+            (&self.local_safe_head, state.local_safe_head()),
+            (&self.safe_head, state.safe_head()),
+            (&self.finalized_head, state.finalized_head()),
+        ];
+        for (tx, head) in heads {
+            tx.send_if_modified(|current| {
+                let modified = *current != head;
+                *current = head;
+                modified
+            });
+        }
+    }
 }
 
 /// The outbound data for the [`EngineActor`].
 #[derive(Debug)]
 pub struct EngineOutboundData {
+    /// A channel to receive L2 unsafe head update notifications.
+    pub unsafe_head_rx: watch::Receiver<L2BlockInfo>,
+    /// A channel to receive L2 cross-unsafe head update notifications.
+    pub cross_unsafe_head_rx: watch::Receiver<L2BlockInfo>,
+    /// A channel to receive L2 local-safe head update notifications.
+    pub local_safe_head_rx: watch::Receiver<L2BlockInfo>,
     /// A channel to receive L2 safe head update notifications.
     pub engine_l2_safe_head_rx: watch::Receiver<L2BlockInfo>,
+    /// A channel to receive L2 finalized head update notifications.
+    pub finalized_head_rx: watch::Receiver<L2BlockInfo>,
     /// A channel to receive a signal that EL sync has completed.
     pub sync_complete_rx: oneshot::Receiver<()>,
     /// A channel to send a [`Signal`] back to the derivation actor.
-    pub derivation_signal_rx: mpsc::Receiver<Signal>,
+    pub derivation_signal_rx: EventReceiver<Signal>,
+    /// A channel to receive whether EL sync was re-triggered after it first completed.
+    pub el_resync_rx: watch::Receiver<bool>,
 }
 
 /// The configuration for the [`EngineActor`].
@@ -61,6 +140,59 @@ pub struct EngineActorState {
     pub client: Arc<EngineClient>,
     /// The [`Engine`] task queue.
     pub engine: Engine,
+    /// The [`SyncMode`] used to start syncing the L2 chain.
+    pub sync_mode: SyncMode,
+    /// Holds unsafe payloads that arrive ahead of the unsafe head until they can be inserted.
+    pub unsafe_payloads: UnsafePayloadBuffer,
+    /// Keeps the envelopes of the latest unsafe payloads inserted into the execution layer.
+    pub envelopes: PayloadEnvelopeStore,
+    /// The [`SafeHeadIndex`] that the safe heads are recorded in, by the L1 block they were
+    /// derived from.
+    pub safe_heads: SafeHeadIndex,
+    /// The sender for [`ReorgEvent`]s of the unsafe and safe heads.
+    pub reorgs: broadcast::Sender<ReorgEvent>,
+    /// The [`BusConfig`] of the actor's outbound channels.
+    pub bus: BusConfig,
+    /// The [`MaintenanceRegistry`] of tasks run while the engine task queue is idle.
+    pub maintenance: MaintenanceRegistry,
+    /// The [`BuildBudget`] that the timings of block builds are recorded against.
+    pub build_budget: BuildBudget,
+    /// The [`EventJournal`] that resets, reorgs of the unsafe and safe heads, and invalid
+    /// payloads are recorded in.
+    pub journal: EventJournal,
+    /// The [`RestartConfig`], if the engine re-attaches to the execution layer after a restart.
+    pub restart: Option<RestartConfig>,
+    /// The [`ElSyncConfig`] of the supervision of the execution layer sync.
+    pub el_sync: ElSyncConfig,
+    /// Where the trusted safe head hint is taken from, if any. It is resolved on the first reset.
+    pub safe_head_hint: Option<SafeHeadHintSource>,
+    /// Whether the blocks promoted to safe are checked against their derived attributes once
+    /// executed.
+    pub consistency_check: bool,
+    /// The [`UnsafeLagConfig`] after which EL sync is re-triggered, if enabled.
+    pub unsafe_lag: Option<UnsafeLagConfig>,
+    /// The [`Clock`] that the age of held back unsafe payloads is measured with.
+    pub clock: Arc<dyn Clock>,
+}
+
+/// The configuration of graceful restarts.
+///
+/// On shutdown, the engine saves a [`RestartCheckpoint`] of its heads. If the node is started
+/// again within the restart window, and the execution layer still holds the checkpointed unsafe
+/// head, execution layer sync is skipped, so that the unsafe head does not lag behind the network
+/// because of the restart.
+///
+/// The derived attributes that were not executed yet are saved as an [`AttributesBacklog`] too,
+/// and replayed on top of the checkpointed safe head, so that a restart during catch-up does not
+/// set the safe head back to the start of the channel being derived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestartConfig {
+    /// The path of the [`RestartCheckpoint`].
+    pub path: PathBuf,
+    /// The path of the [`AttributesBacklog`].
+    pub backlog_path: PathBuf,
+    /// The maximum age of a checkpoint that the engine re-attaches from.
+    pub window: Duration,
 }
 
 /// The communication context used by the engine actor.
@@ -68,18 +200,24 @@ pub struct EngineActorState {
 pub struct EngineContext {
     /// A channel to receive [`RuntimeConfig`] from the runtime actor.
     pub runtime_config_rx: Option<mpsc::Receiver<RuntimeConfig>>,
-    /// A channel to receive [`OpAttributesWithParent`] from the derivation actor.
-    pub attributes_rx: mpsc::Receiver<OpAttributesWithParent>,
+    /// A channel to receive [`DerivedAttributes`] from the derivation actor.
+    pub attributes_rx: EventReceiver<DerivedAttributes>,
     /// A channel to receive [`OpExecutionPayloadEnvelope`] from the network actor.
-    pub unsafe_block_rx: mpsc::Receiver<OpExecutionPayloadEnvelope>,
-    /// A channel to receive reset requests.
-    pub reset_request_rx: mpsc::Receiver<()>,
+    pub unsafe_block_rx: EventReceiver<OpExecutionPayloadEnvelope>,
+    /// A channel to receive [`ReloadableConfig`] updates, used to pick up the unsafe block delay.
+    pub reload: watch::Receiver<ReloadableConfig>,
+    /// The [`ResetCoordinator`], through which derivation requests resets, and the engine picks
+    /// their targets.
+    pub reset: ResetCoordinator,
     /// Handler for inbound queries to the engine.
     pub inbound_queries: mpsc::Receiver<EngineQueries>,
     /// The cancellation token, shared between all tasks.
     pub cancellation: CancellationToken,
     /// The [`L2Finalizer`], used to finalize L2 blocks.
     pub finalizer: L2Finalizer,
+    /// The [`HealthReporter`] for the engine actor. The engine is reported as starting until EL
+    /// sync completes, and as unhealthy while EL sync is stalled.
+    pub health: HealthReporter,
 }
 
 impl CancellableContext for EngineContext {
@@ -91,24 +229,70 @@ impl CancellableContext for EngineContext {
 impl EngineActor {
     /// Constructs a new [`EngineActor`] from the params.
     pub fn new(initial_state: EngineActorState) -> (EngineOutboundData, Self) {
-        let (derivation_signal_tx, derivation_signal_rx) = mpsc::channel(16);
-        let (engine_l2_safe_head_tx, engine_l2_safe_head_rx) =
-            watch::channel(L2BlockInfo::default());
+        let (derivation_signal_tx, derivation_signal_rx) = initial_state
+            .bus
+            .limits
+            .channel("derivation_signals", initial_state.bus.derivation_signals);
+        let (unsafe_head, unsafe_head_rx) = watch::channel(L2BlockInfo::default());
+        let (cross_unsafe_head, cross_unsafe_head_rx) = watch::channel(L2BlockInfo::default());
+        let (local_safe_head, local_safe_head_rx) = watch::channel(L2BlockInfo::default());
+        let (safe_head, engine_l2_safe_head_rx) = watch::channel(L2BlockInfo::default());
+        let (finalized_head, finalized_head_rx) = watch::channel(L2BlockInfo::default());
+        let heads = EngineHeadSenders {
+            unsafe_head,
+            cross_unsafe_head,
+            local_safe_head,
+            safe_head,
+            finalized_head,
+        };
         let (sync_complete_tx, sync_complete_rx) = oneshot::channel();
+        let (el_resync_tx, el_resync_rx) = watch::channel(false);
+        let delayed_payloads = DelayedUnsafePayloads::new(initial_state.unsafe_payloads.capacity());
+        let clock_origin = (Self::unix_now(), initial_state.clock.now());
+        let consistency = initial_state.consistency_check.then(ConsistencyChecker::default);
 
         let actor = Self {
             state: initial_state,
-            engine_l2_safe_head_tx,
+            heads,
             sync_complete_tx,
             derivation_signal_tx,
+            el_resync_tx,
+            delayed_payloads,
+            clock_origin,
+            awaiting_safe: BTreeMap::new(),
+            consistency,
         };
 
-        let outbound_data =
-            EngineOutboundData { engine_l2_safe_head_rx, sync_complete_rx, derivation_signal_rx };
+        let outbound_data = EngineOutboundData {
+            unsafe_head_rx,
+            cross_unsafe_head_rx,
+            local_safe_head_rx,
+            engine_l2_safe_head_rx,
+            finalized_head_rx,
+            sync_complete_rx,
+            derivation_signal_rx,
+            el_resync_rx,
+        };
 
         (outbound_data, actor)
     }
 
+    /// Returns the [`UnsafeDelay`] configured in the given [`ReloadableConfig`].
+    const fn unsafe_delay(config: &ReloadableConfig) -> UnsafeDelay {
+        UnsafeDelay { seconds: config.unsafe_delay_secs, blocks: config.unsafe_delay_blocks }
+    }
+
+    /// Returns the current unix timestamp, in seconds.
+    fn unix_now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+    }
+
+    /// Returns the current unix timestamp, in seconds, as measured by the given [`Clock`] since
+    /// the given origin.
+    fn clock_now(clock: &dyn Clock, (unix, instant): (u64, Instant)) -> u64 {
+        unix + clock.now().saturating_duration_since(instant).as_secs()
+    }
+
     /// Starts a task to handle engine queries.
     fn start_query_task(
         &self,
@@ -133,55 +317,183 @@ impl EngineActor {
 }
 
 impl EngineActorState {
+    /// Enqueues the consolidation of the given derived attributes into the safe chain.
+    fn consolidate(&mut self, attributes: OpAttributesWithParent) {
+        let task = EngineTask::Consolidate(
+            ConsolidateTask::new(self.client.clone(), Arc::clone(&self.rollup), attributes, true)
+                .with_build_budget(self.build_budget),
+        );
+        self.engine.enqueue(task);
+    }
+
     /// Resets the inner [`Engine`] and propagates the reset to the derivation actor.
     pub async fn reset(
         &mut self,
-        derivation_signal_tx: &mpsc::Sender<Signal>,
-        engine_l2_safe_head_tx: &watch::Sender<L2BlockInfo>,
+        reset: &ResetCoordinator,
+        heads: &EngineHeadSenders,
         finalizer: &mut L2Finalizer,
         cancellation: &CancellationToken,
     ) -> Result<(), EngineError> {
-        // Reset the engine.
-        let (l2_safe_head, l1_origin, system_config) =
-            self.engine.reset(self.client.clone(), &self.rollup).await?;
-
-        // Signal the derivation actor to reset.
-        let signal = ResetSignal { l2_safe_head, l1_origin, system_config: Some(system_config) };
-        match derivation_signal_tx.send(signal.signal()).await {
-            Ok(_) => debug!(target: "engine", "Sent reset signal to derivation actor"),
-            Err(err) => {
-                error!(target: "engine", ?err, "Failed to send reset signal to the derivation actor");
-                cancellation.cancel();
-                return Err(EngineError::ChannelClosed);
+        // Resolve the trusted safe head hint, once.
+        if let Some(source) = self.safe_head_hint.take() {
+            match source.resolve(&self.client).await {
+                Ok(hint) => {
+                    info!(target: "engine", %hint, "Resolved the trusted safe head hint");
+                    self.engine.set_safe_head_hint(Some(hint));
+                }
+                Err(err) => {
+                    warn!(target: "engine", ?err, "Failed to resolve the safe head hint, ignoring it");
+                }
             }
         }
 
+        let previous = *self.engine.state();
+        let Some((l2_safe_head, l1_origin, system_config)) =
+            self.reset_engine(cancellation).await?
+        else {
+            // The node is shutting down.
+            return Ok(());
+        };
+        self.publish_reorgs(&previous);
+        self.journal.record(
+            JournalEventKind::Reset,
+            "Engine reset",
+            serde_json::json!({
+                "l2_safe_head": l2_safe_head.block_info.number,
+                "l2_safe_head_hash": l2_safe_head.block_info.hash,
+                "l1_origin": l1_origin.number,
+                "l1_origin_hash": l1_origin.hash,
+            }),
+        );
+
+        // Pick the target that the derivation actor resets the pipeline to. The attributes derived
+        // before are dropped from now on.
+        let target = ResetSignal { l2_safe_head, l1_origin, system_config: Some(system_config) };
+        let id = reset.target(target);
+        debug!(target: "engine", id, "Picked the reset target of the derivation actor");
+
         // Attempt to update the safe head following the reset.
-        self.maybe_update_safe_head(engine_l2_safe_head_tx);
+        self.publish_heads(heads);
+
+        // Drop the L2 blocks awaiting finalization that are no longer safe, and finalize the
+        // remaining ones if their L1 blocks are already finalized, e.g. after a restart.
+        finalizer.reset(l2_safe_head.block_info.number);
+        finalizer.try_finalize_next(&mut self.engine).await;
+
+        Ok(())
+    }
+
+    /// Points derivation at the local safe head, after derived attributes were found not to build
+    /// on it anymore, e.g. because it was rolled back concurrently. Unlike [`Self::reset`], the
+    /// engine is left untouched: the pipeline is reset to the corrected head, and the attributes
+    /// derived before are dropped, instead of failing their build and resetting the engine.
+    async fn realign(
+        &mut self,
+        reset: &ResetCoordinator,
+        cancellation: &CancellationToken,
+    ) -> Result<(), EngineError> {
+        let l2_safe_head = self.engine.state().local_safe_head();
+        loop {
+            match Engine::derivation_start(&self.client, &self.rollup, l2_safe_head).await {
+                Ok((l1_origin, system_config)) => {
+                    let target =
+                        ResetSignal { l2_safe_head, l1_origin, system_config: Some(system_config) };
+                    let id = reset.target(target);
+                    info!(
+                        target: "engine",
+                        id,
+                        safe_head = l2_safe_head.block_info.number,
+                        "Realigned derivation with the local safe head"
+                    );
+                    return Ok(());
+                }
+                Err(err) if err.is_temporary() => {
+                    warn!(target: "engine", ?err, "Temporary error while realigning derivation, retrying");
+                    tokio::select! {
+                        _ = cancellation.cancelled() => return Ok(()),
+                        _ = tokio::time::sleep(RESET_RETRY_INTERVAL) => {}
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
 
-        // Clear the queue of L2 blocks awaiting finalization.
-        finalizer.clear();
+    /// Resets the inner [`Engine`], retrying on RPC errors. Returns the safe head, L1 origin and
+    /// system config that derivation must resume from, or `None` if the node is shutting down.
+    async fn reset_engine(
+        &mut self,
+        cancellation: &CancellationToken,
+    ) -> Result<Option<(L2BlockInfo, BlockInfo, SystemConfig)>, EngineError> {
+        loop {
+            match self.engine.reset(self.client.clone(), &self.rollup).await {
+                Ok(start) => return Ok(Some(start)),
+                Err(err) if err.is_temporary() => {
+                    warn!(target: "engine", ?err, "Temporary error while resetting the engine, retrying");
+                    tokio::select! {
+                        _ = cancellation.cancelled() => return Ok(None),
+                        _ = tokio::time::sleep(RESET_RETRY_INTERVAL) => {}
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
 
+    /// Replays the [`AttributesBacklog`] saved before a graceful restart. The engine is reset to
+    /// the safe head it re-attached at, and the attributes that extend it are consolidated, ahead
+    /// of the initial reset that derivation resumes from.
+    async fn replay_backlog(
+        &mut self,
+        backlog: AttributesBacklog,
+        finalizer: &mut L2Finalizer,
+        cancellation: &CancellationToken,
+    ) -> Result<(), EngineError> {
+        let Some((safe_head, ..)) = self.reset_engine(cancellation).await? else {
+            return Ok(());
+        };
+        let saved = backlog.attributes.len();
+        let attributes = backlog.extending(&safe_head);
+        let (Some(first), Some(last)) = (attributes.first(), attributes.last()) else {
+            info!(target: "engine", saved, "No saved derived attributes extend the safe head");
+            return Ok(());
+        };
+        info!(
+            target: "engine",
+            saved,
+            from = first.block_number(),
+            to = last.block_number(),
+            "Replaying derived attributes saved before the restart"
+        );
+        for attributes in attributes {
+            if let Some((attributes, _)) = finalizer.admit(attributes, Span::current()) {
+                self.consolidate(attributes);
+            }
+        }
         Ok(())
     }
 
     /// Drains the inner [`Engine`] task queue and attempts to update the safe head.
     async fn drain(
         &mut self,
-        derivation_signal_tx: &mpsc::Sender<Signal>,
+        reset: &ResetCoordinator,
+        derivation_signal_tx: &EventSender<Signal>,
         sync_complete_tx: &mut Option<oneshot::Sender<()>>,
-        engine_l2_safe_head_tx: &watch::Sender<L2BlockInfo>,
+        heads: &EngineHeadSenders,
         finalizer: &mut L2Finalizer,
         cancellation: &CancellationToken,
     ) -> Result<(), EngineError> {
-        match self.engine.drain().await {
+        let previous = *self.engine.state();
+        let drained = self.engine.drain().await;
+        self.publish_reorgs(&previous);
+
+        match drained {
             Ok(_) => {
                 trace!(target: "engine", "[ENGINE] tasks drained");
             }
             Err(EngineTaskError::Reset(err)) => {
                 warn!(target: "engine", ?err, "Received reset request");
-                self.reset(derivation_signal_tx, engine_l2_safe_head_tx, finalizer, cancellation)
-                    .await?;
+                self.reset(reset, heads, finalizer, cancellation).await?;
             }
             Err(EngineTaskError::Flush(err)) => {
                 // This error is encountered when the payload is marked INVALID
@@ -189,6 +501,16 @@ impl EngineActorState {
                 // a "deposits-only" block and re-executed. At the same time,
                 // the channel and any remaining buffered batches are flushed.
                 warn!(target: "engine", ?err, "Invalid payload, Flushing derivation pipeline.");
+                kona_macros::inc!(counter, Metrics::DEPOSITS_ONLY_FALLBACK_COUNT);
+                self.journal.record(
+                    JournalEventKind::InvalidPayload,
+                    "Invalid payload, flushed the derivation pipeline",
+                    serde_json::json!({
+                        "error": err.to_string(),
+                        "unsafe_head": self.engine.state().unsafe_head().block_info.number,
+                        "safe_head": self.engine.state().safe_head().block_info.number,
+                    }),
+                );
                 match derivation_signal_tx.send(Signal::FlushChannel).await {
                     Ok(_) => {
                         debug!(target: "engine", "Sent flush signal to derivation actor")
@@ -205,20 +527,18 @@ impl EngineActorState {
                 cancellation.cancel();
                 return Err(err.into());
             }
-            Err(EngineTaskError::Temporary(err)) => {
+            Err(EngineTaskError::Temporary(err) | EngineTaskError::Syncing(err)) => {
                 trace!(target: "engine", ?err, "Temporary error draining engine tasks");
             }
+            Err(EngineTaskError::Stale(err)) => {
+                warn!(target: "engine", ?err, "Dropped stale derived attributes");
+                self.realign(reset, cancellation).await?;
+            }
         }
 
-        self.maybe_update_safe_head(engine_l2_safe_head_tx);
-        self.check_el_sync(
-            derivation_signal_tx,
-            engine_l2_safe_head_tx,
-            sync_complete_tx,
-            finalizer,
-            cancellation,
-        )
-        .await?;
+        self.publish_heads(heads);
+
+        self.check_el_sync(reset, heads, sync_complete_tx, finalizer, cancellation).await?;
 
         Ok(())
     }
@@ -226,8 +546,8 @@ impl EngineActorState {
     /// Checks if the EL has finished syncing, notifying the derivation actor if it has.
     async fn check_el_sync(
         &mut self,
-        derivation_signal_tx: &mpsc::Sender<Signal>,
-        engine_l2_safe_head_tx: &watch::Sender<L2BlockInfo>,
+        reset: &ResetCoordinator,
+        heads: &EngineHeadSenders,
         sync_complete_tx: &mut Option<oneshot::Sender<()>>,
         finalizer: &mut L2Finalizer,
         cancellation: &CancellationToken,
@@ -239,26 +559,294 @@ impl EngineActorState {
 
             // If the sync status is finished, we can reset the engine and start derivation.
             info!(target: "engine", "Performing initial engine reset");
-            self.reset(derivation_signal_tx, engine_l2_safe_head_tx, finalizer, cancellation)
-                .await?;
+            self.reset(reset, heads, finalizer, cancellation).await?;
             sync_complete_tx.send(()).ok();
         }
 
         Ok(())
     }
 
-    /// Attempts to update the safe head via the watch channel.
-    fn maybe_update_safe_head(&self, engine_l2_safe_head_tx: &watch::Sender<L2BlockInfo>) {
-        let state_safe_head = self.engine.state().safe_head();
-        let update = |head: &mut L2BlockInfo| {
-            if head != &state_safe_head {
-                *head = state_safe_head;
-                return true;
+    /// Polls the progress of EL sync with the [`ElSyncSupervisor`], publishing it in the engine
+    /// state. If EL sync stalled, it is re-triggered by re-inserting the latest buffered unsafe
+    /// payload, which points the EL at the tip of the unsafe chain again.
+    async fn supervise_el_sync(
+        &mut self,
+        supervisor: &mut ElSyncSupervisor,
+        health: &HealthReporter,
+    ) {
+        let stalled = match supervisor.poll(&self.client).await {
+            Ok(stalled) => stalled,
+            Err(err) => {
+                warn!(target: "engine", ?err, "Failed to poll the EL sync status");
+                return;
             }
-            false
         };
-        let sent = engine_l2_safe_head_tx.send_if_modified(update);
-        trace!(target: "engine", ?sent, "Attempted L2 Safe Head Update");
+        let progress = supervisor.progress();
+        self.engine.set_el_sync_progress(progress);
+        if progress.stalled {
+            health.unhealthy(format!("EL sync stalled at block {}", progress.current_block));
+        } else {
+            health.report(HealthStatus::Starting);
+        }
+        if !stalled {
+            debug!(
+                target: "engine",
+                current = progress.current_block,
+                highest = progress.highest_block,
+                "EL sync in progress"
+            );
+            return;
+        }
+
+        let Some(envelope) = self.unsafe_payloads.latest().cloned() else {
+            warn!(
+                target: "engine",
+                current = progress.current_block,
+                retries = progress.retries,
+                "EL sync stalled, and no unsafe payload is buffered to re-trigger it"
+            );
+            return;
+        };
+        warn!(
+            target: "engine",
+            current = progress.current_block,
+            highest = progress.highest_block,
+            retries = progress.retries,
+            number = envelope.payload.block_number(),
+            "EL sync stalled, re-sending the latest unsafe payload"
+        );
+        self.insert_unsafe(envelope);
+    }
+
+    /// Re-triggers EL sync towards the latest buffered unsafe payload, after the unsafe head
+    /// lagged behind gossip for too long. Returns `true` if EL sync was re-triggered.
+    fn resync_el(&mut self, health: &HealthReporter) -> bool {
+        let Some(envelope) = self.unsafe_payloads.latest().cloned() else {
+            return false;
+        };
+        let unsafe_head = self.engine.state().unsafe_head().block_info.number;
+        let tip = envelope.payload.block_number();
+        warn!(target: "engine", unsafe_head, tip, "Unsafe head lags behind gossip, re-triggering EL sync");
+        health.degraded(format!("unsafe head {unsafe_head} lags behind gossip at {tip}"));
+        self.engine.restart_el_sync();
+        self.insert_unsafe(envelope);
+        true
+    }
+
+    /// Handles an unsafe payload received from the network.
+    ///
+    /// While the EL is syncing, every payload is inserted to drive EL sync towards the tip, and is
+    /// also buffered so that it can be inserted in order once EL sync completes. Afterwards,
+    /// payloads that do not directly extend the unsafe head are buffered until the gap is filled.
+    fn handle_unsafe_payload(&mut self, envelope: OpExecutionPayloadEnvelope) {
+        let state = self.engine.state();
+        let unsafe_head = state.unsafe_head();
+        if !state.el_sync_finished {
+            self.unsafe_payloads.insert(envelope.clone(), &unsafe_head);
+        } else if envelope.payload.block_number() > unsafe_head.block_info.number + 1 {
+            debug!(
+                target: "engine",
+                number = envelope.payload.block_number(),
+                unsafe_head = unsafe_head.block_info.number,
+                "Buffering unsafe payload ahead of the unsafe head"
+            );
+            self.unsafe_payloads.insert(envelope, &unsafe_head);
+            return;
+        }
+        self.insert_unsafe(envelope);
+    }
+
+    /// Enqueues the next buffered unsafe payload, if it extends the unsafe head. Returns `true` if
+    /// a payload was enqueued.
+    fn try_insert_buffered(&mut self) -> bool {
+        let state = self.engine.state();
+        if !state.el_sync_finished {
+            return false;
+        }
+        let Some(envelope) = self.unsafe_payloads.pop_next(&state.unsafe_head()) else {
+            return false;
+        };
+        debug!(
+            target: "engine",
+            number = envelope.payload.block_number(),
+            "Inserting buffered unsafe payload"
+        );
+        self.insert_unsafe(envelope);
+        true
+    }
+
+    /// Enqueues an [`InsertUnsafeTask`] for the given payload.
+    fn insert_unsafe(&mut self, envelope: OpExecutionPayloadEnvelope) {
+        let task = EngineTask::InsertUnsafe(
+            InsertUnsafeTask::new(self.client.clone(), self.rollup.clone(), envelope)
+                .with_envelope_store(self.envelopes.clone()),
+        );
+        self.engine.enqueue(task);
+    }
+
+    /// Records the latencies of the blocks that were imported as unsafe or promoted to safe since
+    /// the given [`InnerEngineState`]. The `awaiting_safe` map of `L2 block number -> L1 block
+    /// derived from` is drained of the blocks promoted to safe, and the new safe head is recorded
+    /// in the [`SafeHeadIndex`].
+    ///
+    /// Unsafe blocks are only measured once EL sync has finished, and if they are ahead of the
+    /// safe head, so that blocks derived during catch-up do not skew the import latency.
+    fn record_latencies(
+        &self,
+        previous: &InnerEngineState,
+        awaiting_safe: &mut BTreeMap<u64, BlockInfo>,
+    ) {
+        let state = self.engine.state();
+        let now = EngineActor::unix_now();
+
+        let safe_head = state.safe_head().block_info.number;
+        if safe_head > previous.safe_head().block_info.number {
+            let awaiting = awaiting_safe.split_off(&(safe_head + 1));
+            let promoted = std::mem::replace(awaiting_safe, awaiting);
+            for l1_block in promoted.values() {
+                kona_macros::record!(
+                    histogram,
+                    Metrics::SAFE_PROMOTION_LATENCY,
+                    now.saturating_sub(l1_block.timestamp) as f64
+                );
+            }
+            if let Some(l1_block) = promoted.get(&safe_head) {
+                self.safe_heads.record(l1_block.id(), state.safe_head().block_info.id());
+            }
+        }
+
+        let unsafe_head = state.unsafe_head().block_info;
+        if state.el_sync_finished &&
+            unsafe_head.number > previous.unsafe_head().block_info.number &&
+            unsafe_head.number > safe_head
+        {
+            kona_macros::record!(
+                histogram,
+                Metrics::UNSAFE_IMPORT_LATENCY,
+                now.saturating_sub(unsafe_head.timestamp) as f64
+            );
+        }
+    }
+
+    /// Publishes the reorgs of the unsafe and safe heads since the given [`InnerEngineState`].
+    fn publish_reorgs(&self, previous: &InnerEngineState) {
+        for event in ReorgEvent::from_engine_states(previous, self.engine.state()) {
+            warn!(target: "engine", ?event, "Detected L2 reorg");
+            self.journal.record(
+                JournalEventKind::Reorg,
+                "Detected L2 reorg",
+                serde_json::to_value(event).unwrap_or_default(),
+            );
+            // Sending only fails if there are no subscribers.
+            self.reorgs.send(event).ok();
+        }
+    }
+
+    /// Publishes the heads of the engine that changed on their watch channels.
+    fn publish_heads(&self, heads: &EngineHeadSenders) {
+        heads.publish(self.engine.state());
+    }
+
+    /// Loads and removes the [`AttributesBacklog`] saved before the restart, if restarts are
+    /// enabled. It is only replayed if the engine re-attaches from the restart checkpoint.
+    fn take_attributes_backlog(&self) -> AttributesBacklog {
+        let Some(restart) = &self.restart else { return AttributesBacklog::default() };
+        AttributesBacklog::take(&restart.backlog_path).unwrap_or_else(|err| {
+            warn!(target: "engine", ?err, "Failed to load the derived attributes backlog");
+            AttributesBacklog::default()
+        })
+    }
+
+    /// Saves the given derived attributes, not executed yet, as an [`AttributesBacklog`], if
+    /// restarts are enabled. Like the [`RestartCheckpoint`], nothing is saved before execution
+    /// layer sync completes.
+    fn save_attributes_backlog(&self, attributes: Vec<OpAttributesWithParent>) {
+        let Some(restart) = &self.restart else { return };
+        if attributes.is_empty() || !self.engine.state().el_sync_finished {
+            return;
+        }
+        let backlog = AttributesBacklog { attributes };
+        match backlog.save(&restart.backlog_path) {
+            Ok(()) => info!(
+                target: "engine",
+                attributes = backlog.attributes.len(),
+                "Saved derived attributes backlog"
+            ),
+            Err(err) => warn!(target: "engine", ?err, "Failed to save derived attributes backlog"),
+        }
+    }
+
+    /// Flushes the inner [`Engine`] task queue on shutdown, committing any outstanding forkchoice
+    /// state to the execution layer.
+    ///
+    /// Errors are logged rather than propagated, since no further work can be scheduled once the
+    /// node is shutting down. The shutdown coordinator bounds the time spent here by its grace
+    /// period.
+    async fn flush(&mut self) {
+        if self.engine.state().forkchoice_update_needed {
+            self.engine
+                .enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(self.client.clone())));
+        }
+
+        match self.engine.drain().await {
+            Ok(_) => info!(target: "engine", "Flushed engine task queue"),
+            Err(err) => warn!(target: "engine", ?err, "Failed to flush engine task queue"),
+        }
+    }
+
+    /// Loads the [`RestartCheckpoint`] saved by the previous run, if restarts are enabled.
+    /// Returns the checkpoint if the engine can re-attach from it.
+    async fn restart_checkpoint(&self) -> Option<RestartCheckpoint> {
+        let restart = self.restart.as_ref()?;
+        let checkpoint = match RestartCheckpoint::take(&restart.path) {
+            Ok(checkpoint) => checkpoint?,
+            Err(err) => {
+                warn!(target: "engine", ?err, "Failed to load restart checkpoint");
+                return None;
+            }
+        };
+
+        let now = EngineActor::unix_now();
+        if !checkpoint.is_fresh(restart.window.as_secs(), now) {
+            let age = now.saturating_sub(checkpoint.saved_at);
+            info!(target: "engine", age, "Restart checkpoint is too old, syncing normally");
+            return None;
+        }
+        match checkpoint.verify(&self.client).await {
+            Ok(true) => Some(checkpoint),
+            Ok(false) => {
+                warn!(
+                    target: "engine",
+                    unsafe_head = checkpoint.unsafe_head.block_info.number,
+                    "Execution layer no longer holds the checkpointed unsafe head, syncing normally"
+                );
+                None
+            }
+            Err(err) => {
+                warn!(target: "engine", ?err, "Failed to verify restart checkpoint");
+                None
+            }
+        }
+    }
+
+    /// Saves a [`RestartCheckpoint`] of the engine's heads, if restarts are enabled. Nothing is
+    /// saved before execution layer sync completes, as there is nothing to re-attach to.
+    fn save_restart_checkpoint(&self) {
+        let Some(restart) = &self.restart else { return };
+        let state = self.engine.state();
+        if !state.el_sync_finished {
+            return;
+        }
+        let checkpoint = RestartCheckpoint::new(state, EngineActor::unix_now());
+        match checkpoint.save(&restart.path) {
+            Ok(()) => info!(
+                target: "engine",
+                unsafe_head = checkpoint.unsafe_head.block_info.number,
+                buffered = self.unsafe_payloads.len(),
+                "Saved restart checkpoint"
+            ),
+            Err(err) => warn!(target: "engine", ?err, "Failed to save restart checkpoint"),
+        }
     }
 
     fn runtime_config_update(&mut self, config: RuntimeConfig) {
@@ -279,8 +867,21 @@ impl EngineActorState {
     }
 }
 
+/// Receives the next [`RuntimeConfig`] from the runtime loader, or never resolves if the runtime
+/// loader is disabled. The future of a disabled `select!` branch is still created, so it must not
+/// assume that the runtime loader is enabled.
+async fn recv_runtime_config(
+    runtime_config_rx: Option<&mut mpsc::Receiver<RuntimeConfig>>,
+) -> Option<RuntimeConfig> {
+    match runtime_config_rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
 #[async_trait]
 impl NodeActor for EngineActor {
+    const NAME: &'static str = "engine";
     type Error = EngineError;
     type InboundData = EngineContext;
     type OutboundData = EngineOutboundData;
@@ -297,9 +898,11 @@ impl NodeActor for EngineActor {
             mut runtime_config_rx,
             mut attributes_rx,
             mut unsafe_block_rx,
-            mut reset_request_rx,
+            mut reload,
+            reset,
             cancellation,
             inbound_queries,
+            health,
         }: Self::InboundData,
     ) -> Result<(), Self::Error> {
         // Start the engine query server in a separate task to avoid blocking the main task.
@@ -309,68 +912,276 @@ impl NodeActor for EngineActor {
         // it in an `Option` to ensure we satisfy the borrow checker.
         let mut sync_complete_tx = Some(self.sync_complete_tx);
 
+        // The derivation actor is shut down before the engine actor, closing the attributes
+        // channel. Once closed, it is no longer polled, and resets are no longer coordinated.
+        let mut derivation_closed = false;
+        let mut reset_phase = reset.subscribe();
+
+        // The span of the last event that enqueued a task. Tasks are drained right after being
+        // enqueued, so draining within this span links the block import to its cause.
+        let mut cause = Span::none();
+
+        // Held back unsafe payloads are released as the clock advances.
+        let clock = Arc::clone(&self.state.clock);
+
+        // Maintenance tasks are run one at a time, only once the task queue has been drained and
+        // no other events are pending.
+        let mut maintenance = self.state.maintenance.scheduler(tokio::time::Instant::now());
+
+        // While the EL syncs, its progress is polled so that a stalled sync is re-triggered rather
+        // than waited on indefinitely.
+        let mut el_sync = ElSyncSupervisor::new(self.state.el_sync, tokio::time::Instant::now());
+
+        // Once synced, an unsafe head that lags behind gossip without advancing re-triggers EL
+        // sync, if enabled.
+        let mut unsafe_lag = self.state.unsafe_lag.map(UnsafeLagMonitor::new);
+
+        // The engine cannot serve its purpose until EL sync completes.
+        health.report(HealthStatus::Starting);
+
+        // In consensus sync, EL sync is skipped. The initial engine reset then walks back from the
+        // EL's heads to find the sync starting point, and derivation starts immediately. After a
+        // graceful restart, the EL was already synced, so consensus sync is used as well.
+        // The derived attributes saved on shutdown are only replayed when re-attaching.
+        let backlog = self.state.take_attributes_backlog();
+        let checkpoint = self.state.restart_checkpoint().await;
+        let sync_mode = match &checkpoint {
+            Some(checkpoint) => {
+                info!(
+                    target: "engine",
+                    unsafe_head = checkpoint.unsafe_head.block_info.number,
+                    safe_head = checkpoint.safe_head.block_info.number,
+                    buffered = self.state.unsafe_payloads.len(),
+                    "Re-attaching to the execution layer after restart"
+                );
+                SyncMode::Consensus
+            }
+            None => self.state.sync_mode.resolve(&self.state.client).await.unwrap_or_else(|err| {
+                warn!(target: "engine", ?err, "Failed to detect sync mode, falling back to EL sync");
+                SyncMode::ExecutionLayer
+            }),
+        };
+        info!(target: "engine", %sync_mode, "Starting sync");
+
+        // Load the chain config of the EL once, to validate the attributes of block builds against.
+        if let Err(err) = self.state.client.load_el_chain_config().await {
+            warn!(target: "engine", ?err, "Failed to load the EL chain config, block build attributes are not pre-validated");
+        }
+        if sync_mode == SyncMode::Consensus {
+            self.state.engine.skip_el_sync();
+        }
+        if checkpoint.is_some() && !backlog.attributes.is_empty() {
+            self.state.replay_backlog(backlog, &mut finalizer, &cancellation).await?;
+        }
+
         loop {
             // Attempt to drain all outstanding tasks from the engine queue before adding new ones.
+            let previous = *self.state.engine.state();
             self.state
                 .drain(
+                    &reset,
                     &self.derivation_signal_tx,
                     &mut sync_complete_tx,
-                    &self.engine_l2_safe_head_tx,
+                    &self.heads,
                     &mut finalizer,
                     &cancellation,
                 )
+                .instrument(std::mem::replace(&mut cause, Span::none()))
                 .await?;
+            self.state.record_latencies(&previous, &mut self.awaiting_safe);
+
+            // Payloads held back on top of a reorged unsafe chain are stale.
+            let unsafe_head = self.state.engine.state().unsafe_head();
+            if ReorgEvent::unsafe_head(previous.unsafe_head(), unsafe_head).is_some() {
+                self.delayed_payloads.clear();
+            }
+
+            // Derivation is paused while EL sync is re-triggered, and resumed once it completes.
+            let resyncing =
+                sync_complete_tx.is_none() && !self.state.engine.state().el_sync_finished;
+            self.el_resync_tx
+                .send_if_modified(|paused| std::mem::replace(paused, resyncing) != resyncing);
+
+            // The blocks promoted to safe from derived attributes are checked against the
+            // execution layer, if enabled.
+            let local_safe_head = self.state.engine.state().local_safe_head();
+            if let Some(checker) = self.consistency.as_mut() {
+                if local_safe_head != previous.local_safe_head() {
+                    let EngineActorState { client, rollup, journal, .. } = &self.state;
+                    checker.check(client.clone(), rollup.clone(), local_safe_head, journal.clone());
+                }
+            }
+
+            // Blocks whose L1 origin was finalized before they became safe are finalized once the
+            // safe head reaches them.
+            if self.state.engine.state().safe_head() != previous.safe_head() {
+                finalizer.try_finalize_next(&mut self.state.engine).await;
+                if !self.state.engine.is_empty() {
+                    continue;
+                }
+            }
+
+            // The sync complete sender is consumed once EL sync completes. The engine is degraded
+            // while engine tasks are dropped on timeouts, or while the last checked safe block
+            // does not match its derived attributes. While EL sync is re-triggered, the health is
+            // reported by its supervision instead.
+            if sync_complete_tx.is_none() && self.state.engine.state().el_sync_finished {
+                if let Some(kind) = self.state.engine.timed_out() {
+                    health.degraded(format!("{kind} engine task timed out"));
+                } else if let Some(mismatch) =
+                    self.consistency.as_ref().and_then(ConsistencyChecker::mismatch)
+                {
+                    health.degraded(mismatch);
+                } else {
+                    health.healthy();
+                }
+            }
+
+            // Insert buffered unsafe payloads one at a time, draining after each so that the
+            // unsafe head advances before the next one is popped.
+            if self.state.try_insert_buffered() {
+                continue;
+            }
+
+            // Release derived attributes held back by the DA challenge gate one at a time, as
+            // their challenge windows elapse.
+            if let Some((attributes, span)) = finalizer.release_next() {
+                self.awaiting_safe.insert(attributes.block_number(), attributes.l1_origin);
+                if let Some(checker) = self.consistency.as_mut() {
+                    checker.track(&attributes);
+                }
+                self.state.consolidate(attributes);
+                cause = span;
+                continue;
+            }
+
+            // An expired DA challenge makes the data of the held blocks unavailable. Derivation is
+            // reset onto the safe head, rather than stalling on blocks that are never released.
+            if let Some(challenged_block) = finalizer.take_expired_da_challenge() {
+                warn!(target: "engine", challenged_block, "Resetting after an expired DA challenge");
+                self.state.reset(&reset, &self.heads, &mut finalizer, &cancellation).await?;
+                self.delayed_payloads.clear();
+                continue;
+            }
+
+            // Release held back gossiped payloads one at a time once they satisfy the unsafe
+            // delay, which can be changed at runtime.
+            let delay = Self::unsafe_delay(&reload.borrow_and_update());
+            if let Some(envelope) =
+                self.delayed_payloads.pop_ready(delay, Self::clock_now(&*clock, self.clock_origin))
+            {
+                self.state.handle_unsafe_payload(envelope);
+                continue;
+            }
+            let delay_elapsed =
+                self.delayed_payloads.ready_in(delay, Self::clock_now(&*clock, self.clock_origin));
+            let maintenance_due = maintenance.next_due().filter(|_| self.state.engine.is_empty());
+            let el_syncing = !self.state.engine.state().el_sync_finished;
+            // The tasks held back while the EL is syncing are resubmitted by the next drain.
+            let syncing_retry = self.state.engine.syncing_retry_at();
+            let resync_due = match unsafe_lag.as_mut() {
+                Some(monitor) if !el_syncing => {
+                    let unsafe_head = self.state.engine.state().unsafe_head().block_info.number;
+                    let tip = self
+                        .state
+                        .unsafe_payloads
+                        .latest()
+                        .map_or(unsafe_head, |envelope| envelope.payload.block_number());
+                    monitor.observe(unsafe_head, tip, tokio::time::Instant::now());
+                    monitor.deadline()
+                }
+                _ => None,
+            };
+            // A reset target that derivation does not acknowledge in time is picked again.
+            let phase = *reset_phase.borrow();
+            let awaiting_ack = matches!(phase, ResetPhase::Targeted { .. });
 
             tokio::select! {
                 biased;
 
                 _ = cancellation.cancelled() => {
-                    warn!(target: "engine", "EngineActor received shutdown signal. Shutting down engine query task.");
+                    warn!(target: "engine", "EngineActor received shutdown signal. Flushing engine task queue.");
+                    self.state.flush().await;
+                    self.state.save_restart_checkpoint();
+                    // The attributes still queued by the derivation actor, which is already shut
+                    // down, that were derived since the last reset.
+                    let backlog = attributes_rx
+                        .drain()
+                        .into_iter()
+                        .filter(|derived| derived.reset_id == reset.last_target())
+                        .map(|derived| derived.attributes)
+                        .collect();
+                    self.state.save_attributes_backlog(backlog);
                     handle.abort();
 
                     return Ok(());
                 }
-                reset = reset_request_rx.recv() => {
-                    if reset.is_none() {
-                        error!(target: "engine", "Reset request receiver closed unexpectedly");
-                        cancellation.cancel();
-                        return Err(EngineError::ChannelClosed);
+                Ok(()) = reset_phase.changed(), if !derivation_closed => {
+                    let ResetPhase::Requested { id, .. } = *reset_phase.borrow_and_update() else {
+                        continue;
+                    };
+                    let safe_head = self.state.engine.state().safe_head();
+                    if self.state.rollup.is_interop_active(safe_head.block_info.timestamp) {
+                        debug!(target: "engine", id, "Leaving the requested reset to the supervisor");
+                        continue;
                     }
-                    warn!(target: "engine", "Received reset request");
-                    self.state
-                        .reset(&self.derivation_signal_tx, &self.engine_l2_safe_head_tx, &mut finalizer, &cancellation)
-                        .await?;
+                    warn!(target: "engine", id, "Received reset request");
+                    self.state.reset(&reset, &self.heads, &mut finalizer, &cancellation).await?;
+                    self.delayed_payloads.clear();
+                }
+                _ = reset.timeout_of(&phase), if awaiting_ack && !derivation_closed => {
+                    reset.timed_out(&reset.phase());
+                    self.state.reset(&reset, &self.heads, &mut finalizer, &cancellation).await?;
+                    self.delayed_payloads.clear();
                 }
                 unsafe_block = unsafe_block_rx.recv() => {
-                    let Some(envelope) = unsafe_block else {
+                    let Some(Event { payload: envelope, span }) = unsafe_block else {
                         error!(target: "engine", "Unsafe block receiver closed unexpectedly");
                         cancellation.cancel();
                         return Err(EngineError::ChannelClosed);
                     };
-                    let task = EngineTask::InsertUnsafe(InsertUnsafeTask::new(
-                        self.state.client.clone(),
-                        self.state.rollup.clone(),
-                        envelope,
-                    ));
-                    self.state.engine.enqueue(task);
+                    cause = span;
+                    // Without a delay, payloads skip the queue once the held back ones are out.
+                    if !delay.is_enabled() && self.delayed_payloads.is_empty() {
+                        self.state.handle_unsafe_payload(envelope);
+                        continue;
+                    }
+                    let number = envelope.payload.block_number();
+                    if !self.delayed_payloads.insert(envelope) {
+                        debug!(target: "engine", number, "Discarded held back unsafe payload");
+                    } else if delay.is_enabled() {
+                        debug!(target: "engine", number, ?delay, "Holding back unsafe payload");
+                    }
                 }
-                attributes = attributes_rx.recv() => {
-                    let Some(attributes) = attributes else {
-                        error!(target: "engine", "Attributes receiver closed unexpectedly");
-                        cancellation.cancel();
-                        return Err(EngineError::ChannelClosed);
+                _ = clock.sleep(delay_elapsed.unwrap_or_default()), if delay_elapsed.is_some() => {
+                    // The lowest held back payload is now old enough to be released.
+                }
+                Ok(()) = reload.changed() => {
+                    // Re-evaluate the held back payloads against the new unsafe delay.
+                }
+                attributes = attributes_rx.recv(), if !derivation_closed => {
+                    let Some(Event { payload: DerivedAttributes { reset_id, attributes }, span }) = attributes else {
+                        warn!(target: "engine", "Attributes channel closed by the derivation actor");
+                        derivation_closed = true;
+                        continue;
                     };
-                    finalizer.enqueue_for_finalization(&attributes);
-
-                    let task = EngineTask::Consolidate(ConsolidateTask::new(
-                        self.state.client.clone(),
-                        Arc::clone(&self.state.rollup),
-                        attributes,
-                        true,
-                    ));
-                    self.state.engine.enqueue(task);
+                    // Drop the attributes derived before the last reset, which may still be queued.
+                    if reset_id != reset.last_target() {
+                        debug!(target: "engine", reset_id, number = attributes.block_number(), "Dropped attributes derived before the last reset");
+                        continue;
+                    }
+                    // On alt-DA chains, the attributes are held until their data can no longer
+                    // be challenged.
+                    if let Some((attributes, span)) = finalizer.admit(attributes, span) {
+                        self.awaiting_safe.insert(attributes.block_number(), attributes.l1_origin);
+                        if let Some(checker) = self.consistency.as_mut() {
+                            checker.track(&attributes);
+                        }
+                        self.state.consolidate(attributes);
+                        cause = span;
+                    }
                 }
-                config = runtime_config_rx.as_mut().map(|rx| rx.recv()).unwrap(), if runtime_config_rx.is_some() => {
+                config = recv_runtime_config(runtime_config_rx.as_mut()) => {
                     let Some(config) = config else {
                         error!(target: "engine", "Runtime config receiver closed unexpectedly");
                         cancellation.cancel();
@@ -378,6 +1189,23 @@ impl NodeActor for EngineActor {
                     };
                     self.state.runtime_config_update(config);
                 }
+                _ = tokio::time::sleep_until(syncing_retry.unwrap_or_else(tokio::time::Instant::now)), if syncing_retry.is_some() => {
+                    // The backoff of the tasks held back elapsed.
+                }
+                _ = tokio::time::sleep_until(el_sync.next_poll()), if el_syncing => {
+                    self.state.supervise_el_sync(&mut el_sync, &health).await;
+                }
+                _ = tokio::time::sleep_until(resync_due.unwrap_or_else(tokio::time::Instant::now)), if resync_due.is_some() => {
+                    if self.state.resync_el(&health) {
+                        el_sync = ElSyncSupervisor::new(self.state.el_sync, tokio::time::Instant::now());
+                    }
+                    if let Some(monitor) = unsafe_lag.as_mut() {
+                        monitor.clear();
+                    }
+                }
+                _ = tokio::time::sleep_until(maintenance_due.unwrap_or_else(tokio::time::Instant::now)), if maintenance_due.is_some() => {
+                    maintenance.run_next(&self.state.client, self.state.engine.state()).await;
+                }
                 msg = finalizer.new_finalized_block() => {
                     if let Err(err) = msg {
                         error!(target: "engine", ?err, "L1 finalized block receiver closed unexpectedly");
@@ -385,7 +1213,8 @@ impl NodeActor for EngineActor {
                         return Err(EngineError::ChannelClosed);
                     }
                     // Attempt to finalize any L2 blocks that are contained within the finalized L1
-                    // chain.
+                    // chain. Derived attributes held by the DA challenge gate are re-evaluated on
+                    // the next iteration.
                     finalizer.try_finalize_next(&mut self.state.engine).await;
                 }
             }
@@ -406,6 +1235,50 @@ pub struct EngineLauncher {
     pub l1_rpc_url: Url,
     /// The engine jwt secret.
     pub jwt_secret: JwtSecret,
+    /// The [`SyncMode`] used to start syncing the L2 chain.
+    pub sync_mode: SyncMode,
+    /// The maximum number of buffered unsafe payloads.
+    pub unsafe_buffer_capacity: usize,
+    /// The directory to persist buffered unsafe payloads to, if any.
+    pub unsafe_buffer_dir: Option<PathBuf>,
+    /// The number of payload envelopes of the latest unsafe blocks kept in the
+    /// [`PayloadEnvelopeStore`].
+    pub envelope_store_capacity: usize,
+    /// The directory to persist the [`PayloadEnvelopeStore`] to, if any.
+    pub envelope_store_dir: Option<PathBuf>,
+    /// How much of the history of the [`SafeHeadIndex`] is kept.
+    pub safe_head_retention: SafeHeadRetention,
+    /// The path to persist the [`SafeHeadIndex`] to, if any.
+    pub safe_head_index_path: Option<PathBuf>,
+    /// The [`MaintenanceRegistry`] of tasks run while the engine task queue is idle.
+    pub maintenance: MaintenanceRegistry,
+    /// The [`BuildBudget`] that the timings of block builds are recorded against.
+    pub build_budget: BuildBudget,
+    /// The [`RestartConfig`], if graceful restarts are enabled.
+    pub restart: Option<RestartConfig>,
+    /// The fault injector applied to the requests of the [`EngineClient`], if any.
+    #[cfg(feature = "chaos")]
+    pub chaos: Option<kona_providers_alloy::Chaos>,
+    /// The [`MemoryAudit`] that the size of the [`UnsafePayloadBuffer`] is recorded into, if any.
+    pub memory_audit: Option<MemoryAudit>,
+    /// The path to persist the L2 blocks awaiting finalization to, if any.
+    pub finality_path: Option<PathBuf>,
+    /// The [`ElSyncConfig`] of the supervision of the execution layer sync.
+    pub el_sync: ElSyncConfig,
+    /// The [`EngineTaskTimeouts`] of the [`Engine`] tasks.
+    pub task_timeouts: EngineTaskTimeouts,
+    /// The [`SyncingBufferConfig`] of the [`Engine`] tasks held back while the EL is syncing.
+    pub syncing_buffer: SyncingBufferConfig,
+    /// How long ahead of a hardfork the calls of the [`EngineClient`] are shadowed, if they are.
+    pub shadow_lookahead: Option<Duration>,
+    /// The [`EngineTransport`] of the [`EngineClient`].
+    pub transport: EngineTransport,
+    /// Where the trusted safe head hint is taken from, if any.
+    pub safe_head_hint: Option<SafeHeadHintSource>,
+    /// Whether the blocks promoted to safe are checked against their derived attributes.
+    pub consistency_check: bool,
+    /// The [`UnsafeLagConfig`] after which EL sync is re-triggered, if enabled.
+    pub unsafe_lag: Option<UnsafeLagConfig>,
 }
 
 impl EngineLauncher {
@@ -415,16 +1288,392 @@ impl EngineLauncher {
         let state = InnerEngineState::default();
         let (engine_state_send, _) = tokio::sync::watch::channel(state);
         Engine::new(state, engine_state_send)
+            .with_task_timeouts(self.task_timeouts)
+            .with_syncing_buffer(self.syncing_buffer)
+    }
+
+    /// Returns the [`UnsafePayloadBuffer`]. If the persistence directory cannot be loaded, an
+    /// in-memory buffer is returned instead.
+    pub fn unsafe_payload_buffer(&self) -> UnsafePayloadBuffer {
+        let buffer = self.unsafe_buffer_dir.as_ref().map_or_else(
+            || UnsafePayloadBuffer::new(self.unsafe_buffer_capacity),
+            |dir| {
+                UnsafePayloadBuffer::with_persistence(self.unsafe_buffer_capacity, dir)
+                    .unwrap_or_else(|err| {
+                        warn!(target: "engine", ?err, dir = %dir.display(), "Failed to load persisted unsafe payloads");
+                        UnsafePayloadBuffer::new(self.unsafe_buffer_capacity)
+                    })
+            },
+        );
+        match self.memory_audit.clone() {
+            Some(audit) => buffer.with_memory_audit(audit),
+            None => buffer,
+        }
+    }
+
+    /// Returns the [`PayloadEnvelopeStore`]. If the persistence directory cannot be loaded, an
+    /// in-memory store is returned instead.
+    pub fn payload_envelope_store(&self) -> PayloadEnvelopeStore {
+        self.envelope_store_dir.as_ref().map_or_else(
+            || PayloadEnvelopeStore::new(self.envelope_store_capacity),
+            |dir| {
+                PayloadEnvelopeStore::with_persistence(self.envelope_store_capacity, dir)
+                    .unwrap_or_else(|err| {
+                        warn!(target: "engine", ?err, dir = %dir.display(), "Failed to load persisted payload envelopes");
+                        PayloadEnvelopeStore::new(self.envelope_store_capacity)
+                    })
+            },
+        )
+    }
+
+    /// Returns the [`SafeHeadIndex`]. If the persisted index cannot be loaded, an in-memory index
+    /// is returned instead.
+    pub fn safe_head_index(&self) -> SafeHeadIndex {
+        self.safe_head_index_path.as_ref().map_or_else(
+            || SafeHeadIndex::new(self.safe_head_retention),
+            |path| {
+                SafeHeadIndex::with_persistence(self.safe_head_retention, path)
+                    .unwrap_or_else(|err| {
+                        warn!(target: "engine", ?err, path = %path.display(), "Failed to load persisted safe head index");
+                        SafeHeadIndex::new(self.safe_head_retention)
+                    })
+            },
+        )
     }
 
     /// Returns the [`EngineClient`].
     pub fn client(&self) -> EngineClient {
-        EngineClient::new_http(
+        let client = EngineClient::new_with_transport(
             self.engine_url.clone(),
             self.l2_rpc_url.clone(),
             self.l1_rpc_url.clone(),
             self.config.clone(),
             self.jwt_secret,
+            &self.transport,
+        );
+        let client = match self.shadow_lookahead {
+            Some(lookahead) => client.with_shadowing(lookahead),
+            None => client,
+        };
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = self.chaos.clone() {
+            return client.with_layer(kona_providers_alloy::ChaosLayer::new(chaos));
+        }
+        client
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        SystemClock,
+        bus::{self, ChannelConfig},
+    };
+    use alloy_consensus::{Header, transaction::Recovered};
+    use alloy_primitives::{Address, B256, U256};
+    use alloy_rpc_types_eth::{BlockNumberOrTag, BlockTransactions};
+    use kona_engine::MockEngineClient;
+    use kona_protocol::L1BlockInfoTx;
+    use kona_rpc::HealthRegistry;
+    use op_alloy_consensus::OpTxEnvelope;
+    use op_alloy_rpc_types_engine::{OpPayloadAttributes, ProtocolVersion};
+    use std::path::Path;
+
+    /// Returns the L2 block with the given number and parent, holding its L1 info deposit.
+    fn block(
+        cfg: &RollupConfig,
+        number: u64,
+        parent_hash: B256,
+    ) -> alloy_rpc_types_eth::Block<op_alloy_rpc_types::Transaction> {
+        let (_, deposit) = L1BlockInfoTx::try_new_with_deposit_tx(
+            cfg,
+            &SystemConfig::default(),
+            number,
+            &Header::default(),
+            number * cfg.block_time,
+        )
+        .unwrap();
+        let header = Header { number, parent_hash, ..Default::default() };
+        let hash = header.hash_slow();
+        let from = deposit.from;
+        let tx = op_alloy_rpc_types::Transaction {
+            inner: alloy_rpc_types_eth::Transaction {
+                inner: Recovered::new_unchecked(OpTxEnvelope::Deposit(deposit), from),
+                block_hash: Some(hash),
+                block_number: Some(number),
+                transaction_index: Some(0),
+                effective_gas_price: Some(0),
+            },
+            deposit_nonce: None,
+            deposit_receipt_version: None,
+        };
+        alloy_rpc_types_eth::Block {
+            header: alloy_rpc_types_eth::Header {
+                hash,
+                inner: header,
+                total_difficulty: Some(U256::ZERO),
+                size: None,
+            },
+            uncles: vec![],
+            transactions: BlockTransactions::Full(vec![tx]),
+            withdrawals: None,
+        }
+    }
+
+    /// Returns derived attributes extending the given parent.
+    fn attributes(parent: L2BlockInfo) -> OpAttributesWithParent {
+        OpAttributesWithParent::new(
+            OpPayloadAttributes::default(),
+            parent,
+            BlockInfo::default(),
+            true,
         )
     }
+
+    /// Returns the [`RestartConfig`] persisting into the given directory.
+    fn restart_config(dir: &Path) -> RestartConfig {
+        RestartConfig {
+            path: dir.join("checkpoint.json"),
+            backlog_path: dir.join("backlog.json"),
+            window: Duration::from_secs(60),
+        }
+    }
+
+    /// Returns whether the block with the given number was requested from the execution layer.
+    fn requested(mock: &MockEngineClient, number: u64) -> bool {
+        mock.calls().iter().any(|call| {
+            call.method == "eth_getBlockByNumber" && call.params[0] == format!("{number:#x}")
+        })
+    }
+
+    /// An engine actor in consensus sync, with graceful restarts enabled, attached to a mock
+    /// execution layer holding the blocks up to 1. Block 0 stands in for the L1 origin of block 1,
+    /// so that the engine resets to block 1.
+    struct Harness {
+        actor: EngineActor,
+        context: EngineContext,
+        mock: MockEngineClient,
+        attributes_tx: EventSender<DerivedAttributes>,
+        _outbound: EngineOutboundData,
+        _finalized_tx: watch::Sender<Option<BlockInfo>>,
+        _unsafe_block_tx: EventSender<OpExecutionPayloadEnvelope>,
+        _reload_tx: watch::Sender<ReloadableConfig>,
+        _queries_tx: mpsc::Sender<EngineQueries>,
+    }
+
+    impl Harness {
+        /// Creates a harness with the given [`RestartConfig`].
+        fn new(restart: &RestartConfig) -> Self {
+            let cfg = Arc::new(RollupConfig::default());
+            let mock = MockEngineClient::new(cfg.clone());
+            mock.insert_block(
+                0,
+                &alloy_rpc_types_eth::Block::<alloy_rpc_types_eth::Transaction> {
+                    header: alloy_rpc_types_eth::Header::new(Header::default()),
+                    uncles: vec![],
+                    transactions: BlockTransactions::Full(vec![]),
+                    withdrawals: None,
+                },
+            );
+            mock.insert_block(1, &block(&cfg, 1, B256::ZERO));
+
+            let state = InnerEngineState::default();
+            let (outbound, actor) = EngineActor::new(EngineActorState {
+                rollup: cfg,
+                client: mock.client(),
+                engine: Engine::new(state, watch::channel(state).0),
+                sync_mode: SyncMode::Consensus,
+                unsafe_payloads: UnsafePayloadBuffer::new(16),
+                envelopes: PayloadEnvelopeStore::new(16),
+                safe_heads: SafeHeadIndex::new(SafeHeadRetention::default()),
+                reorgs: broadcast::channel(1).0,
+                bus: BusConfig::default(),
+                maintenance: MaintenanceRegistry::default(),
+                build_budget: BuildBudget::default(),
+                journal: EventJournal::disabled(),
+                restart: Some(restart.clone()),
+                el_sync: ElSyncConfig::default(),
+                safe_head_hint: None,
+                consistency_check: false,
+                unsafe_lag: None,
+                clock: Arc::new(SystemClock),
+            });
+
+            let (attributes_tx, attributes_rx) = bus::channel("attributes", ChannelConfig::new(4));
+            let (unsafe_block_tx, unsafe_block_rx) =
+                bus::channel("unsafe_blocks", ChannelConfig::new(1));
+            let (finalized_tx, finalized_rx) = watch::channel(None);
+            let (reload_tx, reload) = watch::channel(ReloadableConfig::default());
+            let (queries_tx, inbound_queries) = mpsc::channel(1);
+            let context = EngineContext {
+                runtime_config_rx: None,
+                attributes_rx,
+                unsafe_block_rx,
+                reload,
+                reset: ResetCoordinator::new(Duration::from_secs(10)),
+                inbound_queries,
+                cancellation: CancellationToken::new(),
+                finalizer: L2Finalizer::new(finalized_rx, mock.client()),
+                health: HealthReporter::new(EngineActor::NAME, HealthRegistry::new()),
+            };
+            Self {
+                actor,
+                context,
+                mock,
+                attributes_tx,
+                _outbound: outbound,
+                _finalized_tx: finalized_tx,
+                _unsafe_block_tx: unsafe_block_tx,
+                _reload_tx: reload_tx,
+                _queries_tx: queries_tx,
+            }
+        }
+
+        /// Returns the block reference of the given block of the execution layer.
+        async fn local(&self, number: u64) -> L2BlockInfo {
+            self.mock
+                .client()
+                .l2_block_info_by_label(BlockNumberOrTag::Number(number))
+                .await
+                .unwrap()
+                .unwrap()
+        }
+
+        /// Runs the actor, shut down before it starts, so that it stops once it has caught up
+        /// with the execution layer. Returns the mock execution layer.
+        async fn run_until_shutdown(self) -> MockEngineClient {
+            self.context.cancellation.cancel();
+            self.actor.start(self.context).await.unwrap();
+            self.mock
+        }
+    }
+
+    #[test]
+    fn test_head_senders_notify_changed_heads_only() {
+        let (unsafe_head, unsafe_head_rx) = watch::channel(L2BlockInfo::default());
+        let (cross_unsafe_head, cross_unsafe_head_rx) = watch::channel(L2BlockInfo::default());
+        let (local_safe_head, local_safe_head_rx) = watch::channel(L2BlockInfo::default());
+        let (safe_head, safe_head_rx) = watch::channel(L2BlockInfo::default());
+        let (finalized_head, finalized_head_rx) = watch::channel(L2BlockInfo::default());
+        let heads = EngineHeadSenders {
+            unsafe_head,
+            cross_unsafe_head,
+            local_safe_head,
+            safe_head,
+            finalized_head,
+        };
+        let mut receivers = [
+            unsafe_head_rx,
+            cross_unsafe_head_rx,
+            local_safe_head_rx,
+            safe_head_rx,
+            finalized_head_rx,
+        ];
+        let setters: [fn(&mut InnerEngineState, L2BlockInfo); 5] = [
+            InnerEngineState::set_unsafe_head,
+            InnerEngineState::set_cross_unsafe_head,
+            InnerEngineState::set_local_safe_head,
+            InnerEngineState::set_safe_head,
+            InnerEngineState::set_finalized_head,
+        ];
+
+        // Publishing unchanged heads notifies no one.
+        let mut state = InnerEngineState::default();
+        heads.publish(&state);
+        assert!(receivers.iter().all(|rx| !rx.has_changed().unwrap()));
+
+        for (changed, set) in setters.into_iter().enumerate() {
+            let head = L2BlockInfo {
+                block_info: BlockInfo { number: changed as u64 + 1, ..Default::default() },
+                ..Default::default()
+            };
+            set(&mut state, head);
+            heads.publish(&state);
+            for (index, rx) in receivers.iter_mut().enumerate() {
+                assert_eq!(rx.has_changed().unwrap(), index == changed, "head {index}");
+                if index == changed {
+                    assert_eq!(*rx.borrow_and_update(), head);
+                }
+            }
+
+            heads.publish(&state);
+            assert!(receivers.iter().all(|rx| !rx.has_changed().unwrap()));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_recv_runtime_config() {
+        // Without a runtime loader, no config is ever received.
+        let disabled = recv_runtime_config(None);
+        assert!(tokio::time::timeout(Duration::from_secs(60), disabled).await.is_err());
+
+        let config = RuntimeConfig {
+            unsafe_block_signer_address: Address::ZERO,
+            required_protocol_version: ProtocolVersion::V0(Default::default()),
+            recommended_protocol_version: ProtocolVersion::V0(Default::default()),
+        };
+        let (tx, mut rx) = mpsc::channel(1);
+        tx.send(config).await.unwrap();
+        assert_eq!(recv_runtime_config(Some(&mut rx)).await, Some(config));
+
+        // A closed runtime loader is reported.
+        drop(tx);
+        assert_eq!(recv_runtime_config(Some(&mut rx)).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_attributes_backlog_saved_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let restart = restart_config(dir.path());
+        let harness = Harness::new(&restart);
+        let safe_head = harness.local(1).await;
+        let reset = harness.context.reset.clone();
+
+        // The attributes still queued by derivation, derived before and after the initial reset,
+        // which is the first reset target.
+        let (stale, current) = (attributes(L2BlockInfo::default()), attributes(safe_head));
+        for (reset_id, attributes) in [(0, stale), (1, current.clone())] {
+            harness.attributes_tx.send(DerivedAttributes { reset_id, attributes }).await.unwrap();
+        }
+        harness.run_until_shutdown().await;
+
+        // Only the attributes derived since the last reset are saved, alongside the checkpoint.
+        assert_eq!(reset.last_target(), 1);
+        let backlog = AttributesBacklog::take(&restart.backlog_path).unwrap();
+        assert_eq!(backlog.attributes, [current]);
+        let checkpoint = RestartCheckpoint::take(&restart.path).unwrap().unwrap();
+        assert_eq!(checkpoint.safe_head, safe_head);
+    }
+
+    #[tokio::test]
+    async fn test_attributes_backlog_replayed_on_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let restart = restart_config(dir.path());
+        let harness = Harness::new(&restart);
+        let attributes = attributes(harness.local(1).await);
+        harness.attributes_tx.send(DerivedAttributes { reset_id: 1, attributes }).await.unwrap();
+        let mock = harness.run_until_shutdown().await;
+        assert!(!requested(&mock, 2));
+
+        // On restart, the engine re-attaches from the checkpoint, and consolidates the saved
+        // attributes of block 2 on top of the safe head.
+        let mock = Harness::new(&restart).run_until_shutdown().await;
+        assert!(requested(&mock, 2));
+        assert!(!restart.backlog_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_attributes_backlog_dropped_without_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let restart = restart_config(dir.path());
+        let harness = Harness::new(&restart);
+        let backlog = AttributesBacklog { attributes: vec![attributes(harness.local(1).await)] };
+        backlog.save(&restart.backlog_path).unwrap();
+
+        // Without a checkpoint to re-attach from, the backlog is discarded rather than replayed.
+        let mock = harness.run_until_shutdown().await;
+        assert!(!requested(&mock, 2));
+        assert!(!restart.backlog_path.exists());
+    }
 }
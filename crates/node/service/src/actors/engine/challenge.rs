@@ -0,0 +1,448 @@
+//! Tracking of the data availability challenges of alt-DA chains.
+//!
+//! On alt-DA chains, the batcher only posts commitments to L1, and the data behind a commitment
+//! can be challenged on the DA challenge contract within the challenge window following the L1
+//! block that included it. A challenged commitment must be resolved, by publishing the data on
+//! L1, within the resolve window. Both windows are measured in L1 blocks. The [`DaChallengeGate`]
+//! holds derived blocks back from being promoted to safe until their data can no longer be
+//! challenged.
+
+use crate::Metrics;
+use alloy_primitives::{B256, U256, keccak256};
+use alloy_rpc_types_eth::Log;
+use kona_genesis::RollupConfig;
+use kona_protocol::{BlockInfo, OpAttributesWithParent};
+use std::collections::{BTreeMap, VecDeque};
+use thiserror::Error;
+use tokio::sync::{mpsc, watch};
+use tracing::Span;
+
+/// The signature of the event emitted by the DA challenge contract when the status of a challenge
+/// changes.
+pub const CHALLENGE_STATUS_CHANGED: &str = "ChallengeStatusChanged(uint256,bytes,uint8)";
+
+/// The status of a data availability challenge, as emitted by the DA challenge contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum ChallengeStatus {
+    /// The commitment was never challenged.
+    #[display("uninitialized")]
+    Uninitialized,
+    /// The commitment is challenged, and awaits resolution.
+    #[display("active")]
+    Active,
+    /// The data behind the commitment was published on L1.
+    #[display("resolved")]
+    Resolved,
+    /// The challenge was not resolved within the resolve window, the data is unavailable.
+    #[display("expired")]
+    Expired,
+}
+
+impl TryFrom<u8> for ChallengeStatus {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Uninitialized),
+            1 => Ok(Self::Active),
+            2 => Ok(Self::Resolved),
+            3 => Ok(Self::Expired),
+            other => Err(other),
+        }
+    }
+}
+
+/// A change of the status of the challenge of the commitments included in an L1 block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DaChallengeUpdate {
+    /// The number of the L1 block that included the challenged commitments.
+    pub challenged_block: u64,
+    /// The new [`ChallengeStatus`].
+    pub status: ChallengeStatus,
+}
+
+impl DaChallengeUpdate {
+    /// Returns the topic of the [`CHALLENGE_STATUS_CHANGED`] event.
+    pub fn topic() -> B256 {
+        keccak256(CHALLENGE_STATUS_CHANGED)
+    }
+
+    /// Decodes a [`CHALLENGE_STATUS_CHANGED`] event. Returns `None` if the log is not one.
+    ///
+    /// The challenged block number is the indexed topic. The data holds the challenged
+    /// commitment, which is not needed, and the status in its second word.
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let topics = log.topics();
+        if topics.len() != 2 || topics[0] != Self::topic() {
+            return None;
+        }
+        let challenged_block = U256::from_be_bytes(topics[1].0).try_into().ok()?;
+        let data = &log.data().data;
+        let status = data.get(32..64)?;
+        if status[..31].iter().any(|&byte| byte != 0) {
+            return None;
+        }
+        let status = ChallengeStatus::try_from(status[31]).ok()?;
+        Some(Self { challenged_block, status })
+    }
+}
+
+/// The state of a challenge tracked by the [`DaChallengeGate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Challenge {
+    /// The challenge is active, anchored at the number of the challenged L1 block. It expires
+    /// once the L1 head is past both windows from the anchor, regardless of when the challenge
+    /// was observed.
+    Active(u64),
+    /// The challenge was resolved.
+    Resolved,
+    /// The challenge expired.
+    Expired,
+}
+
+/// Holds derived blocks back from being promoted to safe until the data they were derived from
+/// can no longer be challenged.
+///
+/// A block derived from the L1 block `B` is released once the L1 head is at least one challenge
+/// window past `B`, unless the commitments of `B` were challenged. A challenged block is released
+/// once the challenge is resolved. If the challenge is not resolved by the time the L1 head is
+/// both windows past `B`, it expires: the data is unavailable, so the held blocks derived from
+/// `B` onwards are dropped, and [`DaChallengeGate::take_expired`] reports the expiry so that the
+/// engine resets derivation onto its safe head. Blocks derived from `B` again after the reset are
+/// dropped as they are held, instead of stalling the safe head.
+///
+/// Blocks are released in the order they were derived. The held blocks are dropped on engine
+/// resets, since they are derived again.
+#[derive(Debug)]
+pub struct DaChallengeGate {
+    /// The challenge window, in L1 blocks.
+    challenge_window: u64,
+    /// The resolve window, in L1 blocks.
+    resolve_window: u64,
+    /// A channel that receives the latest L1 head.
+    l1_head_rx: watch::Receiver<Option<BlockInfo>>,
+    /// A channel that receives the [`DaChallengeUpdate`]s observed on L1.
+    updates_rx: mpsc::Receiver<DaChallengeUpdate>,
+    /// The challenges, keyed by the number of the challenged L1 block.
+    challenges: BTreeMap<u64, Challenge>,
+    /// The held derived blocks, with the spans that they were received in.
+    held: VecDeque<(OpAttributesWithParent, Span)>,
+    /// The challenged L1 block of the last challenge that expired, until it is taken.
+    expired: Option<u64>,
+}
+
+impl DaChallengeGate {
+    /// Creates a new [`DaChallengeGate`] with the given windows, in L1 blocks.
+    pub const fn new(
+        challenge_window: u64,
+        resolve_window: u64,
+        l1_head_rx: watch::Receiver<Option<BlockInfo>>,
+        updates_rx: mpsc::Receiver<DaChallengeUpdate>,
+    ) -> Self {
+        Self {
+            challenge_window,
+            resolve_window,
+            l1_head_rx,
+            updates_rx,
+            challenges: BTreeMap::new(),
+            held: VecDeque::new(),
+            expired: None,
+        }
+    }
+
+    /// Creates a new [`DaChallengeGate`] with the windows of the alt-DA config of the given
+    /// [`RollupConfig`]. Returns `None` if alt-DA is not enabled, and an error if alt-DA is
+    /// enabled without both windows configured.
+    pub fn from_rollup_config(
+        config: &RollupConfig,
+        l1_head_rx: watch::Receiver<Option<BlockInfo>>,
+        updates_rx: mpsc::Receiver<DaChallengeUpdate>,
+    ) -> Result<Option<Self>, DaChallengeConfigError> {
+        if !config.is_alt_da_enabled() {
+            return Ok(None);
+        }
+        let alt_da = config.alt_da_config.as_ref();
+        let challenge_window = alt_da
+            .and_then(|alt_da| alt_da.da_challenge_window)
+            .ok_or(DaChallengeConfigError::MissingChallengeWindow)?;
+        let resolve_window = alt_da
+            .and_then(|alt_da| alt_da.da_resolve_window)
+            .ok_or(DaChallengeConfigError::MissingResolveWindow)?;
+        Ok(Some(Self::new(challenge_window, resolve_window, l1_head_rx, updates_rx)))
+    }
+
+    /// Returns the number of held derived blocks.
+    pub fn len(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Returns true if no derived block is held.
+    pub fn is_empty(&self) -> bool {
+        self.held.is_empty()
+    }
+
+    /// Holds the given derived block until it can be released. Blocks derived from an L1 block
+    /// whose challenge expired are dropped, since their data is unavailable.
+    pub fn hold(&mut self, attributes: OpAttributesWithParent, span: Span) {
+        let derived_from = attributes.l1_origin.number;
+        if self.challenges.get(&derived_from) == Some(&Challenge::Expired) {
+            warn!(
+                target: "engine",
+                number = attributes.block_number(),
+                derived_from,
+                "Dropped derived block, its DA challenge expired"
+            );
+            return;
+        }
+        self.held.push_back((attributes, span));
+        kona_macros::set!(gauge, Metrics::DA_CHALLENGE_HELD_BLOCKS, self.held.len() as f64);
+    }
+
+    /// Drops the held derived blocks.
+    pub fn clear(&mut self) {
+        self.held.clear();
+        kona_macros::set!(gauge, Metrics::DA_CHALLENGE_HELD_BLOCKS, 0);
+    }
+
+    /// Waits for the next change of the L1 head or [`DaChallengeUpdate`], and records it.
+    ///
+    /// The future never completes once both channels are closed.
+    pub async fn next_update(&mut self) {
+        tokio::select! {
+            Ok(()) = self.l1_head_rx.changed() => {}
+            Some(update) = self.updates_rx.recv() => self.record(update),
+            else => std::future::pending().await,
+        }
+    }
+
+    /// Records the given [`DaChallengeUpdate`].
+    fn record(&mut self, update: DaChallengeUpdate) {
+        let DaChallengeUpdate { challenged_block, status } = update;
+        warn!(target: "engine", challenged_block, %status, "DA challenge status changed");
+        kona_macros::inc!(counter, Metrics::DA_CHALLENGE_UPDATES, "status" => status.to_string());
+        let challenge = match status {
+            ChallengeStatus::Uninitialized => {
+                self.challenges.remove(&challenged_block);
+                return;
+            }
+            ChallengeStatus::Active => Challenge::Active(challenged_block),
+            ChallengeStatus::Resolved => Challenge::Resolved,
+            ChallengeStatus::Expired => Challenge::Expired,
+        };
+        self.challenges.insert(challenged_block, challenge);
+    }
+
+    /// Returns the challenged L1 block of the last challenge that expired since this was last
+    /// called, if any. The engine resets derivation onto its safe head when one did.
+    pub const fn take_expired(&mut self) -> Option<u64> {
+        self.expired.take()
+    }
+
+    /// Releases the next held derived block, if it can no longer be challenged. Returns it with
+    /// its span, and the L1 head that released it.
+    pub fn release_next(&mut self) -> Option<(OpAttributesWithParent, Span, BlockInfo)> {
+        let head = (*self.l1_head_rx.borrow())?;
+        let derived_from = self.held.front()?.0.l1_origin.number;
+
+        match self.challenges.get(&derived_from).copied() {
+            None if head.number >= derived_from.saturating_add(self.challenge_window) => {}
+            None => return None,
+            Some(Challenge::Resolved) => {}
+            Some(Challenge::Active(anchor))
+                if head.number <
+                    anchor
+                        .saturating_add(self.challenge_window)
+                        .saturating_add(self.resolve_window) =>
+            {
+                return None;
+            }
+            Some(Challenge::Active(_) | Challenge::Expired) => {
+                self.expire(derived_from);
+                return None;
+            }
+        }
+
+        // Derivation proceeds in L1 order, so the challenges of earlier L1 blocks no longer
+        // apply to any block that is derived later.
+        self.challenges = self.challenges.split_off(&derived_from.number);
+        let (attributes, span) = self.held.pop_front()?;
+        kona_macros::set!(gauge, Metrics::DA_CHALLENGE_HELD_BLOCKS, self.held.len() as f64);
+        Some((attributes, span, head))
+    }
+
+    /// Marks the challenge of the given L1 block as expired, and drops the held blocks. They are
+    /// all derived from it or from later L1 blocks, so they build on the unavailable data.
+    fn expire(&mut self, challenged_block: u64) {
+        error!(
+            target: "engine",
+            challenged_block,
+            dropped = self.held.len(),
+            "DA challenge expired, dropping the blocks derived from the unavailable data"
+        );
+        self.challenges.insert(challenged_block, Challenge::Expired);
+        self.clear();
+        self.expired = Some(challenged_block);
+    }
+}
+
+/// An error in the alt-DA config of a [`RollupConfig`], preventing the creation of a
+/// [`DaChallengeGate`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaChallengeConfigError {
+    /// Alt-DA is enabled without a challenge window.
+    #[error("alt-DA is enabled, but the DA challenge window is not configured")]
+    MissingChallengeWindow,
+    /// Alt-DA is enabled without a resolve window.
+    #[error("alt-DA is enabled, but the DA resolve window is not configured")]
+    MissingResolveWindow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Address;
+    use kona_genesis::AltDAConfig;
+    use kona_protocol::L2BlockInfo;
+    use op_alloy_rpc_types_engine::OpPayloadAttributes;
+
+    const CHALLENGE_WINDOW: u64 = 10;
+    const RESOLVE_WINDOW: u64 = 5;
+
+    fn l1_block(number: u64) -> BlockInfo {
+        // Timestamps grow much faster than numbers, so that mixing them up fails the tests.
+        BlockInfo { number, timestamp: number * 1_000, ..Default::default() }
+    }
+
+    /// Returns the attributes of the L2 block after the given parent, derived from the given L1
+    /// block.
+    fn attributes(parent: u64, derived_from: u64) -> OpAttributesWithParent {
+        let parent = L2BlockInfo {
+            block_info: BlockInfo { number: parent, ..Default::default() },
+            ..Default::default()
+        };
+        OpAttributesWithParent::new(
+            OpPayloadAttributes::default(),
+            parent,
+            l1_block(derived_from),
+            true,
+        )
+    }
+
+    fn gate() -> (DaChallengeGate, watch::Sender<Option<BlockInfo>>, mpsc::Sender<DaChallengeUpdate>)
+    {
+        let (head_tx, head_rx) = watch::channel(None);
+        let (updates_tx, updates_rx) = mpsc::channel(16);
+        let gate = DaChallengeGate::new(CHALLENGE_WINDOW, RESOLVE_WINDOW, head_rx, updates_rx);
+        (gate, head_tx, updates_tx)
+    }
+
+    fn released(gate: &mut DaChallengeGate) -> Option<u64> {
+        gate.release_next().map(|(attributes, _, _)| attributes.block_number())
+    }
+
+    #[test]
+    fn test_release_after_challenge_window() {
+        let (mut gate, head_tx, _) = gate();
+        gate.hold(attributes(0, 100), Span::none());
+        gate.hold(attributes(1, 105), Span::none());
+
+        head_tx.send_replace(Some(l1_block(109)));
+        assert_eq!(released(&mut gate), None);
+
+        // The window is measured in L1 blocks past the L1 block the attributes were derived from.
+        head_tx.send_replace(Some(l1_block(110)));
+        assert_eq!(released(&mut gate), Some(1));
+        assert_eq!(released(&mut gate), None);
+        head_tx.send_replace(Some(l1_block(115)));
+        let (_, _, released_by) = gate.release_next().unwrap();
+        assert_eq!(released_by.number, 115);
+        assert!(gate.is_empty());
+    }
+
+    #[test]
+    fn test_release_once_challenge_resolved() {
+        let (mut gate, head_tx, _) = gate();
+        gate.hold(attributes(0, 100), Span::none());
+        head_tx.send_replace(Some(l1_block(102)));
+
+        gate.record(DaChallengeUpdate { challenged_block: 100, status: ChallengeStatus::Active });
+
+        // An active challenge holds the block past the challenge window.
+        head_tx.send_replace(Some(l1_block(112)));
+        assert_eq!(released(&mut gate), None);
+        assert_eq!(gate.take_expired(), None);
+
+        gate.record(DaChallengeUpdate { challenged_block: 100, status: ChallengeStatus::Resolved });
+        assert_eq!(released(&mut gate), Some(1));
+        assert!(gate.is_empty());
+    }
+
+    #[test]
+    fn test_expired_challenge_drops_held_blocks() {
+        let (mut gate, head_tx, _) = gate();
+        gate.hold(attributes(0, 100), Span::none());
+        gate.hold(attributes(1, 101), Span::none());
+
+        // The challenge is only observed late, but it is anchored at the challenged L1 block.
+        head_tx.send_replace(Some(l1_block(114)));
+        gate.record(DaChallengeUpdate { challenged_block: 100, status: ChallengeStatus::Active });
+        assert_eq!(released(&mut gate), None);
+        assert_eq!(gate.len(), 2);
+
+        // Past both windows, the challenge expires and the held blocks are dropped.
+        head_tx.send_replace(Some(l1_block(115)));
+        assert_eq!(released(&mut gate), None);
+        assert!(gate.is_empty());
+        assert_eq!(gate.take_expired(), Some(100));
+        assert_eq!(gate.take_expired(), None);
+
+        // After the reset, the blocks derived from the unavailable data are not held again.
+        gate.hold(attributes(0, 100), Span::none());
+        assert!(gate.is_empty());
+        gate.hold(attributes(0, 116), Span::none());
+        assert_eq!(gate.len(), 1);
+    }
+
+    #[test]
+    fn test_expired_status_drops_held_blocks() {
+        let (mut gate, head_tx, _) = gate();
+        gate.hold(attributes(0, 100), Span::none());
+        head_tx.send_replace(Some(l1_block(103)));
+        gate.record(DaChallengeUpdate { challenged_block: 100, status: ChallengeStatus::Expired });
+
+        assert_eq!(released(&mut gate), None);
+        assert!(gate.is_empty());
+        assert_eq!(gate.take_expired(), Some(100));
+    }
+
+    #[test]
+    fn test_from_rollup_config_requires_windows() {
+        let (_, head_rx) = watch::channel(None);
+        let alt_da = AltDAConfig {
+            da_challenge_address: Some(Address::repeat_byte(1)),
+            da_challenge_window: Some(CHALLENGE_WINDOW),
+            da_resolve_window: Some(RESOLVE_WINDOW),
+            ..Default::default()
+        };
+        let config = |alt_da: AltDAConfig| RollupConfig {
+            da_challenge_address: alt_da.da_challenge_address,
+            alt_da_config: Some(alt_da),
+            ..Default::default()
+        };
+        let from_config = |config: &RollupConfig| {
+            DaChallengeGate::from_rollup_config(config, head_rx.clone(), mpsc::channel(1).1)
+        };
+
+        assert!(from_config(&RollupConfig::default()).unwrap().is_none());
+        assert!(from_config(&config(alt_da.clone())).unwrap().is_some());
+        assert_eq!(
+            from_config(&config(AltDAConfig { da_challenge_window: None, ..alt_da.clone() }))
+                .unwrap_err(),
+            DaChallengeConfigError::MissingChallengeWindow
+        );
+        assert_eq!(
+            from_config(&config(AltDAConfig { da_resolve_window: None, ..alt_da })).unwrap_err(),
+            DaChallengeConfigError::MissingResolveWindow
+        );
+    }
+}
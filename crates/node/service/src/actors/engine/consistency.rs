@@ -0,0 +1,221 @@
+//! Verification of the safe blocks held by the execution layer against the derived attributes
+//! they were promoted from.
+
+use crate::{EventJournal, JournalEventKind, Metrics};
+use alloy_eips::BlockNumberOrTag;
+use kona_engine::{AttributesDiff, EngineClient};
+use kona_genesis::RollupConfig;
+use kona_protocol::{L2BlockInfo, OpAttributesWithParent};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+use tokio::task::JoinHandle;
+
+/// Checks the blocks promoted to safe by the engine against their derived attributes.
+///
+/// Consolidation compares the derived attributes against the unsafe block before promoting it,
+/// but the execution layer is not asked again afterwards. Once a block is promoted, the
+/// [`ConsistencyChecker`] fetches it from the execution layer and verifies that its transactions
+/// and withdrawals root match the attributes exactly. A mismatch means that the execution layer
+/// drifted from the derived chain, and is logged, counted in
+/// [`Metrics::SAFE_BLOCK_INCONSISTENCY_COUNT`] and recorded in the journal. The engine is
+/// reported as degraded until a later safe block is found consistent.
+///
+/// The blocks are fetched in a background task, so that the checks never hold back the engine.
+/// At most one check runs at a time. The blocks promoted to safe meanwhile are checked by the next
+/// one.
+#[derive(Debug, Default)]
+pub(crate) struct ConsistencyChecker {
+    /// The derived attributes of the blocks awaiting promotion to safe, by block number.
+    pending: BTreeMap<u64, OpAttributesWithParent>,
+    /// The last mismatch found, if no consistent safe block was checked since.
+    mismatch: Arc<Mutex<Option<String>>>,
+    /// The check in progress, if any.
+    running: Option<JoinHandle<()>>,
+}
+
+impl ConsistencyChecker {
+    /// Tracks the given derived attributes, to check the block built from them once it is safe.
+    /// Attributes derived again after a reset replace the ones of the same block.
+    pub(crate) fn track(&mut self, attributes: &OpAttributesWithParent) {
+        self.pending.insert(attributes.block_number(), attributes.clone());
+    }
+
+    /// Returns the last mismatch found, if no consistent safe block was checked since.
+    pub(crate) fn mismatch(&self) -> Option<String> {
+        self.mismatch.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Spawns a check of the tracked blocks up to the given safe head against the execution
+    /// layer. If a check is still running, the blocks are left to the next one.
+    pub(crate) fn check(
+        &mut self,
+        client: Arc<EngineClient>,
+        cfg: Arc<RollupConfig>,
+        safe_head: L2BlockInfo,
+        journal: EventJournal,
+    ) {
+        if self.running.as_ref().is_some_and(|running| !running.is_finished()) {
+            return;
+        }
+        let pending = self.pending.split_off(&(safe_head.block_info.number + 1));
+        let safe = std::mem::replace(&mut self.pending, pending);
+        if safe.is_empty() {
+            return;
+        }
+        let mismatch = self.mismatch.clone();
+        self.running = Some(tokio::spawn(async move {
+            Self::verify(safe, &client, &cfg, &journal, &mismatch).await;
+        }));
+    }
+
+    /// Waits for the check in progress, if any, to complete.
+    #[cfg(test)]
+    async fn wait(&mut self) {
+        if let Some(running) = self.running.take() {
+            running.await.expect("consistency check panicked");
+        }
+    }
+
+    /// Checks the given safe blocks against their derived attributes. Blocks that cannot be
+    /// fetched are skipped.
+    async fn verify(
+        safe: BTreeMap<u64, OpAttributesWithParent>,
+        client: &EngineClient,
+        cfg: &RollupConfig,
+        journal: &EventJournal,
+        mismatch: &Mutex<Option<String>>,
+    ) {
+        for (number, attributes) in safe {
+            let block = match client.l2_block_by_label(BlockNumberOrTag::Number(number)).await {
+                Ok(Some(block)) => block,
+                Ok(None) => {
+                    warn!(target: "engine", number, "Safe block not found, skipping consistency check");
+                    continue;
+                }
+                Err(err) => {
+                    warn!(target: "engine", number, ?err, "Failed to fetch safe block, skipping consistency check");
+                    continue;
+                }
+            };
+
+            let diff = AttributesDiff::new(cfg, &attributes, &block);
+            let Some(field) = Self::inconsistency(&diff) else {
+                trace!(target: "engine", number, "Safe block matches its derived attributes");
+                *mismatch.lock().unwrap_or_else(PoisonError::into_inner) = None;
+                continue;
+            };
+            error!(
+                target: "engine",
+                number,
+                hash = %block.header.hash,
+                field,
+                %diff,
+                "Safe block held by the execution layer does not match its derived attributes"
+            );
+            kona_macros::inc!(counter, Metrics::SAFE_BLOCK_INCONSISTENCY_COUNT, "field" => field);
+            journal.record(
+                JournalEventKind::ConsistencyMismatch,
+                format!("Safe block {number} does not match its derived attributes"),
+                serde_json::json!({
+                    "number": number,
+                    "hash": block.header.hash,
+                    "field": field,
+                    "transactions_hash": diff.transactions_hash,
+                    "withdrawals_root": diff.withdrawals_root,
+                }),
+            );
+            *mismatch.lock().unwrap_or_else(PoisonError::into_inner) =
+                Some(format!("safe block {number} does not match its derived attributes: {field}"));
+        }
+    }
+
+    /// Returns the label of the checked field that differs in the given [`AttributesDiff`], if
+    /// any. Only the transactions and the withdrawals root are checked.
+    const fn inconsistency(diff: &AttributesDiff) -> Option<&'static str> {
+        if diff.transactions_hash.is_some() {
+            Some(kona_engine::Metrics::ATTRIBUTES_MISMATCH_TRANSACTIONS)
+        } else if diff.withdrawals_root.is_some() {
+            Some(kona_engine::Metrics::ATTRIBUTES_MISMATCH_WITHDRAWALS_ROOT)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::Header;
+    use alloy_primitives::Bytes;
+    use kona_engine::MockEngineClient;
+    use kona_protocol::BlockInfo;
+    use op_alloy_rpc_types::Transaction;
+    use op_alloy_rpc_types_engine::OpPayloadAttributes;
+
+    fn block(number: u64) -> alloy_rpc_types_eth::Block<Transaction> {
+        let header = Header { number, ..Default::default() };
+        alloy_rpc_types_eth::Block {
+            header: alloy_rpc_types_eth::Header::new(header),
+            ..Default::default()
+        }
+    }
+
+    fn head(number: u64) -> L2BlockInfo {
+        L2BlockInfo { block_info: BlockInfo { number, ..Default::default() }, ..Default::default() }
+    }
+
+    /// Returns the attributes of the block after the given parent.
+    fn attributes(parent: u64, transactions: Option<Vec<Bytes>>) -> OpAttributesWithParent {
+        OpAttributesWithParent::new(
+            OpPayloadAttributes { transactions, ..Default::default() },
+            head(parent),
+            BlockInfo::default(),
+            true,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_consistent_safe_block() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        mock.insert_block(1, &block(1));
+        let mut checker = ConsistencyChecker::default();
+        checker.track(&attributes(0, None));
+
+        // Blocks are only checked once they are safe.
+        checker.check(mock.client(), cfg.clone(), head(0), EventJournal::disabled());
+        checker.wait().await;
+        assert!(mock.methods().is_empty());
+
+        checker.check(mock.client(), cfg, head(1), EventJournal::disabled());
+        checker.wait().await;
+        assert_eq!(mock.methods(), ["eth_getBlockByNumber"]);
+        assert_eq!(checker.mismatch(), None);
+    }
+
+    #[tokio::test]
+    async fn test_inconsistent_safe_block() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockEngineClient::new(cfg.clone());
+        mock.insert_block(1, &block(1));
+        mock.insert_block(2, &block(2));
+        let mut checker = ConsistencyChecker::default();
+
+        // The execution layer holds an empty block, where the attributes carry a transaction.
+        checker.track(&attributes(0, Some(vec![Bytes::from_static(&[1])])));
+        checker.check(mock.client(), cfg.clone(), head(1), EventJournal::disabled());
+        checker.wait().await;
+        assert_eq!(
+            checker.mismatch().as_deref(),
+            Some("safe block 1 does not match its derived attributes: transactions")
+        );
+
+        // A later consistent safe block clears the mismatch.
+        checker.track(&attributes(1, None));
+        checker.check(mock.client(), cfg, head(2), EventJournal::disabled());
+        checker.wait().await;
+        assert_eq!(checker.mismatch(), None);
+    }
+}
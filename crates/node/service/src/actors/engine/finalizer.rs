@@ -1,9 +1,16 @@
 //! The [`L2Finalizer`].
 
+use crate::DaChallengeGate;
 use kona_engine::{Engine, EngineClient, EngineTask, FinalizeTask};
 use kona_protocol::{BlockInfo, OpAttributesWithParent};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::sync::watch;
+use tracing::Span;
 
 /// An internal type alias for L1 block numbers.
 type L1BlockNumber = u64;
@@ -14,6 +21,15 @@ type L2BlockNumber = u64;
 /// The [`L2Finalizer`] is responsible for finalizing L2 blocks derived from finalized L1 blocks.
 /// It maintains a queue of derived L2 blocks that are awaiting finalization, and finalizes them
 /// as new finalized L1 blocks are received.
+///
+/// If a path is configured with [`L2Finalizer::with_persistence`], the queue is written to disk
+/// on every change and loaded again on startup. The blocks up to the safe head that the engine is
+/// reset to on startup are not derived again, so without it, the finalized head would stall until
+/// the blocks derived after the restart are finalized.
+///
+/// On alt-DA chains, a [`DaChallengeGate`] configured with [`L2Finalizer::with_da_challenges`]
+/// holds the derived blocks back until their data can no longer be challenged. A released block
+/// is finalized once the L1 block that released it is finalized, rather than its L1 origin.
 #[derive(Debug)]
 pub struct L2Finalizer {
     /// A channel that receives new finalized L1 blocks intermittently.
@@ -25,6 +41,10 @@ pub struct L2Finalizer {
     /// block is received, the highest L2 block whose inputs are contained within the finalized
     /// L1 chain is finalized.
     awaiting_finalization: BTreeMap<L1BlockNumber, L2BlockNumber>,
+    /// The path the finalization queue is persisted to, if any.
+    path: Option<PathBuf>,
+    /// The [`DaChallengeGate`] holding derived blocks back on alt-DA chains, if any.
+    da_challenges: Option<DaChallengeGate>,
 }
 
 impl L2Finalizer {
@@ -33,31 +53,154 @@ impl L2Finalizer {
         finalized_l1_block_rx: watch::Receiver<Option<BlockInfo>>,
         client: Arc<EngineClient>,
     ) -> Self {
-        Self { finalized_l1_block_rx, client, awaiting_finalization: BTreeMap::new() }
+        Self {
+            finalized_l1_block_rx,
+            client,
+            awaiting_finalization: BTreeMap::new(),
+            path: None,
+            da_challenges: None,
+        }
+    }
+
+    /// Holds derived blocks back with the given [`DaChallengeGate`] until their data can no
+    /// longer be challenged.
+    pub fn with_da_challenges(mut self, gate: DaChallengeGate) -> Self {
+        self.da_challenges = Some(gate);
+        self
+    }
+
+    /// Persists the finalization queue to the given path, loading the queue left over from a
+    /// previous run. If the queue cannot be loaded, the finalizer starts with an empty queue.
+    pub fn with_persistence(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match Self::load(&path) {
+            Ok(awaiting_finalization) => {
+                if let Some((l1, l2)) = awaiting_finalization.last_key_value() {
+                    info!(
+                        target: "engine",
+                        entries = awaiting_finalization.len(),
+                        l1,
+                        l2,
+                        "Loaded blocks awaiting finalization"
+                    );
+                }
+                self.awaiting_finalization = awaiting_finalization;
+            }
+            Err(err) => {
+                warn!(target: "engine", ?err, path = %path.display(), "Failed to load blocks awaiting finalization");
+            }
+        }
+        self.path = Some(path);
+        self
+    }
+
+    /// Returns the L2 blocks awaiting finalization, keyed by the L1 block they were derived from.
+    pub const fn awaiting_finalization(&self) -> &BTreeMap<L1BlockNumber, L2BlockNumber> {
+        &self.awaiting_finalization
     }
 
     /// Enqueues a derived [`OpAttributesWithParent`] for finalization. When a new finalized L1
     /// block is observed that is `>=` the height of [`OpAttributesWithParent::l1_origin`], the L2
     /// block associated with the payload attributes will be finalized.
     pub fn enqueue_for_finalization(&mut self, attributes: &OpAttributesWithParent) {
-        self.awaiting_finalization
-            .entry(attributes.l1_origin.number)
-            .and_modify(|n| *n = (*n).max(attributes.block_number()))
-            .or_insert(attributes.block_number());
+        self.enqueue_for_finalization_at(attributes.l1_origin.number, attributes);
+    }
+
+    /// Enqueues a derived [`OpAttributesWithParent`] for finalization once the L1 block with the
+    /// given number is finalized.
+    pub fn enqueue_for_finalization_at(
+        &mut self,
+        l1_number: L1BlockNumber,
+        attributes: &OpAttributesWithParent,
+    ) {
+        let l2_number = attributes.block_number();
+        if self.awaiting_finalization.get(&l1_number).is_none_or(|&number| number < l2_number) {
+            self.awaiting_finalization.insert(l1_number, l2_number);
+            self.persist();
+        }
+    }
+
+    /// Admits a derived [`OpAttributesWithParent`], received in the given span. Returns it back
+    /// if it can be consolidated right away, after enqueuing it for finalization. Otherwise, it
+    /// is held by the [`DaChallengeGate`] until [`L2Finalizer::release_next`] returns it.
+    pub fn admit(
+        &mut self,
+        attributes: OpAttributesWithParent,
+        span: Span,
+    ) -> Option<(OpAttributesWithParent, Span)> {
+        match &mut self.da_challenges {
+            Some(gate) => {
+                gate.hold(attributes, span);
+                None
+            }
+            None => {
+                self.enqueue_for_finalization(&attributes);
+                Some((attributes, span))
+            }
+        }
+    }
+
+    /// Releases the next derived block held by the [`DaChallengeGate`], if its data can no longer
+    /// be challenged, after enqueuing it for finalization.
+    pub fn release_next(&mut self) -> Option<(OpAttributesWithParent, Span)> {
+        let (attributes, span, released_by) = self.da_challenges.as_mut()?.release_next()?;
+        debug!(
+            target: "engine",
+            number = attributes.block_number(),
+            derived_from = attributes.l1_origin.number,
+            released_by = released_by.number,
+            "Released derived block past its DA challenge window"
+        );
+        self.enqueue_for_finalization_at(released_by.number, &attributes);
+        Some((attributes, span))
+    }
+
+    /// Returns the challenged L1 block of the last DA challenge that expired since this was last
+    /// called, if any. The held blocks derived from the unavailable data were dropped, and the
+    /// engine must reset derivation onto its safe head.
+    pub const fn take_expired_da_challenge(&mut self) -> Option<u64> {
+        match &mut self.da_challenges {
+            Some(gate) => gate.take_expired(),
+            None => None,
+        }
     }
 
     /// Clears the finalization queue.
     pub fn clear(&mut self) {
         self.awaiting_finalization.clear();
+        self.persist();
+    }
+
+    /// Drops the L2 blocks above the given safe head from the finalization queue, after the
+    /// engine was reset to it. The L2 blocks up to the safe head are kept, since they are not
+    /// derived again. The blocks held by the [`DaChallengeGate`] are dropped, since they are.
+    pub fn reset(&mut self, safe_head: L2BlockNumber) {
+        if let Some(gate) = &mut self.da_challenges {
+            gate.clear();
+        }
+        self.awaiting_finalization.retain(|_, &mut number| number <= safe_head);
+        self.persist();
     }
 
     /// Receives a new finalized L1 block from the channel.
+    ///
+    /// With a [`DaChallengeGate`], it also completes on an update of the gate, after which
+    /// [`L2Finalizer::release_next`] may release held blocks.
     pub async fn new_finalized_block(&mut self) -> Result<(), watch::error::RecvError> {
-        self.finalized_l1_block_rx.changed().await
+        match &mut self.da_challenges {
+            Some(gate) => tokio::select! {
+                changed = self.finalized_l1_block_rx.changed() => changed,
+                _ = gate.next_update() => Ok(()),
+            },
+            None => self.finalized_l1_block_rx.changed().await,
+        }
     }
 
     /// Attempts to finalize any L2 blocks that the finalizer knows about and are contained within
     /// the new finalized L1 chain.
+    ///
+    /// Only blocks that are already safe are finalized, the others are kept in the queue until
+    /// this is called again once the safe head advanced.
     pub async fn try_finalize_next(&mut self, engine: &mut Engine) {
         // If there is no finalized L1 block available in the watch channel, do nothing.
         let Some(new_finalized_l1) = *self.finalized_l1_block_rx.borrow() else {
@@ -66,16 +209,52 @@ impl L2Finalizer {
 
         // Find the highest safe L2 block that is contained within the finalized chain,
         // that the finalizer is aware of.
-        let highest_safe = self.awaiting_finalization.range(..=new_finalized_l1.number).next_back();
+        let safe_head = engine.state().safe_head().block_info.number;
+        let highest_safe = self
+            .awaiting_finalization
+            .range(..=new_finalized_l1.number)
+            .rev()
+            .map(|(_, &number)| number)
+            .find(|&number| number <= safe_head);
 
         // If the highest safe block is found, enqueue a finalization task and drain the
-        // queue of all L1 blocks not contained in the finalized L1 chain.
-        if let Some((_, highest_safe_number)) = highest_safe {
+        // queue of all finalized L2 blocks.
+        if let Some(highest_safe_number) = highest_safe {
             let task =
-                EngineTask::Finalize(FinalizeTask::new(self.client.clone(), *highest_safe_number));
+                EngineTask::Finalize(FinalizeTask::new(self.client.clone(), highest_safe_number));
             engine.enqueue(task);
 
-            self.awaiting_finalization.retain(|&number, _| number > new_finalized_l1.number);
+            self.awaiting_finalization.retain(|&l1_number, &mut l2_number| {
+                l1_number > new_finalized_l1.number || l2_number > highest_safe_number
+            });
+            self.persist();
+        }
+    }
+
+    /// Loads the finalization queue from the given path. Returns an empty queue if there is no
+    /// file at the path.
+    fn load(path: &Path) -> Result<BTreeMap<L1BlockNumber, L2BlockNumber>, io::Error> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the finalization queue to disk, if persistence is enabled, replacing the previous
+    /// file atomically. Failures are logged, and the queue is kept in memory.
+    fn persist(&self) {
+        let Some(path) = &self.path else { return };
+        let result = (|| {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, serde_json::to_vec(&self.awaiting_finalization)?)?;
+            fs::rename(tmp, path)
+        })();
+        if let Err(err) = result {
+            warn!(target: "engine", ?err, path = %path.display(), "Failed to persist blocks awaiting finalization");
         }
     }
 }
@@ -0,0 +1,58 @@
+//! The sources of the trusted safe head hints of follower nodes.
+
+use alloy_eips::BlockNumberOrTag;
+use alloy_provider::{Provider, RootProvider};
+use alloy_transport::{RpcError, TransportErrorKind};
+use kona_engine::EngineClient;
+use kona_rpc::SafeHeadResponse;
+use kona_sources::SafeHeadHint;
+use url::Url;
+
+/// Where the trusted [`SafeHeadHint`] of a node is taken from.
+///
+/// The hint fast-forwards the sync start of nodes that do not need to validate the whole chain,
+/// e.g. infrastructure nodes bootstrapping from a snapshot of the execution layer. It is resolved
+/// once, on the first engine reset, and verified on every reset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SafeHeadHintSource {
+    /// A hint given in the configuration.
+    Static(SafeHeadHint),
+    /// The safe head reported by a trusted rollup node through `optimism_safeHeadAtL1Block`.
+    Rpc {
+        /// The URL of the rollup RPC of the trusted node.
+        url: Url,
+        /// The L1 block to get the safe head at. The finalized L1 block if not set.
+        l1_block: Option<u64>,
+    },
+}
+
+impl SafeHeadHintSource {
+    /// Resolves the [`SafeHeadHint`], querying the trusted rollup node if needed.
+    pub async fn resolve(
+        &self,
+        client: &EngineClient,
+    ) -> Result<SafeHeadHint, RpcError<TransportErrorKind>> {
+        let (url, l1_block) = match self {
+            Self::Static(hint) => return Ok(*hint),
+            Self::Rpc { url, l1_block } => (url, *l1_block),
+        };
+        let l1_block = match l1_block {
+            Some(number) => number,
+            None => {
+                client
+                    .l1_provider()
+                    .get_block(BlockNumberOrTag::Finalized.into())
+                    .await?
+                    .ok_or_else(|| TransportErrorKind::custom_str("no finalized L1 block"))?
+                    .header
+                    .number
+            }
+        };
+        let trusted: RootProvider = RootProvider::new_http(url.clone());
+        let response: SafeHeadResponse = trusted
+            .client()
+            .request("optimism_safeHeadAtL1Block", (BlockNumberOrTag::Number(l1_block),))
+            .await?;
+        Ok(SafeHeadHint { l1_block: response.l1_block, safe_head: response.safe_head })
+    }
+}
@@ -1,10 +1,25 @@
 //! The [`EngineActor`] and its components.
 
 mod actor;
-pub use actor::{EngineActor, EngineActorState, EngineContext, EngineLauncher, EngineOutboundData};
+pub use actor::{
+    EngineActor, EngineActorState, EngineContext, EngineHeadSenders, EngineLauncher,
+    EngineOutboundData, RestartConfig,
+};
 
 mod error;
 pub use error::EngineError;
 
+mod hint;
+pub use hint::SafeHeadHintSource;
+
+mod challenge;
+pub use challenge::{
+    CHALLENGE_STATUS_CHANGED, ChallengeStatus, DaChallengeConfigError, DaChallengeGate,
+    DaChallengeUpdate,
+};
+
 mod finalizer;
 pub use finalizer::L2Finalizer;
+
+mod consistency;
+pub(crate) use consistency::ConsistencyChecker;
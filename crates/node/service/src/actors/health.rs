@@ -0,0 +1,59 @@
+//! Health reporting for [NodeActor]s.
+//!
+//! [NodeActor]: crate::NodeActor
+
+use crate::Metrics;
+use kona_rpc::{HealthRegistry, HealthStatus};
+
+/// Publishes the [`HealthStatus`] of a single [NodeActor] into the node's [`HealthRegistry`].
+///
+/// [NodeActor]: crate::NodeActor
+#[derive(Debug, Clone)]
+pub struct HealthReporter {
+    /// The name of the actor.
+    name: &'static str,
+    /// The registry to publish into.
+    registry: HealthRegistry,
+}
+
+impl HealthReporter {
+    /// Creates a new [`HealthReporter`] for the named actor.
+    pub const fn new(name: &'static str, registry: HealthRegistry) -> Self {
+        Self { name, registry }
+    }
+
+    /// Returns the name of the actor.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Reports the actor's [`HealthStatus`].
+    pub fn report(&self, status: HealthStatus) {
+        let ok = status.is_ok();
+        if self.registry.set(self.name, status.clone()) {
+            debug!(target: "health", actor = self.name, ?status, "Actor health changed");
+            kona_macros::set!(
+                gauge,
+                Metrics::ACTOR_HEALTH,
+                "actor",
+                self.name,
+                if ok { 1.0 } else { 0.0 }
+            );
+        }
+    }
+
+    /// Reports the actor as [`HealthStatus::Healthy`].
+    pub fn healthy(&self) {
+        self.report(HealthStatus::Healthy);
+    }
+
+    /// Reports the actor as [`HealthStatus::Degraded`], with the given reason.
+    pub fn degraded(&self, reason: impl Into<String>) {
+        self.report(HealthStatus::Degraded(reason.into()));
+    }
+
+    /// Reports the actor as [`HealthStatus::Unhealthy`], with the given reason.
+    pub fn unhealthy(&self, reason: impl Into<String>) {
+        self.report(HealthStatus::Unhealthy(reason.into()));
+    }
+}
@@ -1,24 +1,28 @@
 //! [`NodeActor`] implementation for an L1 chain watcher that polls for L1 block updates over HTTP
 //! RPC.
 
-use crate::{NodeActor, actors::CancellableContext};
+use crate::{
+    DaChallengeUpdate, EventJournal, JournalEventKind, NodeActor, actors::CancellableContext,
+};
 use alloy_eips::{BlockId, BlockNumberOrTag};
 use alloy_primitives::{Address, B256};
 use alloy_provider::{Provider, RootProvider};
 use alloy_rpc_client::PollerBuilder;
-use alloy_rpc_types_eth::{Block, Log};
+use alloy_rpc_types_eth::{Block, Filter, Log};
 use alloy_transport::TransportError;
 use async_stream::stream;
 use async_trait::async_trait;
 use futures::{Stream, StreamExt};
 use kona_genesis::{RollupConfig, SystemConfigLog, SystemConfigUpdate, UnsafeBlockSignerUpdate};
 use kona_protocol::BlockInfo;
-use kona_rpc::{L1State, L1WatcherQueries};
+use kona_providers_alloy::L1BlockLimit;
+use kona_rpc::{L1State, L1WatcherQueries, ReorgEvent};
 use std::{sync::Arc, time::Duration};
 use thiserror::Error;
 use tokio::{
     select,
     sync::{
+        broadcast,
         mpsc::{self, error::SendError},
         watch,
     },
@@ -26,16 +30,50 @@ use tokio::{
 };
 use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
 
+/// The maximum number of L1 blocks that DA challenge events are fetched for at once.
+const MAX_DA_CHALLENGE_RANGE: u64 = 1_000;
+
 /// An L1 chain watcher that checks for L1 block updates over RPC.
+///
+/// The watcher exposes a view of each L1 head: the latest, safe, and finalized blocks, as well
+/// as the confirmed head, which trails the latest head by the configured confirmation depth,
+/// and the derivation head, which trails it by the confirmation depth of derivation.
+///
+/// On alt-DA chains, it also watches the DA challenge contract for [`DaChallengeUpdate`]s.
 #[derive(Debug)]
 pub struct L1WatcherRpc {
     state: L1WatcherRpcState,
     /// The latest L1 head block.
     latest_head: watch::Sender<Option<BlockInfo>>,
+    /// The latest L1 safe block.
+    latest_safe: watch::Sender<Option<BlockInfo>>,
     /// The latest L1 finalized block.
     latest_finalized: watch::Sender<Option<BlockInfo>>,
+    /// The latest L1 head block, adjusted by the confirmation depth.
+    confirmed_head: watch::Sender<Option<BlockInfo>>,
+    /// The latest L1 head block, adjusted by the confirmation depth of derivation.
+    derivation_head: watch::Sender<Option<BlockInfo>>,
     /// The block signer sender.
     block_signer_sender: mpsc::Sender<Address>,
+    /// The sender for [`DaChallengeUpdate`]s, on alt-DA chains.
+    da_challenges: mpsc::Sender<DaChallengeUpdate>,
+    /// The next L1 block to fetch DA challenge events from, if any was fetched yet.
+    next_challenge_block: Option<u64>,
+}
+
+/// The intervals at which the L1 watcher polls the L1 heads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1PollIntervals {
+    /// The poll interval of the latest L1 head.
+    pub head: Duration,
+    /// The poll interval of the L1 safe and finalized heads.
+    pub finality: Duration,
+}
+
+impl Default for L1PollIntervals {
+    fn default() -> Self {
+        Self { head: Duration::from_secs(13), finality: Duration::from_secs(60) }
+    }
 }
 
 /// The configuration for the L1 watcher actor.
@@ -46,6 +84,15 @@ pub struct L1WatcherRpcState {
     pub rollup: Arc<RollupConfig>,
     /// The L1 provider.
     pub l1_provider: RootProvider,
+    /// The number of blocks the confirmed head trails the latest L1 head by.
+    pub confirmation_depth: u64,
+    /// The number of blocks the derivation head trails the latest L1 head by.
+    pub derivation_depth: u64,
+    /// The [`L1BlockLimit`] of the L1 provider of derivation, advanced to the derivation head,
+    /// if derivation waits for confirmations.
+    pub derivation_limit: Option<L1BlockLimit>,
+    /// The intervals at which the L1 heads are polled.
+    pub poll_intervals: L1PollIntervals,
 }
 
 /// The outbound channels for the L1 watcher actor.
@@ -53,10 +100,20 @@ pub struct L1WatcherRpcState {
 pub struct L1WatcherRpcOutboundChannels {
     /// The latest L1 head block.
     pub latest_head: watch::Receiver<Option<BlockInfo>>,
+    /// The latest L1 safe block.
+    pub latest_safe: watch::Receiver<Option<BlockInfo>>,
     /// The latest L1 finalized block.
     pub latest_finalized: watch::Receiver<Option<BlockInfo>>,
+    /// The latest L1 head block, adjusted by the confirmation depth. This is the highest block
+    /// that the sequencer may select as an L1 origin.
+    pub confirmed_head: watch::Receiver<Option<BlockInfo>>,
+    /// The latest L1 head block, adjusted by the confirmation depth of derivation. This is the
+    /// highest block that derivation may consume.
+    pub derivation_head: watch::Receiver<Option<BlockInfo>>,
     /// The block signer sender.
     pub block_signer_sender: mpsc::Receiver<Address>,
+    /// The receiver for [`DaChallengeUpdate`]s. Nothing is sent unless alt-DA is enabled.
+    pub da_challenges: mpsc::Receiver<DaChallengeUpdate>,
 }
 
 /// The communication context used by the L1 watcher actor.
@@ -64,6 +121,10 @@ pub struct L1WatcherRpcOutboundChannels {
 pub struct L1WatcherRpcContext {
     /// The inbound queries to the L1 watcher.
     pub inbound_queries: tokio::sync::mpsc::Receiver<L1WatcherQueries>,
+    /// The sender for [`ReorgEvent`]s of the L1 chain.
+    pub reorgs: broadcast::Sender<ReorgEvent>,
+    /// The [`EventJournal`] that L1 reorgs are recorded in.
+    pub journal: EventJournal,
     /// The cancellation token, shared between all tasks.
     pub cancellation: CancellationToken,
 }
@@ -79,19 +140,32 @@ impl L1WatcherRpc {
     pub fn new(config: L1WatcherRpcState) -> (L1WatcherRpcOutboundChannels, Self) {
         let (head_updates_tx, head_updates_rx) = watch::channel(None);
         let (block_signer_tx, block_signer_rx) = mpsc::channel(16);
+        let (safe_updates_tx, safe_updates_rx) = watch::channel(None);
         let (finalized_updates_tx, finalized_updates_rx) = watch::channel(None);
+        let (confirmed_updates_tx, confirmed_updates_rx) = watch::channel(None);
+        let (derivation_updates_tx, derivation_updates_rx) = watch::channel(None);
+        let (da_challenges_tx, da_challenges_rx) = mpsc::channel(16);
 
         let actor = Self {
             state: config,
             latest_head: head_updates_tx,
+            latest_safe: safe_updates_tx,
             latest_finalized: finalized_updates_tx,
+            confirmed_head: confirmed_updates_tx,
+            derivation_head: derivation_updates_tx,
             block_signer_sender: block_signer_tx,
+            da_challenges: da_challenges_tx,
+            next_challenge_block: None,
         };
         (
             L1WatcherRpcOutboundChannels {
                 latest_head: head_updates_rx,
+                latest_safe: safe_updates_rx,
                 latest_finalized: finalized_updates_rx,
+                confirmed_head: confirmed_updates_rx,
+                derivation_head: derivation_updates_rx,
                 block_signer_sender: block_signer_rx,
+                da_challenges: da_challenges_rx,
             },
             actor,
         )
@@ -99,15 +173,92 @@ impl L1WatcherRpc {
 
     /// Fetches logs for the given block hash.
     async fn fetch_logs(&self, block_hash: B256) -> Result<Vec<Log>, L1WatcherRpcError<BlockInfo>> {
-        let logs = self
-            .state
-            .l1_provider
-            .get_logs(&alloy_rpc_types_eth::Filter::new().select(block_hash))
-            .await?;
+        let logs = self.state.l1_provider.get_logs(&Filter::new().select(block_hash)).await?;
 
         Ok(logs)
     }
 
+    /// Fetches the DA challenge events emitted since the last fetch, up to the given L1 head.
+    /// Returns no events if alt-DA is not enabled.
+    async fn fetch_da_challenges(
+        &self,
+        head: BlockInfo,
+    ) -> Result<Vec<DaChallengeUpdate>, L1WatcherRpcError<BlockInfo>> {
+        let Some(address) = self
+            .state
+            .rollup
+            .da_challenge_address
+            .filter(|_| self.state.rollup.is_alt_da_enabled())
+        else {
+            return Ok(Vec::new());
+        };
+
+        // The head may have moved back on a reorg, or skipped blocks since the last fetch. The
+        // skipped blocks are fetched in ranges of at most `MAX_DA_CHALLENGE_RANGE` blocks, so that
+        // no challenge is missed.
+        let mut from = self.next_challenge_block.unwrap_or(head.number).min(head.number);
+        let mut updates = Vec::new();
+        loop {
+            let to = head.number.min(from.saturating_add(MAX_DA_CHALLENGE_RANGE - 1));
+            let filter = Filter::new()
+                .address(address)
+                .event_signature(DaChallengeUpdate::topic())
+                .from_block(from)
+                .to_block(to);
+            let logs = self.state.l1_provider.get_logs(&filter).await?;
+            updates.extend(logs.iter().filter_map(DaChallengeUpdate::from_log));
+            if to == head.number {
+                return Ok(updates);
+            }
+            from = to + 1;
+        }
+    }
+
+    /// Retries the given fetch, waiting one head poll interval between attempts, until it
+    /// succeeds. Returns `None` if the watcher is cancelled in the meantime.
+    async fn retry<T, F>(
+        &self,
+        cancellation: &CancellationToken,
+        what: &'static str,
+        mut fetch: impl FnMut() -> F,
+    ) -> Option<T>
+    where
+        F: Future<Output = Result<T, L1WatcherRpcError<BlockInfo>>>,
+    {
+        loop {
+            match fetch().await {
+                Ok(value) => return Some(value),
+                Err(err) => {
+                    warn!(target: "l1_watcher", %err, what, "Failed to fetch L1 data, retrying")
+                }
+            }
+            select! {
+                _ = cancellation.cancelled() => return None,
+                _ = tokio::time::sleep(self.state.poll_intervals.head) => {}
+            }
+        }
+    }
+
+    /// Fetches the block that trails the given L1 head by the given depth.
+    async fn fetch_confirmed_head(
+        &self,
+        head: BlockInfo,
+        depth: u64,
+    ) -> Result<BlockInfo, L1WatcherRpcError<BlockInfo>> {
+        if depth == 0 {
+            return Ok(head);
+        }
+
+        let number = head.number.saturating_sub(depth);
+        let block = self
+            .state
+            .l1_provider
+            .get_block_by_number(number.into())
+            .await?
+            .ok_or(L1WatcherRpcError::L1BlockNotFound(number.into()))?;
+        Ok(block.into_consensus().into())
+    }
+
     /// Spins up a task to process inbound queries.
     fn start_query_processor(
         &self,
@@ -171,6 +322,7 @@ impl L1WatcherRpc {
 
 #[async_trait]
 impl NodeActor for L1WatcherRpc {
+    const NAME: &'static str = "l1_watcher";
     type Error = L1WatcherRpcError<BlockInfo>;
     type InboundData = L1WatcherRpcContext;
     type OutboundData = L1WatcherRpcOutboundChannels;
@@ -182,18 +334,24 @@ impl NodeActor for L1WatcherRpc {
 
     async fn start(
         mut self,
-        L1WatcherRpcContext { inbound_queries, cancellation }: Self::InboundData,
+        L1WatcherRpcContext { inbound_queries, reorgs, journal, cancellation }: Self::InboundData,
     ) -> Result<(), Self::Error> {
         let mut head_stream = BlockStream::new(
             &self.state.l1_provider,
             BlockNumberOrTag::Latest,
-            Duration::from_secs(13),
+            self.state.poll_intervals.head,
+        )
+        .into_stream();
+        let mut safe_stream = BlockStream::new(
+            &self.state.l1_provider,
+            BlockNumberOrTag::Safe,
+            self.state.poll_intervals.finality,
         )
         .into_stream();
         let mut finalized_stream = BlockStream::new(
             &self.state.l1_provider,
             BlockNumberOrTag::Finalized,
-            Duration::from_secs(60),
+            self.state.poll_intervals.finality,
         )
         .into_stream();
 
@@ -221,13 +379,67 @@ impl NodeActor for L1WatcherRpc {
                     }
                     Some(head_block_info) => {
                         // Send the head update event to all consumers.
-                        self.latest_head.send_replace(Some(head_block_info));
+                        let previous = self.latest_head.send_replace(Some(head_block_info));
+                        if let Some(event) = previous.and_then(|old| ReorgEvent::l1(old, head_block_info)) {
+                            warn!(target: "l1_watcher", ?event, "Detected L1 reorg");
+                            journal.record(
+                                JournalEventKind::Reorg,
+                                "Detected L1 reorg",
+                                serde_json::to_value(event).unwrap_or_default(),
+                            );
+                            // Sending only fails if there are no subscribers.
+                            reorgs.send(event).ok();
+                        }
+
+                        let Some(confirmed_head) = self
+                            .retry(&cancellation, "confirmed head", || {
+                                self.fetch_confirmed_head(head_block_info, self.state.confirmation_depth)
+                            })
+                            .await
+                        else {
+                            inbound_query_processor.abort();
+                            return Ok(());
+                        };
+                        self.confirmed_head.send_if_modified(|head| {
+                            let modified = *head != Some(confirmed_head);
+                            *head = Some(confirmed_head);
+                            modified
+                        });
+
+                        // Derivation only consumes the blocks buried by its confirmation depth.
+                        // The limit is advanced before derivation is woken up, so that it can
+                        // fetch the new head.
+                        let depth = self.state.derivation_depth;
+                        let derivation_head = if depth == self.state.confirmation_depth {
+                            Some(confirmed_head)
+                        } else {
+                            self.retry(&cancellation, "derivation head", || self.fetch_confirmed_head(head_block_info, depth))
+                                .await
+                        };
+                        let Some(derivation_head) = derivation_head else {
+                            inbound_query_processor.abort();
+                            return Ok(());
+                        };
+                        if let Some(limit) = &self.state.derivation_limit {
+                            limit.set(derivation_head.number);
+                        }
+                        self.derivation_head.send_if_modified(|head| {
+                            let modified = *head != Some(derivation_head);
+                            *head = Some(derivation_head);
+                            modified
+                        });
 
                         // For each log, attempt to construct a `SystemConfigLog`.
                         // Build the `SystemConfigUpdate` from the log.
                         // If the update is an Unsafe block signer update, send the address
                         // to the block signer sender.
-                        let logs = self.fetch_logs(head_block_info.hash).await?;
+                        let Some(logs) = self
+                            .retry(&cancellation, "logs", || self.fetch_logs(head_block_info.hash))
+                            .await
+                        else {
+                            inbound_query_processor.abort();
+                            return Ok(());
+                        };
                         let ecotone_active = self.state.rollup.is_ecotone_active(head_block_info.timestamp);
                         for log in logs {
                             if log.address() != self.state.rollup.l1_system_config_address {
@@ -248,8 +460,33 @@ impl NodeActor for L1WatcherRpc {
                                 }
                             }
                         }
+
+                        let Some(updates) = self
+                            .retry(&cancellation, "DA challenges", || self.fetch_da_challenges(head_block_info))
+                            .await
+                        else {
+                            inbound_query_processor.abort();
+                            return Ok(());
+                        };
+                        self.next_challenge_block = Some(head_block_info.number + 1);
+                        for update in updates {
+                            if let Err(e) = self.da_challenges.send(update).await {
+                                error!(
+                                    target: "l1_watcher",
+                                    "Error sending DA challenge update: {e}"
+                                );
+                            }
+                        }
                     },
                 },
+                new_safe = safe_stream.next() => match new_safe {
+                    None => {
+                        return Err(L1WatcherRpcError::StreamEnded);
+                    }
+                    Some(safe_block_info) => {
+                        self.latest_safe.send_replace(Some(safe_block_info));
+                    }
+                },
                 new_finalized = finalized_stream.next() => match new_finalized {
                     None => {
                         return Err(L1WatcherRpcError::StreamEnded);
@@ -291,23 +528,29 @@ impl<'a> BlockStream<'a> {
 
     /// Transforms the watcher into a [`Stream`].
     fn into_stream(self) -> impl Stream<Item = BlockInfo> + Unpin {
-        let mut poll_stream = PollerBuilder::<_, Block>::new(
-            self.l1_provider.weak_client(),
-            "eth_getBlockByNumber",
-            (self.tag, false),
-        )
-        .with_poll_interval(self.poll_interval)
-        .into_stream();
-
         Box::pin(stream! {
             let mut last_block = None;
-            while let Some(next) = poll_stream.next().await {
-                let info: BlockInfo = next.into_consensus().into();
-
-                if last_block.map(|b| b != info).unwrap_or(true) {
-                    last_block = Some(info);
-                    yield info;
+            loop {
+                let mut poll_stream = PollerBuilder::<_, Block>::new(
+                    self.l1_provider.weak_client(),
+                    "eth_getBlockByNumber",
+                    (self.tag, false),
+                )
+                .with_poll_interval(self.poll_interval)
+                .into_stream();
+
+                while let Some(next) = poll_stream.next().await {
+                    let info: BlockInfo = next.into_consensus().into();
+
+                    if last_block.map(|b| b != info).unwrap_or(true) {
+                        last_block = Some(info);
+                        yield info;
+                    }
                 }
+
+                // The poller stops on the first RPC error that it does not retry itself.
+                warn!(target: "l1_watcher", tag = %self.tag, "L1 block poller stopped, restarting");
+                tokio::time::sleep(self.poll_interval).await;
             }
         })
     }
@@ -329,3 +572,80 @@ pub enum L1WatcherRpcError<T> {
     #[error("Stream ended unexpectedly")]
     StreamEnded,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChallengeStatus;
+    use alloy_primitives::{Bytes, LogData, U256};
+    use alloy_provider::mock::{Asserter, MockTransport};
+    use alloy_rpc_client::RpcClient;
+    use kona_genesis::AltDAConfig;
+
+    const CHALLENGE_CONTRACT: Address = Address::repeat_byte(0xda);
+
+    /// Returns a [`DaChallengeUpdate`] event activating the challenge of the given L1 block.
+    fn challenge_log(challenged_block: u64) -> Log {
+        let topics = vec![DaChallengeUpdate::topic(), U256::from(challenged_block).into()];
+        let mut data = vec![0u8; 64];
+        data[63] = 1;
+        Log {
+            inner: alloy_primitives::Log {
+                address: CHALLENGE_CONTRACT,
+                data: LogData::new_unchecked(topics, Bytes::from(data)),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn watcher(asserter: &Asserter) -> L1WatcherRpc {
+        let rollup = RollupConfig {
+            da_challenge_address: Some(CHALLENGE_CONTRACT),
+            alt_da_config: Some(AltDAConfig {
+                da_challenge_address: Some(CHALLENGE_CONTRACT),
+                da_challenge_window: Some(10),
+                da_resolve_window: Some(5),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (_, watcher) = L1WatcherRpc::new(L1WatcherRpcState {
+            rollup: Arc::new(rollup),
+            l1_provider: RootProvider::new(RpcClient::new(
+                MockTransport::new(asserter.clone()),
+                false,
+            )),
+            confirmation_depth: 0,
+            derivation_depth: 0,
+            derivation_limit: None,
+            poll_intervals: L1PollIntervals::default(),
+        });
+        watcher
+    }
+
+    #[tokio::test]
+    async fn test_fetch_da_challenges_pages_large_jumps() {
+        let asserter = Asserter::new();
+        let mut watcher = watcher(&asserter);
+        watcher.next_challenge_block = Some(1);
+
+        // The 2500 blocks since the last fetch are fetched in three ranges. A fourth request
+        // would fail, as no response is queued for it.
+        for challenged_block in [500, 1500, 2500] {
+            asserter.push_success(&vec![challenge_log(challenged_block)]);
+        }
+        let head = BlockInfo { number: 2500, ..Default::default() };
+        let updates = watcher.fetch_da_challenges(head).await.unwrap();
+        assert_eq!(
+            updates.iter().map(|update| update.challenged_block).collect::<Vec<_>>(),
+            [500, 1500, 2500]
+        );
+        assert!(updates.iter().all(|update| update.status == ChallengeStatus::Active));
+
+        // Without a jump, a single range is fetched.
+        watcher.next_challenge_block = Some(2501);
+        asserter.push_success(&Vec::<Log>::new());
+        let head = BlockInfo { number: 2510, ..Default::default() };
+        assert!(watcher.fetch_da_challenges(head).await.unwrap().is_empty());
+    }
+}
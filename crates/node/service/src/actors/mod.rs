@@ -5,16 +5,26 @@
 mod traits;
 pub use traits::{CancellableContext, NodeActor};
 
+mod health;
+pub use health::HealthReporter;
+
+mod stats;
+pub use stats::{ActorMeter, ActorStatsSampler, DEFAULT_ACTOR_STATS_INTERVAL};
+
 mod runtime;
 pub use runtime::{RuntimeActor, RuntimeContext, RuntimeOutboundData, RuntimeState};
 
 mod engine;
 pub use engine::{
-    EngineActor, EngineActorState, EngineContext, EngineError, EngineLauncher, EngineOutboundData,
-    L2Finalizer,
+    CHALLENGE_STATUS_CHANGED, ChallengeStatus, DaChallengeConfigError, DaChallengeGate,
+    DaChallengeUpdate, EngineActor, EngineActorState, EngineContext, EngineError,
+    EngineHeadSenders, EngineLauncher, EngineOutboundData, L2Finalizer, RestartConfig,
+    SafeHeadHintSource,
 };
 
+#[cfg(feature = "interop")]
 mod supervisor;
+#[cfg(feature = "interop")]
 pub use supervisor::{
     SupervisorActor, SupervisorActorContext, SupervisorActorError, SupervisorExt,
     SupervisorOutboundData, SupervisorRpcServerExt,
@@ -25,20 +35,42 @@ pub use rpc::{RpcActor, RpcActorError, RpcContext};
 
 mod derivation;
 pub use derivation::{
-    DerivationActor, DerivationContext, DerivationError, DerivationOutboundChannels,
-    DerivationState, InboundDerivationMessage,
+    AttributesFanout, DerivationActor, DerivationContext, DerivationError,
+    DerivationOutboundChannels, DerivationProgress, DerivationState, DerivedAttributes,
+    InboundDerivationMessage,
+};
+
+mod reset;
+pub use reset::{DEFAULT_RESET_TIMEOUT, ResetCoordinator, ResetPhase};
+
+mod backfill;
+pub use backfill::{BackfillActor, BackfillConfig, BackfillContext, BackfillError, BackfillState};
+
+mod shadow;
+pub use shadow::{
+    ShadowActor, ShadowConfig, ShadowContext, ShadowDivergence, ShadowError, ShadowOutboundData,
+    ShadowState,
+};
+
+mod watchdog;
+pub use watchdog::{
+    ChainHaltCause, ChainHaltConfig, ChainHaltContext, ChainHaltState, ChainHaltWatchdog,
 };
 
 mod l1_watcher_rpc;
 pub use l1_watcher_rpc::{
-    L1WatcherRpc, L1WatcherRpcContext, L1WatcherRpcError, L1WatcherRpcOutboundChannels,
-    L1WatcherRpcState,
+    L1PollIntervals, L1WatcherRpc, L1WatcherRpcContext, L1WatcherRpcError,
+    L1WatcherRpcOutboundChannels, L1WatcherRpcState,
 };
 
+#[cfg(feature = "p2p")]
 mod network;
-pub use network::{NetworkActor, NetworkActorError, NetworkContext, NetworkOutboundData};
+#[cfg(feature = "p2p")]
+pub use network::{NetworkActor, NetworkActorError, NetworkActorState, NetworkContext};
 
+#[cfg(feature = "sequencer")]
 mod sequencer;
+#[cfg(feature = "sequencer")]
 pub use sequencer::{
     L1OriginSelector, L1OriginSelectorError, SequencerActor, SequencerActorError,
     SequencerActorState, SequencerContext, SequencerOutboundData,
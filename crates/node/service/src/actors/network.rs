@@ -1,15 +1,22 @@
 //! Network Actor
 
-use crate::{NodeActor, actors::CancellableContext};
+use crate::{NodeActor, actors::CancellableContext, bus::EventSender};
 use alloy_primitives::Address;
 use async_trait::async_trait;
 use derive_more::Debug;
-use kona_p2p::Network;
-use libp2p::TransportError;
+use kona_p2p::{Network, P2pRpcRequest};
+use kona_protocol::CorrelationId;
+use kona_rpc::ReloadableConfig;
+use libp2p::{Multiaddr, TransportError};
 use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
+use std::collections::HashSet;
 use thiserror::Error;
-use tokio::{select, sync::mpsc};
+use tokio::{
+    select,
+    sync::{mpsc, watch},
+};
 use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+use tracing::Instrument;
 
 /// The network actor handles two core networking components of the rollup node:
 /// - *discovery*: Peer discovery over UDP using discv5.
@@ -35,31 +42,57 @@ use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
 /// //    .build()
 /// //    .unwrap();
 ///
-/// // Construct the `NetworkActor` with the [`Network`].
-/// // let actor = NetworkActor::new(driver);
+/// // Construct the `NetworkActor` with the [`Network`], forwarding unsafe blocks on a bus channel.
+/// // let (blocks, _) = bus::channel("unsafe_blocks", ChannelConfig::new(1024));
+/// // let actor = NetworkActor::new(NetworkActorState { driver, blocks });
 /// ```
 #[derive(Debug)]
 pub struct NetworkActor {
     /// Network driver
     driver: Network,
     /// The channel for sending unsafe blocks from the network actor.
-    blocks: mpsc::Sender<OpExecutionPayloadEnvelope>,
+    blocks: EventSender<OpExecutionPayloadEnvelope>,
 }
 
-/// The outbound data for the network actor.
+/// The state used to build the network actor.
 #[derive(Debug)]
-pub struct NetworkOutboundData {
-    /// The unsafe block received from the network.
-    pub unsafe_block: mpsc::Receiver<OpExecutionPayloadEnvelope>,
+pub struct NetworkActorState {
+    /// The [`Network`] driver.
+    pub driver: Network,
+    /// The channel that unsafe blocks received from the network are forwarded on.
+    pub blocks: EventSender<OpExecutionPayloadEnvelope>,
 }
 
 impl NetworkActor {
-    /// Constructs a new [`NetworkActor`] given the [`Network`]
-    pub fn new(driver: Network) -> (NetworkOutboundData, Self) {
-        let (unsafe_block_tx, unsafe_block_rx) = mpsc::channel(1024);
-        let actor = Self { driver, blocks: unsafe_block_tx };
-        let outbound_data = NetworkOutboundData { unsafe_block: unsafe_block_rx };
-        (outbound_data, actor)
+    /// Constructs a new [`NetworkActor`] given the [`NetworkActorState`].
+    pub fn new(NetworkActorState { driver, blocks }: NetworkActorState) -> ((), Self) {
+        ((), Self { driver, blocks })
+    }
+
+    /// Dials the static peers in the [`ReloadableConfig`] that have not been dialed yet.
+    async fn connect_static_peers(
+        config: &ReloadableConfig,
+        dialed: &mut HashSet<String>,
+        p2p_requests: &mpsc::Sender<P2pRpcRequest>,
+    ) {
+        for peer in &config.static_peers {
+            if dialed.contains(peer) {
+                continue;
+            }
+            let address = match peer.parse::<Multiaddr>() {
+                Ok(address) => address,
+                Err(err) => {
+                    warn!(target: "network", %peer, ?err, "Invalid static peer multiaddr");
+                    continue;
+                }
+            };
+            if p2p_requests.send(P2pRpcRequest::ConnectPeer { address }).await.is_err() {
+                warn!(target: "network", %peer, "Failed to send connect request to network driver");
+                continue;
+            }
+            info!(target: "network", %peer, "Dialing static peer");
+            dialed.insert(peer.clone());
+        }
     }
 }
 
@@ -68,6 +101,10 @@ impl NetworkActor {
 pub struct NetworkContext {
     /// A channel to receive the unsafe block signer address.
     pub signer: mpsc::Receiver<Address>,
+    /// A channel to receive [`ReloadableConfig`] updates, used to dial new static peers.
+    pub reload: watch::Receiver<ReloadableConfig>,
+    /// A channel to send [`P2pRpcRequest`]s to the network driver.
+    pub p2p_requests: mpsc::Sender<P2pRpcRequest>,
     /// Cancels the network actor.
     pub cancellation: CancellationToken,
 }
@@ -80,10 +117,11 @@ impl CancellableContext for NetworkContext {
 
 #[async_trait]
 impl NodeActor for NetworkActor {
+    const NAME: &'static str = "network";
     type Error = NetworkActorError;
     type InboundData = NetworkContext;
-    type OutboundData = NetworkOutboundData;
-    type State = Network;
+    type OutboundData = ();
+    type State = NetworkActorState;
 
     fn build(state: Self::State) -> (Self::OutboundData, Self) {
         Self::new(state)
@@ -91,7 +129,7 @@ impl NodeActor for NetworkActor {
 
     async fn start(
         mut self,
-        NetworkContext { mut signer, cancellation }: Self::InboundData,
+        NetworkContext { mut signer, mut reload, p2p_requests, cancellation }: Self::InboundData,
     ) -> Result<(), Self::Error> {
         // Take the unsafe block receiver
         let mut unsafe_block_receiver = self.driver.unsafe_block_recv();
@@ -102,6 +140,11 @@ impl NodeActor for NetworkActor {
         // Start the network driver.
         self.driver.start().await?;
 
+        // Dial the static peers configured at startup.
+        let mut dialed = HashSet::new();
+        let config = reload.borrow_and_update().clone();
+        Self::connect_static_peers(&config, &mut dialed, &p2p_requests).await;
+
         loop {
             select! {
                 _ = cancellation.cancelled() => {
@@ -111,10 +154,21 @@ impl NodeActor for NetworkActor {
                     );
                     return Ok(());
                 }
+                Ok(()) = reload.changed() => {
+                    let config = reload.borrow_and_update().clone();
+                    Self::connect_static_peers(&config, &mut dialed, &p2p_requests).await;
+                }
                 block = unsafe_block_receiver.recv() => {
                     match block {
                         Ok(block) => {
-                            match self.blocks.send(block).await {
+                            let span = info_span!(
+                                target: "network",
+                                "unsafe_block",
+                                number = block.payload.block_number(),
+                                hash = %block.payload.block_hash(),
+                                correlation_id = %CorrelationId::new(block.payload.parent_hash(), block.payload.timestamp()),
+                            );
+                            match self.blocks.send(block).instrument(span).await {
                                 Ok(_) => debug!(target: "network", "Forwarded unsafe block"),
                                 Err(_) => warn!(target: "network", "Failed to forward unsafe block"),
                             }
@@ -0,0 +1,362 @@
+//! The [`ResetCoordinator`], which owns the reset handshake between the engine and derivation.
+//!
+//! A reset goes through the following [`ResetPhase`]s:
+//!
+//! 1. [`ResetPhase::Requested`]: derivation hit a reset error, and stops stepping the pipeline.
+//!    Resets initiated by the engine skip this phase.
+//! 2. [`ResetPhase::Targeted`]: the engine reset itself, and picked the [`ResetSignal`] that the
+//!    pipeline must be reset to.
+//! 3. [`ResetPhase::Idle`]: derivation reset the pipeline to the target and acknowledged it.
+//!
+//! The phase is held in a [`watch`] channel rather than sent as messages, so a reset cannot be
+//! lost: each actor reacts to the latest phase, however many transitions it missed. Each reset
+//! has an id, that derivation tags the attributes it derives with, so that the engine drops the
+//! attributes derived before the last reset, including those still queued in the attributes
+//! channel when it was targeted. A phase that is not left within the reset timeout is retried by
+//! the actor waiting on it.
+
+use crate::{Clock, Metrics, SystemClock};
+use kona_derive::ResetSignal;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{sync::watch, time::Instant};
+
+/// The default time after which a reset phase that was not left is retried.
+pub const DEFAULT_RESET_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A phase of the reset handshake between the engine and derivation.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ResetPhase {
+    /// No reset is in progress.
+    #[default]
+    Idle,
+    /// Derivation requested a reset, and waits for the engine to pick its target.
+    Requested {
+        /// The id of the reset.
+        id: u64,
+        /// When the reset was requested.
+        since: Instant,
+    },
+    /// The engine was reset, and waits for derivation to reset the pipeline to the target.
+    Targeted {
+        /// The id of the reset.
+        id: u64,
+        /// The [`ResetSignal`] that the pipeline must be reset to.
+        target: ResetSignal,
+        /// When the target was picked.
+        since: Instant,
+    },
+}
+
+impl ResetPhase {
+    /// Returns the name of the phase, used as a metric label.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Requested { .. } => "requested",
+            Self::Targeted { .. } => "targeted",
+        }
+    }
+
+    /// Returns the id of the reset in progress, if any.
+    pub const fn id(&self) -> Option<u64> {
+        match self {
+            Self::Idle => None,
+            Self::Requested { id, .. } | Self::Targeted { id, .. } => Some(*id),
+        }
+    }
+
+    /// Returns when the phase was entered, if a reset is in progress.
+    pub const fn since(&self) -> Option<Instant> {
+        match self {
+            Self::Idle => None,
+            Self::Requested { since, .. } | Self::Targeted { since, .. } => Some(*since),
+        }
+    }
+}
+
+/// The shared state of a [`ResetCoordinator`].
+#[derive(Debug)]
+struct Inner {
+    /// The current [`ResetPhase`].
+    phase: watch::Sender<ResetPhase>,
+    /// The id of the last reset.
+    last_id: AtomicU64,
+    /// The id of the last reset that the engine picked a target for, that up to date attributes
+    /// are tagged with.
+    last_target: AtomicU64,
+}
+
+/// Owns the reset handshake between the engine and derivation. See the [module docs](self).
+///
+/// The coordinator is cheaply cloneable, and its clones share the same state.
+#[derive(Debug, Clone)]
+pub struct ResetCoordinator {
+    /// The shared state.
+    inner: Arc<Inner>,
+    /// The time after which a phase that was not left is retried.
+    timeout: Duration,
+    /// The [`Clock`] that the phases are timed with.
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for ResetCoordinator {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESET_TIMEOUT)
+    }
+}
+
+impl ResetCoordinator {
+    /// Creates a new [`ResetCoordinator`], that retries the phases not left within the given
+    /// timeout.
+    pub fn new(timeout: Duration) -> Self {
+        let inner = Inner {
+            phase: watch::Sender::new(ResetPhase::Idle),
+            last_id: AtomicU64::new(0),
+            last_target: AtomicU64::new(0),
+        };
+        Self { inner: Arc::new(inner), timeout, clock: Arc::new(SystemClock) }
+    }
+
+    /// Sets the [`Clock`] that the phases are timed with. Defaults to the [`SystemClock`].
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock, ..self }
+    }
+
+    /// Returns the time after which a phase that was not left is retried.
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns the [`Clock`] that the phases are timed with.
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Returns the time at which the given phase times out, if a reset is in progress.
+    pub fn deadline(&self, phase: &ResetPhase) -> Option<Instant> {
+        phase.since().map(|since| since + self.timeout)
+    }
+
+    /// Waits until the given phase times out. Never completes if no reset is in progress.
+    pub async fn timeout_of(&self, phase: &ResetPhase) {
+        match self.deadline(phase) {
+            Some(deadline) => self.clock.sleep_until(deadline).await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Returns a receiver of the [`ResetPhase`] updates.
+    pub fn subscribe(&self) -> watch::Receiver<ResetPhase> {
+        self.inner.phase.subscribe()
+    }
+
+    /// Returns the current [`ResetPhase`].
+    pub fn phase(&self) -> ResetPhase {
+        *self.inner.phase.borrow()
+    }
+
+    /// Returns `true` if no reset is in progress.
+    pub fn is_idle(&self) -> bool {
+        self.phase() == ResetPhase::Idle
+    }
+
+    /// Returns the id of the last reset that the engine picked a target for. Attributes tagged
+    /// with another id were derived before it, and must be dropped.
+    pub fn last_target(&self) -> u64 {
+        self.inner.last_target.load(Ordering::Acquire)
+    }
+
+    /// Requests a reset, on behalf of derivation, and returns its id.
+    ///
+    /// Requesting a reset again while the engine has not picked its target restarts its timeout,
+    /// and wakes the engine again. Requesting a reset while a target is already picked is a
+    /// no-op, as the pipeline is about to be reset anyway.
+    pub fn request(&self) -> u64 {
+        let mut requested = 0;
+        self.inner.phase.send_if_modified(|phase| {
+            let id = match *phase {
+                ResetPhase::Idle => self.next_id(),
+                ResetPhase::Requested { id, .. } => id,
+                ResetPhase::Targeted { id, .. } => {
+                    requested = id;
+                    return false;
+                }
+            };
+            requested = id;
+            *phase = ResetPhase::Requested { id, since: self.clock.now() };
+            true
+        });
+        requested
+    }
+
+    /// Picks the target of the requested reset, on behalf of the engine, and returns its id. If
+    /// no reset was requested, the engine initiated it, and a new reset is started.
+    pub fn target(&self, target: ResetSignal) -> u64 {
+        let mut id = 0;
+        self.inner.phase.send_modify(|phase| {
+            id = match *phase {
+                ResetPhase::Requested { id, .. } => id,
+                ResetPhase::Idle | ResetPhase::Targeted { .. } => self.next_id(),
+            };
+            self.inner.last_target.store(id, Ordering::Release);
+            *phase = ResetPhase::Targeted { id, target, since: self.clock.now() };
+        });
+        id
+    }
+
+    /// Acknowledges the reset of the given id, on behalf of derivation, once the pipeline was
+    /// reset to its target. Returns `false` if the reset was superseded by another one, whose
+    /// target is then still to be applied.
+    pub fn acknowledge(&self, id: u64) -> bool {
+        self.inner.phase.send_if_modified(|phase| match *phase {
+            ResetPhase::Targeted { id: targeted, .. } if targeted == id => {
+                *phase = ResetPhase::Idle;
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Withdraws the pending reset request, if any, on behalf of derivation, once the pipeline
+    /// was reset by another party than the engine.
+    pub fn withdraw(&self) {
+        self.inner.phase.send_if_modified(|phase| match phase {
+            ResetPhase::Requested { .. } => {
+                *phase = ResetPhase::Idle;
+                true
+            }
+            _ => false,
+        });
+    }
+
+    /// Records that the given phase timed out.
+    pub(crate) fn timed_out(&self, phase: &ResetPhase) {
+        warn!(
+            target: "reset",
+            phase = phase.name(),
+            id = phase.id(),
+            timeout = ?self.timeout,
+            "Reset phase timed out, retrying"
+        );
+        kona_macros::inc!(counter, Metrics::RESET_TIMEOUT_COUNT, "phase" => phase.name());
+    }
+
+    /// Returns the id of a new reset.
+    fn next_id(&self) -> u64 {
+        self.inner.last_id.fetch_add(1, Ordering::AcqRel) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VirtualClock;
+    use futures::FutureExt;
+    use kona_protocol::BlockInfo;
+
+    fn coordinator() -> (ResetCoordinator, VirtualClock) {
+        let clock = VirtualClock::new();
+        let reset =
+            ResetCoordinator::new(Duration::from_secs(10)).with_clock(Arc::new(clock.clone()));
+        (reset, clock)
+    }
+
+    fn signal() -> ResetSignal {
+        ResetSignal {
+            l1_origin: BlockInfo { number: 1, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_requested_reset() {
+        let (reset, clock) = coordinator();
+        assert!(reset.is_idle());
+
+        let id = reset.request();
+        assert_eq!(reset.phase(), ResetPhase::Requested { id, since: clock.now() });
+
+        // The engine picks the target of the requested reset.
+        assert_eq!(reset.target(signal()), id);
+        assert_eq!(reset.last_target(), id);
+        assert_eq!(
+            reset.phase(),
+            ResetPhase::Targeted { id, target: signal(), since: clock.now() }
+        );
+
+        // Requesting a reset while a target is picked is a no-op.
+        assert_eq!(reset.request(), id);
+        assert!(matches!(reset.phase(), ResetPhase::Targeted { .. }));
+
+        assert!(!reset.acknowledge(id + 1));
+        assert!(reset.acknowledge(id));
+        assert!(reset.is_idle());
+    }
+
+    #[test]
+    fn test_engine_initiated_reset() {
+        let (reset, _) = coordinator();
+        let first = reset.target(signal());
+
+        // A reset targeted before the pipeline acknowledged the previous one supersedes it.
+        let second = reset.target(signal());
+        assert_ne!(first, second);
+        assert_eq!(reset.last_target(), second);
+        assert!(!reset.acknowledge(first));
+        assert!(reset.acknowledge(second));
+        assert!(reset.is_idle());
+    }
+
+    #[test]
+    fn test_withdrawn_reset() {
+        let (reset, _) = coordinator();
+        reset.request();
+        reset.withdraw();
+        assert!(reset.is_idle());
+
+        // A targeted reset cannot be withdrawn.
+        reset.target(signal());
+        reset.withdraw();
+        assert!(matches!(reset.phase(), ResetPhase::Targeted { .. }));
+    }
+
+    #[test]
+    fn test_request_again_restarts_timeout() {
+        let (reset, clock) = coordinator();
+        let id = reset.request();
+        let deadline = reset.deadline(&reset.phase()).unwrap();
+        assert_eq!(deadline, clock.now() + Duration::from_secs(10));
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(reset.request(), id);
+        assert_eq!(reset.deadline(&reset.phase()), Some(deadline + Duration::from_secs(10)));
+        assert_eq!(reset.deadline(&ResetPhase::Idle), None);
+    }
+
+    #[tokio::test]
+    async fn test_phase_timeout() {
+        let (reset, clock) = coordinator();
+        reset.target(signal());
+        let phase = reset.phase();
+
+        let timeout = reset.timeout_of(&phase);
+        tokio::pin!(timeout);
+        clock.advance(Duration::from_secs(9));
+        assert!(timeout.as_mut().now_or_never().is_none());
+        clock.advance(Duration::from_secs(1));
+        timeout.await;
+
+        // No reset in progress never times out.
+        let idle = reset.timeout_of(&ResetPhase::Idle);
+        tokio::pin!(idle);
+        clock.advance(Duration::from_secs(3600));
+        assert!(idle.as_mut().now_or_never().is_none());
+    }
+}
@@ -52,6 +52,7 @@ impl CancellableContext for RpcContext {
 
 #[async_trait]
 impl NodeActor for RpcActor {
+    const NAME: &'static str = "rpc";
     type Error = RpcActorError;
     type InboundData = RpcContext;
     type OutboundData = ();
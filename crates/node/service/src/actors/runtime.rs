@@ -2,11 +2,11 @@
 
 use async_trait::async_trait;
 use kona_sources::{RuntimeConfig, RuntimeLoader, RuntimeLoaderError};
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::mpsc;
 use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
 
-use crate::{NodeActor, actors::CancellableContext};
+use crate::{Clock, NodeActor, actors::CancellableContext};
 
 /// The communication context used by the runtime actor.
 #[derive(Debug)]
@@ -38,6 +38,8 @@ pub struct RuntimeState {
     pub loader: RuntimeLoader,
     /// The interval at which to load the runtime.
     pub interval: Duration,
+    /// The [`Clock`] that drives the load interval.
+    pub clock: Arc<dyn Clock>,
 }
 
 /// The outbound data for the runtime actor.
@@ -59,6 +61,7 @@ impl RuntimeActor {
 
 #[async_trait]
 impl NodeActor for RuntimeActor {
+    const NAME: &'static str = "runtime";
     type Error = RuntimeLoaderError;
     type InboundData = RuntimeContext;
     type OutboundData = RuntimeOutboundData;
@@ -72,14 +75,15 @@ impl NodeActor for RuntimeActor {
         mut self,
         RuntimeContext { cancellation }: Self::InboundData,
     ) -> Result<(), Self::Error> {
-        let mut interval = tokio::time::interval(self.state.interval);
+        let mut next_load = self.state.clock.now();
         loop {
             tokio::select! {
                 _ = cancellation.cancelled() => {
                     warn!(target: "runtime", "RuntimeActor received shutdown signal.");
                     return Ok(());
                 }
-                _ = interval.tick() => {
+                _ = self.state.clock.sleep_until(next_load) => {
+                    next_load += self.state.interval;
                     let config = self.state.loader.load_latest().await?;
                     debug!(target: "runtime", ?config, "Loaded latest runtime config");
                     if let Err(e) = self.runtime_config.send(config).await {
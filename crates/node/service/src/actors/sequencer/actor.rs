@@ -1,12 +1,14 @@
 //! The [`SequencerActor`].
 
-use crate::{CancellableContext, NodeActor};
+use crate::{CancellableContext, Clock, EventJournal, JournalEventKind, NodeActor};
 
 use super::{L1OriginSelector, L1OriginSelectorError};
 use async_trait::async_trait;
 use kona_derive::{AttributesBuilder, PipelineErrorKind};
+use kona_engine::PayloadEnvelopeStore;
 use kona_genesis::RollupConfig;
-use kona_protocol::{BlockInfo, L2BlockInfo, OpAttributesWithParent};
+use kona_protocol::{BlockInfo, CorrelationId, L2BlockInfo, OpAttributesWithParent};
+use kona_rpc::{HealthRegistry, ReloadableConfig};
 use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
 use std::{sync::Arc, time::Duration};
 use tokio::{
@@ -42,6 +44,8 @@ pub struct SequencerActorState<AB> {
     pub builder: AB,
     /// The [`L1OriginSelector`].
     pub origin_selector: L1OriginSelector,
+    /// The [`Clock`] that drives block building ticks.
+    pub clock: Arc<dyn Clock>,
 }
 
 /// The outbound channels for the [`SequencerActor`].
@@ -62,6 +66,19 @@ pub struct SequencerContext {
     pub latest_payload_rx: Option<mpsc::Receiver<OpExecutionPayloadEnvelope>>,
     /// Watch channel to observe the unsafe head of the engine.
     pub unsafe_head: watch::Receiver<L2BlockInfo>,
+    /// Watch channel to observe the confirmed L1 head. L1 origins are never selected past it.
+    pub confirmed_l1_head: watch::Receiver<Option<BlockInfo>>,
+    /// Watch channel to observe the [`ReloadableConfig`], used to toggle recovery mode.
+    pub reload: watch::Receiver<ReloadableConfig>,
+    /// The node's [`HealthRegistry`].
+    pub health: HealthRegistry,
+    /// The names of the actors that blocks are built from. Blocks are not built unless all of
+    /// them are [ok][kona_rpc::HealthStatus::is_ok] in the [`HealthRegistry`].
+    pub dependencies: Vec<&'static str>,
+    /// The [`EventJournal`] that the starts and stops of the sequencer are recorded in.
+    pub journal: EventJournal,
+    /// The [`PayloadEnvelopeStore`] that the envelopes of the built blocks are kept in.
+    pub envelopes: PayloadEnvelopeStore,
     /// The cancellation token, shared between all tasks.
     pub cancellation: CancellationToken,
 }
@@ -111,8 +128,33 @@ where
             return Ok(());
         }
 
+        // Do not build blocks while any of the actors they are built from is not operating.
+        let unhealthy = ctx
+            .dependencies
+            .iter()
+            .filter(|name| !ctx.health.get(name).is_some_and(|status| status.is_ok()))
+            .collect::<Vec<_>>();
+        if !unhealthy.is_empty() {
+            warn!(target: "sequencer", ?unhealthy, "Waiting for unhealthy actors before sequencing");
+            return Ok(());
+        }
+
         let unsafe_head = *ctx.unsafe_head.borrow();
-        let l1_origin = self.state.origin_selector.next_l1_origin(unsafe_head).await?;
+        let confirmed_l1_head = *ctx.confirmed_l1_head.borrow();
+        let l1_origin = match self
+            .state
+            .origin_selector
+            .next_l1_origin(unsafe_head, confirmed_l1_head)
+            .await
+        {
+            Ok(l1_origin) => l1_origin,
+            Err(err) if err.is_temporary() => {
+                // The next L1 origin may not be confirmed yet. Retry on the next tick.
+                warn!(target: "sequencer", %err, "Waiting for the next L1 origin before sequencing");
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        };
 
         // TODO(clabby): Check for consistent L1 origin
 
@@ -151,6 +193,12 @@ where
             l1_origin.timestamp + self.state.cfg.max_sequencer_drift(l1_origin.timestamp))
         .then_some(true);
 
+        // In recovery mode, only build empty blocks.
+        if ctx.reload.borrow().sequencer_recover {
+            info!(target: "sequencer", "Sequencing empty block in recovery mode");
+            attributes.no_tx_pool = Some(true);
+        }
+
         // Do not include transactions in the first Ecotone block.
         if self.state.cfg.is_first_ecotone_block(attributes.payload_attributes.timestamp) {
             info!(target: "sequencer", "Sequencing ecotone upgrade block");
@@ -197,6 +245,12 @@ where
         ctx.latest_payload_rx = Some(payload_rx);
 
         // Send the built attributes to the engine to be built.
+        debug!(
+            target: "sequencer",
+            l2_number = attrs_with_parent.block_number(),
+            correlation_id = %attrs_with_parent.correlation_id(),
+            "Sending payload attributes to the engine"
+        );
         if let Err(err) = self.build_request_tx.send((attrs_with_parent, payload_tx)).await {
             error!(target: "sequencer", ?err, "Failed to send built attributes to engine");
             ctx.cancellation.cancel();
@@ -233,6 +287,13 @@ where
         payload: OpExecutionPayloadEnvelope,
     ) -> Result<(), <Self as NodeActor>::Error> {
         // Send the payload to the P2P layer to be signed and gossipped.
+        debug!(
+            target: "sequencer",
+            l2_number = payload.payload.block_number(),
+            correlation_id = %CorrelationId::new(payload.payload.parent_hash(), payload.payload.timestamp()),
+            "Scheduling payload for gossip"
+        );
+        ctx.envelopes.insert(payload.clone());
         if let Err(err) = self.gossip_payload_tx.send(payload).await {
             error!(target: "sequencer", ?err, "Failed to send payload to be signed and gossipped");
             ctx.cancellation.cancel();
@@ -248,6 +309,7 @@ impl<AB> NodeActor for SequencerActor<AB>
 where
     AB: AttributesBuilder + Send + Sync + 'static,
 {
+    const NAME: &'static str = "sequencer";
     type Error = SequencerActorError;
     type InboundData = SequencerContext;
     type State = SequencerActorState<AB>;
@@ -258,8 +320,13 @@ where
     }
 
     async fn start(mut self, mut ctx: Self::InboundData) -> Result<(), Self::Error> {
-        let mut build_ticker =
-            tokio::time::interval(Duration::from_secs(self.state.cfg.block_time));
+        let block_time = Duration::from_secs(self.state.cfg.block_time);
+        let mut next_build = self.state.clock.now();
+        ctx.journal.record(
+            JournalEventKind::SequencerStarted,
+            "Sequencer started",
+            serde_json::json!({ "unsafe_head": ctx.unsafe_head.borrow().block_info.number }),
+        );
 
         loop {
             // Check if we are waiting on a block to be built. If so, we must wait for the response
@@ -274,9 +341,15 @@ where
                         target: "sequencer",
                         "Received shutdown signal. Exiting sequencer task."
                     );
+                    ctx.journal.record(
+                        JournalEventKind::SequencerStopped,
+                        "Sequencer stopped",
+                        serde_json::json!({ "unsafe_head": ctx.unsafe_head.borrow().block_info.number }),
+                    );
                     return Ok(());
                 }
-                _ = build_ticker.tick() => {
+                _ = self.state.clock.sleep_until(next_build) => {
+                    next_build += block_time;
                     self.start_build(&mut ctx).await?;
                 }
             }
@@ -44,13 +44,19 @@ impl L1OriginSelector {
     /// block's timestamp in relation to the current L1 origin's timestamp. If the next L2
     /// block's timestamp is greater than the L2 unsafe head's L1 origin timestamp, the L1
     /// origin is the block following the current L1 origin.
+    ///
+    /// If the confirmed L1 head is known, blocks past it are never selected as the next L1
+    /// origin.
     pub async fn next_l1_origin(
         &mut self,
         unsafe_head: L2BlockInfo,
+        confirmed_l1_head: Option<BlockInfo>,
     ) -> Result<BlockInfo, L1OriginSelectorError> {
         self.select_origins(&unsafe_head).await?;
 
-        let (current, mut next) = (self.current, self.next);
+        // The next L1 origin must not be past the confirmed L1 head.
+        let confirmed = |number: u64| confirmed_l1_head.is_none_or(|head| number <= head.number);
+        let (current, mut next) = (self.current, self.next.filter(|n| confirmed(n.number)));
 
         // Start building on the next L1 origin block if the next L2 block's timestamp is
         // greater than or equal to the next L1 origin's timestamp.
@@ -77,12 +83,12 @@ impl L1OriginSelector {
         let next_block_number = current.number.saturating_add(1);
 
         // If the next L1 origin is not set, fetch the next block after the current L1 origin.
-        if self.next.is_none() {
+        if next.is_none() && confirmed(next_block_number) {
             let next_block = self
                 .l1
                 .get_block_by_number(next_block_number.into())
                 .await?
-                .ok_or(L1OriginSelectorError::BlockNotFound(next_block_number.into()))?
+                .ok_or(L1OriginSelectorError::NextOriginUnavailable(next_block_number))?
                 .into();
 
             next = Some(next_block);
@@ -105,7 +111,7 @@ impl L1OriginSelector {
             return Ok(current);
         }
 
-        next.ok_or(L1OriginSelectorError::BlockNotFound(next_block_number.into()))
+        next.ok_or(L1OriginSelectorError::NextOriginUnavailable(next_block_number))
     }
 
     /// Selects the current and next L1 origin blocks based on the unsafe head.
@@ -145,4 +151,59 @@ pub enum L1OriginSelectorError {
     /// A block could not be found.
     #[error("Block {0} could not be found")]
     BlockNotFound(BlockId),
+    /// The sequencer drift is exceeded, but the next L1 origin is not yet available or confirmed.
+    #[error("Next L1 origin {0} is not yet available")]
+    NextOriginUnavailable(u64),
+}
+
+impl L1OriginSelectorError {
+    /// Returns `true` if the error is temporary, and the selection should be retried later.
+    pub const fn is_temporary(&self) -> bool {
+        matches!(self, Self::NextOriginUnavailable(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    fn selector(current: BlockInfo) -> L1OriginSelector {
+        let cfg = RollupConfig { block_time: 2, max_sequencer_drift: 10, ..Default::default() };
+        // The provider is never queried, as the current L1 origin is already known and the next
+        // one is past the confirmed L1 head.
+        let l1 = RootProvider::new_http("http://127.0.0.1:1".parse().unwrap());
+        let mut selector = L1OriginSelector::new(Arc::new(cfg), l1);
+        selector.current = Some(current);
+        selector
+    }
+
+    fn unsafe_head(origin: &BlockInfo, timestamp: u64) -> L2BlockInfo {
+        L2BlockInfo {
+            block_info: BlockInfo { timestamp, ..Default::default() },
+            l1_origin: origin.id(),
+            seq_num: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_origin_unconfirmed_within_drift() {
+        let current = BlockInfo::new(B256::with_last_byte(1), 10, B256::ZERO, 100);
+        let mut selector = selector(current);
+
+        let origin =
+            selector.next_l1_origin(unsafe_head(&current, 104), Some(current)).await.unwrap();
+        assert_eq!(origin, current);
+    }
+
+    #[tokio::test]
+    async fn test_next_origin_unconfirmed_past_drift_is_temporary() {
+        let current = BlockInfo::new(B256::with_last_byte(1), 10, B256::ZERO, 100);
+        let mut selector = selector(current);
+
+        let err =
+            selector.next_l1_origin(unsafe_head(&current, 110), Some(current)).await.unwrap_err();
+        assert!(matches!(err, L1OriginSelectorError::NextOriginUnavailable(11)));
+        assert!(err.is_temporary());
+    }
 }
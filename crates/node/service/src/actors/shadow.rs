@@ -0,0 +1,632 @@
+//! [NodeActor] implementation for the shadow sub-routine.
+
+use crate::{
+    Clock, DerivedAttributes, EventJournal, HealthReporter, JournalEventKind, Metrics, NodeActor,
+    actors::CancellableContext,
+    bus::{self, ChannelConfig, EventReceiver, EventSender, OverflowPolicy},
+};
+use alloy_eips::BlockNumberOrTag;
+use alloy_provider::{Provider, RootProvider};
+use async_trait::async_trait;
+use kona_engine::{AttributesMatch, EngineClient, EngineClientError};
+use kona_protocol::{L2BlockInfo, OpAttributesWithParent, SyncStatus};
+use kona_rpc::OutputResponse;
+use op_alloy_network::Optimism;
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::sync::watch;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+use url::Url;
+
+/// The maximum number of safe blocks compared per tick, so that a node far behind the reference
+/// catches up gradually.
+const MAX_BLOCKS_PER_TICK: u64 = 64;
+
+/// The maximum number of derived attributes held until the block they derive is compared.
+const MAX_PENDING_ATTRIBUTES: usize = 1024;
+
+/// The [NodeActor] for the shadow sub-routine.
+///
+/// The shadow actor runs alongside derivation and compares the node against a reference op-node.
+/// At a fixed interval, it fetches the reference's safe head, and walks the safe blocks that both
+/// nodes have derived since the last tick, comparing their block references one by one. The first
+/// block that diverges is reported with its full context: both block references, both safe heads,
+/// the payload attributes the node derived for it, and, if the reference execution layer is
+/// configured, how those attributes differ from the reference block. The report is logged,
+/// recorded in the [`EventJournal`], counted in [`Metrics::SHADOW_DIVERGENCE_COUNT`], and marks
+/// the actor as degraded. The actor stops comparing after the first divergence, since every
+/// later block diverges as well.
+///
+/// The actor never modifies the execution layer, and errors are retried on the next tick, so that
+/// the comparison cannot bring down the node.
+#[derive(Debug)]
+pub struct ShadowActor {
+    /// The state for the shadow actor.
+    state: ShadowState,
+    /// The receiver for the payload attributes derived by the node.
+    attributes_rx: EventReceiver<DerivedAttributes>,
+    /// The provider for the reference node's rollup RPC.
+    reference: RootProvider,
+    /// The provider for the reference node's execution layer, if configured.
+    reference_l2: Option<RootProvider<Optimism>>,
+    /// The number of the last safe block compared.
+    cursor: Option<u64>,
+    /// The derived attributes of the blocks that were not compared yet, by block number.
+    attributes: BTreeMap<u64, OpAttributesWithParent>,
+    /// The first divergence found, after which the actor stops comparing.
+    divergence: Option<ShadowDivergence>,
+}
+
+/// The configuration for the [`ShadowActor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShadowConfig {
+    /// The URL of the reference op-node's rollup RPC.
+    pub rollup_rpc: Url,
+    /// The URL of the reference op-node's execution layer RPC. If set, the attributes of a
+    /// diverging block are compared against the reference block.
+    pub l2_rpc: Option<Url>,
+    /// The interval between comparisons.
+    pub interval: Duration,
+}
+
+/// The state for the shadow actor.
+#[derive(Debug)]
+pub struct ShadowState {
+    /// The engine client, used to fetch safe blocks from the node's execution layer.
+    pub client: Arc<EngineClient>,
+    /// The [`ShadowConfig`].
+    pub config: ShadowConfig,
+    /// The [`Clock`] that drives the comparison interval.
+    pub clock: Arc<dyn Clock>,
+    /// The [`EventJournal`] that divergences are recorded in.
+    pub journal: EventJournal,
+}
+
+/// The outbound data for the shadow actor.
+#[derive(Debug)]
+pub struct ShadowOutboundData {
+    /// The sender for the payload attributes derived by the node. Sends never block, the oldest
+    /// attributes being dropped if the actor falls behind.
+    pub attributes_tx: EventSender<DerivedAttributes>,
+}
+
+/// The communication context used by the shadow actor.
+#[derive(Debug)]
+pub struct ShadowContext {
+    /// The receiver for L2 safe head update notifications.
+    pub engine_l2_safe_head: watch::Receiver<L2BlockInfo>,
+    /// Reports the health of the shadow actor.
+    pub health: HealthReporter,
+    /// The cancellation token, shared between all tasks.
+    pub cancellation: CancellationToken,
+}
+
+impl CancellableContext for ShadowContext {
+    fn cancelled(&self) -> WaitForCancellationFuture<'_> {
+        self.cancellation.cancelled()
+    }
+}
+
+/// The first safe block at which the node diverged from the reference op-node.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ShadowDivergence {
+    /// The number of the diverging block.
+    pub number: u64,
+    /// The block held by the node.
+    pub local: L2BlockInfo,
+    /// The block held by the reference node.
+    pub reference: L2BlockInfo,
+    /// The safe head of the node when the divergence was found.
+    pub local_safe_head: L2BlockInfo,
+    /// The safe head of the reference node when the divergence was found.
+    pub reference_safe_head: L2BlockInfo,
+    /// The payload attributes the node derived for the block, if they were still held.
+    pub attributes: Option<OpAttributesWithParent>,
+    /// How the derived attributes differ from the reference block, if the reference execution
+    /// layer is configured. `None` if the attributes match it, or could not be compared.
+    pub attributes_mismatch: Option<String>,
+}
+
+impl ShadowActor {
+    /// Creates a new instance of the [ShadowActor].
+    pub fn new(state: ShadowState) -> (ShadowOutboundData, Self) {
+        let (attributes_tx, attributes_rx) = bus::channel(
+            "shadow_attributes",
+            ChannelConfig::new(MAX_PENDING_ATTRIBUTES).with_policy(OverflowPolicy::DropOldest),
+        );
+        let reference = RootProvider::new_http(state.config.rollup_rpc.clone());
+        let reference_l2 = state.config.l2_rpc.clone().map(RootProvider::new_http);
+        let actor = Self {
+            state,
+            attributes_rx,
+            reference,
+            reference_l2,
+            cursor: None,
+            attributes: BTreeMap::new(),
+            divergence: None,
+        };
+        (ShadowOutboundData { attributes_tx }, actor)
+    }
+
+    /// Holds the given derived attributes until the block they derive is compared.
+    fn hold(&mut self, attributes: OpAttributesWithParent) {
+        let number = attributes.block_number();
+        if self.cursor.is_some_and(|cursor| number <= cursor) {
+            return;
+        }
+        // Attributes re-derived after a reset replace the previous ones.
+        self.attributes.insert(number, attributes);
+        while self.attributes.len() > MAX_PENDING_ATTRIBUTES {
+            self.attributes.pop_first();
+        }
+    }
+
+    /// Fetches the block reference of the given block from the reference node.
+    async fn reference_block(&self, number: u64) -> Result<L2BlockInfo, ShadowError> {
+        let output: OutputResponse = self
+            .reference
+            .client()
+            .request("optimism_outputAtBlock", (BlockNumberOrTag::Number(number),))
+            .await?;
+        Ok(output.block_ref)
+    }
+
+    /// Compares the derived attributes of the given block against the reference execution
+    /// layer's block, if it is configured.
+    async fn attributes_mismatch(
+        &self,
+        number: u64,
+        attributes: &OpAttributesWithParent,
+    ) -> Result<Option<String>, ShadowError> {
+        let Some(reference_l2) = &self.reference_l2 else {
+            return Ok(None);
+        };
+        let block = reference_l2
+            .get_block_by_number(BlockNumberOrTag::Number(number))
+            .full()
+            .await?
+            .ok_or(ShadowError::BlockNotFound(number))?;
+        Ok(match AttributesMatch::check(self.state.client.cfg(), attributes, &block) {
+            AttributesMatch::Match => None,
+            AttributesMatch::Mismatch(mismatch) => Some(format!("{mismatch:?}")),
+        })
+    }
+
+    /// Compares the safe blocks derived by both nodes since the last comparison, and returns the
+    /// first divergence found.
+    async fn compare(
+        &mut self,
+        safe_head: L2BlockInfo,
+    ) -> Result<Option<ShadowDivergence>, ShadowError> {
+        let status: SyncStatus =
+            self.reference.client().request_noparams("optimism_syncStatus").await?;
+        let reference_safe_head = status.safe_l2;
+        kona_macros::set!(
+            gauge,
+            Metrics::SHADOW_SAFE_HEAD_LAG,
+            reference_safe_head.block_info.number as f64 - safe_head.block_info.number as f64
+        );
+
+        // Start from the current safe head, rather than replaying the whole chain. If the safe
+        // head was rewound, compare the blocks derived again from there.
+        let target = safe_head.block_info.number.min(reference_safe_head.block_info.number);
+        let cursor = self.cursor.map_or(target.saturating_sub(1), |cursor| cursor.min(target));
+        self.cursor = Some(cursor);
+
+        for number in (cursor + 1..=target).take(MAX_BLOCKS_PER_TICK as usize) {
+            let local = self
+                .state
+                .client
+                .l2_block_info_by_label(BlockNumberOrTag::Number(number))
+                .await?
+                .ok_or(ShadowError::BlockNotFound(number))?;
+            let reference = self.reference_block(number).await?;
+
+            if local == reference {
+                trace!(target: "shadow", number, hash = %local.block_info.hash, "Safe block matches the reference");
+                self.attributes.remove(&number);
+                self.cursor = Some(number);
+                kona_macros::set!(gauge, Metrics::SHADOW_VERIFIED_BLOCK, number as f64);
+                continue;
+            }
+
+            let attributes = self.attributes.remove(&number);
+            let attributes_mismatch = match &attributes {
+                Some(attributes) => self.attributes_mismatch(number, attributes).await?,
+                None => None,
+            };
+            return Ok(Some(ShadowDivergence {
+                number,
+                local,
+                reference,
+                local_safe_head: safe_head,
+                reference_safe_head,
+                attributes,
+                attributes_mismatch,
+            }));
+        }
+
+        // Drop the attributes of blocks that were compared, or replaced by a reorg of the safe
+        // chain.
+        let cursor = self.cursor.unwrap_or_default();
+        self.attributes.retain(|number, _| *number > cursor);
+        Ok(None)
+    }
+
+    /// Reports the given divergence.
+    fn report(&self, divergence: &ShadowDivergence, health: &HealthReporter) {
+        error!(
+            target: "shadow",
+            number = divergence.number,
+            local = %divergence.local,
+            reference = %divergence.reference,
+            local_safe_head = %divergence.local_safe_head,
+            reference_safe_head = %divergence.reference_safe_head,
+            attributes = ?divergence.attributes,
+            attributes_mismatch = ?divergence.attributes_mismatch,
+            "Safe block diverges from the reference node"
+        );
+        kona_macros::inc!(counter, Metrics::SHADOW_DIVERGENCE_COUNT);
+        self.state.journal.record(
+            JournalEventKind::ShadowDivergence,
+            format!("Safe block {} diverges from the reference node", divergence.number),
+            serde_json::to_value(divergence).unwrap_or_default(),
+        );
+        health.degraded(format!(
+            "safe block {} diverges from the reference node: {} != {}",
+            divergence.number,
+            divergence.local.block_info.hash,
+            divergence.reference.block_info.hash
+        ));
+    }
+}
+
+#[async_trait]
+impl NodeActor for ShadowActor {
+    const NAME: &'static str = "shadow";
+    type Error = ShadowError;
+    type InboundData = ShadowContext;
+    type State = ShadowState;
+    type OutboundData = ShadowOutboundData;
+
+    fn build(state: Self::State) -> (Self::OutboundData, Self) {
+        Self::new(state)
+    }
+
+    async fn start(
+        mut self,
+        ShadowContext { engine_l2_safe_head, health, cancellation }: Self::InboundData,
+    ) -> Result<(), Self::Error> {
+        let clock = self.state.clock.clone();
+        let mut next_tick = clock.now();
+        loop {
+            tokio::select! {
+                _ = cancellation.cancelled() => {
+                    info!(target: "shadow", "Received shutdown signal. Exiting shadow task.");
+                    return Ok(());
+                }
+                Some(event) = self.attributes_rx.recv() => {
+                    if self.divergence.is_none() {
+                        self.hold(event.payload.attributes);
+                    }
+                }
+                _ = clock.sleep_until(next_tick) => {
+                    next_tick += self.state.config.interval;
+                    if self.divergence.is_some() {
+                        continue;
+                    }
+
+                    // Wait for the engine to initialize its safe head.
+                    let safe_head = *engine_l2_safe_head.borrow();
+                    if safe_head.block_info.hash.is_zero() {
+                        continue;
+                    }
+
+                    match self.compare(safe_head).await {
+                        Ok(Some(divergence)) => {
+                            self.report(&divergence, &health);
+                            self.attributes.clear();
+                            self.divergence = Some(divergence);
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            warn!(target: "shadow", %e, "Failed to compare against the reference node; retrying");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An error from the [`ShadowActor`].
+#[derive(Error, Debug)]
+pub enum ShadowError {
+    /// An error from the engine client.
+    #[error(transparent)]
+    Client(#[from] EngineClientError),
+    /// An error from the reference node.
+    #[error(transparent)]
+    Rpc(#[from] alloy_transport::TransportError),
+    /// A block was not found.
+    #[error("Block {0} not found")]
+    BlockNotFound(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SystemClock;
+    use alloy_consensus::{Header, transaction::Recovered};
+    use alloy_primitives::{B256, Bytes, U256};
+    use alloy_provider::mock::{Asserter, MockTransport};
+    use alloy_rpc_client::RpcClient;
+    use alloy_rpc_types_eth::BlockTransactions;
+    use kona_engine::MockEngineClient;
+    use kona_genesis::{RollupConfig, SystemConfig};
+    use kona_protocol::{BlockInfo, L1BlockInfoTx};
+    use kona_rpc::{HealthRegistry, HealthStatus};
+    use op_alloy_consensus::OpTxEnvelope;
+    use op_alloy_rpc_types_engine::OpPayloadAttributes;
+
+    /// Returns an L2 block with the given number, whose `extra_data` identifies its variant.
+    fn block(
+        cfg: &RollupConfig,
+        number: u64,
+        variant: u8,
+    ) -> alloy_rpc_types_eth::Block<op_alloy_rpc_types::Transaction> {
+        let (_, deposit) = L1BlockInfoTx::try_new_with_deposit_tx(
+            cfg,
+            &SystemConfig::default(),
+            number,
+            &Header::default(),
+            number * cfg.block_time,
+        )
+        .unwrap();
+        let header =
+            Header { number, extra_data: Bytes::from(vec![variant]), ..Default::default() };
+        let hash = header.hash_slow();
+        let from = deposit.from;
+        let tx = op_alloy_rpc_types::Transaction {
+            inner: alloy_rpc_types_eth::Transaction {
+                inner: Recovered::new_unchecked(OpTxEnvelope::Deposit(deposit), from),
+                block_hash: Some(hash),
+                block_number: Some(number),
+                transaction_index: Some(0),
+                effective_gas_price: Some(0),
+            },
+            deposit_nonce: None,
+            deposit_receipt_version: None,
+        };
+        alloy_rpc_types_eth::Block {
+            header: alloy_rpc_types_eth::Header {
+                hash,
+                inner: header,
+                total_difficulty: Some(U256::ZERO),
+                size: None,
+            },
+            uncles: vec![],
+            transactions: BlockTransactions::Full(vec![tx]),
+            withdrawals: None,
+        }
+    }
+
+    /// Returns a sync status with the given safe head.
+    fn sync_status(safe_l2: L2BlockInfo) -> SyncStatus {
+        SyncStatus {
+            current_l1: BlockInfo::default(),
+            current_l1_finalized: BlockInfo::default(),
+            head_l1: BlockInfo::default(),
+            safe_l1: BlockInfo::default(),
+            finalized_l1: BlockInfo::default(),
+            unsafe_l2: safe_l2,
+            safe_l2,
+            finalized_l2: L2BlockInfo::default(),
+            cross_unsafe_l2: safe_l2,
+            local_safe_l2: safe_l2,
+            el_sync: None,
+            deposits_only_l2: None,
+        }
+    }
+
+    /// Returns the output of the given block served by the reference node.
+    fn output(block_ref: L2BlockInfo) -> OutputResponse {
+        OutputResponse {
+            version: B256::ZERO,
+            output_root: B256::ZERO,
+            block_ref,
+            withdrawal_storage_root: B256::ZERO,
+            state_root: B256::ZERO,
+            sync_status: sync_status(block_ref),
+        }
+    }
+
+    /// Returns the derived attributes of the block with the given number.
+    fn attributes(number: u64) -> OpAttributesWithParent {
+        let parent = L2BlockInfo {
+            block_info: BlockInfo { number: number - 1, ..Default::default() },
+            ..Default::default()
+        };
+        OpAttributesWithParent::new(
+            OpPayloadAttributes::default(),
+            parent,
+            BlockInfo::default(),
+            true,
+        )
+    }
+
+    /// A shadow actor comparing a mock execution layer against a mock reference node.
+    struct Harness {
+        actor: ShadowActor,
+        mock: MockEngineClient,
+        reference: Asserter,
+    }
+
+    impl Harness {
+        /// Creates a harness whose execution layer holds the blocks up to the given number.
+        fn new(blocks: u64, journal: EventJournal) -> Self {
+            let cfg = Arc::new(RollupConfig::default());
+            let mock = MockEngineClient::new(cfg.clone());
+            for number in 1..=blocks {
+                mock.insert_block(number, &block(&cfg, number, 0));
+            }
+            let state = ShadowState {
+                client: mock.client(),
+                config: ShadowConfig {
+                    rollup_rpc: "http://127.0.0.1:9545".parse().unwrap(),
+                    l2_rpc: None,
+                    interval: Duration::from_secs(1),
+                },
+                clock: Arc::new(SystemClock),
+                journal,
+            };
+            let (_, mut actor) = ShadowActor::new(state);
+            let reference = Asserter::new();
+            actor.reference =
+                RootProvider::new(RpcClient::new(MockTransport::new(reference.clone()), false));
+            Self { actor, mock, reference }
+        }
+
+        /// Returns the block reference of the given block of the execution layer.
+        async fn local(&self, number: u64) -> L2BlockInfo {
+            self.mock
+                .client()
+                .l2_block_info_by_label(BlockNumberOrTag::Number(number))
+                .await
+                .unwrap()
+                .unwrap()
+        }
+
+        /// Queues the safe head of the reference node, and the references of its blocks in the
+        /// given range, which match those of the execution layer.
+        async fn serve(&self, safe_head: u64, blocks: impl IntoIterator<Item = u64>) {
+            self.reference.push_success(&sync_status(self.local(safe_head).await));
+            for number in blocks {
+                self.reference.push_success(&output(self.local(number).await));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_advances_cursor() {
+        let mut harness = Harness::new(3, EventJournal::disabled());
+        harness.actor.hold(attributes(2));
+        harness.actor.hold(attributes(3));
+
+        // The first comparison starts at the lowest of both safe heads.
+        harness.serve(2, [2]).await;
+        let safe_head = harness.local(3).await;
+        assert_eq!(harness.actor.compare(safe_head).await.unwrap(), None);
+        assert_eq!(harness.actor.cursor, Some(2));
+        assert_eq!(harness.actor.attributes.keys().copied().collect::<Vec<_>>(), [3]);
+
+        // The next one compares the blocks since the previous one.
+        harness.serve(3, [3]).await;
+        assert_eq!(harness.actor.compare(safe_head).await.unwrap(), None);
+        assert_eq!(harness.actor.cursor, Some(3));
+        assert!(harness.actor.attributes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_compare_caps_blocks_per_tick() {
+        let mut harness = Harness::new(MAX_BLOCKS_PER_TICK + 2, EventJournal::disabled());
+        harness.actor.cursor = Some(0);
+
+        let safe_head = harness.local(MAX_BLOCKS_PER_TICK + 2).await;
+        harness.serve(MAX_BLOCKS_PER_TICK + 2, 1..=MAX_BLOCKS_PER_TICK).await;
+        assert_eq!(harness.actor.compare(safe_head).await.unwrap(), None);
+        assert_eq!(harness.actor.cursor, Some(MAX_BLOCKS_PER_TICK));
+
+        harness
+            .serve(MAX_BLOCKS_PER_TICK + 2, [MAX_BLOCKS_PER_TICK + 1, MAX_BLOCKS_PER_TICK + 2])
+            .await;
+        assert_eq!(harness.actor.compare(safe_head).await.unwrap(), None);
+        assert_eq!(harness.actor.cursor, Some(MAX_BLOCKS_PER_TICK + 2));
+    }
+
+    #[tokio::test]
+    async fn test_compare_rewinds_cursor() {
+        let mut harness = Harness::new(3, EventJournal::disabled());
+        harness.actor.cursor = Some(3);
+
+        // The safe head was rewound, so the blocks past it are compared again once re-derived.
+        harness.serve(3, []).await;
+        let safe_head = harness.local(1).await;
+        assert_eq!(harness.actor.compare(safe_head).await.unwrap(), None);
+        assert_eq!(harness.actor.cursor, Some(1));
+
+        harness.actor.hold(attributes(2));
+        harness.serve(3, [2]).await;
+        let safe_head = harness.local(2).await;
+        assert_eq!(harness.actor.compare(safe_head).await.unwrap(), None);
+        assert_eq!(harness.actor.cursor, Some(2));
+        assert!(harness.actor.attributes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hold() {
+        let mut harness = Harness::new(0, EventJournal::disabled());
+        harness.actor.cursor = Some(5);
+
+        // The attributes of compared blocks are not held.
+        harness.actor.hold(attributes(5));
+        assert!(harness.actor.attributes.is_empty());
+
+        // Attributes re-derived after a reset replace the held ones.
+        harness.actor.hold(attributes(6));
+        let mut rederived = attributes(6);
+        rederived.is_last_in_span = false;
+        harness.actor.hold(rederived.clone());
+        assert_eq!(harness.actor.attributes.len(), 1);
+        assert_eq!(harness.actor.attributes[&6], rederived);
+
+        // The oldest attributes are dropped past the limit.
+        for number in 7..7 + MAX_PENDING_ATTRIBUTES as u64 {
+            harness.actor.hold(attributes(number));
+        }
+        assert_eq!(harness.actor.attributes.len(), MAX_PENDING_ATTRIBUTES);
+        assert!(!harness.actor.attributes.contains_key(&6));
+    }
+
+    #[tokio::test]
+    async fn test_compare_reports_divergence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let mut harness = Harness::new(3, EventJournal::open(&path, 1 << 20).unwrap());
+        harness.actor.cursor = Some(1);
+        harness.actor.hold(attributes(3));
+        harness.actor.hold(attributes(2));
+
+        // The reference node holds another block 2.
+        let safe_head = harness.local(3).await;
+        let mut reference = harness.local(2).await;
+        reference.block_info.hash = B256::repeat_byte(0xff);
+        harness.reference.push_success(&sync_status(safe_head));
+        harness.reference.push_success(&output(reference));
+
+        let divergence = harness.actor.compare(safe_head).await.unwrap().unwrap();
+        assert_eq!(
+            divergence,
+            ShadowDivergence {
+                number: 2,
+                local: harness.local(2).await,
+                reference,
+                local_safe_head: safe_head,
+                reference_safe_head: safe_head,
+                attributes: Some(attributes(2)),
+                attributes_mismatch: None,
+            }
+        );
+        assert_eq!(harness.actor.cursor, Some(1));
+
+        let registry = HealthRegistry::new();
+        harness
+            .actor
+            .report(&divergence, &HealthReporter::new(ShadowActor::NAME, registry.clone()));
+        assert!(matches!(registry.get(ShadowActor::NAME), Some(HealthStatus::Degraded(_))));
+        let entries = EventJournal::read(&path).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, JournalEventKind::ShadowDivergence);
+        assert_eq!(entries[0].context["number"], 2);
+    }
+}
@@ -0,0 +1,184 @@
+//! Utilization reporting for [NodeActor]s.
+//!
+//! [NodeActor]: crate::NodeActor
+
+use crate::{Metrics, bus::ChannelLimits};
+use kona_rpc::{ActorStats, ActorStatsRegistry, ActorStatsSnapshot, QueueStats};
+use std::{
+    collections::BTreeMap,
+    future::{Future, poll_fn},
+    pin::pin,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+
+/// The default interval at which the [`ActorStatsSampler`] samples the actors.
+pub const DEFAULT_ACTOR_STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Measures the select-loop iterations and busy time of a single [NodeActor].
+///
+/// Each poll of the actor's task is one iteration of its select loop, and the time spent in the
+/// poll is time the actor was busy rather than waiting on its inputs. Clones share the same
+/// counters.
+///
+/// [NodeActor]: crate::NodeActor
+#[derive(Debug, Clone, Default)]
+pub struct ActorMeter {
+    /// The counters, shared between clones.
+    inner: Arc<MeterInner>,
+}
+
+/// The counters of an [`ActorMeter`].
+#[derive(Debug, Default)]
+struct MeterInner {
+    /// The number of polls of the actor's task.
+    iterations: AtomicU64,
+    /// The total time spent polling the actor's task, in nanoseconds.
+    busy_nanos: AtomicU64,
+}
+
+impl ActorMeter {
+    /// Returns the number of select-loop iterations of the actor.
+    pub fn iterations(&self) -> u64 {
+        self.inner.iterations.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total time the actor was busy.
+    pub fn busy(&self) -> Duration {
+        Duration::from_nanos(self.inner.busy_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Runs the given future, which drives the actor, counting its polls and the time spent in
+    /// them.
+    pub async fn instrument<F: Future>(&self, future: F) -> F::Output {
+        let mut future = pin!(future);
+        poll_fn(|cx| {
+            let start = Instant::now();
+            let poll = future.as_mut().poll(cx);
+            let busy = start.elapsed().as_nanos().try_into().unwrap_or(u64::MAX);
+            self.inner.iterations.fetch_add(1, Ordering::Relaxed);
+            self.inner.busy_nanos.fetch_add(busy, Ordering::Relaxed);
+            poll
+        })
+        .await
+    }
+}
+
+/// Periodically samples the [`ActorMeter`] of each actor and the occupancy of the event channels
+/// created through the [`ChannelLimits`], publishing the sample into the [`ActorStatsRegistry`]
+/// and exporting it through the [`Metrics::ACTOR_ITERATION_RATE`] and
+/// [`Metrics::ACTOR_BUSY_RATIO`] gauges.
+///
+/// The sampler is cheaply cloneable, and its clones share the same meters.
+#[derive(Debug, Clone)]
+pub struct ActorStatsSampler {
+    /// The meter of each actor, by name.
+    meters: Arc<Mutex<BTreeMap<&'static str, ActorMeter>>>,
+    /// The limits the sampled event channels were created through.
+    limits: ChannelLimits,
+    /// The registry the samples are published into.
+    registry: ActorStatsRegistry,
+    /// The sampling interval.
+    interval: Duration,
+}
+
+impl ActorStatsSampler {
+    /// Creates a new [`ActorStatsSampler`] publishing into the given registry, and sampling the
+    /// channels created through the given limits.
+    pub fn new(registry: ActorStatsRegistry, limits: ChannelLimits) -> Self {
+        Self {
+            meters: Default::default(),
+            limits,
+            registry,
+            interval: DEFAULT_ACTOR_STATS_INTERVAL,
+        }
+    }
+
+    /// Sets the sampling interval.
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the [`ActorStatsRegistry`] the samples are published into.
+    pub const fn registry(&self) -> &ActorStatsRegistry {
+        &self.registry
+    }
+
+    /// Returns the [`ActorMeter`] of the named actor, registering it if needed.
+    pub fn meter(&self, name: &'static str) -> ActorMeter {
+        self.meters.lock().unwrap_or_else(PoisonError::into_inner).entry(name).or_default().clone()
+    }
+
+    /// Samples the actors every interval, until the given token is cancelled.
+    pub async fn run(self, cancellation: CancellationToken) {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut previous = BTreeMap::new();
+        let mut last = Instant::now();
+
+        loop {
+            tokio::select! {
+                _ = cancellation.cancelled() => return,
+                _ = interval.tick() => {
+                    let now = Instant::now();
+                    self.sample(&mut previous, now - last);
+                    last = now;
+                }
+            }
+        }
+    }
+
+    /// Takes a sample, computing the rates over the given elapsed time since the `previous`
+    /// counters, which are updated.
+    fn sample(&self, previous: &mut BTreeMap<&'static str, (u64, Duration)>, elapsed: Duration) {
+        let meters = self.meters.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        let secs = elapsed.as_secs_f64();
+        let mut snapshot =
+            ActorStatsSnapshot { interval_ms: elapsed.as_millis() as u64, ..Default::default() };
+
+        for (name, meter) in meters {
+            let (iterations, busy) = (meter.iterations(), meter.busy());
+            let (last_iterations, last_busy) =
+                previous.insert(name, (iterations, busy)).unwrap_or_default();
+            let (iteration_rate, busy_ratio) = if secs > 0.0 {
+                (
+                    (iterations - last_iterations) as f64 / secs,
+                    ((busy - last_busy).as_secs_f64() / secs).min(1.0),
+                )
+            } else {
+                (0.0, 0.0)
+            };
+            kona_macros::set!(gauge, Metrics::ACTOR_ITERATION_RATE, "actor", name, iteration_rate);
+            kona_macros::set!(gauge, Metrics::ACTOR_BUSY_RATIO, "actor", name, busy_ratio);
+            snapshot.actors.insert(
+                name.to_string(),
+                ActorStats {
+                    iterations,
+                    busy_ms: busy.as_millis() as u64,
+                    iteration_rate,
+                    busy_ratio,
+                },
+            );
+        }
+
+        for (name, usage) in self.limits.usage() {
+            snapshot.queues.insert(
+                name.to_string(),
+                QueueStats { depth: usage.depth, capacity: usage.capacity },
+            );
+        }
+
+        if let Some((name, stats)) =
+            snapshot.actors.iter().max_by(|a, b| a.1.busy_ratio.total_cmp(&b.1.busy_ratio))
+        {
+            trace!(target: "actor_stats", busiest = %name, busy_ratio = stats.busy_ratio, "Sampled actor utilization");
+        }
+        self.registry.set(snapshot);
+    }
+}
@@ -1,11 +1,12 @@
 //! Contains an actor for the supervisor rpc api.
 
-use crate::{NodeActor, SupervisorExt, actors::CancellableContext};
+use crate::{EventJournal, JournalEventKind, NodeActor, SupervisorExt, actors::CancellableContext};
 use async_trait::async_trait;
 use futures::StreamExt;
 use kona_interop::{ControlEvent, ManagedEvent};
+use kona_rpc::ReloadableConfig;
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
 
 /// The supervisor actor.
@@ -37,6 +38,12 @@ pub struct SupervisorOutboundData {
 pub struct SupervisorActorContext {
     /// A channel to receive `ManagedEvent`s from the kona node.
     node_events: mpsc::Receiver<ManagedEvent>,
+    /// The receiver for the node's [`ReloadableConfig`], through which the interop dependency set
+    /// is updated at runtime.
+    reload: watch::Receiver<ReloadableConfig>,
+    /// The [`EventJournal`] that control events from the supervisor and updates of the
+    /// dependency set are recorded in.
+    journal: EventJournal,
     /// The cancellation token, shared between all tasks.
     cancellation: CancellationToken,
 }
@@ -65,6 +72,7 @@ impl<E> NodeActor for SupervisorActor<E>
 where
     E: SupervisorExt + Send + Sync + 'static,
 {
+    const NAME: &'static str = "supervisor";
     type Error = SupervisorActorError;
     type InboundData = SupervisorActorContext;
     type OutboundData = SupervisorOutboundData;
@@ -76,9 +84,10 @@ where
 
     async fn start(
         mut self,
-        SupervisorActorContext { mut node_events, cancellation }: Self::InboundData,
+        SupervisorActorContext { mut node_events, mut reload, journal, cancellation }: Self::InboundData,
     ) -> Result<(), Self::Error> {
         let mut control_events = Box::pin(self.supervisor_ext.subscribe_control_events());
+        let mut dependency_set = reload.borrow_and_update().dependency_set.clone();
         loop {
             tokio::select! {
                 _ = cancellation.cancelled() => {
@@ -94,11 +103,35 @@ where
                 Some(control_event) = control_events.next() => {
                     // TODO: Handle the control event (e.g., restart, stop, etc.).
                     debug!(target: "supervisor", "Received control event: {:?}", control_event);
+                    journal.record(
+                        JournalEventKind::SupervisorUpdate,
+                        "Received control event from the supervisor",
+                        serde_json::json!({ "event": format!("{control_event:?}") }),
+                    );
                     self.engine_control
                         .send(control_event)
                         .await
                         .map_err(|_| SupervisorActorError::ControlEventSendFailed)?;
                 },
+                Ok(()) = reload.changed() => {
+                    let next = reload.borrow_and_update().dependency_set.clone();
+                    if next == dependency_set {
+                        continue;
+                    }
+                    let chains = next.as_ref().map(|set| {
+                        let mut chains = set.dependencies.keys().copied().collect::<Vec<_>>();
+                        chains.sort_unstable();
+                        chains
+                    });
+                    let expiry_window = next.as_ref().map(|set| set.get_message_expiry_window());
+                    info!(target: "supervisor", ?chains, ?expiry_window, "Updated the interop dependency set");
+                    journal.record(
+                        JournalEventKind::DependencySetUpdate,
+                        "Updated the interop dependency set",
+                        serde_json::json!({ "chains": chains, "message_expiry_window": expiry_window }),
+                    );
+                    dependency_set = next;
+                },
             }
         }
     }
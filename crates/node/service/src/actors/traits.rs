@@ -15,8 +15,20 @@ pub trait CancellableContext: Send {
 /// - Handle incoming messages.
 ///     - Perform background tasks.
 /// - Emit new events for other actors to process.
+///
+/// Each actor's [`HealthStatus`] is tracked in the node's [`HealthRegistry`] under its
+/// [`NodeActor::NAME`]. The actor is marked healthy when it is started, and stopped or unhealthy
+/// when it exits. Actors may refine their status while running with a [`HealthReporter`].
+///
+/// [`HealthStatus`]: kona_rpc::HealthStatus
+/// [`HealthRegistry`]: kona_rpc::HealthRegistry
+/// [`HealthReporter`]: crate::HealthReporter
 #[async_trait]
 pub trait NodeActor: Send + 'static {
+    /// The name of the actor, used to identify it in the [`HealthRegistry`].
+    ///
+    /// [`HealthRegistry`]: kona_rpc::HealthRegistry
+    const NAME: &'static str;
     /// The error type for the actor.
     type Error: std::fmt::Debug;
     /// The communication context used by the actor.
@@ -0,0 +1,383 @@
+//! [NodeActor] implementation for the chain-halt watchdog.
+
+use crate::{
+    Clock, DerivationProgress, HealthReporter, Metrics, NodeActor, actors::CancellableContext,
+};
+use async_trait::async_trait;
+use kona_engine::EngineState;
+use kona_protocol::{BlockInfo, L2BlockInfo};
+use std::{convert::Infallible, fmt, sync::Arc, time::Duration};
+use tokio::{sync::watch, time::Instant};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+
+/// The minimum interval between two checks of the watchdog.
+const MIN_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The maximum interval between two checks of the watchdog.
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The [NodeActor] for the chain-halt watchdog.
+///
+/// The watchdog detects when the safe head has not advanced for the configured
+/// [`ChainHaltConfig::timeout`], even though the L1 chain has. It classifies the likely
+/// [`ChainHaltCause`] from the [`DerivationProgress`] made in the meantime, and reports it through
+/// a log, the [`Metrics::CHAIN_HALT`] gauge, and the actor's health: a halt caused by missing
+/// batches marks the actor as degraded, as the node itself is operating normally, while any
+/// other halt marks it as unhealthy.
+///
+/// The watchdog is paused while the execution layer is syncing, since the safe head does not
+/// advance until the sync completes.
+#[derive(Debug)]
+pub struct ChainHaltWatchdog {
+    /// The state for the watchdog.
+    state: ChainHaltState,
+    /// The progress of the node when the safe head last advanced, if the watchdog is armed.
+    baseline: Option<Baseline>,
+    /// The cause of the ongoing halt, if the chain is halted.
+    halted: Option<ChainHaltCause>,
+}
+
+/// The configuration for the [`ChainHaltWatchdog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainHaltConfig {
+    /// The duration the safe head may stall for, while the L1 chain advances, before the chain is
+    /// considered halted.
+    pub timeout: Duration,
+    /// The number of pipeline resets during a stall at which the halt is attributed to a
+    /// [`ChainHaltCause::ResetLoop`].
+    pub reset_threshold: u64,
+}
+
+/// The state for the chain-halt watchdog.
+#[derive(Debug)]
+pub struct ChainHaltState {
+    /// The [`ChainHaltConfig`].
+    pub config: ChainHaltConfig,
+    /// The [`Clock`] that drives the checks.
+    pub clock: Arc<dyn Clock>,
+}
+
+/// The communication context used by the chain-halt watchdog.
+#[derive(Debug)]
+pub struct ChainHaltContext {
+    /// The receiver for L2 safe head update notifications.
+    pub engine_l2_safe_head: watch::Receiver<L2BlockInfo>,
+    /// The receiver for L1 head update notifications.
+    pub l1_head: watch::Receiver<Option<BlockInfo>>,
+    /// The receiver for the [`EngineState`].
+    pub engine_state: watch::Receiver<EngineState>,
+    /// The receiver for the [`DerivationProgress`] of the derivation actor.
+    pub derivation: watch::Receiver<DerivationProgress>,
+    /// Reports the health of the watchdog.
+    pub health: HealthReporter,
+    /// The cancellation token, shared between all tasks.
+    pub cancellation: CancellationToken,
+}
+
+impl CancellableContext for ChainHaltContext {
+    fn cancelled(&self) -> WaitForCancellationFuture<'_> {
+        self.cancellation.cancelled()
+    }
+}
+
+/// The likely cause of a chain halt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainHaltCause {
+    /// The derivation pipeline advanced its L1 origin, but found no batches to derive attributes
+    /// from, e.g. because the batcher stopped submitting.
+    NoBatches,
+    /// The derivation pipeline was repeatedly reset without the safe head advancing.
+    ResetLoop,
+    /// The derivation pipeline produced attributes or requested a reset, but the engine did not
+    /// advance the safe head.
+    EngineWedged,
+}
+
+impl ChainHaltCause {
+    /// Contains all chain halt causes.
+    pub const CAUSES: [Self; 3] = [Self::NoBatches, Self::ResetLoop, Self::EngineWedged];
+
+    /// Returns the label of the cause, used for metrics.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::NoBatches => "no_batches",
+            Self::ResetLoop => "reset_loop",
+            Self::EngineWedged => "engine_wedged",
+        }
+    }
+}
+
+impl fmt::Display for ChainHaltCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The progress of the node when the safe head last advanced.
+#[derive(Debug, Clone, Copy)]
+struct Baseline {
+    /// The highest safe block number observed.
+    safe_head: u64,
+    /// The instant at which the safe head advanced to `safe_head`.
+    since: Instant,
+    /// The L1 head block number at `since`.
+    l1_head: u64,
+    /// The [`DerivationProgress`] at `since`.
+    derivation: DerivationProgress,
+}
+
+impl ChainHaltWatchdog {
+    /// Creates a new instance of the [ChainHaltWatchdog].
+    pub const fn new(state: ChainHaltState) -> ((), Self) {
+        ((), Self { state, baseline: None, halted: None })
+    }
+
+    /// Checks whether the chain is halted, and returns the likely cause if so.
+    fn check(&mut self, now: Instant, ctx: &ChainHaltContext) -> Option<ChainHaltCause> {
+        let safe_head = *ctx.engine_l2_safe_head.borrow();
+        let l1_head = ctx.l1_head.borrow().map(|head| head.number).unwrap_or_default();
+        let derivation = *ctx.derivation.borrow();
+
+        // Disarm the watchdog until the engine has initialized its safe head and finished
+        // syncing.
+        if safe_head.block_info.hash.is_zero() || !ctx.engine_state.borrow().el_sync_finished {
+            self.baseline = None;
+            return None;
+        }
+
+        let baseline = match self.baseline {
+            Some(baseline) if baseline.safe_head >= safe_head.block_info.number => baseline,
+            _ => {
+                self.baseline = Some(Baseline {
+                    safe_head: safe_head.block_info.number,
+                    since: now,
+                    l1_head,
+                    derivation,
+                });
+                return None;
+            }
+        };
+
+        // A stalled safe head is expected if the L1 chain has not advanced either.
+        if now.duration_since(baseline.since) < self.state.config.timeout ||
+            l1_head <= baseline.l1_head
+        {
+            return None;
+        }
+
+        let resets = derivation.resets.saturating_sub(baseline.derivation.resets);
+        let attributes = derivation.attributes.saturating_sub(baseline.derivation.attributes);
+        Some(if resets >= self.state.config.reset_threshold {
+            ChainHaltCause::ResetLoop
+        } else if attributes > 0 || resets > 0 {
+            ChainHaltCause::EngineWedged
+        } else {
+            ChainHaltCause::NoBatches
+        })
+    }
+
+    /// Reports a change of the halt status.
+    fn report(&mut self, halted: Option<ChainHaltCause>, now: Instant, health: &HealthReporter) {
+        if halted == self.halted {
+            return;
+        }
+        self.halted = halted;
+
+        for cause in ChainHaltCause::CAUSES {
+            kona_macros::set!(
+                gauge,
+                Metrics::CHAIN_HALT,
+                "cause",
+                cause.as_str(),
+                if halted == Some(cause) { 1.0 } else { 0.0 }
+            );
+        }
+
+        let Some(cause) = halted else {
+            info!(target: "watchdog", "Safe head is advancing again");
+            health.healthy();
+            return;
+        };
+        let (safe_head, stalled_for) = self
+            .baseline
+            .map(|b| (b.safe_head, now.duration_since(b.since).as_secs()))
+            .unwrap_or_default();
+        error!(
+            target: "watchdog",
+            %cause,
+            safe_head,
+            stalled_for,
+            "Chain halt detected: the safe head has not advanced despite new L1 blocks"
+        );
+        let reason = format!("safe head {safe_head} stalled for {stalled_for}s: {cause}");
+        match cause {
+            ChainHaltCause::NoBatches => health.degraded(reason),
+            ChainHaltCause::ResetLoop | ChainHaltCause::EngineWedged => health.unhealthy(reason),
+        }
+    }
+}
+
+#[async_trait]
+impl NodeActor for ChainHaltWatchdog {
+    const NAME: &'static str = "chain_halt";
+    type Error = Infallible;
+    type InboundData = ChainHaltContext;
+    type State = ChainHaltState;
+    type OutboundData = ();
+
+    fn build(state: Self::State) -> (Self::OutboundData, Self) {
+        Self::new(state)
+    }
+
+    async fn start(mut self, ctx: Self::InboundData) -> Result<(), Self::Error> {
+        let clock = self.state.clock.clone();
+        let interval = self.state.config.timeout.clamp(MIN_CHECK_INTERVAL, MAX_CHECK_INTERVAL);
+        let mut next_tick = clock.now();
+        for cause in ChainHaltCause::CAUSES {
+            kona_macros::set!(gauge, Metrics::CHAIN_HALT, "cause", cause.as_str(), 0.0);
+        }
+
+        loop {
+            tokio::select! {
+                _ = ctx.cancellation.cancelled() => {
+                    info!(target: "watchdog", "Received shutdown signal. Exiting watchdog task.");
+                    return Ok(());
+                }
+                _ = clock.sleep_until(next_tick) => {
+                    next_tick += interval;
+                    let now = clock.now();
+                    let halted = self.check(now, &ctx);
+                    self.report(halted, now, &ctx.health);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SystemClock;
+    use alloy_primitives::B256;
+    use kona_rpc::HealthRegistry;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// The senders of the [`ChainHaltContext`] of a test watchdog.
+    struct Inputs {
+        safe_head: watch::Sender<L2BlockInfo>,
+        l1_head: watch::Sender<Option<BlockInfo>>,
+        engine_state: watch::Sender<EngineState>,
+        derivation: watch::Sender<DerivationProgress>,
+    }
+
+    impl Inputs {
+        fn set_safe_head(&self, number: u64) {
+            let block_info =
+                BlockInfo { number, hash: B256::with_last_byte(1), ..Default::default() };
+            self.safe_head.send_replace(L2BlockInfo { block_info, ..Default::default() });
+        }
+
+        fn set_l1_head(&self, number: u64) {
+            self.l1_head.send_replace(Some(BlockInfo { number, ..Default::default() }));
+        }
+    }
+
+    /// Returns a watchdog, and a context whose safe head is initialized and whose EL is synced.
+    fn watchdog() -> (ChainHaltWatchdog, ChainHaltContext, Inputs) {
+        let config = ChainHaltConfig { timeout: TIMEOUT, reset_threshold: 3 };
+        let (_, watchdog) =
+            ChainHaltWatchdog::new(ChainHaltState { config, clock: Arc::new(SystemClock) });
+        let (safe_head, engine_l2_safe_head) = watch::channel(L2BlockInfo::default());
+        let (l1_head, l1_head_rx) = watch::channel(None);
+        let (engine_state, engine_state_rx) =
+            watch::channel(EngineState { el_sync_finished: true, ..Default::default() });
+        let (derivation, derivation_rx) = watch::channel(DerivationProgress::default());
+        let ctx = ChainHaltContext {
+            engine_l2_safe_head,
+            l1_head: l1_head_rx,
+            engine_state: engine_state_rx,
+            derivation: derivation_rx,
+            health: HealthReporter::new(ChainHaltWatchdog::NAME, HealthRegistry::new()),
+            cancellation: CancellationToken::new(),
+        };
+        let inputs = Inputs { safe_head, l1_head, engine_state, derivation };
+        inputs.set_safe_head(10);
+        inputs.set_l1_head(100);
+        (watchdog, ctx, inputs)
+    }
+
+    #[test]
+    fn test_stalled_without_batches() {
+        let (mut watchdog, ctx, inputs) = watchdog();
+        let start = Instant::now();
+        assert_eq!(watchdog.check(start, &ctx), None);
+
+        // The L1 chain advances, but the safe head does not.
+        inputs.set_l1_head(101);
+        assert_eq!(watchdog.check(start + TIMEOUT / 2, &ctx), None);
+        assert_eq!(watchdog.check(start + TIMEOUT, &ctx), Some(ChainHaltCause::NoBatches));
+    }
+
+    #[test]
+    fn test_stalled_causes() {
+        let (mut watchdog, ctx, inputs) = watchdog();
+        let start = Instant::now();
+        assert_eq!(watchdog.check(start, &ctx), None);
+        inputs.set_l1_head(101);
+
+        inputs.derivation.send_modify(|progress| progress.attributes += 1);
+        assert_eq!(watchdog.check(start + TIMEOUT, &ctx), Some(ChainHaltCause::EngineWedged));
+
+        inputs.derivation.send_modify(|progress| progress.resets += 3);
+        assert_eq!(watchdog.check(start + TIMEOUT, &ctx), Some(ChainHaltCause::ResetLoop));
+    }
+
+    #[test]
+    fn test_stalled_l1() {
+        let (mut watchdog, ctx, _inputs) = watchdog();
+        let start = Instant::now();
+        assert_eq!(watchdog.check(start, &ctx), None);
+
+        // A stalled safe head is expected while the L1 chain is stalled too.
+        assert_eq!(watchdog.check(start + TIMEOUT * 2, &ctx), None);
+    }
+
+    #[test]
+    fn test_recovered() {
+        let (mut watchdog, ctx, inputs) = watchdog();
+        let start = Instant::now();
+        assert_eq!(watchdog.check(start, &ctx), None);
+        inputs.set_l1_head(101);
+        assert_eq!(watchdog.check(start + TIMEOUT, &ctx), Some(ChainHaltCause::NoBatches));
+
+        // Once the safe head advances, the watchdog re-arms from the new safe head.
+        inputs.set_safe_head(11);
+        assert_eq!(watchdog.check(start + TIMEOUT, &ctx), None);
+        inputs.set_l1_head(102);
+        assert_eq!(watchdog.check(start + TIMEOUT * 3 / 2, &ctx), None);
+        assert_eq!(watchdog.check(start + TIMEOUT * 2, &ctx), Some(ChainHaltCause::NoBatches));
+    }
+
+    #[test]
+    fn test_startup_grace() {
+        let (mut watchdog, ctx, inputs) = watchdog();
+        let start = Instant::now();
+
+        // The watchdog is disarmed until the safe head is initialized and the EL is synced.
+        inputs.safe_head.send_replace(L2BlockInfo::default());
+        assert_eq!(watchdog.check(start, &ctx), None);
+        inputs.set_safe_head(10);
+        inputs.engine_state.send_modify(|state| state.el_sync_finished = false);
+        assert_eq!(watchdog.check(start, &ctx), None);
+        inputs.set_l1_head(101);
+        assert_eq!(watchdog.check(start + TIMEOUT, &ctx), None);
+
+        // Once synced, the first check only arms it, and the timeout starts from there.
+        inputs.engine_state.send_modify(|state| state.el_sync_finished = true);
+        assert_eq!(watchdog.check(start + TIMEOUT, &ctx), None);
+        inputs.set_l1_head(102);
+        assert_eq!(watchdog.check(start + TIMEOUT * 3 / 2, &ctx), None);
+        assert_eq!(watchdog.check(start + TIMEOUT * 2, &ctx), Some(ChainHaltCause::NoBatches));
+    }
+}
@@ -0,0 +1,805 @@
+//! A typed event bus for communication between [`NodeActor`]s.
+//!
+//! Events are sent over bounded channels, each wrapped with the [`Span`] that was active when the
+//! event was sent. The receiving actor gets a new span for each event that is a child of the
+//! sender's span, so that the causality of events can be traced across actors (e.g. L1 head
+//! update → payload attributes → block import), and exported traces show a single waterfall per
+//! block.
+//!
+//! Each channel is named, and configured with a [`ChannelConfig`] that sets its capacity and the
+//! [`OverflowPolicy`] applied when it is full. The number of queued events is exported through the
+//! [`Metrics::CHANNEL_QUEUE_DEPTH`] gauge, sends that found the channel full through the
+//! [`Metrics::CHANNEL_SATURATED`] counter, and events dropped on overflow through the
+//! [`Metrics::CHANNEL_DROPPED_EVENTS`] counter, all labeled by channel name.
+//!
+//! The capacity of the channels created through the [`ChannelLimits`] of the [`BusConfig`] can be
+//! limited at runtime, e.g. to diagnose backpressure in production without restarting the node.
+//!
+//! [`NodeActor`]: crate::NodeActor
+
+use crate::Metrics;
+use derive_more::Display;
+use std::{
+    collections::BTreeMap,
+    str::FromStr,
+    sync::{
+        Arc, Mutex, PoisonError, Weak,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+use tokio::sync::{
+    Notify, broadcast,
+    mpsc::{self, error::TrySendError},
+};
+use tracing::Span;
+
+/// The policy applied when an event is sent on a full channel.
+#[derive(Debug, Display, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for the receiver to make room in the channel.
+    #[default]
+    #[display("block")]
+    Block,
+    /// Drop the oldest queued event to make room for the new one. Dropped events are counted in
+    /// [`Metrics::CHANNEL_DROPPED_EVENTS`]. The capacity of such channels is rounded up to the
+    /// next power of two.
+    #[display("drop-oldest")]
+    DropOldest,
+    /// Return [`SendError::Full`] to the sender. Only suited to channels whose sender tolerates
+    /// dropped events, since most actors treat a failed send as fatal.
+    #[display("error")]
+    Error,
+}
+
+impl OverflowPolicy {
+    /// Contains all valid overflow policies.
+    pub const POLICIES: [Self; 3] = [Self::Block, Self::DropOldest, Self::Error];
+
+    /// Returns `true` if no event is ever lost on overflow, i.e. [`OverflowPolicy::Block`].
+    pub const fn is_lossless(&self) -> bool {
+        matches!(self, Self::Block)
+    }
+}
+
+impl FromStr for OverflowPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::POLICIES
+            .into_iter()
+            .find(|policy| policy.to_string() == s)
+            .ok_or_else(|| format!("invalid overflow policy: {s}"))
+    }
+}
+
+/// The configuration of an event channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelConfig {
+    /// The maximum number of queued events.
+    pub capacity: usize,
+    /// The [`OverflowPolicy`] applied when the channel is full.
+    pub policy: OverflowPolicy,
+}
+
+impl ChannelConfig {
+    /// Creates a new [`ChannelConfig`] with the given capacity, which blocks senders when full.
+    pub const fn new(capacity: usize) -> Self {
+        Self { capacity, policy: OverflowPolicy::Block }
+    }
+
+    /// Sets the [`OverflowPolicy`] of the channel.
+    pub const fn with_policy(self, policy: OverflowPolicy) -> Self {
+        Self { policy, ..self }
+    }
+}
+
+/// The [`ChannelConfig`]s of the event channels between the node's actors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BusConfig {
+    /// Unsafe blocks sent from the network actor to the engine actor.
+    pub unsafe_blocks: ChannelConfig,
+    /// Payload attributes sent from the derivation actor to the engine actor.
+    pub attributes: ChannelConfig,
+    /// Signals sent from the engine actor to the derivation actor.
+    pub derivation_signals: ChannelConfig,
+    /// The [`ChannelLimits`] that the channels are created with.
+    pub limits: ChannelLimits,
+}
+
+impl BusConfig {
+    /// The names of the channels that must never lose an event, and therefore only accept the
+    /// [`OverflowPolicy::Block`] policy. A dropped payload attribute or derivation signal stalls
+    /// derivation, and a failed send on them is fatal to the sending actor.
+    pub const LOSSLESS_CHANNELS: [&'static str; 2] = ["attributes", "derivation_signals"];
+}
+
+impl Default for BusConfig {
+    fn default() -> Self {
+        Self {
+            unsafe_blocks: ChannelConfig::new(1024),
+            attributes: ChannelConfig::new(16),
+            derivation_signals: ChannelConfig::new(16),
+            limits: ChannelLimits::default(),
+        }
+    }
+}
+
+/// The capacity limits of the event channels, adjustable at runtime by channel name.
+///
+/// Each channel allocates the capacity of its [`ChannelConfig`], which bounds the number of
+/// events it can queue. A limit lowers the number of events it queues before its
+/// [`OverflowPolicy`] is applied, down to one, without recreating the channel. Limits above the
+/// capacity of a channel are capped to it. A limit set before a channel is created applies to it
+/// once it is.
+///
+/// The limits are cheaply cloneable, and their clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelLimits {
+    /// The shared state.
+    inner: Arc<Mutex<LimitsInner>>,
+}
+
+/// The shared state of the [`ChannelLimits`].
+#[derive(Debug, Default)]
+struct LimitsInner {
+    /// The limits, by channel name.
+    limits: BTreeMap<String, usize>,
+    /// The channels created through the limits.
+    channels: Vec<LimitedChannel>,
+}
+
+/// A channel created through the [`ChannelLimits`].
+#[derive(Debug)]
+struct LimitedChannel {
+    /// The name of the channel.
+    name: &'static str,
+    /// The capacity the channel was created with.
+    capacity: usize,
+    /// The state shared by the halves of the channel, until they are dropped.
+    state: Weak<ChannelState>,
+}
+
+impl LimitedChannel {
+    /// Applies the given limit to the channel, or removes its limit.
+    fn apply(&self, limit: Option<usize>) {
+        let Some(state) = self.state.upgrade() else {
+            return;
+        };
+        let limit = limit.map(|limit| limit.clamp(1, self.capacity));
+        state.limit.store(limit.unwrap_or(0), Ordering::Release);
+        // Wake the blocked senders, in case the limit was raised.
+        state.received.notify_waiters();
+        kona_macros::set!(
+            gauge,
+            Metrics::CHANNEL_CAPACITY,
+            "channel",
+            self.name,
+            limit.unwrap_or(self.capacity) as f64
+        );
+    }
+}
+
+impl PartialEq for ChannelLimits {
+    /// Compares the limits set, regardless of the channels created through them.
+    fn eq(&self, other: &Self) -> bool {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            return true;
+        }
+        let limits = self.inner.lock().unwrap_or_else(PoisonError::into_inner).limits.clone();
+        limits == other.inner.lock().unwrap_or_else(PoisonError::into_inner).limits
+    }
+}
+
+impl Eq for ChannelLimits {}
+
+impl ChannelLimits {
+    /// Creates a new named, bounded event channel like [`channel`], whose capacity is limited by
+    /// the limit set for its name, if any.
+    ///
+    /// The [`BusConfig::LOSSLESS_CHANNELS`] are always created with the [`OverflowPolicy::Block`]
+    /// policy, so that limiting their capacity at runtime only ever applies backpressure.
+    pub fn channel<T: Clone>(
+        &self,
+        name: &'static str,
+        mut config: ChannelConfig,
+    ) -> (EventSender<T>, EventReceiver<T>) {
+        if BusConfig::LOSSLESS_CHANNELS.contains(&name) && !config.policy.is_lossless() {
+            warn!(target: "bus", channel = name, policy = %config.policy, "Overflow policy not allowed, blocking on overflow instead");
+            config.policy = OverflowPolicy::Block;
+        }
+        let (tx, rx) = channel(name, config);
+        let channel =
+            LimitedChannel { name, capacity: config.capacity, state: Arc::downgrade(&tx.state) };
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        channel.apply(inner.limits.get(name).copied());
+        inner.channels.retain(|channel| channel.state.strong_count() > 0);
+        inner.channels.push(channel);
+        (tx, rx)
+    }
+
+    /// Sets the limits of the channels, by channel name, replacing the previous ones. The channels
+    /// without a limit get back the capacity they were created with.
+    pub fn set(&self, limits: &BTreeMap<String, usize>) {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        if inner.limits == *limits {
+            return;
+        }
+        inner.limits = limits.clone();
+        for channel in &inner.channels {
+            channel.apply(inner.limits.get(channel.name).copied());
+        }
+    }
+
+    /// Returns the [`ChannelUsage`] of the live channels created through the limits, by channel
+    /// name. The usage of channels sharing a name is summed.
+    pub fn usage(&self) -> BTreeMap<&'static str, ChannelUsage> {
+        let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut usage = BTreeMap::<_, ChannelUsage>::new();
+        for channel in &inner.channels {
+            let Some(state) = channel.state.upgrade() else {
+                continue;
+            };
+            let entry = usage.entry(channel.name).or_default();
+            entry.depth += state.depth.load(Ordering::Relaxed);
+            entry.capacity += state.limit().unwrap_or(channel.capacity);
+        }
+        usage
+    }
+}
+
+/// The occupancy of an event channel. See [`ChannelLimits::usage`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelUsage {
+    /// The number of events queued in the channel, as of the last send or receive.
+    pub depth: usize,
+    /// The effective capacity of the channel.
+    pub capacity: usize,
+}
+
+/// An error returned when sending an event on an [`EventSender`] fails.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SendError<T> {
+    /// The receiver was dropped.
+    #[error("channel closed")]
+    Closed(T),
+    /// The channel was full, and its [`OverflowPolicy`] is [`OverflowPolicy::Error`].
+    #[error("channel full")]
+    Full(T),
+}
+
+/// Creates a new named, bounded event channel with the given [`ChannelConfig`].
+pub fn channel<T: Clone>(
+    name: &'static str,
+    config: ChannelConfig,
+) -> (EventSender<T>, EventReceiver<T>) {
+    let (tx, rx) = match config.policy {
+        OverflowPolicy::Block | OverflowPolicy::Error => {
+            let (tx, rx) = mpsc::channel(config.capacity);
+            (SenderInner::Bounded(tx), ReceiverInner::Bounded(rx))
+        }
+        OverflowPolicy::DropOldest => {
+            let (tx, rx) = broadcast::channel(config.capacity);
+            (SenderInner::Lossy(tx), ReceiverInner::Lossy(rx))
+        }
+    };
+    let state = Arc::new(ChannelState {
+        limit: AtomicUsize::new(0),
+        depth: AtomicUsize::new(0),
+        received: Notify::new(),
+    });
+    (
+        EventSender { name, config, inner: tx, state: state.clone() },
+        EventReceiver { name, inner: rx, state },
+    )
+}
+
+/// The state shared by the halves of an event channel.
+#[derive(Debug)]
+struct ChannelState {
+    /// The runtime limit on the capacity of the channel, or `0` if it is not limited. See
+    /// [`ChannelLimits`].
+    limit: AtomicUsize,
+    /// The number of events queued in the channel, as of the last send or receive.
+    depth: AtomicUsize,
+    /// Notified when events are received, to wake the senders blocked on the limit.
+    received: Notify,
+}
+
+impl ChannelState {
+    /// Returns the runtime limit on the capacity of the channel, if any.
+    fn limit(&self) -> Option<usize> {
+        Some(self.limit.load(Ordering::Acquire)).filter(|limit| *limit > 0)
+    }
+
+    /// Records the number of events queued in the named channel.
+    fn set_depth(&self, name: &'static str, depth: usize) {
+        self.depth.store(depth, Ordering::Relaxed);
+        kona_macros::set!(gauge, Metrics::CHANNEL_QUEUE_DEPTH, "channel", name, depth as f64);
+    }
+}
+
+/// An event received from an [`EventReceiver`].
+#[derive(Debug)]
+pub struct Event<T> {
+    /// The event payload.
+    pub payload: T,
+    /// The span for handling the event, which is a child of the span that sent it.
+    pub span: Span,
+}
+
+/// The inner sender of an event channel. Channels that drop the oldest event on overflow are
+/// backed by a single-receiver [`broadcast`] channel, which overwrites its oldest value when full.
+#[derive(Debug)]
+enum SenderInner<T> {
+    Bounded(mpsc::Sender<(T, Span)>),
+    Lossy(broadcast::Sender<(T, Span)>),
+}
+
+impl<T> Clone for SenderInner<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Bounded(tx) => Self::Bounded(tx.clone()),
+            Self::Lossy(tx) => Self::Lossy(tx.clone()),
+        }
+    }
+}
+
+/// The sending half of an event channel. See [`channel`].
+#[derive(Debug)]
+pub struct EventSender<T> {
+    /// The name of the channel.
+    name: &'static str,
+    /// The [`ChannelConfig`] of the channel.
+    config: ChannelConfig,
+    /// The inner sender.
+    inner: SenderInner<T>,
+    /// The state shared with the receiver.
+    state: Arc<ChannelState>,
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            config: self.config,
+            inner: self.inner.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> EventSender<T> {
+    /// Returns the name of the channel.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the [`ChannelConfig`] of the channel.
+    pub const fn config(&self) -> ChannelConfig {
+        self.config
+    }
+
+    /// Returns the effective capacity of the channel: its runtime limit if any, see
+    /// [`ChannelLimits`], or the capacity of its [`ChannelConfig`].
+    pub fn capacity(&self) -> usize {
+        self.state.limit().unwrap_or(self.config.capacity)
+    }
+
+    /// Returns the number of events currently queued in the channel.
+    pub fn len(&self) -> usize {
+        match &self.inner {
+            SenderInner::Bounded(tx) => tx.max_capacity() - tx.capacity(),
+            SenderInner::Lossy(tx) => tx.len(),
+        }
+    }
+
+    /// Returns `true` if there are no events queued in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the receiver was dropped.
+    pub fn is_closed(&self) -> bool {
+        match &self.inner {
+            SenderInner::Bounded(tx) => tx.is_closed(),
+            SenderInner::Lossy(tx) => tx.receiver_count() == 0,
+        }
+    }
+
+    /// Sends an event, applying the channel's [`OverflowPolicy`] if it is full. The current
+    /// [`Span`] is attached to the event as its cause.
+    pub async fn send(&self, payload: T) -> Result<(), SendError<T>> {
+        let event = (payload, Span::current());
+        match &self.inner {
+            SenderInner::Bounded(tx) if self.state.limit().is_some_and(|l| self.len() >= l) => {
+                self.saturated();
+                if self.config.policy == OverflowPolicy::Error {
+                    return Err(SendError::Full(event.0));
+                }
+                self.wait_below_limit(tx).await;
+                tx.send(event).await.map_err(|e| SendError::Closed(e.0.0))?;
+            }
+            SenderInner::Bounded(tx) => match tx.try_send(event) {
+                Ok(()) => {}
+                Err(TrySendError::Closed((payload, _))) => return Err(SendError::Closed(payload)),
+                Err(TrySendError::Full(event)) => {
+                    self.saturated();
+                    if self.config.policy == OverflowPolicy::Error {
+                        return Err(SendError::Full(event.0));
+                    }
+                    tx.send(event).await.map_err(|e| SendError::Closed(e.0.0))?;
+                }
+            },
+            SenderInner::Lossy(tx) => {
+                if tx.len() >= self.capacity() {
+                    self.saturated();
+                }
+                tx.send(event).map_err(|e| SendError::Closed(e.0.0))?;
+            }
+        }
+        self.state.set_depth(self.name, self.len());
+        Ok(())
+    }
+
+    /// Waits until the number of queued events is below the runtime limit of the channel, or the
+    /// receiver is dropped.
+    async fn wait_below_limit(&self, tx: &mpsc::Sender<(T, Span)>) {
+        loop {
+            let received = self.state.received.notified();
+            tokio::pin!(received);
+            received.as_mut().enable();
+            if self.state.limit().is_none_or(|limit| self.len() < limit) {
+                return;
+            }
+            tokio::select! {
+                _ = received => {}
+                _ = tx.closed() => return,
+            }
+        }
+    }
+
+    /// Records that a send found the channel full.
+    fn saturated(&self) {
+        trace!(target: "bus", channel = self.name, policy = %self.config.policy, "Channel is full");
+        kona_macros::inc!(counter, Metrics::CHANNEL_SATURATED, "channel" => self.name);
+    }
+}
+
+/// The inner receiver of an event channel. See [`SenderInner`].
+#[derive(Debug)]
+enum ReceiverInner<T> {
+    Bounded(mpsc::Receiver<(T, Span)>),
+    Lossy(broadcast::Receiver<(T, Span)>),
+}
+
+/// The receiving half of an event channel. See [`channel`].
+#[derive(Debug)]
+pub struct EventReceiver<T> {
+    /// The name of the channel.
+    name: &'static str,
+    /// The inner receiver.
+    inner: ReceiverInner<T>,
+    /// The state shared with the senders.
+    state: Arc<ChannelState>,
+}
+
+impl<T: Clone> EventReceiver<T> {
+    /// Returns the name of the channel.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the number of events currently queued in the channel.
+    pub fn len(&self) -> usize {
+        match &self.inner {
+            ReceiverInner::Bounded(rx) => rx.len(),
+            ReceiverInner::Lossy(rx) => rx.len(),
+        }
+    }
+
+    /// Returns `true` if there are no events queued in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Receives the next event, or `None` if all senders have been dropped and the channel is
+    /// empty.
+    pub async fn recv(&mut self) -> Option<Event<T>> {
+        let (payload, cause) = match &mut self.inner {
+            ReceiverInner::Bounded(rx) => rx.recv().await?,
+            ReceiverInner::Lossy(rx) => loop {
+                // Drop the oldest events beyond the runtime limit of the channel, if any.
+                let mut trimmed = 0;
+                while self.state.limit().is_some_and(|limit| rx.len() > limit) {
+                    match rx.try_recv() {
+                        Ok(_) => trimmed += 1,
+                        Err(broadcast::error::TryRecvError::Lagged(dropped)) => trimmed += dropped,
+                        Err(_) => break,
+                    }
+                }
+                if trimmed > 0 {
+                    Self::dropped(self.name, trimmed);
+                }
+                match rx.recv().await {
+                    Ok(event) => break event,
+                    Err(broadcast::error::RecvError::Lagged(dropped)) => {
+                        Self::dropped(self.name, dropped)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+        };
+        self.state.received.notify_waiters();
+        self.state.set_depth(self.name, self.len());
+
+        // If the event span is filtered out, handle the event in the sender's span directly, so
+        // that the trace is not broken up.
+        let span = debug_span!(target: "bus", parent: &cause, "event", channel = self.name);
+        let span = if span.is_disabled() { cause } else { span };
+        Some(Event { payload, span })
+    }
+
+    /// Removes the events currently queued in the channel, without waiting for new ones, and
+    /// returns their payloads in order.
+    pub fn drain(&mut self) -> Vec<T> {
+        let mut payloads = Vec::with_capacity(self.len());
+        match &mut self.inner {
+            ReceiverInner::Bounded(rx) => {
+                while let Ok((payload, _)) = rx.try_recv() {
+                    payloads.push(payload);
+                }
+            }
+            ReceiverInner::Lossy(rx) => loop {
+                match rx.try_recv() {
+                    Ok((payload, _)) => payloads.push(payload),
+                    Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                    Err(_) => break,
+                }
+            },
+        }
+        self.state.received.notify_waiters();
+        self.state.set_depth(self.name, self.len());
+        payloads
+    }
+
+    /// Records that the given number of the oldest events were dropped on overflow.
+    fn dropped(name: &'static str, dropped: u64) {
+        warn!(target: "bus", channel = name, dropped, "Dropped oldest events on overflow");
+        #[cfg(feature = "metrics")]
+        metrics::counter!(Metrics::CHANNEL_DROPPED_EVENTS, "channel" => name).increment(dropped);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    /// Returns the payloads received from the given receiver until it is empty.
+    async fn recv_all<T: Clone>(rx: &mut EventReceiver<T>) -> Vec<T> {
+        let mut payloads = Vec::new();
+        while !rx.is_empty() {
+            payloads.push(rx.recv().await.unwrap().payload);
+        }
+        payloads
+    }
+
+    /// Returns a limits map setting the limit of the "test" channel.
+    fn limit(limit: usize) -> BTreeMap<String, usize> {
+        BTreeMap::from([("test".to_string(), limit)])
+    }
+
+    #[test]
+    fn test_overflow_policy_from_str() {
+        for policy in OverflowPolicy::POLICIES {
+            assert_eq!(policy.to_string().parse::<OverflowPolicy>(), Ok(policy));
+        }
+        assert!("drop-newest".parse::<OverflowPolicy>().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_block_policy_waits_for_receiver() {
+        let (tx, mut rx) = channel("test", ChannelConfig::new(1));
+        tx.send(1).await.unwrap();
+        assert_eq!(tx.len(), 1);
+
+        // The send waits until the receiver makes room.
+        assert!(timeout(Duration::from_secs(1), tx.send(2)).await.is_err());
+        let sender = tx.clone();
+        let pending = tokio::spawn(async move { sender.send(2).await });
+        tokio::task::yield_now().await;
+        assert!(!pending.is_finished());
+
+        assert_eq!(rx.recv().await.unwrap().payload, 1);
+        pending.await.unwrap().unwrap();
+        assert_eq!(recv_all(&mut rx).await, [2]);
+    }
+
+    #[tokio::test]
+    async fn test_error_policy_rejects_when_full() {
+        let config = ChannelConfig::new(1).with_policy(OverflowPolicy::Error);
+        let (tx, mut rx) = channel("test", config);
+        tx.send(1).await.unwrap();
+        assert_eq!(tx.send(2).await, Err(SendError::Full(2)));
+        assert_eq!(recv_all(&mut rx).await, [1]);
+
+        drop(rx);
+        assert!(tx.is_closed());
+        assert_eq!(tx.send(3).await, Err(SendError::Closed(3)));
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_policy_lags_receiver() {
+        let config = ChannelConfig::new(2).with_policy(OverflowPolicy::DropOldest);
+        let (tx, mut rx) = channel("test", config);
+
+        // Sends never wait, and the receiver skips the overwritten events.
+        for i in 1..=5 {
+            tx.send(i).await.unwrap();
+        }
+        assert_eq!(rx.recv().await.unwrap().payload, 4);
+        assert_eq!(rx.recv().await.unwrap().payload, 5);
+        assert!(rx.is_empty());
+
+        drop(tx);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lossless_channels_block() {
+        let limits = ChannelLimits::default();
+        let config = ChannelConfig::new(4).with_policy(OverflowPolicy::DropOldest);
+        let (tx, _rx) = limits.channel::<u64>("attributes", config);
+        assert_eq!(tx.config().policy, OverflowPolicy::Block);
+        let (tx, _rx) = limits.channel::<u64>("test", config);
+        assert_eq!(tx.config().policy, OverflowPolicy::DropOldest);
+    }
+
+    #[tokio::test]
+    async fn test_limits_capacity() {
+        let limits = ChannelLimits::default();
+
+        // A limit set before the channel is created applies to it.
+        limits.set(&limit(2));
+        let (tx, _rx) = limits.channel::<u64>("test", ChannelConfig::new(4));
+        assert_eq!(tx.capacity(), 2);
+
+        // Limits are capped to the capacity of the channel, and at least one.
+        limits.set(&limit(16));
+        assert_eq!(tx.capacity(), 4);
+        limits.set(&limit(0));
+        assert_eq!(tx.capacity(), 1);
+
+        // Removing the limit restores the capacity of the channel.
+        limits.set(&BTreeMap::new());
+        assert_eq!(tx.capacity(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_limit_waits_below_limit() {
+        let limits = ChannelLimits::default();
+        limits.set(&limit(1));
+        let (tx, mut rx) = limits.channel("test", ChannelConfig::new(4));
+        tx.send(1).await.unwrap();
+
+        // The send waits until the queue is below the limit, although the channel has room.
+        assert!(timeout(Duration::from_secs(1), tx.send(2)).await.is_err());
+        let sender = tx.clone();
+        let pending = tokio::spawn(async move { sender.send(2).await });
+        tokio::task::yield_now().await;
+        assert!(!pending.is_finished());
+
+        // Raising the limit wakes the waiting sender.
+        limits.set(&limit(2));
+        pending.await.unwrap().unwrap();
+        assert_eq!(tx.len(), 2);
+
+        // So does receiving an event.
+        let sender = tx.clone();
+        let pending = tokio::spawn(async move { sender.send(3).await });
+        tokio::task::yield_now().await;
+        assert!(!pending.is_finished());
+        assert_eq!(rx.recv().await.unwrap().payload, 1);
+        pending.await.unwrap().unwrap();
+        assert_eq!(recv_all(&mut rx).await, [2, 3]);
+
+        // As does dropping the receiver, which fails the send.
+        tx.send(4).await.unwrap();
+        tx.send(5).await.unwrap();
+        let sender = tx.clone();
+        let pending = tokio::spawn(async move { sender.send(6).await });
+        tokio::task::yield_now().await;
+        drop(rx);
+        assert_eq!(pending.await.unwrap(), Err(SendError::Closed(6)));
+    }
+
+    #[tokio::test]
+    async fn test_limit_error_policy() {
+        let limits = ChannelLimits::default();
+        limits.set(&limit(1));
+        let config = ChannelConfig::new(4).with_policy(OverflowPolicy::Error);
+        let (tx, mut rx) = limits.channel("test", config);
+        tx.send(1).await.unwrap();
+        assert_eq!(tx.send(2).await, Err(SendError::Full(2)));
+        assert_eq!(recv_all(&mut rx).await, [1]);
+    }
+
+    #[tokio::test]
+    async fn test_limit_drop_oldest_policy() {
+        let limits = ChannelLimits::default();
+        let config = ChannelConfig::new(4).with_policy(OverflowPolicy::DropOldest);
+        let (tx, mut rx) = limits.channel("test", config);
+        for i in 1..=4 {
+            tx.send(i).await.unwrap();
+        }
+
+        // The receiver drops the oldest events beyond the limit.
+        limits.set(&limit(2));
+        assert_eq!(recv_all(&mut rx).await, [3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_limits_usage() {
+        let limits = ChannelLimits::default();
+        limits.set(&limit(2));
+        let (tx, _rx) = limits.channel("test", ChannelConfig::new(4));
+        let (other, _other_rx) = limits.channel("test", ChannelConfig::new(4));
+        let (unlimited, unlimited_rx) = limits.channel("other", ChannelConfig::new(8));
+        tx.send(1).await.unwrap();
+        other.send(2).await.unwrap();
+        unlimited.send(3).await.unwrap();
+
+        let usage = limits.usage();
+        assert_eq!(usage["test"], ChannelUsage { depth: 2, capacity: 4 });
+        assert_eq!(usage["other"], ChannelUsage { depth: 1, capacity: 8 });
+
+        // Dropped channels are not reported.
+        drop((unlimited, unlimited_rx));
+        assert!(!limits.usage().contains_key("other"));
+    }
+
+    #[tokio::test]
+    async fn test_drain() {
+        let (tx, mut rx) = channel("test", ChannelConfig::new(4));
+        for i in 1..=3 {
+            tx.send(i).await.unwrap();
+        }
+        assert_eq!(rx.drain(), [1, 2, 3]);
+        assert!(rx.is_empty());
+        assert!(rx.drain().is_empty());
+
+        // Events sent after the drain are received as usual.
+        tx.send(4).await.unwrap();
+        assert_eq!(recv_all(&mut rx).await, [4]);
+    }
+
+    #[tokio::test]
+    async fn test_drain_lossy() {
+        let config = ChannelConfig::new(2).with_policy(OverflowPolicy::DropOldest);
+        let (tx, mut rx) = channel("test", config);
+        for i in 1..=5 {
+            tx.send(i).await.unwrap();
+        }
+
+        // The overwritten events are skipped.
+        assert_eq!(rx.drain(), [4, 5]);
+        assert!(rx.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drain_wakes_senders() {
+        let limits = ChannelLimits::default();
+        limits.set(&limit(1));
+        let (tx, mut rx) = limits.channel("test", ChannelConfig::new(4));
+        tx.send(1).await.unwrap();
+        let sender = tx.clone();
+        let pending = tokio::spawn(async move { sender.send(2).await });
+        tokio::task::yield_now().await;
+        assert!(!pending.is_finished());
+
+        assert_eq!(rx.drain(), [1]);
+        pending.await.unwrap().unwrap();
+        assert_eq!(rx.drain(), [2]);
+    }
+}
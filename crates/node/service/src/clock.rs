@@ -0,0 +1,171 @@
+//! Contains the [`Clock`] abstraction used by actors for ticks, timeouts, and deadlines.
+//!
+//! Production nodes use the [`SystemClock`]. Tests and simulations can use the [`VirtualClock`],
+//! which only advances when told to, so that time-dependent behavior can be driven
+//! deterministically and faster than real time.
+
+use async_trait::async_trait;
+use std::{fmt::Debug, sync::Arc, time::Duration};
+use tokio::{sync::watch, time::Instant};
+
+/// A source of time for the rollup node's actors.
+#[async_trait]
+pub trait Clock: Debug + Send + Sync + 'static {
+    /// Returns the current [`Instant`].
+    fn now(&self) -> Instant;
+
+    /// Waits until the given deadline has been reached.
+    async fn sleep_until(&self, deadline: Instant);
+
+    /// Waits for the given duration to elapse.
+    async fn sleep(&self, duration: Duration) {
+        self.sleep_until(self.now() + duration).await;
+    }
+}
+
+/// A [`Clock`] backed by the tokio timer.
+///
+/// Because it uses [`tokio::time`], it also respects [`tokio::time::pause`] in tests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep_until(&self, deadline: Instant) {
+        tokio::time::sleep_until(deadline).await;
+    }
+}
+
+/// A [`Clock`] that only advances when [`VirtualClock::advance`] is called.
+///
+/// Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    /// The current time.
+    now: Arc<watch::Sender<Instant>>,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualClock {
+    /// Creates a new [`VirtualClock`], starting at the current [`Instant`].
+    pub fn new() -> Self {
+        Self { now: Arc::new(watch::Sender::new(Instant::now())) }
+    }
+
+    /// Advances the clock by the given duration, waking any sleepers whose deadline has passed.
+    pub fn advance(&self, duration: Duration) {
+        self.now.send_modify(|now| *now += duration);
+    }
+}
+
+#[async_trait]
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        *self.now.borrow()
+    }
+
+    async fn sleep_until(&self, deadline: Instant) {
+        let mut now = self.now.subscribe();
+        // The sender is owned by `self`, so the channel cannot close while waiting.
+        let _ = now.wait_for(|now| *now >= deadline).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Lets the spawned tasks run until they are all blocked.
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_virtual_clock_now_and_advance() {
+        let clock = VirtualClock::new();
+        let start = clock.now();
+
+        // The clock does not follow the tokio timer.
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(clock.now(), start);
+
+        // Clones share the same time.
+        let clone = clock.clone();
+        clock.advance(Duration::from_secs(3));
+        clone.advance(Duration::from_millis(500));
+        assert_eq!(clock.now(), start + Duration::from_millis(3_500));
+        assert_eq!(clone.now(), clock.now());
+    }
+
+    #[tokio::test]
+    async fn test_virtual_clock_sleep_until_wake_order() {
+        let clock = VirtualClock::new();
+        let start = clock.now();
+        let woken = Arc::new(Mutex::new(Vec::new()));
+        for secs in [3, 1, 2, 2] {
+            let (clock, woken) = (clock.clone(), woken.clone());
+            tokio::spawn(async move {
+                clock.sleep_until(start + Duration::from_secs(secs)).await;
+                woken.lock().unwrap().push(secs);
+            });
+        }
+        settle().await;
+        assert!(woken.lock().unwrap().is_empty());
+
+        // Sleepers wake once their deadline is reached, in the order of their deadlines.
+        clock.advance(Duration::from_millis(999));
+        settle().await;
+        assert!(woken.lock().unwrap().is_empty());
+        clock.advance(Duration::from_millis(1));
+        settle().await;
+        assert_eq!(*woken.lock().unwrap(), [1]);
+        clock.advance(Duration::from_secs(1));
+        settle().await;
+        assert_eq!(*woken.lock().unwrap(), [1, 2, 2]);
+
+        // A single advance past several deadlines wakes all of their sleepers.
+        clock.advance(Duration::from_secs(5));
+        settle().await;
+        assert_eq!(*woken.lock().unwrap(), [1, 2, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_virtual_clock_sleep_past_deadline() {
+        let clock = VirtualClock::new();
+        let start = clock.now();
+        clock.advance(Duration::from_secs(1));
+
+        // Deadlines that have passed are reached immediately.
+        clock.sleep_until(start).await;
+        clock.sleep(Duration::ZERO).await;
+
+        let sleeper = tokio::spawn({
+            let clock = clock.clone();
+            async move { clock.sleep(Duration::from_secs(2)).await }
+        });
+        settle().await;
+        assert!(!sleeper.is_finished());
+        clock.advance(Duration::from_secs(2));
+        sleeper.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_system_clock_follows_tokio_time() {
+        let clock = SystemClock;
+        let start = clock.now();
+        clock.sleep(Duration::from_secs(5)).await;
+        assert_eq!(clock.now(), start + Duration::from_secs(5));
+    }
+}
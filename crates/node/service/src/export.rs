@@ -0,0 +1,266 @@
+//! Export of the node's chain events to message queues.
+//!
+//! The [`ChainEventExporter`] is a [`NodeExtension`] that publishes the payload attributes
+//! derived by the node, the updates of its unsafe and safe heads, and the reorgs it observes to a
+//! [`ChainEventSink`], so that data pipelines can consume the chain as a stream of events rather
+//! than by polling the node's RPC.
+//!
+//! Each event is serialized as a JSON [`ExportedEvent`], and published under the subject (or
+//! topic) `<prefix>.<event kind>`, e.g. `kona.attributes`. The sinks backed by NATS and Kafka are
+//! available behind the `export-nats` and `export-kafka` features respectively.
+//!
+//! The exporter never holds back the node: it receives the derived attributes through the
+//! [`AttributesFanout`], which drops the oldest attributes if the exporter falls behind, and
+//! failures to publish an event are logged and counted in [`Metrics::EXPORT_FAILURES`], but never
+//! fail the node.
+//!
+//! [`AttributesFanout`]: crate::AttributesFanout
+
+use crate::{Metrics, NodeExtension, NodeHandles};
+use async_trait::async_trait;
+use kona_protocol::{L2BlockInfo, OpAttributesWithParent};
+use kona_rpc::ReorgEvent;
+use std::{error::Error, fmt::Debug};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+/// The default number of derived attributes queued for the exporter before the oldest are
+/// dropped.
+pub const DEFAULT_EXPORT_CAPACITY: usize = 256;
+
+/// A chain event published by the [`ChainEventExporter`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ChainEvent {
+    /// Payload attributes derived from L1.
+    Attributes {
+        /// The id of the last reset applied to the pipeline when the attributes were derived.
+        reset_id: u64,
+        /// The derived attributes.
+        attributes: OpAttributesWithParent,
+    },
+    /// The unsafe head was updated, by a block built by the sequencer, received over gossip, or
+    /// derived.
+    UnsafeHead {
+        /// The new unsafe head.
+        block: L2BlockInfo,
+    },
+    /// The safe head was updated.
+    SafeHead {
+        /// The new safe head.
+        block: L2BlockInfo,
+    },
+    /// A reorg observed by the node.
+    Reorg {
+        /// The reorg.
+        reorg: ReorgEvent,
+    },
+}
+
+impl ChainEvent {
+    /// Returns the kind of the event, which is the suffix of the subject it is published under.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Attributes { .. } => "attributes",
+            Self::UnsafeHead { .. } => "unsafe_head",
+            Self::SafeHead { .. } => "safe_head",
+            Self::Reorg { .. } => "reorg",
+        }
+    }
+}
+
+/// The envelope of a [`ChainEvent`], as serialized to the [`ChainEventSink`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedEvent {
+    /// The L2 chain id.
+    pub chain_id: u64,
+    /// The event.
+    #[serde(flatten)]
+    pub event: ChainEvent,
+}
+
+/// A message queue that the [`ChainEventExporter`] publishes events to.
+#[async_trait]
+pub trait ChainEventSink: Debug + Send + Sync + 'static {
+    /// Publishes the given serialized event under the given subject.
+    async fn publish(
+        &self,
+        subject: &str,
+        payload: Vec<u8>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// The configuration of the [`ChainEventExporter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportConfig {
+    /// The L2 chain id, included in each event.
+    pub chain_id: u64,
+    /// The prefix of the subjects the events are published under.
+    pub prefix: String,
+    /// The number of derived attributes queued for the exporter before the oldest are dropped.
+    pub capacity: usize,
+}
+
+/// A [`NodeExtension`] that publishes the node's [`ChainEvent`]s to a [`ChainEventSink`].
+#[derive(Debug)]
+pub struct ChainEventExporter<S> {
+    /// The sink the events are published to.
+    sink: S,
+    /// The [`ExportConfig`].
+    config: ExportConfig,
+}
+
+impl<S: ChainEventSink> ChainEventExporter<S> {
+    /// Creates a new [`ChainEventExporter`] publishing to the given sink.
+    pub const fn new(sink: S, config: ExportConfig) -> Self {
+        Self { sink, config }
+    }
+
+    /// Returns the subject the given event is published under.
+    pub fn subject(&self, event: &ChainEvent) -> String {
+        format!("{}.{}", self.config.prefix, event.kind())
+    }
+
+    /// Publishes the given event. Failures are logged and counted, but never returned.
+    async fn export(&self, event: ChainEvent) {
+        let subject = self.subject(&event);
+        let kind = event.kind();
+        let envelope = ExportedEvent { chain_id: self.config.chain_id, event };
+        let res = match serde_json::to_vec(&envelope) {
+            Ok(payload) => self.sink.publish(&subject, payload).await,
+            Err(e) => Err(e.into()),
+        };
+        match res {
+            Ok(()) => {
+                trace!(target: "export", %subject, "Exported chain event");
+                kona_macros::inc!(counter, Metrics::EXPORTED_EVENTS, "kind" => kind);
+            }
+            Err(err) => {
+                warn!(target: "export", %subject, %err, "Failed to export chain event");
+                kona_macros::inc!(counter, Metrics::EXPORT_FAILURES, "kind" => kind);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: ChainEventSink> NodeExtension for ChainEventExporter<S> {
+    fn name(&self) -> &'static str {
+        "exporter"
+    }
+
+    async fn run(
+        self: Box<Self>,
+        handles: NodeHandles,
+        cancellation: CancellationToken,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut attributes =
+            handles.derived_attributes.subscribe("exported_attributes", self.config.capacity);
+        let mut reorgs = handles.reorgs.subscribe();
+        let mut unsafe_head = handles.unsafe_head;
+        let mut safe_head = handles.safe_head;
+
+        loop {
+            let event = tokio::select! {
+                _ = cancellation.cancelled() => return Ok(()),
+                Some(event) = attributes.recv() => ChainEvent::Attributes {
+                    reset_id: event.payload.reset_id,
+                    attributes: event.payload.attributes,
+                },
+                reorg = reorgs.recv() => match reorg {
+                    Ok(reorg) => ChainEvent::Reorg { reorg },
+                    Err(RecvError::Lagged(dropped)) => {
+                        warn!(target: "export", dropped, "Dropped reorg events");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => {
+                        cancellation.cancelled().await;
+                        return Ok(());
+                    }
+                },
+                Ok(()) = unsafe_head.changed() => {
+                    ChainEvent::UnsafeHead { block: *unsafe_head.borrow_and_update() }
+                }
+                Ok(()) = safe_head.changed() => {
+                    ChainEvent::SafeHead { block: *safe_head.borrow_and_update() }
+                }
+            };
+            self.export(event).await;
+        }
+    }
+}
+
+/// A [`ChainEventSink`] publishing to a NATS server.
+#[cfg(feature = "export-nats")]
+#[derive(Debug, Clone)]
+pub struct NatsSink {
+    /// The NATS client.
+    client: async_nats::Client,
+}
+
+#[cfg(feature = "export-nats")]
+impl NatsSink {
+    /// Connects to the NATS server at the given address, e.g. `nats://localhost:4222`.
+    pub async fn connect(addr: &str) -> Result<Self, async_nats::ConnectError> {
+        Ok(Self { client: async_nats::connect(addr).await? })
+    }
+}
+
+#[cfg(feature = "export-nats")]
+#[async_trait]
+impl ChainEventSink for NatsSink {
+    async fn publish(
+        &self,
+        subject: &str,
+        payload: Vec<u8>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.client.publish(subject.to_string(), payload.into()).await?;
+        Ok(())
+    }
+}
+
+/// A [`ChainEventSink`] publishing to Kafka topics named after the subjects of the events.
+#[cfg(feature = "export-kafka")]
+pub struct KafkaSink {
+    /// The Kafka producer.
+    producer: rdkafka::producer::FutureProducer,
+}
+
+#[cfg(feature = "export-kafka")]
+impl Debug for KafkaSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaSink").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "export-kafka")]
+impl KafkaSink {
+    /// The time a message may wait to be delivered before its publication fails.
+    const MESSAGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+    /// Creates a producer for the Kafka cluster with the given comma-separated bootstrap brokers,
+    /// e.g. `localhost:9092`.
+    pub fn new(brokers: &str) -> Result<Self, rdkafka::error::KafkaError> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("message.timeout.ms", Self::MESSAGE_TIMEOUT.as_millis().to_string())
+            .create()?;
+        Ok(Self { producer })
+    }
+}
+
+#[cfg(feature = "export-kafka")]
+#[async_trait]
+impl ChainEventSink for KafkaSink {
+    async fn publish(
+        &self,
+        subject: &str,
+        payload: Vec<u8>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let record = rdkafka::producer::FutureRecord::<(), _>::to(subject).payload(&payload);
+        self.producer.send(record, Self::MESSAGE_TIMEOUT).await.map_err(|(e, _)| e)?;
+        Ok(())
+    }
+}
+
@@ -0,0 +1,306 @@
+//! An append-only, on-disk journal of key node events, for post-incident analysis.
+//!
+//! The [`EventJournal`] records resets, reorgs, invalid payloads, sequencer starts and stops,
+//! supervisor updates, interop dependency set updates, shadow divergences, safe blocks that do
+//! not match their derived attributes, and rejected batcher transactions as [`JournalEntry`]s,
+//! one JSON object per line. The journal is bounded: once the journal file reaches half of the
+//! configured maximum size, it is rotated to a single `<path>.1` backup, which replaces any
+//! previous backup.
+//!
+//! Failures to write the journal are logged, but never fail the node.
+
+use derive_more::Display;
+use kona_derive::{BatcherTxObserver, RejectedBatcherTx};
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The kind of a [`JournalEntry`].
+#[derive(
+    Debug, Display, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalEventKind {
+    /// The engine was reset, and the derivation pipeline reset to the new safe head.
+    #[display("reset")]
+    Reset,
+    /// The L1 chain, the unsafe chain or the safe chain was reorged.
+    #[display("reorg")]
+    Reorg,
+    /// A payload was marked invalid by the execution layer.
+    #[display("invalid_payload")]
+    InvalidPayload,
+    /// The sequencer started building blocks.
+    #[display("sequencer_started")]
+    SequencerStarted,
+    /// The sequencer stopped building blocks.
+    #[display("sequencer_stopped")]
+    SequencerStopped,
+    /// A control event was received from the supervisor.
+    #[display("supervisor_update")]
+    SupervisorUpdate,
+    /// The interop dependency set was updated at runtime.
+    #[display("dependency_set_update")]
+    DependencySetUpdate,
+    /// A safe block diverged from the reference node in shadow mode.
+    #[display("shadow_divergence")]
+    ShadowDivergence,
+    /// A safe block held by the execution layer did not match its derived attributes.
+    #[display("consistency_mismatch")]
+    ConsistencyMismatch,
+    /// A candidate batcher transaction was rejected by the derivation pipeline.
+    #[display("batcher_tx_rejected")]
+    BatcherTxRejected,
+}
+
+impl JournalEventKind {
+    /// Contains all journal event kinds.
+    pub const KINDS: [Self; 10] = [
+        Self::Reset,
+        Self::Reorg,
+        Self::InvalidPayload,
+        Self::SequencerStarted,
+        Self::SequencerStopped,
+        Self::SupervisorUpdate,
+        Self::DependencySetUpdate,
+        Self::ShadowDivergence,
+        Self::ConsistencyMismatch,
+        Self::BatcherTxRejected,
+    ];
+}
+
+impl FromStr for JournalEventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::KINDS
+            .into_iter()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| format!("invalid journal event kind: {s}"))
+    }
+}
+
+/// An event recorded in the [`EventJournal`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JournalEntry {
+    /// The time the event was recorded at, in milliseconds since the unix epoch.
+    pub timestamp: u64,
+    /// The kind of the event.
+    pub kind: JournalEventKind,
+    /// A human-readable description of the event.
+    pub message: String,
+    /// The context of the event, e.g. the affected blocks.
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub context: serde_json::Value,
+}
+
+/// A handle to the node's event journal.
+///
+/// The handle is cheap to clone, and shared between the actors that record events. A
+/// [`EventJournal::disabled`] journal discards all events.
+#[derive(Debug, Clone, Default)]
+pub struct EventJournal {
+    /// The journal file, if the journal is enabled.
+    inner: Option<Arc<Mutex<JournalFile>>>,
+}
+
+/// The journal file written by an [`EventJournal`].
+#[derive(Debug)]
+struct JournalFile {
+    /// The path of the journal file.
+    path: PathBuf,
+    /// The open journal file.
+    file: File,
+    /// The current size of the journal file, in bytes.
+    size: u64,
+    /// The size at which the journal file is rotated, in bytes.
+    rotate_at: u64,
+}
+
+impl JournalFile {
+    /// Appends the given line to the journal file, rotating it first if it would grow past
+    /// `rotate_at`.
+    fn append(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 > self.rotate_at {
+            fs::rename(&self.path, rotated_path(&self.path))?;
+            self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+            self.size = 0;
+        }
+        self.file.write_all(line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+impl EventJournal {
+    /// Returns a journal that discards all events.
+    pub const fn disabled() -> Self {
+        Self { inner: None }
+    }
+
+    /// Opens the journal at the given path, appending to it if it exists. The journal and its
+    /// rotated backup together use at most `max_size` bytes.
+    pub fn open(path: impl Into<PathBuf>, max_size: u64) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        let journal = JournalFile { path, file, size, rotate_at: max_size / 2 };
+        Ok(Self { inner: Some(Arc::new(Mutex::new(journal))) })
+    }
+
+    /// Returns `true` if the journal records events.
+    pub const fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Records an event of the given kind, with a description and its context.
+    pub fn record(
+        &self,
+        kind: JournalEventKind,
+        message: impl Into<String>,
+        context: serde_json::Value,
+    ) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let entry = JournalEntry { timestamp, kind, message: message.into(), context };
+
+        let mut line = match serde_json::to_vec(&entry) {
+            Ok(line) => line,
+            Err(err) => {
+                warn!(target: "journal", ?err, %kind, "Failed to serialize journal entry");
+                return;
+            }
+        };
+        line.push(b'\n');
+        let mut journal = inner.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(err) = journal.append(&line) {
+            warn!(target: "journal", ?err, %kind, "Failed to write journal entry");
+        }
+    }
+
+    /// Reads all entries of the journal at the given path, oldest first, including those in its
+    /// rotated backup. Lines that cannot be parsed, e.g. a line truncated by a crash, are skipped.
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Vec<JournalEntry>> {
+        let path = path.as_ref();
+        let mut entries = Vec::new();
+        for path in [rotated_path(path), path.to_path_buf()] {
+            let file = match File::open(&path) {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            for line in BufReader::new(file).lines() {
+                if let Ok(entry) = serde_json::from_str(&line?) {
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+impl BatcherTxObserver for EventJournal {
+    fn rejected(&self, tx: &RejectedBatcherTx) {
+        self.record(
+            JournalEventKind::BatcherTxRejected,
+            format!("Rejected candidate batcher transaction {}: {}", tx.tx_hash, tx.reason),
+            serde_json::json!({
+                "block": tx.block,
+                "tx_hash": tx.tx_hash,
+                "reason": tx.reason.as_str(),
+                "from": tx.from,
+                "to": tx.to,
+                "inbox": tx.inbox,
+                "batcher": tx.batcher,
+            }),
+        );
+    }
+}
+
+/// Returns the path of the rotated backup of the journal at the given path.
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = OsString::from(path.as_os_str());
+    rotated.push(".1");
+    rotated.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records an `event {i}` into the journal for each index of the given range.
+    fn record(journal: &EventJournal, events: std::ops::Range<usize>) {
+        for i in events {
+            journal.record(JournalEventKind::Reset, format!("event {i}"), serde_json::Value::Null);
+        }
+    }
+
+    fn messages(path: &Path) -> Vec<String> {
+        EventJournal::read(path).unwrap().into_iter().map(|entry| entry.message).collect()
+    }
+
+    /// Returns the size of a journal line recorded by [`record`].
+    fn line_size(dir: &Path) -> u64 {
+        let path = dir.join("line.jsonl");
+        record(&EventJournal::open(&path, u64::MAX).unwrap(), 0..1);
+        fs::metadata(path).unwrap().len()
+    }
+
+    #[test]
+    fn test_journal_rotates_at_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let line = line_size(dir.path());
+        let path = dir.path().join("journal.jsonl");
+
+        // The journal and its backup hold two lines each.
+        let journal = EventJournal::open(&path, 4 * line).unwrap();
+        record(&journal, 0..2);
+        assert!(!rotated_path(&path).exists());
+        assert_eq!(fs::metadata(&path).unwrap().len(), 2 * line);
+
+        record(&journal, 2..3);
+        assert_eq!(fs::metadata(rotated_path(&path)).unwrap().len(), 2 * line);
+        assert_eq!(fs::metadata(&path).unwrap().len(), line);
+
+        // Rotating again replaces the previous backup, dropping the oldest events.
+        record(&journal, 3..10);
+        assert_eq!(fs::metadata(rotated_path(&path)).unwrap().len(), 2 * line);
+        assert_eq!(fs::metadata(&path).unwrap().len(), 2 * line);
+        assert_eq!(messages(&path), ["event 6", "event 7", "event 8", "event 9"]);
+    }
+
+    #[test]
+    fn test_journal_read_across_rotated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let line = line_size(dir.path());
+        let path = dir.path().join("journal.jsonl");
+        assert!(messages(&path).is_empty());
+
+        record(&EventJournal::open(&path, 4 * line).unwrap(), 0..3);
+        assert_eq!(messages(&path), ["event 0", "event 1", "event 2"]);
+
+        // A reopened journal appends to the existing file, and counts it towards the size limit.
+        record(&EventJournal::open(&path, 4 * line).unwrap(), 3..4);
+        assert_eq!(fs::metadata(&path).unwrap().len(), 2 * line);
+        record(&EventJournal::open(&path, 4 * line).unwrap(), 4..5);
+        assert_eq!(messages(&path), ["event 2", "event 3", "event 4"]);
+
+        // A line truncated by a crash is skipped.
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"timestamp\"").unwrap();
+        assert_eq!(messages(&path), ["event 2", "event 3", "event 4"]);
+    }
+}
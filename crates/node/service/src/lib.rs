@@ -11,22 +11,69 @@ extern crate tracing;
 
 mod service;
 pub use service::{
-    InteropMode, NodeMode, RollupNode, RollupNodeBuilder, RollupNodeError, RollupNodeService,
+    DEFAULT_SHUTDOWN_GRACE_PERIOD, InteropMode, NodeExtension, NodeHandles, NodeMode, RollupNode,
+    RollupNodeBuilder, RollupNodeError, RollupNodeService, ShutdownCoordinator, ShutdownStage,
 };
 
 mod actors;
 pub use actors::{
-    CancellableContext, DerivationActor, DerivationContext, DerivationError,
-    DerivationOutboundChannels, DerivationState, EngineActor, EngineActorState, EngineContext,
-    EngineError, EngineLauncher, EngineOutboundData, InboundDerivationMessage, L1OriginSelector,
-    L1OriginSelectorError, L1WatcherRpc, L1WatcherRpcContext, L1WatcherRpcError,
-    L1WatcherRpcOutboundChannels, L1WatcherRpcState, L2Finalizer, NetworkActor, NetworkActorError,
-    NetworkContext, NetworkOutboundData, NodeActor, RpcActor, RpcActorError, RpcContext,
-    RuntimeActor, RuntimeContext, RuntimeOutboundData, RuntimeState, SequencerActor,
-    SequencerActorError, SequencerActorState, SequencerContext, SequencerOutboundData,
+    ActorMeter, ActorStatsSampler, AttributesFanout, BackfillActor, BackfillConfig,
+    BackfillContext, BackfillError, BackfillState, CHALLENGE_STATUS_CHANGED, CancellableContext,
+    ChainHaltCause, ChainHaltConfig, ChainHaltContext, ChainHaltState, ChainHaltWatchdog,
+    ChallengeStatus, DEFAULT_ACTOR_STATS_INTERVAL, DEFAULT_RESET_TIMEOUT, DaChallengeConfigError,
+    DaChallengeGate, DaChallengeUpdate, DerivationActor, DerivationContext, DerivationError,
+    DerivationOutboundChannels, DerivationProgress, DerivationState, DerivedAttributes,
+    EngineActor, EngineActorState, EngineContext, EngineError, EngineHeadSenders, EngineLauncher,
+    EngineOutboundData, HealthReporter, InboundDerivationMessage, L1PollIntervals, L1WatcherRpc,
+    L1WatcherRpcContext, L1WatcherRpcError, L1WatcherRpcOutboundChannels, L1WatcherRpcState,
+    L2Finalizer, NodeActor, ResetCoordinator, ResetPhase, RestartConfig, RpcActor, RpcActorError,
+    RpcContext, RuntimeActor, RuntimeContext, RuntimeOutboundData, RuntimeState,
+    SafeHeadHintSource, ShadowActor, ShadowConfig, ShadowContext, ShadowDivergence, ShadowError,
+    ShadowOutboundData, ShadowState,
+};
+#[cfg(feature = "sequencer")]
+pub use actors::{
+    L1OriginSelector, L1OriginSelectorError, SequencerActor, SequencerActorError,
+    SequencerActorState, SequencerContext, SequencerOutboundData,
+};
+#[cfg(feature = "p2p")]
+pub use actors::{NetworkActor, NetworkActorError, NetworkActorState, NetworkContext};
+#[cfg(feature = "interop")]
+pub use actors::{
     SupervisorActor, SupervisorActorContext, SupervisorActorError, SupervisorExt,
     SupervisorOutboundData, SupervisorRpcServerExt,
 };
 
+pub mod bus;
+
+mod journal;
+pub use journal::{EventJournal, JournalEntry, JournalEventKind};
+
+mod submission;
+pub use submission::SubmissionTracker;
+
+#[cfg(feature = "export")]
+mod export;
+#[cfg(feature = "export-kafka")]
+pub use export::KafkaSink;
+#[cfg(feature = "export-nats")]
+pub use export::NatsSink;
+#[cfg(feature = "export")]
+pub use export::{
+    ChainEvent, ChainEventExporter, ChainEventSink, DEFAULT_EXPORT_CAPACITY, ExportConfig,
+    ExportedEvent,
+};
+
+mod range_backfill;
+pub use range_backfill::{
+    EpochDeriver, EpochRange, RangeBackfill, RangeBackfillConfig, RangeBackfillError,
+    RangeBackfillSummary,
+};
+
+mod clock;
+pub use clock::{Clock, SystemClock, VirtualClock};
+
 mod metrics;
-pub use metrics::Metrics;
+pub use metrics::{CHAIN_ID_LABEL, Metrics, current_chain_id, sync_with_chain_id, with_chain_id};
+#[cfg(feature = "metrics")]
+pub use metrics::{ChainLabelLayer, ChainLabelRecorder};
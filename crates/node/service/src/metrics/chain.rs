@@ -0,0 +1,285 @@
+//! Labels the metrics of each rollup node in a process with its chain id.
+//!
+//! When multiple rollup nodes are embedded in one process, e.g. in an interop devnet, they share
+//! the global metrics recorder. Each node runs its actors within a [`with_chain_id`] scope, and
+//! the [`ChainLabelLayer`] adds a [`CHAIN_ID_LABEL`] to every metric registered within it, so
+//! that the metrics of different chains are exported as different series rather than colliding.
+//! This covers the metrics of every crate the actors call into, including those recorded with the
+//! `kona_macros` macros in the derivation pipeline and the engine.
+//!
+//! The [`ChainLabelLayer`] must be installed by the process that embeds the nodes, e.g. with
+//! `kona_cli::init_prometheus_server_layered`. Processes that run a single node can label all
+//! metrics with a constant label instead.
+//!
+//! Metrics recorded outside of a scope, e.g. from tasks spawned by an actor, are not labeled.
+
+use std::future::Future;
+use tokio::task::futures::TaskLocalFuture;
+
+/// The label that metrics recorded within a [`with_chain_id`] scope are labeled with.
+pub const CHAIN_ID_LABEL: &str = "chain_id";
+
+tokio::task_local! {
+    /// The chain id of the rollup node that the current task belongs to.
+    static CHAIN_ID: u64;
+}
+
+/// Runs the given future within the scope of the given chain id, so that the metrics it records
+/// are labeled with it by the [`ChainLabelLayer`].
+pub fn with_chain_id<F: Future>(chain_id: u64, future: F) -> TaskLocalFuture<u64, F> {
+    CHAIN_ID.scope(chain_id, future)
+}
+
+/// Runs the given closure within the scope of the given chain id. See [`with_chain_id`].
+pub fn sync_with_chain_id<R>(chain_id: u64, f: impl FnOnce() -> R) -> R {
+    CHAIN_ID.sync_scope(chain_id, f)
+}
+
+/// Returns the chain id of the current [`with_chain_id`] scope, if any.
+pub fn current_chain_id() -> Option<u64> {
+    CHAIN_ID.try_with(|chain_id| *chain_id).ok()
+}
+
+/// A [`Layer`] that wraps a [`Recorder`] in a [`ChainLabelRecorder`].
+///
+/// [`Layer`]: metrics_util::layers::Layer
+/// [`Recorder`]: metrics::Recorder
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ChainLabelLayer;
+
+#[cfg(feature = "metrics")]
+impl<R> metrics_util::layers::Layer<R> for ChainLabelLayer {
+    type Output = ChainLabelRecorder<R>;
+
+    fn layer(&self, inner: R) -> Self::Output {
+        ChainLabelRecorder { inner }
+    }
+}
+
+/// A [`Recorder`] that labels the metrics registered within a [`with_chain_id`] scope with the
+/// [`CHAIN_ID_LABEL`], unless they already carry one.
+///
+/// [`Recorder`]: metrics::Recorder
+#[cfg(feature = "metrics")]
+#[derive(Debug)]
+pub struct ChainLabelRecorder<R> {
+    /// The wrapped recorder.
+    inner: R,
+}
+
+#[cfg(feature = "metrics")]
+impl<R> ChainLabelRecorder<R> {
+    /// Returns the given key labeled with the chain id of the current scope, if it needs one.
+    fn labeled(key: &metrics::Key) -> std::borrow::Cow<'_, metrics::Key> {
+        match current_chain_id() {
+            Some(chain_id) if !key.labels().any(|label| label.key() == CHAIN_ID_LABEL) => {
+                let label = metrics::Label::new(CHAIN_ID_LABEL, chain_id.to_string());
+                std::borrow::Cow::Owned(key.with_extra_labels(vec![label]))
+            }
+            _ => std::borrow::Cow::Borrowed(key),
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl<R: metrics::Recorder> metrics::Recorder for ChainLabelRecorder<R> {
+    fn describe_counter(
+        &self,
+        key: metrics::KeyName,
+        unit: Option<metrics::Unit>,
+        description: metrics::SharedString,
+    ) {
+        self.inner.describe_counter(key, unit, description)
+    }
+
+    fn describe_gauge(
+        &self,
+        key: metrics::KeyName,
+        unit: Option<metrics::Unit>,
+        description: metrics::SharedString,
+    ) {
+        self.inner.describe_gauge(key, unit, description)
+    }
+
+    fn describe_histogram(
+        &self,
+        key: metrics::KeyName,
+        unit: Option<metrics::Unit>,
+        description: metrics::SharedString,
+    ) {
+        self.inner.describe_histogram(key, unit, description)
+    }
+
+    fn register_counter(
+        &self,
+        key: &metrics::Key,
+        metadata: &metrics::Metadata<'_>,
+    ) -> metrics::Counter {
+        self.inner.register_counter(&Self::labeled(key), metadata)
+    }
+
+    fn register_gauge(
+        &self,
+        key: &metrics::Key,
+        metadata: &metrics::Metadata<'_>,
+    ) -> metrics::Gauge {
+        self.inner.register_gauge(&Self::labeled(key), metadata)
+    }
+
+    fn register_histogram(
+        &self,
+        key: &metrics::Key,
+        metadata: &metrics::Metadata<'_>,
+    ) -> metrics::Histogram {
+        self.inner.register_histogram(&Self::labeled(key), metadata)
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use metrics_util::layers::Layer;
+    use std::sync::{Arc, Mutex};
+
+    /// A [`metrics::Recorder`] that records the keys of the registered metrics.
+    #[derive(Debug, Default, Clone)]
+    struct KeyRecorder(Arc<Mutex<Vec<metrics::Key>>>);
+
+    impl KeyRecorder {
+        /// Returns the labels of the registered metrics, by name.
+        fn labels(&self) -> Vec<(String, Vec<(String, String)>)> {
+            self.0
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|key| {
+                    let labels = key
+                        .labels()
+                        .map(|label| (label.key().to_string(), label.value().to_string()))
+                        .collect();
+                    (key.name().to_string(), labels)
+                })
+                .collect()
+        }
+    }
+
+    impl metrics::Recorder for KeyRecorder {
+        fn describe_counter(
+            &self,
+            _: metrics::KeyName,
+            _: Option<metrics::Unit>,
+            _: metrics::SharedString,
+        ) {
+        }
+
+        fn describe_gauge(
+            &self,
+            _: metrics::KeyName,
+            _: Option<metrics::Unit>,
+            _: metrics::SharedString,
+        ) {
+        }
+
+        fn describe_histogram(
+            &self,
+            _: metrics::KeyName,
+            _: Option<metrics::Unit>,
+            _: metrics::SharedString,
+        ) {
+        }
+
+        fn register_counter(
+            &self,
+            key: &metrics::Key,
+            _: &metrics::Metadata<'_>,
+        ) -> metrics::Counter {
+            self.0.lock().unwrap().push(key.clone());
+            metrics::Counter::noop()
+        }
+
+        fn register_gauge(&self, key: &metrics::Key, _: &metrics::Metadata<'_>) -> metrics::Gauge {
+            self.0.lock().unwrap().push(key.clone());
+            metrics::Gauge::noop()
+        }
+
+        fn register_histogram(
+            &self,
+            key: &metrics::Key,
+            _: &metrics::Metadata<'_>,
+        ) -> metrics::Histogram {
+            self.0.lock().unwrap().push(key.clone());
+            metrics::Histogram::noop()
+        }
+    }
+
+    /// Returns the label of the given chain id.
+    fn chain_label(chain_id: &str) -> Vec<(String, String)> {
+        vec![(CHAIN_ID_LABEL.to_string(), chain_id.to_string())]
+    }
+
+    #[tokio::test]
+    async fn test_metrics_labeled_within_scope() {
+        let keys = KeyRecorder::default();
+        let recorder = ChainLabelLayer.layer(keys.clone());
+
+        with_chain_id(10, async {
+            assert_eq!(current_chain_id(), Some(10));
+            metrics::with_local_recorder(&recorder, || {
+                metrics::counter!("counter").increment(1);
+                metrics::gauge!("gauge").set(1.0);
+                metrics::histogram!("histogram").record(1.0);
+            });
+        })
+        .await;
+        sync_with_chain_id(8453, || {
+            metrics::with_local_recorder(&recorder, || metrics::counter!("counter").increment(1))
+        });
+
+        assert_eq!(
+            keys.labels(),
+            [
+                ("counter".to_string(), chain_label("10")),
+                ("gauge".to_string(), chain_label("10")),
+                ("histogram".to_string(), chain_label("10")),
+                ("counter".to_string(), chain_label("8453")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_existing_chain_label_kept() {
+        let keys = KeyRecorder::default();
+        let recorder = ChainLabelLayer.layer(keys.clone());
+
+        sync_with_chain_id(10, || {
+            metrics::with_local_recorder(&recorder, || {
+                metrics::counter!("counter", CHAIN_ID_LABEL => "7", "kind" => "test").increment(1)
+            })
+        });
+
+        let labels = [(CHAIN_ID_LABEL, "7"), ("kind", "test")]
+            .map(|(key, value)| (key.to_string(), value.to_string()));
+        assert_eq!(keys.labels(), [("counter".to_string(), labels.to_vec())]);
+    }
+
+    #[test]
+    fn test_metrics_unlabeled_outside_scope() {
+        let keys = KeyRecorder::default();
+        let recorder = ChainLabelLayer.layer(keys.clone());
+
+        assert_eq!(current_chain_id(), None);
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!("counter").increment(1);
+            metrics::gauge!("gauge", "kind" => "test").set(1.0);
+        });
+
+        assert_eq!(
+            keys.labels(),
+            [
+                ("counter".to_string(), vec![]),
+                ("gauge".to_string(), vec![("kind".to_string(), "test".to_string())]),
+            ]
+        );
+    }
+}
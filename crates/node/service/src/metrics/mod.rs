@@ -1,5 +1,10 @@
 //! Metrics for the node service
 
+mod chain;
+pub use chain::{CHAIN_ID_LABEL, current_chain_id, sync_with_chain_id, with_chain_id};
+#[cfg(feature = "metrics")]
+pub use chain::{ChainLabelLayer, ChainLabelRecorder};
+
 /// Container for metrics.
 #[derive(Debug, Clone)]
 pub struct Metrics;
@@ -14,6 +19,101 @@ impl Metrics {
     /// Identifier for the counter of critical derivation errors (strictly for alerting.)
     pub const DERIVATION_CRITICAL_ERROR: &str = "kona_node_derivation_critical_errors";
 
+    /// Identifier for the gauge that tracks the number of events queued in an actor channel.
+    pub const CHANNEL_QUEUE_DEPTH: &str = "kona_node_channel_queue_depth";
+
+    /// Identifier for the counter of sends that found an actor channel full.
+    pub const CHANNEL_SATURATED: &str = "kona_node_channel_saturated";
+
+    /// Identifier for the counter of events dropped from a full actor channel.
+    pub const CHANNEL_DROPPED_EVENTS: &str = "kona_node_channel_dropped_events";
+
+    /// Identifier for the gauge that tracks the effective capacity of an actor channel.
+    pub const CHANNEL_CAPACITY: &str = "kona_node_channel_capacity";
+
+    /// Identifier for the gauge that tracks whether an actor is healthy (`1`) or not (`0`).
+    pub const ACTOR_HEALTH: &str = "kona_node_actor_health";
+
+    /// Identifier for the gauge that tracks the select-loop iterations per second of an actor.
+    pub const ACTOR_ITERATION_RATE: &str = "kona_node_actor_iteration_rate";
+
+    /// Identifier for the gauge that tracks the fraction of time an actor spends busy in its
+    /// select loop.
+    pub const ACTOR_BUSY_RATIO: &str = "kona_node_actor_busy_ratio";
+
+    /// Identifier for the gauge that tracks the last safe block verified by the backfill actor.
+    pub const BACKFILL_VERIFIED_BLOCK: &str = "kona_node_backfill_verified_block";
+
+    /// Identifier for the counter of safe blocks that diverged when re-derived by the backfill
+    /// actor (strictly for alerting.)
+    pub const BACKFILL_DIVERGENCE_COUNT: &str = "kona_node_backfill_divergences";
+
+    /// Identifier for the gauge that tracks the last safe block found to match the reference node
+    /// by the shadow actor.
+    pub const SHADOW_VERIFIED_BLOCK: &str = "kona_node_shadow_verified_block";
+
+    /// Identifier for the counter of safe blocks that diverged from the reference node, as found
+    /// by the shadow actor (strictly for alerting.)
+    pub const SHADOW_DIVERGENCE_COUNT: &str = "kona_node_shadow_divergences";
+
+    /// Identifier for the gauge that tracks the number of blocks the safe head of the reference
+    /// node is ahead of the node's safe head, as observed by the shadow actor.
+    pub const SHADOW_SAFE_HEAD_LAG: &str = "kona_node_shadow_safe_head_lag";
+
+    /// Identifier for the counter of safe blocks held by the execution layer that did not match
+    /// their derived attributes, by mismatching field (strictly for alerting.)
+    pub const SAFE_BLOCK_INCONSISTENCY_COUNT: &str = "kona_node_safe_block_inconsistencies";
+
+    /// Identifier for the gauge that tracks whether the chain is halted (`1`) or not (`0`),
+    /// labeled by the likely cause of the halt (strictly for alerting.)
+    pub const CHAIN_HALT: &str = "kona_node_chain_halt";
+
+    /// Identifier for the histogram that tracks the time from the L1 inclusion of a batch to the
+    /// promotion of the L2 block derived from it to safe, in seconds.
+    pub const SAFE_PROMOTION_LATENCY: &str = "kona_node_safe_promotion_latency_seconds";
+
+    /// Identifier for the histogram that tracks the time from the timestamp of an unsafe block to
+    /// its import, in seconds.
+    pub const UNSAFE_IMPORT_LATENCY: &str = "kona_node_unsafe_import_latency_seconds";
+
+    /// Identifier for the counter of derived payloads that were replaced by a deposits-only
+    /// payload after being rejected by the execution layer.
+    pub const DEPOSITS_ONLY_FALLBACK_COUNT: &str = "kona_node_deposits_only_fallbacks";
+
+    /// Identifier for the histogram that tracks the time between two resets of the derivation
+    /// pipeline, in seconds.
+    pub const DERIVATION_RESET_INTERVAL: &str = "kona_node_derivation_reset_interval_seconds";
+
+    /// Identifier for the gauge that tracks the number of bytes buffered by the derivation
+    /// pipeline.
+    pub const DERIVATION_STATE_SIZE: &str = "kona_node_derivation_state_size_bytes";
+
+    /// Identifier for the counter of prunes of the derivation pipeline over its memory ceiling.
+    pub const DERIVATION_STATE_PRUNE_COUNT: &str = "kona_node_derivation_state_prunes";
+
+    /// Identifier for the counter of reset handshake phases that timed out, labeled by phase.
+    pub const RESET_TIMEOUT_COUNT: &str = "kona_node_reset_timeouts";
+
+    /// Identifier for the gauge that tracks the number of derived blocks held back until their
+    /// DA challenge windows elapse, on alt-DA chains.
+    pub const DA_CHALLENGE_HELD_BLOCKS: &str = "kona_node_da_challenge_held_blocks";
+
+    /// Identifier for the counter of DA challenge status changes, labeled by the new status.
+    pub const DA_CHALLENGE_UPDATES: &str = "kona_node_da_challenge_updates";
+
+    /// Identifier for the gauge that tracks the number of unsafe blocks that are neither safe nor
+    /// covered by a batch read from L1.
+    pub const UNSUBMITTED_UNSAFE_BLOCKS: &str = "kona_node_unsubmitted_unsafe_blocks";
+
+    /// Identifier for the gauge that tracks the last L1 block stitched by the range backfill.
+    pub const RANGE_BACKFILL_L1_BLOCK: &str = "kona_node_range_backfill_l1_block";
+
+    /// Identifier for the counter of chain events published by the exporter, labeled by kind.
+    pub const EXPORTED_EVENTS: &str = "kona_node_exported_events";
+
+    /// Identifier for the counter of chain events the exporter failed to publish, labeled by kind.
+    pub const EXPORT_FAILURES: &str = "kona_node_export_failures";
+
     /// Initializes metrics for the node service.
     ///
     /// This does two things:
@@ -39,6 +139,152 @@ impl Metrics {
             Self::DERIVATION_CRITICAL_ERROR,
             "Critical errors in the derivation pipeline"
         );
+
+        // Actor channel queue depth
+        metrics::describe_gauge!(
+            Self::CHANNEL_QUEUE_DEPTH,
+            metrics::Unit::Count,
+            "Number of events queued in an actor channel"
+        );
+        metrics::describe_counter!(
+            Self::CHANNEL_SATURATED,
+            metrics::Unit::Count,
+            "Number of sends that found an actor channel full"
+        );
+        metrics::describe_counter!(
+            Self::CHANNEL_DROPPED_EVENTS,
+            metrics::Unit::Count,
+            "Number of events dropped from a full actor channel"
+        );
+        metrics::describe_gauge!(
+            Self::CHANNEL_CAPACITY,
+            metrics::Unit::Count,
+            "Effective capacity of an actor channel, including its runtime limit"
+        );
+
+        // Actor health
+        metrics::describe_gauge!(Self::ACTOR_HEALTH, "Whether an actor is healthy (1) or not (0)");
+
+        // Actor utilization
+        metrics::describe_gauge!(
+            Self::ACTOR_ITERATION_RATE,
+            "Select-loop iterations per second of an actor"
+        );
+        metrics::describe_gauge!(
+            Self::ACTOR_BUSY_RATIO,
+            "Fraction of time an actor spends busy in its select loop"
+        );
+
+        // Backfill verification
+        metrics::describe_gauge!(
+            Self::BACKFILL_VERIFIED_BLOCK,
+            "Last safe block verified by the backfill actor"
+        );
+        metrics::describe_counter!(
+            Self::BACKFILL_DIVERGENCE_COUNT,
+            metrics::Unit::Count,
+            "Safe blocks that diverged when re-derived by the backfill actor"
+        );
+
+        // Shadow comparison
+        metrics::describe_gauge!(
+            Self::SHADOW_VERIFIED_BLOCK,
+            "Last safe block found to match the reference node by the shadow actor"
+        );
+        metrics::describe_counter!(
+            Self::SHADOW_DIVERGENCE_COUNT,
+            metrics::Unit::Count,
+            "Safe blocks that diverged from the reference node"
+        );
+        metrics::describe_gauge!(
+            Self::SHADOW_SAFE_HEAD_LAG,
+            "Number of blocks the safe head of the reference node is ahead of the node's"
+        );
+
+        // Safe block consistency checks
+        metrics::describe_counter!(
+            Self::SAFE_BLOCK_INCONSISTENCY_COUNT,
+            metrics::Unit::Count,
+            "Safe blocks held by the execution layer that did not match their derived attributes"
+        );
+
+        // Chain halt watchdog
+        metrics::describe_gauge!(
+            Self::CHAIN_HALT,
+            "Whether the safe head has stalled despite new L1 blocks, by likely cause"
+        );
+
+        // Block production and import SLOs
+        metrics::describe_histogram!(
+            Self::SAFE_PROMOTION_LATENCY,
+            metrics::Unit::Seconds,
+            "Time from the L1 inclusion of a batch to the safe promotion of the L2 block derived from it"
+        );
+        metrics::describe_histogram!(
+            Self::UNSAFE_IMPORT_LATENCY,
+            metrics::Unit::Seconds,
+            "Time from the timestamp of an unsafe block to its import"
+        );
+        metrics::describe_counter!(
+            Self::DEPOSITS_ONLY_FALLBACK_COUNT,
+            metrics::Unit::Count,
+            "Derived payloads replaced by a deposits-only payload"
+        );
+        metrics::describe_histogram!(
+            Self::DERIVATION_RESET_INTERVAL,
+            metrics::Unit::Seconds,
+            "Time between two resets of the derivation pipeline"
+        );
+        metrics::describe_gauge!(
+            Self::DERIVATION_STATE_SIZE,
+            metrics::Unit::Bytes,
+            "Bytes buffered by the derivation pipeline"
+        );
+        metrics::describe_counter!(
+            Self::DERIVATION_STATE_PRUNE_COUNT,
+            metrics::Unit::Count,
+            "Prunes of the derivation pipeline over its memory ceiling"
+        );
+        metrics::describe_counter!(
+            Self::RESET_TIMEOUT_COUNT,
+            metrics::Unit::Count,
+            "Reset handshake phases between the engine and derivation that timed out"
+        );
+        metrics::describe_gauge!(
+            Self::DA_CHALLENGE_HELD_BLOCKS,
+            metrics::Unit::Count,
+            "Derived blocks held back until their DA challenge windows elapse"
+        );
+        metrics::describe_counter!(
+            Self::DA_CHALLENGE_UPDATES,
+            metrics::Unit::Count,
+            "DA challenge status changes"
+        );
+
+        // Batch submission
+        metrics::describe_gauge!(
+            Self::UNSUBMITTED_UNSAFE_BLOCKS,
+            metrics::Unit::Count,
+            "Unsafe blocks that are neither safe nor covered by a batch read from L1"
+        );
+
+        // Range backfill
+        metrics::describe_gauge!(
+            Self::RANGE_BACKFILL_L1_BLOCK,
+            "Last L1 block whose epochs were derived and stitched by the range backfill"
+        );
+
+        // Chain event export
+        metrics::describe_counter!(
+            Self::EXPORTED_EVENTS,
+            metrics::Unit::Count,
+            "Chain events published to the message queue"
+        );
+        metrics::describe_counter!(
+            Self::EXPORT_FAILURES,
+            metrics::Unit::Count,
+            "Chain events that failed to be published to the message queue"
+        );
     }
 
     /// Initializes metrics to `0` so they can be queried immediately by consumers of prometheus
@@ -50,5 +296,31 @@ impl Metrics {
 
         // Derivation critical error
         kona_macros::set!(counter, Self::DERIVATION_CRITICAL_ERROR, 0);
+
+        // Backfill divergences
+        kona_macros::set!(counter, Self::BACKFILL_DIVERGENCE_COUNT, 0);
+
+        // Shadow divergences
+        kona_macros::set!(counter, Self::SHADOW_DIVERGENCE_COUNT, 0);
+
+        // Safe block inconsistencies
+        for field in [
+            kona_engine::Metrics::ATTRIBUTES_MISMATCH_TRANSACTIONS,
+            kona_engine::Metrics::ATTRIBUTES_MISMATCH_WITHDRAWALS_ROOT,
+        ] {
+            kona_macros::set!(counter, Self::SAFE_BLOCK_INCONSISTENCY_COUNT, "field", field, 0);
+        }
+
+        // Deposits-only fallbacks
+        kona_macros::set!(counter, Self::DEPOSITS_ONLY_FALLBACK_COUNT, 0);
+
+        // Derivation state prunes
+        kona_macros::set!(counter, Self::DERIVATION_STATE_PRUNE_COUNT, 0);
+
+        // DA challenge held blocks
+        kona_macros::set!(gauge, Self::DA_CHALLENGE_HELD_BLOCKS, 0);
+
+        // Unsubmitted unsafe blocks
+        kona_macros::set!(gauge, Self::UNSUBMITTED_UNSAFE_BLOCKS, 0);
     }
 }
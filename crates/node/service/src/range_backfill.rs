@@ -0,0 +1,273 @@
+//! Parallel derivation of historical L1 ranges.
+//!
+//! The [`RangeBackfill`] engine speeds up the initial sync of a follower by deriving a historical
+//! L1 range with several pipelines at once. The range is split into [`EpochRange`]s of consecutive
+//! L1 epochs, which are handed to a pool of workers. Each worker derives the payload attributes of
+//! its range with an [`EpochDeriver`], independently of the others. The results are then stitched
+//! back in L1 order, and the parent link of every attributes is checked against the attributes
+//! before it, including across ranges, so that ranges derived from mismatched starting points are
+//! rejected rather than silently spliced into the chain.
+
+use crate::Metrics;
+use async_trait::async_trait;
+use futures::{StreamExt, stream};
+use kona_protocol::OpAttributesWithParent;
+use std::{fmt::Display, sync::Arc};
+use thiserror::Error;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// A range of consecutive L1 epochs derived by a single worker of the [`RangeBackfill`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+#[display("#{index} [{l1_start}, {l1_end}]")]
+pub struct EpochRange {
+    /// The position of the range in the backfilled range.
+    pub index: usize,
+    /// The first L1 block of the range.
+    pub l1_start: u64,
+    /// The last L1 block of the range, inclusive.
+    pub l1_end: u64,
+}
+
+impl EpochRange {
+    /// Returns `true` if the given L1 block is part of the range.
+    pub const fn contains(&self, l1_block: u64) -> bool {
+        self.l1_start <= l1_block && l1_block <= self.l1_end
+    }
+}
+
+/// Derives the payload attributes of an [`EpochRange`], for the workers of the [`RangeBackfill`].
+///
+/// Each call must use a derivation pipeline of its own, started at the L2 safe head of the
+/// beginning of the range, since calls run concurrently.
+#[async_trait]
+pub trait EpochDeriver: Send + Sync + 'static {
+    /// The error type of the deriver.
+    type Error: Display + Send + 'static;
+
+    /// Derives the payload attributes of all L2 blocks whose L1 origin is within the given range,
+    /// in order.
+    async fn derive(&self, range: EpochRange) -> Result<Vec<OpAttributesWithParent>, Self::Error>;
+}
+
+/// The configuration of the [`RangeBackfill`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeBackfillConfig {
+    /// The number of ranges derived concurrently.
+    pub workers: usize,
+    /// The number of L1 epochs in each range.
+    pub epochs_per_range: u64,
+}
+
+impl Default for RangeBackfillConfig {
+    fn default() -> Self {
+        Self { workers: 4, epochs_per_range: 64 }
+    }
+}
+
+/// The outcome of a completed [`RangeBackfill`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RangeBackfillSummary {
+    /// The number of ranges derived.
+    pub ranges: usize,
+    /// The number of payload attributes derived.
+    pub attributes: usize,
+    /// The number of the last L2 block derived, if any.
+    pub last_block: Option<u64>,
+}
+
+/// An engine deriving a historical L1 range in parallel. See the [module docs](self).
+#[derive(Debug)]
+pub struct RangeBackfill<D> {
+    /// The deriver shared by the workers.
+    deriver: Arc<D>,
+    /// The [`RangeBackfillConfig`].
+    config: RangeBackfillConfig,
+}
+
+impl<D: EpochDeriver> RangeBackfill<D> {
+    /// Creates a new [`RangeBackfill`] deriving ranges with the given deriver.
+    pub fn new(deriver: D, config: RangeBackfillConfig) -> Self {
+        Self { deriver: Arc::new(deriver), config }
+    }
+
+    /// Splits the L1 range `[l1_start, l1_end]` into [`EpochRange`]s.
+    pub fn split(&self, l1_start: u64, l1_end: u64) -> Vec<EpochRange> {
+        let size = self.config.epochs_per_range.max(1);
+        (l1_start..=l1_end)
+            .step_by(size as usize)
+            .enumerate()
+            .map(|(index, start)| EpochRange {
+                index,
+                l1_start: start,
+                l1_end: start.saturating_add(size - 1).min(l1_end),
+            })
+            .collect()
+    }
+
+    /// Derives the L1 range `[l1_start, l1_end]`, and sends the derived attributes on `out`, in
+    /// order. Ranges are derived by up to [`RangeBackfillConfig::workers`] workers at once, and
+    /// the attributes of a range are only sent once all the ranges before it were stitched.
+    ///
+    /// Stops at the first range that fails to derive or to stitch, after the attributes of the
+    /// ranges before it were sent.
+    pub async fn run(
+        &self,
+        l1_start: u64,
+        l1_end: u64,
+        out: mpsc::Sender<OpAttributesWithParent>,
+        cancellation: CancellationToken,
+    ) -> Result<RangeBackfillSummary, RangeBackfillError<D::Error>> {
+        let ranges = self.split(l1_start, l1_end);
+        info!(
+            target: "range_backfill",
+            l1_start,
+            l1_end,
+            ranges = ranges.len(),
+            workers = self.config.workers,
+            "Starting range backfill"
+        );
+
+        // Spawn the workers in order, keeping at most `workers` ranges in flight. `buffered`
+        // yields the results in the order of the ranges, regardless of the order they complete in.
+        let deriver = self.deriver.clone();
+        let mut results = stream::iter(ranges)
+            .map(|range| {
+                let deriver = deriver.clone();
+                tokio::spawn(async move { (range, deriver.derive(range).await) })
+            })
+            .buffered(self.config.workers.max(1));
+
+        let mut stitcher = Stitcher::default();
+        let mut summary = RangeBackfillSummary::default();
+        loop {
+            let result = tokio::select! {
+                _ = cancellation.cancelled() => return Err(RangeBackfillError::Cancelled),
+                result = results.next() => result,
+            };
+            let Some(result) = result else {
+                break;
+            };
+            let (range, attributes) =
+                result.map_err(|e| RangeBackfillError::Worker(e.to_string()))?;
+            let attributes =
+                attributes.map_err(|error| RangeBackfillError::Derive { range, error })?;
+
+            stitcher.stitch(range, &attributes)?;
+            debug!(
+                target: "range_backfill",
+                %range,
+                attributes = attributes.len(),
+                "Stitched derived range"
+            );
+            summary.ranges += 1;
+            summary.attributes += attributes.len();
+            summary.last_block = stitcher.last_block().or(summary.last_block);
+            for attributes in attributes {
+                out.send(attributes).await.map_err(|_| RangeBackfillError::Closed)?;
+            }
+            kona_macros::set!(gauge, Metrics::RANGE_BACKFILL_L1_BLOCK, range.l1_end as f64);
+        }
+
+        info!(
+            target: "range_backfill",
+            ranges = summary.ranges,
+            attributes = summary.attributes,
+            last_block = ?summary.last_block,
+            "Completed range backfill"
+        );
+        Ok(summary)
+    }
+}
+
+/// Checks that the attributes of consecutive ranges link up.
+#[derive(Debug, Default)]
+struct Stitcher {
+    /// The last attributes stitched.
+    last: Option<OpAttributesWithParent>,
+}
+
+impl Stitcher {
+    /// Returns the number of the last L2 block stitched, if any.
+    fn last_block(&self) -> Option<u64> {
+        self.last.as_ref().map(|last| last.block_number())
+    }
+
+    /// Checks the attributes derived for the given range, in order, against the attributes
+    /// stitched before them.
+    fn stitch<E>(
+        &mut self,
+        range: EpochRange,
+        attributes: &[OpAttributesWithParent],
+    ) -> Result<(), RangeBackfillError<E>> {
+        for next in attributes {
+            let number = next.block_number();
+            if !range.contains(next.l1_origin.number) {
+                return Err(RangeBackfillError::OriginOutOfRange {
+                    range,
+                    number,
+                    origin: next.l1_origin.number,
+                });
+            }
+            if let Some(last) = &self.last {
+                let parent = &next.parent;
+                let reason = if parent.block_info.number != last.block_number() {
+                    Some("parent number")
+                } else if parent.block_info.timestamp != last.inner.payload_attributes.timestamp {
+                    Some("parent timestamp")
+                } else if parent.l1_origin != last.l1_origin.id() {
+                    Some("parent L1 origin")
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    return Err(RangeBackfillError::ParentMismatch { range, number, reason });
+                }
+            }
+            self.last = Some(next.clone());
+        }
+        Ok(())
+    }
+}
+
+/// An error from the [`RangeBackfill`].
+#[derive(Error, Debug)]
+pub enum RangeBackfillError<E> {
+    /// A range failed to derive.
+    #[error("Failed to derive range {range}: {error}")]
+    Derive {
+        /// The range.
+        range: EpochRange,
+        /// The error of the deriver.
+        error: E,
+    },
+    /// A worker panicked or was aborted.
+    #[error("Range backfill worker failed: {0}")]
+    Worker(String),
+    /// Attributes were derived from an L1 origin outside of their range.
+    #[error("Block {number} of range {range} was derived from L1 block {origin}")]
+    OriginOutOfRange {
+        /// The range.
+        range: EpochRange,
+        /// The number of the L2 block.
+        number: u64,
+        /// The L1 origin of the L2 block.
+        origin: u64,
+    },
+    /// Attributes do not build on the attributes stitched before them.
+    #[error("Block {number} of range {range} does not link to the previous block: {reason}")]
+    ParentMismatch {
+        /// The range.
+        range: EpochRange,
+        /// The number of the L2 block.
+        number: u64,
+        /// The field of the parent that mismatches.
+        reason: &'static str,
+    },
+    /// The receiver of the derived attributes was dropped.
+    #[error("Attributes receiver closed")]
+    Closed,
+    /// The backfill was cancelled.
+    #[error("Range backfill cancelled")]
+    Cancelled,
+}
@@ -1,29 +1,58 @@
 //! The core [`RollupNodeService`] trait
 
-use super::NodeMode;
+use super::{NodeExtension, NodeHandles, NodeMode, ShutdownCoordinator, ShutdownStage};
 use crate::{
-    DerivationContext, DerivationState, EngineContext, EngineLauncher, L1WatcherRpcContext,
-    L2Finalizer, NetworkContext, NodeActor, RpcContext, RuntimeContext, SequencerActorState,
-    SequencerContext, SequencerOutboundData, SupervisorActorContext, SupervisorExt,
+    ActorStatsSampler, AttributesFanout, BackfillConfig, BackfillContext, BackfillState,
+    ChainHaltConfig, ChainHaltContext, ChainHaltState, Clock, DaChallengeConfigError,
+    DaChallengeGate, DerivationContext, DerivationState, EngineContext, EngineLauncher,
+    EventJournal, HealthReporter, L1PollIntervals, L1WatcherRpcContext, L2Finalizer, NodeActor,
+    ResetCoordinator, RpcContext, RuntimeContext, ShadowConfig, ShadowContext, ShadowState,
     actors::{
         DerivationOutboundChannels, EngineActorState, EngineOutboundData,
-        L1WatcherRpcOutboundChannels, L1WatcherRpcState, NetworkOutboundData, RuntimeOutboundData,
-        RuntimeState, SupervisorOutboundData,
+        L1WatcherRpcOutboundChannels, L1WatcherRpcState, RuntimeOutboundData, RuntimeState,
+        ShadowOutboundData,
     },
+    bus::BusConfig,
     service::spawn_and_wait,
 };
 use alloy_provider::RootProvider;
+use alloy_rpc_client::RpcClient;
 use async_trait::async_trait;
-use kona_derive::{AttributesBuilder, Pipeline, SignalReceiver};
+use kona_derive::{Pipeline, SignalReceiver};
+use kona_engine::{DEFAULT_SAFE_HEAD_PRUNE_INTERVAL, SafeHeadIndexPruning};
 use kona_genesis::RollupConfig;
+use kona_providers_alloy::L1BlockLimit;
+use kona_rpc::{
+    ActorStatsRegistry, EthProxyRpc, HealthRegistry, ReloadableConfig, ReorgEvent,
+    RollupNodeApiServer, RollupRpc, RpcLauncher, RpcLauncherError, SubmissionRegistry, WsRPC,
+    WsServer,
+};
+use std::{fmt::Display, sync::Arc, time::Duration};
+use tokio::sync::{broadcast, mpsc, watch};
+
+#[cfg(feature = "sequencer")]
+use crate::{SequencerActorState, SequencerContext, SequencerOutboundData};
+#[cfg(feature = "sequencer")]
+use kona_derive::AttributesBuilder;
+
+#[cfg(feature = "p2p")]
+use crate::{NetworkActorState, NetworkContext};
+#[cfg(feature = "p2p")]
 use kona_p2p::Network;
+#[cfg(all(feature = "p2p", feature = "rpc-admin"))]
+use kona_rpc::GossipArchiveApiServer;
+#[cfg(feature = "p2p")]
+use kona_rpc::{NetworkRpc, OpP2PApiServer};
+
+#[cfg(feature = "interop")]
+use crate::{SupervisorActorContext, SupervisorExt, actors::SupervisorOutboundData};
+
+#[cfg(feature = "rpc-admin")]
 use kona_rpc::{
-    NetworkRpc, OpP2PApiServer, RollupNodeApiServer, RollupRpc, RpcLauncher, RpcLauncherError,
-    WsRPC, WsServer,
+    ActorStatsApiServer, ActorStatsRpc, ConfigReloadApiServer, ConfigReloadRpc,
+    EngineDebugApiServer, EngineStateHistoryRpc, PayloadEnvelopeApiServer, PayloadEnvelopeRpc,
+    SubmissionApiServer, SubmissionRpc,
 };
-use std::{fmt::Display, sync::Arc};
-use tokio::sync::mpsc;
-use tokio_util::sync::CancellationToken;
 
 /// The [`RollupNodeService`] trait defines the common interface for running a rollup node.
 ///
@@ -52,6 +81,13 @@ use tokio_util::sync::CancellationToken;
 /// - `SupervisorExt`: The type of [`SupervisorExt`] to use for the service, which provides an
 ///   interface for sending events to the supervisor.
 /// - `Error`: The type of error for the service's entrypoint.
+///
+/// ## Features
+///
+/// The follower path, from the DA watcher through derivation to the engine, is always available.
+/// The p2p network, the sequencer, the supervisor and the admin RPC are optional components, gated
+/// behind the `p2p`, `sequencer`, `interop` and `rpc-admin` features respectively. Their types and
+/// methods only exist on the trait when the corresponding feature is enabled.
 #[async_trait]
 pub trait RollupNodeService {
     /// The type of [`NodeActor`] to use for the DA watcher service.
@@ -66,6 +102,7 @@ pub trait RollupNodeService {
     type DerivationPipeline: Pipeline + SignalReceiver + Send + Sync + 'static;
 
     /// The type of attributes builder to use for the sequener.
+    #[cfg(feature = "sequencer")]
     type AttributesBuilder: AttributesBuilder + Send + Sync + 'static;
 
     /// The type of derivation actor to use for the service.
@@ -85,17 +122,20 @@ pub trait RollupNodeService {
         >;
 
     /// The type of network actor to use for the service.
+    #[cfg(feature = "p2p")]
     type NetworkActor: NodeActor<
             Error: Display,
             InboundData = NetworkContext,
-            State = Network,
-            OutboundData = NetworkOutboundData,
+            State = NetworkActorState,
+            OutboundData = (),
         >;
 
     /// The supervisor ext provider.
+    #[cfg(feature = "interop")]
     type SupervisorExt: SupervisorExt + Send + Sync + 'static;
 
     /// The type of supervisor actor to use for the service.
+    #[cfg(feature = "interop")]
     type SupervisorActor: NodeActor<
             Error: Display,
             InboundData = SupervisorActorContext,
@@ -112,6 +152,7 @@ pub trait RollupNodeService {
         >;
 
     /// The type of sequencer actor to use for the service.
+    #[cfg(feature = "sequencer")]
     type SequencerActor: NodeActor<
             Error: Display,
             InboundData = SequencerContext,
@@ -119,12 +160,37 @@ pub trait RollupNodeService {
             OutboundData = SequencerOutboundData,
         >;
 
+    /// The type of backfill actor to use for the service.
+    type BackfillActor: NodeActor<
+            Error: Display,
+            InboundData = BackfillContext,
+            State = BackfillState<Self::DerivationPipeline>,
+            OutboundData = (),
+        >;
+
+    /// The type of shadow actor to use for the service.
+    type ShadowActor: NodeActor<
+            Error: Display,
+            InboundData = ShadowContext,
+            State = ShadowState,
+            OutboundData = ShadowOutboundData,
+        >;
+
+    /// The type of chain-halt watchdog to use for the service.
+    type ChainHaltWatchdog: NodeActor<
+            Error: Display,
+            InboundData = ChainHaltContext,
+            State = ChainHaltState,
+            OutboundData = (),
+        >;
+
     /// The type of rpc actor to use for the service.
     type RpcActor: NodeActor<Error: Display, InboundData = RpcContext, State = RpcLauncher, OutboundData = ()>;
 
     /// The type of error for the service's entrypoint.
     type Error: From<RpcLauncherError>
         + From<jsonrpsee::server::RegisterMethodError>
+        + From<DaChallengeConfigError>
         + std::fmt::Debug;
 
     /// Returns the [`NodeMode`] of the service.
@@ -136,14 +202,20 @@ pub trait RollupNodeService {
     /// Returns the [`RootProvider`] for the L1 chain.
     fn l1_provider(&self) -> RootProvider;
 
+    /// Returns the [`RpcClient`] of the L2 EL RPC, that the `eth_*` proxy forwards queries to.
+    fn l2_rpc_client(&self) -> RpcClient;
+
     /// Creates a new instance of the [`Pipeline`] and initializes it. Returns the starting L2
     /// forkchoice state and the initialized derivation pipeline.
     async fn init_derivation(&self) -> Result<Self::DerivationPipeline, Self::Error>;
 
-    /// Creates a new instance of the [`Network`].
-    async fn init_network(&self) -> Result<(Network, NetworkRpc), Self::Error>;
+    /// Creates a new instance of the [`Network`], or `None` if the node runs without its p2p
+    /// network, e.g. because an embedding program supplies unsafe blocks itself.
+    #[cfg(feature = "p2p")]
+    async fn init_network(&self) -> Result<Option<(Network, NetworkRpc)>, Self::Error>;
 
     /// Creates a new [`Self::SupervisorExt`] to be used in the supervisor rpc actor.
+    #[cfg(feature = "interop")]
     async fn supervisor_ext(&self) -> Option<Self::SupervisorExt>;
 
     /// Returns the [`RuntimeState`] for the node.
@@ -156,8 +228,72 @@ pub trait RollupNodeService {
     fn rpc(&self) -> RpcLauncher;
 
     /// Returns the initial [`SequencerActorState`].
+    #[cfg(feature = "sequencer")]
     fn sequencer_state(&self) -> SequencerActorState<Self::AttributesBuilder>;
 
+    /// Returns the number of L1 blocks the sequencer keeps between the L1 head and the L1 origins
+    /// it selects.
+    fn sequencer_l1_confs(&self) -> u64;
+
+    /// Returns the number of confirmations that L1 blocks must have before derivation consumes
+    /// them.
+    fn l1_confirmation_depth(&self) -> u64;
+
+    /// Returns the [`L1BlockLimit`] of the L1 provider of derivation, if derivation waits for
+    /// L1 confirmations. The L1 watcher advances it as blocks become confirmed.
+    fn l1_derivation_limit(&self) -> Option<L1BlockLimit>;
+
+    /// Returns the [`L1PollIntervals`] at which the L1 watcher polls the L1 heads.
+    fn l1_poll_intervals(&self) -> L1PollIntervals;
+
+    /// Returns the [`BackfillConfig`] for the node, if historical safe blocks should be
+    /// re-derived and verified in the background.
+    fn backfill(&self) -> Option<BackfillConfig>;
+
+    /// Returns the [`ShadowConfig`] for the node, if its safe chain should be compared against a
+    /// reference op-node.
+    fn shadow(&self) -> Option<ShadowConfig>;
+
+    /// Returns the [`ChainHaltConfig`] for the node, if the chain-halt watchdog is enabled.
+    fn chain_halt(&self) -> Option<ChainHaltConfig>;
+
+    /// Returns the number of bytes the state buffered by the derivation pipeline must not
+    /// exceed, if any.
+    fn derivation_memory_ceiling(&self) -> Option<usize>;
+
+    /// Returns the [`Clock`] used by the node's actors for ticks, timeouts, and deadlines.
+    fn clock(&self) -> Arc<dyn Clock>;
+
+    /// Returns the grace period given to each [`ShutdownStage`] before its actors are aborted.
+    fn shutdown_grace_period(&self) -> Duration;
+
+    /// Returns the time after which a reset phase that was not left is retried, and the
+    /// derivation actor reported as degraded.
+    fn reset_timeout(&self) -> Duration;
+
+    /// Returns the sender for the node's [`ReloadableConfig`]. Updates sent on this channel are
+    /// propagated to the actors at runtime.
+    fn reloadable_config(&self) -> watch::Sender<ReloadableConfig>;
+
+    /// Returns the sender on which the node publishes [`ReorgEvent`]s. Subscribers receive every
+    /// L1 reorg, unsafe chain reorg, and safe head rewind observed by the node.
+    fn reorg_events(&self) -> broadcast::Sender<ReorgEvent>;
+
+    /// Returns the [`BusConfig`] of the event channels between the node's actors.
+    fn bus_config(&self) -> BusConfig;
+
+    /// Returns the [`EventJournal`] that the node's actors record resets, reorgs, invalid
+    /// payloads, sequencer starts and stops, supervisor updates, and shadow divergences in.
+    fn journal(&self) -> EventJournal;
+
+    /// Returns the [`SubmissionRegistry`] tracking the unsafe blocks covered by the batches read
+    /// from L1.
+    fn submission(&self) -> SubmissionRegistry;
+
+    /// Takes the [`NodeExtension`]s to run alongside the node's actors. Extensions are started
+    /// once, so subsequent calls may return an empty list.
+    fn extensions(&self) -> Vec<Box<dyn NodeExtension>>;
+
     /// Starts the rollup node service.
     async fn start(&self) -> Result<(), Self::Error> {
         info!(
@@ -170,22 +306,96 @@ pub trait RollupNodeService {
             info!(target: "rollup_node", "{hf}");
         }
 
-        // Create a global cancellation token for graceful shutdown of tasks.
-        let cancellation = CancellationToken::new();
+        // Initialize the metrics of this chain to 0, so that they can be queried immediately even
+        // if other nodes in the process already initialized theirs.
+        #[cfg(feature = "metrics")]
+        crate::sync_with_chain_id(self.config().l2_chain_id, || {
+            crate::Metrics::zero();
+            kona_engine::Metrics::zero();
+            kona_derive::Metrics::zero();
+            #[cfg(feature = "p2p")]
+            kona_p2p::Metrics::zero();
+        });
+
+        // Create the shutdown coordinator, which hands out a cancellation token per shutdown
+        // stage so that actors are torn down in dependency order.
+        let coordinator =
+            ShutdownCoordinator::new(self.shutdown_grace_period()).with_clock(self.clock());
+
+        // Create the registry that tracks the health of each actor.
+        let health = HealthRegistry::new();
+
+        // The channel that reorgs observed by the actors are published on.
+        let reorgs = self.reorg_events();
+
+        // The configuration of the event channels between actors.
+        let bus = self.bus_config();
+
+        // The journal that key node events are recorded in.
+        let journal = self.journal();
+
+        // The sampler of the utilization of each actor and of the event channels between them.
+        let stats = ActorStatsSampler::new(ActorStatsRegistry::new(), bus.limits.clone());
 
         // Create the DA watcher actor.
         let (
-            L1WatcherRpcOutboundChannels { latest_head, latest_finalized, block_signer_sender },
+            L1WatcherRpcOutboundChannels {
+                latest_head,
+                latest_safe,
+                latest_finalized,
+                #[cfg(feature = "sequencer")]
+                confirmed_head,
+                derivation_head,
+                #[cfg(feature = "p2p")]
+                block_signer_sender,
+                da_challenges,
+                ..
+            },
             da_watcher,
         ) = Self::DataAvailabilityWatcher::build(L1WatcherRpcState {
             rollup: self.config(),
             l1_provider: self.l1_provider(),
+            confirmation_depth: self.sequencer_l1_confs(),
+            derivation_depth: self.l1_confirmation_depth(),
+            derivation_limit: self.l1_derivation_limit(),
+            poll_intervals: self.l1_poll_intervals(),
         });
 
+        // The engine client, shared by the engine actor and the actors that read the execution
+        // layer.
+        let engine_launcher = self.engine();
+        let client = engine_launcher.client();
+
+        // Create the shadow actor, which is fed a copy of the derived attributes.
+        let (shadow_attributes, shadow) = self
+            .shadow()
+            .map(|config| {
+                let (ShadowOutboundData { attributes_tx }, shadow) =
+                    Self::ShadowActor::build(ShadowState {
+                        client: client.clone().into(),
+                        config,
+                        clock: self.clock(),
+                        journal: journal.clone(),
+                    });
+                (attributes_tx, shadow)
+            })
+            .unzip();
+
         // Create the derivation actor.
         let derivation_pipeline = self.init_derivation().await?;
-        let (DerivationOutboundChannels { attributes_out, reset_request_tx }, derivation) =
-            Self::DerivationActor::build(DerivationState::new(derivation_pipeline));
+        // The derived attributes are copied to the shadow actor and the extensions that
+        // subscribe to them, alongside the engine actor.
+        let attributes_fanout = AttributesFanout::default();
+        if let Some(shadow_attributes) = shadow_attributes {
+            attributes_fanout.add(shadow_attributes);
+        }
+        let mut derivation_state = DerivationState::new(derivation_pipeline, bus.clone())
+            .with_fanout(attributes_fanout.clone());
+        if let Some(ceiling) = self.derivation_memory_ceiling() {
+            derivation_state = derivation_state.with_memory_ceiling(ceiling);
+        }
+        let (DerivationOutboundChannels { attributes_out, progress }, derivation) =
+            Self::DerivationActor::build(derivation_state);
 
         // TODO: get the supervisor ext.
         // TODO: use the supervisor ext to create the supervisor actor.
@@ -205,90 +415,304 @@ pub trait RollupNodeService {
             .unzip();
 
         // Create the engine actor.
-        let engine_launcher = self.engine();
-        let client = engine_launcher.client();
+        let sync_mode = engine_launcher.sync_mode;
+        let unsafe_payloads = engine_launcher.unsafe_payload_buffer();
+        let envelopes = engine_launcher.payload_envelope_store();
+        let safe_heads = engine_launcher.safe_head_index();
+        // The safe head index is pruned to its retention while the engine is idle.
+        let maintenance = engine_launcher.maintenance.clone().with_task(SafeHeadIndexPruning {
+            index: safe_heads.clone(),
+            interval: DEFAULT_SAFE_HEAD_PRUNE_INTERVAL,
+        });
+        let build_budget = engine_launcher.build_budget;
+        let restart = engine_launcher.restart.clone();
+        let finality_path = engine_launcher.finality_path.clone();
+        let el_sync = engine_launcher.el_sync;
+        let safe_head_hint = engine_launcher.safe_head_hint.clone();
+        let consistency_check = engine_launcher.consistency_check;
+        let unsafe_lag = engine_launcher.unsafe_lag;
         let engine_task_queue = engine_launcher.launch();
+        let engine_state = engine_task_queue.subscribe();
+        #[cfg(feature = "rpc-admin")]
+        let engine_history = engine_task_queue.history().clone();
         let (
-            EngineOutboundData { engine_l2_safe_head_rx, sync_complete_rx, derivation_signal_rx },
+            EngineOutboundData {
+                unsafe_head_rx,
+                cross_unsafe_head_rx,
+                local_safe_head_rx,
+                engine_l2_safe_head_rx,
+                finalized_head_rx,
+                sync_complete_rx,
+                derivation_signal_rx,
+                el_resync_rx,
+            },
             engine,
         ) = Self::EngineActor::build(EngineActorState {
             rollup: self.config(),
             client: client.clone().into(),
             engine: engine_task_queue,
+            sync_mode,
+            unsafe_payloads,
+            envelopes: envelopes.clone(),
+            safe_heads: safe_heads.clone(),
+            reorgs: reorgs.clone(),
+            bus: bus.clone(),
+            maintenance,
+            build_budget,
+            journal: journal.clone(),
+            restart,
+            el_sync,
+            safe_head_hint,
+            consistency_check,
+            unsafe_lag,
+            clock: self.clock(),
         });
 
-        // Create the p2p actor.
-        let (driver, p2p_rpc_module) = self.init_network().await?;
-        let (NetworkOutboundData { unsafe_block }, network) = Self::NetworkActor::build(driver);
+        // Create the backfill actor, with its own derivation pipeline.
+        let backfill = match self.backfill() {
+            Some(config) => {
+                let (_, backfill) = Self::BackfillActor::build(BackfillState {
+                    pipeline: self.init_derivation().await?,
+                    client: client.clone().into(),
+                    config,
+                    clock: self.clock(),
+                });
+                Some(backfill)
+            }
+            None => None,
+        };
+
+        // Create the chain-halt watchdog.
+        let watchdog = self.chain_halt().map(|config| {
+            let (_, watchdog) =
+                Self::ChainHaltWatchdog::build(ChainHaltState { config, clock: self.clock() });
+            watchdog
+        });
+
+        // Create the channel that unsafe blocks are imported from, and the p2p actor that feeds
+        // it, if the p2p network is enabled.
+        let (unsafe_blocks, unsafe_block) = bus.limits.channel("unsafe_blocks", bus.unsafe_blocks);
+        #[cfg(feature = "p2p")]
+        let (network, p2p_rpc_module) = match self.init_network().await? {
+            Some((driver, p2p_rpc_module)) => {
+                let (_, network) = Self::NetworkActor::build(NetworkActorState {
+                    driver,
+                    blocks: unsafe_blocks.clone(),
+                });
+                (Some(network), Some(p2p_rpc_module))
+            }
+            None => (None, None),
+        };
+        #[cfg(feature = "p2p")]
+        let p2p_requests = p2p_rpc_module.as_ref().map(|module| module.sender.clone());
+
+        // Create the channel used to propagate runtime config reloads.
+        let reload = self.reloadable_config();
 
         // Create the RPC server actor.
-        let (engine_query_recv, l1_watcher_queries_recv, (_, rpc)) = {
-            let mut rpc_launcher = self.rpc().with_healthz()?;
+        let (engine_query_recv, l1_watcher_queries_recv, rpc) = {
+            let mut rpc_launcher = self.rpc().with_healthz(health.clone())?;
+
+            #[cfg(feature = "p2p")]
+            if let Some(p2p_rpc_module) = p2p_rpc_module {
+                rpc_launcher.merge(p2p_rpc_module.into_rpc())?;
+            }
 
-            rpc_launcher.merge(p2p_rpc_module.into_rpc())?;
+            #[cfg(feature = "rpc-admin")]
+            if rpc_launcher.admin_enabled() {
+                rpc_launcher.merge(ConfigReloadRpc::new(reload.clone()).into_rpc())?;
+                rpc_launcher.merge(PayloadEnvelopeRpc::new(envelopes.clone()).into_rpc())?;
+                rpc_launcher.merge(ActorStatsRpc::new(stats.registry().clone()).into_rpc())?;
+                rpc_launcher.merge(EngineStateHistoryRpc::new(engine_history).into_rpc())?;
+                rpc_launcher.merge(SubmissionRpc::new(self.submission()).into_rpc())?;
+                #[cfg(feature = "p2p")]
+                if let Some(sender) = p2p_requests.clone() {
+                    rpc_launcher
+                        .merge(GossipArchiveApiServer::into_rpc(NetworkRpc::new(sender)))?;
+                }
+            }
+            #[cfg(not(feature = "rpc-admin"))]
+            if rpc_launcher.admin_enabled() {
+                warn!(target: "rollup_node", "Admin RPC requested, but the node was built without the `rpc-admin` feature");
+            }
+
+            #[cfg(feature = "profiling")]
+            if rpc_launcher.admin_enabled() {
+                use kona_rpc::ProfilingApiServer;
+                rpc_launcher.merge(kona_rpc::ProfilingRpc.into_rpc())?;
+            }
 
             // Create context for communication between actors.
             let (l1_watcher_queries_sender, l1_watcher_queries_recv) = mpsc::channel(1024);
             let (engine_query_sender, engine_query_recv) = mpsc::channel(1024);
-            let rollup_rpc = RollupRpc::new(engine_query_sender.clone(), l1_watcher_queries_sender);
+            let rollup_rpc = RollupRpc::new(engine_query_sender.clone(), l1_watcher_queries_sender)
+                .with_block_labels(rpc_launcher.block_labels())
+                .with_safe_head_index(safe_heads);
             rpc_launcher.merge(rollup_rpc.into_rpc())?;
 
+            if rpc_launcher.eth_proxy_enabled() {
+                let proxy = EthProxyRpc::new(engine_query_sender.clone(), self.l2_rpc_client());
+                rpc_launcher.merge(proxy.into_rpc()?)?;
+            }
+
             if rpc_launcher.ws_enabled() {
                 rpc_launcher
-                    .merge(WsRPC::new(engine_query_sender).into_rpc())
+                    .merge(WsRPC::new(engine_query_sender, reorgs.clone()).into_rpc())
                     .map_err(Self::Error::from)?;
             }
 
-            (engine_query_recv, l1_watcher_queries_recv, Self::RpcActor::build(rpc_launcher))
+            // A disabled RPC server exits immediately, so the actor is not started at all.
+            let rpc = rpc_launcher
+                .enabled()
+                .then(|| Self::RpcActor::build(rpc_launcher))
+                .map(|(_, rpc)| rpc);
+            (engine_query_recv, l1_watcher_queries_recv, rpc)
         };
 
+        #[cfg(feature = "sequencer")]
         let (_, sequencer) = Self::SequencerActor::build(self.sequencer_state());
 
-        let network_context =
-            NetworkContext { signer: block_signer_sender, cancellation: cancellation.clone() };
+        #[cfg(feature = "p2p")]
+        let network_context = p2p_requests.clone().map(|p2p_requests| NetworkContext {
+            signer: block_signer_sender,
+            reload: reload.subscribe(),
+            p2p_requests,
+            cancellation: coordinator.token(ShutdownStage::Network),
+        });
+
+        let watchdog_context = ChainHaltContext {
+            engine_l2_safe_head: engine_l2_safe_head_rx.clone(),
+            l1_head: latest_head.clone(),
+            engine_state: engine_state.clone(),
+            derivation: progress,
+            health: HealthReporter::new(Self::ChainHaltWatchdog::NAME, health.clone()),
+            cancellation: coordinator.token(ShutdownStage::Derivation),
+        };
+
+        let handles = NodeHandles {
+            engine_state,
+            unsafe_head: unsafe_head_rx.clone(),
+            cross_unsafe_head: cross_unsafe_head_rx,
+            local_safe_head: local_safe_head_rx,
+            safe_head: engine_l2_safe_head_rx.clone(),
+            finalized_head: finalized_head_rx,
+            l1_head: latest_head.clone(),
+            l1_safe: latest_safe,
+            l1_finalized: latest_finalized.clone(),
+            reorgs: reorgs.clone(),
+            derived_attributes: attributes_fanout,
+            unsafe_blocks,
+            #[cfg(feature = "p2p")]
+            p2p_requests,
+            reload: reload.clone(),
+            health: health.clone(),
+            shutdown: coordinator.clone(),
+        };
 
         let da_watcher_context = L1WatcherRpcContext {
             inbound_queries: l1_watcher_queries_recv,
-            cancellation: cancellation.clone(),
+            reorgs,
+            journal: journal.clone(),
+            cancellation: coordinator.token(ShutdownStage::Network),
         };
 
+        // The reset handshake between the engine and derivation.
+        let reset = ResetCoordinator::new(self.reset_timeout()).with_clock(self.clock());
+
         let derivation_context = DerivationContext {
-            l1_head_updates: latest_head,
+            l1_head_updates: derivation_head,
             engine_l2_safe_head: engine_l2_safe_head_rx.clone(),
             el_sync_complete_rx: sync_complete_rx,
+            el_resync: el_resync_rx,
+            reset: reset.clone(),
             derivation_signal_rx,
-            cancellation: cancellation.clone(),
+            health: HealthReporter::new(Self::DerivationActor::NAME, health.clone()),
+            cancellation: coordinator.token(ShutdownStage::Derivation),
+        };
+
+        let backfill_context = BackfillContext {
+            engine_l2_safe_head: engine_l2_safe_head_rx.clone(),
+            health: HealthReporter::new(Self::BackfillActor::NAME, health.clone()),
+            cancellation: coordinator.token(ShutdownStage::Derivation),
+        };
+
+        let shadow_context = ShadowContext {
+            engine_l2_safe_head: engine_l2_safe_head_rx.clone(),
+            health: HealthReporter::new(Self::ShadowActor::NAME, health.clone()),
+            cancellation: coordinator.token(ShutdownStage::Derivation),
         };
 
         let engine_context = EngineContext {
             runtime_config_rx: runtime_config,
             attributes_rx: attributes_out,
             unsafe_block_rx: unsafe_block,
-            reset_request_rx: reset_request_tx,
+            reload: reload.subscribe(),
+            reset,
             inbound_queries: engine_query_recv,
-            cancellation: cancellation.clone(),
-            finalizer: L2Finalizer::new(latest_finalized, client.into()),
+            cancellation: coordinator.token(ShutdownStage::Engine),
+            finalizer: {
+                let finalizer = match finality_path {
+                    Some(path) => {
+                        L2Finalizer::new(latest_finalized, client.into()).with_persistence(path)
+                    }
+                    None => L2Finalizer::new(latest_finalized, client.into()),
+                };
+                // On alt-DA chains, derived blocks are only promoted to safe once their data can
+                // no longer be challenged.
+                match DaChallengeGate::from_rollup_config(
+                    &self.config(),
+                    latest_head,
+                    da_challenges,
+                )? {
+                    Some(gate) => finalizer.with_da_challenges(gate),
+                    None => finalizer,
+                }
+            },
+            health: HealthReporter::new(Self::EngineActor::NAME, health.clone()),
         };
 
-        let rpc_context = RpcContext { cancellation: cancellation.clone() };
+        let rpc_context = RpcContext { cancellation: coordinator.token(ShutdownStage::Network) };
 
+        #[cfg(feature = "sequencer")]
         let sequencer_context = SequencerContext {
             latest_payload_rx: None,
-            unsafe_head: engine_l2_safe_head_rx,
-            cancellation: cancellation.clone(),
+            unsafe_head: unsafe_head_rx,
+            confirmed_l1_head: confirmed_head,
+            reload: reload.subscribe(),
+            health: health.clone(),
+            dependencies: vec![Self::EngineActor::NAME, Self::DerivationActor::NAME],
+            journal,
+            envelopes,
+            cancellation: coordinator.token(ShutdownStage::Sequencer),
         };
 
+        let runtime_context =
+            RuntimeContext { cancellation: coordinator.token(ShutdownStage::Network) };
+
         spawn_and_wait!(
-            cancellation,
+            coordinator,
+            health,
+            stats,
+            chain_id = self.config().l2_chain_id,
             actors = [
-                runtime.map(|r| (r, RuntimeContext { cancellation: cancellation.clone() })),
-                Some((network, network_context)),
-                Some((da_watcher, da_watcher_context)),
-                Some((derivation, derivation_context)),
-                Some((engine, engine_context)),
-                Some((rpc, rpc_context)),
-                (self.mode() == NodeMode::Sequencer).then_some((sequencer, sequencer_context))
-            ]
+                (ShutdownStage::Network, runtime.map(|r| (r, runtime_context))),
+                #[cfg(feature = "p2p")]
+                (ShutdownStage::Network, network.zip(network_context)),
+                (ShutdownStage::Network, Some((da_watcher, da_watcher_context))),
+                (ShutdownStage::Derivation, Some((derivation, derivation_context))),
+                (ShutdownStage::Derivation, backfill.map(|b| (b, backfill_context))),
+                (ShutdownStage::Derivation, shadow.map(|s| (s, shadow_context))),
+                (ShutdownStage::Derivation, watchdog.map(|w| (w, watchdog_context))),
+                (ShutdownStage::Engine, Some((engine, engine_context))),
+                (ShutdownStage::Network, rpc.map(|r| (r, rpc_context))),
+                #[cfg(feature = "sequencer")]
+                (
+                    ShutdownStage::Sequencer,
+                    (self.mode() == NodeMode::Sequencer).then_some((sequencer, sequencer_context))
+                )
+            ],
+            extensions = self.extensions(),
+            handles = handles,
         );
         Ok(())
     }
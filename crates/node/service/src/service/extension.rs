@@ -0,0 +1,230 @@
+//! Extension points for embedding the rollup node in another program.
+
+use super::{ShutdownCoordinator, ShutdownStage};
+use crate::{
+    AttributesFanout,
+    bus::{ChannelLimits, EventSender},
+};
+use async_trait::async_trait;
+use kona_engine::EngineState;
+#[cfg(feature = "p2p")]
+use kona_p2p::P2pRpcRequest;
+use kona_protocol::{BlockInfo, L2BlockInfo};
+use kona_rpc::{HealthRegistry, ReloadableConfig, ReorgEvent};
+use op_alloy_rpc_types_engine::OpExecutionPayloadEnvelope;
+use std::{error::Error, fmt::Debug};
+#[cfg(feature = "p2p")]
+use tokio::sync::mpsc;
+use tokio::sync::{broadcast, oneshot, watch};
+use tokio_util::sync::CancellationToken;
+
+/// Typed handles to the event streams of a running rollup node.
+///
+/// The handles are created when the node starts, and handed to each [`NodeExtension`]. Programs
+/// that embed the node without a custom extension can receive them through
+/// [`RollupNodeBuilder::with_handles_sender`].
+///
+/// [`RollupNodeBuilder::with_handles_sender`]: crate::RollupNodeBuilder::with_handles_sender
+#[derive(Debug, Clone)]
+pub struct NodeHandles {
+    /// The state of the engine, updated as engine tasks are executed.
+    pub engine_state: watch::Receiver<EngineState>,
+    /// The L2 unsafe head.
+    pub unsafe_head: watch::Receiver<L2BlockInfo>,
+    /// The L2 cross-unsafe head.
+    pub cross_unsafe_head: watch::Receiver<L2BlockInfo>,
+    /// The L2 local-safe head.
+    pub local_safe_head: watch::Receiver<L2BlockInfo>,
+    /// The L2 safe head.
+    pub safe_head: watch::Receiver<L2BlockInfo>,
+    /// The L2 finalized head.
+    pub finalized_head: watch::Receiver<L2BlockInfo>,
+    /// The latest L1 head block.
+    pub l1_head: watch::Receiver<Option<BlockInfo>>,
+    /// The latest L1 safe block.
+    pub l1_safe: watch::Receiver<Option<BlockInfo>>,
+    /// The latest L1 finalized block.
+    pub l1_finalized: watch::Receiver<Option<BlockInfo>>,
+    /// The sender on which the node publishes [`ReorgEvent`]s. Call
+    /// [`broadcast::Sender::subscribe`] to receive them.
+    pub reorgs: broadcast::Sender<ReorgEvent>,
+    /// The fan-out of the payload attributes derived by the node. Call
+    /// [`AttributesFanout::subscribe`] to receive them, without holding back the engine.
+    pub derived_attributes: AttributesFanout,
+    /// The channel unsafe blocks are imported from. Blocks sent on it are handled exactly like
+    /// blocks received over p2p gossip, which allows an extension to stand in for the network
+    /// layer.
+    pub unsafe_blocks: EventSender<OpExecutionPayloadEnvelope>,
+    /// The channel to send [`P2pRpcRequest`]s to the p2p network driver on, e.g. to query the
+    /// connected peers. `None` if the p2p network is disabled.
+    #[cfg(feature = "p2p")]
+    pub p2p_requests: Option<mpsc::Sender<P2pRpcRequest>>,
+    /// The sender for the node's [`ReloadableConfig`].
+    pub reload: watch::Sender<ReloadableConfig>,
+    /// The health of the node's actors and extensions.
+    pub health: HealthRegistry,
+    /// The node's [`ShutdownCoordinator`], which can be used to stop the node.
+    pub shutdown: ShutdownCoordinator,
+}
+
+/// A task that runs inside the rollup node, alongside its actors.
+///
+/// Extensions are registered with [`RollupNodeBuilder::with_extension`], and started with the
+/// node's [`NodeHandles`] once all actors have been built. They are tracked like the node's
+/// actors: their health is published under their [name][Self::name], an extension returning an
+/// error shuts down the node, and an extension is cancelled along with the actors of its
+/// [`ShutdownStage`].
+///
+/// [`RollupNodeBuilder::with_extension`]: crate::RollupNodeBuilder::with_extension
+#[async_trait]
+pub trait NodeExtension: Debug + Send + 'static {
+    /// The name of the extension, used for health and utilization reporting, and logs.
+    fn name(&self) -> &'static str;
+
+    /// The [`ShutdownStage`] in which the extension is cancelled. Defaults to
+    /// [`ShutdownStage::Network`], so that the extension outlives the node's other actors.
+    fn stage(&self) -> ShutdownStage {
+        ShutdownStage::Network
+    }
+
+    /// Runs the extension until the `cancellation` token is cancelled.
+    async fn run(
+        self: Box<Self>,
+        handles: NodeHandles,
+        cancellation: CancellationToken,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// A [`NodeExtension`] that hands the [`NodeHandles`] to the embedding program. It then idles
+/// until the node shuts down, since a stopped extension would mark the node unhealthy.
+#[derive(Debug)]
+pub(crate) struct HandlesExtension(pub(crate) oneshot::Sender<NodeHandles>);
+
+#[async_trait]
+impl NodeExtension for HandlesExtension {
+    fn name(&self) -> &'static str {
+        "embedder"
+    }
+
+    async fn run(
+        self: Box<Self>,
+        handles: NodeHandles,
+        cancellation: CancellationToken,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.0.send(handles).is_err() {
+            debug!(target: "rollup_node", "Node handles receiver dropped");
+        }
+        cancellation.cancelled().await;
+        Ok(())
+    }
+}
+
+/// A [`NodeExtension`] that applies the channel capacities of the node's [`ReloadableConfig`] to
+/// its [`ChannelLimits`], as they are reloaded.
+#[derive(Debug)]
+pub(crate) struct ChannelLimitsReloader(pub(crate) ChannelLimits);
+
+#[async_trait]
+impl NodeExtension for ChannelLimitsReloader {
+    fn name(&self) -> &'static str {
+        "channel_limits"
+    }
+
+    async fn run(
+        self: Box<Self>,
+        handles: NodeHandles,
+        cancellation: CancellationToken,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut reload = handles.reload.subscribe();
+        loop {
+            self.0.set(&reload.borrow_and_update().channel_capacities);
+            tokio::select! {
+                _ = cancellation.cancelled() => return Ok(()),
+                changed = reload.changed() => {
+                    if changed.is_err() {
+                        cancellation.cancelled().await;
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RollupNode, RollupNodeService};
+    use alloy_rpc_types_engine::JwtSecret;
+    use kona_genesis::RollupConfig;
+    use kona_rpc::HealthStatus;
+    use std::time::Duration;
+
+    /// A [`NodeExtension`] that hands out the [`NodeHandles`] along with its own health, and
+    /// reports when it is cancelled.
+    #[derive(Debug)]
+    struct ProbeExtension {
+        handles: oneshot::Sender<(NodeHandles, Option<HealthStatus>)>,
+        stopped: oneshot::Sender<()>,
+    }
+
+    #[async_trait]
+    impl NodeExtension for ProbeExtension {
+        fn name(&self) -> &'static str {
+            "probe"
+        }
+
+        async fn run(
+            self: Box<Self>,
+            handles: NodeHandles,
+            cancellation: CancellationToken,
+        ) -> Result<(), Box<dyn Error + Send + Sync>> {
+            let health = handles.health.get(self.name());
+            let _ = self.handles.send((handles, health));
+            cancellation.cancelled().await;
+            let _ = self.stopped.send(());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_node_runs_extension_with_handles() {
+        let (handles_tx, handles_rx) = oneshot::channel();
+        let (stopped_tx, stopped_rx) = oneshot::channel();
+        let (reload_tx, reload_rx) = watch::channel(ReloadableConfig::default());
+        let (reorgs, mut reorgs_rx) = broadcast::channel(1);
+
+        // Nothing listens on the endpoints, which the node only connects to lazily.
+        let url: url::Url = "http://127.0.0.1:1".parse().unwrap();
+        let node = RollupNode::builder(RollupConfig::default())
+            .with_l1_provider_rpc_url(url.clone())
+            .with_l2_provider_rpc_url(url.clone())
+            .with_l2_engine_rpc_url(url)
+            .with_jwt_secret(JwtSecret::random())
+            .with_p2p_disabled()
+            .with_reloadable_config(reload_tx)
+            .with_reorg_events(reorgs)
+            .with_shutdown_grace_period(Duration::from_secs(1))
+            .with_extension(ProbeExtension { handles: handles_tx, stopped: stopped_tx })
+            .build();
+        let node = tokio::spawn(async move { node.start().await });
+
+        let (handles, health) = handles_rx.await.unwrap();
+        assert_eq!(health, Some(HealthStatus::Healthy));
+        #[cfg(feature = "p2p")]
+        assert!(handles.p2p_requests.is_none());
+
+        // The handles are wired to the channels the node was built with.
+        handles.reload.send_modify(|_| {});
+        assert!(reload_rx.has_changed().unwrap());
+        let reorg =
+            ReorgEvent::L1 { old_head: BlockInfo::default(), new_head: BlockInfo::default() };
+        handles.reorgs.send(reorg).unwrap();
+        assert_eq!(reorgs_rx.try_recv().unwrap(), reorg);
+
+        // Shutting down the node through the handles cancels the extension.
+        handles.shutdown.shutdown();
+        stopped_rx.await.unwrap();
+        node.await.unwrap().unwrap();
+    }
+}
@@ -9,8 +9,16 @@ pub use core::RollupNodeService;
 mod standard;
 pub use standard::{RollupNode, RollupNodeBuilder, RollupNodeError};
 
+mod extension;
+pub(crate) use extension::{ChannelLimitsReloader, HandlesExtension};
+pub use extension::{NodeExtension, NodeHandles};
+
 mod mode;
 pub use mode::{InteropMode, NodeMode};
 
+mod shutdown;
+pub(crate) use shutdown::ActorTasks;
+pub use shutdown::{DEFAULT_SHUTDOWN_GRACE_PERIOD, ShutdownCoordinator, ShutdownStage};
+
 pub(crate) mod util;
 pub(crate) use util::spawn_and_wait;
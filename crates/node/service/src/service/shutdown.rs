@@ -0,0 +1,260 @@
+//! Contains the [`ShutdownCoordinator`], which tears down the node's actors in dependency order.
+
+use crate::{Clock, SystemClock};
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
+use tokio::task::{AbortHandle, Id, JoinSet};
+use tokio_util::sync::CancellationToken;
+
+/// The default grace period given to each [`ShutdownStage`] before its actors are aborted.
+pub const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// A stage of the rollup node's shutdown sequence.
+///
+/// Stages are shut down in the order they are declared. Actors that produce work for the engine
+/// are stopped first so that the engine can flush its in-flight tasks without new work arriving,
+/// and the networking actors are stopped last so that the engine can still receive L1 and p2p
+/// updates while it drains.
+#[derive(Debug, derive_more::Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShutdownStage {
+    /// The sequencer actor.
+    #[display("sequencer")]
+    Sequencer,
+    /// The derivation actor.
+    #[display("derivation")]
+    Derivation,
+    /// The engine actor.
+    #[display("engine")]
+    Engine,
+    /// The networking actors: p2p, rpc, the DA watcher and the runtime config loader.
+    #[display("network")]
+    Network,
+}
+
+impl ShutdownStage {
+    /// All [`ShutdownStage`]s, in shutdown order.
+    pub const ALL: [Self; 4] = [Self::Sequencer, Self::Derivation, Self::Engine, Self::Network];
+}
+
+/// The [`ShutdownCoordinator`] owns a [`CancellationToken`] per [`ShutdownStage`].
+///
+/// When a shutdown is triggered, either by an actor failing, by [`Self::shutdown`], or by a
+/// `SIGINT`, each stage's token is cancelled in turn. The coordinator waits up to the configured
+/// grace period for all actors in the stage to exit before aborting them and moving on to the
+/// next stage.
+#[derive(Debug, Clone)]
+pub struct ShutdownCoordinator {
+    /// Triggers the shutdown sequence.
+    signal: CancellationToken,
+    /// The cancellation tokens for each [`ShutdownStage`], indexed by stage.
+    stages: [CancellationToken; 4],
+    /// The time given to each stage to exit before its actors are aborted.
+    grace_period: Duration,
+    /// The [`Clock`] used to enforce the grace period.
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new(DEFAULT_SHUTDOWN_GRACE_PERIOD)
+    }
+}
+
+impl ShutdownCoordinator {
+    /// Creates a new [`ShutdownCoordinator`] with the given per-stage grace period.
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            signal: CancellationToken::new(),
+            stages: Default::default(),
+            grace_period,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Sets the [`Clock`] used to enforce the grace period. Defaults to the [`SystemClock`].
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock, ..self }
+    }
+
+    /// Returns the per-stage grace period.
+    pub const fn grace_period(&self) -> Duration {
+        self.grace_period
+    }
+
+    /// Returns the [`CancellationToken`] that is cancelled when the given [`ShutdownStage`] is
+    /// shut down.
+    pub fn token(&self, stage: ShutdownStage) -> CancellationToken {
+        self.stages[stage as usize].clone()
+    }
+
+    /// Triggers the shutdown sequence.
+    pub fn shutdown(&self) {
+        self.signal.cancel();
+    }
+
+    /// Returns `true` if the shutdown sequence has been triggered.
+    pub fn is_shutting_down(&self) -> bool {
+        self.signal.is_cancelled()
+    }
+
+    /// Waits for the shutdown sequence to be triggered, then shuts down the [`ActorTasks`] stage
+    /// by stage.
+    pub(crate) async fn run(&self, mut tasks: ActorTasks) {
+        while !tasks.is_empty() {
+            tokio::select! {
+                _ = self.signal.cancelled() => break,
+                _ = tokio::signal::ctrl_c() => {
+                    info!(target: "rollup_node", "Received SIGINT");
+                    break;
+                }
+                Some((stage, result)) = tasks.join_next() => {
+                    if let Err(e) = result {
+                        error!(target: "rollup_node", %stage, "Critical error in sub-routine: {e}");
+                        break;
+                    }
+                }
+            }
+        }
+        self.signal.cancel();
+
+        info!(target: "rollup_node", grace_period = ?self.grace_period, "Shutting down rollup node");
+        for stage in ShutdownStage::ALL {
+            self.stages[stage as usize].cancel();
+
+            let deadline = self.clock.now() + self.grace_period;
+            while tasks.contains(stage) {
+                let result = tokio::select! {
+                    result = tasks.join_next() => Some(result),
+                    _ = self.clock.sleep_until(deadline) => None,
+                };
+                match result {
+                    Some(Some((stage, Err(e)))) => {
+                        warn!(target: "rollup_node", %stage, "Sub-routine exited with error during shutdown: {e}");
+                    }
+                    Some(_) => { /* Actor exited cleanly */ }
+                    None => {
+                        warn!(target: "rollup_node", %stage, "Grace period elapsed, aborting sub-routines");
+                        tasks.abort(stage);
+                        while tasks.contains(stage) {
+                            tasks.join_next().await;
+                        }
+                    }
+                }
+            }
+            debug!(target: "rollup_node", %stage, "Shutdown stage complete");
+        }
+        info!(target: "rollup_node", "Rollup node shut down");
+    }
+}
+
+/// A set of spawned actor tasks, tagged with the [`ShutdownStage`] they belong to.
+#[derive(Debug, Default)]
+pub(crate) struct ActorTasks {
+    /// The running tasks.
+    tasks: JoinSet<Result<(), String>>,
+    /// The stage and abort handle of each running task.
+    handles: HashMap<Id, (ShutdownStage, AbortHandle)>,
+}
+
+impl ActorTasks {
+    /// Spawns a new task for the given [`ShutdownStage`].
+    pub(crate) fn spawn<F>(&mut self, stage: ShutdownStage, task: F)
+    where
+        F: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let handle = self.tasks.spawn(task);
+        self.handles.insert(handle.id(), (stage, handle));
+    }
+
+    /// Returns `true` if there are no running tasks.
+    fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Returns `true` if any task in the given [`ShutdownStage`] is still running.
+    fn contains(&self, stage: ShutdownStage) -> bool {
+        self.handles.values().any(|(s, _)| *s == stage)
+    }
+
+    /// Aborts all tasks in the given [`ShutdownStage`].
+    fn abort(&self, stage: ShutdownStage) {
+        self.handles.values().filter(|(s, _)| *s == stage).for_each(|(_, h)| h.abort());
+    }
+
+    /// Waits for the next task to exit, returning its [`ShutdownStage`] and result. Join errors
+    /// are flattened into the task's error.
+    async fn join_next(&mut self) -> Option<(ShutdownStage, Result<(), String>)> {
+        let (id, result) = match self.tasks.join_next_with_id().await? {
+            Ok((id, result)) => (id, result),
+            Err(e) => (e.id(), Err(format!("Task join error: {e}"))),
+        };
+        let (stage, _) = self.handles.remove(&id)?;
+        Some((stage, result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::time::Instant;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_in_stage_order() {
+        let coordinator = ShutdownCoordinator::default();
+        let stopped = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = ActorTasks::default();
+
+        // Stages are shut down in their declared order, not in the order they were spawned.
+        for stage in ShutdownStage::ALL.into_iter().rev() {
+            let (token, stopped) = (coordinator.token(stage), stopped.clone());
+            tasks.spawn(stage, async move {
+                token.cancelled().await;
+                stopped.lock().unwrap().push(stage);
+                Ok(())
+            });
+        }
+
+        coordinator.shutdown();
+        coordinator.run(tasks).await;
+        assert_eq!(*stopped.lock().unwrap(), ShutdownStage::ALL);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_on_actor_failure() {
+        let coordinator = ShutdownCoordinator::default();
+        let mut tasks = ActorTasks::default();
+        let token = coordinator.token(ShutdownStage::Network);
+        tasks.spawn(ShutdownStage::Network, async move {
+            token.cancelled().await;
+            Ok(())
+        });
+        tasks.spawn(ShutdownStage::Engine, async { Err("engine failed".to_string()) });
+
+        coordinator.run(tasks).await;
+        assert!(coordinator.is_shutting_down());
+        assert!(coordinator.token(ShutdownStage::Network).is_cancelled());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_aborts_stage_after_grace_period() {
+        let coordinator = ShutdownCoordinator::new(Duration::from_secs(5));
+        let stopped_at = Arc::new(Mutex::new(None));
+        let mut tasks = ActorTasks::default();
+
+        // The engine ignores its token, and holds back the next stage until it is aborted.
+        tasks.spawn(ShutdownStage::Engine, std::future::pending());
+        let (token, network_stopped_at) =
+            (coordinator.token(ShutdownStage::Network), stopped_at.clone());
+        tasks.spawn(ShutdownStage::Network, async move {
+            token.cancelled().await;
+            *network_stopped_at.lock().unwrap() = Some(Instant::now());
+            Ok(())
+        });
+
+        let start = Instant::now();
+        coordinator.shutdown();
+        coordinator.run(tasks).await;
+        let stopped_at = stopped_at.lock().unwrap().expect("network stage not shut down");
+        assert_eq!(stopped_at - start, coordinator.grace_period());
+    }
+}
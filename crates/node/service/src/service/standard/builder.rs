@@ -1,26 +1,70 @@
 //! Contains the builder for the [`RollupNode`].
 
-use crate::{EngineLauncher, InteropMode, NodeMode, RollupNode, actors::RuntimeState};
-use alloy_primitives::Bytes;
+use crate::{
+    BackfillConfig, ChainHaltConfig, Clock, DEFAULT_RESET_TIMEOUT, DEFAULT_SHUTDOWN_GRACE_PERIOD,
+    EngineLauncher, EventJournal, InteropMode, L1PollIntervals, NodeExtension, NodeHandles,
+    NodeMode, RestartConfig, RollupNode, SafeHeadHintSource, ShadowConfig, SubmissionTracker,
+    SystemClock,
+    actors::RuntimeState,
+    bus::BusConfig,
+    service::{ChannelLimitsReloader, HandlesExtension},
+};
 use alloy_provider::RootProvider;
-use alloy_rpc_client::RpcClient;
 use alloy_rpc_types_engine::JwtSecret;
-use alloy_transport_http::{
-    AuthLayer, Http, HyperClient,
-    hyper_util::{client::legacy::Client, rt::TokioExecutor},
-};
-use http_body_util::Full;
 use op_alloy_network::Optimism;
-use std::sync::Arc;
-use tower::ServiceBuilder;
+use std::sync::{Arc, Mutex};
 use url::Url;
 
+use kona_engine::{
+    BuildBudget, DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY, DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY,
+    ElSyncConfig, EngineTaskTimeouts, EngineTransport, MaintenanceRegistry, MaintenanceTask,
+    SafeHeadRetention, SyncMode, SyncingBufferConfig, UnsafeLagConfig,
+};
 use kona_genesis::RollupConfig;
+#[cfg(feature = "p2p")]
 use kona_p2p::Config;
-use kona_providers_alloy::OnlineBeaconClient;
-use kona_rpc::{RpcConfig, RpcLauncher, SupervisorRpcConfig};
+use kona_protocol::MemoryAudit;
+use kona_providers_alloy::{L1BlockLimit, L1Recorder, OnlineBeaconClient, SidecarVerification};
+use kona_rpc::{
+    ReloadableConfig, ReorgEvent, RpcConfig, RpcLauncher, SubmissionRegistry, SupervisorRpcConfig,
+};
+use tokio::sync::{broadcast, oneshot, watch};
+
+/// The number of [`ReorgEvent`]s buffered for each subscriber before the oldest are dropped.
+const REORG_EVENT_CAPACITY: usize = 256;
 
 /// The [`RollupNodeBuilder`] is used to construct a [`RollupNode`] service.
+///
+/// Besides backing the `kona-node` binary, the builder is the entrypoint for running the node
+/// in-process from another program. Embedders can run their own tasks inside the node with
+/// [`Self::with_extension`], receive [`NodeHandles`] to the node's event streams with
+/// [`Self::with_handles_sender`], and replace the p2p network with [`Self::with_p2p_disabled`].
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// use kona_node_service::{RollupNode, RollupNodeService};
+///
+/// let (handles_tx, handles_rx) = tokio::sync::oneshot::channel();
+/// let node = RollupNode::builder(rollup_config)
+///     .with_l1_provider_rpc_url(l1_rpc)
+///     .with_l1_beacon_api_url(l1_beacon)
+///     .with_l2_provider_rpc_url(l2_rpc)
+///     .with_l2_engine_rpc_url(l2_engine)
+///     .with_jwt_secret(jwt_secret)
+///     .with_p2p_disabled()
+///     .with_extension(MyGossipBridge::new())
+///     .with_handles_sender(handles_tx)
+///     .build();
+///
+/// tokio::spawn(async move { node.start().await });
+///
+/// let handles = handles_rx.await?;
+/// let mut engine_state = handles.engine_state;
+/// while engine_state.changed().await.is_ok() {
+///     println!("unsafe head: {:?}", engine_state.borrow().unsafe_head());
+/// }
+/// ```
 #[derive(Debug, Default)]
 pub struct RollupNodeBuilder {
     /// The rollup configuration.
@@ -29,6 +73,18 @@ pub struct RollupNodeBuilder {
     l1_provider_rpc_url: Option<Url>,
     /// The L1 beacon API URL.
     l1_beacon_api_url: Option<Url>,
+    /// The L1 EL provider shared with other nodes of the process, if any.
+    l1_provider: Option<RootProvider>,
+    /// The L1 beacon client shared with other nodes of the process, if any.
+    l1_beacon: Option<OnlineBeaconClient>,
+    /// The cold-tier L1 EL provider RPC URL, and the age (in L1 blocks) after which it is used.
+    l1_cold_provider_rpc_url: Option<(Url, u64)>,
+    /// The cold-tier L1 beacon API URL, and the age (in seconds) after which it is used.
+    l1_cold_beacon_api_url: Option<(Url, u64)>,
+    /// The fallback L1 beacon API URLs, e.g. other beacon nodes or blob archivers.
+    l1_beacon_fallback_api_urls: Vec<Url>,
+    /// How strictly the blob sidecars served by the L1 beacon APIs are verified.
+    sidecar_verification: SidecarVerification,
     /// The L2 engine RPC URL.
     l2_engine_rpc_url: Option<Url>,
     /// The L2 EL provider RPC URL.
@@ -36,6 +92,7 @@ pub struct RollupNodeBuilder {
     /// The JWT secret.
     jwt_secret: Option<JwtSecret>,
     /// The [`Config`].
+    #[cfg(feature = "p2p")]
     p2p_config: Option<Config>,
     /// An RPC Configuration.
     rpc_config: Option<RpcConfig>,
@@ -47,6 +104,84 @@ pub struct RollupNodeBuilder {
     mode: NodeMode,
     /// Whether to run the node in interop mode.
     interop_mode: InteropMode,
+    /// The grace period given to each shutdown stage before its actors are aborted.
+    shutdown_grace_period: Option<std::time::Duration>,
+    /// The time after which a reset phase that was not left is retried.
+    reset_timeout: Option<std::time::Duration>,
+    /// The sender for the runtime-reloadable subset of the node's configuration.
+    reloadable_config: Option<watch::Sender<ReloadableConfig>>,
+    /// The strategy used to start syncing the L2 chain.
+    sync_mode: SyncMode,
+    /// The number of L1 blocks the sequencer keeps between the L1 head and its L1 origins.
+    sequencer_l1_confs: u64,
+    /// The number of confirmations that L1 blocks must have before derivation consumes them.
+    l1_confirmation_depth: u64,
+    /// The intervals at which the L1 heads are polled.
+    l1_poll_intervals: L1PollIntervals,
+    /// The maximum number of buffered unsafe payloads.
+    unsafe_buffer_capacity: Option<usize>,
+    /// The directory to persist buffered unsafe payloads to, if any.
+    unsafe_buffer_dir: Option<std::path::PathBuf>,
+    /// The number of payload envelopes of the latest unsafe blocks kept for retrieval by hash.
+    envelope_store_capacity: Option<usize>,
+    /// The directory to persist the payload envelopes of the latest unsafe blocks to, if any.
+    envelope_store_dir: Option<std::path::PathBuf>,
+    /// How much of the history of the safe head index is kept.
+    safe_head_retention: SafeHeadRetention,
+    /// The path to persist the safe head index to, if any.
+    safe_head_index_path: Option<std::path::PathBuf>,
+    /// The configuration of graceful restarts, if enabled.
+    restart: Option<RestartConfig>,
+    /// The configuration of the backfill actor, if enabled.
+    backfill: Option<BackfillConfig>,
+    /// The configuration of shadow mode, if enabled.
+    shadow: Option<ShadowConfig>,
+    /// The configuration of the chain-halt watchdog, if enabled.
+    chain_halt: Option<ChainHaltConfig>,
+    /// The [`Clock`] used by the node's actors.
+    clock: Option<Arc<dyn Clock>>,
+    /// The sender on which [`ReorgEvent`]s are published.
+    reorg_events: Option<broadcast::Sender<ReorgEvent>>,
+    /// The [`BusConfig`] of the event channels between actors.
+    bus: BusConfig,
+    /// The [`EventJournal`] that key node events are recorded in.
+    journal: EventJournal,
+    /// The [`L1Recorder`] that the L1 data consumed by derivation is recorded into, if any.
+    l1_recorder: Option<L1Recorder>,
+    /// The [`MaintenanceRegistry`] of tasks run while the engine is idle.
+    maintenance: MaintenanceRegistry,
+    /// The [`BuildBudget`] of block builds.
+    build_budget: BuildBudget,
+    /// Whether the p2p network is disabled.
+    #[cfg(feature = "p2p")]
+    p2p_disabled: bool,
+    /// The [`NodeExtension`]s to run alongside the actors.
+    extensions: Vec<Box<dyn NodeExtension>>,
+    /// The fault injector applied to the L1 provider, beacon client and engine client, if any.
+    #[cfg(feature = "chaos")]
+    chaos: Option<kona_providers_alloy::Chaos>,
+    /// The [`MemoryAudit`] that the sizes of the node's buffers are recorded into, if any.
+    memory_audit: Option<MemoryAudit>,
+    /// The path to persist the L2 blocks awaiting finalization to, if any.
+    finality_path: Option<std::path::PathBuf>,
+    /// The [`ElSyncConfig`] of the supervision of the execution layer sync.
+    el_sync: ElSyncConfig,
+    /// The [`EngineTaskTimeouts`] of the engine tasks.
+    task_timeouts: EngineTaskTimeouts,
+    /// The [`SyncingBufferConfig`] of the engine tasks held back while the EL is syncing.
+    syncing_buffer: SyncingBufferConfig,
+    /// How long ahead of a hardfork the engine API calls are shadowed, if they are.
+    engine_shadow_lookahead: Option<std::time::Duration>,
+    /// Whether the blocks promoted to safe are checked against their derived attributes.
+    consistency_check: bool,
+    /// The [`UnsafeLagConfig`] after which EL sync is re-triggered, if enabled.
+    unsafe_lag: Option<UnsafeLagConfig>,
+    /// The [`EngineTransport`] to the execution client.
+    engine_transport: EngineTransport,
+    /// Where the trusted safe head hint is taken from, if any.
+    safe_head_hint: Option<SafeHeadHintSource>,
+    /// The memory ceiling of the state buffered by the derivation pipeline, in bytes, if any.
+    derivation_memory_ceiling: Option<usize>,
 }
 
 impl RollupNodeBuilder {
@@ -75,11 +210,55 @@ impl RollupNodeBuilder {
         Self { l1_provider_rpc_url: Some(l1_provider_rpc_url), ..self }
     }
 
-    /// Appends an L1 beacon API URL to the builder.
+    /// Appends an L1 beacon API URL to the builder. It is only used by chains that post blobs,
+    /// see [`RollupConfig::blobs_enabled`].
     pub fn with_l1_beacon_api_url(self, l1_beacon_api_url: Url) -> Self {
         Self { l1_beacon_api_url: Some(l1_beacon_api_url), ..self }
     }
 
+    /// Sets the L1 EL provider used by the node, instead of one connected to the
+    /// [L1 provider RPC URL][Self::with_l1_provider_rpc_url], which must still be set.
+    ///
+    /// This allows nodes of multiple chains embedded in one process to share the connection pool
+    /// of their L1 EL provider.
+    pub fn with_l1_provider(self, l1_provider: RootProvider) -> Self {
+        Self { l1_provider: Some(l1_provider), ..self }
+    }
+
+    /// Sets the L1 beacon client used by the node, instead of one connected to the
+    /// [L1 beacon API URL][Self::with_l1_beacon_api_url]. See [`Self::with_l1_provider`].
+    pub fn with_l1_beacon_client(self, l1_beacon: OnlineBeaconClient) -> Self {
+        Self { l1_beacon: Some(l1_beacon), ..self }
+    }
+
+    /// Appends a cold-tier L1 EL provider RPC URL to the builder, e.g. an archive node. The
+    /// derivation pipeline queries it first for blocks more than `after_blocks` behind the L1 head.
+    pub fn with_l1_cold_provider_rpc_url(self, url: Url, after_blocks: u64) -> Self {
+        Self { l1_cold_provider_rpc_url: Some((url, after_blocks)), ..self }
+    }
+
+    /// Appends a cold-tier L1 beacon API URL to the builder, e.g. a blob archiver. The derivation
+    /// pipeline queries it first for the blobs of blocks older than `after_secs`.
+    pub fn with_l1_cold_beacon_api_url(self, url: Url, after_secs: u64) -> Self {
+        Self { l1_cold_beacon_api_url: Some((url, after_secs)), ..self }
+    }
+
+    /// Sets fallback L1 beacon API URLs on the builder, e.g. other beacon nodes or blob
+    /// archivers. The blob sidecar availability window of each endpoint is probed at startup, so
+    /// that the sidecars of old blocks are fetched from the endpoints that retain them, and a
+    /// request that fails on one endpoint resumes from the next. See [`FailoverBeaconClient`].
+    ///
+    /// [`FailoverBeaconClient`]: kona_providers_alloy::FailoverBeaconClient
+    pub fn with_l1_beacon_fallback_api_urls(self, urls: Vec<Url>) -> Self {
+        Self { l1_beacon_fallback_api_urls: urls, ..self }
+    }
+
+    /// Sets how strictly the blob sidecars served by the L1 beacon APIs are verified against the
+    /// beacon block header they are served with. Defaults to [`SidecarVerification::Strict`].
+    pub fn with_sidecar_verification(self, sidecar_verification: SidecarVerification) -> Self {
+        Self { sidecar_verification, ..self }
+    }
+
     /// Appends an L2 engine RPC URL to the builder.
     pub fn with_l2_engine_rpc_url(self, l2_engine_rpc_url: Url) -> Self {
         Self { l2_engine_rpc_url: Some(l2_engine_rpc_url), ..self }
@@ -96,6 +275,7 @@ impl RollupNodeBuilder {
     }
 
     /// Appends the P2P [`Config`] to the builder.
+    #[cfg(feature = "p2p")]
     pub fn with_p2p_config(self, config: Config) -> Self {
         Self { p2p_config: Some(config), ..self }
     }
@@ -110,6 +290,299 @@ impl RollupNodeBuilder {
         Self { runtime_load_interval: Some(interval), ..self }
     }
 
+    /// Sets the per-stage shutdown grace period on the [`RollupNodeBuilder`].
+    ///
+    /// Defaults to [`DEFAULT_SHUTDOWN_GRACE_PERIOD`].
+    pub fn with_shutdown_grace_period(self, grace_period: std::time::Duration) -> Self {
+        Self { shutdown_grace_period: Some(grace_period), ..self }
+    }
+
+    /// Sets the reset timeout on the [`RollupNodeBuilder`]: a reset requested by derivation
+    /// that the engine does not pick up within it is requested again, and the derivation actor
+    /// is reported as degraded until it is.
+    ///
+    /// Defaults to [`DEFAULT_RESET_TIMEOUT`].
+    pub fn with_reset_timeout(self, timeout: std::time::Duration) -> Self {
+        Self { reset_timeout: Some(timeout), ..self }
+    }
+
+    /// Sets the sender for the node's [`ReloadableConfig`] on the [`RollupNodeBuilder`].
+    ///
+    /// Updates sent on this channel, e.g. on `SIGHUP`, are propagated to the node's actors at
+    /// runtime. If not set, the node starts with a default [`ReloadableConfig`], which can still be
+    /// updated over the admin RPC.
+    pub fn with_reloadable_config(self, sender: watch::Sender<ReloadableConfig>) -> Self {
+        Self { reloadable_config: Some(sender), ..self }
+    }
+
+    /// Sets the sender on which the node publishes [`ReorgEvent`]s on the [`RollupNodeBuilder`].
+    ///
+    /// Embedders can subscribe to the sender before the node is started to receive every reorg
+    /// observed by the node. If not set, a new channel is created, which is still exposed over the
+    /// `ws_subscribe_reorgs` RPC subscription.
+    pub fn with_reorg_events(self, sender: broadcast::Sender<ReorgEvent>) -> Self {
+        Self { reorg_events: Some(sender), ..self }
+    }
+
+    /// Sets the [`BusConfig`] of the event channels between the node's actors on the
+    /// [`RollupNodeBuilder`].
+    ///
+    /// Defaults to [`BusConfig::default`].
+    pub fn with_bus_config(self, bus: BusConfig) -> Self {
+        Self { bus, ..self }
+    }
+
+    /// Disables the p2p network on the [`RollupNodeBuilder`].
+    ///
+    /// The node then neither gossips nor receives unsafe blocks over p2p, and the P2P config
+    /// is not required. A [`NodeExtension`] can take the place of the network layer by sending
+    /// unsafe blocks on [`NodeHandles::unsafe_blocks`].
+    ///
+    /// Without the `p2p` feature, the p2p network is always disabled.
+    pub fn with_p2p_disabled(self) -> Self {
+        Self {
+            #[cfg(feature = "p2p")]
+            p2p_disabled: true,
+            ..self
+        }
+    }
+
+    /// Adds a [`NodeExtension`] to the [`RollupNodeBuilder`], which is run alongside the node's
+    /// actors once it starts.
+    pub fn with_extension(mut self, extension: impl NodeExtension) -> Self {
+        self.extensions.push(Box::new(extension));
+        self
+    }
+
+    /// Sets a channel on the [`RollupNodeBuilder`] that the node's [`NodeHandles`] are sent on
+    /// once it starts.
+    pub fn with_handles_sender(self, sender: oneshot::Sender<NodeHandles>) -> Self {
+        self.with_extension(HandlesExtension(sender))
+    }
+
+    /// Sets the [`SyncMode`] on the [`RollupNodeBuilder`].
+    ///
+    /// Defaults to [`SyncMode::ExecutionLayer`].
+    pub fn with_sync_mode(self, sync_mode: SyncMode) -> Self {
+        Self { sync_mode, ..self }
+    }
+
+    /// Sets the number of L1 blocks the sequencer keeps between the L1 head and the L1 origins it
+    /// selects on the [`RollupNodeBuilder`].
+    ///
+    /// Defaults to `0`, i.e. L1 origins may be selected up to the L1 head.
+    pub fn with_sequencer_l1_confs(self, l1_confs: u64) -> Self {
+        Self { sequencer_l1_confs: l1_confs, ..self }
+    }
+
+    /// Sets the number of confirmations that L1 blocks must have before derivation consumes them
+    /// on the [`RollupNodeBuilder`].
+    ///
+    /// A deeper confirmation depth delays derivation, but makes the L1 reorgs that reset the
+    /// pipeline rarer. Defaults to `0`, i.e. derivation follows the L1 head.
+    pub fn with_l1_confirmation_depth(self, l1_confirmation_depth: u64) -> Self {
+        Self { l1_confirmation_depth, ..self }
+    }
+
+    /// Sets the [`L1PollIntervals`] at which the L1 heads are polled on the [`RollupNodeBuilder`].
+    ///
+    /// Defaults to [`L1PollIntervals::default`].
+    pub fn with_l1_poll_intervals(self, l1_poll_intervals: L1PollIntervals) -> Self {
+        Self { l1_poll_intervals, ..self }
+    }
+
+    /// Sets the maximum number of unsafe payloads buffered ahead of the unsafe head on the
+    /// [`RollupNodeBuilder`].
+    ///
+    /// Defaults to [`DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY`].
+    pub fn with_unsafe_buffer_capacity(self, capacity: usize) -> Self {
+        Self { unsafe_buffer_capacity: Some(capacity), ..self }
+    }
+
+    /// Sets the directory that buffered unsafe payloads are persisted to on the
+    /// [`RollupNodeBuilder`]. If not set, the buffer is kept in memory.
+    pub fn with_unsafe_buffer_dir(self, dir: std::path::PathBuf) -> Self {
+        Self { unsafe_buffer_dir: Some(dir), ..self }
+    }
+
+    /// Sets the number of payload envelopes of the latest unsafe blocks, built or imported, kept
+    /// for retrieval by hash on the [`RollupNodeBuilder`].
+    ///
+    /// Defaults to [`DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY`].
+    pub fn with_envelope_store_capacity(self, capacity: usize) -> Self {
+        Self { envelope_store_capacity: Some(capacity), ..self }
+    }
+
+    /// Sets the directory that the payload envelopes of the latest unsafe blocks are persisted to
+    /// on the [`RollupNodeBuilder`]. If not set, they are kept in memory.
+    pub fn with_envelope_store_dir(self, dir: std::path::PathBuf) -> Self {
+        Self { envelope_store_dir: Some(dir), ..self }
+    }
+
+    /// Sets how much of the history of the [`SafeHeadIndex`] is kept on the
+    /// [`RollupNodeBuilder`].
+    ///
+    /// Defaults to the last [`DEFAULT_SAFE_HEAD_RETENTION_EPOCHS`] L1 blocks.
+    ///
+    /// [`SafeHeadIndex`]: kona_engine::SafeHeadIndex
+    /// [`DEFAULT_SAFE_HEAD_RETENTION_EPOCHS`]: kona_engine::DEFAULT_SAFE_HEAD_RETENTION_EPOCHS
+    pub fn with_safe_head_retention(self, retention: SafeHeadRetention) -> Self {
+        Self { safe_head_retention: retention, ..self }
+    }
+
+    /// Sets the path that the safe head index is persisted to on the [`RollupNodeBuilder`]. If
+    /// not set, it is kept in memory.
+    pub fn with_safe_head_index_path(self, path: std::path::PathBuf) -> Self {
+        Self { safe_head_index_path: Some(path), ..self }
+    }
+
+    /// Enables graceful restarts on the [`RollupNodeBuilder`]. See [`RestartConfig`].
+    ///
+    /// For the unsafe head not to lag behind after a restart, the unsafe payload buffer should be
+    /// [persisted][Self::with_unsafe_buffer_dir] as well.
+    pub fn with_restart_config(self, restart: RestartConfig) -> Self {
+        Self { restart: Some(restart), ..self }
+    }
+
+    /// Sets the path that the L2 blocks awaiting finalization are persisted to on the
+    /// [`RollupNodeBuilder`], so that the finalized head keeps advancing right after a restart.
+    /// If not set, they are kept in memory.
+    pub fn with_finality_path(self, path: std::path::PathBuf) -> Self {
+        Self { finality_path: Some(path), ..self }
+    }
+
+    /// Sets the [`ElSyncConfig`] on the [`RollupNodeBuilder`], configuring how often the progress
+    /// of the execution layer sync is polled, and after how long without progress it is
+    /// re-triggered.
+    pub fn with_el_sync_config(self, el_sync: ElSyncConfig) -> Self {
+        Self { el_sync, ..self }
+    }
+
+    /// Sets the [`SafeHeadHintSource`] on the [`RollupNodeBuilder`]. The engine then starts
+    /// derivation from the trusted safe head hint, once verified, rather than walking back from
+    /// the heads of the execution layer.
+    pub fn with_safe_head_hint(self, source: SafeHeadHintSource) -> Self {
+        Self { safe_head_hint: Some(source), ..self }
+    }
+
+    /// Sets the [`EngineTaskTimeouts`] on the [`RollupNodeBuilder`], bounding the time each kind
+    /// of engine task may run for before it is cancelled.
+    pub fn with_engine_task_timeouts(self, task_timeouts: EngineTaskTimeouts) -> Self {
+        Self { task_timeouts, ..self }
+    }
+
+    /// Sets the [`SyncingBufferConfig`] on the [`RollupNodeBuilder`], bounding the payload
+    /// attributes held back while the execution layer is syncing, and how often they are
+    /// resubmitted.
+    pub fn with_syncing_buffer_config(self, syncing_buffer: SyncingBufferConfig) -> Self {
+        Self { syncing_buffer, ..self }
+    }
+
+    /// Shadows the engine API calls for payloads within the given lookahead of a hardfork that
+    /// changes the engine API versions, with the versions it activates. The shadow calls are
+    /// logged and recorded in metrics, but never trusted. See [`EngineClient::with_shadowing`].
+    ///
+    /// [`EngineClient::with_shadowing`]: kona_engine::EngineClient::with_shadowing
+    pub fn with_engine_shadow_lookahead(self, lookahead: std::time::Duration) -> Self {
+        Self { engine_shadow_lookahead: Some(lookahead), ..self }
+    }
+
+    /// Enables the consistency check of the safe chain on the [`RollupNodeBuilder`]: each block
+    /// promoted to safe is fetched from the execution layer, and its transactions and withdrawals
+    /// root are checked against the derived attributes. Mismatches are logged, exported as
+    /// metrics and recorded in the journal, and the engine is reported as degraded.
+    pub fn with_consistency_check(self, consistency_check: bool) -> Self {
+        Self { consistency_check, ..self }
+    }
+
+    /// Sets the [`UnsafeLagConfig`] on the [`RollupNodeBuilder`]: once the execution layer has
+    /// synced, an unsafe head that lags behind the tip of gossip by more than the threshold, and
+    /// does not advance within the timeout, re-triggers execution layer sync towards the tip.
+    pub fn with_unsafe_lag_config(self, unsafe_lag: UnsafeLagConfig) -> Self {
+        Self { unsafe_lag: Some(unsafe_lag), ..self }
+    }
+
+    /// Sets the [`EngineTransport`] of the engine API and L2 RPC clients, e.g. to enable mutual
+    /// TLS or request signing. Defaults to plain HTTP authenticated with the JWT secret only.
+    pub fn with_engine_transport(self, engine_transport: EngineTransport) -> Self {
+        Self { engine_transport, ..self }
+    }
+
+    /// Registers a [`MaintenanceTask`] on the [`RollupNodeBuilder`], which the engine actor runs
+    /// against the execution layer while its task queue is idle.
+    pub fn with_maintenance_task(mut self, task: impl MaintenanceTask) -> Self {
+        self.maintenance.register(task);
+        self
+    }
+
+    /// Sets the [`BuildBudget`] on the [`RollupNodeBuilder`], the fraction of the block time that
+    /// building, importing and canonicalizing a block may take before a warning is logged.
+    pub fn with_build_budget(self, build_budget: BuildBudget) -> Self {
+        Self { build_budget, ..self }
+    }
+
+    /// Enables the backfill actor on the [`RollupNodeBuilder`], which re-derives historical safe
+    /// blocks in the background and checks them against the execution layer.
+    pub fn with_backfill_config(self, config: BackfillConfig) -> Self {
+        Self { backfill: Some(config), ..self }
+    }
+
+    /// Enables shadow mode on the [`RollupNodeBuilder`], which compares the safe chain against a
+    /// reference op-node block by block, and reports the first divergence.
+    pub fn with_shadow_config(self, config: ShadowConfig) -> Self {
+        Self { shadow: Some(config), ..self }
+    }
+
+    /// Enables the chain-halt watchdog on the [`RollupNodeBuilder`], which reports when the safe
+    /// head stalls while the L1 chain advances.
+    pub fn with_chain_halt_config(self, config: ChainHaltConfig) -> Self {
+        Self { chain_halt: Some(config), ..self }
+    }
+
+    /// Sets the [`EventJournal`] on the [`RollupNodeBuilder`], which records key node events for
+    /// post-incident analysis.
+    ///
+    /// Defaults to a disabled journal.
+    pub fn with_journal(self, journal: EventJournal) -> Self {
+        Self { journal, ..self }
+    }
+
+    /// Sets the [`L1Recorder`] on the [`RollupNodeBuilder`], which records all L1 data consumed by
+    /// the derivation pipeline into an archive that can be replayed later.
+    pub fn with_l1_recorder(self, recorder: L1Recorder) -> Self {
+        Self { l1_recorder: Some(recorder), ..self }
+    }
+
+    /// Sets the [`MemoryAudit`] on the [`RollupNodeBuilder`], which records the sizes of the
+    /// channel bank, the attributes queue and the unsafe payload buffer against their ceilings.
+    pub fn with_memory_audit(self, audit: MemoryAudit) -> Self {
+        Self { memory_audit: Some(audit), ..self }
+    }
+
+    /// Sets the number of bytes the state buffered by the derivation pipeline must not exceed.
+    /// Beyond it, the derivation actor prunes the oldest buffered channels.
+    ///
+    /// Defaults to no ceiling.
+    pub fn with_derivation_memory_ceiling(self, bytes: usize) -> Self {
+        Self { derivation_memory_ceiling: Some(bytes), ..self }
+    }
+
+    /// Injects the faults of the given [`Chaos`] injector into the requests of the L1 providers,
+    /// the L1 beacon clients and the engine client, to exercise the node's retry and reset logic.
+    ///
+    /// [`Chaos`]: kona_providers_alloy::Chaos
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(self, chaos: kona_providers_alloy::Chaos) -> Self {
+        Self { chaos: Some(chaos), ..self }
+    }
+
+    /// Sets the [`Clock`] used by the node's actors on the [`RollupNodeBuilder`].
+    ///
+    /// Defaults to the [`SystemClock`].
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock: Some(clock), ..self }
+    }
+
     /// Assembles the [`RollupNode`] service.
     ///
     /// By default, the supervisor RPC is disabled.
@@ -119,48 +592,124 @@ impl RollupNodeBuilder {
     ///
     /// Panics if:
     /// - The L1 provider RPC URL is not set.
-    /// - The L1 beacon API URL is not set.
+    /// - The L1 beacon API URL and client are not set, and the chain posts blobs.
     /// - The L2 provider RPC URL is not set.
     /// - The L2 engine URL is not set.
     /// - The jwt secret is not set.
-    /// - The P2P config is not set.
+    /// - The P2P config is not set, and the p2p network is not [disabled][Self::with_p2p_disabled].
+    /// - The node runs in sequencer mode, but the `sequencer` feature is disabled.
     pub fn build(self) -> RollupNode {
-        let l1_rpc_url = self.l1_provider_rpc_url.expect("l1 provider rpc url not set");
-        let l1_provider = RootProvider::new_http(l1_rpc_url.clone());
-        let l1_beacon = OnlineBeaconClient::new_http(
-            self.l1_beacon_api_url.expect("l1 beacon api url not set").to_string(),
+        #[cfg(not(feature = "sequencer"))]
+        assert!(
+            self.mode != NodeMode::Sequencer,
+            "sequencer mode requires the `sequencer` feature of kona-node-service"
         );
+        let l1_rpc_url = self.l1_provider_rpc_url.expect("l1 provider rpc url not set");
+        let l1_provider =
+            self.l1_provider.unwrap_or_else(|| RootProvider::new_http(l1_rpc_url.clone()));
+        // Chains that never post blobs are derived from calldata only, without a beacon API.
+        let blobs_enabled = self.config.blobs_enabled();
+        let l1_beacon = blobs_enabled.then(|| {
+            self.l1_beacon.unwrap_or_else(|| {
+                OnlineBeaconClient::new_http(
+                    self.l1_beacon_api_url.expect("l1 beacon api url not set").to_string(),
+                )
+            })
+        });
+        let l1_cold_provider =
+            self.l1_cold_provider_rpc_url.map(|(url, after)| (RootProvider::new_http(url), after));
+        let l1_cold_beacon = self
+            .l1_cold_beacon_api_url
+            .filter(|_| blobs_enabled)
+            .map(|(url, after)| (OnlineBeaconClient::new_http(url.to_string()), after));
+        let l1_beacon_fallbacks = self
+            .l1_beacon_fallback_api_urls
+            .into_iter()
+            .filter(|_| blobs_enabled)
+            .map(|url| OnlineBeaconClient::new_http(url.to_string()))
+            .collect::<Vec<_>>();
+        #[cfg(feature = "chaos")]
+        let (l1_provider, l1_beacon, l1_cold_provider, l1_cold_beacon, l1_beacon_fallbacks) =
+            match self.chaos.clone() {
+                Some(chaos) => {
+                    let layer = kona_providers_alloy::ChaosLayer::new(chaos.clone());
+                    (
+                        layer.provider(&l1_provider),
+                        l1_beacon.map(|beacon| beacon.with_chaos(chaos.clone())),
+                        l1_cold_provider.map(|(cold, after)| (layer.provider(&cold), after)),
+                        l1_cold_beacon.map(|(cold, after)| (cold.with_chaos(chaos.clone()), after)),
+                        l1_beacon_fallbacks
+                            .into_iter()
+                            .map(|beacon| beacon.with_chaos(chaos.clone()))
+                            .collect(),
+                    )
+                }
+                None => {
+                    (l1_provider, l1_beacon, l1_cold_provider, l1_cold_beacon, l1_beacon_fallbacks)
+                }
+            };
 
         let l2_rpc_url = self.l2_provider_rpc_url.expect("l2 provider rpc url not set");
         let jwt_secret = self.jwt_secret.expect("jwt secret not set");
-        let hyper_client = Client::builder(TokioExecutor::new()).build_http::<Full<Bytes>>();
-
-        let auth_layer = AuthLayer::new(jwt_secret);
-        let service = ServiceBuilder::new().layer(auth_layer).service(hyper_client);
-
-        let layer_transport = HyperClient::with_service(service);
-        let http_hyper = Http::with_client(layer_transport, l2_rpc_url.clone());
-        let rpc_client = RpcClient::new(http_hyper, false);
-        let l2_provider = RootProvider::<Optimism>::new(rpc_client);
+        let rpc_client = self.engine_transport.rpc_client(l2_rpc_url.clone(), jwt_secret);
+        let l2_provider = RootProvider::<Optimism>::new(rpc_client.clone());
 
         let rpc_launcher =
             self.rpc_config.map(|c| c.as_launcher()).unwrap_or(RpcLauncher::new_disabled());
 
         let rollup_config = Arc::new(self.config);
+        let clock = self.clock.unwrap_or_else(|| Arc::new(SystemClock));
+        // The channel capacities of the reloadable config are applied to the bus by an extension.
+        let mut extensions = self.extensions;
+        extensions.push(Box::new(ChannelLimitsReloader(self.bus.limits.clone())));
+        // The submission tracker follows the unsafe and safe heads as an extension.
+        let submission = SubmissionTracker::new(rollup_config.clone(), SubmissionRegistry::new());
+        extensions.push(Box::new(submission.clone()));
         let engine_launcher = EngineLauncher {
             config: Arc::clone(&rollup_config),
             l2_rpc_url,
             l1_rpc_url: l1_rpc_url.clone(),
             engine_url: self.l2_engine_rpc_url.expect("missing l2 engine rpc url"),
             jwt_secret,
+            sync_mode: self.sync_mode,
+            unsafe_buffer_capacity: self
+                .unsafe_buffer_capacity
+                .unwrap_or(DEFAULT_UNSAFE_PAYLOAD_BUFFER_CAPACITY),
+            unsafe_buffer_dir: self.unsafe_buffer_dir,
+            envelope_store_capacity: self
+                .envelope_store_capacity
+                .unwrap_or(DEFAULT_PAYLOAD_ENVELOPE_STORE_CAPACITY),
+            envelope_store_dir: self.envelope_store_dir,
+            safe_head_retention: self.safe_head_retention,
+            safe_head_index_path: self.safe_head_index_path,
+            maintenance: self.maintenance,
+            build_budget: self.build_budget,
+            restart: self.restart,
+            #[cfg(feature = "chaos")]
+            chaos: self.chaos,
+            memory_audit: self.memory_audit.clone(),
+            finality_path: self.finality_path,
+            el_sync: self.el_sync,
+            task_timeouts: self.task_timeouts,
+            syncing_buffer: self.syncing_buffer,
+            shadow_lookahead: self.engine_shadow_lookahead,
+            transport: self.engine_transport,
+            safe_head_hint: self.safe_head_hint,
+            consistency_check: self.consistency_check,
+            unsafe_lag: self.unsafe_lag,
         };
 
         let runtime_launcher = self.runtime_load_interval.map(|load_interval| RuntimeState {
             loader: kona_sources::RuntimeLoader::new(l1_rpc_url, rollup_config.clone()),
             interval: load_interval,
+            clock: clock.clone(),
         });
 
-        let p2p_config = self.p2p_config.expect("P2P config not set");
+        #[cfg(feature = "p2p")]
+        let p2p_config = match self.p2p_disabled {
+            true => None,
+            false => Some(self.p2p_config.expect("P2P config not set")),
+        };
 
         let interop_mode = match self.supervisor_rpc_config.is_disabled() {
             true => self.interop_mode,
@@ -173,13 +722,45 @@ impl RollupNodeBuilder {
             interop_mode,
             l1_provider,
             l1_beacon,
+            l1_cold_provider,
+            l1_cold_beacon,
+            l1_beacon_fallbacks,
+            sidecar_verification: self.sidecar_verification,
             l2_provider,
+            l2_rpc_client: rpc_client,
             engine_launcher,
             rpc_launcher,
+            #[cfg(feature = "p2p")]
             p2p_config,
             runtime_launcher,
             // By default, the supervisor rpc config is disabled.
+            #[cfg(feature = "interop")]
             supervisor_rpc: self.supervisor_rpc_config,
+            sequencer_l1_confs: self.sequencer_l1_confs,
+            l1_confirmation_depth: self.l1_confirmation_depth,
+            l1_derivation_limit: (self.l1_confirmation_depth > 0).then(L1BlockLimit::default),
+            l1_poll_intervals: self.l1_poll_intervals,
+            backfill: self.backfill,
+            shadow: self.shadow,
+            chain_halt: self.chain_halt,
+            clock,
+            shutdown_grace_period: self
+                .shutdown_grace_period
+                .unwrap_or(DEFAULT_SHUTDOWN_GRACE_PERIOD),
+            reset_timeout: self.reset_timeout.unwrap_or(DEFAULT_RESET_TIMEOUT),
+            reloadable_config: self
+                .reloadable_config
+                .unwrap_or_else(|| watch::Sender::new(ReloadableConfig::default())),
+            reorg_events: self
+                .reorg_events
+                .unwrap_or_else(|| broadcast::Sender::new(REORG_EVENT_CAPACITY)),
+            bus: self.bus,
+            journal: self.journal,
+            submission,
+            l1_recorder: self.l1_recorder,
+            memory_audit: self.memory_audit,
+            derivation_memory_ceiling: self.derivation_memory_ceiling,
+            extensions: Mutex::new(extensions),
         }
     }
 }
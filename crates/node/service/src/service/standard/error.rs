@@ -1,7 +1,9 @@
 //! Contains the error type for the [`crate::RollupNode`].
 
+use crate::DaChallengeConfigError;
 use jsonrpsee::server::RegisterMethodError;
 use kona_derive::PipelineErrorKind;
+#[cfg(feature = "p2p")]
 use kona_p2p::NetworkBuilderError;
 use kona_providers_alloy::AlloyChainProviderError;
 use kona_rpc::RpcLauncherError;
@@ -20,6 +22,7 @@ pub enum RollupNodeError {
     #[error(transparent)]
     AlloyChainProvider(#[from] AlloyChainProviderError),
     /// An error occurred while initializing the network.
+    #[cfg(feature = "p2p")]
     #[error(transparent)]
     Network(#[from] NetworkBuilderError),
     /// An error occurred while launching the RPC server.
@@ -28,4 +31,7 @@ pub enum RollupNodeError {
     /// An error occurred while registering RPC methods.
     #[error(transparent)]
     RegisterMethod(#[from] RegisterMethodError),
+    /// The alt-DA config is invalid.
+    #[error(transparent)]
+    DaChallengeConfig(#[from] DaChallengeConfigError),
 }
@@ -1,24 +1,47 @@
 //! Contains the [`RollupNode`] implementation.
 
 use crate::{
-    DerivationActor, EngineActor, EngineLauncher, InteropMode, L1OriginSelector, L1WatcherRpc,
-    NetworkActor, NodeMode, RollupNodeBuilder, RollupNodeError, RollupNodeService, RpcActor,
-    RuntimeActor, SequencerActor, SequencerActorState, SupervisorActor, SupervisorRpcServerExt,
-    actors::RuntimeState,
+    BackfillActor, BackfillConfig, ChainHaltConfig, ChainHaltWatchdog, Clock, DerivationActor,
+    EngineActor, EngineLauncher, EventJournal, InteropMode, L1PollIntervals, L1WatcherRpc,
+    NodeExtension, NodeMode, RollupNodeBuilder, RollupNodeError, RollupNodeService, RpcActor,
+    RuntimeActor, ShadowActor, ShadowConfig, SubmissionTracker, actors::RuntimeState,
+    bus::BusConfig,
 };
 use alloy_provider::RootProvider;
+use alloy_rpc_client::RpcClient;
 use async_trait::async_trait;
-use kona_derive::StatefulAttributesBuilder;
 use op_alloy_network::Optimism;
-use std::sync::Arc;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::{broadcast, watch};
 
+use kona_derive::{BatchObserver, BatcherTxObserver};
 use kona_genesis::RollupConfig;
-use kona_p2p::{Config, Network, NetworkBuilder};
+use kona_protocol::MemoryAudit;
 use kona_providers_alloy::{
-    AlloyChainProvider, AlloyL2ChainProvider, OnlineBeaconClient, OnlineBlobProvider,
-    OnlinePipeline,
+    AlloyChainProvider, AlloyL2ChainProvider, FailoverBeaconClient, L1BlockLimit, L1Recorder,
+    OnlineBeaconClient, OnlineBlobProvider, OnlinePipeline, SidecarVerification,
 };
-use kona_rpc::{NetworkRpc, RpcLauncher, SupervisorRpcConfig, SupervisorRpcServer};
+use kona_rpc::{ReloadableConfig, ReorgEvent, RpcLauncher, SubmissionRegistry};
+
+#[cfg(feature = "sequencer")]
+use crate::{L1OriginSelector, SequencerActor, SequencerActorState};
+#[cfg(feature = "sequencer")]
+use kona_derive::StatefulAttributesBuilder;
+
+#[cfg(feature = "p2p")]
+use crate::NetworkActor;
+#[cfg(feature = "p2p")]
+use kona_p2p::{Config, Network, NetworkBuilder};
+#[cfg(feature = "p2p")]
+use kona_rpc::NetworkRpc;
+
+#[cfg(feature = "interop")]
+use crate::{SupervisorActor, SupervisorRpcServerExt};
+#[cfg(feature = "interop")]
+use kona_rpc::{SupervisorRpcConfig, SupervisorRpcServer};
 
 /// The size of the cache used in the derivation pipeline's providers.
 const DERIVATION_PROVIDER_CACHE_SIZE: usize = 1024;
@@ -35,20 +58,71 @@ pub struct RollupNode {
     pub(crate) interop_mode: InteropMode,
     /// The L1 EL provider.
     pub(crate) l1_provider: RootProvider,
-    /// The L1 beacon API.
-    pub(crate) l1_beacon: OnlineBeaconClient,
+    /// The L1 beacon API, or `None` if the chain never posts blobs.
+    pub(crate) l1_beacon: Option<OnlineBeaconClient>,
+    /// The cold-tier L1 EL provider, and the age (in L1 blocks) after which it is used.
+    pub(crate) l1_cold_provider: Option<(RootProvider, u64)>,
+    /// The cold-tier L1 beacon API, and the age (in seconds) after which it is used.
+    pub(crate) l1_cold_beacon: Option<(OnlineBeaconClient, u64)>,
+    /// The fallback L1 beacon APIs, whose blob sidecar availability windows are probed when the
+    /// derivation pipeline is created.
+    pub(crate) l1_beacon_fallbacks: Vec<OnlineBeaconClient>,
+    /// How strictly the blob sidecars served by the L1 beacon APIs are verified.
+    pub(crate) sidecar_verification: SidecarVerification,
     /// The L2 EL provider.
     pub(crate) l2_provider: RootProvider<Optimism>,
+    /// The RPC client of the L2 EL RPC, that the `eth_*` proxy forwards queries to.
+    pub(crate) l2_rpc_client: RpcClient,
     /// The [`EngineLauncher`] handles launching the engine api.
     pub(crate) engine_launcher: EngineLauncher,
     /// The [`RpcLauncher`] for the node.
     pub(crate) rpc_launcher: RpcLauncher,
-    /// The P2P [`Config`] for the node.
-    pub(crate) p2p_config: Config,
+    /// The P2P [`Config`] for the node, or `None` if the p2p network is disabled.
+    #[cfg(feature = "p2p")]
+    pub(crate) p2p_config: Option<Config>,
     /// The [`RuntimeState`] for the runtime loading service.
     pub(crate) runtime_launcher: Option<RuntimeState>,
     /// The supervisor rpc server config.
+    #[cfg(feature = "interop")]
     pub(crate) supervisor_rpc: SupervisorRpcConfig,
+    /// The number of L1 blocks the sequencer keeps between the L1 head and its L1 origins.
+    pub(crate) sequencer_l1_confs: u64,
+    /// The number of confirmations that L1 blocks must have before derivation consumes them.
+    pub(crate) l1_confirmation_depth: u64,
+    /// The limit of the L1 blocks served to derivation, if it waits for confirmations.
+    pub(crate) l1_derivation_limit: Option<L1BlockLimit>,
+    /// The intervals at which the L1 heads are polled.
+    pub(crate) l1_poll_intervals: L1PollIntervals,
+    /// The [`BackfillConfig`], if the backfill actor is enabled.
+    pub(crate) backfill: Option<BackfillConfig>,
+    /// The [`ShadowConfig`], if shadow mode is enabled.
+    pub(crate) shadow: Option<ShadowConfig>,
+    /// The [`ChainHaltConfig`], if the chain-halt watchdog is enabled.
+    pub(crate) chain_halt: Option<ChainHaltConfig>,
+    /// The [`Clock`] used by the node's actors.
+    pub(crate) clock: Arc<dyn Clock>,
+    /// The grace period given to each shutdown stage before its actors are aborted.
+    pub(crate) shutdown_grace_period: Duration,
+    /// The time after which a reset phase that was not left is retried.
+    pub(crate) reset_timeout: Duration,
+    /// The sender for the runtime-reloadable subset of the node's configuration.
+    pub(crate) reloadable_config: watch::Sender<ReloadableConfig>,
+    /// The sender on which [`ReorgEvent`]s are published.
+    pub(crate) reorg_events: broadcast::Sender<ReorgEvent>,
+    /// The [`BusConfig`] of the event channels between actors.
+    pub(crate) bus: BusConfig,
+    /// The [`EventJournal`] that key node events are recorded in.
+    pub(crate) journal: EventJournal,
+    /// The [`SubmissionTracker`] that the batches read by derivation are reported to.
+    pub(crate) submission: SubmissionTracker,
+    /// The [`L1Recorder`] that the L1 data consumed by derivation is recorded into, if any.
+    pub(crate) l1_recorder: Option<L1Recorder>,
+    /// The [`MemoryAudit`] that the sizes of the node's buffers are recorded into, if any.
+    pub(crate) memory_audit: Option<MemoryAudit>,
+    /// The memory ceiling of the state buffered by the derivation pipeline, in bytes, if any.
+    pub(crate) derivation_memory_ceiling: Option<usize>,
+    /// The [`NodeExtension`]s to run alongside the actors, taken when the node starts.
+    pub(crate) extensions: Mutex<Vec<Box<dyn NodeExtension>>>,
 }
 
 impl RollupNode {
@@ -62,17 +136,25 @@ impl RollupNode {
 impl RollupNodeService for RollupNode {
     type DataAvailabilityWatcher = L1WatcherRpc;
     type DerivationPipeline = OnlinePipeline;
+    #[cfg(feature = "sequencer")]
     type AttributesBuilder = StatefulAttributesBuilder<AlloyChainProvider, AlloyL2ChainProvider>;
+    #[cfg(feature = "interop")]
     type SupervisorExt = SupervisorRpcServerExt;
     type Error = RollupNodeError;
 
     type RuntimeActor = RuntimeActor;
     type RpcActor = RpcActor;
     type EngineActor = EngineActor;
+    #[cfg(feature = "p2p")]
     type NetworkActor = NetworkActor;
     type DerivationActor = DerivationActor<Self::DerivationPipeline>;
+    #[cfg(feature = "interop")]
     type SupervisorActor = SupervisorActor<Self::SupervisorExt>;
+    #[cfg(feature = "sequencer")]
     type SequencerActor = SequencerActor<Self::AttributesBuilder>;
+    type BackfillActor = BackfillActor<Self::DerivationPipeline>;
+    type ShadowActor = ShadowActor;
+    type ChainHaltWatchdog = ChainHaltWatchdog;
 
     fn mode(&self) -> NodeMode {
         self.mode
@@ -86,6 +168,11 @@ impl RollupNodeService for RollupNode {
         self.l1_provider.clone()
     }
 
+    fn l2_rpc_client(&self) -> RpcClient {
+        self.l2_rpc_client.clone()
+    }
+
+    #[cfg(feature = "interop")]
     async fn supervisor_ext(&self) -> Option<Self::SupervisorExt> {
         if self.supervisor_rpc.is_disabled() {
             return None;
@@ -116,6 +203,7 @@ impl RollupNodeService for RollupNode {
         self.rpc_launcher.clone()
     }
 
+    #[cfg(feature = "sequencer")]
     fn sequencer_state(&self) -> SequencerActorState<Self::AttributesBuilder> {
         let l1_derivation_provider =
             AlloyChainProvider::new(self.l1_provider.clone(), DERIVATION_PROVIDER_CACHE_SIZE);
@@ -132,41 +220,164 @@ impl RollupNodeService for RollupNode {
 
         let origin_selector = L1OriginSelector::new(self.config(), self.l1_provider.clone());
 
-        SequencerActorState { cfg: self.config(), builder, origin_selector }
+        SequencerActorState { cfg: self.config(), builder, origin_selector, clock: self.clock() }
+    }
+
+    fn sequencer_l1_confs(&self) -> u64 {
+        self.sequencer_l1_confs
+    }
+
+    fn l1_confirmation_depth(&self) -> u64 {
+        self.l1_confirmation_depth
     }
 
-    async fn init_network(&self) -> Result<(Network, NetworkRpc), Self::Error> {
+    fn l1_derivation_limit(&self) -> Option<L1BlockLimit> {
+        self.l1_derivation_limit.clone()
+    }
+
+    fn l1_poll_intervals(&self) -> L1PollIntervals {
+        self.l1_poll_intervals
+    }
+
+    fn backfill(&self) -> Option<BackfillConfig> {
+        self.backfill
+    }
+
+    fn shadow(&self) -> Option<ShadowConfig> {
+        self.shadow.clone()
+    }
+
+    fn chain_halt(&self) -> Option<ChainHaltConfig> {
+        self.chain_halt
+    }
+
+    fn derivation_memory_ceiling(&self) -> Option<usize> {
+        self.derivation_memory_ceiling
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
+    }
+
+    fn shutdown_grace_period(&self) -> Duration {
+        self.shutdown_grace_period
+    }
+
+    fn reset_timeout(&self) -> Duration {
+        self.reset_timeout
+    }
+
+    fn reloadable_config(&self) -> watch::Sender<ReloadableConfig> {
+        self.reloadable_config.clone()
+    }
+
+    fn reorg_events(&self) -> broadcast::Sender<ReorgEvent> {
+        self.reorg_events.clone()
+    }
+
+    fn bus_config(&self) -> BusConfig {
+        self.bus.clone()
+    }
+
+    fn journal(&self) -> EventJournal {
+        self.journal.clone()
+    }
+
+    fn submission(&self) -> SubmissionRegistry {
+        self.submission.registry().clone()
+    }
+
+    fn extensions(&self) -> Vec<Box<dyn NodeExtension>> {
+        std::mem::take(&mut *self.extensions.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    #[cfg(feature = "p2p")]
+    async fn init_network(&self) -> Result<Option<(Network, NetworkRpc)>, Self::Error> {
+        let Some(p2p_config) = self.p2p_config.clone() else {
+            return Ok(None);
+        };
         let (tx, rx) = tokio::sync::mpsc::channel(1024);
         let p2p_module = NetworkRpc::new(tx);
-        let builder = NetworkBuilder::from(self.p2p_config.clone())
+        let builder = NetworkBuilder::from(p2p_config)
             .with_rpc_receiver(rx)
             .build()
             .map_err(RollupNodeError::Network)?;
-        Ok((builder, p2p_module))
+        Ok(Some((builder, p2p_module)))
     }
 
     async fn init_derivation(&self) -> Result<OnlinePipeline, Self::Error> {
-        // Create the caching L1/L2 EL providers for derivation.
-        let l1_derivation_provider =
+        // Create the caching L1/L2 EL providers for derivation, routing historical requests to
+        // the cold tiers if configured.
+        let mut l1_derivation_provider =
             AlloyChainProvider::new(self.l1_provider.clone(), DERIVATION_PROVIDER_CACHE_SIZE);
+        if let Some((cold, after_blocks)) = self.l1_cold_provider.clone() {
+            l1_derivation_provider = l1_derivation_provider.with_cold(cold, after_blocks);
+        }
+        if let Some(limit) = self.l1_derivation_limit.clone() {
+            l1_derivation_provider = l1_derivation_provider.with_limit(limit);
+        }
         let l2_derivation_provider = AlloyL2ChainProvider::new(
             self.l2_provider.clone(),
             self.config.clone(),
             DERIVATION_PROVIDER_CACHE_SIZE,
         );
 
+        let mut blob_provider = match self.l1_beacon.clone() {
+            Some(beacon) => {
+                let beacon = if self.l1_beacon_fallbacks.is_empty() {
+                    FailoverBeaconClient::from(beacon)
+                } else {
+                    let endpoints = std::iter::once(beacon)
+                        .chain(self.l1_beacon_fallbacks.iter().cloned())
+                        .collect();
+                    FailoverBeaconClient::probe(endpoints).await
+                };
+                Some(
+                    OnlineBlobProvider::init(beacon)
+                        .await
+                        .with_sidecar_verification(self.sidecar_verification)
+                        .with_l1_provider(self.l1_provider.clone()),
+                )
+            }
+            None => {
+                info!(target: "rollup_node", "The chain never posts blobs, deriving from calldata only");
+                None
+            }
+        };
+        if let Some((cold, after_secs)) = self.l1_cold_beacon.clone() {
+            blob_provider = blob_provider
+                .map(|blobs| blobs.with_cold(FailoverBeaconClient::from(cold), after_secs));
+        }
+
+        // Record the L1 data fetched by the providers, if enabled.
+        if let Some(recorder) = self.l1_recorder.clone() {
+            l1_derivation_provider = l1_derivation_provider.with_recorder(recorder.clone());
+            blob_provider = blob_provider.map(|blobs| blobs.with_recorder(recorder));
+        }
+
+        // Report the rejected candidate batcher transactions to the journal, which also enables
+        // the detection of batcher transactions sent to the wrong inbox.
+        let batcher_tx_observer: Arc<dyn BatcherTxObserver> = Arc::new(self.journal.clone());
+        // Track the unsafe blocks covered by the batches read from L1.
+        let batch_observer: Arc<dyn BatchObserver> = Arc::new(self.submission.clone());
         let pipeline = match self.interop_mode {
             InteropMode::Polled => OnlinePipeline::new_polled(
                 self.config.clone(),
-                OnlineBlobProvider::init(self.l1_beacon.clone()).await,
+                blob_provider,
                 l1_derivation_provider,
                 l2_derivation_provider,
+                self.memory_audit.clone(),
+                Some(batcher_tx_observer.clone()),
+                Some(batch_observer.clone()),
             ),
             InteropMode::Indexed => OnlinePipeline::new_indexed(
                 self.config.clone(),
-                OnlineBlobProvider::init(self.l1_beacon.clone()).await,
+                blob_provider,
                 l1_derivation_provider,
                 l2_derivation_provider,
+                self.memory_audit.clone(),
+                Some(batcher_tx_observer),
+                Some(batch_observer),
             ),
         };
 
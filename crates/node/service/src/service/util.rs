@@ -1,46 +1,103 @@
 //! Utilities for the rollup node service, internal to the crate.
 
-/// Spawns a set of parallel actors in a [JoinSet], and cancels all actors if any of them fail. The
-/// type of the error in the [NodeActor]s is erased to avoid having to specify a common error type
-/// between actors.
+use crate::{ActorMeter, ActorStatsSampler, HealthReporter, NodeActor};
+use kona_rpc::HealthRegistry;
+
+/// Spawns a set of parallel actors, each tagged with the [ShutdownStage] it belongs to, and
+/// hands them to the [ShutdownCoordinator]. If any actor fails, the coordinator shuts down all
+/// actors in stage order. The type of the error in the [NodeActor]s is erased to avoid having to
+/// specify a common error type between actors.
+///
+/// Actors are passed in as optional arguments, in case a given actor is not needed. Each spawned
+/// actor's health is published into the given [HealthRegistry] under its [NodeActor::NAME].
+///
+/// Each actor is also metered by the given [ActorStatsSampler] under its [NodeActor::NAME], which
+/// samples the actors until the node shuts down.
 ///
-/// Actors are passed in as optional arguments, in case a given actor is not needed.
+/// Every actor runs within the scope of `chain_id`, so that the metrics it records are labeled
+/// with it, see [with_chain_id].
 ///
-/// [JoinSet]: tokio::task::JoinSet
+/// [NodeExtension]s are spawned in the same way, each with a clone of the given [NodeHandles], in
+/// the [ShutdownStage] they report and under their [name][NodeExtension::name].
+///
+/// [ShutdownStage]: crate::ShutdownStage
+/// [ShutdownCoordinator]: crate::ShutdownCoordinator
 /// [NodeActor]: crate::NodeActor
+/// [NodeActor::NAME]: crate::NodeActor::NAME
+/// [HealthRegistry]: kona_rpc::HealthRegistry
+/// [ActorStatsSampler]: crate::ActorStatsSampler
+/// [NodeExtension]: crate::NodeExtension
+/// [NodeExtension::name]: crate::NodeExtension::name
+/// [NodeHandles]: crate::NodeHandles
+/// [with_chain_id]: crate::with_chain_id
 macro_rules! spawn_and_wait {
-    ($cancellation:expr, actors = [$($actor:expr$(,)?)*]) => {
-        let mut task_handles = tokio::task::JoinSet::new();
+    (
+        $coordinator:expr,
+        $health:expr,
+        $stats:expr,
+        chain_id = $chain_id:expr,
+        actors = [$($(#[$meta:meta])* ($stage:expr, $actor:expr)$(,)?)*],
+        extensions = $extensions:expr,
+        handles = $handles:expr$(,)?
+    ) => {
+        let mut tasks = $crate::service::ActorTasks::default();
+        let chain_id: u64 = $chain_id;
 
-        // Check if the actor is present, and spawn it if it is.
+        // Check if the actor is present, and spawn it if it is. Actors may be gated behind a cfg
+        // attribute, for the components that are optional features of the crate.
         $(
+            $(#[$meta])*
             if let Some((actor, context)) = $actor {
-                task_handles.spawn(async move {
-                    if let Err(e) = actor.start(context).await {
-                        return Err(format!("{e:?}"));
+                let health = $crate::service::util::health_reporter(&actor, &$health);
+                let meter = $crate::service::util::actor_meter(&actor, &$stats);
+                tasks.spawn($stage, $crate::with_chain_id(chain_id, async move {
+                    health.healthy();
+                    if let Err(e) = meter.instrument(actor.start(context)).await {
+                        let e = format!("{e:?}");
+                        health.unhealthy(e.clone());
+                        return Err(e);
                     }
+                    health.report(kona_rpc::HealthStatus::Stopped);
                     Ok(())
-                });
+                }));
             }
         )*
 
-        while let Some(result) = task_handles.join_next().await {
-            match result {
-                Ok(Ok(())) => { /* Actor completed successfully */ }
-                Ok(Err(e)) => {
-                    tracing::error!(target: "rollup_node", "Critical error in sub-routine: {e}");
-                    // Cancel all tasks and gracefully shutdown.
-                    $cancellation.cancel();
-                }
-                Err(e) => {
-                    tracing::error!(target: "rollup_node", "Task join error: {e}");
-                    // Cancel all tasks and gracefully shutdown.
-                    $cancellation.cancel();
+        // Spawn the extensions alongside the actors.
+        for extension in $extensions {
+            let stage = extension.stage();
+            let health = $crate::HealthReporter::new(extension.name(), $health.clone());
+            let meter = $stats.meter(extension.name());
+            let (handles, cancellation) = ($handles.clone(), $coordinator.token(stage));
+            tasks.spawn(stage, $crate::with_chain_id(chain_id, async move {
+                health.healthy();
+                if let Err(e) = meter.instrument(extension.run(handles, cancellation)).await {
+                    let e = format!("{e:?}");
+                    health.unhealthy(e.clone());
+                    return Err(e);
                 }
-            }
+                health.report(kona_rpc::HealthStatus::Stopped);
+                Ok(())
+            }));
         }
+
+        // Sample the utilization of the actors until the last shutdown stage.
+        let cancellation = $coordinator.token($crate::ShutdownStage::Network);
+        tokio::spawn($crate::with_chain_id(chain_id, $stats.clone().run(cancellation)));
+
+        $coordinator.run(tasks).await;
     };
 }
 
 // Export the `spawn_and_wait` macro for use in other modules.
 pub(crate) use spawn_and_wait;
+
+/// Creates a [`HealthReporter`] for the given [`NodeActor`], named after [`NodeActor::NAME`].
+pub(crate) fn health_reporter<A: NodeActor>(_: &A, registry: &HealthRegistry) -> HealthReporter {
+    HealthReporter::new(A::NAME, registry.clone())
+}
+
+/// Returns the [`ActorMeter`] of the given [`NodeActor`], named after [`NodeActor::NAME`].
+pub(crate) fn actor_meter<A: NodeActor>(_: &A, sampler: &ActorStatsSampler) -> ActorMeter {
+    sampler.meter(A::NAME)
+}
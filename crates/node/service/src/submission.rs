@@ -0,0 +1,99 @@
+//! Tracking of the submission of the unsafe chain to the batch inbox on L1.
+//!
+//! The [`SubmissionTracker`] is reported the batches read by the derivation pipeline, as soon as
+//! they are decoded from their channels, and follows the unsafe and safe heads. The number of
+//! unsafe blocks not yet covered by a batch is published into the [`SubmissionRegistry`] and
+//! exported through the [`Metrics::UNSUBMITTED_UNSAFE_BLOCKS`] gauge, so that sequencer operators
+//! are warned of a stalled batcher well before the safe head lags behind by the sequencing
+//! window.
+
+use crate::{Metrics, NodeExtension, NodeHandles};
+use async_trait::async_trait;
+use kona_derive::BatchObserver;
+use kona_genesis::RollupConfig;
+use kona_protocol::{Batch, BlockInfo};
+use kona_rpc::SubmissionRegistry;
+use std::{error::Error, sync::Arc};
+use tokio_util::sync::CancellationToken;
+
+/// Tracks the unsafe blocks covered by the batches read from L1.
+///
+/// The tracker is a [`BatchObserver`] of the node's derivation pipelines, and a [`NodeExtension`]
+/// following the unsafe and safe heads. Clones share the same [`SubmissionRegistry`].
+#[derive(Debug, Clone)]
+pub struct SubmissionTracker {
+    /// The rollup config, to map the timestamps of the batches to block numbers.
+    cfg: Arc<RollupConfig>,
+    /// The registry the submission status is published into.
+    registry: SubmissionRegistry,
+}
+
+impl SubmissionTracker {
+    /// Creates a new [`SubmissionTracker`] publishing into the given registry.
+    pub const fn new(cfg: Arc<RollupConfig>, registry: SubmissionRegistry) -> Self {
+        Self { cfg, registry }
+    }
+
+    /// Returns the [`SubmissionRegistry`] the submission status is published into.
+    pub const fn registry(&self) -> &SubmissionRegistry {
+        &self.registry
+    }
+
+    /// Exports the number of unsubmitted unsafe blocks.
+    fn report(&self) {
+        let unsubmitted = self.registry.status().unsubmitted;
+        kona_macros::set!(gauge, Metrics::UNSUBMITTED_UNSAFE_BLOCKS, unsubmitted as f64);
+    }
+}
+
+impl BatchObserver for SubmissionTracker {
+    fn batch_read(&self, origin: Option<BlockInfo>, batch: &Batch) {
+        let timestamp = match batch {
+            Batch::Single(batch) => batch.timestamp,
+            Batch::Span(batch) if batch.batches.is_empty() => return,
+            Batch::Span(batch) => batch.final_timestamp(),
+        };
+        if timestamp < self.cfg.genesis.l2_time {
+            return;
+        }
+        let number = self.cfg.genesis.l2.number + self.cfg.block_number_from_timestamp(timestamp);
+        trace!(
+            target: "submission",
+            number,
+            origin = ?origin.map(|origin| origin.number),
+            "Read batch covering unsafe blocks"
+        );
+        self.registry.record_submitted(number);
+        self.report();
+    }
+}
+
+#[async_trait]
+impl NodeExtension for SubmissionTracker {
+    fn name(&self) -> &'static str {
+        "submission"
+    }
+
+    async fn run(
+        self: Box<Self>,
+        handles: NodeHandles,
+        cancellation: CancellationToken,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut unsafe_head = handles.unsafe_head;
+        let mut safe_head = handles.safe_head;
+        loop {
+            let (unsafe_number, safe_number) = (
+                unsafe_head.borrow_and_update().block_info.number,
+                safe_head.borrow_and_update().block_info.number,
+            );
+            self.registry.update_heads(unsafe_number, safe_number);
+            self.report();
+
+            tokio::select! {
+                _ = cancellation.cancelled() => return Ok(()),
+                Ok(()) = unsafe_head.changed() => {}
+                Ok(()) = safe_head.changed() => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Contains the [`SimBatcher`], which submits L2 blocks to the batch inbox.
+
+use crate::SimError;
+use alloy_consensus::{SignableTransaction, TxEip1559, TxEnvelope, transaction::Recovered};
+use alloy_primitives::{Address, Bytes, TxKind, keccak256};
+use alloy_rlp::Encodable;
+use alloy_signer::SignerSync;
+use alloy_signer_local::PrivateKeySigner;
+use kona_protocol::{Batch, ChannelId, DERIVATION_VERSION_0, Frame, L2BlockInfo, SingleBatch};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+
+/// The zlib compression level used for channels.
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Submits L2 blocks to the batch inbox of a simulated chain.
+///
+/// The batcher only prepares the L1 transactions carrying the frames of a channel: the test
+/// decides which L1 block includes which frame, in which order, and how often. This is what lets
+/// scenarios split a channel across L1 blocks, reorg some of them out, or submit frames again.
+///
+/// Channels are made of [`SingleBatch`]es, compressed with zlib. The blocks built by the
+/// [`SimSequencer`] only contain deposits, so batches never carry transactions.
+///
+/// [`SimSequencer`]: crate::SimSequencer
+#[derive(Debug)]
+pub struct SimBatcher {
+    /// The key of the batcher account.
+    signer: PrivateKeySigner,
+    /// The batch inbox address.
+    inbox: Address,
+    /// The L1 chain id.
+    l1_chain_id: u64,
+    /// The nonce of the next L1 transaction.
+    nonce: u64,
+    /// The number of channels opened so far.
+    channels: u64,
+}
+
+impl SimBatcher {
+    /// Creates a new [`SimBatcher`] submitting to the given batch inbox.
+    pub const fn new(signer: PrivateKeySigner, inbox: Address, l1_chain_id: u64) -> Self {
+        Self { signer, inbox, l1_chain_id, nonce: 0, channels: 0 }
+    }
+
+    /// Returns the address of the batcher account.
+    pub const fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// Encodes the given blocks into a new channel, split into the given number of frames.
+    pub fn channel(
+        &mut self,
+        blocks: &[L2BlockInfo],
+        frames: usize,
+    ) -> Result<Vec<Frame>, SimError> {
+        let mut rlp = Vec::new();
+        for block in blocks {
+            let batch = Batch::Single(SingleBatch {
+                parent_hash: block.block_info.parent_hash,
+                epoch_num: block.l1_origin.number,
+                epoch_hash: block.l1_origin.hash,
+                timestamp: block.block_info.timestamp,
+                transactions: vec![],
+            });
+            let mut encoded = Vec::new();
+            batch.encode(&mut encoded).map_err(|e| SimError::Batch(e.to_string()))?;
+            Bytes::from(encoded).encode(&mut rlp);
+        }
+        let data = compress_to_vec_zlib(&rlp, COMPRESSION_LEVEL);
+
+        let id = self.next_channel_id();
+        let frames = frames.max(1);
+        let size = data.len().div_ceil(frames).max(1);
+        Ok((0..frames)
+            .map(|number| {
+                let start = (number * size).min(data.len());
+                let end = (start + size).min(data.len());
+                Frame::new(id, number as u16, data[start..end].to_vec(), number == frames - 1)
+            })
+            .collect())
+    }
+
+    /// Returns the signed L1 transaction submitting the given frame to the batch inbox. Every
+    /// call returns a new transaction.
+    pub fn submit(&mut self, frame: &Frame) -> Result<Recovered<TxEnvelope>, SimError> {
+        let mut input = vec![DERIVATION_VERSION_0];
+        input.extend_from_slice(&frame.encode());
+        let tx = TxEip1559 {
+            chain_id: self.l1_chain_id,
+            nonce: self.nonce,
+            gas_limit: 1_000_000,
+            max_fee_per_gas: 10_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: TxKind::Call(self.inbox),
+            input: input.into(),
+            ..Default::default()
+        };
+        let signature = self.signer.sign_hash_sync(&tx.signature_hash())?;
+        self.nonce += 1;
+        Ok(Recovered::new_unchecked(tx.into_signed(signature).into(), self.address()))
+    }
+
+    /// Returns the signed L1 transactions submitting the given frames, one frame per transaction.
+    pub fn submit_all(&mut self, frames: &[Frame]) -> Result<Vec<Recovered<TxEnvelope>>, SimError> {
+        frames.iter().map(|frame| self.submit(frame)).collect()
+    }
+
+    /// Returns the id of the next channel.
+    fn next_channel_id(&mut self) -> ChannelId {
+        self.channels += 1;
+        let hash = keccak256(self.channels.to_be_bytes());
+        hash[..16].try_into().expect("a channel id is 16 bytes")
+    }
+}
@@ -0,0 +1,92 @@
+//! Contains the [`SimBeacon`], a mock L1 beacon API.
+
+use crate::{SimError, l1::L1_BLOCK_TIME};
+use kona_providers_alloy::{APIConfigResponse, APIGenesisResponse};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use url::Url;
+
+/// The largest request the [`SimBeacon`] reads.
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// A mock L1 beacon API, serving the genesis time and the slot interval of the [`SimL1`].
+///
+/// These are all the rollup node queries at startup. Simulated chains do not activate Ecotone, so
+/// blob sidecars are never requested, and any other request is answered with `404 Not Found`.
+///
+/// The server is stopped when dropped.
+///
+/// [`SimL1`]: crate::SimL1
+#[derive(Debug)]
+pub struct SimBeacon {
+    /// The url the server listens on.
+    url: Url,
+    /// The task accepting connections.
+    task: JoinHandle<()>,
+}
+
+impl SimBeacon {
+    /// Starts serving a beacon chain with the given genesis time on a free local port.
+    pub async fn start(genesis_time: u64) -> Result<Self, SimError> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?)
+            .parse()
+            .expect("a socket address is a valid url");
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(Self::serve(stream, genesis_time));
+            }
+        });
+        Ok(Self { url, task })
+    }
+
+    /// Returns the url the server listens on.
+    pub fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    /// Answers a single request on the given connection, then closes it.
+    async fn serve(mut stream: TcpStream, genesis_time: u64) {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_SIZE {
+            match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => request.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        let request = String::from_utf8_lossy(&request);
+        let path = request.split_whitespace().nth(1).unwrap_or_default();
+        let body = match path.trim_start_matches('/') {
+            "eth/v1/beacon/genesis" => {
+                serde_json::to_string(&APIGenesisResponse::new(genesis_time))
+            }
+            "eth/v1/config/spec" => serde_json::to_string(&APIConfigResponse::new(L1_BLOCK_TIME)),
+            _ => {
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    )
+                    .await;
+                return;
+            }
+        }
+        .expect("beacon responses serialize");
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+    }
+}
+
+impl Drop for SimBeacon {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
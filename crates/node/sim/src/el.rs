@@ -0,0 +1,381 @@
+//! Contains the [`SimEl`], a mock L2 execution layer.
+
+use crate::{SimError, rpc::RpcResult};
+use alloy_consensus::{
+    BlockBody, EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH, Header,
+    proofs::calculate_transaction_root,
+    transaction::{Recovered, SignerRecoverable},
+};
+use alloy_eips::{BlockId, BlockNumberOrTag, Decodable2718, Encodable2718, eip4895::Withdrawals};
+use alloy_primitives::{B256, U64, U256};
+use alloy_rpc_types_engine::{
+    ExecutionPayload, ExecutionPayloadEnvelopeV2, ExecutionPayloadFieldV2, ExecutionPayloadInputV2,
+    ExecutionPayloadV2, ForkchoiceState, ForkchoiceUpdated, PayloadId, PayloadStatus,
+    PayloadStatusEnum,
+};
+use alloy_rpc_types_eth::BlockTransactions;
+use jsonrpsee::{RpcModule, types::ErrorObjectOwned};
+use op_alloy_consensus::{OpBlock, OpTxEnvelope};
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// The base fee of every block built by the [`SimEl`].
+const BASE_FEE: u64 = 1_000_000_000;
+
+/// An L2 block served by the [`SimEl`] over RPC.
+pub type RpcBlock = alloy_rpc_types_eth::Block<op_alloy_rpc_types::Transaction>;
+
+/// A mock L2 execution layer, serving the Engine API and the subset of the `eth` namespace used by
+/// the rollup node.
+///
+/// The [`SimEl`] does not execute transactions. A block built from payload attributes contains
+/// exactly the attributes' transactions, and its header is derived from its parent and the
+/// attributes alone. Building the same attributes on the same parent therefore always results in
+/// the same block hash, which lets the harness predict the blocks derived by the node under test.
+///
+/// Only the Engine API methods in use before Ecotone are served, so simulated chains must not
+/// activate Ecotone.
+///
+/// Clones share the same chain.
+#[derive(Debug, Clone)]
+pub struct SimEl {
+    inner: Arc<Mutex<ElState>>,
+}
+
+/// The chain of a [`SimEl`].
+#[derive(Debug)]
+struct ElState {
+    /// All known blocks, by hash.
+    blocks: HashMap<B256, OpBlock>,
+    /// The hashes of the canonical chain, by block number.
+    canonical: Vec<B256>,
+    /// The hash of the safe block.
+    safe: B256,
+    /// The hash of the finalized block.
+    finalized: B256,
+    /// The blocks built by `engine_forkchoiceUpdated`, by payload id.
+    payloads: HashMap<PayloadId, B256>,
+}
+
+impl SimEl {
+    /// Creates a new [`SimEl`] whose chain starts at a genesis block with the given timestamp and
+    /// gas limit.
+    pub fn new(timestamp: u64, gas_limit: u64) -> Self {
+        let header = Header {
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            transactions_root: EMPTY_ROOT_HASH,
+            receipts_root: EMPTY_ROOT_HASH,
+            withdrawals_root: Some(EMPTY_ROOT_HASH),
+            gas_limit,
+            timestamp,
+            base_fee_per_gas: Some(BASE_FEE),
+            ..Default::default()
+        };
+        let genesis = OpBlock {
+            header,
+            body: BlockBody {
+                transactions: vec![],
+                ommers: vec![],
+                withdrawals: Some(Withdrawals::default()),
+            },
+        };
+        let hash = genesis.header.hash_slow();
+        let state = ElState {
+            blocks: HashMap::from([(hash, genesis)]),
+            canonical: vec![hash],
+            safe: hash,
+            finalized: hash,
+            payloads: HashMap::new(),
+        };
+        Self { inner: Arc::new(Mutex::new(state)) }
+    }
+
+    fn state(&self) -> MutexGuard<'_, ElState> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the hash of the genesis block.
+    pub fn genesis_hash(&self) -> B256 {
+        self.state().canonical[0]
+    }
+
+    /// Returns the block with the given hash, if known.
+    pub fn block(&self, hash: B256) -> Option<OpBlock> {
+        self.state().blocks.get(&hash).cloned()
+    }
+
+    /// Returns the canonical block with the given number, if any.
+    pub fn block_by_number(&self, number: u64) -> Option<OpBlock> {
+        let state = self.state();
+        let hash = state.canonical.get(number as usize)?;
+        state.blocks.get(hash).cloned()
+    }
+
+    /// Returns the hash of the canonical head.
+    pub fn head(&self) -> B256 {
+        *self.state().canonical.last().expect("the genesis block is always canonical")
+    }
+
+    /// Builds a block from the given payload attributes on top of the given parent. The block is
+    /// known to the [`SimEl`] afterwards, but not canonical. Returns its hash.
+    pub fn build(&self, parent: B256, attributes: &OpPayloadAttributes) -> Result<B256, SimError> {
+        let mut state = self.state();
+        let parent = state.blocks.get(&parent).ok_or(SimError::UnknownBlock(parent))?;
+        let block = Self::build_block(parent, attributes)?;
+        let hash = block.header.hash_slow();
+        state.blocks.insert(hash, block);
+        Ok(hash)
+    }
+
+    /// Makes the given block the canonical head, along with its ancestors.
+    pub fn set_head(&self, head: B256) -> Result<(), SimError> {
+        self.state().set_head(head)
+    }
+
+    /// Builds the block for the given payload attributes on top of the given parent.
+    fn build_block(
+        parent: &OpBlock,
+        attributes: &OpPayloadAttributes,
+    ) -> Result<OpBlock, SimError> {
+        let transactions = attributes
+            .transactions
+            .iter()
+            .flatten()
+            .map(|tx| OpTxEnvelope::decode_2718(&mut tx.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| SimError::InvalidPayload(e.to_string()))?;
+        let payload = &attributes.payload_attributes;
+        let header = Header {
+            parent_hash: parent.header.hash_slow(),
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            beneficiary: payload.suggested_fee_recipient,
+            state_root: parent.header.state_root,
+            transactions_root: calculate_transaction_root(&transactions),
+            receipts_root: EMPTY_ROOT_HASH,
+            withdrawals_root: payload.withdrawals.as_ref().map(|_| EMPTY_ROOT_HASH),
+            number: parent.header.number + 1,
+            gas_limit: attributes.gas_limit.unwrap_or(parent.header.gas_limit),
+            timestamp: payload.timestamp,
+            mix_hash: payload.prev_randao,
+            base_fee_per_gas: Some(BASE_FEE),
+            parent_beacon_block_root: payload.parent_beacon_block_root,
+            ..Default::default()
+        };
+        let withdrawals = payload.withdrawals.clone().map(Withdrawals::new);
+        Ok(OpBlock { header, body: BlockBody { transactions, ommers: vec![], withdrawals } })
+    }
+
+    /// Handles an `engine_forkchoiceUpdated` call.
+    fn forkchoice_updated(
+        &self,
+        forkchoice: ForkchoiceState,
+        attributes: Option<OpPayloadAttributes>,
+    ) -> Result<ForkchoiceUpdated, SimError> {
+        let head = forkchoice.head_block_hash;
+        debug!(target: "sim", ?forkchoice, attributes = attributes.is_some(), "Forkchoice update");
+        if !self.state().blocks.contains_key(&head) {
+            return Ok(ForkchoiceUpdated::from_status(PayloadStatusEnum::Syncing));
+        }
+
+        let mut state = self.state();
+        state.set_head(head)?;
+        if state.blocks.contains_key(&forkchoice.safe_block_hash) {
+            state.safe = forkchoice.safe_block_hash;
+        }
+        if state.blocks.contains_key(&forkchoice.finalized_block_hash) {
+            state.finalized = forkchoice.finalized_block_hash;
+        }
+        drop(state);
+
+        let mut update =
+            ForkchoiceUpdated::from_status(PayloadStatusEnum::Valid).with_latest_valid_hash(head);
+        if let Some(attributes) = attributes {
+            let hash = self.build(head, &attributes)?;
+            let mut state = self.state();
+            let id = PayloadId::new((state.payloads.len() as u64 + 1).to_be_bytes());
+            state.payloads.insert(id, hash);
+            update = update.with_payload_id(id);
+        }
+        Ok(update)
+    }
+
+    /// Handles an `engine_newPayload` call. Payloads built elsewhere, e.g. gossiped ones, are
+    /// accepted as long as their parent is known and their block hash matches their contents.
+    fn new_payload(&self, payload: ExecutionPayloadInputV2) -> PayloadStatus {
+        let hash = payload.execution_payload.block_hash;
+        let parent = payload.execution_payload.parent_hash;
+        let mut state = self.state();
+        if state.blocks.contains_key(&hash) {
+            return PayloadStatus::new(PayloadStatusEnum::Valid, Some(hash));
+        }
+        if !state.blocks.contains_key(&parent) {
+            return PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+        }
+
+        let invalid = |validation_error: String| {
+            PayloadStatus::new(PayloadStatusEnum::Invalid { validation_error }, Some(parent))
+        };
+        let block = match ExecutionPayload::from(payload).try_into_block::<OpTxEnvelope>() {
+            Ok(block) => block,
+            Err(e) => return invalid(e.to_string()),
+        };
+        if block.header.hash_slow() != hash {
+            return invalid("block hash mismatch".to_string());
+        }
+        state.blocks.insert(hash, block);
+        PayloadStatus::new(PayloadStatusEnum::Valid, Some(hash))
+    }
+
+    /// Handles an `engine_getPayloadV2` call.
+    fn get_payload(&self, id: PayloadId) -> Option<ExecutionPayloadEnvelopeV2> {
+        let state = self.state();
+        let hash = state.payloads.get(&id)?;
+        let block = state.blocks.get(hash)?;
+        Some(ExecutionPayloadEnvelopeV2 {
+            execution_payload: ExecutionPayloadFieldV2::V2(
+                ExecutionPayloadV2::from_block_unchecked(*hash, block),
+            ),
+            block_value: U256::ZERO,
+        })
+    }
+
+    /// Returns the hash of the block identified by the given [`BlockId`], if known.
+    fn resolve(&self, id: BlockId) -> Option<B256> {
+        let state = self.state();
+        match id {
+            BlockId::Hash(hash) => {
+                state.blocks.contains_key(&hash.block_hash).then_some(hash.block_hash)
+            }
+            BlockId::Number(BlockNumberOrTag::Number(number)) => {
+                state.canonical.get(number as usize).copied()
+            }
+            BlockId::Number(BlockNumberOrTag::Safe) => Some(state.safe),
+            BlockId::Number(BlockNumberOrTag::Finalized) => Some(state.finalized),
+            BlockId::Number(BlockNumberOrTag::Earliest) => state.canonical.first().copied(),
+            BlockId::Number(_) => state.canonical.last().copied(),
+        }
+    }
+
+    /// Returns the RPC representation of the block identified by the given [`BlockId`].
+    fn rpc_block(&self, id: BlockId, full: bool) -> Option<RpcBlock> {
+        let hash = self.resolve(id)?;
+        let block = self.block(hash)?;
+        let number = block.header.number;
+        let transactions = if full {
+            BlockTransactions::Full(
+                block
+                    .body
+                    .transactions
+                    .iter()
+                    .enumerate()
+                    .map(|(index, tx)| {
+                        let signer = match tx {
+                            OpTxEnvelope::Deposit(deposit) => deposit.from,
+                            tx => tx.recover_signer().unwrap_or_default(),
+                        };
+                        op_alloy_rpc_types::Transaction {
+                            inner: alloy_rpc_types_eth::Transaction {
+                                inner: Recovered::new_unchecked(tx.clone(), signer),
+                                block_hash: Some(hash),
+                                block_number: Some(number),
+                                transaction_index: Some(index as u64),
+                                effective_gas_price: Some(0),
+                            },
+                            deposit_nonce: None,
+                            deposit_receipt_version: None,
+                        }
+                    })
+                    .collect(),
+            )
+        } else {
+            BlockTransactions::Hashes(
+                block.body.transactions.iter().map(|tx| tx.trie_hash()).collect(),
+            )
+        };
+        Some(RpcBlock {
+            header: alloy_rpc_types_eth::Header {
+                hash,
+                inner: block.header,
+                total_difficulty: Some(U256::ZERO),
+                size: None,
+            },
+            uncles: vec![],
+            transactions,
+            withdrawals: block.body.withdrawals,
+        })
+    }
+
+    /// Returns the [`RpcModule`] serving the [`SimEl`].
+    pub(crate) fn rpc_module(&self) -> RpcModule<Self> {
+        let mut module = RpcModule::new(self.clone());
+        module
+            .register_method("eth_blockNumber", |_, el, _| {
+                RpcResult::Ok(U64::from(el.state().canonical.len() - 1))
+            })
+            .expect("unique method name");
+        module
+            .register_method("eth_getBlockByNumber", |params, el, _| {
+                let (number, full): (BlockNumberOrTag, bool) = params.parse()?;
+                RpcResult::Ok(el.rpc_block(number.into(), full))
+            })
+            .expect("unique method name");
+        module
+            .register_method("eth_getBlockByHash", |params, el, _| {
+                let (hash, full): (B256, bool) = params.parse()?;
+                RpcResult::Ok(el.rpc_block(hash.into(), full))
+            })
+            .expect("unique method name");
+        for method in [
+            "engine_forkchoiceUpdatedV1",
+            "engine_forkchoiceUpdatedV2",
+            "engine_forkchoiceUpdatedV3",
+        ] {
+            module
+                .register_method(method, |params, el, _| {
+                    let mut params = params.sequence();
+                    let forkchoice: ForkchoiceState = params.next()?;
+                    let attributes: Option<OpPayloadAttributes> = params.optional_next()?;
+                    el.forkchoice_updated(forkchoice, attributes).map_err(ErrorObjectOwned::from)
+                })
+                .expect("unique method name");
+        }
+        for method in ["engine_newPayloadV1", "engine_newPayloadV2"] {
+            module
+                .register_method(method, |params, el, _| {
+                    let (payload,): (ExecutionPayloadInputV2,) = params.parse()?;
+                    RpcResult::Ok(el.new_payload(payload))
+                })
+                .expect("unique method name");
+        }
+        module
+            .register_method("engine_getPayloadV2", |params, el, _| {
+                let (id,): (PayloadId,) = params.parse()?;
+                el.get_payload(id)
+                    .ok_or_else(|| ErrorObjectOwned::from(SimError::UnknownPayload(id)))
+            })
+            .expect("unique method name");
+        module
+    }
+}
+
+impl ElState {
+    /// Makes the given block the canonical head, along with its ancestors.
+    fn set_head(&mut self, head: B256) -> Result<(), SimError> {
+        let mut canonical = vec![];
+        let mut cursor = head;
+        loop {
+            let block = self.blocks.get(&cursor).ok_or(SimError::UnknownBlock(cursor))?;
+            canonical.push(cursor);
+            if block.header.number == 0 {
+                break;
+            }
+            cursor = block.header.parent_hash;
+        }
+        canonical.reverse();
+        self.canonical = canonical;
+        Ok(())
+    }
+}
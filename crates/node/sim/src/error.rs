@@ -0,0 +1,47 @@
+//! Contains the [`SimError`].
+
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::PayloadId;
+use jsonrpsee::types::{ErrorObjectOwned, error::INTERNAL_ERROR_CODE};
+use std::time::Duration;
+
+/// An error in a simulation.
+#[derive(Debug, thiserror::Error)]
+pub enum SimError {
+    /// A block is not known to a mock.
+    #[error("unknown block {0}")]
+    UnknownBlock(B256),
+    /// A payload id is not known to the [`SimEl`][crate::SimEl].
+    #[error("unknown payload {0}")]
+    UnknownPayload(PayloadId),
+    /// A payload or payload attributes could not be decoded.
+    #[error("invalid payload: {0}")]
+    InvalidPayload(String),
+    /// A mock server could not be started.
+    #[error("failed to start mock server: {0}")]
+    Server(#[from] std::io::Error),
+    /// The [`SimSequencer`][crate::SimSequencer] failed to build payload attributes.
+    #[error("failed to build payload attributes: {0}")]
+    Attributes(String),
+    /// A batch or channel could not be encoded.
+    #[error("failed to encode batch: {0}")]
+    Batch(String),
+    /// An L1 transaction could not be signed.
+    #[error(transparent)]
+    Signer(#[from] alloy_signer::Error),
+    /// The node under test stopped unexpectedly.
+    #[error("the node stopped unexpectedly")]
+    NodeStopped,
+    /// The node under test failed.
+    #[error("the node failed: {0}")]
+    Node(String),
+    /// The node under test did not reach the expected state in time.
+    #[error("timed out after {0:?} waiting for {1}")]
+    Timeout(Duration, &'static str),
+}
+
+impl From<SimError> for ErrorObjectOwned {
+    fn from(err: SimError) -> Self {
+        Self::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>)
+    }
+}
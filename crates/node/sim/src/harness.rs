@@ -0,0 +1,391 @@
+//! Contains the [`Simulation`] harness, which runs rollup nodes against the mocks.
+
+use crate::{RpcServer, SimBatcher, SimBeacon, SimEl, SimError, SimL1, SimSequencer};
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{Address, U256, address};
+use alloy_rpc_types_engine::JwtSecret;
+use alloy_signer_local::PrivateKeySigner;
+use kona_engine::SyncMode;
+use kona_genesis::{ChainGenesis, HardForkConfig, RollupConfig, SystemConfig};
+use kona_node_service::{
+    L1PollIntervals, NodeHandles, RollupNode, RollupNodeBuilder, RollupNodeError,
+    RollupNodeService, VirtualClock,
+};
+use kona_protocol::{BlockInfo, Frame, L2BlockInfo, MemoryAudit};
+use kona_providers_alloy::Chaos;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::{sync::oneshot, task::JoinHandle};
+
+/// The L1 chain id of simulated chains.
+const L1_CHAIN_ID: u64 = 900;
+
+/// The L2 chain id of simulated chains.
+const L2_CHAIN_ID: u64 = 901;
+
+/// The timestamp of the L1 genesis block.
+const L1_GENESIS_TIME: u64 = 1_700_000_000;
+
+/// The gas limit of L2 blocks.
+const L2_GAS_LIMIT: u64 = 30_000_000;
+
+/// The private key of the batcher account.
+const BATCHER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+/// The interval at which simulated nodes poll the L1 heads.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long to wait for a simulated node to reach an expected state, in tokio time.
+const WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long the state of a simulated node must remain unchanged to be considered settled, in
+/// tokio time. With the tokio clock paused, time only advances once every task is idle, so the
+/// node has processed everything it could before the period elapses.
+const SETTLE_PERIOD: Duration = Duration::from_millis(500);
+
+/// A simulated OP Stack chain: a [`SimL1`] chain and its beacon API, a [`SimSequencer`] building
+/// the reference L2 chain, and a [`SimBatcher`] submitting it to L1. Rollup nodes spawned with
+/// [`Self::spawn_verifier`] run against these mocks, each with a [`SimEl`] of its own.
+///
+/// The chain activates Regolith and Canyon at genesis, and no later hardfork.
+///
+/// Simulations must run on a `current_thread` runtime with the tokio clock paused, e.g. with
+/// `#[tokio::test(start_paused = true)]`, so that the timeouts of the harness and the polling of
+/// the nodes only advance while every task is idle. The actors of the nodes are driven by the
+/// [`VirtualClock`] of the simulation instead.
+#[derive(Debug)]
+pub struct Simulation {
+    /// The rollup configuration of the chain.
+    config: Arc<RollupConfig>,
+    /// The L1 chain.
+    l1: SimL1,
+    /// The server of the L1 chain.
+    l1_server: RpcServer,
+    /// The L1 beacon API.
+    beacon: SimBeacon,
+    /// The sequencer of the reference chain.
+    sequencer: SimSequencer,
+    /// The batcher.
+    batcher: SimBatcher,
+    /// The clock driving the actors of the spawned nodes.
+    clock: VirtualClock,
+}
+
+impl Simulation {
+    /// Creates a new [`Simulation`], with an L1 chain and a reference L2 chain at genesis.
+    pub async fn new() -> Result<Self, SimError> {
+        let signer: PrivateKeySigner = BATCHER_KEY.parse().expect("valid batcher key");
+        let batcher = SimBatcher::new(
+            signer,
+            address!("0xff00000000000000000000000000000000000901"),
+            L1_CHAIN_ID,
+        );
+
+        let l1 = SimL1::new(L1_GENESIS_TIME);
+        let l1_genesis = l1.genesis();
+        let el = SimEl::new(l1_genesis.timestamp, L2_GAS_LIMIT);
+        let config =
+            Arc::new(Self::rollup_config(l1_genesis, el.genesis_hash(), batcher.address()));
+
+        let l1_server = RpcServer::start(l1.rpc_module(L1_CHAIN_ID)).await?;
+        let beacon = SimBeacon::start(l1_genesis.timestamp).await?;
+        let sequencer = SimSequencer::new(config.clone(), l1.clone(), l1_server.url(), el).await?;
+        Ok(Self { config, l1, l1_server, beacon, sequencer, batcher, clock: VirtualClock::new() })
+    }
+
+    /// Returns the rollup configuration of a chain starting at the given L1 and L2 genesis blocks.
+    fn rollup_config(
+        l1_genesis: BlockInfo,
+        l2_genesis: alloy_primitives::B256,
+        batcher: Address,
+    ) -> RollupConfig {
+        RollupConfig {
+            genesis: ChainGenesis {
+                l1: l1_genesis.id(),
+                l2: BlockNumHash { number: 0, hash: l2_genesis },
+                l2_time: l1_genesis.timestamp,
+                system_config: Some(SystemConfig {
+                    batcher_address: batcher,
+                    overhead: U256::from(188),
+                    scalar: U256::from(684_000),
+                    gas_limit: L2_GAS_LIMIT,
+                    ..Default::default()
+                }),
+            },
+            block_time: 2,
+            max_sequencer_drift: 600,
+            seq_window_size: 200,
+            channel_timeout: 50,
+            l1_chain_id: L1_CHAIN_ID,
+            l2_chain_id: L2_CHAIN_ID,
+            hardforks: HardForkConfig {
+                regolith_time: Some(0),
+                canyon_time: Some(0),
+                ..Default::default()
+            },
+            batch_inbox_address: address!("0xff00000000000000000000000000000000000901"),
+            deposit_contract_address: address!("0x00000000000000000000000000000000000d0901"),
+            l1_system_config_address: address!("0x0000000000000000000000000000000000051901"),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the rollup configuration of the chain.
+    pub const fn config(&self) -> &Arc<RollupConfig> {
+        &self.config
+    }
+
+    /// Returns the L1 chain.
+    pub const fn l1(&self) -> &SimL1 {
+        &self.l1
+    }
+
+    /// Returns the url of the L1 chain's RPC server.
+    pub fn l1_url(&self) -> url::Url {
+        self.l1_server.url()
+    }
+
+    /// Returns the url of the L1 beacon API.
+    pub fn beacon_url(&self) -> url::Url {
+        self.beacon.url()
+    }
+
+    /// Returns the sequencer of the reference chain.
+    pub const fn sequencer(&mut self) -> &mut SimSequencer {
+        &mut self.sequencer
+    }
+
+    /// Returns the batcher.
+    pub const fn batcher(&mut self) -> &mut SimBatcher {
+        &mut self.batcher
+    }
+
+    /// Returns the clock driving the actors of the spawned nodes.
+    pub const fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    /// Builds the given number of blocks on top of the reference chain, and returns them.
+    pub async fn sequence(&mut self, blocks: usize) -> Result<Vec<L2BlockInfo>, SimError> {
+        let mut built = Vec::with_capacity(blocks);
+        for _ in 0..blocks {
+            built.push(self.sequencer.build().await?);
+        }
+        Ok(built)
+    }
+
+    /// Mines an L1 block including the given frames, one per batcher transaction, and returns it.
+    pub fn submit(&mut self, frames: &[Frame]) -> Result<BlockInfo, SimError> {
+        let transactions = self.batcher.submit_all(frames)?;
+        Ok(self.l1.mine(transactions))
+    }
+
+    /// Spawns a rollup node in validator mode, deriving the chain from L1 into a fresh [`SimEl`].
+    /// The p2p network is disabled.
+    pub async fn spawn_verifier(&self) -> Result<SimNode, SimError> {
+        self.spawn(self.el(), |builder| builder).await
+    }
+
+    /// Spawns a rollup node in validator mode, like [`Self::spawn_verifier`], whose L1 provider,
+    /// beacon client and engine client are subject to the faults of the given [`Chaos`] injector.
+    pub async fn spawn_verifier_with_chaos(&self, chaos: Chaos) -> Result<SimNode, SimError> {
+        self.spawn(self.el(), |builder| builder.with_chaos(chaos)).await
+    }
+
+    /// Spawns a rollup node in validator mode, like [`Self::spawn_verifier`], whose channel bank,
+    /// attributes queue and unsafe payload buffer record their sizes into the given
+    /// [`MemoryAudit`].
+    pub async fn spawn_verifier_with_memory_audit(
+        &self,
+        audit: MemoryAudit,
+    ) -> Result<SimNode, SimError> {
+        self.spawn(self.el(), |builder| builder.with_memory_audit(audit)).await
+    }
+
+    /// Spawns a rollup node in validator mode, like [`Self::spawn_verifier`], that persists the L2
+    /// blocks awaiting finalization to the given path.
+    pub async fn spawn_verifier_with_finality_path(
+        &self,
+        path: PathBuf,
+    ) -> Result<SimNode, SimError> {
+        self.spawn(self.el(), |builder| builder.with_finality_path(path)).await
+    }
+
+    /// Spawns a rollup node in validator mode, like [`Self::spawn_verifier`], that only derives
+    /// from the L1 blocks with the given number of confirmations.
+    pub async fn spawn_verifier_with_l1_confirmation_depth(
+        &self,
+        depth: u64,
+    ) -> Result<SimNode, SimError> {
+        self.spawn(self.el(), |builder| builder.with_l1_confirmation_depth(depth)).await
+    }
+
+    /// Shuts the given node down, and spawns a new rollup node in validator mode on its execution
+    /// layer, persisting the L2 blocks awaiting finalization to the given path, if any.
+    pub async fn restart_verifier(
+        &self,
+        node: SimNode,
+        finality_path: Option<PathBuf>,
+    ) -> Result<SimNode, SimError> {
+        let el = node.stop().await?;
+        self.spawn(el, |builder| match finality_path {
+            Some(path) => builder.with_finality_path(path),
+            None => builder,
+        })
+        .await
+    }
+
+    /// Returns a fresh [`SimEl`] at the L2 genesis.
+    fn el(&self) -> SimEl {
+        SimEl::new(self.config.genesis.l2_time, L2_GAS_LIMIT)
+    }
+
+    /// Spawns a rollup node in validator mode on the given execution layer, with its builder
+    /// customized by the given function.
+    async fn spawn(
+        &self,
+        el: SimEl,
+        customize: impl FnOnce(RollupNodeBuilder) -> RollupNodeBuilder,
+    ) -> Result<SimNode, SimError> {
+        let el_server = RpcServer::start(el.rpc_module()).await?;
+
+        let (handles_tx, handles_rx) = oneshot::channel();
+        let builder = RollupNode::builder((*self.config).clone())
+            .with_l1_provider_rpc_url(self.l1_server.url())
+            .with_l1_beacon_api_url(self.beacon.url())
+            .with_l2_provider_rpc_url(el_server.url())
+            .with_l2_engine_rpc_url(el_server.url())
+            .with_jwt_secret(JwtSecret::from_hex(BATCHER_KEY).expect("valid jwt secret"))
+            .with_p2p_disabled()
+            .with_sync_mode(SyncMode::Consensus)
+            .with_l1_poll_intervals(L1PollIntervals {
+                head: POLL_INTERVAL,
+                finality: POLL_INTERVAL,
+            })
+            .with_clock(Arc::new(self.clock.clone()))
+            .with_handles_sender(handles_tx);
+        let node = customize(builder).build();
+        let task = tokio::spawn(async move { node.start().await });
+        let handles = handles_rx.await.map_err(|_| SimError::NodeStopped)?;
+        Ok(SimNode { el, el_server, handles, task, clock: self.clock.clone() })
+    }
+}
+
+/// A rollup node spawned by a [`Simulation`].
+#[derive(Debug)]
+pub struct SimNode {
+    /// The execution layer of the node.
+    el: SimEl,
+    /// The server of the execution layer.
+    el_server: RpcServer,
+    /// The handles of the running node.
+    handles: NodeHandles,
+    /// The task running the node.
+    task: JoinHandle<Result<(), RollupNodeError>>,
+    /// The clock driving the actors of the node.
+    clock: VirtualClock,
+}
+
+impl SimNode {
+    /// Returns the execution layer of the node.
+    pub const fn el(&self) -> &SimEl {
+        &self.el
+    }
+
+    /// Returns the url of the execution layer of the node.
+    pub fn el_url(&self) -> url::Url {
+        self.el_server.url()
+    }
+
+    /// Returns the handles of the running node.
+    pub const fn handles(&self) -> &NodeHandles {
+        &self.handles
+    }
+
+    /// Returns the safe head of the node.
+    pub fn safe_head(&self) -> L2BlockInfo {
+        *self.handles.safe_head.borrow()
+    }
+
+    /// Waits until the safe head of the node is the given block.
+    pub async fn wait_for_safe_head(&self, block: &L2BlockInfo) -> Result<(), SimError> {
+        let mut safe_head = self.handles.safe_head.clone();
+        let hash = block.block_info.hash;
+        tokio::time::timeout(WAIT_TIMEOUT, safe_head.wait_for(|head| head.block_info.hash == hash))
+            .await
+            .map_err(|_| SimError::Timeout(WAIT_TIMEOUT, "the safe head"))?
+            .map_err(|_| SimError::NodeStopped)?;
+        Ok(())
+    }
+
+    /// Returns the finalized head of the node.
+    pub fn finalized_head(&self) -> L2BlockInfo {
+        self.handles.engine_state.borrow().finalized_head()
+    }
+
+    /// Waits until the finalized head of the node is the given block.
+    pub async fn wait_for_finalized_head(&self, block: &L2BlockInfo) -> Result<(), SimError> {
+        let mut engine_state = self.handles.engine_state.clone();
+        let hash = block.block_info.hash;
+        tokio::time::timeout(
+            WAIT_TIMEOUT,
+            engine_state.wait_for(|state| state.finalized_head().block_info.hash == hash),
+        )
+        .await
+        .map_err(|_| SimError::Timeout(WAIT_TIMEOUT, "the finalized head"))?
+        .map_err(|_| SimError::NodeStopped)?;
+        Ok(())
+    }
+
+    /// Waits until the node has seen the given L1 head, and its safe head then remained unchanged
+    /// for a while. Used to assert that the node does _not_ make progress.
+    ///
+    /// The settle period is only meaningful with the tokio clock paused, as it then only elapses
+    /// once the node is idle. See [`Simulation`].
+    pub async fn settle(&self, l1_head: &BlockInfo) -> Result<(), SimError> {
+        let mut l1 = self.handles.l1_head.clone();
+        let hash = l1_head.hash;
+        tokio::time::timeout(
+            WAIT_TIMEOUT,
+            l1.wait_for(|head| head.is_some_and(|h| h.hash == hash)),
+        )
+        .await
+        .map_err(|_| SimError::Timeout(WAIT_TIMEOUT, "the L1 head"))?
+        .map_err(|_| SimError::NodeStopped)?;
+
+        let mut safe_head = self.handles.safe_head.clone();
+        safe_head.mark_unchanged();
+        loop {
+            match tokio::time::timeout(SETTLE_PERIOD, safe_head.changed()).await {
+                Ok(Ok(())) => continue,
+                Ok(Err(_)) => return Err(SimError::NodeStopped),
+                Err(_) => return Ok(()),
+            }
+        }
+    }
+
+    /// Shuts the node down, and waits until all of its actors have stopped.
+    pub async fn shutdown(self) -> Result<(), SimError> {
+        self.stop().await.map(|_| ())
+    }
+
+    /// Shuts the node down like [`Self::shutdown`], and returns its execution layer, e.g. to
+    /// restart a node on it.
+    pub async fn stop(self) -> Result<SimEl, SimError> {
+        self.handles.shutdown.shutdown();
+        let mut task = self.task;
+        // Actors that do not stop on cancellation are aborted after the shutdown grace period,
+        // which only elapses on the virtual clock.
+        loop {
+            tokio::select! {
+                result = &mut task => {
+                    return match result {
+                        Ok(Ok(())) => Ok(self.el),
+                        Ok(Err(e)) => Err(SimError::Node(e.to_string())),
+                        Err(e) => Err(SimError::Node(e.to_string())),
+                    };
+                }
+                _ = tokio::time::sleep(POLL_INTERVAL) => self.clock.advance(Duration::from_secs(1)),
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Contains the [`SimL1`], a scripted L1 chain.
+
+use crate::rpc::RpcResult;
+use alloy_consensus::{
+    EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH, Header, TxEnvelope, proofs::calculate_transaction_root,
+    transaction::Recovered,
+};
+use alloy_eips::{BlockId, BlockNumberOrTag, Encodable2718};
+use alloy_primitives::{B256, Bytes, U64, U256, keccak256};
+use alloy_rpc_types_eth::{Block, BlockTransactions, Transaction};
+use jsonrpsee::RpcModule;
+use kona_protocol::BlockInfo;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The time between two L1 blocks, in seconds.
+pub(crate) const L1_BLOCK_TIME: u64 = 12;
+
+/// The base fee of every L1 block.
+const BASE_FEE: u64 = 1_000_000_000;
+
+/// The gas limit of every L1 block.
+const GAS_LIMIT: u64 = 30_000_000;
+
+/// A scripted L1 chain, serving the subset of the `eth` namespace used by the rollup node.
+///
+/// Blocks are only added when the test [mines][Self::mine] them, and only removed when it
+/// [reorgs][Self::reorg] the chain. Block contents, and therefore block hashes, only depend on the
+/// sequence of calls, so scenarios are reproducible. Blocks that are reorged out are forgotten:
+/// like an L1 node that has pruned them, the [`SimL1`] only serves canonical blocks, by number as
+/// well as by hash.
+///
+/// Receipts and logs are always empty, so the system config of simulated chains never changes.
+///
+/// Clones share the same chain.
+#[derive(Debug, Clone)]
+pub struct SimL1 {
+    inner: Arc<Mutex<L1State>>,
+}
+
+/// The chain of a [`SimL1`].
+#[derive(Debug)]
+struct L1State {
+    /// The canonical chain, by block number.
+    blocks: Vec<L1Block>,
+    /// The number of the safe block.
+    safe: u64,
+    /// The number of the finalized block.
+    finalized: u64,
+    /// The number of reorgs so far, which makes blocks on different forks distinct.
+    forks: u64,
+}
+
+/// A block of the [`SimL1`].
+#[derive(Debug, Clone)]
+struct L1Block {
+    /// The header of the block.
+    header: Header,
+    /// The hash of the header.
+    hash: B256,
+    /// The transactions of the block.
+    transactions: Vec<Recovered<TxEnvelope>>,
+}
+
+impl SimL1 {
+    /// Creates a new [`SimL1`] whose chain starts at a genesis block with the given timestamp.
+    pub fn new(timestamp: u64) -> Self {
+        let header = Header {
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            transactions_root: EMPTY_ROOT_HASH,
+            receipts_root: EMPTY_ROOT_HASH,
+            gas_limit: GAS_LIMIT,
+            timestamp,
+            base_fee_per_gas: Some(BASE_FEE),
+            ..Default::default()
+        };
+        let genesis = L1Block { hash: header.hash_slow(), header, transactions: vec![] };
+        let state = L1State { blocks: vec![genesis], safe: 0, finalized: 0, forks: 0 };
+        Self { inner: Arc::new(Mutex::new(state)) }
+    }
+
+    fn state(&self) -> MutexGuard<'_, L1State> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the genesis block.
+    pub fn genesis(&self) -> BlockInfo {
+        self.state().blocks[0].info()
+    }
+
+    /// Returns the head block.
+    pub fn head(&self) -> BlockInfo {
+        self.state().blocks.last().expect("the genesis block is always canonical").info()
+    }
+
+    /// Returns the canonical block with the given number, if any.
+    pub fn block(&self, number: u64) -> Option<BlockInfo> {
+        self.state().blocks.get(number as usize).map(L1Block::info)
+    }
+
+    /// Mines a new head block containing the given transactions, and returns it.
+    pub fn mine(&self, transactions: Vec<Recovered<TxEnvelope>>) -> BlockInfo {
+        let mut state = self.state();
+        let forks = state.forks;
+        let parent = state.blocks.last().expect("the genesis block is always canonical");
+        let number = parent.header.number + 1;
+        let envelopes = transactions.iter().map(|tx| tx.inner().clone()).collect::<Vec<_>>();
+        let header = Header {
+            parent_hash: parent.hash,
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            transactions_root: calculate_transaction_root(&envelopes),
+            receipts_root: EMPTY_ROOT_HASH,
+            number,
+            gas_limit: GAS_LIMIT,
+            timestamp: parent.header.timestamp + L1_BLOCK_TIME,
+            extra_data: Bytes::copy_from_slice(&forks.to_be_bytes()),
+            mix_hash: keccak256([number.to_be_bytes(), forks.to_be_bytes()].concat()),
+            base_fee_per_gas: Some(BASE_FEE),
+            ..Default::default()
+        };
+        let block = L1Block { hash: header.hash_slow(), header, transactions };
+        let info = block.info();
+        debug!(target: "sim", number, hash = %info.hash, "Mined L1 block");
+        state.blocks.push(block);
+        info
+    }
+
+    /// Removes the given number of blocks from the tip of the chain. Blocks mined afterwards are
+    /// distinct from the removed ones, even with the same transactions. The safe and finalized
+    /// blocks are moved back if they were removed.
+    pub fn reorg(&self, depth: u64) {
+        let mut state = self.state();
+        let keep = state.blocks.len().saturating_sub(depth as usize).max(1);
+        state.blocks.truncate(keep);
+        state.forks += 1;
+        let head = keep as u64 - 1;
+        state.safe = state.safe.min(head);
+        state.finalized = state.finalized.min(head);
+        debug!(target: "sim", depth, head, "Reorged L1 chain");
+    }
+
+    /// Marks the canonical block with the given number as safe.
+    pub fn set_safe(&self, number: u64) {
+        let mut state = self.state();
+        state.safe = number.min(state.blocks.len() as u64 - 1);
+    }
+
+    /// Marks the canonical block with the given number as finalized, and as safe if the safe block
+    /// is older.
+    pub fn set_finalized(&self, number: u64) {
+        let mut state = self.state();
+        state.finalized = number.min(state.blocks.len() as u64 - 1);
+        state.safe = state.safe.max(state.finalized);
+    }
+
+    /// Returns the canonical block identified by the given [`BlockId`], if any.
+    fn resolve(&self, id: BlockId) -> Option<L1Block> {
+        let state = self.state();
+        let number = match id {
+            BlockId::Hash(hash) => {
+                return state.blocks.iter().find(|b| b.hash == hash.block_hash).cloned();
+            }
+            BlockId::Number(BlockNumberOrTag::Number(number)) => number,
+            BlockId::Number(BlockNumberOrTag::Safe) => state.safe,
+            BlockId::Number(BlockNumberOrTag::Finalized) => state.finalized,
+            BlockId::Number(BlockNumberOrTag::Earliest) => 0,
+            BlockId::Number(_) => state.blocks.len() as u64 - 1,
+        };
+        state.blocks.get(number as usize).cloned()
+    }
+
+    /// Returns the RPC representation of the block identified by the given [`BlockId`].
+    fn rpc_block(&self, id: BlockId, full: bool) -> Option<Block> {
+        let block = self.resolve(id)?;
+        let transactions = if full {
+            BlockTransactions::Full(
+                block
+                    .transactions
+                    .iter()
+                    .enumerate()
+                    .map(|(index, tx)| Transaction {
+                        inner: tx.clone(),
+                        block_hash: Some(block.hash),
+                        block_number: Some(block.header.number),
+                        transaction_index: Some(index as u64),
+                        effective_gas_price: Some(BASE_FEE as u128),
+                    })
+                    .collect(),
+            )
+        } else {
+            BlockTransactions::Hashes(block.transactions.iter().map(|tx| tx.trie_hash()).collect())
+        };
+        Some(Block {
+            header: alloy_rpc_types_eth::Header {
+                hash: block.hash,
+                inner: block.header,
+                total_difficulty: Some(U256::ZERO),
+                size: None,
+            },
+            uncles: vec![],
+            transactions,
+            withdrawals: None,
+        })
+    }
+
+    /// Returns the [`RpcModule`] serving the [`SimL1`].
+    pub(crate) fn rpc_module(&self, chain_id: u64) -> RpcModule<Self> {
+        let mut module = RpcModule::new(self.clone());
+        module
+            .register_method("eth_chainId", move |_, _, _| RpcResult::Ok(U64::from(chain_id)))
+            .expect("unique method name");
+        module
+            .register_method("eth_blockNumber", |_, l1, _| {
+                RpcResult::Ok(U64::from(l1.head().number))
+            })
+            .expect("unique method name");
+        module
+            .register_method("eth_getBlockByNumber", |params, l1, _| {
+                let (number, full): (BlockNumberOrTag, bool) = params.parse()?;
+                RpcResult::Ok(l1.rpc_block(number.into(), full))
+            })
+            .expect("unique method name");
+        module
+            .register_method("eth_getBlockByHash", |params, l1, _| {
+                let (hash, full): (B256, bool) = params.parse()?;
+                RpcResult::Ok(l1.rpc_block(hash.into(), full))
+            })
+            .expect("unique method name");
+        module
+            .register_method("eth_getBlockReceipts", |params, l1, _| {
+                let (id,): (BlockId,) = params.parse()?;
+                RpcResult::Ok(l1.resolve(id).map(|_| Vec::<serde_json::Value>::new()))
+            })
+            .expect("unique method name");
+        module
+            .register_method(
+                "eth_getLogs",
+                |_, _, _| RpcResult::Ok(Vec::<serde_json::Value>::new()),
+            )
+            .expect("unique method name");
+        module
+    }
+}
+
+impl L1Block {
+    /// Returns the [`BlockInfo`] of the block.
+    const fn info(&self) -> BlockInfo {
+        BlockInfo {
+            hash: self.hash,
+            number: self.header.number,
+            parent_hash: self.header.parent_hash,
+            timestamp: self.header.timestamp,
+        }
+    }
+}
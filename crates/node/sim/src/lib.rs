@@ -0,0 +1,35 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/op-rs/kona/main/assets/square.png",
+    html_favicon_url = "https://raw.githubusercontent.com/op-rs/kona/main/assets/favicon.ico",
+    issue_tracker_base_url = "https://github.com/op-rs/kona/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+#[macro_use]
+extern crate tracing;
+
+mod error;
+pub use error::SimError;
+
+mod rpc;
+pub use rpc::RpcServer;
+
+mod el;
+pub use el::{RpcBlock, SimEl};
+
+mod l1;
+pub use l1::SimL1;
+
+mod beacon;
+pub use beacon::SimBeacon;
+
+mod batcher;
+pub use batcher::SimBatcher;
+
+mod sequencer;
+pub use sequencer::SimSequencer;
+
+mod harness;
+pub use harness::{SimNode, Simulation};
@@ -0,0 +1,47 @@
+//! Contains the [`RpcServer`] the mocks are served by.
+
+use crate::SimError;
+use jsonrpsee::{
+    RpcModule,
+    server::{Server, ServerHandle},
+    types::ErrorObjectOwned,
+};
+use url::Url;
+
+/// The result of a mock RPC method.
+pub(crate) type RpcResult<T> = Result<T, ErrorObjectOwned>;
+
+/// A JSON-RPC server on a local port, serving one of the mocks. The server is stopped when
+/// dropped.
+#[derive(Debug)]
+pub struct RpcServer {
+    /// The url the server listens on.
+    url: Url,
+    /// The handle of the running server.
+    handle: ServerHandle,
+}
+
+impl RpcServer {
+    /// Starts serving the given [`RpcModule`] on a free local port.
+    pub(crate) async fn start<T: Send + Sync + 'static>(
+        module: RpcModule<T>,
+    ) -> Result<Self, SimError> {
+        let server = Server::builder().build("127.0.0.1:0").await?;
+        let url = format!("http://{}", server.local_addr()?)
+            .parse()
+            .expect("a socket address is a valid url");
+        let handle = server.start(module);
+        Ok(Self { url, handle })
+    }
+
+    /// Returns the url the server listens on.
+    pub fn url(&self) -> Url {
+        self.url.clone()
+    }
+}
+
+impl Drop for RpcServer {
+    fn drop(&mut self) {
+        let _ = self.handle.stop();
+    }
+}
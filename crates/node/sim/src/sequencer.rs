@@ -0,0 +1,110 @@
+//! Contains the [`SimSequencer`], which builds the reference L2 chain.
+
+use crate::{RpcServer, SimEl, SimError, SimL1};
+use alloy_eips::BlockNumHash;
+use alloy_provider::RootProvider;
+use kona_derive::{AttributesBuilder, StatefulAttributesBuilder};
+use kona_genesis::RollupConfig;
+use kona_protocol::L2BlockInfo;
+use kona_providers_alloy::{AlloyChainProvider, AlloyL2ChainProvider};
+use op_alloy_network::Optimism;
+use std::sync::Arc;
+
+/// The size of the caches of the providers used to build payload attributes.
+const PROVIDER_CACHE_SIZE: usize = 64;
+
+/// Builds the reference L2 chain of a simulation: the chain a verifier is expected to derive
+/// once the batches of its blocks are included on L1.
+///
+/// Payload attributes are built with the [`StatefulAttributesBuilder`] of the derivation
+/// pipeline, from the [`SimL1`] and a reference [`SimEl`] of its own, and without sequencer
+/// transactions. Blocks are built by the reference [`SimEl`], so the hashes of the reference
+/// chain match the hashes of the blocks derived by a verifier.
+///
+/// The L1 origin of a block is the L1 origin of its parent, unless the next L1 block is already
+/// mined and not later than the block itself.
+#[derive(Debug)]
+pub struct SimSequencer {
+    /// The rollup configuration.
+    config: Arc<RollupConfig>,
+    /// The L1 chain.
+    l1: SimL1,
+    /// The server of the L1 chain.
+    l1_url: url::Url,
+    /// The reference execution layer.
+    el: SimEl,
+    /// The server of the reference execution layer.
+    el_server: RpcServer,
+    /// The head of the reference chain.
+    head: L2BlockInfo,
+}
+
+impl SimSequencer {
+    /// Creates a new [`SimSequencer`] building on top of the L2 genesis block.
+    pub async fn new(
+        config: Arc<RollupConfig>,
+        l1: SimL1,
+        l1_url: url::Url,
+        el: SimEl,
+    ) -> Result<Self, SimError> {
+        let el_server = RpcServer::start(el.rpc_module()).await?;
+        let genesis = &config.genesis;
+        let head = L2BlockInfo::new(
+            kona_protocol::BlockInfo {
+                hash: genesis.l2.hash,
+                number: genesis.l2.number,
+                parent_hash: Default::default(),
+                timestamp: genesis.l2_time,
+            },
+            genesis.l1,
+            0,
+        );
+        Ok(Self { config, l1, l1_url, el, el_server, head })
+    }
+
+    /// Returns the head of the reference chain.
+    pub const fn head(&self) -> L2BlockInfo {
+        self.head
+    }
+
+    /// Returns the reference [`SimEl`].
+    pub const fn el(&self) -> &SimEl {
+        &self.el
+    }
+
+    /// Builds the next block of the reference chain, and returns it.
+    pub async fn build(&mut self) -> Result<L2BlockInfo, SimError> {
+        let timestamp = self.head.block_info.timestamp + self.config.block_time;
+        let epoch = match self.l1.block(self.head.l1_origin.number + 1) {
+            Some(next) if next.timestamp <= timestamp => next.id(),
+            _ => self
+                .l1
+                .block(self.head.l1_origin.number)
+                .map(|origin| origin.id())
+                .ok_or(SimError::UnknownBlock(self.head.l1_origin.hash))?,
+        };
+
+        let mut builder = StatefulAttributesBuilder::new(
+            self.config.clone(),
+            AlloyL2ChainProvider::new(
+                RootProvider::<Optimism>::new_http(self.el_server.url()),
+                self.config.clone(),
+                PROVIDER_CACHE_SIZE,
+            ),
+            AlloyChainProvider::new_http(self.l1_url.clone(), PROVIDER_CACHE_SIZE),
+        );
+        let mut attributes = builder
+            .prepare_payload_attributes(self.head, BlockNumHash::from(epoch))
+            .await
+            .map_err(|e| SimError::Attributes(e.to_string()))?;
+        attributes.no_tx_pool = Some(true);
+
+        let hash = self.el.build(self.head.block_info.hash, &attributes)?;
+        self.el.set_head(hash)?;
+        let block = self.el.block(hash).ok_or(SimError::UnknownBlock(hash))?;
+        self.head = L2BlockInfo::from_block_and_genesis(&block, &self.config.genesis)
+            .map_err(|e| SimError::InvalidPayload(e.to_string()))?;
+        debug!(target: "sim", number = self.head.block_info.number, %hash, "Sequenced L2 block");
+        Ok(self.head)
+    }
+}
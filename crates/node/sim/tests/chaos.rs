@@ -0,0 +1,31 @@
+//! Simulations of a verifier whose L1 and engine backends are unreliable.
+
+use kona_node_sim::Simulation;
+use kona_providers_alloy::{Chaos, ChaosConfig};
+use std::time::Duration;
+
+/// A verifier whose L1 provider, beacon client and engine client fail, reset or lag on a share of
+/// their requests still derives the reference chain.
+#[tokio::test(start_paused = true)]
+async fn test_derives_through_faults() {
+    let chaos = Chaos::new(
+        ChaosConfig::default()
+            .with_error_rate(0.1)
+            .with_reset_rate(0.05)
+            .with_latency(0.1, Duration::from_millis(20))
+            .with_seed(1),
+    );
+    let mut sim = Simulation::new().await.unwrap();
+    let node = sim.spawn_verifier_with_chaos(chaos.clone()).await.unwrap();
+
+    let blocks = sim.sequence(6).await.unwrap();
+    let frames = sim.batcher().channel(&blocks, 1).unwrap();
+    sim.l1().mine(vec![]);
+    sim.submit(&frames).unwrap();
+
+    node.wait_for_safe_head(&blocks[5]).await.unwrap();
+    assert_eq!(node.el().head(), blocks[5].block_info.hash);
+    let stats = chaos.stats();
+    assert!(stats.errors > 0 && stats.resets > 0 && stats.delayed > 0, "{stats:?}");
+    node.shutdown().await.unwrap();
+}
@@ -0,0 +1,41 @@
+//! Simulations of a verifier deriving the chain from L1.
+
+use kona_node_sim::Simulation;
+
+/// A channel carrying six blocks in a single frame is derived into the same blocks as the
+/// reference chain.
+#[tokio::test(start_paused = true)]
+async fn test_derives_batched_blocks() {
+    let mut sim = Simulation::new().await.unwrap();
+    let node = sim.spawn_verifier().await.unwrap();
+
+    let blocks = sim.sequence(6).await.unwrap();
+    let frames = sim.batcher().channel(&blocks, 1).unwrap();
+    sim.l1().mine(vec![]);
+    sim.submit(&frames).unwrap();
+
+    node.wait_for_safe_head(&blocks[5]).await.unwrap();
+    assert_eq!(node.el().head(), blocks[5].block_info.hash);
+    node.shutdown().await.unwrap();
+}
+
+/// A subscriber of the derived attributes receives a copy of the attributes of each derived
+/// block, alongside the engine.
+#[tokio::test(start_paused = true)]
+async fn test_derived_attributes_fanout() {
+    let mut sim = Simulation::new().await.unwrap();
+    let node = sim.spawn_verifier().await.unwrap();
+    let mut attributes = node.handles().derived_attributes.subscribe("test_attributes", 16);
+
+    let blocks = sim.sequence(6).await.unwrap();
+    let frames = sim.batcher().channel(&blocks, 1).unwrap();
+    sim.l1().mine(vec![]);
+    sim.submit(&frames).unwrap();
+
+    node.wait_for_safe_head(&blocks[5]).await.unwrap();
+    let numbers: Vec<_> =
+        attributes.drain().iter().map(|derived| derived.attributes.block_number()).collect();
+    let expected: Vec<_> = blocks.iter().map(|block| block.block_info.number).collect();
+    assert_eq!(numbers, expected);
+    node.shutdown().await.unwrap();
+}
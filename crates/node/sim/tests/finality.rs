@@ -0,0 +1,34 @@
+//! Simulations of a verifier persisting the L2 blocks awaiting finalization, restarted between
+//! their derivation and the finalization of their L1 origins.
+
+use kona_node_sim::Simulation;
+use std::collections::BTreeMap;
+
+/// A restarted verifier picks up the persisted blocks awaiting finalization, and finalizes them
+/// once their L1 origins are finalized.
+#[tokio::test(start_paused = true)]
+async fn test_finalizes_after_restart() {
+    let mut sim = Simulation::new().await.unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("finality.json");
+    let node = sim.spawn_verifier_with_finality_path(path.clone()).await.unwrap();
+
+    let blocks = sim.sequence(6).await.unwrap();
+    let frames = sim.batcher().channel(&blocks, 1).unwrap();
+    sim.l1().mine(vec![]);
+    let l1_end = sim.submit(&frames).unwrap();
+    node.wait_for_safe_head(&blocks[5]).await.unwrap();
+
+    let node = sim.restart_verifier(node, Some(path.clone())).await.unwrap();
+    let persisted: BTreeMap<u64, u64> =
+        serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert_eq!(persisted.last_key_value(), Some((&l1_end.number, &6)));
+
+    sim.l1().set_finalized(l1_end.number);
+    node.wait_for_finalized_head(&blocks[5]).await.unwrap();
+    node.shutdown().await.unwrap();
+
+    let persisted: BTreeMap<u64, u64> =
+        serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert!(persisted.is_empty());
+}
@@ -0,0 +1,89 @@
+//! Golden traces of the derivation of a simulated chain, recorded from a verifier and replayed
+//! offline from the recorded L1 archive.
+
+use alloy_primitives::B256;
+use alloy_provider::RootProvider;
+use kona_node_sim::Simulation;
+use kona_protocol::{BatchValidationProvider, L2BlockInfo};
+use kona_providers_alloy::{
+    AlloyChainProvider, AlloyL2ChainProvider, FailoverBeaconClient, GoldenTrace, GoldenTraceError,
+    L1Archive, L1Recorder, OnlineBeaconClient, OnlineBlobProvider, OnlinePipeline,
+};
+use tempfile::TempDir;
+
+/// Derives six blocks with a verifier, then records the golden trace of their derivation from
+/// genesis, along with the L1 archive it is replayed from.
+async fn record() -> (Simulation, Vec<L2BlockInfo>, GoldenTrace, L1Archive, TempDir) {
+    let mut sim = Simulation::new().await.unwrap();
+    let node = sim.spawn_verifier().await.unwrap();
+
+    let blocks = sim.sequence(6).await.unwrap();
+    let frames = sim.batcher().channel(&blocks, 1).unwrap();
+    sim.l1().mine(vec![]);
+    let l1_end = sim.submit(&frames).unwrap();
+    node.wait_for_safe_head(&blocks[5]).await.unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let recorder = L1Recorder::open(dir.path().join("l1.archive")).unwrap();
+    let chain_provider =
+        AlloyChainProvider::new_http(sim.l1_url(), 64).with_recorder(recorder.clone());
+    let beacon = OnlineBeaconClient::new_http(sim.beacon_url().to_string());
+    let blob_provider =
+        OnlineBlobProvider::init(FailoverBeaconClient::from(beacon)).await.with_recorder(recorder);
+    let mut l2_provider =
+        AlloyL2ChainProvider::new(RootProvider::new_http(node.el_url()), sim.config().clone(), 64);
+    let mut pipeline = OnlinePipeline::new_polled(
+        sim.config().clone(),
+        blob_provider,
+        chain_provider,
+        l2_provider.clone(),
+        None,
+        None,
+        None,
+    );
+
+    let genesis = l2_provider.l2_block_info_by_number(0).await.unwrap();
+    let trace = GoldenTrace::record(
+        &mut pipeline,
+        &mut l2_provider,
+        sim.l1().genesis(),
+        l1_end.number,
+        genesis,
+    )
+    .await
+    .unwrap();
+    node.shutdown().await.unwrap();
+
+    let archive = L1Archive::read(dir.path().join("l1.archive")).unwrap();
+    (sim, blocks, trace, archive, dir)
+}
+
+/// The recorded trace holds the blocks of the reference chain, survives a round trip through its
+/// file, and is re-derived from the archive alone.
+#[tokio::test(start_paused = true)]
+async fn test_replays_golden_trace() {
+    let (sim, blocks, trace, archive, dir) = record().await;
+    assert_eq!(trace.blocks.iter().map(|block| block.block).collect::<Vec<_>>(), blocks);
+
+    let path = dir.path().join("trace.json");
+    trace.write(&path).unwrap();
+    let trace = GoldenTrace::read(&path).unwrap();
+    trace.verify_archive(sim.config().clone(), archive).await.unwrap();
+}
+
+/// A trace whose digest of a block differs from the derived attributes, or that ends before the
+/// range is exhausted, is rejected.
+#[tokio::test(start_paused = true)]
+async fn test_detects_golden_trace_regressions() {
+    let (sim, _, trace, archive, _dir) = record().await;
+
+    let mut tampered = trace.clone();
+    tampered.blocks[2].attributes = B256::ZERO;
+    let err = tampered.verify_archive(sim.config().clone(), archive.clone()).await.unwrap_err();
+    assert!(matches!(err, GoldenTraceError::Mismatch { number: 3, expected: B256::ZERO, .. }));
+
+    let mut truncated = trace;
+    truncated.blocks.truncate(5);
+    let err = truncated.verify_archive(sim.config().clone(), archive).await.unwrap_err();
+    assert!(matches!(err, GoldenTraceError::UnexpectedBlock(6)), "{err}");
+}
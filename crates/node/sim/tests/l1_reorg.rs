@@ -0,0 +1,65 @@
+//! Simulations of L1 reorgs.
+
+use kona_node_sim::Simulation;
+use tokio::time::{Duration, timeout};
+
+/// An L1 reorg of depth 3 removes the first frames of a channel while the channel is still being
+/// decoded. The last frame alone must not make any progress, and the channel is derived once it is
+/// submitted in full on the new L1 chain.
+#[tokio::test(start_paused = true)]
+async fn test_l1_reorg_during_channel_decoding() {
+    let mut sim = Simulation::new().await.unwrap();
+    let genesis = sim.sequencer().head();
+    let node = sim.spawn_verifier().await.unwrap();
+    let mut reorgs = node.handles().reorgs.subscribe();
+
+    let blocks = sim.sequence(6).await.unwrap();
+    let frames = sim.batcher().channel(&blocks, 3).unwrap();
+    sim.l1().mine(vec![]);
+    sim.submit(&frames[..1]).unwrap();
+    sim.submit(&frames[1..2]).unwrap();
+    let head = sim.l1().mine(vec![]);
+    node.settle(&head).await.unwrap();
+    assert_eq!(node.safe_head(), genesis);
+
+    sim.l1().reorg(3);
+    for _ in 0..3 {
+        sim.l1().mine(vec![]);
+    }
+    let head = sim.submit(&frames[2..]).unwrap();
+    node.settle(&head).await.unwrap();
+    assert_eq!(node.safe_head(), genesis);
+
+    sim.submit(&frames).unwrap();
+    node.wait_for_safe_head(&blocks[5]).await.unwrap();
+    assert_eq!(node.el().head(), blocks[5].block_info.hash);
+    timeout(Duration::from_secs(5), reorgs.recv()).await.unwrap().unwrap();
+    node.shutdown().await.unwrap();
+}
+
+/// With a confirmation depth of 2, the block including a channel is only derived from once it is
+/// buried by two blocks, so a reorg of depth 1 that removes it goes unnoticed by derivation.
+#[tokio::test(start_paused = true)]
+async fn test_l1_confirmation_depth_hides_shallow_reorgs() {
+    let mut sim = Simulation::new().await.unwrap();
+    let genesis = sim.sequencer().head();
+    let node = sim.spawn_verifier_with_l1_confirmation_depth(2).await.unwrap();
+
+    let blocks = sim.sequence(6).await.unwrap();
+    let frames = sim.batcher().channel(&blocks, 1).unwrap();
+    sim.l1().mine(vec![]);
+    let head = sim.submit(&frames).unwrap();
+    node.settle(&head).await.unwrap();
+    assert_eq!(node.safe_head(), genesis);
+
+    sim.l1().reorg(1);
+    let head = sim.submit(&frames).unwrap();
+    node.settle(&head).await.unwrap();
+    assert_eq!(node.safe_head(), genesis);
+
+    sim.l1().mine(vec![]);
+    sim.l1().mine(vec![]);
+    node.wait_for_safe_head(&blocks[5]).await.unwrap();
+    assert_eq!(node.el().head(), blocks[5].block_info.hash);
+    node.shutdown().await.unwrap();
+}
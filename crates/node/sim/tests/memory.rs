@@ -0,0 +1,45 @@
+//! Simulations of a verifier whose buffers are audited against memory ceilings.
+
+use kona_node_sim::Simulation;
+use kona_protocol::{MemoryAudit, MemoryComponent};
+
+/// Derives a channel whose frames are spread over several L1 blocks with the given audit, and
+/// returns the audit once the verifier reached the safe head.
+async fn derive(audit: MemoryAudit) -> MemoryAudit {
+    let mut sim = Simulation::new().await.unwrap();
+    let node = sim.spawn_verifier_with_memory_audit(audit.clone()).await.unwrap();
+
+    let blocks = sim.sequence(6).await.unwrap();
+    let frames = sim.batcher().channel(&blocks, 3).unwrap();
+    sim.l1().mine(vec![]);
+    for frame in frames.chunks(1) {
+        sim.submit(frame).unwrap();
+    }
+
+    node.wait_for_safe_head(&blocks[5]).await.unwrap();
+    node.shutdown().await.unwrap();
+    audit
+}
+
+/// The buffers of a verifier deriving multi-frame channels stay within generous ceilings.
+#[tokio::test(start_paused = true)]
+async fn test_buffers_stay_within_ceilings() {
+    let audit = MemoryAudit::new()
+        .with_ceiling(MemoryComponent::ChannelBank, 1 << 20)
+        .with_ceiling(MemoryComponent::AttributesQueue, 1 << 20)
+        .with_ceiling(MemoryComponent::UnsafePayloads, 1 << 20);
+    let audit = derive(audit).await;
+
+    assert!(audit.usage(MemoryComponent::ChannelBank).peak > 0);
+    audit.check().unwrap();
+}
+
+/// A ceiling below the size of the buffered frames fails the audit.
+#[tokio::test(start_paused = true)]
+async fn test_detects_ceiling_breach() {
+    let audit = derive(MemoryAudit::new().with_ceiling(MemoryComponent::ChannelBank, 1)).await;
+
+    let err = audit.check().unwrap_err();
+    assert_eq!(err.component, MemoryComponent::ChannelBank);
+    assert!(err.peak > 1);
+}
@@ -0,0 +1,114 @@
+//! Parallel derivation of historical L1 ranges, stitched back in order.
+
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::PayloadAttributes;
+use async_trait::async_trait;
+use kona_node_service::{
+    EpochDeriver, EpochRange, RangeBackfill, RangeBackfillConfig, RangeBackfillError,
+};
+use kona_protocol::{BlockInfo, L2BlockInfo, OpAttributesWithParent};
+use op_alloy_rpc_types_engine::OpPayloadAttributes;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// The L2 block time of the synthetic chain.
+const BLOCK_TIME: u64 = 2;
+
+/// Returns the L1 block of the given number in the synthetic chain.
+fn l1_block(number: u64) -> BlockInfo {
+    BlockInfo::new(B256::with_last_byte(number as u8), number, B256::ZERO, number * 12)
+}
+
+/// Returns the attributes of the given L2 block of the synthetic chain, which derives two L2
+/// blocks from each L1 block.
+fn attributes(number: u64) -> OpAttributesWithParent {
+    let parent = number - 1;
+    OpAttributesWithParent::new(
+        OpPayloadAttributes {
+            payload_attributes: PayloadAttributes {
+                timestamp: number * BLOCK_TIME,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        L2BlockInfo::new(
+            BlockInfo::new(B256::ZERO, parent, B256::ZERO, parent * BLOCK_TIME),
+            l1_block(parent / 2).id(),
+            parent % 2,
+        ),
+        l1_block(number / 2),
+        false,
+    )
+}
+
+/// An [`EpochDeriver`] of the synthetic chain, which derives later ranges faster, so that they
+/// complete out of order.
+#[derive(Debug)]
+struct SyntheticDeriver {
+    /// The L2 block whose parent is corrupted, if any.
+    corrupt: Option<u64>,
+}
+
+#[async_trait]
+impl EpochDeriver for SyntheticDeriver {
+    type Error = String;
+
+    async fn derive(&self, range: EpochRange) -> Result<Vec<OpAttributesWithParent>, String> {
+        tokio::time::sleep(Duration::from_millis(50u64.saturating_sub(range.index as u64 * 10)))
+            .await;
+        Ok((range.l1_start * 2..=range.l1_end * 2 + 1)
+            .filter(|number| *number > 0)
+            .map(|number| {
+                let mut attributes = attributes(number);
+                if self.corrupt == Some(number) {
+                    attributes.parent.block_info.timestamp += 1;
+                }
+                attributes
+            })
+            .collect())
+    }
+}
+
+/// The ranges derived concurrently are sent in order.
+#[tokio::test(start_paused = true)]
+async fn test_ranges_stitched_in_order() {
+    let backfill = RangeBackfill::new(
+        SyntheticDeriver { corrupt: None },
+        RangeBackfillConfig { workers: 4, epochs_per_range: 3 },
+    );
+    let (tx, mut rx) = mpsc::channel(1024);
+    let summary = backfill.run(0, 11, tx, CancellationToken::new()).await.unwrap();
+
+    assert_eq!(summary.ranges, 4);
+    assert_eq!(summary.attributes, 23);
+    assert_eq!(summary.last_block, Some(23));
+    let mut expected = 1;
+    while let Some(attributes) = rx.recv().await {
+        assert_eq!(attributes.block_number(), expected);
+        expected += 1;
+    }
+    assert_eq!(expected, 24);
+}
+
+/// A range whose attributes do not link to the range before it is rejected, after the ranges
+/// before it were sent.
+#[tokio::test(start_paused = true)]
+async fn test_rejects_broken_parent_link() {
+    let backfill = RangeBackfill::new(
+        SyntheticDeriver { corrupt: Some(6) },
+        RangeBackfillConfig { workers: 2, epochs_per_range: 3 },
+    );
+    let (tx, mut rx) = mpsc::channel(1024);
+    let err = backfill.run(0, 11, tx, CancellationToken::new()).await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        RangeBackfillError::ParentMismatch { number: 6, reason: "parent timestamp", .. }
+    ));
+    let mut sent = 0;
+    while rx.recv().await.is_some() {
+        sent += 1;
+    }
+    assert_eq!(sent, 5);
+}
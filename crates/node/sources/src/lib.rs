@@ -11,7 +11,10 @@
 extern crate tracing;
 
 mod sync;
-pub use sync::{L2ForkchoiceState, SyncStartError, find_starting_forkchoice};
+pub use sync::{
+    L2ForkchoiceState, SafeHeadHint, SafeHeadHintParseError, SyncStartError,
+    find_starting_forkchoice,
+};
 
 mod runtime;
 pub use runtime::{RuntimeConfig, RuntimeLoader, RuntimeLoaderError};
@@ -38,4 +38,13 @@ pub enum SyncStartError {
     /// Inconsistent sequence number.
     #[error("Inconsistent sequence number; Must monotonically increase.")]
     InconsistentSequenceNumber,
+    /// The L1 block of a safe head hint is not canonical.
+    #[error("Safe head hint L1 block {0} is not canonical")]
+    NonCanonicalHintL1Block(B256),
+    /// The safe head of a hint does not match the execution layer's block.
+    #[error("Safe head hint mismatch. Expected block #{0}, Got #{1}")]
+    MismatchedHintSafeHead(u64, u64),
+    /// The L1 origin of the safe head of a hint is after the hinted L1 block.
+    #[error("Safe head hint L1 origin #{0} is after the hinted L1 block #{1}")]
+    HintL1OriginTooNew(u64, u64),
 }
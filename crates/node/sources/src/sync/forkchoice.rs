@@ -80,6 +80,22 @@ impl L2ForkchoiceState {
 
         Ok(Self { un_safe, safe, finalized })
     }
+
+    /// Fast-forwards the safe head to the given trusted safe head, e.g. a verified
+    /// [`SafeHeadHint`], if it is ahead. The unsafe head is moved along if it is behind, and the
+    /// finalized head is left as-is. Returns `true` if the safe head moved.
+    ///
+    /// [`SafeHeadHint`]: crate::SafeHeadHint
+    pub const fn fast_forward(&mut self, safe: L2BlockInfo) -> bool {
+        if safe.block_info.number <= self.safe.block_info.number {
+            return false;
+        }
+        self.safe = safe;
+        if self.un_safe.block_info.number < safe.block_info.number {
+            self.un_safe = safe;
+        }
+        true
+    }
 }
 
 /// Wrapper function around [`Provider::get_block`] to handle compatibility issues with geth and
@@ -102,3 +118,33 @@ async fn get_block_compat<P: Provider<Optimism>>(
         r => r,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kona_protocol::BlockInfo;
+
+    fn block(number: u64) -> L2BlockInfo {
+        L2BlockInfo { block_info: BlockInfo { number, ..Default::default() }, ..Default::default() }
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let start =
+            L2ForkchoiceState { un_safe: block(100), safe: block(50), finalized: block(10) };
+
+        let mut fc = start;
+        assert!(!fc.fast_forward(block(50)));
+        assert!(!fc.fast_forward(block(40)));
+        assert_eq!(fc, start);
+
+        assert!(fc.fast_forward(block(80)));
+        assert_eq!(fc, L2ForkchoiceState { safe: block(80), ..start });
+
+        assert!(fc.fast_forward(block(120)));
+        assert_eq!(
+            fc,
+            L2ForkchoiceState { un_safe: block(120), safe: block(120), finalized: block(10) }
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Trusted safe head hints, to fast-forward the sync start.
+
+use crate::SyncStartError;
+use alloy_eips::BlockNumHash;
+use alloy_primitives::B256;
+use alloy_provider::{Provider, RootProvider};
+use kona_genesis::RollupConfig;
+use kona_protocol::L2BlockInfo;
+use op_alloy_network::Optimism;
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+/// A trusted hint of the safe head of the L2 chain at an L1 block: the highest L2 block derived
+/// from the L1 chain up to and including the L1 block.
+///
+/// The hint is taken from the configuration, or from a trusted rollup node, and lets a node that
+/// does not need to validate the whole chain start derivation from the hinted safe head rather
+/// than walking back from the heads of the execution layer. It is [verified][Self::verify] against
+/// the L1 chain and the execution layer before being used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SafeHeadHint {
+    /// The L1 block.
+    pub l1_block: BlockNumHash,
+    /// The safe head at the L1 block.
+    pub safe_head: BlockNumHash,
+}
+
+impl SafeHeadHint {
+    /// Verifies the hint, returning the [`L2BlockInfo`] of the hinted safe head.
+    ///
+    /// The hinted L1 block must be canonical, the execution layer must hold the hinted safe head,
+    /// and the L1 origin of the safe head must be canonical, at or before the hinted L1 block.
+    pub async fn verify(
+        &self,
+        cfg: &RollupConfig,
+        l1_provider: &RootProvider,
+        l2_provider: &RootProvider<Optimism>,
+    ) -> Result<L2BlockInfo, SyncStartError> {
+        let l1_block = l1_provider
+            .get_block(self.l1_block.number.into())
+            .await?
+            .ok_or(SyncStartError::BlockNotFound(self.l1_block.number.into()))?;
+        if l1_block.header.hash != self.l1_block.hash {
+            return Err(SyncStartError::NonCanonicalHintL1Block(self.l1_block.hash));
+        }
+
+        let l2_block = l2_provider
+            .get_block(self.safe_head.hash.into())
+            .full()
+            .await?
+            .ok_or(SyncStartError::BlockNotFound(self.safe_head.hash.into()))?;
+        let safe_head =
+            L2BlockInfo::from_block_and_genesis(&l2_block.into_consensus(), &cfg.genesis)?;
+        if safe_head.block_info.number != self.safe_head.number {
+            return Err(SyncStartError::MismatchedHintSafeHead(
+                self.safe_head.number,
+                safe_head.block_info.number,
+            ));
+        }
+
+        // The safe head cannot have been derived from L1 blocks after the hinted one.
+        if safe_head.l1_origin.number > self.l1_block.number {
+            return Err(SyncStartError::HintL1OriginTooNew(
+                safe_head.l1_origin.number,
+                self.l1_block.number,
+            ));
+        }
+        let l1_origin = l1_provider
+            .get_block(safe_head.l1_origin.number.into())
+            .await?
+            .ok_or(SyncStartError::BlockNotFound(safe_head.l1_origin.number.into()))?;
+        if l1_origin.header.hash != safe_head.l1_origin.hash {
+            return Err(SyncStartError::L1OriginMismatch);
+        }
+
+        Ok(safe_head)
+    }
+}
+
+impl Display for SafeHeadHint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}@{}:{}",
+            self.safe_head.number, self.safe_head.hash, self.l1_block.number, self.l1_block.hash
+        )
+    }
+}
+
+/// An error parsing a [`SafeHeadHint`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid safe head hint {0:?}, expected <l2_number>:<l2_hash>@<l1_number>:<l1_hash>")]
+pub struct SafeHeadHintParseError(String);
+
+impl FromStr for SafeHeadHint {
+    type Err = SafeHeadHintParseError;
+
+    /// Parses a hint of the form `<l2_number>:<l2_hash>@<l1_number>:<l1_hash>`, i.e. the safe
+    /// head at the L1 block.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let block = |block: &str| {
+            let (number, hash) = block.split_once(':')?;
+            Some(BlockNumHash { number: number.parse().ok()?, hash: B256::from_str(hash).ok()? })
+        };
+        let (safe_head, l1_block) = s
+            .split_once('@')
+            .and_then(|(safe_head, l1_block)| Some((block(safe_head)?, block(l1_block)?)))
+            .ok_or_else(|| SafeHeadHintParseError(s.to_string()))?;
+        Ok(Self { l1_block, safe_head })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_safe_head_hint() {
+        let hint = SafeHeadHint {
+            l1_block: BlockNumHash { number: 6834391, hash: B256::repeat_byte(1) },
+            safe_head: BlockNumHash { number: 18266679, hash: B256::repeat_byte(2) },
+        };
+        assert_eq!(hint.to_string().parse::<SafeHeadHint>(), Ok(hint));
+
+        for invalid in ["", "18266679", "18266679:0x02@6834391", "0x02@6834391:0x01", "a:b@c:d"] {
+            assert_eq!(
+                invalid.parse::<SafeHeadHint>(),
+                Err(SafeHeadHintParseError(invalid.to_string()))
+            );
+        }
+    }
+}
@@ -9,6 +9,9 @@ pub use forkchoice::L2ForkchoiceState;
 
 mod error;
 pub use error::SyncStartError;
+
+mod hint;
+pub use hint::{SafeHeadHint, SafeHeadHintParseError};
 use op_alloy_network::Optimism;
 
 /// Searches for the latest [`L2ForkchoiceState`] that we can use to start the sync process with.
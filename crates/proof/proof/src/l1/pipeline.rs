@@ -6,8 +6,9 @@ use async_trait::async_trait;
 use core::fmt::Debug;
 use kona_derive::{
     ChainProvider, DataAvailabilityProvider, DerivationPipeline, L2ChainProvider, OriginProvider,
-    Pipeline, PipelineBuilder, PipelineErrorKind, PipelineResult, PolledAttributesQueueStage,
-    ResetSignal, Signal, SignalReceiver, StatefulAttributesBuilder, StepResult,
+    Pipeline, PipelineBuilder, PipelineErrorKind, PipelineResult, PipelineStateSize,
+    PolledAttributesQueueStage, ResetSignal, Signal, SignalReceiver, StatefulAttributesBuilder,
+    StepResult,
 };
 use kona_driver::{DriverPipeline, PipelineCursor};
 use kona_genesis::{RollupConfig, SystemConfig};
@@ -169,6 +170,11 @@ where
         self.pipeline.rollup_config()
     }
 
+    /// Returns the [PipelineStateSize] of the pipeline.
+    fn state_size(&self) -> PipelineStateSize {
+        self.pipeline.state_size()
+    }
+
     /// Returns the [SystemConfig] by L2 number.
     async fn system_config_by_number(
         &mut self,
@@ -125,10 +125,6 @@ where
             return Err(ChannelOutError::MaxFrameSizeTooSmall);
         }
 
-        // Construct an empty frame.
-        let mut frame =
-            Frame { id: self.id, number: self.frame_number, is_last: self.closed, data: vec![] };
-
         let mut max_size = max_size - FRAME_V0_OVERHEAD;
         if max_size > self.ready_bytes() {
             max_size = self.ready_bytes();
@@ -137,7 +133,7 @@ where
         // Read `max_size` bytes from the compressed data.
         let mut data = Vec::with_capacity(max_size);
         self.compressor.read(&mut data).map_err(ChannelOutError::Compression)?;
-        frame.data.extend_from_slice(data.as_slice());
+        let frame = Frame::new(self.id, self.frame_number, data, self.closed);
 
         // Update the compressed data.
         self.frame_number += 1;
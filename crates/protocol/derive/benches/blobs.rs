@@ -0,0 +1,82 @@
+#![allow(missing_docs)]
+//! Contains benchmarks for decoding blobs in the [BlobSource].
+
+use alloy_consensus::{Transaction, TxEnvelope};
+use alloy_eips::eip4844::{BYTES_PER_BLOB, Blob};
+use alloy_primitives::{address, hex};
+use alloy_rlp::Decodable;
+use criterion::{Criterion, criterion_group, criterion_main};
+use kona_derive::{
+    BlobSource, DataAvailabilityProvider, PipelineError, PipelineErrorKind,
+    test_utils::{TestBlobProvider, TestChainProvider},
+};
+use kona_protocol::BlockInfo;
+use pprof::criterion::{Output, PProfProfiler};
+use tokio::runtime::Runtime;
+
+/// Returns a blob holding data up to the end of the given number of rounds of 4 field elements.
+/// The field elements carry arbitrary bytes, with their two high order bits cleared.
+fn blob(rounds: usize) -> Blob {
+    let mut data = [0u8; BYTES_PER_BLOB];
+    for (i, byte) in data[..rounds * 128].iter_mut().enumerate() {
+        *byte = if i % 32 == 0 { (i / 32) as u8 & 0b0011_1111 } else { (i * 7) as u8 };
+    }
+    let length = (rounds * 127 - 4) as u32;
+    data[1] = 0;
+    data[2..5].copy_from_slice(&length.to_be_bytes()[1..]);
+    Blob::from(data)
+}
+
+/// Returns a blob source whose L1 block holds a batcher transaction of 5 blobs, each holding data
+/// up to the end of the given number of rounds.
+fn source(rounds: usize) -> BlobSource<TestChainProvider, TestBlobProvider> {
+    // https://sepolia.etherscan.io/getRawTx?tx=0x9a22ccb0029bc8b0ddd073be1a1d923b7ae2b2ea52100bae0db4424f9107e9c0
+    let raw_tx = hex::decode("0x03f9011d83aa36a7820fa28477359400852e90edd0008252089411e9ca82a3a762b4b5bd264d4173a242e7a770648080c08504a817c800f8a5a0012ec3d6f66766bedb002a190126b3549fce0047de0d4c25cffce0dc1c57921aa00152d8e24762ff22b1cfd9f8c0683786a7ca63ba49973818b3d1e9512cd2cec4a0013b98c6c83e066d5b14af2b85199e3d4fc7d1e778dd53130d180f5077e2d1c7a001148b495d6e859114e670ca54fb6e2657f0cbae5b08063605093a4b3dc9f8f1a0011ac212f13c5dff2b2c6b600a79635103d6f580a4221079951181b25c7e654901a0c8de4cced43169f9aa3d36506363b2d2c44f6c49fc1fd91ea114c86f3757077ea01e11fdd0d1934eda0492606ee0bb80a7bf8f35cc5f86ec60fe5031ba48bfd544").unwrap();
+    let tx = TxEnvelope::decode(&mut raw_tx.as_slice()).unwrap();
+
+    let mut source = BlobSource::new(
+        TestChainProvider::default(),
+        TestBlobProvider::default(),
+        address!("11E9CA82A3a762b4B5bd264d4173a242e7a77064"),
+    );
+    let blob = blob(rounds);
+    for hash in tx.blob_versioned_hashes().unwrap() {
+        source.blob_fetcher.insert_blob(*hash, blob);
+    }
+    source.chain_provider.insert_block_with_transactions(1, BlockInfo::default(), vec![tx]);
+    source
+}
+
+fn blobs(c: &mut Criterion) {
+    let mut g = c.benchmark_group("blobs");
+    g.sample_size(10);
+
+    let rt = Runtime::new().unwrap();
+    let batcher_address = address!("A83C816D4f9b2783761a22BA6FADB0eB0606D7B2");
+
+    for (name, rounds) in [("Full", 1024), ("Quarter", 256), ("Small", 8)] {
+        g.bench_function(format!("Decode 5 blobs - {name} ({} bytes)", rounds * 127 - 4), |b| {
+            let mut source = source(rounds);
+
+            b.iter(|| {
+                rt.block_on(async {
+                    source.clear();
+                    loop {
+                        match source.next(&BlockInfo::default(), batcher_address).await {
+                            Ok(data) => assert_eq!(data.len(), rounds * 127 - 4),
+                            Err(PipelineErrorKind::Temporary(PipelineError::Eof)) => break,
+                            Err(e) => panic!("Failed to decode blobs: {e}"),
+                        }
+                    }
+                })
+            });
+        });
+    }
+}
+
+criterion_group! {
+    name = blob_benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = blobs
+}
+criterion_main!(blob_benches);
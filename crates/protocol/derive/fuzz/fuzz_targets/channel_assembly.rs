@@ -0,0 +1,73 @@
+//! Fuzzes channel assembly and batch decoding, through the channel stages of the derivation
+//! pipeline.
+//!
+//! The first byte of the input selects the active hardforks, and the rest is the calldata of a
+//! batcher transaction.
+
+#![no_main]
+
+use kona_derive::{
+    BatchStreamProvider, ChannelProvider, ChannelReader, PipelineError, PipelineErrorKind,
+    test_utils::TestNextFrameProvider,
+};
+use kona_genesis::{HardForkConfig, RollupConfig};
+use kona_protocol::Frame;
+use libfuzzer_sys::fuzz_target;
+use std::{
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+/// The L2 block time of OP Mainnet, which the seed corpus is captured from.
+const OP_MAINNET_BLOCK_TIME: u64 = 2;
+
+/// The L2 chain id of OP Mainnet, which signs the transactions of the seed corpus.
+const OP_MAINNET_CHAIN_ID: u64 = 10;
+
+/// The number of batches read from the channel stages, for each frame of the input.
+const BATCHES_PER_FRAME: usize = 4;
+
+/// Polls the given future to completion. The test providers never wait, so neither do the
+/// stages.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("the channel stages never wait on the test providers"),
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&hardforks, calldata)) = data.split_first() else {
+        return;
+    };
+    let Ok(frames) = Frame::parse_frames(calldata) else {
+        return;
+    };
+
+    let hardforks = match hardforks % 3 {
+        0 => HardForkConfig::default(),
+        1 => HardForkConfig { fjord_time: Some(0), ..Default::default() },
+        _ => HardForkConfig { holocene_time: Some(0), ..Default::default() },
+    };
+    let cfg = Arc::new(RollupConfig {
+        block_time: OP_MAINNET_BLOCK_TIME,
+        l2_chain_id: OP_MAINNET_CHAIN_ID,
+        hardforks,
+        ..Default::default()
+    });
+
+    // The test provider returns the frames from the back.
+    let reads = frames.len() * BATCHES_PER_FRAME;
+    let frames = TestNextFrameProvider::new(frames.into_iter().rev().map(Ok).collect());
+    let channels = ChannelProvider::new(cfg.clone(), frames);
+    let mut reader = ChannelReader::new(channels, cfg);
+
+    for _ in 0..reads {
+        match block_on(reader.next_batch()) {
+            Err(PipelineErrorKind::Temporary(PipelineError::Eof)) => break,
+            Ok(_) | Err(_) => {}
+        }
+    }
+});
@@ -0,0 +1,20 @@
+//! Fuzzes the parsing of the frames in a batcher transaction.
+
+#![no_main]
+
+use kona_protocol::Frame;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(frames) = Frame::parse_frames(data) else {
+        return;
+    };
+
+    // Every parsed frame must survive an encoding round trip.
+    for frame in frames {
+        let encoded = frame.encode();
+        let (len, decoded) = Frame::decode(&encoded).expect("encoded frames decode");
+        assert_eq!(len, encoded.len());
+        assert_eq!(decoded, frame);
+    }
+});
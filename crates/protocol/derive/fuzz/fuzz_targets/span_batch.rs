@@ -0,0 +1,28 @@
+//! Fuzzes the decoding of span batches.
+
+#![no_main]
+
+use kona_protocol::RawSpanBatch;
+use libfuzzer_sys::fuzz_target;
+
+/// The L2 block time of OP Mainnet, which the seed corpus is captured from.
+const OP_MAINNET_BLOCK_TIME: u64 = 2;
+
+/// The L2 chain id of OP Mainnet, which signs the transactions of the seed corpus.
+const OP_MAINNET_CHAIN_ID: u64 = 10;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(mut batch) = RawSpanBatch::decode(&mut &data[..]) else {
+        return;
+    };
+
+    // A decoded span batch must survive an encoding round trip.
+    let mut encoded = Vec::new();
+    batch.encode(&mut encoded).expect("decoded span batches encode");
+    let decoded =
+        RawSpanBatch::decode(&mut encoded.as_slice()).expect("encoded span batches decode");
+    assert_eq!(decoded, batch);
+
+    // Deriving the blocks of the span batch may fail, but must not panic.
+    let _ = batch.derive(OP_MAINNET_BLOCK_TIME, 0, OP_MAINNET_CHAIN_ID);
+});
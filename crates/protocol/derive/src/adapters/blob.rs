@@ -0,0 +1,41 @@
+//! A blob provider adapted from the [`BlobOracle`].
+
+use crate::{BlobOracle, BlobProvider};
+use alloc::{boxed::Box, vec::Vec};
+use alloy_eips::eip4844::{Blob, IndexedBlobHash};
+use async_trait::async_trait;
+use kona_protocol::BlockInfo;
+
+/// A [`BlobProvider`] backed by a [`BlobOracle`], fetching the blobs of a block one by one.
+///
+/// The blobs are not checked against their versioned hashes, so the oracle must be trusted to
+/// return the right blobs, as e.g. the preimage oracle of an FPVM does.
+#[derive(Debug, Clone)]
+pub struct OracleBlobProvider<O> {
+    /// The [`BlobOracle`].
+    pub oracle: O,
+}
+
+impl<O> OracleBlobProvider<O> {
+    /// Creates a new [`OracleBlobProvider`] backed by the given oracle.
+    pub const fn new(oracle: O) -> Self {
+        Self { oracle }
+    }
+}
+
+#[async_trait]
+impl<O: BlobOracle + Send> BlobProvider for OracleBlobProvider<O> {
+    type Error = O::Error;
+
+    async fn get_blobs(
+        &mut self,
+        block_ref: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Box<Blob>>, Self::Error> {
+        let mut blobs = Vec::with_capacity(blob_hashes.len());
+        for blob_hash in blob_hashes {
+            blobs.push(self.oracle.blob(block_ref, blob_hash).await?);
+        }
+        Ok(blobs)
+    }
+}
@@ -0,0 +1,142 @@
+//! Chain providers adapted from the [`ChainOracle`] and [`L2ChainOracle`].
+
+use crate::{
+    ChainOracle, ChainProvider, L2ChainOracle, L2ChainProvider, PipelineError, PipelineErrorKind,
+};
+use alloc::{boxed::Box, string::ToString, sync::Arc, vec::Vec};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use core::fmt::Display;
+use kona_genesis::{RollupConfig, SystemConfig};
+use kona_protocol::{
+    BatchValidationProvider, BlockInfo, FromBlockError, L2BlockInfo, OpBlockConversionError,
+    to_system_config,
+};
+use op_alloy_consensus::OpBlock;
+use thiserror::Error;
+
+/// A [`ChainProvider`] backed by a [`ChainOracle`].
+///
+/// The [`BlockInfo`]s are derived from the headers returned by the oracle, so that the oracle
+/// only has to provide the raw chain data.
+#[derive(Debug, Clone)]
+pub struct OracleChainProvider<O> {
+    /// The [`ChainOracle`].
+    pub oracle: O,
+}
+
+impl<O> OracleChainProvider<O> {
+    /// Creates a new [`OracleChainProvider`] backed by the given oracle.
+    pub const fn new(oracle: O) -> Self {
+        Self { oracle }
+    }
+}
+
+/// Returns the [`BlockInfo`] of the given header.
+fn block_info(header: &Header) -> BlockInfo {
+    BlockInfo::new(header.hash_slow(), header.number, header.parent_hash, header.timestamp)
+}
+
+#[async_trait]
+impl<O: ChainOracle + Send> ChainProvider for OracleChainProvider<O> {
+    type Error = O::Error;
+
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        self.oracle.header_by_hash(hash).await
+    }
+
+    async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo, Self::Error> {
+        Ok(block_info(&self.oracle.header_by_number(number).await?))
+    }
+
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error> {
+        self.oracle.receipts_by_hash(hash).await
+    }
+
+    async fn block_info_and_transactions_by_hash(
+        &mut self,
+        hash: B256,
+    ) -> Result<(BlockInfo, Vec<TxEnvelope>), Self::Error> {
+        let header = self.oracle.header_by_hash(hash).await?;
+        let transactions = self.oracle.transactions_by_hash(hash).await?;
+        Ok((block_info(&header), transactions))
+    }
+}
+
+/// An error of the [`OracleL2ChainProvider`].
+#[derive(Error, Debug)]
+pub enum OracleProviderError<E> {
+    /// The oracle failed.
+    #[error("{0}")]
+    Oracle(E),
+    /// The [`L2BlockInfo`] could not be derived from the block returned by the oracle.
+    #[error(transparent)]
+    FromBlock(#[from] FromBlockError),
+    /// The [`SystemConfig`] could not be derived from the block returned by the oracle.
+    #[error(transparent)]
+    SystemConfig(#[from] OpBlockConversionError),
+}
+
+impl<E: Display + Into<PipelineErrorKind>> From<OracleProviderError<E>> for PipelineErrorKind {
+    fn from(err: OracleProviderError<E>) -> Self {
+        match err {
+            OracleProviderError::Oracle(err) => err.into(),
+            err => PipelineError::Provider(err.to_string()).crit(),
+        }
+    }
+}
+
+/// An [`L2ChainProvider`] backed by an [`L2ChainOracle`].
+///
+/// The [`L2BlockInfo`]s and [`SystemConfig`]s are derived from the blocks returned by the oracle,
+/// against the [`RollupConfig`].
+#[derive(Debug, Clone)]
+pub struct OracleL2ChainProvider<O> {
+    /// The [`L2ChainOracle`].
+    pub oracle: O,
+    /// The [`RollupConfig`] of the L2 chain.
+    pub rollup_config: Arc<RollupConfig>,
+}
+
+impl<O> OracleL2ChainProvider<O> {
+    /// Creates a new [`OracleL2ChainProvider`] backed by the given oracle.
+    pub const fn new(oracle: O, rollup_config: Arc<RollupConfig>) -> Self {
+        Self { oracle, rollup_config }
+    }
+}
+
+impl<O: L2ChainOracle> OracleL2ChainProvider<O> {
+    /// Returns the block with the given number from the oracle.
+    async fn block(&mut self, number: u64) -> Result<OpBlock, OracleProviderError<O::Error>> {
+        self.oracle.l2_block_by_number(number).await.map_err(OracleProviderError::Oracle)
+    }
+}
+
+#[async_trait]
+impl<O: L2ChainOracle + Send> BatchValidationProvider for OracleL2ChainProvider<O> {
+    type Error = OracleProviderError<O::Error>;
+
+    async fn l2_block_info_by_number(&mut self, number: u64) -> Result<L2BlockInfo, Self::Error> {
+        let block = self.block(number).await?;
+        Ok(L2BlockInfo::from_block_and_genesis(&block, &self.rollup_config.genesis)?)
+    }
+
+    async fn block_by_number(&mut self, number: u64) -> Result<OpBlock, Self::Error> {
+        self.block(number).await
+    }
+}
+
+#[async_trait]
+impl<O: L2ChainOracle + Send> L2ChainProvider for OracleL2ChainProvider<O> {
+    type Error = OracleProviderError<O::Error>;
+
+    async fn system_config_by_number(
+        &mut self,
+        number: u64,
+        rollup_config: Arc<RollupConfig>,
+    ) -> Result<SystemConfig, <Self as L2ChainProvider>::Error> {
+        let block = self.block(number).await?;
+        Ok(to_system_config(&block, &rollup_config)?)
+    }
+}
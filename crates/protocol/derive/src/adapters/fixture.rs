@@ -0,0 +1,234 @@
+//! Oracles backed by a static fixture of chain data.
+
+use crate::{BlobOracle, ChainOracle, L2ChainOracle, PipelineError, PipelineErrorKind};
+use alloc::{boxed::Box, collections::BTreeMap, string::ToString, vec::Vec};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_eips::eip4844::{Blob, IndexedBlobHash};
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use kona_protocol::BlockInfo;
+use op_alloy_consensus::OpBlock;
+use thiserror::Error;
+
+/// An L1 block of a [`StaticFixture`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FixtureBlock {
+    /// The header of the block.
+    pub header: Header,
+    /// The transactions of the block.
+    pub transactions: Vec<TxEnvelope>,
+    /// The receipts of the block.
+    pub receipts: Vec<Receipt>,
+}
+
+/// An error of the [`StaticFixture`]: the fixture does not hold the requested data.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FixtureError {
+    /// The L1 block with the given hash is not in the fixture.
+    #[error("L1 block {0} not in the fixture")]
+    L1BlockNotFound(B256),
+    /// The L1 block with the given number is not in the fixture.
+    #[error("L1 block #{0} not in the fixture")]
+    L1BlockNumberNotFound(u64),
+    /// The L2 block with the given number is not in the fixture.
+    #[error("L2 block #{0} not in the fixture")]
+    L2BlockNotFound(u64),
+    /// The blob with the given versioned hash is not in the fixture.
+    #[error("Blob {0} not in the fixture")]
+    BlobNotFound(B256),
+}
+
+impl From<FixtureError> for PipelineErrorKind {
+    fn from(err: FixtureError) -> Self {
+        // The pipeline waits at the end of the fixture, as it does at the tip of a live chain.
+        PipelineError::Provider(err.to_string()).temp()
+    }
+}
+
+/// Chain data held in memory, implementing the [`ChainOracle`], [`L2ChainOracle`] and
+/// [`BlobOracle`].
+///
+/// It is an example of the oracles of a custom proof environment, e.g. one whose chain data is
+/// embedded in the program image, and serves to run the pipeline against a fixed chain in tests:
+///
+/// ```
+/// use alloy_consensus::Header;
+/// use kona_derive::{ChainProvider, FixtureBlock, OracleChainProvider, StaticFixture};
+///
+/// # async fn example() {
+/// let header = Header { number: 1, ..Default::default() };
+/// let fixture = StaticFixture::new().with_l1_block(FixtureBlock { header, ..Default::default() });
+/// let mut provider = OracleChainProvider::new(fixture);
+/// assert_eq!(provider.block_info_by_number(1).await.unwrap().number, 1);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StaticFixture {
+    /// The L1 blocks, by hash.
+    l1_blocks: BTreeMap<B256, FixtureBlock>,
+    /// The hashes of the canonical L1 blocks, by number.
+    l1_hashes: BTreeMap<u64, B256>,
+    /// The L2 blocks, by number.
+    l2_blocks: BTreeMap<u64, OpBlock>,
+    /// The blobs, by versioned hash.
+    blobs: BTreeMap<B256, Box<Blob>>,
+}
+
+impl StaticFixture {
+    /// Creates a new, empty [`StaticFixture`].
+    pub const fn new() -> Self {
+        Self {
+            l1_blocks: BTreeMap::new(),
+            l1_hashes: BTreeMap::new(),
+            l2_blocks: BTreeMap::new(),
+            blobs: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the given L1 block to the fixture, as the canonical block of its number.
+    pub fn with_l1_block(mut self, block: FixtureBlock) -> Self {
+        let hash = block.header.hash_slow();
+        self.l1_hashes.insert(block.header.number, hash);
+        self.l1_blocks.insert(hash, block);
+        self
+    }
+
+    /// Adds the given L2 block to the fixture, as the canonical block of its number.
+    pub fn with_l2_block(mut self, block: OpBlock) -> Self {
+        self.l2_blocks.insert(block.header.number, block);
+        self
+    }
+
+    /// Adds the given blob to the fixture, under the given versioned hash.
+    pub fn with_blob(mut self, hash: B256, blob: Blob) -> Self {
+        self.blobs.insert(hash, Box::new(blob));
+        self
+    }
+
+    /// Returns the L1 block with the given hash.
+    fn l1_block(&self, hash: B256) -> Result<&FixtureBlock, FixtureError> {
+        self.l1_blocks.get(&hash).ok_or(FixtureError::L1BlockNotFound(hash))
+    }
+}
+
+#[async_trait]
+impl ChainOracle for StaticFixture {
+    type Error = FixtureError;
+
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        Ok(self.l1_block(hash)?.header.clone())
+    }
+
+    async fn header_by_number(&mut self, number: u64) -> Result<Header, Self::Error> {
+        let hash =
+            self.l1_hashes.get(&number).ok_or(FixtureError::L1BlockNumberNotFound(number))?;
+        Ok(self.l1_block(*hash)?.header.clone())
+    }
+
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error> {
+        Ok(self.l1_block(hash)?.receipts.clone())
+    }
+
+    async fn transactions_by_hash(&mut self, hash: B256) -> Result<Vec<TxEnvelope>, Self::Error> {
+        Ok(self.l1_block(hash)?.transactions.clone())
+    }
+}
+
+#[async_trait]
+impl L2ChainOracle for StaticFixture {
+    type Error = FixtureError;
+
+    async fn l2_block_by_number(&mut self, number: u64) -> Result<OpBlock, Self::Error> {
+        self.l2_blocks.get(&number).cloned().ok_or(FixtureError::L2BlockNotFound(number))
+    }
+}
+
+#[async_trait]
+impl BlobOracle for StaticFixture {
+    type Error = FixtureError;
+
+    async fn blob(
+        &mut self,
+        _: &BlockInfo,
+        blob_hash: &IndexedBlobHash,
+    ) -> Result<Box<Blob>, Self::Error> {
+        self.blobs.get(&blob_hash.hash).cloned().ok_or(FixtureError::BlobNotFound(blob_hash.hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BlobProvider, ChainProvider, L2ChainProvider, OracleBlobProvider, OracleChainProvider,
+        OracleL2ChainProvider,
+    };
+    use alloc::sync::Arc;
+    use kona_genesis::{RollupConfig, SystemConfig};
+    use kona_protocol::BatchValidationProvider;
+
+    #[tokio::test]
+    async fn test_oracle_chain_provider() {
+        let parent = Header { number: 1, timestamp: 12, ..Default::default() };
+        let header = Header {
+            number: 2,
+            timestamp: 24,
+            parent_hash: parent.hash_slow(),
+            ..Default::default()
+        };
+        let fixture = StaticFixture::new()
+            .with_l1_block(FixtureBlock { header: parent, ..Default::default() })
+            .with_l1_block(FixtureBlock { header: header.clone(), ..Default::default() });
+        let mut provider = OracleChainProvider::new(fixture);
+
+        let expected = BlockInfo::new(header.hash_slow(), 2, header.parent_hash, 24);
+        assert_eq!(provider.block_info_by_number(2).await.unwrap(), expected);
+        let (info, txs) =
+            provider.block_info_and_transactions_by_hash(header.hash_slow()).await.unwrap();
+        assert_eq!(info, expected);
+        assert!(txs.is_empty());
+        assert!(matches!(
+            provider.block_info_by_number(3).await,
+            Err(FixtureError::L1BlockNumberNotFound(3))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_oracle_l2_chain_provider() {
+        let genesis = OpBlock::default();
+        let mut config = RollupConfig::default();
+        config.genesis.l2.hash = genesis.header.hash_slow();
+        config.genesis.system_config = Some(SystemConfig::default());
+        let config = Arc::new(config);
+        let fixture = StaticFixture::new().with_l2_block(genesis.clone());
+        let mut provider = OracleL2ChainProvider::new(fixture, config.clone());
+
+        let info = provider.l2_block_info_by_number(0).await.unwrap();
+        assert_eq!(info.block_info.hash, genesis.header.hash_slow());
+        assert_eq!(info.l1_origin, config.genesis.l1);
+        assert_eq!(
+            provider.system_config_by_number(0, config).await.unwrap(),
+            SystemConfig::default()
+        );
+        let err: PipelineErrorKind = provider.block_by_number(1).await.unwrap_err().into();
+        assert!(matches!(err, PipelineErrorKind::Temporary(_)));
+    }
+
+    #[tokio::test]
+    async fn test_oracle_blob_provider() {
+        let hash = B256::repeat_byte(1);
+        let fixture = StaticFixture::new().with_blob(hash, Blob::with_last_byte(2));
+        let mut provider = OracleBlobProvider::new(fixture);
+
+        let blobs = provider
+            .get_blobs(&BlockInfo::default(), &[IndexedBlobHash { index: 0, hash }])
+            .await
+            .unwrap();
+        assert_eq!(blobs, [Box::new(Blob::with_last_byte(2))]);
+        let missing = IndexedBlobHash { index: 1, hash: B256::ZERO };
+        assert!(matches!(
+            provider.get_blobs(&BlockInfo::default(), &[missing]).await,
+            Err(FixtureError::BlobNotFound(_))
+        ));
+    }
+}
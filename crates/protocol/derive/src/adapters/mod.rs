@@ -0,0 +1,32 @@
+//! Adapters implementing the pipeline's providers on top of the minimal oracle traits.
+//!
+//! A custom proof environment implements the [`ChainOracle`], [`L2ChainOracle`] and
+//! [`BlobOracle`] traits over whatever it can read the chain data from, and wraps them in the
+//! adapters below to get a [`ChainProvider`], an [`L2ChainProvider`] and a [`BlobProvider`]:
+//!
+//! - [`OracleChainProvider`] derives the [`BlockInfo`]s from the L1 headers.
+//! - [`OracleL2ChainProvider`] derives the [`L2BlockInfo`]s and [`SystemConfig`]s from the L2
+//!   blocks, against the [`RollupConfig`].
+//! - [`OracleBlobProvider`] fetches the blobs of a block one by one.
+//!
+//! The [`StaticFixture`] is an example of such oracles, backed by chain data held in memory.
+//!
+//! [`ChainOracle`]: crate::ChainOracle
+//! [`L2ChainOracle`]: crate::L2ChainOracle
+//! [`BlobOracle`]: crate::BlobOracle
+//! [`ChainProvider`]: crate::ChainProvider
+//! [`L2ChainProvider`]: crate::L2ChainProvider
+//! [`BlobProvider`]: crate::BlobProvider
+//! [`BlockInfo`]: kona_protocol::BlockInfo
+//! [`L2BlockInfo`]: kona_protocol::L2BlockInfo
+//! [`SystemConfig`]: kona_genesis::SystemConfig
+//! [`RollupConfig`]: kona_genesis::RollupConfig
+
+mod chain;
+pub use chain::{OracleChainProvider, OracleL2ChainProvider, OracleProviderError};
+
+mod blob;
+pub use blob::OracleBlobProvider;
+
+mod fixture;
+pub use fixture::{FixtureBlock, FixtureError, StaticFixture};
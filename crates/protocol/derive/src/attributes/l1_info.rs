@@ -0,0 +1,45 @@
+//! The [`L1InfoTxOverride`] hook of custom chains.
+
+use crate::BuilderError;
+use alloc::fmt::Debug;
+use alloy_consensus::Header;
+use kona_genesis::{RollupConfig, SystemConfig};
+use kona_protocol::L1BlockInfoTx;
+use op_alloy_consensus::TxDeposit;
+
+/// The inputs that the L1 info deposit transaction of an L2 block is built from.
+#[derive(Debug, Clone, Copy)]
+pub struct L1InfoTxInputs<'a> {
+    /// The rollup config.
+    pub rollup_config: &'a RollupConfig,
+    /// The system config of the L2 block, including the updates of its L1 origin.
+    pub system_config: &'a SystemConfig,
+    /// The header of the L1 origin of the L2 block.
+    pub l1_header: &'a Header,
+    /// The sequence number of the L2 block within its epoch.
+    pub sequence_number: u64,
+    /// The timestamp of the L2 block.
+    pub l2_block_time: u64,
+    /// The [`L1BlockInfoTx`] encoded in the calldata of the transaction.
+    pub l1_info: &'a L1BlockInfoTx,
+}
+
+/// A hook customizing the L1 info deposit transaction built by the
+/// [`StatefulAttributesBuilder`], for OP Stack derivatives that extend it, e.g. with extra fields
+/// in its calldata or a custom L1 block predeploy.
+///
+/// The hook receives the transaction built for the OP Stack, and returns the one to include
+/// instead. Chains that change the calldata must also recover the [`SystemConfig`] from their L2
+/// blocks accordingly, through their [`L2ChainProvider`].
+///
+/// [`StatefulAttributesBuilder`]: crate::StatefulAttributesBuilder
+/// [`L2ChainProvider`]: crate::L2ChainProvider
+pub trait L1InfoTxOverride: Debug + Send + Sync {
+    /// Returns the L1 info deposit transaction of the L2 block built from the given inputs, in
+    /// place of the given OP Stack transaction.
+    fn override_l1_info_tx(
+        &self,
+        inputs: L1InfoTxInputs<'_>,
+        tx: TxDeposit,
+    ) -> Result<TxDeposit, BuilderError>;
+}
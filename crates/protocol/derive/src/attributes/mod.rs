@@ -4,3 +4,8 @@
 
 mod stateful;
 pub use stateful::StatefulAttributesBuilder;
+
+#[cfg(feature = "custom-chain")]
+mod l1_info;
+#[cfg(feature = "custom-chain")]
+pub use l1_info::{L1InfoTxInputs, L1InfoTxOverride};
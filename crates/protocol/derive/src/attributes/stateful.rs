@@ -1,21 +1,24 @@
 //! The [`AttributesBuilder`] and it's default implementation.
 
 use crate::{
-    AttributesBuilder, BuilderError, ChainProvider, L2ChainProvider, PipelineEncodingError,
-    PipelineError, PipelineErrorKind, PipelineResult,
+    AttributesBuilder, BuilderError, ChainProvider, L2ChainProvider, LogFilter,
+    PipelineEncodingError, PipelineError, PipelineErrorKind, PipelineResult,
 };
+#[cfg(feature = "custom-chain")]
+use crate::{L1InfoTxInputs, L1InfoTxOverride};
 use alloc::{boxed::Box, fmt::Debug, string::ToString, sync::Arc, vec, vec::Vec};
-use alloy_consensus::{Eip658Value, Receipt};
+use alloy_consensus::{Eip658Value, Header, Receipt, Sealed};
 use alloy_eips::{BlockNumHash, eip2718::Encodable2718};
 use alloy_primitives::{Address, B256, Bytes};
 use alloy_rlp::Encodable;
 use alloy_rpc_types_engine::PayloadAttributes;
 use async_trait::async_trait;
-use kona_genesis::RollupConfig;
+use kona_genesis::{RollupConfig, SystemConfig};
 use kona_hardforks::{Hardfork, Hardforks};
 use kona_protocol::{
     DEPOSIT_EVENT_ABI_HASH, L1BlockInfoTx, L2BlockInfo, Predeploys, decode_deposit,
 };
+use op_alloy_consensus::TxDeposit;
 use op_alloy_rpc_types_engine::OpPayloadAttributes;
 
 /// A stateful implementation of the [`AttributesBuilder`].
@@ -31,6 +34,9 @@ where
     config_fetcher: L2P,
     /// The L1 receipts fetcher.
     receipts_fetcher: L1P,
+    /// The hook customizing the L1 info deposit transaction, if any.
+    #[cfg(feature = "custom-chain")]
+    l1_info_override: Option<Arc<dyn L1InfoTxOverride>>,
 }
 
 impl<L1P, L2P> StatefulAttributesBuilder<L1P, L2P>
@@ -40,7 +46,60 @@ where
 {
     /// Create a new [`StatefulAttributesBuilder`] with the given epoch.
     pub const fn new(rcfg: Arc<RollupConfig>, sys_cfg_fetcher: L2P, receipts: L1P) -> Self {
-        Self { rollup_cfg: rcfg, config_fetcher: sys_cfg_fetcher, receipts_fetcher: receipts }
+        Self {
+            rollup_cfg: rcfg,
+            config_fetcher: sys_cfg_fetcher,
+            receipts_fetcher: receipts,
+            #[cfg(feature = "custom-chain")]
+            l1_info_override: None,
+        }
+    }
+
+    /// Customizes the L1 info deposit transaction of the built payloads with the given
+    /// [`L1InfoTxOverride`].
+    #[cfg(feature = "custom-chain")]
+    pub fn with_l1_info_override(mut self, hook: Arc<dyn L1InfoTxOverride>) -> Self {
+        self.l1_info_override = Some(hook);
+        self
+    }
+
+    /// Builds the L1 info deposit transaction of the L2 block with the given timestamp.
+    fn l1_info_tx(
+        &self,
+        sys_config: &SystemConfig,
+        sequence_number: u64,
+        l1_header: &Header,
+        l2_block_time: u64,
+    ) -> PipelineResult<Sealed<TxDeposit>> {
+        #[cfg_attr(not(feature = "custom-chain"), allow(unused_variables))]
+        let (l1_info, tx) = L1BlockInfoTx::try_new_with_deposit_tx(
+            &self.rollup_cfg,
+            sys_config,
+            sequence_number,
+            l1_header,
+            l2_block_time,
+        )
+        .map_err(|e| {
+            PipelineError::AttributesBuilder(BuilderError::Custom(e.to_string())).crit()
+        })?;
+
+        #[cfg(feature = "custom-chain")]
+        if let Some(hook) = &self.l1_info_override {
+            let inputs = L1InfoTxInputs {
+                rollup_config: &self.rollup_cfg,
+                system_config: sys_config,
+                l1_header,
+                sequence_number,
+                l2_block_time,
+                l1_info: &l1_info,
+            };
+            let tx = hook
+                .override_l1_info_tx(inputs, tx.into_inner())
+                .map_err(|e| PipelineError::AttributesBuilder(e).crit())?;
+            return Ok(alloy_consensus::Sealable::seal_slow(tx));
+        }
+
+        Ok(tx)
     }
 }
 
@@ -80,8 +139,22 @@ where
                     .into(),
                 ));
             }
-            let receipts =
-                self.receipts_fetcher.receipts_by_hash(epoch.hash).await.map_err(Into::into)?;
+            // The receipts of L1 origins that cannot contain deposits or system config updates
+            // are skipped.
+            let filters = [
+                LogFilter::deposits(&self.rollup_cfg),
+                LogFilter::system_config_updates(&self.rollup_cfg),
+            ];
+            let receipts = if self
+                .receipts_fetcher
+                .may_contain_logs(epoch.hash, &filters)
+                .await
+                .map_err(Into::into)?
+            {
+                self.receipts_fetcher.receipts_by_hash(epoch.hash).await.map_err(Into::into)?
+            } else {
+                Vec::new()
+            };
             let deposits =
                 derive_deposits(epoch.hash, &receipts, self.rollup_cfg.deposit_contract_address)
                     .await
@@ -149,16 +222,8 @@ where
         }
 
         // Build and encode the L1 info transaction for the current payload.
-        let (_, l1_info_tx_envelope) = L1BlockInfoTx::try_new_with_deposit_tx(
-            &self.rollup_cfg,
-            &sys_config,
-            sequence_number,
-            &l1_header,
-            next_l2_time,
-        )
-        .map_err(|e| {
-            PipelineError::AttributesBuilder(BuilderError::Custom(e.to_string())).crit()
-        })?;
+        let l1_info_tx_envelope =
+            self.l1_info_tx(&sys_config, sequence_number, &l1_header, next_l2_time)?;
         let mut encoded_l1_info_tx = Vec::with_capacity(l1_info_tx_envelope.length());
         l1_info_tx_envelope.encode_2718(&mut encoded_l1_info_tx);
 
@@ -189,9 +254,9 @@ where
             },
             transactions: Some(txs),
             no_tx_pool: Some(true),
-            gas_limit: Some(u64::from_be_bytes(
-                alloy_primitives::U64::from(sys_config.gas_limit).to_be_bytes(),
-            )),
+            // The gas limit and EIP-1559 parameters follow the system config, so that updates
+            // emitted on L1 take effect from the first block of the epoch that includes them.
+            gas_limit: Some(sys_config.gas_limit),
             eip_1559_params: sys_config.eip_1559_params(
                 &self.rollup_cfg,
                 l2_parent.block_info.timestamp,
@@ -241,11 +306,53 @@ mod tests {
         test_utils::{TestChainProvider, TestSystemConfigL2Fetcher},
     };
     use alloc::vec;
-    use alloy_consensus::Header;
-    use alloy_primitives::{B256, Log, LogData, U64, U256, address};
-    use kona_genesis::{HardForkConfig, SystemConfig};
+    use alloy_eips::eip2718::Decodable2718;
+    use alloy_primitives::{B64, B256, Log, LogData, U64, U256, address, logs_bloom};
+    use kona_genesis::{
+        CONFIG_UPDATE_EVENT_VERSION_0, CONFIG_UPDATE_TOPIC, HardForkConfig, SystemConfig,
+        SystemConfigUpdateKind,
+    };
     use kona_protocol::{BlockInfo, DepositError};
 
+    /// The address of the system config contract in the [`holocene_config`].
+    const SYSTEM_CONFIG: Address = address!("4444444444444444444444444444444444444444");
+
+    /// Returns a [`RollupConfig`] with Holocene active from the given timestamp.
+    fn holocene_config(holocene_time: u64) -> RollupConfig {
+        RollupConfig {
+            block_time: 2,
+            l1_system_config_address: SYSTEM_CONFIG,
+            hardforks: HardForkConfig { holocene_time: Some(holocene_time), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    /// Returns a receipt holding a `ConfigUpdate` log of the system config contract, with the
+    /// given update type and value.
+    fn config_update_receipt(kind: SystemConfigUpdateKind, value: u64) -> Receipt {
+        let mut data = vec![0u8; 96];
+        data[31] = 0x20;
+        data[63] = 0x20;
+        data[88..96].copy_from_slice(&value.to_be_bytes());
+        let log = Log {
+            address: SYSTEM_CONFIG,
+            data: LogData::new_unchecked(
+                vec![
+                    CONFIG_UPDATE_TOPIC,
+                    CONFIG_UPDATE_EVENT_VERSION_0,
+                    B256::from(U256::from(kind as u64)),
+                ],
+                Bytes::from(data),
+            ),
+        };
+        Receipt { status: Eip658Value::Eip658(true), logs: vec![log], ..Default::default() }
+    }
+
+    /// Returns the encoded EIP-1559 parameters of the payload attributes.
+    fn eip_1559_params(denominator: u32, elasticity: u32) -> Option<B64> {
+        Some(B64::from_slice(&[denominator.to_be_bytes(), elasticity.to_be_bytes()].concat()))
+    }
+
     fn generate_valid_log() -> Log {
         let deposit_contract = address!("1111111111111111111111111111111111111111");
         let mut data = vec![0u8; 192];
@@ -620,4 +727,176 @@ mod tests {
         assert_eq!(payload.transactions.as_ref().unwrap().len(), 10);
         assert_eq!(payload, expected);
     }
+
+    #[tokio::test]
+    async fn test_prepare_payload_system_config_updates_at_epoch_start() {
+        let cfg = Arc::new(holocene_config(0));
+        let config = SystemConfig {
+            gas_limit: 30_000_000,
+            eip1559_denominator: Some(50),
+            eip1559_elasticity: Some(6),
+            ..Default::default()
+        };
+        let mut fetcher = TestSystemConfigL2Fetcher::default();
+        fetcher.insert(10, config);
+        fetcher.insert(11, config);
+
+        // The L1 block of the next epoch updates the gas limit and the EIP-1559 parameters.
+        let mut provider = TestChainProvider::default();
+        let epoch_1 = Header { number: 1, timestamp: 98, ..Default::default() };
+        let epoch_1_hash = epoch_1.hash_slow();
+        let receipts = vec![
+            config_update_receipt(SystemConfigUpdateKind::GasLimit, 60_000_000),
+            config_update_receipt(SystemConfigUpdateKind::Eip1559, (250 << 32) | 2),
+        ];
+        let epoch_2 = Header {
+            number: 2,
+            timestamp: 102,
+            parent_hash: epoch_1_hash,
+            logs_bloom: logs_bloom(receipts.iter().flat_map(|r| r.logs.iter())),
+            ..Default::default()
+        };
+        let epoch_2_hash = epoch_2.hash_slow();
+        provider.insert_header(epoch_1_hash, epoch_1);
+        provider.insert_header(epoch_2_hash, epoch_2);
+        provider.insert_receipts(epoch_2_hash, receipts);
+        let mut builder = StatefulAttributesBuilder::new(cfg, fetcher, provider);
+
+        // The last block of the previous epoch still uses the parent's system config.
+        let parent = L2BlockInfo {
+            block_info: BlockInfo { number: 10, timestamp: 100, ..Default::default() },
+            l1_origin: BlockNumHash { hash: epoch_1_hash, number: 1 },
+            seq_num: 0,
+        };
+        let epoch = BlockNumHash { hash: epoch_1_hash, number: 1 };
+        let payload = builder.prepare_payload_attributes(parent, epoch).await.unwrap();
+        assert_eq!(payload.gas_limit, Some(30_000_000));
+        assert_eq!(payload.eip_1559_params, eip_1559_params(50, 6));
+
+        // The updates take effect from the first block of the epoch.
+        let parent = L2BlockInfo {
+            block_info: BlockInfo { number: 11, timestamp: 102, ..Default::default() },
+            l1_origin: BlockNumHash { hash: epoch_1_hash, number: 1 },
+            seq_num: 1,
+        };
+        let epoch = BlockNumHash { hash: epoch_2_hash, number: 2 };
+        let payload = builder.prepare_payload_attributes(parent, epoch).await.unwrap();
+        assert_eq!(payload.gas_limit, Some(60_000_000));
+        assert_eq!(payload.eip_1559_params, eip_1559_params(250, 2));
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_skips_receipts_by_logs_bloom() {
+        let cfg = Arc::new(holocene_config(0));
+        let mut fetcher = TestSystemConfigL2Fetcher::default();
+        fetcher.insert(10, SystemConfig::default());
+
+        // The L1 block of the next epoch has an empty logs bloom, and its receipts are unknown to
+        // the provider, so fetching them would fail.
+        let mut provider = TestChainProvider::default();
+        let epoch_1 = Header { number: 1, timestamp: 98, ..Default::default() };
+        let epoch_1_hash = epoch_1.hash_slow();
+        let epoch_2 =
+            Header { number: 2, timestamp: 102, parent_hash: epoch_1_hash, ..Default::default() };
+        let epoch_2_hash = epoch_2.hash_slow();
+        provider.insert_header(epoch_1_hash, epoch_1);
+        provider.insert_header(epoch_2_hash, epoch_2);
+        let mut builder = StatefulAttributesBuilder::new(cfg, fetcher, provider);
+
+        let parent = L2BlockInfo {
+            block_info: BlockInfo { number: 10, timestamp: 102, ..Default::default() },
+            l1_origin: BlockNumHash { hash: epoch_1_hash, number: 1 },
+            seq_num: 1,
+        };
+        let epoch = BlockNumHash { hash: epoch_2_hash, number: 2 };
+        let payload = builder.prepare_payload_attributes(parent, epoch).await.unwrap();
+        assert_eq!(payload.transactions.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_eip_1559_params_at_holocene_activation() {
+        let cfg = Arc::new(holocene_config(102));
+        let config = SystemConfig {
+            eip1559_denominator: Some(250),
+            eip1559_elasticity: Some(2),
+            ..Default::default()
+        };
+        let mut provider = TestChainProvider::default();
+        let header = Header { timestamp: 96, ..Default::default() };
+        let hash = header.hash_slow();
+        provider.insert_header(hash, header);
+        let epoch = BlockNumHash { hash, number: 0 };
+
+        // Before Holocene, no parameters are set. The activation block signals the execution
+        // layer to use the Canyon parameters, and the following blocks use the system config's.
+        let expected = [(98, None), (100, Some(B64::ZERO)), (102, eip_1559_params(250, 2))];
+        for (number, (timestamp, expected)) in expected.into_iter().enumerate() {
+            let mut fetcher = TestSystemConfigL2Fetcher::default();
+            fetcher.insert(number as u64, config);
+            let parent = L2BlockInfo {
+                block_info: BlockInfo { number: number as u64, timestamp, ..Default::default() },
+                l1_origin: epoch,
+                seq_num: number as u64,
+            };
+            let mut builder =
+                StatefulAttributesBuilder::new(cfg.clone(), fetcher, provider.clone());
+            let payload = builder.prepare_payload_attributes(parent, epoch).await.unwrap();
+            assert_eq!(payload.eip_1559_params, expected, "timestamp {}", timestamp + 2);
+        }
+    }
+
+    #[derive(Debug)]
+    struct CustomL1Info;
+
+    impl L1InfoTxOverride for CustomL1Info {
+        fn override_l1_info_tx(
+            &self,
+            inputs: L1InfoTxInputs<'_>,
+            mut tx: TxDeposit,
+        ) -> Result<TxDeposit, BuilderError> {
+            tx.to = alloy_primitives::TxKind::Call(SYSTEM_CONFIG);
+            let mut input = tx.input.to_vec();
+            input.extend_from_slice(&inputs.sequence_number.to_be_bytes());
+            tx.input = input.into();
+            Ok(tx)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_with_l1_info_override() {
+        let cfg = Arc::new(RollupConfig { block_time: 2, ..Default::default() });
+        let fetcher = || {
+            let mut fetcher = TestSystemConfigL2Fetcher::default();
+            fetcher.insert(1, SystemConfig::default());
+            fetcher
+        };
+        let mut provider = TestChainProvider::default();
+        let header = Header { timestamp: 100, ..Default::default() };
+        let hash = header.hash_slow();
+        provider.insert_header(hash, header);
+        let epoch = BlockNumHash { hash, number: 1 };
+        let l2_parent = L2BlockInfo {
+            block_info: BlockInfo { number: 1, timestamp: 100, ..Default::default() },
+            l1_origin: epoch,
+            seq_num: 4,
+        };
+
+        let mut default_builder =
+            StatefulAttributesBuilder::new(cfg.clone(), fetcher(), provider.clone());
+        let default = default_builder.prepare_payload_attributes(l2_parent, epoch).await.unwrap();
+        let mut builder = StatefulAttributesBuilder::new(cfg, fetcher(), provider)
+            .with_l1_info_override(Arc::new(CustomL1Info));
+        let payload = builder.prepare_payload_attributes(l2_parent, epoch).await.unwrap();
+
+        let decode = |payload: &OpPayloadAttributes| {
+            let tx = &payload.transactions.as_ref().unwrap()[0];
+            op_alloy_consensus::OpTxEnvelope::decode_2718(&mut tx.as_ref()).unwrap()
+        };
+        let (default, custom) = (decode(&default), decode(&payload));
+        let (default, custom) = (default.as_deposit().unwrap(), custom.as_deposit().unwrap());
+        assert_eq!(custom.to, alloy_primitives::TxKind::Call(SYSTEM_CONFIG));
+        assert_eq!(custom.input[..default.input.len()], default.input[..]);
+        assert_eq!(custom.input[default.input.len()..], 5u64.to_be_bytes());
+        assert_eq!(custom.source_hash, default.source_hash);
+    }
 }
@@ -12,8 +12,16 @@ extern crate alloc;
 #[macro_use]
 extern crate tracing;
 
+mod adapters;
+pub use adapters::{
+    FixtureBlock, FixtureError, OracleBlobProvider, OracleChainProvider, OracleL2ChainProvider,
+    OracleProviderError, StaticFixture,
+};
+
 mod attributes;
 pub use attributes::StatefulAttributesBuilder;
+#[cfg(feature = "custom-chain")]
+pub use attributes::{L1InfoTxInputs, L1InfoTxOverride};
 
 mod errors;
 pub use errors::{
@@ -29,25 +37,32 @@ pub use pipeline::{
 };
 
 mod sources;
-pub use sources::{BlobData, BlobSource, CalldataSource, EthereumDataSource};
+pub use sources::{
+    BatcherTxObserver, BatcherTxRejection, BlobData, BlobSource, CalldataSource,
+    EthereumDataSource, RejectedBatcherTx,
+};
 
 mod stages;
 pub use stages::{
-    AttributesQueue, BatchProvider, BatchQueue, BatchStream, BatchStreamProvider, BatchValidator,
-    ChannelAssembler, ChannelBank, ChannelProvider, ChannelReader, ChannelReaderProvider,
-    FrameQueue, FrameQueueProvider, IndexedTraversal, L1Retrieval, L1RetrievalProvider,
-    NextBatchProvider, NextFrameProvider, PollingTraversal, TraversalStage,
+    AttributesQueue, BatchObserver, BatchProvider, BatchQueue, BatchStream, BatchStreamProvider,
+    BatchValidator, ChannelAssembler, ChannelBank, ChannelBankEvictionPolicy, ChannelProvider,
+    ChannelReader, ChannelReaderProvider, FrameQueue, FrameQueueProvider, IndexedTraversal,
+    L1Retrieval, L1RetrievalProvider, NextBatchProvider, NextFrameProvider, PollingTraversal,
+    TraversalStage,
 };
 
 mod traits;
 pub use traits::{
-    AttributesBuilder, AttributesProvider, BatchValidationProviderDerive, BlobProvider,
-    ChainProvider, DataAvailabilityProvider, L2ChainProvider, NextAttributes, OriginAdvancer,
-    OriginProvider, Pipeline, ResetProvider, SignalReceiver,
+    AttributesBuilder, AttributesProvider, BatchValidationProviderDerive, BlobOracle, BlobProvider,
+    ChainOracle, ChainProvider, DataAvailabilityProvider, L2ChainOracle, L2ChainProvider,
+    NextAttributes, OriginAdvancer, OriginProvider, Pipeline, ResetProvider, SignalReceiver,
 };
 
 mod types;
-pub use types::{ActivationSignal, PipelineResult, ResetSignal, Signal, StepResult};
+pub use types::{
+    ActivationSignal, DerivationSnapshot, LogFilter, PipelineResult, PipelineStateSize,
+    ResetSignal, Signal, StepResult,
+};
 
 mod metrics;
 pub use metrics::Metrics;
@@ -1,5 +1,8 @@
 //! Metrics for the derivation pipeline.
 
+#[cfg(feature = "metrics")]
+use crate::BatcherTxRejection;
+
 /// Container for metrics.
 #[derive(Debug, Clone)]
 pub struct Metrics;
@@ -71,6 +74,10 @@ impl Metrics {
     /// Identifier for the data availability provider data.
     pub const PIPELINE_DATA_AVAILABILITY_PROVIDER: &str = "kona_derive_dap_sources";
 
+    /// Identifier for the gauge that counts the candidate batcher transactions rejected by the data
+    /// sources, by reason.
+    pub const PIPELINE_BATCHER_TX_REJECTED: &str = "kona_derive_batcher_tx_rejected";
+
     /// Identifier for a gauge that tracks batch validity.
     pub const PIPELINE_BATCH_VALIDITY: &str = "kona_derive_batch_validity";
 
@@ -101,6 +108,12 @@ impl Metrics {
     /// Gauge that tracks the latest decompressed batch type.
     pub const PIPELINE_LATEST_DECOMPRESSED_BATCH_TYPE: &str =
         "kona_derive_latest_decompressed_batch_type";
+
+    /// Gauge that tracks the total number of bytes decompressed from channels.
+    pub const PIPELINE_DECOMPRESSED_BYTES: &str = "kona_derive_decompressed_bytes";
+
+    /// Identifier for the histogram that tracks the time it takes to decompress a channel.
+    pub const PIPELINE_DECOMPRESSION_DURATION: &str = "kona_derive_decompression_duration";
 }
 
 impl Metrics {
@@ -134,6 +147,14 @@ impl Metrics {
             Self::PIPELINE_LATEST_DECOMPRESSED_BATCH_TYPE,
             "The latest decompressed batch type"
         );
+        metrics::describe_gauge!(
+            Self::PIPELINE_DECOMPRESSED_BYTES,
+            "The total number of bytes decompressed from channels"
+        );
+        metrics::describe_histogram!(
+            Self::PIPELINE_DECOMPRESSION_DURATION,
+            "The time it takes to decompress a channel"
+        );
         metrics::describe_gauge!(
             Self::PIPELINE_ORIGIN,
             "The block height of the pipeline l1 origin"
@@ -146,6 +167,10 @@ impl Metrics {
             Self::PIPELINE_DATA_AVAILABILITY_PROVIDER,
             "The source of pipeline data"
         );
+        metrics::describe_gauge!(
+            Self::PIPELINE_BATCHER_TX_REJECTED,
+            "The number of candidate batcher transactions rejected, by reason"
+        );
         metrics::describe_gauge!(
             Self::PIPELINE_DERIVED_SPAN_SIZE,
             "The number of payload attributes in the current span"
@@ -244,6 +269,17 @@ impl Metrics {
             0
         );
 
+        // No batcher transactions are initially rejected.
+        for reason in [BatcherTxRejection::SignerMismatch, BatcherTxRejection::WrongInbox] {
+            kona_macros::set!(
+                gauge,
+                Self::PIPELINE_BATCHER_TX_REJECTED,
+                "reason",
+                reason.as_str(),
+                0
+            );
+        }
+
         // Manually translate a value of `0` for sys config update as no update yet.
         kona_macros::set!(gauge, Self::PIPELINE_LATEST_SYS_CONFIG_UPDATE, 0);
         kona_macros::set!(gauge, Self::PIPELINE_SYS_CONFIG_UPDATE_ERROR, 0);
@@ -260,6 +296,7 @@ impl Metrics {
         // Cumulative counters start at zero.
         kona_macros::set!(gauge, Self::PIPELINE_STEPS, 0);
         kona_macros::set!(gauge, Self::PIPELINE_PREPARED_ATTRIBUTES, 0);
+        kona_macros::set!(gauge, Self::PIPELINE_DECOMPRESSED_BYTES, 0);
 
         // All buffers can be zeroed out since they are expected to return to zero.
         kona_macros::set!(gauge, Self::PIPELINE_BATCH_BUFFER, 0);
@@ -1,15 +1,15 @@
 //! Contains the `PipelineBuilder` object that is used to build a `DerivationPipeline`.
 
 use crate::{
-    AttributesBuilder, AttributesQueue, BatchProvider, BatchStream, ChainProvider, ChannelProvider,
-    ChannelReader, DataAvailabilityProvider, DerivationPipeline, FrameQueue,
-    IndexedAttributesQueueStage, IndexedTraversal, L1Retrieval, L2ChainProvider,
-    PolledAttributesQueueStage, PollingTraversal,
+    AttributesBuilder, AttributesQueue, BatchObserver, BatchProvider, BatchStream, ChainProvider,
+    ChannelBankEvictionPolicy, ChannelProvider, ChannelReader, DataAvailabilityProvider,
+    DerivationPipeline, FrameQueue, IndexedAttributesQueueStage, IndexedTraversal, L1Retrieval,
+    L2ChainProvider, PolledAttributesQueueStage, PollingTraversal,
 };
 use alloc::sync::Arc;
 use core::fmt::Debug;
 use kona_genesis::RollupConfig;
-use kona_protocol::BlockInfo;
+use kona_protocol::{BlockInfo, MemoryAudit};
 
 /// The `PipelineBuilder` constructs a [`DerivationPipeline`] using a builder pattern.
 #[derive(Debug)]
@@ -26,6 +26,9 @@ where
     builder: Option<B>,
     origin: Option<BlockInfo>,
     rollup_config: Option<Arc<RollupConfig>>,
+    memory_audit: Option<MemoryAudit>,
+    channel_bank_eviction: ChannelBankEvictionPolicy,
+    batch_observer: Option<Arc<dyn BatchObserver>>,
 }
 
 impl<B, P, T, D> Default for PipelineBuilder<B, P, T, D>
@@ -43,6 +46,9 @@ where
             builder: None,
             origin: None,
             rollup_config: None,
+            memory_audit: None,
+            channel_bank_eviction: ChannelBankEvictionPolicy::default(),
+            batch_observer: None,
         }
     }
 }
@@ -95,6 +101,24 @@ where
         self
     }
 
+    /// Sets the [`MemoryAudit`] the channel bank and the attributes queue record their size into.
+    pub fn memory_audit(mut self, memory_audit: MemoryAudit) -> Self {
+        self.memory_audit = Some(memory_audit);
+        self
+    }
+
+    /// Sets the order in which the channel bank evicts its channels before Holocene.
+    pub const fn channel_bank_eviction(mut self, policy: ChannelBankEvictionPolicy) -> Self {
+        self.channel_bank_eviction = policy;
+        self
+    }
+
+    /// Sets the [`BatchObserver`] the batches read from the channels are reported to.
+    pub fn batch_observer(mut self, observer: Arc<dyn BatchObserver>) -> Self {
+        self.batch_observer = Some(observer);
+        self
+    }
+
     /// Builds a derivation pipeline with the [`PolledAttributesQueueStage`].
     pub fn build_polled(self) -> DerivationPipeline<PolledAttributesQueueStage<D, P, T, B>, T> {
         self.into()
@@ -127,14 +151,24 @@ where
         l1_traversal.block = Some(builder.origin.expect("origin must be set"));
         let l1_retrieval = L1Retrieval::new(l1_traversal, dap_source);
         let frame_queue = FrameQueue::new(l1_retrieval, Arc::clone(&rollup_config));
-        let channel_provider = ChannelProvider::new(Arc::clone(&rollup_config), frame_queue);
-        let channel_reader = ChannelReader::new(channel_provider, Arc::clone(&rollup_config));
+        let mut channel_provider = ChannelProvider::new(Arc::clone(&rollup_config), frame_queue)
+            .with_eviction_policy(builder.channel_bank_eviction);
+        if let Some(audit) = builder.memory_audit.clone() {
+            channel_provider = channel_provider.with_memory_audit(audit);
+        }
+        let mut channel_reader = ChannelReader::new(channel_provider, Arc::clone(&rollup_config));
+        if let Some(observer) = builder.batch_observer {
+            channel_reader = channel_reader.with_observer(observer);
+        }
         let batch_stream =
             BatchStream::new(channel_reader, rollup_config.clone(), l2_chain_provider.clone());
         let batch_provider =
             BatchProvider::new(rollup_config.clone(), batch_stream, l2_chain_provider.clone());
-        let attributes =
+        let mut attributes =
             AttributesQueue::new(rollup_config.clone(), batch_provider, attributes_builder);
+        if let Some(audit) = builder.memory_audit {
+            attributes = attributes.with_memory_audit(audit);
+        }
 
         // Create the pipeline.
         Self::new(attributes, rollup_config, l2_chain_provider)
@@ -162,14 +196,24 @@ where
         l1_traversal.block = Some(builder.origin.expect("origin must be set"));
         let l1_retrieval = L1Retrieval::new(l1_traversal, dap_source);
         let frame_queue = FrameQueue::new(l1_retrieval, Arc::clone(&rollup_config));
-        let channel_provider = ChannelProvider::new(Arc::clone(&rollup_config), frame_queue);
-        let channel_reader = ChannelReader::new(channel_provider, Arc::clone(&rollup_config));
+        let mut channel_provider = ChannelProvider::new(Arc::clone(&rollup_config), frame_queue)
+            .with_eviction_policy(builder.channel_bank_eviction);
+        if let Some(audit) = builder.memory_audit.clone() {
+            channel_provider = channel_provider.with_memory_audit(audit);
+        }
+        let mut channel_reader = ChannelReader::new(channel_provider, Arc::clone(&rollup_config));
+        if let Some(observer) = builder.batch_observer {
+            channel_reader = channel_reader.with_observer(observer);
+        }
         let batch_stream =
             BatchStream::new(channel_reader, rollup_config.clone(), l2_chain_provider.clone());
         let batch_provider =
             BatchProvider::new(rollup_config.clone(), batch_stream, l2_chain_provider.clone());
-        let attributes =
+        let mut attributes =
             AttributesQueue::new(rollup_config.clone(), batch_provider, attributes_builder);
+        if let Some(audit) = builder.memory_audit {
+            attributes = attributes.with_memory_audit(audit);
+        }
 
         // Create the pipeline.
         Self::new(attributes, rollup_config, l2_chain_provider)
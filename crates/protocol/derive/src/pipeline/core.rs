@@ -2,8 +2,8 @@
 
 use crate::{
     ActivationSignal, L2ChainProvider, NextAttributes, OriginAdvancer, OriginProvider, Pipeline,
-    PipelineError, PipelineErrorKind, PipelineResult, ResetSignal, Signal, SignalReceiver,
-    StepResult,
+    PipelineError, PipelineErrorKind, PipelineResult, PipelineStateSize, ResetSignal, Signal,
+    SignalReceiver, StepResult,
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use async_trait::async_trait;
@@ -119,7 +119,7 @@ where
             Signal::FlushChannel => {
                 self.attributes.signal(signal).await?;
             }
-            Signal::ProvideBlock(_) => {
+            Signal::ProvideBlock(_) | Signal::Prune(_) => {
                 self.attributes.signal(signal).await?;
             }
         }
@@ -138,6 +138,11 @@ where
     S: NextAttributes + SignalReceiver + OriginProvider + OriginAdvancer + Debug + Send + Sync,
     P: L2ChainProvider + Send + Sync + Debug,
 {
+    /// Returns the [`PipelineStateSize`] of the stages of the pipeline.
+    fn state_size(&self) -> PipelineStateSize {
+        self.attributes.state_size()
+    }
+
     /// Peeks at the next prepared [`OpAttributesWithParent`] from the pipeline.
     fn peek(&self) -> Option<&OpAttributesWithParent> {
         self.prepared.front()
@@ -0,0 +1,171 @@
+//! Authentication of candidate batcher transactions.
+//!
+//! A batcher transaction is only accepted if it was sent to the batch inbox by the batcher. Any
+//! other candidate is silently dropped by the data sources, so a misconfigured batcher or rollup
+//! config would leave the pipeline waiting for batches that never come. Rejected candidates are
+//! therefore logged, counted by reason, and reported to the [`BatcherTxObserver`], if any.
+
+use alloc::{fmt::Debug, sync::Arc};
+use alloy_consensus::{TxEnvelope, transaction::SignerRecoverable};
+use alloy_primitives::{Address, B256};
+use core::fmt::{self, Display};
+use kona_protocol::BlockInfo;
+
+/// The reason a candidate batcher transaction was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BatcherTxRejection {
+    /// The transaction was sent to the batch inbox, but not by the batcher.
+    SignerMismatch,
+    /// The transaction was sent by the batcher, but not to the batch inbox.
+    WrongInbox,
+}
+
+impl BatcherTxRejection {
+    /// Returns the label of the reason, as used in metrics and logs.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::SignerMismatch => "signer_mismatch",
+            Self::WrongInbox => "wrong_inbox",
+        }
+    }
+}
+
+impl Display for BatcherTxRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A candidate batcher transaction rejected by a data source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedBatcherTx {
+    /// The L1 block the transaction was included in.
+    pub block: BlockInfo,
+    /// The hash of the transaction.
+    pub tx_hash: B256,
+    /// The reason the transaction was rejected.
+    pub reason: BatcherTxRejection,
+    /// The signer of the transaction, if it could be recovered.
+    pub from: Option<Address>,
+    /// The recipient of the transaction.
+    pub to: Address,
+    /// The batch inbox address the transaction was checked against.
+    pub inbox: Address,
+    /// The batcher address the transaction was checked against.
+    pub batcher: Address,
+}
+
+/// An observer of the candidate batcher transactions rejected by the data sources.
+///
+/// Setting an observer on a data source also enables the detection of
+/// [`BatcherTxRejection::WrongInbox`], which takes recovering the signer of every transaction of
+/// an L1 block rather than only of those sent to the batch inbox.
+pub trait BatcherTxObserver: Debug + Send + Sync {
+    /// Called for each rejected candidate batcher transaction.
+    fn rejected(&self, tx: &RejectedBatcherTx);
+}
+
+/// Returns `true` if the given transaction, sent to `to` in the given L1 block, was sent to the
+/// batch inbox by the batcher. Rejected candidates are logged, counted and reported to the
+/// observer.
+pub(crate) fn authenticate(
+    tx: &TxEnvelope,
+    to: Address,
+    block: &BlockInfo,
+    inbox: Address,
+    batcher: Address,
+    observer: Option<&Arc<dyn BatcherTxObserver>>,
+) -> bool {
+    let (reason, from) = if to == inbox {
+        let from = tx.recover_signer().ok();
+        if from == Some(batcher) {
+            return true;
+        }
+        (BatcherTxRejection::SignerMismatch, from)
+    } else {
+        // Only the observed sources pay for recovering the signer of every transaction.
+        if observer.is_none() {
+            return false;
+        }
+        let from = tx.recover_signer().ok();
+        if from != Some(batcher) {
+            return false;
+        }
+        (BatcherTxRejection::WrongInbox, from)
+    };
+
+    let rejected = RejectedBatcherTx {
+        block: *block,
+        tx_hash: *tx.tx_hash(),
+        reason,
+        from,
+        to,
+        inbox,
+        batcher,
+    };
+    warn!(
+        target: "batcher_auth",
+        block = rejected.block.number,
+        tx = %rejected.tx_hash,
+        %reason,
+        from = ?rejected.from,
+        to = %rejected.to,
+        inbox = %rejected.inbox,
+        batcher = %rejected.batcher,
+        "Rejected candidate batcher transaction"
+    );
+    kona_macros::inc!(
+        gauge,
+        crate::metrics::Metrics::PIPELINE_BATCHER_TX_REJECTED,
+        "reason" => reason.as_str()
+    );
+    if let Some(observer) = observer {
+        observer.rejected(&rejected);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::calldata::tests::test_legacy_tx;
+    use alloc::vec::Vec;
+    use alloy_primitives::address;
+    use spin::Mutex;
+
+    const INBOX: Address = address!("0123456789012345678901234567890123456789");
+
+    #[derive(Debug, Default)]
+    struct RecordingObserver(Mutex<Vec<RejectedBatcherTx>>);
+
+    impl BatcherTxObserver for RecordingObserver {
+        fn rejected(&self, tx: &RejectedBatcherTx) {
+            self.0.lock().push(tx.clone());
+        }
+    }
+
+    #[test]
+    fn test_authenticate_rejections() {
+        let recording = Arc::new(RecordingObserver::default());
+        let observer: Arc<dyn BatcherTxObserver> = recording.clone();
+        let block = BlockInfo { number: 7, ..Default::default() };
+        let tx = test_legacy_tx(INBOX);
+        let batcher = tx.recover_signer().unwrap();
+
+        // Sent to the inbox by the batcher.
+        assert!(authenticate(&tx, INBOX, &block, INBOX, batcher, Some(&observer)));
+        // Sent to the inbox by someone else.
+        assert!(!authenticate(&tx, INBOX, &block, INBOX, Address::ZERO, Some(&observer)));
+        // Sent by the batcher to another address, only detected when observed.
+        assert!(!authenticate(&tx, INBOX, &block, Address::ZERO, batcher, None));
+        assert!(!authenticate(&tx, INBOX, &block, Address::ZERO, batcher, Some(&observer)));
+
+        let rejected = recording.0.lock();
+        let reasons: Vec<_> = rejected.iter().map(|tx| tx.reason).collect();
+        assert_eq!(reasons, [BatcherTxRejection::SignerMismatch, BatcherTxRejection::WrongInbox]);
+        assert_eq!(rejected[0].from, Some(batcher));
+        assert_eq!(rejected[0].block.number, 7);
+        assert_eq!(rejected[1].to, INBOX);
+        assert_eq!(rejected[1].inbox, Address::ZERO);
+    }
+}
@@ -1,4 +1,7 @@
 //! Contains the `BlobData` struct.
+//!
+//! Decoding relies on the compiler to vectorize its fixed-size copies and zero checks, rather than
+//! on explicit SIMD intrinsics, which would need `unsafe`, per-target code in this `no_std` crate.
 
 use crate::BlobDecodingError;
 use alloc::{boxed::Box, vec};
@@ -14,6 +17,49 @@ pub(crate) const BLOB_MAX_DATA_SIZE: usize = (4 * 31 + 3) * 1024 - 4; // 130044
 /// Blob Encoding/Decoding Rounds
 pub(crate) const BLOB_ENCODING_ROUNDS: usize = 1024;
 
+/// The number of input bytes decoded in each round, i.e. 4 field elements.
+const ROUND_INPUT_SIZE: usize = 128;
+
+/// The number of output bytes decoded in each round after the first one.
+const ROUND_OUTPUT_SIZE: usize = 127;
+
+/// Decodes a round of 4 field elements into 127 bytes of output: the lower 31 bytes of each field
+/// element, and the 3 bytes reassembled from the 6 low order bits of their high order bytes.
+/// Returns a [`BlobDecodingError`] if a field element has either of its two high order bits set.
+///
+/// This is equivalent to 4 calls to [`BlobData::decode_field_element`] followed by
+/// [`BlobData::reassemble_bytes`], on fixed-size buffers.
+#[inline]
+fn decode_round(
+    input: &[u8; ROUND_INPUT_SIZE],
+    output: &mut [u8; ROUND_OUTPUT_SIZE],
+) -> Result<(), BlobDecodingError> {
+    let encoded_byte = [input[0], input[32], input[64], input[96]];
+    if (encoded_byte[0] | encoded_byte[1] | encoded_byte[2] | encoded_byte[3]) & 0b1100_0000 != 0 {
+        return Err(BlobDecodingError::InvalidFieldElement);
+    }
+
+    output[0..31].copy_from_slice(&input[1..32]);
+    output[32..63].copy_from_slice(&input[33..64]);
+    output[64..95].copy_from_slice(&input[65..96]);
+    output[96..127].copy_from_slice(&input[97..128]);
+
+    output[31] = (encoded_byte[0] & 0b0011_1111) | ((encoded_byte[1] & 0b0011_0000) << 2);
+    output[63] = (encoded_byte[1] & 0b0000_1111) | ((encoded_byte[3] & 0b0000_1111) << 4);
+    output[95] = (encoded_byte[2] & 0b0011_1111) | ((encoded_byte[3] & 0b0011_0000) << 2);
+    Ok(())
+}
+
+/// Returns `true` if all the given bytes are zero. The bytes are folded 16 at a time, which the
+/// compiler vectorizes.
+fn is_zero(bytes: &[u8]) -> bool {
+    let mut chunks = bytes.chunks_exact(16);
+    let folded = chunks
+        .by_ref()
+        .fold(0u128, |acc, chunk| acc | u128::from_ne_bytes(chunk.try_into().expect("16 bytes")));
+    folded == 0 && chunks.remainder().iter().all(|b| *b == 0)
+}
+
 /// The Blob Data
 #[derive(Default, Clone, Debug)]
 pub struct BlobData {
@@ -26,6 +72,10 @@ pub struct BlobData {
 impl BlobData {
     /// Decodes the blob into raw byte data.
     /// Returns a [`BlobDecodingError`] if the blob is invalid.
+    ///
+    /// The output is only allocated up to the round that holds the last byte of data, rather than
+    /// for the maximum data size, and every round after the first one is decoded from a fixed-size
+    /// window of the blob, which lets the compiler elide bounds checks and vectorize the copies.
     pub(crate) fn decode(&self) -> Result<Bytes, BlobDecodingError> {
         let data = self.data.as_ref().ok_or(BlobDecodingError::MissingData)?;
 
@@ -42,8 +92,11 @@ impl BlobData {
             return Err(BlobDecodingError::InvalidLength);
         }
 
+        // Only the rounds up to the one that holds the last byte of data are decoded, and a round
+        // decodes at most `ROUND_OUTPUT_SIZE` bytes past the start of its output.
+        let mut output = vec![0u8; (length + ROUND_OUTPUT_SIZE).min(BLOB_MAX_DATA_SIZE)];
+
         // Round 0 copies the remaining 27 bytes of the first field element
-        let mut output = vec![0u8; BLOB_MAX_DATA_SIZE];
         output[0..27].copy_from_slice(&data[5..32]);
 
         // Process the remaining 3 field elements to complete round 0
@@ -71,29 +124,29 @@ impl BlobData {
                 break;
             }
 
-            for d in &mut encoded_byte {
-                let (enc, opos, ipos) =
-                    self.decode_field_element(output_pos, input_pos, &mut output)?;
-                *d = enc;
-                output_pos = opos;
-                input_pos = ipos;
-            }
-            output_pos = self.reassemble_bytes(output_pos, &encoded_byte, &mut output);
+            let input = data
+                .get(input_pos..input_pos + ROUND_INPUT_SIZE)
+                .and_then(|input| input.try_into().ok())
+                .ok_or(BlobDecodingError::InvalidLength)?;
+            let round = (&mut output[output_pos..output_pos + ROUND_OUTPUT_SIZE])
+                .try_into()
+                .expect("output is allocated for whole rounds");
+            decode_round(input, round)?;
+            output_pos += ROUND_OUTPUT_SIZE;
+            input_pos += ROUND_INPUT_SIZE;
         }
 
         // Validate the remaining bytes
-        for o in output.iter().skip(length) {
-            if *o != 0u8 {
-                return Err(BlobDecodingError::InvalidFieldElement);
-            }
+        if !is_zero(&output[length..]) {
+            return Err(BlobDecodingError::InvalidFieldElement);
         }
 
         // Validate the remaining bytes
         output.truncate(length);
-        for i in input_pos..BYTES_PER_BLOB {
-            if data[i] != 0 {
-                return Err(BlobDecodingError::InvalidFieldElement);
-            }
+        let remaining =
+            data.get(input_pos..BYTES_PER_BLOB).ok_or(BlobDecodingError::InvalidLength)?;
+        if !is_zero(remaining) {
+            return Err(BlobDecodingError::InvalidFieldElement);
         }
 
         Ok(Bytes::from(output))
@@ -169,6 +222,74 @@ impl BlobData {
 mod tests {
     use super::*;
 
+    /// Returns a blob whose field elements carry arbitrary data, and whose length ends at the end
+    /// of the given round.
+    fn encoded_blob(rounds: usize) -> Vec<u8> {
+        let mut data = vec![0u8; BYTES_PER_BLOB];
+        for (i, byte) in data[..rounds * ROUND_INPUT_SIZE].iter_mut().enumerate() {
+            *byte = if i % 32 == 0 { (i / 32) as u8 & 0b0011_1111 } else { (i * 7) as u8 };
+        }
+        let length = (rounds * ROUND_OUTPUT_SIZE - 4) as u32;
+        data[VERSIONED_HASH_VERSION_KZG as usize] = BLOB_ENCODING_VERSION;
+        data[2..5].copy_from_slice(&length.to_be_bytes()[1..]);
+        data
+    }
+
+    /// Decodes the blob with [`BlobData::decode_field_element`] and [`BlobData::reassemble_bytes`]
+    /// only.
+    fn decode_reference(blob_data: &BlobData, length: usize) -> Vec<u8> {
+        let data = blob_data.data.as_ref().unwrap();
+        let mut output = vec![0u8; BLOB_MAX_DATA_SIZE];
+        output[0..27].copy_from_slice(&data[5..32]);
+        let (mut output_pos, mut input_pos) = (28, 32);
+        let mut encoded_byte = [data[0], 0, 0, 0];
+        for round in 0..BLOB_ENCODING_ROUNDS {
+            if round > 0 && output_pos >= length {
+                break;
+            }
+            for b in encoded_byte.iter_mut().skip(usize::from(round == 0)) {
+                let (enc, opos, ipos) =
+                    blob_data.decode_field_element(output_pos, input_pos, &mut output).unwrap();
+                (*b, output_pos, input_pos) = (enc, opos, ipos);
+            }
+            output_pos = blob_data.reassemble_bytes(output_pos, &encoded_byte, &mut output);
+        }
+        output.truncate(length);
+        output
+    }
+
+    #[test]
+    fn test_decode_matches_reference() {
+        for rounds in [1, 2, 255, BLOB_ENCODING_ROUNDS] {
+            let data = encoded_blob(rounds);
+            let length = rounds * ROUND_OUTPUT_SIZE - 4;
+            let blob_data = BlobData { data: Some(Bytes::from(data)), ..Default::default() };
+            let decoded = blob_data.decode().unwrap();
+            assert_eq!(decoded.len(), length);
+            assert_eq!(decoded.as_ref(), decode_reference(&blob_data, length).as_slice());
+        }
+    }
+
+    #[test]
+    fn test_decode_round_invalid_field_element() {
+        let mut data = encoded_blob(3);
+        data[2 * ROUND_INPUT_SIZE + 64] = 0b1000_0000;
+        let blob_data = BlobData { data: Some(Bytes::from(data)), ..Default::default() };
+        assert_eq!(blob_data.decode(), Err(BlobDecodingError::InvalidFieldElement));
+    }
+
+    #[test]
+    fn test_is_zero() {
+        assert!(is_zero(&[]));
+        assert!(is_zero(&[0u8; 37]));
+        let mut bytes = [0u8; 37];
+        bytes[36] = 1;
+        assert!(!is_zero(&bytes));
+        bytes[36] = 0;
+        bytes[3] = 1;
+        assert!(!is_zero(&bytes));
+    }
+
     #[test]
     fn test_reassemble_bytes() {
         let blob_data = BlobData::default();
@@ -1,13 +1,11 @@
 //! Blob Data Source
 
 use crate::{
-    BlobData, BlobProvider, BlobProviderError, ChainProvider, DataAvailabilityProvider,
-    PipelineError, PipelineResult,
-};
-use alloc::{boxed::Box, string::ToString, vec::Vec};
-use alloy_consensus::{
-    Transaction, TxEip4844Variant, TxEnvelope, TxType, transaction::SignerRecoverable,
+    BatcherTxObserver, BlobData, BlobProvider, BlobProviderError, ChainProvider,
+    DataAvailabilityProvider, PipelineError, PipelineResult, sources::authenticate,
 };
+use alloc::{boxed::Box, string::ToString, sync::Arc, vec::Vec};
+use alloy_consensus::{Transaction, TxEip4844Variant, TxEnvelope, TxType};
 use alloy_eips::eip4844::IndexedBlobHash;
 use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
@@ -30,6 +28,8 @@ where
     pub data: Vec<BlobData>,
     /// Whether the source is open.
     pub open: bool,
+    /// The observer of the rejected candidate batcher transactions, if any.
+    pub observer: Option<Arc<dyn BatcherTxObserver>>,
 }
 
 impl<F, B> BlobSource<F, B>
@@ -39,11 +39,25 @@ where
 {
     /// Creates a new blob source.
     pub const fn new(chain_provider: F, blob_fetcher: B, batcher_address: Address) -> Self {
-        Self { chain_provider, blob_fetcher, batcher_address, data: Vec::new(), open: false }
+        Self {
+            chain_provider,
+            blob_fetcher,
+            batcher_address,
+            data: Vec::new(),
+            open: false,
+            observer: None,
+        }
+    }
+
+    /// Reports the rejected candidate batcher transactions to the given observer.
+    pub fn with_observer(mut self, observer: Arc<dyn BatcherTxObserver>) -> Self {
+        self.observer = Some(observer);
+        self
     }
 
     fn extract_blob_data(
         &self,
+        block_ref: &BlockInfo,
         txs: Vec<TxEnvelope>,
         batcher_address: Address,
     ) -> (Vec<BlobData>, Vec<IndexedBlobHash>) {
@@ -68,11 +82,14 @@ where
             };
             let Some(to) = tx_kind else { continue };
 
-            if to != self.batcher_address {
-                index += blob_hashes.map_or(0, |h| h.len() as u64);
-                continue;
-            }
-            if tx.recover_signer().unwrap_or_default() != batcher_address {
+            if !authenticate(
+                &tx,
+                to,
+                block_ref,
+                self.batcher_address,
+                batcher_address,
+                self.observer.as_ref(),
+            ) {
                 index += blob_hashes.map_or(0, |h| h.len() as u64);
                 continue;
             }
@@ -128,7 +145,7 @@ where
             .await
             .map_err(|e| BlobProviderError::Backend(e.to_string()))?;
 
-        let (mut data, blob_hashes) = self.extract_blob_data(info.1, batcher_address);
+        let (mut data, blob_hashes) = self.extract_blob_data(block_ref, info.1, batcher_address);
 
         // If there are no hashes, set the calldata and return.
         if blob_hashes.is_empty() {
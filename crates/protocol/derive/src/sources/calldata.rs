@@ -1,8 +1,11 @@
 //! CallData Source
 
-use crate::{ChainProvider, DataAvailabilityProvider, PipelineError, PipelineResult};
-use alloc::{boxed::Box, collections::VecDeque};
-use alloy_consensus::{Transaction, TxEnvelope, transaction::SignerRecoverable};
+use crate::{
+    BatcherTxObserver, ChainProvider, DataAvailabilityProvider, PipelineError, PipelineResult,
+    sources::authenticate,
+};
+use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
+use alloy_consensus::{Transaction, TxEnvelope};
 use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
 use kona_protocol::BlockInfo;
@@ -21,12 +24,26 @@ where
     pub calldata: VecDeque<Bytes>,
     /// Whether the calldata source is open.
     pub open: bool,
+    /// The observer of the rejected candidate batcher transactions, if any.
+    pub observer: Option<Arc<dyn BatcherTxObserver>>,
 }
 
 impl<CP: ChainProvider + Send> CalldataSource<CP> {
     /// Creates a new calldata source.
     pub const fn new(chain_provider: CP, batch_inbox_address: Address) -> Self {
-        Self { chain_provider, batch_inbox_address, calldata: VecDeque::new(), open: false }
+        Self {
+            chain_provider,
+            batch_inbox_address,
+            calldata: VecDeque::new(),
+            open: false,
+            observer: None,
+        }
+    }
+
+    /// Reports the rejected candidate batcher transactions to the given observer.
+    pub fn with_observer(mut self, observer: Arc<dyn BatcherTxObserver>) -> Self {
+        self.observer = Some(observer);
+        self
     }
 
     /// Loads the calldata into the source if it is not open.
@@ -53,13 +70,15 @@ impl<CP: ChainProvider + Send> CalldataSource<CP> {
                 };
                 let to = tx_kind?;
 
-                if to != self.batch_inbox_address {
-                    return None;
-                }
-                if tx.recover_signer().ok()? != batcher_address {
-                    return None;
-                }
-                Some(data.to_vec().into())
+                authenticate(
+                    tx,
+                    to,
+                    block_ref,
+                    self.batch_inbox_address,
+                    batcher_address,
+                    self.observer.as_ref(),
+                )
+                .then(|| data.to_vec().into())
             })
             .collect::<VecDeque<_>>();
 
@@ -96,11 +115,14 @@ impl<CP: ChainProvider + Send> DataAvailabilityProvider for CalldataSource<CP> {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use crate::{errors::PipelineErrorKind, test_utils::TestChainProvider};
     use alloc::{vec, vec::Vec};
-    use alloy_consensus::{Signed, TxEip2930, TxEip4844, TxEip4844Variant, TxEip7702, TxLegacy};
+    use alloy_consensus::{
+        Signed, TxEip2930, TxEip4844, TxEip4844Variant, TxEip7702, TxLegacy,
+        transaction::SignerRecoverable,
+    };
     use alloy_primitives::{Address, Signature, TxKind, address};
 
     pub(crate) fn test_legacy_tx(to: Address) -> TxEnvelope {
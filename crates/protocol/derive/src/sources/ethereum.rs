@@ -2,10 +2,10 @@
 //! [DataAvailabilityProvider] trait for the Ethereum protocol.
 
 use crate::{
-    BlobProvider, BlobSource, CalldataSource, ChainProvider, DataAvailabilityProvider,
-    PipelineResult,
+    BatcherTxObserver, BlobProvider, BlobSource, CalldataSource, ChainProvider,
+    DataAvailabilityProvider, PipelineResult,
 };
-use alloc::{boxed::Box, fmt::Debug};
+use alloc::{boxed::Box, fmt::Debug, sync::Arc};
 use alloy_primitives::{Address, Bytes};
 use async_trait::async_trait;
 use kona_genesis::RollupConfig;
@@ -48,6 +48,13 @@ where
             calldata_source: CalldataSource::new(provider, cfg.batch_inbox_address),
         }
     }
+
+    /// Reports the candidate batcher transactions rejected by both sources to the given observer.
+    pub fn with_observer(mut self, observer: Arc<dyn BatcherTxObserver>) -> Self {
+        self.blob_source.observer = Some(observer.clone());
+        self.calldata_source.observer = Some(observer);
+        self
+    }
 }
 
 #[async_trait]
@@ -159,4 +166,30 @@ mod tests {
         let calldata_batch = data_source.next(&block_ref, batcher_address).await.unwrap();
         assert_eq!(calldata_batch.len(), 119823);
     }
+
+    #[tokio::test]
+    async fn test_open_ethereum_calldata_source_without_blob_provider() {
+        let mut chain = TestChainProvider::default();
+        let batcher_address = address!("6887246668a3b87F54DeB3b94Ba47a6f63F32985");
+        let batch_inbox = address!("FF00000000000000000000000000000000000010");
+        let block_ref = BlockInfo { number: 10, ..Default::default() };
+
+        let mut cfg = RollupConfig::default();
+        cfg.genesis.system_config = Some(SystemConfig { batcher_address, ..Default::default() });
+        cfg.batch_inbox_address = batch_inbox;
+        assert!(!cfg.blobs_enabled());
+
+        let raw_batcher_tx = include_bytes!("../../testdata/raw_batcher_tx.hex");
+        let tx = TxEnvelope::decode_2718(&mut raw_batcher_tx.as_ref()).unwrap();
+        chain.insert_block_with_transactions(10, block_ref, vec![tx]);
+
+        // Chains without blobs are derived from calldata, without a blob provider.
+        let mut data_source =
+            EthereumDataSource::new_from_parts(chain, None::<TestBlobProvider>, &cfg);
+        let calldata_batch = data_source.next(&block_ref, batcher_address).await.unwrap();
+        assert_eq!(calldata_batch.len(), 119823);
+
+        let mut blobs = None::<TestBlobProvider>;
+        assert!(blobs.get_blobs(&block_ref, &[]).await.is_err());
+    }
 }
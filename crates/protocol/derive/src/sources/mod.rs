@@ -7,6 +7,10 @@
 //! [DataAvailabilityProvider]: crate::traits::DataAvailabilityProvider
 //! [BlockInfo]: kona_protocol::BlockInfo
 
+mod auth;
+pub(crate) use auth::authenticate;
+pub use auth::{BatcherTxObserver, BatcherTxRejection, RejectedBatcherTx};
+
 mod blob_data;
 pub use blob_data::BlobData;
 
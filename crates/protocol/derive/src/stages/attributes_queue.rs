@@ -6,13 +6,15 @@ use crate::{
         AttributesBuilder, AttributesProvider, NextAttributes, OriginAdvancer, OriginProvider,
         SignalReceiver,
     },
-    types::{PipelineResult, Signal},
+    types::{PipelineResult, PipelineStateSize, Signal},
 };
 use alloc::{boxed::Box, sync::Arc};
 use async_trait::async_trait;
 use core::fmt::Debug;
 use kona_genesis::RollupConfig;
-use kona_protocol::{BlockInfo, L2BlockInfo, OpAttributesWithParent, SingleBatch};
+use kona_protocol::{
+    BlockInfo, L2BlockInfo, MemoryAudit, MemoryComponent, OpAttributesWithParent, SingleBatch,
+};
 use op_alloy_rpc_types_engine::OpPayloadAttributes;
 
 /// [`AttributesQueue`] accepts batches from the [`BatchQueue`] stage
@@ -44,6 +46,8 @@ where
     batch: Option<SingleBatch>,
     /// The attributes builder.
     builder: AB,
+    /// The audit the size of the current batch is recorded into, if any.
+    audit: Option<MemoryAudit>,
 }
 
 impl<P, AB> AttributesQueue<P, AB>
@@ -53,7 +57,25 @@ where
 {
     /// Create a new [`AttributesQueue`] stage.
     pub const fn new(cfg: Arc<RollupConfig>, prev: P, builder: AB) -> Self {
-        Self { cfg, prev, is_last_in_span: false, batch: None, builder }
+        Self { cfg, prev, is_last_in_span: false, batch: None, builder, audit: None }
+    }
+
+    /// Records the size of the current batch into the given [`MemoryAudit`].
+    pub fn with_memory_audit(mut self, audit: MemoryAudit) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Returns the number of bytes of the transactions of the current batch.
+    pub fn size(&self) -> usize {
+        self.batch.as_ref().map_or(0, |batch| batch.transactions.iter().map(|tx| tx.len()).sum())
+    }
+
+    /// Records the size of the current batch into the audit, if any.
+    fn record_size(&self) {
+        if let Some(audit) = &self.audit {
+            audit.record(MemoryComponent::AttributesQueue, self.size());
+        }
     }
 
     /// Loads a [`SingleBatch`] from the [`AttributesProvider`] if needed.
@@ -62,6 +84,7 @@ where
             let batch = self.prev.next_batch(parent).await?;
             self.batch = Some(batch);
             self.is_last_in_span = self.prev.is_last_in_span();
+            self.record_size();
         }
         self.batch.as_ref().cloned().ok_or(PipelineError::Eof.temp())
     }
@@ -99,6 +122,7 @@ where
         // Clear out the local state once payload attributes are prepared.
         self.batch = None;
         self.is_last_in_span = false;
+        self.record_size();
         Ok(populated_attributes)
     }
 
@@ -161,6 +185,10 @@ where
     P: AttributesProvider + OriginAdvancer + OriginProvider + SignalReceiver + Debug + Send,
     AB: AttributesBuilder + Debug + Send,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        self.prev.state_size().with_single_batches(&self.batch)
+    }
+
     async fn next_attributes(
         &mut self,
         parent: L2BlockInfo,
@@ -196,10 +224,11 @@ where
                 self.batch = None;
                 self.prev.signal(s).await?;
             }
-            s @ Signal::ProvideBlock(_) => {
+            s @ Signal::ProvideBlock(_) | s @ Signal::Prune(_) => {
                 self.prev.signal(s).await?;
             }
         }
+        self.record_size();
         Ok(())
     }
 }
@@ -283,6 +312,28 @@ mod tests {
         assert!(attributes_queue.is_last_in_span);
     }
 
+    #[tokio::test]
+    async fn test_attributes_queue_records_size() {
+        let audit = MemoryAudit::new();
+        let batch = SingleBatch {
+            transactions: vec![Bytes::from(vec![0; 10]), Bytes::from(vec![0; 20])],
+            ..Default::default()
+        };
+        let mock = new_test_attributes_provider(Some(Default::default()), vec![Ok(batch)]);
+        let mock_builder =
+            TestAttributesBuilder { attributes: vec![Ok(default_optimism_payload_attributes())] };
+        let mut aq = AttributesQueue::new(Arc::new(RollupConfig::default()), mock, mock_builder)
+            .with_memory_audit(audit.clone());
+
+        aq.load_batch(L2BlockInfo::default()).await.unwrap();
+        assert_eq!(aq.size(), 30);
+        assert_eq!(audit.usage(MemoryComponent::AttributesQueue).current, 30);
+
+        aq.next_attributes(L2BlockInfo::default()).await.unwrap();
+        let usage = audit.usage(MemoryComponent::AttributesQueue);
+        assert_eq!((usage.current, usage.peak), (0, 30));
+    }
+
     #[tokio::test]
     async fn test_create_next_attributes_bad_parent_hash() {
         let mut attributes_queue = new_attributes_queue(None, None, vec![]);
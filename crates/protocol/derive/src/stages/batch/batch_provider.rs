@@ -3,7 +3,7 @@
 use super::NextBatchProvider;
 use crate::{
     AttributesProvider, BatchQueue, BatchValidator, L2ChainProvider, OriginAdvancer,
-    OriginProvider, PipelineError, PipelineResult, Signal, SignalReceiver,
+    OriginProvider, PipelineError, PipelineResult, PipelineStateSize, Signal, SignalReceiver,
 };
 use alloc::{boxed::Box, sync::Arc};
 use async_trait::async_trait;
@@ -151,6 +151,15 @@ where
     P: NextBatchProvider + OriginAdvancer + OriginProvider + SignalReceiver + Debug + Send,
     F: L2ChainProvider + Clone + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        match (&self.batch_validator, &self.batch_queue, &self.prev) {
+            (Some(batch_validator), _, _) => batch_validator.state_size(),
+            (None, Some(batch_queue), _) => batch_queue.state_size(),
+            (None, None, Some(prev)) => prev.state_size(),
+            (None, None, None) => PipelineStateSize::default(),
+        }
+    }
+
     fn is_last_in_span(&self) -> bool {
         self.batch_validator.as_ref().map_or_else(
             || self.batch_queue.as_ref().is_some_and(|batch_queue| batch_queue.is_last_in_span()),
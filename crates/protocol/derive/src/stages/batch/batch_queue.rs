@@ -4,7 +4,7 @@ use super::NextBatchProvider;
 use crate::{
     errors::{PipelineEncodingError, PipelineError, PipelineErrorKind, ResetError},
     traits::{AttributesProvider, L2ChainProvider, OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, ResetSignal, Signal},
+    types::{PipelineResult, PipelineStateSize, ResetSignal, Signal},
 };
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use async_trait::async_trait;
@@ -276,6 +276,10 @@ where
     P: NextBatchProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
     BF: L2ChainProvider + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        self.prev.state_size().with_batches(&self.batches).with_single_batches(&self.next_spans)
+    }
+
     /// Returns the next valid batch upon the given safe head.
     /// Also returns the boolean that indicates if the batch is the last block in the batch.
     async fn next_batch(&mut self, parent: L2BlockInfo) -> PipelineResult<SingleBatch> {
@@ -452,7 +456,7 @@ where
                 self.batches.clear();
                 self.next_spans.clear();
             }
-            s @ Signal::ProvideBlock(_) => {
+            s @ Signal::ProvideBlock(_) | s @ Signal::Prune(_) => {
                 self.prev.signal(s).await?;
             }
         }
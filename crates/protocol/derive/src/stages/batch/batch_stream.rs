@@ -2,7 +2,7 @@
 
 use crate::{
     L2ChainProvider, NextBatchProvider, OriginAdvancer, OriginProvider, PipelineEncodingError,
-    PipelineError, PipelineResult, Signal, SignalReceiver,
+    PipelineError, PipelineResult, PipelineStateSize, Signal, SignalReceiver,
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use async_trait::async_trait;
@@ -20,6 +20,11 @@ pub trait BatchStreamProvider {
 
     /// Drains the recent `Channel` if an invalid span batch is found post-holocene.
     fn flush(&mut self);
+
+    /// Returns the [`PipelineStateSize`] buffered by this stage and the stages before it.
+    fn state_size(&self) -> PipelineStateSize {
+        PipelineStateSize::default()
+    }
 }
 
 /// [`BatchStream`] stage in the derivation pipeline.
@@ -108,6 +113,10 @@ where
     P: BatchStreamProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
     BF: L2ChainProvider + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        self.prev.state_size().with_span_batches(&self.span).with_single_batches(&self.buffer)
+    }
+
     fn flush(&mut self) {
         if self.is_active().unwrap_or(false) {
             self.prev.flush();
@@ -227,6 +236,9 @@ where
 {
     async fn signal(&mut self, signal: Signal) -> PipelineResult<()> {
         self.prev.signal(signal).await?;
+        if matches!(signal, Signal::Prune(_)) {
+            return Ok(());
+        }
         self.buffer.clear();
         self.span.take();
         Ok(())
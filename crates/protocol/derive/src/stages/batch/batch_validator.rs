@@ -4,7 +4,7 @@ use super::NextBatchProvider;
 use crate::{
     errors::{PipelineError, PipelineErrorKind, ResetError},
     traits::{AttributesProvider, OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, ResetSignal, Signal},
+    types::{PipelineResult, PipelineStateSize, ResetSignal, Signal},
 };
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use async_trait::async_trait;
@@ -198,6 +198,10 @@ impl<P> AttributesProvider for BatchValidator<P>
 where
     P: NextBatchProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        self.prev.state_size()
+    }
+
     async fn next_batch(&mut self, parent: L2BlockInfo) -> PipelineResult<SingleBatch> {
         // Update the L1 origin blocks within the stage.
         self.update_origins(&parent)?;
@@ -315,7 +319,10 @@ where
                 self.l1_blocks.clear();
                 self.l1_blocks.push(l1_origin);
             }
-            s @ Signal::Activation(_) | s @ Signal::FlushChannel | s @ Signal::ProvideBlock(_) => {
+            s @ Signal::Activation(_) |
+            s @ Signal::FlushChannel |
+            s @ Signal::ProvideBlock(_) |
+            s @ Signal::Prune(_) => {
                 self.prev.signal(s).await?;
             }
         }
@@ -12,7 +12,7 @@
 //! [ChannelReader]: crate::stages::channel::ChannelReader
 //! [AttributesQueue]: crate::stages::attributes_queue::AttributesQueue
 
-use crate::types::PipelineResult;
+use crate::types::{PipelineResult, PipelineStateSize};
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use kona_protocol::{Batch, BlockInfo, L2BlockInfo};
@@ -55,4 +55,9 @@ pub trait NextBatchProvider {
     /// Allows the stage to flush the buffer in the [crate::stages::BatchStream]
     /// if an invalid single batch is found. Pre-holocene hardfork, this will be a no-op.
     fn flush(&mut self);
+
+    /// Returns the [`PipelineStateSize`] buffered by this stage and the stages before it.
+    fn state_size(&self) -> PipelineStateSize {
+        PipelineStateSize::default()
+    }
 }
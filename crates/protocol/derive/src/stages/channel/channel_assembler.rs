@@ -4,7 +4,7 @@ use super::{ChannelReaderProvider, NextFrameProvider};
 use crate::{
     errors::PipelineError,
     traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    types::{PipelineResult, PipelineStateSize, Signal},
 };
 use alloc::{boxed::Box, sync::Arc};
 use alloy_primitives::{Bytes, hex};
@@ -63,6 +63,11 @@ impl<P> ChannelReaderProvider for ChannelAssembler<P>
 where
     P: NextFrameProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        let channel_bank = self.channel.as_ref().map_or(0, |channel| channel.size());
+        PipelineStateSize { channel_bank, ..self.prev.state_size() }
+    }
+
     async fn next_data(&mut self) -> PipelineResult<Option<Bytes>> {
         let origin = self.origin().ok_or(PipelineError::MissingOrigin.crit())?;
 
@@ -194,6 +199,18 @@ where
     P: NextFrameProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
 {
     async fn signal(&mut self, signal: Signal) -> PipelineResult<()> {
+        // The channel is only buffered by this stage, so pruning stops here.
+        if let Signal::Prune(bytes) = signal {
+            if let Some(channel) = self.channel.take_if(|_| bytes > 0) {
+                warn!(
+                    target: "channel_assembler",
+                    "Evicted channel (ID: {}) with {} bytes",
+                    hex::encode(channel.id()),
+                    channel.size()
+                );
+            }
+            return Ok(());
+        }
         self.prev.signal(signal).await?;
         self.channel = None;
         Ok(())
@@ -329,7 +346,7 @@ mod test {
             crate::frame!(0xFF, 0, vec![0xDD; 50], false),
             crate::frame!(0xFF, 1, vec![0xDD; 50], true),
         ];
-        frames[1].data = vec![0; MAX_RLP_BYTES_PER_CHANNEL_BEDROCK as usize];
+        frames[1].data = vec![0; MAX_RLP_BYTES_PER_CHANNEL_BEDROCK as usize].into();
         let mock = TestNextFrameProvider::new(frames.into_iter().rev().map(Ok).collect());
         let cfg = Arc::new(RollupConfig::default());
 
@@ -364,7 +381,7 @@ mod test {
             crate::frame!(0xFF, 0, vec![0xDD; 50], false),
             crate::frame!(0xFF, 1, vec![0xDD; 50], true),
         ];
-        frames[1].data = vec![0; MAX_RLP_BYTES_PER_CHANNEL_FJORD as usize];
+        frames[1].data = vec![0; MAX_RLP_BYTES_PER_CHANNEL_FJORD as usize].into();
         let mock = TestNextFrameProvider::new(frames.into_iter().rev().map(Ok).collect());
         let cfg = Arc::new(RollupConfig {
             hardforks: HardForkConfig { fjord_time: Some(0), ..Default::default() },
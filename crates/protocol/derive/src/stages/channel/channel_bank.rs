@@ -2,12 +2,12 @@
 
 use crate::{
     ChannelReaderProvider, NextFrameProvider, OriginAdvancer, OriginProvider, PipelineError,
-    PipelineErrorKind, PipelineResult, Signal, SignalReceiver,
+    PipelineErrorKind, PipelineResult, PipelineStateSize, Signal, SignalReceiver,
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use alloy_primitives::{Bytes, hex, map::HashMap};
 use async_trait::async_trait;
-use core::fmt::Debug;
+use core::{cmp::Reverse, fmt::Debug};
 use kona_genesis::RollupConfig;
 use kona_protocol::{BlockInfo, Channel, ChannelId, Frame};
 
@@ -17,6 +17,21 @@ pub(crate) const MAX_CHANNEL_BANK_SIZE: usize = 100_000_000;
 /// The maximum size of a channel bank after the Fjord Hardfork.
 pub(crate) const FJORD_MAX_CHANNEL_BANK_SIZE: usize = 1_000_000_000;
 
+/// The order in which the [`ChannelBank`] evicts its channels, when it exceeds its maximum size
+/// or is asked to free memory with a [`Signal::Prune`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelBankEvictionPolicy {
+    /// Evicts the oldest channel first, as specified.
+    #[default]
+    Fifo,
+    /// Evicts the largest channel first, and the oldest among channels of the same size.
+    ///
+    /// Large channels from a misbehaving batcher are evicted before the small valid channels
+    /// opened before them, which eases debugging. This deviates from the specification: when the
+    /// channel bank overflows, the derived chain may differ from the canonical one.
+    Largest,
+}
+
 /// [`ChannelBank`] is a stateful stage that does the following:
 /// 1. Unmarshalls frames from L1 transaction data
 /// 2. Applies those frames to a channel
@@ -39,6 +54,8 @@ where
     pub(crate) channels: HashMap<ChannelId, Channel>,
     /// Channels in FIFO order.
     pub(crate) channel_queue: VecDeque<ChannelId>,
+    /// The order in which channels are evicted.
+    pub(crate) eviction_policy: ChannelBankEvictionPolicy,
     /// The previous stage of the derivation pipeline.
     pub(crate) prev: P,
 }
@@ -49,7 +66,19 @@ where
 {
     /// Create a new [`ChannelBank`] stage.
     pub fn new(cfg: Arc<RollupConfig>, prev: P) -> Self {
-        Self { cfg, channels: HashMap::default(), channel_queue: VecDeque::new(), prev }
+        Self {
+            cfg,
+            channels: HashMap::default(),
+            channel_queue: VecDeque::new(),
+            eviction_policy: ChannelBankEvictionPolicy::default(),
+            prev,
+        }
+    }
+
+    /// Sets the order in which channels are evicted.
+    pub const fn with_eviction_policy(mut self, policy: ChannelBankEvictionPolicy) -> Self {
+        self.eviction_policy = policy;
+        self
     }
 
     /// Returns the size of the channel bank by accumulating over all channels.
@@ -58,7 +87,8 @@ where
     }
 
     /// Prunes the Channel bank, until it is below the max channel bank size.
-    /// Prunes from the high-priority channel since it failed to be read.
+    /// Prunes in the order of the [`ChannelBankEvictionPolicy`], which by default is the
+    /// high-priority channel since it failed to be read.
     pub fn prune(&mut self) -> PipelineResult<()> {
         let mut total_size = self.size();
         let origin = self.origin().ok_or(PipelineError::MissingOrigin.crit())?;
@@ -68,14 +98,50 @@ where
             MAX_CHANNEL_BANK_SIZE
         };
         while total_size > max_channel_bank_size {
-            let id =
-                self.channel_queue.pop_front().ok_or(PipelineError::ChannelProviderEmpty.crit())?;
+            let id = self.next_evicted().ok_or(PipelineError::ChannelProviderEmpty.crit())?;
             let channel = self.channels.remove(&id).ok_or(PipelineError::ChannelNotFound.crit())?;
+            debug!(
+                target: "channel_bank",
+                "Pruned channel (ID: {}) with {} bytes", hex::encode(id), channel.size()
+            );
             total_size -= channel.size();
         }
         Ok(())
     }
 
+    /// Evicts channels in the order of the [`ChannelBankEvictionPolicy`], until at least the given
+    /// number of bytes is freed or no channel is left. Returns the number of bytes freed.
+    pub fn evict(&mut self, bytes: usize) -> usize {
+        let mut freed = 0;
+        while freed < bytes {
+            let Some(id) = self.next_evicted() else { break };
+            let Some(channel) = self.channels.remove(&id) else { continue };
+            warn!(
+                target: "channel_bank",
+                "Evicted channel (ID: {}) with {} bytes", hex::encode(id), channel.size()
+            );
+            freed += channel.size();
+        }
+        freed
+    }
+
+    /// Removes the next channel to evict from the queue, according to the
+    /// [`ChannelBankEvictionPolicy`].
+    fn next_evicted(&mut self) -> Option<ChannelId> {
+        let index = match self.eviction_policy {
+            ChannelBankEvictionPolicy::Fifo => 0,
+            ChannelBankEvictionPolicy::Largest => self
+                .channel_queue
+                .iter()
+                .enumerate()
+                .max_by_key(|(i, id)| {
+                    (self.channels.get(*id).map_or(0, |channel| channel.size()), Reverse(*i))
+                })
+                .map(|(i, _)| i)?,
+        };
+        self.channel_queue.remove(index)
+    }
+
     /// Adds new L1 data to the channel bank. Should only be called after all data has been read.
     pub fn ingest_frame(&mut self, frame: Frame) -> PipelineResult<()> {
         let origin = self.origin().ok_or(PipelineError::MissingOrigin.crit())?;
@@ -192,6 +258,10 @@ impl<P> ChannelReaderProvider for ChannelBank<P>
 where
     P: NextFrameProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        PipelineStateSize { channel_bank: self.size(), ..self.prev.state_size() }
+    }
+
     async fn next_data(&mut self) -> PipelineResult<Option<Bytes>> {
         match self.read() {
             Err(e) => {
@@ -230,6 +300,11 @@ where
     P: NextFrameProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
 {
     async fn signal(&mut self, signal: Signal) -> PipelineResult<()> {
+        // The channels are only buffered by this stage, so pruning stops here.
+        if let Signal::Prune(bytes) = signal {
+            self.evict(bytes);
+            return Ok(());
+        }
         self.prev.signal(signal).await?;
         self.channels.clear();
         self.channel_queue = VecDeque::with_capacity(10);
@@ -293,19 +368,19 @@ mod tests {
         let mut channel = Channel::new(id, BlockInfo::default());
         channel
             .add_frame(
-                Frame { id, number: 0, data: b"seven__".to_vec(), is_last: false },
+                Frame { id, number: 0, data: b"seven__".to_vec().into(), is_last: false },
                 BlockInfo::default(),
             )
             .unwrap();
         channel
             .add_frame(
-                Frame { id, number: 1, data: b"seven__".to_vec(), is_last: false },
+                Frame { id, number: 1, data: b"seven__".to_vec().into(), is_last: false },
                 BlockInfo::default(),
             )
             .unwrap();
         channel
             .add_frame(
-                Frame { id, number: 2, data: b"seven__".to_vec(), is_last: true },
+                Frame { id, number: 2, data: b"seven__".to_vec().into(), is_last: true },
                 BlockInfo::default(),
             )
             .unwrap();
@@ -328,19 +403,19 @@ mod tests {
         let mut channel = Channel::new(id, BlockInfo::default());
         channel
             .add_frame(
-                Frame { id, number: 0, data: b"seven__".to_vec(), is_last: false },
+                Frame { id, number: 0, data: b"seven__".to_vec().into(), is_last: false },
                 BlockInfo::default(),
             )
             .unwrap();
         channel
             .add_frame(
-                Frame { id, number: 1, data: b"seven__".to_vec(), is_last: false },
+                Frame { id, number: 1, data: b"seven__".to_vec().into(), is_last: false },
                 BlockInfo::default(),
             )
             .unwrap();
         channel
             .add_frame(
-                Frame { id, number: 2, data: b"seven__".to_vec(), is_last: true },
+                Frame { id, number: 2, data: b"seven__".to_vec().into(), is_last: true },
                 BlockInfo::default(),
             )
             .unwrap();
@@ -366,19 +441,19 @@ mod tests {
         let mut channel = Channel::new(id, BlockInfo::default());
         channel
             .add_frame(
-                Frame { id, number: 0, data: b"seven__".to_vec(), is_last: false },
+                Frame { id, number: 0, data: b"seven__".to_vec().into(), is_last: false },
                 BlockInfo::default(),
             )
             .unwrap();
         channel
             .add_frame(
-                Frame { id, number: 1, data: b"seven__".to_vec(), is_last: false },
+                Frame { id, number: 1, data: b"seven__".to_vec().into(), is_last: false },
                 BlockInfo::default(),
             )
             .unwrap();
         channel
             .add_frame(
-                Frame { id, number: 2, data: b"seven__".to_vec(), is_last: true },
+                Frame { id, number: 2, data: b"seven__".to_vec().into(), is_last: true },
                 BlockInfo::default(),
             )
             .unwrap();
@@ -416,6 +491,73 @@ mod tests {
         assert!(channel_bank.prev.reset);
     }
 
+    #[tokio::test]
+    async fn test_prune_signal_evicts_oldest_channels() {
+        let mock = TestNextFrameProvider::new(vec![]);
+        let mut channel_bank = ChannelBank::new(Arc::new(RollupConfig::default()), mock);
+        for id in 0..3 {
+            let frame = Frame { id: [id; 16], data: vec![0; 100].into(), ..Default::default() };
+            channel_bank.ingest_frame(frame).unwrap();
+        }
+        let channel_size = kona_protocol::FRAME_OVERHEAD + 100;
+        assert_eq!(channel_bank.state_size().channel_bank, 3 * channel_size);
+
+        // Freeing a single byte evicts the whole oldest channel.
+        channel_bank.signal(Signal::Prune(1)).await.unwrap();
+        assert_eq!(channel_bank.channel_queue, [[1; 16], [2; 16]]);
+        assert_eq!(channel_bank.state_size().channel_bank, 2 * channel_size);
+        assert!(!channel_bank.prev.reset);
+
+        // Pruning stops once no channel is left.
+        channel_bank.signal(Signal::Prune(usize::MAX)).await.unwrap();
+        assert!(channel_bank.channels.is_empty());
+        assert_eq!(channel_bank.state_size(), PipelineStateSize::default());
+    }
+
+    #[tokio::test]
+    async fn test_prune_signal_evicts_largest_channels() {
+        let mock = TestNextFrameProvider::new(vec![]);
+        let mut channel_bank = ChannelBank::new(Arc::new(RollupConfig::default()), mock)
+            .with_eviction_policy(ChannelBankEvictionPolicy::Largest);
+        for (id, size) in [(0, 100), (1, 300), (2, 300)] {
+            let frame = Frame { id: [id; 16], data: vec![0; size].into(), ..Default::default() };
+            channel_bank.ingest_frame(frame).unwrap();
+        }
+
+        // The oldest of the largest channels is evicted first.
+        channel_bank.signal(Signal::Prune(1)).await.unwrap();
+        assert_eq!(channel_bank.channel_queue, [[0; 16], [2; 16]]);
+        channel_bank.signal(Signal::Prune(1)).await.unwrap();
+        assert_eq!(channel_bank.channel_queue, [[0; 16]]);
+    }
+
+    /// Ingests a small channel followed by two channels that overflow the channel bank together,
+    /// and returns the channels left in the bank.
+    fn ingest_overflowing_channels(policy: ChannelBankEvictionPolicy) -> VecDeque<ChannelId> {
+        let mock = TestNextFrameProvider::new(vec![]);
+        let mut channel_bank =
+            ChannelBank::new(Arc::new(RollupConfig::default()), mock).with_eviction_policy(policy);
+        for (id, size) in [(0, 100), (1, 60_000_000), (2, 50_000_000)] {
+            let frame = Frame { id: [id; 16], data: vec![0; size].into(), ..Default::default() };
+            channel_bank.ingest_frame(frame).unwrap();
+        }
+        assert!(channel_bank.size() <= MAX_CHANNEL_BANK_SIZE);
+        channel_bank.channel_queue
+    }
+
+    #[test]
+    fn test_prune_fifo_conforms_to_spec() {
+        // The channels are pruned oldest first, even if that evicts the small valid channel.
+        let queue = ingest_overflowing_channels(ChannelBankEvictionPolicy::Fifo);
+        assert_eq!(queue, [[2; 16]]);
+    }
+
+    #[test]
+    fn test_prune_largest_first() {
+        let queue = ingest_overflowing_channels(ChannelBankEvictionPolicy::Largest);
+        assert_eq!(queue, [[0; 16], [2; 16]]);
+    }
+
     #[test]
     fn test_ingest_invalid_frame() {
         let trace_store: TraceStorage = Default::default();
@@ -1,17 +1,20 @@
 //! This module contains the [ChannelProvider] stage.
 
-use super::{ChannelAssembler, ChannelBank, ChannelReaderProvider, NextFrameProvider};
+use super::{
+    ChannelAssembler, ChannelBank, ChannelBankEvictionPolicy, ChannelReaderProvider,
+    NextFrameProvider,
+};
 use crate::{
     errors::PipelineError,
     traits::{OriginAdvancer, OriginProvider, SignalReceiver},
-    types::{PipelineResult, Signal},
+    types::{PipelineResult, PipelineStateSize, Signal},
 };
 use alloc::{boxed::Box, sync::Arc};
 use alloy_primitives::Bytes;
 use async_trait::async_trait;
 use core::fmt::Debug;
 use kona_genesis::RollupConfig;
-use kona_protocol::BlockInfo;
+use kona_protocol::{BlockInfo, MemoryAudit, MemoryComponent};
 
 /// The [`ChannelProvider`] stage is a mux between the [`ChannelBank`] and [`ChannelAssembler`]
 /// stages.
@@ -41,6 +44,10 @@ where
     ///
     /// Must be [`None`] if `prev` or `channel_bank` is [`Some`].
     channel_assembler: Option<ChannelAssembler<P>>,
+    /// The audit the size of the active stage is recorded into, if any.
+    audit: Option<MemoryAudit>,
+    /// The order in which the channel bank evicts its channels.
+    eviction_policy: ChannelBankEvictionPolicy,
 }
 
 impl<P> ChannelProvider<P>
@@ -49,7 +56,44 @@ where
 {
     /// Creates a new [`ChannelProvider`] with the given configuration and previous stage.
     pub const fn new(cfg: Arc<RollupConfig>, prev: P) -> Self {
-        Self { cfg, prev: Some(prev), channel_bank: None, channel_assembler: None }
+        Self {
+            cfg,
+            prev: Some(prev),
+            channel_bank: None,
+            channel_assembler: None,
+            audit: None,
+            eviction_policy: ChannelBankEvictionPolicy::Fifo,
+        }
+    }
+
+    /// Records the size of the active stage into the given [`MemoryAudit`].
+    pub fn with_memory_audit(mut self, audit: MemoryAudit) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Sets the order in which the [`ChannelBank`] evicts its channels.
+    pub const fn with_eviction_policy(mut self, policy: ChannelBankEvictionPolicy) -> Self {
+        self.eviction_policy = policy;
+        self
+    }
+
+    /// Returns the number of bytes of the frames buffered by the active stage.
+    pub fn size(&self) -> usize {
+        match (&self.channel_assembler, &self.channel_bank) {
+            (Some(channel_assembler), _) => {
+                channel_assembler.channel.as_ref().map_or(0, |channel| channel.size())
+            }
+            (None, Some(channel_bank)) => channel_bank.size(),
+            (None, None) => 0,
+        }
+    }
+
+    /// Records the size of the active stage into the audit, if any.
+    fn record_size(&self) {
+        if let Some(audit) = &self.audit {
+            audit.record(MemoryComponent::ChannelBank, self.size());
+        }
     }
 
     /// Attempts to update the active stage of the mux.
@@ -61,7 +105,10 @@ where
             if self.cfg.is_holocene_active(origin.timestamp) {
                 self.channel_assembler = Some(ChannelAssembler::new(self.cfg.clone(), prev));
             } else {
-                self.channel_bank = Some(ChannelBank::new(self.cfg.clone(), prev));
+                self.channel_bank = Some(
+                    ChannelBank::new(self.cfg.clone(), prev)
+                        .with_eviction_policy(self.eviction_policy),
+                );
             }
         } else if self.channel_bank.is_some() && self.cfg.is_holocene_active(origin.timestamp) {
             // If the channel bank is active and Holocene is also active, transition to the channel
@@ -76,7 +123,10 @@ where
             // until Holocene re-activates.
             let channel_assembler =
                 self.channel_assembler.take().expect("Must have channel assembler");
-            self.channel_bank = Some(ChannelBank::new(self.cfg.clone(), channel_assembler.prev));
+            self.channel_bank = Some(
+                ChannelBank::new(self.cfg.clone(), channel_assembler.prev)
+                    .with_eviction_policy(self.eviction_policy),
+            );
         }
         Ok(())
     }
@@ -125,13 +175,15 @@ where
     async fn signal(&mut self, signal: Signal) -> PipelineResult<()> {
         self.attempt_update()?;
 
-        if let Some(channel_assembler) = self.channel_assembler.as_mut() {
+        let result = if let Some(channel_assembler) = self.channel_assembler.as_mut() {
             channel_assembler.signal(signal).await
         } else if let Some(channel_bank) = self.channel_bank.as_mut() {
             channel_bank.signal(signal).await
         } else {
             Err(PipelineError::NotEnoughData.temp())
-        }
+        };
+        self.record_size();
+        result
     }
 }
 
@@ -140,16 +192,27 @@ impl<P> ChannelReaderProvider for ChannelProvider<P>
 where
     P: NextFrameProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        match (&self.channel_assembler, &self.channel_bank, &self.prev) {
+            (Some(channel_assembler), _, _) => channel_assembler.state_size(),
+            (None, Some(channel_bank), _) => channel_bank.state_size(),
+            (None, None, Some(prev)) => prev.state_size(),
+            (None, None, None) => PipelineStateSize::default(),
+        }
+    }
+
     async fn next_data(&mut self) -> PipelineResult<Option<Bytes>> {
         self.attempt_update()?;
 
-        if let Some(channel_assembler) = self.channel_assembler.as_mut() {
+        let result = if let Some(channel_assembler) = self.channel_assembler.as_mut() {
             channel_assembler.next_data().await
         } else if let Some(channel_bank) = self.channel_bank.as_mut() {
             channel_bank.next_data().await
         } else {
             Err(PipelineError::NotEnoughData.temp())
-        }
+        };
+        self.record_size();
+        result
     }
 }
 
@@ -161,7 +224,7 @@ mod test {
     };
     use alloc::{sync::Arc, vec};
     use kona_genesis::{HardForkConfig, RollupConfig};
-    use kona_protocol::BlockInfo;
+    use kona_protocol::{BlockInfo, FRAME_OVERHEAD, MemoryAudit, MemoryComponent};
 
     #[test]
     fn test_channel_provider_assembler_active() {
@@ -361,4 +424,29 @@ mod test {
         };
         assert!(channel_assembler.channel.is_none());
     }
+
+    #[tokio::test]
+    async fn test_channel_provider_records_size() {
+        let frames = [
+            crate::frame!(0xFF, 0, vec![0xDD; 50], false),
+            crate::frame!(0xFF, 1, vec![0xDD; 50], true),
+        ];
+        let provider = TestNextFrameProvider::new(frames.into_iter().rev().map(Ok).collect());
+        let audit = MemoryAudit::new().with_ceiling(MemoryComponent::ChannelBank, FRAME_OVERHEAD);
+        let mut channel_provider =
+            ChannelProvider::new(Arc::new(RollupConfig::default()), provider)
+                .with_memory_audit(audit.clone());
+
+        // The first frame is buffered.
+        assert!(channel_provider.next_data().await.is_err());
+        assert_eq!(channel_provider.size(), FRAME_OVERHEAD + 50);
+        assert_eq!(audit.usage(MemoryComponent::ChannelBank).current, FRAME_OVERHEAD + 50);
+
+        // The second frame completes the channel, which is then read out of the bank.
+        assert!(channel_provider.next_data().await.is_err());
+        assert!(channel_provider.next_data().await.unwrap().is_some());
+        let usage = audit.usage(MemoryComponent::ChannelBank);
+        assert_eq!((usage.current, usage.peak), (0, 2 * (FRAME_OVERHEAD + 50)));
+        assert!(audit.check().is_err());
+    }
 }
@@ -1,8 +1,8 @@
 //! This module contains the `ChannelReader` struct.
 
 use crate::{
-    BatchStreamProvider, OriginAdvancer, OriginProvider, PipelineError, PipelineResult, Signal,
-    SignalReceiver,
+    BatchStreamProvider, OriginAdvancer, OriginProvider, PipelineError, PipelineResult,
+    PipelineStateSize, Signal, SignalReceiver,
 };
 use alloc::{boxed::Box, sync::Arc};
 use alloy_primitives::Bytes;
@@ -22,6 +22,20 @@ pub trait ChannelReaderProvider {
     /// ensure maintain consistency around channel bank pruning which depends upon the order
     /// of operations.
     async fn next_data(&mut self) -> PipelineResult<Option<Bytes>>;
+
+    /// Returns the [`PipelineStateSize`] buffered by this stage and the stages before it.
+    fn state_size(&self) -> PipelineStateSize {
+        PipelineStateSize::default()
+    }
+}
+
+/// An observer of the [`Batch`]es read by the [`ChannelReader`].
+///
+/// A batch is reported as soon as it is decoded from its channel, before it is validated by the
+/// later stages, so that the batches submitted to L1 can be tracked ahead of the safe head.
+pub trait BatchObserver: Debug + Send + Sync {
+    /// Called for each batch read from a channel, with the L1 origin the channel was read at.
+    fn batch_read(&self, origin: Option<BlockInfo>, batch: &Batch);
 }
 
 /// [`ChannelReader`] is a stateful stage that reads [`Batch`]es from `Channel`s.
@@ -44,6 +58,8 @@ where
     next_batch: Option<BatchReader>,
     /// The rollup coonfiguration.
     cfg: Arc<RollupConfig>,
+    /// The observer the read batches are reported to, if any.
+    observer: Option<Arc<dyn BatchObserver>>,
 }
 
 impl<P> ChannelReader<P>
@@ -52,7 +68,13 @@ where
 {
     /// Create a new [`ChannelReader`] stage.
     pub const fn new(prev: P, cfg: Arc<RollupConfig>) -> Self {
-        Self { prev, next_batch: None, cfg }
+        Self { prev, next_batch: None, cfg, observer: None }
+    }
+
+    /// Reports the read batches to the given [`BatchObserver`].
+    pub fn with_observer(mut self, observer: Arc<dyn BatchObserver>) -> Self {
+        self.observer = Some(observer);
+        self
     }
 
     /// Creates the batch reader from available channel data.
@@ -98,6 +120,10 @@ impl<P> BatchStreamProvider for ChannelReader<P>
 where
     P: ChannelReaderProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        self.prev.state_size()
+    }
+
     /// This method is called by the BatchStream if an invalid span batch is found.
     /// In the case of an invalid span batch, the associated channel must be flushed.
     ///
@@ -118,10 +144,23 @@ where
 
         // SAFETY: The batch reader must be set above.
         let next_batch = self.next_batch.as_mut().expect("Batch reader must be set");
+        // The reader only decompresses its data on the first call.
+        #[cfg(feature = "metrics")]
+        let (first, start) = (next_batch.decompressed.is_empty(), std::time::Instant::now());
         match next_batch.decompress() {
             Ok(()) => {
                 // Record the decompressed size and type.
                 let size = next_batch.decompressed.len() as f64;
+                #[cfg(feature = "metrics")]
+                if first {
+                    kona_macros::record!(
+                        histogram,
+                        crate::metrics::Metrics::PIPELINE_DECOMPRESSION_DURATION,
+                        start.elapsed().as_secs_f64()
+                    );
+                    metrics::gauge!(crate::metrics::Metrics::PIPELINE_DECOMPRESSED_BYTES)
+                        .increment(size);
+                }
                 let ty = if next_batch.brotli_used {
                     BatchReader::CHANNEL_VERSION_BROTLI
                 } else {
@@ -153,6 +192,9 @@ where
                     crate::metrics::Metrics::PIPELINE_READ_BATCHES,
                     "type" => batch.to_string(),
                 );
+                if let Some(observer) = &self.observer {
+                    observer.batch_read(self.prev.origin(), &batch);
+                }
                 Ok(batch)
             }
             Err(e) => {
@@ -185,6 +227,9 @@ where
                 self.next_batch = None;
                 kona_macros::set!(gauge, crate::metrics::Metrics::PIPELINE_BATCH_READER_SET, 0);
             }
+            s @ Signal::Prune(_) => {
+                self.prev.signal(s).await?;
+            }
             s => {
                 self.prev.signal(s).await?;
                 self.next_channel();
@@ -291,4 +336,31 @@ mod test {
         reader.flush();
         assert!(reader.next_batch.is_none());
     }
+
+    #[derive(Debug, Default)]
+    struct RecordingObserver(spin::Mutex<alloc::vec::Vec<u64>>);
+
+    impl BatchObserver for RecordingObserver {
+        fn batch_read(&self, _: Option<BlockInfo>, batch: &Batch) {
+            self.0.lock().push(batch.timestamp());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_batch_reports_to_observer() {
+        let raw = new_compressed_batch_data();
+        let mock = TestChannelReaderProvider::new(vec![Ok(Some(raw))]);
+        let observer = Arc::new(RecordingObserver::default());
+        let mut reader = ChannelReader::new(mock, Arc::new(RollupConfig::default()))
+            .with_observer(observer.clone());
+        let batch = reader.next_batch().await.unwrap();
+        assert_eq!(*observer.0.lock(), [batch.timestamp()]);
+
+        // Failed reads are not reported.
+        let mock = TestChannelReaderProvider::new(vec![Ok(None)]);
+        let mut reader = ChannelReader::new(mock, Arc::new(RollupConfig::default()))
+            .with_observer(observer.clone());
+        assert!(reader.next_batch().await.is_err());
+        assert_eq!(observer.0.lock().len(), 1);
+    }
 }
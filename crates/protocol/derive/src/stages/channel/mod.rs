@@ -11,7 +11,7 @@
 //! [FrameQueue]: crate::stages::FrameQueue
 //! [BatchQueue]: crate::stages::BatchQueue
 
-use crate::types::PipelineResult;
+use crate::types::{PipelineResult, PipelineStateSize};
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use kona_protocol::Frame;
@@ -20,13 +20,13 @@ pub(crate) mod channel_provider;
 pub use channel_provider::ChannelProvider;
 
 pub(crate) mod channel_bank;
-pub use channel_bank::ChannelBank;
+pub use channel_bank::{ChannelBank, ChannelBankEvictionPolicy};
 
 pub(crate) mod channel_assembler;
 pub use channel_assembler::ChannelAssembler;
 
 pub(crate) mod channel_reader;
-pub use channel_reader::{ChannelReader, ChannelReaderProvider};
+pub use channel_reader::{BatchObserver, ChannelReader, ChannelReaderProvider};
 
 /// Provides frames for the [`ChannelBank`] and [`ChannelAssembler`] stages.
 #[async_trait]
@@ -35,4 +35,9 @@ pub trait NextFrameProvider {
     ///
     /// [`FrameQueue`]: crate::stages::FrameQueue
     async fn next_frame(&mut self) -> PipelineResult<Frame>;
+
+    /// Returns the [`PipelineStateSize`] buffered by this stage and the stages before it.
+    fn state_size(&self) -> PipelineStateSize {
+        PipelineStateSize::default()
+    }
 }
@@ -1,8 +1,8 @@
 //! This module contains the [FrameQueue] stage of the derivation pipeline.
 
 use crate::{
-    NextFrameProvider, OriginAdvancer, OriginProvider, PipelineError, PipelineResult, Signal,
-    SignalReceiver,
+    NextFrameProvider, OriginAdvancer, OriginProvider, PipelineError, PipelineResult,
+    PipelineStateSize, Signal, SignalReceiver,
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use alloy_primitives::Bytes;
@@ -121,7 +121,7 @@ where
             }
         };
 
-        let Ok(frames) = Frame::parse_frames(&data.into()) else {
+        let Ok(frames) = Frame::parse_frames_bytes(&data.into()) else {
             // There may be more frames in the queue for the
             // pipeline to advance, so don't return an error here.
             error!(target: "frame_queue", "Failed to parse frames from data.");
@@ -163,6 +163,11 @@ impl<P> NextFrameProvider for FrameQueue<P>
 where
     P: FrameQueueProvider + OriginAdvancer + OriginProvider + SignalReceiver + Send + Debug,
 {
+    fn state_size(&self) -> PipelineStateSize {
+        let frame_queue = self.queue.iter().map(Frame::size).sum();
+        PipelineStateSize { frame_queue, ..Default::default() }
+    }
+
     async fn next_frame(&mut self) -> PipelineResult<Frame> {
         self.load_frames().await?;
 
@@ -204,6 +209,23 @@ pub(crate) mod tests {
     use alloc::vec;
     use kona_genesis::HardForkConfig;
 
+    #[tokio::test]
+    async fn test_frame_queue_state_size() {
+        let frames = [
+            crate::frame!(0xFF, 0, vec![0xDD; 50], false),
+            crate::frame!(0xFF, 1, vec![0xDD; 50], true),
+        ];
+        let mut data = vec![kona_protocol::DERIVATION_VERSION_0];
+        frames.iter().for_each(|frame| data.extend_from_slice(&frame.encode()));
+        let mut mock = TestFrameQueueProvider::new(vec![Ok(data.into())]);
+        mock.set_origin(BlockInfo::default());
+        let mut frame_queue = FrameQueue::new(mock, Default::default());
+        assert_eq!(frame_queue.state_size(), PipelineStateSize::default());
+
+        frame_queue.next_frame().await.unwrap();
+        assert_eq!(frame_queue.state_size().frame_queue, kona_protocol::FRAME_OVERHEAD + 50);
+    }
+
     #[tokio::test]
     async fn test_frame_queue_reset() {
         let mock = TestFrameQueueProvider::new(vec![]);
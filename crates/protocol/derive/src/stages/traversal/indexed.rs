@@ -1,10 +1,10 @@
 //! Contains the [`IndexedTraversal`] stage of the derivation pipeline.
 
 use crate::{
-    ActivationSignal, ChainProvider, L1RetrievalProvider, OriginAdvancer, OriginProvider,
-    PipelineError, PipelineResult, ResetError, ResetSignal, Signal, SignalReceiver,
+    ActivationSignal, ChainProvider, L1RetrievalProvider, LogFilter, OriginAdvancer,
+    OriginProvider, PipelineError, PipelineResult, ResetError, ResetSignal, Signal, SignalReceiver,
 };
-use alloc::{boxed::Box, sync::Arc};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_primitives::Address;
 use async_trait::async_trait;
 use kona_genesis::{RollupConfig, SystemConfig};
@@ -47,7 +47,7 @@ impl<F: ChainProvider + Send> L1RetrievalProvider for IndexedTraversal<F> {
     }
 }
 
-impl<F: ChainProvider> IndexedTraversal<F> {
+impl<F: ChainProvider + Send> IndexedTraversal<F> {
     /// Creates a new [`IndexedTraversal`] instance.
     pub fn new(data_source: F, cfg: Arc<RollupConfig>) -> Self {
         Self {
@@ -86,9 +86,19 @@ impl<F: ChainProvider> IndexedTraversal<F> {
             );
         }
 
-        // Fetch receipts for the next l1 block and update the system config.
-        let receipts =
-            self.data_source.receipts_by_hash(block_info.hash).await.map_err(Into::into)?;
+        // Fetch receipts for the next l1 block and update the system config. The receipts of
+        // blocks that cannot contain system config updates are skipped.
+        let filters = [LogFilter::system_config_updates(&self.rollup_config)];
+        let receipts = if self
+            .data_source
+            .may_contain_logs(block_info.hash, &filters)
+            .await
+            .map_err(Into::into)?
+        {
+            self.data_source.receipts_by_hash(block_info.hash).await.map_err(Into::into)?
+        } else {
+            Vec::new()
+        };
 
         let addr = self.rollup_config.l1_system_config_address;
         let active = self.rollup_config.is_ecotone_active(block_info.timestamp);
@@ -1,10 +1,10 @@
 //! Contains the [`PollingTraversal`] stage of the derivation pipeline.
 
 use crate::{
-    ActivationSignal, ChainProvider, L1RetrievalProvider, OriginAdvancer, OriginProvider,
-    PipelineError, PipelineResult, ResetError, ResetSignal, Signal, SignalReceiver,
+    ActivationSignal, ChainProvider, L1RetrievalProvider, LogFilter, OriginAdvancer,
+    OriginProvider, PipelineError, PipelineResult, ResetError, ResetSignal, Signal, SignalReceiver,
 };
-use alloc::{boxed::Box, sync::Arc};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_primitives::Address;
 use async_trait::async_trait;
 use kona_genesis::{RollupConfig, SystemConfig};
@@ -94,9 +94,19 @@ impl<F: ChainProvider + Send> OriginAdvancer for PollingTraversal<F> {
             return Err(ResetError::ReorgDetected(block.hash, next_l1_origin.parent_hash).into());
         }
 
-        // Fetch receipts for the next l1 block and update the system config.
-        let receipts =
-            self.data_source.receipts_by_hash(next_l1_origin.hash).await.map_err(Into::into)?;
+        // Fetch receipts for the next l1 block and update the system config. The receipts of
+        // blocks that cannot contain system config updates are skipped.
+        let filters = [LogFilter::system_config_updates(&self.rollup_config)];
+        let receipts = if self
+            .data_source
+            .may_contain_logs(next_l1_origin.hash, &filters)
+            .await
+            .map_err(Into::into)?
+        {
+            self.data_source.receipts_by_hash(next_l1_origin.hash).await.map_err(Into::into)?
+        } else {
+            Vec::new()
+        };
 
         let addr = self.rollup_config.l1_system_config_address;
         let active = self.rollup_config.is_ecotone_active(next_l1_origin.timestamp);
@@ -260,6 +270,20 @@ pub(crate) mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_l1_traversal_skips_receipts_by_logs_bloom() {
+        // The header of the next block has an empty logs bloom, so its missing receipts are not
+        // fetched.
+        let header = alloy_consensus::Header { number: 1, ..Default::default() };
+        let next = BlockInfo { hash: header.hash_slow(), number: 1, ..Default::default() };
+        let blocks = vec![BlockInfo::default(), next];
+        let mut traversal = TraversalTestHelper::new_from_blocks(blocks, vec![]);
+        traversal.data_source.insert_header(next.hash, header);
+        assert_eq!(traversal.next_l1_block().await.unwrap(), Some(BlockInfo::default()));
+        assert!(traversal.advance_origin().await.is_ok());
+        assert_eq!(traversal.origin(), Some(next));
+    }
+
     #[tokio::test]
     async fn test_l1_traversal_reorgs() {
         let hash = b256!("3333333333333333333333333333333333333333333333333333333333333333");
@@ -3,6 +3,7 @@
 use crate::{
     errors::{PipelineError, PipelineErrorKind},
     traits::{ChainProvider, L2ChainProvider},
+    types::LogFilter,
 };
 use alloc::{boxed::Box, string::ToString, sync::Arc, vec::Vec};
 use alloy_consensus::{Header, Receipt, TxEnvelope};
@@ -130,6 +131,17 @@ impl ChainProvider for TestChainProvider {
         }
     }
 
+    async fn may_contain_logs(
+        &mut self,
+        hash: B256,
+        filters: &[LogFilter],
+    ) -> Result<bool, Self::Error> {
+        // The receipts of blocks without a header are always fetched.
+        Ok(self.headers.iter().find(|(_, h)| h.hash_slow() == hash).is_none_or(|(_, header)| {
+            filters.iter().any(|filter| filter.matches_bloom(&header.logs_bloom))
+        }))
+    }
+
     async fn block_info_and_transactions_by_hash(
         &mut self,
         hash: B256,
@@ -4,7 +4,12 @@
 #[macro_export]
 macro_rules! frame {
     ($id:expr, $number:expr, $data:expr, $is_last:expr) => {
-        kona_protocol::Frame { id: [$id; 16], number: $number, data: $data, is_last: $is_last }
+        kona_protocol::Frame {
+            id: [$id; 16],
+            number: $number,
+            data: $data.into(),
+            is_last: $is_last,
+        }
     };
 }
 
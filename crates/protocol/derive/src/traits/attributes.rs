@@ -1,6 +1,6 @@
 //! Contains traits for working with payload attributes and their providers.
 
-use crate::PipelineResult;
+use crate::{PipelineResult, PipelineStateSize};
 use alloc::boxed::Box;
 use alloy_eips::BlockNumHash;
 use async_trait::async_trait;
@@ -17,6 +17,11 @@ pub trait AttributesProvider {
 
     /// Returns whether the current batch is the last in its span.
     fn is_last_in_span(&self) -> bool;
+
+    /// Returns the [`PipelineStateSize`] buffered by this stage and the stages before it.
+    fn state_size(&self) -> PipelineStateSize {
+        PipelineStateSize::default()
+    }
 }
 
 /// [`NextAttributes`] defines the interface for pulling attributes from
@@ -28,6 +33,11 @@ pub trait NextAttributes {
         &mut self,
         parent: L2BlockInfo,
     ) -> PipelineResult<OpAttributesWithParent>;
+
+    /// Returns the [`PipelineStateSize`] buffered by this stage and the stages before it.
+    fn state_size(&self) -> PipelineStateSize {
+        PipelineStateSize::default()
+    }
 }
 
 /// The [`AttributesBuilder`] is responsible for preparing [`OpPayloadAttributes`]
@@ -1,7 +1,7 @@
 //! Contains traits that describe the functionality of various data sources used in the derivation
 //! pipeline's stages.
 
-use crate::{PipelineErrorKind, PipelineResult};
+use crate::{BlobProviderError, PipelineErrorKind, PipelineResult};
 use alloc::{boxed::Box, fmt::Debug, string::ToString, vec::Vec};
 use alloy_eips::eip4844::{Blob, IndexedBlobHash};
 use alloy_primitives::{Address, Bytes};
@@ -23,6 +23,31 @@ pub trait BlobProvider {
     ) -> Result<Vec<Box<Blob>>, Self::Error>;
 }
 
+/// An optional [`BlobProvider`]. `None` stands in for the blob provider of chains that never post
+/// blobs, i.e. that never activate Ecotone, and fails all requests.
+#[async_trait]
+impl<B> BlobProvider for Option<B>
+where
+    B: BlobProvider + Send,
+    B::Error: From<BlobProviderError>,
+{
+    type Error = B::Error;
+
+    async fn get_blobs(
+        &mut self,
+        block_ref: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Box<Blob>>, Self::Error> {
+        match self {
+            Some(provider) => provider.get_blobs(block_ref, blob_hashes).await,
+            None => Err(BlobProviderError::Backend(
+                "No blob provider is configured, the chain does not post blobs".to_string(),
+            )
+            .into()),
+        }
+    }
+}
+
 /// Describes the functionality of a data source that can provide data availability information.
 #[async_trait]
 pub trait DataAvailabilityProvider {
@@ -10,6 +10,9 @@ pub use providers::{BatchValidationProviderDerive, ChainProvider, L2ChainProvide
 mod attributes;
 pub use attributes::{AttributesBuilder, AttributesProvider, NextAttributes};
 
+mod oracles;
+pub use oracles::{BlobOracle, ChainOracle, L2ChainOracle};
+
 mod data_sources;
 pub use data_sources::{BlobProvider, DataAvailabilityProvider};
 
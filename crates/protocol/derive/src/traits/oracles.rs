@@ -0,0 +1,78 @@
+//! Minimal data sources for proof environments, from which the pipeline's providers are adapted.
+//!
+//! The [`ChainProvider`], [`L2ChainProvider`] and [`BlobProvider`] traits describe what the
+//! pipeline needs, which is more than what a proof environment, e.g. an alternative FPVM or an
+//! embedded target, usually exposes. The oracle traits below only ask for the raw chain data, and
+//! are turned into providers by the [`OracleChainProvider`], [`OracleL2ChainProvider`] and
+//! [`OracleBlobProvider`] adapters, which derive the rest.
+//!
+//! [`ChainProvider`]: crate::ChainProvider
+//! [`L2ChainProvider`]: crate::L2ChainProvider
+//! [`BlobProvider`]: crate::BlobProvider
+//! [`OracleChainProvider`]: crate::OracleChainProvider
+//! [`OracleL2ChainProvider`]: crate::OracleL2ChainProvider
+//! [`OracleBlobProvider`]: crate::OracleBlobProvider
+
+use crate::PipelineErrorKind;
+use alloc::{boxed::Box, vec::Vec};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_eips::eip4844::{Blob, IndexedBlobHash};
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use core::fmt::Display;
+use kona_protocol::BlockInfo;
+use op_alloy_consensus::OpBlock;
+
+/// A source of raw L1 chain data, adapted into a [`ChainProvider`] by the
+/// [`OracleChainProvider`].
+///
+/// [`ChainProvider`]: crate::ChainProvider
+/// [`OracleChainProvider`]: crate::OracleChainProvider
+#[async_trait]
+pub trait ChainOracle {
+    /// The error type for the [`ChainOracle`].
+    type Error: Display + Into<PipelineErrorKind>;
+
+    /// Returns the L1 [`Header`] with the given hash.
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error>;
+
+    /// Returns the canonical L1 [`Header`] with the given number.
+    async fn header_by_number(&mut self, number: u64) -> Result<Header, Self::Error>;
+
+    /// Returns the receipts of the L1 block with the given hash.
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error>;
+
+    /// Returns the transactions of the L1 block with the given hash.
+    async fn transactions_by_hash(&mut self, hash: B256) -> Result<Vec<TxEnvelope>, Self::Error>;
+}
+
+/// A source of raw L2 chain data, adapted into an [`L2ChainProvider`] by the
+/// [`OracleL2ChainProvider`].
+///
+/// [`L2ChainProvider`]: crate::L2ChainProvider
+/// [`OracleL2ChainProvider`]: crate::OracleL2ChainProvider
+#[async_trait]
+pub trait L2ChainOracle {
+    /// The error type for the [`L2ChainOracle`].
+    type Error: Display + Into<PipelineErrorKind>;
+
+    /// Returns the canonical L2 [`OpBlock`] with the given number.
+    async fn l2_block_by_number(&mut self, number: u64) -> Result<OpBlock, Self::Error>;
+}
+
+/// A source of blobs, adapted into a [`BlobProvider`] by the [`OracleBlobProvider`].
+///
+/// [`BlobProvider`]: crate::BlobProvider
+/// [`OracleBlobProvider`]: crate::OracleBlobProvider
+#[async_trait]
+pub trait BlobOracle {
+    /// The error type for the [`BlobOracle`].
+    type Error: Display + Into<PipelineErrorKind>;
+
+    /// Returns the blob with the given [`IndexedBlobHash`], included in the given L1 block.
+    async fn blob(
+        &mut self,
+        block_ref: &BlockInfo,
+        blob_hash: &IndexedBlobHash,
+    ) -> Result<Box<Blob>, Self::Error>;
+}
@@ -6,7 +6,7 @@ use core::iter::Iterator;
 use kona_genesis::{RollupConfig, SystemConfig};
 use kona_protocol::{L2BlockInfo, OpAttributesWithParent};
 
-use crate::{OriginProvider, PipelineErrorKind, StepResult};
+use crate::{OriginProvider, PipelineErrorKind, PipelineStateSize, StepResult};
 
 /// This trait defines the interface for interacting with the derivation pipeline.
 #[async_trait]
@@ -20,6 +20,10 @@ pub trait Pipeline: OriginProvider + Iterator<Item = OpAttributesWithParent> {
     /// Returns the rollup config.
     fn rollup_config(&self) -> &RollupConfig;
 
+    /// Returns the [`PipelineStateSize`] of the in-memory state buffered by the pipeline's
+    /// stages, e.g. to keep it within a memory ceiling by [pruning][crate::Signal::Prune] it.
+    fn state_size(&self) -> PipelineStateSize;
+
     /// Returns the [`SystemConfig`] by L2 number.
     async fn system_config_by_number(
         &mut self,
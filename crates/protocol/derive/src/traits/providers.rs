@@ -1,6 +1,6 @@
 //! Chain providers for the derivation pipeline.
 
-use crate::PipelineErrorKind;
+use crate::{LogFilter, PipelineErrorKind};
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::{Header, Receipt, TxEnvelope};
 use alloy_primitives::B256;
@@ -26,6 +26,19 @@ pub trait ChainProvider {
     /// exist in the data source.
     async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error>;
 
+    /// Returns false if the block with the given hash certainly contains no log matching any of
+    /// the given [`LogFilter`]s, in which case fetching its receipts can be skipped.
+    ///
+    /// The default implementation checks the logs bloom of the block header.
+    async fn may_contain_logs(
+        &mut self,
+        hash: B256,
+        filters: &[LogFilter],
+    ) -> Result<bool, Self::Error> {
+        let header = self.header_by_hash(hash).await?;
+        Ok(filters.iter().any(|filter| filter.matches_bloom(&header.logs_bloom)))
+    }
+
     /// Returns the [`BlockInfo`] and list of [`TxEnvelope`]s from the given block hash.
     async fn block_info_and_transactions_by_hash(
         &mut self,
@@ -0,0 +1,72 @@
+//! Contains the [`LogFilter`], used to skip fetching the receipts of L1 blocks.
+
+use alloy_primitives::{Address, B256, Bloom, BloomInput};
+use kona_genesis::{CONFIG_UPDATE_TOPIC, RollupConfig};
+use kona_protocol::DEPOSIT_EVENT_ABI_HASH;
+
+/// A filter matching the logs emitted by an address with a given first topic.
+///
+/// The pipeline only reads the deposit and system config update logs out of the receipts of L1
+/// blocks. Before fetching the receipts of a block, it asks its [`ChainProvider`] whether the block
+/// may contain logs matching its filters, which is answered from the logs bloom of the block
+/// header at no extra cost.
+///
+/// [`ChainProvider`]: crate::ChainProvider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogFilter {
+    /// The address that emits the logs.
+    pub address: Address,
+    /// The first topic of the logs, i.e. the event signature.
+    pub topic: B256,
+}
+
+impl LogFilter {
+    /// Creates a new [`LogFilter`].
+    pub const fn new(address: Address, topic: B256) -> Self {
+        Self { address, topic }
+    }
+
+    /// Returns the [`LogFilter`] of the user deposit logs of the given [`RollupConfig`].
+    pub const fn deposits(config: &RollupConfig) -> Self {
+        Self::new(config.deposit_contract_address, DEPOSIT_EVENT_ABI_HASH)
+    }
+
+    /// Returns the [`LogFilter`] of the system config update logs of the given
+    /// [`RollupConfig`].
+    pub const fn system_config_updates(config: &RollupConfig) -> Self {
+        Self::new(config.l1_system_config_address, CONFIG_UPDATE_TOPIC)
+    }
+
+    /// Returns false if the block with the given logs bloom certainly contains no matching log.
+    ///
+    /// Blooms have false positives, so a block for which this returns true may still contain no
+    /// matching log.
+    pub fn matches_bloom(&self, bloom: &Bloom) -> bool {
+        bloom.contains_input(BloomInput::Raw(self.address.as_slice())) &&
+            bloom.contains_input(BloomInput::Raw(self.topic.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Log, address};
+
+    #[test]
+    fn test_log_filter_matches_bloom() {
+        let filter =
+            LogFilter::new(address!("1337000000000000000000000000000000000000"), B256::ZERO);
+        assert!(!filter.matches_bloom(&Bloom::default()));
+
+        let mut bloom = Bloom::default();
+        bloom.accrue_log(&Log::new_unchecked(
+            filter.address,
+            alloc::vec![filter.topic],
+            Default::default(),
+        ));
+        assert!(filter.matches_bloom(&bloom));
+
+        let other = LogFilter::new(filter.address, B256::with_last_byte(1));
+        assert!(!other.matches_bloom(&bloom));
+    }
+}
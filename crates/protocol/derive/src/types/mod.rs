@@ -1,7 +1,16 @@
 //! Primitive types for `kona-derive`.
 
+mod log_filter;
+pub use log_filter::LogFilter;
+
 mod results;
 pub use results::{PipelineResult, StepResult};
 
 mod signals;
 pub use signals::{ActivationSignal, ResetSignal, Signal};
+
+mod snapshot;
+pub use snapshot::DerivationSnapshot;
+
+mod state_size;
+pub use state_size::PipelineStateSize;
@@ -19,6 +19,9 @@ pub enum Signal {
     FlushChannel,
     /// Provide a new L1 block to the L1 traversal stage.
     ProvideBlock(BlockInfo),
+    /// Prune the oldest buffered channels, until at least the given number of bytes is freed or
+    /// no channel is left.
+    Prune(usize),
 }
 
 impl core::fmt::Display for Signal {
@@ -28,6 +31,7 @@ impl core::fmt::Display for Signal {
             Self::Activation(_) => write!(f, "activation"),
             Self::FlushChannel => write!(f, "flush_channel"),
             Self::ProvideBlock(_) => write!(f, "provide_block"),
+            Self::Prune(_) => write!(f, "prune"),
         }
     }
 }
@@ -40,6 +44,7 @@ impl Signal {
             Self::Activation(activation) => activation.with_system_config(system_config).signal(),
             Self::FlushChannel => Self::FlushChannel,
             Self::ProvideBlock(block) => Self::ProvideBlock(block),
+            Self::Prune(bytes) => Self::Prune(bytes),
         }
     }
 }
@@ -123,5 +128,6 @@ mod tests {
         );
 
         assert_eq!(Signal::FlushChannel.with_system_config(system_config), Signal::FlushChannel);
+        assert_eq!(Signal::Prune(1).with_system_config(system_config), Signal::Prune(1));
     }
 }
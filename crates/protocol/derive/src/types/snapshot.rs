@@ -0,0 +1,131 @@
+//! Contains the [`DerivationSnapshot`], a serializable starting condition of the derivation
+//! pipeline.
+
+use crate::{ChainProvider, L2ChainProvider, PipelineErrorKind, PipelineResult, ResetSignal};
+use alloc::sync::Arc;
+use alloy_primitives::B256;
+use kona_genesis::{RollupConfig, SystemConfig};
+use kona_protocol::{BatchValidationProvider, BlockInfo, L2BlockInfo};
+
+/// The starting condition of the derivation pipeline: the agreed L2 output, the L1 origin to
+/// derive from, and the [`SystemConfig`] at the agreed L2 block.
+///
+/// This is the starting condition of the fault-proof programs, so that a disputed range of L2
+/// blocks can be re-derived from its agreed output by any pipeline, e.g. in a challenger, without
+/// an execution layer synced to it. The snapshot is sent to the pipeline as a [`ResetSignal`], see
+/// [`Self::reset_signal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DerivationSnapshot {
+    /// The agreed output root of the L2 safe head.
+    pub agreed_output_root: B256,
+    /// The agreed L2 safe head, which derivation continues from.
+    pub l2_safe_head: L2BlockInfo,
+    /// The L1 block the pipeline starts reading from. It is the L1 origin of the safe head, walked
+    /// back by the channel timeout, so that the channels still open at the safe head are read
+    /// fully.
+    pub l1_origin: BlockInfo,
+    /// The [`SystemConfig`] at the L2 safe head.
+    pub system_config: SystemConfig,
+}
+
+impl DerivationSnapshot {
+    /// Captures the [`DerivationSnapshot`] of the L2 block with the given number, whose output
+    /// root is agreed to be `agreed_output_root`, from the given providers.
+    ///
+    /// The L1 origin is selected like the fault-proof programs do: the L1 origin of the safe head,
+    /// walked back by the channel timeout active at the safe head, but no further than the L1
+    /// genesis of the rollup.
+    pub async fn capture<L1, L2>(
+        cfg: Arc<RollupConfig>,
+        agreed_output_root: B256,
+        l2_safe_head_number: u64,
+        chain_provider: &mut L1,
+        l2_chain_provider: &mut L2,
+    ) -> PipelineResult<Self>
+    where
+        L1: ChainProvider + Send,
+        L2: L2ChainProvider + Send,
+        <L2 as BatchValidationProvider>::Error: Into<PipelineErrorKind>,
+    {
+        let l2_safe_head = l2_chain_provider
+            .l2_block_info_by_number(l2_safe_head_number)
+            .await
+            .map_err(Into::into)?;
+        let channel_timeout = cfg.channel_timeout(l2_safe_head.block_info.timestamp);
+        let l1_origin_number = l2_safe_head
+            .l1_origin
+            .number
+            .saturating_sub(channel_timeout)
+            .max(cfg.genesis.l1.number);
+        let l1_origin =
+            chain_provider.block_info_by_number(l1_origin_number).await.map_err(Into::into)?;
+        let system_config = l2_chain_provider
+            .system_config_by_number(l2_safe_head_number, cfg)
+            .await
+            .map_err(Into::into)?;
+        Ok(Self { agreed_output_root, l2_safe_head, l1_origin, system_config })
+    }
+
+    /// Returns the [`ResetSignal`] that initializes a pipeline to the snapshot.
+    pub const fn reset_signal(&self) -> ResetSignal {
+        ResetSignal {
+            l2_safe_head: self.l2_safe_head,
+            l1_origin: self.l1_origin,
+            system_config: Some(self.system_config),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestChainProvider, TestL2ChainProvider};
+    use alloc::vec;
+    use alloy_eips::BlockNumHash;
+    use alloy_primitives::b256;
+    use kona_genesis::ChainGenesis;
+
+    #[tokio::test]
+    async fn test_capture_snapshot() {
+        let cfg = Arc::new(RollupConfig {
+            channel_timeout: 50,
+            genesis: ChainGenesis {
+                l1: BlockNumHash { number: 10, ..Default::default() },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let output_root = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        let system_config = SystemConfig { gas_limit: 30_000_000, ..Default::default() };
+
+        let head = |number, l1_origin| L2BlockInfo {
+            block_info: BlockInfo { number, ..Default::default() },
+            l1_origin: BlockNumHash { number: l1_origin, ..Default::default() },
+            seq_num: 0,
+        };
+        let mut l2 = TestL2ChainProvider::new(
+            vec![head(100, 80), head(20, 30)],
+            vec![],
+            [(100, system_config), (20, system_config)].into_iter().collect(),
+        );
+        let mut l1 = TestChainProvider::default();
+        for number in [10, 30] {
+            l1.insert_block(number, BlockInfo { number, ..Default::default() });
+        }
+
+        // The L1 origin is walked back by the channel timeout.
+        let snapshot = DerivationSnapshot::capture(cfg.clone(), output_root, 100, &mut l1, &mut l2)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.agreed_output_root, output_root);
+        assert_eq!(snapshot.l1_origin.number, 30);
+        assert_eq!(snapshot.reset_signal().l2_safe_head, head(100, 80));
+        assert_eq!(snapshot.reset_signal().system_config, Some(system_config));
+
+        // But not past the L1 genesis.
+        let snapshot = DerivationSnapshot::capture(cfg, output_root, 20, &mut l1, &mut l2).await;
+        assert_eq!(snapshot.unwrap().l1_origin.number, 10);
+    }
+}
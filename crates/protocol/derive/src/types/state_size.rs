@@ -0,0 +1,108 @@
+//! Contains the [`PipelineStateSize`], the in-memory state buffered by the derivation pipeline.
+
+use kona_protocol::{Batch, BatchWithInclusionBlock, SingleBatch, SpanBatch};
+
+/// The in-memory state buffered by the stages of the derivation pipeline.
+///
+/// It is accumulated from the bottom stage up, each stage adding what it buffers to the size of
+/// the stages before it, so that the size returned by [`Pipeline::state_size`] covers the whole
+/// pipeline.
+///
+/// [`Pipeline::state_size`]: crate::Pipeline::state_size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineStateSize {
+    /// The number of bytes of the frames buffered by the frame queue.
+    pub frame_queue: usize,
+    /// The number of bytes of the frames buffered by the channel bank, or by the channel
+    /// assembler after Holocene.
+    pub channel_bank: usize,
+    /// The number of batches queued by the batch stages and the attributes queue. A span batch
+    /// counts as one batch per block.
+    pub batches: usize,
+    /// The number of bytes of the transactions of the queued batches.
+    pub batch_bytes: usize,
+}
+
+impl PipelineStateSize {
+    /// Returns the total number of bytes buffered by the pipeline.
+    pub const fn total_bytes(&self) -> usize {
+        self.frame_queue + self.channel_bank + self.batch_bytes
+    }
+
+    /// Returns the size with the given [`SingleBatch`]es queued on top.
+    pub fn with_single_batches<'a>(
+        mut self,
+        batches: impl IntoIterator<Item = &'a SingleBatch>,
+    ) -> Self {
+        for batch in batches {
+            self.batches += 1;
+            self.batch_bytes += batch.transactions.iter().map(|tx| tx.len()).sum::<usize>();
+        }
+        self
+    }
+
+    /// Returns the size with the given [`SpanBatch`]es queued on top.
+    pub fn with_span_batches<'a>(
+        mut self,
+        batches: impl IntoIterator<Item = &'a SpanBatch>,
+    ) -> Self {
+        for batch in batches {
+            self.batches += batch.batches.len();
+            self.batch_bytes += batch
+                .batches
+                .iter()
+                .flat_map(|element| element.transactions.iter())
+                .map(|tx| tx.len())
+                .sum::<usize>();
+        }
+        self
+    }
+
+    /// Returns the size with the given [`BatchWithInclusionBlock`]s queued on top.
+    pub fn with_batches<'a>(
+        self,
+        batches: impl IntoIterator<Item = &'a BatchWithInclusionBlock>,
+    ) -> Self {
+        batches.into_iter().fold(self, |size, batch| match &batch.batch {
+            Batch::Single(single) => size.with_single_batches([single]),
+            Batch::Span(span) => size.with_span_batches([span]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloy_primitives::Bytes;
+    use kona_protocol::{BlockInfo, SpanBatchElement};
+
+    #[test]
+    fn test_state_size_accumulates_batches() {
+        let single = SingleBatch {
+            transactions: vec![Bytes::from(vec![0; 10]), Bytes::from(vec![0; 5])],
+            ..Default::default()
+        };
+        let span = SpanBatch {
+            batches: vec![
+                SpanBatchElement {
+                    transactions: vec![Bytes::from(vec![0; 7])],
+                    ..Default::default()
+                },
+                SpanBatchElement::default(),
+            ],
+            ..Default::default()
+        };
+        let batches = [
+            BatchWithInclusionBlock::new(BlockInfo::default(), Batch::Single(single.clone())),
+            BatchWithInclusionBlock::new(BlockInfo::default(), Batch::Span(span)),
+        ];
+
+        let size = PipelineStateSize { frame_queue: 100, channel_bank: 200, ..Default::default() }
+            .with_batches(&batches)
+            .with_single_batches([&single]);
+        assert_eq!(size.batches, 4);
+        assert_eq!(size.batch_bytes, 37);
+        assert_eq!(size.total_bytes(), 337);
+    }
+}
@@ -307,6 +307,12 @@ impl RollupConfig {
             !self.is_interop_active(timestamp.saturating_sub(self.block_time))
     }
 
+    /// Returns true if the chain activates Ecotone at any time, after which batches may be posted
+    /// in blobs. Chains that never do are derived from calldata only, and need no L1 beacon API.
+    pub fn blobs_enabled(&self) -> bool {
+        self.is_ecotone_active(u64::MAX)
+    }
+
     /// Returns true if a DA Challenge proxy Address is provided in the rollup config and the
     /// address is not zero.
     pub fn is_alt_da_enabled(&self) -> bool {
@@ -575,6 +581,19 @@ mod tests {
         assert!(!config.is_holocene_active(9));
     }
 
+    #[test]
+    fn test_blobs_enabled() {
+        let mut config = RollupConfig::default();
+        assert!(!config.blobs_enabled());
+        config.hardforks.delta_time = Some(10);
+        assert!(!config.blobs_enabled());
+        config.hardforks.holocene_time = Some(20);
+        assert!(config.blobs_enabled());
+        config.hardforks.holocene_time = None;
+        config.hardforks.ecotone_time = Some(u64::MAX);
+        assert!(config.blobs_enabled());
+    }
+
     #[test]
     fn test_pectra_blob_schedule_active() {
         let mut config = RollupConfig::default();
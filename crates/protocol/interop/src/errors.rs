@@ -92,7 +92,7 @@ pub enum MessageGraphError<E: Debug> {
 pub type MessageGraphResult<T, P: InteropProvider> =
     core::result::Result<T, MessageGraphError<P::Error>>;
 
-/// An error type for the [SuperRoot] struct's serialization and deserialization.
+/// An error type for the [SuperRoot] struct's serialization, deserialization and computation.
 ///
 /// [SuperRoot]: crate::SuperRoot
 #[derive(Debug, Clone, Error)]
@@ -106,6 +106,15 @@ pub enum SuperRootError {
     /// Slice conversion error
     #[error("Slice conversion error: {0}")]
     SliceConversionError(#[from] core::array::TryFromSliceError),
+    /// The output root of a chain of the dependency set is missing
+    #[error("Missing output root for chain {0}")]
+    MissingOutputRoot(u64),
+    /// An output root was given for a chain outside of the dependency set
+    #[error("Output root given for chain {0}, outside of the dependency set")]
+    UnknownChain(u64),
+    /// Several output roots were given for the same chain
+    #[error("Duplicate output root for chain {0}")]
+    DuplicateOutputRoot(u64),
 }
 
 /// A [Result] alias for the [SuperRootError] type.
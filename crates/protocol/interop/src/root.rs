@@ -2,11 +2,12 @@
 //!
 //! Represents a snapshot of the state of the superchain at a given integer timestamp.
 
-use crate::{SUPER_ROOT_VERSION, SuperRootError, SuperRootResult};
+use crate::{DependencySet, SUPER_ROOT_VERSION, SuperRootError, SuperRootResult};
 use alloc::vec::Vec;
 use alloy_eips::BlockNumHash;
 use alloy_primitives::{B256, Bytes, U256, keccak256};
 use alloy_rlp::{Buf, BufMut};
+use kona_protocol::OutputRoot;
 
 /// The [SuperRoot] is the snapshot of the superchain at a given timestamp.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -27,6 +28,41 @@ impl SuperRoot {
         Self { timestamp, output_roots }
     }
 
+    /// Computes the [SuperRoot] of the given [DependencySet] at the given timestamp, from the
+    /// output roots of its chains at that timestamp.
+    ///
+    /// Exactly one output root must be given for every chain of the dependency set, and none for
+    /// chains outside of it, so that the commitment covers the whole set.
+    pub fn from_dependency_set(
+        timestamp: u64,
+        dependency_set: &DependencySet,
+        output_roots: impl IntoIterator<Item = OutputRootWithChain>,
+    ) -> SuperRootResult<Self> {
+        let mut output_roots = output_roots.into_iter().collect::<Vec<_>>();
+        output_roots.sort_by_key(|r| r.chain_id);
+
+        if let Some(pair) = output_roots.windows(2).find(|w| w[0].chain_id == w[1].chain_id) {
+            return Err(SuperRootError::DuplicateOutputRoot(pair[0].chain_id));
+        }
+        if let Some(root) =
+            output_roots.iter().find(|r| !dependency_set.dependencies.contains_key(&r.chain_id))
+        {
+            return Err(SuperRootError::UnknownChain(root.chain_id));
+        }
+        if output_roots.len() != dependency_set.dependencies.len() {
+            let missing = dependency_set
+                .dependencies
+                .keys()
+                .filter(|id| output_roots.binary_search_by_key(*id, |r| r.chain_id).is_err())
+                .min()
+                .copied()
+                .unwrap_or_default();
+            return Err(SuperRootError::MissingOutputRoot(missing));
+        }
+
+        Ok(Self { timestamp, output_roots })
+    }
+
     /// Decodes a [SuperRoot] from the given buffer.
     pub fn decode(buf: &mut &[u8]) -> SuperRootResult<Self> {
         if buf.is_empty() {
@@ -105,6 +141,18 @@ pub struct ChainRootInfo {
     pub pending: Bytes,
 }
 
+impl ChainRootInfo {
+    /// Creates a new [ChainRootInfo] for the given chain, committing to the canonical output root
+    /// and carrying the preimage of the pending output root.
+    pub fn new(chain_id: u64, canonical: &OutputRoot, pending: &OutputRoot) -> Self {
+        Self {
+            chain_id,
+            canonical: canonical.hash(),
+            pending: Bytes::copy_from_slice(&pending.encode()),
+        }
+    }
+}
+
 /// The super root response type.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -123,6 +171,26 @@ pub struct SuperRootOutput {
     pub chains: Vec<ChainRootInfo>,
 }
 
+impl SuperRootOutput {
+    /// Creates a new [SuperRootOutput] committing to the given [SuperRoot].
+    ///
+    /// The chain root infos are sorted by chain ID, like the output roots of the [SuperRoot].
+    pub fn new(
+        super_root: &SuperRoot,
+        cross_safe_derived_from: BlockNumHash,
+        mut chains: Vec<ChainRootInfo>,
+    ) -> Self {
+        chains.sort_by_key(|c| c.chain_id);
+        Self {
+            cross_safe_derived_from,
+            timestamp: super_root.timestamp,
+            super_root: super_root.hash(),
+            version: SUPER_ROOT_VERSION,
+            chains,
+        }
+    }
+}
+
 /// A wrapper around an output root hash with the chain ID it belongs to.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -145,8 +213,35 @@ impl OutputRootWithChain {
 mod test {
     use crate::{SUPER_ROOT_VERSION, errors::SuperRootError};
 
-    use super::{OutputRootWithChain, SuperRoot};
-    use alloy_primitives::{B256, b256};
+    use super::{ChainRootInfo, OutputRootWithChain, SuperRoot, SuperRootOutput};
+    use crate::{ChainDependency, DependencySet};
+    use alloy_eips::BlockNumHash;
+    use alloy_primitives::{B256, Bytes, b256, bytes, keccak256};
+    use kona_protocol::OutputRoot;
+
+    fn dependency_set(chain_ids: &[u64]) -> DependencySet {
+        DependencySet {
+            dependencies: chain_ids.iter().map(|id| (*id, ChainDependency {})).collect(),
+            override_message_expiry_window: None,
+        }
+    }
+
+    /// The output root of the spec test vectors.
+    fn canonical() -> OutputRoot {
+        OutputRoot::from_parts(
+            B256::left_padding_from(&[0xbe, 0xef]),
+            B256::left_padding_from(&[0xba, 0xbe]),
+            B256::left_padding_from(&[0xc0, 0xde]),
+        )
+    }
+
+    fn output_root(seed: u8) -> OutputRoot {
+        OutputRoot::from_parts(
+            B256::left_padding_from(&[seed, 0x01]),
+            B256::left_padding_from(&[seed, 0x02]),
+            B256::left_padding_from(&[seed, 0x03]),
+        )
+    }
 
     #[test]
     fn test_super_root_sorts_outputs() {
@@ -241,4 +336,96 @@ mod test {
         super_root.encode(&mut rlp_buf);
         assert_eq!(super_root, SuperRoot::decode(&mut rlp_buf.as_slice()).unwrap());
     }
+
+    #[test]
+    fn test_super_root_from_dependency_set() {
+        // The super root commitment of the spec: the version byte, the timestamp as a big-endian
+        // u64, then the chain ID as a big-endian u256 and the output root of each chain, by
+        // ascending chain ID.
+        const PREIMAGE: Bytes = bytes!(
+            "01000000000000000a000000000000000000000000000000000000000000000000000000000000000a0c39fb6b07cf6694b13e63e59f7b15255be1c93a4d6d3e0da6c99729647c0d1100000000000000000000000000000000000000000000000000000000000021050000000000000000000000000000000000000000000000000000000000000000"
+        );
+
+        let super_root = SuperRoot::from_dependency_set(
+            10,
+            &dependency_set(&[10, 8453]),
+            vec![
+                OutputRootWithChain::new(8453, B256::ZERO),
+                OutputRootWithChain::new(10, canonical().hash()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(super_root.hash(), keccak256(PREIMAGE));
+    }
+
+    #[test]
+    fn test_super_root_from_dependency_set_static_hash() {
+        const EXPECTED: B256 =
+            b256!("0980033cbf4337f614a2401ab7efbfdc66ab647812f1c98d891d92ddfb376541");
+
+        let super_root = SuperRoot::from_dependency_set(
+            10,
+            &dependency_set(&[1, 2]),
+            vec![OutputRootWithChain::new(2, B256::ZERO), OutputRootWithChain::new(1, B256::ZERO)],
+        )
+        .unwrap();
+        assert_eq!(super_root.hash(), EXPECTED);
+    }
+
+    #[test]
+    fn test_super_root_from_dependency_set_mismatch() {
+        let depset = dependency_set(&[1, 2, 3]);
+
+        let err = SuperRoot::from_dependency_set(
+            10,
+            &depset,
+            vec![OutputRootWithChain::new(3, B256::ZERO), OutputRootWithChain::new(1, B256::ZERO)],
+        )
+        .unwrap_err();
+        assert!(matches!(err, SuperRootError::MissingOutputRoot(2)));
+
+        let err = SuperRoot::from_dependency_set(
+            10,
+            &depset,
+            [1, 2, 3, 4].map(|id| OutputRootWithChain::new(id, B256::ZERO)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, SuperRootError::UnknownChain(4)));
+
+        let err = SuperRoot::from_dependency_set(
+            10,
+            &depset,
+            [1, 2, 2, 3].map(|id| OutputRootWithChain::new(id, B256::ZERO)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, SuperRootError::DuplicateOutputRoot(2)));
+    }
+
+    #[test]
+    fn test_super_root_output() {
+        // The hash and encoding of the output root of `canonical`, from the spec.
+        const CANONICAL: B256 =
+            b256!("0c39fb6b07cf6694b13e63e59f7b15255be1c93a4d6d3e0da6c99729647c0d11");
+        const PENDING: Bytes = bytes!(
+            "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000beef000000000000000000000000000000000000000000000000000000000000babe000000000000000000000000000000000000000000000000000000000000c0de"
+        );
+
+        let chains = vec![
+            ChainRootInfo::new(2, &output_root(2), &output_root(2)),
+            ChainRootInfo::new(1, &canonical(), &canonical()),
+        ];
+        assert_eq!(chains[1].canonical, CANONICAL);
+        assert_eq!(chains[1].pending, PENDING);
+
+        let super_root = SuperRoot::from_dependency_set(
+            10,
+            &dependency_set(&[1, 2]),
+            chains.iter().map(|c| OutputRootWithChain::new(c.chain_id, c.canonical)),
+        )
+        .unwrap();
+        let output = SuperRootOutput::new(&super_root, BlockNumHash::default(), chains);
+        assert_eq!(output.super_root, super_root.hash());
+        assert_eq!(output.version, SUPER_ROOT_VERSION);
+        assert_eq!(output.chains[0].chain_id, 1);
+    }
 }
@@ -0,0 +1,203 @@
+#![allow(missing_docs)]
+//! Contains benchmarks for parsing [Frame]s out of batcher transactions, and assembling them into
+//! [Channel]s, for frames copied out of the transaction data ([Frame::parse_frames]) and for
+//! frames sliced out of it ([Frame::parse_frames_bytes]).
+//!
+//! Each case is measured both in time, and in bytes allocated with [AllocatedBytes].
+
+use alloy_primitives::Bytes;
+use criterion::{
+    Criterion, Throughput, criterion_group, criterion_main,
+    measurement::{Measurement, ValueFormatter},
+};
+use kona_protocol::{BlockInfo, Channel, DERIVATION_VERSION_0, Frame};
+use pprof::criterion::{Output, PProfProfiler};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The number of batcher transactions read in each iteration, a multiple of the number of frames
+/// per channel of each case.
+const TRANSACTIONS: usize = 120;
+
+/// The size of the data of each frame, about the size of a blob.
+const FRAME_SIZE: usize = 128 * 1024;
+
+/// The number of bytes allocated through the [GlobalAlloc].
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// A [GlobalAlloc] that counts the bytes allocated through it.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// A [Measurement] of the bytes allocated by the benchmarked routine.
+struct AllocatedBytes;
+
+impl Measurement for AllocatedBytes {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        ALLOCATED_BYTES.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - start
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self
+    }
+}
+
+impl ValueFormatter for AllocatedBytes {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        let (factor, unit) = if typical_value < 1024.0 {
+            (1.0, "B")
+        } else if typical_value < 1024.0 * 1024.0 {
+            (1024.0, "KiB")
+        } else {
+            (1024.0 * 1024.0, "MiB")
+        };
+        values.iter_mut().for_each(|v| *v /= factor);
+        unit
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        _throughput: &Throughput,
+        _values: &mut [f64],
+    ) -> &'static str {
+        "B"
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "B"
+    }
+}
+
+/// Returns the data of batcher transactions that each carry one frame, of channels made of the
+/// given number of frames.
+fn batcher_transactions(frames_per_channel: usize) -> Vec<Bytes> {
+    (0..TRANSACTIONS)
+        .map(|i| {
+            let channel = i / frames_per_channel;
+            let number = i % frames_per_channel;
+            let mut id = [0u8; 16];
+            id[..8].copy_from_slice(&(channel as u64).to_be_bytes());
+            let frame = Frame::new(
+                id,
+                number as u16,
+                vec![i as u8; FRAME_SIZE],
+                number == frames_per_channel - 1,
+            );
+            let mut data = vec![DERIVATION_VERSION_0];
+            data.extend_from_slice(&frame.encode());
+            data.into()
+        })
+        .collect()
+}
+
+/// Reads the frames of the given batcher transactions into channels, and returns the total size
+/// of the channel data.
+fn read_channels(
+    transactions: &[Bytes],
+    parse: impl Fn(&Bytes) -> Vec<Frame>,
+    frames_per_channel: usize,
+) -> usize {
+    let block = BlockInfo::default();
+    let mut total = 0;
+    for chunk in transactions.chunks(frames_per_channel) {
+        let mut channel: Option<Channel> = None;
+        for frame in chunk.iter().flat_map(&parse) {
+            let channel = channel.get_or_insert_with(|| Channel::new(frame.id, block));
+            channel.add_frame(frame, block).expect("adds frame");
+        }
+        let channel = channel.expect("channel has frames");
+        assert!(channel.is_ready());
+        total += channel.frame_data().expect("channel has frame data").len();
+    }
+    total
+}
+
+/// Benchmarks reading the frames of batcher transactions into channels, in the given group.
+fn bench_frames<M: Measurement>(c: &mut Criterion<M>, group: &str) {
+    let mut g = c.benchmark_group(group);
+    g.sample_size(10);
+
+    for frames_per_channel in [1, 6] {
+        let transactions = batcher_transactions(frames_per_channel);
+
+        g.bench_function(format!("Copied - {frames_per_channel} frame(s) per channel"), |b| {
+            b.iter(|| {
+                read_channels(
+                    &transactions,
+                    |data| Frame::parse_frames(data).expect("parses frames"),
+                    frames_per_channel,
+                )
+            });
+        });
+
+        g.bench_function(format!("Zero-copy - {frames_per_channel} frame(s) per channel"), |b| {
+            b.iter(|| {
+                read_channels(
+                    &transactions,
+                    |data| Frame::parse_frames_bytes(data).expect("parses frames"),
+                    frames_per_channel,
+                )
+            });
+        });
+    }
+}
+
+fn frames(c: &mut Criterion) {
+    bench_frames(c, "frames");
+}
+
+fn frame_allocations(c: &mut Criterion<AllocatedBytes>) {
+    bench_frames(c, "frame allocations");
+}
+
+criterion_group! {
+    name = frame_benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = frames
+}
+criterion_group! {
+    name = frame_allocation_benches;
+    config = Criterion::default().with_measurement(AllocatedBytes);
+    targets = frame_allocations
+}
+criterion_main!(frame_benches, frame_allocation_benches);
@@ -2,9 +2,32 @@
 
 use crate::{BlockInfo, L2BlockInfo};
 use alloc::vec;
+use alloy_primitives::{B256, keccak256};
 use op_alloy_consensus::OpTxType;
 use op_alloy_rpc_types_engine::OpPayloadAttributes;
 
+/// A stable identifier of the lifecycle of an L2 block, from the derivation or sequencing of its
+/// [`OpAttributesWithParent`], through the engine tasks that build and import it, to its
+/// publication over gossip.
+///
+/// The id is derived from the parent hash and the timestamp of the block, so that it is the same
+/// on every node, and can be recomputed from the built payload with [`CorrelationId::new`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, derive_more::Display)]
+#[display("{_0:016x}")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CorrelationId(pub u64);
+
+impl CorrelationId {
+    /// Returns the [`CorrelationId`] of the block with the given parent hash and timestamp.
+    pub fn new(parent_hash: B256, timestamp: u64) -> Self {
+        let mut preimage = [0u8; 40];
+        preimage[..32].copy_from_slice(parent_hash.as_slice());
+        preimage[32..].copy_from_slice(&timestamp.to_be_bytes());
+        let hash = keccak256(preimage);
+        Self(u64::from_be_bytes(hash.0[..8].try_into().expect("slice of length 8")))
+    }
+}
+
 /// Optimism Payload Attributes with parent block reference and the L1 origin block.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -36,6 +59,11 @@ impl OpAttributesWithParent {
         self.parent.block_info.number.saturating_add(1)
     }
 
+    /// Returns the [`CorrelationId`] of the block built from the payload attributes.
+    pub fn correlation_id(&self) -> CorrelationId {
+        CorrelationId::new(self.parent.block_info.hash, self.inner.payload_attributes.timestamp)
+    }
+
     /// Consumes `self` and returns the inner [`OpPayloadAttributes`].
     pub fn take_inner(self) -> OpPayloadAttributes {
         self.inner
@@ -90,6 +118,7 @@ impl OpAttributesWithParent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     #[test]
     fn test_op_attributes_with_parent() {
@@ -104,4 +133,22 @@ mod tests {
         assert_eq!(op_attributes_with_parent.parent(), &parent);
         assert_eq!(op_attributes_with_parent.is_last_in_span(), is_last_in_span);
     }
+
+    #[test]
+    fn test_correlation_id() {
+        let mut attributes = OpAttributesWithParent::new(
+            OpPayloadAttributes::default(),
+            L2BlockInfo::default(),
+            BlockInfo::default(),
+            false,
+        );
+        attributes.inner.payload_attributes.timestamp = 2;
+        let id = attributes.correlation_id();
+        assert_eq!(id, CorrelationId::new(B256::ZERO, 2));
+        assert_eq!(id, attributes.as_deposits_only().correlation_id());
+        assert_eq!(id.to_string().len(), 16);
+
+        attributes.inner.payload_attributes.timestamp = 4;
+        assert_ne!(id, attributes.correlation_id());
+    }
 }
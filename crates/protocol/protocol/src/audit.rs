@@ -0,0 +1,168 @@
+//! Contains the [`MemoryAudit`], which tracks the peak memory held by the buffers of the node.
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use tracing::warn;
+
+/// A buffer whose memory is tracked by a [`MemoryAudit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum MemoryComponent {
+    /// The frames of the channels buffered by the channel bank, or by the channel assembler
+    /// after Holocene.
+    #[display("channel bank")]
+    ChannelBank,
+    /// The transactions of the batch buffered by the attributes queue.
+    #[display("attributes queue")]
+    AttributesQueue,
+    /// The unsafe payloads buffered ahead of the unsafe head.
+    #[display("unsafe payload buffer")]
+    UnsafePayloads,
+}
+
+impl MemoryComponent {
+    /// All tracked components.
+    pub const ALL: [Self; 3] = [Self::ChannelBank, Self::AttributesQueue, Self::UnsafePayloads];
+
+    /// Returns the index of the component in the counters of a [`MemoryAudit`].
+    const fn index(self) -> usize {
+        match self {
+            Self::ChannelBank => 0,
+            Self::AttributesQueue => 1,
+            Self::UnsafePayloads => 2,
+        }
+    }
+}
+
+/// The memory held by a [`MemoryComponent`], as recorded by a [`MemoryAudit`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The number of bytes currently held.
+    pub current: usize,
+    /// The largest number of bytes held so far.
+    pub peak: usize,
+    /// The ceiling configured for the component, if any.
+    pub ceiling: Option<usize>,
+}
+
+/// An instrumentation handle that tracks the memory held by the channel bank, the attributes
+/// queue and the unsafe payload buffer, and checks their peaks against configured ceilings.
+///
+/// The components record their size, by explicit accounting of the bytes they buffer, into the
+/// audit they were given, e.g. with `PipelineBuilder::memory_audit`. Clones share the same
+/// counters, so that a test can hand an audit to a node and [check][Self::check] it once the
+/// scenario has run, failing on unbounded growth.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryAudit {
+    /// The counters shared between clones.
+    inner: Arc<MemoryAuditInner>,
+}
+
+/// The counters of a [`MemoryAudit`], indexed by [`MemoryComponent`].
+#[derive(Debug, Default)]
+struct MemoryAuditInner {
+    current: [AtomicUsize; 3],
+    peak: [AtomicUsize; 3],
+    /// The ceilings, with `0` standing for no ceiling.
+    ceiling: [AtomicUsize; 3],
+}
+
+impl MemoryAudit {
+    /// Creates a new [`MemoryAudit`], with no ceilings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of bytes the given component must not exceed.
+    pub fn with_ceiling(self, component: MemoryComponent, bytes: usize) -> Self {
+        self.inner.ceiling[component.index()].store(bytes.max(1), Ordering::Relaxed);
+        self
+    }
+
+    /// Records the number of bytes currently held by the given component.
+    pub fn record(&self, component: MemoryComponent, bytes: usize) {
+        let index = component.index();
+        self.inner.current[index].store(bytes, Ordering::Relaxed);
+        let peak = self.inner.peak[index].fetch_max(bytes, Ordering::Relaxed);
+        if bytes > peak {
+            if let Some(ceiling) = self.usage(component).ceiling.filter(|c| bytes > *c) {
+                warn!(
+                    target: "memory_audit",
+                    %component,
+                    bytes,
+                    ceiling,
+                    "Memory ceiling exceeded"
+                );
+            }
+        }
+    }
+
+    /// Returns the memory held by the given component.
+    pub fn usage(&self, component: MemoryComponent) -> MemoryUsage {
+        let index = component.index();
+        let ceiling = self.inner.ceiling[index].load(Ordering::Relaxed);
+        MemoryUsage {
+            current: self.inner.current[index].load(Ordering::Relaxed),
+            peak: self.inner.peak[index].load(Ordering::Relaxed),
+            ceiling: (ceiling != 0).then_some(ceiling),
+        }
+    }
+
+    /// Checks that the peak of every component stayed within its ceiling.
+    pub fn check(&self) -> Result<(), MemoryAuditError> {
+        for component in MemoryComponent::ALL {
+            let usage = self.usage(component);
+            if let Some(ceiling) = usage.ceiling.filter(|c| usage.peak > *c) {
+                return Err(MemoryAuditError { component, peak: usage.peak, ceiling });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A [`MemoryComponent`] whose peak exceeded its ceiling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Peak memory of the {component} exceeded its ceiling: {peak} > {ceiling} bytes")]
+pub struct MemoryAuditError {
+    /// The component.
+    pub component: MemoryComponent,
+    /// The peak number of bytes held by the component.
+    pub peak: usize,
+    /// The ceiling of the component.
+    pub ceiling: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_audit_tracks_peak() {
+        let audit = MemoryAudit::new();
+        audit.record(MemoryComponent::ChannelBank, 100);
+        audit.record(MemoryComponent::ChannelBank, 40);
+        assert_eq!(
+            audit.usage(MemoryComponent::ChannelBank),
+            MemoryUsage { current: 40, peak: 100, ceiling: None }
+        );
+        assert_eq!(audit.usage(MemoryComponent::UnsafePayloads), MemoryUsage::default());
+        assert_eq!(audit.check(), Ok(()));
+    }
+
+    #[test]
+    fn test_memory_audit_checks_ceiling() {
+        let audit = MemoryAudit::new().with_ceiling(MemoryComponent::AttributesQueue, 64);
+        audit.record(MemoryComponent::AttributesQueue, 64);
+        assert_eq!(audit.check(), Ok(()));
+
+        audit.record(MemoryComponent::AttributesQueue, 65);
+        audit.record(MemoryComponent::AttributesQueue, 0);
+        assert_eq!(
+            audit.check(),
+            Err(MemoryAuditError {
+                component: MemoryComponent::AttributesQueue,
+                peak: 65,
+                ceiling: 64
+            })
+        );
+    }
+}
@@ -1,8 +1,10 @@
 //! Span Batch Element
 
-use crate::SingleBatch;
+use crate::{BatchTransactionError, SingleBatch};
 use alloc::vec::Vec;
 use alloy_primitives::Bytes;
+use kona_genesis::RollupConfig;
+use op_alloy_consensus::OpTxType;
 
 /// MAX_SPAN_BATCH_ELEMENTS is the maximum number of blocks, transactions in total,
 /// or transaction per block allowed in a span batch.
@@ -31,9 +33,36 @@ impl From<SingleBatch> for SpanBatchElement {
     }
 }
 
+impl SpanBatchElement {
+    /// Checks that the transactions of the element are not empty, do not contain any deposits,
+    /// and do not contain any EIP-7702 transactions before Isthmus. Returns the first invalid
+    /// transaction, if any.
+    ///
+    /// The check only depends on the element itself, so that the elements of a span batch can be
+    /// checked independently from one another.
+    pub fn check_transactions(&self, cfg: &RollupConfig) -> Result<(), BatchTransactionError> {
+        let isthmus = cfg.is_isthmus_active(self.timestamp);
+        for (i, tx) in self.transactions.iter().enumerate() {
+            match tx.as_ref().first() {
+                None => return Err(BatchTransactionError::Empty(i)),
+                Some(&ty) if ty == OpTxType::Deposit as u8 => {
+                    return Err(BatchTransactionError::Deposit(i));
+                }
+                Some(&ty) if !isthmus && ty == OpTxType::Eip7702 as u8 => {
+                    return Err(BatchTransactionError::Eip7702(i));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_primitives::bytes;
+    use kona_genesis::HardForkConfig;
     use proptest::{collection::vec, prelude::any, proptest};
 
     proptest! {
@@ -53,4 +82,29 @@ mod tests {
             assert_eq!(span_batch_element.transactions, transactions);
         }
     }
+
+    #[test]
+    fn test_check_transactions() {
+        let cfg = RollupConfig {
+            hardforks: HardForkConfig { isthmus_time: Some(10), ..Default::default() },
+            ..Default::default()
+        };
+        let element =
+            |timestamp, transactions| SpanBatchElement { epoch_num: 0, timestamp, transactions };
+
+        assert_eq!(element(0, vec![]).check_transactions(&cfg), Ok(()));
+        assert_eq!(
+            element(0, vec![bytes!("02"), Bytes::new()]).check_transactions(&cfg),
+            Err(BatchTransactionError::Empty(1))
+        );
+        assert_eq!(
+            element(10, vec![bytes!("7e00")]).check_transactions(&cfg),
+            Err(BatchTransactionError::Deposit(0))
+        );
+        assert_eq!(
+            element(0, vec![bytes!("02"), bytes!("04")]).check_transactions(&cfg),
+            Err(BatchTransactionError::Eip7702(1))
+        );
+        assert_eq!(element(10, vec![bytes!("02"), bytes!("04")]).check_transactions(&cfg), Ok(()));
+    }
 }
@@ -20,6 +20,22 @@ pub enum SpanBatchError {
     Decoding(#[from] SpanDecodingError),
 }
 
+/// An invalid transaction in the data of a batch element.
+#[derive(Debug, thiserror::Error, Clone, Copy, PartialEq, Eq)]
+pub enum BatchTransactionError {
+    /// The transaction data is empty.
+    #[error("transaction data must not be empty, but found empty tx, tx_index: {0}")]
+    Empty(usize),
+    /// The transaction is a deposit.
+    #[error(
+        "sequencers may not embed any deposits into batch data, but found tx that has one, tx_index: {0}"
+    )]
+    Deposit(usize),
+    /// The transaction is an EIP-7702 transaction, before Isthmus.
+    #[error("EIP-7702 transactions are not supported pre-isthmus. tx_index: {0}")]
+    Eip7702(usize),
+}
+
 /// An error encoding a batch.
 #[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
 pub enum BatchEncodingError {
@@ -25,7 +25,12 @@ mod inclusion;
 pub use inclusion::BatchWithInclusionBlock;
 
 mod errors;
-pub use errors::{BatchDecodingError, BatchEncodingError, SpanBatchError, SpanDecodingError};
+pub use errors::{
+    BatchDecodingError, BatchEncodingError, BatchTransactionError, SpanBatchError,
+    SpanDecodingError,
+};
+
+mod parallel;
 
 mod bits;
 pub use bits::SpanBatchBits;
@@ -0,0 +1,29 @@
+//! Parallel validation of the contents of span batches.
+//!
+//! Span batches carry up to thousands of blocks and transactions, and validating them dominates
+//! the CPU time of derivation during catch-up sync. With the `rayon` feature, the checks that are
+//! independent from one element to the next are spread over the rayon thread pool, while the
+//! decisions depending on the order of the elements are still taken sequentially.
+
+use alloc::vec::Vec;
+
+/// The minimum number of items from which they are processed in parallel. Below it, the
+/// overhead of the thread pool outweighs the gain.
+#[cfg(feature = "rayon")]
+pub(crate) const PARALLEL_THRESHOLD: usize = 32;
+
+/// Maps the items with `f`, preserving their order. The items are processed in parallel if the
+/// `rayon` feature is enabled, and there are at least [`PARALLEL_THRESHOLD`] of them.
+pub(crate) fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    if items.len() >= PARALLEL_THRESHOLD {
+        use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+        return items.par_iter().map(f).collect();
+    }
+    items.iter().map(f).collect()
+}
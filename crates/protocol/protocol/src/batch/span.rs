@@ -4,13 +4,12 @@ use alloc::vec::Vec;
 use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::FixedBytes;
 use kona_genesis::RollupConfig;
-use op_alloy_consensus::OpTxType;
 use tracing::{info, warn};
 
 use crate::{
     BatchValidationProvider, BatchValidity, BlockInfo, L2BlockInfo, RawSpanBatch, SingleBatch,
     SpanBatchBits, SpanBatchElement, SpanBatchError, SpanBatchPayload, SpanBatchPrefix,
-    SpanBatchTransactions,
+    SpanBatchTransactions, batch::parallel,
 };
 
 /// Container of the inputs required to build a span of L2 blocks in derived form.
@@ -193,6 +192,14 @@ impl SpanBatch {
         let starting_epoch_num = self.starting_epoch_num();
         let parent_block = parent_block.expect("parent_block must be Some");
 
+        // The transactions of the elements are checked independently from one another, in
+        // parallel on large span batches. The checks are only acted upon in order below, so that
+        // the outcome is the same as checking them one element at a time.
+        let safe_timestamp = l2_safe_head.block_info.timestamp;
+        let tx_checks = parallel::map(&self.batches, |batch| {
+            (batch.timestamp > safe_timestamp).then(|| batch.check_transactions(cfg))
+        });
+
         let mut origin_index = 0;
         let mut origin_advanced = starting_epoch_num == parent_block.l1_origin.number + 1;
         for (i, batch) in self.batches.iter().enumerate() {
@@ -265,29 +272,9 @@ impl SpanBatch {
             }
 
             // Check that the transactions are not empty and do not contain any deposits.
-            for (i, tx) in batch.transactions.iter().enumerate() {
-                if tx.is_empty() {
-                    warn!(
-                        "transaction data must not be empty, but found empty tx, tx_index: {}",
-                        i
-                    );
-                    return BatchValidity::Drop;
-                }
-                if tx.as_ref().first() == Some(&(OpTxType::Deposit as u8)) {
-                    warn!(
-                        "sequencers may not embed any deposits into batch data, but found tx that has one, tx_index: {}",
-                        i
-                    );
-                    return BatchValidity::Drop;
-                }
-
-                // If isthmus is not active yet and the transaction is a 7702, drop the batch.
-                if !cfg.is_isthmus_active(batch.timestamp) &&
-                    tx.as_ref().first() == Some(&(OpTxType::Eip7702 as u8))
-                {
-                    warn!("EIP-7702 transactions are not supported pre-isthmus. tx_index: {}", i);
-                    return BatchValidity::Drop;
-                }
+            if let Some(Err(e)) = tx_checks[i] {
+                warn!("{e}");
+                return BatchValidity::Drop;
             }
         }
 
@@ -518,7 +505,7 @@ mod tests {
     use alloy_eips::BlockNumHash;
     use alloy_primitives::{Bytes, b256};
     use kona_genesis::{ChainGenesis, HardForkConfig};
-    use op_alloy_consensus::OpBlock;
+    use op_alloy_consensus::{OpBlock, OpTxType};
     use tracing::Level;
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -2042,4 +2029,75 @@ mod tests {
         );
         assert!(trace_store.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_check_batch_large_batch_txs() {
+        let trace_store: TraceStorage = Default::default();
+        let layer = CollectingLayer::new(trace_store.clone());
+        tracing_subscriber::Registry::default().with(layer).init();
+
+        let cfg = RollupConfig {
+            seq_window_size: 100,
+            max_sequencer_drift: 100,
+            hardforks: HardForkConfig { delta_time: Some(0), ..Default::default() },
+            block_time: 10,
+            ..Default::default()
+        };
+        let l1_block_hash =
+            b256!("3333333333333333333333333333333333333333000000000000000000000000");
+        let block =
+            BlockInfo { number: 11, timestamp: 10, hash: l1_block_hash, ..Default::default() };
+        let second_block =
+            BlockInfo { number: 12, timestamp: 21, hash: l1_block_hash, ..Default::default() };
+        let l1_blocks = vec![block, second_block];
+        let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
+        let l2_safe_head = L2BlockInfo {
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
+            l1_origin: BlockNumHash { number: 10, ..Default::default() },
+            ..Default::default()
+        };
+        let inclusion_block = BlockInfo { number: 50, ..Default::default() };
+        let mut fetcher: TestBatchValidator = TestBatchValidator::default();
+        let batch = |invalid: &[(usize, Bytes)]| {
+            let mut batches = (0..64)
+                .map(|_| SpanBatchElement {
+                    epoch_num: 11,
+                    timestamp: 20,
+                    transactions: vec![Bytes::from_static(&[EIP1559_TX_TYPE_ID])],
+                })
+                .collect::<Vec<_>>();
+            for (i, tx) in invalid {
+                batches[*i].transactions.push(tx.clone());
+            }
+            SpanBatch {
+                batches,
+                parent_check: FixedBytes::<20>::from_slice(&parent_hash[..20]),
+                l1_origin_check: FixedBytes::<20>::from_slice(&l1_block_hash[..20]),
+                ..Default::default()
+            }
+        };
+
+        let valid = batch(&[]);
+        assert_eq!(
+            valid.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
+            BatchValidity::Accept
+        );
+        let invalid =
+            batch(&[(40, Bytes::new()), (50, Bytes::copy_from_slice(&[OpTxType::Deposit as u8]))]);
+        assert_eq!(
+            invalid
+                .check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher)
+                .await,
+            BatchValidity::Drop
+        );
+        // The first invalid transaction is reported, regardless of the order of the checks.
+        let logs = trace_store.get_by_level(Level::WARN);
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].contains("found empty tx"));
+    }
 }
@@ -3,7 +3,7 @@
 
 use crate::{
     MAX_SPAN_BATCH_ELEMENTS, SpanBatchBits, SpanBatchError, SpanBatchTransactionData,
-    SpanDecodingError, read_tx_data,
+    SpanDecodingError, batch::parallel, read_tx_data,
 };
 use alloc::vec::Vec;
 use alloy_consensus::{Transaction, TxEnvelope, TxType};
@@ -11,6 +11,10 @@ use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::{Address, Bytes, Signature, U256, bytes};
 use alloy_rlp::{Buf, Decodable, Encodable};
 
+/// The inputs to sign a span batch transaction: its data, nonce, gas limit, `to` address,
+/// signature, and whether it is replay protected.
+type TxInputs = (SpanBatchTransactionData, u64, u64, Option<Address>, Signature, bool);
+
 /// This struct contains the decoded information for transactions in a span batch.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct SpanBatchTransactions {
@@ -229,51 +233,86 @@ impl SpanBatchTransactions {
     }
 
     /// Retrieve all of the raw transactions from the [SpanBatchTransactions].
+    ///
+    /// Decoding and signing the transactions is the bulk of the work, and is done in parallel on
+    /// large span batches with the `rayon` feature. Only the assignment of the `to` addresses and
+    /// protected bits, which depends on the preceding transactions, is sequential. Either way, the
+    /// error of the first invalid transaction is returned.
     pub fn full_txs(&self, chain_id: u64) -> Result<Vec<Vec<u8>>, SpanBatchError> {
-        let mut txs = Vec::new();
+        let tx_datas = &self.tx_datas[..self.total_block_tx_count as usize];
+        let decoded = parallel::map(tx_datas, |datas| {
+            SpanBatchTransactionData::decode(&mut datas.as_slice())
+                .map_err(|_| SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))
+        });
+
+        let mut inputs = Vec::with_capacity(decoded.len());
+        let mut error = None;
         let mut to_idx = 0;
         let mut protected_bit_idx = 0;
-        for idx in 0..self.total_block_tx_count {
-            let mut datas = self.tx_datas[idx as usize].as_slice();
-            let tx = SpanBatchTransactionData::decode(&mut datas)
-                .map_err(|_| SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
-            let nonce = self
-                .tx_nonces
-                .get(idx as usize)
-                .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
-            let gas = self
-                .tx_gases
-                .get(idx as usize)
-                .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
-            let bit = self
-                .contract_creation_bits
-                .get_bit(idx as usize)
-                .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
-            let to = if bit == 0 {
-                if self.tx_tos.len() <= to_idx {
-                    return Err(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData));
+        for (idx, tx) in decoded.into_iter().enumerate() {
+            match self.tx_inputs(idx, tx, &mut to_idx, &mut protected_bit_idx) {
+                Ok(input) => inputs.push(input),
+                Err(e) => {
+                    error = Some(e);
+                    break;
                 }
-                to_idx += 1;
-                Some(self.tx_tos[to_idx - 1])
-            } else {
-                None
-            };
-            let sig = *self
-                .tx_sigs
-                .get(idx as usize)
-                .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
-            let is_protected = if tx.tx_type() == TxType::Legacy {
-                protected_bit_idx += 1;
-                self.protected_bits.get_bit(protected_bit_idx - 1).unwrap_or_default() == 1
-            } else {
-                true
-            };
-            let tx_envelope = tx.to_signed_tx(*nonce, *gas, to, chain_id, sig, is_protected)?;
+            }
+        }
+
+        let txs = parallel::map(&inputs, |(tx, nonce, gas, to, sig, is_protected)| {
+            let tx_envelope = tx.to_signed_tx(*nonce, *gas, *to, chain_id, *sig, *is_protected)?;
             let mut buf = Vec::new();
             tx_envelope.encode_2718(&mut buf);
-            txs.push(buf);
-        }
-        Ok(txs)
+            Ok::<_, SpanBatchError>(buf)
+        });
+        let txs = txs.into_iter().collect::<Result<Vec<_>, _>>()?;
+        error.map_or(Ok(txs), Err)
+    }
+
+    /// Returns the [`TxInputs`] of the transaction at the given index, given its decoded data.
+    ///
+    /// The `to` addresses and protected bits are only stored for the contract calls and legacy
+    /// transactions respectively, so the given indices into them are advanced accordingly.
+    fn tx_inputs(
+        &self,
+        idx: usize,
+        tx: Result<SpanBatchTransactionData, SpanBatchError>,
+        to_idx: &mut usize,
+        protected_bit_idx: &mut usize,
+    ) -> Result<TxInputs, SpanBatchError> {
+        let tx = tx?;
+        let nonce = self
+            .tx_nonces
+            .get(idx)
+            .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
+        let gas = self
+            .tx_gases
+            .get(idx)
+            .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
+        let bit = self
+            .contract_creation_bits
+            .get_bit(idx)
+            .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
+        let to = if bit == 0 {
+            if self.tx_tos.len() <= *to_idx {
+                return Err(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData));
+            }
+            *to_idx += 1;
+            Some(self.tx_tos[*to_idx - 1])
+        } else {
+            None
+        };
+        let sig = *self
+            .tx_sigs
+            .get(idx)
+            .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
+        let is_protected = if tx.tx_type() == TxType::Legacy {
+            *protected_bit_idx += 1;
+            self.protected_bits.get_bit(*protected_bit_idx - 1).unwrap_or_default() == 1
+        } else {
+            true
+        };
+        Ok((tx, *nonce, *gas, to, sig, is_protected))
     }
 
     /// Add raw transactions into the [SpanBatchTransactions].
@@ -349,7 +388,7 @@ impl SpanBatchTransactions {
 mod tests {
     use super::*;
     use alloc::vec;
-    use alloy_consensus::{Signed, TxEip1559, TxEip2930, TxEip7702};
+    use alloy_consensus::{Signed, TxEip1559, TxEip2930, TxEip7702, TxLegacy};
     use alloy_primitives::{Signature, TxKind, address};
 
     #[test]
@@ -436,4 +475,61 @@ mod tests {
         assert_eq!(result, Ok(()));
         assert_eq!(span_batch_txs.total_block_tx_count, 1);
     }
+
+    #[test]
+    fn test_span_batch_transactions_full_txs_large_batch() {
+        let sig = Signature::test_signature();
+        let to = address!("0123456789012345678901234567890123456789");
+        let txs = (0..100u64)
+            .map(|nonce| match nonce % 4 {
+                0 => TxEnvelope::Eip1559(Signed::new_unchecked(
+                    TxEip1559 { to: TxKind::Call(to), chain_id: 1, nonce, ..Default::default() },
+                    sig,
+                    Default::default(),
+                )),
+                1 => TxEnvelope::Eip1559(Signed::new_unchecked(
+                    TxEip1559 { to: TxKind::Create, chain_id: 1, nonce, ..Default::default() },
+                    sig,
+                    Default::default(),
+                )),
+                2 => TxEnvelope::Legacy(Signed::new_unchecked(
+                    TxLegacy {
+                        to: TxKind::Call(to),
+                        chain_id: Some(1),
+                        nonce,
+                        ..Default::default()
+                    },
+                    sig,
+                    Default::default(),
+                )),
+                _ => TxEnvelope::Legacy(Signed::new_unchecked(
+                    TxLegacy { to: TxKind::Create, chain_id: None, nonce, ..Default::default() },
+                    sig,
+                    Default::default(),
+                )),
+            })
+            .collect::<Vec<_>>();
+        let mut span_batch_txs = SpanBatchTransactions::default();
+        let raw = txs
+            .iter()
+            .map(|tx| {
+                let mut buf = vec![];
+                tx.encode(&mut buf);
+                Bytes::from(buf)
+            })
+            .collect();
+        span_batch_txs.add_txs(raw, 1).unwrap();
+
+        let full_txs = span_batch_txs.full_txs(1).unwrap();
+        let expected = txs.iter().map(|tx| tx.encoded_2718()).collect::<Vec<_>>();
+        assert_eq!(full_txs, expected);
+
+        // The first invalid transaction is reported, even when a later one is invalid too.
+        span_batch_txs.tx_datas[90].clear();
+        span_batch_txs.tx_sigs.truncate(50);
+        assert_eq!(
+            span_batch_txs.full_txs(1),
+            Err(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))
+        );
+    }
 }
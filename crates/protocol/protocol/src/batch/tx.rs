@@ -44,7 +44,8 @@ mod test {
 
     #[test]
     fn test_batch_transaction() {
-        let frame = Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50], is_last: true };
+        let frame =
+            Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50].into(), is_last: true };
         let batch = BatchTransaction { frames: vec![frame.clone(); 5], size: 5 * frame.size() };
         let bytes: Bytes = batch.to_bytes();
         let bytes =
@@ -183,7 +183,14 @@ impl Channel {
         if self.is_empty() {
             return None;
         }
-        let mut data = Vec::with_capacity(self.size());
+        // Most channels fit in a single frame, whose data is returned without copying.
+        if self.last_frame_number == 0 {
+            return self.inputs.get(&0).map(|frame| frame.data.clone());
+        }
+        let len = (0..=self.last_frame_number)
+            .map(|i| self.inputs.get(&i).map(|frame| frame.data.len()))
+            .sum::<Option<usize>>()?;
+        let mut data = Vec::with_capacity(len);
         (0..=self.last_frame_number).try_for_each(|i| {
             let frame = self.inputs.get(&i)?;
             data.extend_from_slice(&frame.data);
@@ -267,7 +274,7 @@ mod test {
             FrameValidityTestCase {
                 name: "double close".to_string(),
                 frames: vec![
-                    Frame { id, is_last: true, number: 2, data: b"four".to_vec() },
+                    Frame { id, is_last: true, number: 2, data: Bytes::from_static(b"four") },
                     Frame { id, is_last: true, number: 1, ..Default::default() },
                 ],
                 should_error: vec![false, true],
@@ -277,8 +284,18 @@ mod test {
             FrameValidityTestCase {
                 name: "duplicate frame".to_string(),
                 frames: vec![
-                    Frame { id, number: 2, data: b"four".to_vec(), ..Default::default() },
-                    Frame { id, number: 2, data: b"seven".to_vec(), ..Default::default() },
+                    Frame {
+                        id,
+                        number: 2,
+                        data: Bytes::from_static(b"four"),
+                        ..Default::default()
+                    },
+                    Frame {
+                        id,
+                        number: 2,
+                        data: Bytes::from_static(b"seven"),
+                        ..Default::default()
+                    },
                 ],
                 should_error: vec![false, true],
                 sizes: vec![204, 204],
@@ -287,8 +304,8 @@ mod test {
             FrameValidityTestCase {
                 name: "duplicate closing frames".to_string(),
                 frames: vec![
-                    Frame { id, number: 2, is_last: true, data: b"four".to_vec() },
-                    Frame { id, number: 2, is_last: true, data: b"seven".to_vec() },
+                    Frame { id, number: 2, is_last: true, data: Bytes::from_static(b"four") },
+                    Frame { id, number: 2, is_last: true, data: Bytes::from_static(b"seven") },
                 ],
                 should_error: vec![false, true],
                 sizes: vec![204, 204],
@@ -297,8 +314,13 @@ mod test {
             FrameValidityTestCase {
                 name: "frame past closing".to_string(),
                 frames: vec![
-                    Frame { id, number: 2, is_last: true, data: b"four".to_vec() },
-                    Frame { id, number: 10, data: b"seven".to_vec(), ..Default::default() },
+                    Frame { id, number: 2, is_last: true, data: Bytes::from_static(b"four") },
+                    Frame {
+                        id,
+                        number: 10,
+                        data: Bytes::from_static(b"seven"),
+                        ..Default::default()
+                    },
                 ],
                 should_error: vec![false, true],
                 sizes: vec![204, 204],
@@ -307,8 +329,8 @@ mod test {
             FrameValidityTestCase {
                 name: "prune after close frame".to_string(),
                 frames: vec![
-                    Frame { id, number: 0, is_last: false, data: b"seven".to_vec() },
-                    Frame { id, number: 1, is_last: true, data: b"four".to_vec() },
+                    Frame { id, number: 0, is_last: false, data: Bytes::from_static(b"seven") },
+                    Frame { id, number: 1, is_last: true, data: Bytes::from_static(b"four") },
                 ],
                 should_error: vec![false, false],
                 sizes: vec![205, 409],
@@ -317,8 +339,18 @@ mod test {
             FrameValidityTestCase {
                 name: "multiple valid frames, no data".to_string(),
                 frames: vec![
-                    Frame { id, number: 1, data: b"seven__".to_vec(), ..Default::default() },
-                    Frame { id, number: 2, data: b"four".to_vec(), ..Default::default() },
+                    Frame {
+                        id,
+                        number: 1,
+                        data: Bytes::from_static(b"seven__"),
+                        ..Default::default()
+                    },
+                    Frame {
+                        id,
+                        number: 2,
+                        data: Bytes::from_static(b"four"),
+                        ..Default::default()
+                    },
                 ],
                 should_error: vec![false, false],
                 sizes: vec![207, 411],
@@ -329,8 +361,18 @@ mod test {
             FrameValidityTestCase {
                 name: "multiple valid frames".to_string(),
                 frames: vec![
-                    Frame { id, number: 0, data: b"seven__".to_vec(), ..Default::default() },
-                    Frame { id, number: 1, data: b"four".to_vec(), ..Default::default() },
+                    Frame {
+                        id,
+                        number: 0,
+                        data: Bytes::from_static(b"seven__"),
+                        ..Default::default()
+                    },
+                    Frame {
+                        id,
+                        number: 1,
+                        data: Bytes::from_static(b"four"),
+                        ..Default::default()
+                    },
                 ],
                 should_error: vec![false, false],
                 sizes: vec![207, 411],
@@ -340,4 +382,17 @@ mod test {
 
         test_cases.into_iter().for_each(run_frame_validity_test);
     }
+
+    #[test]
+    fn test_frame_data_single_frame_is_not_copied() {
+        let id = [0xFF; 16];
+        let block = BlockInfo::default();
+        let mut channel = Channel::new(id, block);
+        let data = Bytes::from_static(b"single frame");
+        channel.add_frame(Frame::new(id, 0, data.clone(), true), block).unwrap();
+
+        let frame_data = channel.frame_data().unwrap();
+        assert_eq!(frame_data, data);
+        assert_eq!(frame_data.as_ptr(), data.as_ptr());
+    }
 }
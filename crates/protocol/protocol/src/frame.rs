@@ -2,6 +2,8 @@
 
 use crate::ChannelId;
 use alloc::vec::Vec;
+use alloy_primitives::Bytes;
+use core::ops::Range;
 
 /// The version of the derivation pipeline.
 pub const DERIVATION_VERSION_0: u8 = 0;
@@ -64,6 +66,10 @@ pub enum FrameParseError {
 /// * frame_data_length = uint32
 /// * frame_data        = bytes
 /// * is_last           = bool
+///
+/// The frame data is held as [`Bytes`], so that frames decoded from [`Bytes`] with
+/// [`Frame::decode_bytes`] or [`Frame::parse_frames_bytes`] share the buffer of the L1 transaction
+/// data that carried them instead of copying it.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Frame {
     /// The unique idetifier for the frame.
@@ -71,15 +77,15 @@ pub struct Frame {
     /// The number of the frame.
     pub number: u16,
     /// The data within the frame.
-    pub data: Vec<u8>,
+    pub data: Bytes,
     /// Whether or not the frame is the last in the sequence.
     pub is_last: bool,
 }
 
 impl Frame {
     /// Creates a new [Frame].
-    pub const fn new(id: ChannelId, number: u16, data: Vec<u8>, is_last: bool) -> Self {
-        Self { id, number, data, is_last }
+    pub fn new(id: ChannelId, number: u16, data: impl Into<Bytes>, is_last: bool) -> Self {
+        Self { id, number, data: data.into(), is_last }
     }
 
     /// Encode the frame into a byte vector.
@@ -93,8 +99,32 @@ impl Frame {
         encoded
     }
 
-    /// Decode a frame from a byte vector.
+    /// Decode a frame from a byte slice, copying the frame data out of it.
     pub fn decode(encoded: &[u8]) -> Result<(usize, Self), FrameDecodingError> {
+        Self::decode_with(encoded, |range| Bytes::copy_from_slice(&encoded[range]))
+    }
+
+    /// Decode a frame from [`Bytes`]. The frame data is a slice of `encoded`, and is not copied.
+    pub fn decode_bytes(encoded: &Bytes) -> Result<(usize, Self), FrameDecodingError> {
+        Self::decode_bytes_at(encoded, 0)
+    }
+
+    /// Decode a frame from [`Bytes`] at the given offset, slicing the frame data out of it.
+    fn decode_bytes_at(
+        encoded: &Bytes,
+        offset: usize,
+    ) -> Result<(usize, Self), FrameDecodingError> {
+        Self::decode_with(&encoded[offset..], |range| {
+            encoded.slice(offset + range.start..offset + range.end)
+        })
+    }
+
+    /// Decode a frame from a byte slice, producing the frame data from its range in `encoded`
+    /// with the given function.
+    fn decode_with(
+        encoded: &[u8],
+        data: impl FnOnce(Range<usize>) -> Bytes,
+    ) -> Result<(usize, Self), FrameDecodingError> {
         const BASE_FRAME_LEN: usize = 16 + 2 + 4 + 1;
 
         if encoded.len() < BASE_FRAME_LEN {
@@ -113,8 +143,8 @@ impl Frame {
             return Err(FrameDecodingError::DataTooLarge(data_len));
         }
 
-        let data = encoded[22..22 + data_len].to_vec();
         let is_last = encoded[22 + data_len] == 1;
+        let data = data(22..22 + data_len);
         Ok((BASE_FRAME_LEN + data_len, Self { id, number, data, is_last }))
     }
 
@@ -133,7 +163,25 @@ impl Frame {
     /// Frames are stored in L1 transactions with the following format:
     /// * `data = DerivationVersion0 ++ Frame(s)` Where there is one or more frames concatenated
     ///   together.
+    ///
+    /// The data of each frame is copied out of `encoded`. Use [`Frame::parse_frames_bytes`] to
+    /// parse frames from [`Bytes`] without copying.
     pub fn parse_frames(encoded: &[u8]) -> Result<Vec<Self>, FrameParseError> {
+        Self::parse_frames_with(encoded, |offset| Self::decode(&encoded[offset..]))
+    }
+
+    /// Parse the on chain serialization of frame(s) in an L1 transaction from [`Bytes`], like
+    /// [`Frame::parse_frames`]. The data of the frames are slices of `encoded`, and are not
+    /// copied.
+    pub fn parse_frames_bytes(encoded: &Bytes) -> Result<Vec<Self>, FrameParseError> {
+        Self::parse_frames_with(encoded, |offset| Self::decode_bytes_at(encoded, offset))
+    }
+
+    /// Parse the frames in `encoded`, decoding the frame at each offset with the given function.
+    fn parse_frames_with(
+        encoded: &[u8],
+        decode: impl Fn(usize) -> Result<(usize, Self), FrameDecodingError>,
+    ) -> Result<Vec<Self>, FrameParseError> {
         if encoded.is_empty() {
             return Err(FrameParseError::NoFrames);
         }
@@ -141,17 +189,16 @@ impl Frame {
             return Err(FrameParseError::UnsupportedVersion);
         }
 
-        let data = &encoded[1..];
         let mut frames = Vec::new();
-        let mut offset = 0;
-        while offset < data.len() {
+        let mut offset = 1;
+        while offset < encoded.len() {
             let (frame_length, frame) =
-                Self::decode(&data[offset..]).map_err(FrameParseError::FrameDecodingError)?;
+                decode(offset).map_err(FrameParseError::FrameDecodingError)?;
             frames.push(frame);
             offset += frame_length;
         }
 
-        if offset != data.len() {
+        if offset != encoded.len() {
             return Err(FrameParseError::DataLengthMismatch);
         }
         if frames.is_empty() {
@@ -164,8 +211,8 @@ impl Frame {
     /// Calculates the size of the frame + overhead for storing the frame. The sum of the frame size
     /// of each frame in a channel determines the channel's size. The sum of the channel sizes
     /// is used for pruning & compared against the max channel bank size.
-    pub fn size(&self) -> usize {
-        self.data.len() + FRAME_OVERHEAD
+    pub const fn size(&self) -> usize {
+        self.data.0.len() + FRAME_OVERHEAD
     }
 }
 
@@ -176,7 +223,8 @@ mod test {
 
     #[test]
     fn test_encode_frame_roundtrip() {
-        let frame = Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50], is_last: true };
+        let frame =
+            Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50].into(), is_last: true };
 
         let (_, frame_decoded) = Frame::decode(&frame.encode()).unwrap();
         assert_eq!(frame, frame_decoded);
@@ -184,7 +232,8 @@ mod test {
 
     #[test]
     fn test_data_too_short() {
-        let frame = Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 22], is_last: true };
+        let frame =
+            Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 22].into(), is_last: true };
         let err = Frame::decode(&frame.encode()[..22]).unwrap_err();
         assert_eq!(err, FrameDecodingError::DataTooShort(22));
     }
@@ -194,7 +243,7 @@ mod test {
         let frame = Frame {
             id: [0xFF; 16],
             number: 0xEE,
-            data: vec![0xDD; MAX_FRAME_LEN + 1],
+            data: vec![0xDD; MAX_FRAME_LEN + 1].into(),
             is_last: true,
         };
         let err = Frame::decode(&frame.encode()).unwrap_err();
@@ -203,7 +252,8 @@ mod test {
 
     #[test]
     fn test_decode_malicious_data_len() {
-        let frame = Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50], is_last: true };
+        let frame =
+            Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50].into(), is_last: true };
         let mut encoded = frame.encode();
         let data_len = (encoded.len() - 22) as u32;
         encoded[18..22].copy_from_slice(&data_len.to_be_bytes());
@@ -219,7 +269,8 @@ mod test {
 
     #[test]
     fn test_decode_many() {
-        let frame = Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50], is_last: true };
+        let frame =
+            Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50].into(), is_last: true };
         let mut bytes = Vec::new();
         bytes.extend_from_slice(&[DERIVATION_VERSION_0]);
         (0..5).for_each(|_| {
@@ -232,4 +283,38 @@ mod test {
             assert_eq!(frames[i], frame);
         });
     }
+
+    #[test]
+    fn test_decode_many_bytes_zero_copy() {
+        let frame =
+            Frame { id: [0xFF; 16], number: 0xEE, data: vec![0xDD; 50].into(), is_last: true };
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&[DERIVATION_VERSION_0]);
+        (0..5).for_each(|_| {
+            bytes.extend_from_slice(&frame.encode());
+        });
+        let bytes = Bytes::from(bytes);
+
+        let frames = Frame::parse_frames_bytes(&bytes).unwrap();
+        assert_eq!(frames, Frame::parse_frames(&bytes).unwrap());
+        assert_eq!(frames.len(), 5);
+        let buffer = bytes.as_ptr_range();
+        frames.iter().for_each(|decoded| {
+            assert_eq!(decoded, &frame);
+            assert!(buffer.contains(&decoded.data.as_ptr()));
+        });
+    }
+
+    #[test]
+    fn test_parse_frames_bytes_errors() {
+        assert_eq!(Frame::parse_frames_bytes(&Bytes::new()), Err(FrameParseError::NoFrames));
+        assert_eq!(
+            Frame::parse_frames_bytes(&Bytes::from_static(&[1])),
+            Err(FrameParseError::UnsupportedVersion)
+        );
+        assert_eq!(
+            Frame::parse_frames_bytes(&Bytes::from_static(&[DERIVATION_VERSION_0, 0xFF])),
+            Err(FrameParseError::FrameDecodingError(FrameDecodingError::DataTooShort(1)))
+        );
+    }
 }
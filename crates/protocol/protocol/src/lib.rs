@@ -11,10 +11,11 @@ extern crate alloc;
 
 mod batch;
 pub use batch::{
-    Batch, BatchDecodingError, BatchEncodingError, BatchReader, BatchTransaction, BatchType,
-    BatchValidationProvider, BatchValidity, BatchWithInclusionBlock, DecompressionError,
-    MAX_SPAN_BATCH_ELEMENTS, RawSpanBatch, SINGLE_BATCH_TYPE, SPAN_BATCH_TYPE, SingleBatch,
-    SpanBatch, SpanBatchBits, SpanBatchEip1559TransactionData, SpanBatchEip2930TransactionData,
+    Batch, BatchDecodingError, BatchEncodingError, BatchReader, BatchTransaction,
+    BatchTransactionError, BatchType, BatchValidationProvider, BatchValidity,
+    BatchWithInclusionBlock, DecompressionError, MAX_SPAN_BATCH_ELEMENTS, RawSpanBatch,
+    SINGLE_BATCH_TYPE, SPAN_BATCH_TYPE, SingleBatch, SpanBatch, SpanBatchBits,
+    SpanBatchEip1559TransactionData, SpanBatchEip2930TransactionData,
     SpanBatchEip7702TransactionData, SpanBatchElement, SpanBatchError,
     SpanBatchLegacyTransactionData, SpanBatchPayload, SpanBatchPrefix, SpanBatchTransactionData,
     SpanBatchTransactions, SpanDecodingError,
@@ -24,10 +25,13 @@ mod brotli;
 pub use brotli::{BrotliDecompressionError, decompress_brotli};
 
 mod sync;
-pub use sync::SyncStatus;
+pub use sync::{ElSyncProgress, SyncStatus};
+
+mod audit;
+pub use audit::{MemoryAudit, MemoryAuditError, MemoryComponent, MemoryUsage};
 
 mod attributes;
-pub use attributes::OpAttributesWithParent;
+pub use attributes::{CorrelationId, OpAttributesWithParent};
 
 mod errors;
 pub use errors::OpBlockConversionError;
@@ -64,4 +64,97 @@ pub struct SyncStatus {
     ///
     /// This is an L2 block derived from L1, not yet verified to have valid cross-L2 dependencies.
     pub local_safe_l2: L2BlockInfo,
+    /// The progress of the execution layer sync, while the execution layer is syncing.
+    ///
+    /// This is a kona extension of the sync status, omitted once the execution layer has finished
+    /// syncing.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub el_sync: Option<ElSyncProgress>,
+    /// The last L2 block produced by the deposits-only fallback, if any: a derived payload that
+    /// the execution layer found invalid, replaced by a block holding its deposits only.
+    ///
+    /// This is a kona extension of the sync status, omitted until such a block is produced.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub deposits_only_l2: Option<L2BlockInfo>,
+}
+
+/// The progress of the execution layer sync, as reported by `eth_syncing`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub struct ElSyncProgress {
+    /// The block the execution layer has synced up to.
+    pub current_block: u64,
+    /// The highest block known to the execution layer.
+    pub highest_block: u64,
+    /// Whether the sync is stalled, i.e. the execution layer has not made progress within the
+    /// stall timeout.
+    pub stalled: bool,
+    /// The number of times the sync was re-triggered after stalling.
+    pub retries: u32,
+}
+
+impl ElSyncProgress {
+    /// Returns the number of blocks left to sync.
+    pub const fn remaining(&self) -> u64 {
+        self.highest_block.saturating_sub(self.current_block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_el_sync_progress_remaining() {
+        let progress =
+            ElSyncProgress { current_block: 10, highest_block: 25, ..Default::default() };
+        assert_eq!(progress.remaining(), 15);
+        let progress =
+            ElSyncProgress { current_block: 30, highest_block: 25, ..Default::default() };
+        assert_eq!(progress.remaining(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_sync_status_el_sync_serde() {
+        let status = SyncStatus {
+            current_l1: BlockInfo::default(),
+            current_l1_finalized: BlockInfo::default(),
+            head_l1: BlockInfo::default(),
+            safe_l1: BlockInfo::default(),
+            finalized_l1: BlockInfo::default(),
+            unsafe_l2: L2BlockInfo::default(),
+            safe_l2: L2BlockInfo::default(),
+            finalized_l2: L2BlockInfo::default(),
+            cross_unsafe_l2: L2BlockInfo::default(),
+            local_safe_l2: L2BlockInfo::default(),
+            el_sync: None,
+            deposits_only_l2: None,
+        };
+        let json = serde_json::to_value(&status).unwrap();
+        assert!(json.get("el_sync").is_none());
+        assert!(json.get("deposits_only_l2").is_none());
+        assert_eq!(serde_json::from_value::<SyncStatus>(json).unwrap(), status);
+
+        let progress =
+            ElSyncProgress { current_block: 1, highest_block: 2, stalled: true, retries: 3 };
+        let status = SyncStatus { el_sync: Some(progress), ..status };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            json["el_sync"],
+            serde_json::json!({
+                "current_block": 1,
+                "highest_block": 2,
+                "stalled": true,
+                "retries": 3,
+            })
+        );
+        assert_eq!(serde_json::from_value::<SyncStatus>(json).unwrap(), status);
+
+        let status = SyncStatus { deposits_only_l2: Some(L2BlockInfo::default()), ..status };
+        let json = serde_json::to_value(&status).unwrap();
+        assert!(json.get("deposits_only_l2").is_some());
+        assert_eq!(serde_json::from_value::<SyncStatus>(json).unwrap(), status);
+    }
 }
@@ -31,8 +31,12 @@ impl Registry {
 
     /// Initialize the superchain configurations from the chain list.
     pub fn from_chain_list() -> Self {
-        let chain_list = Self::read_chain_list();
-        let superchains = Self::read_superchain_configs();
+        Self::from_superchains(Self::read_chain_list(), Self::read_superchain_configs())
+    }
+
+    /// Initialize the superchain configurations from the given chain list and superchain configs,
+    /// e.g. a newer version of the registry than the one embedded in the binary.
+    pub fn from_superchains(chain_list: ChainList, superchains: Superchains) -> Self {
         let mut op_chains = HashMap::default();
         let mut rollup_configs = HashMap::default();
 
@@ -0,0 +1,466 @@
+//! Contains the [`L1Archive`] of recorded L1 data, and the [`L1Recorder`] that writes it.
+
+use alloy_consensus::{Header, ReceiptEnvelope, TxEnvelope};
+use alloy_eips::{
+    eip2718::{Decodable2718, Eip2718Error, Encodable2718},
+    eip4844::{BYTES_PER_BLOB, Blob, BlobTransactionSidecarItem},
+};
+use alloy_primitives::{B256, Bytes, FixedBytes};
+use alloy_rlp::{Decodable, Encodable};
+use std::{
+    boxed::Box,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+    vec::Vec,
+};
+
+/// The magic bytes at the start of an archive file.
+const MAGIC: &[u8; 4] = b"KL1A";
+
+/// The version of the archive format.
+const VERSION: u8 = 1;
+
+/// The size of the length of the compressed KZG commitments and proofs.
+const BYTES48: usize = 48;
+
+/// The tag of a record in an archive file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+enum RecordTag {
+    /// An RLP-encoded header, fetched by hash.
+    Header = 1,
+    /// An RLP-encoded header, fetched by number, i.e. canonical when it was recorded.
+    CanonicalHeader = 2,
+    /// The hash of a block, followed by the RLP list of its EIP-2718 encoded transactions.
+    Transactions = 3,
+    /// The hash of a block, followed by the RLP list of its EIP-2718 encoded receipts.
+    Receipts = 4,
+    /// The versioned hash of a blob, followed by its KZG commitment, its KZG proof and the blob.
+    Blob = 5,
+}
+
+impl TryFrom<u8> for RecordTag {
+    type Error = L1ArchiveError;
+
+    fn try_from(tag: u8) -> Result<Self, Self::Error> {
+        Ok(match tag {
+            1 => Self::Header,
+            2 => Self::CanonicalHeader,
+            3 => Self::Transactions,
+            4 => Self::Receipts,
+            5 => Self::Blob,
+            tag => return Err(L1ArchiveError::UnknownRecord(tag)),
+        })
+    }
+}
+
+/// A blob held by an [`L1Archive`], with the KZG commitment and proof of its sidecar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedBlob {
+    /// The blob.
+    pub blob: Box<Blob>,
+    /// The KZG commitment of the blob.
+    pub kzg_commitment: FixedBytes<48>,
+    /// The KZG proof of the blob.
+    pub kzg_proof: FixedBytes<48>,
+}
+
+impl From<&BlobTransactionSidecarItem> for ArchivedBlob {
+    fn from(sidecar: &BlobTransactionSidecarItem) -> Self {
+        Self {
+            blob: sidecar.blob.clone(),
+            kzg_commitment: sidecar.kzg_commitment,
+            kzg_proof: sidecar.kzg_proof,
+        }
+    }
+}
+
+/// An archive of L1 data: headers, transactions, receipts and blobs.
+///
+/// Archives are recorded by an [`L1Recorder`] while the derivation pipeline follows a live chain,
+/// and served back by the [`ReplayProvider`]. On disk, an archive is a sequence of records, each
+/// holding one header, the transactions or receipts of one block, or one blob, in the order they
+/// were recorded. A truncated record at the end of the file, e.g. left by a crash, is ignored.
+///
+/// [`ReplayProvider`]: crate::ReplayProvider
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct L1Archive {
+    /// The headers, by block hash.
+    headers: HashMap<B256, Header>,
+    /// The hashes of the blocks fetched by number, by block number.
+    canonical: BTreeMap<u64, B256>,
+    /// The transactions, by block hash.
+    transactions: HashMap<B256, Vec<TxEnvelope>>,
+    /// The receipts, by block hash.
+    receipts: HashMap<B256, Vec<ReceiptEnvelope>>,
+    /// The blobs, by versioned hash.
+    blobs: HashMap<B256, ArchivedBlob>,
+}
+
+impl L1Archive {
+    /// Reads the archive at the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, L1ArchiveError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads an archive from the given reader.
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, L1ArchiveError> {
+        let mut header = [0u8; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(L1ArchiveError::InvalidMagic);
+        }
+        if header[4] != VERSION {
+            return Err(L1ArchiveError::UnsupportedVersion(header[4]));
+        }
+
+        let mut archive = Self::default();
+        while let Some((tag, payload)) = read_record(&mut reader)? {
+            archive.decode_record(tag, &payload)?;
+        }
+        Ok(archive)
+    }
+
+    /// Writes the archive to the given writer.
+    pub fn to_writer(&self, mut writer: impl Write) -> Result<(), L1ArchiveError> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        for (hash, header) in &self.headers {
+            let tag = if self.canonical.get(&header.number) == Some(hash) {
+                RecordTag::CanonicalHeader
+            } else {
+                RecordTag::Header
+            };
+            write_record(&mut writer, tag, &alloy_rlp::encode(header))?;
+        }
+        for (hash, transactions) in &self.transactions {
+            write_record(&mut writer, RecordTag::Transactions, &encode_list(hash, transactions))?;
+        }
+        for (hash, receipts) in &self.receipts {
+            write_record(&mut writer, RecordTag::Receipts, &encode_list(hash, receipts))?;
+        }
+        for (hash, blob) in &self.blobs {
+            write_record(&mut writer, RecordTag::Blob, &encode_blob(hash, blob))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the archive to the given path, replacing any existing file.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), L1ArchiveError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.to_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Decodes a record into the archive.
+    fn decode_record(&mut self, tag: RecordTag, mut payload: &[u8]) -> Result<(), L1ArchiveError> {
+        match tag {
+            RecordTag::Header | RecordTag::CanonicalHeader => {
+                let header = Header::decode(&mut payload)?;
+                self.insert_header(header, tag == RecordTag::CanonicalHeader);
+            }
+            RecordTag::Transactions => {
+                let (hash, transactions) = decode_list(payload)?;
+                self.transactions.insert(hash, transactions);
+            }
+            RecordTag::Receipts => {
+                let (hash, receipts) = decode_list(payload)?;
+                self.receipts.insert(hash, receipts);
+            }
+            RecordTag::Blob => {
+                let (hash, blob) = decode_blob(payload)?;
+                self.blobs.insert(hash, blob);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the archive holds no data.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.blobs.is_empty()
+    }
+
+    /// Inserts the given header. If `canonical` is set, the header is also returned by
+    /// [`Self::header_by_number`].
+    pub fn insert_header(&mut self, header: Header, canonical: bool) {
+        let hash = header.hash_slow();
+        if canonical {
+            self.canonical.insert(header.number, hash);
+        }
+        self.headers.insert(hash, header);
+    }
+
+    /// Inserts the transactions of the block with the given hash.
+    pub fn insert_transactions(&mut self, hash: B256, transactions: Vec<TxEnvelope>) {
+        self.transactions.insert(hash, transactions);
+    }
+
+    /// Inserts the receipts of the block with the given hash.
+    pub fn insert_receipts(&mut self, hash: B256, receipts: Vec<ReceiptEnvelope>) {
+        self.receipts.insert(hash, receipts);
+    }
+
+    /// Inserts the blob with the given versioned hash.
+    pub fn insert_blob(&mut self, hash: B256, blob: ArchivedBlob) {
+        self.blobs.insert(hash, blob);
+    }
+
+    /// Returns the header of the block with the given hash.
+    pub fn header(&self, hash: &B256) -> Option<&Header> {
+        self.headers.get(hash)
+    }
+
+    /// Returns the header of the block with the given number, if it was recorded as canonical.
+    pub fn header_by_number(&self, number: u64) -> Option<&Header> {
+        self.canonical.get(&number).and_then(|hash| self.headers.get(hash))
+    }
+
+    /// Returns the transactions of the block with the given hash.
+    pub fn transactions(&self, hash: &B256) -> Option<&[TxEnvelope]> {
+        self.transactions.get(hash).map(Vec::as_slice)
+    }
+
+    /// Returns the receipts of the block with the given hash.
+    pub fn receipts(&self, hash: &B256) -> Option<&[ReceiptEnvelope]> {
+        self.receipts.get(hash).map(Vec::as_slice)
+    }
+
+    /// Returns the blob with the given versioned hash.
+    pub fn blob(&self, hash: &B256) -> Option<&ArchivedBlob> {
+        self.blobs.get(hash)
+    }
+
+    /// Returns an iterator over all headers, by block hash.
+    pub fn headers(&self) -> impl Iterator<Item = (&B256, &Header)> {
+        self.headers.iter()
+    }
+
+    /// Returns an iterator over the transactions of all blocks, by block hash.
+    pub fn all_transactions(&self) -> impl Iterator<Item = (&B256, &[TxEnvelope])> {
+        self.transactions.iter().map(|(hash, txs)| (hash, txs.as_slice()))
+    }
+
+    /// Returns an iterator over the receipts of all blocks, by block hash.
+    pub fn all_receipts(&self) -> impl Iterator<Item = (&B256, &[ReceiptEnvelope])> {
+        self.receipts.iter().map(|(hash, receipts)| (hash, receipts.as_slice()))
+    }
+
+    /// Returns an iterator over all blobs, by versioned hash.
+    pub fn blobs(&self) -> impl Iterator<Item = (&B256, &ArchivedBlob)> {
+        self.blobs.iter()
+    }
+
+    /// Returns the range of block numbers recorded as canonical, if any.
+    pub fn canonical_range(&self) -> Option<(u64, u64)> {
+        Some((*self.canonical.first_key_value()?.0, *self.canonical.last_key_value()?.0))
+    }
+}
+
+/// Records the L1 data consumed by the derivation pipeline into an archive file, which can be
+/// read back as an [`L1Archive`].
+///
+/// The recorder is a cheap handle, shared between the providers that record into it with
+/// `with_recorder`. Each piece of data is appended to the file once, when it is first fetched,
+/// and flushed straight away, so that the archive survives the node being killed.
+#[derive(Debug, Clone)]
+pub struct L1Recorder {
+    /// The archive file, and the keys of the records it holds.
+    inner: Arc<Mutex<RecorderFile>>,
+}
+
+/// The archive file written by an [`L1Recorder`].
+#[derive(Debug)]
+struct RecorderFile {
+    /// The open archive file.
+    writer: BufWriter<File>,
+    /// The tags and keys of the records in the file.
+    recorded: HashSet<(RecordTag, B256)>,
+}
+
+impl L1Recorder {
+    /// Opens the archive at the given path, appending to it if it exists.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, L1ArchiveError> {
+        let path = path.as_ref();
+        let mut recorded = HashSet::new();
+        let existing = path.exists() && path.metadata()?.len() > 0;
+        if existing {
+            let archive = L1Archive::read(path)?;
+            for (hash, header) in &archive.headers {
+                recorded.insert((RecordTag::Header, *hash));
+                if archive.canonical.get(&header.number) == Some(hash) {
+                    recorded.insert((RecordTag::CanonicalHeader, *hash));
+                }
+            }
+            recorded.extend(archive.transactions.keys().map(|h| (RecordTag::Transactions, *h)));
+            recorded.extend(archive.receipts.keys().map(|h| (RecordTag::Receipts, *h)));
+            recorded.extend(archive.blobs.keys().map(|h| (RecordTag::Blob, *h)));
+        }
+
+        let mut writer = BufWriter::new(OpenOptions::new().create(true).append(true).open(path)?);
+        if !existing {
+            writer.write_all(MAGIC)?;
+            writer.write_all(&[VERSION])?;
+            writer.flush()?;
+        }
+        Ok(Self { inner: Arc::new(Mutex::new(RecorderFile { writer, recorded })) })
+    }
+
+    /// Returns `true` if a record with the given tag and key was appended to the archive.
+    fn contains(&self, tag: RecordTag, key: B256) -> bool {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner).recorded.contains(&(tag, key))
+    }
+
+    /// Appends a record to the archive, unless a record with the same tag and key was already
+    /// appended. Failures are returned, but leave the archive readable.
+    fn record(
+        &self,
+        tag: RecordTag,
+        key: B256,
+        payload: impl FnOnce() -> Vec<u8>,
+    ) -> Result<(), L1ArchiveError> {
+        let mut file = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        if file.recorded.contains(&(tag, key)) {
+            return Ok(());
+        }
+        write_record(&mut file.writer, tag, &payload())?;
+        file.writer.flush()?;
+        file.recorded.insert((tag, key));
+        Ok(())
+    }
+
+    /// Records the given header. If `canonical` is set, it is recorded as the canonical header
+    /// at its number.
+    pub fn record_header(&self, header: &Header, canonical: bool) -> Result<(), L1ArchiveError> {
+        let hash = header.hash_slow();
+        if canonical {
+            return self.record(RecordTag::CanonicalHeader, hash, || alloy_rlp::encode(header));
+        }
+        if self.contains(RecordTag::CanonicalHeader, hash) {
+            return Ok(());
+        }
+        self.record(RecordTag::Header, hash, || alloy_rlp::encode(header))
+    }
+
+    /// Records the transactions of the block with the given hash.
+    pub fn record_transactions(
+        &self,
+        hash: B256,
+        transactions: &[TxEnvelope],
+    ) -> Result<(), L1ArchiveError> {
+        self.record(RecordTag::Transactions, hash, || encode_list(&hash, transactions))
+    }
+
+    /// Records the receipts of the block with the given hash.
+    pub fn record_receipts(
+        &self,
+        hash: B256,
+        receipts: &[ReceiptEnvelope],
+    ) -> Result<(), L1ArchiveError> {
+        self.record(RecordTag::Receipts, hash, || encode_list(&hash, receipts))
+    }
+
+    /// Records the blob with the given versioned hash.
+    pub fn record_blob(&self, hash: B256, blob: &ArchivedBlob) -> Result<(), L1ArchiveError> {
+        self.record(RecordTag::Blob, hash, || encode_blob(&hash, blob))
+    }
+}
+
+/// Writes a record with the given tag and payload.
+fn write_record(writer: &mut impl Write, tag: RecordTag, payload: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "record too large"))?;
+    writer.write_all(&[tag as u8])?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(payload)
+}
+
+/// Reads the next record, or `None` at the end of the archive or at a truncated record.
+fn read_record(reader: &mut impl Read) -> Result<Option<(RecordTag, Vec<u8>)>, L1ArchiveError> {
+    let mut prefix = [0u8; 5];
+    match reader.read_exact(&mut prefix) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let tag = RecordTag::try_from(prefix[0])?;
+    let len = u32::from_be_bytes([prefix[1], prefix[2], prefix[3], prefix[4]]) as usize;
+    let mut payload = Vec::new();
+    if reader.take(len as u64).read_to_end(&mut payload)? < len {
+        return Ok(None);
+    }
+    Ok(Some((tag, payload)))
+}
+
+/// Encodes the given block hash, followed by the RLP list of the EIP-2718 encoded items.
+fn encode_list<T: Encodable2718>(hash: &B256, items: &[T]) -> Vec<u8> {
+    let encoded = items.iter().map(|item| Bytes::from(item.encoded_2718())).collect::<Vec<_>>();
+    let mut out = Vec::with_capacity(32 + encoded.length());
+    out.extend_from_slice(hash.as_slice());
+    encoded.encode(&mut out);
+    out
+}
+
+/// Decodes a block hash, followed by the RLP list of the EIP-2718 encoded items.
+fn decode_list<T: Decodable2718>(payload: &[u8]) -> Result<(B256, Vec<T>), L1ArchiveError> {
+    let (hash, mut list) = payload.split_at_checked(32).ok_or(alloy_rlp::Error::InputTooShort)?;
+    let items = Vec::<Bytes>::decode(&mut list)?
+        .into_iter()
+        .map(|item| T::decode_2718(&mut item.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((B256::from_slice(hash), items))
+}
+
+/// Encodes the given versioned hash, followed by the KZG commitment, the KZG proof and the blob.
+fn encode_blob(hash: &B256, blob: &ArchivedBlob) -> Vec<u8> {
+    let mut out = Vec::with_capacity(32 + 2 * BYTES48 + BYTES_PER_BLOB);
+    out.extend_from_slice(hash.as_slice());
+    out.extend_from_slice(blob.kzg_commitment.as_slice());
+    out.extend_from_slice(blob.kzg_proof.as_slice());
+    out.extend_from_slice(blob.blob.as_slice());
+    out
+}
+
+/// Decodes a versioned hash, followed by the KZG commitment, the KZG proof and the blob.
+fn decode_blob(payload: &[u8]) -> Result<(B256, ArchivedBlob), L1ArchiveError> {
+    if payload.len() != 32 + 2 * BYTES48 + BYTES_PER_BLOB {
+        return Err(alloy_rlp::Error::UnexpectedLength.into());
+    }
+    let (hash, rest) = payload.split_at(32);
+    let (kzg_commitment, rest) = rest.split_at(BYTES48);
+    let (kzg_proof, blob) = rest.split_at(BYTES48);
+    Ok((
+        B256::from_slice(hash),
+        ArchivedBlob {
+            blob: Box::new(Blob::from_slice(blob)),
+            kzg_commitment: FixedBytes::from_slice(kzg_commitment),
+            kzg_proof: FixedBytes::from_slice(kzg_proof),
+        },
+    ))
+}
+
+/// An error reading or writing an [`L1Archive`].
+#[derive(Debug, thiserror::Error)]
+pub enum L1ArchiveError {
+    /// An I/O error.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The file is not an archive.
+    #[error("Not an L1 archive")]
+    InvalidMagic,
+    /// The archive was written with an unsupported version of the format.
+    #[error("Unsupported L1 archive version: {0}")]
+    UnsupportedVersion(u8),
+    /// A record has an unknown tag.
+    #[error("Unknown L1 archive record: {0}")]
+    UnknownRecord(u8),
+    /// A record could not be decoded.
+    #[error("Failed to decode L1 archive record: {0}")]
+    Rlp(#[from] alloy_rlp::Error),
+    /// A transaction or receipt could not be decoded.
+    #[error("Failed to decode L1 archive record: {0}")]
+    Eip2718(#[from] Eip2718Error),
+}
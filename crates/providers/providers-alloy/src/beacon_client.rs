@@ -7,13 +7,13 @@ use reqwest::Client;
 use std::{boxed::Box, format, string::String, vec::Vec};
 
 /// The config spec engine api method.
-const SPEC_METHOD: &str = "eth/v1/config/spec";
+pub(crate) const SPEC_METHOD: &str = "eth/v1/config/spec";
 
 /// The beacon genesis engine api method.
 const GENESIS_METHOD: &str = "eth/v1/beacon/genesis";
 
 /// The blob sidecars engine api method prefix.
-const SIDECARS_METHOD_PREFIX: &str = "eth/v1/beacon/blob_sidecars";
+pub(crate) const SIDECARS_METHOD_PREFIX: &str = "eth/v1/beacon/blob_sidecars";
 
 /// A reduced genesis data.
 #[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -83,6 +83,18 @@ pub trait BeaconClient {
     ) -> Result<Vec<BlobData>, Self::Error>;
 }
 
+/// An error for the [OnlineBeaconClient].
+#[derive(Debug, thiserror::Error)]
+pub enum OnlineBeaconClientError {
+    /// The request to the beacon API failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// A fault was injected into the request.
+    #[cfg(feature = "chaos")]
+    #[error(transparent)]
+    Chaos(#[from] crate::ChaosFault),
+}
+
 /// An online implementation of the [BeaconClient] trait.
 #[derive(Debug, Clone)]
 pub struct OnlineBeaconClient {
@@ -90,6 +102,9 @@ pub struct OnlineBeaconClient {
     pub base: String,
     /// The inner reqwest client.
     pub inner: Client,
+    /// The fault injector applied to the requests, if any.
+    #[cfg(feature = "chaos")]
+    pub chaos: Option<crate::Chaos>,
 }
 
 impl OnlineBeaconClient {
@@ -99,22 +114,48 @@ impl OnlineBeaconClient {
         if base.ends_with("/") {
             base.remove(base.len() - 1);
         }
-        Self { base, inner: Client::new() }
+        Self {
+            base,
+            inner: Client::new(),
+            #[cfg(feature = "chaos")]
+            chaos: None,
+        }
+    }
+
+    /// Injects the faults of the given [`Chaos`] injector into the requests of the client.
+    ///
+    /// [`Chaos`]: crate::Chaos
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: crate::Chaos) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Sends a GET request for the given beacon API method.
+    pub(crate) async fn get(
+        &self,
+        method: &str,
+    ) -> Result<reqwest::Response, OnlineBeaconClientError> {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.chaos {
+            chaos.inject().await?;
+        }
+        Ok(self.inner.get(format!("{}/{}", self.base, method)).send().await?)
     }
 }
 
 #[async_trait]
 impl BeaconClient for OnlineBeaconClient {
-    type Error = reqwest::Error;
+    type Error = OnlineBeaconClientError;
 
     async fn config_spec(&self) -> Result<APIConfigResponse, Self::Error> {
-        let first = self.inner.get(format!("{}/{}", self.base, SPEC_METHOD)).send().await?;
-        first.json::<APIConfigResponse>().await
+        let first = self.get(SPEC_METHOD).await?;
+        Ok(first.json::<APIConfigResponse>().await?)
     }
 
     async fn beacon_genesis(&self) -> Result<APIGenesisResponse, Self::Error> {
-        let first = self.inner.get(format!("{}/{}", self.base, GENESIS_METHOD)).send().await?;
-        first.json::<APIGenesisResponse>().await
+        let first = self.get(GENESIS_METHOD).await?;
+        Ok(first.json::<APIGenesisResponse>().await?)
     }
 
     async fn beacon_blob_side_cars(
@@ -122,11 +163,7 @@ impl BeaconClient for OnlineBeaconClient {
         slot: u64,
         hashes: &[IndexedBlobHash],
     ) -> Result<Vec<BlobData>, Self::Error> {
-        let raw_response = self
-            .inner
-            .get(format!("{}/{}/{}", self.base, SIDECARS_METHOD_PREFIX, slot))
-            .send()
-            .await?;
+        let raw_response = self.get(&format!("{SIDECARS_METHOD_PREFIX}/{slot}")).await?;
         let raw_response = raw_response.json::<BeaconBlobBundle>().await?;
 
         // Filter the sidecars by the hashes, in-order.
@@ -1,22 +1,57 @@
 //! Contains an online implementation of the `BlobProvider` trait.
 
-use crate::BeaconClient;
+use crate::{ArchivedBlob, BeaconClient, L1Recorder, SidecarVerification, verify_sidecars};
 use alloy_eips::eip4844::{Blob, BlobTransactionSidecarItem, IndexedBlobHash};
+use alloy_primitives::B256;
+use alloy_provider::{Provider, RootProvider};
 use alloy_rpc_types_beacon::sidecar::BlobData;
 use async_trait::async_trait;
 use kona_derive::{BlobProvider, BlobProviderError};
 use kona_protocol::BlockInfo;
-use std::{boxed::Box, string::ToString, vec::Vec};
+use std::{
+    boxed::Box,
+    format,
+    string::ToString,
+    time::{SystemTime, UNIX_EPOCH},
+    vec::Vec,
+};
+use tracing::warn;
 
 /// An online implementation of the [BlobProvider] trait.
+///
+/// Like the [`AlloyChainProvider`], the provider may be backed by a "hot" Beacon API client, used
+/// for recent blocks, and an optional "cold" client, e.g. a blob archiver, set with
+/// [`Self::with_cold`]. Blobs of blocks older than the configured age are fetched from the cold
+/// client first, and each client serves as the fallback of the other.
+///
+/// If an [`L1Recorder`] is set with [`Self::with_recorder`], all verified blobs are recorded into
+/// its archive, along with the KZG commitments and proofs of their sidecars.
+///
+/// The sidecars served by the clients are verified against the beacon block header they are
+/// served with, as configured with [`Self::with_sidecar_verification`]. By default, the sidecars
+/// of a client that fail verification are rejected, and fetched from the next client. If an L1
+/// provider is set with [`Self::with_l1_provider`], the header is also verified against the
+/// `parent_beacon_block_root` of the child of the L1 block the blobs belong to, which delays the
+/// blobs of the L1 head until its child is available.
+///
+/// [`AlloyChainProvider`]: crate::AlloyChainProvider
 #[derive(Debug, Clone)]
 pub struct OnlineBlobProvider<B: BeaconClient> {
     /// The Beacon API client.
     pub beacon_client: B,
+    /// The cold Beacon API client, and the age (in seconds) of blocks after which it is queried
+    /// first.
+    pub cold: Option<(B, u64)>,
     /// Beacon Genesis time used for the time to slot conversion.
     pub genesis_time: u64,
     /// Slot interval used for the time to slot conversion.
     pub slot_interval: u64,
+    /// The recorder that verified blobs are recorded into, if any.
+    pub recorder: Option<L1Recorder>,
+    /// How strictly the sidecars are verified against their beacon block header.
+    pub sidecar_verification: SidecarVerification,
+    /// The L1 provider the beacon block headers of the sidecars are anchored with, if any.
+    pub l1_provider: Option<RootProvider>,
 }
 
 impl<B: BeaconClient> OnlineBlobProvider<B> {
@@ -41,7 +76,54 @@ impl<B: BeaconClient> OnlineBlobProvider<B> {
             .map(|r| r.data.seconds_per_slot)
             .map_err(|e| BlobProviderError::Backend(e.to_string()))
             .expect("Failed to load slot interval from beacon client");
-        Self { beacon_client, genesis_time, slot_interval }
+        Self {
+            beacon_client,
+            cold: None,
+            genesis_time,
+            slot_interval,
+            recorder: None,
+            sidecar_verification: SidecarVerification::default(),
+            l1_provider: None,
+        }
+    }
+
+    /// Sets the cold Beacon API client, from which the blobs of blocks older than `after_secs`
+    /// are fetched first.
+    pub fn with_cold(mut self, cold: B, after_secs: u64) -> Self {
+        self.cold = Some((cold, after_secs));
+        self
+    }
+
+    /// Sets the recorder that all verified blobs are recorded into.
+    pub fn with_recorder(mut self, recorder: L1Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Sets how strictly the sidecars are verified against their beacon block header.
+    pub const fn with_sidecar_verification(mut self, verification: SidecarVerification) -> Self {
+        self.sidecar_verification = verification;
+        self
+    }
+
+    /// Sets the L1 provider the beacon block headers of the sidecars are anchored with, through
+    /// the `parent_beacon_block_root` of the child of their L1 block.
+    pub fn with_l1_provider(mut self, l1_provider: RootProvider) -> Self {
+        self.l1_provider = Some(l1_provider);
+        self
+    }
+
+    /// Returns the clients to fetch the blobs of the given block from, in order.
+    fn tiers(&self, block_ref: &BlockInfo) -> Vec<&B> {
+        let Some((cold, after_secs)) = &self.cold else {
+            return vec![&self.beacon_client];
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs());
+        if now.unwrap_or_default().saturating_sub(block_ref.timestamp) > *after_secs {
+            vec![cold, &self.beacon_client]
+        } else {
+            vec![&self.beacon_client, cold]
+        }
     }
 
     /// Fetches blob sidecars for the given slot and blob hashes.
@@ -50,8 +132,16 @@ impl<B: BeaconClient> OnlineBlobProvider<B> {
         slot: u64,
         hashes: &[IndexedBlobHash],
     ) -> Result<Vec<BlobData>, BlobProviderError> {
-        self.beacon_client
-            .beacon_blob_side_cars(slot, hashes)
+        Self::fetch_sidecars_from(&self.beacon_client, slot, hashes).await
+    }
+
+    /// Fetches blob sidecars for the given slot and blob hashes from the given client.
+    async fn fetch_sidecars_from(
+        client: &B,
+        slot: u64,
+        hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<BlobData>, BlobProviderError> {
+        BeaconClient::beacon_blob_side_cars(client, slot, hashes)
             .await
             .map_err(|e| BlobProviderError::Backend(e.to_string()))
     }
@@ -81,9 +171,99 @@ impl<B: BeaconClient> OnlineBlobProvider<B> {
         // Calculate the slot for the given timestamp.
         let slot = Self::slot(self.genesis_time, self.slot_interval, block_ref.timestamp)?;
 
-        // Fetch blob sidecars for the slot using the given blob hashes.
-        let sidecars = self.fetch_sidecars(slot, blob_hashes).await?;
+        // Fetch the root of the beacon block of the slot that the served headers must match.
+        let block_root = self.block_root(block_ref).await?;
+
+        // Fetch blob sidecars for the slot using the given blob hashes, falling back to the next
+        // tier if a client fails, misses some of the blobs, e.g. because it pruned them, or
+        // serves sidecars that fail verification.
+        let mut result = Err(BlobProviderError::SidecarLengthMismatch(blob_hashes.len(), 0));
+        for client in self.tiers(block_ref) {
+            result = Self::fetch_sidecars_from(client, slot, blob_hashes)
+                .await
+                .and_then(|sidecars| self.verify_sidecars(slot, block_root, sidecars))
+                .and_then(|sidecars| Self::filter_sidecars(sidecars, blob_hashes));
+            if result.is_ok() {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Returns the root of the beacon block that includes the given L1 block, as committed to by
+    /// the `parent_beacon_block_root` of its child (EIP-4788), if an L1 provider is set and the
+    /// sidecars are verified.
+    ///
+    /// In [`SidecarVerification::Warn`] mode, a root that cannot be fetched is logged, and the
+    /// sidecars are verified without it.
+    async fn block_root(&self, block_ref: &BlockInfo) -> Result<Option<B256>, BlobProviderError> {
+        let Some(l1_provider) = &self.l1_provider else {
+            return Ok(None);
+        };
+        if self.sidecar_verification == SidecarVerification::Disabled {
+            return Ok(None);
+        }
+        let root = match Self::fetch_block_root(l1_provider, block_ref).await {
+            Err(err) if self.sidecar_verification == SidecarVerification::Warn => {
+                warn!(target: "blob_provider", block = block_ref.number, %err, "Failed to fetch beacon block root");
+                return Ok(None);
+            }
+            root => root?,
+        };
+        Ok(Some(root))
+    }
+
+    /// Fetches the `parent_beacon_block_root` of the child of the given L1 block.
+    async fn fetch_block_root(
+        l1_provider: &RootProvider,
+        block_ref: &BlockInfo,
+    ) -> Result<B256, BlobProviderError> {
+        let number = block_ref.number + 1;
+        let child = l1_provider
+            .get_block_by_number(number.into())
+            .await
+            .map_err(|e| BlobProviderError::Backend(e.to_string()))?
+            .ok_or_else(|| {
+                BlobProviderError::Backend(format!("L1 block {number} is not available yet"))
+            })?;
+        if child.header.parent_hash != block_ref.hash {
+            return Err(BlobProviderError::Backend(format!(
+                "L1 block {number} is not a child of block {}",
+                block_ref.hash
+            )));
+        }
+        child.header.parent_beacon_block_root.ok_or_else(|| {
+            BlobProviderError::Backend(format!("L1 block {number} has no parent beacon block root"))
+        })
+    }
+
+    /// Verifies the given sidecars of the given slot against their beacon block header, and the
+    /// header against the given block root, if any, according to the configured
+    /// [`SidecarVerification`].
+    fn verify_sidecars(
+        &self,
+        slot: u64,
+        block_root: Option<B256>,
+        sidecars: Vec<BlobData>,
+    ) -> Result<Vec<BlobData>, BlobProviderError> {
+        if self.sidecar_verification == SidecarVerification::Disabled {
+            return Ok(sidecars);
+        }
+        match verify_sidecars(slot, block_root, &sidecars) {
+            Ok(()) => Ok(sidecars),
+            Err(err) if self.sidecar_verification == SidecarVerification::Warn => {
+                warn!(target: "blob_provider", slot, %err, "Blob sidecar verification failed");
+                Ok(sidecars)
+            }
+            Err(err) => Err(BlobProviderError::Backend(err.to_string())),
+        }
+    }
 
+    /// Filters the sidecars matching the given blob hashes.
+    fn filter_sidecars(
+        sidecars: Vec<BlobData>,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<BlobTransactionSidecarItem>, BlobProviderError> {
         // Filter blob sidecars that match the indicies in the specified list.
         let blob_hash_indicies = blob_hashes.iter().map(|b| b.index).collect::<Vec<u64>>();
         let filtered = sidecars
@@ -136,8 +316,13 @@ where
                     .ok_or(BlobProviderError::Backend("Missing blob hash".to_string()))?;
                 sidecar
                     .verify_blob(&IndexedBlobHash { hash: hash.hash, index: hash.index })
-                    .map(|_| sidecar.blob)
-                    .map_err(|e| BlobProviderError::Backend(e.to_string()))
+                    .map_err(|e| BlobProviderError::Backend(e.to_string()))?;
+                if let Some(recorder) = &self.recorder {
+                    recorder
+                        .record_blob(hash.hash, &ArchivedBlob::from(&sidecar))
+                        .map_err(|e| BlobProviderError::Backend(e.to_string()))?;
+                }
+                Ok(sidecar.blob)
             })
             .collect::<Result<Vec<Box<Blob>>, BlobProviderError>>()
             .map_err(|e| BlobProviderError::Backend(e.to_string()))?;
@@ -174,3 +359,93 @@ impl<B: BeaconClient + Send + Sync> BlobSidecarProvider for B {
             .map_err(|e| BlobProviderError::Backend(e.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{APIConfigResponse, APIGenesisResponse};
+    use alloy_primitives::B256;
+    use std::sync::{Arc, Mutex};
+
+    /// A [`BeaconClient`] that records the requests for sidecars into a shared log, and serves
+    /// none of them.
+    #[derive(Debug, Clone)]
+    struct MockBeaconClient {
+        name: &'static str,
+        requests: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl BeaconClient for MockBeaconClient {
+        type Error = String;
+
+        async fn config_spec(&self) -> Result<APIConfigResponse, Self::Error> {
+            Err("unsupported".to_string())
+        }
+
+        async fn beacon_genesis(&self) -> Result<APIGenesisResponse, Self::Error> {
+            Err("unsupported".to_string())
+        }
+
+        async fn beacon_blob_side_cars(
+            &self,
+            _: u64,
+            _: &[IndexedBlobHash],
+        ) -> Result<Vec<BlobData>, Self::Error> {
+            self.requests.lock().unwrap().push(self.name);
+            Ok(Vec::new())
+        }
+    }
+
+    /// Returns a provider backed by a hot and a cold client, along with their shared log of
+    /// requests.
+    fn provider(
+        after_secs: u64,
+    ) -> (OnlineBlobProvider<MockBeaconClient>, Arc<Mutex<Vec<&'static str>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let client = |name| MockBeaconClient { name, requests: requests.clone() };
+        let provider = OnlineBlobProvider {
+            beacon_client: client("hot"),
+            cold: None,
+            genesis_time: 0,
+            slot_interval: 12,
+            recorder: None,
+            sidecar_verification: SidecarVerification::default(),
+            l1_provider: None,
+        }
+        .with_cold(client("cold"), after_secs);
+        (provider, requests)
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    #[tokio::test]
+    async fn test_tiers_by_block_age() {
+        let hashes = [IndexedBlobHash { index: 0, hash: B256::ZERO }];
+
+        // Recent blocks are fetched from the hot client first.
+        let (provider, requests) = provider(3600);
+        let recent = BlockInfo { timestamp: now(), ..Default::default() };
+        assert!(provider.fetch_filtered_sidecars(&recent, &hashes).await.is_err());
+        assert_eq!(*requests.lock().unwrap(), ["hot", "cold"]);
+
+        // Old blocks are fetched from the cold client first.
+        let (provider, requests) = provider(3600);
+        let old = BlockInfo { timestamp: now() - 7200, ..Default::default() };
+        assert!(provider.fetch_filtered_sidecars(&old, &hashes).await.is_err());
+        assert_eq!(*requests.lock().unwrap(), ["cold", "hot"]);
+    }
+
+    #[tokio::test]
+    async fn test_tiers_without_cold_client() {
+        let (mut provider, requests) = provider(3600);
+        provider.cold = None;
+
+        let hashes = [IndexedBlobHash { index: 0, hash: B256::ZERO }];
+        let old = BlockInfo { timestamp: 12, ..Default::default() };
+        assert!(provider.fetch_filtered_sidecars(&old, &hashes).await.is_err());
+        assert_eq!(*requests.lock().unwrap(), ["hot"]);
+    }
+}
@@ -1,28 +1,137 @@
 //! Providers that use alloy provider types on the backend.
 
-use alloy_consensus::{Header, Receipt, TxEnvelope};
+use crate::{L1ArchiveError, L1Recorder};
+use alloy_consensus::{Header, Receipt, ReceiptEnvelope, TxEnvelope};
 use alloy_eips::BlockId;
-use alloy_primitives::B256;
+use alloy_primitives::{B256, map::HashSet};
 use alloy_provider::{Provider, RootProvider};
+use alloy_rpc_types_eth::Filter;
 use alloy_transport::{RpcError, TransportErrorKind};
 use async_trait::async_trait;
-use kona_derive::{ChainProvider, PipelineError, PipelineErrorKind};
+use kona_derive::{ChainProvider, LogFilter, PipelineError, PipelineErrorKind};
 use kona_protocol::BlockInfo;
 use lru::LruCache;
-use std::{boxed::Box, num::NonZeroUsize, vec::Vec};
+use std::{
+    boxed::Box,
+    num::NonZeroUsize,
+    ops::RangeInclusive,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    vec::Vec,
+};
+use tracing::{debug, error, warn};
+
+/// The default number of blocks covered by each `eth_getLogs` scan.
+const DEFAULT_LOG_SCAN_RANGE: u64 = 1000;
+
+/// The number of blocks that a scanned range must be behind the head, past which L1 reorgs are not
+/// expected.
+const LOG_SCAN_CONFIRMATIONS: u64 = 64;
+
+/// The JSON-RPC error code of the "pruned history unavailable" error of EIP-4444.
+const PRUNED_HISTORY_ERROR_CODE: i64 = 4444;
+
+/// The message of the "pruned history unavailable" error of EIP-4444, which some endpoints return
+/// with another error code.
+const PRUNED_HISTORY_ERROR_MESSAGE: &str = "pruned history unavailable";
+
+/// A tier of the endpoints of an [`AlloyChainProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tier {
+    /// The inner provider.
+    Hot,
+    /// The cold provider.
+    Cold,
+}
+
+/// The blocks with logs matching a set of [`LogFilter`]s, within a range of blocks scanned with
+/// `eth_getLogs`.
+#[derive(Debug, Clone)]
+struct LogScan {
+    /// The filters of the scan.
+    filters: Vec<LogFilter>,
+    /// The scanned range of block numbers.
+    range: RangeInclusive<u64>,
+    /// The hashes of the blocks with matching logs.
+    blocks: HashSet<B256>,
+}
+
+/// The highest L1 block number that an [`AlloyChainProvider`] serves blocks by number up to,
+/// advanced by another task, e.g. as L1 blocks gain confirmations.
+///
+/// The limit is cheaply cloneable, and its clones share the same value. It starts at zero, so no
+/// block past the genesis is served until it is first advanced.
+#[derive(Debug, Clone, Default)]
+pub struct L1BlockLimit(Arc<AtomicU64>);
+
+impl L1BlockLimit {
+    /// Returns the highest block number that may be served.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Sets the highest block number that may be served.
+    pub fn set(&self, number: u64) {
+        self.0.store(number, Ordering::Release);
+    }
+}
 
 /// The [AlloyChainProvider] is a concrete implementation of the [ChainProvider] trait, providing
 /// data over Ethereum JSON-RPC using an alloy provider as the backend.
+///
+/// The provider may be backed by two tiers of endpoints: the "hot" `inner` provider, e.g. a
+/// low-latency node used to track the head, and an optional "cold" provider, e.g. an archive node,
+/// set with [`Self::with_cold`]. Requests for blocks older than the configured age are routed to
+/// the cold provider first, and each tier serves as the fallback of the other.
+///
+/// If an [`L1Recorder`] is set with [`Self::with_recorder`], all data fetched from the network is
+/// recorded into its archive, to be served back by the [`ReplayProvider`].
+///
+/// Whether a block may contain logs of interest to the pipeline is answered from the logs bloom of
+/// its header. While catching up, the false positives of the blooms are weeded out by scanning
+/// ranges of blocks with `eth_getLogs`, see [`Self::with_log_scan_range`].
+///
+/// If an [`L1BlockLimit`] is set with [`Self::with_limit`], blocks past it are not served by
+/// number, so that the pipeline does not advance its origin past them.
+///
+/// The hot provider may have pruned the history the pipeline needs, e.g. after a long downtime. A
+/// block is considered pruned if the hot provider fails to serve it with a pruning error, or
+/// serves nothing for it while the cold provider serves it. The provider then logs the range of
+/// blocks that derivation requires, and fetches all blocks up to the pruned one from the cold
+/// provider first. Without a cold provider, requests for pruned blocks fail with
+/// [`AlloyChainProviderError::PrunedHistory`].
+///
+/// [`ReplayProvider`]: crate::ReplayProvider
 #[derive(Debug, Clone)]
 pub struct AlloyChainProvider {
     /// The inner Ethereum JSON-RPC provider.
     pub inner: RootProvider,
+    /// The cold tier, and the number of blocks behind the head after which it is queried first.
+    cold: Option<(RootProvider, u64)>,
+    /// The highest block number known to the provider, used to compute the age of blocks. Fetched
+    /// from the hot tier on the first routed request.
+    head: Option<u64>,
+    /// The block number below which the hot provider is known to have pruned its history, or zero
+    /// if it is not known to have pruned any.
+    pruned_below: u64,
+    /// The numbers of recently fetched blocks, used to route requests by hash.
+    block_numbers: LruCache<B256, u64>,
     /// `header_by_hash` LRU cache.
     header_by_hash_cache: LruCache<B256, Header>,
     /// `receipts_by_hash_cache` LRU cache.
     receipts_by_hash_cache: LruCache<B256, Vec<Receipt>>,
     /// `block_info_and_transactions_by_hash` LRU cache.
     block_info_and_transactions_by_hash_cache: LruCache<B256, (BlockInfo, Vec<TxEnvelope>)>,
+    /// The recorder that fetched data is recorded into, if any.
+    recorder: Option<L1Recorder>,
+    /// The number of blocks covered by each `eth_getLogs` scan, or zero if scans are disabled.
+    log_scan_range: u64,
+    /// The latest `eth_getLogs` scans, one per set of filters.
+    log_scans: Vec<LogScan>,
+    /// The highest block number served by number, if limited.
+    limit: Option<L1BlockLimit>,
 }
 
 impl AlloyChainProvider {
@@ -33,11 +142,19 @@ impl AlloyChainProvider {
     pub fn new(inner: RootProvider, cache_size: usize) -> Self {
         Self {
             inner,
+            cold: None,
+            head: None,
+            pruned_below: 0,
+            block_numbers: LruCache::new(NonZeroUsize::new(cache_size).unwrap()),
             header_by_hash_cache: LruCache::new(NonZeroUsize::new(cache_size).unwrap()),
             receipts_by_hash_cache: LruCache::new(NonZeroUsize::new(cache_size).unwrap()),
             block_info_and_transactions_by_hash_cache: LruCache::new(
                 NonZeroUsize::new(cache_size).unwrap(),
             ),
+            recorder: None,
+            log_scan_range: DEFAULT_LOG_SCAN_RANGE,
+            log_scans: Vec::new(),
+            limit: None,
         }
     }
 
@@ -47,6 +164,165 @@ impl AlloyChainProvider {
         Self::new(inner, cache_size)
     }
 
+    /// Sets the cold tier of the provider, which is queried first for blocks more than
+    /// `after_blocks` behind the head.
+    pub fn with_cold(mut self, cold: RootProvider, after_blocks: u64) -> Self {
+        self.cold = Some((cold, after_blocks));
+        self
+    }
+
+    /// Sets the recorder that all data fetched from the network is recorded into.
+    pub fn with_recorder(mut self, recorder: L1Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Sets the [`L1BlockLimit`] past which blocks are not served by number. Requesting such a
+    /// block fails with [`AlloyChainProviderError::BlockBeyondLimit`], which the pipeline treats
+    /// as the end of its data source, until the limit is advanced.
+    pub fn with_limit(mut self, limit: L1BlockLimit) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the number of blocks covered by each `eth_getLogs` scan, 1000 by default. Zero
+    /// disables the scans, in which case the logs blooms of the block headers are trusted.
+    ///
+    /// A block whose bloom may match is looked up in a scan of the range of blocks starting at it,
+    /// if that range is far enough behind the head, which answers for all blocks of the range with
+    /// one request. Scans are disabled after the first failed request, e.g. if the endpoint limits
+    /// the range of `eth_getLogs` queries, and while recording, since they are not recorded.
+    pub const fn with_log_scan_range(mut self, blocks: u64) -> Self {
+        self.log_scan_range = blocks;
+        self
+    }
+
+    /// Returns whether the block with the given hash and number has logs matching the given
+    /// filters, according to an `eth_getLogs` scan of the range of blocks starting at it. Returns
+    /// `None` if the block cannot be scanned.
+    async fn scan_logs(&mut self, hash: B256, number: u64, filters: &[LogFilter]) -> Option<bool> {
+        if self.log_scan_range == 0 || self.recorder.is_some() {
+            return None;
+        }
+        if let Some(scan) = self
+            .log_scans
+            .iter()
+            .find(|scan| scan.filters == filters && scan.range.contains(&number))
+        {
+            return Some(scan.blocks.contains(&hash));
+        }
+
+        // Only ranges that are far behind the head, i.e. while catching up, are scanned.
+        let to = number.saturating_add(self.log_scan_range - 1);
+        let head = self.inner.get_block_number().await.ok()?;
+        if to.saturating_add(LOG_SCAN_CONFIRMATIONS) > head {
+            return None;
+        }
+
+        let filter = Filter::new()
+            .from_block(number)
+            .to_block(to)
+            .address(filters.iter().map(|filter| filter.address).collect::<Vec<_>>())
+            .event_signature(filters.iter().map(|filter| filter.topic).collect::<Vec<_>>());
+        let (_, provider) = self.tiers(Some(number)).await.swap_remove(0);
+        let Ok(logs) = provider.get_logs(&filter).await else {
+            self.log_scan_range = 0;
+            return None;
+        };
+        let blocks = logs.iter().filter_map(|log| log.block_hash).collect::<HashSet<_>>();
+        let contains = blocks.contains(&hash);
+        self.log_scans.retain(|scan| scan.filters != filters);
+        self.log_scans.push(LogScan { filters: filters.to_vec(), range: number..=to, blocks });
+        Some(contains)
+    }
+
+    /// Records data into the recorder, if one is set.
+    fn record(
+        &self,
+        record: impl FnOnce(&L1Recorder) -> Result<(), L1ArchiveError>,
+    ) -> Result<(), AlloyChainProviderError> {
+        self.recorder.as_ref().map_or(Ok(()), record).map_err(AlloyChainProviderError::Record)
+    }
+
+    /// Returns the providers to query for the block with the given number, in order. Requests for
+    /// blocks of unknown age go to the hot tier first, and requests for blocks pruned by the hot
+    /// tier go to the cold tier first.
+    async fn tiers(&mut self, number: Option<u64>) -> Vec<(Tier, RootProvider)> {
+        let hot = (Tier::Hot, self.inner.clone());
+        let Some((cold, after_blocks)) = self.cold.clone() else {
+            return vec![hot];
+        };
+        if self.head.is_none() {
+            // The head is fetched again on the next request if it cannot be fetched now.
+            self.head = self.inner.get_block_number().await.ok();
+        }
+        let (pruned_below, head) = (self.pruned_below, self.head.unwrap_or_default());
+        if number.is_some_and(|n| n < pruned_below || n.saturating_add(after_blocks) < head) {
+            vec![(Tier::Cold, cold), hot]
+        } else {
+            vec![hot, (Tier::Cold, cold)]
+        }
+    }
+
+    /// Records the number of a fetched block, so that later requests by its hash are routed by
+    /// its age. The block advances the head, once known.
+    fn observe(&mut self, hash: B256, number: u64) {
+        if let Some(head) = self.head.as_mut() {
+            *head = (*head).max(number);
+        }
+        self.block_numbers.put(hash, number);
+    }
+
+    /// Records that the hot tier pruned the given block, whose number is given if known. Blocks up
+    /// to it are fetched from the cold tier first from now on, if any, otherwise
+    /// [`AlloyChainProviderError::PrunedHistory`] is returned. The required range of blocks is
+    /// logged the first time the pruning horizon is pushed past a block.
+    async fn pruned(
+        &mut self,
+        block: BlockId,
+        number: Option<u64>,
+    ) -> Result<(), AlloyChainProviderError> {
+        let advanced = number.is_some_and(|n| n >= self.pruned_below);
+        if let Some(n) = number {
+            self.pruned_below = self.pruned_below.max(n.saturating_add(1));
+        }
+
+        if self.cold.is_some() {
+            if advanced {
+                warn!(
+                    target: "l1_provider",
+                    %block,
+                    pruned_below = self.pruned_below,
+                    "L1 RPC has pruned history required by derivation, fetching older blocks from the cold L1 RPC"
+                );
+            }
+            return Ok(());
+        }
+
+        match number {
+            Some(n) if advanced => {
+                let head =
+                    self.inner.get_block_number().await.unwrap_or(self.head.unwrap_or_default());
+                error!(
+                    target: "l1_provider",
+                    %block,
+                    required = %format!("{n}..={head}"),
+                    "L1 RPC has pruned history required by derivation. Configure a cold L1 RPC serving the required blocks, e.g. an archive node"
+                );
+            }
+            Some(_) => {}
+            None => {
+                debug!(target: "l1_provider", %block, "L1 RPC has pruned the requested block");
+            }
+        }
+        Err(AlloyChainProviderError::PrunedHistory(block))
+    }
+
+    /// Returns the number of the block with the given hash, if it was recently fetched.
+    fn number_of(&mut self, hash: &B256) -> Option<u64> {
+        self.block_numbers.get(hash).copied()
+    }
+
     /// Returns the latest L2 block number.
     pub async fn latest_block_number(&mut self) -> Result<u64, RpcError<TransportErrorKind>> {
         self.inner.get_block_number().await
@@ -58,6 +334,43 @@ impl AlloyChainProvider {
     }
 }
 
+/// Queries the given tiers in order, until one of them returns the requested data. Also returns
+/// whether the hot tier pruned the data: it failed with a pruning error, or returned nothing while
+/// the cold tier returned the data.
+async fn query_tiers<T, F, Fut>(
+    tiers: Vec<(Tier, RootProvider)>,
+    query: F,
+) -> (Result<Option<T>, RpcError<TransportErrorKind>>, bool)
+where
+    F: Fn(RootProvider) -> Fut,
+    Fut: Future<Output = Result<Option<T>, RpcError<TransportErrorKind>>>,
+{
+    let mut result = Ok(None);
+    let (mut hot_pruned, mut hot_empty) = (false, false);
+    for (tier, provider) in tiers {
+        result = query(provider).await;
+        if tier == Tier::Hot {
+            hot_pruned = result.as_ref().is_err_and(is_pruned);
+            hot_empty = matches!(result, Ok(None));
+        }
+        if matches!(result, Ok(Some(_))) {
+            break;
+        }
+    }
+    let pruned = hot_pruned || (hot_empty && matches!(result, Ok(Some(_))));
+    (result, pruned)
+}
+
+/// Returns `true` if the error is the "pruned history unavailable" error of EIP-4444, identified
+/// by its code or its message. Other errors, e.g. for blocks that are not available yet, are not
+/// treated as pruned history.
+fn is_pruned(err: &RpcError<TransportErrorKind>) -> bool {
+    err.as_error_resp().is_some_and(|resp| {
+        resp.code == PRUNED_HISTORY_ERROR_CODE ||
+            resp.message.to_lowercase().contains(PRUNED_HISTORY_ERROR_MESSAGE)
+    })
+}
+
 /// An error for the [AlloyChainProvider].
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, thiserror::Error)]
@@ -71,6 +384,20 @@ pub enum AlloyChainProviderError {
     /// Failed to convert RPC receipts into consensus receipts.
     #[error("Failed to convert RPC receipts into consensus receipts {0}")]
     ReceiptsConversion(B256),
+    /// Failed to record fetched data into the L1 archive.
+    #[error("Failed to record L1 data: {0}")]
+    Record(L1ArchiveError),
+    /// The block was pruned by the hot provider, and no cold provider is set.
+    #[error("Block {0} was pruned by the L1 RPC")]
+    PrunedHistory(BlockId),
+    /// The block is past the [`L1BlockLimit`] of the provider.
+    #[error("Block {number} is beyond the limit {limit}")]
+    BlockBeyondLimit {
+        /// The number of the requested block.
+        number: u64,
+        /// The limit of the provider.
+        limit: u64,
+    },
 }
 
 impl From<AlloyChainProviderError> for PipelineErrorKind {
@@ -87,6 +414,13 @@ impl From<AlloyChainProviderError> for PipelineErrorKind {
                     "Failed to convert RPC receipts into consensus receipts".to_string(),
                 ))
             }
+            AlloyChainProviderError::Record(e) => PipelineErrorKind::Temporary(
+                PipelineError::Provider(format!("Failed to record L1 data: {e}")),
+            ),
+            AlloyChainProviderError::PrunedHistory(id) => PipelineErrorKind::Temporary(
+                PipelineError::Provider(format!("L1 block {id} was pruned by the L1 RPC")),
+            ),
+            AlloyChainProviderError::BlockBeyondLimit { .. } => PipelineError::Eof.temp(),
         }
     }
 }
@@ -100,25 +434,37 @@ impl ChainProvider for AlloyChainProvider {
             return Ok(header.clone());
         }
 
-        let block = self
-            .inner
-            .get_block_by_hash(hash)
-            .await?
-            .ok_or(AlloyChainProviderError::BlockNotFound(hash.into()))?;
+        let number = self.number_of(&hash);
+        let tiers = self.tiers(number).await;
+        let (block, pruned) =
+            query_tiers(tiers, |p| async move { p.get_block_by_hash(hash).await }).await;
+        if pruned {
+            self.pruned(hash.into(), number).await?;
+        }
+        let block = block?.ok_or(AlloyChainProviderError::BlockNotFound(hash.into()))?;
         let header = block.header.into_consensus();
+        self.record(|r| r.record_header(&header, false))?;
 
+        self.observe(hash, header.number);
         self.header_by_hash_cache.put(hash, header.clone());
 
         Ok(header)
     }
 
     async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo, Self::Error> {
-        let block = self
-            .inner
-            .get_block_by_number(number.into())
-            .await?
-            .ok_or(AlloyChainProviderError::BlockNotFound(number.into()))?;
+        if let Some(limit) = self.limit.as_ref().map(L1BlockLimit::get).filter(|l| number > *l) {
+            return Err(AlloyChainProviderError::BlockBeyondLimit { number, limit });
+        }
+
+        let tiers = self.tiers(Some(number)).await;
+        let (block, pruned) =
+            query_tiers(tiers, |p| async move { p.get_block_by_number(number.into()).await }).await;
+        if pruned {
+            self.pruned(number.into(), Some(number)).await?;
+        }
+        let block = block?.ok_or(AlloyChainProviderError::BlockNotFound(number.into()))?;
         let header = block.header.into_consensus();
+        self.record(|r| r.record_header(&header, true))?;
 
         let block_info = BlockInfo {
             hash: header.hash_slow(),
@@ -126,6 +472,9 @@ impl ChainProvider for AlloyChainProvider {
             parent_hash: header.parent_hash,
             timestamp: header.timestamp,
         };
+        self.observe(block_info.hash, number);
+        // The header is usually requested next, e.g. to check its logs bloom.
+        self.header_by_hash_cache.put(block_info.hash, header);
         Ok(block_info)
     }
 
@@ -134,21 +483,41 @@ impl ChainProvider for AlloyChainProvider {
             return Ok(receipts.clone());
         }
 
-        let receipts = self
-            .inner
-            .get_block_receipts(hash.into())
-            .await?
-            .ok_or(AlloyChainProviderError::BlockNotFound(hash.into()))?;
-        let consensus_receipts = receipts
+        let number = self.number_of(&hash);
+        let tiers = self.tiers(number).await;
+        let (receipts, pruned) =
+            query_tiers(tiers, |p| async move { p.get_block_receipts(hash.into()).await }).await;
+        if pruned {
+            self.pruned(hash.into(), number).await?;
+        }
+        let receipts = receipts?.ok_or(AlloyChainProviderError::BlockNotFound(hash.into()))?;
+        let envelopes = receipts
             .into_iter()
-            .map(|r| r.inner.into_primitives_receipt().as_receipt().cloned())
+            .map(|r| r.inner.into_primitives_receipt())
+            .collect::<Vec<ReceiptEnvelope>>();
+        let consensus_receipts = envelopes
+            .iter()
+            .map(|r| r.as_receipt().cloned())
             .collect::<Option<Vec<_>>>()
             .ok_or(AlloyChainProviderError::ReceiptsConversion(hash))?;
+        self.record(|r| r.record_receipts(hash, &envelopes))?;
 
         self.receipts_by_hash_cache.put(hash, consensus_receipts.clone());
         Ok(consensus_receipts)
     }
 
+    async fn may_contain_logs(
+        &mut self,
+        hash: B256,
+        filters: &[LogFilter],
+    ) -> Result<bool, Self::Error> {
+        let header = self.header_by_hash(hash).await?;
+        if !filters.iter().any(|filter| filter.matches_bloom(&header.logs_bloom)) {
+            return Ok(false);
+        }
+        Ok(self.scan_logs(hash, header.number, filters).await.unwrap_or(true))
+    }
+
     async fn block_info_and_transactions_by_hash(
         &mut self,
         hash: B256,
@@ -158,11 +527,14 @@ impl ChainProvider for AlloyChainProvider {
             return Ok(block_info_and_txs.clone());
         }
 
-        let block = self
-            .inner
-            .get_block_by_hash(hash)
-            .full()
-            .await?
+        let number = self.number_of(&hash);
+        let tiers = self.tiers(number).await;
+        let (block, pruned) =
+            query_tiers(tiers, |p| async move { p.get_block_by_hash(hash).full().await }).await;
+        if pruned {
+            self.pruned(hash.into(), number).await?;
+        }
+        let block = block?
             .ok_or(AlloyChainProviderError::BlockNotFound(hash.into()))?
             .into_consensus()
             .map_transactions(|t| t.inner.into_inner());
@@ -173,10 +545,161 @@ impl ChainProvider for AlloyChainProvider {
             parent_hash: block.header.parent_hash,
             timestamp: block.header.timestamp,
         };
+        self.record(|r| {
+            r.record_header(&block.header, false)?;
+            r.record_transactions(hash, &block.body.transactions)
+        })?;
 
+        self.observe(hash, block_info.number);
         self.block_info_and_transactions_by_hash_cache
             .put(hash, (block_info, block.body.transactions.clone()));
 
         Ok((block_info, block.body.transactions))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_json_rpc::ErrorPayload;
+    use alloy_primitives::U64;
+    use alloy_provider::mock::{Asserter, MockTransport};
+    use alloy_rpc_client::RpcClient;
+
+    /// Returns a provider answering the requests with the responses queued in the asserter.
+    fn provider(asserter: &Asserter) -> RootProvider {
+        RootProvider::new(RpcClient::new(MockTransport::new(asserter.clone()), false))
+    }
+
+    /// Returns an error response with the given code and message.
+    fn error(code: i64, message: &'static str) -> ErrorPayload {
+        ErrorPayload { code, message: message.into(), data: None }
+    }
+
+    /// Returns a block with the given number, whose timestamp identifies the tier serving it.
+    fn block(number: u64, timestamp: u64) -> alloy_rpc_types_eth::Block {
+        let header = Header { number, timestamp, ..Default::default() };
+        alloy_rpc_types_eth::Block {
+            header: alloy_rpc_types_eth::Header::new(header),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tiers_by_block_age() {
+        let (hot, cold) = (Asserter::new(), Asserter::new());
+        let mut chain = AlloyChainProvider::new(provider(&hot), 16).with_cold(provider(&cold), 50);
+
+        // Blocks far behind the head are fetched from the cold tier first.
+        hot.push_success(&U64::from(100));
+        cold.push_success(&block(10, 2));
+        assert_eq!(chain.block_info_by_number(10).await.unwrap().timestamp, 2);
+
+        // Recent blocks are fetched from the hot tier first.
+        hot.push_success(&block(90, 1));
+        assert_eq!(chain.block_info_by_number(90).await.unwrap().timestamp, 1);
+
+        // The cold tier serves as the fallback of the hot tier.
+        hot.push_failure_msg("unavailable");
+        cold.push_success(&block(95, 2));
+        assert_eq!(chain.block_info_by_number(95).await.unwrap().timestamp, 2);
+    }
+
+    #[tokio::test]
+    async fn test_head_fetched_once() {
+        let (hot, cold) = (Asserter::new(), Asserter::new());
+        let mut chain = AlloyChainProvider::new(provider(&hot), 16).with_cold(provider(&cold), 10);
+
+        // The head is fetched once, even if the chain is at genesis.
+        hot.push_success(&U64::ZERO);
+        hot.push_success(&block(0, 1));
+        hot.push_success(&block(0, 3));
+        cold.push_success(&block(0, 2));
+        assert_eq!(chain.block_info_by_number(0).await.unwrap().timestamp, 1);
+        assert_eq!(chain.block_info_by_number(0).await.unwrap().timestamp, 3);
+    }
+
+    #[tokio::test]
+    async fn test_head_fetched_again_after_failure() {
+        let (hot, cold) = (Asserter::new(), Asserter::new());
+        let mut chain = AlloyChainProvider::new(provider(&hot), 16).with_cold(provider(&cold), 50);
+
+        // Without a head, blocks are fetched from the hot tier first.
+        hot.push_failure_msg("unavailable");
+        hot.push_success(&block(10, 1));
+        assert_eq!(chain.block_info_by_number(10).await.unwrap().timestamp, 1);
+
+        // The fetched block does not stand in for the head, which is fetched on the next request.
+        hot.push_success(&U64::from(100));
+        cold.push_success(&block(20, 2));
+        assert_eq!(chain.block_info_by_number(20).await.unwrap().timestamp, 2);
+    }
+
+    #[test]
+    fn test_is_pruned() {
+        let pruned = |code, message| is_pruned(&RpcError::ErrorResp(error(code, message)));
+        assert!(pruned(PRUNED_HISTORY_ERROR_CODE, "unknown block"));
+        assert!(pruned(-32000, "Pruned history unavailable"));
+        assert!(!pruned(-32000, "header not available"));
+        assert!(!pruned(-32000, "block not found"));
+        assert!(!is_pruned(&RpcError::NullResp));
+    }
+
+    #[tokio::test]
+    async fn test_hot_pruned_routes_to_cold() {
+        let (hot, cold) = (Asserter::new(), Asserter::new());
+        let mut chain =
+            AlloyChainProvider::new(provider(&hot), 16).with_cold(provider(&cold), 1000);
+
+        // A recent block is fetched from the hot tier first, which pruned it.
+        hot.push_success(&U64::from(100));
+        hot.push_failure(error(PRUNED_HISTORY_ERROR_CODE, "pruned history unavailable"));
+        cold.push_success(&block(80, 2));
+        assert_eq!(chain.block_info_by_number(80).await.unwrap().timestamp, 2);
+
+        // Blocks up to the pruned one are now fetched from the cold tier first.
+        cold.push_success(&block(70, 2));
+        assert_eq!(chain.block_info_by_number(70).await.unwrap().timestamp, 2);
+
+        // Later blocks are still fetched from the hot tier first.
+        hot.push_success(&block(90, 1));
+        assert_eq!(chain.block_info_by_number(90).await.unwrap().timestamp, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_block_not_pruned() {
+        let (hot, cold) = (Asserter::new(), Asserter::new());
+        let mut chain =
+            AlloyChainProvider::new(provider(&hot), 16).with_cold(provider(&cold), 1000);
+
+        // A block that the hot tier does not serve yet is fetched from the cold tier, without
+        // routing older blocks to it.
+        hot.push_success(&U64::from(100));
+        hot.push_failure(error(-32000, "header not available"));
+        cold.push_success(&block(80, 2));
+        assert_eq!(chain.block_info_by_number(80).await.unwrap().timestamp, 2);
+
+        hot.push_success(&block(70, 1));
+        assert_eq!(chain.block_info_by_number(70).await.unwrap().timestamp, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pruned_history_without_cold_tier() {
+        let hot = Asserter::new();
+        let mut chain = AlloyChainProvider::new(provider(&hot), 16);
+
+        hot.push_failure(error(PRUNED_HISTORY_ERROR_CODE, "pruned history unavailable"));
+        hot.push_success(&U64::from(100));
+        assert!(matches!(
+            chain.block_info_by_number(80).await,
+            Err(AlloyChainProviderError::PrunedHistory(block)) if block == BlockId::from(80)
+        ));
+
+        // Other errors are returned as is.
+        hot.push_failure(error(-32000, "header not available"));
+        assert!(matches!(
+            chain.block_info_by_number(90).await,
+            Err(AlloyChainProviderError::Transport(_))
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+//! Fault injection for the L1 provider, the beacon client and the engine client.
+//!
+//! A [`Chaos`] injector delays or fails requests at configured rates. It is applied to RPC
+//! transports with the [`ChaosLayer`], and to the [`OnlineBeaconClient`] with
+//! [`OnlineBeaconClient::with_chaos`], so that the retry and reset logic of the node can be
+//! exercised against unreliable backends.
+//!
+//! [`OnlineBeaconClient`]: crate::OnlineBeaconClient
+//! [`OnlineBeaconClient::with_chaos`]: crate::OnlineBeaconClient::with_chaos
+
+use alloy_json_rpc::{ErrorPayload, RequestPacket, ResponsePacket};
+use alloy_provider::{Provider, RootProvider, network::Network};
+use alloy_rpc_client::RpcClient;
+use alloy_transport::{TransportError, TransportErrorKind, TransportFut};
+use std::{
+    io,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// The JSON-RPC error code of injected error responses.
+const INTERNAL_ERROR_CODE: i64 = -32603;
+
+/// The increment of the SplitMix64 generator.
+const SPLITMIX_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The configuration of a [`Chaos`] injector.
+///
+/// Each rate is the probability, between 0 and 1, that a request is affected by the fault. A
+/// request is first delayed by a latency spike, and may then fail with a connection reset or an
+/// error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChaosConfig {
+    /// The probability that a request fails with an error.
+    pub error_rate: f64,
+    /// The probability that a request fails with a connection reset.
+    pub reset_rate: f64,
+    /// The probability that a request is delayed by [`Self::latency`].
+    pub latency_rate: f64,
+    /// The latency spike added to delayed requests.
+    pub latency: Duration,
+    /// The seed of the random number generator, making a sequence of faults reproducible.
+    pub seed: u64,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            error_rate: 0.0,
+            reset_rate: 0.0,
+            latency_rate: 0.0,
+            latency: Duration::from_millis(500),
+            seed: 0,
+        }
+    }
+}
+
+impl ChaosConfig {
+    /// Sets the probability that a request fails with an error.
+    pub const fn with_error_rate(mut self, rate: f64) -> Self {
+        self.error_rate = rate;
+        self
+    }
+
+    /// Sets the probability that a request fails with a connection reset.
+    pub const fn with_reset_rate(mut self, rate: f64) -> Self {
+        self.reset_rate = rate;
+        self
+    }
+
+    /// Sets the probability that a request is delayed, and the latency spike added to it.
+    pub const fn with_latency(mut self, rate: f64, latency: Duration) -> Self {
+        self.latency_rate = rate;
+        self.latency = latency;
+        self
+    }
+
+    /// Sets the seed of the random number generator.
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// A fault injected into a request by a [`Chaos`] injector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ChaosFault {
+    /// The request failed with an error.
+    #[error("Chaos: injected error")]
+    Error,
+    /// The connection was reset.
+    #[error("Chaos: injected connection reset")]
+    ConnectionReset,
+}
+
+/// The number of requests seen, and faults injected, by a [`Chaos`] injector.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ChaosStats {
+    /// The number of requests.
+    pub requests: u64,
+    /// The number of delayed requests.
+    pub delayed: u64,
+    /// The number of requests failed with an error.
+    pub errors: u64,
+    /// The number of requests failed with a connection reset.
+    pub resets: u64,
+}
+
+/// A fault injector, delaying and failing requests at the rates of its [`ChaosConfig`].
+///
+/// Clones share the same random number generator and [`ChaosStats`], so that a single injector
+/// can be applied to several clients.
+#[derive(Debug, Clone)]
+pub struct Chaos {
+    inner: Arc<ChaosInner>,
+}
+
+/// The shared state of a [`Chaos`] injector.
+#[derive(Debug)]
+struct ChaosInner {
+    config: ChaosConfig,
+    /// The state of the SplitMix64 generator.
+    rng: AtomicU64,
+    requests: AtomicU64,
+    delayed: AtomicU64,
+    errors: AtomicU64,
+    resets: AtomicU64,
+}
+
+impl Chaos {
+    /// Creates a new [`Chaos`] injector with the given [`ChaosConfig`].
+    pub fn new(config: ChaosConfig) -> Self {
+        Self {
+            inner: Arc::new(ChaosInner {
+                config,
+                rng: AtomicU64::new(config.seed),
+                requests: AtomicU64::new(0),
+                delayed: AtomicU64::new(0),
+                errors: AtomicU64::new(0),
+                resets: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Returns the [`ChaosConfig`] of the injector.
+    pub fn config(&self) -> &ChaosConfig {
+        &self.inner.config
+    }
+
+    /// Returns the [`ChaosStats`] of the injector so far.
+    pub fn stats(&self) -> ChaosStats {
+        ChaosStats {
+            requests: self.inner.requests.load(Ordering::Relaxed),
+            delayed: self.inner.delayed.load(Ordering::Relaxed),
+            errors: self.inner.errors.load(Ordering::Relaxed),
+            resets: self.inner.resets.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Applies the faults to a request: delays it by a latency spike, then fails it with a
+    /// connection reset or an error, each at its configured rate.
+    pub async fn inject(&self) -> Result<(), ChaosFault> {
+        let config = &self.inner.config;
+        self.inner.requests.fetch_add(1, Ordering::Relaxed);
+        if self.roll(config.latency_rate) {
+            self.inner.delayed.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(config.latency).await;
+        }
+        if self.roll(config.reset_rate) {
+            self.inner.resets.fetch_add(1, Ordering::Relaxed);
+            return Err(ChaosFault::ConnectionReset);
+        }
+        if self.roll(config.error_rate) {
+            self.inner.errors.fetch_add(1, Ordering::Relaxed);
+            return Err(ChaosFault::Error);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` with the given probability.
+    fn roll(&self, rate: f64) -> bool {
+        if rate <= 0.0 {
+            return false;
+        }
+        let mut z = self.inner.rng.fetch_add(SPLITMIX_GAMMA, Ordering::Relaxed);
+        z = z.wrapping_add(SPLITMIX_GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        ((z >> 11) as f64 / (1u64 << 53) as f64) < rate
+    }
+}
+
+/// A [`tower::Layer`] injecting the faults of a [`Chaos`] injector into the requests of an RPC
+/// transport.
+///
+/// Injected errors are returned as JSON-RPC internal error responses, and connection resets as
+/// transport errors.
+#[derive(Debug, Clone)]
+pub struct ChaosLayer {
+    chaos: Chaos,
+}
+
+impl ChaosLayer {
+    /// Creates a new [`ChaosLayer`] for the given injector.
+    pub const fn new(chaos: Chaos) -> Self {
+        Self { chaos }
+    }
+
+    /// Returns a copy of the given provider whose transport is wrapped in the layer.
+    pub fn provider<N: Network>(&self, provider: &RootProvider<N>) -> RootProvider<N> {
+        let client = provider.client();
+        let transport = tower::Layer::layer(self, client.transport().clone());
+        RootProvider::new(RpcClient::new(transport, client.is_local()))
+    }
+}
+
+impl<S> tower::Layer<S> for ChaosLayer {
+    type Service = ChaosService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ChaosService { inner, chaos: self.chaos.clone() }
+    }
+}
+
+/// An RPC transport injecting the faults of a [`Chaos`] injector. See [`ChaosLayer`].
+#[derive(Debug, Clone)]
+pub struct ChaosService<S> {
+    inner: S,
+    chaos: Chaos,
+}
+
+impl<S> tower::Service<RequestPacket> for ChaosService<S>
+where
+    S: tower::Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        // Take the service that was driven to readiness, leaving a clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let chaos = self.chaos.clone();
+        Box::pin(async move {
+            match chaos.inject().await {
+                Ok(()) => inner.call(request).await,
+                Err(fault @ ChaosFault::Error) => Err(TransportError::ErrorResp(ErrorPayload {
+                    code: INTERNAL_ERROR_CODE,
+                    message: fault.to_string().into(),
+                    data: None,
+                })),
+                Err(fault @ ChaosFault::ConnectionReset) => Err(TransportErrorKind::custom(
+                    io::Error::new(io::ErrorKind::ConnectionReset, fault),
+                )),
+            }
+        })
+    }
+}
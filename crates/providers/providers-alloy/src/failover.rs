@@ -0,0 +1,280 @@
+//! Contains a [`BeaconClient`] that fails over between several beacon API endpoints.
+
+use crate::{
+    APIConfigResponse, APIGenesisResponse, BeaconClient, OnlineBeaconClient,
+    OnlineBeaconClientError,
+    beacon_client::{SIDECARS_METHOD_PREFIX, SPEC_METHOD},
+};
+use alloy_eips::eip4844::IndexedBlobHash;
+use alloy_rpc_types_beacon::sidecar::{BeaconBlobBundle, BlobData};
+use async_trait::async_trait;
+use core::fmt;
+use std::{format, string::String, vec::Vec};
+use tracing::{debug, info, warn};
+
+/// The beacon block header engine api method for the head block.
+const HEAD_HEADER_METHOD: &str = "eth/v1/beacon/headers/head";
+
+/// The number of slots right before the retention window of an endpoint that are probed for
+/// sidecars, to tell blob archivers apart from beacon nodes that prune their blobs.
+const ARCHIVE_PROBE_SLOTS: u64 = 4;
+
+/// The range of slots that a beacon API endpoint serves blob sidecars for.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SidecarWindow {
+    /// The range is unknown, e.g. because the endpoint could not be probed.
+    #[default]
+    Unknown,
+    /// The endpoint serves the sidecars of the given slot onwards, and prunes older ones.
+    From(u64),
+    /// The endpoint serves the sidecars of all slots, e.g. a blob archiver.
+    Archive,
+}
+
+impl SidecarWindow {
+    /// Returns whether the window contains the given slot, or `None` if the window is unknown.
+    pub const fn contains(&self, slot: u64) -> Option<bool> {
+        match self {
+            Self::Unknown => None,
+            Self::From(earliest) => Some(slot >= *earliest),
+            Self::Archive => Some(true),
+        }
+    }
+}
+
+impl fmt::Display for SidecarWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => f.write_str("unknown"),
+            Self::From(earliest) => write!(f, "from slot {earliest}"),
+            Self::Archive => f.write_str("archive"),
+        }
+    }
+}
+
+/// An error for the [`FailoverBeaconClient`].
+#[derive(Debug, thiserror::Error)]
+pub enum FailoverBeaconClientError {
+    /// No beacon API endpoint is configured.
+    #[error("No beacon API endpoint configured")]
+    NoEndpoints,
+    /// All beacon API endpoints failed.
+    #[error("All beacon API endpoints failed, last error: {0}")]
+    Exhausted(String),
+}
+
+/// A [`BeaconClient`] backed by several beacon API endpoints, e.g. beacon nodes and blob
+/// archivers.
+///
+/// The [`SidecarWindow`] of each endpoint is probed once, with [`Self::probe`]. Blob sidecars
+/// are requested from the endpoints whose window contains the requested slot first, then from
+/// those whose window is unknown, and finally from the others, each group in the configured
+/// order. If an endpoint fails, or misses some of the requested sidecars, e.g. because it pruned
+/// them since it was probed, the request resumes from the next endpoint, for the missing sidecars
+/// only.
+///
+/// The other requests are sent to the endpoints in the configured order, until one succeeds.
+#[derive(Debug, Clone)]
+pub struct FailoverBeaconClient<B = OnlineBeaconClient> {
+    /// The endpoints, with their sidecar windows, in the configured order.
+    pub endpoints: Vec<(B, SidecarWindow)>,
+}
+
+impl<B> FailoverBeaconClient<B> {
+    /// Creates a new [`FailoverBeaconClient`] from the given endpoints and their windows.
+    pub const fn new(endpoints: Vec<(B, SidecarWindow)>) -> Self {
+        Self { endpoints }
+    }
+
+    /// Returns the endpoints to request the sidecars of the given slot from, in order.
+    fn route(&self, slot: u64) -> Vec<&B> {
+        let mut endpoints = self.endpoints.iter().collect::<Vec<_>>();
+        endpoints.sort_by_key(|(_, window)| match window.contains(slot) {
+            Some(true) => 0,
+            None => 1,
+            Some(false) => 2,
+        });
+        endpoints.into_iter().map(|(client, _)| client).collect()
+    }
+}
+
+impl FailoverBeaconClient<OnlineBeaconClient> {
+    /// Creates a new [`FailoverBeaconClient`] from the given endpoints, probing the
+    /// [`SidecarWindow`] of each. Endpoints that cannot be probed are kept, with an unknown
+    /// window.
+    pub async fn probe(clients: Vec<OnlineBeaconClient>) -> Self {
+        let mut endpoints = Vec::with_capacity(clients.len());
+        for client in clients {
+            let window = match probe_window(&client).await {
+                Ok(window) => window,
+                Err(err) => {
+                    warn!(target: "beacon_failover", base = %client.base, %err, "Failed to probe blob sidecar availability");
+                    SidecarWindow::Unknown
+                }
+            };
+            info!(target: "beacon_failover", base = %client.base, %window, "Probed blob sidecar availability");
+            endpoints.push((client, window));
+        }
+        Self::new(endpoints)
+    }
+}
+
+impl From<OnlineBeaconClient> for FailoverBeaconClient<OnlineBeaconClient> {
+    fn from(client: OnlineBeaconClient) -> Self {
+        Self::new(vec![(client, SidecarWindow::Unknown)])
+    }
+}
+
+/// A reduced beacon block header response, holding the slot of the block.
+#[derive(Debug, serde::Deserialize)]
+struct APIHeaderResponse {
+    /// The data.
+    data: APIHeaderData,
+}
+
+/// The data of an [`APIHeaderResponse`].
+#[derive(Debug, serde::Deserialize)]
+struct APIHeaderData {
+    /// The signed block header.
+    header: APISignedHeader,
+}
+
+/// A reduced signed beacon block header.
+#[derive(Debug, serde::Deserialize)]
+struct APISignedHeader {
+    /// The block header.
+    message: APIHeaderMessage,
+}
+
+/// A reduced beacon block header.
+#[derive(Debug, serde::Deserialize)]
+struct APIHeaderMessage {
+    /// The slot of the block.
+    #[serde(with = "alloy_serde::quantity")]
+    slot: u64,
+}
+
+/// A reduced config spec response, holding the blob retention parameters.
+#[derive(Debug, serde::Deserialize)]
+struct APIRetentionResponse {
+    /// The data.
+    data: APIRetentionData,
+}
+
+/// The blob retention parameters of the config spec.
+#[derive(Debug, serde::Deserialize)]
+struct APIRetentionData {
+    /// The number of epochs that beacon nodes serve blob sidecars for.
+    #[serde(rename = "MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS")]
+    #[serde(with = "alloy_serde::quantity")]
+    min_epochs_for_blob_sidecars_requests: u64,
+    /// The number of slots per epoch.
+    #[serde(rename = "SLOTS_PER_EPOCH")]
+    #[serde(with = "alloy_serde::quantity")]
+    slots_per_epoch: u64,
+}
+
+/// Probes the [`SidecarWindow`] of the given endpoint.
+///
+/// Beacon nodes only retain the sidecars of the last `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS`
+/// epochs. The endpoint is considered an archive if it serves sidecars for any of the slots right
+/// before that window.
+async fn probe_window(
+    client: &OnlineBeaconClient,
+) -> Result<SidecarWindow, OnlineBeaconClientError> {
+    let head = client.get(HEAD_HEADER_METHOD).await?.json::<APIHeaderResponse>().await?;
+    let spec = client.get(SPEC_METHOD).await?;
+    let spec = spec.json::<APIRetentionResponse>().await?.data;
+    let retained = spec.min_epochs_for_blob_sidecars_requests * spec.slots_per_epoch;
+    let Some(earliest) = head.data.header.message.slot.checked_sub(retained) else {
+        // The chain is younger than the retention window.
+        return Ok(SidecarWindow::From(0));
+    };
+
+    for slot in earliest.saturating_sub(ARCHIVE_PROBE_SLOTS)..earliest {
+        let Ok(response) = client.get(&format!("{SIDECARS_METHOD_PREFIX}/{slot}")).await else {
+            continue;
+        };
+        if response.json::<BeaconBlobBundle>().await.is_ok_and(|bundle| !bundle.data.is_empty()) {
+            return Ok(SidecarWindow::Archive);
+        }
+    }
+    Ok(SidecarWindow::From(earliest))
+}
+
+#[async_trait]
+impl<B> BeaconClient for FailoverBeaconClient<B>
+where
+    B: BeaconClient + Send + Sync,
+{
+    type Error = FailoverBeaconClientError;
+
+    async fn config_spec(&self) -> Result<APIConfigResponse, Self::Error> {
+        let mut result = Err(FailoverBeaconClientError::NoEndpoints);
+        for (client, _) in &self.endpoints {
+            match client.config_spec().await {
+                Ok(spec) => return Ok(spec),
+                Err(err) => result = Err(FailoverBeaconClientError::Exhausted(err.to_string())),
+            }
+        }
+        result
+    }
+
+    async fn beacon_genesis(&self) -> Result<APIGenesisResponse, Self::Error> {
+        let mut result = Err(FailoverBeaconClientError::NoEndpoints);
+        for (client, _) in &self.endpoints {
+            match client.beacon_genesis().await {
+                Ok(genesis) => return Ok(genesis),
+                Err(err) => result = Err(FailoverBeaconClientError::Exhausted(err.to_string())),
+            }
+        }
+        result
+    }
+
+    async fn beacon_blob_side_cars(
+        &self,
+        slot: u64,
+        hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<BlobData>, Self::Error> {
+        let mut found = Vec::with_capacity(hashes.len());
+        let mut missing = hashes.to_vec();
+        let mut last_err = None;
+        for (i, client) in self.route(slot).into_iter().enumerate() {
+            if missing.is_empty() {
+                break;
+            }
+            if i > 0 {
+                debug!(target: "beacon_failover", slot, missing = missing.len(), "Resuming blob sidecar request from the next endpoint");
+            }
+            match client.beacon_blob_side_cars(slot, &missing).await {
+                Ok(sidecars) => {
+                    missing.retain(|hash| !sidecars.iter().any(|s| s.index == hash.index));
+                    found.extend(sidecars);
+                }
+                Err(err) => {
+                    warn!(target: "beacon_failover", slot, %err, "Beacon API endpoint failed to serve blob sidecars");
+                    last_err = Some(err.to_string());
+                }
+            }
+        }
+
+        if found.is_empty() {
+            match last_err {
+                Some(err) => return Err(FailoverBeaconClientError::Exhausted(err)),
+                None if self.endpoints.is_empty() => {
+                    return Err(FailoverBeaconClientError::NoEndpoints);
+                }
+                None => {}
+            }
+        }
+
+        // Restore the order of the hashes, as the sidecars may come from several endpoints.
+        let mut sidecars = Vec::with_capacity(found.len());
+        for hash in hashes {
+            if let Some(pos) = found.iter().position(|s| s.index == hash.index) {
+                sidecars.push(found.swap_remove(pos));
+            }
+        }
+        Ok(sidecars)
+    }
+}
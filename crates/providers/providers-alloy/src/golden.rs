@@ -0,0 +1,434 @@
+//! Contains the [`GoldenTrace`] of a historical derivation range, and the pipeline that replays it.
+//!
+//! A golden trace records, for every L2 block derived from an L1 range, a digest of the derived
+//! payload attributes and the canonical block they were executed into. Traces are small enough
+//! to be committed next to the tests that check them: they hold no transactions, only the
+//! digests, and the system configuration when it changes.
+//!
+//! A trace is recorded with [`GoldenTrace::record`], by a pipeline over the L1 providers of a
+//! node recording its [`L1Archive`] with an [`L1Recorder`], and the L2 provider of a trusted
+//! node. It is checked with [`GoldenTrace::verify_archive`], which re-derives the range offline
+//! from the archive, with the canonical L2 blocks served from the trace itself, and compares the
+//! derived attributes byte for byte to the recorded digests.
+//!
+//! [`L1Recorder`]: crate::L1Recorder
+
+use crate::{L1Archive, ReplayProvider};
+use alloy_primitives::{B256, keccak256};
+use alloy_rlp::Encodable;
+use async_trait::async_trait;
+use kona_derive::{
+    ActivationSignal, DerivationPipeline, EthereumDataSource, L2ChainProvider, Pipeline,
+    PipelineBuilder, PipelineError, PipelineErrorKind, PolledAttributesQueueStage, ResetError,
+    ResetSignal, SignalReceiver, StatefulAttributesBuilder, StepResult,
+};
+use kona_genesis::{RollupConfig, SystemConfig};
+use kona_protocol::{BatchValidationProvider, BlockInfo, L2BlockInfo, OpAttributesWithParent};
+use op_alloy_consensus::OpBlock;
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+/// A derivation pipeline replaying a [`GoldenTrace`] from an [`L1Archive`].
+pub type ReplayDerivationPipeline = DerivationPipeline<
+    PolledAttributesQueueStage<
+        EthereumDataSource<ReplayProvider, ReplayProvider>,
+        ReplayProvider,
+        TraceL2Provider,
+        StatefulAttributesBuilder<ReplayProvider, TraceL2Provider>,
+    >,
+    TraceL2Provider,
+>;
+
+/// The payload attributes derived from a range of L1 blocks, and the canonical L2 blocks they
+/// were executed into.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoldenTrace {
+    /// The chain id of the L2 chain.
+    pub l2_chain_id: u64,
+    /// The L1 origin the pipeline is reset to.
+    pub l1_origin: BlockInfo,
+    /// The number of the last L1 block of the range. The trace holds the blocks derived from L1
+    /// blocks up to and including it.
+    pub l1_end: u64,
+    /// The L2 safe head the pipeline is reset to.
+    pub l2_safe_head: L2BlockInfo,
+    /// The system configuration at the L2 safe head.
+    pub system_config: SystemConfig,
+    /// The blocks derived from the range, in order.
+    pub blocks: Vec<TracedBlock>,
+}
+
+/// A block of a [`GoldenTrace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracedBlock {
+    /// The canonical block.
+    pub block: L2BlockInfo,
+    /// The digest of the payload attributes the block was derived from. See
+    /// [`attributes_digest`].
+    pub attributes: B256,
+    /// The system configuration at the block, if it differs from the one at its parent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_config: Option<SystemConfig>,
+}
+
+impl GoldenTrace {
+    /// Reads the trace at the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, GoldenTraceError> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    /// Writes the trace to the given path, replacing any existing file.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), GoldenTraceError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Records the blocks derived by the given pipeline from the L1 blocks following `l1_origin`
+    /// up to `l1_end`, starting at the L2 safe head `l2_safe_head`.
+    ///
+    /// The canonical block of each derived payload attributes, and its system configuration, are
+    /// fetched from the given L2 provider, which should be backed by a trusted node.
+    pub async fn record<P, L>(
+        pipeline: &mut P,
+        l2_provider: &mut L,
+        l1_origin: BlockInfo,
+        l1_end: u64,
+        l2_safe_head: L2BlockInfo,
+    ) -> Result<Self, GoldenTraceError>
+    where
+        P: Pipeline + SignalReceiver + Send,
+        L: L2ChainProvider + Send,
+        <L as BatchValidationProvider>::Error: Into<PipelineErrorKind>,
+    {
+        let rollup_config = Arc::new(pipeline.rollup_config().clone());
+        let system_config = l2_provider
+            .system_config_by_number(l2_safe_head.block_info.number, rollup_config.clone())
+            .await
+            .map_err(Into::<PipelineErrorKind>::into)?;
+        pipeline
+            .signal(
+                ResetSignal { l2_safe_head, l1_origin, system_config: Some(system_config) }
+                    .signal(),
+            )
+            .await?;
+
+        let mut trace = Self {
+            l2_chain_id: rollup_config.l2_chain_id,
+            l1_origin,
+            l1_end,
+            l2_safe_head,
+            system_config,
+            blocks: Vec::new(),
+        };
+        let (mut safe_head, mut current_config) = (l2_safe_head, system_config);
+        while let Some(attributes) = next_attributes(pipeline, safe_head, l1_end).await? {
+            let number = attributes.block_number();
+            let block = l2_provider
+                .l2_block_info_by_number(number)
+                .await
+                .map_err(Into::<PipelineErrorKind>::into)?;
+            if block.block_info.parent_hash != safe_head.block_info.hash {
+                return Err(GoldenTraceError::Diverged(number));
+            }
+            let block_config = l2_provider
+                .system_config_by_number(number, rollup_config.clone())
+                .await
+                .map_err(Into::<PipelineErrorKind>::into)?;
+            trace.blocks.push(TracedBlock {
+                block,
+                attributes: attributes_digest(&attributes),
+                system_config: (block_config != current_config).then_some(block_config),
+            });
+            (safe_head, current_config) = (block, block_config);
+        }
+        Ok(trace)
+    }
+
+    /// Returns a [`TraceL2Provider`] serving the canonical blocks of the trace.
+    pub fn l2_provider(&self) -> TraceL2Provider {
+        TraceL2Provider::new(self.clone())
+    }
+
+    /// Returns an uninitialized pipeline deriving the range of the trace from the given archive.
+    pub fn pipeline(
+        &self,
+        rollup_config: Arc<RollupConfig>,
+        archive: impl Into<Arc<L1Archive>>,
+    ) -> ReplayDerivationPipeline {
+        let provider = ReplayProvider::new(archive);
+        let l2_provider = self.l2_provider();
+        let attributes = StatefulAttributesBuilder::new(
+            rollup_config.clone(),
+            l2_provider.clone(),
+            provider.clone(),
+        );
+        let dap =
+            EthereumDataSource::new_from_parts(provider.clone(), provider.clone(), &rollup_config);
+        PipelineBuilder::new()
+            .rollup_config(rollup_config)
+            .dap_source(dap)
+            .l2_chain_provider(l2_provider)
+            .chain_provider(provider)
+            .builder(attributes)
+            .origin(BlockInfo::default())
+            .build_polled()
+    }
+
+    /// Re-derives the range of the trace offline from the given archive, and checks that the
+    /// derived payload attributes match the trace. See [`Self::verify`].
+    pub async fn verify_archive(
+        &self,
+        rollup_config: Arc<RollupConfig>,
+        archive: impl Into<Arc<L1Archive>>,
+    ) -> Result<(), GoldenTraceError> {
+        if rollup_config.l2_chain_id != self.l2_chain_id {
+            return Err(GoldenTraceError::ChainIdMismatch(
+                self.l2_chain_id,
+                rollup_config.l2_chain_id,
+            ));
+        }
+        self.verify(&mut self.pipeline(rollup_config, archive)).await
+    }
+
+    /// Resets the given pipeline to the start of the trace, re-derives the range, and checks that
+    /// the digest of each derived payload attributes matches the trace. The pipeline's L2 provider
+    /// must serve the canonical blocks of the trace, e.g. be a [`TraceL2Provider`].
+    pub async fn verify<P>(&self, pipeline: &mut P) -> Result<(), GoldenTraceError>
+    where
+        P: Pipeline + SignalReceiver + Send,
+    {
+        pipeline
+            .signal(
+                ResetSignal {
+                    l2_safe_head: self.l2_safe_head,
+                    l1_origin: self.l1_origin,
+                    system_config: Some(self.system_config),
+                }
+                .signal(),
+            )
+            .await?;
+
+        let mut safe_head = self.l2_safe_head;
+        for (i, expected) in self.blocks.iter().enumerate() {
+            let number = expected.block.block_info.number;
+            let Some(attributes) = next_attributes(pipeline, safe_head, self.l1_end).await? else {
+                return Err(GoldenTraceError::MissingBlocks(number, self.blocks.len() - i));
+            };
+            let actual = attributes_digest(&attributes);
+            if attributes.block_number() != number || actual != expected.attributes {
+                return Err(GoldenTraceError::Mismatch {
+                    number,
+                    expected: expected.attributes,
+                    actual,
+                    attributes: Box::new(attributes),
+                });
+            }
+            safe_head = expected.block;
+        }
+        if let Some(attributes) = next_attributes(pipeline, safe_head, self.l1_end).await? {
+            return Err(GoldenTraceError::UnexpectedBlock(attributes.block_number()));
+        }
+        Ok(())
+    }
+}
+
+/// Steps the pipeline until it produces the next payload attributes, or until the L1 range up to
+/// `l1_end` is exhausted.
+async fn next_attributes<P>(
+    pipeline: &mut P,
+    safe_head: L2BlockInfo,
+    l1_end: u64,
+) -> Result<Option<OpAttributesWithParent>, GoldenTraceError>
+where
+    P: Pipeline + SignalReceiver + Send,
+{
+    loop {
+        if let Some(attributes) = pipeline.next() {
+            return Ok(Some(attributes));
+        }
+        let origin = pipeline.origin().ok_or(PipelineError::MissingOrigin.crit())?;
+        if origin.number > l1_end {
+            return Ok(None);
+        }
+        match pipeline.step(safe_head).await {
+            StepResult::PreparedAttributes | StepResult::AdvancedOrigin => {}
+            // The data of the last block of the range is consumed, and the next block may not be
+            // available, neither on a live chain nor in the archive.
+            StepResult::OriginAdvanceErr(_) if origin.number == l1_end => return Ok(None),
+            StepResult::OriginAdvanceErr(e) | StepResult::StepFailed(e) => match e {
+                PipelineErrorKind::Temporary(PipelineError::NotEnoughData) => {}
+                PipelineErrorKind::Reset(ResetError::HoloceneActivation) => {
+                    let system_config =
+                        pipeline.system_config_by_number(safe_head.block_info.number).await?;
+                    pipeline
+                        .signal(
+                            ActivationSignal {
+                                l2_safe_head: safe_head,
+                                l1_origin: origin,
+                                system_config: Some(system_config),
+                            }
+                            .signal(),
+                        )
+                        .await?;
+                }
+                e => return Err(e.into()),
+            },
+        }
+    }
+}
+
+/// Returns the digest of the given payload attributes: the keccak256 hash of the RLP encoding of
+/// the hash of their parent, followed by each of their fields in order.
+pub fn attributes_digest(attributes: &OpAttributesWithParent) -> B256 {
+    let inner = attributes.inner();
+    let payload = &inner.payload_attributes;
+    let mut out = Vec::new();
+    attributes.parent().block_info.hash.encode(&mut out);
+    payload.timestamp.encode(&mut out);
+    payload.prev_randao.encode(&mut out);
+    payload.suggested_fee_recipient.encode(&mut out);
+    encode_option(payload.withdrawals.as_ref(), &mut out);
+    encode_option(payload.parent_beacon_block_root.as_ref(), &mut out);
+    encode_option(inner.transactions.as_ref(), &mut out);
+    encode_option(inner.no_tx_pool.as_ref(), &mut out);
+    encode_option(inner.gas_limit.as_ref(), &mut out);
+    encode_option(inner.eip_1559_params.as_ref(), &mut out);
+    keccak256(out)
+}
+
+/// Encodes an optional value as an RLP list holding zero or one item.
+fn encode_option<T: Encodable>(value: Option<&T>, out: &mut Vec<u8>) {
+    alloy_rlp::encode_list::<_, T>(value.as_slice(), out);
+}
+
+/// An [`L2ChainProvider`] serving the canonical blocks of a [`GoldenTrace`], with their system
+/// configurations.
+///
+/// The trace holds no transactions, so [`BatchValidationProvider::block_by_number`] always
+/// fails. It is only needed to validate span batches overlapping the safe head, which canonical
+/// ranges are not expected to contain.
+#[derive(Debug, Clone)]
+pub struct TraceL2Provider {
+    /// The trace the blocks are served from.
+    trace: Arc<GoldenTrace>,
+}
+
+impl TraceL2Provider {
+    /// Creates a new [`TraceL2Provider`] serving the blocks of the given trace.
+    pub fn new(trace: impl Into<Arc<GoldenTrace>>) -> Self {
+        Self { trace: trace.into() }
+    }
+
+    /// Returns the index of the block with the given number in the trace, or `None` for the safe
+    /// head the trace starts at.
+    fn index(&self, number: u64) -> Result<Option<usize>, TraceL2ProviderError> {
+        let start = self.trace.l2_safe_head.block_info.number;
+        if number == start {
+            return Ok(None);
+        }
+        number
+            .checked_sub(start + 1)
+            .map(|index| index as usize)
+            .filter(|index| *index < self.trace.blocks.len())
+            .map(Some)
+            .ok_or(TraceL2ProviderError::BlockNotFound(number))
+    }
+}
+
+/// An error for the [`TraceL2Provider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TraceL2ProviderError {
+    /// The block is not in the trace.
+    #[error("Block not in the golden trace: {0}")]
+    BlockNotFound(u64),
+    /// The trace holds no transactions, so full blocks cannot be served.
+    #[error("Golden traces hold no full blocks: {0}")]
+    BlockUnavailable(u64),
+}
+
+impl From<TraceL2ProviderError> for PipelineErrorKind {
+    fn from(e: TraceL2ProviderError) -> Self {
+        PipelineErrorKind::Temporary(PipelineError::Provider(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl BatchValidationProvider for TraceL2Provider {
+    type Error = TraceL2ProviderError;
+
+    async fn l2_block_info_by_number(&mut self, number: u64) -> Result<L2BlockInfo, Self::Error> {
+        Ok(match self.index(number)? {
+            Some(index) => self.trace.blocks[index].block,
+            None => self.trace.l2_safe_head,
+        })
+    }
+
+    async fn block_by_number(&mut self, number: u64) -> Result<OpBlock, Self::Error> {
+        Err(TraceL2ProviderError::BlockUnavailable(number))
+    }
+}
+
+#[async_trait]
+impl L2ChainProvider for TraceL2Provider {
+    type Error = TraceL2ProviderError;
+
+    async fn system_config_by_number(
+        &mut self,
+        number: u64,
+        _: Arc<RollupConfig>,
+    ) -> Result<SystemConfig, <Self as BatchValidationProvider>::Error> {
+        let blocks = self.index(number)?.map_or(0, |index| index + 1);
+        Ok(self.trace.blocks[..blocks]
+            .iter()
+            .rev()
+            .find_map(|block| block.system_config)
+            .unwrap_or(self.trace.system_config))
+    }
+}
+
+/// An error recording or verifying a [`GoldenTrace`].
+#[derive(Debug, thiserror::Error)]
+pub enum GoldenTraceError {
+    /// An I/O error.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The trace could not be encoded or decoded.
+    #[error("Failed to encode or decode the golden trace: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The derivation pipeline failed.
+    #[error("Derivation failed: {0}")]
+    Pipeline(#[from] PipelineErrorKind),
+    /// The trace was recorded for another chain than the one of the rollup configuration.
+    #[error("Golden trace of chain {0} verified with the rollup configuration of chain {1}")]
+    ChainIdMismatch(u64, u64),
+    /// While recording, the canonical block does not build on the previous derived block.
+    #[error("Canonical block {0} does not build on the previous derived block")]
+    Diverged(u64),
+    /// The payload attributes derived for a block don't match the trace.
+    #[error("Derived attributes of block {number} don't match the trace: {actual} != {expected}")]
+    Mismatch {
+        /// The number of the traced block.
+        number: u64,
+        /// The digest in the trace.
+        expected: B256,
+        /// The digest of the derived attributes.
+        actual: B256,
+        /// The derived attributes.
+        attributes: Box<OpAttributesWithParent>,
+    },
+    /// The range was exhausted before all blocks of the trace were derived.
+    #[error("Derivation stopped at block {0}, {1} blocks short of the trace")]
+    MissingBlocks(u64, usize),
+    /// A block was derived past the end of the trace.
+    #[error("Block {0} was derived past the end of the trace")]
+    UnexpectedBlock(u64),
+}
@@ -6,20 +6,46 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod archive;
+pub use archive::{ArchivedBlob, L1Archive, L1ArchiveError, L1Recorder};
+
 mod beacon_client;
 pub use beacon_client::{
-    APIConfigResponse, APIGenesisResponse, BeaconClient, OnlineBeaconClient, ReducedConfigData,
-    ReducedGenesisData,
+    APIConfigResponse, APIGenesisResponse, BeaconClient, OnlineBeaconClient,
+    OnlineBeaconClientError, ReducedConfigData, ReducedGenesisData,
+};
+
+#[cfg(feature = "chaos")]
+mod chaos;
+#[cfg(feature = "chaos")]
+pub use chaos::{Chaos, ChaosConfig, ChaosFault, ChaosLayer, ChaosService, ChaosStats};
+
+mod golden;
+pub use golden::{
+    GoldenTrace, GoldenTraceError, ReplayDerivationPipeline, TraceL2Provider, TraceL2ProviderError,
+    TracedBlock, attributes_digest,
 };
 
 mod blobs;
 pub use blobs::{BlobSidecarProvider, OnlineBlobProvider};
 
+mod failover;
+pub use failover::{FailoverBeaconClient, FailoverBeaconClientError, SidecarWindow};
+
+mod sidecar;
+pub use sidecar::{
+    KZG_COMMITMENT_INCLUSION_PROOF_DEPTH, SidecarVerification, SidecarVerificationError,
+    beacon_block_root, verify_inclusion_proof, verify_sidecars,
+};
+
 mod chain_provider;
-pub use chain_provider::{AlloyChainProvider, AlloyChainProviderError};
+pub use chain_provider::{AlloyChainProvider, AlloyChainProviderError, L1BlockLimit};
 
 mod l2_chain_provider;
 pub use l2_chain_provider::{AlloyL2ChainProvider, AlloyL2ChainProviderError};
 
 mod pipeline;
 pub use pipeline::OnlinePipeline;
+
+mod replay;
+pub use replay::{ReplayProvider, ReplayProviderError};
@@ -1,16 +1,16 @@
 //! Contains an online derivation pipeline.
 
-use crate::{AlloyChainProvider, AlloyL2ChainProvider, OnlineBeaconClient, OnlineBlobProvider};
+use crate::{AlloyChainProvider, AlloyL2ChainProvider, FailoverBeaconClient, OnlineBlobProvider};
 use async_trait::async_trait;
 use core::fmt::Debug;
 use kona_derive::{
-    DerivationPipeline, EthereumDataSource, IndexedAttributesQueueStage, L2ChainProvider,
-    OriginProvider, Pipeline, PipelineBuilder, PipelineErrorKind, PipelineResult,
-    PolledAttributesQueueStage, ResetSignal, Signal, SignalReceiver, StatefulAttributesBuilder,
-    StepResult,
+    BatchObserver, BatcherTxObserver, DerivationPipeline, DerivationSnapshot, EthereumDataSource,
+    IndexedAttributesQueueStage, L2ChainProvider, OriginProvider, Pipeline, PipelineBuilder,
+    PipelineErrorKind, PipelineResult, PipelineStateSize, PolledAttributesQueueStage, ResetSignal,
+    Signal, SignalReceiver, StatefulAttributesBuilder, StepResult,
 };
 use kona_genesis::{RollupConfig, SystemConfig};
-use kona_protocol::{BlockInfo, L2BlockInfo, OpAttributesWithParent};
+use kona_protocol::{BlockInfo, L2BlockInfo, MemoryAudit, OpAttributesWithParent};
 use std::sync::Arc;
 
 /// An online polled derivation pipeline.
@@ -35,9 +35,10 @@ pub type OnlineManagedDerivationPipeline = DerivationPipeline<
     AlloyL2ChainProvider,
 >;
 
-/// An RPC-backed Ethereum data source.
+/// An RPC-backed Ethereum data source. The blob provider is `None` for chains that never post
+/// blobs, see [`RollupConfig::blobs_enabled`].
 pub type OnlineDataProvider =
-    EthereumDataSource<AlloyChainProvider, OnlineBlobProvider<OnlineBeaconClient>>;
+    EthereumDataSource<AlloyChainProvider, Option<OnlineBlobProvider<FailoverBeaconClient>>>;
 
 /// An RPC-backed payload attributes builder for the `AttributesQueue` stage of the derivation
 /// pipeline.
@@ -59,12 +60,19 @@ impl OnlinePipeline {
         cfg: Arc<RollupConfig>,
         l2_safe_head: L2BlockInfo,
         l1_origin: BlockInfo,
-        blob_provider: OnlineBlobProvider<OnlineBeaconClient>,
+        blob_provider: impl Into<Option<OnlineBlobProvider<FailoverBeaconClient>>>,
         chain_provider: AlloyChainProvider,
         mut l2_chain_provider: AlloyL2ChainProvider,
     ) -> PipelineResult<Self> {
-        let mut pipeline =
-            Self::new_polled(cfg.clone(), blob_provider, chain_provider, l2_chain_provider.clone());
+        let mut pipeline = Self::new_polled(
+            cfg.clone(),
+            blob_provider,
+            chain_provider,
+            l2_chain_provider.clone(),
+            None,
+            None,
+            None,
+        );
 
         // Reset the pipeline to populate the initial L1/L2 cursor and system configuration in L1
         // Traversal.
@@ -85,6 +93,31 @@ impl OnlinePipeline {
         Ok(pipeline)
     }
 
+    /// Constructs a new polled derivation pipeline that is initialized to the given
+    /// [`DerivationSnapshot`], e.g. the agreed starting point of a disputed range of L2 blocks.
+    ///
+    /// Unlike [`Self::new`], the system config is taken from the snapshot rather than fetched from
+    /// the L2 chain provider, so the L2 endpoint need not have the state of the safe head.
+    pub async fn from_snapshot(
+        cfg: Arc<RollupConfig>,
+        snapshot: DerivationSnapshot,
+        blob_provider: impl Into<Option<OnlineBlobProvider<FailoverBeaconClient>>>,
+        chain_provider: AlloyChainProvider,
+        l2_chain_provider: AlloyL2ChainProvider,
+    ) -> PipelineResult<Self> {
+        let mut pipeline = Self::new_polled(
+            cfg,
+            blob_provider,
+            chain_provider,
+            l2_chain_provider,
+            None,
+            None,
+            None,
+        );
+        pipeline.signal(snapshot.reset_signal().signal()).await?;
+        Ok(pipeline)
+    }
+
     /// Constructs a new polled derivation pipeline that is uninitialized.
     ///
     /// Uses online providers as specified by the arguments.
@@ -92,20 +125,42 @@ impl OnlinePipeline {
     /// Before using the returned pipeline, a [`ResetSignal`] must be sent to
     /// instantiate the pipeline state. [`Self::new`] is a convenience method that
     /// constructs a new online pipeline and sends the reset signal.
+    ///
+    /// If a [`MemoryAudit`] is given, the channel bank and the attributes queue of the pipeline
+    /// record their size into it. If a [`BatcherTxObserver`] is given, the candidate batcher
+    /// transactions rejected by the data sources are reported to it, and if a [`BatchObserver`]
+    /// is given, the batches read from the channels are.
+    ///
+    /// The blob provider may be `None` for chains that never post blobs, see
+    /// [`RollupConfig::blobs_enabled`].
     pub fn new_polled(
         cfg: Arc<RollupConfig>,
-        blob_provider: OnlineBlobProvider<OnlineBeaconClient>,
+        blob_provider: impl Into<Option<OnlineBlobProvider<FailoverBeaconClient>>>,
         chain_provider: AlloyChainProvider,
         l2_chain_provider: AlloyL2ChainProvider,
+        memory_audit: Option<MemoryAudit>,
+        batcher_tx_observer: Option<Arc<dyn BatcherTxObserver>>,
+        batch_observer: Option<Arc<dyn BatchObserver>>,
     ) -> Self {
         let attributes = StatefulAttributesBuilder::new(
             cfg.clone(),
             l2_chain_provider.clone(),
             chain_provider.clone(),
         );
-        let dap = EthereumDataSource::new_from_parts(chain_provider.clone(), blob_provider, &cfg);
+        let mut dap =
+            EthereumDataSource::new_from_parts(chain_provider.clone(), blob_provider.into(), &cfg);
+        if let Some(observer) = batcher_tx_observer {
+            dap = dap.with_observer(observer);
+        }
 
-        let pipeline = PipelineBuilder::new()
+        let mut builder = PipelineBuilder::new();
+        if let Some(audit) = memory_audit {
+            builder = builder.memory_audit(audit);
+        }
+        if let Some(observer) = batch_observer {
+            builder = builder.batch_observer(observer);
+        }
+        let pipeline = builder
             .rollup_config(cfg.clone())
             .dap_source(dap)
             .l2_chain_provider(l2_chain_provider.clone())
@@ -124,20 +179,42 @@ impl OnlinePipeline {
     /// Before using the returned pipeline, a [`ResetSignal`] must be sent to
     /// instantiate the pipeline state. [`Self::new`] is a convenience method that
     /// constructs a new online pipeline and sends the reset signal.
+    ///
+    /// If a [`MemoryAudit`] is given, the channel bank and the attributes queue of the pipeline
+    /// record their size into it. If a [`BatcherTxObserver`] is given, the candidate batcher
+    /// transactions rejected by the data sources are reported to it, and if a [`BatchObserver`]
+    /// is given, the batches read from the channels are.
+    ///
+    /// The blob provider may be `None` for chains that never post blobs, see
+    /// [`RollupConfig::blobs_enabled`].
     pub fn new_indexed(
         cfg: Arc<RollupConfig>,
-        blob_provider: OnlineBlobProvider<OnlineBeaconClient>,
+        blob_provider: impl Into<Option<OnlineBlobProvider<FailoverBeaconClient>>>,
         chain_provider: AlloyChainProvider,
         l2_chain_provider: AlloyL2ChainProvider,
+        memory_audit: Option<MemoryAudit>,
+        batcher_tx_observer: Option<Arc<dyn BatcherTxObserver>>,
+        batch_observer: Option<Arc<dyn BatchObserver>>,
     ) -> Self {
         let attributes = StatefulAttributesBuilder::new(
             cfg.clone(),
             l2_chain_provider.clone(),
             chain_provider.clone(),
         );
-        let dap = EthereumDataSource::new_from_parts(chain_provider.clone(), blob_provider, &cfg);
+        let mut dap =
+            EthereumDataSource::new_from_parts(chain_provider.clone(), blob_provider.into(), &cfg);
+        if let Some(observer) = batcher_tx_observer {
+            dap = dap.with_observer(observer);
+        }
 
-        let pipeline = PipelineBuilder::new()
+        let mut builder = PipelineBuilder::new();
+        if let Some(audit) = memory_audit {
+            builder = builder.memory_audit(audit);
+        }
+        if let Some(observer) = batch_observer {
+            builder = builder.batch_observer(observer);
+        }
+        let pipeline = builder
             .rollup_config(cfg.clone())
             .dap_source(dap)
             .l2_chain_provider(l2_chain_provider.clone())
@@ -208,6 +285,14 @@ impl Pipeline for OnlinePipeline {
         }
     }
 
+    /// Returns the [PipelineStateSize] of the pipeline.
+    fn state_size(&self) -> PipelineStateSize {
+        match self {
+            Self::Polled(pipeline) => pipeline.state_size(),
+            Self::Managed(pipeline) => pipeline.state_size(),
+        }
+    }
+
     /// Returns the [SystemConfig] by L2 number.
     async fn system_config_by_number(
         &mut self,
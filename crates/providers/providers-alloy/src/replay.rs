@@ -0,0 +1,136 @@
+//! Contains the [`ReplayProvider`], which serves the L1 data of an [`L1Archive`].
+
+use crate::L1Archive;
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_eips::{
+    BlockId,
+    eip4844::{Blob, IndexedBlobHash},
+};
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use kona_derive::{
+    BlobProvider, BlobProviderError, ChainProvider, PipelineError, PipelineErrorKind,
+};
+use kona_protocol::BlockInfo;
+use std::{boxed::Box, sync::Arc, vec::Vec};
+
+/// A [ChainProvider] and [BlobProvider] that serves the L1 data recorded in an [`L1Archive`],
+/// without network access.
+///
+/// The provider is cheap to clone, so that a single archive can back both the chain provider and
+/// the blob provider of a derivation pipeline. Requests for data missing from the archive fail
+/// with a [`ReplayProviderError`].
+#[derive(Debug, Clone)]
+pub struct ReplayProvider {
+    /// The archive the data is served from.
+    archive: Arc<L1Archive>,
+}
+
+impl ReplayProvider {
+    /// Creates a new [ReplayProvider] serving the given archive.
+    pub fn new(archive: impl Into<Arc<L1Archive>>) -> Self {
+        Self { archive: archive.into() }
+    }
+
+    /// Returns the archive the data is served from.
+    pub fn archive(&self) -> &L1Archive {
+        &self.archive
+    }
+
+    /// Returns the header of the block with the given hash.
+    fn header(&self, hash: B256) -> Result<&Header, ReplayProviderError> {
+        self.archive.header(&hash).ok_or(ReplayProviderError::BlockNotFound(hash.into()))
+    }
+}
+
+/// An error for the [ReplayProvider].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReplayProviderError {
+    /// The block is not in the archive.
+    #[error("Block not in the L1 archive: {0}")]
+    BlockNotFound(BlockId),
+    /// The transactions of the block are not in the archive.
+    #[error("Transactions not in the L1 archive: {0}")]
+    TransactionsNotFound(B256),
+    /// The receipts of the block are not in the archive.
+    #[error("Receipts not in the L1 archive: {0}")]
+    ReceiptsNotFound(B256),
+    /// The blob is not in the archive.
+    #[error("Blob not in the L1 archive: {0}")]
+    BlobNotFound(B256),
+}
+
+impl From<ReplayProviderError> for PipelineErrorKind {
+    fn from(e: ReplayProviderError) -> Self {
+        PipelineErrorKind::Temporary(PipelineError::Provider(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl ChainProvider for ReplayProvider {
+    type Error = ReplayProviderError;
+
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        self.header(hash).cloned()
+    }
+
+    async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo, Self::Error> {
+        let header = self
+            .archive
+            .header_by_number(number)
+            .ok_or(ReplayProviderError::BlockNotFound(number.into()))?;
+        Ok(BlockInfo {
+            hash: header.hash_slow(),
+            number,
+            parent_hash: header.parent_hash,
+            timestamp: header.timestamp,
+        })
+    }
+
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>, Self::Error> {
+        self.archive
+            .receipts(&hash)
+            .and_then(|receipts| receipts.iter().map(|r| r.as_receipt().cloned()).collect())
+            .ok_or(ReplayProviderError::ReceiptsNotFound(hash))
+    }
+
+    async fn block_info_and_transactions_by_hash(
+        &mut self,
+        hash: B256,
+    ) -> Result<(BlockInfo, Vec<TxEnvelope>), Self::Error> {
+        let header = self.header(hash)?;
+        let block_info = BlockInfo {
+            hash,
+            number: header.number,
+            parent_hash: header.parent_hash,
+            timestamp: header.timestamp,
+        };
+        let transactions = self
+            .archive
+            .transactions(&hash)
+            .ok_or(ReplayProviderError::TransactionsNotFound(hash))?;
+        Ok((block_info, transactions.to_vec()))
+    }
+}
+
+#[async_trait]
+impl BlobProvider for ReplayProvider {
+    type Error = BlobProviderError;
+
+    async fn get_blobs(
+        &mut self,
+        _: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Box<Blob>>, Self::Error> {
+        blob_hashes
+            .iter()
+            .map(|hash| {
+                self.archive
+                    .blob(&hash.hash)
+                    .map(|blob| blob.blob.clone())
+                    .ok_or_else(|| ReplayProviderError::BlobNotFound(hash.hash).to_string())
+            })
+            .collect::<Result<_, _>>()
+            .map_err(BlobProviderError::Backend)
+    }
+}
@@ -0,0 +1,392 @@
+//! Verification of the blob sidecars served by beacon API endpoints.
+//!
+//! Each blob sidecar carries the header of the beacon block that includes it, and a Merkle proof
+//! of the inclusion of its KZG commitment in the body of that block. Verifying the proofs of the
+//! sidecars of a slot against their header ensures that an endpoint cannot serve blobs that the
+//! block it claims they belong to does not commit to.
+//!
+//! The header itself is anchored to the execution chain: the root of the beacon block that
+//! includes an L1 block is committed to by the `parent_beacon_block_root` of the child of that L1
+//! block, as specified by [EIP-4788]. Verifying the hash tree root of the header against it
+//! ensures that an endpoint cannot serve the sidecars of another block along with their header.
+//!
+//! [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
+//!
+//! Consensus specs: <https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/p2p-interface.md#verify_blob_sidecar_inclusion_proof>
+
+use alloy_primitives::B256;
+use alloy_rpc_types_beacon::sidecar::BlobData;
+use core::{fmt, str::FromStr};
+use sha2::{Digest, Sha256};
+use std::{format, string::String};
+
+/// The depth of the Merkle proof of the inclusion of a KZG commitment in a beacon block body.
+pub const KZG_COMMITMENT_INCLUSION_PROOF_DEPTH: usize = 17;
+
+/// The index of the `blob_kzg_commitments` field of the beacon block body.
+const BLOB_KZG_COMMITMENTS_INDEX: u64 = 11;
+
+/// The depth of the tree of the `blob_kzg_commitments` list, which holds up to
+/// `MAX_BLOB_COMMITMENTS_PER_BLOCK = 4096` commitments.
+const BLOB_KZG_COMMITMENTS_DEPTH: u32 = 12;
+
+/// How strictly the [`OnlineBlobProvider`] verifies the blob sidecars served by the beacon API.
+///
+/// The blobs are always verified against the versioned hashes of the batcher transactions, and
+/// their KZG proofs. This additionally verifies the sidecars against the beacon block header
+/// they are served with.
+///
+/// [`OnlineBlobProvider`]: crate::OnlineBlobProvider
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SidecarVerification {
+    /// The sidecars are not verified against their block header.
+    Disabled,
+    /// Sidecars that fail verification are logged, but still used.
+    Warn,
+    /// Sidecars that fail verification are rejected, and fetched from the next client, if any.
+    #[default]
+    Strict,
+}
+
+impl fmt::Display for SidecarVerification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => f.write_str("disabled"),
+            Self::Warn => f.write_str("warn"),
+            Self::Strict => f.write_str("strict"),
+        }
+    }
+}
+
+impl FromStr for SidecarVerification {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disabled" => Ok(Self::Disabled),
+            "warn" => Ok(Self::Warn),
+            "strict" => Ok(Self::Strict),
+            other => Err(format!(
+                "invalid sidecar verification `{other}`, expected `disabled`, `warn` or `strict`"
+            )),
+        }
+    }
+}
+
+/// An error verifying blob sidecars against their beacon block header.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SidecarVerificationError {
+    /// The sidecar is included in the block of another slot.
+    #[error("Blob sidecar {index} is for slot {got}, expected slot {expected}")]
+    SlotMismatch {
+        /// The index of the sidecar.
+        index: u64,
+        /// The requested slot.
+        expected: u64,
+        /// The slot of the block header of the sidecar.
+        got: u64,
+    },
+    /// The sidecars of the same slot are served with different block headers.
+    #[error("Blob sidecar {0} is served with another block header than the other sidecars")]
+    HeaderMismatch(u64),
+    /// The inclusion proof of the KZG commitment of the sidecar is invalid.
+    #[error("Blob sidecar {0} has an invalid KZG commitment inclusion proof")]
+    InvalidInclusionProof(u64),
+    /// The block header of the sidecars is not the one of the beacon block committed to by the
+    /// execution chain.
+    #[error("Blob sidecars are served with block root {got}, expected block root {expected}")]
+    BlockRootMismatch {
+        /// The root of the beacon block committed to by the execution chain.
+        expected: B256,
+        /// The hash tree root of the block header of the sidecars.
+        got: B256,
+    },
+}
+
+/// Verifies the given blob sidecars of the given slot: all sidecars must be served with the same
+/// block header, of the slot, and the inclusion proofs of their KZG commitments must be valid
+/// against its body root.
+///
+/// If the `block_root` of the beacon block of the slot is given, e.g. the
+/// `parent_beacon_block_root` of the child of the L1 block the sidecars belong to, the hash tree
+/// root of the block header must match it.
+pub fn verify_sidecars(
+    slot: u64,
+    block_root: Option<B256>,
+    sidecars: &[BlobData],
+) -> Result<(), SidecarVerificationError> {
+    let Some(first) = sidecars.first() else {
+        return Ok(());
+    };
+    if let Some(expected) = block_root {
+        let got = beacon_block_root(first);
+        if got != expected {
+            return Err(SidecarVerificationError::BlockRootMismatch { expected, got });
+        }
+    }
+    for sidecar in sidecars {
+        let header = &sidecar.signed_block_header.message;
+        if header.slot != slot {
+            return Err(SidecarVerificationError::SlotMismatch {
+                index: sidecar.index,
+                expected: slot,
+                got: header.slot,
+            });
+        }
+        if sidecar.signed_block_header != first.signed_block_header {
+            return Err(SidecarVerificationError::HeaderMismatch(sidecar.index));
+        }
+        if !verify_inclusion_proof(sidecar) {
+            return Err(SidecarVerificationError::InvalidInclusionProof(sidecar.index));
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if the inclusion proof of the KZG commitment of the given sidecar is valid
+/// against the body root of its block header.
+pub fn verify_inclusion_proof(sidecar: &BlobData) -> bool {
+    let proof = &sidecar.kzg_commitment_inclusion_proof;
+    if proof.len() != KZG_COMMITMENT_INCLUSION_PROOF_DEPTH ||
+        sidecar.index >= 1 << BLOB_KZG_COMMITMENTS_DEPTH
+    {
+        return false;
+    }
+
+    // The generalized index of the commitment, relative to the body root: the field index, the
+    // list data (the left child of the list root, mixed in with its length), and the index of
+    // the commitment in the list.
+    let index = (BLOB_KZG_COMMITMENTS_INDEX << (BLOB_KZG_COMMITMENTS_DEPTH + 1)) | sidecar.index;
+
+    // The hash tree root of a commitment is the root of its two 32 bytes chunks.
+    let commitment = sidecar.kzg_commitment.as_slice();
+    let mut node = [0u8; 64];
+    node[..commitment.len()].copy_from_slice(commitment);
+    let mut root = B256::from_slice(&Sha256::digest(node));
+
+    for (depth, sibling) in proof.iter().enumerate() {
+        root = if (index >> depth) & 1 == 1 {
+            hash_pair(sibling, &root)
+        } else {
+            hash_pair(&root, sibling)
+        };
+    }
+    root == sidecar.signed_block_header.message.body_root
+}
+
+/// Returns the hash tree root of the block header the given sidecar is served with, i.e. the root
+/// of its beacon block.
+///
+/// Consensus specs: <https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#beaconblockheader>
+pub fn beacon_block_root(sidecar: &BlobData) -> B256 {
+    let header = &sidecar.signed_block_header.message;
+
+    // The five fields of the header, padded to the next power of two.
+    let mut leaves = [B256::ZERO; 8];
+    leaves[0][..8].copy_from_slice(&header.slot.to_le_bytes());
+    leaves[1][..8].copy_from_slice(&header.proposer_index.to_le_bytes());
+    leaves[2] = header.parent_root;
+    leaves[3] = header.state_root;
+    leaves[4] = header.body_root;
+
+    let mut width = leaves.len();
+    while width > 1 {
+        width /= 2;
+        for i in 0..width {
+            leaves[i] = hash_pair(&leaves[2 * i], &leaves[2 * i + 1]);
+        }
+    }
+    leaves[0]
+}
+
+/// Returns the SHA-256 hash of the concatenation of the given nodes.
+fn hash_pair(left: &B256, right: &B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, hex};
+    use std::{vec, vec::Vec};
+
+    /// The index of the sidecar in the body of [`sidecar`].
+    const INDEX: u64 = 1;
+
+    /// Returns the layers of the Merkle tree of the given leaves, from the leaves up to the root,
+    /// padded with zero leaves to `2^depth` leaves.
+    fn tree(mut leaves: Vec<B256>, depth: u32) -> Vec<Vec<B256>> {
+        leaves.resize(1 << depth, B256::ZERO);
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers.last().unwrap().chunks(2).map(|c| hash_pair(&c[0], &c[1])).collect();
+            layers.push(layer);
+        }
+        layers
+    }
+
+    /// Returns the Merkle proof of the leaf at the given index of the given tree.
+    fn branch(layers: &[Vec<B256>], index: usize) -> Vec<B256> {
+        layers[..layers.len() - 1].iter().enumerate().map(|(d, l)| l[(index >> d) ^ 1]).collect()
+    }
+
+    /// Returns the sidecar of the commitment at [`INDEX`] of a Deneb beacon block body holding
+    /// three commitments, built top-down from the SSZ containers of the consensus specs.
+    fn sidecar() -> BlobData {
+        let commitments: Vec<[u8; 48]> = (1..=3u8).map(|i| [i; 48]).collect();
+        let commitment_roots = commitments
+            .iter()
+            .map(|c| {
+                let mut node = [0u8; 64];
+                node[..48].copy_from_slice(c);
+                B256::from_slice(&Sha256::digest(node))
+            })
+            .collect();
+
+        // The `blob_kzg_commitments` list: its data tree, mixed in with its length.
+        let list = tree(commitment_roots, BLOB_KZG_COMMITMENTS_DEPTH);
+        let mut length = B256::ZERO;
+        length[0] = commitments.len() as u8;
+        let list_root = hash_pair(list.last().unwrap().first().unwrap(), &length);
+
+        // The twelve fields of the Deneb body, with the commitments at their index.
+        let mut fields: Vec<B256> = (0..12u8).map(|i| B256::repeat_byte(0xa0 + i)).collect();
+        fields[BLOB_KZG_COMMITMENTS_INDEX as usize] = list_root;
+        let body = tree(fields, 4);
+
+        let mut proof = branch(&list, INDEX as usize);
+        proof.push(length);
+        proof.extend(branch(&body, BLOB_KZG_COMMITMENTS_INDEX as usize));
+
+        serde_json::from_value(serde_json::json!({
+            "index": INDEX.to_string(),
+            "blob": format!("0x{}", "00".repeat(131072)),
+            "kzg_commitment": hex::encode_prefixed(commitments[INDEX as usize]),
+            "kzg_proof": hex::encode_prefixed([0u8; 48]),
+            "signed_block_header": {
+                "message": {
+                    "slot": "8626176",
+                    "proposer_index": "1024",
+                    "parent_root": B256::repeat_byte(0x11),
+                    "state_root": B256::repeat_byte(0x22),
+                    "body_root": body.last().unwrap().first().unwrap(),
+                },
+                "signature": hex::encode_prefixed([0u8; 96]),
+            },
+            "kzg_commitment_inclusion_proof": proof,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_beacon_block_root_of_empty_header() {
+        // The hash tree root of an empty header is the root of eight zero leaves.
+        let mut sidecar = sidecar();
+        let header = &mut sidecar.signed_block_header.message;
+        header.slot = 0;
+        header.proposer_index = 0;
+        header.parent_root = B256::ZERO;
+        header.state_root = B256::ZERO;
+        header.body_root = B256::ZERO;
+        assert_eq!(
+            beacon_block_root(&sidecar),
+            b256!("c78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c")
+        );
+    }
+
+    #[test]
+    fn test_verify_sidecars() {
+        let sidecar = sidecar();
+        assert_eq!(
+            sidecar.kzg_commitment_inclusion_proof.len(),
+            KZG_COMMITMENT_INCLUSION_PROOF_DEPTH
+        );
+        assert!(verify_inclusion_proof(&sidecar));
+
+        let root = beacon_block_root(&sidecar);
+        assert_eq!(verify_sidecars(8626176, Some(root), &[sidecar.clone()]), Ok(()));
+        assert_eq!(verify_sidecars(8626176, None, &[sidecar]), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_sidecars_tampered_proof() {
+        let mut sidecar = sidecar();
+        sidecar.kzg_commitment_inclusion_proof[3] = B256::repeat_byte(0xff);
+        assert_eq!(
+            verify_sidecars(8626176, None, &[sidecar]),
+            Err(SidecarVerificationError::InvalidInclusionProof(INDEX))
+        );
+
+        let mut sidecar = self::sidecar();
+        sidecar.kzg_commitment_inclusion_proof.pop();
+        assert!(!verify_inclusion_proof(&sidecar));
+    }
+
+    #[test]
+    fn test_verify_sidecars_tampered_index() {
+        let mut sidecar = sidecar();
+        sidecar.index = INDEX + 1;
+        assert_eq!(
+            verify_sidecars(8626176, None, &[sidecar]),
+            Err(SidecarVerificationError::InvalidInclusionProof(INDEX + 1))
+        );
+    }
+
+    #[test]
+    fn test_verify_sidecars_tampered_commitment() {
+        let mut sidecar = sidecar();
+        sidecar.kzg_commitment[0] ^= 1;
+        assert_eq!(
+            verify_sidecars(8626176, None, &[sidecar]),
+            Err(SidecarVerificationError::InvalidInclusionProof(INDEX))
+        );
+    }
+
+    #[test]
+    fn test_verify_sidecars_tampered_header() {
+        let sidecar = sidecar();
+        let root = beacon_block_root(&sidecar);
+
+        // A header of another block with the same body passes the inclusion proofs, but not the
+        // block root committed to by the execution chain.
+        let mut tampered = sidecar.clone();
+        tampered.signed_block_header.message.state_root = B256::repeat_byte(0x33);
+        assert!(verify_inclusion_proof(&tampered));
+        assert_eq!(
+            verify_sidecars(8626176, Some(root), &[tampered.clone()]),
+            Err(SidecarVerificationError::BlockRootMismatch {
+                expected: root,
+                got: beacon_block_root(&tampered),
+            })
+        );
+
+        // A header with another body root fails the inclusion proofs.
+        let mut tampered = sidecar.clone();
+        tampered.signed_block_header.message.body_root = B256::repeat_byte(0x44);
+        assert_eq!(
+            verify_sidecars(8626176, None, &[tampered]),
+            Err(SidecarVerificationError::InvalidInclusionProof(INDEX))
+        );
+
+        // A header of another slot.
+        assert_eq!(
+            verify_sidecars(8626177, None, &[sidecar.clone()]),
+            Err(SidecarVerificationError::SlotMismatch {
+                index: INDEX,
+                expected: 8626177,
+                got: 8626176,
+            })
+        );
+
+        // Sidecars served with different headers.
+        let mut other = sidecar.clone();
+        other.signed_block_header.message.proposer_index = 1025;
+        assert_eq!(
+            verify_sidecars(8626176, None, &[sidecar, other]),
+            Err(SidecarVerificationError::HeaderMismatch(INDEX))
+        );
+    }
+}
@@ -46,6 +46,10 @@ pub enum SupervisorError {
     #[error(transparent)]
     CrossSafetyCheckerError(#[from] CrossSafetyError),
 
+    /// Indicates that the super root could not be computed over the dependency set.
+    #[error("unable to compute the super root: {0}")]
+    SuperRoot(String),
+
     /// Indicates the L1 block does not match the epxected L1 block.
     #[error("L1 block number mismatch. expected: {expected}, but got {got}")]
     L1BlockMismatch {
@@ -64,6 +68,7 @@ impl From<SupervisorError> for ErrorObjectOwned {
             SupervisorError::EmptyDependencySet |
             SupervisorError::L1BlockMismatch { .. } |
             SupervisorError::Initialise(_) |
+            SupervisorError::SuperRoot(_) |
             SupervisorError::StorageError(_) |
             SupervisorError::ManagedNodeError(_) |
             SupervisorError::ChainProcessorError(_) |
@@ -1,13 +1,13 @@
 use alloy_eips::BlockNumHash;
 use alloy_network::Ethereum;
-use alloy_primitives::{B256, Bytes, ChainId, keccak256};
+use alloy_primitives::{B256, ChainId};
 use alloy_provider::RootProvider;
 use alloy_rpc_client::RpcClient;
 use async_trait::async_trait;
 use core::fmt::Debug;
 use kona_interop::{
-    ChainRootInfo, DependencySet, ExecutingDescriptor, OutputRootWithChain, SUPER_ROOT_VERSION,
-    SafetyLevel, SuperRoot, SuperRootOutput,
+    ChainRootInfo, DependencySet, ExecutingDescriptor, OutputRootWithChain, SafetyLevel, SuperRoot,
+    SuperRootOutput,
 };
 use kona_protocol::BlockInfo;
 use kona_supervisor_storage::{
@@ -351,11 +351,10 @@ impl SupervisorService for Supervisor {
         timestamp: u64,
     ) -> Result<SuperRootOutput, SupervisorError> {
         let mut chain_ids = self.config.dependency_set.dependencies.keys().collect::<Vec<_>>();
-        // Sorting chain ids for deterministic super root hash
+        // Sorting chain ids for a deterministic order of the queries
         chain_ids.sort();
 
         let mut chain_infos = Vec::<ChainRootInfo>::with_capacity(chain_ids.len());
-        let mut super_root_chains = Vec::<OutputRootWithChain>::with_capacity(chain_ids.len());
         let mut cross_safe_source = BlockNumHash::default();
 
         for id in chain_ids {
@@ -366,8 +365,6 @@ impl SupervisorService for Supervisor {
                 .inspect_err(|e| {
                     error!(target: "supervisor_service", %e, "Failed to get output v0 at timestamp {timestamp} for chain {id}");
                 })?;
-            let output_v0_string = serde_json::to_string(&output_v0).unwrap();
-            let canonical_root = keccak256(output_v0_string.as_bytes());
 
             let pending_output_v0 = managed_node
                 .pending_output_v0_at_timestamp(timestamp)
@@ -375,18 +372,8 @@ impl SupervisorService for Supervisor {
                 .inspect_err(|e| {
                     error!(target: "supervisor_service", %e, "Failed to get pending output v0 at timestamp {timestamp} for chain {id}");
                 })?;
-            let pending_output_v0_bytes = Bytes::copy_from_slice(
-                serde_json::to_string(&pending_output_v0).unwrap().as_bytes(),
-            );
-
-            chain_infos.push(ChainRootInfo {
-                chain_id: *id,
-                canonical: canonical_root,
-                pending: pending_output_v0_bytes,
-            });
 
-            super_root_chains
-                .push(OutputRootWithChain { chain_id: *id, output_root: canonical_root });
+            chain_infos.push(ChainRootInfo::new(*id, &output_v0.into(), &pending_output_v0.into()));
 
             let l2_block = managed_node
                 .l2_block_ref_by_timestamp(timestamp)
@@ -407,17 +394,14 @@ impl SupervisorService for Supervisor {
             }
         }
 
-        let super_root = SuperRoot { timestamp, output_roots: super_root_chains };
-
-        let super_root_hash = super_root.hash();
-
-        Ok(SuperRootOutput {
-            cross_safe_derived_from: cross_safe_source,
+        let super_root = SuperRoot::from_dependency_set(
             timestamp,
-            super_root: super_root_hash,
-            chains: chain_infos,
-            version: SUPER_ROOT_VERSION,
-        })
+            &self.config.dependency_set,
+            chain_infos.iter().map(|c| OutputRootWithChain::new(c.chain_id, c.canonical)),
+        )
+        .map_err(|e| SupervisorError::SuperRoot(e.to_string()))?;
+
+        Ok(SuperRootOutput::new(&super_root, cross_safe_source, chain_infos))
     }
 
     fn check_access_list(
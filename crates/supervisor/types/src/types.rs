@@ -6,6 +6,7 @@
 
 use alloy_primitives::{B256, U64};
 use kona_interop::ManagedEvent;
+use kona_protocol::OutputRoot;
 use serde::{Deserialize, Serialize};
 
 // todo:: Determine appropriate locations for these structs and move them accordingly.
@@ -52,9 +53,34 @@ impl OutputV0 {
     }
 }
 
+impl From<OutputV0> for OutputRoot {
+    fn from(output: OutputV0) -> Self {
+        Self::from_parts(output.state_root, output.message_passer_storage_root, output.block_hash)
+    }
+}
+
 /// Represents the events structure sent by the node to the supervisor.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SubscriptionEvent {
     /// Represents the event data sent by the node
     pub data: Option<ManagedEvent>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    #[test]
+    fn test_output_v0_commitment() {
+        let output = OutputV0::new(
+            B256::left_padding_from(&[0xbe, 0xef]),
+            B256::left_padding_from(&[0xba, 0xbe]),
+            B256::left_padding_from(&[0xc0, 0xde]),
+        );
+        assert_eq!(
+            OutputRoot::from(output).hash(),
+            b256!("0c39fb6b07cf6694b13e63e59f7b15255be1c93a4d6d3e0da6c99729647c0d11")
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Pluggable backends for the metrics recorded by kona.
+//!
+//! The `kona_macros` metric macros (`inc!`, `set!`, `record!`) record through the [`metrics`]
+//! facade, into the global [`Recorder`]. A [`MetricsBackend`] builds that recorder, so the
+//! destination of every metric is chosen once, when the backend is installed with
+//! [`install_metrics_backend`], rather than at the call sites:
+//!
+//! - [`PrometheusBackend`] serves the metrics to Prometheus over HTTP. This is the default.
+//! - [`StatsdBackend`] pushes the metrics to a StatsD agent over UDP.
+//! - [`RecorderBackend`] installs any other [`Recorder`], e.g. the exporter of an embedder.
+
+use crate::BoxedRecorder;
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use metrics_util::layers::{Layer, PrefixLayer};
+use std::{
+    fmt::Write,
+    net::{SocketAddr, UdpSocket},
+    sync::Arc,
+};
+use tracing::{info, trace};
+
+/// A destination of the metrics recorded by kona. See the [module docs](self).
+pub trait MetricsBackend {
+    /// Builds the [`Recorder`] of the backend, spawning its exporter if it has one.
+    fn build(self) -> anyhow::Result<BoxedRecorder>;
+}
+
+/// Builds the recorder of the given backend, prefixing the name of every metric with `prefix`,
+/// and installs it globally, wrapped in the given [`Layer`].
+///
+/// ## Errors
+///
+/// - If the recorder of the backend cannot be built.
+/// - If a global recorder is already installed.
+pub fn install_metrics_backend<B, L>(
+    backend: B,
+    prefix: Option<&str>,
+    layer: L,
+) -> anyhow::Result<()>
+where
+    B: MetricsBackend,
+    L: Layer<BoxedRecorder>,
+    L::Output: Recorder + Sync + 'static,
+{
+    metrics::set_global_recorder(layered_recorder(backend, prefix, layer)?)
+        .map_err(|_| anyhow::anyhow!("a global metrics recorder is already installed"))
+}
+
+/// Builds the recorder of the given backend like [`install_metrics_backend`], without installing
+/// it.
+fn layered_recorder<B, L>(backend: B, prefix: Option<&str>, layer: L) -> anyhow::Result<L::Output>
+where
+    B: MetricsBackend,
+    L: Layer<BoxedRecorder>,
+{
+    let recorder = backend.build()?;
+    let recorder: BoxedRecorder = match prefix {
+        None => recorder,
+        Some(prefix) => Box::new(PrefixLayer::new(prefix).layer(recorder)),
+    };
+    Ok(layer.layer(recorder))
+}
+
+/// A [`Layer`] that returns the recorder unchanged.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IdentityLayer;
+
+impl<R> Layer<R> for IdentityLayer {
+    type Output = R;
+
+    fn layer(&self, inner: R) -> R {
+        inner
+    }
+}
+
+/// A [`MetricsBackend`] installing the given [`Recorder`] as is.
+#[derive(Debug, Clone)]
+pub struct RecorderBackend<R>(pub R);
+
+impl<R: Recorder + Send + Sync + 'static> MetricsBackend for RecorderBackend<R> {
+    fn build(self) -> anyhow::Result<BoxedRecorder> {
+        Ok(Box::new(self.0))
+    }
+}
+
+/// A [`MetricsBackend`] pushing metrics to a StatsD agent over UDP.
+///
+/// Each update of a metric is sent as its own datagram, with the labels of the metric as
+/// DogStatsD tags, e.g. `kona_node_rpc_calls:1|c|#method:optimism_syncStatus`. Updates that
+/// cannot be sent are dropped.
+#[derive(Debug, Clone)]
+pub struct StatsdBackend {
+    /// The address of the StatsD agent.
+    pub addr: SocketAddr,
+    /// Constant labels attached to every metric.
+    pub labels: Vec<(String, String)>,
+}
+
+impl MetricsBackend for StatsdBackend {
+    fn build(self) -> anyhow::Result<BoxedRecorder> {
+        let bind: SocketAddr = match self.addr {
+            SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+        };
+        let socket = UdpSocket::bind(bind)?;
+        socket.connect(self.addr)?;
+        socket.set_nonblocking(true)?;
+        info!(target: "metrics", addr = %self.addr, "Pushing metrics to StatsD");
+        Ok(Box::new(StatsdRecorder { socket: Arc::new(socket), labels: self.labels }))
+    }
+}
+
+/// The [`Recorder`] of the [`StatsdBackend`].
+#[derive(Debug)]
+struct StatsdRecorder {
+    /// The socket connected to the StatsD agent.
+    socket: Arc<UdpSocket>,
+    /// Constant labels attached to every metric.
+    labels: Vec<(String, String)>,
+}
+
+impl StatsdRecorder {
+    /// Returns the handle of the metric with the given key.
+    fn handle(&self, key: &Key) -> Arc<StatsdMetric> {
+        let mut tags = String::new();
+        let labels = self
+            .labels
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .chain(key.labels().map(|label| (label.key(), label.value())));
+        for (i, (k, v)) in labels.enumerate() {
+            let _ = write!(tags, "{}{}:{}", if i == 0 { "|#" } else { "," }, k, v);
+        }
+        Arc::new(StatsdMetric { socket: self.socket.clone(), name: key.name().to_string(), tags })
+    }
+}
+
+impl Recorder for StatsdRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.handle(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.handle(key))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(self.handle(key))
+    }
+}
+
+/// A metric of the [`StatsdRecorder`].
+#[derive(Debug)]
+struct StatsdMetric {
+    /// The socket connected to the StatsD agent.
+    socket: Arc<UdpSocket>,
+    /// The name of the metric.
+    name: String,
+    /// The DogStatsD tags of the metric, including the leading `|#`, if it has any.
+    tags: String,
+}
+
+impl StatsdMetric {
+    /// Sends the given value of the metric, of the given StatsD type.
+    fn send(&self, value: impl std::fmt::Display, kind: &str) {
+        let line = format!("{}:{}|{}{}", self.name, value, kind, self.tags);
+        if let Err(err) = self.socket.send(line.as_bytes()) {
+            trace!(target: "metrics", %err, metric = %self.name, "Failed to send StatsD update");
+        }
+    }
+}
+
+impl CounterFn for StatsdMetric {
+    fn increment(&self, value: u64) {
+        self.send(value, "c");
+    }
+
+    fn absolute(&self, value: u64) {
+        // StatsD counters are deltas, so absolute values are reported as gauges.
+        self.send(value, "g");
+    }
+}
+
+impl GaugeFn for StatsdMetric {
+    fn increment(&self, value: f64) {
+        self.send(format_args!("+{value}"), "g");
+    }
+
+    fn decrement(&self, value: f64) {
+        self.send(format_args!("-{value}"), "g");
+    }
+
+    fn set(&self, value: f64) {
+        // A leading sign would be read as a delta, so negative gauges are reset to zero first.
+        if value.is_sign_negative() {
+            self.send(0, "g");
+        }
+        self.send(value, "g");
+    }
+}
+
+impl HistogramFn for StatsdMetric {
+    fn record(&self, value: f64) {
+        self.send(value, "h");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_statsd_backend_sends_tagged_updates() {
+        let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        agent.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let backend = StatsdBackend {
+            addr: agent.local_addr().unwrap(),
+            labels: vec![("chain_id".to_string(), "10".to_string())],
+        };
+        let recorder = layered_recorder(backend, Some("op"), IdentityLayer).unwrap();
+
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!("kona_calls", "method" => "sync").increment(2);
+            metrics::gauge!("kona_head").set(7.0);
+        });
+
+        let mut buf = [0u8; 256];
+        let mut recv = || {
+            let len = agent.recv(&mut buf).unwrap();
+            String::from_utf8(buf[..len].to_vec()).unwrap()
+        };
+        assert_eq!(recv(), "op.kona_calls:2|c|#chain_id:10,method:sync");
+        assert_eq!(recv(), "op.kona_head:7|g|#chain_id:10");
+    }
+}
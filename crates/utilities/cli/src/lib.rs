@@ -18,10 +18,24 @@ pub mod backtrace;
 pub mod log;
 
 mod tracing;
-pub use tracing::{init_test_tracing, init_tracing_subscriber};
+pub use tracing::{
+    LogFormat, LogOutput, ReloadableRegistry, init_reloadable_tracing_subscriber,
+    init_reloadable_tracing_subscriber_with, init_test_tracing, init_tracing_subscriber,
+    init_tracing_subscriber_with_output, reload_tracing_filter, reload_tracing_levels,
+};
+
+mod sampling;
+pub use sampling::SamplingFilter;
+
+mod backend;
+pub(crate) use backend::IdentityLayer;
+pub use backend::{MetricsBackend, RecorderBackend, StatsdBackend, install_metrics_backend};
 
 mod prometheus;
-pub use prometheus::init_prometheus_server;
+pub use prometheus::{
+    BoxedRecorder, PrometheusBackend, init_prometheus_server, init_prometheus_server_layered,
+    init_prometheus_server_with,
+};
 
 pub mod sigsegv_handler;
 
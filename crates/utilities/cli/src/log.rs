@@ -1,9 +1,12 @@
 //! Arguments for logging.
 
 use clap::{ArgAction, Args};
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::{EnvFilter, Layer, layer::Identity};
 
-use crate::init_tracing_subscriber;
+use crate::{
+    LogFormat, LogOutput, ReloadableRegistry, init_reloadable_tracing_subscriber_with,
+    init_tracing_subscriber_with_output,
+};
 
 /// Global configuration arguments.
 #[derive(Args, Debug, Default, Clone)]
@@ -19,12 +22,43 @@ pub struct LogArgs {
         action = ArgAction::Count,
     )]
     pub v: u8,
+    /// The format of log output.
+    #[arg(long = "log.format", global = true, default_value = "text")]
+    pub log_format: LogFormat,
+    /// The maximum number of events logged per second by a single log statement. Further events
+    /// are dropped until the next second; errors are never dropped. Disabled if `0`.
+    #[arg(long = "log.sample-burst", global = true, default_value = "0")]
+    pub log_sample_burst: u32,
 }
 
 impl LogArgs {
+    /// Returns the [`LogOutput`] configured by the arguments.
+    pub const fn output(&self) -> LogOutput {
+        LogOutput { format: self.log_format, sample_burst: self.log_sample_burst }
+    }
+
     /// Initializes the telemetry stack.
     pub fn init_tracing(&self, filter: Option<EnvFilter>) -> anyhow::Result<()> {
-        Ok(init_tracing_subscriber(self.v, filter)?)
+        Ok(init_tracing_subscriber_with_output(self.v, filter, self.output())?)
+    }
+
+    /// Initializes the telemetry stack with a filter that can be replaced at runtime using
+    /// [`crate::reload_tracing_filter`] and [`crate::reload_tracing_levels`].
+    pub fn init_reloadable_tracing(&self, filter: Option<EnvFilter>) -> anyhow::Result<()> {
+        self.init_reloadable_tracing_with(filter, Identity::new())
+    }
+
+    /// Initializes the telemetry stack like [`Self::init_reloadable_tracing`], with an extra
+    /// [`Layer`], e.g. to export traces.
+    pub fn init_reloadable_tracing_with<L>(
+        &self,
+        filter: Option<EnvFilter>,
+        layer: L,
+    ) -> anyhow::Result<()>
+    where
+        L: Layer<ReloadableRegistry> + Send + Sync + 'static,
+    {
+        Ok(init_reloadable_tracing_subscriber_with(self.v, filter, self.output(), layer)?)
     }
 }
 
@@ -57,4 +91,14 @@ mod tests {
         let cli_v5 = TestCli::parse_from(["test_app", "-vvvvv"]);
         assert_eq!(cli_v5.global.v, 5, "Verbosity with -vvvvv should be 5.");
     }
+
+    #[test]
+    fn test_log_output() {
+        let cli = TestCli::parse_from(["test_app"]);
+        assert_eq!(cli.global.output(), LogOutput::default());
+
+        let cli =
+            TestCli::parse_from(["test_app", "--log.format", "json", "--log.sample-burst", "10"]);
+        assert_eq!(cli.global.output(), LogOutput { format: LogFormat::Json, sample_burst: 10 });
+    }
 }
@@ -1,9 +1,14 @@
 //! Utility module to house implementation and declaration of MetricsArgs since it's being used in
 //! multiple places, it's just being referenced from this module.
 
-use crate::init_prometheus_server;
+use crate::{
+    BoxedRecorder, IdentityLayer, MetricsBackend, PrometheusBackend, StatsdBackend,
+    install_metrics_backend,
+};
 use clap::{Parser, arg};
-use std::net::IpAddr;
+use metrics::Recorder;
+use metrics_util::layers::Layer;
+use std::net::{IpAddr, SocketAddr};
 
 /// Configuration for Prometheus metrics.
 #[derive(Debug, Clone, Parser)]
@@ -30,6 +35,35 @@ pub struct MetricsArgs {
         env = "KONA_METRICS_ADDR"
     )]
     pub addr: IpAddr,
+
+    /// Prefix prepended to the name of every metric, e.g. `op` to export `kona_node_rpc_calls` as
+    /// `op_kona_node_rpc_calls`.
+    #[arg(long = "metrics.prefix", global = true, env = "KONA_METRICS_PREFIX")]
+    pub prefix: Option<String>,
+
+    /// Constant labels attached to every metric, as comma-separated `key=value` pairs, e.g.
+    /// `chain_id=10,role=validator`.
+    #[arg(
+        long = "metrics.labels",
+        global = true,
+        value_delimiter = ',',
+        value_parser = parse_label,
+        env = "KONA_METRICS_LABELS"
+    )]
+    pub labels: Vec<(String, String)>,
+
+    /// Address of a StatsD agent to push metrics to over UDP, e.g. `127.0.0.1:8125`, instead of
+    /// serving them to Prometheus.
+    #[arg(long = "metrics.statsd-addr", global = true, env = "KONA_METRICS_STATSD_ADDR")]
+    pub statsd_addr: Option<SocketAddr>,
+}
+
+/// Parses a `key=value` metric label.
+fn parse_label(label: &str) -> Result<(String, String), String> {
+    match label.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("invalid metric label `{label}`, expected `key=value`")),
+    }
 }
 
 impl Default for MetricsArgs {
@@ -39,15 +73,67 @@ impl Default for MetricsArgs {
 }
 
 impl MetricsArgs {
-    /// Initialize the tracing stack and Prometheus metrics recorder.
+    /// Initialize the metrics recorder, serving metrics to Prometheus, or pushing them to the
+    /// StatsD agent if one is configured.
     ///
     /// This function should be called at the beginning of the program.
     pub fn init_metrics(&self) -> anyhow::Result<()> {
-        if self.enabled {
-            init_prometheus_server(self.addr, self.port)?;
+        self.init_metrics_with_layer(IdentityLayer)
+    }
+
+    /// Initializes the metrics recorder like [`Self::init_metrics`], wrapping it in the given
+    /// [`Layer`], e.g. to label the metrics of each node of a process that embeds several.
+    pub fn init_metrics_with_layer<L>(&self, layer: L) -> anyhow::Result<()>
+    where
+        L: Layer<BoxedRecorder>,
+        L::Output: Recorder + Sync + 'static,
+    {
+        if !self.enabled {
+            return Ok(());
+        }
+        match self.statsd_addr {
+            Some(addr) => self.init_metrics_with_backend(
+                StatsdBackend { addr, labels: self.labels.clone() },
+                layer,
+            ),
+            None => self.init_metrics_with_backend(
+                PrometheusBackend {
+                    addr: SocketAddr::from((self.addr, self.port)),
+                    labels: self.labels.clone(),
+                },
+                layer,
+            ),
         }
+    }
 
-        Ok(())
+    /// Installs the given [`MetricsBackend`] instead of the one configured by the flags, with the
+    /// configured prefix, wrapped in the given [`Layer`]. This allows embedders to route the
+    /// metrics of the node to their own exporter.
+    pub fn init_metrics_with_backend<B, L>(&self, backend: B, layer: L) -> anyhow::Result<()>
+    where
+        B: MetricsBackend,
+        L: Layer<BoxedRecorder>,
+        L::Output: Recorder + Sync + 'static,
+    {
+        install_metrics_backend(backend, self.prefix.as_deref(), layer)
+    }
+
+    /// Adds the given constant labels, unless a label with the same key is already configured.
+    ///
+    /// This allows applications to attach labels they can infer, such as the chain id, while
+    /// letting users override them.
+    pub fn with_default_labels<K, V>(mut self, labels: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        for (key, value) in labels {
+            let key = key.into();
+            if !self.labels.iter().any(|(k, _)| *k == key) {
+                self.labels.push((key, value.into()));
+            }
+        }
+        self
     }
 }
 
@@ -74,6 +160,36 @@ mod tests {
             IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
             "Default for metrics.addr should be 0.0.0.0."
         );
+        assert_eq!(cli.metrics.prefix, None, "Default for metrics.prefix should be None.");
+        assert!(cli.metrics.labels.is_empty(), "Default for metrics.labels should be empty.");
+        assert_eq!(
+            cli.metrics.statsd_addr, None,
+            "Default for metrics.statsd-addr should be None."
+        );
+    }
+
+    #[test]
+    fn test_metrics_prefix_and_labels() {
+        let cli = TestCli::parse_from([
+            "test_app",
+            "--metrics.prefix",
+            "op",
+            "--metrics.labels",
+            "chain_id=10,role=sequencer",
+        ]);
+        assert_eq!(cli.metrics.prefix.as_deref(), Some("op"));
+
+        let metrics = cli.metrics.with_default_labels([("role", "validator"), ("network", "op")]);
+        assert_eq!(
+            metrics.labels,
+            vec![
+                ("chain_id".to_string(), "10".to_string()),
+                ("role".to_string(), "sequencer".to_string()),
+                ("network".to_string(), "op".to_string()),
+            ]
+        );
+
+        assert!(TestCli::try_parse_from(["test_app", "--metrics.labels", "chain_id"]).is_err());
     }
 
     #[test]
@@ -85,6 +201,8 @@ mod tests {
             "9999",
             "--metrics.addr",
             "127.0.0.1",
+            "--metrics.statsd-addr",
+            "127.0.0.1:8125",
         ]);
         assert!(cli.metrics.enabled, "metrics.enabled should be true.");
         assert_eq!(cli.metrics.port, 9999, "metrics.port should be parsed from CLI.");
@@ -93,5 +211,10 @@ mod tests {
             IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
             "metrics.addr should be parsed from CLI."
         );
+        assert_eq!(
+            cli.metrics.statsd_addr,
+            Some(SocketAddr::from(([127, 0, 0, 1], 8125))),
+            "metrics.statsd-addr should be parsed from CLI."
+        );
     }
 }
@@ -1,7 +1,14 @@
 //! Utilities for spinning up a prometheus metrics server.
 
-use metrics_exporter_prometheus::{BuildError, PrometheusBuilder};
-use std::net::{IpAddr, SocketAddr};
+use crate::{IdentityLayer, MetricsBackend, install_metrics_backend};
+use metrics::Recorder;
+use metrics_exporter_prometheus::{BuildError, PrometheusBuilder, PrometheusRecorder};
+use metrics_util::layers::Layer;
+use std::{
+    net::{IpAddr, SocketAddr},
+    thread,
+};
+use tokio::runtime;
 use tracing::info;
 
 /// Start a Prometheus metrics server on the given port.
@@ -18,3 +25,98 @@ pub fn init_prometheus_server(addr: IpAddr, metrics_port: u16) -> Result<(), Bui
 
     Ok(())
 }
+
+/// Start a Prometheus metrics server on the given port, prefixing the name of every metric with
+/// `prefix` and attaching the given constant labels to every metric.
+///
+/// The prefix is separated from the metric name by an underscore, e.g. a prefix of `op` exports
+/// `kona_node_rpc_calls` as `op_kona_node_rpc_calls`.
+pub fn init_prometheus_server_with(
+    addr: IpAddr,
+    metrics_port: u16,
+    prefix: Option<&str>,
+    labels: impl IntoIterator<Item = (String, String)>,
+) -> anyhow::Result<()> {
+    init_prometheus_server_layered(addr, metrics_port, prefix, labels, IdentityLayer)
+}
+
+/// Start a Prometheus metrics server like [`init_prometheus_server_with`], wrapping the recorder
+/// in the given [`Layer`] before installing it globally.
+///
+/// This allows processes that embed multiple nodes to install a layer that labels the metrics of
+/// each node, so that the series recorded by different nodes do not collide.
+pub fn init_prometheus_server_layered<L>(
+    addr: IpAddr,
+    metrics_port: u16,
+    prefix: Option<&str>,
+    labels: impl IntoIterator<Item = (String, String)>,
+    layer: L,
+) -> anyhow::Result<()>
+where
+    L: Layer<BoxedRecorder>,
+    L::Output: Recorder + Sync + 'static,
+{
+    let backend = PrometheusBackend {
+        addr: SocketAddr::from((addr, metrics_port)),
+        labels: labels.into_iter().collect(),
+    };
+    install_metrics_backend(backend, prefix, layer)
+}
+
+/// A type-erased [`Recorder`], as wrapped by the [`Layer`] of [`init_prometheus_server_layered`].
+pub type BoxedRecorder = Box<dyn Recorder + Send + Sync>;
+
+/// A [`MetricsBackend`] serving metrics to Prometheus over HTTP.
+#[derive(Debug, Clone)]
+pub struct PrometheusBackend {
+    /// The address to serve the metrics on.
+    pub addr: SocketAddr,
+    /// Constant labels attached to every metric.
+    pub labels: Vec<(String, String)>,
+}
+
+impl MetricsBackend for PrometheusBackend {
+    fn build(self) -> anyhow::Result<BoxedRecorder> {
+        let builder = self
+            .labels
+            .into_iter()
+            .fold(PrometheusBuilder::new().with_http_listener(self.addr), |builder, (k, v)| {
+                builder.add_global_label(k, v)
+            });
+        let recorder = build_recorder(builder)?;
+        info!(
+            target: "prometheus",
+            "Serving metrics at: http://{}",
+            self.addr
+        );
+        Ok(Box::new(recorder))
+    }
+}
+
+/// Builds the recorder of the [`PrometheusBuilder`], and spawns its exporter like
+/// [`PrometheusBuilder::install`] does.
+fn build_recorder(builder: PrometheusBuilder) -> anyhow::Result<PrometheusRecorder> {
+    let recorder = if let Ok(handle) = runtime::Handle::try_current() {
+        let (recorder, exporter) = {
+            let _guard = handle.enter();
+            builder.build()?
+        };
+        handle.spawn(exporter);
+        recorder
+    } else {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| BuildError::FailedToCreateRuntime(e.to_string()))?;
+        let (recorder, exporter) = {
+            let _guard = runtime.enter();
+            builder.build()?
+        };
+        thread::Builder::new()
+            .name("metrics-exporter-prometheus-http".to_string())
+            .spawn(move || runtime.block_on(exporter))
+            .map_err(|e| BuildError::FailedToCreateRuntime(e.to_string()))?;
+        recorder
+    };
+    Ok(recorder)
+}
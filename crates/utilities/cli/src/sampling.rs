@@ -0,0 +1,99 @@
+//! A [`Filter`] that samples high-frequency log events.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+use tracing::{Level, Metadata, callsite::Identifier, subscriber::Interest};
+use tracing_subscriber::layer::{Context, Filter};
+
+/// The window over which events are counted by the [`SamplingFilter`].
+const SAMPLING_WINDOW: Duration = Duration::from_secs(1);
+
+/// A per-layer [`Filter`] that samples high-frequency log events.
+///
+/// Each event callsite may emit up to `burst` events per second; further events from the same
+/// callsite are dropped until the next second. Errors and spans are never sampled. A `burst` of
+/// `0` disables sampling.
+#[derive(Debug, Default)]
+pub struct SamplingFilter {
+    /// The maximum number of events per callsite per second.
+    burst: u32,
+    /// The sampling window of each callsite.
+    windows: Mutex<HashMap<Identifier, Window>>,
+}
+
+/// The events emitted by a callsite in the current sampling window.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    /// The start of the window.
+    start: Instant,
+    /// The number of events emitted in the window.
+    count: u32,
+}
+
+impl SamplingFilter {
+    /// Creates a new [`SamplingFilter`] that allows up to `burst` events per callsite per second.
+    pub fn new(burst: u32) -> Self {
+        Self { burst, windows: Mutex::default() }
+    }
+
+    /// Returns `true` if the event with the given [`Metadata`] is subject to sampling.
+    fn is_sampled(&self, meta: &Metadata<'_>) -> bool {
+        self.burst > 0 && meta.is_event() && *meta.level() != Level::ERROR
+    }
+
+    /// Records an event from the given callsite at `now`, and returns `true` if it is within
+    /// the callsite's burst.
+    fn sample(&self, callsite: Identifier, now: Instant) -> bool {
+        let mut windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+        let window = windows.entry(callsite).or_insert(Window { start: now, count: 0 });
+        if now.duration_since(window.start) >= SAMPLING_WINDOW {
+            *window = Window { start: now, count: 0 };
+        }
+        window.count += 1;
+        window.count <= self.burst
+    }
+}
+
+impl<S> Filter<S> for SamplingFilter {
+    fn enabled(&self, meta: &Metadata<'_>, _: &Context<'_, S>) -> bool {
+        !self.is_sampled(meta) || self.sample(meta.callsite(), Instant::now())
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> Interest {
+        // Sampled callsites must be re-evaluated for every event.
+        if self.is_sampled(meta) { Interest::sometimes() } else { Interest::always() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::callsite::Callsite;
+
+    struct TestCallsite;
+
+    impl Callsite for TestCallsite {
+        fn set_interest(&self, _: Interest) {}
+
+        fn metadata(&self) -> &Metadata<'_> {
+            unimplemented!()
+        }
+    }
+
+    static CALLSITE: TestCallsite = TestCallsite;
+
+    #[test]
+    fn test_sample_burst_per_window() {
+        let filter = SamplingFilter::new(2);
+        let now = Instant::now();
+        let id = || Identifier(&CALLSITE);
+
+        assert!(filter.sample(id(), now));
+        assert!(filter.sample(id(), now));
+        assert!(!filter.sample(id(), now + Duration::from_millis(500)));
+        assert!(filter.sample(id(), now + SAMPLING_WINDOW));
+    }
+}
@@ -1,7 +1,60 @@
 //! [tracing_subscriber] utilities.
 
-use tracing::{Level, subscriber::SetGlobalDefaultError};
-use tracing_subscriber::EnvFilter;
+use crate::SamplingFilter;
+use std::{collections::BTreeMap, sync::OnceLock};
+use tracing::{Level, Subscriber, subscriber::SetGlobalDefaultError};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    filter::{Filtered, LevelFilter},
+    layer::{Identity, Layered, SubscriberExt},
+    registry::LookupSpan,
+    reload,
+};
+
+/// The handle used to swap the global [`EnvFilter`], set by
+/// [`init_reloadable_tracing_subscriber`].
+static FILTER_RELOAD_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// The directives of the [`EnvFilter`] the reloadable subscriber was initialized with.
+static STARTUP_DIRECTIVES: OnceLock<String> = OnceLock::new();
+
+/// The format of log output.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+/// The configuration of the log output layer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LogOutput {
+    /// The [`LogFormat`].
+    pub format: LogFormat,
+    /// The maximum number of events logged per callsite per second. See [`SamplingFilter`].
+    /// Sampling is disabled if `0`.
+    pub sample_burst: u32,
+}
+
+impl LogOutput {
+    /// Returns the log output [`Layer`].
+    pub fn layer<S>(self) -> Filtered<Box<dyn Layer<S> + Send + Sync>, SamplingFilter, S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let layer = match self.format {
+            LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+            LogFormat::Json => tracing_subscriber::fmt::layer().json().boxed(),
+        };
+        layer.with_filter(SamplingFilter::new(self.sample_burst))
+    }
+}
+
+/// The subscriber that the extra [`Layer`] passed to [`init_reloadable_tracing_subscriber_with`]
+/// is stacked onto: a [`Registry`] filtered by a reloadable [`EnvFilter`].
+pub type ReloadableRegistry = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
 
 /// Initializes the tracing subscriber
 ///
@@ -14,6 +67,21 @@ use tracing_subscriber::EnvFilter;
 pub fn init_tracing_subscriber(
     verbosity_level: u8,
     env_filter: Option<impl Into<EnvFilter>>,
+) -> Result<(), SetGlobalDefaultError> {
+    init_tracing_subscriber_with_output(verbosity_level, env_filter, LogOutput::default())
+}
+
+/// Initializes the tracing subscriber like [`init_tracing_subscriber`], with the given
+/// [`LogOutput`].
+///
+/// # Arguments
+/// * `verbosity_level` - The verbosity level (0-5). If `0`, no logs are printed.
+/// * `env_filter` - Optional environment filter for the subscriber.
+/// * `output` - The [`LogOutput`] configuration.
+pub fn init_tracing_subscriber_with_output(
+    verbosity_level: u8,
+    env_filter: Option<impl Into<EnvFilter>>,
+    output: LogOutput,
 ) -> Result<(), SetGlobalDefaultError> {
     let level = match verbosity_level {
         1 => Level::ERROR,
@@ -27,8 +95,129 @@ pub fn init_tracing_subscriber(
     }
     let filter = env_filter.map(|e| e.into()).unwrap_or(EnvFilter::from_default_env());
     let filter = filter.add_directive(level.into());
-    let subscriber = tracing_subscriber::fmt().with_max_level(level);
-    tracing::subscriber::set_global_default(subscriber.with_env_filter(filter).finish())
+    let subscriber = tracing_subscriber::registry().with(filter).with(output.layer());
+    tracing::subscriber::set_global_default(subscriber)
+}
+
+/// Initializes the tracing subscriber with a filter that can later be replaced with
+/// [`reload_tracing_filter`].
+///
+/// Like [`init_tracing_subscriber`], a verbosity level of `0` uses the default `INFO` level of
+/// [`tracing_subscriber::fmt`], ignoring the environment filter.
+///
+/// # Arguments
+/// * `verbosity_level` - The verbosity level (0-5).
+/// * `env_filter` - Optional environment filter for the subscriber.
+pub fn init_reloadable_tracing_subscriber(
+    verbosity_level: u8,
+    env_filter: Option<impl Into<EnvFilter>>,
+) -> Result<(), SetGlobalDefaultError> {
+    init_reloadable_tracing_subscriber_with(
+        verbosity_level,
+        env_filter,
+        LogOutput::default(),
+        Identity::new(),
+    )
+}
+
+/// Initializes the tracing subscriber like [`init_reloadable_tracing_subscriber`], with an extra
+/// [`Layer`] that receives the same filtered spans and events as the log output, e.g. to export
+/// traces.
+///
+/// # Arguments
+/// * `verbosity_level` - The verbosity level (0-5).
+/// * `env_filter` - Optional environment filter for the subscriber.
+/// * `output` - The [`LogOutput`] configuration.
+/// * `layer` - The extra [`Layer`].
+pub fn init_reloadable_tracing_subscriber_with<L>(
+    verbosity_level: u8,
+    env_filter: Option<impl Into<EnvFilter>>,
+    output: LogOutput,
+    layer: L,
+) -> Result<(), SetGlobalDefaultError>
+where
+    L: Layer<ReloadableRegistry> + Send + Sync + 'static,
+{
+    let level = match verbosity_level {
+        1 => Level::ERROR,
+        2 => Level::WARN,
+        3 => Level::INFO,
+        4 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    let filter = if verbosity_level == 0 {
+        EnvFilter::default().add_directive(Level::INFO.into())
+    } else {
+        let filter = env_filter.map(|e| e.into()).unwrap_or(EnvFilter::from_default_env());
+        filter.add_directive(level.into())
+    };
+    let _ = STARTUP_DIRECTIVES.set(filter.to_string());
+    let (filter, handle) = reload::Layer::new(filter);
+    let _ = FILTER_RELOAD_HANDLE.set(handle);
+    let subscriber = tracing_subscriber::registry().with(filter).with(layer).with(output.layer());
+    tracing::subscriber::set_global_default(subscriber)
+}
+
+/// Replaces the global tracing filter with the given directives, in `RUST_LOG` syntax. The
+/// directives the subscriber was initialized with are kept, unless the given directives set the
+/// level of the same target.
+///
+/// Returns an error if the directives are invalid, or if the global subscriber was not
+/// initialized with [`init_reloadable_tracing_subscriber`].
+pub fn reload_tracing_filter(directives: &str) -> anyhow::Result<()> {
+    let handle = FILTER_RELOAD_HANDLE
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("tracing subscriber is not reloadable"))?;
+    let base = STARTUP_DIRECTIVES.get().map(String::as_str).unwrap_or("");
+    let filter = EnvFilter::try_new(merge_directives(base, split_directives(directives)))?;
+    handle.reload(filter)?;
+    Ok(())
+}
+
+/// Replaces the global tracing filter with the given base directives, overriding the level of
+/// the given targets. If no base directives are given, the filter the subscriber was initialized
+/// with is used as the base.
+///
+/// This allows the level of a single target (e.g. `engine_builder`) to be changed at runtime,
+/// without having to restate the rest of the filter.
+pub fn reload_tracing_levels(
+    directives: Option<&str>,
+    levels: &BTreeMap<String, String>,
+) -> anyhow::Result<()> {
+    let base = directives.or_else(|| STARTUP_DIRECTIVES.get().map(String::as_str)).unwrap_or("");
+    reload_tracing_filter(&with_target_levels(base, levels))
+}
+
+/// Appends a directive for each of the given target levels to the base directives, replacing
+/// any base directive for the same target.
+fn with_target_levels(base: &str, levels: &BTreeMap<String, String>) -> String {
+    merge_directives(base, levels.iter().map(|(target, level)| format!("{target}={level}")))
+}
+
+/// Appends the given directives to the base directives, replacing any base directive for the
+/// same target. A bare level replaces the global level of the base directives.
+fn merge_directives(base: &str, directives: impl IntoIterator<Item = String>) -> String {
+    let directives: Vec<_> = directives.into_iter().collect();
+    let targets: Vec<_> = directives.iter().map(|directive| directive_target(directive)).collect();
+    split_directives(base)
+        .filter(|directive| !targets.contains(&directive_target(directive)))
+        .chain(directives.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Splits the given comma-separated directives, skipping empty ones.
+fn split_directives(directives: &str) -> impl Iterator<Item = String> + '_ {
+    directives.split(',').map(str::trim).filter(|d| !d.is_empty()).map(str::to_string)
+}
+
+/// Returns the target of the given directive, or an empty string if it sets the global level.
+fn directive_target(directive: &str) -> &str {
+    match directive.split_once('=') {
+        Some((target, _)) => target,
+        None if directive.parse::<LevelFilter>().is_ok() => "",
+        None => directive,
+    }
 }
 
 /// This provides function for init tracing in testing
@@ -40,3 +229,35 @@ pub fn init_tracing_subscriber(
 pub fn init_test_tracing() {
     let _ = init_tracing_subscriber(4, None::<EnvFilter>);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_target_levels() {
+        let levels = BTreeMap::from([
+            ("engine_builder".to_string(), "trace".to_string()),
+            ("discv5".to_string(), "warn".to_string()),
+        ]);
+        assert_eq!(
+            with_target_levels("discv5=error, info,engine=debug", &levels),
+            "info,engine=debug,discv5=warn,engine_builder=trace"
+        );
+        assert_eq!(with_target_levels("", &BTreeMap::new()), "");
+    }
+
+    #[test]
+    fn test_merge_directives() {
+        // The base directives are kept, unless overridden for the same target.
+        assert_eq!(
+            merge_directives("discv5=error,info", split_directives("engine=debug")),
+            "discv5=error,info,engine=debug"
+        );
+        assert_eq!(
+            merge_directives("discv5=error,info", split_directives("debug, discv5=warn")),
+            "debug,discv5=warn"
+        );
+        assert_eq!(merge_directives("", split_directives("")), "");
+    }
+}
@@ -1,4 +1,8 @@
 //! Macros for recording metrics.
+//!
+//! The macros record through the `metrics` facade of the calling crate, into whichever global
+//! recorder the application installed, so the metrics backend is chosen once at startup rather
+//! than at each call site. See the `MetricsBackend` of `kona-cli`.
 
 /// Sets a metric value, optionally with a specified label.
 #[macro_export]